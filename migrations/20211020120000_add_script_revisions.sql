@@ -0,0 +1 @@
+ALTER TABLE scripts ADD COLUMN revision INTEGER NOT NULL DEFAULT 1;
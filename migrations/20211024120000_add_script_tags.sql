@@ -0,0 +1,2 @@
+ALTER TABLE scripts ADD COLUMN tags TEXT NOT NULL DEFAULT '';
+ALTER TABLE scripts ADD COLUMN folder TEXT;
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS 'player_ignores'
+(
+  player_id  INTEGER NOT NULL,
+  ignored_id INTEGER NOT NULL,
+  PRIMARY KEY (player_id, ignored_id),
+  FOREIGN KEY (player_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (ignored_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
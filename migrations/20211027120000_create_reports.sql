@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS 'reports'
+(
+  id          INTEGER PRIMARY KEY NOT NULL,
+  reporter_id INTEGER             NOT NULL,
+  reported_id INTEGER             NOT NULL,
+  reason      TEXT                NOT NULL,
+  context     TEXT                NOT NULL,
+  created_at  TEXT                NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY (reporter_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (reported_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
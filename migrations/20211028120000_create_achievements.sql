@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS 'achievements'
+(
+  id          TEXT PRIMARY KEY NOT NULL,
+  name        TEXT             NOT NULL,
+  description TEXT             NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS 'player_achievements'
+(
+  player_id      INTEGER NOT NULL,
+  achievement_id TEXT    NOT NULL,
+  earned_at      TEXT    NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (player_id, achievement_id),
+  FOREIGN KEY (player_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (achievement_id)
+    REFERENCES 'achievements' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
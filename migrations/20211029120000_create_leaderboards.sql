@@ -0,0 +1,29 @@
+ALTER TABLE players ADD COLUMN playtime INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS 'player_rooms'
+(
+  player_id INTEGER NOT NULL,
+  room_id   INTEGER NOT NULL,
+  PRIMARY KEY (player_id, room_id),
+  FOREIGN KEY (player_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (room_id)
+    REFERENCES 'rooms' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS 'leaderboards'
+(
+  category  TEXT    NOT NULL,
+  rank      INTEGER NOT NULL,
+  player_id INTEGER NOT NULL,
+  value     INTEGER NOT NULL,
+  PRIMARY KEY (category, rank),
+  FOREIGN KEY (player_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS 'sessions'
+(
+  id              INTEGER PRIMARY KEY NOT NULL,
+  player_id       INTEGER             NOT NULL,
+  connected_at    TEXT                NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  disconnected_at TEXT,
+  FOREIGN KEY (player_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
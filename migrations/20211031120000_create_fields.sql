@@ -0,0 +1,25 @@
+CREATE TABLE IF NOT EXISTS 'prototype_fields'
+(
+  prototype_id INTEGER NOT NULL,
+  key          TEXT    NOT NULL,
+  kind         TEXT    NOT NULL,
+  value        TEXT    NOT NULL,
+  UNIQUE(prototype_id, key),
+  FOREIGN KEY (prototype_id)
+    REFERENCES 'prototypes' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS 'object_fields'
+(
+  object_id INTEGER NOT NULL,
+  key       TEXT    NOT NULL,
+  kind      TEXT    NOT NULL,
+  value     TEXT    NOT NULL,
+  UNIQUE(object_id, key),
+  FOREIGN KEY (object_id)
+    REFERENCES 'objects' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS 'globals'
+(
+  key   TEXT PRIMARY KEY NOT NULL,
+  kind  TEXT             NOT NULL,
+  value TEXT             NOT NULL
+);
@@ -0,0 +1,48 @@
+CREATE TABLE IF NOT EXISTS 'spawn_tables'
+(
+  id            TEXT PRIMARY KEY NOT NULL,
+  cap           INTEGER          NOT NULL,
+  interval_secs INTEGER          NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS 'spawn_table_entries'
+(
+  table_id     TEXT    NOT NULL,
+  prototype_id INTEGER NOT NULL,
+  weight       INTEGER NOT NULL,
+  PRIMARY KEY (table_id, prototype_id),
+  FOREIGN KEY (table_id)
+    REFERENCES 'spawn_tables' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (prototype_id)
+    REFERENCES 'prototypes' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS 'spawn_table_rooms'
+(
+  table_id TEXT    NOT NULL,
+  room_id  INTEGER NOT NULL,
+  PRIMARY KEY (table_id, room_id),
+  FOREIGN KEY (table_id)
+    REFERENCES 'spawn_tables' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (room_id)
+    REFERENCES 'rooms' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS 'spawn_table_regions'
+(
+  table_id TEXT NOT NULL,
+  region   TEXT NOT NULL,
+  PRIMARY KEY (table_id, region),
+  FOREIGN KEY (table_id)
+    REFERENCES 'spawn_tables' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
@@ -0,0 +1,25 @@
+CREATE TABLE IF NOT EXISTS 'loot_tables'
+(
+  id    TEXT PRIMARY KEY NOT NULL,
+  rolls INTEGER          NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS 'loot_table_entries'
+(
+  table_id     TEXT    NOT NULL,
+  position     INTEGER NOT NULL,
+  prototype_id INTEGER,
+  weight       INTEGER NOT NULL,
+  min_quantity INTEGER NOT NULL,
+  max_quantity INTEGER NOT NULL,
+  rarity       TEXT    NOT NULL,
+  PRIMARY KEY (table_id, position),
+  FOREIGN KEY (table_id)
+    REFERENCES 'loot_tables' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (prototype_id)
+    REFERENCES 'prototypes' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
@@ -0,0 +1,16 @@
+ALTER TABLE players ADD COLUMN coins INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE players ADD COLUMN bank_balance INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS 'bank_objects'
+(
+  player_id INTEGER        NOT NULL,
+  object_id INTEGER UNIQUE NOT NULL,
+  FOREIGN KEY (player_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (object_id)
+    REFERENCES 'objects' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS 'market_listings'
+(
+  id         INTEGER PRIMARY KEY NOT NULL,
+  seller_id  INTEGER             NOT NULL,
+  object_id  INTEGER UNIQUE      NOT NULL,
+  price      INTEGER             NOT NULL,
+  expires_at INTEGER             NOT NULL,
+  FOREIGN KEY (seller_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (object_id)
+    REFERENCES 'objects' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
@@ -0,0 +1,29 @@
+CREATE TABLE IF NOT EXISTS 'clans'
+(
+  name       TEXT PRIMARY KEY NOT NULL COLLATE NOCASE,
+  founder_id INTEGER          NOT NULL,
+  hall_id    INTEGER,
+  FOREIGN KEY (founder_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (hall_id)
+    REFERENCES 'rooms' (id)
+      ON UPDATE NO ACTION
+      ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS 'clan_members'
+(
+  clan      TEXT           NOT NULL COLLATE NOCASE,
+  player_id INTEGER UNIQUE NOT NULL,
+  rank      TEXT           NOT NULL,
+  FOREIGN KEY (clan)
+    REFERENCES 'clans' (name)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (player_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
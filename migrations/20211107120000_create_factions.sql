@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS 'factions'
+(
+  id   TEXT PRIMARY KEY NOT NULL,
+  name TEXT             NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS 'player_reputation'
+(
+  player_id  INTEGER NOT NULL,
+  faction_id TEXT    NOT NULL,
+  reputation INTEGER NOT NULL DEFAULT 0,
+  PRIMARY KEY (player_id, faction_id),
+  FOREIGN KEY (player_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (faction_id)
+    REFERENCES 'factions' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
@@ -0,0 +1,39 @@
+CREATE TABLE IF NOT EXISTS 'campaigns'
+(
+  id    TEXT PRIMARY KEY NOT NULL,
+  name  TEXT             NOT NULL,
+  stage INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS 'campaign_stages'
+(
+  campaign_id  TEXT    NOT NULL,
+  position     INTEGER NOT NULL,
+  announcement TEXT    NOT NULL,
+  goal         TEXT,
+  PRIMARY KEY (campaign_id, position),
+  FOREIGN KEY (campaign_id)
+    REFERENCES 'campaigns' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS 'campaign_spawns'
+(
+  campaign_id  TEXT    NOT NULL,
+  position     INTEGER NOT NULL,
+  prototype_id INTEGER NOT NULL,
+  room_id      INTEGER NOT NULL,
+  FOREIGN KEY (campaign_id, position)
+    REFERENCES 'campaign_stages' (campaign_id, position)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (prototype_id)
+    REFERENCES 'prototypes' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (room_id)
+    REFERENCES 'rooms' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
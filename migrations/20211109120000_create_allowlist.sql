@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS 'allowlist'
+(
+  name TEXT PRIMARY KEY NOT NULL
+);
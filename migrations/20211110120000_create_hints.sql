@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS 'hints'
+(
+  trigger TEXT PRIMARY KEY NOT NULL,
+  message TEXT             NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS 'player_hints'
+(
+  player_id INTEGER NOT NULL,
+  trigger   TEXT    NOT NULL,
+  PRIMARY KEY (player_id, trigger),
+  FOREIGN KEY (player_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
@@ -0,0 +1 @@
+ALTER TABLE players ADD COLUMN pronouns TEXT NOT NULL DEFAULT 'they';
@@ -0,0 +1,4 @@
+ALTER TABLE player_scripts ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE room_scripts ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE prototype_scripts ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE object_scripts ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+ALTER TABLE scripts ADD COLUMN enabled BOOLEAN NOT NULL DEFAULT true;
+ALTER TABLE player_scripts ADD COLUMN enabled BOOLEAN NOT NULL DEFAULT true;
+ALTER TABLE room_scripts ADD COLUMN enabled BOOLEAN NOT NULL DEFAULT true;
+ALTER TABLE prototype_scripts ADD COLUMN enabled BOOLEAN NOT NULL DEFAULT true;
+ALTER TABLE object_scripts ADD COLUMN enabled BOOLEAN NOT NULL DEFAULT true;
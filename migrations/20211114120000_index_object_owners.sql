@@ -0,0 +1,3 @@
+CREATE INDEX IF NOT EXISTS objects_prototype_id ON objects (prototype_id);
+CREATE INDEX IF NOT EXISTS player_objects_player_id ON player_objects (player_id);
+CREATE INDEX IF NOT EXISTS bank_objects_player_id ON bank_objects (player_id);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS 'content_migrations'
+(
+  version    INTEGER PRIMARY KEY NOT NULL,
+  name       TEXT                NOT NULL,
+  applied_at TEXT                NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
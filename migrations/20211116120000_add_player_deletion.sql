@@ -0,0 +1 @@
+ALTER TABLE players ADD COLUMN delete_after INTEGER;
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS 'erasure_requests'
+(
+  player_id    INTEGER PRIMARY KEY NOT NULL,
+  requested_at TEXT                NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY (player_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
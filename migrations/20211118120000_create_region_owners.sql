@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS 'region_owners'
+(
+  region    TEXT PRIMARY KEY NOT NULL,
+  player_id INTEGER          NOT NULL,
+  FOREIGN KEY (player_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
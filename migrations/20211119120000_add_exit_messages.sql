@@ -0,0 +1 @@
+ALTER TABLE exits ADD COLUMN message TEXT;
@@ -0,0 +1,4 @@
+ALTER TABLE rooms ADD COLUMN leave_message TEXT;
+ALTER TABLE rooms ADD COLUMN arrive_message TEXT;
+ALTER TABLE exits ADD COLUMN leave_message TEXT;
+ALTER TABLE exits ADD COLUMN arrive_message TEXT;
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS 'prototype_behaviors'
+(
+  prototype_id INTEGER NOT NULL,
+  kind         TEXT    NOT NULL,
+  value        TEXT    NOT NULL,
+  UNIQUE(prototype_id, kind),
+  FOREIGN KEY (prototype_id)
+    REFERENCES 'prototypes' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
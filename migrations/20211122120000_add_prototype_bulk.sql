@@ -0,0 +1,2 @@
+ALTER TABLE prototypes ADD COLUMN weight INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE prototypes ADD COLUMN size INTEGER NOT NULL DEFAULT 0;
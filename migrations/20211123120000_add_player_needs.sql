@@ -0,0 +1,2 @@
+ALTER TABLE players ADD COLUMN hunger INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE players ADD COLUMN thirst INTEGER NOT NULL DEFAULT 0;
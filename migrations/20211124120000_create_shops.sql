@@ -0,0 +1,36 @@
+CREATE TABLE IF NOT EXISTS 'shops'
+(
+  id            TEXT PRIMARY KEY NOT NULL,
+  room_id       INTEGER UNIQUE   NOT NULL,
+  loot_table_id TEXT,
+  markup        INTEGER          NOT NULL,
+  cap           INTEGER          NOT NULL,
+  interval_secs INTEGER          NOT NULL,
+  sunk          INTEGER          NOT NULL DEFAULT 0,
+  FOREIGN KEY (room_id)
+    REFERENCES 'rooms' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (loot_table_id)
+    REFERENCES 'loot_tables' (id)
+      ON UPDATE NO ACTION
+      ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS 'shop_stock'
+(
+  shop_id      TEXT    NOT NULL,
+  position     INTEGER NOT NULL,
+  prototype_id INTEGER NOT NULL,
+  rarity       TEXT    NOT NULL,
+  quantity     INTEGER NOT NULL,
+  PRIMARY KEY (shop_id, position),
+  FOREIGN KEY (shop_id)
+    REFERENCES 'shops' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (prototype_id)
+    REFERENCES 'prototypes' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
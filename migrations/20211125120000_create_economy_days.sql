@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS 'economy_days'
+(
+  day       INTEGER NOT NULL,
+  source    TEXT    NOT NULL,
+  created   INTEGER NOT NULL DEFAULT 0,
+  destroyed INTEGER NOT NULL DEFAULT 0,
+  PRIMARY KEY (day, source)
+);
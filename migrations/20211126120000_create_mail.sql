@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS 'mail'
+(
+  id           INTEGER PRIMARY KEY NOT NULL,
+  sender_id    INTEGER             NOT NULL,
+  recipient_id INTEGER             NOT NULL,
+  body         TEXT                NOT NULL,
+  coins        INTEGER             NOT NULL DEFAULT 0,
+  object_id    INTEGER UNIQUE,
+  sent_at      INTEGER             NOT NULL,
+  expires_at   INTEGER             NOT NULL,
+  read         INTEGER             NOT NULL DEFAULT 0,
+  FOREIGN KEY (sender_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (recipient_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (object_id)
+    REFERENCES 'objects' (id)
+      ON UPDATE NO ACTION
+      ON DELETE SET NULL
+);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS 'news'
+(
+  id        INTEGER PRIMARY KEY NOT NULL,
+  author    TEXT                NOT NULL,
+  body      TEXT                NOT NULL,
+  posted_at INTEGER             NOT NULL
+);
+
+ALTER TABLE players ADD COLUMN news_read INTEGER NOT NULL DEFAULT 0;
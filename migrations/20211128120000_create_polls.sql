@@ -0,0 +1,36 @@
+CREATE TABLE IF NOT EXISTS 'polls'
+(
+  id         INTEGER PRIMARY KEY NOT NULL,
+  question   TEXT                NOT NULL,
+  author     TEXT                NOT NULL,
+  created_at INTEGER             NOT NULL,
+  closed     INTEGER             NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS 'poll_options'
+(
+  poll_id  INTEGER NOT NULL,
+  position INTEGER NOT NULL,
+  text     TEXT    NOT NULL,
+  PRIMARY KEY (poll_id, position),
+  FOREIGN KEY (poll_id)
+    REFERENCES 'polls' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS 'poll_votes'
+(
+  poll_id   INTEGER NOT NULL,
+  player_id INTEGER NOT NULL,
+  position  INTEGER NOT NULL,
+  PRIMARY KEY (poll_id, player_id),
+  FOREIGN KEY (poll_id)
+    REFERENCES 'polls' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE,
+  FOREIGN KEY (player_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
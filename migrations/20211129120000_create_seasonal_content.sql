@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS 'seasonal_content'
+(
+  kind   TEXT NOT NULL,
+  target TEXT NOT NULL,
+  season TEXT NOT NULL,
+  PRIMARY KEY (kind, target)
+);
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS 'communications'
+(
+  id        INTEGER PRIMARY KEY NOT NULL,
+  channel   TEXT                NOT NULL,
+  player_id INTEGER             NOT NULL,
+  name      TEXT                NOT NULL,
+  room_id   INTEGER,
+  target    TEXT,
+  text      TEXT                NOT NULL,
+  said_at   INTEGER             NOT NULL,
+  FOREIGN KEY (player_id)
+    REFERENCES 'players' (id)
+      ON UPDATE NO ACTION
+      ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS communications_said_at ON communications (said_at);
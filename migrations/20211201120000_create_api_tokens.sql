@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS 'api_tokens'
+(
+  id           INTEGER PRIMARY KEY NOT NULL,
+  name         TEXT                NOT NULL UNIQUE,
+  hash         TEXT                NOT NULL,
+  scopes       TEXT                NOT NULL,
+  created_by   TEXT                NOT NULL,
+  created_at   INTEGER             NOT NULL,
+  last_used_at INTEGER
+);
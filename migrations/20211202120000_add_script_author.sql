@@ -0,0 +1 @@
+ALTER TABLE scripts ADD COLUMN author_id INTEGER REFERENCES players (id) ON DELETE SET NULL;
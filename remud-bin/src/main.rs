@@ -1,21 +1,251 @@
 use std::{
     env,
     fs::create_dir_all,
+    io::{self, Write},
     path::{Path, PathBuf},
 };
 
 use anyhow::bail;
-use clap::{App, Arg, ArgMatches};
-use remud_lib::{run_remud, TlsOptions, WebOptions};
+use clap::{App, AppSettings, Arg, ArgMatches};
+use remud_lib::{
+    admin, run_remud, Clock, Config, LogChannel, LoggingConfig, MigrationOptions, ProfileLayer,
+    TlsOptions, WebOptions,
+};
+use tracing::Subscriber;
+use tracing_appender::{non_blocking::WorkerGuard, rolling};
+use tracing_subscriber::{
+    fmt::MakeWriter,
+    layer::{Layer, SubscriberExt},
+    registry::LookupSpan,
+    util::SubscriberInitExt,
+    EnvFilter,
+};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt::init();
-
     let matches = App::new("ReMUD")
         .version("0.1")
         .author("Shaen & krixi - https://github.com/siler/remud")
         .about("A MUD in Rust.")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::new("log-format")
+                .long("log-format")
+                .default_value("pretty")
+                .possible_values(&["pretty", "json"])
+                .about(
+                    "Sets the log output format. JSON logs carry the client, player and action \
+                     being handled as structured span fields.",
+                )
+                .takes_value(true),
+        )
+        .subcommand(
+            App::new("check-db")
+                .about(
+                    "Reports dangling references to missing prototypes, rooms and scripts in the \
+                     database.",
+                )
+                .arg(db_arg())
+                .arg(
+                    Arg::new("repair")
+                        .long("repair")
+                        .about("Deletes the dangling references which were found."),
+                ),
+        )
+        .subcommand(
+            App::new("create-admin")
+                .about("Creates an immortal player. The password is read from standard input.")
+                .arg(db_arg())
+                .arg(
+                    Arg::new("name")
+                        .about("The name of the player to create.")
+                        .required(true)
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            App::new("export-world")
+                .about("Writes the world's rooms, objects, prototypes and scripts to a JSON file.")
+                .arg(db_arg())
+                .arg(
+                    Arg::new("file")
+                        .about("The file to write the world to.")
+                        .required(true)
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            App::new("import-world")
+                .about(
+                    "Replaces the world's content with a JSON file written by export-world. The \
+                     database must not have any players yet.",
+                )
+                .arg(db_arg())
+                .arg(
+                    Arg::new("file")
+                        .about("The file to read the world from.")
+                        .required(true)
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            App::new("reset-password")
+                .about("Sets a player's password. The password is read from standard input.")
+                .arg(db_arg())
+                .arg(
+                    Arg::new("name")
+                        .about("The name of the player whose password to set.")
+                        .required(true)
+                        .index(1),
+                ),
+        )
+        .subcommand(serve_app())
+        .get_matches();
+
+    // Log files are configured in the config file, so it is read once here before the server
+    // reads it again at startup.
+    let logging = match matches.subcommand() {
+        Some(("serve", matches)) => match matches.value_of("config") {
+            Some(config) => Config::load(Path::new(config))?.logging,
+            None => LoggingConfig::default(),
+        },
+        _ => LoggingConfig::default(),
+    };
+    let _guards = init_tracing(matches.value_of("log-format").unwrap(), &logging)?;
+
+    match matches.subcommand() {
+        Some(("check-db", matches)) => {
+            let db = parse_db_path(matches)?;
+            let repair = matches.is_present("repair");
+
+            let issues = admin::check_db(db, repair).await?;
+            for issue in issues.iter() {
+                println!("{}", issue);
+            }
+            println!(
+                "{} {} issues.",
+                if repair { "Repaired" } else { "Found" },
+                issues.len()
+            );
+        }
+        Some(("create-admin", matches)) => {
+            let db = parse_db_path(matches)?;
+            let name = matches.value_of("name").unwrap();
+            let password = read_password()?;
+
+            admin::create_admin(db, name, password.as_str()).await?;
+            println!("Created immortal {}.", name);
+        }
+        Some(("export-world", matches)) => {
+            let db = parse_db_path(matches)?;
+            let file = Path::new(matches.value_of("file").unwrap());
+
+            let rows = admin::export_world(db, file).await?;
+            println!("Exported {} rows to {}.", rows, file.display());
+        }
+        Some(("import-world", matches)) => {
+            let db = parse_db_path(matches)?;
+            let file = Path::new(matches.value_of("file").unwrap());
+
+            let rows = admin::import_world(db, file).await?;
+            println!("Imported {} rows from {}.", rows, file.display());
+        }
+        Some(("reset-password", matches)) => {
+            let db = parse_db_path(matches)?;
+            let name = matches.value_of("name").unwrap();
+            let password = read_password()?;
+
+            admin::reset_password(db, name, password.as_str()).await?;
+            println!("Reset the password of {}.", name);
+        }
+        Some(("serve", matches)) => serve(matches).await?,
+        _ => unreachable!("clap requires a subcommand"),
+    }
+
+    Ok(())
+}
+
+// Sets up logging to standard output and, when the config file names a log directory, to daily
+// rotated engine and game log files in it. The returned guards flush the files when dropped.
+fn init_tracing(format: &str, logging: &LoggingConfig) -> anyhow::Result<Vec<WorkerGuard>> {
+    let json = format == "json";
+    let mut guards = Vec::new();
+
+    let (engine, game) = match &logging.directory {
+        Some(directory) => {
+            if let Err(e) = create_dir_all(directory) {
+                bail!("failed to create log directory {:?}: {}", directory, e);
+            }
+
+            let (engine, guard) = tracing_appender::non_blocking(rolling::daily(
+                directory,
+                logging.engine_file.as_str(),
+            ));
+            guards.push(guard);
+
+            let (game, guard) = tracing_appender::non_blocking(rolling::daily(
+                directory,
+                logging.game_file.as_str(),
+            ));
+            guards.push(guard);
+
+            (
+                Some(LogChannel::engine(fmt_layer(json, engine, false))),
+                Some(LogChannel::game(fmt_layer(json, game, false))),
+            )
+        }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(EnvFilter::from_default_env())
+        .with(fmt_layer(json, io::stdout, true))
+        .with(engine)
+        .with(game)
+        .with(ProfileLayer::default())
+        .init();
+
+    Ok(guards)
+}
+
+fn fmt_layer<S, W>(json: bool, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+    W: MakeWriter + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(ansi);
+
+    if json {
+        Box::new(layer.json().with_current_span(true).with_span_list(true))
+    } else {
+        Box::new(layer)
+    }
+}
+
+fn serve_app() -> App<'static> {
+    App::new("serve")
+        .about("Runs the ReMUD server.")
+        .arg(
+            Arg::new("acme-port")
+                .long("acme-port")
+                .default_value("80")
+                .about(
+                    "Sets the port used to answer Let's Encrypt HTTP-01 challenges when \
+                     requesting or renewing TLS certificates.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .about(
+                    "Loads settings from the specified TOML file. The file is re-read when ReMUD \
+                     receives SIGHUP.",
+                )
+                .takes_value(true),
+        )
         .arg(
             Arg::new("cors")
                 .short('c')
@@ -23,14 +253,7 @@ async fn main() -> anyhow::Result<()> {
                 .about("Specify which domains should be allowed origins via CORS.")
                 .takes_value(true),
         )
-        .arg(
-            Arg::new("db")
-                .short('d')
-                .long("db")
-                .default_value("./world.db")
-                .about("Sets the database file path.")
-                .takes_value(true),
-        )
+        .arg(db_arg())
         .arg(
             Arg::new("email")
                 .short('e')
@@ -41,6 +264,14 @@ async fn main() -> anyhow::Result<()> {
                 )
                 .takes_value(true),
         )
+        .arg(
+            Arg::new("dry-run-migrations")
+                .long("dry-run-migrations")
+                .about(
+                    "Reports what pending content migrations would change in the database, then \
+                     exits without changing anything.",
+                ),
+        )
         .arg(Arg::new("in-memory").long("in-memory").about(
             "Runs ReMUD with an in-memory SQLite database - all data will be lost when the \
              program is closed",
@@ -53,6 +284,11 @@ async fn main() -> anyhow::Result<()> {
                 .about("Sets the key storage path.")
                 .takes_value(true),
         )
+        .arg(
+            Arg::new("no-backup")
+                .long("no-backup")
+                .about("Skips backing up the database before applying pending content migrations."),
+        )
         .arg(
             Arg::new("telnet")
                 .short('t')
@@ -76,10 +312,21 @@ async fn main() -> anyhow::Result<()> {
                 .about("Sets the web API port.")
                 .takes_value(true),
         )
-        .get_matches();
+}
+
+fn db_arg() -> Arg<'static> {
+    Arg::new("db")
+        .short('d')
+        .long("db")
+        .default_value("./world.db")
+        .about("Sets the database file path.")
+        .takes_value(true)
+}
 
-    let db = parse_db(&matches)?;
-    let keys = parse_keys(&matches)?;
+async fn serve(matches: &ArgMatches) -> anyhow::Result<()> {
+    let config = matches.value_of("config").map(Path::new);
+    let db = parse_db(matches)?;
+    let keys = parse_keys(matches)?;
 
     let telnet = parse_port(matches.value_of("telnet").unwrap())?;
     let web = parse_port(matches.value_of("web").unwrap())?;
@@ -89,9 +336,14 @@ async fn main() -> anyhow::Result<()> {
         .map(|domains| domains.split(',').collect())
         .unwrap_or_else(Vec::new);
 
-    let tls = parse_tls(&matches)?;
+    let tls = parse_tls(matches)?;
     let web = WebOptions::new(web, keys, cors, tls);
 
+    let migrations = MigrationOptions {
+        dry_run: matches.is_present("dry-run-migrations"),
+        backup: !matches.is_present("no-backup"),
+    };
+
     let cwd = env::current_dir();
     let dir = match &cwd {
         Ok(path) => path.to_str(),
@@ -102,12 +354,15 @@ async fn main() -> anyhow::Result<()> {
         Some(dir) => tracing::info!("running ReMUD from {:?} with:", dir),
         None => tracing::info!("running Remud with:"),
     }
+    if let Some(config) = config {
+        tracing::info!("  config: {:?}", config);
+    }
     tracing::info!("  database: {}", db.unwrap_or("in-memory"));
     tracing::info!("  telnet: {}", format!("0.0.0.0:{}", telnet));
     tracing::info!("  web: {}", web.uri());
     tracing::info!("  cors: {:?}", web.cors());
 
-    run_remud(db, telnet, web, None).await?;
+    run_remud(db, config, telnet, web, migrations, Clock::system(), None).await?;
 
     Ok(())
 }
@@ -116,30 +371,34 @@ fn parse_db(matches: &ArgMatches) -> anyhow::Result<Option<&str>> {
     if matches.is_present("in-memory") {
         Ok(None)
     } else {
-        let path_str = matches.value_of("db").unwrap();
+        parse_db_path(matches).map(Some)
+    }
+}
 
-        let path = PathBuf::from(path_str);
+fn parse_db_path(matches: &ArgMatches) -> anyhow::Result<&str> {
+    let path_str = matches.value_of("db").unwrap();
 
-        // Validate the database path, creating directories if necessary.
-        if path.is_dir() {
-            bail!("parameter 'db' must be a filename, not a directory.");
-        }
+    let path = PathBuf::from(path_str);
 
-        if let Some(parent) = path.parent() {
-            if !parent.exists() {
-                if let Err(e) = create_dir_all(parent) {
-                    bail!("failed to create directory path for database path: {}", e);
-                }
+    // Validate the database path, creating directories if necessary.
+    if path.is_dir() {
+        bail!("parameter 'db' must be a filename, not a directory.");
+    }
+
+    if let Some(parent) = path.parent() {
+        if !parent.exists() {
+            if let Err(e) = create_dir_all(parent) {
+                bail!("failed to create directory path for database path: {}", e);
             }
-        } else {
-            bail!(
-                "unable to determine parent directory of database path: {:?}",
-                path.as_os_str()
-            );
         }
-
-        Ok(Some(path_str))
+    } else {
+        bail!(
+            "unable to determine parent directory of database path: {:?}",
+            path.as_os_str()
+        );
     }
+
+    Ok(path_str)
 }
 
 fn parse_keys(matches: &ArgMatches) -> anyhow::Result<&Path> {
@@ -170,9 +429,28 @@ fn parse_port(port: &str) -> anyhow::Result<u16> {
     Ok(port)
 }
 
+fn read_password() -> anyhow::Result<String> {
+    eprint!("Password: ");
+    io::stderr().flush()?;
+
+    let mut password = String::new();
+    io::stdin().read_line(&mut password)?;
+
+    Ok(password.trim_end_matches(&['\r', '\n'][..]).to_string())
+}
+
 fn parse_tls(matches: &ArgMatches) -> anyhow::Result<Option<TlsOptions>> {
     match (matches.value_of("tls"), matches.value_of("email")) {
-        (Some(domain), Some(email)) => Ok(Some(TlsOptions::new(domain, email))),
+        (Some(domain), Some(email)) => {
+            let challenge_port = match matches.value_of("acme-port").unwrap().parse::<u16>() {
+                Ok(port) => port,
+                Err(_) => bail!("--acme-port should be an integer between 1 and 65,535 inclusive."),
+            };
+
+            Ok(Some(
+                TlsOptions::new(domain, email).with_challenge_port(challenge_port),
+            ))
+        }
         (None, None) => Ok(None),
         _ => {
             bail!("--tls and --email must be set together");
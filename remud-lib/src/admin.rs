@@ -0,0 +1,105 @@
+//! Administrative tasks which operate directly on a world database, without starting the server.
+
+use std::{fs, path::Path};
+
+use thiserror::Error;
+
+pub use crate::engine::db::check::Issue;
+
+use crate::engine::{
+    db::{self, export::WorldExport, AuthDb, Db, GameDb},
+    fsm::hash_input,
+    name_valid,
+};
+
+const MIN_PASSWORD_LEN: usize = 5;
+const MAX_PASSWORD_LEN: usize = 1024;
+
+#[derive(Debug, Error)]
+pub enum AdminError {
+    #[error("database error: {0}")]
+    Db(#[from] db::Error),
+    #[error("{0}")]
+    Other(#[from] anyhow::Error),
+    #[error("invalid player name: {0}")]
+    InvalidName(String),
+    #[error(
+        "passwords must be between {} and {} characters",
+        MIN_PASSWORD_LEN,
+        MAX_PASSWORD_LEN
+    )]
+    InvalidPassword,
+    #[error("player {0} already exists")]
+    PlayerExists(String),
+    #[error("player {0} does not exist")]
+    PlayerNotFound(String),
+    #[error("failed to access world file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to read world file: {0}")]
+    Format(#[from] serde_json::Error),
+}
+
+/// Creates an immortal administrator, for setting up a new world or recovering one without any.
+pub async fn create_admin(db_path: &str, name: &str, password: &str) -> Result<i64, AdminError> {
+    if !name_valid(name) {
+        return Err(AdminError::InvalidName(name.to_string()));
+    }
+    let hash = hash_password(password)?;
+
+    let db = Db::new(Some(db_path)).await?;
+    if db.has_player(name).await? {
+        return Err(AdminError::PlayerExists(name.to_string()));
+    }
+
+    Ok(db.create_immortal(name, hash.as_str()).await?)
+}
+
+/// Sets a player's password, for players who can no longer log in to change it themselves.
+pub async fn reset_password(db_path: &str, name: &str, password: &str) -> Result<(), AdminError> {
+    let hash = hash_password(password)?;
+
+    let db = Db::new(Some(db_path)).await?;
+    if !db.has_player(name).await? {
+        return Err(AdminError::PlayerNotFound(name.to_string()));
+    }
+
+    db.update_password(name, hash.as_str()).await?;
+    db.logout(name).await?;
+
+    Ok(())
+}
+
+/// Writes the world's content to a JSON file, returning how many rows were written.
+pub async fn export_world(db_path: &str, path: &Path) -> Result<usize, AdminError> {
+    let db = Db::new(Some(db_path)).await?;
+    let export = db.export_world().await?;
+
+    fs::write(path, serde_json::to_string_pretty(&export)?)?;
+
+    Ok(export.rows())
+}
+
+/// Replaces the world's content with a JSON file written by `export_world`, returning how many
+/// rows were read.
+pub async fn import_world(db_path: &str, path: &Path) -> Result<usize, AdminError> {
+    let export: WorldExport = serde_json::from_str(fs::read_to_string(path)?.as_str())?;
+
+    let db = Db::new(Some(db_path)).await?;
+    db.import_world(&export).await?;
+
+    Ok(export.rows())
+}
+
+/// Finds dangling references to missing prototypes, rooms and scripts, deleting them if asked.
+pub async fn check_db(db_path: &str, repair: bool) -> Result<Vec<Issue>, AdminError> {
+    let db = Db::new(Some(db_path)).await?;
+    Ok(db.check_integrity(repair).await?)
+}
+
+fn hash_password(password: &str) -> Result<String, AdminError> {
+    if password.len() < MIN_PASSWORD_LEN || password.len() > MAX_PASSWORD_LEN {
+        return Err(AdminError::InvalidPassword);
+    }
+
+    Ok(hash_input(password)?)
+}
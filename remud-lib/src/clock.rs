@@ -0,0 +1,133 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// The source of the current time for the engine and web server. Servers normally follow the
+/// system clock. A manual clock stands still until it is advanced, so tests can run timers and
+/// expire tokens without waiting.
+#[derive(Debug, Clone, Default)]
+pub struct Clock {
+    manual: Option<Arc<ManualTime>>,
+}
+
+// The time a manual clock was started at, and how far it has been advanced since.
+#[derive(Debug)]
+struct ManualTime {
+    instant: Instant,
+    system: SystemTime,
+    elapsed: Mutex<Duration>,
+}
+
+impl ManualTime {
+    fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap()
+    }
+}
+
+impl Clock {
+    pub fn system() -> Self {
+        Clock::default()
+    }
+
+    /// A clock which starts at the current time and only moves when advanced.
+    pub fn manual() -> Self {
+        Clock {
+            manual: Some(Arc::new(ManualTime {
+                instant: Instant::now(),
+                system: SystemTime::now(),
+                elapsed: Mutex::new(Duration::ZERO),
+            })),
+        }
+    }
+
+    /// Moves a manual clock forward. The system clock cannot be moved.
+    pub fn advance(&self, duration: Duration) {
+        match &self.manual {
+            Some(manual) => *manual.elapsed.lock().unwrap() += duration,
+            None => tracing::warn!("attempted to advance the system clock"),
+        }
+    }
+
+    pub fn now(&self) -> Instant {
+        match &self.manual {
+            Some(manual) => manual.instant + manual.elapsed(),
+            None => Instant::now(),
+        }
+    }
+
+    pub fn system_time(&self) -> SystemTime {
+        match &self.manual {
+            Some(manual) => manual.system + manual.elapsed(),
+            None => SystemTime::now(),
+        }
+    }
+
+    /// The current time, in seconds since the Unix epoch.
+    pub fn unix_now(&self) -> i64 {
+        self.system_time()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default()
+    }
+
+    /// How far this clock has moved ahead of the system clock.
+    pub fn skew(&self) -> Duration {
+        self.system_time()
+            .duration_since(SystemTime::now())
+            .unwrap_or_default()
+    }
+}
+
+/// The time at the start of the current tick, read from the clock once per tick so every system
+/// sees the same time.
+#[derive(Debug, Default)]
+pub struct Time {
+    now: Option<Instant>,
+    delta: Duration,
+}
+
+impl Time {
+    pub fn update(&mut self, now: Instant) {
+        if let Some(last) = self.now {
+            self.delta = now.saturating_duration_since(last);
+        }
+        self.now = Some(now);
+    }
+
+    /// The time elapsed since the previous tick.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock() {
+        let clock = Clock::manual();
+        let instant = clock.now();
+        let unix = clock.unix_now();
+
+        assert_eq!(clock.now(), instant);
+
+        clock.clone().advance(Duration::from_secs(90));
+        assert_eq!(clock.now(), instant + Duration::from_secs(90));
+        assert_eq!(clock.unix_now(), unix + 90);
+        assert!(clock.skew() >= Duration::from_secs(89));
+    }
+
+    #[test]
+    fn test_time_delta() {
+        let start = Instant::now();
+        let mut time = Time::default();
+
+        time.update(start);
+        assert_eq!(time.delta(), Duration::ZERO);
+
+        time.update(start + Duration::from_millis(250));
+        assert_eq!(time.delta(), Duration::from_millis(250));
+    }
+}
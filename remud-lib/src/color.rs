@@ -8,6 +8,7 @@ use std::{collections::HashMap, num::ParseIntError, str::FromStr};
 // https://github.com/tmux/tmux/blob/8554b80b8b9e70b641847a8534af6d5fbc1a39c7/colour.c
 
 pub const CLEAR_COLOR: &str = "\x1b[m";
+const DEFAULT_FOREGROUND: &str = "\x1b[39m";
 
 pub static COLOR_TAG_MATCHER: Lazy<Regex> = Lazy::new(|| {
     Regex::new(
@@ -15,7 +16,7 @@ pub static COLOR_TAG_MATCHER: Lazy<Regex> = Lazy::new(|| {
     ).unwrap()
 });
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ColorSupport {
     None,
     Colors16,
@@ -24,6 +25,15 @@ pub enum ColorSupport {
 }
 
 impl ColorSupport {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColorSupport::None => "none",
+            ColorSupport::Colors16 => "16",
+            ColorSupport::Colors256 => "256",
+            ColorSupport::TrueColor => "truecolor",
+        }
+    }
+
     fn supports_color(&self) -> bool {
         match self {
             ColorSupport::None => false,
@@ -49,6 +59,46 @@ impl ColorSupport {
     }
 }
 
+/// Text styles, started with tags such as `|bold|` and ended with `|-|` like colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Style {
+    Bold,
+    Italic,
+    Underline,
+}
+
+impl Style {
+    fn on(&self) -> &'static str {
+        match self {
+            Style::Bold => "\x1b[1m",
+            Style::Italic => "\x1b[3m",
+            Style::Underline => "\x1b[4m",
+        }
+    }
+
+    fn off(&self) -> &'static str {
+        match self {
+            Style::Bold => "\x1b[22m",
+            Style::Italic => "\x1b[23m",
+            Style::Underline => "\x1b[24m",
+        }
+    }
+}
+
+impl FromStr for Style {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bold" => Ok(Style::Bold),
+            "italic" => Ok(Style::Italic),
+            "underline" => Ok(Style::Underline),
+            _ => Err(()),
+        }
+    }
+}
+
 pub fn colorize_telnet(message: &str, color_support: ColorSupport) -> String {
     let mut closed = true;
     let replacer = TelnetReplacer::new(color_support, &mut closed);
@@ -59,9 +109,17 @@ pub fn colorize_telnet(message: &str, color_support: ColorSupport) -> String {
     message
 }
 
+// An open tag. Colors the client cannot display are kept as None so that their closing tags
+// still match up.
+#[derive(Debug, Clone, Copy)]
+enum OpenTag {
+    Color(Option<Color>),
+    Style(Style),
+}
+
 struct TelnetReplacer<'a> {
     color_support: ColorSupport,
-    stack: Vec<Option<Color>>,
+    stack: Vec<OpenTag>,
     closed: &'a mut bool,
 }
 
@@ -82,51 +140,76 @@ impl<'a> Replacer for TelnetReplacer<'a> {
         } else if let Some(m) = caps.name("byte") {
             if let Ok(color) = Color256::from_str(m.as_str()) {
                 if let Some(color) = self.color_support.supported_from_256(color) {
-                    self.stack.push(Some(color));
+                    self.stack.push(OpenTag::Color(Some(color)));
                     dst.push_str(color.to_string().as_str());
                     *self.closed = false;
                 }
             } else {
-                self.stack.push(None);
+                self.stack.push(OpenTag::Color(None));
                 tracing::warn!("failed to capture matched 256 color: {}", m.as_str());
             }
         } else if let Some(m) = caps.name("true") {
             if let Ok(color) = ColorTrue::from_str(m.as_str()) {
                 if let Some(color) = self.color_support.supported_from_true(color) {
-                    self.stack.push(Some(color));
+                    self.stack.push(OpenTag::Color(Some(color)));
                     dst.push_str(color.to_string().as_str());
                     *self.closed = false;
                 } else {
-                    self.stack.push(None);
+                    self.stack.push(OpenTag::Color(None));
                 }
             } else {
-                self.stack.push(None);
+                self.stack.push(OpenTag::Color(None));
                 tracing::warn!("failed to capture matched true color: {}", m.as_str());
             }
         } else if let Some(name) = caps.name("name") {
-            if let Some(index) = COLOR_NAME_MAP.get(name.as_str().to_lowercase().as_str()) {
+            if let Ok(style) = Style::from_str(name.as_str()) {
+                if self.color_support.supports_color() {
+                    self.stack.push(OpenTag::Style(style));
+                    dst.push_str(style.on());
+                    *self.closed = false;
+                }
+            } else if let Some(index) = COLOR_NAME_MAP.get(name.as_str().to_lowercase().as_str()) {
                 if let Some(color) = self.color_support.supported_from_256(Color256::new(*index)) {
-                    self.stack.push(Some(color));
+                    self.stack.push(OpenTag::Color(Some(color)));
                     dst.push_str(color.to_string().as_str());
                     *self.closed = false;
                 } else {
-                    self.stack.push(None)
+                    self.stack.push(OpenTag::Color(None))
                 }
             } else {
-                self.stack.push(None)
+                self.stack.push(OpenTag::Color(None))
             }
         } else if caps.name("clear").is_some() {
-            if self.color_support.supports_color() && !self.stack.is_empty() {
-                self.stack.pop();
-
-                if let Some(color) = self.stack.last() {
-                    if let Some(color) = color {
-                        // Only resume coloring if there is a previous color and the opening color tag was valid
-                        dst.push_str(color.to_string().as_str())
+            if self.color_support.supports_color() {
+                if let Some(tag) = self.stack.pop() {
+                    if self.stack.is_empty() {
+                        *self.closed = true;
+                        dst.push_str(CLEAR_COLOR);
+                    } else {
+                        match tag {
+                            OpenTag::Color(_) => {
+                                let previous = self.stack.iter().rev().find_map(|tag| match tag {
+                                    OpenTag::Color(color) => Some(color),
+                                    OpenTag::Style(_) => None,
+                                });
+
+                                match previous {
+                                    // Only resume coloring if the opening color tag was valid
+                                    Some(Some(color)) => dst.push_str(color.to_string().as_str()),
+                                    Some(None) => (),
+                                    None => dst.push_str(DEFAULT_FOREGROUND),
+                                }
+                            }
+                            OpenTag::Style(style) => {
+                                // Nested tags of the same style keep it on
+                                if !self.stack.iter().any(
+                                    |tag| matches!(tag, OpenTag::Style(open) if *open == style),
+                                ) {
+                                    dst.push_str(style.off());
+                                }
+                            }
+                        }
                     }
-                } else {
-                    *self.closed = true;
-                    dst.push_str(CLEAR_COLOR);
                 }
             }
         } else {
@@ -815,6 +898,33 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_replacer_styles() {
+        let mut closed = true;
+        let replacer = TelnetReplacer::new(ColorSupport::Colors256, &mut closed);
+        let result = COLOR_TAG_MATCHER.replace_all("|bold|some |48|text|-| here|-|", replacer);
+        assert_eq!(
+            result,
+            Cow::from("\x1b[1msome \x1b[38;5;48mtext\x1b[39m here\x1b[m")
+        );
+
+        let mut closed = true;
+        let replacer = TelnetReplacer::new(ColorSupport::Colors256, &mut closed);
+        let result = COLOR_TAG_MATCHER.replace_all("|48|some |underline|text|-| here|-|", replacer);
+        assert_eq!(
+            result,
+            Cow::from("\x1b[38;5;48msome \x1b[4mtext\x1b[24m here\x1b[m")
+        );
+    }
+
+    #[test]
+    fn test_replacer_removes_styles() {
+        let mut closed = true;
+        let replacer = TelnetReplacer::new(ColorSupport::None, &mut closed);
+        let result = COLOR_TAG_MATCHER.replace_all("|bold|some |italic|text|-||-|", replacer);
+        assert_eq!(result, Cow::from("some text"));
+    }
+
     #[test]
     fn test_replacer_extra_close() {
         let mut closed = true;
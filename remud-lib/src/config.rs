@@ -0,0 +1,672 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::time::Duration;
+
+use crate::{
+    web::RateLimitOptions,
+    world::types::season::{parse_season_name, SeasonWindow},
+};
+
+const DEFAULT_TICK_MS: u64 = 15;
+const DEFAULT_METRICS_MS: u64 = 1000;
+const DEFAULT_LEADERBOARD_SECS: u64 = 300;
+const DEFAULT_LINT_SECS: u64 = 86_400;
+const DEFAULT_AUTOSAVE_SECS: u64 = 60;
+const DEFAULT_KEEPALIVE_SECS: u64 = 60;
+const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 3600;
+const DEFAULT_HTTP_TIMEOUT_MS: u64 = 5000;
+const DEFAULT_HTTP_MAX_BYTES: usize = 65536;
+const DEFAULT_SLOW_SCRIPT_MS: u64 = 5;
+const DEFAULT_LISTING_SECS: u64 = 259_200;
+const DEFAULT_MAIL_EXPIRY_SECS: u64 = 2_592_000;
+const DEFAULT_REJECTION: &str = "City Six is closed for testing.";
+const DEFAULT_ENGINE_LOG: &str = "engine.log";
+const DEFAULT_GAME_LOG: &str = "game.log";
+const DEFAULT_STALL_SECS: u64 = 30;
+const DEFAULT_RESUME_SECS: u64 = 60;
+const DEFAULT_AUTH_PER_MINUTE: u32 = 10;
+const DEFAULT_AUTH_BURST: u32 = 10;
+const DEFAULT_WRITE_PER_MINUTE: u32 = 120;
+const DEFAULT_WRITE_BURST: u32 = 60;
+const DEFAULT_DELETION_SECS: u64 = 604_800;
+const DEFAULT_RELOG_SECS: u64 = 300;
+const DEFAULT_RELOG_PLAYERS: usize = 64;
+const DEFAULT_SNOOP_SECS: u64 = 600;
+const DEFAULT_COMMUNICATION_RETENTION_SECS: u64 = 2_592_000;
+const DEFAULT_NEEDS_SECS: u64 = 600;
+const DEFAULT_REGION_IDLE_SECS: u64 = 300;
+const DEFAULT_METRICS_PORT: u16 = 8125;
+pub(crate) const DEFAULT_METRICS_PREFIX: &str = "remud";
+const DEFAULT_MAINTENANCE: &str = "City Six is down for maintenance. Please try again later.";
+
+/// Settings which can be changed while ReMUD is running. These are read from an optional TOML
+/// file at startup and re-read when the server receives SIGHUP or an immortal runs
+/// `config reload`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub motd: Option<String>,
+    pub art_dir: Option<String>,
+    pub engine: EngineConfig,
+    pub telnet: TelnetConfig,
+    pub login: LoginConfig,
+    pub web: WebConfig,
+    pub metrics: MetricsConfig,
+    pub scripting: ScriptingConfig,
+    pub market: MarketConfig,
+    pub mail: MailConfig,
+    pub needs: NeedsConfig,
+    pub regions: RegionsConfig,
+    pub moderation: ModerationConfig,
+    pub logging: LoggingConfig,
+    pub watchdog: WatchdogConfig,
+    pub seasons: BTreeMap<String, SeasonConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EngineConfig {
+    pub tick_ms: u64,
+    pub metrics_ms: u64,
+    pub leaderboard_secs: u64,
+    pub lint_secs: u64,
+    // the most player progress which can be lost in a crash
+    pub autosave_secs: u64,
+    // players beyond this wait in a queue to log in, immortals excepted
+    pub max_players: Option<usize>,
+}
+
+impl EngineConfig {
+    pub fn tick_rate(&self) -> Duration {
+        Duration::from_millis(self.tick_ms)
+    }
+
+    pub fn metrics_rate(&self) -> Duration {
+        Duration::from_millis(self.metrics_ms)
+    }
+
+    pub fn leaderboard_rate(&self) -> Duration {
+        Duration::from_secs(self.leaderboard_secs)
+    }
+
+    pub fn lint_rate(&self) -> Duration {
+        Duration::from_secs(self.lint_secs)
+    }
+
+    pub fn autosave_rate(&self) -> Duration {
+        Duration::from_secs(self.autosave_secs)
+    }
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        EngineConfig {
+            tick_ms: DEFAULT_TICK_MS,
+            metrics_ms: DEFAULT_METRICS_MS,
+            leaderboard_secs: DEFAULT_LEADERBOARD_SECS,
+            lint_secs: DEFAULT_LINT_SECS,
+            autosave_secs: DEFAULT_AUTOSAVE_SECS,
+            max_players: None,
+        }
+    }
+}
+
+/// Connection upkeep for telnet clients. Changes apply to clients which connect after the
+/// configuration is reloaded.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelnetConfig {
+    pub keepalive_secs: u64,
+    pub idle_timeout_secs: u64,
+}
+
+impl TelnetConfig {
+    pub fn keepalive_rate(&self) -> Duration {
+        Duration::from_secs(self.keepalive_secs)
+    }
+
+    pub fn idle_timeout(&self) -> Duration {
+        Duration::from_secs(self.idle_timeout_secs)
+    }
+}
+
+impl Default for TelnetConfig {
+    fn default() -> Self {
+        TelnetConfig {
+            keepalive_secs: DEFAULT_KEEPALIVE_SECS,
+            idle_timeout_secs: DEFAULT_IDLE_TIMEOUT_SECS,
+        }
+    }
+}
+
+/// Who may log in. While the allowlist is enforced, only players whose names are on it, and
+/// immortals, may create characters or log in; everyone else is shown the rejection message. The
+/// maintenance notice is shown on connect while maintenance mode is on. With takeover on, logging
+/// in as a player who is already online disconnects their old connection instead of being
+/// rejected. A deleted character is kept for `deletion_secs`, and logging in before then cancels
+/// the deletion. The banner, if set, is shown in large letters on connect. Up to `relog_players`
+/// players who log out are kept in memory for `relog_secs`, so logging back in restores them as
+/// they left instead of loading them again. Setting either to 0 turns this off.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoginConfig {
+    pub allowlist: bool,
+    pub rejection: String,
+    pub maintenance: String,
+    pub takeover: bool,
+    pub deletion_secs: u64,
+    pub banner: Option<String>,
+    pub relog_secs: u64,
+    pub relog_players: usize,
+}
+
+impl LoginConfig {
+    pub fn deletion_grace(&self) -> Duration {
+        Duration::from_secs(self.deletion_secs)
+    }
+
+    pub fn relog_grace(&self) -> Duration {
+        Duration::from_secs(self.relog_secs)
+    }
+}
+
+impl Default for LoginConfig {
+    fn default() -> Self {
+        LoginConfig {
+            allowlist: false,
+            rejection: DEFAULT_REJECTION.to_string(),
+            maintenance: DEFAULT_MAINTENANCE.to_string(),
+            takeover: false,
+            deletion_secs: DEFAULT_DELETION_SECS,
+            banner: None,
+            relog_secs: DEFAULT_RELOG_SECS,
+            relog_players: DEFAULT_RELOG_PLAYERS,
+        }
+    }
+}
+
+/// Web server settings. A web client which loses its connection, such as by reloading the page,
+/// may resume its session for `resume_secs`, during which the player stays in the world. Setting
+/// it to 0 disables resuming.
+///
+/// Logins and token refreshes are limited to `auth_per_minute` per address and per account after
+/// an initial `auth_burst`, and script, allowlist, token, and erasure changes to `write_per_minute`
+/// after `write_burst`. A rate of 0 disables the limit.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WebConfig {
+    pub cors: Option<Vec<String>>,
+    pub docs: bool,
+    pub resume_secs: u64,
+    pub auth_per_minute: u32,
+    pub auth_burst: u32,
+    pub write_per_minute: u32,
+    pub write_burst: u32,
+}
+
+impl WebConfig {
+    pub fn rate_limits(&self) -> RateLimitOptions {
+        RateLimitOptions {
+            auth_per_minute: self.auth_per_minute,
+            auth_burst: self.auth_burst,
+            write_per_minute: self.write_per_minute,
+            write_burst: self.write_burst,
+        }
+    }
+
+    pub fn resume_grace(&self) -> Duration {
+        Duration::from_secs(self.resume_secs)
+    }
+}
+
+impl Default for WebConfig {
+    fn default() -> Self {
+        WebConfig {
+            cors: None,
+            docs: false,
+            resume_secs: DEFAULT_RESUME_SECS,
+            auth_per_minute: DEFAULT_AUTH_PER_MINUTE,
+            auth_burst: DEFAULT_AUTH_BURST,
+            write_per_minute: DEFAULT_WRITE_PER_MINUTE,
+            write_burst: DEFAULT_WRITE_BURST,
+        }
+    }
+}
+
+/// Where statsd metrics are sent. Timers for things which happen many times a second, such as
+/// the engine loop and player input, are only sent for `timer_sample_rate` of their runs.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+    pub host: Option<String>,
+    pub port: u16,
+    pub prefix: String,
+    pub timer_sample_rate: f64,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        MetricsConfig {
+            enabled: true,
+            host: None,
+            port: DEFAULT_METRICS_PORT,
+            prefix: DEFAULT_METRICS_PREFIX.to_string(),
+            timer_sample_rate: 1.0,
+        }
+    }
+}
+
+/// Limits on what scripts may do. Scripts may only fetch URLs whose host is on the allowlist or
+/// is a subdomain of a host on it; the allowlist is empty by default.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScriptingConfig {
+    pub http_allowlist: Vec<String>,
+    pub http_timeout_ms: u64,
+    pub http_max_bytes: usize,
+    pub slow_script_ms: u64,
+}
+
+impl ScriptingConfig {
+    pub fn http_timeout(&self) -> Duration {
+        Duration::from_millis(self.http_timeout_ms)
+    }
+
+    pub fn slow_script_threshold(&self) -> Duration {
+        Duration::from_millis(self.slow_script_ms)
+    }
+}
+
+impl Default for ScriptingConfig {
+    fn default() -> Self {
+        ScriptingConfig {
+            http_allowlist: Vec::new(),
+            http_timeout_ms: DEFAULT_HTTP_TIMEOUT_MS,
+            http_max_bytes: DEFAULT_HTTP_MAX_BYTES,
+            slow_script_ms: DEFAULT_SLOW_SCRIPT_MS,
+        }
+    }
+}
+
+/// How the player market runs. Changes apply to listings created after the configuration is
+/// reloaded.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarketConfig {
+    pub listing_secs: u64,
+}
+
+impl MarketConfig {
+    pub fn listing_duration(&self) -> Duration {
+        Duration::from_secs(self.listing_secs)
+    }
+}
+
+impl Default for MarketConfig {
+    fn default() -> Self {
+        MarketConfig {
+            listing_secs: DEFAULT_LISTING_SECS,
+        }
+    }
+}
+
+/// How in-game mail runs. Changes apply to mail sent after the configuration is reloaded.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MailConfig {
+    pub expiry_secs: u64,
+}
+
+impl MailConfig {
+    pub fn expiry(&self) -> Duration {
+        Duration::from_secs(self.expiry_secs)
+    }
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        MailConfig {
+            expiry_secs: DEFAULT_MAIL_EXPIRY_SECS,
+        }
+    }
+}
+
+/// Hunger and thirst, which are off by default. While enabled, mortals grow a little hungrier and
+/// thirstier every `interval_secs` and are told when they need to eat or drink.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NeedsConfig {
+    pub enabled: bool,
+    pub interval_secs: u64,
+}
+
+impl NeedsConfig {
+    pub fn growth_rate(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+}
+
+impl Default for NeedsConfig {
+    fn default() -> Self {
+        NeedsConfig {
+            enabled: false,
+            interval_secs: DEFAULT_NEEDS_SECS,
+        }
+    }
+}
+
+/// Loading rooms a region at a time, for worlds too large to keep in memory, which is off by
+/// default. While on, only rooms in no region, the regions players are in, and the regions next to
+/// those are kept in the world. Other regions are loaded as they are needed, and leave the world
+/// once they have gone unneeded for `idle_secs`. Whether regions are loaded lazily is only read at
+/// startup.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RegionsConfig {
+    pub lazy: bool,
+    pub idle_secs: u64,
+}
+
+impl RegionsConfig {
+    pub fn idle(&self) -> Duration {
+        Duration::from_secs(self.idle_secs)
+    }
+}
+
+impl Default for RegionsConfig {
+    fn default() -> Self {
+        RegionsConfig {
+            lazy: false,
+            idle_secs: DEFAULT_REGION_IDLE_SECS,
+        }
+    }
+}
+
+/// How immortals moderate players. A snoop ends after `snoop_secs`. With `snoop_approval` on,
+/// immortals who are not administrators need another immortal to approve each snoop. What players
+/// say, emote, send, and say to their clans is kept for `communication_retention_secs`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModerationConfig {
+    pub snoop_secs: u64,
+    pub snoop_approval: bool,
+    pub communication_retention_secs: u64,
+}
+
+impl ModerationConfig {
+    pub fn snoop_duration(&self) -> Duration {
+        Duration::from_secs(self.snoop_secs)
+    }
+
+    pub fn communication_retention(&self) -> Duration {
+        Duration::from_secs(self.communication_retention_secs)
+    }
+}
+
+impl Default for ModerationConfig {
+    fn default() -> Self {
+        ModerationConfig {
+            snoop_secs: DEFAULT_SNOOP_SECS,
+            snoop_approval: false,
+            communication_retention_secs: DEFAULT_COMMUNICATION_RETENTION_SECS,
+        }
+    }
+}
+
+/// Where logs are written besides standard output. When a directory is set, engine diagnostics
+/// and gameplay events are written to separate files in it, each rotated daily. Logging is set up
+/// once at startup, so changes apply after a restart.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    pub directory: Option<PathBuf>,
+    pub engine_file: String,
+    pub game_file: String,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        LoggingConfig {
+            directory: None,
+            engine_file: DEFAULT_ENGINE_LOG.to_string(),
+            game_file: DEFAULT_GAME_LOG.to_string(),
+        }
+    }
+}
+
+/// Watches for the engine's game loop stalling. When it has not finished a tick for `stall_secs`,
+/// the watchdog logs what the engine was doing, posts to the webhook if one is set and, if
+/// `restart` is set, restarts the game server.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WatchdogConfig {
+    pub stall_secs: u64,
+    pub webhook: Option<String>,
+    pub restart: bool,
+}
+
+impl WatchdogConfig {
+    pub fn stall_timeout(&self) -> Duration {
+        Duration::from_secs(self.stall_secs)
+    }
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        WatchdogConfig {
+            stall_secs: DEFAULT_STALL_SECS,
+            webhook: None,
+            restart: false,
+        }
+    }
+}
+
+/// The days of the year a season is under way, as MM-DD dates in UTC. Content tagged with the
+/// season is only active between `start` and `end`, inclusive, which may wrap past the new year.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeasonConfig {
+    pub start: String,
+    pub end: String,
+}
+
+impl SeasonConfig {
+    pub fn window(&self) -> Option<SeasonWindow> {
+        Some(SeasonWindow::new(
+            self.start.parse().ok()?,
+            self.end.parse().ok()?,
+        ))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("failed to read {0:?}: {1}")]
+    Read(PathBuf, std::io::Error),
+    #[error("failed to parse {0:?}: {1}")]
+    Parse(PathBuf, toml::de::Error),
+    #[error("invalid setting: {0}")]
+    Invalid(&'static str),
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| ConfigError::Read(path.to_path_buf(), e))?;
+        let config: Config = toml::from_str(contents.as_str())
+            .map_err(|e| ConfigError::Parse(path.to_path_buf(), e))?;
+
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    pub fn season_windows(&self) -> BTreeMap<String, SeasonWindow> {
+        self.seasons
+            .iter()
+            .filter_map(|(name, season)| season.window().map(|window| (name.clone(), window)))
+            .collect()
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.engine.tick_ms == 0 {
+            return Err(ConfigError::Invalid(
+                "engine.tick_ms must be greater than 0",
+            ));
+        }
+
+        if self.engine.metrics_ms == 0 {
+            return Err(ConfigError::Invalid(
+                "engine.metrics_ms must be greater than 0",
+            ));
+        }
+
+        if self.engine.leaderboard_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "engine.leaderboard_secs must be greater than 0",
+            ));
+        }
+
+        if self.engine.lint_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "engine.lint_secs must be greater than 0",
+            ));
+        }
+
+        if self.engine.autosave_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "engine.autosave_secs must be greater than 0",
+            ));
+        }
+
+        if self.engine.max_players == Some(0) {
+            return Err(ConfigError::Invalid(
+                "engine.max_players must be greater than 0",
+            ));
+        }
+
+        if self.telnet.keepalive_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "telnet.keepalive_secs must be greater than 0",
+            ));
+        }
+
+        if self.telnet.idle_timeout_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "telnet.idle_timeout_secs must be greater than 0",
+            ));
+        }
+
+        if self.web.auth_per_minute > 0 && self.web.auth_burst == 0 {
+            return Err(ConfigError::Invalid(
+                "web.auth_burst must be greater than 0 when web.auth_per_minute is",
+            ));
+        }
+
+        if self.web.write_per_minute > 0 && self.web.write_burst == 0 {
+            return Err(ConfigError::Invalid(
+                "web.write_burst must be greater than 0 when web.write_per_minute is",
+            ));
+        }
+
+        if self.metrics.port == 0 {
+            return Err(ConfigError::Invalid("metrics.port must be greater than 0"));
+        }
+
+        if !(self.metrics.timer_sample_rate > 0.0 && self.metrics.timer_sample_rate <= 1.0) {
+            return Err(ConfigError::Invalid(
+                "metrics.timer_sample_rate must be greater than 0 and at most 1",
+            ));
+        }
+
+        if self.scripting.http_timeout_ms == 0 {
+            return Err(ConfigError::Invalid(
+                "scripting.http_timeout_ms must be greater than 0",
+            ));
+        }
+
+        if self.scripting.http_max_bytes == 0 {
+            return Err(ConfigError::Invalid(
+                "scripting.http_max_bytes must be greater than 0",
+            ));
+        }
+
+        if self.scripting.slow_script_ms == 0 {
+            return Err(ConfigError::Invalid(
+                "scripting.slow_script_ms must be greater than 0",
+            ));
+        }
+
+        if self.watchdog.stall_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "watchdog.stall_secs must be greater than 0",
+            ));
+        }
+
+        if self.market.listing_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "market.listing_secs must be greater than 0",
+            ));
+        }
+
+        if self.mail.expiry_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "mail.expiry_secs must be greater than 0",
+            ));
+        }
+
+        if self.needs.interval_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "needs.interval_secs must be greater than 0",
+            ));
+        }
+
+        if self.moderation.snoop_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "moderation.snoop_secs must be greater than 0",
+            ));
+        }
+
+        if self.moderation.communication_retention_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "moderation.communication_retention_secs must be greater than 0",
+            ));
+        }
+
+        if self.logging.engine_file.is_empty() || self.logging.game_file.is_empty() {
+            return Err(ConfigError::Invalid(
+                "logging.engine_file and logging.game_file must not be empty",
+            ));
+        }
+
+        if self.logging.engine_file == self.logging.game_file {
+            return Err(ConfigError::Invalid(
+                "logging.engine_file and logging.game_file must differ",
+            ));
+        }
+
+        for (name, season) in self.seasons.iter() {
+            if parse_season_name(name).is_err() {
+                return Err(ConfigError::Invalid(
+                    "season names must contain only lowercase letters, digits, dashes, and \
+                     underscores",
+                ));
+            }
+
+            if season.window().is_none() {
+                return Err(ConfigError::Invalid(
+                    "seasons must give their start and end as MM-DD",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
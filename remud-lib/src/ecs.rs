@@ -1,12 +1,28 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    any::Any,
+    borrow::Cow,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex, PoisonError, RwLock},
+};
 
 use bevy_app::Events;
-use bevy_core::Time;
-use bevy_ecs::{prelude::*, schedule::SystemDescriptor};
-
-use crate::world::{
-    scripting::time::Timers,
-    types::{room::Room, Location},
+use bevy_ecs::{
+    archetype::{Archetype, ArchetypeComponentId},
+    component::ComponentId,
+    prelude::*,
+    query::Access,
+    schedule::{GraphNode, SystemDescriptor},
+    system::{IntoSystem, SystemId},
+};
+use tracing::Span;
+
+use crate::{
+    clock::{Clock, Time},
+    metrics::stats_incr,
+    world::{
+        scripting::time::Timers,
+        types::{room::Room, Location},
+    },
 };
 
 pub type SharedWorld = Arc<RwLock<World>>;
@@ -18,12 +34,39 @@ pub enum Step {
     PostEvent,
 }
 
+impl Step {
+    /// The phases scheduled for this step, in execution order.
+    fn phases(&self) -> &'static [Phase] {
+        match self {
+            Step::PreEvent | Step::Main => &[Phase::First, Phase::Update],
+            Step::PostEvent => &[Phase::Update],
+        }
+    }
+
+    fn span(&self) -> Span {
+        match self {
+            Step::PreEvent => tracing::info_span!("pre-event step"),
+            Step::Main => tracing::info_span!("main step"),
+            Step::PostEvent => tracing::info_span!("post-event step"),
+        }
+    }
+}
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, StageLabel)]
 pub enum Phase {
     First,
     Update,
 }
 
+impl Phase {
+    fn span(&self) -> Span {
+        match self {
+            Phase::First => tracing::info_span!("first phase"),
+            Phase::Update => tracing::info_span!("update phase"),
+        }
+    }
+}
+
 pub struct Ecs {
     world: Option<World>,
     pre_event: Schedule,
@@ -33,7 +76,8 @@ pub struct Ecs {
 
 impl Ecs {
     pub fn new() -> Self {
-        let world = World::new();
+        let mut world = World::new();
+        world.insert_resource(SystemPanics::default());
 
         let mut pre_event = Schedule::default();
         pre_event.add_stage(Phase::First, SystemStage::parallel());
@@ -62,27 +106,80 @@ impl Ecs {
         self.world.as_mut().unwrap()
     }
 
-    pub fn run(&mut self, step: Step) {
+    /// Runs each phase of a step, returning the guarded systems which panicked. The rest of a
+    /// phase runs without a system which panics.
+    ///
+    /// Any other panic, such as in an exclusive system, is logged and stops the rest of the step
+    /// rather than the engine. The systems it skipped still see the tick's events when they next
+    /// run.
+    ///
+    /// After a panic, whatever the systems which finished made of the world is kept, along with
+    /// the updates they queued, so what is saved still matches the world.
+    pub fn run(&mut self, step: Step) -> Vec<SystemPanic> {
         let world = self.world.as_mut().unwrap();
-        match step {
-            Step::PreEvent => self.pre_event.run_once(world),
-            Step::Main => self.main.run_once(world),
-            Step::PostEvent => self.post_event.run_once(world),
+        let schedule = match step {
+            Step::PreEvent => &mut self.pre_event,
+            Step::Main => &mut self.main,
+            Step::PostEvent => &mut self.post_event,
+        };
+
+        let mut panics = Vec::new();
+
+        // Stages are run individually rather than through Schedule::run_once so each phase gets
+        // its own span nested under the step's span.
+        let _step = step.span().entered();
+        for phase in step.phases() {
+            let _phase = phase.span().entered();
+            let stage = schedule.get_stage_mut::<SystemStage>(phase).unwrap();
+
+            let result = panic::catch_unwind(AssertUnwindSafe(|| stage.run(world)));
+
+            let systems = world.get_resource::<SystemPanics>().unwrap().take();
+            panics.extend(systems.into_iter().map(|system| {
+                let labels = stage
+                    .parallel_systems()
+                    .iter()
+                    .find(|container| container.name() == system)
+                    .map(|container| container.labels().to_vec())
+                    .unwrap_or_default();
+                SystemPanic { system, labels }
+            }));
+
+            if let Err(payload) = result {
+                stats_incr("ecs.system-panic");
+                tracing::error!(
+                    "system panicked during {:?} {:?}: {}",
+                    step,
+                    phase,
+                    panic_message(&*payload)
+                );
+                break;
+            }
         }
+
+        panics
+    }
+
+    pub fn insert_resource<T: Send + Sync + 'static>(&mut self, resource: T) -> &mut Self {
+        self.world_mut().insert_resource(resource);
+        self
     }
 
-    pub fn init_resource<T: Default + Send + Sync + 'static>(&mut self) -> &mut Self {
-        self.world_mut().insert_resource(T::default());
+    pub fn init_resource<T: FromWorld + Send + Sync + 'static>(&mut self) -> &mut Self {
+        let resource = T::from_world(self.world_mut());
+        self.world_mut().insert_resource(resource);
         self
     }
 
     pub fn add_event<T: Send + Sync + 'static>(&mut self) -> &mut Self {
         self.world_mut().insert_resource(Events::<T>::default());
         self.pre_event
-            .add_system_to_stage(Phase::First, Events::<T>::update_system.system());
+            .add_system_to_stage(Phase::First, Events::<T>::update_system.guarded());
         self
     }
 
+    /// Adds a system to a phase of a step. Systems in parallel stages should be built with
+    /// `guarded` rather than `system`, so a panic in one does not stall the stage.
     pub fn add_system(
         &mut self,
         step: Step,
@@ -109,11 +206,133 @@ impl Ecs {
 
         f(shared_world.clone());
 
-        let world = RwLock::into_inner(Arc::try_unwrap(shared_world).unwrap()).unwrap();
+        // A script which panicked while holding the lock poisons it, but the world is still usable.
+        let world = RwLock::into_inner(Arc::try_unwrap(shared_world).unwrap())
+            .unwrap_or_else(PoisonError::into_inner);
         self.world = Some(world);
     }
 }
 
+/// The message a panic was raised with, for logging.
+pub fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.as_str()
+    } else {
+        "unknown panic"
+    }
+}
+
+/// A guarded system which panicked while a step ran, and the labels it was added with.
+#[derive(Debug)]
+pub struct SystemPanic {
+    pub system: Cow<'static, str>,
+    pub labels: Vec<Box<dyn SystemLabel>>,
+}
+
+// The guarded systems which panicked during the stage being run.
+#[derive(Default)]
+struct SystemPanics(Mutex<Vec<Cow<'static, str>>>);
+
+impl SystemPanics {
+    fn record(&self, system: Cow<'static, str>) {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(system);
+    }
+
+    fn take(&self) -> Vec<Cow<'static, str>> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+/// A system which catches its own panics. Parallel stages run systems on the task pool's threads,
+/// and a system which panics there never reports finishing, leaving the stage waiting on it
+/// forever. The panic is logged and recorded for `Ecs::run` to return instead.
+pub struct Guarded<S> {
+    system: S,
+}
+
+impl<S: System<In = (), Out = ()>> System for Guarded<S> {
+    type In = ();
+    type Out = ();
+
+    fn name(&self) -> Cow<'static, str> {
+        self.system.name()
+    }
+
+    fn id(&self) -> SystemId {
+        self.system.id()
+    }
+
+    fn new_archetype(&mut self, archetype: &Archetype) {
+        self.system.new_archetype(archetype)
+    }
+
+    fn component_access(&self) -> &Access<ComponentId> {
+        self.system.component_access()
+    }
+
+    fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        self.system.archetype_component_access()
+    }
+
+    fn is_send(&self) -> bool {
+        self.system.is_send()
+    }
+
+    unsafe fn run_unsafe(&mut self, input: (), world: &World) {
+        let system = &mut self.system;
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| unsafe {
+            system.run_unsafe(input, world)
+        })) {
+            stats_incr("ecs.system-panic");
+            tracing::error!(
+                "system {} panicked: {}",
+                self.system.name(),
+                panic_message(&*payload)
+            );
+
+            // No system takes the panics while the stage runs, only Ecs::run once it is done.
+            world
+                .get_resource::<SystemPanics>()
+                .unwrap()
+                .record(self.system.name());
+        }
+    }
+
+    fn apply_buffers(&mut self, world: &mut World) {
+        self.system.apply_buffers(world)
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.system.initialize(world)
+    }
+
+    fn check_change_tick(&mut self, change_tick: u32) {
+        self.system.check_change_tick(change_tick)
+    }
+}
+
+pub trait GuardSystem<Params, S> {
+    /// Builds the system like `system`, but guarded against panics.
+    fn guarded(self) -> Guarded<S>;
+}
+
+impl<F, Params, S> GuardSystem<Params, S> for F
+where
+    F: IntoSystem<Params, S>,
+    S: System<In = (), Out = ()>,
+{
+    fn guarded(self) -> Guarded<S> {
+        Guarded {
+            system: self.system(),
+        }
+    }
+}
+
 pub trait Plugin {
     fn build(&self, ecs: &mut Ecs);
 }
@@ -124,20 +343,31 @@ pub enum CoreSystem {
 }
 
 #[derive(Default)]
-pub struct CorePlugin {}
+pub struct CorePlugin {
+    clock: Clock,
+}
+
+impl CorePlugin {
+    pub fn new(clock: Clock) -> Self {
+        CorePlugin { clock }
+    }
+}
 
 impl Plugin for CorePlugin {
     fn build(&self, ecs: &mut Ecs) {
-        ecs.init_resource::<Time>().add_system(
-            Step::PreEvent,
-            Phase::First,
-            time_system.system().label(CoreSystem::Time),
-        );
+        ecs.insert_resource(self.clock.clone())
+            .init_resource::<Time>()
+            .add_system(
+                Step::PreEvent,
+                Phase::First,
+                time_system.guarded().label(CoreSystem::Time),
+            );
     }
 }
 
-fn time_system(mut time: ResMut<Time>) {
-    time.update()
+#[tracing::instrument(name = "time system", skip_all)]
+fn time_system(clock: Res<Clock>, mut time: ResMut<Time>) {
+    time.update(clock.now())
 }
 
 pub trait WorldExt {
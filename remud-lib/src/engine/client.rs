@@ -1,19 +1,34 @@
-use std::{borrow::Cow, collections::HashMap, iter};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+    iter,
+    time::{Duration, Instant},
+};
 
 use bevy_ecs::prelude::Entity;
-use tokio::sync::mpsc;
+use tokio::{
+    sync::mpsc::{self, error::TrySendError},
+    time,
+};
 
 use crate::{
     engine::{
         db::Db,
         fsm::{negotiate_login::ClientLoginFsm, Params, StackFsm, UpdateResult},
-        ClientMessage, EngineResponse,
+        ClientMessage, Credentials, EngineResponse,
     },
-    world::GameWorld,
+    metrics::stats_incr,
+    world::{types::player::ClientCaps, GameWorld},
     ClientId,
 };
 use std::sync::atomic::{AtomicBool, Ordering};
 
+const SHED_NOTICE: &str =
+    "|Gray69|Some output was dropped while your connection fell behind. Use 'recall' to see it.|-|";
+
+// How long the engine waits for room in a client's queue for output meant for the player.
+const DELIVER_TIMEOUT: Duration = Duration::from_millis(250);
+
 #[derive(Debug)]
 pub enum SendPrompt {
     None,
@@ -23,12 +38,14 @@ pub enum SendPrompt {
 
 #[derive(Debug, Clone)]
 pub enum ClientEvent<'a> {
+    Admitted,
     Advance,
     Disconnect,
     Input(&'a str),
     PasswordHash(Option<String>),
     PasswordVerification(Option<bool>),
     Ready,
+    ReadyAs(Credentials),
 }
 
 pub struct Client {
@@ -36,16 +53,24 @@ pub struct Client {
     client_sender: ClientSender,
     root: ClientLoginFsm,
     fsms: Vec<Box<dyn StackFsm + Send + Sync>>,
+    caps: ClientCaps,
+    // lets a new connection take over this client's session after its connection drops
+    resume_token: Option<String>,
+    // while detached, the client has no connection and is removed once this passes
+    detached_until: Option<Instant>,
 }
 
 impl Client {
     #[tracing::instrument(
         name = "process client event", 
         fields(
+            client_id = self.engine_sender.id.id(),
             event = match &event {
                 ClientEvent::Input(input) => if self.expecting_sensitive_input() { "******" } else { input },
                 ClientEvent::Ready => "ready",
+                ClientEvent::ReadyAs(_) => "ready-as",
                 ClientEvent::Disconnect => "disconnect",
+                ClientEvent::Admitted => "admitted",
                 ClientEvent::Advance => "advance",
                 ClientEvent::PasswordHash(_) => "pw-hash",
                 ClientEvent::PasswordVerification(verified) => 
@@ -67,6 +92,7 @@ impl Client {
             let mut params = Params::new(
                 self.engine_sender.clone(),
                 &self.client_sender,
+                &self.caps,
                 world,
                 db,
             );
@@ -106,6 +132,53 @@ impl Client {
         self.root.player()
     }
 
+    /// Lets go of the client's player after another client took it over.
+    pub fn release_player(&mut self) {
+        self.root.release_player();
+    }
+
+    /// Whether the client is waiting for room in the world to log in.
+    pub fn queued(&self) -> bool {
+        self.root.queued()
+    }
+
+    /// What the client supports, as last reported by its connection.
+    pub fn caps(&self) -> &ClientCaps {
+        &self.caps
+    }
+
+    pub fn set_caps(&mut self, caps: ClientCaps) {
+        self.caps = caps;
+    }
+
+    pub fn set_resume_token(&mut self, token: String) {
+        self.resume_token = Some(token);
+    }
+
+    /// Whether the session can be held for the client to resume after its connection drops. Only
+    /// players in the world who were not disconnected by the engine can resume.
+    pub fn resumable(&self) -> bool {
+        self.resume_token.is_some()
+            && self.player().is_some()
+            && !self.client_sender.closed.load(Ordering::SeqCst)
+    }
+
+    pub fn detach(&mut self, until: Instant) {
+        self.detached_until = Some(until);
+    }
+
+    pub fn detached_until(&self) -> Option<Instant> {
+        self.detached_until
+    }
+
+    /// Hands the session over to a new connection. The resume token is spent, the new
+    /// connection registers its own.
+    pub fn reattach(&mut self, engine_tx: mpsc::Sender<EngineResponse>) {
+        self.client_sender = ClientSender::new(engine_tx);
+        self.resume_token = None;
+        self.detached_until = None;
+    }
+
     pub fn expecting_sensitive_input(&self) -> bool {
         self.client_sender
             .expecting_sensitive_input
@@ -117,7 +190,43 @@ impl Client {
         prompt: SendPrompt,
         messages: impl IntoIterator<Item = M>,
     ) {
-        self.client_sender.send(prompt, messages).await;
+        // there is nobody to see messages sent while detached
+        if self.detached_until.is_none() {
+            self.client_sender.send(prompt, messages).await;
+        }
+    }
+
+    /// Sends world output meant for the player, waiting a short while for room in its queue. See
+    /// `ClientSender::deliver`.
+    pub async fn deliver<'a, M: Into<Cow<'a, str>>>(
+        &self,
+        prompt: SendPrompt,
+        messages: impl IntoIterator<Item = M>,
+    ) {
+        if self.detached_until.is_none() {
+            self.client_sender.deliver(prompt, messages).await;
+        }
+    }
+
+    /// Sends world output broadcast to the player without waiting for room in its queue. See
+    /// `ClientSender::offer`.
+    pub fn offer<'a, M: Into<Cow<'a, str>>>(
+        &self,
+        prompt: SendPrompt,
+        messages: impl IntoIterator<Item = M>,
+    ) {
+        if self.detached_until.is_none() {
+            self.client_sender.offer(prompt, messages);
+        }
+    }
+
+    /// Sends final messages to the client and closes its connection.
+    pub async fn disconnect<'a, M: Into<Cow<'a, str>>>(
+        &self,
+        messages: impl IntoIterator<Item = M>,
+    ) {
+        self.client_sender.send(SendPrompt::None, messages).await;
+        self.client_sender.disconnect().await;
     }
 }
 
@@ -129,26 +238,41 @@ pub struct EngineSender {
 
 impl EngineSender {
     pub fn password_hash(&self, hash: Option<String>) {
-        self.tx.blocking_send(ClientMessage::PasswordHash(self.id, hash)).ok();
+        self.tx
+            .blocking_send(ClientMessage::PasswordHash(self.id, hash))
+            .ok();
     }
 
     pub fn password_verification(&self, verified: Option<bool>) {
-        self.tx.blocking_send(ClientMessage::PasswordVerification(self.id, verified)).ok();
+        self.tx
+            .blocking_send(ClientMessage::PasswordVerification(self.id, verified))
+            .ok();
     }
 }
 
 pub struct ClientSender {
     tx: mpsc::Sender<EngineResponse>,
     expecting_sensitive_input: AtomicBool,
+    closed: AtomicBool,
+    // whether output was dropped since the client last accepted some
+    shed: AtomicBool,
 }
 
 impl ClientSender {
-    #[tracing::instrument(name = "client send", skip(self, messages))]
-    pub async fn send<'a, M: Into<Cow<'a, str>>>(
+    fn new(tx: mpsc::Sender<EngineResponse>) -> Self {
+        ClientSender {
+            tx,
+            expecting_sensitive_input: AtomicBool::new(false),
+            closed: AtomicBool::new(false),
+            shed: AtomicBool::new(false),
+        }
+    }
+
+    fn response<'a, M: Into<Cow<'a, str>>>(
         &self,
         prompt: SendPrompt,
         messages: impl IntoIterator<Item = M>,
-    ) {
+    ) -> EngineResponse {
         self.expecting_sensitive_input
             .store(false, Ordering::SeqCst);
         let message = match prompt {
@@ -160,16 +284,98 @@ impl ClientSender {
             }
         };
         tracing::debug!("{:?}", message);
+        message
+    }
+
+    #[tracing::instrument(name = "client send", skip(self, messages))]
+    pub async fn send<'a, M: Into<Cow<'a, str>>>(
+        &self,
+        prompt: SendPrompt,
+        messages: impl IntoIterator<Item = M>,
+    ) {
+        let message = self.response(prompt, messages);
         if let Err(e) = self.tx.send(message).await {
             tracing::error!("failed to send message to client: {}", e);
         }
     }
+
+    /// Sends output meant for the player, such as replies to their commands, waiting a short while
+    /// for room in the client's queue. Only a client which stays full for that long has it dropped.
+    #[tracing::instrument(name = "client deliver", skip(self, messages))]
+    pub async fn deliver<'a, M: Into<Cow<'a, str>>>(
+        &self,
+        prompt: SendPrompt,
+        messages: impl IntoIterator<Item = M>,
+    ) {
+        let shed = self.shed.load(Ordering::SeqCst);
+        let message = self.response_after_shed(shed, prompt, messages);
+
+        match time::timeout(DELIVER_TIMEOUT, self.tx.send(message)).await {
+            Ok(Ok(())) => self.shed.store(false, Ordering::SeqCst),
+            Ok(Err(e)) => tracing::error!("failed to send message to client: {}", e),
+            Err(_) => self.mark_shed(shed),
+        }
+    }
+
+    /// Sends broadcast output without waiting. A client which has fallen too far behind to take it
+    /// has the output dropped rather than stalling the engine, and is told so once it catches up.
+    /// Dropped output is still in the player's history.
+    #[tracing::instrument(name = "client offer", skip(self, messages))]
+    pub fn offer<'a, M: Into<Cow<'a, str>>>(
+        &self,
+        prompt: SendPrompt,
+        messages: impl IntoIterator<Item = M>,
+    ) {
+        let shed = self.shed.load(Ordering::SeqCst);
+        let message = self.response_after_shed(shed, prompt, messages);
+
+        match self.tx.try_send(message) {
+            Ok(()) => self.shed.store(false, Ordering::SeqCst),
+            Err(TrySendError::Full(_)) => self.mark_shed(shed),
+            Err(TrySendError::Closed(_)) => {
+                tracing::error!("failed to send message to client: channel closed");
+            }
+        }
+    }
+
+    // Builds a response, telling the client first if output was dropped since it last took some.
+    fn response_after_shed<'a, M: Into<Cow<'a, str>>>(
+        &self,
+        shed: bool,
+        prompt: SendPrompt,
+        messages: impl IntoIterator<Item = M>,
+    ) -> EngineResponse {
+        let notice: Option<Cow<'a, str>> = shed.then(|| Cow::Borrowed(SHED_NOTICE));
+        self.response(
+            prompt,
+            notice
+                .into_iter()
+                .chain(messages.into_iter().map(Into::into)),
+        )
+    }
+
+    fn mark_shed(&self, shed: bool) {
+        if !shed {
+            tracing::warn!("client output queue is full, shedding output");
+        }
+        self.shed.store(true, Ordering::SeqCst);
+        stats_incr("engine.shed.client_output");
+    }
+
+    pub async fn disconnect(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        if let Err(e) = self.tx.send(EngineResponse::Disconnect).await {
+            tracing::error!("failed to disconnect client: {}", e);
+        }
+    }
 }
 
 #[derive(Default)]
 pub(crate) struct Clients {
     clients: HashMap<ClientId, Client>,
     by_player: HashMap<Entity, ClientId>,
+    // clients waiting to log in, in the order they arrived
+    queue: VecDeque<ClientId>,
 }
 
 impl Clients {
@@ -186,18 +392,22 @@ impl Clients {
                     id: client_id,
                     tx: client_tx,
                 },
-                client_sender: ClientSender {
-                    tx: engine_tx,
-                    expecting_sensitive_input: AtomicBool::new(false),
-                },
+                client_sender: ClientSender::new(engine_tx),
                 root: ClientLoginFsm::default(),
                 fsms: Vec::new(),
+                caps: ClientCaps::default(),
+                resume_token: None,
+                detached_until: None,
             },
         );
     }
 
-    pub fn init_player(&mut self, client: ClientId, player: Entity) {
-        self.by_player.entry(player).or_insert(client);
+    /// Associates the client with its player, returning the client previously associated with
+    /// the player if it was taken over from another one.
+    pub fn init_player(&mut self, client: ClientId, player: Entity) -> Option<ClientId> {
+        self.by_player
+            .insert(player, client)
+            .filter(|previous| *previous != client)
     }
 
     pub fn get(&self, client: ClientId) -> Option<&Client> {
@@ -216,6 +426,50 @@ impl Clients {
         }
 
         self.clients.remove(&client);
+        self.queue.retain(|queued| *queued != client);
+    }
+
+    /// Adds a client to the back of the login queue, returning its position. Returns None if the
+    /// client is already queued.
+    pub fn enqueue(&mut self, client: ClientId) -> Option<usize> {
+        if self.queue.contains(&client) {
+            None
+        } else {
+            self.queue.push_back(client);
+            Some(self.queue.len())
+        }
+    }
+
+    pub fn dequeue(&mut self) -> Option<ClientId> {
+        self.queue.pop_front()
+    }
+
+    pub fn queued(&self) -> impl Iterator<Item = (usize, &Client)> {
+        self.queue
+            .iter()
+            .filter_map(|client| self.clients.get(client))
+            .enumerate()
+            .map(|(index, client)| (index + 1, client))
+    }
+
+    /// Finds the detached client a connection may resume with the token.
+    pub fn resumable(&self, token: &str, now: Instant) -> Option<ClientId> {
+        self.clients
+            .iter()
+            .find(|(_, client)| {
+                client.resume_token.as_deref() == Some(token)
+                    && client.detached_until.map_or(false, |until| until > now)
+            })
+            .map(|(client_id, _)| *client_id)
+    }
+
+    /// Detached clients which were not resumed in time.
+    pub fn expired(&self, now: Instant) -> Vec<ClientId> {
+        self.clients
+            .iter()
+            .filter(|(_, client)| client.detached_until.map_or(false, |until| until <= now))
+            .map(|(client_id, _)| *client_id)
+            .collect()
     }
 
     pub fn by_player(&self, player: Entity) -> Option<&Client> {
@@ -224,3 +478,88 @@ impl Clients {
             .and_then(|player| self.clients.get(player))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use crate::engine::{
+        client::{ClientSender, SendPrompt, SHED_NOTICE},
+        EngineResponse, Output, Text,
+    };
+
+    fn messages(response: EngineResponse) -> Vec<String> {
+        match response {
+            EngineResponse::Output(output) => output
+                .into_iter()
+                .filter_map(|output| match output {
+                    Output::Message(message) => Some(message.as_str().to_string()),
+                    _ => None,
+                })
+                .collect(),
+            EngineResponse::Disconnect => panic!("expected output"),
+        }
+    }
+
+    fn first_message(response: EngineResponse) -> Text {
+        match response {
+            EngineResponse::Output(mut output) => match output.pop_front() {
+                Some(Output::Message(message)) => message,
+                other => panic!("expected a message, got {:?}", other),
+            },
+            EngineResponse::Disconnect => panic!("expected output"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_offer_sheds_when_full() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let sender = ClientSender::new(tx);
+
+        sender.offer(SendPrompt::None, vec!["one"]);
+        // The queue is full, so this is dropped rather than waiting
+        sender.offer(SendPrompt::None, vec!["two"]);
+
+        assert_eq!(first_message(rx.recv().await.unwrap()).as_str(), "one");
+
+        // Once the client catches up it is told output was dropped
+        sender.offer(SendPrompt::None, vec!["three"]);
+        assert_eq!(
+            first_message(rx.recv().await.unwrap()).as_str(),
+            SHED_NOTICE
+        );
+
+        sender.offer(SendPrompt::None, vec!["four"]);
+        assert_eq!(first_message(rx.recv().await.unwrap()).as_str(), "four");
+    }
+
+    #[tokio::test]
+    async fn test_deliver_waits_when_full() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let sender = ClientSender::new(tx);
+
+        // A broadcast fills the queue, and the next is dropped
+        sender.offer(SendPrompt::None, vec!["someone arrives"]);
+        sender.offer(SendPrompt::Prompt, vec!["someone says hello"]);
+
+        // A reply waits for the client to catch up rather than being dropped
+        let (_, received) = tokio::join!(
+            sender.deliver(SendPrompt::Prompt, vec!["You say hello"]),
+            async {
+                vec![
+                    messages(rx.recv().await.unwrap()),
+                    messages(rx.recv().await.unwrap()),
+                ]
+            }
+        );
+
+        assert_eq!(
+            received,
+            vec![
+                vec!["someone arrives".to_string()],
+                vec![SHED_NOTICE.to_string(), "You say hello".to_string()],
+            ]
+        );
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt;
+
+use itertools::Itertools;
+use sqlx::{Row, SqlitePool};
+
+use crate::engine::db::DbResult;
+
+const HOOK_TABLES: [(&str, &str); 4] = [
+    ("player_scripts", "player_id"),
+    ("room_scripts", "room_id"),
+    ("prototype_scripts", "prototype_id"),
+    ("object_scripts", "object_id"),
+];
+
+/// A reference in the database to something which no longer exists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    MissingPrototype {
+        object: i64,
+        prototype: i64,
+    },
+    MissingRoom {
+        from: i64,
+        to: i64,
+        direction: String,
+    },
+    MissingScript {
+        table: &'static str,
+        owner: i64,
+        script: String,
+    },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::MissingPrototype { object, prototype } => write!(
+                f,
+                "object {} refers to missing prototype {}",
+                object, prototype
+            ),
+            Issue::MissingRoom {
+                from,
+                to,
+                direction,
+            } => write!(
+                f,
+                "exit {} from room {} to room {} refers to a missing room",
+                direction, from, to
+            ),
+            Issue::MissingScript {
+                table,
+                owner,
+                script,
+            } => write!(
+                f,
+                "hook in {} on {} refers to missing script {}",
+                table, owner, script
+            ),
+        }
+    }
+}
+
+/// Finds objects, exits and script hooks which refer to missing prototypes, rooms and scripts. When
+/// repairing, the dangling rows are deleted along with anything which cascades from them.
+pub async fn check_integrity(pool: &SqlitePool, repair: bool) -> DbResult<Vec<Issue>> {
+    let mut tx = pool.begin().await?;
+    let mut issues = Vec::new();
+
+    let objects = sqlx::query(
+        r#"SELECT id, prototype_id FROM objects
+            WHERE prototype_id NOT IN (SELECT id FROM prototypes)"#,
+    )
+    .fetch_all(&mut tx)
+    .await?;
+    issues.extend(objects.into_iter().map(|row| Issue::MissingPrototype {
+        object: row.get("id"),
+        prototype: row.get("prototype_id"),
+    }));
+
+    let exits = sqlx::query(
+        r#"SELECT room_from, room_to, direction FROM exits
+            WHERE room_from NOT IN (SELECT id FROM rooms) OR room_to NOT IN (SELECT id FROM rooms)"#,
+    )
+    .fetch_all(&mut tx)
+    .await?;
+    issues.extend(exits.into_iter().map(|row| Issue::MissingRoom {
+        from: row.get("room_from"),
+        to: row.get("room_to"),
+        direction: row.get("direction"),
+    }));
+
+    for (table, owner) in HOOK_TABLES {
+        let hooks = sqlx::query(
+            format!(
+                "SELECT {} AS owner, script FROM {} WHERE script NOT IN (SELECT name FROM scripts)",
+                owner, table
+            )
+            .as_str(),
+        )
+        .fetch_all(&mut tx)
+        .await?;
+        issues.extend(hooks.into_iter().map(|row| Issue::MissingScript {
+            table,
+            owner: row.get("owner"),
+            script: row.get("script"),
+        }));
+    }
+
+    if repair && !issues.is_empty() {
+        sqlx::query("DELETE FROM objects WHERE prototype_id NOT IN (SELECT id FROM prototypes)")
+            .execute(&mut tx)
+            .await?;
+        sqlx::query(
+            r#"DELETE FROM exits
+                WHERE room_from NOT IN (SELECT id FROM rooms) OR room_to NOT IN (SELECT id FROM rooms)"#,
+        )
+        .execute(&mut tx)
+        .await?;
+        for (table, _) in HOOK_TABLES {
+            sqlx::query(
+                format!(
+                    "DELETE FROM {} WHERE script NOT IN (SELECT name FROM scripts)",
+                    table
+                )
+                .as_str(),
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+
+        tracing::info!("repaired {} database integrity issues", issues.len());
+    }
+
+    tx.commit().await?;
+
+    Ok(issues)
+}
+
+/// Describes the outcome of an integrity check for an immortal or the log.
+pub fn describe_issues(issues: &[Issue], repair: bool) -> String {
+    if issues.is_empty() {
+        return "Database check found no issues.".to_string();
+    }
+
+    let verb = if repair { "repaired" } else { "found" };
+    let plural = if issues.len() == 1 { "" } else { "s" };
+
+    format!(
+        "Database check {} {} issue{}:\r\n{}",
+        verb,
+        issues.len(),
+        plural,
+        issues
+            .iter()
+            .map(|issue| format!("  {}", issue))
+            .join("\r\n")
+    )
+}
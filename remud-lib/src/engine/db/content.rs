@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use itertools::Itertools;
+use sqlx::{Row, Sqlite, SqlitePool, Transaction};
+
+use crate::engine::db::DbResult;
+
+/// How content migrations are run at startup. By default pending migrations are applied after
+/// backing up the database. A dry run reports what each pending migration would change without
+/// changing anything, and ReMUD exits afterwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationOptions {
+    pub dry_run: bool,
+    pub backup: bool,
+}
+
+impl Default for MigrationOptions {
+    fn default() -> Self {
+        MigrationOptions {
+            dry_run: false,
+            backup: true,
+        }
+    }
+}
+
+/// A versioned step which transforms world data, for changes which are easier to make in Rust
+/// than in SQL. Steps run in version order, each in its own transaction, and are recorded once
+/// applied so they run only once.
+#[async_trait]
+trait ContentMigration: Send + Sync {
+    fn version(&self) -> i64;
+    fn name(&self) -> &'static str;
+    /// Transforms world data, returning how many rows were changed.
+    async fn apply(&self, tx: &mut Transaction<'_, Sqlite>) -> DbResult<u64>;
+}
+
+fn migrations() -> Vec<Box<dyn ContentMigration>> {
+    vec![Box::new(SplitKeywords)]
+}
+
+pub async fn migrate_content(
+    pool: &SqlitePool,
+    options: MigrationOptions,
+    db_path: Option<&str>,
+) -> DbResult<usize> {
+    let applied = sqlx::query("SELECT version FROM content_migrations")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| row.get::<i64, _>("version"))
+        .collect::<HashSet<_>>();
+
+    let pending = migrations()
+        .into_iter()
+        .filter(|migration| !applied.contains(&migration.version()))
+        .sorted_by_key(|migration| migration.version())
+        .collect_vec();
+
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    if !options.dry_run && options.backup {
+        if let Some(path) = db_path {
+            let backup = format!("{}.content-{}.bak", path, pending[0].version());
+            tracing::info!("backing up world database to {}", backup);
+            sqlx::query("VACUUM INTO ?")
+                .bind(backup.as_str())
+                .execute(pool)
+                .await?;
+        }
+    }
+
+    let count = pending.len();
+
+    for migration in pending {
+        let mut tx = pool.begin().await?;
+        let changed = migration.apply(&mut tx).await?;
+
+        if options.dry_run {
+            tx.rollback().await?;
+            tracing::info!(
+                "content migration {} ({}) would change {} rows",
+                migration.version(),
+                migration.name(),
+                changed
+            );
+        } else {
+            sqlx::query("INSERT INTO content_migrations (version, name) VALUES (?, ?)")
+                .bind(migration.version())
+                .bind(migration.name())
+                .execute(&mut tx)
+                .await?;
+            tx.commit().await?;
+            tracing::info!(
+                "applied content migration {} ({}), changing {} rows",
+                migration.version(),
+                migration.name(),
+                changed
+            );
+        }
+    }
+
+    Ok(count)
+}
+
+// Keywords are matched one word at a time, so keywords containing spaces can never match. Splits
+// them into single words and drops empty and repeated keywords.
+struct SplitKeywords;
+
+#[async_trait]
+impl ContentMigration for SplitKeywords {
+    fn version(&self) -> i64 {
+        1
+    }
+
+    fn name(&self) -> &'static str {
+        "split keywords"
+    }
+
+    async fn apply(&self, tx: &mut Transaction<'_, Sqlite>) -> DbResult<u64> {
+        let mut changed = 0;
+
+        for table in ["prototypes", "objects"] {
+            let select = format!(
+                "SELECT id, keywords FROM {} WHERE keywords IS NOT NULL",
+                table
+            );
+            let update = format!("UPDATE {} SET keywords = ? WHERE id = ?", table);
+
+            let rows = {
+                let mut results = sqlx::query(select.as_str()).fetch(&mut *tx);
+
+                let mut rows = Vec::new();
+                while let Some(row) = results.try_next().await? {
+                    rows.push((row.get::<i64, _>("id"), row.get::<String, _>("keywords")));
+                }
+                rows
+            };
+
+            for (id, keywords) in rows {
+                let split = split_keywords(keywords.as_str());
+                if split == keywords {
+                    continue;
+                }
+
+                sqlx::query(update.as_str())
+                    .bind(split)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+                changed += 1;
+            }
+        }
+
+        Ok(changed)
+    }
+}
+
+fn split_keywords(keywords: &str) -> String {
+    keywords
+        .split(',')
+        .flat_map(str::split_whitespace)
+        .unique()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_keywords;
+
+    #[test]
+    fn test_split_keywords() {
+        assert_eq!(split_keywords("ledger,book"), "ledger,book");
+        assert_eq!(split_keywords("old book, ledger,,book"), "old,book,ledger");
+        assert_eq!(split_keywords(""), "");
+    }
+}
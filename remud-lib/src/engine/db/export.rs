@@ -0,0 +1,224 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use sqlx::{sqlite::SqliteRow, Column, Row, SqlitePool, TypeInfo, ValueRef};
+
+use crate::engine::db::{DbResult, Error};
+
+const EXPORT_VERSION: u32 = 1;
+
+// World tables in dependency order, each with an optional filter. Objects are only exported when
+// they lie in a room, since everything else belongs to players.
+const WORLD_TABLES: [(&str, Option<&str>); 31] = [
+    ("config", None),
+    ("rooms", None),
+    ("regions", None),
+    ("room_regions", None),
+    ("exits", None),
+    ("prototypes", None),
+    ("prototype_fields", None),
+    ("prototype_behaviors", None),
+    ("scripts", None),
+    ("prototype_scripts", None),
+    ("room_scripts", None),
+    (
+        "objects",
+        Some("id IN (SELECT object_id FROM room_objects)"),
+    ),
+    (
+        "object_fields",
+        Some("object_id IN (SELECT object_id FROM room_objects)"),
+    ),
+    (
+        "object_scripts",
+        Some("object_id IN (SELECT object_id FROM room_objects)"),
+    ),
+    ("room_objects", None),
+    ("globals", None),
+    ("achievements", None),
+    ("factions", None),
+    ("hints", None),
+    ("spawn_tables", None),
+    ("spawn_table_entries", None),
+    ("spawn_table_rooms", None),
+    ("spawn_table_regions", None),
+    ("loot_tables", None),
+    ("loot_table_entries", None),
+    ("shops", None),
+    ("campaigns", None),
+    ("campaign_stages", None),
+    ("campaign_spawns", None),
+    ("seasonal_content", None),
+    ("content_migrations", None),
+];
+
+// Columns of world tables which name players, left out since players are not exported.
+const PLAYER_COLUMNS: [(&str, &str); 1] = [("scripts", "author_id")];
+
+/// The world's content as rows of each world table, without any player data.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorldExport {
+    version: u32,
+    tables: Vec<TableExport>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TableExport {
+    name: String,
+    rows: Vec<Map<String, Value>>,
+}
+
+impl WorldExport {
+    pub fn rows(&self) -> usize {
+        self.tables.iter().map(|table| table.rows.len()).sum()
+    }
+}
+
+pub async fn export_world(pool: &SqlitePool) -> DbResult<WorldExport> {
+    let mut tables = Vec::new();
+
+    for (name, filter) in WORLD_TABLES {
+        let query = match filter {
+            Some(filter) => format!("SELECT * FROM {} WHERE {}", name, filter),
+            None => format!("SELECT * FROM {}", name),
+        };
+
+        let mut rows = sqlx::query(query.as_str())
+            .fetch_all(pool)
+            .await?
+            .iter()
+            .map(row_to_json)
+            .collect::<DbResult<Vec<_>>>()?;
+
+        for (_, column) in PLAYER_COLUMNS.iter().filter(|(table, _)| *table == name) {
+            for row in rows.iter_mut() {
+                row.remove(*column);
+            }
+        }
+
+        tables.push(TableExport {
+            name: name.to_string(),
+            rows,
+        });
+    }
+
+    Ok(WorldExport {
+        version: EXPORT_VERSION,
+        tables,
+    })
+}
+
+/// Replaces the world's content with an export. Importing into a database which already has
+/// players is refused, since clearing prototypes and rooms would take their objects with them.
+pub async fn import_world(pool: &SqlitePool, export: &WorldExport) -> DbResult<()> {
+    if export.version != EXPORT_VERSION {
+        return Err(Error::Import(format!(
+            "unsupported version {}",
+            export.version
+        )));
+    }
+
+    for table in export.tables.iter() {
+        if !WORLD_TABLES.iter().any(|(name, _)| *name == table.name) {
+            return Err(Error::Import(format!(
+                "unknown table {}",
+                table.name
+            )));
+        }
+    }
+
+    let players = sqlx::query("SELECT COUNT(*) AS count FROM players")
+        .fetch_one(pool)
+        .await?
+        .get::<i64, _>("count");
+    if players > 0 {
+        return Err(Error::Import(
+            "the database already has players, import into a new database".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    for (name, _) in WORLD_TABLES.iter().rev() {
+        sqlx::query(format!("DELETE FROM {}", name).as_str())
+            .execute(&mut tx)
+            .await?;
+    }
+
+    for (name, _) in WORLD_TABLES {
+        let table = match export.tables.iter().find(|table| table.name == name) {
+            Some(table) => table,
+            None => continue,
+        };
+
+        for row in table.rows.iter() {
+            if let Some(column) = row.keys().find(|column| {
+                !column
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            }) {
+                return Err(Error::Import(format!(
+                    "invalid column {} in table {}",
+                    column, name
+                )));
+            }
+
+            let insert = format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                name,
+                row.keys().cloned().collect::<Vec<_>>().join(", "),
+                vec!["?"; row.len()].join(", ")
+            );
+
+            let mut query = sqlx::query(insert.as_str());
+            for value in row.values() {
+                query = match value {
+                    Value::Null => query.bind(Option::<i64>::None),
+                    Value::Bool(value) => query.bind(*value),
+                    Value::Number(number) => match number.as_i64() {
+                        Some(value) => query.bind(value),
+                        None => query.bind(number.as_f64()),
+                    },
+                    Value::String(value) => query.bind(value.as_str()),
+                    Value::Array(_) | Value::Object(_) => {
+                        return Err(Error::Import(format!(
+                            "nested value in table {}",
+                            name
+                        )))
+                    }
+                };
+            }
+
+            query.execute(&mut tx).await?;
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+fn row_to_json(row: &SqliteRow) -> DbResult<Map<String, Value>> {
+    let mut map = Map::new();
+
+    for column in row.columns() {
+        let index = column.ordinal();
+        let raw = row.try_get_raw(index)?;
+
+        let value = if raw.is_null() {
+            Value::Null
+        } else {
+            match raw.type_info().name() {
+                "INTEGER" | "BOOLEAN" => Value::from(row.try_get::<i64, _>(index)?),
+                "REAL" => Number::from_f64(row.try_get::<f64, _>(index)?)
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null),
+                "TEXT" => Value::from(row.try_get::<String, _>(index)?),
+                _ => return Err(Error::Deserialize("unsupported column type")),
+            }
+        };
+
+        map.insert(column.name().to_string(), value);
+    }
+
+    Ok(map)
+}
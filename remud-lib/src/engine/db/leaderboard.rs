@@ -0,0 +1,76 @@
+use bevy_ecs::prelude::*;
+use sqlx::SqlitePool;
+
+use crate::{
+    engine::db::DbResult,
+    world::types::leaderboard::{
+        LeaderboardCategory, LeaderboardEntry, Leaderboards, LEADERBOARD_SIZE,
+    },
+};
+
+#[tracing::instrument(name = "aggregate leaderboards", skip_all)]
+pub async fn aggregate_leaderboards(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM leaderboards")
+        .execute(&mut tx)
+        .await?;
+
+    for category in LeaderboardCategory::ALL.iter() {
+        let ranking = match category {
+            LeaderboardCategory::Explored => {
+                r#"SELECT ?, ROW_NUMBER() OVER (ORDER BY COUNT(*) DESC, player_id) AS rank,
+                        player_id, COUNT(*)
+                    FROM player_rooms
+                    GROUP BY player_id
+                    ORDER BY rank
+                    LIMIT ?"#
+            }
+            LeaderboardCategory::Playtime => {
+                r#"SELECT ?, ROW_NUMBER() OVER (ORDER BY playtime DESC, id) AS rank, id, playtime
+                    FROM players
+                    WHERE playtime > 0
+                    ORDER BY rank
+                    LIMIT ?"#
+            }
+        };
+
+        sqlx::query(
+            format!(
+                "INSERT INTO leaderboards (category, rank, player_id, value) {}",
+                ranking
+            )
+            .as_str(),
+        )
+        .bind(category.as_str())
+        .bind(LEADERBOARD_SIZE)
+        .execute(&mut tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    let mut leaderboards = Leaderboards::default();
+    for category in LeaderboardCategory::ALL.iter() {
+        leaderboards.set(*category, load_leaderboard(pool, *category).await?);
+    }
+    world.insert_resource(leaderboards);
+
+    Ok(())
+}
+
+pub async fn load_leaderboard(
+    pool: &SqlitePool,
+    category: LeaderboardCategory,
+) -> DbResult<Vec<LeaderboardEntry>> {
+    Ok(sqlx::query_as::<_, LeaderboardEntry>(
+        r#"SELECT players.username AS name, leaderboards.value
+            FROM leaderboards
+            INNER JOIN players ON leaderboards.player_id = players.id
+            WHERE leaderboards.category = ?
+            ORDER BY leaderboards.rank"#,
+    )
+    .bind(category.as_str())
+    .fetch_all(pool)
+    .await?)
+}
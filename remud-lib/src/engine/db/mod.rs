@@ -1,3 +1,7 @@
+pub mod check;
+pub mod content;
+pub mod export;
+mod leaderboard;
 mod player;
 mod world;
 
@@ -10,16 +14,24 @@ use itertools::Itertools;
 use sqlx::{migrate::MigrateError, sqlite::SqliteConnectOptions, Row, SqlitePool};
 use thiserror::Error;
 
-use crate::world::{
-    scripting::{ScriptHook, ScriptHooks, ScriptName, ScriptTrigger, TriggerEvent, TriggerKind},
-    types::{
-        self,
-        object::{
-            Keywords, Object, ObjectBundle, ObjectFlags, ObjectId, Objects, PrototypeId, Prototypes,
+use crate::{
+    engine::db::content::MigrationOptions,
+    web::API_TOKEN_PLAYER_PREFIX,
+    world::{
+        scripting::{
+            ScriptHook, ScriptHooks, ScriptName, ScriptTrigger, TriggerEvent, TriggerKind,
+        },
+        types::{
+            self,
+            leaderboard::{LeaderboardCategory, LeaderboardEntry},
+            object::{
+                FieldValue, Fields, Keywords, Object, ObjectBundle, ObjectFlags, ObjectId, Objects,
+                PrototypeId, Prototypes,
+            },
+            player::{PlayerFlags, PlayerId},
+            room::RoomId,
+            Description, Id, Location, Named,
         },
-        player::{Player, PlayerFlags},
-        room::RoomId,
-        Contents, Description, Id, Location, Named,
     },
 };
 
@@ -40,6 +52,8 @@ pub enum Error {
     MissingData(&'static str),
     #[error("password verification error: {0}")]
     PasswordVerification(String),
+    #[error("failed to import world: {0}")]
+    Import(String),
 }
 
 #[async_trait]
@@ -56,6 +70,156 @@ pub trait AuthDb {
     async fn logout(&self, player: &str) -> Result<(), Error>;
     async fn access_issued_secs(&self, player: &str) -> Result<Option<i64>, Error>;
     async fn refresh_issued_secs(&self, player: &str) -> Result<Option<i64>, Error>;
+    async fn player_achievements(
+        &self,
+        player: &str,
+    ) -> Result<Option<Vec<EarnedAchievement>>, Error>;
+    async fn leaderboard(
+        &self,
+        category: LeaderboardCategory,
+    ) -> Result<Vec<LeaderboardEntry>, Error>;
+    async fn session_stats(&self) -> Result<SessionStats, Error>;
+    async fn market_listings(&self) -> Result<Vec<MarketListing>, Error>;
+    async fn news(&self) -> Result<Vec<NewsItem>, Error>;
+    async fn polls(&self) -> Result<Vec<PollResults>, Error>;
+    async fn request_erasure(&self, player: &str) -> Result<bool, Error>;
+    async fn erasure_requests(&self) -> Result<Vec<ErasureRequest>, Error>;
+    async fn create_api_token(
+        &self,
+        name: &str,
+        hash: &str,
+        scopes: &str,
+        created_by: &str,
+        created_at: i64,
+    ) -> Result<Option<i64>, Error>;
+    async fn api_token(&self, id: i64) -> Result<Option<ApiToken>, Error>;
+    async fn api_tokens(&self) -> Result<Vec<ApiToken>, Error>;
+    async fn touch_api_token(&self, id: i64, used_at: i64) -> Result<(), Error>;
+    async fn revoke_api_token(&self, name: &str) -> Result<bool, Error>;
+    async fn ping(&self) -> Result<(), Error>;
+}
+
+/// An achievement a player has earned, with the time it was earned.
+#[derive(Debug, sqlx::FromRow)]
+pub struct EarnedAchievement {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub earned_at: String,
+}
+
+/// Everything stored about a player, for them to take with them.
+#[derive(Debug)]
+pub struct PlayerExport {
+    pub name: String,
+    pub description: String,
+    pub pronouns: String,
+    pub flags: i64,
+    pub room: i64,
+    pub playtime: i64,
+    pub inventory: Vec<ExportedObject>,
+    pub vault: Vec<ExportedObject>,
+    pub achievements: Vec<EarnedAchievement>,
+    pub ignores: Vec<String>,
+    pub sessions: Vec<PlayerSession>,
+    pub reports: Vec<FiledReport>,
+}
+
+/// An object owned by an exported player.
+#[derive(Debug, sqlx::FromRow)]
+pub struct ExportedObject {
+    pub id: i64,
+    pub name: String,
+}
+
+/// One of an exported player's sessions. Sessions still open have not disconnected.
+#[derive(Debug, sqlx::FromRow)]
+pub struct PlayerSession {
+    pub connected_at: String,
+    pub disconnected_at: Option<String>,
+}
+
+/// A report an exported player filed against another player.
+#[derive(Debug, sqlx::FromRow)]
+pub struct FiledReport {
+    pub reported: String,
+    pub reason: String,
+    pub created_at: String,
+}
+
+/// A player's request to have their data erased.
+#[derive(Debug, sqlx::FromRow)]
+pub struct ErasureRequest {
+    pub name: String,
+    pub requested_at: String,
+}
+
+/// A long-lived token for automation, with the hash of its secret and its space separated scopes.
+#[derive(Debug, sqlx::FromRow)]
+pub struct ApiToken {
+    pub id: i64,
+    pub name: String,
+    pub hash: String,
+    pub scopes: String,
+    pub created_by: String,
+    pub created_at: i64,
+    pub last_used_at: Option<i64>,
+}
+
+/// Aggregate player and session counts, for population dashboards.
+#[derive(Debug, sqlx::FromRow)]
+pub struct SessionStats {
+    pub players: i64,
+    pub players_online: i64,
+    pub sessions_last_day: i64,
+    pub unique_players_last_day: i64,
+    pub average_session_secs: i64,
+    pub total_playtime_secs: i64,
+}
+
+/// An object for sale on the player market.
+#[derive(Debug, sqlx::FromRow)]
+pub struct MarketListing {
+    pub id: i64,
+    pub name: String,
+    pub price: i64,
+    pub seller: String,
+    pub expires_at: i64,
+}
+
+/// An entry in the news changelog.
+#[derive(Debug, sqlx::FromRow)]
+pub struct NewsItem {
+    pub id: i64,
+    pub author: String,
+    pub body: String,
+    pub posted_at: i64,
+}
+
+/// A poll with the votes cast for each of its options.
+#[derive(Debug)]
+pub struct PollResults {
+    pub id: i64,
+    pub question: String,
+    pub author: String,
+    pub created_at: i64,
+    pub closed: bool,
+    pub options: Vec<PollOptionResults>,
+}
+
+/// An option of a poll and how many votes it received.
+#[derive(Debug)]
+pub struct PollOptionResults {
+    pub text: String,
+    pub votes: i64,
+}
+
+/// The outcome of renaming a player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rename {
+    Renamed,
+    NotFound,
+    Taken,
 }
 
 #[async_trait]
@@ -64,6 +228,10 @@ pub trait GameDb {
     async fn has_player(&self, user: &str) -> anyhow::Result<bool>;
     async fn create_player(&self, user: &str, hash: &str, room: RoomId) -> anyhow::Result<i64>;
     async fn load_player(&self, world: &mut World, name: &str) -> anyhow::Result<Entity>;
+    async fn load_vault(&self, world: &mut World, player: Entity) -> anyhow::Result<()>;
+    async fn load_region(&self, world: &mut World, region: &str) -> DbResult<()>;
+    async fn load_room(&self, world: &mut World, room: RoomId) -> DbResult<()>;
+    async fn aggregate_leaderboards(&self, world: &mut World) -> DbResult<()>;
     async fn reload_prototype(
         &self,
         world: &mut World,
@@ -118,6 +286,111 @@ impl Db {
         self.pool.clone()
     }
 
+    /// Runs the content migrations which have not been applied yet, returning how many ran.
+    pub async fn migrate_content(
+        &self,
+        options: MigrationOptions,
+        path: Option<&str>,
+    ) -> DbResult<usize> {
+        content::migrate_content(&self.pool, options, path).await
+    }
+
+    /// Checks for dangling references to prototypes, rooms and scripts, removing them if asked.
+    pub async fn check_integrity(&self, repair: bool) -> DbResult<Vec<check::Issue>> {
+        check::check_integrity(&self.pool, repair).await
+    }
+
+    /// Creates an immortal administrator in the spawn room, returning their ID.
+    pub async fn create_immortal(&self, user: &str, hash: &str) -> anyhow::Result<i64> {
+        let spawn_room = sqlx::query(r#"SELECT value FROM config WHERE key = "spawn_room""#)
+            .fetch_one(&self.pool)
+            .await?
+            .get::<String, _>("value")
+            .parse::<RoomId>()
+            .map_err(|_| Error::Deserialize("spawn room config value"))?;
+
+        let id = self.create_player(user, hash, spawn_room).await?;
+
+        sqlx::query("UPDATE players SET flags = ? WHERE id = ?")
+            .bind((types::player::Flags::IMMORTAL | types::player::Flags::ADMIN).bits())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(id)
+    }
+
+    /// Renames a player everywhere they are referred to by name.
+    pub async fn rename_player(&self, old: &str, new: &str) -> DbResult<Rename> {
+        player::rename_player(&self.pool, old, new).await
+    }
+
+    /// Sets when a player is to be deleted, in seconds since the Unix epoch.
+    pub async fn schedule_deletion(&self, name: &str, delete_after: i64) -> DbResult<()> {
+        player::schedule_deletion(&self.pool, name, delete_after).await
+    }
+
+    /// Cancels a player's deletion if it is not yet due, returning whether one was cancelled.
+    pub async fn cancel_deletion(&self, name: &str, now: i64) -> DbResult<bool> {
+        player::cancel_deletion(&self.pool, name, now).await
+    }
+
+    /// Whether a player is due to be deleted.
+    pub async fn deletion_due(&self, name: &str, now: i64) -> DbResult<bool> {
+        player::deletion_due(&self.pool, name, now).await
+    }
+
+    /// The ID of a player if they are a builder, limited to scripting within regions they own.
+    /// API tokens are limited like the player who created them.
+    pub async fn builder_id(&self, name: &str) -> DbResult<Option<PlayerId>> {
+        match name.strip_prefix(API_TOKEN_PLAYER_PREFIX) {
+            Some(token) => player::token_builder_id(&self.pool, token).await,
+            None => player::builder_id(&self.pool, name).await,
+        }
+    }
+
+    /// The ID of the player credited with what a player or API token writes. API tokens credit
+    /// the player who created them.
+    pub async fn author_id(&self, name: &str) -> DbResult<PlayerId> {
+        match name.strip_prefix(API_TOKEN_PLAYER_PREFIX) {
+            Some(token) => player::token_creator_id(&self.pool, token).await,
+            None => player::player_id(&self.pool, name).await,
+        }
+    }
+
+    /// Makes a player who asked to be erased due for deletion, returning false if they had not.
+    pub async fn approve_erasure(&self, name: &str, now: i64) -> DbResult<bool> {
+        player::approve_erasure(&self.pool, name, now).await
+    }
+
+    /// Gathers everything stored about a player, or None if they do not exist.
+    pub async fn export_player(&self, name: &str) -> DbResult<Option<PlayerExport>> {
+        match self.player_achievements(name).await? {
+            Some(achievements) => player::export_player(&self.pool, name, achievements).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Deletes the players whose deletion is due, returning their names.
+    pub async fn purge_deleted_players(&self, now: i64) -> DbResult<Vec<String>> {
+        player::purge_deleted_players(&self.pool, now).await
+    }
+
+    /// Dumps the world's content, without any player data.
+    pub async fn export_world(&self) -> DbResult<export::WorldExport> {
+        export::export_world(&self.pool).await
+    }
+
+    /// Replaces the world's content with a dump made by `export_world`.
+    pub async fn import_world(&self, export: &export::WorldExport) -> DbResult<()> {
+        export::import_world(&self.pool, export).await
+    }
+
+    /// Whether the database can be reached.
+    pub async fn is_available(&self) -> bool {
+        sqlx::query("SELECT 1").execute(&self.pool).await.is_ok()
+    }
+
     pub async fn vacuum(&self) -> Result<(), sqlx::Error> {
         sqlx::query("VACUUM").execute(&self.pool).await?;
         Ok(())
@@ -217,6 +490,218 @@ impl AuthDb for Db {
 
         Ok(results.map(|r| r.get("refresh")))
     }
+
+    async fn player_achievements(
+        &self,
+        player: &str,
+    ) -> Result<Option<Vec<EarnedAchievement>>, Error> {
+        let id = sqlx::query("SELECT id FROM players WHERE username = ?")
+            .bind(player)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let id: i64 = match id {
+            Some(row) => row.get("id"),
+            None => return Ok(None),
+        };
+
+        let achievements = sqlx::query_as::<_, EarnedAchievement>(
+            r#"SELECT achievements.id, achievements.name, achievements.description,
+                    player_achievements.earned_at
+                FROM player_achievements
+                INNER JOIN achievements ON player_achievements.achievement_id = achievements.id
+                WHERE player_achievements.player_id = ?
+                ORDER BY player_achievements.earned_at, achievements.id"#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Some(achievements))
+    }
+
+    async fn leaderboard(
+        &self,
+        category: LeaderboardCategory,
+    ) -> Result<Vec<LeaderboardEntry>, Error> {
+        leaderboard::load_leaderboard(&self.pool, category).await
+    }
+
+    async fn session_stats(&self) -> Result<SessionStats, Error> {
+        Ok(sqlx::query_as::<_, SessionStats>(
+            r#"SELECT
+                (SELECT COUNT(*) FROM players) AS players,
+                (SELECT COUNT(DISTINCT player_id) FROM sessions WHERE disconnected_at IS NULL)
+                    AS players_online,
+                (SELECT COUNT(*) FROM sessions WHERE connected_at >= datetime('now', '-1 day'))
+                    AS sessions_last_day,
+                (SELECT COUNT(DISTINCT player_id) FROM sessions
+                    WHERE connected_at >= datetime('now', '-1 day')) AS unique_players_last_day,
+                (SELECT COALESCE(CAST(AVG(strftime('%s', disconnected_at)
+                        - strftime('%s', connected_at)) AS INTEGER), 0)
+                    FROM sessions WHERE disconnected_at IS NOT NULL) AS average_session_secs,
+                (SELECT COALESCE(SUM(playtime), 0) FROM players) AS total_playtime_secs"#,
+        )
+        .fetch_one(&self.pool)
+        .await?)
+    }
+
+    async fn market_listings(&self) -> Result<Vec<MarketListing>, Error> {
+        Ok(sqlx::query_as::<_, MarketListing>(
+            r#"SELECT market_listings.id, COALESCE(objects.name, prototypes.name) AS name,
+                    market_listings.price, players.username AS seller, market_listings.expires_at
+                FROM market_listings
+                INNER JOIN objects ON objects.id = market_listings.object_id
+                INNER JOIN prototypes ON prototypes.id = objects.prototype_id
+                INNER JOIN players ON players.id = market_listings.seller_id
+                WHERE market_listings.expires_at > CAST(strftime('%s', 'now') AS INTEGER)
+                ORDER BY market_listings.id"#,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn news(&self) -> Result<Vec<NewsItem>, Error> {
+        Ok(sqlx::query_as::<_, NewsItem>(
+            "SELECT id, author, body, posted_at FROM news ORDER BY id DESC",
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn polls(&self) -> Result<Vec<PollResults>, Error> {
+        let mut polls = sqlx::query(
+            "SELECT id, question, author, created_at, closed FROM polls ORDER BY id DESC",
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| PollResults {
+            id: row.get("id"),
+            question: row.get("question"),
+            author: row.get("author"),
+            created_at: row.get("created_at"),
+            closed: row.get("closed"),
+            options: Vec::new(),
+        })
+        .collect_vec();
+
+        let options = sqlx::query(
+            r#"SELECT poll_options.poll_id, poll_options.text, COUNT(poll_votes.player_id) AS votes
+                FROM poll_options
+                LEFT JOIN poll_votes ON poll_votes.poll_id = poll_options.poll_id
+                    AND poll_votes.position = poll_options.position
+                GROUP BY poll_options.poll_id, poll_options.position
+                ORDER BY poll_options.poll_id, poll_options.position"#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        for row in options {
+            let id = row.get::<i64, _>("poll_id");
+            if let Some(poll) = polls.iter_mut().find(|poll| poll.id == id) {
+                poll.options.push(PollOptionResults {
+                    text: row.get("text"),
+                    votes: row.get("votes"),
+                });
+            }
+        }
+
+        Ok(polls)
+    }
+
+    async fn request_erasure(&self, player: &str) -> Result<bool, Error> {
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO erasure_requests (player_id) SELECT id FROM players WHERE \
+             username = ?",
+        )
+        .bind(player)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn erasure_requests(&self) -> Result<Vec<ErasureRequest>, Error> {
+        Ok(sqlx::query_as::<_, ErasureRequest>(
+            r#"SELECT players.username AS name, erasure_requests.requested_at
+                FROM erasure_requests
+                INNER JOIN players ON erasure_requests.player_id = players.id
+                ORDER BY erasure_requests.requested_at, players.username"#,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn create_api_token(
+        &self,
+        name: &str,
+        hash: &str,
+        scopes: &str,
+        created_by: &str,
+        created_at: i64,
+    ) -> Result<Option<i64>, Error> {
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO api_tokens (name, hash, scopes, created_by, created_at) VALUES \
+             (?, ?, ?, ?, ?)",
+        )
+        .bind(name)
+        .bind(hash)
+        .bind(scopes)
+        .bind(created_by)
+        .bind(created_at)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            Ok(Some(result.last_insert_rowid()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn api_token(&self, id: i64) -> Result<Option<ApiToken>, Error> {
+        Ok(sqlx::query_as::<_, ApiToken>(
+            r#"SELECT id, name, hash, scopes, created_by, created_at, last_used_at
+                FROM api_tokens WHERE id = ?"#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    async fn api_tokens(&self) -> Result<Vec<ApiToken>, Error> {
+        Ok(sqlx::query_as::<_, ApiToken>(
+            r#"SELECT id, name, hash, scopes, created_by, created_at, last_used_at
+                FROM api_tokens ORDER BY name"#,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn touch_api_token(&self, id: i64, used_at: i64) -> Result<(), Error> {
+        sqlx::query("UPDATE api_tokens SET last_used_at = ? WHERE id = ?")
+            .bind(used_at)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn revoke_api_token(&self, name: &str) -> Result<bool, Error> {
+        let result = sqlx::query("DELETE FROM api_tokens WHERE name = ?")
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn ping(&self) -> Result<(), Error> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -243,16 +728,16 @@ impl GameDb for Db {
         .bind(hash)
         .bind(room)
         .bind(DEFAULT_PLAYER_DESCRIPTION)
-        .bind(0)
+        .bind(types::player::Flags::TUTORIAL.bits())
         .fetch_one(&self.pool)
         .await?;
 
         let id = results.get("id");
 
-        // Player 1 is always an immortal by default.
+        // Player 1 is always an immortal administrator by default.
         if id == 1 {
             sqlx::query("UPDATE players SET flags = ? WHERE username = ?")
-                .bind(types::player::Flags::IMMORTAL.bits())
+                .bind((types::player::Flags::IMMORTAL | types::player::Flags::ADMIN).bits())
                 .bind(user)
                 .execute(&self.pool)
                 .await?;
@@ -265,20 +750,38 @@ impl GameDb for Db {
         player::load_player(&self.pool, world, name).await
     }
 
+    async fn load_vault(&self, world: &mut World, player: Entity) -> anyhow::Result<()> {
+        player::load_player_vault(&self.pool, world, player).await
+    }
+
+    async fn load_region(&self, world: &mut World, region: &str) -> DbResult<()> {
+        world::load_region(&self.pool, world, region).await
+    }
+
+    async fn load_room(&self, world: &mut World, room: RoomId) -> DbResult<()> {
+        world::load_room(&self.pool, world, room).await
+    }
+
+    async fn aggregate_leaderboards(&self, world: &mut World) -> DbResult<()> {
+        leaderboard::aggregate_leaderboards(&self.pool, world).await
+    }
+
     #[tracing::instrument(name = "reload prototype", skip(self, world))]
     async fn reload_prototype(
         &self,
         world: &mut World,
         prototype_id: PrototypeId,
     ) -> anyhow::Result<()> {
+        // Objects of the prototype are found through its index rather than by walking who owns
+        // what. Those which are not loaded, such as objects in vaults which have not been opened
+        // yet, are skipped and pick up the changes when loaded.
         let mut results = sqlx::query_as::<_, ObjectRow>(
             r#"SELECT objects.id, objects.prototype_id, objects.inherit_scripts, NULL AS location,
                         COALESCE(objects.name, prototypes.name) AS name, COALESCE(objects.description, prototypes.description) AS description,
                         COALESCE(objects.flags, prototypes.flags) AS flags, COALESCE(objects.keywords, prototypes.keywords) AS keywords
                     FROM objects
-                    INNER JOIN room_objects ON room_objects.object_id = objects.id
                     INNER JOIN prototypes ON objects.prototype_id = prototypes.id
-                    WHERE prototypes.id = ?"#,
+                    WHERE objects.prototype_id = ?"#,
         )
         .bind(prototype_id)
         .fetch(&self.pool);
@@ -289,15 +792,16 @@ impl GameDb for Db {
             .by_id(prototype_id)
             .unwrap();
 
+        let mut reloaded = Vec::new();
+
         while let Some(object_row) = results.try_next().await? {
             let inherit_scripts = object_row.inherit_scripts;
 
             let object_id = ObjectId::try_from(object_row.id)?;
-            let object_entity = world
-                .get_resource::<Objects>()
-                .unwrap()
-                .by_id(object_id)
-                .unwrap();
+            let object_entity = match world.get_resource::<Objects>().unwrap().by_id(object_id) {
+                Some(entity) => entity,
+                None => continue,
+            };
 
             let (id, object, named, description, flags, keywords) =
                 object_row.into_components(prototype)?;
@@ -312,90 +816,63 @@ impl GameDb for Db {
                 .insert(keywords);
 
             if inherit_scripts {
-                let mut results = sqlx::query_as::<_, HookRow>(
-                    r#"SELECT kind, script, trigger FROM prototype_scripts WHERE prototype_id = ?"#,
-                )
-                .bind(prototype_id)
-                .fetch(&self.pool);
-
-                world.entity_mut(object_entity).remove::<ScriptHooks>();
+                reloaded.push(object_entity);
+            }
+        }
 
-                while let Some(hook_row) = results.try_next().await? {
-                    let hook = ScriptHook::try_from(hook_row)?;
+        if reloaded.is_empty() {
+            return Ok(());
+        }
 
-                    if let Some(mut hooks) = world.get_mut::<ScriptHooks>(object_entity) {
-                        hooks.insert(hook);
-                    } else {
-                        world
-                            .entity_mut(object_entity)
-                            .insert(ScriptHooks::new(hook));
-                    }
+        let hooks = sqlx::query_as::<_, HookRow>(
+            r#"SELECT kind, script, trigger, priority, enabled
+                FROM prototype_scripts WHERE prototype_id = ?"#,
+        )
+        .bind(prototype_id)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(ScriptHook::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+        for object_entity in reloaded {
+            world.entity_mut(object_entity).remove::<ScriptHooks>();
+
+            for hook in hooks.iter() {
+                if let Some(mut hooks) = world.get_mut::<ScriptHooks>(object_entity) {
+                    hooks.insert(hook.clone());
+                } else {
+                    world
+                        .entity_mut(object_entity)
+                        .insert(ScriptHooks::new(hook.clone()));
                 }
             }
         }
 
-        let player_objects = {
-            world
-                .query_filtered::<&Contents, With<Player>>()
-                .iter(&*world)
-                .flat_map(|contents| contents.get_objects())
-                .dedup()
-                .collect_vec()
-        };
-
-        for object_entity in player_objects {
-            let id = world.get::<Object>(object_entity).unwrap().id();
-            let object_row = sqlx::query_as::<_, ObjectRow>(
-            r#"SELECT objects.id, objects.prototype_id, objects.inherit_scripts, NULL AS location,
-                        COALESCE(objects.name, prototypes.name) AS name, COALESCE(objects.description, prototypes.description) AS description,
-                        COALESCE(objects.flags, prototypes.flags) AS flags, COALESCE(objects.keywords, prototypes.keywords) AS keywords
-                    FROM objects
-                    INNER JOIN prototypes ON objects.prototype_id = prototypes.id
-                    WHERE objects.id = ?"#,
-            )
-            .bind(id)
-            .fetch_one(&self.pool).await?;
-
-            let inherit_scripts = object_row.inherit_scripts;
+        Ok(())
+    }
+}
 
-            let prototype_id = object_row.prototype_id;
-            let prototype = world
-                .get_resource::<Prototypes>()
-                .unwrap()
-                .by_id(PrototypeId::try_from(object_row.prototype_id)?)
-                .unwrap();
+#[derive(Debug, sqlx::FromRow)]
+struct FieldRow {
+    id: i64,
+    key: String,
+    kind: String,
+    value: String,
+}
 
-            let (id, object, named, description, flags, keywords) =
-                object_row.into_components(prototype)?;
+impl FieldRow {
+    // Adds the field to the entity's custom fields.
+    fn insert_into(self, world: &mut World, entity: Entity) -> DbResult<()> {
+        let value = FieldValue::from_stored(self.kind.as_str(), self.value)
+            .ok_or(Error::Deserialize("field value"))?;
 
+        if let Some(mut fields) = world.get_mut::<Fields>(entity) {
+            fields.insert(self.key, value);
+        } else {
             world
-                .entity_mut(object_entity)
-                .insert(id)
-                .insert(object)
-                .insert(named)
-                .insert(description)
-                .insert(flags)
-                .insert(keywords);
-
-            if inherit_scripts {
-                let mut results = sqlx::query_as::<_, HookRow>(
-                    r#"SELECT kind, script, trigger FROM prototype_scripts WHERE prototype_id = ?"#,
-                )
-                .bind(prototype_id)
-                .fetch(&self.pool);
-
-                while let Some(hook_row) = results.try_next().await? {
-                    let hook = ScriptHook::try_from(hook_row)?;
-
-                    if let Some(mut hooks) = world.get_mut::<ScriptHooks>(object_entity) {
-                        hooks.insert(hook)
-                    } else {
-                        world
-                            .entity_mut(object_entity)
-                            .insert(ScriptHooks::new(hook));
-                    }
-                }
-            }
+                .entity_mut(entity)
+                .insert(Fields::new_with_entry(self.key, value));
         }
 
         Ok(())
@@ -407,6 +884,8 @@ struct HookRow {
     kind: String,
     script: String,
     trigger: String,
+    priority: i64,
+    enabled: bool,
 }
 
 impl TryFrom<HookRow> for ScriptHook {
@@ -418,6 +897,7 @@ impl TryFrom<HookRow> for ScriptHook {
         let kind = TriggerKind::from_str(value.kind.as_str())
             .map_err(|_| Error::Deserialize("script trigger kind"))?;
         let trigger = match kind {
+            TriggerKind::Custom => ScriptTrigger::Custom(value.trigger),
             TriggerKind::Init => ScriptTrigger::Init,
             TriggerKind::PreEvent => {
                 let trigger = TriggerEvent::from_str(value.trigger.as_str())
@@ -432,7 +912,12 @@ impl TryFrom<HookRow> for ScriptHook {
             TriggerKind::Timer => ScriptTrigger::Timer(value.trigger),
         };
 
-        Ok(ScriptHook { script, trigger })
+        Ok(ScriptHook {
+            script,
+            trigger,
+            priority: value.priority,
+            enabled: value.enabled,
+        })
     }
 }
 
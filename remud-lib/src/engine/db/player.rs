@@ -1,26 +1,40 @@
-use std::convert::TryFrom;
+use std::{convert::TryFrom, str::FromStr};
 
 use anyhow::bail;
 use bevy_app::Events;
 use bevy_ecs::prelude::*;
 use futures::TryStreamExt;
-use sqlx::SqlitePool;
+use itertools::Itertools;
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
 
 use crate::{
-    engine::db::HookRow,
+    engine::db::{
+        DbResult, EarnedAchievement, Error, ExportedObject, FiledReport, HookRow, PlayerExport,
+        PlayerSession, Rename,
+    },
     world::{
+        action::immortal::region::is_builder,
         scripting::{RunInitScript, ScriptHook, ScriptHooks, TriggerKind},
         types::{
+            achievement::{AchievementId, EarnedAchievements},
+            bank::Vault,
+            faction::{FactionId, Reputation},
+            grammar::Pronouns,
+            news::{NewsId, NewsRead},
             object::{ObjectId, Objects, PrototypeId, Prototypes},
-            player::{Messages, Player, PlayerBundle, PlayerFlags, PlayerId, Players},
+            player::{
+                ClientCaps, CommunicationLog, Ignores, Messages, Needs, Player, PlayerBundle,
+                PlayerFlags, PlayerId, Players, Playtime, VisitedRooms,
+            },
             room::{Room, RoomId, Rooms},
+            tutorial::{HintTrigger, SeenHints},
             Contents, Description, Id, Location, Named,
         },
         VOID_ROOM_ID,
     },
 };
 use crate::{
-    engine::db::ObjectRow,
+    engine::db::{FieldRow, ObjectRow},
     world::types::{Attributes, Health},
 };
 
@@ -31,7 +45,8 @@ pub async fn load_player(
 ) -> anyhow::Result<Entity> {
     let (player, id) = {
         let player_row = sqlx::query_as::<_, PlayerRow>(
-            "SELECT id, description, room, flags FROM players WHERE username = ?",
+            "SELECT id, description, room, flags, playtime, pronouns, hunger, thirst, news_read \
+             FROM players WHERE username = ?",
         )
         .bind(name)
         .fetch_one(pool)
@@ -39,6 +54,15 @@ pub async fn load_player(
 
         let id = PlayerId::try_from(player_row.id)?;
 
+        // A player logging in to a room which is not loaded brings its regions into the world.
+        if let Ok(room) = RoomId::try_from(player_row.room) {
+            if world.get_resource::<Rooms>().unwrap().is_unloaded(room) {
+                if let Err(error) = super::world::load_room(pool, world, room).await {
+                    tracing::warn!("failed to load room {} for {}: {}", room, name, error);
+                }
+            }
+        }
+
         let room = RoomId::try_from(player_row.room)
             .ok()
             .and_then(|id| world.get_resource::<Rooms>().unwrap().by_id(id))
@@ -58,13 +82,30 @@ pub async fn load_player(
                 id: Id::Player(id),
                 player: Player::from(id),
                 messages: Messages::default(),
+                ignores: Ignores::default(),
+                communication_log: CommunicationLog::default(),
+                achievements: EarnedAchievements::default(),
+                reputation: Reputation::default(),
+                playtime: Playtime::new(player_row.playtime),
+                visited: VisitedRooms::default(),
+                seen_hints: SeenHints::default(),
+                client_caps: ClientCaps::default(),
                 name: Named::from(name.to_string()),
+                pronouns: Pronouns::from_str(player_row.pronouns.as_str()).unwrap_or_default(),
                 description: Description::from(player_row.description),
                 flags: PlayerFlags::from(player_row.flags),
                 location: Location::from(room),
                 contents: Contents::default(),
+                vault: Vault::default(),
                 health: Health::new(&attributes),
                 attributes,
+                needs: Needs::new(
+                    player_row.hunger.clamp(0, Needs::MAX.into()) as u8,
+                    player_row.thirst.clamp(0, Needs::MAX.into()) as u8,
+                ),
+                news_read: NewsId::try_from(player_row.news_read)
+                    .map(NewsRead::from)
+                    .unwrap_or_default(),
             })
             .id();
 
@@ -80,10 +121,17 @@ pub async fn load_player(
 
     load_player_inventory(pool, world, name, player).await?;
     load_player_scripts(pool, world, id, player).await?;
+    load_player_ignores(pool, world, id, player).await?;
+    load_player_achievements(pool, world, id, player).await?;
+    load_player_reputation(pool, world, id, player).await?;
+    load_player_visits(pool, world, id, player).await?;
+    load_player_hints(pool, world, id, player).await?;
 
     Ok(player)
 }
 
+// Carried and worn objects are loaded with the player, as nearly every action looks at them. Only
+// vaults wait until they are first used.
 async fn load_player_inventory(
     pool: &SqlitePool,
     world: &mut World,
@@ -104,69 +152,123 @@ async fn load_player_inventory(
     .fetch(pool);
 
     while let Some(object_row) = results.try_next().await? {
-        let id = ObjectId::try_from(object_row.id)?;
-        let inherit_scripts = object_row.inherit_scripts;
-        let prototype_id = object_row.prototype_id;
+        let object = load_player_object(pool, world, object_row, player).await?;
 
-        let object = {
-            let prototype = match world
-                .get_resource::<Prototypes>()
-                .unwrap()
-                .by_id(PrototypeId::try_from(object_row.prototype_id)?)
-            {
-                Some(entity) => entity,
-                None => bail!("Prototype {} not found", object_row.prototype_id),
-            };
+        world.get_mut::<Contents>(player).unwrap().insert(object);
+    }
 
-            let bundle = object_row.into_object_bundle(prototype, Location::from(player))?;
-            let object_entity = world.spawn().insert_bundle(bundle).id();
+    Ok(())
+}
 
-            world
-                .get_mut::<Contents>(player)
-                .unwrap()
-                .insert(object_entity);
+// Vaults are only needed inside banks, so they are loaded on first use instead of at login.
+pub async fn load_player_vault(
+    pool: &SqlitePool,
+    world: &mut World,
+    player: Entity,
+) -> anyhow::Result<()> {
+    let id = match world.get::<Vault>(player) {
+        Some(vault) if !vault.is_loaded() => world.get::<Player>(player).unwrap().id(),
+        _ => return Ok(()),
+    };
 
-            world
-                .get_resource_mut::<Objects>()
-                .unwrap()
-                .insert(id, object_entity);
+    let mut results = sqlx::query_as::<_, ObjectRow>(
+        r#"SELECT objects.id, objects.prototype_id, objects.inherit_scripts, NULL AS location,
+                    COALESCE(objects.name, prototypes.name) AS name, COALESCE(objects.description, prototypes.description) AS description,
+                    COALESCE(objects.flags, prototypes.flags) AS flags, COALESCE(objects.keywords, prototypes.keywords) AS keywords
+                FROM objects
+                INNER JOIN bank_objects ON bank_objects.object_id = objects.id
+                INNER JOIN prototypes ON objects.prototype_id = prototypes.id
+                    WHERE bank_objects.player_id = ?"#,
+    )
+    .bind(id)
+    .fetch(pool);
 
-            object_entity
-        };
+    while let Some(object_row) = results.try_next().await? {
+        let object = load_player_object(pool, world, object_row, player).await?;
 
-        let mut results = if inherit_scripts {
-            sqlx::query_as::<_, HookRow>(
-                r#"SELECT kind, script, trigger FROM prototype_scripts WHERE prototype_id = ?"#,
-            )
-            .bind(prototype_id)
-            .fetch(pool)
-        } else {
-            sqlx::query_as::<_, HookRow>(
-                r#"SELECT kind, script, trigger FROM object_scripts WHERE object_id = ?"#,
-            )
-            .bind(id)
-            .fetch(pool)
+        world.get_mut::<Vault>(player).unwrap().insert(object);
+    }
+
+    world.get_mut::<Vault>(player).unwrap().set_loaded();
+
+    Ok(())
+}
+
+async fn load_player_object(
+    pool: &SqlitePool,
+    world: &mut World,
+    object_row: ObjectRow,
+    player: Entity,
+) -> anyhow::Result<Entity> {
+    let id = ObjectId::try_from(object_row.id)?;
+    let inherit_scripts = object_row.inherit_scripts;
+    let prototype_id = object_row.prototype_id;
+
+    let object = {
+        let prototype = match world
+            .get_resource::<Prototypes>()
+            .unwrap()
+            .by_id(PrototypeId::try_from(object_row.prototype_id)?)
+        {
+            Some(entity) => entity,
+            None => bail!("Prototype {} not found", object_row.prototype_id),
         };
 
-        while let Some(hook_row) = results.try_next().await? {
-            let hook = ScriptHook::try_from(hook_row)?;
+        let bundle = object_row.into_object_bundle(prototype, Location::from(player))?;
+        let object_entity = world.spawn().insert_bundle(bundle).id();
 
-            if hook.trigger.kind() == TriggerKind::Init {
-                world
-                    .get_resource_mut::<Events<RunInitScript>>()
-                    .unwrap()
-                    .send(RunInitScript::new(object, hook.script.clone()));
-            }
+        world
+            .get_resource_mut::<Objects>()
+            .unwrap()
+            .insert(id, object_entity);
 
-            if let Some(mut hooks) = world.get_mut::<ScriptHooks>(object) {
-                hooks.insert(hook)
-            } else {
-                world.entity_mut(object).insert(ScriptHooks::new(hook));
-            }
+        object_entity
+    };
+
+    let mut results = sqlx::query_as::<_, FieldRow>(
+        r#"SELECT object_id AS id, key, kind, value FROM object_fields WHERE object_id = ?"#,
+    )
+    .bind(id)
+    .fetch(pool);
+
+    while let Some(field_row) = results.try_next().await? {
+        field_row.insert_into(world, object)?;
+    }
+
+    let mut results = if inherit_scripts {
+        sqlx::query_as::<_, HookRow>(
+            r#"SELECT kind, script, trigger, priority, enabled
+                FROM prototype_scripts WHERE prototype_id = ?"#,
+        )
+        .bind(prototype_id)
+        .fetch(pool)
+    } else {
+        sqlx::query_as::<_, HookRow>(
+            r#"SELECT kind, script, trigger, priority, enabled
+                FROM object_scripts WHERE object_id = ?"#,
+        )
+        .bind(id)
+        .fetch(pool)
+    };
+
+    while let Some(hook_row) = results.try_next().await? {
+        let hook = ScriptHook::try_from(hook_row)?;
+
+        if hook.trigger.kind() == TriggerKind::Init {
+            world
+                .get_resource_mut::<Events<RunInitScript>>()
+                .unwrap()
+                .send(RunInitScript::new(object, hook.script.clone()));
+        }
+
+        if let Some(mut hooks) = world.get_mut::<ScriptHooks>(object) {
+            hooks.insert(hook)
+        } else {
+            world.entity_mut(object).insert(ScriptHooks::new(hook));
         }
     }
 
-    Ok(())
+    Ok(object)
 }
 
 async fn load_player_scripts(
@@ -176,7 +278,8 @@ async fn load_player_scripts(
     player: Entity,
 ) -> anyhow::Result<()> {
     let mut results = sqlx::query_as::<_, HookRow>(
-        r#"SELECT kind, script, trigger FROM player_scripts WHERE player_id = ?"#,
+        r#"SELECT kind, script, trigger, priority, enabled
+            FROM player_scripts WHERE player_id = ?"#,
     )
     .bind(id)
     .fetch(pool);
@@ -194,10 +297,451 @@ async fn load_player_scripts(
     Ok(())
 }
 
+async fn load_player_ignores(
+    pool: &SqlitePool,
+    world: &mut World,
+    id: PlayerId,
+    player: Entity,
+) -> anyhow::Result<()> {
+    let mut results = sqlx::query_as::<_, IgnoreRow>(
+        r#"SELECT player_ignores.ignored_id, players.username FROM player_ignores
+                INNER JOIN players ON player_ignores.ignored_id = players.id
+                WHERE player_ignores.player_id = ?"#,
+    )
+    .bind(id)
+    .fetch(pool);
+
+    while let Some(ignore_row) = results.try_next().await? {
+        let ignored = PlayerId::try_from(ignore_row.ignored_id)?;
+
+        world
+            .get_mut::<Ignores>(player)
+            .unwrap()
+            .insert(ignored, ignore_row.username);
+    }
+
+    Ok(())
+}
+
+async fn load_player_achievements(
+    pool: &SqlitePool,
+    world: &mut World,
+    id: PlayerId,
+    player: Entity,
+) -> anyhow::Result<()> {
+    let mut results = sqlx::query_as::<_, (AchievementId,)>(
+        r#"SELECT achievement_id FROM player_achievements WHERE player_id = ?"#,
+    )
+    .bind(id)
+    .fetch(pool);
+
+    while let Some((achievement,)) = results.try_next().await? {
+        world
+            .get_mut::<EarnedAchievements>(player)
+            .unwrap()
+            .insert(achievement);
+    }
+
+    Ok(())
+}
+
+async fn load_player_reputation(
+    pool: &SqlitePool,
+    world: &mut World,
+    id: PlayerId,
+    player: Entity,
+) -> anyhow::Result<()> {
+    let mut results = sqlx::query_as::<_, (FactionId, i64)>(
+        r#"SELECT faction_id, reputation FROM player_reputation WHERE player_id = ?"#,
+    )
+    .bind(id)
+    .fetch(pool);
+
+    while let Some((faction, reputation)) = results.try_next().await? {
+        world
+            .get_mut::<Reputation>(player)
+            .unwrap()
+            .insert(faction, reputation);
+    }
+
+    Ok(())
+}
+
+async fn load_player_visits(
+    pool: &SqlitePool,
+    world: &mut World,
+    id: PlayerId,
+    player: Entity,
+) -> anyhow::Result<()> {
+    let mut results =
+        sqlx::query_as::<_, (i64,)>(r#"SELECT room_id FROM player_rooms WHERE player_id = ?"#)
+            .bind(id)
+            .fetch(pool);
+
+    while let Some((room,)) = results.try_next().await? {
+        world
+            .get_mut::<VisitedRooms>(player)
+            .unwrap()
+            .insert(RoomId::try_from(room)?);
+    }
+
+    Ok(())
+}
+
+async fn load_player_hints(
+    pool: &SqlitePool,
+    world: &mut World,
+    id: PlayerId,
+    player: Entity,
+) -> anyhow::Result<()> {
+    let mut results =
+        sqlx::query_as::<_, (String,)>(r#"SELECT trigger FROM player_hints WHERE player_id = ?"#)
+            .bind(id)
+            .fetch(pool);
+
+    while let Some((trigger,)) = results.try_next().await? {
+        world
+            .get_mut::<SeenHints>(player)
+            .unwrap()
+            .insert(HintTrigger::from_str(trigger.as_str())?);
+    }
+
+    Ok(())
+}
+
+// Renames a player, carrying their allowlist entry and what they have authored over to the new
+// name. Web tokens name the player they were issued to, so the player's tokens are revoked.
+pub async fn rename_player(pool: &SqlitePool, old: &str, new: &str) -> DbResult<Rename> {
+    let mut tx = pool.begin().await?;
+
+    let id = match sqlx::query("SELECT id FROM players WHERE username = ?")
+        .bind(old)
+        .fetch_optional(&mut tx)
+        .await?
+    {
+        Some(row) => row.get::<i64, _>("id"),
+        None => return Ok(Rename::NotFound),
+    };
+
+    if sqlx::query("SELECT id FROM players WHERE username = ?")
+        .bind(new)
+        .fetch_optional(&mut tx)
+        .await?
+        .is_some()
+    {
+        return Ok(Rename::Taken);
+    }
+
+    sqlx::query("UPDATE players SET username = ? WHERE id = ?")
+        .bind(new)
+        .bind(id)
+        .execute(&mut tx)
+        .await?;
+
+    sqlx::query("UPDATE OR IGNORE allowlist SET name = ? WHERE name = ?")
+        .bind(new.to_lowercase())
+        .bind(old.to_lowercase())
+        .execute(&mut tx)
+        .await?;
+
+    sqlx::query("DELETE FROM tokens WHERE player_id = ?")
+        .bind(id)
+        .execute(&mut tx)
+        .await?;
+
+    sqlx::query("UPDATE news SET author = ? WHERE author = ?")
+        .bind(new)
+        .bind(old)
+        .execute(&mut tx)
+        .await?;
+
+    sqlx::query("UPDATE polls SET author = ? WHERE author = ?")
+        .bind(new)
+        .bind(old)
+        .execute(&mut tx)
+        .await?;
+
+    sqlx::query("UPDATE api_tokens SET created_by = ? WHERE created_by = ?")
+        .bind(new)
+        .bind(old)
+        .execute(&mut tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Rename::Renamed)
+}
+
+// Sets when a player is to be deleted.
+pub async fn schedule_deletion(pool: &SqlitePool, name: &str, delete_after: i64) -> DbResult<()> {
+    sqlx::query("UPDATE players SET delete_after = ? WHERE username = ?")
+        .bind(delete_after)
+        .bind(name)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+// Cancels a player's deletion if it is not yet due, returning whether one was cancelled.
+pub async fn cancel_deletion(pool: &SqlitePool, name: &str, now: i64) -> DbResult<bool> {
+    let result = sqlx::query(
+        "UPDATE players SET delete_after = NULL WHERE username = ? AND delete_after > ?",
+    )
+    .bind(name)
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// Whether a player is due to be deleted, after which they may no longer log in.
+pub async fn deletion_due(pool: &SqlitePool, name: &str, now: i64) -> DbResult<bool> {
+    let row = sqlx::query("SELECT id FROM players WHERE username = ? AND delete_after <= ?")
+        .bind(name)
+        .bind(now)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.is_some())
+}
+
+// Retrieves the ID of a player who is a builder, and so limited to the regions they own. Fails
+// with missing data if the player does not exist, so changes by them are refused rather than left
+// unrestricted.
+pub async fn builder_id(pool: &SqlitePool, name: &str) -> DbResult<Option<PlayerId>> {
+    let row = sqlx::query("SELECT id, flags FROM players WHERE username = ?")
+        .bind(name)
+        .fetch_optional(pool)
+        .await?;
+
+    builder_from(row)
+}
+
+// Retrieves the ID of the builder who created an API token, whose regions limit the token. Fails
+// with missing data if the token or its creator no longer exist.
+pub async fn token_builder_id(pool: &SqlitePool, token: &str) -> DbResult<Option<PlayerId>> {
+    let row = sqlx::query(
+        "SELECT players.id, players.flags FROM api_tokens \
+        INNER JOIN players ON players.username = api_tokens.created_by \
+        WHERE api_tokens.name = ?",
+    )
+    .bind(token)
+    .fetch_optional(pool)
+    .await?;
+
+    builder_from(row)
+}
+
+// Retrieves the ID of a player, failing with missing data if they do not exist.
+pub async fn player_id(pool: &SqlitePool, name: &str) -> DbResult<PlayerId> {
+    let row = sqlx::query("SELECT id FROM players WHERE username = ?")
+        .bind(name)
+        .fetch_optional(pool)
+        .await?;
+
+    id_from(row)
+}
+
+// Retrieves the ID of the player who created an API token, failing with missing data if the token
+// or its creator no longer exist.
+pub async fn token_creator_id(pool: &SqlitePool, token: &str) -> DbResult<PlayerId> {
+    let row = sqlx::query(
+        "SELECT players.id FROM api_tokens \
+        INNER JOIN players ON players.username = api_tokens.created_by \
+        WHERE api_tokens.name = ?",
+    )
+    .bind(token)
+    .fetch_optional(pool)
+    .await?;
+
+    id_from(row)
+}
+
+fn id_from(row: Option<SqliteRow>) -> DbResult<PlayerId> {
+    let row = row.ok_or(Error::MissingData("player"))?;
+    PlayerId::try_from(row.get::<i64, _>("id")).map_err(|_| Error::Deserialize("player ID"))
+}
+
+fn builder_from(row: Option<SqliteRow>) -> DbResult<Option<PlayerId>> {
+    let row = row.ok_or(Error::MissingData("player"))?;
+
+    if is_builder(&PlayerFlags::from(row.get::<i64, _>("flags"))) {
+        let id = PlayerId::try_from(row.get::<i64, _>("id"))
+            .map_err(|_| Error::Deserialize("player ID"))?;
+        Ok(Some(id))
+    } else {
+        Ok(None)
+    }
+}
+
+// Marks a player's requested erasure as due, returning false if they had not requested one.
+pub async fn approve_erasure(pool: &SqlitePool, name: &str, now: i64) -> DbResult<bool> {
+    let result = sqlx::query(
+        r#"UPDATE players SET delete_after = ?
+            WHERE username = ? AND id IN (SELECT player_id FROM erasure_requests)"#,
+    )
+    .bind(now)
+    .bind(name)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// Gathers everything stored about a player.
+pub async fn export_player(
+    pool: &SqlitePool,
+    name: &str,
+    achievements: Vec<EarnedAchievement>,
+) -> DbResult<Option<PlayerExport>> {
+    let player = match sqlx::query_as::<_, PlayerRow>(
+        "SELECT id, description, room, flags, playtime, pronouns, hunger, thirst, news_read \
+         FROM players WHERE username = ?",
+    )
+    .bind(name)
+    .fetch_optional(pool)
+    .await?
+    {
+        Some(player) => player,
+        None => return Ok(None),
+    };
+
+    let inventory = sqlx::query_as::<_, ExportedObject>(
+        r#"SELECT objects.id, COALESCE(objects.name, prototypes.name) AS name
+            FROM player_objects
+            INNER JOIN objects ON player_objects.object_id = objects.id
+            INNER JOIN prototypes ON objects.prototype_id = prototypes.id
+            WHERE player_objects.player_id = ?
+            ORDER BY objects.id"#,
+    )
+    .bind(player.id)
+    .fetch_all(pool)
+    .await?;
+
+    let vault = sqlx::query_as::<_, ExportedObject>(
+        r#"SELECT objects.id, COALESCE(objects.name, prototypes.name) AS name
+            FROM bank_objects
+            INNER JOIN objects ON bank_objects.object_id = objects.id
+            INNER JOIN prototypes ON objects.prototype_id = prototypes.id
+            WHERE bank_objects.player_id = ?
+            ORDER BY objects.id"#,
+    )
+    .bind(player.id)
+    .fetch_all(pool)
+    .await?;
+
+    let ignores = sqlx::query_as::<_, IgnoreRow>(
+        r#"SELECT player_ignores.ignored_id, players.username
+            FROM player_ignores
+            INNER JOIN players ON player_ignores.ignored_id = players.id
+            WHERE player_ignores.player_id = ?
+            ORDER BY players.username"#,
+    )
+    .bind(player.id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| row.username)
+    .collect_vec();
+
+    let sessions = sqlx::query_as::<_, PlayerSession>(
+        "SELECT connected_at, disconnected_at FROM sessions WHERE player_id = ? ORDER BY id",
+    )
+    .bind(player.id)
+    .fetch_all(pool)
+    .await?;
+
+    let reports = sqlx::query_as::<_, FiledReport>(
+        r#"SELECT players.username AS reported, reports.reason, reports.created_at
+            FROM reports
+            INNER JOIN players ON reports.reported_id = players.id
+            WHERE reports.reporter_id = ?
+            ORDER BY reports.id"#,
+    )
+    .bind(player.id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Some(PlayerExport {
+        name: name.to_string(),
+        description: player.description,
+        pronouns: player.pronouns,
+        flags: player.flags,
+        room: player.room,
+        playtime: player.playtime,
+        inventory,
+        vault,
+        achievements,
+        ignores,
+        sessions,
+        reports,
+    }))
+}
+
+// Deletes the players whose deletion is due, along with the objects they carry, bank, have listed
+// on the market and have attached to mail they sent or received. Everything else they own cascades
+// from the players table.
+pub async fn purge_deleted_players(pool: &SqlitePool, now: i64) -> DbResult<Vec<String>> {
+    let mut tx = pool.begin().await?;
+
+    for (table, column) in [
+        ("player_objects", "player_id"),
+        ("bank_objects", "player_id"),
+        ("market_listings", "seller_id"),
+        ("mail", "sender_id"),
+        ("mail", "recipient_id"),
+    ] {
+        sqlx::query(
+            format!(
+                r#"DELETE FROM objects WHERE id IN (
+                    SELECT object_id FROM {} WHERE {} IN (
+                        SELECT id FROM players WHERE delete_after <= ?))"#,
+                table, column
+            )
+            .as_str(),
+        )
+        .bind(now)
+        .execute(&mut tx)
+        .await?;
+    }
+
+    let names = sqlx::query("DELETE FROM players WHERE delete_after <= ? RETURNING username")
+        .bind(now)
+        .fetch_all(&mut tx)
+        .await?
+        .into_iter()
+        .map(|row| row.get::<String, _>("username"))
+        .collect_vec();
+
+    for name in names.iter() {
+        sqlx::query("DELETE FROM allowlist WHERE name = ?")
+            .bind(name.to_lowercase())
+            .execute(&mut tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(names)
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct IgnoreRow {
+    ignored_id: i64,
+    username: String,
+}
+
 #[derive(Debug, sqlx::FromRow)]
 struct PlayerRow {
     id: i64,
     description: String,
     room: i64,
     flags: i64,
+    playtime: i64,
+    pronouns: String,
+    hunger: i64,
+    thirst: i64,
+    news_read: i64,
 }
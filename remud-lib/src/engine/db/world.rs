@@ -1,40 +1,236 @@
-use std::{collections::HashMap, convert::TryFrom, str::FromStr};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    convert::TryFrom,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 use bevy_app::Events;
 use bevy_ecs::prelude::*;
 use futures::TryStreamExt;
 use itertools::Itertools;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use sqlx::{Row, SqlitePool};
 
 use crate::{
-    engine::db::{DbResult, Error, HookRow, ObjectRow},
+    clock::Clock,
+    engine::db::{DbResult, Error, FieldRow, HookRow, ObjectRow},
+    metrics::StatsTimer,
     world::{
         scripting::{
             RunInitScript, Script, ScriptHook, ScriptHooks, ScriptName, Scripts, TriggerEvent,
             TriggerKind,
         },
         types::{
+            achievement::{Achievement, AchievementId, Achievements},
+            bank::{Account, Bank},
+            campaign::{Campaign, CampaignId, CampaignSpawn, CampaignStage, Campaigns},
+            clan::{Clan, ClanMember, ClanRank, Clans},
+            communication::{Channel, Communication, CommunicationId, Communications},
+            economy::{CoinSource, Economy, Flow},
+            faction::{Faction, FactionId, Factions},
+            global::Globals,
+            loot::{LootEntry, LootTable, LootTableId, LootTables, Rarity},
+            mail::{Mail, MailId, Mailbox},
+            market::{Listing, ListingId, Market},
+            news::{News, NewsEntry, NewsId},
             object::{
-                Keywords, Object, ObjectFlags, ObjectId, Objects, Prototype, PrototypeBundle,
-                PrototypeId, Prototypes,
+                Behavior, Behaviors, Bulk, FieldValue, Keywords, Object, ObjectFlags, ObjectId,
+                Objects, Prototype, PrototypeBundle, PrototypeId, Prototypes,
+            },
+            player::{Allowlist, PlayerId},
+            poll::{Poll, PollId, Polls},
+            report::Reports,
+            room::{
+                Direction, Echo, Exit, LoadedRegions, RegionOwners, Regions, Room, RoomBundle,
+                RoomId, Rooms,
             },
-            room::{Direction, Regions, Room, RoomBundle, RoomId, Rooms},
+            season::{Seasonal, Seasons},
+            shop::{Shop, ShopId, Shops, StockItem},
+            spawn::{SpawnTable, SpawnTableId, SpawnTables},
+            tutorial::{HintTrigger, Tutorial},
             Configuration, Contents, Description, Id, Location, Named,
         },
+        VOID_ROOM_ID,
     },
 };
 
 #[tracing::instrument(name = "loading world")]
 pub async fn load_world(pool: &SqlitePool, world: &mut World) -> Result<(), Error> {
+    let _timer = StatsTimer::new("startup-load-world");
+    let start = Instant::now();
+
+    close_sessions(pool).await?;
     load_configuration(pool, world).await?;
-    load_rooms(pool, world).await?;
-    load_exits(pool, world).await?;
+    let scope = load_rooms(pool, world).await?;
+    load_anchored_regions(pool, world).await?;
+    load_exits(pool, world, &scope).await?;
     load_prototypes(pool, world).await?;
-    load_room_objects(pool, world).await?;
+    load_room_objects(pool, world, &scope).await?;
+    load_market(pool, world).await?;
+    load_mail(pool, world).await?;
+    load_news(pool, world).await?;
+    load_polls(pool, world).await?;
+    load_fields(pool, world, &scope).await?;
+    load_behaviors(pool, world).await?;
     load_scripts(pool, world).await?;
-    load_room_scripts(pool, world).await?;
+    load_room_scripts(pool, world, &scope).await?;
     load_prototype_scripts(pool, world).await?;
     load_object_scripts(pool, world).await?;
+    load_reports(pool, world).await?;
+    load_communications(pool, world).await?;
+    load_achievements(pool, world).await?;
+    load_factions(pool, world).await?;
+    load_globals(pool, world).await?;
+    load_spawn_tables(pool, world).await?;
+    load_loot_tables(pool, world).await?;
+    load_shops(pool, world).await?;
+    load_campaigns(pool, world).await?;
+    load_seasons(pool, world).await?;
+    load_bank(pool, world).await?;
+    load_economy(pool, world).await?;
+    load_clans(pool, world).await?;
+    load_allowlist(pool, world).await?;
+    load_region_owners(pool, world).await?;
+    load_tutorial(pool, world).await?;
+
+    tracing::info!("loaded world in {:?}", start.elapsed());
+
+    Ok(())
+}
+
+/// Brings the rooms of a region into the world, along with their exits, objects and scripts,
+/// when regions are loaded lazily. Rooms of the region already in the world are left alone.
+#[tracing::instrument(name = "loading region", skip(world))]
+pub async fn load_region(pool: &SqlitePool, world: &mut World, region: &str) -> DbResult<()> {
+    let _timer = StatsTimer::new("engine-load-region");
+
+    let region_id = sqlx::query("SELECT id FROM regions WHERE name = ?")
+        .bind(region)
+        .fetch_optional(pool)
+        .await?
+        .map(|row| row.get::<i64, _>("id"));
+
+    // A region with no rooms is still marked loaded, so what asked for it is not retried forever.
+    let neighbors = match region_id {
+        Some(region_id) => {
+            let rooms = spawn_rooms(pool, world, &Scope::Region(region_id)).await?;
+            let scope = Scope::Rooms(rooms);
+
+            load_exits(pool, world, &scope).await?;
+            let objects = spawn_room_objects(pool, world, &scope).await?;
+            load_object_fields(
+                pool,
+                world,
+                &format!(
+                    "SELECT object_id FROM room_objects WHERE {}",
+                    scope.covers("room_id")
+                ),
+            )
+            .await?;
+            load_room_scripts(pool, world, &scope).await?;
+            attach_object_scripts(
+                pool,
+                world,
+                objects,
+                &format!(
+                    r#"SELECT object_id AS owner, kind, script, trigger, priority, enabled
+                        FROM object_scripts
+                        WHERE object_id IN (SELECT object_id FROM room_objects WHERE {})"#,
+                    scope.covers("room_id")
+                ),
+            )
+            .await?;
+
+            load_neighbors(pool, &Scope::Region(region_id)).await?
+        }
+        None => HashSet::new(),
+    };
+
+    let now = world.get_resource::<Clock>().unwrap().now();
+    world
+        .get_resource_mut::<LoadedRegions>()
+        .unwrap()
+        .insert(region.to_string(), neighbors, now);
+
+    Ok(())
+}
+
+/// Loads the regions a room is in which are not yet in the world, failing if that does not bring
+/// the room into the world.
+#[tracing::instrument(name = "loading room", skip(world))]
+pub async fn load_room(pool: &SqlitePool, world: &mut World, room: RoomId) -> DbResult<()> {
+    let regions = sqlx::query(
+        r#"SELECT name FROM regions
+            INNER JOIN room_regions ON region_id = regions.id
+            WHERE room_id = ?"#,
+    )
+    .bind(room)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| row.get::<String, _>("name"))
+    .collect_vec();
+
+    for region in regions {
+        if !world
+            .get_resource::<LoadedRegions>()
+            .unwrap()
+            .is_loaded(region.as_str())
+        {
+            load_region(pool, world, region.as_str()).await?;
+        }
+    }
+
+    if world.get_resource::<Rooms>().unwrap().is_unloaded(room) {
+        return Err(Error::MissingData("room regions"));
+    }
+
+    Ok(())
+}
+
+// The rooms a load covers. When regions are loaded lazily, only the rooms in no region are loaded
+// with the world, and a region's rooms are loaded when it is needed.
+#[derive(Debug)]
+enum Scope {
+    World,
+    Unregioned,
+    Region(i64),
+    Rooms(Vec<RoomId>),
+}
+
+impl Scope {
+    // A condition selecting the rows whose room, held in the column, is covered.
+    fn covers(&self, column: &str) -> String {
+        match self {
+            Scope::World => "1".to_string(),
+            Scope::Unregioned => format!(
+                "({0} = {1} OR {0} NOT IN (SELECT room_id FROM room_regions))",
+                column,
+                i64::from(*VOID_ROOM_ID)
+            ),
+            Scope::Region(region) => format!(
+                "{} IN (SELECT room_id FROM room_regions WHERE region_id = {})",
+                column, region
+            ),
+            Scope::Rooms(rooms) => format!(
+                "{} IN ({})",
+                column,
+                rooms.iter().map(|room| i64::from(*room)).join(", ")
+            ),
+        }
+    }
+}
+
+// No one is connected when the world loads, so any sessions still open were interrupted by a
+// restart or crash.
+#[tracing::instrument(name = "closing sessions")]
+async fn close_sessions(pool: &SqlitePool) -> DbResult<()> {
+    sqlx::query(
+        "UPDATE sessions SET disconnected_at = CURRENT_TIMESTAMP WHERE disconnected_at IS NULL",
+    )
+    .execute(pool)
+    .await?;
 
     Ok(())
 }
@@ -53,10 +249,35 @@ async fn load_configuration(pool: &SqlitePool, world: &mut World) -> DbResult<()
     )
     .map_err(|_| Error::Deserialize("spawn room Room ID"))?;
 
+    let maintenance_row = sqlx::query(r#"SELECT value FROM config WHERE key = "maintenance""#)
+        .fetch_optional(pool)
+        .await?;
+
+    let maintenance = match maintenance_row {
+        Some(row) => row.get::<String, _>("value") == "on",
+        None => false,
+    };
+
     let configuration = Configuration {
         restart: false,
         shutdown: false,
         spawn_room,
+        profile: None,
+        config_reload: None,
+        db_check: None,
+        lint_request: None,
+        player_rename: None,
+        motd: None,
+        login_banner: None,
+        max_players: None,
+        allowlist_rejection: None,
+        maintenance,
+        maintenance_notice: String::new(),
+        maintenance_boot: None,
+        degraded: false,
+        takeover: false,
+        deletion_grace: Duration::default(),
+        needs: false,
     };
 
     world.insert_resource(configuration);
@@ -65,80 +286,192 @@ async fn load_configuration(pool: &SqlitePool, world: &mut World) -> DbResult<()
 }
 
 #[tracing::instrument(name = "loading rooms")]
-async fn load_rooms(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
-    let mut rooms_by_id = HashMap::new();
+async fn load_rooms(pool: &SqlitePool, world: &mut World) -> DbResult<Scope> {
+    let _timer = StatsTimer::new("startup-load-rooms");
 
-    let mut results =
-        sqlx::query_as::<_, RoomRow>("SELECT id, name, description FROM rooms").fetch(pool);
-
-    while let Some(room) = results.try_next().await? {
-        let regions = sqlx::query(
-            r#"SELECT name FROM regions
-                INNER JOIN room_regions ON region_id = regions.id
-                        AND room_id = ?"#,
-        )
-        .bind(room.id)
-        .fetch_all(pool)
+    let highest_id = sqlx::query("SELECT MAX(id) AS max_id FROM rooms")
+        .fetch_one(pool)
         .await?
+        .get("max_id");
+
+    if !world.get_resource::<LoadedRegions>().unwrap().is_lazy() {
+        world.insert_resource(Rooms::new(HashMap::new(), highest_id));
+        spawn_rooms(pool, world, &Scope::World).await?;
+        return Ok(Scope::World);
+    }
+
+    // Rooms in a region stay out of the world until the region is needed. The void room is always
+    // loaded, whatever regions it is in.
+    let mut unloaded = HashSet::new();
+    let query = format!(
+        "SELECT id FROM rooms WHERE NOT {}",
+        Scope::Unregioned.covers("id")
+    );
+    let mut results = sqlx::query(&query).fetch(pool);
+
+    while let Some(row) = results.try_next().await? {
+        unloaded.insert(
+            RoomId::try_from(row.get::<i64, _>("id")).map_err(|_| Error::Deserialize("room ID"))?,
+        );
+    }
+
+    world.insert_resource(Rooms::new(HashMap::new(), highest_id).with_unloaded(unloaded));
+    let rooms = spawn_rooms(pool, world, &Scope::Unregioned).await?;
+
+    Ok(Scope::Rooms(rooms))
+}
+
+// Spawns the rooms covered by the scope which are not already in the world, returning their IDs.
+async fn spawn_rooms(pool: &SqlitePool, world: &mut World, scope: &Scope) -> DbResult<Vec<RoomId>> {
+    let mut room_rows = sqlx::query_as::<_, RoomRow>(&format!(
+        "SELECT id, name, description, leave_message, arrive_message FROM rooms WHERE {}",
+        scope.covers("id")
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    {
+        let rooms = world.get_resource::<Rooms>().unwrap();
+        room_rows
+            .retain(|room| RoomId::try_from(room.id).map_or(true, |id| rooms.by_id(id).is_none()));
+    }
+
+    let mut regions_by_room: HashMap<i64, Vec<String>> = HashMap::new();
+    let query = format!(
+        r#"SELECT room_id, name FROM regions
+            INNER JOIN room_regions ON region_id = regions.id
+            WHERE {}"#,
+        scope.covers("room_id")
+    );
+    let mut results = sqlx::query(&query).fetch(pool);
+
+    while let Some(row) = results.try_next().await? {
+        regions_by_room
+            .entry(row.get("room_id"))
+            .or_default()
+            .push(row.get("name"));
+    }
+
+    let (ids, bundles): (Vec<_>, Vec<_>) = room_rows
+        .into_par_iter()
+        .map(|room| {
+            let id = RoomId::try_from(room.id).map_err(|_| Error::Deserialize("room ID"))?;
+            let regions = regions_by_room.get(&room.id).cloned().unwrap_or_default();
+
+            let mut room_component = Room::from(id);
+            room_component.set_echo(Echo::Leave, room.leave_message);
+            room_component.set_echo(Echo::Arrive, room.arrive_message);
+
+            Ok((
+                id,
+                RoomBundle {
+                    id: Id::Room(id),
+                    room: room_component,
+                    name: Named::from(room.name),
+                    description: Description::from(room.description),
+                    regions: Regions::new(regions),
+                    contents: Contents::default(),
+                },
+            ))
+        })
+        .collect::<DbResult<Vec<_>>>()?
         .into_iter()
-        .map(|row| row.get::<String, _>("name"))
-        .collect_vec();
+        .unzip();
 
-        let id = RoomId::try_from(room.id).map_err(|_| Error::Deserialize("room ID"))?;
-        let entity = world
-            .spawn()
-            .insert_bundle(RoomBundle {
-                id: Id::Room(id),
-                room: Room::from(id),
-                name: Named::from(room.name),
-                description: Description::from(room.description.clone()),
-                regions: Regions::new(regions),
-                contents: Contents::default(),
-            })
-            .id();
-        rooms_by_id.insert(id, entity);
+    for (id, bundle) in ids.iter().zip(bundles) {
+        let entity = world.spawn().insert_bundle(bundle).id();
+        world
+            .get_resource_mut::<Rooms>()
+            .unwrap()
+            .insert(*id, entity);
     }
 
-    let highest_id = sqlx::query("SELECT MAX(id) AS max_id FROM rooms")
-        .fetch_one(pool)
-        .await?
-        .get("max_id");
+    Ok(ids)
+}
 
-    let rooms = Rooms::new(rooms_by_id, highest_id);
-    world.insert_resource(rooms);
+// The regions next to rooms in no region stay loaded, so exits out of the rooms which are always
+// in the world lead somewhere.
+#[tracing::instrument(name = "loading anchored regions")]
+async fn load_anchored_regions(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    if !world.get_resource::<LoadedRegions>().unwrap().is_lazy() {
+        return Ok(());
+    }
+
+    let anchored = load_neighbors(pool, &Scope::Unregioned).await?;
+    world
+        .get_resource_mut::<LoadedRegions>()
+        .unwrap()
+        .set_anchored(anchored);
 
     Ok(())
 }
 
+// The regions which exits out of the rooms covered by the scope lead into.
+async fn load_neighbors(pool: &SqlitePool, scope: &Scope) -> DbResult<HashSet<String>> {
+    let neighbors = sqlx::query(&format!(
+        r#"SELECT DISTINCT regions.name FROM exits
+            INNER JOIN room_regions ON room_regions.room_id = exits.room_to
+            INNER JOIN regions ON regions.id = room_regions.region_id
+            WHERE {}"#,
+        scope.covers("exits.room_from")
+    ))
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| row.get::<String, _>("name"))
+    .collect();
+
+    Ok(neighbors)
+}
+
+// Exits are only linked between rooms which are both in the world. The rest are linked when the
+// region at their other end is loaded.
 #[tracing::instrument(name = "loading exits")]
-async fn load_exits(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
-    let mut results =
-        sqlx::query_as::<_, ExitRow>("SELECT room_from, room_to, direction FROM exits").fetch(pool);
+async fn load_exits(pool: &SqlitePool, world: &mut World, scope: &Scope) -> DbResult<()> {
+    let _timer = StatsTimer::new("startup-load-exits");
+
+    let query = format!(
+        "SELECT room_from, room_to, direction, message, leave_message, arrive_message FROM \
+         exits WHERE {} OR {}",
+        scope.covers("room_from"),
+        scope.covers("room_to")
+    );
+    let mut results = sqlx::query_as::<_, ExitRow>(&query).fetch(pool);
 
     while let Some(exit) = results.try_next().await? {
         let (from, to) = {
             let rooms = &world.get_resource::<Rooms>().unwrap();
-            let from = rooms
-                .by_id(
-                    RoomId::try_from(exit.room_from)
-                        .map_err(|_| Error::Deserialize("room exit from Room ID"))?,
-                )
-                .unwrap();
-            let to = rooms
-                .by_id(
-                    RoomId::try_from(exit.room_to)
-                        .map_err(|_| Error::Deserialize("room exit to Room ID"))?,
-                )
-                .unwrap();
-            (from, to)
+            let from = rooms.by_id(
+                RoomId::try_from(exit.room_from)
+                    .map_err(|_| Error::Deserialize("room exit from Room ID"))?,
+            );
+            let to = rooms.by_id(
+                RoomId::try_from(exit.room_to)
+                    .map_err(|_| Error::Deserialize("room exit to Room ID"))?,
+            );
+            match (from, to) {
+                (Some(from), Some(to)) => (from, to),
+                _ => continue,
+            }
         };
 
-        let direction = Direction::from_str(exit.direction.as_str()).unwrap();
+        let direction = Direction::named(exit.direction.as_str())
+            .ok_or(Error::Deserialize("room exit direction"))?;
 
-        world
-            .get_mut::<Room>(from)
-            .unwrap()
-            .insert_exit(direction, to);
+        let mut room = world.get_mut::<Room>(from).unwrap();
+        if room.exit(&direction).is_some() {
+            continue;
+        }
+
+        room.insert_exit(
+            direction,
+            Exit {
+                destination: to,
+                message: exit.message,
+                leave: exit.leave_message,
+                arrive: exit.arrive_message,
+            },
+        );
     }
 
     Ok(())
@@ -147,7 +480,7 @@ async fn load_exits(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
 #[tracing::instrument(name = "loading prototypes")]
 async fn load_prototypes(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
     let mut results = sqlx::query_as::<_, PrototypeRow>(
-        r#"SELECT id, flags, keywords, name, description FROM prototypes"#,
+        r#"SELECT id, flags, keywords, name, description, weight, size FROM prototypes"#,
     )
     .fetch(pool);
 
@@ -162,6 +495,7 @@ async fn load_prototypes(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
             name: Named::from(prototype_row.name.clone()),
             description: Description::from(prototype_row.description.clone()),
             keywords: Keywords::from(prototype_row.keywords()),
+            bulk: Bulk::new(prototype_row.weight as u32, prototype_row.size as u32),
         };
 
         let object_entity = world.spawn().insert_bundle(bundle).id();
@@ -180,38 +514,74 @@ async fn load_prototypes(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
 }
 
 #[tracing::instrument(name = "loading room objects")]
-async fn load_room_objects(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
-    let mut results = sqlx::query_as::<_, ObjectRow>(
+async fn load_room_objects(pool: &SqlitePool, world: &mut World, scope: &Scope) -> DbResult<()> {
+    let _timer = StatsTimer::new("startup-load-room-objects");
+
+    let results = sqlx::query("SELECT MAX(id) AS max_id FROM objects")
+        .fetch_one(pool)
+        .await?;
+    let highest_id = results.get("max_id");
+
+    world.insert_resource(Objects::new(highest_id, HashMap::new()));
+
+    spawn_room_objects(pool, world, scope).await?;
+
+    Ok(())
+}
+
+// Spawns the objects in the rooms covered by the scope, returning their entities.
+async fn spawn_room_objects(
+    pool: &SqlitePool,
+    world: &mut World,
+    scope: &Scope,
+) -> DbResult<Vec<Entity>> {
+    let object_rows = sqlx::query_as::<_, ObjectRow>(&format!(
         r#"SELECT objects.id, objects.prototype_id, objects.inherit_scripts, room_id AS location,
                     COALESCE(objects.name, prototypes.name) AS name, COALESCE(objects.description, prototypes.description) AS description,
                     COALESCE(objects.flags, prototypes.flags) AS flags, COALESCE(objects.keywords, prototypes.keywords) AS keywords
                 FROM objects
                 INNER JOIN room_objects ON room_objects.object_id = objects.id
-                INNER JOIN prototypes ON objects.prototype_id = prototypes.id"#,
-    )
-    .fetch(pool);
-
-    let mut by_id = HashMap::new();
+                INNER JOIN prototypes ON objects.prototype_id = prototypes.id
+                WHERE {}"#,
+        scope.covers("room_id")
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    let (placements, bundles): (Vec<_>, Vec<_>) = {
+        let rooms = world.get_resource::<Rooms>().unwrap();
+        let prototypes = world.get_resource::<Prototypes>().unwrap();
+
+        object_rows
+            .into_par_iter()
+            .map(|object_row| {
+                let room_id = RoomId::try_from(object_row.location.unwrap())
+                    .map_err(|_| Error::Deserialize("room ID"))?;
+                let room_entity = rooms
+                    .by_id(room_id)
+                    .ok_or(Error::MissingData("room not found"))?;
+                let id = ObjectId::try_from(object_row.id)
+                    .map_err(|_| Error::Deserialize("object ID"))?;
+                let prototype = prototypes
+                    .by_id(
+                        PrototypeId::try_from(object_row.prototype_id)
+                            .map_err(|_| Error::Deserialize("prototype ID"))?,
+                    )
+                    .ok_or(Error::MissingData("prototype not found"))?;
+
+                let bundle =
+                    object_row.into_object_bundle(prototype, Location::from(room_entity))?;
+
+                Ok(((id, room_entity), bundle))
+            })
+            .collect::<DbResult<Vec<_>>>()?
+            .into_iter()
+            .unzip()
+    };
 
-    while let Some(object_row) = results.try_next().await? {
-        let room_id = RoomId::try_from(object_row.location.unwrap())
-            .map_err(|_| Error::Deserialize("room ID"))?;
-        let room_entity = world
-            .get_resource::<Rooms>()
-            .unwrap()
-            .by_id(room_id)
-            .ok_or(Error::MissingData("room not found"))?;
-        let id = ObjectId::try_from(object_row.id).map_err(|_| Error::Deserialize("object ID"))?;
-        let prototype = world
-            .get_resource::<Prototypes>()
-            .unwrap()
-            .by_id(
-                PrototypeId::try_from(object_row.prototype_id)
-                    .map_err(|_| Error::Deserialize("prototype ID"))?,
-            )
-            .ok_or(Error::MissingData("prototype not found"))?;
+    let mut objects = Vec::new();
 
-        let bundle = object_row.into_object_bundle(prototype, Location::from(room_entity))?;
+    for ((id, room_entity), bundle) in placements.into_iter().zip(bundles) {
         let object_entity = world.spawn().insert_bundle(bundle).id();
 
         world
@@ -219,208 +589,1073 @@ async fn load_room_objects(pool: &SqlitePool, world: &mut World) -> DbResult<()>
             .ok_or(Error::MissingData("room contents"))?
             .insert(object_entity);
 
-        by_id.insert(id, object_entity);
+        world
+            .get_resource_mut::<Objects>()
+            .unwrap()
+            .insert(id, object_entity);
+
+        objects.push(object_entity);
     }
 
-    let results = sqlx::query("SELECT MAX(id) AS max_id FROM objects")
+    Ok(objects)
+}
+
+// Objects listed on the market are held in escrow in the void room, but are not part of its
+// contents.
+#[tracing::instrument(name = "loading market")]
+async fn load_market(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let void_room = world.get_resource::<Rooms>().unwrap().by_id(*VOID_ROOM_ID);
+
+    let mut listings = BTreeMap::new();
+
+    let mut results = sqlx::query(
+        r#"SELECT market_listings.id, seller_id, players.username, object_id, price, expires_at
+                FROM market_listings
+                INNER JOIN players ON market_listings.seller_id = players.id"#,
+    )
+    .fetch(pool);
+
+    while let Some(row) = results.try_next().await? {
+        let id = ListingId::try_from(row.get::<i64, _>("id"))
+            .map_err(|_| Error::Deserialize("listing ID"))?;
+        let seller = PlayerId::try_from(row.get::<i64, _>("seller_id"))
+            .map_err(|_| Error::Deserialize("player ID"))?;
+
+        let void_room = void_room.ok_or(Error::MissingData("void room not found"))?;
+        let object = load_escrowed_object(pool, world, row.get("object_id"), void_room).await?;
+
+        listings.insert(
+            id,
+            Listing {
+                seller,
+                seller_name: row.get("username"),
+                object,
+                price: row.get("price"),
+                expires_at: row.get("expires_at"),
+            },
+        );
+    }
+
+    let results = sqlx::query("SELECT MAX(id) AS max_id FROM market_listings")
         .fetch_one(pool)
         .await?;
     let highest_id = results.get("max_id");
 
-    world.insert_resource(Objects::new(highest_id, by_id));
+    world.insert_resource(Market::new(highest_id, listings));
 
     Ok(())
 }
 
-#[tracing::instrument(name = "loading scripts")]
-pub async fn load_scripts(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
-    world.insert_resource(Scripts::default());
+// Objects attached to mail are held in escrow in the void room, like market listings. Players are
+// addressed by name, so the mailbox knows every player, not just those with mail.
+#[tracing::instrument(name = "loading mail")]
+async fn load_mail(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let void_room = world.get_resource::<Rooms>().unwrap().by_id(*VOID_ROOM_ID);
 
-    let mut results = sqlx::query_as::<_, ScriptRow>(
-        r#"SELECT name, trigger, code
-                    FROM scripts"#,
+    let mut addresses = HashMap::new();
+
+    let mut results = sqlx::query("SELECT id, username FROM players").fetch(pool);
+
+    while let Some(row) = results.try_next().await? {
+        let id = PlayerId::try_from(row.get::<i64, _>("id"))
+            .map_err(|_| Error::Deserialize("player ID"))?;
+
+        addresses.insert(row.get::<String, _>("username"), id);
+    }
+
+    let mut mail = BTreeMap::new();
+
+    let mut results = sqlx::query(
+        r#"SELECT mail.id, mail.sender_id, senders.username AS sender_name, mail.recipient_id,
+                    recipients.username AS recipient_name, mail.body, mail.coins, mail.object_id,
+                    mail.sent_at, mail.expires_at, mail.read
+                FROM mail
+                INNER JOIN players AS senders ON mail.sender_id = senders.id
+                INNER JOIN players AS recipients ON mail.recipient_id = recipients.id"#,
     )
     .fetch(pool);
 
-    while let Some(script_row) = results.try_next().await? {
-        let script = Script::try_from(script_row)?;
-        let name = script.name().clone();
-        let entity = world.spawn().insert(script).id();
-        world
-            .get_resource_mut::<Scripts>()
-            .unwrap()
-            .insert(name, entity);
+    while let Some(row) = results.try_next().await? {
+        let id =
+            MailId::try_from(row.get::<i64, _>("id")).map_err(|_| Error::Deserialize("mail ID"))?;
+        let sender = PlayerId::try_from(row.get::<i64, _>("sender_id"))
+            .map_err(|_| Error::Deserialize("player ID"))?;
+        let recipient = PlayerId::try_from(row.get::<i64, _>("recipient_id"))
+            .map_err(|_| Error::Deserialize("player ID"))?;
+
+        let object = match row.get::<Option<i64>, _>("object_id") {
+            Some(object_id) => {
+                let void_room = void_room.ok_or(Error::MissingData("void room not found"))?;
+                Some(load_escrowed_object(pool, world, object_id, void_room).await?)
+            }
+            None => None,
+        };
+
+        mail.insert(
+            id,
+            Mail {
+                sender,
+                sender_name: row.get("sender_name"),
+                recipient,
+                recipient_name: row.get("recipient_name"),
+                body: row.get("body"),
+                coins: row.get("coins"),
+                object,
+                sent_at: row.get("sent_at"),
+                expires_at: row.get("expires_at"),
+                read: row.get("read"),
+            },
+        );
     }
 
+    let results = sqlx::query("SELECT MAX(id) AS max_id FROM mail")
+        .fetch_one(pool)
+        .await?;
+    let highest_id = results.get("max_id");
+
+    world.insert_resource(Mailbox::new(highest_id, mail, addresses));
+
     Ok(())
 }
 
-#[tracing::instrument(name = "loading prototype scripts")]
-async fn load_prototype_scripts(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
-    let prototypes = world
-        .query::<&Prototype>()
-        .iter(world)
-        .map(|prototype| prototype.id())
-        .collect_vec();
-
-    for prototype_id in prototypes {
-        let prototype = world
-            .get_resource::<Prototypes>()
-            .unwrap()
-            .by_id(prototype_id)
-            .unwrap();
+#[tracing::instrument(name = "loading news")]
+async fn load_news(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut entries = BTreeMap::new();
 
-        let mut results = sqlx::query_as::<_, HookRow>(
-            r#"SELECT kind, script, trigger FROM prototype_scripts WHERE prototype_id = ?"#,
-        )
-        .bind(prototype_id)
-        .fetch(pool);
+    let mut results = sqlx::query("SELECT id, author, body, posted_at FROM news").fetch(pool);
 
-        while let Some(hook_row) = results.try_next().await? {
-            let hook = ScriptHook::try_from(hook_row)?;
+    while let Some(row) = results.try_next().await? {
+        let id =
+            NewsId::try_from(row.get::<i64, _>("id")).map_err(|_| Error::Deserialize("news ID"))?;
 
-            if let Some(mut hooks) = world.get_mut::<ScriptHooks>(prototype) {
-                hooks.insert(hook)
-            } else {
-                world.entity_mut(prototype).insert(ScriptHooks::new(hook));
-            }
-        }
+        entries.insert(
+            id,
+            NewsEntry {
+                author: row.get("author"),
+                body: row.get("body"),
+                posted_at: row.get("posted_at"),
+            },
+        );
     }
 
+    let results = sqlx::query("SELECT MAX(id) AS max_id FROM news")
+        .fetch_one(pool)
+        .await?;
+    let highest_id = results.get("max_id");
+
+    world.insert_resource(News::new(highest_id, entries));
+
     Ok(())
 }
 
-#[tracing::instrument(name = "loading prototype scripts")]
-async fn load_room_scripts(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
-    let rooms = world
-        .query::<&Room>()
-        .iter(world)
-        .map(|room| room.id())
-        .collect_vec();
+#[tracing::instrument(name = "loading polls")]
+async fn load_polls(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut polls = BTreeMap::new();
 
-    for room_id in rooms {
-        let room = world
-            .get_resource::<Rooms>()
-            .unwrap()
-            .by_id(room_id)
-            .unwrap();
+    let mut results =
+        sqlx::query("SELECT id, question, author, created_at, closed FROM polls").fetch(pool);
 
-        let mut results = sqlx::query_as::<_, HookRow>(
-            r#"SELECT kind, script, trigger FROM room_scripts WHERE room_id = ?"#,
-        )
-        .bind(room_id)
-        .fetch(pool);
+    while let Some(row) = results.try_next().await? {
+        let id =
+            PollId::try_from(row.get::<i64, _>("id")).map_err(|_| Error::Deserialize("poll ID"))?;
 
-        while let Some(hook_row) = results.try_next().await? {
-            let hook = ScriptHook::try_from(hook_row)?;
+        let mut poll = Poll::new(
+            row.get("question"),
+            row.get("author"),
+            Vec::new(),
+            row.get("created_at"),
+        );
+        poll.closed = row.get("closed");
 
-            if let Some(mut hooks) = world.get_mut::<ScriptHooks>(room) {
-                hooks.insert(hook)
-            } else {
-                world.entity_mut(room).insert(ScriptHooks::new(hook));
-            }
-        }
+        polls.insert(id, poll);
     }
 
-    Ok(())
-}
-
-#[tracing::instrument(name = "loading object scripts")]
-async fn load_object_scripts(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
-    let objects = world
-        .query::<&Object>()
-        .iter(world)
-        .map(|object| (object.id(), object.inherit_scripts(), object.prototype()))
-        .collect_vec();
+    let mut results =
+        sqlx::query("SELECT poll_id, text FROM poll_options ORDER BY poll_id, position")
+            .fetch(pool);
 
-    for (object_id, inherit, prototype) in objects {
-        let object = world
-            .get_resource::<Objects>()
-            .unwrap()
-            .by_id(object_id)
-            .unwrap();
+    while let Some(row) = results.try_next().await? {
+        let id = PollId::try_from(row.get::<i64, _>("poll_id"))
+            .map_err(|_| Error::Deserialize("poll ID"))?;
 
-        let mut results = if inherit {
-            let prototype_id = world.get::<Prototype>(prototype).unwrap().id();
-            sqlx::query_as::<_, HookRow>(
-                r#"SELECT kind, script, trigger FROM prototype_scripts WHERE prototype_id = ?"#,
-            )
-            .bind(prototype_id)
-            .fetch(pool)
-        } else {
-            sqlx::query_as::<_, HookRow>(
-                r#"SELECT kind, script, trigger FROM object_scripts WHERE object_id = ?"#,
-            )
-            .bind(object_id)
-            .fetch(pool)
-        };
+        if let Some(poll) = polls.get_mut(&id) {
+            poll.options.push(row.get("text"));
+        }
+    }
 
-        while let Some(hook_row) = results.try_next().await? {
-            let hook = ScriptHook::try_from(hook_row)?;
+    let mut results =
+        sqlx::query("SELECT poll_id, player_id, position FROM poll_votes").fetch(pool);
 
-            if hook.trigger.kind() == TriggerKind::Init {
-                world
-                    .get_resource_mut::<Events<RunInitScript>>()
-                    .unwrap()
-                    .send(RunInitScript::new(object, hook.script.clone()));
-            }
+    while let Some(row) = results.try_next().await? {
+        let id = PollId::try_from(row.get::<i64, _>("poll_id"))
+            .map_err(|_| Error::Deserialize("poll ID"))?;
+        let player = PlayerId::try_from(row.get::<i64, _>("player_id"))
+            .map_err(|_| Error::Deserialize("player ID"))?;
 
-            if let Some(mut hooks) = world.get_mut::<ScriptHooks>(object) {
-                hooks.insert(hook)
-            } else {
-                world.entity_mut(object).insert(ScriptHooks::new(hook));
-            }
+        if let Some(poll) = polls.get_mut(&id) {
+            poll.votes
+                .insert(player, row.get::<i64, _>("position") as usize);
         }
     }
 
-    Ok(())
-}
+    let results = sqlx::query("SELECT MAX(id) AS max_id FROM polls")
+        .fetch_one(pool)
+        .await?;
+    let highest_id = results.get("max_id");
 
-#[derive(Debug, sqlx::FromRow)]
-struct RoomRow {
-    id: i64,
-    name: String,
-    description: String,
-}
+    world.insert_resource(Polls::new(highest_id, polls));
 
-#[derive(Debug, sqlx::FromRow)]
-struct ExitRow {
-    room_from: i64,
-    room_to: i64,
-    direction: String,
+    Ok(())
 }
 
-#[derive(Debug, sqlx::FromRow)]
-struct RoomObjectRow {
-    room_id: i64,
+// Spawns an object held in escrow, out of sight in the void room but not part of its contents.
+async fn load_escrowed_object(
+    pool: &SqlitePool,
+    world: &mut World,
     object_id: i64,
+    void_room: Entity,
+) -> DbResult<Entity> {
+    let object_row = sqlx::query_as::<_, ObjectRow>(
+        r#"SELECT objects.id, objects.prototype_id, objects.inherit_scripts, NULL AS location,
+                COALESCE(objects.name, prototypes.name) AS name, COALESCE(objects.description, prototypes.description) AS description,
+                COALESCE(objects.flags, prototypes.flags) AS flags, COALESCE(objects.keywords, prototypes.keywords) AS keywords
+            FROM objects
+            INNER JOIN prototypes ON objects.prototype_id = prototypes.id
+            WHERE objects.id = ?"#,
+    )
+    .bind(object_id)
+    .fetch_one(pool)
+    .await?;
+
+    let object_id =
+        ObjectId::try_from(object_row.id).map_err(|_| Error::Deserialize("object ID"))?;
+    let prototype = world
+        .get_resource::<Prototypes>()
+        .unwrap()
+        .by_id(
+            PrototypeId::try_from(object_row.prototype_id)
+                .map_err(|_| Error::Deserialize("prototype ID"))?,
+        )
+        .ok_or(Error::MissingData("prototype not found"))?;
+
+    let bundle = object_row.into_object_bundle(prototype, Location::from(void_room))?;
+    let object = world.spawn().insert_bundle(bundle).id();
+
+    world
+        .get_resource_mut::<Objects>()
+        .unwrap()
+        .insert(object_id, object);
+
+    Ok(object)
 }
 
-impl TryFrom<RoomObjectRow> for (RoomId, ObjectId) {
-    type Error = anyhow::Error;
+#[tracing::instrument(name = "loading fields")]
+async fn load_fields(pool: &SqlitePool, world: &mut World, scope: &Scope) -> DbResult<()> {
+    let mut results = sqlx::query_as::<_, FieldRow>(
+        r#"SELECT prototype_id AS id, key, kind, value FROM prototype_fields"#,
+    )
+    .fetch(pool);
 
-    fn try_from(value: RoomObjectRow) -> Result<Self, Self::Error> {
-        let room_id = RoomId::try_from(value.room_id)?;
-        let object_id = ObjectId::try_from(value.object_id)?;
-        Ok((room_id, object_id))
+    while let Some(field_row) = results.try_next().await? {
+        let id =
+            PrototypeId::try_from(field_row.id).map_err(|_| Error::Deserialize("prototype ID"))?;
+        let prototype = world
+            .get_resource::<Prototypes>()
+            .unwrap()
+            .by_id(id)
+            .ok_or(Error::MissingData("prototype not found"))?;
+
+        field_row.insert_into(world, prototype)?;
     }
-}
 
-#[derive(Debug, sqlx::FromRow)]
-struct PrototypeRow {
-    id: i64,
-    flags: i64,
-    name: String,
-    keywords: String,
-    description: String,
+    // Objects held by players are loaded with their fields when the player logs in.
+    load_object_fields(
+        pool,
+        world,
+        &format!(
+            r#"SELECT object_id FROM room_objects WHERE {}
+                UNION SELECT object_id FROM market_listings
+                UNION SELECT object_id FROM mail WHERE object_id IS NOT NULL"#,
+            scope.covers("room_id")
+        ),
+    )
+    .await
 }
 
-impl PrototypeRow {
-    fn keywords(&self) -> Vec<String> {
-        self.keywords
-            .split(',')
-            .map(ToString::to_string)
-            .collect_vec()
+// Loads the fields of the objects whose IDs are selected by the query.
+async fn load_object_fields(pool: &SqlitePool, world: &mut World, objects: &str) -> DbResult<()> {
+    let query = format!(
+        r#"SELECT object_id AS id, key, kind, value
+                FROM object_fields
+                WHERE object_id IN ({})"#,
+        objects
+    );
+    let mut results = sqlx::query_as::<_, FieldRow>(&query).fetch(pool);
+
+    while let Some(field_row) = results.try_next().await? {
+        let id = ObjectId::try_from(field_row.id).map_err(|_| Error::Deserialize("object ID"))?;
+        let object = world
+            .get_resource::<Objects>()
+            .unwrap()
+            .by_id(id)
+            .ok_or(Error::MissingData("object not found"))?;
+
+        field_row.insert_into(world, object)?;
+    }
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading behaviors")]
+async fn load_behaviors(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut results = sqlx::query_as::<_, BehaviorRow>(
+        r#"SELECT prototype_id, kind, value FROM prototype_behaviors"#,
+    )
+    .fetch(pool);
+
+    while let Some(behavior_row) = results.try_next().await? {
+        let id = PrototypeId::try_from(behavior_row.prototype_id)
+            .map_err(|_| Error::Deserialize("prototype ID"))?;
+        let prototype = world
+            .get_resource::<Prototypes>()
+            .unwrap()
+            .by_id(id)
+            .ok_or(Error::MissingData("prototype not found"))?;
+
+        let behavior = Behavior::from_stored(behavior_row.kind.as_str(), behavior_row.value)
+            .ok_or(Error::Deserialize("prototype behavior"))?;
+
+        if let Some(mut behaviors) = world.get_mut::<Behaviors>(prototype) {
+            behaviors.insert(behavior);
+        } else {
+            world
+                .entity_mut(prototype)
+                .insert(Behaviors::from(behavior));
+        }
+    }
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading reports")]
+async fn load_reports(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let results = sqlx::query("SELECT MAX(id) AS max_id FROM reports")
+        .fetch_one(pool)
+        .await?;
+    let highest_id = results.get("max_id");
+
+    world.insert_resource(Reports::new(highest_id));
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading communications")]
+async fn load_communications(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut entries = VecDeque::new();
+
+    let mut results = sqlx::query(
+        "SELECT id, channel, player_id, name, room_id, target, text, said_at FROM communications \
+         ORDER BY id",
+    )
+    .fetch(pool);
+
+    while let Some(row) = results.try_next().await? {
+        let id = CommunicationId::try_from(row.get::<i64, _>("id"))
+            .map_err(|_| Error::Deserialize("communication ID"))?;
+        let channel = Channel::from_str(row.get("channel"))
+            .map_err(|_| Error::Deserialize("communication channel"))?;
+        let player = PlayerId::try_from(row.get::<i64, _>("player_id"))
+            .map_err(|_| Error::Deserialize("player ID"))?;
+        let room = match row.get::<Option<i64>, _>("room_id") {
+            Some(room) => Some(RoomId::try_from(room).map_err(|_| Error::Deserialize("room ID"))?),
+            None => None,
+        };
+
+        entries.push_back((
+            id,
+            Communication {
+                channel,
+                player,
+                name: row.get("name"),
+                room,
+                target: row.get("target"),
+                text: row.get("text"),
+                said_at: row.get("said_at"),
+            },
+        ));
+    }
+
+    let results = sqlx::query("SELECT MAX(id) AS max_id FROM communications")
+        .fetch_one(pool)
+        .await?;
+    let highest_id = results.get("max_id");
+
+    world.insert_resource(Communications::new(highest_id, entries));
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading achievements")]
+async fn load_achievements(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut achievements = Achievements::default();
+
+    let mut results = sqlx::query_as::<_, AchievementRow>(
+        r#"SELECT id, name, description,
+                    EXISTS(SELECT 1 FROM player_achievements WHERE achievement_id = achievements.id) AS earned
+                FROM achievements"#,
+    )
+    .fetch(pool);
+
+    while let Some(achievement_row) = results.try_next().await? {
+        achievements.insert(
+            achievement_row.id,
+            Achievement::new(
+                achievement_row.name,
+                achievement_row.description,
+                achievement_row.earned,
+            ),
+        );
+    }
+
+    world.insert_resource(achievements);
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading factions")]
+async fn load_factions(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut factions = Factions::default();
+
+    let mut results =
+        sqlx::query_as::<_, (FactionId, String)>(r#"SELECT id, name FROM factions"#).fetch(pool);
+
+    while let Some((id, name)) = results.try_next().await? {
+        factions.insert(id, Faction { name });
+    }
+
+    world.insert_resource(factions);
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading globals")]
+async fn load_globals(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut globals = Globals::default();
+
+    let mut results =
+        sqlx::query_as::<_, GlobalRow>("SELECT key, kind, value FROM globals").fetch(pool);
+
+    while let Some(global_row) = results.try_next().await? {
+        let value = FieldValue::from_stored(global_row.kind.as_str(), global_row.value)
+            .ok_or(Error::Deserialize("global value"))?;
+        globals.insert(global_row.key, value);
+    }
+
+    world.insert_resource(globals);
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading spawn tables")]
+async fn load_spawn_tables(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut spawn_tables = SpawnTables::default();
+
+    let mut results =
+        sqlx::query_as::<_, SpawnTableRow>("SELECT id, cap, interval_secs FROM spawn_tables")
+            .fetch(pool);
+
+    while let Some(table_row) = results.try_next().await? {
+        spawn_tables.insert(
+            table_row.id,
+            SpawnTable::new(
+                table_row.cap as u32,
+                Duration::from_secs(table_row.interval_secs as u64),
+            ),
+        );
+    }
+
+    let mut results =
+        sqlx::query("SELECT table_id, prototype_id, weight FROM spawn_table_entries").fetch(pool);
+
+    while let Some(row) = results.try_next().await? {
+        let prototype = PrototypeId::try_from(row.get::<i64, _>("prototype_id"))
+            .map_err(|_| Error::Deserialize("spawn table prototype ID"))?;
+        if let Some(table) = spawn_tables.get_mut(&row.get("table_id")) {
+            table
+                .entries
+                .insert(prototype, row.get::<i64, _>("weight") as u32);
+        }
+    }
+
+    let mut results = sqlx::query("SELECT table_id, room_id FROM spawn_table_rooms").fetch(pool);
+
+    while let Some(row) = results.try_next().await? {
+        let room = RoomId::try_from(row.get::<i64, _>("room_id"))
+            .map_err(|_| Error::Deserialize("spawn table room ID"))?;
+        if let Some(table) = spawn_tables.get_mut(&row.get("table_id")) {
+            table.rooms.insert(room);
+        }
+    }
+
+    let mut results = sqlx::query("SELECT table_id, region FROM spawn_table_regions").fetch(pool);
+
+    while let Some(row) = results.try_next().await? {
+        if let Some(table) = spawn_tables.get_mut(&row.get("table_id")) {
+            table.regions.insert(row.get("region"));
+        }
+    }
+
+    world.insert_resource(spawn_tables);
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading loot tables")]
+async fn load_loot_tables(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut loot_tables = LootTables::default();
+
+    let mut results = sqlx::query("SELECT id, rolls FROM loot_tables").fetch(pool);
+
+    while let Some(row) = results.try_next().await? {
+        loot_tables.insert(
+            row.get("id"),
+            LootTable::new(row.get::<i64, _>("rolls") as u32),
+        );
+    }
+
+    let mut results = sqlx::query_as::<_, LootEntryRow>(
+        r#"SELECT table_id, prototype_id, weight, min_quantity, max_quantity, rarity
+                FROM loot_table_entries
+                ORDER BY table_id, position"#,
+    )
+    .fetch(pool);
+
+    while let Some(entry_row) = results.try_next().await? {
+        let prototype = entry_row
+            .prototype_id
+            .map(PrototypeId::try_from)
+            .transpose()
+            .map_err(|_| Error::Deserialize("loot table prototype ID"))?;
+        let rarity = Rarity::from_str(entry_row.rarity.as_str())
+            .map_err(|_| Error::Deserialize("loot table rarity"))?;
+
+        if let Some(table) = loot_tables.get_mut(&entry_row.table_id) {
+            table.entries.push(LootEntry {
+                prototype,
+                weight: entry_row.weight as u32,
+                min: entry_row.min_quantity as u32,
+                max: entry_row.max_quantity as u32,
+                rarity,
+            });
+        }
+    }
+
+    world.insert_resource(loot_tables);
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading shops")]
+async fn load_shops(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut shops = Shops::default();
+
+    let mut results = sqlx::query_as::<_, ShopRow>(
+        "SELECT id, room_id, loot_table_id, markup, cap, interval_secs, sunk FROM shops",
+    )
+    .fetch(pool);
+
+    while let Some(shop_row) = results.try_next().await? {
+        let room =
+            RoomId::try_from(shop_row.room_id).map_err(|_| Error::Deserialize("shop room ID"))?;
+
+        let mut shop = Shop::new(room, Duration::from_secs(shop_row.interval_secs as u64));
+        shop.loot_table = shop_row.loot_table_id;
+        shop.markup = shop_row.markup as u32;
+        shop.cap = shop_row.cap as u32;
+        shop.sunk = shop_row.sunk;
+
+        shops.insert(shop_row.id, shop);
+    }
+
+    let mut results = sqlx::query(
+        r#"SELECT shop_id, prototype_id, rarity, quantity FROM shop_stock
+                ORDER BY shop_id, position"#,
+    )
+    .fetch(pool);
+
+    while let Some(row) = results.try_next().await? {
+        let prototype = PrototypeId::try_from(row.get::<i64, _>("prototype_id"))
+            .map_err(|_| Error::Deserialize("shop stock prototype ID"))?;
+        let rarity = Rarity::from_str(row.get::<&str, _>("rarity"))
+            .map_err(|_| Error::Deserialize("shop stock rarity"))?;
+
+        if let Some(shop) = shops.get_mut(&row.get("shop_id")) {
+            shop.stock.push(StockItem {
+                prototype,
+                rarity,
+                quantity: row.get::<i64, _>("quantity") as u32,
+            });
+        }
+    }
+
+    world.insert_resource(shops);
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading campaigns")]
+async fn load_campaigns(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut campaigns = Vec::new();
+
+    let mut results =
+        sqlx::query_as::<_, CampaignRow>("SELECT id, name, stage FROM campaigns").fetch(pool);
+
+    while let Some(campaign_row) = results.try_next().await? {
+        campaigns.push(campaign_row);
+    }
+
+    let mut stages = HashMap::<CampaignId, Vec<CampaignStage>>::new();
+
+    let mut results = sqlx::query_as::<_, CampaignStageRow>(
+        r#"SELECT campaign_id, announcement, goal FROM campaign_stages
+                ORDER BY campaign_id, position"#,
+    )
+    .fetch(pool);
+
+    while let Some(stage_row) = results.try_next().await? {
+        let mut stage = CampaignStage::new(stage_row.announcement);
+        stage.goal = stage_row.goal;
+        stages.entry(stage_row.campaign_id).or_default().push(stage);
+    }
+
+    let mut results = sqlx::query_as::<_, CampaignSpawnRow>(
+        r#"SELECT campaign_id, position, prototype_id, room_id FROM campaign_spawns"#,
+    )
+    .fetch(pool);
+
+    while let Some(spawn_row) = results.try_next().await? {
+        let spawn = CampaignSpawn {
+            prototype: PrototypeId::try_from(spawn_row.prototype_id)
+                .map_err(|_| Error::Deserialize("campaign spawn prototype ID"))?,
+            room: RoomId::try_from(spawn_row.room_id)
+                .map_err(|_| Error::Deserialize("campaign spawn room ID"))?,
+        };
+
+        if let Some(stage) = stages
+            .get_mut(&spawn_row.campaign_id)
+            .and_then(|stages| stages.get_mut(spawn_row.position as usize))
+        {
+            stage.spawns.push(spawn);
+        }
+    }
+
+    let mut resource = Campaigns::default();
+
+    for campaign_row in campaigns {
+        let mut campaign = Campaign::new(campaign_row.name);
+        campaign.stages = stages.remove(&campaign_row.id).unwrap_or_default();
+
+        resource.insert(
+            campaign_row.id,
+            campaign.with_stage(campaign_row.stage.map(|stage| stage as usize)),
+        );
+    }
+
+    world.insert_resource(resource);
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading seasons")]
+async fn load_seasons(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut tags = HashMap::new();
+
+    let mut results = sqlx::query("SELECT kind, target, season FROM seasonal_content").fetch(pool);
+
+    while let Some(row) = results.try_next().await? {
+        let content = Seasonal::parse(row.get("kind"), row.get("target"))
+            .ok_or(Error::Deserialize("seasonal content"))?;
+        tags.insert(content, row.get("season"));
+    }
+
+    world.insert_resource(Seasons::new(tags));
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading bank")]
+async fn load_bank(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut bank = Bank::default();
+
+    let mut results = sqlx::query(
+        "SELECT id, username, coins, bank_balance FROM players WHERE coins > 0 OR bank_balance > 0",
+    )
+    .fetch(pool);
+
+    while let Some(row) = results.try_next().await? {
+        let id = PlayerId::try_from(row.get::<i64, _>("id"))
+            .map_err(|_| Error::Deserialize("bank player ID"))?;
+
+        bank.insert(
+            id,
+            Account {
+                name: row.get("username"),
+                coins: row.get("coins"),
+                balance: row.get("bank_balance"),
+            },
+        );
+    }
+
+    world.insert_resource(bank);
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading economy")]
+async fn load_economy(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut economy = Economy::default();
+
+    let mut results =
+        sqlx::query("SELECT day, source, created, destroyed FROM economy_days").fetch(pool);
+
+    while let Some(row) = results.try_next().await? {
+        let source = CoinSource::from_str(row.get::<&str, _>("source"))
+            .map_err(|_| Error::Deserialize("economy coin source"))?;
+
+        economy.insert(
+            row.get("day"),
+            source,
+            Flow {
+                created: row.get("created"),
+                destroyed: row.get("destroyed"),
+            },
+        );
+    }
+
+    world.insert_resource(economy);
+
+    Ok(())
+}
+
+async fn load_clans(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut clans = Clans::default();
+
+    let mut results = sqlx::query("SELECT name, founder_id, hall_id FROM clans").fetch(pool);
+
+    while let Some(row) = results.try_next().await? {
+        let founder = PlayerId::try_from(row.get::<i64, _>("founder_id"))
+            .map_err(|_| Error::Deserialize("clan founder ID"))?;
+        let hall = match row.get::<Option<i64>, _>("hall_id") {
+            Some(id) => Some(RoomId::try_from(id).map_err(|_| Error::Deserialize("clan hall ID"))?),
+            None => None,
+        };
+
+        clans.insert(Clan::new(row.get("name"), founder, hall));
+    }
+
+    let mut results = sqlx::query(
+        r#"SELECT clan, player_id, username, rank FROM clan_members
+            INNER JOIN players ON players.id = clan_members.player_id"#,
+    )
+    .fetch(pool);
+
+    while let Some(row) = results.try_next().await? {
+        let id = PlayerId::try_from(row.get::<i64, _>("player_id"))
+            .map_err(|_| Error::Deserialize("clan member ID"))?;
+        let rank = ClanRank::from_str(row.get("rank"))
+            .map_err(|_| Error::Deserialize("clan member rank"))?;
+
+        clans.join(
+            row.get("clan"),
+            id,
+            ClanMember {
+                name: row.get("username"),
+                rank,
+            },
+        );
+    }
+
+    world.insert_resource(clans);
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading scripts")]
+pub async fn load_scripts(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    world.insert_resource(Scripts::default());
+
+    let mut results = sqlx::query_as::<_, ScriptRow>(
+        r#"SELECT name, trigger, code, revision, tags, folder, enabled, author_id
+                    FROM scripts"#,
+    )
+    .fetch(pool);
+
+    while let Some(script_row) = results.try_next().await? {
+        let script = Script::try_from(script_row)?;
+        let name = script.name().clone();
+        let entity = world.spawn().insert(script).id();
+        world
+            .get_resource_mut::<Scripts>()
+            .unwrap()
+            .insert(name, entity);
+    }
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading prototype scripts")]
+async fn load_prototype_scripts(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let _timer = StatsTimer::new("startup-load-prototype-scripts");
+
+    let hooks = load_hooks(
+        pool,
+        r#"SELECT prototype_id AS owner, kind, script, trigger, priority, enabled
+            FROM prototype_scripts"#,
+    )
+    .await?;
+
+    for (prototype_id, hooks) in hooks {
+        let prototype = PrototypeId::try_from(prototype_id)
+            .ok()
+            .and_then(|id| world.get_resource::<Prototypes>().unwrap().by_id(id));
+
+        // Hooks left behind by a prototype which no longer exists are skipped rather than
+        // keeping the world from loading.
+        match prototype {
+            Some(prototype) => insert_hooks(world, prototype, hooks),
+            None => tracing::warn!(
+                "skipping {} scripts attached to missing prototype {}",
+                hooks.len(),
+                prototype_id
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading room scripts")]
+async fn load_room_scripts(pool: &SqlitePool, world: &mut World, scope: &Scope) -> DbResult<()> {
+    let _timer = StatsTimer::new("startup-load-room-scripts");
+
+    let hooks = load_hooks(
+        pool,
+        &format!(
+            r#"SELECT room_id AS owner, kind, script, trigger, priority, enabled
+                FROM room_scripts
+                WHERE {}"#,
+            scope.covers("room_id")
+        ),
+    )
+    .await?;
+
+    for (room_id, hooks) in hooks {
+        let room = RoomId::try_from(room_id)
+            .ok()
+            .and_then(|id| world.get_resource::<Rooms>().unwrap().by_id(id));
+
+        // Hooks left behind by a room which no longer exists are skipped rather than keeping the
+        // world from loading.
+        match room {
+            Some(room) => insert_hooks(world, room, hooks),
+            None => tracing::warn!(
+                "skipping {} scripts attached to missing room {}",
+                hooks.len(),
+                room_id
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading object scripts")]
+async fn load_object_scripts(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let _timer = StatsTimer::new("startup-load-object-scripts");
+
+    let objects = world
+        .query_filtered::<Entity, With<Object>>()
+        .iter(world)
+        .collect_vec();
+
+    attach_object_scripts(
+        pool,
+        world,
+        objects,
+        r#"SELECT object_id AS owner, kind, script, trigger, priority, enabled
+            FROM object_scripts"#,
+    )
+    .await
+}
+
+// Attaches scripts to the objects, from their prototypes when they inherit them, or else from the
+// object hooks selected by the query. Init scripts are queued to run for each of them.
+async fn attach_object_scripts(
+    pool: &SqlitePool,
+    world: &mut World,
+    objects: Vec<Entity>,
+    object_hooks: &str,
+) -> DbResult<()> {
+    let prototype_hooks = load_hooks(
+        pool,
+        r#"SELECT prototype_id AS owner, kind, script, trigger, priority, enabled
+            FROM prototype_scripts"#,
+    )
+    .await?;
+
+    let mut object_hooks = load_hooks(pool, object_hooks).await?;
+
+    let objects = objects
+        .into_iter()
+        .filter_map(|entity| {
+            world.get::<Object>(entity).map(|object| {
+                (
+                    entity,
+                    object.id(),
+                    object.inherit_scripts(),
+                    object.prototype(),
+                )
+            })
+        })
+        .collect_vec();
+
+    for (object, object_id, inherit, prototype) in objects {
+        let hooks = if inherit {
+            let prototype_id = world.get::<Prototype>(prototype).unwrap().id();
+            prototype_hooks.get(&i64::from(prototype_id)).cloned()
+        } else {
+            object_hooks.remove(&i64::from(object_id))
+        };
+
+        if let Some(hooks) = hooks {
+            for hook in hooks.iter() {
+                if hook.trigger.kind() == TriggerKind::Init {
+                    world
+                        .get_resource_mut::<Events<RunInitScript>>()
+                        .unwrap()
+                        .send(RunInitScript::new(object, hook.script.clone()));
+                }
+            }
+
+            insert_hooks(world, object, hooks);
+        }
+    }
+
+    Ok(())
+}
+
+// Reads every hook from one of the script tables at once, grouped by the ID of what they are
+// attached to.
+async fn load_hooks(pool: &SqlitePool, query: &str) -> DbResult<HashMap<i64, Vec<ScriptHook>>> {
+    let hook_rows = sqlx::query_as::<_, OwnedHookRow>(query)
+        .fetch_all(pool)
+        .await?;
+
+    let hooks = hook_rows
+        .into_par_iter()
+        .map(|row| {
+            let owner = row.owner;
+            ScriptHook::try_from(HookRow::from(row)).map(|hook| (owner, hook))
+        })
+        .collect::<DbResult<Vec<_>>>()?;
+
+    let mut by_owner: HashMap<i64, Vec<ScriptHook>> = HashMap::new();
+    for (owner, hook) in hooks {
+        by_owner.entry(owner).or_default().push(hook);
+    }
+
+    Ok(by_owner)
+}
+
+fn insert_hooks(world: &mut World, entity: Entity, hooks: Vec<ScriptHook>) {
+    let mut script_hooks = ScriptHooks::default();
+    for hook in hooks {
+        script_hooks.insert(hook);
+    }
+    world.entity_mut(entity).insert(script_hooks);
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct RoomRow {
+    id: i64,
+    name: String,
+    description: String,
+    leave_message: Option<String>,
+    arrive_message: Option<String>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct OwnedHookRow {
+    owner: i64,
+    kind: String,
+    script: String,
+    trigger: String,
+    priority: i64,
+    enabled: bool,
+}
+
+impl From<OwnedHookRow> for HookRow {
+    fn from(value: OwnedHookRow) -> Self {
+        HookRow {
+            kind: value.kind,
+            script: value.script,
+            trigger: value.trigger,
+            priority: value.priority,
+            enabled: value.enabled,
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct BehaviorRow {
+    prototype_id: i64,
+    kind: String,
+    value: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ExitRow {
+    room_from: i64,
+    room_to: i64,
+    direction: String,
+    message: Option<String>,
+    leave_message: Option<String>,
+    arrive_message: Option<String>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct RoomObjectRow {
+    room_id: i64,
+    object_id: i64,
+}
+
+impl TryFrom<RoomObjectRow> for (RoomId, ObjectId) {
+    type Error = anyhow::Error;
+
+    fn try_from(value: RoomObjectRow) -> Result<Self, Self::Error> {
+        let room_id = RoomId::try_from(value.room_id)?;
+        let object_id = ObjectId::try_from(value.object_id)?;
+        Ok((room_id, object_id))
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct PrototypeRow {
+    id: i64,
+    flags: i64,
+    name: String,
+    keywords: String,
+    description: String,
+    weight: i64,
+    size: i64,
+}
+
+impl PrototypeRow {
+    fn keywords(&self) -> Vec<String> {
+        self.keywords
+            .split(',')
+            .map(ToString::to_string)
+            .collect_vec()
     }
 }
 
@@ -429,17 +1664,178 @@ struct ScriptRow {
     name: String,
     trigger: String,
     code: String,
+    revision: i64,
+    tags: String,
+    folder: Option<String>,
+    enabled: bool,
+    author_id: Option<PlayerId>,
+}
+
+impl ScriptRow {
+    fn tags(&self) -> Vec<String> {
+        self.tags
+            .split(',')
+            .filter(|tag| !tag.is_empty())
+            .map(ToString::to_string)
+            .collect_vec()
+    }
 }
 
 impl TryFrom<ScriptRow> for Script {
     type Error = Error;
 
     fn try_from(value: ScriptRow) -> Result<Self, Self::Error> {
+        let tags = value.tags();
         let name =
             ScriptName::try_from(value.name).map_err(|_| Error::Deserialize("script name"))?;
         let trigger = TriggerEvent::from_str(value.trigger.as_str())
             .map_err(|_| Error::Deserialize("script trigger event"))?;
 
-        Ok(Script::new(name, trigger, value.code))
+        Ok(Script::new(name, trigger, value.code)
+            .with_revision(value.revision)
+            .with_tags(tags)
+            .with_folder(value.folder)
+            .with_enabled(value.enabled)
+            .with_author(value.author_id))
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct AchievementRow {
+    id: AchievementId,
+    name: String,
+    description: String,
+    earned: bool,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct CampaignRow {
+    id: CampaignId,
+    name: String,
+    stage: Option<i64>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct CampaignStageRow {
+    campaign_id: CampaignId,
+    announcement: String,
+    goal: Option<String>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct CampaignSpawnRow {
+    campaign_id: CampaignId,
+    position: i64,
+    prototype_id: i64,
+    room_id: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct GlobalRow {
+    key: String,
+    kind: String,
+    value: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct LootEntryRow {
+    table_id: LootTableId,
+    prototype_id: Option<i64>,
+    weight: i64,
+    min_quantity: i64,
+    max_quantity: i64,
+    rarity: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ShopRow {
+    id: ShopId,
+    room_id: i64,
+    loot_table_id: Option<LootTableId>,
+    markup: i64,
+    cap: i64,
+    interval_secs: i64,
+    sunk: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct SpawnTableRow {
+    id: SpawnTableId,
+    cap: i64,
+    interval_secs: i64,
+}
+
+#[tracing::instrument(name = "loading allowlist")]
+async fn load_allowlist(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut allowlist = Allowlist::default();
+
+    let mut results = sqlx::query_as::<_, (String,)>("SELECT name FROM allowlist").fetch(pool);
+
+    while let Some((name,)) = results.try_next().await? {
+        allowlist.insert(name.as_str());
+    }
+
+    world.insert_resource(allowlist);
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading region owners")]
+async fn load_region_owners(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut owners = RegionOwners::default();
+
+    let mut results =
+        sqlx::query_as::<_, (String, i64)>("SELECT region, player_id FROM region_owners")
+            .fetch(pool);
+
+    while let Some((region, player_id)) = results.try_next().await? {
+        let owner =
+            PlayerId::try_from(player_id).map_err(|_| Error::Deserialize("region owner ID"))?;
+        owners.set(region, owner);
     }
+
+    world.insert_resource(owners);
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "loading tutorial")]
+async fn load_tutorial(pool: &SqlitePool, world: &mut World) -> DbResult<()> {
+    let mut tutorial = Tutorial::default();
+
+    let start_row = sqlx::query(r#"SELECT value FROM config WHERE key = "tutorial_room""#)
+        .fetch_optional(pool)
+        .await?;
+
+    if let Some(row) = start_row {
+        let start = row
+            .get::<String, _>("value")
+            .parse::<i64>()
+            .ok()
+            .and_then(|id| RoomId::try_from(id).ok())
+            .ok_or(Error::Deserialize("tutorial room ID"))?;
+
+        // the room may have been removed since it was chosen
+        if world
+            .get_resource::<Rooms>()
+            .unwrap()
+            .by_id(start)
+            .is_some()
+        {
+            tutorial.set_start(Some(start));
+        }
+    }
+
+    let mut results =
+        sqlx::query_as::<_, (String, String)>("SELECT trigger, message FROM hints").fetch(pool);
+
+    while let Some((trigger, message)) = results.try_next().await? {
+        let trigger = HintTrigger::from_str(trigger.as_str())
+            .map_err(|_| Error::Deserialize("hint trigger"))?;
+        tutorial.set_hint(trigger, message);
+    }
+
+    world.insert_resource(tutorial);
+
+    Ok(())
 }
@@ -0,0 +1,97 @@
+use std::{borrow::Cow, fmt, ops::Deref, str};
+
+use ascii::AsciiString;
+use bytes::Bytes;
+
+/// Text passed between connections and the engine. Cloning it shares the underlying buffer. Owned
+/// strings and lines read from telnet clients are taken over rather than copied, and static text
+/// is never allocated.
+///
+/// The buffer is only ever filled from `str`, `String` and `AsciiString`, so it always holds valid
+/// UTF-8. Anything added which builds a `Text` must keep to that, since `as_str` relies on it
+/// rather than checking on every read.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Text(Bytes);
+
+impl Text {
+    pub const fn from_static(text: &'static str) -> Self {
+        Text(Bytes::from_static(text.as_bytes()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        // SAFETY: the buffer was filled from a string, so it is valid UTF-8.
+        unsafe { str::from_utf8_unchecked(&self.0) }
+    }
+}
+
+impl Deref for Text {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for Text {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for Text {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<String> for Text {
+    fn from(value: String) -> Self {
+        Text(Bytes::from(value))
+    }
+}
+
+impl From<&str> for Text {
+    fn from(value: &str) -> Self {
+        Text(Bytes::copy_from_slice(value.as_bytes()))
+    }
+}
+
+impl From<Cow<'_, str>> for Text {
+    fn from(value: Cow<'_, str>) -> Self {
+        match value {
+            Cow::Borrowed(value) => Text::from(value),
+            Cow::Owned(value) => Text::from(value),
+        }
+    }
+}
+
+impl From<AsciiString> for Text {
+    fn from(value: AsciiString) -> Self {
+        let bytes: Vec<u8> = value.into();
+        Text(Bytes::from(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use ascii::AsciiString;
+
+    use crate::engine::Text;
+
+    #[test]
+    fn test_text_shares_buffers() {
+        let text = Text::from(String::from("look north"));
+        assert_eq!(text.as_str(), "look north");
+        assert_eq!(text.clone().as_ptr(), text.as_ptr());
+
+        let text = Text::from(Cow::Owned(String::from("say hi")));
+        assert_eq!(text.as_str(), "say hi");
+
+        let text = Text::from(AsciiString::from_ascii("inventory").unwrap());
+        assert_eq!(text.as_str(), "inventory");
+
+        assert_eq!(Text::from_static("> ").as_str(), "> ");
+    }
+}
@@ -0,0 +1,290 @@
+use crate::{
+    engine::{
+        client::ClientEvent,
+        db::AuthDb,
+        fsm::{
+            verify_password, Fsm, FsmBuilder, FsmState, Params, StackFsm, State, TransitionAction,
+            UpdateResult, VerifyError,
+        },
+    },
+    logging::GAME_LOG_TARGET,
+    text::format_duration,
+};
+
+static DELETE_CHARACTER_ERROR: &str = "|Red1|Failed to delete character.|-|";
+
+pub struct DeleteCharacterFsm {
+    fsm: Fsm<Transition, StateId, DeleteCharacterData>,
+    data: DeleteCharacterData,
+}
+
+impl DeleteCharacterFsm {
+    pub fn new(username: String) -> Self {
+        let fsm = FsmBuilder::new()
+            .with_state(Box::new(WarnState::default()))
+            .with_state(Box::new(VerifyPasswordState::default()))
+            .with_state(Box::new(DeleteState::default()))
+            .with_state(Box::new(FailState::default()))
+            .build()
+            .unwrap();
+
+        Self {
+            fsm,
+            data: DeleteCharacterData { username },
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl StackFsm for DeleteCharacterFsm {
+    async fn on_update(
+        &mut self,
+        event: ClientEvent<'_>,
+        params: &mut Params,
+    ) -> Option<UpdateResult> {
+        if let Ok(event) = event.try_into() {
+            Some(self.fsm.on_update(event, &mut self.data, params).await)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> TryFrom<ClientEvent<'a>> for Transition {
+    type Error = ();
+
+    fn try_from(value: ClientEvent<'a>) -> Result<Self, Self::Error> {
+        let event = match value {
+            ClientEvent::PasswordVerification(verified) => match verified {
+                Some(true) => Transition::VerifiedPassword,
+                None | Some(false) => Transition::VerificationFailed,
+            },
+            _ => return Err(()),
+        };
+
+        Ok(event)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Transition {
+    Ready,
+    VerifiedPassword,
+    VerificationFailed,
+}
+
+impl From<Transition> for TransitionAction<Transition> {
+    fn from(tx: Transition) -> Self {
+        TransitionAction::Transition(tx)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum StateId {
+    Warn,
+    VerifyPassword,
+    Delete,
+    Fail,
+}
+
+pub struct DeleteCharacterData {
+    pub username: String,
+}
+
+impl FsmState<Transition> for DeleteCharacterData {
+    fn update(&mut self, _tx: &Transition) {}
+}
+
+#[derive(Default)]
+pub struct WarnState {}
+
+#[async_trait::async_trait]
+impl State<Transition, StateId, DeleteCharacterData> for WarnState {
+    fn id(&self) -> StateId {
+        StateId::Warn
+    }
+
+    fn output_state(&self, next: &Transition) -> Option<StateId> {
+        match next {
+            Transition::Ready => Some(StateId::VerifyPassword),
+            _ => None,
+        }
+    }
+
+    fn keep_going(&self) -> bool {
+        true
+    }
+
+    async fn process<'a>(
+        &mut self,
+        _input: Option<&str>,
+        _data: &mut DeleteCharacterData,
+        params: &'a mut Params<'_>,
+    ) -> Option<TransitionAction<Transition>> {
+        let grace = format_duration(params.game_world.deletion_grace().as_secs() as i64);
+
+        params
+            .send(vec![
+                "".to_string(),
+                "|White|Character Deletion|-|".to_string(),
+                "".to_string(),
+                format!(
+                    "Your character and everything they own will be deleted in {}. Logging in \
+                     before then cancels the deletion.",
+                    grace
+                ),
+            ])
+            .await;
+
+        Some(Transition::Ready.into())
+    }
+}
+
+#[derive(Default)]
+pub struct VerifyPasswordState {}
+
+#[async_trait::async_trait]
+impl State<Transition, StateId, DeleteCharacterData> for VerifyPasswordState {
+    fn id(&self) -> StateId {
+        StateId::VerifyPassword
+    }
+
+    fn output_state(&self, next: &Transition) -> Option<StateId> {
+        match next {
+            Transition::VerifiedPassword => Some(StateId::Delete),
+            Transition::VerificationFailed => Some(StateId::Fail),
+            _ => None,
+        }
+    }
+
+    async fn on_enter<'a>(&mut self, _data: &mut DeleteCharacterData, params: &'a mut Params<'_>) {
+        params
+            .send_sensitive_prompt(vec!["|SteelBlue3|Enter your password to confirm.|-|"])
+            .await;
+    }
+
+    async fn process<'a>(
+        &mut self,
+        input: Option<&str>,
+        data: &mut DeleteCharacterData,
+        params: &'a mut Params<'_>,
+    ) -> Option<TransitionAction<Transition>> {
+        let input = input?.to_string();
+
+        let hash = match params.db.player_hash(data.username.as_str()).await {
+            Ok(Some(hash)) => hash,
+            Ok(None) => {
+                params.send_prompt(vec![DELETE_CHARACTER_ERROR]).await;
+                return Some(TransitionAction::PopFsm);
+            }
+            Err(e) => {
+                tracing::error!("get user hash error: {:?}", e);
+                params.send_prompt(vec![DELETE_CHARACTER_ERROR]).await;
+                return Some(TransitionAction::PopFsm);
+            }
+        };
+
+        let sender = params.engine_sender.clone();
+        tokio::task::spawn_blocking(
+            move || match verify_password(hash.as_str(), input.as_str()) {
+                Ok(_) => sender.password_verification(Some(true)),
+                Err(e) => match e {
+                    VerifyError::Unknown(e) => {
+                        tracing::error!("failed to verify password: {}", e);
+                        sender.password_verification(None)
+                    }
+                    VerifyError::BadPassword => sender.password_verification(Some(false)),
+                },
+            },
+        );
+
+        None
+    }
+}
+
+#[derive(Default)]
+pub struct DeleteState {}
+
+#[async_trait::async_trait]
+impl State<Transition, StateId, DeleteCharacterData> for DeleteState {
+    fn id(&self) -> StateId {
+        StateId::Delete
+    }
+
+    fn output_state(&self, _next: &Transition) -> Option<StateId> {
+        None
+    }
+
+    fn keep_going(&self) -> bool {
+        true
+    }
+
+    async fn process<'a>(
+        &mut self,
+        _input: Option<&str>,
+        data: &mut DeleteCharacterData,
+        params: &'a mut Params<'_>,
+    ) -> Option<TransitionAction<Transition>> {
+        let delete_after = params.game_world.clock().unix_now()
+            + params.game_world.deletion_grace().as_secs() as i64;
+
+        match params
+            .db
+            .schedule_deletion(data.username.as_str(), delete_after)
+            .await
+        {
+            Ok(_) => {
+                tracing::info!(
+                    target: GAME_LOG_TARGET,
+                    player = data.username.as_str(),
+                    "deletion scheduled"
+                );
+                params
+                    .send(vec![
+                        "|SteelBlue3|Your character will be deleted. Goodbye.|-|",
+                    ])
+                    .await;
+                params.sender.disconnect().await;
+            }
+            Err(e) => {
+                tracing::error!("failed to schedule deletion: {}", e);
+                params.send_prompt(vec![DELETE_CHARACTER_ERROR]).await;
+            }
+        }
+
+        Some(TransitionAction::PopFsm)
+    }
+}
+
+#[derive(Default)]
+pub struct FailState {}
+
+#[async_trait::async_trait]
+impl State<Transition, StateId, DeleteCharacterData> for FailState {
+    fn id(&self) -> StateId {
+        StateId::Fail
+    }
+
+    fn output_state(&self, _next: &Transition) -> Option<StateId> {
+        None
+    }
+
+    fn keep_going(&self) -> bool {
+        true
+    }
+
+    async fn on_enter<'a>(&mut self, _data: &mut DeleteCharacterData, params: &'a mut Params<'_>) {
+        params
+            .send_prompt(vec!["|Red1|Verification failed. Character not deleted.|-|"])
+            .await;
+    }
+
+    async fn process<'a>(
+        &mut self,
+        _input: Option<&str>,
+        _data: &mut DeleteCharacterData,
+        _params: &'a mut Params<'_>,
+    ) -> Option<TransitionAction<Transition>> {
+        Some(TransitionAction::PopFsm)
+    }
+}
@@ -1,3 +1,4 @@
+mod delete_character;
 pub mod negotiate_login;
 mod update_password;
 
@@ -7,19 +8,14 @@ use argon2::{
     Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
 };
 use rand::rngs::OsRng;
-use std::{
-    borrow::Cow,
-    collections::HashMap,
-    fmt::Debug,
-    hash::Hash,
-};
+use std::{borrow::Cow, collections::HashMap, fmt::Debug, hash::Hash};
 
 use crate::{
     engine::{
         client::{ClientEvent, ClientSender, EngineSender, SendPrompt},
         db::Db,
     },
-    world::GameWorld,
+    world::{types::player::ClientCaps, GameWorld},
 };
 
 #[async_trait::async_trait]
@@ -44,6 +40,7 @@ pub trait FsmState<T>: Send + Sync {
 pub struct Params<'p> {
     pub engine_sender: EngineSender,
     pub sender: &'p ClientSender,
+    pub caps: &'p ClientCaps,
     pub game_world: &'p mut GameWorld,
     pub db: &'p Db,
 }
@@ -52,12 +49,14 @@ impl<'p> Params<'p> {
     pub fn new(
         engine_sender: EngineSender,
         sender: &'p ClientSender,
+        caps: &'p ClientCaps,
         world: &'p mut GameWorld,
         db: &'p Db,
     ) -> Self {
         Params {
             engine_sender,
             sender,
+            caps,
             game_world: world,
             db,
         }
@@ -227,6 +226,10 @@ where
     SID: StateId,
     S: FsmState<T>,
 {
+    pub fn current(&self) -> SID {
+        self.current
+    }
+
     pub async fn on_update(
         &mut self,
         event: FsmEvent<'_, T>,
@@ -3,11 +3,13 @@ use crate::{
         client::ClientEvent,
         db::{AuthDb, GameDb},
         fsm::{
-            hash_input, update_password::UpdatePasswordFsm, verify_len, verify_password, Fsm,
-            FsmBuilder, FsmState, Params, State, TransitionAction, UpdateResult, VerifyError,
+            delete_character::DeleteCharacterFsm, hash_input, update_password::UpdatePasswordFsm,
+            verify_len, verify_password, Fsm, FsmBuilder, FsmState, Params, State,
+            TransitionAction, UpdateResult, VerifyError,
         },
-        name_valid,
+        name_valid, Credentials,
     },
+    logging::GAME_LOG_TARGET,
     world::action::{observe::Look, system::Login, Action},
 };
 
@@ -15,7 +17,11 @@ use bevy_ecs::prelude::Entity;
 use std::fmt::Debug;
 
 static DEFAULT_LOGIN_ERROR: &str = "|Red1|Error retrieving user.|-|";
+static DELETED_LOGIN_ERROR: &str = "|Red1|This character has been deleted.|-|";
 static DEFAULT_PASSWORD_ERROR: &str = "|Red1|Verification failed.|-|";
+static IMMORTAL_ONLY_ERROR: &str = "|Red1|This connection is reserved for immortals.|-|";
+static DEGRADED_LOGIN_ERROR: &str =
+    "|Red1|The world is unavailable for logins at the moment. Please try again later.|-|";
 
 pub struct ClientLoginFsm {
     fsm: Fsm<Transition, StateId, ClientState>,
@@ -38,6 +44,14 @@ impl ClientLoginFsm {
     pub fn player(&self) -> Option<Entity> {
         self.data.player()
     }
+
+    pub fn release_player(&mut self) {
+        self.data.player = None;
+    }
+
+    pub fn queued(&self) -> bool {
+        self.fsm.current() == StateId::Queued
+    }
 }
 
 // this state machine will always have the same shape, and there's only one of them,
@@ -47,12 +61,15 @@ impl Default for ClientLoginFsm {
         let fsm = FsmBuilder::new()
             .with_state(Box::new(NotConnectedState::default()))
             .with_state(Box::new(ConnectionReady::default()))
+            .with_state(Box::new(AuthenticatedState::default()))
             .with_state(Box::new(LoginNameState::default()))
             .with_state(Box::new(LoginPasswordState::default()))
             .with_state(Box::new(FailLoginState::default()))
             .with_state(Box::new(CreatePasswordState::default()))
             .with_state(Box::new(VerifyPasswordState::default()))
             .with_state(Box::new(FailPasswordState::default()))
+            .with_state(Box::new(AdmitPlayerState::default()))
+            .with_state(Box::new(QueuedState::default()))
             .with_state(Box::new(SpawnPlayerState::default()))
             .with_state(Box::new(CreateNewPlayerState::default()))
             .with_state(Box::new(InGameState::default()))
@@ -69,6 +86,7 @@ impl Default for ClientLoginFsm {
 pub enum Transition {
     Disconnect,
     Ready,
+    Authenticated(Credentials),
     BeginLogin,
     FailLogin,
     ExistsOffline(String),
@@ -78,6 +96,8 @@ pub enum Transition {
     FailPassword,
     BeginPassword,
     PlayerCreated,
+    ServerFull,
+    Admitted,
     PlayerLoaded(Entity),
 }
 
@@ -102,6 +122,8 @@ impl<'a> TryFrom<ClientEvent<'a>> for Transition {
                 None | Some(false) => Transition::FailPassword,
             },
             ClientEvent::Ready => Transition::Ready,
+            ClientEvent::ReadyAs(credentials) => Transition::Authenticated(credentials),
+            ClientEvent::Admitted => Transition::Admitted,
             _ => return Err(()),
         };
 
@@ -113,6 +135,7 @@ impl<'a> TryFrom<ClientEvent<'a>> for Transition {
 pub enum StateId {
     NotConnected,
     ConnectionReady,
+    Authenticated,
     LoginName,
     LoginPassword,
     FailLogin,
@@ -120,6 +143,8 @@ pub enum StateId {
     VerifyPassword,
     FailPassword,
     CreateNewPlayer,
+    AdmitPlayer,
+    Queued,
     SpawnPlayer,
     InGame,
 }
@@ -129,6 +154,7 @@ pub struct ClientState {
     pub username: Option<String>,
     pub pw_hash: Option<String>,
     pub player: Option<Entity>,
+    pub credentials: Option<Credentials>,
 }
 
 impl ClientState {
@@ -144,6 +170,10 @@ impl ClientState {
 impl FsmState<Transition> for ClientState {
     fn update(&mut self, tx: &Transition) {
         match tx {
+            Transition::Authenticated(credentials) => {
+                self.username = Some(credentials.player.clone());
+                self.credentials = Some(credentials.clone());
+            }
             Transition::CreatedPassword(hash) => self.pw_hash = Some(hash.to_owned()),
             Transition::ExistsOffline(name) => self.username = Some(name.to_owned()),
             Transition::PlayerDoesNotExist(name) => self.username = Some(name.to_owned()),
@@ -169,6 +199,7 @@ impl State<Transition, StateId, ClientState> for NotConnectedState {
     fn output_state(&self, next: &Transition) -> Option<StateId> {
         match next {
             Transition::Ready => Some(StateId::ConnectionReady),
+            Transition::Authenticated(_) => Some(StateId::Authenticated),
             Transition::Disconnect => Some(StateId::NotConnected),
             _ => None,
         }
@@ -201,12 +232,7 @@ impl State<Transition, StateId, ClientState> for ConnectionReady {
     }
 
     async fn on_enter<'a>(&mut self, _data: &mut ClientState, params: &'a mut Params<'_>) {
-        params
-            .send(vec![
-                "|SteelBlue3|Connected to|-| |white|ucs://uplink.six.city|-|",
-                "",
-            ])
-            .await;
+        send_greeting(params).await;
     }
 
     async fn process<'a>(
@@ -219,6 +245,129 @@ impl State<Transition, StateId, ClientState> for ConnectionReady {
     }
 }
 
+// the client presented credentials when connecting, so admit them without asking for a name or
+// password as long as a regular login would have been allowed
+#[derive(Default)]
+pub struct AuthenticatedState {}
+
+#[async_trait::async_trait]
+impl State<Transition, StateId, ClientState> for AuthenticatedState {
+    fn id(&self) -> StateId {
+        StateId::Authenticated
+    }
+
+    fn output_state(&self, next: &Transition) -> Option<StateId> {
+        match next {
+            Transition::Disconnect => Some(StateId::NotConnected),
+            Transition::VerifiedPassword => Some(StateId::AdmitPlayer),
+            _ => None,
+        }
+    }
+
+    fn keep_going(&self) -> bool {
+        true
+    }
+
+    async fn on_enter<'a>(&mut self, _data: &mut ClientState, params: &'a mut Params<'_>) {
+        send_greeting(params).await;
+    }
+
+    async fn process<'a>(
+        &mut self,
+        _input: Option<&str>,
+        data: &mut ClientState,
+        params: &'a mut Params<'_>,
+    ) -> Option<TransitionAction<Transition>> {
+        let credentials = match data.credentials.as_ref() {
+            Some(credentials) => credentials,
+            None => return Some(refuse(params, DEFAULT_LOGIN_ERROR).await),
+        };
+        let name = credentials.player.as_str();
+
+        if params.game_world.is_degraded() {
+            return Some(refuse(params, DEGRADED_LOGIN_ERROR).await);
+        }
+
+        let has_user = match params.db.has_player(name).await {
+            Ok(has_user) => has_user,
+            Err(e) => {
+                tracing::error!("player presence check error: {}", e);
+                false
+            }
+        };
+        let online = params.game_world.player_online(name);
+        if !has_user || (online && !params.game_world.takeover()) {
+            return Some(refuse(params, DEFAULT_LOGIN_ERROR).await);
+        }
+
+        if let Some(rejection) = login_rejection(name, has_user, params).await {
+            return Some(refuse(params, rejection.as_str()).await);
+        }
+
+        if credentials.immortal && !is_immortal(name, params).await {
+            return Some(refuse(params, IMMORTAL_ONLY_ERROR).await);
+        }
+
+        Some(Transition::VerifiedPassword.into())
+    }
+}
+
+async fn send_greeting(params: &mut Params<'_>) {
+    if let Some(banner) = params.game_world.login_banner(params.caps.width()) {
+        params
+            .send(vec![format!("|SteelBlue3|{}|-|", banner), String::new()])
+            .await;
+    }
+
+    params
+        .send(vec![
+            "|SteelBlue3|Connected to|-| |white|ucs://uplink.six.city|-|",
+            "",
+        ])
+        .await;
+
+    if let Some(notice) = params.game_world.maintenance_notice() {
+        params
+            .send(vec![
+                format!("|Gold1|{}|-|", notice.replace("|", "||")),
+                String::new(),
+            ])
+            .await;
+    }
+}
+
+// Sends the reason a pre-authenticated client was turned away and closes its connection.
+async fn refuse(params: &mut Params<'_>, message: &str) -> TransitionAction<Transition> {
+    params.send(vec![message.to_string()]).await;
+    params.sender.disconnect().await;
+    Transition::Disconnect.into()
+}
+
+// During maintenance, or while the allowlist is enforced and does not contain the name, only
+// immortals may log in. Returns the message to show anyone else.
+async fn login_rejection(name: &str, has_user: bool, params: &mut Params<'_>) -> Option<String> {
+    let rejection = params
+        .game_world
+        .maintenance_notice()
+        .or_else(|| params.game_world.allowlist_rejection(name))?;
+
+    if has_user && is_immortal(name, params).await {
+        None
+    } else {
+        Some(format!("|Red1|{}|-|", rejection.replace("|", "||")))
+    }
+}
+
+async fn is_immortal(name: &str, params: &mut Params<'_>) -> bool {
+    match params.db.is_immortal(name).await {
+        Ok(immortal) => immortal,
+        Err(e) => {
+            tracing::error!("immortal check error: {}", e);
+            false
+        }
+    }
+}
+
 // in this state we introduce ourselves
 #[derive(Default)]
 pub struct LoginNameState {}
@@ -257,6 +406,12 @@ impl State<Transition, StateId, ClientState> for LoginNameState {
             return Some(Transition::FailLogin.into());
         }
 
+        // players cannot be loaded or saved while the database is unavailable
+        if params.game_world.is_degraded() {
+            params.send(vec![DEGRADED_LOGIN_ERROR]).await;
+            return Some(Transition::FailLogin.into());
+        }
+
         let (has_user, user_online) = match params.db.has_player(name).await {
             Ok(has_user) => (has_user, params.game_world.player_online(name)),
             Err(e) => {
@@ -266,11 +421,27 @@ impl State<Transition, StateId, ClientState> for LoginNameState {
             }
         };
 
+        if let Some(rejection) = login_rejection(name, has_user, params).await {
+            params.send(vec![rejection]).await;
+            return Some(Transition::FailLogin.into());
+        }
+
         // was there a user and what is their connection status?
         if has_user {
             if user_online {
-                params.send(vec![DEFAULT_LOGIN_ERROR]).await;
-                return Some(Transition::FailLogin.into());
+                if !params.game_world.takeover() {
+                    params.send(vec![DEFAULT_LOGIN_ERROR]).await;
+                    return Some(Transition::FailLogin.into());
+                }
+
+                // the password is verified as usual, then the old connection is closed
+                params
+                    .send(vec![
+                        "|SteelBlue3|User located. Logging in will disconnect their other \
+                         session.|-|",
+                    ])
+                    .await;
+                return Some(Transition::ExistsOffline(name.to_string()).into());
             }
 
             // they were offline
@@ -301,7 +472,7 @@ impl State<Transition, StateId, ClientState> for LoginPasswordState {
     fn output_state(&self, next: &Transition) -> Option<StateId> {
         match next {
             Transition::Disconnect => Some(StateId::NotConnected),
-            Transition::VerifiedPassword => Some(StateId::SpawnPlayer),
+            Transition::VerifiedPassword => Some(StateId::AdmitPlayer),
             Transition::FailPassword => Some(StateId::FailLogin),
             _ => None,
         }
@@ -543,6 +714,118 @@ impl State<Transition, StateId, ClientState> for FailPasswordState {
     }
 }
 
+// Decides whether a player may enter the world now or must wait for someone to leave.
+#[derive(Default)]
+pub struct AdmitPlayerState {}
+
+#[async_trait::async_trait]
+impl State<Transition, StateId, ClientState> for AdmitPlayerState {
+    fn id(&self) -> StateId {
+        StateId::AdmitPlayer
+    }
+
+    fn output_state(&self, next: &Transition) -> Option<StateId> {
+        match next {
+            Transition::Disconnect => Some(StateId::NotConnected),
+            Transition::Admitted => Some(StateId::SpawnPlayer),
+            Transition::ServerFull => Some(StateId::Queued),
+            Transition::FailLogin => Some(StateId::LoginName),
+            _ => None,
+        }
+    }
+
+    fn keep_going(&self) -> bool {
+        true
+    }
+
+    async fn on_enter<'a>(&mut self, data: &mut ClientState, params: &'a mut Params<'_>) {
+        let message = if data.credentials.is_some() {
+            "|SteelBlue3|Token verified.|-|"
+        } else {
+            "|SteelBlue3|Password verified.|-|"
+        };
+        params.send(vec![message, ""]).await;
+    }
+
+    async fn process<'a>(
+        &mut self,
+        _input: Option<&str>,
+        data: &mut ClientState,
+        params: &'a mut Params<'_>,
+    ) -> Option<TransitionAction<Transition>> {
+        if !params.game_world.is_full() {
+            return Some(Transition::Admitted.into());
+        }
+
+        let name = match data.username.as_deref() {
+            Some(name) => name,
+            None => {
+                params.send(vec![DEFAULT_LOGIN_ERROR]).await;
+                return Some(Transition::FailLogin.into());
+            }
+        };
+
+        // taking over a session does not add a player to the world
+        if params.game_world.player_online(name) {
+            return Some(Transition::Admitted.into());
+        }
+
+        match params.db.is_immortal(name).await {
+            Ok(true) => Some(Transition::Admitted.into()),
+            Ok(false) => Some(Transition::ServerFull.into()),
+            Err(e) => {
+                tracing::error!("immortal check error: {}", e);
+                params.send(vec![DEFAULT_LOGIN_ERROR]).await;
+                Some(Transition::FailLogin.into())
+            }
+        }
+    }
+}
+
+// Waits for the engine to admit the player once there is room in the world.
+#[derive(Default)]
+pub struct QueuedState {}
+
+#[async_trait::async_trait]
+impl State<Transition, StateId, ClientState> for QueuedState {
+    fn id(&self) -> StateId {
+        StateId::Queued
+    }
+
+    fn output_state(&self, next: &Transition) -> Option<StateId> {
+        match next {
+            Transition::Disconnect => Some(StateId::NotConnected),
+            Transition::Admitted => Some(StateId::SpawnPlayer),
+            _ => None,
+        }
+    }
+
+    async fn on_enter<'a>(&mut self, _data: &mut ClientState, params: &'a mut Params<'_>) {
+        params
+            .send(vec![
+                "|SteelBlue3|City Six is full. You have been placed in a queue.|-|",
+            ])
+            .await;
+    }
+
+    async fn process<'a>(
+        &mut self,
+        input: Option<&str>,
+        _data: &mut ClientState,
+        params: &'a mut Params<'_>,
+    ) -> Option<TransitionAction<Transition>> {
+        input?;
+
+        params
+            .send_prompt(vec![
+                "|SteelBlue3|Please wait, you will be connected when there is room.|-|",
+            ])
+            .await;
+
+        None
+    }
+}
+
 #[derive(Default)]
 pub struct SpawnPlayerState {}
 
@@ -565,10 +848,7 @@ impl State<Transition, StateId, ClientState> for SpawnPlayerState {
         true
     }
 
-    async fn on_enter<'a>(&mut self, data: &mut ClientState, params: &'a mut Params<'_>) {
-        params
-            .send(vec!["|SteelBlue3|Password verified.|-|", ""])
-            .await;
+    async fn on_enter<'a>(&mut self, data: &mut ClientState, _params: &'a mut Params<'_>) {
         data.player = None
     }
 
@@ -585,11 +865,55 @@ impl State<Transition, StateId, ClientState> for SpawnPlayerState {
 
         let name = data.username.as_ref().unwrap();
 
-        let player = match params
-            .db
-            .load_player(params.game_world.world_mut(), name.as_str())
-            .await
-        {
+        // The player may have logged in from another connection while this one was queued.
+        if let Some(player) = params.game_world.online_player(name) {
+            if !params.game_world.takeover() {
+                params.send(vec![DEFAULT_LOGIN_ERROR]).await;
+                return Some(Transition::FailLogin.into());
+            }
+
+            // The engine closes the other connection once this one is associated with the player.
+            tracing::info!(target: GAME_LOG_TARGET, player = name.as_str(), "takeover");
+
+            params
+                .send(vec!["|white|Welcome back to City Six.|-|", ""])
+                .await;
+            params.game_world.player_action(Action::from(Look {
+                actor: player,
+                direction: None,
+                brief: false,
+            }));
+
+            return Some(Transition::PlayerLoaded(player).into());
+        }
+
+        // Characters due to be deleted are only waiting to be purged
+        let now = params.game_world.clock().unix_now();
+        match params.db.deletion_due(name.as_str(), now).await {
+            Ok(false) => (),
+            Ok(true) => {
+                params.send(vec![DELETED_LOGIN_ERROR]).await;
+                return Some(Transition::FailLogin.into());
+            }
+            Err(e) => {
+                tracing::error!("failed to check for deletion: {}", e);
+                params.send(vec![DEFAULT_LOGIN_ERROR]).await;
+                return Some(Transition::FailLogin.into());
+            }
+        }
+
+        // Players who left moments ago are still in memory and are restored as they left.
+        let restored = params.game_world.restore_player(name.as_str());
+        let player = match restored {
+            Some(player) => Ok(player),
+            None => {
+                params
+                    .db
+                    .load_player(params.game_world.world_mut(), name.as_str())
+                    .await
+            }
+        };
+        let player = match player {
             Ok(player) => (player),
             Err(e) => {
                 tracing::error!("failed to load player: {}", e);
@@ -598,13 +922,32 @@ impl State<Transition, StateId, ClientState> for SpawnPlayerState {
             }
         };
 
+        tracing::info!(target: GAME_LOG_TARGET, player = name.as_str(), "login");
+
         params.send(vec!["|white|Welcome to City Six.", ""]).await;
+        let now = params.game_world.clock().unix_now();
+        match params.db.cancel_deletion(name.as_str(), now).await {
+            Ok(true) => {
+                params
+                    .send(vec![
+                        "|SteelBlue3|Your character's deletion has been cancelled.|-|",
+                        "",
+                    ])
+                    .await
+            }
+            Ok(false) => (),
+            Err(e) => tracing::error!("failed to cancel deletion: {}", e),
+        }
+        if let Some(motd) = params.game_world.motd() {
+            params.send(vec![motd, String::new()]).await;
+        }
         params
             .game_world
             .player_action(Action::from(Login { actor: player }));
         params.game_world.player_action(Action::from(Look {
             actor: player,
             direction: None,
+            brief: false,
             // TODO: one-shot in the FSM to let the engine know the player is ready / in game?
         }));
 
@@ -624,7 +967,7 @@ impl State<Transition, StateId, ClientState> for CreateNewPlayerState {
     fn output_state(&self, next: &Transition) -> Option<StateId> {
         match next {
             Transition::Disconnect => Some(StateId::NotConnected),
-            Transition::PlayerCreated => Some(StateId::SpawnPlayer),
+            Transition::PlayerCreated => Some(StateId::AdmitPlayer),
             Transition::FailLogin => Some(StateId::LoginName),
             _ => None,
         }
@@ -651,14 +994,17 @@ impl State<Transition, StateId, ClientState> for CreateNewPlayerState {
 
         let name = data.username.as_ref().unwrap();
         let hash = data.pw_hash.as_ref().unwrap();
-        let spawn_room = params.game_world.spawn_room();
+        let spawn_room = params.game_world.new_player_room();
 
         return match params
             .db
             .create_player(name.as_str(), hash.as_str(), spawn_room)
             .await
         {
-            Ok(_) => Some(Transition::PlayerCreated.into()),
+            Ok(_) => {
+                tracing::info!(target: GAME_LOG_TARGET, player = name.as_str(), "created player");
+                Some(Transition::PlayerCreated.into())
+            }
             Err(e) => {
                 tracing::error!("user creation error: {}", e);
                 params.send(vec![DEFAULT_LOGIN_ERROR]).await;
@@ -707,6 +1053,12 @@ impl State<Transition, StateId, ClientState> for InGameState {
             ))));
         }
 
+        if input == "delete character" {
+            return Some(TransitionAction::PushFsm(Box::new(
+                DeleteCharacterFsm::new(data.username.as_ref().unwrap().clone()),
+            )));
+        }
+
         let player = data.player.unwrap();
 
         if let Err(message) = params.game_world.player_input(player, input) {
@@ -0,0 +1,57 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// Records when the engine last completed a tick and what it is doing now, so the web server and
+/// watchdog can report whether the game is still advancing. Clones share the same record.
+#[derive(Debug, Clone)]
+pub(crate) struct Heartbeat {
+    start: Instant,
+    // Milliseconds since start of the last tick, plus one, or zero before the first tick.
+    last: Arc<AtomicU64>,
+    stage: Arc<Mutex<&'static str>>,
+}
+
+impl Heartbeat {
+    pub fn beat(&self) {
+        let elapsed = self.start.elapsed().as_millis() as u64;
+        self.last.store(elapsed + 1, Ordering::Relaxed);
+    }
+
+    /// Forgets the last tick, for when the engine is being restarted.
+    pub fn reset(&self) {
+        self.last.store(0, Ordering::Relaxed);
+        self.enter("starting");
+    }
+
+    /// Records the step the engine is about to run.
+    pub fn enter(&self, stage: &'static str) {
+        *self.stage.lock().unwrap() = stage;
+    }
+
+    /// Time since the last tick, or `None` if the engine has not ticked yet.
+    pub fn since_last(&self) -> Option<Duration> {
+        match self.last.load(Ordering::Relaxed) {
+            0 => None,
+            last => Some(self.start.elapsed() - Duration::from_millis(last - 1)),
+        }
+    }
+
+    pub fn stage(&self) -> &'static str {
+        *self.stage.lock().unwrap()
+    }
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Heartbeat {
+            start: Instant::now(),
+            last: Arc::new(AtomicU64::new(0)),
+            stage: Arc::new(Mutex::new("starting")),
+        }
+    }
+}
@@ -1,73 +1,146 @@
 mod client;
 pub mod db;
 pub mod dialog;
+mod envelope;
 pub mod fsm;
+mod heartbeat;
 pub mod persist;
 
-use std::{borrow::Cow, collections::VecDeque};
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    path::Path,
+    time::{Duration, Instant},
+};
 
+use bevy_ecs::prelude::Entity;
 use futures::future::join_all;
 use itertools::Itertools;
 use thiserror::Error;
 use tokio::{
-    sync::mpsc,
-    time::{interval, Duration, Interval},
+    sync::{mpsc, oneshot},
+    time::{interval, Interval},
 };
 
+pub(crate) use envelope::Text;
+pub(crate) use heartbeat::Heartbeat;
+
 use crate::metrics::stats_gauge;
 use crate::world::scripting::Script;
 use crate::world::types::object::{Object, Prototype};
 use crate::world::types::player::Player;
-use crate::world::types::room::Room;
+use crate::world::types::room::{LoadedRegions, Room};
 use crate::{
+    clock::Clock,
+    config::Config,
     ecs::{CorePlugin, Ecs},
     engine::{
         client::{Client, ClientEvent, Clients, SendPrompt},
-        db::{Db, GameDb},
-        persist::PersistPlugin,
+        db::{check::describe_issues, Db, GameDb, Rename},
+        persist::{
+            retry::{Failure, Retries, ESCALATE_ATTEMPTS},
+            DynPersist, PersistPlugin, RoomLoad,
+        },
     },
+    logging::{DEAD_LETTER_TARGET, GAME_LOG_TARGET},
     macros::regex,
     metrics::StatsTimer,
+    profile::{finish_capture, start_capture},
     web::{
-        scripts::{JsonScript, JsonScriptInfo, JsonScriptName, JsonScriptResponse},
+        players::JsonPlayerExport,
+        scripts::{
+            JsonScript, JsonScriptInfo, JsonScriptName, JsonScriptResponse,
+            JsonScriptStatsResponse, ScriptError,
+        },
         ScriptsRequest, ScriptsResponse, WebMessage,
     },
     world::{
-        action::ActionsPlugin, fsm::FsmPlugin, scripting::ScriptPlugin, types::TypesPlugin,
+        action::ActionsPlugin,
+        behavior::BehaviorPlugin,
+        communication::CommunicationPlugin,
+        fsm::FsmPlugin,
+        lint::LintReport,
+        mail::MailPlugin,
+        market::MarketPlugin,
+        scripting::{
+            http::{HttpClient, HttpResponse},
+            ScriptPlugin,
+        },
+        season::SeasonPlugin,
+        shop::ShopPlugin,
+        spawn::SpawnPlugin,
+        types::{art::Art, player::ClientCaps, DbCheckRequest, PlayerRenameRequest, TypesPlugin},
         GameWorld,
     },
     ClientId,
 };
 
+const PROFILE_DIRECTORY: &str = "./profiles";
+const ERASED_NOTICE: &str = "|Red1|Your character has been erased. Goodbye.|-|";
+const PROMPT: &str = "> ";
+
 pub(crate) enum EngineMessage {
     Disconnect(ClientId),
+    ReloadConfig(Entity),
     Restart,
     Shutdown,
 }
 
+/// The result of re-reading the configuration file, sent to the engine so it can apply the new
+/// settings and report back to whoever requested the reload.
+pub(crate) struct ConfigUpdate {
+    pub requester: Option<Entity>,
+    pub result: Result<Config, String>,
+}
+
+/// A player whose identity was established before connecting, such as by a web access token, so
+/// the login FSM can skip asking for a name and password.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Credentials {
+    pub player: String,
+    /// Whether the connection may only be used by an immortal.
+    pub immortal: bool,
+}
+
 #[derive(Debug)]
 pub enum ClientMessage {
+    // reports what the client supports, whenever it changes
+    Capabilities(ClientId, ClientCaps),
     Connect(
         ClientId,
         mpsc::Sender<ClientMessage>,
         mpsc::Sender<EngineResponse>,
     ),
     Disconnect(ClientId),
-    Input(ClientId, String),
+    Input(ClientId, Text),
     PasswordHash(ClientId, Option<String>),
     PasswordVerification(ClientId, Option<bool>),
     Ready(ClientId),
+    ReadyAs(ClientId, Credentials),
+    // registers the token a later connection may use to resume the client's session
+    Resumable(ClientId, String),
+    // asks to take over a detached session, replying with its client ID if the token is valid
+    Resume(
+        ClientId,
+        String,
+        mpsc::Sender<EngineResponse>,
+        oneshot::Sender<Option<ClientId>>,
+    ),
 }
 
 impl ClientMessage {
     fn client_id(&self) -> ClientId {
         match self {
+            ClientMessage::Capabilities(id, _) => *id,
             ClientMessage::Connect(id, _, _) => *id,
             ClientMessage::Disconnect(id) => *id,
             ClientMessage::Input(id, _) => *id,
             ClientMessage::Ready(id) => *id,
+            ClientMessage::ReadyAs(id, _) => *id,
             ClientMessage::PasswordHash(id, _) => *id,
             ClientMessage::PasswordVerification(id, _) => *id,
+            ClientMessage::Resumable(id, _) => *id,
+            ClientMessage::Resume(id, _, _, _) => *id,
         }
     }
 }
@@ -80,6 +153,7 @@ impl ClientMessage {
 #[derive(Debug)]
 pub enum EngineResponse {
     Output(VecDeque<Output>),
+    Disconnect,
 }
 
 impl EngineResponse {
@@ -90,11 +164,11 @@ impl EngineResponse {
         EngineResponse::Output(
             messages
                 .into_iter()
-                .map(Into::into)
-                .map(|m| m.to_string())
+                .map(Into::<Cow<str>>::into)
+                .map(Text::from)
                 .map(Output::Message)
                 .chain(std::iter::once(Output::Prompt {
-                    format: "> ".to_string(),
+                    format: Text::from_static(PROMPT),
                     sensitive,
                 }))
                 .collect(),
@@ -105,8 +179,8 @@ impl EngineResponse {
         EngineResponse::Output(
             messages
                 .into_iter()
-                .map(Into::into)
-                .map(|m| m.to_string())
+                .map(Into::<Cow<str>>::into)
+                .map(Text::from)
                 .map(Output::Message)
                 .collect(),
         )
@@ -121,7 +195,7 @@ impl From<Output> for EngineResponse {
 
         if !is_prompt {
             vec.push_back(Output::Prompt {
-                format: "> ".to_string(),
+                format: Text::from_static(PROMPT),
                 sensitive: false,
             });
         }
@@ -132,19 +206,31 @@ impl From<Output> for EngineResponse {
 
 #[derive(Debug)]
 pub enum Output {
-    Message(String),
-    Prompt { format: String, sensitive: bool },
+    Message(Text),
+    Prompt { format: Text, sensitive: bool },
 }
 
 pub struct Engine {
     client_rx: mpsc::Receiver<ClientMessage>,
     engine_tx: mpsc::Sender<EngineMessage>,
     web_rx: mpsc::Receiver<WebMessage>,
+    config_rx: mpsc::Receiver<ConfigUpdate>,
+    http_tx: mpsc::Sender<HttpResponse>,
+    http_rx: mpsc::Receiver<HttpResponse>,
+    http_client: HttpClient,
     clients: Clients,
     metrics_ticker: Interval,
     game_update_ticker: Interval,
+    leaderboard_ticker: Interval,
+    lint_ticker: Interval,
+    autosave_ticker: Interval,
+    needs_ticker: Interval,
     game_world: GameWorld,
     db: Db,
+    retries: Retries,
+    profile_ticks: Option<u32>,
+    heartbeat: Heartbeat,
+    resume_grace: Duration,
 }
 
 #[derive(Debug, Error)]
@@ -157,38 +243,95 @@ impl Engine {
     #[tracing::instrument(name = "creating engine", skip_all)]
     pub(crate) async fn new(
         db: Db,
+        config: &Config,
         client_rx: mpsc::Receiver<ClientMessage>,
         engine_tx: mpsc::Sender<EngineMessage>,
         web_rx: mpsc::Receiver<WebMessage>,
+        config_rx: mpsc::Receiver<ConfigUpdate>,
+        heartbeat: Heartbeat,
+        clock: Clock,
     ) -> Result<Self, Error> {
         let mut ecs = Ecs::new();
 
-        ecs.register(CorePlugin::default()).await;
+        ecs.register(CorePlugin::new(clock)).await;
         ecs.register(TypesPlugin::default()).await;
         ecs.register(ActionsPlugin::default()).await;
         ecs.register(ScriptPlugin::default()).await;
         ecs.register(FsmPlugin::default()).await;
+        ecs.register(SpawnPlugin::default()).await;
+        ecs.register(MarketPlugin::default()).await;
+        ecs.register(MailPlugin::default()).await;
+        ecs.register(CommunicationPlugin::default()).await;
+        ecs.register(SeasonPlugin::default()).await;
+        ecs.register(ShopPlugin::default()).await;
+        ecs.register(BehaviorPlugin::default()).await;
         ecs.register(PersistPlugin::default()).await;
 
+        if config.regions.lazy {
+            ecs.world_mut()
+                .get_resource_mut::<LoadedRegions>()
+                .unwrap()
+                .enable(config.regions.idle());
+        }
+
         {
             db.load_world(ecs.world_mut()).await?;
         }
 
         let mut game_world = GameWorld::new(ecs);
+        game_world.set_motd(config.motd.clone());
+        game_world.set_art(load_art(config.art_dir.as_deref()));
+        game_world.set_login_banner(config.login.banner.clone());
+        game_world.set_http_allowlist(config.scripting.http_allowlist.clone());
+        game_world.set_slow_script_threshold(config.scripting.slow_script_threshold());
+        game_world.set_listing_duration(config.market.listing_duration());
+        game_world.set_mail_expiry(config.mail.expiry());
+        game_world.set_seasons(config.season_windows());
+        game_world.set_max_players(config.engine.max_players);
+        game_world.set_allowlist(config.login.allowlist, config.login.rejection.clone());
+        game_world.set_maintenance_notice(config.login.maintenance.clone());
+        game_world.set_takeover(config.login.takeover);
+        game_world.set_needs(config.needs.enabled);
+        game_world.set_deletion_grace(config.login.deletion_grace());
+        game_world.set_departed_policy(config.login.relog_grace(), config.login.relog_players);
+        game_world.set_snoop_policy(
+            config.moderation.snoop_duration(),
+            config.moderation.snoop_approval,
+        );
+        game_world.set_communication_retention(config.moderation.communication_retention());
+
+        let (http_tx, http_rx) = mpsc::channel(16);
 
         // Run a tick to perform initialization of loaded objects.
         game_world.run_pre_init();
 
-        Ok(Engine {
+        let mut engine = Engine {
             client_rx,
             engine_tx,
             web_rx,
+            config_rx,
+            http_tx,
+            http_rx,
+            http_client: HttpClient::new(&config.scripting),
             clients: Clients::default(),
-            metrics_ticker: interval(Duration::from_secs(1)),
-            game_update_ticker: interval(Duration::from_millis(15)),
+            metrics_ticker: interval(config.engine.metrics_rate()),
+            game_update_ticker: interval(config.engine.tick_rate()),
+            leaderboard_ticker: interval(config.engine.leaderboard_rate()),
+            lint_ticker: interval(config.engine.lint_rate()),
+            autosave_ticker: interval(config.engine.autosave_rate()),
+            needs_ticker: interval(config.needs.growth_rate()),
             game_world,
             db,
-        })
+            retries: Retries::default(),
+            profile_ticks: None,
+            heartbeat,
+            resume_grace: config.web.resume_grace(),
+        };
+
+        // Bring in the regions next to the rooms which are always loaded.
+        engine.cycle_regions().await;
+
+        Ok(engine)
     }
 
     fn tick_metrics(&mut self) {
@@ -203,6 +346,7 @@ impl Engine {
         stats_gauge("num-prototypes", prototypes_query.iter(world).len() as u64);
         stats_gauge("num-rooms", rooms_query.iter(world).len() as u64);
         stats_gauge("num-scripts", scripts_query.iter(world).len() as u64);
+        stats_gauge("num-unpersisted-updates", self.retries.len() as u64);
     }
 
     #[tracing::instrument(name = "run engine", skip_all)]
@@ -211,56 +355,173 @@ impl Engine {
             tokio::select! {
                 _ = self.metrics_ticker.tick() => {
                     let _timer = StatsTimer::new("engine-tick-metrics");
+                    self.heartbeat.enter("tick metrics");
                     self.tick_metrics();
                 }
+                _ = self.leaderboard_ticker.tick() => {
+                    let _timer = StatsTimer::new("engine-tick-leaderboards");
+                    self.heartbeat.enter("update leaderboards");
+                    self.update_leaderboards().await;
+
+                    self.heartbeat.enter("purge deleted players");
+                    self.purge_deleted_players().await;
+                }
+                _ = self.lint_ticker.tick() => {
+                    let _timer = StatsTimer::new("engine-tick-lint");
+                    self.heartbeat.enter("lint world");
+                    self.lint_world();
+                }
+                _ = self.autosave_ticker.tick() => {
+                    let _timer = StatsTimer::new("engine-tick-autosave");
+                    self.heartbeat.enter("autosave players");
+                    self.save_players().await;
+                }
+                _ = self.needs_ticker.tick() => {
+                    let _timer = StatsTimer::new("engine-tick-needs");
+                    self.heartbeat.enter("grow player needs");
+                    self.game_world.grow_needs();
+                }
                 _ = self.game_update_ticker.tick() => {
-                    let _timer = StatsTimer::new("engine-run-loop");
+                    let _timer = StatsTimer::sampled("engine-run-loop");
 
+                    self.heartbeat.enter("run pre-init systems");
                     self.game_world.run_pre_init();
                     self.dispatch_engine_messages().await;
 
+                    self.heartbeat.enter("run main systems");
                     self.game_world.run_main();
                     self.dispatch_engine_messages().await;
 
+                    self.heartbeat.enter("run post-timed systems");
                     self.game_world.run_post_timed();
                     self.dispatch_engine_messages().await;
 
+                    self.heartbeat.enter("run forced commands");
+                    self.game_world.run_forced_commands();
+
+                    #[cfg(feature = "invariants")]
+                    {
+                        self.heartbeat.enter("check world invariants");
+                        self.game_world.check_invariants();
+                    }
+
+                    self.heartbeat.enter("persist updates");
                     self.persist_updates().await;
+                    self.retry_updates().await;
+
+                    self.dispatch_http_requests();
 
+                    self.heartbeat.enter("reload prototypes");
                     self.reload_prototypes().await;
 
+                    self.heartbeat.enter("load vaults");
+                    self.load_vaults().await;
+
+                    self.heartbeat.enter("load regions");
+                    self.cycle_regions().await;
+
+                    self.update_profile();
+
+                    self.heartbeat.enter("expire detached clients");
+                    self.expire_detached().await;
+
+                    self.heartbeat.enter("expire departed players");
+                    self.game_world.expire_departed();
+
+                    self.heartbeat.beat();
+
+                    // Ask the server to re-read the configuration file if requested
+                    if let Some(requester) = self.game_world.take_config_reload() {
+                        self.engine_tx.send(EngineMessage::ReloadConfig(requester)).await.ok();
+                    }
+
+                    // Check the database's integrity if requested
+                    if let Some(request) = self.game_world.take_db_check() {
+                        self.heartbeat.enter("check database integrity");
+                        self.check_database_integrity(request).await;
+                    }
+
+                    // Lint the world if requested
+                    if let Some(requester) = self.game_world.take_lint_request() {
+                        self.heartbeat.enter("lint world");
+                        let report = self.lint_world();
+                        self.game_world.finish_lint(requester, &report);
+                    }
+
+                    // Rename a player if requested
+                    if let Some(request) = self.game_world.take_player_rename() {
+                        self.heartbeat.enter("rename player");
+                        self.rename_player(request).await;
+                    }
+
+                    // Disconnect mortals once a maintenance countdown runs out
+                    if let Some((notice, players)) = self.game_world.take_maintenance_boot() {
+                        self.boot_players(notice, players).await;
+                    }
+
                     // Shutdown if requested
                     if self.game_world.should_shutdown(){
+                        self.save_players().await;
                         self.engine_tx.send(EngineMessage::Shutdown).await.ok();
                         break
                     }
 
                     // Restart if requested
                     if self.game_world.should_restart(){
+                        self.save_players().await;
                         self.engine_tx.send(EngineMessage::Restart).await.ok();
                         break
                     }
                 }
                 maybe_message = self.client_rx.recv() => {
                     if let Some(message) = maybe_message {
+                        self.heartbeat.enter("process client message");
                         self.process(message).await;
                     }
                 }
                 maybe_message = self.web_rx.recv() => {
                     if let Some(message) = maybe_message {
+                        self.heartbeat.enter("process web message");
                         self.process_web(message).await;
                     }
                 }
+                maybe_update = self.config_rx.recv() => {
+                    if let Some(update) = maybe_update {
+                        self.heartbeat.enter("apply config update");
+                        self.apply_config(update);
+                        if self.admit_queued().await {
+                            self.update_queue_positions().await;
+                        }
+                    }
+                }
+                maybe_response = self.http_rx.recv() => {
+                    if let Some(response) = maybe_response {
+                        self.heartbeat.enter("deliver http response");
+                        self.game_world.http_response(response);
+                    }
+                }
             }
+
+            self.heartbeat.enter("idle");
         }
     }
 
     #[tracing::instrument(name = "dispatch engine messages", skip_all)]
     pub async fn dispatch_engine_messages(&mut self) {
-        // Dispatch all queued messages to players
-        for (player, messages) in self.game_world.messages() {
+        // Dispatch all queued messages to players. Only broadcasts are dropped for clients which
+        // have fallen behind, a player's own output is always delivered.
+        for (player, messages, prompt, broadcast) in self.game_world.messages() {
             if let Some(client) = self.clients.by_player(player) {
-                client.send(SendPrompt::Prompt, messages).await;
+                let prompt = if prompt {
+                    SendPrompt::Prompt
+                } else {
+                    SendPrompt::None
+                };
+                if broadcast {
+                    client.offer(prompt, messages);
+                } else {
+                    client.deliver(prompt, messages).await;
+                }
             } else {
                 tracing::error!(
                     "attempting to send messages to player without client: {:?}",
@@ -272,18 +533,201 @@ impl Engine {
 
     #[tracing::instrument(name = "persist updates", skip_all)]
     pub async fn persist_updates(&mut self) {
-        // Dispatch all persistence requests
+        let updates = self.game_world.updates();
+
+        self.retries.supersede(&updates);
+
+        // Hold updates while the database is unavailable, or behind older updates waiting to be
+        // retried so they are saved in the order they were made
+        if self.retries.is_degraded() || !self.retries.is_empty() {
+            for update in updates {
+                self.retries.hold(update);
+            }
+            return;
+        }
+
+        // Dispatch all persistence requests, handing back those which fail
         let mut handles = Vec::new();
-        for update in self.game_world.updates() {
+        for update in updates {
             let pool = self.db.get_pool();
             handles.push(tokio::spawn(async move {
                 match update.enact(&pool).await {
-                    Ok(_) => (),
-                    Err(e) => tracing::error!("failed to execute update: {}", e),
-                };
+                    Ok(_) => Ok(update.write()),
+                    Err(e) => {
+                        tracing::error!("failed to execute update: {}", e);
+                        Err((update, e))
+                    }
+                }
             }));
         }
-        join_all(handles).await;
+
+        let (written, failed): (Vec<_>, Vec<_>) = join_all(handles)
+            .await
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .partition_result();
+
+        self.game_world
+            .record_writes(written.into_iter().flatten().collect());
+
+        if !failed.is_empty() {
+            let now = Instant::now();
+            for (update, e) in failed {
+                if let Failure::DeadLetter(update) = self.retries.fail(update, &e, now) {
+                    self.dead_letter(update, &e);
+                }
+            }
+            self.check_database().await;
+        }
+    }
+
+    #[tracing::instrument(name = "retry updates", skip_all)]
+    async fn retry_updates(&mut self) {
+        let now = Instant::now();
+
+        if self.retries.probe_due(now) {
+            if self.db.is_available().await {
+                tracing::info!(
+                    "database available again, saving {} held updates",
+                    self.retries.len()
+                );
+                self.retries.recover(now);
+                self.game_world.set_degraded(false);
+                self.game_world.notify_immortals(format!(
+                    "The database is available again. Saving {} held changes.",
+                    self.retries.len()
+                ));
+            } else {
+                self.retries.probe_failed(now);
+            }
+        }
+
+        // Retries run one at a time in the order the updates were made, stopping at a failure
+        while let Some(retry) = self.retries.take_next(now) {
+            if let Err(e) = retry.update.enact(&self.db.get_pool()).await {
+                tracing::error!("failed to retry update: {}", e);
+
+                match self.retries.retry(retry, &e, now) {
+                    Failure::Retrying(ESCALATE_ATTEMPTS) => {
+                        self.game_world.notify_immortals(format!(
+                            "A change has failed to save {} times and is still being retried: {}",
+                            ESCALATE_ATTEMPTS, e
+                        ));
+                    }
+                    Failure::Retrying(_) => (),
+                    Failure::DeadLetter(update) => {
+                        self.dead_letter(update, &e);
+                        continue;
+                    }
+                }
+
+                self.check_database().await;
+                break;
+            }
+        }
+    }
+
+    // Drops an update which can never be saved, so it does not hold back those made after it. It
+    // is written to the dead letter log for operators to repair by hand.
+    fn dead_letter(&mut self, update: DynPersist, error: &anyhow::Error) {
+        tracing::error!(
+            target: DEAD_LETTER_TARGET,
+            update = ?update,
+            "dropped update which cannot be saved: {}",
+            error
+        );
+        self.game_world.notify_immortals(format!(
+            "A change could not be saved and was dropped: {}",
+            error
+        ));
+    }
+
+    // Enters degraded mode if the database cannot be reached, holding updates and refusing
+    // logins until it returns.
+    async fn check_database(&mut self) {
+        if self.retries.is_degraded() || self.db.is_available().await {
+            return;
+        }
+
+        tracing::error!("database unavailable, holding updates until it returns");
+        self.retries.degrade(Instant::now());
+        self.game_world.set_degraded(true);
+        self.game_world.notify_immortals(
+            "The database is unavailable. Changes are being held and logins are disabled until it \
+             returns."
+                .to_string(),
+        );
+    }
+
+    #[tracing::instrument(name = "dispatch http requests", skip_all)]
+    fn dispatch_http_requests(&mut self) {
+        // Perform script requests in the background, the responses are delivered to the world
+        // when they arrive.
+        for request in self.game_world.http_requests() {
+            let client = self.http_client.clone();
+            let http_tx = self.http_tx.clone();
+            tokio::spawn(async move {
+                http_tx.send(client.fetch(request).await).await.ok();
+            });
+        }
+    }
+
+    #[tracing::instrument(name = "record playtime", skip_all)]
+    async fn record_playtime(&mut self) {
+        self.game_world.record_playtime();
+        self.persist_updates().await;
+
+        if !self.retries.is_empty() {
+            tracing::error!("{} updates could not be saved", self.retries.len());
+        }
+    }
+
+    // Saves every online player in full and waits for the writes to finish.
+    #[tracing::instrument(name = "save players", skip_all)]
+    async fn save_players(&mut self) {
+        self.game_world.save_players();
+        self.record_playtime().await;
+    }
+
+    #[tracing::instrument(name = "update leaderboards", skip_all)]
+    async fn update_leaderboards(&mut self) {
+        // Flush accumulated playtime so the aggregation sees current totals
+        self.record_playtime().await;
+
+        if let Err(e) = self
+            .db
+            .aggregate_leaderboards(self.game_world.world_mut())
+            .await
+        {
+            tracing::error!("failed to aggregate leaderboards: {}", e);
+        }
+    }
+
+    #[tracing::instrument(name = "lint world", skip_all)]
+    fn lint_world(&mut self) -> LintReport {
+        let report = self.game_world.lint();
+
+        for (kind, count) in report.summary() {
+            tracing::info!("world lint found {} {} issues", count, kind);
+        }
+
+        report
+    }
+
+    #[tracing::instrument(name = "purge deleted players", skip_all)]
+    async fn purge_deleted_players(&mut self) {
+        let now = self.game_world.clock().unix_now();
+        match self.db.purge_deleted_players(now).await {
+            Ok(names) => {
+                for name in names {
+                    tracing::info!(target: GAME_LOG_TARGET, player = name.as_str(), "deleted");
+                    self.game_world.forget_departed(name.as_str());
+                    self.game_world.forget_mail(name.as_str());
+                    self.game_world.forget_communications(name.as_str());
+                }
+            }
+            Err(e) => tracing::error!("failed to purge deleted players: {}", e),
+        }
     }
 
     #[tracing::instrument(name = "reload prototypes", skip_all)]
@@ -303,6 +747,355 @@ impl Engine {
         }
     }
 
+    #[tracing::instrument(name = "load vaults", skip_all)]
+    pub async fn load_vaults(&mut self) {
+        // Load vaults requested by bank actions, then run those actions again
+        let vault_loads = self.game_world.vault_loads();
+        for (player, action) in vault_loads {
+            match self
+                .db
+                .load_vault(self.game_world.world_mut(), player)
+                .await
+            {
+                Ok(_) => self.game_world.player_action(action),
+                Err(e) => tracing::error!("failed to load vault for {:?}: {}", player, e),
+            };
+        }
+    }
+
+    #[tracing::instrument(name = "cycle regions", skip_all)]
+    pub async fn cycle_regions(&mut self) {
+        if let Some(plan) = self.game_world.plan_regions() {
+            // Evicted rooms are loaded again from the database, so regions only leave the world
+            // once every update made to them has been saved. Regions are evicted before any are
+            // loaded, so those loaded for an action are still there when it runs again.
+            if self.retries.is_empty() {
+                for region in plan.evict {
+                    if self.game_world.evict_region(region.as_str()) {
+                        tracing::debug!("evicted region {}", region);
+                    }
+                }
+            }
+
+            for room in plan.rooms {
+                if let Err(e) = self.db.load_room(self.game_world.world_mut(), room).await {
+                    tracing::error!("failed to load room {}: {}", room, e);
+                }
+            }
+
+            for region in plan.load {
+                if let Err(e) = self
+                    .db
+                    .load_region(self.game_world.world_mut(), region.as_str())
+                    .await
+                {
+                    tracing::error!("failed to load region {}: {}", region, e);
+                }
+            }
+        }
+
+        // Load rooms requested by actions, then run those actions again
+        let room_loads = self.game_world.room_loads();
+        for (load, action) in room_loads {
+            let world = self.game_world.world_mut();
+            let result = match &load {
+                RoomLoad::Room(room) => self.db.load_room(world, *room).await,
+                RoomLoad::Region(region) => self.db.load_region(world, region.as_str()).await,
+            };
+            match result {
+                Ok(_) => self.game_world.player_action(action),
+                Err(e) => tracing::error!("failed to load {:?}: {}", load, e),
+            };
+        }
+    }
+
+    #[tracing::instrument(name = "check database integrity", skip_all)]
+    async fn check_database_integrity(&mut self, request: DbCheckRequest) {
+        let message = match self.db.check_integrity(request.repair).await {
+            Ok(issues) => describe_issues(issues.as_slice(), request.repair),
+            Err(e) => {
+                tracing::error!("failed to check database integrity: {}", e);
+                format!("Failed to check the database: {}", e)
+            }
+        };
+
+        self.game_world.finish_db_check(request.actor, message);
+    }
+
+    #[tracing::instrument(name = "rename player", skip_all)]
+    async fn rename_player(&mut self, request: PlayerRenameRequest) {
+        let (renamed, message) = match self
+            .db
+            .rename_player(request.old.as_str(), request.new.as_str())
+            .await
+        {
+            Ok(Rename::Renamed) => {
+                tracing::info!(
+                    target: GAME_LOG_TARGET,
+                    player = request.old.as_str(),
+                    new = request.new.as_str(),
+                    "rename"
+                );
+                (true, format!("Renamed {} to {}.", request.old, request.new))
+            }
+            Ok(Rename::NotFound) => (false, format!("Player '{}' not found.", request.old)),
+            Ok(Rename::Taken) => (false, format!("The name {} is taken.", request.new)),
+            Err(e) => {
+                tracing::error!("failed to rename player: {}", e);
+                (false, format!("Failed to rename {}: {}", request.old, e))
+            }
+        };
+
+        // The renamed player's connection still knows them by their old name
+        if let Some(player) = self
+            .game_world
+            .finish_player_rename(&request, renamed, message)
+        {
+            let notice = format!(
+                "|Red1|You have been renamed to {}. Please log in again.|-|",
+                request.new
+            );
+            self.boot_players(notice, vec![player]).await;
+        }
+    }
+
+    #[tracing::instrument(name = "update profile", skip_all)]
+    fn update_profile(&mut self) {
+        match self.profile_ticks {
+            Some(ticks) if ticks > 1 => self.profile_ticks = Some(ticks - 1),
+            Some(_) => {
+                self.profile_ticks = None;
+
+                let message = match finish_capture(Path::new(PROFILE_DIRECTORY)) {
+                    Ok((path, spans)) => {
+                        tracing::info!("wrote profile with {} spans to {:?}", spans, path);
+                        format!(
+                            "Profile complete: wrote {} spans to {}.",
+                            spans,
+                            path.display()
+                        )
+                    }
+                    Err(e) => {
+                        tracing::error!("failed to write profile: {}", e);
+                        format!("Failed to write profile: {}", e)
+                    }
+                };
+
+                self.game_world.finish_profile(message);
+            }
+            None => {
+                if let Some(request) = self.game_world.profile_request() {
+                    if start_capture() {
+                        tracing::info!("profiling {} ticks", request.ticks);
+                        self.profile_ticks = Some(request.ticks);
+                    } else {
+                        self.game_world
+                            .finish_profile("A profile is already being captured.".to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    #[tracing::instrument(name = "apply config", skip_all)]
+    fn apply_config(&mut self, update: ConfigUpdate) {
+        let message = match update.result {
+            Ok(config) => {
+                self.game_update_ticker = interval(config.engine.tick_rate());
+                self.metrics_ticker = interval(config.engine.metrics_rate());
+                self.leaderboard_ticker = interval(config.engine.leaderboard_rate());
+                self.lint_ticker = interval(config.engine.lint_rate());
+                self.autosave_ticker = interval(config.engine.autosave_rate());
+                self.needs_ticker = interval(config.needs.growth_rate());
+                self.game_world
+                    .set_deletion_grace(config.login.deletion_grace());
+                self.game_world
+                    .set_departed_policy(config.login.relog_grace(), config.login.relog_players);
+                self.game_world.set_seasons(config.season_windows());
+                self.game_world.set_motd(config.motd);
+                self.game_world.set_art(load_art(config.art_dir.as_deref()));
+                self.game_world.set_login_banner(config.login.banner);
+                self.http_client.configure(&config.scripting);
+                self.game_world
+                    .set_slow_script_threshold(config.scripting.slow_script_threshold());
+                self.game_world
+                    .set_http_allowlist(config.scripting.http_allowlist);
+                self.game_world
+                    .set_listing_duration(config.market.listing_duration());
+                self.game_world.set_mail_expiry(config.mail.expiry());
+                self.game_world.set_max_players(config.engine.max_players);
+                self.game_world
+                    .set_allowlist(config.login.allowlist, config.login.rejection);
+                self.resume_grace = config.web.resume_grace();
+                self.game_world
+                    .set_maintenance_notice(config.login.maintenance);
+                self.game_world.set_takeover(config.login.takeover);
+                self.game_world.set_needs(config.needs.enabled);
+                self.game_world.set_region_idle(config.regions.idle());
+                self.game_world.set_snoop_policy(
+                    config.moderation.snoop_duration(),
+                    config.moderation.snoop_approval,
+                );
+                self.game_world
+                    .set_communication_retention(config.moderation.communication_retention());
+
+                tracing::info!("applied reloaded configuration");
+                "Configuration reloaded.".to_string()
+            }
+            Err(e) => {
+                tracing::error!("failed to reload configuration: {}", e);
+                format!("Failed to reload configuration: {}", e)
+            }
+        };
+
+        if let Some(requester) = update.requester {
+            self.game_world.finish_config_reload(requester, message);
+        }
+    }
+
+    // Associates a client with the player it logged in as, passing along its capabilities. If
+    // the client took over the player from another connection, that connection is closed.
+    async fn init_player(&mut self, client_id: ClientId, player: Entity) {
+        if let Some(previous) = self.clients.init_player(client_id, player) {
+            tracing::info!("{} took over the player of {}", client_id, previous);
+            // releasing a client can admit queued clients, which initializes their players
+            Box::pin(self.release_client(previous)).await;
+        }
+
+        if let Some(client) = self.clients.get(client_id) {
+            self.game_world
+                .set_client_caps(player, client.caps().clone());
+        }
+    }
+
+    // Follows up on a client's login progress, placing it in the login queue if the world is full.
+    async fn update_client(&mut self, client_id: ClientId) {
+        let (player, queued) = match self.clients.get(client_id) {
+            Some(client) => (client.player(), client.queued()),
+            None => return,
+        };
+
+        if let Some(player) = player {
+            self.init_player(client_id, player).await;
+        } else if queued {
+            if let Some(position) = self.clients.enqueue(client_id) {
+                tracing::info!("{} queued at position {}", client_id, position);
+                self.clients
+                    .get(client_id)
+                    .unwrap()
+                    .send(SendPrompt::Prompt, vec![queue_position(position)])
+                    .await;
+            }
+        }
+    }
+
+    // Removes a client whose connection is gone, taking its player out of the world.
+    async fn remove_client(&mut self, client_id: ClientId) {
+        let queued = self.clients.get(client_id).map_or(false, Client::queued);
+
+        if let Some(player) = self.clients.get(client_id).and_then(Client::player) {
+            if let Err(e) = self.game_world.despawn_player(player) {
+                tracing::error!("failed to despawn player: {}", e);
+            }
+
+            // the player's final state is written now rather than with the next tick's updates
+            self.persist_updates().await;
+        }
+
+        if let Some(client) = self.clients.get_mut(client_id) {
+            client
+                .process(ClientEvent::Disconnect, &mut self.game_world, &self.db)
+                .await;
+        }
+
+        self.clients.remove(client_id);
+        self.engine_tx
+            .send(EngineMessage::Disconnect(client_id))
+            .await
+            .ok();
+
+        if self.admit_queued().await || queued {
+            self.update_queue_positions().await;
+        }
+    }
+
+    // Closes a client whose player was taken over by another connection, leaving the player in
+    // the world.
+    async fn release_client(&mut self, client_id: ClientId) {
+        let detached = match self.clients.get_mut(client_id) {
+            Some(client) => {
+                client.release_player();
+                client.detached_until().is_some()
+            }
+            None => return,
+        };
+
+        if detached {
+            self.remove_client(client_id).await;
+        } else if let Some(client) = self.clients.get(client_id) {
+            client
+                .disconnect(vec![
+                    "|Red1|You have logged in from another connection. Disconnecting.|-|",
+                ])
+                .await;
+        }
+    }
+
+    // Removes detached clients which were not resumed in time.
+    async fn expire_detached(&mut self) {
+        for client_id in self.clients.expired(self.game_world.clock().now()) {
+            tracing::info!("{} was not resumed, disconnecting", client_id);
+            self.remove_client(client_id).await;
+        }
+    }
+
+    // Lets queued clients into the world while there is room, returning true if any were admitted.
+    #[tracing::instrument(name = "admit queued clients", skip_all)]
+    async fn admit_queued(&mut self) -> bool {
+        let mut admitted = false;
+
+        while !self.game_world.is_full() {
+            let client_id = match self.clients.dequeue() {
+                Some(client_id) => client_id,
+                None => break,
+            };
+
+            if let Some(client) = self.clients.get_mut(client_id) {
+                tracing::info!("{} admitted from queue", client_id);
+                client
+                    .process(ClientEvent::Admitted, &mut self.game_world, &self.db)
+                    .await;
+                admitted = true;
+
+                if let Some(player) = client.player() {
+                    self.init_player(client_id, player).await;
+                }
+            }
+        }
+
+        admitted
+    }
+
+    // Shows players a notice and closes their connections. Their clients report back as
+    // disconnected, which removes them from the world.
+    #[tracing::instrument(name = "boot players", skip_all)]
+    async fn boot_players(&self, notice: String, players: Vec<Entity>) {
+        for player in players {
+            if let Some(client) = self.clients.by_player(player) {
+                client.disconnect(vec![notice.as_str()]).await;
+            }
+        }
+    }
+
+    // Tells each queued client where it now stands in the queue.
+    async fn update_queue_positions(&self) {
+        for (position, client) in self.clients.queued() {
+            client
+                .send(SendPrompt::Prompt, vec![queue_position(position)])
+                .await;
+        }
+    }
+
     #[tracing::instrument(name = "process client message", skip_all, fields(client_id = message.client_id().id()))]
     async fn process(&mut self, message: ClientMessage) {
         match message {
@@ -315,28 +1108,28 @@ impl Engine {
             }
             ClientMessage::Disconnect(client_id) => {
                 let _timer = StatsTimer::new("engine-process-disconnect");
-                tracing::info!("{} disconnected", client_id);
 
-                if let Some(player) = self.clients.get(client_id).and_then(Client::player) {
-                    if let Err(e) = self.game_world.despawn_player(player) {
-                        tracing::error!("failed to despawn player: {}", e);
-                    }
-                }
+                // hold the player in the world for a while in case the client comes back
+                let resume_grace = self.resume_grace;
+                match self.clients.get_mut(client_id) {
+                    Some(client) if !resume_grace.is_zero() && client.resumable() => {
+                        tracing::info!("{} detached", client_id);
+                        client.detach(self.game_world.clock().now() + resume_grace);
 
-                if let Some(client) = self.clients.get_mut(client_id) {
-                    client
-                        .process(ClientEvent::Disconnect, &mut self.game_world, &self.db)
-                        .await;
+                        // the player stays in the world, but is saved in case they never return
+                        if let Some(player) = client.player() {
+                            self.game_world.save_player(player);
+                            self.persist_updates().await;
+                        }
+                    }
+                    _ => {
+                        tracing::info!("{} disconnected", client_id);
+                        self.remove_client(client_id).await;
+                    }
                 }
-
-                self.clients.remove(client_id);
-                self.engine_tx
-                    .send(EngineMessage::Disconnect(client_id))
-                    .await
-                    .ok();
             }
             ClientMessage::Input(client_id, input) => {
-                let _timer = StatsTimer::new("engine-process-input");
+                let _timer = StatsTimer::sampled("engine-process-input");
 
                 if let Some(client) = self.clients.get_mut(client_id) {
                     if client.expecting_sensitive_input() {
@@ -353,15 +1146,13 @@ impl Engine {
                         )
                         .await;
 
-                    if let Some(player) = client.player() {
-                        self.clients.init_player(client_id, player);
-                    }
+                    self.update_client(client_id).await;
                 } else {
                     tracing::error!("received input from unknown client");
                 }
             }
             ClientMessage::Ready(client_id) => {
-                let _timer = StatsTimer::new("engine-process-ready");
+                let _timer = StatsTimer::sampled("engine-process-ready");
 
                 tracing::info!("{} ready", client_id);
 
@@ -374,6 +1165,23 @@ impl Engine {
                     tracing::error!("received message from unknown client: {:?}", message);
                 }
             }
+            ClientMessage::ReadyAs(client_id, credentials) => {
+                let _timer = StatsTimer::sampled("engine-process-ready");
+
+                tracing::info!("{} ready as {}", client_id, credentials.player);
+
+                if let Some(client) = self.clients.get_mut(client_id) {
+                    client
+                        .process(
+                            ClientEvent::ReadyAs(credentials),
+                            &mut self.game_world,
+                            &self.db,
+                        )
+                        .await;
+                } else {
+                    tracing::error!("received ready from unknown client");
+                }
+            }
             ClientMessage::PasswordHash(client_id, hash) => {
                 let _timer = StatsTimer::new("engine-process-password-hash");
                 if let Some(client) = self.clients.get_mut(client_id) {
@@ -399,30 +1207,99 @@ impl Engine {
                         )
                         .await;
 
+                    self.update_client(client_id).await;
+                } else {
+                    tracing::error!("received password verification from unknown client");
+                }
+            }
+            ClientMessage::Resumable(client_id, token) => {
+                if let Some(client) = self.clients.get_mut(client_id) {
+                    client.set_resume_token(token);
+                } else {
+                    tracing::error!("received resume token from unknown client");
+                }
+            }
+            ClientMessage::Resume(connection_id, token, engine_tx, reply) => {
+                let _timer = StatsTimer::new("engine-process-resume");
+
+                let now = self.game_world.clock().now();
+                let resumed = self.clients.resumable(token.as_str(), now);
+                if let Some(client_id) = resumed {
+                    tracing::info!("{} resumed by connection {}", client_id, connection_id);
+                    let client = self.clients.get_mut(client_id).unwrap();
+                    client.reattach(engine_tx);
+                    client
+                        .send(SendPrompt::Prompt, vec!["|SteelBlue3|Session resumed.|-|"])
+                        .await;
+                } else {
+                    tracing::info!("connection {} failed to resume a session", connection_id);
+                }
+
+                reply.send(resumed).ok();
+            }
+            ClientMessage::Capabilities(client_id, caps) => {
+                if let Some(client) = self.clients.get_mut(client_id) {
                     if let Some(player) = client.player() {
-                        self.clients.init_player(client_id, player);
+                        self.game_world.set_client_caps(player, caps.clone());
                     }
+
+                    client.set_caps(caps);
                 } else {
-                    tracing::error!("received password verification from unknown client");
+                    tracing::error!("received capabilities from unknown client");
                 }
             }
         }
     }
 
-    #[tracing::instrument(name = "process web message", skip_all)]
+    #[tracing::instrument(
+        name = "process web message",
+        skip_all,
+        fields(request_id = message.request_id.as_str())
+    )]
     async fn process_web(&mut self, message: WebMessage) {
         match message.request {
-            ScriptsRequest::CreateScript(JsonScript {
-                name,
-                trigger,
-                code,
-            }) => {
+            ScriptsRequest::CreateScript(
+                player,
+                JsonScript {
+                    name,
+                    trigger,
+                    code,
+                    tags,
+                    folder,
+                    enabled,
+                    ..
+                },
+            ) => {
                 let _timer = StatsTimer::new("engine-process-web-create-script");
-                match self.game_world.create_script(name, trigger, code) {
-                    Ok(e) => {
+                // A failed lookup drops the response, which the web server reports as an error
+                let author = match self.db.author_id(player.as_str()).await {
+                    Ok(author) => author,
+                    Err(db::Error::MissingData(_)) => {
+                        tracing::warn!("refused script by unknown author {}", player);
+                        message
+                            .response
+                            .send(ScriptsResponse::Error(ScriptError::UnknownAuthor))
+                            .ok();
+                        return;
+                    }
+                    Err(e) => {
+                        tracing::error!("failed to look up author {}: {}", player, e);
+                        return;
+                    }
+                };
+                match self.game_world.create_script(
+                    name,
+                    trigger,
+                    code,
+                    tags.unwrap_or_default(),
+                    folder,
+                    enabled.unwrap_or(true),
+                    author,
+                ) {
+                    Ok((e, revision)) => {
                         message
                             .response
-                            .send(ScriptsResponse::ScriptCompiled(e.map(Into::into)))
+                            .send(ScriptsResponse::ScriptCompiled(e.map(Into::into), revision))
                             .ok();
                     }
                     Err(e) => {
@@ -448,30 +1325,65 @@ impl Engine {
                     }
                 }
             }
-            ScriptsRequest::ReadAllScripts => {
+            ScriptsRequest::ReadAllScripts(query) => {
                 let _timer = StatsTimer::new("engine-process-web-read-all-scripts");
-                let scripts = self.game_world.read_all_scripts();
-                message
-                    .response
-                    .send(ScriptsResponse::ScriptList(
-                        scripts
-                            .into_iter()
-                            .map(|(script, error)| JsonScriptInfo::new(script, error))
-                            .collect_vec(),
-                    ))
-                    .ok();
+                let scripts = self
+                    .game_world
+                    .read_all_scripts()
+                    .into_iter()
+                    .map(|(script, error)| JsonScriptInfo::new(script, error))
+                    .collect_vec();
+                match query.apply(scripts) {
+                    Ok(list) => {
+                        message
+                            .response
+                            .send(ScriptsResponse::ScriptList(list))
+                            .ok();
+                    }
+                    Err(e) => {
+                        tracing::error!("failed ReadAllScripts request: {}", e);
+                        message.response.send(ScriptsResponse::Error(e)).ok();
+                    }
+                }
             }
-            ScriptsRequest::UpdateScript(JsonScript {
-                name,
-                trigger,
-                code,
-            }) => {
+            ScriptsRequest::UpdateScript(
+                player,
+                JsonScript {
+                    name,
+                    trigger,
+                    code,
+                    revision,
+                    tags,
+                    folder,
+                    enabled,
+                },
+            ) => {
                 let _timer = StatsTimer::new("engine-process-web-update-script");
-                match self.game_world.update_script(name, trigger, code) {
-                    Ok(e) => {
+                // A failed lookup drops the response, which the web server reports as an error
+                let builder = match self.db.builder_id(player.as_str()).await {
+                    Ok(builder) => builder,
+                    Err(db::Error::MissingData(_)) => {
+                        tracing::warn!("refused script change by unknown author {}", player);
                         message
                             .response
-                            .send(ScriptsResponse::ScriptCompiled(e.map(Into::into)))
+                            .send(ScriptsResponse::Error(ScriptError::UnknownAuthor))
+                            .ok();
+                        return;
+                    }
+                    Err(e) => {
+                        tracing::error!("failed to look up builder {}: {}", player, e);
+                        return;
+                    }
+                };
+                // The web layer rejects updates without a revision before they reach the engine.
+                let revision = revision.unwrap_or_default();
+                match self.game_world.update_script(
+                    name, trigger, code, tags, folder, enabled, revision, builder,
+                ) {
+                    Ok((e, revision)) => {
+                        message
+                            .response
+                            .send(ScriptsResponse::ScriptCompiled(e.map(Into::into), revision))
                             .ok();
                     }
                     Err(e) => {
@@ -480,9 +1392,33 @@ impl Engine {
                     }
                 }
             }
-            ScriptsRequest::DeleteScript(JsonScriptName { name }) => {
+            ScriptsRequest::ReadStats => {
+                let _timer = StatsTimer::new("engine-process-web-read-stats");
+                let stats = JsonScriptStatsResponse::new(
+                    self.game_world.script_stats(),
+                    self.game_world.slow_script_threshold(),
+                );
+                message.response.send(ScriptsResponse::Stats(stats)).ok();
+            }
+            ScriptsRequest::DeleteScript(player, JsonScriptName { name }) => {
                 let _timer = StatsTimer::new("engine-process-web-delete-script");
-                match self.game_world.delete_script(name) {
+                // A failed lookup drops the response, which the web server reports as an error
+                let builder = match self.db.builder_id(player.as_str()).await {
+                    Ok(builder) => builder,
+                    Err(db::Error::MissingData(_)) => {
+                        tracing::warn!("refused script change by unknown author {}", player);
+                        message
+                            .response
+                            .send(ScriptsResponse::Error(ScriptError::UnknownAuthor))
+                            .ok();
+                        return;
+                    }
+                    Err(e) => {
+                        tracing::error!("failed to look up builder {}: {}", player, e);
+                        return;
+                    }
+                };
+                match self.game_world.delete_script(name, builder) {
                     Ok(_) => {
                         message.response.send(ScriptsResponse::Done).ok();
                     }
@@ -492,10 +1428,127 @@ impl Engine {
                     }
                 }
             }
+            ScriptsRequest::ReadLint => {
+                let _timer = StatsTimer::new("engine-process-web-read-lint");
+                message
+                    .response
+                    .send(ScriptsResponse::Lint(Box::new(
+                        self.game_world.lint_report(),
+                    )))
+                    .ok();
+            }
+            ScriptsRequest::ReadEconomy(days) => {
+                let _timer = StatsTimer::new("engine-process-web-read-economy");
+                message
+                    .response
+                    .send(ScriptsResponse::Economy(Box::new(
+                        self.game_world.economy_summary(days),
+                    )))
+                    .ok();
+            }
+            ScriptsRequest::ReadCommunications(filter, page, limit) => {
+                let _timer = StatsTimer::new("engine-process-web-read-communications");
+                message
+                    .response
+                    .send(ScriptsResponse::Communications(Box::new(
+                        self.game_world.communications(&filter, page, limit),
+                    )))
+                    .ok();
+            }
+            ScriptsRequest::ReadAllowlist => {
+                let _timer = StatsTimer::new("engine-process-web-read-allowlist");
+                message
+                    .response
+                    .send(ScriptsResponse::Allowlist(self.game_world.allowlist()))
+                    .ok();
+            }
+            ScriptsRequest::AddToAllowlist(name) => {
+                let _timer = StatsTimer::new("engine-process-web-add-to-allowlist");
+                self.game_world.allow(name.as_str());
+                message
+                    .response
+                    .send(ScriptsResponse::Allowlist(self.game_world.allowlist()))
+                    .ok();
+            }
+            ScriptsRequest::RemoveFromAllowlist(name) => {
+                let _timer = StatsTimer::new("engine-process-web-remove-from-allowlist");
+                let response = if self.game_world.disallow(name.as_str()) {
+                    ScriptsResponse::Allowlist(self.game_world.allowlist())
+                } else {
+                    ScriptsResponse::NotFound
+                };
+                message.response.send(response).ok();
+            }
+            ScriptsRequest::ExportPlayer(name) => {
+                let _timer = StatsTimer::new("engine-process-web-export-player");
+                // A failed export drops the response, which the web server reports as an error
+                match self.db.export_player(name.as_str()).await {
+                    Ok(Some(export)) => {
+                        let communication = self.game_world.communication_log(name.as_str());
+                        message
+                            .response
+                            .send(ScriptsResponse::PlayerExport(Box::new(
+                                JsonPlayerExport::new(export, communication),
+                            )))
+                            .ok();
+                    }
+                    Ok(None) => {
+                        message.response.send(ScriptsResponse::NotFound).ok();
+                    }
+                    Err(e) => tracing::error!("failed to export player {}: {}", name, e),
+                }
+            }
+            ScriptsRequest::ErasePlayer(name) => {
+                let _timer = StatsTimer::new("engine-process-web-erase-player");
+                let now = self.game_world.clock().unix_now();
+                match self.db.approve_erasure(name.as_str(), now).await {
+                    Ok(true) => {
+                        tracing::info!(target: GAME_LOG_TARGET, player = name.as_str(), "erasure");
+
+                        // The player is purged with other deleted players once they are gone
+                        if let Some(player) = self.game_world.online_player(name.as_str()) {
+                            self.boot_players(ERASED_NOTICE.to_string(), vec![player])
+                                .await;
+                        }
+                        message.response.send(ScriptsResponse::Done).ok();
+                    }
+                    Ok(false) => {
+                        message.response.send(ScriptsResponse::NotFound).ok();
+                    }
+                    Err(e) => tracing::error!("failed to erase player {}: {}", name, e),
+                }
+            }
+            ScriptsRequest::WorldSnapshot => {
+                let _timer = StatsTimer::new("engine-process-web-world-snapshot");
+                let snapshot = self.game_world.snapshot();
+                message
+                    .response
+                    .send(ScriptsResponse::WorldSnapshot(Box::new(snapshot)))
+                    .ok();
+            }
         };
     }
 }
 
+// Reads the configured directory of ASCII art. Art which cannot be read is left out rather than
+// keeping the engine from starting.
+fn load_art(dir: Option<&str>) -> Art {
+    match dir {
+        Some(dir) => match Art::load(Path::new(dir)) {
+            Ok(art) => art,
+            Err(e) => {
+                tracing::error!("failed to load ASCII art from {}: {}", dir, e);
+                Art::default()
+            }
+        },
+        None => Art::default(),
+    }
+}
+
+fn queue_position(position: usize) -> String {
+    format!("|SteelBlue3|You are number {} in the queue.|-|", position)
+}
+
 pub fn name_valid(name: &str) -> bool {
     // Match names with between 2 and 32 characters which are alphanumeric and possibly include
     // the following characters: ' ', ''', '-', and '_'
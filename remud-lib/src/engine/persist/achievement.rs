@@ -0,0 +1,103 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::Persist,
+    world::types::{achievement::AchievementId, player::PlayerId},
+};
+
+#[derive(Debug)]
+pub struct Create {
+    id: AchievementId,
+    name: String,
+    description: String,
+}
+
+impl Create {
+    pub fn new(id: AchievementId, name: String, description: String) -> Box<Self> {
+        Box::new(Create {
+            id,
+            name,
+            description,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Create {
+    #[tracing::instrument(name = "create achievement", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("INSERT INTO achievements (id, name, description) VALUES (?, ?, ?)")
+            .bind(&self.id)
+            .bind(self.name.as_str())
+            .bind(self.description.as_str())
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Update {
+    id: AchievementId,
+    name: String,
+    description: String,
+}
+
+impl Update {
+    pub fn new(id: AchievementId, name: String, description: String) -> Box<Self> {
+        Box::new(Update {
+            id,
+            name,
+            description,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Update {
+    #[tracing::instrument(name = "update achievement", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE achievements SET name = ?, description = ? WHERE id = ?")
+            .bind(self.name.as_str())
+            .bind(self.description.as_str())
+            .bind(&self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Grant {
+    player: PlayerId,
+    id: AchievementId,
+}
+
+impl Grant {
+    pub fn new(player: PlayerId, id: AchievementId) -> Box<Self> {
+        Box::new(Grant { player, id })
+    }
+}
+
+#[async_trait]
+impl Persist for Grant {
+    #[tracing::instrument(name = "grant achievement", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO player_achievements (player_id, achievement_id) VALUES (?, ?)",
+        )
+        .bind(self.player)
+        .bind(&self.id)
+        .execute(pool)
+        .in_current_span()
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::engine::persist::Persist;
+
+#[derive(Debug)]
+pub struct Add {
+    name: String,
+}
+
+impl Add {
+    pub fn new(name: String) -> Box<Self> {
+        Box::new(Add { name })
+    }
+}
+
+#[async_trait]
+impl Persist for Add {
+    #[tracing::instrument(name = "add to allowlist", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("INSERT OR IGNORE INTO allowlist (name) VALUES (?)")
+            .bind(self.name.to_lowercase())
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Remove {
+    name: String,
+}
+
+impl Remove {
+    pub fn new(name: String) -> Box<Self> {
+        Box::new(Remove { name })
+    }
+}
+
+#[async_trait]
+impl Persist for Remove {
+    #[tracing::instrument(name = "remove from allowlist", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM allowlist WHERE name = ?")
+            .bind(self.name.to_lowercase())
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
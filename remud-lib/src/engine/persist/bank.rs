@@ -0,0 +1,109 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::{Persist, Write},
+    world::types::{object::ObjectId, player::PlayerId},
+};
+
+#[derive(Debug)]
+pub struct Account {
+    player_id: PlayerId,
+    coins: i64,
+    balance: i64,
+}
+
+impl Account {
+    pub fn new(player_id: PlayerId, coins: i64, balance: i64) -> Box<Self> {
+        Box::new(Account {
+            player_id,
+            coins,
+            balance,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Account {
+    #[tracing::instrument(name = "update bank account", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE players SET coins = ?, bank_balance = ? WHERE id = ?")
+            .bind(self.coins)
+            .bind(self.balance)
+            .bind(self.player_id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+
+    fn write(&self) -> Option<Write> {
+        Some(Write::new(
+            "players.coins",
+            self.player_id,
+            (self.coins, self.balance),
+        ))
+    }
+}
+
+#[derive(Debug)]
+pub struct AddObject {
+    player_id: PlayerId,
+    object_id: ObjectId,
+}
+
+impl AddObject {
+    pub fn new(player_id: PlayerId, object_id: ObjectId) -> Box<Self> {
+        Box::new(AddObject {
+            player_id,
+            object_id,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for AddObject {
+    #[tracing::instrument(name = "add bank object", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("INSERT INTO bank_objects (player_id, object_id) VALUES (?, ?)")
+            .bind(self.player_id)
+            .bind(self.object_id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct RemoveObject {
+    player_id: PlayerId,
+    object_id: ObjectId,
+}
+
+impl RemoveObject {
+    pub fn new(player_id: PlayerId, object_id: ObjectId) -> Box<Self> {
+        Box::new(RemoveObject {
+            player_id,
+            object_id,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for RemoveObject {
+    #[tracing::instrument(name = "remove bank object", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM bank_objects WHERE player_id = ? AND object_id = ?")
+            .bind(self.player_id)
+            .bind(self.object_id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
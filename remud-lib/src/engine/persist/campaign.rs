@@ -0,0 +1,137 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::Persist,
+    world::types::campaign::{Campaign, CampaignId, CampaignStage},
+};
+
+#[derive(Debug)]
+pub struct Progress {
+    id: CampaignId,
+    stage: Option<usize>,
+}
+
+impl Progress {
+    pub fn new(id: CampaignId, stage: Option<usize>) -> Box<Self> {
+        Box::new(Progress { id, stage })
+    }
+}
+
+#[async_trait]
+impl Persist for Progress {
+    #[tracing::instrument(name = "update campaign progress", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE campaigns SET stage = ? WHERE id = ?")
+            .bind(self.stage.map(|stage| stage as i64))
+            .bind(&self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Remove {
+    id: CampaignId,
+}
+
+impl Remove {
+    pub fn new(id: CampaignId) -> Box<Self> {
+        Box::new(Remove { id })
+    }
+}
+
+#[async_trait]
+impl Persist for Remove {
+    #[tracing::instrument(name = "remove campaign", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM campaigns WHERE id = ?")
+            .bind(&self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+// Writes the whole campaign, replacing its stages and their spawns, in a single transaction.
+#[derive(Debug)]
+pub struct Update {
+    id: CampaignId,
+    name: String,
+    stage: Option<usize>,
+    stages: Vec<CampaignStage>,
+}
+
+impl Update {
+    pub fn new(id: CampaignId, campaign: &Campaign) -> Box<Self> {
+        Box::new(Update {
+            id,
+            name: campaign.name.clone(),
+            stage: campaign.stage(),
+            stages: campaign.stages.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Update {
+    #[tracing::instrument(name = "update campaign", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO campaigns (id, name, stage) VALUES (?, ?, ?) ON CONFLICT(id) DO UPDATE \
+             SET name = excluded.name, stage = excluded.stage",
+        )
+        .bind(&self.id)
+        .bind(self.name.as_str())
+        .bind(self.stage.map(|stage| stage as i64))
+        .execute(&mut tx)
+        .in_current_span()
+        .await?;
+
+        sqlx::query("DELETE FROM campaign_stages WHERE campaign_id = ?")
+            .bind(&self.id)
+            .execute(&mut tx)
+            .in_current_span()
+            .await?;
+
+        for (position, stage) in self.stages.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO campaign_stages (campaign_id, position, announcement, goal) VALUES \
+                 (?, ?, ?, ?)",
+            )
+            .bind(&self.id)
+            .bind(position as i64)
+            .bind(stage.announcement.as_str())
+            .bind(stage.goal.as_deref())
+            .execute(&mut tx)
+            .in_current_span()
+            .await?;
+
+            for spawn in stage.spawns.iter() {
+                sqlx::query(
+                    "INSERT INTO campaign_spawns (campaign_id, position, prototype_id, room_id) \
+                     VALUES (?, ?, ?, ?)",
+                )
+                .bind(&self.id)
+                .bind(position as i64)
+                .bind(spawn.prototype)
+                .bind(spawn.room)
+                .execute(&mut tx)
+                .in_current_span()
+                .await?;
+            }
+        }
+
+        tx.commit().in_current_span().await?;
+
+        Ok(())
+    }
+}
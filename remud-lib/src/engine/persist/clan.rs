@@ -0,0 +1,172 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::Persist,
+    world::types::{clan::ClanRank, player::PlayerId, room::RoomId},
+};
+
+#[derive(Debug)]
+pub struct Create {
+    name: String,
+    founder: PlayerId,
+}
+
+impl Create {
+    pub fn new(name: String, founder: PlayerId) -> Box<Self> {
+        Box::new(Create { name, founder })
+    }
+}
+
+#[async_trait]
+impl Persist for Create {
+    #[tracing::instrument(name = "create clan", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("INSERT INTO clans (name, founder_id) VALUES (?, ?)")
+            .bind(self.name.as_str())
+            .bind(self.founder)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Delete {
+    name: String,
+}
+
+impl Delete {
+    pub fn new(name: String) -> Box<Self> {
+        Box::new(Delete { name })
+    }
+}
+
+#[async_trait]
+impl Persist for Delete {
+    #[tracing::instrument(name = "delete clan", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM clans WHERE name = ?")
+            .bind(self.name.as_str())
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Hall {
+    name: String,
+    hall: Option<RoomId>,
+}
+
+impl Hall {
+    pub fn new(name: String, hall: Option<RoomId>) -> Box<Self> {
+        Box::new(Hall { name, hall })
+    }
+}
+
+#[async_trait]
+impl Persist for Hall {
+    #[tracing::instrument(name = "update clan hall", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE clans SET hall_id = ? WHERE name = ?")
+            .bind(self.hall)
+            .bind(self.name.as_str())
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct AddMember {
+    name: String,
+    player_id: PlayerId,
+    rank: ClanRank,
+}
+
+impl AddMember {
+    pub fn new(name: String, player_id: PlayerId, rank: ClanRank) -> Box<Self> {
+        Box::new(AddMember {
+            name,
+            player_id,
+            rank,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for AddMember {
+    #[tracing::instrument(name = "add clan member", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("INSERT INTO clan_members (clan, player_id, rank) VALUES (?, ?, ?)")
+            .bind(self.name.as_str())
+            .bind(self.player_id)
+            .bind(self.rank.as_str())
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct UpdateMember {
+    player_id: PlayerId,
+    rank: ClanRank,
+}
+
+impl UpdateMember {
+    pub fn new(player_id: PlayerId, rank: ClanRank) -> Box<Self> {
+        Box::new(UpdateMember { player_id, rank })
+    }
+}
+
+#[async_trait]
+impl Persist for UpdateMember {
+    #[tracing::instrument(name = "update clan member", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE clan_members SET rank = ? WHERE player_id = ?")
+            .bind(self.rank.as_str())
+            .bind(self.player_id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct RemoveMember {
+    player_id: PlayerId,
+}
+
+impl RemoveMember {
+    pub fn new(player_id: PlayerId) -> Box<Self> {
+        Box::new(RemoveMember { player_id })
+    }
+}
+
+#[async_trait]
+impl Persist for RemoveMember {
+    #[tracing::instrument(name = "remove clan member", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM clan_members WHERE player_id = ?")
+            .bind(self.player_id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
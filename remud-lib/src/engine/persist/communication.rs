@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::Persist,
+    world::types::communication::{Communication, CommunicationId},
+};
+
+#[derive(Debug)]
+pub struct Record {
+    id: CommunicationId,
+    communication: Communication,
+}
+
+impl Record {
+    pub fn new(id: CommunicationId, communication: Communication) -> Box<Self> {
+        Box::new(Record { id, communication })
+    }
+}
+
+#[async_trait]
+impl Persist for Record {
+    #[tracing::instrument(name = "record communication", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT INTO communications (id, channel, player_id, name, room_id, target, text, \
+             said_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(self.id)
+        .bind(self.communication.channel.as_str())
+        .bind(self.communication.player)
+        .bind(self.communication.name.as_str())
+        .bind(self.communication.room)
+        .bind(self.communication.target.as_deref())
+        .bind(self.communication.text.as_str())
+        .bind(self.communication.said_at)
+        .execute(pool)
+        .in_current_span()
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Prune {
+    before: i64,
+}
+
+impl Prune {
+    pub fn new(before: i64) -> Box<Self> {
+        Box::new(Prune { before })
+    }
+}
+
+#[async_trait]
+impl Persist for Prune {
+    #[tracing::instrument(name = "prune communications", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM communications WHERE said_at < ?")
+            .bind(self.before)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::engine::persist::Persist;
+
+#[derive(Debug)]
+pub struct Maintenance {
+    enabled: bool,
+}
+
+impl Maintenance {
+    pub fn new(enabled: bool) -> Box<Self> {
+        Box::new(Maintenance { enabled })
+    }
+}
+
+#[async_trait]
+impl Persist for Maintenance {
+    #[tracing::instrument(name = "update maintenance mode", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"INSERT INTO config (key, value) VALUES ("maintenance", ?)
+                ON CONFLICT(key) DO UPDATE SET value = excluded.value"#,
+        )
+        .bind(if self.enabled { "on" } else { "off" })
+        .execute(pool)
+        .in_current_span()
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::Persist,
+    world::types::economy::{CoinSource, Flow},
+};
+
+#[derive(Debug)]
+pub struct Update {
+    day: i64,
+    source: CoinSource,
+    flow: Flow,
+}
+
+impl Update {
+    pub fn new(day: i64, source: CoinSource, flow: Flow) -> Box<Self> {
+        Box::new(Update { day, source, flow })
+    }
+}
+
+#[async_trait]
+impl Persist for Update {
+    #[tracing::instrument(name = "update economy", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO economy_days (day, source, created, destroyed) VALUES (?, ?, \
+             ?, ?)",
+        )
+        .bind(self.day)
+        .bind(self.source.as_str())
+        .bind(self.flow.created)
+        .bind(self.flow.destroyed)
+        .execute(pool)
+        .in_current_span()
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::{Persist, Write},
+    world::types::{faction::FactionId, player::PlayerId},
+};
+
+#[derive(Debug)]
+pub struct Create {
+    id: FactionId,
+    name: String,
+}
+
+impl Create {
+    pub fn new(id: FactionId, name: String) -> Box<Self> {
+        Box::new(Create { id, name })
+    }
+}
+
+#[async_trait]
+impl Persist for Create {
+    #[tracing::instrument(name = "create faction", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("INSERT INTO factions (id, name) VALUES (?, ?)")
+            .bind(&self.id)
+            .bind(self.name.as_str())
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Update {
+    id: FactionId,
+    name: String,
+}
+
+impl Update {
+    pub fn new(id: FactionId, name: String) -> Box<Self> {
+        Box::new(Update { id, name })
+    }
+}
+
+#[async_trait]
+impl Persist for Update {
+    #[tracing::instrument(name = "update faction", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE factions SET name = ? WHERE id = ?")
+            .bind(self.name.as_str())
+            .bind(&self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Reputation {
+    player: PlayerId,
+    id: FactionId,
+    reputation: i64,
+}
+
+impl Reputation {
+    pub fn new(player: PlayerId, id: FactionId, reputation: i64) -> Box<Self> {
+        Box::new(Reputation {
+            player,
+            id,
+            reputation,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Reputation {
+    #[tracing::instrument(name = "update reputation", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"INSERT INTO player_reputation (player_id, faction_id, reputation) VALUES (?, ?, ?)
+                ON CONFLICT (player_id, faction_id) DO UPDATE SET reputation = excluded.reputation"#,
+        )
+        .bind(self.player)
+        .bind(&self.id)
+        .bind(self.reputation)
+        .execute(pool)
+        .in_current_span()
+        .await?;
+
+        Ok(())
+    }
+
+    fn write(&self) -> Option<Write> {
+        Some(Write::new(
+            "player_reputation.reputation",
+            format!("{}:{}", self.player, self.id),
+            self.reputation,
+        ))
+    }
+}
@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{engine::persist::Persist, world::types::object::FieldValue};
+
+#[derive(Debug)]
+pub struct Update {
+    key: String,
+    value: Option<FieldValue>,
+}
+
+impl Update {
+    pub fn new(key: String, value: Option<FieldValue>) -> Box<Self> {
+        Box::new(Update { key, value })
+    }
+}
+
+#[async_trait]
+impl Persist for Update {
+    #[tracing::instrument(name = "update global", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        if let Some(value) = &self.value {
+            sqlx::query("INSERT OR REPLACE INTO globals (key, kind, value) VALUES (?, ?, ?)")
+                .bind(self.key.as_str())
+                .bind(value.kind())
+                .bind(value.stored_value())
+                .execute(pool)
+                .in_current_span()
+                .await?;
+        } else {
+            sqlx::query("DELETE FROM globals WHERE key = ?")
+                .bind(self.key.as_str())
+                .execute(pool)
+                .in_current_span()
+                .await?;
+        }
+
+        Ok(())
+    }
+}
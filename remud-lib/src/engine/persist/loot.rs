@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::Persist,
+    world::types::loot::{LootEntry, LootTable, LootTableId},
+};
+
+#[derive(Debug)]
+pub struct Remove {
+    id: LootTableId,
+}
+
+impl Remove {
+    pub fn new(id: LootTableId) -> Box<Self> {
+        Box::new(Remove { id })
+    }
+}
+
+#[async_trait]
+impl Persist for Remove {
+    #[tracing::instrument(name = "remove loot table", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM loot_tables WHERE id = ?")
+            .bind(&self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+// Writes the whole table, replacing its entries, in a single transaction.
+#[derive(Debug)]
+pub struct Update {
+    id: LootTableId,
+    rolls: u32,
+    entries: Vec<LootEntry>,
+}
+
+impl Update {
+    pub fn new(id: LootTableId, table: &LootTable) -> Box<Self> {
+        Box::new(Update {
+            id,
+            rolls: table.rolls,
+            entries: table.entries.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Update {
+    #[tracing::instrument(name = "update loot table", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO loot_tables (id, rolls) VALUES (?, ?) ON CONFLICT(id) DO UPDATE SET \
+             rolls = excluded.rolls",
+        )
+        .bind(&self.id)
+        .bind(self.rolls)
+        .execute(&mut tx)
+        .in_current_span()
+        .await?;
+
+        sqlx::query("DELETE FROM loot_table_entries WHERE table_id = ?")
+            .bind(&self.id)
+            .execute(&mut tx)
+            .in_current_span()
+            .await?;
+
+        for (position, entry) in self.entries.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO loot_table_entries (table_id, position, prototype_id, weight, \
+                 min_quantity, max_quantity, rarity) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&self.id)
+            .bind(position as i64)
+            .bind(entry.prototype)
+            .bind(entry.weight)
+            .bind(entry.min)
+            .bind(entry.max)
+            .bind(entry.rarity.as_str())
+            .execute(&mut tx)
+            .in_current_span()
+            .await?;
+        }
+
+        tx.commit().in_current_span().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::Persist,
+    world::types::{mail::MailId, object::ObjectId, player::PlayerId},
+};
+
+#[derive(Debug)]
+pub struct Create {
+    id: MailId,
+    sender: PlayerId,
+    recipient: PlayerId,
+    body: String,
+    coins: i64,
+    object: Option<ObjectId>,
+    sent_at: i64,
+    expires_at: i64,
+}
+
+impl Create {
+    pub fn new(
+        id: MailId,
+        sender: PlayerId,
+        recipient: PlayerId,
+        body: String,
+        coins: i64,
+        object: Option<ObjectId>,
+        sent_at: i64,
+        expires_at: i64,
+    ) -> Box<Self> {
+        Box::new(Create {
+            id,
+            sender,
+            recipient,
+            body,
+            coins,
+            object,
+            sent_at,
+            expires_at,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Create {
+    #[tracing::instrument(name = "create mail", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT INTO mail (id, sender_id, recipient_id, body, coins, object_id, sent_at, \
+             expires_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(self.id)
+        .bind(self.sender)
+        .bind(self.recipient)
+        .bind(self.body.as_str())
+        .bind(self.coins)
+        .bind(self.object)
+        .bind(self.sent_at)
+        .bind(self.expires_at)
+        .execute(pool)
+        .in_current_span()
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Claim {
+    id: MailId,
+}
+
+impl Claim {
+    pub fn new(id: MailId) -> Box<Self> {
+        Box::new(Claim { id })
+    }
+}
+
+#[async_trait]
+impl Persist for Claim {
+    #[tracing::instrument(name = "claim mail", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE mail SET coins = 0, object_id = NULL WHERE id = ?")
+            .bind(self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Read {
+    id: MailId,
+}
+
+impl Read {
+    pub fn new(id: MailId) -> Box<Self> {
+        Box::new(Read { id })
+    }
+}
+
+#[async_trait]
+impl Persist for Read {
+    #[tracing::instrument(name = "read mail", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE mail SET read = 1 WHERE id = ?")
+            .bind(self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Remove {
+    id: MailId,
+}
+
+impl Remove {
+    pub fn new(id: MailId) -> Box<Self> {
+        Box::new(Remove { id })
+    }
+}
+
+#[async_trait]
+impl Persist for Remove {
+    #[tracing::instrument(name = "remove mail", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM mail WHERE id = ?")
+            .bind(self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
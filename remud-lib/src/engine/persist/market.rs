@@ -0,0 +1,81 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::Persist,
+    world::types::{market::ListingId, object::ObjectId, player::PlayerId},
+};
+
+#[derive(Debug)]
+pub struct Create {
+    id: ListingId,
+    seller: PlayerId,
+    object: ObjectId,
+    price: i64,
+    expires_at: i64,
+}
+
+impl Create {
+    pub fn new(
+        id: ListingId,
+        seller: PlayerId,
+        object: ObjectId,
+        price: i64,
+        expires_at: i64,
+    ) -> Box<Self> {
+        Box::new(Create {
+            id,
+            seller,
+            object,
+            price,
+            expires_at,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Create {
+    #[tracing::instrument(name = "create market listing", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT INTO market_listings (id, seller_id, object_id, price, expires_at) VALUES (?, \
+             ?, ?, ?, ?)",
+        )
+        .bind(self.id)
+        .bind(self.seller)
+        .bind(self.object)
+        .bind(self.price)
+        .bind(self.expires_at)
+        .execute(pool)
+        .in_current_span()
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Remove {
+    id: ListingId,
+}
+
+impl Remove {
+    pub fn new(id: ListingId) -> Box<Self> {
+        Box::new(Remove { id })
+    }
+}
+
+#[async_trait]
+impl Persist for Remove {
+    #[tracing::instrument(name = "remove market listing", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM market_listings WHERE id = ?")
+            .bind(self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
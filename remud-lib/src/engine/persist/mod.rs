@@ -1,17 +1,48 @@
+pub mod achievement;
+pub mod allowlist;
+pub mod bank;
+pub mod campaign;
+pub mod clan;
+pub mod communication;
+pub mod config;
+pub mod economy;
+pub mod faction;
+pub mod global;
+pub mod loot;
+pub mod mail;
+pub mod market;
+pub mod news;
 pub mod object;
 pub mod player;
+pub mod poll;
 pub mod prototype;
+pub mod region;
+pub mod report;
+pub mod retry;
 pub mod room;
 pub mod script;
+pub mod season;
+pub mod shop;
+pub mod spawn;
+pub mod tutorial;
 
-use std::mem;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fmt::{self, Display},
+    hash::{Hash, Hasher},
+    mem,
+};
 
 use async_trait::async_trait;
+use bevy_ecs::prelude::*;
 use sqlx::SqlitePool;
 
 use crate::{
     ecs::{Ecs, Plugin},
-    world::types::object::PrototypeId,
+    world::{
+        action::Action,
+        types::{object::PrototypeId, room::RoomId},
+    },
 };
 
 pub type DynPersist = Box<dyn Persist + Send + Sync>;
@@ -29,10 +60,36 @@ impl Plugin for PersistPlugin {
 pub struct Updates {
     updates: Vec<DynPersist>,
     reloads: Vec<PrototypeId>,
+    vault_loads: Vec<(Entity, Action)>,
+    room_loads: Vec<(RoomLoad, Action)>,
+    // index into updates of the pending write to each value
+    pending: HashMap<Target, usize>,
+    // the last value successfully written to each value, as a hash
+    written: HashMap<Target, u64>,
+    // rows which left the world since updates were last taken, as a table and row
+    evicted: Vec<(&'static str, String)>,
 }
 
 impl Updates {
+    /// Queues an update. Writes of a value which was already written are dropped, and several
+    /// writes to the same value in a tick are coalesced into the last, which takes the place of
+    /// the earlier ones in the order updates are made.
     pub fn persist(&mut self, update: DynPersist) {
+        if let Some(Write { target, value }) = update.write() {
+            if let Some(index) = self.pending.remove(&target) {
+                self.updates.remove(index);
+                for pending in self.pending.values_mut() {
+                    if *pending > index {
+                        *pending -= 1;
+                    }
+                }
+            } else if self.written.get(&target) == Some(&value) {
+                return;
+            }
+
+            self.pending.insert(target, self.updates.len());
+        }
+
         self.updates.push(update);
     }
 
@@ -40,25 +97,142 @@ impl Updates {
         self.reloads.push(prototype);
     }
 
+    /// Requests that a player's vault be loaded, after which the action needing it is run again.
+    pub fn load_vault(&mut self, player: Entity, action: Action) {
+        self.vault_loads.push((player, action));
+    }
+
+    /// Requests that the regions a room is in be loaded, after which the action needing the room
+    /// is run again.
+    pub fn load_room(&mut self, room: RoomId, action: Action) {
+        self.room_loads.push((RoomLoad::Room(room), action));
+    }
+
+    /// Requests that a region be loaded, after which the action needing it is run again.
+    pub fn load_region(&mut self, region: String, action: Action) {
+        self.room_loads.push((RoomLoad::Region(region), action));
+    }
+
+    /// Takes the queued updates to be enacted. Values they write are unknown until they succeed,
+    /// so later writes of them are not skipped in the meantime.
     pub fn take_updates(&mut self) -> Vec<DynPersist> {
         let mut updates = Vec::new();
         mem::swap(&mut self.updates, &mut updates);
+
+        self.pending.clear();
+        for write in updates.iter().filter_map(|update| update.write()) {
+            self.written.remove(&write.target);
+        }
+
         updates
     }
 
+    /// Records the values written by updates which succeeded, so rewrites of them can be skipped.
+    /// Rows evicted since the updates were taken are forgotten after, as they may change in the
+    /// database while out of the world.
+    pub fn record_writes(&mut self, writes: Vec<Write>) {
+        for Write { target, value } in writes {
+            self.written.insert(target, value);
+        }
+
+        let evicted = mem::take(&mut self.evicted);
+        self.written.retain(|target, _| {
+            !evicted
+                .iter()
+                .any(|(table, row)| target.is_row(table, row.as_str()))
+        });
+    }
+
+    /// Forgets the values written to a row, and rows keyed beneath it, once it leaves the world
+    /// or the database.
+    pub fn evict(&mut self, table: &'static str, row: impl Display) {
+        self.evicted.push((table, row.to_string()));
+    }
+
     pub fn take_reloads(&mut self) -> Vec<PrototypeId> {
         let mut reloads = Vec::new();
         mem::swap(&mut self.reloads, &mut reloads);
         reloads
     }
+
+    pub fn take_vault_loads(&mut self) -> Vec<(Entity, Action)> {
+        let mut vault_loads = Vec::new();
+        mem::swap(&mut self.vault_loads, &mut vault_loads);
+        vault_loads
+    }
+
+    pub fn take_room_loads(&mut self) -> Vec<(RoomLoad, Action)> {
+        let mut room_loads = Vec::new();
+        mem::swap(&mut self.room_loads, &mut room_loads);
+        room_loads
+    }
+}
+
+/// Rooms out of the world which an action needs loaded, while regions are loaded lazily.
+#[derive(Debug)]
+pub enum RoomLoad {
+    Room(RoomId),
+    Region(String),
 }
 
 #[async_trait]
-pub trait Persist {
+pub trait Persist: fmt::Debug {
     async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()>;
+
+    /// The value this update overwrites, for updates which only set a single value that nothing
+    /// else writes.
+    fn write(&self) -> Option<Write> {
+        None
+    }
+}
+
+// A column of a row in the database.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Target {
+    column: &'static str,
+    row: String,
+}
+
+impl Target {
+    // Whether this is a column of the row, or of a row keyed beneath it such as "1:2" under "1".
+    fn is_row(&self, table: &str, row: &str) -> bool {
+        let in_table = self
+            .column
+            .strip_prefix(table)
+            .map_or(false, |column| column.starts_with('.'));
+        let in_row = self
+            .row
+            .strip_prefix(row)
+            .map_or(false, |rest| rest.is_empty() || rest.starts_with(':'));
+
+        in_table && in_row
+    }
+}
+
+/// A value set by an update, used to skip and coalesce redundant writes.
+#[derive(Debug)]
+pub struct Write {
+    target: Target,
+    value: u64,
+}
+
+impl Write {
+    pub fn new(column: &'static str, row: impl Display, value: impl Hash) -> Self {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+
+        Write {
+            target: Target {
+                column,
+                row: row.to_string(),
+            },
+            value: hasher.finish(),
+        }
+    }
 }
 
 // An list of Persist operations that must be completed in order.
+#[derive(Debug)]
 pub struct UpdateGroup {
     list: Vec<DynPersist>,
 }
@@ -84,3 +258,72 @@ impl Persist for UpdateGroup {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::{bank, player, Updates};
+    use crate::world::types::{player::PlayerId, room::RoomId};
+
+    #[test]
+    fn test_coalesce_writes() {
+        let player = PlayerId::try_from(1).unwrap();
+        let mut updates = Updates::default();
+
+        updates.persist(bank::Account::new(player, 1, 0));
+        updates.persist(player::Room::new(player, RoomId::try_from(1).unwrap()));
+        updates.persist(bank::Account::new(player, 2, 0));
+        updates.persist(bank::Account::new(player, 3, 0));
+        let taken = updates.take_updates();
+        assert_eq!(taken.len(), 2);
+
+        // Nothing is skipped until the writes succeed
+        updates.persist(bank::Account::new(player, 3, 0));
+        assert_eq!(updates.take_updates().len(), 1);
+
+        updates.record_writes(taken.iter().filter_map(|update| update.write()).collect());
+        updates.persist(bank::Account::new(player, 3, 0));
+        assert!(updates.take_updates().is_empty());
+
+        updates.persist(bank::Account::new(player, 4, 0));
+        updates.persist(bank::Account::new(player, 3, 0));
+        assert_eq!(updates.take_updates().len(), 1);
+    }
+
+    #[test]
+    fn test_coalesce_keeps_order() {
+        let player = PlayerId::try_from(1).unwrap();
+        let mut updates = Updates::default();
+
+        // The later write of the room is made after the account, so it must land after it
+        updates.persist(player::Room::new(player, RoomId::try_from(1).unwrap()));
+        updates.persist(bank::Account::new(player, 1, 0));
+        updates.persist(player::Room::new(player, RoomId::try_from(2).unwrap()));
+
+        let columns = updates
+            .take_updates()
+            .iter()
+            .map(|update| update.write().unwrap().target.column)
+            .collect::<Vec<_>>();
+        assert_eq!(columns, vec!["players.coins", "players.room"]);
+    }
+
+    #[test]
+    fn test_evict_forgets_writes() {
+        let player = PlayerId::try_from(1).unwrap();
+        let other = PlayerId::try_from(12).unwrap();
+        let mut updates = Updates::default();
+
+        updates.persist(bank::Account::new(player, 1, 0));
+        updates.persist(bank::Account::new(other, 1, 0));
+        let taken = updates.take_updates();
+
+        updates.evict("players", player);
+        updates.record_writes(taken.iter().filter_map(|update| update.write()).collect());
+
+        updates.persist(bank::Account::new(player, 1, 0));
+        updates.persist(bank::Account::new(other, 1, 0));
+        assert_eq!(updates.take_updates().len(), 1);
+    }
+}
@@ -0,0 +1,100 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::{Persist, Write},
+    world::types::{news::NewsId, player::PlayerId},
+};
+
+#[derive(Debug)]
+pub struct Create {
+    id: NewsId,
+    author: String,
+    body: String,
+    posted_at: i64,
+}
+
+impl Create {
+    pub fn new(id: NewsId, author: String, body: String, posted_at: i64) -> Box<Self> {
+        Box::new(Create {
+            id,
+            author,
+            body,
+            posted_at,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Create {
+    #[tracing::instrument(name = "create news", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("INSERT INTO news (id, author, body, posted_at) VALUES (?, ?, ?, ?)")
+            .bind(self.id)
+            .bind(self.author.as_str())
+            .bind(self.body.as_str())
+            .bind(self.posted_at)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Read {
+    player: PlayerId,
+    id: NewsId,
+}
+
+impl Read {
+    pub fn new(player: PlayerId, id: NewsId) -> Box<Self> {
+        Box::new(Read { player, id })
+    }
+}
+
+#[async_trait]
+impl Persist for Read {
+    #[tracing::instrument(name = "read news", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE players SET news_read = ? WHERE id = ?")
+            .bind(self.id)
+            .bind(self.player)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+
+    fn write(&self) -> Option<Write> {
+        Some(Write::new("players.news_read", self.player, self.id))
+    }
+}
+
+#[derive(Debug)]
+pub struct Remove {
+    id: NewsId,
+}
+
+impl Remove {
+    pub fn new(id: NewsId) -> Box<Self> {
+        Box::new(Remove { id })
+    }
+}
+
+#[async_trait]
+impl Persist for Remove {
+    #[tracing::instrument(name = "remove news", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM news WHERE id = ?")
+            .bind(self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
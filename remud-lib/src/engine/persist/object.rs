@@ -4,7 +4,7 @@ use tracing::Instrument;
 
 use crate::{
     engine::persist::Persist,
-    world::types::object::{self, InheritableFields, ObjectId, PrototypeId},
+    world::types::object::{self, FieldValue, InheritableFields, ObjectId, PrototypeId},
 };
 
 #[derive(Debug)]
@@ -64,6 +64,48 @@ impl Persist for Description {
     }
 }
 
+#[derive(Debug)]
+pub struct Field {
+    id: ObjectId,
+    key: String,
+    value: Option<FieldValue>,
+}
+
+impl Field {
+    pub fn new(id: ObjectId, key: String, value: Option<FieldValue>) -> Box<Self> {
+        Box::new(Field { id, key, value })
+    }
+}
+
+#[async_trait]
+impl Persist for Field {
+    #[tracing::instrument(name = "update object field", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        if let Some(value) = &self.value {
+            sqlx::query(
+                "INSERT OR REPLACE INTO object_fields (object_id, key, kind, value) VALUES (?, ?, \
+                 ?, ?)",
+            )
+            .bind(self.id)
+            .bind(self.key.as_str())
+            .bind(value.kind())
+            .bind(value.stored_value())
+            .execute(pool)
+            .in_current_span()
+            .await?;
+        } else {
+            sqlx::query("DELETE FROM object_fields WHERE object_id = ? AND key = ?")
+                .bind(self.id)
+                .bind(self.key.as_str())
+                .execute(pool)
+                .in_current_span()
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct Flags {
     id: ObjectId,
@@ -109,6 +151,13 @@ impl Persist for Inherit {
     async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
         for field in self.fields.iter() {
             match field {
+                InheritableFields::Fields => {
+                    sqlx::query("DELETE FROM object_fields WHERE object_id = ?")
+                        .bind(self.id)
+                        .execute(pool)
+                        .in_current_span()
+                        .await?;
+                }
                 InheritableFields::Flags => {
                     sqlx::query("UPDATE objects SET flags = null WHERE id = ?")
                         .bind(self.id)
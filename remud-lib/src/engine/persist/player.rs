@@ -3,8 +3,9 @@ use sqlx::SqlitePool;
 use tracing::Instrument;
 
 use crate::{
-    engine::persist::Persist,
+    engine::persist::{Persist, Write},
     world::types::{
+        grammar,
         object::ObjectId,
         player::{self, PlayerId},
         room::RoomId,
@@ -66,6 +67,42 @@ impl Persist for Description {
 
         Ok(())
     }
+
+    fn write(&self) -> Option<Write> {
+        Some(Write::new(
+            "players.description",
+            self.id,
+            &self.description,
+        ))
+    }
+}
+
+#[derive(Debug)]
+pub struct EndSession {
+    id: PlayerId,
+}
+
+impl EndSession {
+    pub fn new(id: PlayerId) -> Box<Self> {
+        Box::new(EndSession { id })
+    }
+}
+
+#[async_trait]
+impl Persist for EndSession {
+    #[tracing::instrument(name = "end player session", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query(
+            "UPDATE sessions SET disconnected_at = CURRENT_TIMESTAMP WHERE player_id = ? AND \
+             disconnected_at IS NULL",
+        )
+        .bind(self.id)
+        .execute(pool)
+        .in_current_span()
+        .await?;
+
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
@@ -93,6 +130,178 @@ impl Persist for Flags {
 
         Ok(())
     }
+
+    fn write(&self) -> Option<Write> {
+        Some(Write::new("players.flags", self.id, self.flags))
+    }
+}
+
+#[derive(Debug)]
+pub struct Ignore {
+    player_id: PlayerId,
+    ignored_id: PlayerId,
+}
+
+impl Ignore {
+    pub fn new(player_id: PlayerId, ignored_id: PlayerId) -> Box<Self> {
+        Box::new(Ignore {
+            player_id,
+            ignored_id,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Ignore {
+    #[tracing::instrument(name = "add player ignore", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("INSERT OR IGNORE INTO player_ignores (player_id, ignored_id) VALUES (?, ?)")
+            .bind(self.player_id)
+            .bind(self.ignored_id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+// Writes everything the player is carrying, replacing the objects recorded for them, in a single
+// transaction.
+#[derive(Debug)]
+pub struct Needs {
+    id: PlayerId,
+    needs: player::Needs,
+}
+
+impl Needs {
+    pub fn new(id: PlayerId, needs: player::Needs) -> Box<Self> {
+        Box::new(Needs { id, needs })
+    }
+}
+
+#[async_trait]
+impl Persist for Needs {
+    #[tracing::instrument(name = "update player needs", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE players SET hunger = ?, thirst = ? WHERE id = ?")
+            .bind(self.needs.hunger)
+            .bind(self.needs.thirst)
+            .bind(self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+
+    fn write(&self) -> Option<Write> {
+        Some(Write::new("players.needs", self.id, self.needs))
+    }
+}
+
+#[derive(Debug)]
+pub struct Objects {
+    player_id: PlayerId,
+    object_ids: Vec<ObjectId>,
+}
+
+impl Objects {
+    pub fn new(player_id: PlayerId, object_ids: Vec<ObjectId>) -> Box<Self> {
+        Box::new(Objects {
+            player_id,
+            object_ids,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Objects {
+    #[tracing::instrument(name = "update player objects", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM player_objects WHERE player_id = ?")
+            .bind(self.player_id)
+            .execute(&mut tx)
+            .in_current_span()
+            .await?;
+
+        // an object recorded as carried by someone else has since changed hands
+        for object_id in self.object_ids.iter() {
+            sqlx::query(
+                "INSERT INTO player_objects (player_id, object_id) VALUES (?, ?) ON \
+                 CONFLICT(object_id) DO UPDATE SET player_id = excluded.player_id",
+            )
+            .bind(self.player_id)
+            .bind(*object_id)
+            .execute(&mut tx)
+            .in_current_span()
+            .await?;
+        }
+
+        tx.commit().in_current_span().await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Playtime {
+    id: PlayerId,
+    seconds: i64,
+}
+
+impl Playtime {
+    pub fn new(id: PlayerId, seconds: i64) -> Box<Self> {
+        Box::new(Playtime { id, seconds })
+    }
+}
+
+#[async_trait]
+impl Persist for Playtime {
+    #[tracing::instrument(name = "add player playtime", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE players SET playtime = playtime + ? WHERE id = ?")
+            .bind(self.seconds)
+            .bind(self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Pronouns {
+    id: PlayerId,
+    pronouns: grammar::Pronouns,
+}
+
+impl Pronouns {
+    pub fn new(id: PlayerId, pronouns: grammar::Pronouns) -> Box<Self> {
+        Box::new(Pronouns { id, pronouns })
+    }
+}
+
+#[async_trait]
+impl Persist for Pronouns {
+    #[tracing::instrument(name = "update player pronouns", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE players SET pronouns = ? WHERE id = ?")
+            .bind(self.pronouns.as_str())
+            .bind(self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+
+    fn write(&self) -> Option<Write> {
+        Some(Write::new("players.pronouns", self.id, self.pronouns))
+    }
 }
 
 #[derive(Debug)]
@@ -150,4 +359,90 @@ impl Persist for Room {
 
         Ok(())
     }
+
+    fn write(&self) -> Option<Write> {
+        Some(Write::new("players.room", self.player_id, self.room_id))
+    }
+}
+
+#[derive(Debug)]
+pub struct StartSession {
+    id: PlayerId,
+}
+
+impl StartSession {
+    pub fn new(id: PlayerId) -> Box<Self> {
+        Box::new(StartSession { id })
+    }
+}
+
+#[async_trait]
+impl Persist for StartSession {
+    #[tracing::instrument(name = "start player session", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("INSERT INTO sessions (player_id) VALUES (?)")
+            .bind(self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Unignore {
+    player_id: PlayerId,
+    ignored_id: PlayerId,
+}
+
+impl Unignore {
+    pub fn new(player_id: PlayerId, ignored_id: PlayerId) -> Box<Self> {
+        Box::new(Unignore {
+            player_id,
+            ignored_id,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Unignore {
+    #[tracing::instrument(name = "remove player ignore", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM player_ignores WHERE player_id = ? AND ignored_id = ?")
+            .bind(self.player_id)
+            .bind(self.ignored_id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Visit {
+    id: PlayerId,
+    room: RoomId,
+}
+
+impl Visit {
+    pub fn new(id: PlayerId, room: RoomId) -> Box<Self> {
+        Box::new(Visit { id, room })
+    }
+}
+
+#[async_trait]
+impl Persist for Visit {
+    #[tracing::instrument(name = "record player visit", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("INSERT OR IGNORE INTO player_rooms (player_id, room_id) VALUES (?, ?)")
+            .bind(self.id)
+            .bind(self.room)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
 }
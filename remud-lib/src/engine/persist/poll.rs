@@ -0,0 +1,120 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::Persist,
+    world::types::{player::PlayerId, poll::PollId},
+};
+
+#[derive(Debug)]
+pub struct Create {
+    id: PollId,
+    question: String,
+    author: String,
+    options: Vec<String>,
+    created_at: i64,
+}
+
+impl Create {
+    pub fn new(
+        id: PollId,
+        question: String,
+        author: String,
+        options: Vec<String>,
+        created_at: i64,
+    ) -> Box<Self> {
+        Box::new(Create {
+            id,
+            question,
+            author,
+            options,
+            created_at,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Create {
+    #[tracing::instrument(name = "create poll", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("INSERT INTO polls (id, question, author, created_at) VALUES (?, ?, ?, ?)")
+            .bind(self.id)
+            .bind(self.question.as_str())
+            .bind(self.author.as_str())
+            .bind(self.created_at)
+            .execute(&mut tx)
+            .in_current_span()
+            .await?;
+
+        for (position, text) in self.options.iter().enumerate() {
+            sqlx::query("INSERT INTO poll_options (poll_id, position, text) VALUES (?, ?, ?)")
+                .bind(self.id)
+                .bind(position as i64)
+                .bind(text.as_str())
+                .execute(&mut tx)
+                .in_current_span()
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Close {
+    id: PollId,
+}
+
+impl Close {
+    pub fn new(id: PollId) -> Box<Self> {
+        Box::new(Close { id })
+    }
+}
+
+#[async_trait]
+impl Persist for Close {
+    #[tracing::instrument(name = "close poll", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE polls SET closed = 1 WHERE id = ?")
+            .bind(self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Vote {
+    id: PollId,
+    player: PlayerId,
+    option: usize,
+}
+
+impl Vote {
+    pub fn new(id: PollId, player: PlayerId, option: usize) -> Box<Self> {
+        Box::new(Vote { id, player, option })
+    }
+}
+
+#[async_trait]
+impl Persist for Vote {
+    #[tracing::instrument(name = "vote in poll", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("INSERT INTO poll_votes (poll_id, player_id, position) VALUES (?, ?, ?)")
+            .bind(self.id)
+            .bind(self.player)
+            .bind(self.option as i64)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
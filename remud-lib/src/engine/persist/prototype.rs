@@ -3,10 +3,83 @@ use sqlx::SqlitePool;
 use tracing::Instrument;
 
 use crate::{
-    engine::persist::Persist,
-    world::types::object::{self, PrototypeId},
+    engine::persist::{Persist, Write},
+    world::types::object::{self, BehaviorKind, FieldValue, PrototypeId},
 };
 
+#[derive(Debug)]
+pub struct Behavior {
+    id: PrototypeId,
+    kind: BehaviorKind,
+    behavior: Option<object::Behavior>,
+}
+
+impl Behavior {
+    pub fn new(
+        id: PrototypeId,
+        kind: BehaviorKind,
+        behavior: Option<object::Behavior>,
+    ) -> Box<Self> {
+        Box::new(Behavior { id, kind, behavior })
+    }
+}
+
+#[async_trait]
+impl Persist for Behavior {
+    #[tracing::instrument(name = "update prototype behavior", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        if let Some(behavior) = &self.behavior {
+            sqlx::query(
+                "INSERT OR REPLACE INTO prototype_behaviors (prototype_id, kind, value) VALUES (?, \
+                 ?, ?)",
+            )
+            .bind(self.id)
+            .bind(self.kind.as_str())
+            .bind(behavior.stored_value())
+            .execute(pool)
+            .in_current_span()
+            .await?;
+        } else {
+            sqlx::query("DELETE FROM prototype_behaviors WHERE prototype_id = ? AND kind = ?")
+                .bind(self.id)
+                .bind(self.kind.as_str())
+                .execute(pool)
+                .in_current_span()
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Bulk {
+    id: PrototypeId,
+    bulk: object::Bulk,
+}
+
+impl Bulk {
+    pub fn new(id: PrototypeId, bulk: object::Bulk) -> Box<Self> {
+        Box::new(Bulk { id, bulk })
+    }
+}
+
+#[async_trait]
+impl Persist for Bulk {
+    #[tracing::instrument(name = "update prototype bulk", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE prototypes SET weight = ?, size = ? WHERE id = ?")
+            .bind(self.bulk.weight)
+            .bind(self.bulk.size)
+            .bind(self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct Create {
     id: PrototypeId,
@@ -80,6 +153,56 @@ impl Persist for Description {
 
         Ok(())
     }
+
+    fn write(&self) -> Option<Write> {
+        Some(Write::new(
+            "prototypes.description",
+            self.id,
+            &self.description,
+        ))
+    }
+}
+
+#[derive(Debug)]
+pub struct Field {
+    id: PrototypeId,
+    key: String,
+    value: Option<FieldValue>,
+}
+
+impl Field {
+    pub fn new(id: PrototypeId, key: String, value: Option<FieldValue>) -> Box<Self> {
+        Box::new(Field { id, key, value })
+    }
+}
+
+#[async_trait]
+impl Persist for Field {
+    #[tracing::instrument(name = "update prototype field", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        if let Some(value) = &self.value {
+            sqlx::query(
+                "INSERT OR REPLACE INTO prototype_fields (prototype_id, key, kind, value) VALUES \
+                 (?, ?, ?, ?)",
+            )
+            .bind(self.id)
+            .bind(self.key.as_str())
+            .bind(value.kind())
+            .bind(value.stored_value())
+            .execute(pool)
+            .in_current_span()
+            .await?;
+        } else {
+            sqlx::query("DELETE FROM prototype_fields WHERE prototype_id = ? AND key = ?")
+                .bind(self.id)
+                .bind(self.key.as_str())
+                .execute(pool)
+                .in_current_span()
+                .await?;
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
@@ -107,6 +230,10 @@ impl Persist for Flags {
 
         Ok(())
     }
+
+    fn write(&self) -> Option<Write> {
+        Some(Write::new("prototypes.flags", self.id, self.flags))
+    }
 }
 
 #[derive(Debug)]
@@ -135,6 +262,10 @@ impl Persist for Keywords {
 
         Ok(())
     }
+
+    fn write(&self) -> Option<Write> {
+        Some(Write::new("prototypes.keywords", self.id, &self.keywords))
+    }
 }
 
 #[derive(Debug)]
@@ -162,4 +293,8 @@ impl Persist for Name {
 
         Ok(())
     }
+
+    fn write(&self) -> Option<Write> {
+        Some(Write::new("prototypes.name", self.id, &self.name))
+    }
 }
@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{engine::persist::Persist, world::types::player::PlayerId};
+
+#[derive(Debug)]
+pub struct SetOwner {
+    region: String,
+    owner: PlayerId,
+}
+
+impl SetOwner {
+    pub fn new(region: String, owner: PlayerId) -> Box<Self> {
+        Box::new(SetOwner { region, owner })
+    }
+}
+
+#[async_trait]
+impl Persist for SetOwner {
+    #[tracing::instrument(name = "set region owner", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("INSERT OR REPLACE INTO region_owners (region, player_id) VALUES (?, ?)")
+            .bind(self.region.as_str())
+            .bind(self.owner)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct RemoveOwner {
+    region: String,
+}
+
+impl RemoveOwner {
+    pub fn new(region: String) -> Box<Self> {
+        Box::new(RemoveOwner { region })
+    }
+}
+
+#[async_trait]
+impl Persist for RemoveOwner {
+    #[tracing::instrument(name = "remove region owner", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM region_owners WHERE region = ?")
+            .bind(self.region.as_str())
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
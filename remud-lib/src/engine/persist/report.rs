@@ -0,0 +1,56 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::Persist,
+    world::types::{player::PlayerId, report::ReportId},
+};
+
+#[derive(Debug)]
+pub struct Create {
+    id: ReportId,
+    reporter: PlayerId,
+    reported: PlayerId,
+    reason: String,
+    context: Vec<String>,
+}
+
+impl Create {
+    pub fn new(
+        id: ReportId,
+        reporter: PlayerId,
+        reported: PlayerId,
+        reason: String,
+        context: Vec<String>,
+    ) -> Box<Self> {
+        Box::new(Create {
+            id,
+            reporter,
+            reported,
+            reason,
+            context,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Create {
+    #[tracing::instrument(name = "create report", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT INTO reports (id, reporter_id, reported_id, reason, context) VALUES (?, ?, ?, \
+             ?, ?)",
+        )
+        .bind(self.id)
+        .bind(self.reporter)
+        .bind(self.reported)
+        .bind(self.reason.as_str())
+        .bind(self.context.join("\n"))
+        .execute(pool)
+        .in_current_span()
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,302 @@
+use std::{
+    cmp,
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::engine::persist::DynPersist;
+
+// The delay before the first retry of a failed update, doubled for each further failure.
+const BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(60);
+
+// Immortals are told about an update once it has failed this many times.
+pub const ESCALATE_ATTEMPTS: u32 = 5;
+
+// SQLite's busy, locked, I/O error, full, and can't open result codes.
+const TRANSIENT_CODES: [i32; 5] = [5, 6, 10, 13, 14];
+
+/// Updates which failed to persist, retried strictly in the order they were made with exponential
+/// backoff. Updates made while any are waiting are held behind them, so none can land ahead of an
+/// older one. While the database is unavailable the world is degraded: nothing is written and
+/// updates are held until it returns.
+#[derive(Default)]
+pub struct Retries {
+    queue: VecDeque<Retry>,
+    degraded: bool,
+    // when the database should next be checked while degraded, and how many checks have failed
+    probe: Option<(Instant, u32)>,
+}
+
+/// What became of an update which failed.
+pub enum Failure {
+    /// It will be retried, having failed this many times.
+    Retrying(u32),
+    /// It can never succeed, so it was dropped rather than hold back the updates behind it.
+    DeadLetter(DynPersist),
+}
+
+impl Retries {
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        self.degraded
+    }
+
+    /// Queues an update which failed, to be retried after a delay, unless it can never succeed.
+    pub fn fail(&mut self, update: DynPersist, error: &anyhow::Error, now: Instant) -> Failure {
+        if is_permanent(error) {
+            return Failure::DeadLetter(update);
+        }
+
+        self.queue.push_back(Retry {
+            update,
+            attempts: 1,
+            due: now + backoff(1),
+        });
+        Failure::Retrying(1)
+    }
+
+    /// Holds an update made while degraded or while older updates wait to be retried, until
+    /// those ahead of it are saved.
+    pub fn hold(&mut self, update: DynPersist) {
+        self.queue.push_back(Retry {
+            update,
+            attempts: 0,
+            due: Instant::now(),
+        });
+    }
+
+    /// Drops queued updates which are superseded by newer writes of the same value. The newer
+    /// write lands after everything queued, so dropping the older one changes no outcome.
+    pub fn supersede(&mut self, updates: &[DynPersist]) {
+        let targets = updates
+            .iter()
+            .filter_map(|update| update.write())
+            .map(|write| write.target)
+            .collect::<Vec<_>>();
+
+        if targets.is_empty() {
+            return;
+        }
+
+        self.queue.retain(|retry| {
+            retry
+                .update
+                .write()
+                .map(|write| !targets.contains(&write.target))
+                .unwrap_or(true)
+        });
+    }
+
+    /// Takes the oldest update if it is due for another attempt. Nothing is due while degraded,
+    /// and nothing behind an update which is waiting is taken before it.
+    pub fn take_next(&mut self, now: Instant) -> Option<Retry> {
+        if self.degraded {
+            return None;
+        }
+
+        match self.queue.front() {
+            Some(retry) if retry.due <= now => self.queue.pop_front(),
+            _ => None,
+        }
+    }
+
+    /// Puts back an update which failed again ahead of those made after it, unless it can never
+    /// succeed.
+    pub fn retry(&mut self, mut retry: Retry, error: &anyhow::Error, now: Instant) -> Failure {
+        if is_permanent(error) {
+            return Failure::DeadLetter(retry.update);
+        }
+
+        retry.attempts += 1;
+        retry.due = now + backoff(retry.attempts);
+        let attempts = retry.attempts;
+        self.queue.push_front(retry);
+        Failure::Retrying(attempts)
+    }
+
+    /// Stops writing to the database until a check finds it is available again.
+    pub fn degrade(&mut self, now: Instant) {
+        self.degraded = true;
+        self.probe = Some((now + backoff(1), 1));
+    }
+
+    /// Whether the database should be checked, while degraded.
+    pub fn probe_due(&self, now: Instant) -> bool {
+        matches!(self.probe, Some((due, _)) if self.degraded && due <= now)
+    }
+
+    pub fn probe_failed(&mut self, now: Instant) {
+        if let Some((due, checks)) = self.probe.as_mut() {
+            *checks += 1;
+            *due = now + backoff(*checks);
+        }
+    }
+
+    /// Leaves degraded mode, making every held update due immediately.
+    pub fn recover(&mut self, now: Instant) {
+        self.degraded = false;
+        self.probe = None;
+        for retry in self.queue.iter_mut() {
+            retry.due = now;
+        }
+    }
+}
+
+pub struct Retry {
+    pub update: DynPersist,
+    attempts: u32,
+    due: Instant,
+}
+
+fn backoff(attempts: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+    cmp::min(BASE_DELAY.saturating_mul(factor), MAX_DELAY)
+}
+
+// Whether an update failed in a way retrying cannot fix, such as breaking a constraint or reading a
+// column which does not exist. Failures to reach the database, and the database being busy, full,
+// or failing to read or write, may pass.
+fn is_permanent(error: &anyhow::Error) -> bool {
+    match error.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::Database(error)) => !error
+            .code()
+            .and_then(|code| code.parse::<i32>().ok())
+            .map_or(false, |code| TRANSIENT_CODES.contains(&(code & 0xff))),
+        Some(
+            sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::Protocol(_)
+            | sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed
+            | sqlx::Error::WorkerCrashed,
+        ) => false,
+        Some(_) => true,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::TryFrom,
+        time::{Duration, Instant},
+    };
+
+    use super::{backoff, is_permanent, Failure, Retries};
+    use crate::{
+        engine::persist::{bank, player},
+        world::types::{player::PlayerId, room::RoomId},
+    };
+
+    fn transient() -> anyhow::Error {
+        anyhow::Error::from(sqlx::Error::PoolTimedOut)
+    }
+
+    fn permanent() -> anyhow::Error {
+        anyhow::Error::from(sqlx::Error::RowNotFound)
+    }
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(3), Duration::from_secs(4));
+        assert_eq!(backoff(40), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_retries() {
+        let player = PlayerId::try_from(1).unwrap();
+        let now = Instant::now();
+        let mut retries = Retries::default();
+
+        retries.fail(bank::Account::new(player, 1, 0), &transient(), now);
+        assert!(retries.take_next(now).is_none());
+
+        let retry = retries.take_next(now + Duration::from_secs(1)).unwrap();
+        assert!(retries.is_empty());
+
+        assert!(matches!(
+            retries.retry(retry, &transient(), now),
+            Failure::Retrying(2)
+        ));
+        assert!(retries.take_next(now + Duration::from_secs(1)).is_none());
+
+        retries.degrade(now);
+        assert!(retries.take_next(now + Duration::from_secs(10)).is_none());
+        assert!(retries.probe_due(now + Duration::from_secs(1)));
+
+        retries.recover(now);
+        assert!(retries.take_next(now).is_some());
+
+        retries.fail(bank::Account::new(player, 2, 0), &transient(), now);
+        retries.supersede(&[bank::Account::new(player, 3, 0)]);
+        assert!(retries.is_empty());
+    }
+
+    #[test]
+    fn test_retries_in_order() {
+        let player = PlayerId::try_from(1).unwrap();
+        let now = Instant::now();
+        let mut retries = Retries::default();
+
+        retries.fail(bank::Account::new(player, 1, 0), &transient(), now);
+        retries.hold(player::Room::new(player, RoomId::try_from(1).unwrap()));
+
+        // The held update is due, but waits behind the older one
+        assert!(retries.take_next(now).is_none());
+
+        let later = now + Duration::from_secs(1);
+        let retry = retries.take_next(later).unwrap();
+        assert_eq!(retry.update.write().unwrap().target.column, "players.coins");
+
+        // Failing again keeps it ahead of the held update
+        retries.retry(retry, &transient(), later);
+        assert!(retries.take_next(later).is_none());
+
+        let retry = retries.take_next(later + Duration::from_secs(2)).unwrap();
+        assert_eq!(retry.update.write().unwrap().target.column, "players.coins");
+        let retry = retries.take_next(later + Duration::from_secs(2)).unwrap();
+        assert_eq!(retry.update.write().unwrap().target.column, "players.room");
+    }
+
+    #[test]
+    fn test_retries_poison() {
+        let player = PlayerId::try_from(1).unwrap();
+        let now = Instant::now();
+        let mut retries = Retries::default();
+
+        assert!(matches!(
+            retries.fail(bank::Account::new(player, 1, 0), &permanent(), now),
+            Failure::DeadLetter(_)
+        ));
+        assert!(retries.is_empty());
+
+        retries.fail(bank::Account::new(player, 1, 0), &transient(), now);
+        retries.hold(player::Room::new(player, RoomId::try_from(1).unwrap()));
+
+        // An update which starts failing for good is dropped, and stops holding back the rest
+        let later = now + Duration::from_secs(1);
+        let retry = retries.take_next(later).unwrap();
+        assert!(matches!(
+            retries.retry(retry, &permanent(), later),
+            Failure::DeadLetter(_)
+        ));
+        let retry = retries.take_next(later).unwrap();
+        assert_eq!(retry.update.write().unwrap().target.column, "players.room");
+    }
+
+    #[test]
+    fn test_permanent_errors() {
+        assert!(is_permanent(&permanent()));
+        assert!(!is_permanent(&transient()));
+        assert!(!is_permanent(&anyhow::anyhow!("unknown")));
+    }
+}
@@ -6,7 +6,7 @@ use crate::{
     engine::persist::Persist,
     world::types::{
         object::ObjectId,
-        room::{Direction, RoomId},
+        room::{Direction, Echo, Exit, RoomId},
     },
 };
 
@@ -15,14 +15,16 @@ pub struct AddExit {
     from_id: RoomId,
     to_id: RoomId,
     direction: Direction,
+    exit: Exit,
 }
 
 impl AddExit {
-    pub fn new(from_id: RoomId, to_id: RoomId, direction: Direction) -> Box<Self> {
+    pub fn new(from_id: RoomId, to_id: RoomId, direction: Direction, exit: Exit) -> Box<Self> {
         Box::new(AddExit {
             from_id,
             to_id,
             direction,
+            exit,
         })
     }
 }
@@ -31,13 +33,19 @@ impl AddExit {
 impl Persist for AddExit {
     #[tracing::instrument(name = "add room exit", skip(pool))]
     async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
-        sqlx::query("INSERT INTO exits (room_from, room_to, direction) VALUES (?, ?, ?)")
-            .bind(self.from_id)
-            .bind(self.to_id)
-            .bind(self.direction.as_str())
-            .execute(pool)
-            .in_current_span()
-            .await?;
+        sqlx::query(
+            "INSERT INTO exits (room_from, room_to, direction, message, leave_message, \
+             arrive_message) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(self.from_id)
+        .bind(self.to_id)
+        .bind(self.direction.as_str())
+        .bind(self.exit.message.as_deref())
+        .bind(self.exit.echo(Echo::Leave))
+        .bind(self.exit.echo(Echo::Arrive))
+        .execute(pool)
+        .in_current_span()
+        .await?;
 
         Ok(())
     }
@@ -200,6 +208,35 @@ impl Persist for Description {
     }
 }
 
+#[derive(Debug)]
+pub struct Echoes {
+    id: RoomId,
+    leave: Option<String>,
+    arrive: Option<String>,
+}
+
+impl Echoes {
+    pub fn new(id: RoomId, leave: Option<String>, arrive: Option<String>) -> Box<Self> {
+        Box::new(Echoes { id, leave, arrive })
+    }
+}
+
+#[async_trait]
+impl Persist for Echoes {
+    #[tracing::instrument(name = "update room echoes", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE rooms SET leave_message = ?, arrive_message = ? WHERE id = ?")
+            .bind(self.leave.as_deref())
+            .bind(self.arrive.as_deref())
+            .bind(self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct Name {
     id: RoomId,
@@ -227,6 +264,88 @@ impl Persist for Name {
     }
 }
 
+/// The published state of a staged room.
+#[derive(Debug)]
+pub struct Published {
+    id: RoomId,
+    name: String,
+    description: String,
+    exits: Vec<(Direction, RoomId, Exit)>,
+}
+
+impl Published {
+    pub fn new(
+        id: RoomId,
+        name: String,
+        description: String,
+        exits: Vec<(Direction, RoomId, Exit)>,
+    ) -> Self {
+        Published {
+            id,
+            name,
+            description,
+            exits,
+        }
+    }
+}
+
+// Merges a staged region into the database. All rooms are written in a single transaction so a
+// failure part way through cannot leave a partially published region behind.
+#[derive(Debug)]
+pub struct Publish {
+    rooms: Vec<Published>,
+}
+
+impl Publish {
+    pub fn new(rooms: Vec<Published>) -> Box<Self> {
+        Box::new(Publish { rooms })
+    }
+}
+
+#[async_trait]
+impl Persist for Publish {
+    #[tracing::instrument(name = "publish rooms", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        let mut tx = pool.begin().await?;
+
+        for room in self.rooms.iter() {
+            sqlx::query("UPDATE rooms SET name = ?, description = ? WHERE id = ?")
+                .bind(room.name.as_str())
+                .bind(room.description.as_str())
+                .bind(room.id)
+                .execute(&mut tx)
+                .in_current_span()
+                .await?;
+
+            sqlx::query("DELETE FROM exits WHERE room_from = ?")
+                .bind(room.id)
+                .execute(&mut tx)
+                .in_current_span()
+                .await?;
+
+            for (direction, to_id, exit) in room.exits.iter() {
+                sqlx::query(
+                    "INSERT INTO exits (room_from, room_to, direction, message, leave_message, \
+                     arrive_message) VALUES (?, ?, ?, ?, ?, ?)",
+                )
+                .bind(room.id)
+                .bind(to_id)
+                .bind(direction.as_str())
+                .bind(exit.message.as_deref())
+                .bind(exit.echo(Echo::Leave))
+                .bind(exit.echo(Echo::Arrive))
+                .execute(&mut tx)
+                .in_current_span()
+                .await?;
+            }
+        }
+
+        tx.commit().in_current_span().await?;
+
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct RemoveExit {
     id: RoomId,
@@ -6,7 +6,7 @@ use crate::{
     engine::persist::Persist,
     world::{
         scripting::{ScriptName, ScriptTrigger},
-        types::{object::PrototypeId, Id},
+        types::{object::PrototypeId, player::PlayerId, Id},
     },
 };
 
@@ -15,6 +15,7 @@ pub struct Attach {
     target: Id,
     script: ScriptName,
     trigger: ScriptTrigger,
+    priority: i64,
     copy: Option<PrototypeId>,
 }
 
@@ -23,12 +24,14 @@ impl Attach {
         target: Id,
         script: ScriptName,
         trigger: ScriptTrigger,
+        priority: i64,
         copy: Option<PrototypeId>,
     ) -> Box<Self> {
         Box::new(Attach {
             target,
             script,
             trigger,
+            priority,
             copy,
         })
     }
@@ -43,26 +46,28 @@ impl Persist for Attach {
         match self.target {
             Id::Player(id) => {
                 sqlx::query(
-                    "INSERT INTO player_scripts (player_id, kind, script, trigger) VALUES (?, ?, \
-                     ?, ?)",
+                    "INSERT INTO player_scripts (player_id, kind, script, trigger, priority) \
+                     VALUES (?, ?, ?, ?, ?)",
                 )
                 .bind(id.to_string())
                 .bind(self.trigger.kind().to_string())
                 .bind(self.script.to_string())
                 .bind(trigger)
+                .bind(self.priority)
                 .execute(pool)
                 .in_current_span()
                 .await?;
             }
             Id::Prototype(id) => {
                 sqlx::query(
-                    "INSERT INTO prototype_scripts (prototype_id, kind, script, trigger) VALUES \
-                     (?, ?, ?, ?)",
+                    "INSERT INTO prototype_scripts (prototype_id, kind, script, trigger, \
+                     priority) VALUES (?, ?, ?, ?, ?)",
                 )
                 .bind(id.to_string())
                 .bind(self.trigger.kind().to_string())
                 .bind(self.script.to_string())
                 .bind(trigger)
+                .bind(self.priority)
                 .execute(pool)
                 .in_current_span()
                 .await?;
@@ -86,25 +91,28 @@ impl Persist for Attach {
                 }
 
                 sqlx::query(
-                    "INSERT INTO object_scripts (object_id, kind, script, trigger) VALUES (?, ?, \
-                     ?, ?)",
+                    "INSERT INTO object_scripts (object_id, kind, script, trigger, priority) \
+                     VALUES (?, ?, ?, ?, ?)",
                 )
                 .bind(id.to_string())
                 .bind(self.trigger.kind().to_string())
                 .bind(self.script.to_string())
                 .bind(trigger)
+                .bind(self.priority)
                 .execute(pool)
                 .in_current_span()
                 .await?;
             }
             Id::Room(id) => {
                 sqlx::query(
-                    "INSERT INTO room_scripts (room_id, kind, script, trigger) VALUES (?, ?, ?, ?)",
+                    "INSERT INTO room_scripts (room_id, kind, script, trigger, priority) VALUES \
+                     (?, ?, ?, ?, ?)",
                 )
                 .bind(id.to_string())
                 .bind(self.trigger.kind().to_string())
                 .bind(self.script.to_string())
                 .bind(trigger)
+                .bind(self.priority)
                 .execute(pool)
                 .in_current_span()
                 .await?;
@@ -120,14 +128,30 @@ pub struct Create {
     name: String,
     trigger: String,
     code: String,
+    tags: Vec<String>,
+    folder: Option<String>,
+    enabled: bool,
+    author: Option<PlayerId>,
 }
 
 impl Create {
-    pub fn new(name: String, trigger: String, code: String) -> Box<Self> {
+    pub fn new(
+        name: String,
+        trigger: String,
+        code: String,
+        tags: Vec<String>,
+        folder: Option<String>,
+        enabled: bool,
+        author: Option<PlayerId>,
+    ) -> Box<Self> {
         Box::new(Create {
             name,
             trigger,
             code,
+            tags,
+            folder,
+            enabled,
+            author,
         })
     }
 }
@@ -136,13 +160,20 @@ impl Create {
 impl Persist for Create {
     #[tracing::instrument(name = "script create", skip(pool))]
     async fn enact(&self, pool: &sqlx::SqlitePool) -> anyhow::Result<()> {
-        sqlx::query("INSERT INTO scripts (name, trigger, code) VALUES (?, ?, ?)")
-            .bind(self.name.as_str())
-            .bind(self.trigger.to_string())
-            .bind(self.code.as_str())
-            .execute(pool)
-            .in_current_span()
-            .await?;
+        sqlx::query(
+            "INSERT INTO scripts (name, trigger, code, tags, folder, enabled, author_id) VALUES \
+             (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(self.name.as_str())
+        .bind(self.trigger.to_string())
+        .bind(self.code.as_str())
+        .bind(self.tags.join(","))
+        .bind(self.folder.as_deref())
+        .bind(self.enabled)
+        .bind(self.author)
+        .execute(pool)
+        .in_current_span()
+        .await?;
 
         Ok(())
     }
@@ -254,6 +285,128 @@ impl Persist for Detach {
     }
 }
 
+#[derive(Debug)]
+pub struct Enable {
+    name: ScriptName,
+    enabled: bool,
+}
+
+impl Enable {
+    pub fn new(name: ScriptName, enabled: bool) -> Box<Self> {
+        Box::new(Enable { name, enabled })
+    }
+}
+
+#[async_trait]
+impl Persist for Enable {
+    #[tracing::instrument(name = "script enable", skip(pool))]
+    async fn enact(&self, pool: &sqlx::SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE scripts SET enabled = ? WHERE name = ?")
+            .bind(self.enabled)
+            .bind(self.name.to_string())
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct EnableHook {
+    target: Id,
+    script: ScriptName,
+    enabled: bool,
+    copy: Option<PrototypeId>,
+}
+
+impl EnableHook {
+    pub fn new(
+        target: Id,
+        script: ScriptName,
+        enabled: bool,
+        copy: Option<PrototypeId>,
+    ) -> Box<Self> {
+        Box::new(EnableHook {
+            target,
+            script,
+            enabled,
+            copy,
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for EnableHook {
+    #[tracing::instrument(name = "script enable hook", skip(pool))]
+    async fn enact(&self, pool: &sqlx::SqlitePool) -> anyhow::Result<()> {
+        match self.target {
+            Id::Player(id) => {
+                sqlx::query(
+                    "UPDATE player_scripts SET enabled = ? WHERE player_id = ? AND script = ?",
+                )
+                .bind(self.enabled)
+                .bind(id.to_string())
+                .bind(self.script.to_string())
+                .execute(pool)
+                .in_current_span()
+                .await?;
+            }
+            Id::Prototype(id) => {
+                sqlx::query(
+                    "UPDATE prototype_scripts SET enabled = ? WHERE prototype_id = ? AND script = \
+                     ?",
+                )
+                .bind(self.enabled)
+                .bind(id.to_string())
+                .bind(self.script.to_string())
+                .execute(pool)
+                .in_current_span()
+                .await?;
+            }
+            Id::Object(id) => {
+                if let Some(prototype) = self.copy {
+                    sqlx::query(
+                        "INSERT INTO object_scripts SELECT * FROM prototype_scripts WHERE \
+                         prototype_scripts.prototype_id = ?",
+                    )
+                    .bind(prototype)
+                    .execute(pool)
+                    .in_current_span()
+                    .await?;
+
+                    sqlx::query("UPDATE objects SET inherit_scripts = false WHERE id = ?")
+                        .bind(id)
+                        .execute(pool)
+                        .in_current_span()
+                        .await?;
+                }
+
+                sqlx::query(
+                    "UPDATE object_scripts SET enabled = ? WHERE object_id = ? AND script = ?",
+                )
+                .bind(self.enabled)
+                .bind(id.to_string())
+                .bind(self.script.to_string())
+                .execute(pool)
+                .in_current_span()
+                .await?;
+            }
+            Id::Room(id) => {
+                sqlx::query("UPDATE room_scripts SET enabled = ? WHERE room_id = ? AND script = ?")
+                    .bind(self.enabled)
+                    .bind(id.to_string())
+                    .bind(self.script.to_string())
+                    .execute(pool)
+                    .in_current_span()
+                    .await?;
+            }
+        };
+
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct Remove {
     name: String,
@@ -284,14 +437,30 @@ pub struct Update {
     name: String,
     trigger: String,
     code: String,
+    revision: i64,
+    tags: Vec<String>,
+    folder: Option<String>,
+    enabled: bool,
 }
 
 impl Update {
-    pub fn new(name: String, trigger: String, code: String) -> Box<Self> {
+    pub fn new(
+        name: String,
+        trigger: String,
+        code: String,
+        revision: i64,
+        tags: Vec<String>,
+        folder: Option<String>,
+        enabled: bool,
+    ) -> Box<Self> {
         Box::new(Update {
             name,
             trigger,
             code,
+            revision,
+            tags,
+            folder,
+            enabled,
         })
     }
 }
@@ -300,13 +469,20 @@ impl Update {
 impl Persist for Update {
     #[tracing::instrument(name = "remove script", skip(pool))]
     async fn enact(&self, pool: &sqlx::SqlitePool) -> anyhow::Result<()> {
-        sqlx::query("UPDATE scripts SET trigger = ?, code = ? WHERE name = ?")
-            .bind(self.trigger.to_string())
-            .bind(self.code.as_str())
-            .bind(self.name.as_str())
-            .execute(pool)
-            .in_current_span()
-            .await?;
+        sqlx::query(
+            "UPDATE scripts SET trigger = ?, code = ?, revision = ?, tags = ?, folder = ?, \
+             enabled = ? WHERE name = ?",
+        )
+        .bind(self.trigger.to_string())
+        .bind(self.code.as_str())
+        .bind(self.revision)
+        .bind(self.tags.join(","))
+        .bind(self.folder.as_deref())
+        .bind(self.enabled)
+        .bind(self.name.as_str())
+        .execute(pool)
+        .in_current_span()
+        .await?;
 
         Ok(())
     }
@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{engine::persist::Persist, world::types::season::Seasonal};
+
+#[derive(Debug)]
+pub struct Tag {
+    content: Seasonal,
+    season: String,
+}
+
+impl Tag {
+    pub fn new(content: Seasonal, season: String) -> Box<Self> {
+        Box::new(Tag { content, season })
+    }
+}
+
+#[async_trait]
+impl Persist for Tag {
+    #[tracing::instrument(name = "tag seasonal content", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT INTO seasonal_content (kind, target, season) VALUES (?, ?, ?) ON CONFLICT \
+             (kind, target) DO UPDATE SET season = excluded.season",
+        )
+        .bind(self.content.kind())
+        .bind(self.content.target())
+        .bind(self.season.as_str())
+        .execute(pool)
+        .in_current_span()
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Untag {
+    content: Seasonal,
+}
+
+impl Untag {
+    pub fn new(content: Seasonal) -> Box<Self> {
+        Box::new(Untag { content })
+    }
+}
+
+#[async_trait]
+impl Persist for Untag {
+    #[tracing::instrument(name = "untag seasonal content", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM seasonal_content WHERE kind = ? AND target = ?")
+            .bind(self.content.kind())
+            .bind(self.content.target())
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
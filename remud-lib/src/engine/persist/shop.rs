@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::Persist,
+    world::types::{
+        loot::LootTableId,
+        room::RoomId,
+        shop::{Shop, ShopId, StockItem},
+    },
+};
+
+#[derive(Debug)]
+pub struct Remove {
+    id: ShopId,
+}
+
+impl Remove {
+    pub fn new(id: ShopId) -> Box<Self> {
+        Box::new(Remove { id })
+    }
+}
+
+#[async_trait]
+impl Persist for Remove {
+    #[tracing::instrument(name = "remove shop", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM shops WHERE id = ?")
+            .bind(&self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+// Writes the whole shop, replacing its stock, in a single transaction.
+#[derive(Debug)]
+pub struct Update {
+    id: ShopId,
+    room: RoomId,
+    loot_table: Option<LootTableId>,
+    markup: u32,
+    cap: u32,
+    interval_secs: u64,
+    sunk: i64,
+    stock: Vec<StockItem>,
+}
+
+impl Update {
+    pub fn new(id: ShopId, shop: &Shop) -> Box<Self> {
+        Box::new(Update {
+            id,
+            room: shop.room,
+            loot_table: shop.loot_table.clone(),
+            markup: shop.markup,
+            cap: shop.cap,
+            interval_secs: shop.interval().as_secs(),
+            sunk: shop.sunk,
+            stock: shop.stock.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Update {
+    #[tracing::instrument(name = "update shop", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO shops (id, room_id, loot_table_id, markup, cap, interval_secs, sunk) \
+             VALUES (?, ?, ?, ?, ?, ?, ?) ON CONFLICT(id) DO UPDATE SET room_id = \
+             excluded.room_id, loot_table_id = excluded.loot_table_id, markup = excluded.markup, \
+             cap = excluded.cap, interval_secs = excluded.interval_secs, sunk = excluded.sunk",
+        )
+        .bind(&self.id)
+        .bind(self.room)
+        .bind(&self.loot_table)
+        .bind(self.markup)
+        .bind(self.cap)
+        .bind(self.interval_secs as i64)
+        .bind(self.sunk)
+        .execute(&mut tx)
+        .in_current_span()
+        .await?;
+
+        sqlx::query("DELETE FROM shop_stock WHERE shop_id = ?")
+            .bind(&self.id)
+            .execute(&mut tx)
+            .in_current_span()
+            .await?;
+
+        for (position, item) in self.stock.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO shop_stock (shop_id, position, prototype_id, rarity, quantity) \
+                 VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(&self.id)
+            .bind(position as i64)
+            .bind(item.prototype)
+            .bind(item.rarity.as_str())
+            .bind(item.quantity)
+            .execute(&mut tx)
+            .in_current_span()
+            .await?;
+        }
+
+        tx.commit().in_current_span().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::Persist,
+    world::types::{
+        object::PrototypeId,
+        room::RoomId,
+        spawn::{SpawnTable, SpawnTableId},
+    },
+};
+
+#[derive(Debug)]
+pub struct Remove {
+    id: SpawnTableId,
+}
+
+impl Remove {
+    pub fn new(id: SpawnTableId) -> Box<Self> {
+        Box::new(Remove { id })
+    }
+}
+
+#[async_trait]
+impl Persist for Remove {
+    #[tracing::instrument(name = "remove spawn table", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM spawn_tables WHERE id = ?")
+            .bind(&self.id)
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+// Writes the whole table, replacing its entries and the rooms and regions it covers, in a single
+// transaction.
+#[derive(Debug)]
+pub struct Update {
+    id: SpawnTableId,
+    cap: u32,
+    interval_secs: u64,
+    entries: Vec<(PrototypeId, u32)>,
+    rooms: Vec<RoomId>,
+    regions: Vec<String>,
+}
+
+impl Update {
+    pub fn new(id: SpawnTableId, table: &SpawnTable) -> Box<Self> {
+        Box::new(Update {
+            id,
+            cap: table.cap,
+            interval_secs: table.interval().as_secs(),
+            entries: table
+                .entries
+                .iter()
+                .map(|(prototype, weight)| (*prototype, *weight))
+                .collect(),
+            rooms: table.rooms.iter().copied().collect(),
+            regions: table.regions.iter().cloned().collect(),
+        })
+    }
+}
+
+#[async_trait]
+impl Persist for Update {
+    #[tracing::instrument(name = "update spawn table", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO spawn_tables (id, cap, interval_secs) VALUES (?, ?, ?) ON CONFLICT(id) \
+             DO UPDATE SET cap = excluded.cap, interval_secs = excluded.interval_secs",
+        )
+        .bind(&self.id)
+        .bind(self.cap)
+        .bind(self.interval_secs as i64)
+        .execute(&mut tx)
+        .in_current_span()
+        .await?;
+
+        for table in [
+            "spawn_table_entries",
+            "spawn_table_rooms",
+            "spawn_table_regions",
+        ] {
+            sqlx::query(format!("DELETE FROM {} WHERE table_id = ?", table).as_str())
+                .bind(&self.id)
+                .execute(&mut tx)
+                .in_current_span()
+                .await?;
+        }
+
+        for (prototype, weight) in self.entries.iter() {
+            sqlx::query(
+                "INSERT INTO spawn_table_entries (table_id, prototype_id, weight) VALUES (?, ?, ?)",
+            )
+            .bind(&self.id)
+            .bind(prototype)
+            .bind(weight)
+            .execute(&mut tx)
+            .in_current_span()
+            .await?;
+        }
+
+        for room in self.rooms.iter() {
+            sqlx::query("INSERT INTO spawn_table_rooms (table_id, room_id) VALUES (?, ?)")
+                .bind(&self.id)
+                .bind(room)
+                .execute(&mut tx)
+                .in_current_span()
+                .await?;
+        }
+
+        for region in self.regions.iter() {
+            sqlx::query("INSERT INTO spawn_table_regions (table_id, region) VALUES (?, ?)")
+                .bind(&self.id)
+                .bind(region.as_str())
+                .execute(&mut tx)
+                .in_current_span()
+                .await?;
+        }
+
+        tx.commit().in_current_span().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use tracing::Instrument;
+
+use crate::{
+    engine::persist::Persist,
+    world::types::{player::PlayerId, room::RoomId, tutorial::HintTrigger},
+};
+
+#[derive(Debug)]
+pub struct Start {
+    room: Option<RoomId>,
+}
+
+impl Start {
+    pub fn new(room: Option<RoomId>) -> Box<Self> {
+        Box::new(Start { room })
+    }
+}
+
+#[async_trait]
+impl Persist for Start {
+    #[tracing::instrument(name = "update tutorial start", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        match self.room {
+            Some(room) => {
+                sqlx::query(
+                    r#"INSERT INTO config (key, value) VALUES ("tutorial_room", ?)
+                        ON CONFLICT(key) DO UPDATE SET value = excluded.value"#,
+                )
+                .bind(room.to_string())
+                .execute(pool)
+                .in_current_span()
+                .await?;
+            }
+            None => {
+                sqlx::query(r#"DELETE FROM config WHERE key = "tutorial_room""#)
+                    .execute(pool)
+                    .in_current_span()
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Hint {
+    trigger: HintTrigger,
+    message: String,
+}
+
+impl Hint {
+    pub fn new(trigger: HintTrigger, message: String) -> Box<Self> {
+        Box::new(Hint { trigger, message })
+    }
+}
+
+#[async_trait]
+impl Persist for Hint {
+    #[tracing::instrument(name = "update hint", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"INSERT INTO hints (trigger, message) VALUES (?, ?)
+                ON CONFLICT(trigger) DO UPDATE SET message = excluded.message"#,
+        )
+        .bind(self.trigger.as_str())
+        .bind(self.message.as_str())
+        .execute(pool)
+        .in_current_span()
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct RemoveHint {
+    trigger: HintTrigger,
+}
+
+impl RemoveHint {
+    pub fn new(trigger: HintTrigger) -> Box<Self> {
+        Box::new(RemoveHint { trigger })
+    }
+}
+
+#[async_trait]
+impl Persist for RemoveHint {
+    #[tracing::instrument(name = "remove hint", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM hints WHERE trigger = ?")
+            .bind(self.trigger.as_str())
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct SeeHint {
+    player: PlayerId,
+    trigger: HintTrigger,
+}
+
+impl SeeHint {
+    pub fn new(player: PlayerId, trigger: HintTrigger) -> Box<Self> {
+        Box::new(SeeHint { player, trigger })
+    }
+}
+
+#[async_trait]
+impl Persist for SeeHint {
+    #[tracing::instrument(name = "record seen hint", skip(pool))]
+    async fn enact(&self, pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::query("INSERT OR IGNORE INTO player_hints (player_id, trigger) VALUES (?, ?)")
+            .bind(self.player)
+            .bind(self.trigger.as_str())
+            .execute(pool)
+            .in_current_span()
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,33 +1,55 @@
 // #![warn(clippy::pedantic)]
 #![allow(clippy::too_many_arguments)]
 
+pub mod admin;
+mod clock;
 mod color;
+mod config;
 mod ecs;
 mod engine;
+mod logging;
 mod macros;
+mod markup;
 mod metrics;
+mod profile;
 mod telnet;
 mod text;
+mod watchdog;
 mod web;
 mod world;
 
-use std::{collections::HashMap, fmt, sync::atomic::AtomicUsize};
+use std::{collections::HashMap, fmt, path::Path, sync::atomic::AtomicUsize};
 
 use futures::future::join_all;
 use once_cell::sync::Lazy;
 use thiserror::Error;
-use tokio::sync::mpsc;
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::mpsc,
+    task::JoinHandle,
+};
 
 use crate::{
-    engine::{db::Db, Engine, EngineMessage},
+    engine::{db::Db, ConfigUpdate, Engine, EngineMessage, Heartbeat},
     metrics::{init_metrics, stats_gauge, stats_incr},
-    web::run_web_server,
+    watchdog::run_watchdog,
+    web::{run_web_server, WebSettings},
 };
 
+pub use clock::Clock;
+pub use config::{Config, ConfigError, LoggingConfig};
+pub use engine::db::content::MigrationOptions;
+pub use logging::{LogChannel, GAME_LOG_TARGET};
+pub use profile::ProfileLayer;
 pub use web::{TlsOptions, WebOptions};
+#[cfg(feature = "invariants")]
+pub use world::invariants::violations as invariant_violations;
 
 static CLIENT_ID_COUNTER: Lazy<AtomicUsize> = Lazy::new(|| AtomicUsize::new(1));
 
+const CLIENT_QUEUE_SIZE: usize = 256;
+const WEB_QUEUE_SIZE: usize = 16;
+
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
 pub struct ClientId(usize);
 
@@ -57,31 +79,91 @@ pub enum RemudError {
     IoError(#[from] std::io::Error),
     #[error("failed to init metrics sink: {0}")]
     MetricError(#[from] cadence::MetricError),
+    #[error("failed to load configuration: {0}")]
+    ConfigError(#[from] ConfigError),
 }
 
 pub async fn run_remud(
     db_path: Option<&str>,
+    config_path: Option<&Path>,
     telnet_port: u16,
     web: WebOptions<'_>,
+    migrations: MigrationOptions,
+    clock: Clock,
     ready_tx: Option<mpsc::Sender<()>>,
 ) -> Result<(), RemudError> {
     let db = Db::new(db_path).await.map_err(engine::Error::from)?;
-    init_metrics().await;
+
+    let pending = db
+        .migrate_content(migrations, db_path)
+        .await
+        .map_err(engine::Error::from)?;
+    if migrations.dry_run {
+        tracing::info!("dry run of {} pending content migrations complete", pending);
+        return Ok(());
+    }
+
+    let mut config = match config_path {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    init_metrics(&config.metrics).await;
+
+    let mut hangup = signal(SignalKind::hangup())?;
+    let heartbeat = Heartbeat::default();
+    let web_settings = WebSettings::new(
+        web_cors(&web, &config),
+        config.web.docs,
+        config.web.rate_limits(),
+        clock.clone(),
+    );
 
     'program: loop {
-        let (client_tx, client_rx) = mpsc::channel(256);
+        let (client_tx, client_rx) = mpsc::channel(CLIENT_QUEUE_SIZE);
         let (engine_tx, mut engine_rx) = mpsc::channel(16);
-        let (web_tx, web_rx) = mpsc::channel(16);
+        let (web_tx, web_rx) = mpsc::channel(WEB_QUEUE_SIZE);
+        let (config_tx, config_rx) = mpsc::channel(16);
+        let (stalled_tx, mut stalled_rx) = mpsc::channel(1);
+
+        heartbeat.reset();
 
-        let mut engine = Engine::new(db.clone(), client_rx, engine_tx, web_rx).await?;
+        let mut engine = Engine::new(
+            db.clone(),
+            &config,
+            client_rx,
+            engine_tx,
+            web_rx,
+            config_rx,
+            heartbeat.clone(),
+            clock.clone(),
+        )
+        .await?;
         let engine_handle = tokio::spawn(async move {
             engine.run().await;
         });
 
+        let mut watchdog_handle = tokio::spawn(run_watchdog(
+            config.watchdog.clone(),
+            heartbeat.clone(),
+            client_tx.clone(),
+            web_tx.clone(),
+            stalled_tx.clone(),
+        ));
+
         let telnet_address = format!("0.0.0.0:{}", telnet_port);
-        let telnet = telnet::Server::new(telnet_address.as_str()).await?;
+        let mut telnet =
+            telnet::Server::new(telnet_address.as_str(), config.telnet.clone()).await?;
 
-        let web_handle = run_web_server(&web, db.clone(), web_tx, client_tx.clone()).await?;
+        let web_handle = run_web_server(
+            &web,
+            web_settings.clone(),
+            db.clone(),
+            web_tx.clone(),
+            client_tx.clone(),
+            heartbeat.clone(),
+            clock.clone(),
+        )
+        .await?;
 
         if let Some(tx) = ready_tx.clone() {
             tracing::info!("server ready");
@@ -91,6 +173,8 @@ pub async fn run_remud(
         let mut join_handles = HashMap::new();
 
         'main: loop {
+            let mut reload = None;
+
             tokio::select! {
                 handle = telnet.accept(client_tx.clone()) => {
                     match handle {
@@ -119,6 +203,10 @@ pub async fn run_remud(
                                     tracing::warn!("engine shutdown, halting server");
                                     break 'program
                                 },
+                                EngineMessage::ReloadConfig(requester) => {
+                                    tracing::info!("configuration reload requested");
+                                    reload = Some(Some(requester));
+                                },
                             }
                         },
                         None => {
@@ -127,6 +215,57 @@ pub async fn run_remud(
                         },
                     }
                 }
+                _ = stalled_rx.recv() => {
+                    tracing::error!("engine stalled, restarting server");
+                    engine_handle.abort();
+                    break 'main
+                }
+                _ = hangup.recv() => {
+                    tracing::info!("received SIGHUP, reloading configuration");
+                    reload = Some(None);
+                }
+            }
+
+            if let Some(requester) = reload {
+                let result = match config_path {
+                    Some(path) => Config::load(path).map_err(|e| e.to_string()),
+                    None => Err("no configuration file was provided at startup".to_string()),
+                };
+
+                if let Ok(new_config) = &result {
+                    if new_config.metrics != config.metrics {
+                        init_metrics(&new_config.metrics).await;
+                    }
+
+                    if new_config.web != config.web {
+                        tracing::info!("web configuration changed, updating web server");
+                        web_settings.update(
+                            web_cors(&web, new_config),
+                            new_config.web.docs,
+                            new_config.web.rate_limits(),
+                        );
+                    }
+
+                    if new_config.watchdog != config.watchdog {
+                        watchdog_handle.abort();
+                        watchdog_handle = tokio::spawn(run_watchdog(
+                            new_config.watchdog.clone(),
+                            heartbeat.clone(),
+                            client_tx.clone(),
+                            web_tx.clone(),
+                            stalled_tx.clone(),
+                        ));
+                    }
+
+                    telnet.set_config(new_config.telnet.clone());
+
+                    config = new_config.clone();
+                }
+
+                config_tx
+                    .send(ConfigUpdate { requester, result })
+                    .await
+                    .ok();
             }
         }
 
@@ -135,21 +274,16 @@ pub async fn run_remud(
         join_all(join_handles.values_mut()).await;
 
         // Make sure everything is shutdown before restarting
-        tracing::info!("aborting web server");
-        web_handle.abort();
-        match web_handle.await {
-            Ok(_) => (),
-            Err(e) => {
-                if !e.is_cancelled() {
-                    tracing::error!("error halting web server: {}", e);
-                    break;
-                }
-            }
-        };
+        if !halt_web_server(web_handle).await {
+            break;
+        }
+
+        watchdog_handle.abort();
 
         tracing::info!("joining engine");
         match engine_handle.await {
             Ok(_) => (),
+            Err(e) if e.is_cancelled() => tracing::warn!("stalled engine cancelled"),
             Err(e) => {
                 tracing::error!("error halting engine: {}", e);
                 break;
@@ -163,3 +297,27 @@ pub async fn run_remud(
 
     Ok(())
 }
+
+// Origins from the configuration file take precedence over those provided on the command line.
+fn web_cors(web: &WebOptions<'_>, config: &Config) -> Vec<String> {
+    match &config.web.cors {
+        Some(cors) => cors.clone(),
+        None => web.cors().iter().map(|origin| origin.to_string()).collect(),
+    }
+}
+
+async fn halt_web_server(handle: JoinHandle<()>) -> bool {
+    tracing::info!("aborting web server");
+    handle.abort();
+    match handle.await {
+        Ok(_) => true,
+        Err(e) => {
+            if e.is_cancelled() {
+                true
+            } else {
+                tracing::error!("error halting web server: {}", e);
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::any::TypeId;
+
+use tracing::{span, Event, Metadata, Subscriber};
+use tracing_subscriber::{
+    layer::{Context, Layer},
+    registry::LookupSpan,
+};
+
+/// The target of gameplay events such as chat, logins and immortal commands, which are written to
+/// the game log rather than alongside engine diagnostics.
+pub const GAME_LOG_TARGET: &str = "remud_lib::game";
+
+/// The target of updates which could never be saved and were dropped, so operators can find and
+/// repair them by hand.
+pub const DEAD_LETTER_TARGET: &str = "remud_lib::dead_letter";
+
+/// A tracing layer which passes either only gameplay events or only everything else on to the
+/// layer it wraps, so the two can be written to separate files. Spans are always passed on, so
+/// events in either channel carry the fields of the spans they occurred in.
+pub struct LogChannel<L> {
+    inner: L,
+    game: bool,
+}
+
+impl<L> LogChannel<L> {
+    /// Passes on only gameplay events.
+    pub fn game(inner: L) -> Self {
+        LogChannel { inner, game: true }
+    }
+
+    /// Passes on every event except gameplay events.
+    pub fn engine(inner: L) -> Self {
+        LogChannel { inner, game: false }
+    }
+
+    fn accepts(&self, metadata: &Metadata<'_>) -> bool {
+        (metadata.target() == GAME_LOG_TARGET) == self.game
+    }
+}
+
+impl<S, L> Layer<S> for LogChannel<L>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    L: Layer<S>,
+{
+    fn on_layer(&mut self, subscriber: &mut S) {
+        self.inner.on_layer(subscriber);
+    }
+
+    fn new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        self.inner.new_span(attrs, id, ctx);
+    }
+
+    fn on_record(&self, span: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        self.inner.on_record(span, values, ctx);
+    }
+
+    fn on_follows_from(&self, span: &span::Id, follows: &span::Id, ctx: Context<'_, S>) {
+        self.inner.on_follows_from(span, follows, ctx);
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if self.accepts(event.metadata()) {
+            self.inner.on_event(event, ctx);
+        }
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        self.inner.on_enter(id, ctx);
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        self.inner.on_exit(id, ctx);
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        self.inner.on_close(id, ctx);
+    }
+
+    fn on_id_change(&self, old: &span::Id, new: &span::Id, ctx: Context<'_, S>) {
+        self.inner.on_id_change(old, new, ctx);
+    }
+
+    #[doc(hidden)]
+    unsafe fn downcast_raw(&self, id: TypeId) -> Option<*const ()> {
+        if id == TypeId::of::<Self>() {
+            Some(self as *const _ as *const ())
+        } else {
+            self.inner.downcast_raw(id)
+        }
+    }
+}
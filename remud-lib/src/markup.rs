@@ -0,0 +1,134 @@
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// Links are written alongside color tags, as |send <commands>|text|/send|. Commands are separated
+// by semicolons; links with more than one command open a menu of them. Escaped pipes and color
+// tags are matched so their pipes are never mistaken for the start of another tag.
+pub static LINK_TAG_MATCHER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?P<escape>\|\|)|\|send (?P<commands>[^|]+)\||(?P<close>\|/send\|)|(?P<color>\|[#\w-]+\|)"#,
+    )
+    .unwrap()
+});
+
+// Selects secure line mode, in which MXP tags are allowed until the end of the line.
+const MXP_SECURE_LINE: &str = "\x1b[1z";
+
+/// Marks up text which sends the commands when clicked. With more than one command, clicking
+/// opens a menu of them.
+pub fn link<S: AsRef<str>>(commands: &[S], text: &str) -> String {
+    let commands = commands
+        .iter()
+        .map(|command| command.as_ref().replace(['|', ';'], " "))
+        .join(";");
+
+    if commands.trim().is_empty() {
+        return text.to_string();
+    }
+
+    format!("|send {}|{}|/send|", commands, text)
+}
+
+/// Removes links, keeping their text, for clients which cannot follow them.
+pub fn strip_links(message: &str) -> String {
+    LINK_TAG_MATCHER
+        .replace_all(message, |captures: &regex::Captures| {
+            if captures.name("escape").is_some() {
+                "||".to_string()
+            } else if let Some(color) = captures.name("color") {
+                color.as_str().to_string()
+            } else {
+                String::new()
+            }
+        })
+        .to_string()
+}
+
+/// Renders links as MXP send tags. Lines with links are sent in secure line mode, with the rest
+/// of their text escaped; other lines are left alone, as the client is kept in locked mode.
+pub fn render_mxp(message: &str) -> String {
+    message
+        .split('\n')
+        .map(|line| {
+            let has_link = LINK_TAG_MATCHER
+                .captures_iter(line)
+                .any(|captures| captures.name("commands").is_some());
+
+            if !has_link {
+                return line.to_string();
+            }
+
+            let mut rendered = MXP_SECURE_LINE.to_string();
+            let mut last = 0;
+
+            for captures in LINK_TAG_MATCHER.captures_iter(line) {
+                let tag = captures.get(0).unwrap();
+                rendered.push_str(escape(&line[last..tag.start()]).as_str());
+                last = tag.end();
+
+                if captures.name("escape").is_some() {
+                    rendered.push_str("||");
+                } else if let Some(color) = captures.name("color") {
+                    rendered.push_str(color.as_str());
+                } else if let Some(commands) = captures.name("commands") {
+                    // Pipes are doubled so color tags are not found among the commands
+                    let href = commands.as_str().split(';').map(escape).join("||");
+                    rendered.push_str(format!("<send href=\"{}\">", href).as_str());
+                } else {
+                    rendered.push_str("</send>");
+                }
+            }
+
+            rendered.push_str(escape(&line[last..]).as_str());
+            rendered
+        })
+        .join("\n")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{link, render_mxp, strip_links};
+
+    #[test]
+    fn test_link() {
+        assert_eq!(link(&["north"], "north"), "|send north|north|/send|");
+        assert_eq!(
+            link(&["look at rock", "get rock"], "a rock"),
+            "|send look at rock;get rock|a rock|/send|"
+        );
+        assert_eq!(link::<&str>(&[], "a rock"), "a rock");
+    }
+
+    #[test]
+    fn test_strip_links() {
+        assert_eq!(
+            strip_links("You see |send look at rock;get rock|a rock|/send|."),
+            "You see a rock."
+        );
+        assert_eq!(
+            strip_links("|white|||send north||"),
+            "|white|||send north||"
+        );
+    }
+
+    #[test]
+    fn test_render_mxp() {
+        assert_eq!(
+            render_mxp("Exits:\r\n|send north|north|/send| & |send up|up|/send|"),
+            "Exits:\r\n\x1b[1z<send href=\"north\">north</send> &amp; <send href=\"up\">up</send>"
+        );
+        assert_eq!(
+            render_mxp("|send look at rock;get rock|a <rock>|/send|"),
+            "\x1b[1z<send href=\"look at rock||get rock\">a &lt;rock&gt;</send>"
+        );
+        assert_eq!(render_mxp("a <rock>"), "a <rock>");
+    }
+}
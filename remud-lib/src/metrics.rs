@@ -1,63 +1,118 @@
-use cadence::{Counted, Gauged, NopMetricSink, StatsdClient, Timed, DEFAULT_PORT};
-use once_cell::sync::OnceCell;
-use std::time::Instant;
+use cadence::{Counted, Gauged, MetricError, NopMetricSink, StatsdClient, Timed};
+use once_cell::sync::Lazy;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    },
+    time::Instant,
+};
 use tokio::net::UdpSocket;
 use tokio_cadence::TokioBatchUdpMetricSink;
 
-static METRICS: OnceCell<StatsdClient> = OnceCell::new();
+use crate::config::{MetricsConfig, DEFAULT_METRICS_PREFIX};
 
-async fn init_telegraf_metrics(host: &str) -> anyhow::Result<()> {
+struct Metrics {
+    client: StatsdClient,
+    timer_sample_rate: f64,
+}
+
+impl Metrics {
+    fn nop(prefix: &str) -> Self {
+        Metrics {
+            client: StatsdClient::from_sink(prefix, NopMetricSink),
+            timer_sample_rate: 1.0,
+        }
+    }
+}
+
+// The client is swapped out when the metrics target is changed by a configuration reload.
+static METRICS: Lazy<RwLock<Metrics>> =
+    Lazy::new(|| RwLock::new(Metrics::nop(DEFAULT_METRICS_PREFIX)));
+
+// Whether metrics are being dropped. Metrics are the first thing shed when the sink backs up, and
+// the failure is reported once rather than for every metric.
+static SHEDDING: AtomicBool = AtomicBool::new(false);
+
+fn posted<T>(kind: &str, result: Result<T, MetricError>) {
+    match result {
+        Ok(_) => SHEDDING.store(false, Ordering::Relaxed),
+        Err(err) => {
+            if !SHEDDING.swap(true, Ordering::Relaxed) {
+                tracing::warn!("unable to post {}, dropping metrics: {:?}", kind, err);
+            }
+        }
+    }
+}
+
+async fn init_telegraf_metrics(host: &str, config: &MetricsConfig) -> anyhow::Result<()> {
     let socket = UdpSocket::bind("0.0.0.0:0").await?;
-    let (sink, process) = TokioBatchUdpMetricSink::from((host, DEFAULT_PORT), socket)?;
+    let (sink, process) = TokioBatchUdpMetricSink::from((host, config.port), socket)?;
     tokio::spawn(process);
-    let client = StatsdClient::from_sink("remud", sink);
-    METRICS.get_or_init(|| client);
+    *METRICS.write().unwrap() = Metrics {
+        client: StatsdClient::from_sink(config.prefix.as_str(), sink),
+        timer_sample_rate: config.timer_sample_rate,
+    };
     tracing::info!("initialized metrics client for host: {}", host);
     Ok(())
 }
 
-pub(crate) async fn init_metrics() {
-    if init_telegraf_metrics("telegraf").await.is_ok() {
+/// Points metrics at the configured host, or searches for telegraf if no host is configured. Falls
+/// back to a no-op client if metrics are disabled or the target cannot be reached.
+pub(crate) async fn init_metrics(config: &MetricsConfig) {
+    if !config.enabled {
+        tracing::info!("using a no-op metrics client because metrics are disabled");
+        *METRICS.write().unwrap() = Metrics::nop(config.prefix.as_str());
+        return;
+    }
+
+    if let Some(host) = config.host.as_deref() {
+        if init_telegraf_metrics(host, config).await.is_ok() {
+            tracing::info!("initialized metrics to host: {}", host);
+            return;
+        }
+    } else if init_telegraf_metrics("telegraf", config).await.is_ok() {
         tracing::info!("initialized metrics to host: telegraf");
-    } else if init_telegraf_metrics("127.0.0.1").await.is_ok() {
+        return;
+    } else if init_telegraf_metrics("127.0.0.1", config).await.is_ok() {
         tracing::info!("initialized metrics to host: 127.0.0.1");
-    } else {
-        tracing::info!("using a no-op metrics client because telegraf is not available");
-        let client = StatsdClient::from_sink("remud", NopMetricSink);
-        METRICS.get_or_init(|| client);
+        return;
     }
+
+    tracing::info!("using a no-op metrics client because telegraf is not available");
+    *METRICS.write().unwrap() = Metrics::nop(config.prefix.as_str());
 }
 
 pub(crate) fn stats_time<'a, T: Into<&'a str>>(key: T, start: Instant) {
-    if let Err(err) = METRICS
-        .get()
-        .unwrap()
-        .time(key.into(), (Instant::now() - start).as_millis() as u64)
-    {
-        tracing::warn!("unable to post time: {:?}", err);
-    }
+    posted(
+        "time",
+        METRICS
+            .read()
+            .unwrap()
+            .client
+            .time(key.into(), (Instant::now() - start).as_millis() as u64),
+    );
 }
 
 pub(crate) fn stats_incr<'a, T: Into<&'a str>>(key: T) {
-    if let Err(err) = METRICS.get().unwrap().incr(key.into()) {
-        tracing::warn!("unable to post incr: {:?}", err);
-    }
+    posted("incr", METRICS.read().unwrap().client.incr(key.into()));
 }
 
 pub(crate) fn stats_gauge<'a, T: Into<&'a str>>(key: T, value: u64) {
-    if let Err(err) = METRICS.get().unwrap().gauge(key.into(), value) {
-        tracing::warn!("unable to post gauge: {:?}", err);
-    }
+    posted(
+        "gauge",
+        METRICS.read().unwrap().client.gauge(key.into(), value),
+    );
 }
 
 // pub(crate) fn stats_decr<'a, T: Into<&'a str>>(key: T) {
-//     if let Err(err) = METRICS.get().unwrap().decr(key.into()) {
+//     if let Err(err) = METRICS.read().unwrap().client.decr(key.into()) {
 //         tracing::warn!("unable to post decr: {:?}", err);
 //     }
 // }
 //
 // pub(crate) fn stats_histogram<'a, T: Into<&'a str>>(key: T, value: u64) {
-//     if let Err(err) = METRICS.get().unwrap().histogram(key.into(), value) {
+//     if let Err(err) = METRICS.read().unwrap().client.histogram(key.into(), value) {
 //         tracing::warn!("unable to post histogram: {:?}", err);
 //     }
 // }
@@ -65,18 +120,34 @@ pub(crate) fn stats_gauge<'a, T: Into<&'a str>>(key: T, value: u64) {
 pub(crate) struct StatsTimer<'a> {
     key: &'a str,
     start: Instant,
+    sampled: bool,
 }
 impl<'a> StatsTimer<'a> {
     pub fn new<T: Into<&'a str>>(key: T) -> Self {
         StatsTimer {
             key: key.into(),
             start: Instant::now(),
+            sampled: true,
+        }
+    }
+
+    /// A timer for something which happens many times a second, posted for only a fraction of
+    /// runs as set by the timer sample rate.
+    pub fn sampled<T: Into<&'a str>>(key: T) -> Self {
+        let rate = METRICS.read().unwrap().timer_sample_rate;
+
+        StatsTimer {
+            key: key.into(),
+            start: Instant::now(),
+            sampled: rate >= 1.0 || rand::random::<f64>() < rate,
         }
     }
 }
 
 impl<'a> Drop for StatsTimer<'a> {
     fn drop(&mut self) {
-        stats_time(self.key, self.start);
+        if self.sampled {
+            stats_time(self.key, self.start);
+        }
     }
 }
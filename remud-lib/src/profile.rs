@@ -0,0 +1,161 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs::{create_dir_all, File},
+    hash::{Hash, Hasher},
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tracing::{span, Subscriber};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+static CAPTURING: AtomicBool = AtomicBool::new(false);
+static CAPTURE: Lazy<Mutex<Option<Capture>>> = Lazy::new(|| Mutex::new(None));
+
+// Trace viewers only need a stable integer per thread, so the opaque thread ID is hashed down.
+fn thread_id() -> u32 {
+    let mut hasher = DefaultHasher::new();
+    thread::current().id().hash(&mut hasher);
+    hasher.finish() as u32
+}
+
+/// A tracing layer which records span timings while a profile capture is running. Captures are
+/// written in the Chrome trace event format, which can be loaded by chrome://tracing, Perfetto,
+/// or speedscope to produce flamegraphs.
+///
+/// The layer only sees spans enabled by the installed subscriber's filter. Engine step, phase, and
+/// system spans are emitted at the info level.
+#[derive(Debug, Default)]
+pub struct ProfileLayer {}
+
+struct Entered(Instant);
+
+impl<S> Layer<S> for ProfileLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        if !CAPTURING.load(Ordering::Relaxed) {
+            return;
+        }
+
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().replace(Entered(Instant::now()));
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        if !CAPTURING.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+
+        let entered = match span.extensions_mut().remove::<Entered>() {
+            Some(Entered(entered)) => entered,
+            None => return,
+        };
+
+        let exited = Instant::now();
+        let metadata = span.metadata();
+
+        if let Some(capture) = CAPTURE.lock().unwrap().as_mut() {
+            // Spans entered before the capture started are clamped to the start of the capture.
+            let start = entered.max(capture.start);
+            capture.events.push(TraceEvent {
+                name: metadata.name(),
+                cat: metadata.target(),
+                ph: "X",
+                ts: (start - capture.start).as_micros() as u64,
+                dur: exited.saturating_duration_since(start).as_micros() as u64,
+                pid: 1,
+                tid: thread_id(),
+            });
+        }
+    }
+}
+
+struct Capture {
+    start: Instant,
+    events: Vec<TraceEvent>,
+}
+
+#[derive(Serialize)]
+struct TraceEvent {
+    name: &'static str,
+    cat: &'static str,
+    ph: &'static str,
+    ts: u64,
+    dur: u64,
+    pid: u32,
+    tid: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Trace<'a> {
+    trace_events: &'a [TraceEvent],
+    display_time_unit: &'static str,
+}
+
+/// Begins recording span timings. Returns false if a capture is already in progress.
+pub(crate) fn start_capture() -> bool {
+    let mut capture = CAPTURE.lock().unwrap();
+    if capture.is_some() {
+        return false;
+    }
+
+    *capture = Some(Capture {
+        start: Instant::now(),
+        events: Vec::new(),
+    });
+    CAPTURING.store(true, Ordering::Relaxed);
+
+    true
+}
+
+/// Stops the current capture and writes it as a Chrome trace file to the provided directory.
+/// Returns the path of the written file and the number of recorded spans.
+pub(crate) fn finish_capture(directory: &Path) -> io::Result<(PathBuf, usize)> {
+    CAPTURING.store(false, Ordering::Relaxed);
+
+    let capture = match CAPTURE.lock().unwrap().take() {
+        Some(capture) => capture,
+        None => {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no profile capture in progress",
+            ))
+        }
+    };
+
+    create_dir_all(directory)?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let path = directory.join(format!("profile-{}.json", timestamp));
+
+    let writer = BufWriter::new(File::create(&path)?);
+    serde_json::to_writer(
+        writer,
+        &Trace {
+            trace_events: capture.events.as_slice(),
+            display_time_unit: "ms",
+        },
+    )?;
+
+    Ok((path, capture.events.len()))
+}
@@ -0,0 +1,267 @@
+use std::collections::VecDeque;
+
+use ascii::{AsciiString, ToAsciiChar};
+use bytes::{BufMut, Bytes, BytesMut};
+
+const HISTORY_LENGTH: usize = 20;
+// Longer lines are refused rather than buffered without bound.
+const MAX_LINE_LENGTH: usize = 4096;
+
+const BACKSPACE: u8 = 0x08;
+const DELETE: u8 = 0x7f;
+const ESCAPE: u8 = 0x1b;
+const ERASE: &[u8] = b"\x08 \x08";
+
+/// Which input the server writes back to the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Echo {
+    // The client echoes its own input
+    Off,
+    // Only line endings are echoed, for sensitive input in character mode
+    Hidden,
+    On,
+}
+
+/// The client sent a line longer than the editor will buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineTooLong;
+
+enum Escape {
+    None,
+    Started,
+    Sequence,
+}
+
+/// Assembles lines from client input. Clients in character mode send keys as they are pressed,
+/// so the editor also handles erasing and browsing previously entered lines with the arrow keys.
+pub struct LineEditor {
+    line: AsciiString,
+    history: VecDeque<AsciiString>,
+    // index into the history while browsing it
+    browsing: Option<usize>,
+    escape: Escape,
+    after_cr: bool,
+}
+
+impl LineEditor {
+    pub fn new() -> Self {
+        LineEditor {
+            line: AsciiString::new(),
+            history: VecDeque::new(),
+            browsing: None,
+            escape: Escape::None,
+            after_cr: false,
+        }
+    }
+
+    /// Processes client input, returning completed lines and the bytes to echo to the client.
+    pub fn input(
+        &mut self,
+        data: Bytes,
+        echo: Echo,
+    ) -> Result<(Vec<AsciiString>, Bytes), LineTooLong> {
+        let mut lines = Vec::new();
+        let mut output = BytesMut::new();
+
+        for byte in data {
+            let after_cr = std::mem::replace(&mut self.after_cr, false);
+
+            match self.escape {
+                Escape::Started => {
+                    self.escape = if byte == b'[' || byte == b'O' {
+                        Escape::Sequence
+                    } else {
+                        Escape::None
+                    };
+                    continue;
+                }
+                Escape::Sequence => {
+                    // parameter and intermediate bytes precede the final byte
+                    if (0x40..=0x7e).contains(&byte) {
+                        self.escape = Escape::None;
+                        match byte {
+                            b'A' => self.history_previous(&mut output),
+                            b'B' => self.history_next(&mut output),
+                            _ => (),
+                        }
+                    }
+                    continue;
+                }
+                Escape::None => (),
+            }
+
+            match byte {
+                b'\r' => {
+                    self.after_cr = true;
+                    lines.push(self.complete(echo, &mut output));
+                }
+                // CR LF and CR NUL end a single line
+                b'\n' | b'\0' if after_cr => (),
+                b'\n' => lines.push(self.complete(echo, &mut output)),
+                BACKSPACE | DELETE => {
+                    if self.line.pop().is_some() && echo == Echo::On {
+                        output.put(ERASE);
+                    }
+                }
+                ESCAPE => self.escape = Escape::Started,
+                byte => {
+                    if let Ok(char) = byte.to_ascii_char() {
+                        if !char.is_ascii_control() {
+                            if self.line.len() >= MAX_LINE_LENGTH {
+                                return Err(LineTooLong);
+                            }
+                            self.line.push(char);
+                            if echo == Echo::On {
+                                output.put_u8(byte);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok((lines, output.freeze()))
+    }
+
+    /// Adds a line to the history. Sensitive input should not be recorded.
+    pub fn record(&mut self, line: &AsciiString) {
+        if line.is_empty() || self.history.back() == Some(line) {
+            return;
+        }
+
+        if self.history.len() == HISTORY_LENGTH {
+            self.history.pop_front();
+        }
+        self.history.push_back(line.clone());
+    }
+
+    fn complete(&mut self, echo: Echo, output: &mut BytesMut) -> AsciiString {
+        self.browsing = None;
+        if echo != Echo::Off {
+            output.put_slice(b"\r\n");
+        }
+        std::mem::take(&mut self.line)
+    }
+
+    fn history_previous(&mut self, output: &mut BytesMut) {
+        let index = match self.browsing {
+            None if self.history.is_empty() => return,
+            None => self.history.len() - 1,
+            Some(index) => index.saturating_sub(1),
+        };
+
+        self.browsing = Some(index);
+        let line = self.history[index].clone();
+        self.replace_line(line, output);
+    }
+
+    fn history_next(&mut self, output: &mut BytesMut) {
+        let line = match self.browsing {
+            None => return,
+            Some(index) if index + 1 < self.history.len() => {
+                self.browsing = Some(index + 1);
+                self.history[index + 1].clone()
+            }
+            Some(_) => {
+                self.browsing = None;
+                AsciiString::new()
+            }
+        };
+
+        self.replace_line(line, output);
+    }
+
+    // History is only browsed by clients in character mode, which the server echoes for.
+    fn replace_line(&mut self, line: AsciiString, output: &mut BytesMut) {
+        for _ in 0..self.line.len() {
+            output.put(ERASE);
+        }
+        output.put(line.as_bytes());
+        self.line = line;
+    }
+}
+
+impl Default for LineEditor {
+    fn default() -> Self {
+        LineEditor::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(editor: &mut LineEditor, data: &'static [u8], echo: Echo) -> (Vec<String>, Bytes) {
+        let (lines, output) = editor.input(Bytes::from_static(data), echo).unwrap();
+        (
+            lines.into_iter().map(|line| line.to_string()).collect(),
+            output,
+        )
+    }
+
+    #[test]
+    fn test_line_endings() {
+        let mut editor = LineEditor::new();
+
+        let (lines, output) = input(&mut editor, b"look\r\nsay hi\r\0who\nexits", Echo::Off);
+        assert_eq!(lines, vec!["look", "say hi", "who"]);
+        assert!(output.is_empty());
+
+        let (lines, _) = input(&mut editor, b"\r", Echo::Off);
+        assert_eq!(lines, vec!["exits"]);
+    }
+
+    #[test]
+    fn test_character_mode_echo() {
+        let mut editor = LineEditor::new();
+
+        let (lines, output) = input(&mut editor, b"lookk\x7f", Echo::On);
+        assert!(lines.is_empty());
+        assert_eq!(output.as_ref(), b"lookk\x08 \x08");
+
+        let (lines, output) = input(&mut editor, b"\r\n", Echo::On);
+        assert_eq!(lines, vec!["look"]);
+        assert_eq!(output.as_ref(), b"\r\n");
+
+        let (lines, output) = input(&mut editor, b"secret\r", Echo::Hidden);
+        assert_eq!(lines, vec!["secret"]);
+        assert_eq!(output.as_ref(), b"\r\n");
+    }
+
+    #[test]
+    fn test_history() {
+        let mut editor = LineEditor::new();
+        editor.record(&AsciiString::from_ascii("look").unwrap());
+        editor.record(&AsciiString::from_ascii("say hi").unwrap());
+
+        let (_, output) = input(&mut editor, b"x\x1b[A", Echo::On);
+        assert_eq!(output.as_ref(), b"x\x08 \x08say hi");
+
+        let (_, output) = input(&mut editor, b"\x1b[A", Echo::On);
+        assert_eq!(
+            output.as_ref(),
+            b"\x08 \x08\x08 \x08\x08 \x08\x08 \x08\x08 \x08\x08 \x08look"
+        );
+
+        let (_, output) = input(&mut editor, b"\x1bOB", Echo::On);
+        assert_eq!(
+            output.as_ref(),
+            b"\x08 \x08\x08 \x08\x08 \x08\x08 \x08say hi"
+        );
+
+        let (lines, _) = input(&mut editor, b"\x1b[B\x1b[Cwho\r", Echo::On);
+        assert_eq!(lines, vec!["who"]);
+    }
+
+    #[test]
+    fn test_line_too_long() {
+        let mut editor = LineEditor::new();
+
+        let line = Bytes::from(vec![b'a'; MAX_LINE_LENGTH]);
+        assert!(editor.input(line, Echo::Off).unwrap().0.is_empty());
+        assert_eq!(
+            editor.input(Bytes::from_static(b"a"), Echo::Off),
+            Err(LineTooLong)
+        );
+    }
+}
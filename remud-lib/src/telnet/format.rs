@@ -0,0 +1,268 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use ascii::IntoAsciiString;
+use bytes::Bytes;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::{
+    color::colorize_telnet,
+    engine::{Output, Text},
+    markup::{render_mxp, strip_links},
+    metrics::stats_incr,
+    world::types::player::ClientCaps,
+};
+
+const WORKER_QUEUE: usize = 64;
+// Broadcast messages are formatted once per profile, up to this many messages per profile and
+// this many profiles before the cache starts over.
+const MAX_CACHED_MESSAGES: usize = 256;
+const MAX_CACHED_PROFILES: usize = 32;
+
+/// Output formatted for a telnet client, ready to be written to its socket.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Formatted {
+    Message(Bytes),
+    Prompt { data: Bytes, sensitive: bool },
+}
+
+struct Job {
+    caps: ClientCaps,
+    outputs: VecDeque<Output>,
+    reply: oneshot::Sender<Vec<Formatted>>,
+}
+
+/// A pool of threads which format engine output for telnet clients, so connection tasks only
+/// write prebuilt frames. Jobs are handed to the workers in turn, and the workers share a cache
+/// so clients with the same capabilities share formatted broadcast messages.
+#[derive(Clone)]
+pub struct Formatter {
+    workers: Vec<mpsc::Sender<Job>>,
+    next: Arc<AtomicUsize>,
+    cache: Arc<Mutex<Cache>>,
+}
+
+impl Formatter {
+    pub fn new(workers: usize) -> Self {
+        let cache = Arc::new(Mutex::new(Cache::default()));
+
+        let workers = (0..workers.max(1))
+            .map(|index| {
+                let (tx, rx) = mpsc::channel(WORKER_QUEUE);
+                let cache = cache.clone();
+                thread::Builder::new()
+                    .name(format!("telnet-format-{}", index))
+                    .spawn(move || work(rx, &cache))
+                    .expect("failed to spawn telnet format worker");
+                tx
+            })
+            .collect();
+
+        Formatter {
+            workers,
+            next: Arc::new(AtomicUsize::new(0)),
+            cache,
+        }
+    }
+
+    /// Formats the outputs for a client with the given capabilities, in order.
+    pub async fn format(&self, caps: &ClientCaps, outputs: VecDeque<Output>) -> Vec<Formatted> {
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        let worker = &self.workers[next % self.workers.len()];
+
+        let (tx, rx) = oneshot::channel();
+        let job = Job {
+            caps: caps.clone(),
+            outputs,
+            reply: tx,
+        };
+
+        if let Err(mpsc::error::SendError(job)) = worker.send(job).await {
+            tracing::error!("telnet format worker stopped, formatting in place");
+            return format_outputs(&self.cache, &job.caps, job.outputs);
+        }
+
+        rx.await.unwrap_or_else(|_| {
+            tracing::error!("telnet format worker dropped output");
+            stats_incr("telnet.format_failed");
+            Vec::new()
+        })
+    }
+}
+
+fn work(mut rx: mpsc::Receiver<Job>, cache: &Mutex<Cache>) {
+    while let Some(job) = rx.blocking_recv() {
+        let formatted = format_outputs(cache, &job.caps, job.outputs);
+        // the connection may have closed while waiting
+        job.reply.send(formatted).ok();
+    }
+}
+
+#[derive(Default)]
+struct Cache {
+    profiles: HashMap<ClientCaps, HashMap<Text, Bytes>>,
+}
+
+impl Cache {
+    fn get(&self, caps: &ClientCaps, message: &Text) -> Option<Bytes> {
+        self.profiles
+            .get(caps)
+            .and_then(|cached| cached.get(message))
+            .cloned()
+    }
+
+    fn insert(&mut self, caps: &ClientCaps, message: Text, bytes: Bytes) {
+        if !self.profiles.contains_key(caps) && self.profiles.len() >= MAX_CACHED_PROFILES {
+            self.profiles.clear();
+        }
+        let cached = self.profiles.entry(caps.clone()).or_default();
+        if cached.len() >= MAX_CACHED_MESSAGES {
+            cached.clear();
+        }
+        cached.insert(message, bytes);
+    }
+}
+
+// Formats a message, or takes it from the cache. The cache is only locked to look the message up
+// and to store it, so workers format in parallel.
+fn format_message(cache: &Mutex<Cache>, caps: &ClientCaps, message: Text) -> Option<Bytes> {
+    if let Some(bytes) = cache.lock().unwrap().get(caps, &message) {
+        return Some(bytes);
+    }
+
+    let bytes = to_bytes(colorize_telnet(
+        format!("|Gray69|{}|-|\r\n", markup(message.as_str(), caps)).as_str(),
+        caps.color,
+    ))?;
+
+    cache.lock().unwrap().insert(caps, message, bytes.clone());
+
+    Some(bytes)
+}
+
+fn format_outputs(
+    cache: &Mutex<Cache>,
+    caps: &ClientCaps,
+    outputs: VecDeque<Output>,
+) -> Vec<Formatted> {
+    outputs
+        .into_iter()
+        .filter_map(|output| match output {
+            Output::Message(message) => {
+                format_message(cache, caps, message).map(Formatted::Message)
+            }
+            Output::Prompt { format, sensitive } => to_bytes(colorize_telnet(
+                format!("|Gray69|{}|-|", markup(format.as_str(), caps)).as_str(),
+                caps.color,
+            ))
+            .map(|data| Formatted::Prompt { data, sensitive }),
+        })
+        .collect()
+}
+
+fn to_bytes(message: String) -> Option<Bytes> {
+    match message.into_ascii_string() {
+        Ok(str) => {
+            let bytes: Vec<u8> = str.into();
+            Some(Bytes::from(bytes))
+        }
+        Err(e) => {
+            tracing::error!("Engine returned non-ASCII string: \"{}\"", e);
+            None
+        }
+    }
+}
+
+// Renders links for clients which understand MXP, and removes them for the rest.
+fn markup(message: &str, caps: &ClientCaps) -> String {
+    if caps.mxp {
+        render_mxp(message)
+    } else {
+        strip_links(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, sync::atomic::Ordering};
+
+    use bytes::Bytes;
+    use futures::future::join_all;
+
+    use crate::{
+        engine::{Output, Text},
+        telnet::format::{Formatted, Formatter},
+        world::types::player::ClientCaps,
+    };
+
+    #[tokio::test]
+    async fn test_format_in_order() {
+        let formatter = Formatter::new(2);
+        let caps = ClientCaps::default();
+
+        let outputs = VecDeque::from(vec![
+            Output::Message(Text::from("|white|one|-|")),
+            Output::Message(Text::from("two")),
+            Output::Prompt {
+                format: Text::from("> "),
+                sensitive: true,
+            },
+        ]);
+
+        assert_eq!(
+            formatter.format(&caps, outputs).await,
+            vec![
+                Formatted::Message(Bytes::from("one\r\n")),
+                Formatted::Message(Bytes::from("two\r\n")),
+                Formatted::Prompt {
+                    data: Bytes::from("> "),
+                    sensitive: true
+                },
+            ]
+        );
+
+        // Messages already formatted for the profile are served from the cache
+        let outputs = VecDeque::from(vec![Output::Message(Text::from("|white|one|-|"))]);
+        assert_eq!(
+            formatter.format(&caps, outputs).await,
+            vec![Formatted::Message(Bytes::from("one\r\n"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_format_same_caps_concurrently() {
+        let formatter = Formatter::new(4);
+        let caps = ClientCaps::default();
+
+        let formatted = join_all((0..32).map(|index| {
+            let outputs = VecDeque::from(vec![
+                Output::Message(Text::from("|white|everyone|-|")),
+                Output::Message(Text::from(format!("client {}", index))),
+            ]);
+            formatter.format(&caps, outputs)
+        }))
+        .await;
+
+        for (index, formatted) in formatted.into_iter().enumerate() {
+            assert_eq!(
+                formatted,
+                vec![
+                    Formatted::Message(Bytes::from("everyone\r\n")),
+                    Formatted::Message(Bytes::from(format!("client {}\r\n", index))),
+                ]
+            );
+        }
+
+        // Every worker took a turn, and they share one cache for the profile
+        assert_eq!(formatter.next.load(Ordering::Relaxed), 32);
+        let cache = formatter.cache.lock().unwrap();
+        assert_eq!(cache.profiles.len(), 1);
+        assert_eq!(cache.profiles[&caps].len(), 33);
+    }
+}
@@ -1,35 +1,62 @@
+mod editor;
+mod format;
 mod protocol;
 
-use std::{io, sync::atomic::Ordering};
+use std::{io, sync::atomic::Ordering, time::Duration};
 
-use ascii::{AsciiString, IntoAsciiString, ToAsciiChar};
-use bytes::{Buf, Bytes};
+use ascii::AsciiString;
+use bytes::Bytes;
 use futures::{FutureExt, SinkExt, StreamExt};
 use thiserror::Error;
 use tokio::{
     net::{TcpListener, TcpStream, ToSocketAddrs},
     sync::mpsc,
     task::JoinHandle,
+    time::Instant,
 };
 use tokio_util::codec::Framed;
 
 use crate::{
-    color::colorize_telnet,
-    engine::{ClientMessage, EngineResponse, Output},
-    telnet::protocol::{Codec, Frame, Telnet},
+    config::TelnetConfig,
+    engine::{ClientMessage, EngineResponse, Text},
+    metrics::stats_incr,
+    telnet::{
+        editor::{Echo, LineEditor, LineTooLong},
+        format::{Formatted, Formatter},
+        protocol::{Codec, Frame, Telnet, NOP},
+    },
     ClientId, CLIENT_ID_COUNTER,
 };
 
+// Clients which have not finished option negotiation by this time, such as netcat, proceed to
+// login with the default terminal profile.
+const NEGOTIATION_TIMEOUT: Duration = Duration::from_secs(5);
+// Clients may not send more lines than this while negotiation is still under way.
+const MAX_PENDING_INPUTS: usize = 32;
+// Threads formatting output for all telnet clients.
+const FORMAT_WORKERS: usize = 4;
+
 pub struct Server {
     listener: TcpListener,
+    config: TelnetConfig,
+    formatter: Formatter,
 }
 
 impl Server {
     #[tracing::instrument(name = "initializing telnet server", skip_all)]
-    pub async fn new<A: ToSocketAddrs>(address: A) -> Result<Self, Error> {
+    pub async fn new<A: ToSocketAddrs>(address: A, config: TelnetConfig) -> Result<Self, Error> {
         let listener = TcpListener::bind(address).await?;
 
-        Ok(Server { listener })
+        Ok(Server {
+            listener,
+            config,
+            formatter: Formatter::new(FORMAT_WORKERS),
+        })
+    }
+
+    /// Replaces the connection settings used for clients accepted from now on.
+    pub fn set_config(&mut self, config: TelnetConfig) {
+        self.config = config;
     }
 
     #[tracing::instrument(name = "accepting telnet connection", skip_all)]
@@ -42,6 +69,8 @@ impl Server {
             .map(|connection| match connection {
                 Ok((stream, address)) => {
                     let client_id = ClientId(CLIENT_ID_COUNTER.fetch_add(1, Ordering::SeqCst));
+                    let config = self.config.clone();
+                    let formatter = self.formatter.clone();
 
                     let handle = tokio::spawn(async move {
                         tracing::info!("new client ({:?}): {:?}", client_id, address);
@@ -56,7 +85,15 @@ impl Server {
 
                         stream.set_nodelay(true).unwrap();
 
-                        process(client_id, stream, client_tx.clone(), engine_rx).await;
+                        process(
+                            client_id,
+                            stream,
+                            config,
+                            formatter,
+                            client_tx.clone(),
+                            engine_rx,
+                        )
+                        .await;
 
                         let message = ClientMessage::Disconnect(client_id);
                         client_tx.send(message).await.ok();
@@ -80,11 +117,13 @@ pub enum Error {
 
 #[tracing::instrument(
     name = "processing telnet connection",
-    skip(socket, client_tx, engine_rx)
+    skip(socket, config, formatter, client_tx, engine_rx)
 )]
 async fn process(
     client_id: ClientId,
     socket: TcpStream,
+    config: TelnetConfig,
+    formatter: Formatter,
     client_tx: mpsc::Sender<ClientMessage>,
     mut engine_rx: mpsc::Receiver<EngineResponse>,
 ) {
@@ -92,6 +131,8 @@ async fn process(
     let mut telnet = Telnet::new();
     let mut ready = false;
     let mut awaiting_input = false;
+    let mut sensitive_input = false;
+    let mut caps = telnet.capabilities();
 
     // Send initial telnet negotiation frames to the client to kick off negotiation
     for frame in telnet.initiate() {
@@ -100,11 +141,61 @@ async fn process(
         }
     }
 
-    let mut inputs = Vec::new();
-    let mut input_buffer = AsciiString::new();
+    let mut inputs: Vec<AsciiString> = Vec::new();
+    let mut editor = LineEditor::new();
+
+    let negotiation_timeout = tokio::time::sleep(NEGOTIATION_TIMEOUT);
+    tokio::pin!(negotiation_timeout);
+
+    // Keepalives expose half-open connections through failed writes, while clients which have
+    // not sent anything before the idle timeout are disconnected.
+    let keepalive_rate = config.keepalive_rate();
+    let mut keepalive = tokio::time::interval_at(Instant::now() + keepalive_rate, keepalive_rate);
+    let idle_timeout = tokio::time::sleep(config.idle_timeout());
+    tokio::pin!(idle_timeout);
 
     loop {
         tokio::select! {
+            _ = keepalive.tick() => {
+                let sent = tokio::time::timeout(keepalive_rate, framed.send(Frame::Command(NOP))).await;
+                if !matches!(sent, Ok(Ok(()))) {
+                    tracing::info!("keepalive failed, dropping client");
+                    stats_incr("telnet.keepalive_failed");
+                    break
+                }
+            }
+            _ = &mut idle_timeout => {
+                tracing::info!("client idle, dropping client");
+                stats_incr("telnet.idle_disconnect");
+
+                let frame = Frame::Data(Bytes::from("\r\nDisconnected for inactivity.\r\n"));
+                tokio::time::timeout(keepalive_rate, framed.send(frame)).await.ok();
+                break
+            }
+            _ = &mut negotiation_timeout, if !ready => {
+                tracing::info!("telnet negotiation timed out, using default terminal profile");
+                stats_incr("telnet.negotiation_fallback");
+
+                telnet.fallback();
+                if telnet.capabilities() != caps {
+                    caps = telnet.capabilities();
+                    if client_tx.send(ClientMessage::Capabilities(client_id, caps.clone())).await.is_err() {
+                        break
+                    }
+                }
+
+                ready = true;
+                if client_tx.send(ClientMessage::Ready(client_id)).await.is_err() {
+                    break
+                }
+
+                // Send any input received while negotiating
+                for input in inputs.drain(..) {
+                    if client_tx.send(ClientMessage::Input(client_id, Text::from(input))).await.is_err() {
+                        break
+                    }
+                }
+            }
             maybe_message = engine_rx.recv() => {
                 if let Some(message) = maybe_message {
                     match message {
@@ -117,28 +208,40 @@ async fn process(
                                 }
                             }
 
-                            // Send all output to the client
-                            for output in outputs {
-                                let message = match output {
-                                    Output::Message(message) => colorize_telnet(format!("|Gray69|{}|-|\r\n", message.as_str()).as_str(), telnet.color_support()),
-                                    // what to do to make telnet hide this input when sensitive?
-                                    Output::Prompt{format, ..} => {
+                            // Send all output to the client, formatted for what it supports
+                            for formatted in formatter.format(&caps, outputs).await {
+                                let data = match formatted {
+                                    Formatted::Message(data) => data,
+                                    Formatted::Prompt{data, sensitive} => {
                                         awaiting_input = true;
-                                        colorize_telnet(format!("|Gray69|{}|-|", format.as_str()).as_str(), telnet.color_support())
+                                        sensitive_input = sensitive;
+
+                                        // Compliant clients stop echoing input while the server
+                                        // claims to echo it, which hides sensitive input
+                                        let echo = if sensitive {
+                                            telnet.hide_input()
+                                        } else {
+                                            telnet.show_input()
+                                        };
+                                        if let Some(frame) = echo {
+                                            if framed.send(frame).await.is_err() {
+                                                break;
+                                            }
+                                        }
+
+                                        data
                                     }
                                 };
 
-                                match message.into_ascii_string() {
-                                    Ok(str) => {
-                                        let bytes: Vec<u8> = str.into();
-                                        if framed.send(Frame::Data(Bytes::from(bytes))).await.is_err() {
-                                            break;
-                                        }
-                                    },
-                                    Err(e) => tracing::error!("Engine returned non-ASCII string: \"{}\"", e),
+                                if framed.send(Frame::Data(data)).await.is_err() {
+                                    break
                                 }
                             }
                         }
+                        EngineResponse::Disconnect => {
+                            tracing::info!("engine closed connection");
+                            break
+                        }
                     }
                 } else {
                     let frame = Frame::Data(Bytes::from("\r\nServer shutting down. Thanks for playing. <3\r\n"));
@@ -149,6 +252,8 @@ async fn process(
                 }
             }
             maybe_frame = framed.next() => {
+                idle_timeout.as_mut().reset(Instant::now() + config.idle_timeout());
+
                 if let Some(frame) = maybe_frame {
                     match frame {
                         Ok(frame) => match frame {
@@ -167,40 +272,86 @@ async fn process(
                                     }
                                 }
                             }
-                            Frame::Data(mut data) => {
-                                while let Some(end_of_command) = data
-                                    .as_ref()
-                                    .windows(2)
-                                    .position(|b| b[0] == b'\r' && b[1] == b'\n')
-                                {
-                                    let rest_of_command = data.split_to(end_of_command);
-                                    append_input(rest_of_command, &mut input_buffer);
-                                    inputs.push(input_buffer);
-
-                                    data.advance(2);
-
-                                    input_buffer = AsciiString::new();
+                            Frame::Data(data) => {
+                                let echo = match (telnet.echoing(), sensitive_input) {
+                                    (false, _) => Echo::Off,
+                                    (true, true) => Echo::Hidden,
+                                    (true, false) => Echo::On,
+                                };
+
+                                let (lines, output) = match editor.input(data, echo) {
+                                    Ok(input) => input,
+                                    Err(LineTooLong) => {
+                                        tracing::warn!("client sent an overlong line, dropping client");
+                                        stats_incr("telnet.protocol_violation");
+                                        break
+                                    }
+                                };
+                                inputs.extend(lines);
+
+                                if !ready && inputs.len() > MAX_PENDING_INPUTS {
+                                    tracing::warn!("client sent too much input while negotiating, dropping client");
+                                    stats_incr("telnet.protocol_violation");
+                                    break
                                 }
 
-                                append_input(data, &mut input_buffer);
+                                if !output.is_empty() && framed.send(Frame::Data(output)).await.is_err() {
+                                    break
+                                }
                             }
                         },
+                        Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                            tracing::warn!("client violated the telnet protocol, dropping client: {}", e);
+                            stats_incr("telnet.protocol_violation");
+                            break;
+                        }
                         Err(_) => {
                             tracing::info!("client disconnected");
                             break;
                         }
                     }
 
+                    // Let the engine know about anything newly negotiated, such as a resized
+                    // window, before the client is ready
+                    if telnet.capabilities() != caps {
+                        caps = telnet.capabilities();
+                        if client_tx.send(ClientMessage::Capabilities(client_id, caps.clone())).await.is_err() {
+                            break
+                        }
+                    }
+
                     if ready {
                         // send input and do things
                         for input in inputs.drain(..) {
                             awaiting_input = false;
-                            if client_tx.send(ClientMessage::Input(client_id, input.to_string())).await.is_err() {
+
+                            // The client did not echo the end of the sensitive input, restore
+                            // echo and move past the prompt
+                            if sensitive_input {
+                                sensitive_input = false;
+                                if let Some(frame) = telnet.show_input() {
+                                    if framed.send(frame).await.is_err() {
+                                        break
+                                    }
+                                    if framed.send(Frame::Data(Bytes::from("\r\n"))).await.is_err() {
+                                        break
+                                    }
+                                }
+                            } else {
+                                editor.record(&input);
+                            }
+
+                            if client_tx.send(ClientMessage::Input(client_id, Text::from(input))).await.is_err() {
                                 break
                             }
                         }
                     } else if telnet.configured() {
                         ready = true;
+                        if let Some(frame) = telnet.begin_character_mode() {
+                            if framed.send(frame).await.is_err() {
+                                break
+                            }
+                        }
                         if client_tx.send(ClientMessage::Ready(client_id)).await.is_err() {
                             break
                         }
@@ -219,17 +370,3 @@ async fn process(
         }
     }
 }
-
-fn append_input(input: Bytes, buffer: &mut AsciiString) {
-    for byte in input {
-        if let Ok(char) = byte.to_ascii_char() {
-            if char.is_ascii_control() {
-                if matches!(char, ascii::AsciiChar::BackSpace) {
-                    buffer.pop();
-                }
-            } else {
-                buffer.push(char);
-            }
-        }
-    }
-}
@@ -9,7 +9,7 @@ use bytes::{Buf, BufMut, Bytes, BytesMut};
 use once_cell::sync::Lazy;
 use tokio_util::codec::{Decoder, Encoder};
 
-use crate::color::ColorSupport;
+use crate::{color::ColorSupport, world::types::player::ClientCaps};
 
 #[derive(Debug, Copy, Clone)]
 pub enum Negotiate {
@@ -46,16 +46,26 @@ impl TryFrom<u8> for Negotiate {
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
 pub enum OptionCode {
+    Echo,
+    SuppressGoAhead,
     TerminalType,
     Naws,
+    Linemode,
+    Mxp,
+    Gmcp,
     Unknown(u8),
 }
 
 impl OptionCode {
     fn byte(self) -> u8 {
         match self {
+            OptionCode::Echo => 1,
+            OptionCode::SuppressGoAhead => 3,
             OptionCode::TerminalType => 24,
             OptionCode::Naws => 31,
+            OptionCode::Linemode => 34,
+            OptionCode::Mxp => 91,
+            OptionCode::Gmcp => 201,
             OptionCode::Unknown(option) => option,
         }
     }
@@ -64,8 +74,13 @@ impl OptionCode {
 impl From<u8> for OptionCode {
     fn from(value: u8) -> Self {
         match value {
+            1 => OptionCode::Echo,
+            3 => OptionCode::SuppressGoAhead,
             24 => OptionCode::TerminalType,
             31 => OptionCode::Naws,
+            34 => OptionCode::Linemode,
+            91 => OptionCode::Mxp,
+            201 => OptionCode::Gmcp,
             option => OptionCode::Unknown(option),
         }
     }
@@ -148,19 +163,28 @@ impl Decoder for Codec {
                     }
                     SB => {
                         // Subnegotations resemble IAC SB <OPTION CODE> <DATA> IAC SE
-                        if src.len() > 4 {
-                            src.as_ref()
-                                .windows(2)
-                                .position(|b| b[0] == IAC && b[1] == SE)
-                                .map(|suffix_pos| {
-                                    let mut data = src.split_to(suffix_pos);
-                                    src.advance(2);
-
-                                    let prefix = data.split_to(3);
-                                    Frame::Subnegotiate(OptionCode::from(prefix[2]), data.freeze())
-                                })
-                        } else {
-                            None
+                        match src.get(3..).and_then(|rest| {
+                            rest.windows(2).position(|b| b[0] == IAC && b[1] == SE)
+                        }) {
+                            Some(suffix_pos) => {
+                                let mut data = src.split_to(suffix_pos + 3);
+                                src.advance(2);
+
+                                let prefix = data.split_to(3);
+                                Some(Frame::Subnegotiate(
+                                    OptionCode::from(prefix[2]),
+                                    data.freeze(),
+                                ))
+                            }
+                            // Clients cannot hold the connection's buffer open indefinitely with
+                            // an unterminated subnegotiation
+                            None if src.len() > MAX_SUBNEGOTIATION_LEN + 5 => {
+                                return Err(io::Error::new(
+                                    io::ErrorKind::InvalidData,
+                                    "subnegotiation exceeds maximum length",
+                                ));
+                            }
+                            None => None,
                         }
                     }
                     _ => {
@@ -214,16 +238,51 @@ bitflags! {
     }
 }
 
+impl TerminalFeatures {
+    /// The names of the features, as shown to scripts.
+    pub fn names(&self) -> Vec<String> {
+        [
+            (TerminalFeatures::ANSI, "ansi"),
+            (TerminalFeatures::VT100, "vt100"),
+            (TerminalFeatures::UTF_8, "utf_8"),
+            (TerminalFeatures::COLORS_256, "colors_256"),
+            (TerminalFeatures::MOUSE_TRACKING, "mouse_tracking"),
+            (TerminalFeatures::OSC_COLOR_PALETTE, "osc_color_palette"),
+            (TerminalFeatures::SCREEN_READER, "screen_reader"),
+            (TerminalFeatures::PROXY, "proxy"),
+            (TerminalFeatures::TRUE_COLOR, "true_color"),
+            (TerminalFeatures::MNES, "mnes"),
+            (TerminalFeatures::MSLP, "mslp"),
+        ]
+        .iter()
+        .filter(|(feature, _)| self.contains(*feature))
+        .map(|(_, name)| name.to_string())
+        .collect()
+    }
+}
+
 pub struct Telnet {
     options: Options,
+    echo: OptionState,
+    suppress_go_ahead: OptionState,
+    mxp: OptionState,
+    gmcp: OptionState,
+    character_mode: bool,
     terminal_selection_state: TerminalSelectionState,
+    width: Option<u16>,
 }
 
 impl Telnet {
     pub fn new() -> Self {
         Telnet {
             options: Options::default(),
+            echo: OptionState::No,
+            suppress_go_ahead: OptionState::No,
+            mxp: OptionState::No,
+            gmcp: OptionState::No,
+            character_mode: false,
             terminal_selection_state: TerminalSelectionState::Begin,
+            width: None,
         }
     }
 
@@ -232,15 +291,76 @@ impl Telnet {
         if let Some(frame) = self.options.enable(OptionCode::TerminalType) {
             frames.push(frame);
         }
+        if let Some(frame) = self.options.enable(OptionCode::Linemode) {
+            frames.push(frame);
+        }
+        if let Some(frame) = self.options.enable(OptionCode::Naws) {
+            frames.push(frame);
+        }
+
+        // ReMUD never sends go ahead
+        self.suppress_go_ahead = OptionState::WantYes;
+        frames.push(Frame::Negotiate(
+            Negotiate::Will,
+            OptionCode::SuppressGoAhead,
+        ));
+
+        // Offer clickable links to clients which understand MXP
+        self.mxp = OptionState::WantYes;
+        frames.push(Frame::Negotiate(Negotiate::Will, OptionCode::Mxp));
+
+        // Offer out of band data to clients which understand GMCP
+        self.gmcp = OptionState::WantYes;
+        frames.push(Frame::Negotiate(Negotiate::Will, OptionCode::Gmcp));
+
         frames
     }
 
     pub fn negotiate(&mut self, command: Negotiate, option: OptionCode) -> Vec<Frame> {
         let mut frames = Vec::new();
+
+        if option == OptionCode::Echo {
+            if let Some(response) = self.negotiate_echo(command) {
+                frames.push(response);
+            }
+            return frames;
+        }
+
+        if option == OptionCode::SuppressGoAhead
+            && matches!(command, Negotiate::Do | Negotiate::Dont)
+        {
+            if let Some(response) = self.negotiate_suppress_go_ahead(command) {
+                frames.push(response);
+            }
+            return frames;
+        }
+
+        if option == OptionCode::Mxp && matches!(command, Negotiate::Do | Negotiate::Dont) {
+            frames.extend(self.negotiate_mxp(command));
+            return frames;
+        }
+
+        if option == OptionCode::Gmcp && matches!(command, Negotiate::Do | Negotiate::Dont) {
+            if let Some(response) = self.negotiate_gmcp(command) {
+                frames.push(response);
+            }
+            return frames;
+        }
+
+        let linemode = self.options.enabled(OptionCode::Linemode);
+
         if let Some(response) = self.options.negotiate(command, option) {
             frames.push(response);
         }
 
+        // Clients which agree to LINEMODE edit lines locally and send them complete
+        if option == OptionCode::Linemode && !linemode && self.options.enabled(option) {
+            frames.push(Frame::Subnegotiate(
+                OptionCode::Linemode,
+                Bytes::from_static(&[LINEMODE_MODE, LINEMODE_MODE_EDIT]),
+            ));
+        }
+
         if self.options.disabled(OptionCode::TerminalType) {
             self.terminal_selection_state = TerminalSelectionState::Done(None);
         }
@@ -248,12 +368,194 @@ impl Telnet {
         frames
     }
 
+    /// Offers to echo input so compliant clients stop echoing locally, without echoing anything
+    /// back. Used to hide sensitive input such as passwords.
+    pub fn hide_input(&mut self) -> Option<Frame> {
+        if self.character_mode {
+            return None;
+        }
+
+        match self.echo {
+            OptionState::No | OptionState::WantNo => {
+                self.echo = OptionState::WantYes;
+                Some(Frame::Negotiate(Negotiate::Will, OptionCode::Echo))
+            }
+            OptionState::Yes | OptionState::WantYes => None,
+        }
+    }
+
+    /// Returns local echo to the client after sensitive input has been entered.
+    pub fn show_input(&mut self) -> Option<Frame> {
+        if self.character_mode {
+            return None;
+        }
+
+        match self.echo {
+            OptionState::Yes | OptionState::WantYes => {
+                self.echo = OptionState::WantNo;
+                Some(Frame::Negotiate(Negotiate::Wont, OptionCode::Echo))
+            }
+            OptionState::No | OptionState::WantNo => None,
+        }
+    }
+
+    /// Clients which suppress go ahead without editing lines locally send each key as it is
+    /// pressed. Terminals like this expect the server to echo and edit input, which is requested
+    /// once negotiation completes. MUD clients are assumed to edit lines themselves.
+    pub fn begin_character_mode(&mut self) -> Option<Frame> {
+        let mud_client = matches!(
+            self.terminal_selection_state,
+            TerminalSelectionState::Done(Some(TerminalType { mtts: true, .. }))
+        );
+
+        if !matches!(self.suppress_go_ahead, OptionState::Yes)
+            || self.options.enabled(OptionCode::Linemode)
+            || mud_client
+        {
+            return None;
+        }
+
+        self.character_mode = true;
+        self.echo = OptionState::WantYes;
+        Some(Frame::Negotiate(Negotiate::Will, OptionCode::Echo))
+    }
+
+    /// Returns true when the server is responsible for echoing the client's input.
+    pub fn echoing(&self) -> bool {
+        self.character_mode && matches!(self.echo, OptionState::Yes | OptionState::WantYes)
+    }
+
+    fn negotiate_suppress_go_ahead(&mut self, command: Negotiate) -> Option<Frame> {
+        match command {
+            Negotiate::Do => match self.suppress_go_ahead {
+                OptionState::WantYes | OptionState::Yes => {
+                    self.suppress_go_ahead = OptionState::Yes;
+                    None
+                }
+                OptionState::WantNo | OptionState::No => {
+                    self.suppress_go_ahead = OptionState::Yes;
+                    Some(Frame::Negotiate(
+                        Negotiate::Will,
+                        OptionCode::SuppressGoAhead,
+                    ))
+                }
+            },
+            _ => match self.suppress_go_ahead {
+                OptionState::Yes => {
+                    self.suppress_go_ahead = OptionState::No;
+                    Some(Frame::Negotiate(
+                        Negotiate::Wont,
+                        OptionCode::SuppressGoAhead,
+                    ))
+                }
+                _ => {
+                    self.suppress_go_ahead = OptionState::No;
+                    None
+                }
+            },
+        }
+    }
+
+    // MXP is performed by the server. Once a client agrees, MXP is started with an empty
+    // subnegotiation and locked mode is selected, so text is only parsed for tags on lines which
+    // select secure mode.
+    fn negotiate_mxp(&mut self, command: Negotiate) -> Vec<Frame> {
+        match command {
+            Negotiate::Do => {
+                let offered = matches!(self.mxp, OptionState::WantYes);
+                self.mxp = OptionState::Yes;
+
+                let mut frames = Vec::new();
+                if !offered {
+                    frames.push(Frame::Negotiate(Negotiate::Will, OptionCode::Mxp));
+                }
+                frames.push(Frame::Subnegotiate(OptionCode::Mxp, Bytes::new()));
+                frames.push(Frame::Data(Bytes::from_static(MXP_LOCKED_MODE)));
+                frames
+            }
+            _ => {
+                let enabled = matches!(self.mxp, OptionState::Yes);
+                self.mxp = OptionState::No;
+
+                if enabled {
+                    vec![Frame::Negotiate(Negotiate::Wont, OptionCode::Mxp)]
+                } else {
+                    Vec::new()
+                }
+            }
+        }
+    }
+
+    // GMCP messages are sent by the server, so clients answer with DO and DONT. Nothing is sent
+    // until a client agrees.
+    fn negotiate_gmcp(&mut self, command: Negotiate) -> Option<Frame> {
+        match command {
+            Negotiate::Do => {
+                let offered = matches!(self.gmcp, OptionState::WantYes | OptionState::Yes);
+                self.gmcp = OptionState::Yes;
+
+                if offered {
+                    None
+                } else {
+                    Some(Frame::Negotiate(Negotiate::Will, OptionCode::Gmcp))
+                }
+            }
+            _ => {
+                let enabled = matches!(self.gmcp, OptionState::Yes);
+                self.gmcp = OptionState::No;
+
+                if enabled {
+                    Some(Frame::Negotiate(Negotiate::Wont, OptionCode::Gmcp))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    // Echo is performed by the server, so clients answer with DO and DONT rather than WILL and
+    // WONT.
+    fn negotiate_echo(&mut self, command: Negotiate) -> Option<Frame> {
+        match command {
+            Negotiate::Do => match self.echo {
+                OptionState::WantYes => {
+                    self.echo = OptionState::Yes;
+                    None
+                }
+                OptionState::Yes => None,
+                OptionState::WantNo => {
+                    self.echo = OptionState::No;
+                    None
+                }
+                // Refuse to echo unless the server offered to.
+                OptionState::No => Some(Frame::Negotiate(Negotiate::Wont, OptionCode::Echo)),
+            },
+            Negotiate::Dont => match self.echo {
+                OptionState::Yes => {
+                    self.echo = OptionState::No;
+                    Some(Frame::Negotiate(Negotiate::Wont, OptionCode::Echo))
+                }
+                OptionState::WantYes | OptionState::WantNo => {
+                    self.echo = OptionState::No;
+                    None
+                }
+                OptionState::No => None,
+            },
+            Negotiate::Will => Some(Frame::Negotiate(Negotiate::Dont, OptionCode::Echo)),
+            Negotiate::Wont => None,
+        }
+    }
+
     pub fn subnegotiate(&mut self, option: OptionCode, mut data: Bytes) -> Vec<Frame> {
         let mut frames = Vec::new();
         if self.options.enabled(option) {
             match option {
                 OptionCode::TerminalType => {
                     if let TerminalSelectionState::List(list) = &mut self.terminal_selection_state {
+                        // Replies resemble IS <TERMINAL TYPE>
+                        if data.is_empty() {
+                            return frames;
+                        }
                         data.advance(1);
                         if let Some(response) = list.checked_add_type(data) {
                             frames.push(response);
@@ -279,7 +581,21 @@ impl Telnet {
                         }
                     }
                 }
-                OptionCode::Naws | OptionCode::Unknown(_) => (),
+                // The window size is sent as a 16-bit width followed by a 16-bit height
+                OptionCode::Naws => {
+                    if data.len() >= NAWS_LENGTH {
+                        let width = data.get_u16();
+                        // Zero means the client does not know its width
+                        self.width = if width > 0 { Some(width) } else { None };
+                    }
+                }
+                // Mode acknowledgements and special character lists are not needed
+                OptionCode::Linemode => (),
+                OptionCode::Echo
+                | OptionCode::SuppressGoAhead
+                | OptionCode::Mxp
+                | OptionCode::Gmcp
+                | OptionCode::Unknown(_) => (),
             }
         }
         frames
@@ -325,11 +641,67 @@ impl Telnet {
         frames
     }
 
+    /// Abandons any outstanding negotiation and selects the default terminal profile, for clients
+    /// which do not respond to negotiation.
+    pub fn fallback(&mut self) {
+        self.options.abandon();
+        self.suppress_go_ahead = OptionState::No;
+        self.mxp = OptionState::No;
+        self.gmcp = OptionState::No;
+        self.terminal_selection_state = TerminalSelectionState::Done(None);
+    }
+
     pub fn configured(&self) -> bool {
         let negotiating = self.options.negotiating();
         let terminal_selected =
             matches! {self.terminal_selection_state, TerminalSelectionState::Done(_)};
-        !negotiating && terminal_selected
+        let suppress_go_ahead_negotiating = matches!(
+            self.suppress_go_ahead,
+            OptionState::WantYes | OptionState::WantNo
+        );
+        let mxp_negotiating = matches!(self.mxp, OptionState::WantYes | OptionState::WantNo);
+        let gmcp_negotiating = matches!(self.gmcp, OptionState::WantYes | OptionState::WantNo);
+        !negotiating
+            && !suppress_go_ahead_negotiating
+            && !mxp_negotiating
+            && !gmcp_negotiating
+            && terminal_selected
+    }
+
+    /// Returns true when the client understands MXP, so output may contain clickable links.
+    pub fn mxp(&self) -> bool {
+        matches!(self.mxp, OptionState::Yes)
+    }
+
+    /// Returns true when the client understands GMCP, so out of band data may be sent to it.
+    pub fn gmcp(&self) -> bool {
+        matches!(self.gmcp, OptionState::Yes)
+    }
+
+    /// The width of the client's window in characters, if it has reported one.
+    pub fn width(&self) -> Option<u16> {
+        self.width
+    }
+
+    /// Everything negotiated with the client so far, for the engine to lay out output and for
+    /// scripts to tailor it.
+    pub fn capabilities(&self) -> ClientCaps {
+        let (terminal, features) = match &self.terminal_selection_state {
+            TerminalSelectionState::Done(Some(terminal_type)) => (
+                Some(String::from_utf8_lossy(&terminal_type.name).to_string()),
+                terminal_type.features.names(),
+            ),
+            _ => (None, Vec::new()),
+        };
+
+        ClientCaps {
+            width: self.width().map(usize::from),
+            color: self.color_support(),
+            terminal,
+            features,
+            mxp: self.mxp(),
+            gmcp: self.gmcp(),
+        }
     }
 
     pub fn color_support(&self) -> ColorSupport {
@@ -357,12 +729,23 @@ const WONT: u8 = 252;
 const WILL: u8 = 251;
 const SB: u8 = 250;
 const SE: u8 = 240;
+pub const NOP: u8 = 241;
 
 const TERMINAL_TYPE_SEND: u8 = 1;
+const LINEMODE_MODE: u8 = 1;
+const LINEMODE_MODE_EDIT: u8 = 1;
+const NAWS_LENGTH: usize = 4;
+const MXP_LOCKED_MODE: &[u8] = b"\x1b[7z";
+
+// Limits on what a client may make the server hold on to while decoding its input.
+const MAX_SUBNEGOTIATION_LEN: usize = 1024;
+const MAX_TERMINAL_TYPES: usize = 16;
 
 static ALLOWED_OPTIONS: Lazy<HashSet<OptionCode>> = Lazy::new(|| {
     let mut allowed = HashSet::new();
     allowed.insert(OptionCode::TerminalType);
+    allowed.insert(OptionCode::Linemode);
+    allowed.insert(OptionCode::Naws);
     allowed
 });
 
@@ -403,6 +786,16 @@ impl Options {
             || !self.queue_opposite.is_empty();
     }
 
+    // Treats options still being negotiated as disabled.
+    fn abandon(&mut self) {
+        for state in self.state.values_mut() {
+            if matches!(state, OptionState::WantNo | OptionState::WantYes) {
+                *state = OptionState::No;
+            }
+        }
+        self.queue_opposite.clear();
+    }
+
     fn enable(&mut self, option: OptionCode) -> Option<Frame> {
         if !ALLOWED_OPTIONS.contains(&option) {
             return None;
@@ -525,7 +918,10 @@ impl TerminalTypes {
     }
 
     fn checked_add_type(&mut self, ttype: Bytes) -> Option<Frame> {
-        if self.types.last().map_or(false, |last| last == &ttype) {
+        // Clients repeat the last type once they have listed them all, but some cycle forever.
+        if self.types.last().map_or(false, |last| last == &ttype)
+            || self.types.len() >= MAX_TERMINAL_TYPES
+        {
             tracing::info!("TType list: {:?}", self.types);
         } else {
             self.types.push(ttype);
@@ -553,7 +949,7 @@ impl TerminalTypes {
 
             let name = self.types.last().expect("last TType exists").clone();
             let mut mtts_flags = name.clone();
-            mtts_flags.advance(5);
+            mtts_flags.advance(mtts_flags.len().min(5));
             if let Ok(flag_string) = str::from_utf8(&mtts_flags) {
                 if let Ok(int_flags) = flag_string.parse::<u16>() {
                     if let Some(flags) = TerminalFeatures::from_bits(int_flags) {
@@ -659,3 +1055,244 @@ fn supports_true_color(terminal_type: &Bytes) -> bool {
 fn supports_256_color(terminal_type: &Bytes) -> bool {
     terminal_type.to_ascii_uppercase().ends_with(b"256COLOR")
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    const CASES: usize = 500;
+
+    // Feeds the input to a codec in the given chunks, decoding as many frames as possible after
+    // each one, as a connection would.
+    fn decode_chunks(input: &[u8], splits: &[usize]) -> io::Result<Vec<Frame>> {
+        let mut codec = Codec;
+        let mut src = BytesMut::new();
+        let mut frames = Vec::new();
+        let mut start = 0;
+
+        for end in splits.iter().copied().chain(std::iter::once(input.len())) {
+            src.extend_from_slice(&input[start..end]);
+            start = end;
+
+            while let Some(frame) = codec.decode(&mut src)? {
+                frames.push(frame);
+            }
+
+            assert!(src.len() <= MAX_SUBNEGOTIATION_LEN + 5);
+        }
+
+        Ok(frames)
+    }
+
+    // Encodes frames back to bytes with adjacent data merged, since data frames end wherever the
+    // input happened to be split.
+    fn normalize(frames: Vec<Frame>) -> Vec<Bytes> {
+        let mut normalized: Vec<Bytes> = Vec::new();
+        let mut data = BytesMut::new();
+
+        for frame in frames {
+            match frame {
+                Frame::Data(bytes) => data.extend_from_slice(&bytes),
+                frame => {
+                    if !data.is_empty() {
+                        normalized.push(data.split().freeze());
+                    }
+                    normalized.push(Bytes::from(frame));
+                }
+            }
+        }
+        if !data.is_empty() {
+            normalized.push(data.freeze());
+        }
+
+        normalized
+    }
+
+    // Bytes weighted towards those with meaning to the protocol.
+    fn random_input(rng: &mut StdRng, len: usize) -> Vec<u8> {
+        (0..len)
+            .map(|_| match rng.gen_range(0..8) {
+                0 | 1 => IAC,
+                2 => SB,
+                3 => SE,
+                4 => [WILL, WONT, DO, DONT][rng.gen_range(0..4)],
+                _ => rng.gen(),
+            })
+            .collect()
+    }
+
+    fn random_splits(rng: &mut StdRng, len: usize) -> Vec<usize> {
+        let mut splits: Vec<usize> = (0..rng.gen_range(0..8))
+            .map(|_| rng.gen_range(0..=len))
+            .collect();
+        splits.sort_unstable();
+        splits
+    }
+
+    #[test]
+    fn test_decode_arbitrary_input() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..CASES {
+            let len = rng.gen_range(0..64);
+            let input = random_input(&mut rng, len);
+            let splits = random_splits(&mut rng, input.len());
+
+            // Decoding must not panic, and results must not depend on how the input is split.
+            let whole = decode_chunks(&input, &[]).map(normalize);
+            let split = decode_chunks(&input, &splits).map(normalize);
+            match (whole, split) {
+                (Ok(whole), Ok(split)) => assert_eq!(whole, split, "input: {:?}", input),
+                (Err(_), Err(_)) => (),
+                (whole, split) => panic!("{:?} != {:?} for input {:?}", whole, split, input),
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_partial_frames() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let frames = vec![
+            Frame::Data(Bytes::from_static(b"look\r\n")),
+            Frame::Negotiate(Negotiate::Will, OptionCode::TerminalType),
+            Frame::Subnegotiate(OptionCode::TerminalType, Bytes::from_static(b"\0xterm")),
+            Frame::Command(NOP),
+            Frame::Subnegotiate(OptionCode::Naws, Bytes::from_static(&[0, 80, 0, 24])),
+            Frame::Data(Bytes::from_static(b"say hi\r\n")),
+        ];
+        let input: Vec<u8> = frames
+            .iter()
+            .cloned()
+            .flat_map(|frame| Bytes::from(frame).to_vec())
+            .collect();
+        let expected = normalize(frames);
+
+        for _ in 0..CASES {
+            let splits = random_splits(&mut rng, input.len());
+            let decoded = decode_chunks(&input, &splits).unwrap();
+            assert_eq!(normalize(decoded), expected, "splits: {:?}", splits);
+        }
+    }
+
+    #[test]
+    fn test_unterminated_subnegotiation() {
+        let mut input = vec![IAC, SB, OptionCode::TerminalType.byte()];
+        input.extend(std::iter::repeat(b'a').take(MAX_SUBNEGOTIATION_LEN));
+        assert!(decode_chunks(&input, &[]).unwrap().is_empty());
+
+        input.extend(b"aaa");
+        let error = decode_chunks(&input, &[]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    fn negotiating_terminal_type() -> Telnet {
+        let mut telnet = Telnet::new();
+        telnet.initiate();
+        telnet.negotiate(Negotiate::Will, OptionCode::TerminalType);
+        telnet.configure();
+        telnet
+    }
+
+    #[test]
+    fn test_subnegotiate_malformed_terminal_types() {
+        let mut telnet = negotiating_terminal_type();
+        assert!(telnet
+            .subnegotiate(OptionCode::TerminalType, Bytes::new())
+            .is_empty());
+
+        // MTTS without any flags
+        telnet.subnegotiate(OptionCode::TerminalType, Bytes::from_static(b"\0MTTS"));
+        telnet.subnegotiate(OptionCode::TerminalType, Bytes::from_static(b"\0MTTS"));
+        assert!(matches!(
+            telnet.terminal_selection_state,
+            TerminalSelectionState::Done(Some(TerminalType { mtts: true, .. }))
+        ));
+
+        // A client which never repeats itself stops being asked for more types.
+        let mut telnet = negotiating_terminal_type();
+        for index in 0..MAX_TERMINAL_TYPES {
+            let ttype = Bytes::from(format!("\0type-{}", index));
+            assert_eq!(
+                telnet.subnegotiate(OptionCode::TerminalType, ttype).len(),
+                1
+            );
+        }
+        telnet.subnegotiate(OptionCode::TerminalType, Bytes::from_static(b"\0type-last"));
+        assert!(matches!(
+            telnet.terminal_selection_state,
+            TerminalSelectionState::Done(None)
+        ));
+    }
+
+    #[test]
+    fn test_negotiate_mxp() {
+        let mut telnet = Telnet::new();
+        let frames = telnet.initiate();
+        assert!(frames
+            .iter()
+            .any(|frame| matches!(frame, Frame::Negotiate(Negotiate::Will, OptionCode::Mxp))));
+        assert!(!telnet.mxp());
+
+        // Agreeing starts MXP in locked mode
+        let frames = telnet.negotiate(Negotiate::Do, OptionCode::Mxp);
+        assert!(matches!(
+            frames.as_slice(),
+            [Frame::Subnegotiate(OptionCode::Mxp, data), Frame::Data(mode)]
+                if data.is_empty() && mode.as_ref() == MXP_LOCKED_MODE
+        ));
+        assert!(telnet.mxp());
+
+        let frames = telnet.negotiate(Negotiate::Dont, OptionCode::Mxp);
+        assert!(matches!(
+            frames.as_slice(),
+            [Frame::Negotiate(Negotiate::Wont, OptionCode::Mxp)]
+        ));
+        assert!(!telnet.mxp());
+
+        // Refusing is not answered
+        let mut telnet = Telnet::new();
+        telnet.initiate();
+        assert!(telnet
+            .negotiate(Negotiate::Dont, OptionCode::Mxp)
+            .is_empty());
+        assert!(!telnet.mxp());
+    }
+
+    #[test]
+    fn test_negotiate_gmcp() {
+        let mut telnet = Telnet::new();
+        let frames = telnet.initiate();
+        assert!(frames
+            .iter()
+            .any(|frame| matches!(frame, Frame::Negotiate(Negotiate::Will, OptionCode::Gmcp))));
+        assert!(!telnet.capabilities().gmcp);
+
+        assert!(telnet.negotiate(Negotiate::Do, OptionCode::Gmcp).is_empty());
+        assert!(telnet.capabilities().gmcp);
+
+        let frames = telnet.negotiate(Negotiate::Dont, OptionCode::Gmcp);
+        assert!(matches!(
+            frames.as_slice(),
+            [Frame::Negotiate(Negotiate::Wont, OptionCode::Gmcp)]
+        ));
+        assert!(!telnet.capabilities().gmcp);
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let mut telnet = Telnet::new();
+        telnet.initiate();
+        telnet.negotiate(Negotiate::Will, OptionCode::Naws);
+        telnet.subnegotiate(OptionCode::Naws, Bytes::from_static(&[0, 120, 0, 40]));
+        telnet.negotiate(Negotiate::Do, OptionCode::Mxp);
+
+        let caps = telnet.capabilities();
+        assert_eq!(caps.width, Some(120));
+        assert!(caps.mxp);
+        assert!(!caps.gmcp);
+        assert_eq!(caps.terminal, None);
+    }
+}
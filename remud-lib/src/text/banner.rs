@@ -0,0 +1,154 @@
+use itertools::Itertools;
+
+use crate::text::clip;
+
+// Every glyph is this many rows tall.
+const GLYPH_HEIGHT: usize = 5;
+// Blank columns between the letters of a word, and between words on the same row.
+const LETTER_GAP: usize = 1;
+const WORD_GAP: usize = 3;
+// Windows narrower than the widest glyph are sent the text as written.
+const MIN_BANNER_WIDTH: usize = 5;
+
+type Glyph = [&'static str; GLYPH_HEIGHT];
+
+const UNKNOWN: Glyph = [" ### ", "#   #", "  ## ", "     ", "  #  "];
+
+// A block font in the style of figlet's "banner". Letters are drawn in uppercase.
+fn glyph(c: char) -> Glyph {
+    match c.to_ascii_uppercase() {
+        'A' => [" ### ", "#   #", "#####", "#   #", "#   #"],
+        'B' => ["#### ", "#   #", "#### ", "#   #", "#### "],
+        'C' => [" ####", "#    ", "#    ", "#    ", " ####"],
+        'D' => ["#### ", "#   #", "#   #", "#   #", "#### "],
+        'E' => ["#####", "#    ", "#### ", "#    ", "#####"],
+        'F' => ["#####", "#    ", "#### ", "#    ", "#    "],
+        'G' => [" ####", "#    ", "#  ##", "#   #", " ####"],
+        'H' => ["#   #", "#   #", "#####", "#   #", "#   #"],
+        'I' => ["###", " # ", " # ", " # ", "###"],
+        'J' => ["  ###", "    #", "    #", "#   #", " ### "],
+        'K' => ["#   #", "#  # ", "###  ", "#  # ", "#   #"],
+        'L' => ["#    ", "#    ", "#    ", "#    ", "#####"],
+        'M' => ["#   #", "## ##", "# # #", "#   #", "#   #"],
+        'N' => ["#   #", "##  #", "# # #", "#  ##", "#   #"],
+        'O' => [" ### ", "#   #", "#   #", "#   #", " ### "],
+        'P' => ["#### ", "#   #", "#### ", "#    ", "#    "],
+        'Q' => [" ### ", "#   #", "# # #", "#  # ", " ## #"],
+        'R' => ["#### ", "#   #", "#### ", "#  # ", "#   #"],
+        'S' => [" ####", "#    ", " ### ", "    #", "#### "],
+        'T' => ["#####", "  #  ", "  #  ", "  #  ", "  #  "],
+        'U' => ["#   #", "#   #", "#   #", "#   #", " ### "],
+        'V' => ["#   #", "#   #", "#   #", " # # ", "  #  "],
+        'W' => ["#   #", "#   #", "# # #", "## ##", "#   #"],
+        'X' => ["#   #", " # # ", "  #  ", " # # ", "#   #"],
+        'Y' => ["#   #", " # # ", "  #  ", "  #  ", "  #  "],
+        'Z' => ["#####", "   # ", "  #  ", " #   ", "#####"],
+        '0' => [" ### ", "#  ##", "# # #", "##  #", " ### "],
+        '1' => [" # ", "## ", " # ", " # ", "###"],
+        '2' => [" ### ", "#   #", "  ## ", " #   ", "#####"],
+        '3' => ["#### ", "    #", " ### ", "    #", "#### "],
+        '4' => ["#   #", "#   #", "#####", "    #", "    #"],
+        '5' => ["#####", "#    ", "#### ", "    #", "#### "],
+        '6' => [" ### ", "#    ", "#### ", "#   #", " ### "],
+        '7' => ["#####", "    #", "   # ", "  #  ", "  #  "],
+        '8' => [" ### ", "#   #", " ### ", "#   #", " ### "],
+        '9' => [" ### ", "#   #", " ####", "    #", " ### "],
+        '!' => ["#", "#", "#", " ", "#"],
+        '.' => [" ", " ", " ", " ", "#"],
+        ',' => ["  ", "  ", "  ", " #", "# "],
+        '\'' => ["#", "#", " ", " ", " "],
+        ':' => [" ", "#", " ", "#", " "],
+        '-' => ["    ", "    ", "####", "    ", "    "],
+        '/' => ["    #", "   # ", "  #  ", " #   ", "#    "],
+        _ => UNKNOWN,
+    }
+}
+
+// Draws a word as rows of glyphs.
+fn render_word(word: &str) -> Vec<String> {
+    let glyphs = word.chars().map(glyph).collect_vec();
+
+    (0..GLYPH_HEIGHT)
+        .map(|row| {
+            glyphs
+                .iter()
+                .map(|glyph| glyph[row])
+                .join(" ".repeat(LETTER_GAP).as_str())
+        })
+        .collect_vec()
+}
+
+/// Renders text in large block letters for login screens and announcements. Words are moved to
+/// a new row of letters when they would not fit in the window, and words too wide for the window
+/// on their own are cut off. Windows too narrow for any letter are sent the text as written.
+pub fn banner(text: &str, width: usize) -> String {
+    let words = text.split_whitespace().collect_vec();
+
+    if width < MIN_BANNER_WIDTH || words.is_empty() {
+        return text.trim().to_string();
+    }
+
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut current: Option<Vec<String>> = None;
+
+    for word in words.into_iter().map(render_word) {
+        current = match current.take() {
+            Some(row) if row[0].len() + WORD_GAP + word[0].len() <= width => Some(
+                row.into_iter()
+                    .zip(word)
+                    .map(|(line, part)| format!("{}{}{}", line, " ".repeat(WORD_GAP), part))
+                    .collect_vec(),
+            ),
+            Some(row) => {
+                rows.push(row);
+                Some(word)
+            }
+            None => Some(word),
+        };
+    }
+    rows.extend(current);
+
+    rows.into_iter()
+        .map(|row| {
+            row.iter()
+                .map(|line| clip(line, width).trim_end().to_string())
+                .join("\r\n")
+        })
+        .join("\r\n\r\n")
+}
+
+/// Fits ASCII art to the window, cutting off lines which are too wide.
+pub fn fit_art(art: &str, width: usize) -> String {
+    art.split("\r\n").map(|line| clip(line, width)).join("\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_banner() {
+        assert_eq!(
+            banner("hi", 80),
+            "#   # ###\r\n#   #  #\r\n#####  #\r\n#   #  #\r\n#   # ###"
+        );
+        assert_eq!(banner("hi", 3), "hi");
+    }
+
+    #[test]
+    fn test_banner_wraps_words() {
+        let rendered = banner("hi hi", 12);
+        assert_eq!(rendered.split("\r\n\r\n").count(), 2);
+        assert!(rendered.split("\r\n").all(|line| line.len() <= 12));
+
+        let rendered = banner("welcome", 12);
+        assert_eq!(rendered.split("\r\n").count(), GLYPH_HEIGHT);
+        assert!(rendered.split("\r\n").all(|line| line.len() <= 12));
+    }
+
+    #[test]
+    fn test_fit_art() {
+        assert_eq!(fit_art("/\\_/\\\r\n( o.o )", 5), "/\\_/\\\r\n( o.o");
+        assert_eq!(fit_art("||||", 1), "||");
+    }
+}
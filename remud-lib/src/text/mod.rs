@@ -0,0 +1,451 @@
+pub mod banner;
+
+use std::borrow::Cow;
+
+use itertools::Itertools;
+
+use crate::{color::COLOR_TAG_MATCHER, markup::strip_links};
+
+/// The window width assumed for clients which have not reported one.
+pub const DEFAULT_WIDTH: usize = 80;
+
+// Spaces before each row, and between columns
+const INDENT: usize = 2;
+const COLUMN_GAP: usize = 2;
+const ELLIPSIS: &str = "...";
+// Truncated columns keep at least one character ahead of the ellipsis.
+const MIN_TRUNCATED_WIDTH: usize = ELLIPSIS.len() + 1;
+
+/// Formats the title at the top of an info screen.
+pub fn header(title: &str) -> String {
+    format!("|white||bold|{}|-||-|", title)
+}
+
+/// The number of characters a message occupies once its color and style tags are rendered.
+pub fn visible_width(message: &str) -> usize {
+    strip_colors(message).chars().count()
+}
+
+/// Removes color, style, and link markup from a message, leaving the text a player without color
+/// support would read. Messages written to logs or served over the web pass through this first.
+pub fn plain(message: &str) -> String {
+    strip_colors(strip_links(message).as_str()).into_owned()
+}
+
+fn strip_colors(message: &str) -> Cow<'_, str> {
+    COLOR_TAG_MATCHER.replace_all(message, |captures: &regex::Captures| {
+        if captures.name("escape").is_some() {
+            "|"
+        } else {
+            ""
+        }
+    })
+}
+
+/// Shortens a message to the given rendered width, ending it with an ellipsis. Tags opened before
+/// the cut are closed so their colors and styles do not spill into the following text.
+pub fn truncate(message: &str, width: usize) -> String {
+    cut(message, width, ELLIPSIS)
+}
+
+/// Shortens a message to the given rendered width without marking the cut, for text such as
+/// ASCII art where an ellipsis would look out of place.
+pub fn clip(message: &str, width: usize) -> String {
+    cut(message, width, "")
+}
+
+fn cut(message: &str, width: usize, marker: &str) -> String {
+    if visible_width(message) <= width {
+        return message.to_string();
+    }
+
+    let keep = width.saturating_sub(marker.len());
+    let mut truncated = String::new();
+    let mut kept = 0;
+    let mut open = 0;
+
+    let mut tags = COLOR_TAG_MATCHER.captures_iter(message).peekable();
+    let mut index = 0;
+
+    while index < message.len() {
+        let tag = tags
+            .peek()
+            .map(|captures| captures.get(0).unwrap())
+            .filter(|tag| tag.start() == index);
+
+        if let Some(tag) = tag {
+            let captures = tags.next().unwrap();
+
+            if captures.name("escape").is_some() {
+                if kept == keep {
+                    break;
+                }
+                truncated.push_str(tag.as_str());
+                kept += 1;
+            } else if captures.name("clear").is_some() {
+                if open > 0 {
+                    truncated.push_str(tag.as_str());
+                    open -= 1;
+                }
+            } else {
+                truncated.push_str(tag.as_str());
+                open += 1;
+            }
+
+            index = tag.end();
+        } else {
+            if kept == keep {
+                break;
+            }
+            let c = message[index..].chars().next().unwrap();
+            truncated.push(c);
+            kept += 1;
+            index += c.len_utf8();
+        }
+    }
+
+    for _ in 0..open {
+        truncated.push_str("|-|");
+    }
+    truncated.push_str(marker);
+
+    truncated
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+struct Column {
+    header: String,
+    align: Align,
+    truncate: bool,
+}
+
+/// Lays out rows of text in aligned columns. Cells are measured by their rendered width, so they
+/// may contain color and style tags. When the table is wider than the window, truncated columns
+/// are shortened, widest first, until it fits.
+pub struct Table {
+    width: usize,
+    gap: usize,
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Creates a table for a window of the given width. Rows are indented by two spaces to sit
+    /// beneath a header.
+    pub fn new(width: usize) -> Self {
+        Table {
+            width,
+            gap: COLUMN_GAP,
+            columns: Vec::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Creates a table of labelled fields, as shown on info screens.
+    pub fn fields(width: usize) -> Self {
+        Table::new(width)
+            .gap(1)
+            .column("", Align::Right)
+            .column("", Align::Left)
+    }
+
+    pub fn gap(mut self, gap: usize) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Adds a column which is never shortened. Columns with empty headers show no header row.
+    pub fn column(mut self, header: &str, align: Align) -> Self {
+        self.columns.push(Column {
+            header: header.to_string(),
+            align,
+            truncate: false,
+        });
+        self
+    }
+
+    /// Adds a column which is shortened with an ellipsis when the table does not fit the window.
+    pub fn truncated_column(mut self, header: &str, align: Align) -> Self {
+        self.columns.push(Column {
+            header: header.to_string(),
+            align,
+            truncate: true,
+        });
+        self
+    }
+
+    pub fn row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// Adds a labelled field to a table created with `fields`.
+    pub fn field(&mut self, label: &str, value: String) {
+        self.rows.push(vec![format!("|white|{}|-|:", label), value]);
+    }
+
+    /// Adds a labelled field with a value on each row, or "none" if there are no values.
+    pub fn list_field(&mut self, label: &str, values: Vec<String>) {
+        if values.is_empty() {
+            self.field(label, "none".to_string());
+        }
+
+        for (index, value) in values.into_iter().enumerate() {
+            if index == 0 {
+                self.field(label, value);
+            } else {
+                self.rows.push(vec![String::new(), value]);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn render(&self) -> String {
+        let widths = self.widths();
+
+        let headers = if self.columns.iter().any(|column| !column.header.is_empty()) {
+            let headers = self
+                .columns
+                .iter()
+                .map(|column| format!("|white|{}|-|", column.header.replace('|', "||")))
+                .collect_vec();
+            Some(self.render_row(&headers, &widths))
+        } else {
+            None
+        };
+
+        headers
+            .into_iter()
+            .chain(self.rows.iter().map(|row| self.render_row(row, &widths)))
+            .join("\r\n")
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        let mut widths = self
+            .columns
+            .iter()
+            .map(|column| column.header.chars().count())
+            .collect_vec();
+
+        for row in self.rows.iter() {
+            for (cell, width) in row.iter().zip(widths.iter_mut()) {
+                *width = (*width).max(visible_width(cell));
+            }
+        }
+
+        let gaps = self.gap * self.columns.len().saturating_sub(1);
+        let available = self.width.saturating_sub(INDENT + gaps);
+        let mut excess = widths.iter().sum::<usize>().saturating_sub(available);
+
+        while excess > 0 {
+            let widest = widths
+                .iter_mut()
+                .zip(self.columns.iter())
+                .filter(|(width, column)| column.truncate && **width > MIN_TRUNCATED_WIDTH)
+                .map(|(width, _)| width)
+                .max_by_key(|width| **width);
+
+            match widest {
+                Some(width) => {
+                    *width -= 1;
+                    excess -= 1;
+                }
+                None => break,
+            }
+        }
+
+        widths
+    }
+
+    fn render_row(&self, row: &[String], widths: &[usize]) -> String {
+        let line = self
+            .columns
+            .iter()
+            .zip(widths.iter())
+            .enumerate()
+            .map(|(index, (column, width))| {
+                let cell = row.get(index).map(String::as_str).unwrap_or_default();
+                let cell = if column.truncate {
+                    truncate(cell, *width)
+                } else {
+                    cell.to_string()
+                };
+
+                let padding = " ".repeat(width.saturating_sub(visible_width(cell.as_str())));
+                match column.align {
+                    Align::Left => format!("{}{}", cell, padding),
+                    Align::Right => format!("{}{}", padding, cell),
+                }
+            })
+            .join(" ".repeat(self.gap).as_str());
+
+        format!("{}{}", " ".repeat(INDENT), line.trim_end())
+    }
+}
+
+pub fn sorted_word_list(mut words: Vec<String>) -> String {
+    words.sort();
+    word_list(words)
+}
+
+/// Joins words into a list in the order given, e.g. "a, b, and c".
+pub fn word_list(mut words: Vec<String>) -> String {
+    if words.is_empty() {
+        String::new()
+    } else if words.len() == 1 {
+        words.pop().unwrap()
+    } else if words.len() == 2 {
+        words.join(" and ")
+    } else {
+        let last = words.pop().unwrap();
+        let joined = words.join(", ");
+        format!("{}, and {}", joined, last)
+    }
+}
+
+/// Formats a number of seconds as a short duration, e.g. "3h 12m", "4m 5s", or "9s".
+pub fn format_duration(seconds: i64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+// https://nitschinger.at/Text-Analysis-in-Rust-Tokenization/
+pub struct Tokenizer<'a> {
+    input: &'a str,
+    byte_offset: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Tokenizer {
+            input,
+            byte_offset: 0,
+        }
+    }
+
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.byte_offset..]
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        // Accounts for skipped whitespace at the beginning of the next token
+        let mut skipped_bytes = 0;
+
+        // Iterate through whitespace indices with various offsets
+        for (byte_index, c) in self.input[self.byte_offset..]
+            .char_indices()
+            .filter(|(_, c)| c.is_whitespace())
+        {
+            let char_len = c.len_utf8();
+
+            // Leading whitespace should be consumed
+            if byte_index - skipped_bytes == 0 {
+                skipped_bytes += char_len;
+                continue;
+            }
+
+            // We found non-leading whitespace, return the token in between
+            let slice =
+                &self.input[self.byte_offset + skipped_bytes..self.byte_offset + byte_index];
+            self.byte_offset += byte_index + char_len;
+            return Some(slice);
+        }
+
+        // If there is no trailing whitespace, consume the rest as the final token
+        if self.byte_offset < self.input.len() {
+            let slice = &self.input[self.byte_offset + skipped_bytes..];
+            self.byte_offset = self.input.len();
+            Some(slice)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain() {
+        assert_eq!(plain("a rusty lantern"), "a rusty lantern");
+        assert_eq!(plain("|red|a |bold|rusty|-| lantern|-|"), "a rusty lantern");
+        assert_eq!(plain("|#FF0000|red|-| and |123|blue|-|"), "red and blue");
+        assert_eq!(plain("a ||rusty|| lantern"), "a |rusty| lantern");
+        assert_eq!(
+            plain("|white|take |send take lantern|lantern|/send||-|"),
+            "take lantern"
+        );
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("lantern", 10), "lantern");
+        assert_eq!(truncate("a rusty lantern", 10), "a rusty...");
+        assert_eq!(truncate("a ||rusty|| lantern", 6), "a ||...");
+    }
+
+    #[test]
+    fn test_truncate_closes_tags() {
+        assert_eq!(
+            truncate("|red|a |bold|rusty|-| lantern|-|", 8),
+            "|red|a |bold|rus|-||-|..."
+        );
+        assert_eq!(truncate("|red|red|-| lantern", 6), "|red|red|-|...");
+    }
+
+    #[test]
+    fn test_clip() {
+        assert_eq!(clip("lantern", 10), "lantern");
+        assert_eq!(clip("|red|a rusty|-| lantern", 4), "|red|a ru|-|");
+    }
+
+    #[test]
+    fn test_table_alignment() {
+        let mut table = Table::new(DEFAULT_WIDTH)
+            .column("Name", Align::Left)
+            .column("Count", Align::Right);
+        table.row(vec!["|green|lantern|-|".to_string(), "12".to_string()]);
+        table.row(vec!["key".to_string(), "1".to_string()]);
+
+        assert_eq!(
+            table.render(),
+            "  |white|Name|-|     |white|Count|-|\r\n  |green|lantern|-|     12\r\n  key          \
+             1"
+        );
+    }
+
+    #[test]
+    fn test_table_shrinks_to_width() {
+        let mut table = Table::new(20)
+            .truncated_column("Name", Align::Left)
+            .column("Flags", Align::Left);
+        table.row(vec![
+            "a very long name indeed".to_string(),
+            "fixed".to_string(),
+        ]);
+
+        assert_eq!(
+            table.render(),
+            "  |white|Name|-|         |white|Flags|-|\r\n  a very l...  fixed"
+        );
+    }
+}
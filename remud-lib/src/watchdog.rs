@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::{sync::mpsc, time::interval};
+
+use crate::{
+    config::WatchdogConfig,
+    engine::{ClientMessage, Heartbeat},
+    metrics::{stats_gauge, stats_incr},
+    web::WebMessage,
+    CLIENT_QUEUE_SIZE, WEB_QUEUE_SIZE,
+};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Serialize)]
+struct WebhookAlert<'a> {
+    text: &'a str,
+}
+
+/// Checks the engine's heartbeat every second. When the engine stops finishing ticks, the stall is
+/// reported once until ticks resume, and the server is asked to restart through `stalled_tx` if
+/// configured to. The depths of the engine's queues are reported each check, stalled or not.
+pub(crate) async fn run_watchdog(
+    config: WatchdogConfig,
+    heartbeat: Heartbeat,
+    client_tx: mpsc::Sender<ClientMessage>,
+    web_tx: mpsc::Sender<WebMessage>,
+    stalled_tx: mpsc::Sender<()>,
+) {
+    let http_client = reqwest::Client::new();
+    let mut ticker = interval(CHECK_INTERVAL);
+    let mut stalled = false;
+
+    loop {
+        ticker.tick().await;
+
+        stats_gauge(
+            "engine.client_queue_depth",
+            (CLIENT_QUEUE_SIZE - client_tx.capacity()) as u64,
+        );
+        stats_gauge(
+            "engine.web_queue_depth",
+            (WEB_QUEUE_SIZE - web_tx.capacity()) as u64,
+        );
+
+        // The engine has not finished its first tick since starting, which includes loading the
+        // world, so there is nothing to compare against yet.
+        let since_last = match heartbeat.since_last() {
+            Some(since_last) => since_last,
+            None => continue,
+        };
+
+        if since_last < config.stall_timeout() {
+            if stalled {
+                tracing::warn!("engine recovered after stalling");
+                stalled = false;
+            }
+            continue;
+        }
+
+        if stalled {
+            continue;
+        }
+        stalled = true;
+
+        let message = format!(
+            "ReMUD engine stalled: no tick for {}s while in stage '{}', client queue {}/{}, web \
+             queue {}/{}",
+            since_last.as_secs(),
+            heartbeat.stage(),
+            CLIENT_QUEUE_SIZE - client_tx.capacity(),
+            CLIENT_QUEUE_SIZE,
+            WEB_QUEUE_SIZE - web_tx.capacity(),
+            WEB_QUEUE_SIZE,
+        );
+        tracing::error!("{}", message);
+        stats_incr("engine.stalled");
+
+        if let Some(webhook) = &config.webhook {
+            if let Err(e) = http_client
+                .post(webhook.as_str())
+                .timeout(WEBHOOK_TIMEOUT)
+                .json(&WebhookAlert {
+                    text: message.as_str(),
+                })
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+            {
+                tracing::error!("failed to post engine stall alert: {}", e);
+            }
+        }
+
+        if config.restart {
+            stalled_tx.send(()).await.ok();
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+use utoipa::ToSchema;
+use warp::Filter;
+
+use crate::{
+    clock::Clock,
+    engine::{db::AuthDb, name_valid},
+    web::{
+        auth::{verify_access, SCOPE_WORLD, SCOPE_WORLD_READ},
+        limit::{limited_access, RateLimiter, RateLimits},
+        request_id::{request_id, RequestId},
+        send_to_engine, with_sender, ErrorMessage, InternalError, Player, ScriptsRequest,
+        ScriptsResponse, WebMessage,
+    },
+};
+
+pub fn allowlist_filters<DB>(
+    db: DB,
+    clock: Clock,
+    tx: mpsc::Sender<WebMessage>,
+    limits: RateLimits,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path("allowlist").and(warp::post()).and(
+        read(db.clone(), clock.clone(), tx.clone())
+            .or(add(
+                db.clone(),
+                clock.clone(),
+                tx.clone(),
+                limits.writes.clone(),
+            ))
+            .or(remove(db, clock, tx, limits.writes)),
+    )
+}
+
+#[derive(Debug, Error)]
+pub enum AllowlistError {
+    #[error("bad player name")]
+    BadName,
+    #[error("name not on the allowlist")]
+    NameNotFound,
+}
+
+impl warp::reject::Reject for AllowlistError {}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct JsonAllowlistName {
+    name: String,
+}
+
+fn json_allowlist_name(
+) -> impl Filter<Extract = (JsonAllowlistName,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonAllowlistResponse {
+    /// The names on the allowlist, in lowercase.
+    names: Vec<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/allowlist/read",
+    tag = "allowlist",
+    responses(
+        (status = 200, description = "The names on the allowlist", body = JsonAllowlistResponse),
+        (status = 503, description = "The engine is too busy to take the request", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
+pub fn read<DB>(
+    db: DB,
+    clock: Clock,
+    tx: mpsc::Sender<WebMessage>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path("read")
+        .and(verify_access(db, clock, vec![SCOPE_WORLD_READ.to_string()]))
+        .and(request_id())
+        .and(with_sender(tx))
+        .and_then(handle_read)
+}
+
+#[utoipa::path(
+    post,
+    path = "/allowlist/add",
+    tag = "allowlist",
+    request_body = JsonAllowlistName,
+    responses(
+        (status = 200, description = "Added the name, returning the allowlist", body = JsonAllowlistResponse),
+        (status = 400, description = "Bad player name", body = ErrorMessage),
+        (status = 429, description = "Too many changes from the address or by the player", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds until another change is allowed"))),
+        (status = 503, description = "The engine is too busy to take the request", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
+pub fn add<DB>(
+    db: DB,
+    clock: Clock,
+    tx: mpsc::Sender<WebMessage>,
+    limiter: RateLimiter,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path("add")
+        .and(limited_access(
+            db,
+            clock,
+            vec![SCOPE_WORLD.to_string()],
+            limiter,
+        ))
+        .and(json_allowlist_name())
+        .and(request_id())
+        .and(with_sender(tx))
+        .and_then(handle_add)
+}
+
+#[utoipa::path(
+    post,
+    path = "/allowlist/remove",
+    tag = "allowlist",
+    request_body = JsonAllowlistName,
+    responses(
+        (status = 200, description = "Removed the name, returning the allowlist", body = JsonAllowlistResponse),
+        (status = 400, description = "Bad player name", body = ErrorMessage),
+        (status = 404, description = "The name is not on the allowlist", body = ErrorMessage),
+        (status = 429, description = "Too many changes from the address or by the player", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds until another change is allowed"))),
+        (status = 503, description = "The engine is too busy to take the request", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
+pub fn remove<DB>(
+    db: DB,
+    clock: Clock,
+    tx: mpsc::Sender<WebMessage>,
+    limiter: RateLimiter,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path("remove")
+        .and(limited_access(
+            db,
+            clock,
+            vec![SCOPE_WORLD.to_string()],
+            limiter,
+        ))
+        .and(json_allowlist_name())
+        .and(request_id())
+        .and(with_sender(tx))
+        .and_then(handle_remove)
+}
+
+#[tracing::instrument(name = "read allowlist", skip_all, fields(player = player.name.as_str()))]
+async fn handle_read(
+    player: Player,
+    request_id: RequestId,
+    sender: mpsc::Sender<WebMessage>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    tracing::debug!("player {} reading allowlist", player.name());
+
+    dispatch(request_id, sender, ScriptsRequest::ReadAllowlist).await
+}
+
+#[tracing::instrument(
+    name = "add to allowlist",
+    skip_all,
+    fields(player = player.name.as_str(), name = name.name.as_str())
+)]
+async fn handle_add(
+    player: Player,
+    name: JsonAllowlistName,
+    request_id: RequestId,
+    sender: mpsc::Sender<WebMessage>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    tracing::debug!("player {} adding {} to allowlist", player.name(), name.name);
+
+    if !name_valid(name.name.as_str()) {
+        return Err(warp::reject::custom(AllowlistError::BadName));
+    }
+
+    dispatch(
+        request_id,
+        sender,
+        ScriptsRequest::AddToAllowlist(name.name),
+    )
+    .await
+}
+
+#[tracing::instrument(
+    name = "remove from allowlist",
+    skip_all,
+    fields(player = player.name.as_str(), name = name.name.as_str())
+)]
+async fn handle_remove(
+    player: Player,
+    name: JsonAllowlistName,
+    request_id: RequestId,
+    sender: mpsc::Sender<WebMessage>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    tracing::debug!(
+        "player {} removing {} from allowlist",
+        player.name(),
+        name.name
+    );
+
+    if !name_valid(name.name.as_str()) {
+        return Err(warp::reject::custom(AllowlistError::BadName));
+    }
+
+    dispatch(
+        request_id,
+        sender,
+        ScriptsRequest::RemoveFromAllowlist(name.name),
+    )
+    .await
+}
+
+// Sends an allowlist request to the engine, replying with the resulting allowlist.
+async fn dispatch(
+    request_id: RequestId,
+    sender: mpsc::Sender<WebMessage>,
+    request: ScriptsRequest,
+) -> Result<warp::reply::Json, warp::Rejection> {
+    let (tx, rx) = oneshot::channel();
+    send_to_engine(
+        &sender,
+        WebMessage {
+            request_id,
+            response: tx,
+            request,
+        },
+    )?;
+
+    match rx.await {
+        Ok(ScriptsResponse::Allowlist(names)) => {
+            Ok(warp::reply::json(&JsonAllowlistResponse { names }))
+        }
+        Ok(ScriptsResponse::NotFound) => Err(warp::reject::custom(AllowlistError::NameNotFound)),
+        other => {
+            tracing::error!(
+                "received unexpected response to allowlist request: {:?}",
+                other
+            );
+            Err(warp::reject::custom(InternalError {}))
+        }
+    }
+}
@@ -1,27 +1,53 @@
 use std::{collections::HashSet, convert::TryFrom};
 
-use jwt_simple::prelude::{
-    Claims, Duration, ECDSAP256KeyPairLike, ECDSAP256PublicKeyLike, ES256KeyPair,
-    VerificationOptions,
+use jwt_simple::{
+    prelude::{
+        Claims, Duration, ECDSAP256KeyPairLike, ECDSAP256PublicKeyLike, ES256KeyPair, JWTClaims,
+        VerificationOptions,
+    },
+    JWTError,
 };
 use once_cell::sync::Lazy;
+use rand::{distributions::Alphanumeric, rngs::OsRng, Rng};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
 use warp::{reject, Filter, Rejection};
 
 use crate::{
+    clock::Clock,
     engine::{
         db::AuthDb,
         fsm::{verify_password, VerifyError},
     },
-    web::{security::with_jwt_key, with_db, InternalError, Player},
+    web::{
+        limit::{limit_address, with_limiter, RateLimiter, RateLimits},
+        security::with_jwt_key,
+        with_clock, with_db, ErrorMessage, InternalError, Player,
+    },
 };
 
-pub const SCOPE_SCRIPTS: &str = "scripts";
+pub const SCOPE_SCRIPTS_READ: &str = "scripts:read";
+pub const SCOPE_SCRIPTS_WRITE: &str = "scripts:write";
+pub const SCOPE_WORLD: &str = "world";
+pub const SCOPE_WORLD_READ: &str = "world:read";
+/// The scopes API tokens may be granted. Changing the world takes an immortal's own access token.
+pub const API_TOKEN_SCOPES: [&str; 3] = [SCOPE_SCRIPTS_READ, SCOPE_SCRIPTS_WRITE, SCOPE_WORLD_READ];
 
 const TOKEN_ISSUER: &str = "remud";
 const TOKEN_AUDIENCE: &str = "remud";
 const SCOPE_ACCESS: &str = "access";
 const SCOPE_REFRESH: &str = "refresh";
+// Access tokens issued before script access was split into reading and writing carry this scope,
+// which grants both.
+const SCOPE_SCRIPTS_LEGACY: &str = "scripts";
+// API tokens look like remud_<id>_<secret>, which can't be mistaken for a JWT.
+const API_TOKEN_PREFIX: &str = "remud_";
+const API_TOKEN_SECRET_LEN: usize = 40;
+/// Prefixes the name of an API token where a player's name would be used, such as in logs.
+pub const API_TOKEN_PLAYER_PREFIX: &str = "token:";
+// jwt-simple's default allowance for drift between the clocks of a token's issuer and verifier.
+const TIME_TOLERANCE_SECS: u64 = 900;
 
 static TOKEN_ISSUERS: Lazy<HashSet<String>> = Lazy::new(|| {
     let mut issuers = HashSet::new();
@@ -36,16 +62,20 @@ static TOKEN_AUDIENCES: Lazy<HashSet<String>> = Lazy::new(|| {
 
 pub fn auth_filters<DB>(
     db: DB,
+    clock: Clock,
+    limits: RateLimits,
 ) -> impl Filter<Extract = impl warp::Reply, Error = Rejection> + Clone
 where
     DB: AuthDb + Send + Sync + Clone + 'static,
 {
-    warp::path("auth")
-        .and(warp::post())
-        .and(login(db.clone()).or(refresh(db.clone())).or(logout(db)))
+    warp::path("auth").and(warp::post()).and(
+        login(db.clone(), clock.clone(), limits.auth.clone())
+            .or(refresh(db.clone(), clock.clone(), limits.auth))
+            .or(logout(db, clock)),
+    )
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct JsonTokenRequest {
     username: String,
     password: String,
@@ -55,7 +85,7 @@ fn json_login() -> impl Filter<Extract = (JsonTokenRequest,), Error = Rejection>
     warp::body::content_length_limit(1024 * 16).and(warp::body::json())
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct JsonRefreshRequest {
     refresh_token: String,
 }
@@ -64,7 +94,7 @@ fn json_refresh() -> impl Filter<Extract = (JsonRefreshRequest,), Error = Reject
     warp::body::content_length_limit(1024 * 16).and(warp::body::json())
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct JsonTokenResponse {
     access_token: String,
     refresh_token: String,
@@ -88,6 +118,7 @@ impl reject::Reject for AuthError {}
 
 pub fn verify_access<DB>(
     db: DB,
+    clock: Clock,
     scopes: Vec<String>,
 ) -> impl Filter<Extract = (Player,), Error = Rejection> + Clone
 where
@@ -96,67 +127,176 @@ where
     warp::header::<String>("Authorization")
         .and(with_db(db))
         .and(with_jwt_key())
+        .and(with_clock(clock))
         .and(with_scopes(scopes))
         .and_then(handle_verify_access)
 }
 
-pub fn login<DB>(db: DB) -> impl Filter<Extract = impl warp::Reply, Error = Rejection> + Clone
+#[derive(Debug, Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+/// Like `verify_access`, but a missing token is allowed and the token may also be passed as a
+/// `token` query parameter, since browsers cannot set headers when opening a websocket. A token
+/// that is present must still be valid.
+pub fn optional_access<DB>(
+    db: DB,
+    clock: Clock,
+    scopes: Vec<String>,
+) -> impl Filter<Extract = (Option<Player>,), Error = Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::header::optional::<String>("Authorization")
+        .and(warp::query::<TokenQuery>())
+        .and(with_db(db))
+        .and(with_jwt_key())
+        .and(with_clock(clock))
+        .and(with_scopes(scopes))
+        .and_then(handle_optional_access)
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/login",
+    tag = "auth",
+    request_body = JsonTokenRequest,
+    responses(
+        (status = 200, description = "Issued access and refresh tokens", body = JsonTokenResponse),
+        (status = 401, description = "Bad username or password", body = ErrorMessage),
+        (status = 429, description = "Too many attempts from the address or for the player", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds until another attempt is allowed"))),
+    )
+)]
+pub fn login<DB>(
+    db: DB,
+    clock: Clock,
+    limiter: RateLimiter,
+) -> impl Filter<Extract = impl warp::Reply, Error = Rejection> + Clone
 where
     DB: AuthDb + Send + Sync + Clone + 'static,
 {
     warp::path("login")
+        .and(limit_address(limiter.clone()))
         .and(json_login())
         .and(with_db(db))
         .and(with_jwt_key())
+        .and(with_clock(clock))
+        .and(with_limiter(limiter))
         .and_then(handle_login)
 }
 
-pub fn refresh<DB>(db: DB) -> impl Filter<Extract = impl warp::Reply, Error = Rejection> + Clone
+#[utoipa::path(
+    post,
+    path = "/auth/refresh",
+    tag = "auth",
+    request_body = JsonRefreshRequest,
+    responses(
+        (status = 200, description = "Issued new access and refresh tokens", body = JsonTokenResponse),
+        (status = 401, description = "Invalid refresh token", body = ErrorMessage),
+        (status = 429, description = "Too many attempts from the address or for the player", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds until another attempt is allowed"))),
+    )
+)]
+pub fn refresh<DB>(
+    db: DB,
+    clock: Clock,
+    limiter: RateLimiter,
+) -> impl Filter<Extract = impl warp::Reply, Error = Rejection> + Clone
 where
     DB: AuthDb + Send + Sync + Clone + 'static,
 {
     warp::path("refresh")
+        .and(limit_address(limiter.clone()))
         .and(json_refresh())
         .and(with_db(db))
         .and(with_jwt_key())
+        .and(with_clock(clock))
+        .and(with_limiter(limiter))
         .and_then(handle_refresh)
 }
 
-pub fn logout<DB>(db: DB) -> impl Filter<Extract = impl warp::Reply, Error = Rejection> + Clone
+#[utoipa::path(
+    post,
+    path = "/auth/logout",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Revoked all tokens for the player"),
+        (status = 401, description = "Missing or invalid access token", body = ErrorMessage),
+    ),
+    security(("bearer" = []))
+)]
+pub fn logout<DB>(
+    db: DB,
+    clock: Clock,
+) -> impl Filter<Extract = impl warp::Reply, Error = Rejection> + Clone
 where
     DB: AuthDb + Send + Sync + Clone + 'static,
 {
     warp::path("logout")
         .and(with_db(db.clone()))
-        .and(verify_access(db, vec![]))
+        .and(verify_access(db, clock, vec![]))
         .and_then(handle_logout)
 }
 
-#[tracing::instrument(name = "verify access", skip(auth_header, db, jwt_key))]
+#[tracing::instrument(name = "verify access", skip(auth_header, db, jwt_key, clock))]
 async fn handle_verify_access<DB: AuthDb>(
     auth_header: String,
     db: DB,
     jwt_key: &ES256KeyPair,
+    clock: Clock,
     scopes: Vec<String>,
 ) -> Result<Player, Rejection> {
     tracing::debug!("verifying bearer access token");
-    let token = match auth_header.split_whitespace().nth(1) {
-        Some(token) => token,
+    let token = bearer_token(auth_header.as_str())?;
+    verify_token(token, db, jwt_key, &clock, scopes).await
+}
+
+#[tracing::instrument(name = "verify optional access", skip_all)]
+async fn handle_optional_access<DB: AuthDb>(
+    auth_header: Option<String>,
+    query: TokenQuery,
+    db: DB,
+    jwt_key: &ES256KeyPair,
+    clock: Clock,
+    scopes: Vec<String>,
+) -> Result<Option<Player>, Rejection> {
+    let token = match (auth_header.as_deref(), query.token.as_deref()) {
+        (Some(auth_header), _) => bearer_token(auth_header)?,
+        (None, Some(token)) => token,
+        (None, None) => return Ok(None),
+    };
+
+    tracing::debug!("verifying access token");
+    verify_token(token, db, jwt_key, &clock, scopes)
+        .await
+        .map(Some)
+}
+
+fn bearer_token(auth_header: &str) -> Result<&str, Rejection> {
+    match auth_header.split_whitespace().nth(1) {
+        Some(token) => Ok(token),
         None => {
             tracing::warn!("received invalid Authorization header");
-            return Err(reject::custom(AuthError::InvalidAuthHeader));
+            Err(reject::custom(AuthError::InvalidAuthHeader))
         }
-    };
+    }
+}
 
-    // Verify the token signature, issuer, and audience
-    let claims = match jwt_key.public_key().verify_token::<TokenData>(
-        token,
-        Some(VerificationOptions {
-            allowed_issuers: Some(TOKEN_ISSUERS.clone()),
-            allowed_audiences: Some(TOKEN_AUDIENCES.clone()),
-            ..Default::default()
-        }),
-    ) {
+async fn verify_token<DB: AuthDb>(
+    token: &str,
+    db: DB,
+    jwt_key: &ES256KeyPair,
+    clock: &Clock,
+    scopes: Vec<String>,
+) -> Result<Player, Rejection> {
+    if let Some(token) = token.strip_prefix(API_TOKEN_PREFIX) {
+        return verify_api_token(token, db, clock, scopes).await;
+    }
+
+    // Verify the token signature, issuer, audience, and lifetime
+    let claims = match verify_claims(token, jwt_key, clock) {
         Ok(claims) => claims,
         Err(e) => {
             tracing::warn!("token could not be verified: {}", e);
@@ -171,7 +311,12 @@ async fn handle_verify_access<DB: AuthDb>(
     }
 
     for scope in &scopes {
-        if !claims.custom.scopes.contains(scope) {
+        if !claims
+            .custom
+            .scopes
+            .iter()
+            .any(|granted| grants(granted, scope))
+        {
             tracing::warn!("missing required scope: {}", scope);
             return Err(reject::custom(AuthError::InadequateAccess));
         }
@@ -212,18 +357,125 @@ async fn handle_verify_access<DB: AuthDb>(
     })
 }
 
-#[tracing::instrument(name = "login", fields(player = request.username.as_str()), skip(db, jwt_key, request))]
+// Whether a scope granted to an access token grants a required one. Access tokens issued before
+// scopes were split up are still honored: "scripts" grants reading and writing scripts, and
+// "world" grants reading it.
+fn grants(granted: &str, required: &str) -> bool {
+    granted == required
+        || (granted == SCOPE_SCRIPTS_LEGACY
+            && (required == SCOPE_SCRIPTS_READ || required == SCOPE_SCRIPTS_WRITE))
+        || (granted == SCOPE_WORLD && required == SCOPE_WORLD_READ)
+}
+
+// Verifies an API token's secret against its stored hash and that it was granted the required
+// scopes. API tokens act for no player, so endpoints which act on the caller's own player refuse
+// them by requiring no scopes.
+async fn verify_api_token<DB: AuthDb>(
+    token: &str,
+    db: DB,
+    clock: &Clock,
+    scopes: Vec<String>,
+) -> Result<Player, Rejection> {
+    if scopes.is_empty() {
+        tracing::warn!("API token used for a player's own access");
+        return Err(reject::custom(AuthError::InadequateAccess));
+    }
+
+    let (id, secret) = match token
+        .split_once('_')
+        .and_then(|(id, secret)| id.parse::<i64>().ok().map(|id| (id, secret)))
+    {
+        Some(parts) => parts,
+        None => {
+            tracing::warn!("received malformed API token");
+            return Err(reject::custom(AuthError::InvalidToken));
+        }
+    };
+
+    let api_token = match db.api_token(id).await {
+        Ok(Some(api_token)) => api_token,
+        Ok(None) => {
+            tracing::warn!("API token {} does not exist", id);
+            return Err(reject::custom(AuthError::InvalidToken));
+        }
+        Err(e) => {
+            tracing::error!("failed to retrieve API token: {}", e);
+            return Err(reject::custom(InternalError {}));
+        }
+    };
+
+    if api_token_hash(secret) != api_token.hash {
+        tracing::warn!("API token {} presented a bad secret", api_token.name);
+        return Err(reject::custom(AuthError::InvalidToken));
+    }
+
+    for scope in &scopes {
+        if !api_token
+            .scopes
+            .split_whitespace()
+            .any(|granted| granted == scope)
+        {
+            tracing::warn!(
+                "API token {} missing required scope: {}",
+                api_token.name,
+                scope
+            );
+            return Err(reject::custom(AuthError::InadequateAccess));
+        }
+    }
+
+    if let Err(e) = db.touch_api_token(api_token.id, clock.unix_now()).await {
+        tracing::error!("failed to record API token use: {}", e);
+    }
+
+    Ok(Player {
+        name: format!("{}{}", API_TOKEN_PLAYER_PREFIX, api_token.name),
+    })
+}
+
+/// Generates the secret of a new API token.
+pub fn api_token_secret() -> String {
+    OsRng
+        .sample_iter(&Alphanumeric)
+        .take(API_TOKEN_SECRET_LEN)
+        .map(char::from)
+        .collect()
+}
+
+/// Hashes the secret of an API token for storage. Secrets are long and random, so unlike passwords
+/// they need no slow, salted hash, and checking one on every request stays cheap.
+pub fn api_token_hash(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
+
+/// Formats the API token presented by clients from its ID and secret.
+pub fn api_token(id: i64, secret: &str) -> String {
+    format!("{}{}_{}", API_TOKEN_PREFIX, id, secret)
+}
+
+#[tracing::instrument(name = "login", fields(player = request.username.as_str()), skip(db, jwt_key, clock, request, limiter))]
 async fn handle_login<DB: AuthDb>(
     request: JsonTokenRequest,
     db: DB,
     jwt_key: &ES256KeyPair,
+    clock: Clock,
+    limiter: RateLimiter,
 ) -> Result<impl warp::Reply, Rejection> {
     let player = request.username.as_str();
     tracing::debug!("attempting login for {}", player);
 
+    // Refuse the player while failed attempts have locked them out, to slow guessing. Only
+    // failures are charged, so others can't lock a player out just by knowing their name.
+    limiter
+        .check_account_attempt(player)
+        .map_err(reject::custom)?;
+
     let hash = match db.player_hash(player).await {
         Ok(Some(hash)) => hash,
-        Ok(None) => return Err(reject::custom(AuthError::AuthenticationError)),
+        Ok(None) => {
+            limiter.charge_account_failure(player);
+            return Err(reject::custom(AuthError::AuthenticationError));
+        }
         Err(e) => {
             tracing::error!("failed to retrieve player during token request: {}", e);
             return Err(reject::custom(InternalError {}));
@@ -237,6 +489,7 @@ async fn handle_login<DB: AuthDb>(
                 tracing::error!("failed to verify player during token request: {}", e);
                 return Err(reject::custom(InternalError {}));
             } else {
+                limiter.charge_account_failure(player);
                 return Err(reject::custom(AuthError::AuthenticationError));
             }
         }
@@ -251,7 +504,7 @@ async fn handle_login<DB: AuthDb>(
     };
 
     let (access_token, access_issued_secs, refresh_token, refresh_issued_secs) =
-        match generate_tokens(jwt_key, player, immortal) {
+        match generate_tokens(jwt_key, &clock, player, immortal) {
             Ok(result) => result,
             Err(err) => {
                 tracing::error!("failed to generate tokens: {}", err);
@@ -275,21 +528,16 @@ async fn handle_login<DB: AuthDb>(
     Ok(warp::reply::json(&response))
 }
 
-#[tracing::instrument(name = "refresh", skip(db, jwt_key))]
+#[tracing::instrument(name = "refresh", skip(db, jwt_key, clock, limiter))]
 async fn handle_refresh<DB: AuthDb>(
     request: JsonRefreshRequest,
     db: DB,
     jwt_key: &ES256KeyPair,
+    clock: Clock,
+    limiter: RateLimiter,
 ) -> Result<impl warp::Reply, Rejection> {
     tracing::debug!("attempting token refresh");
-    let claims = match jwt_key.public_key().verify_token::<TokenData>(
-        request.refresh_token.as_str(),
-        Some(VerificationOptions {
-            allowed_issuers: Some(TOKEN_ISSUERS.clone()),
-            allowed_audiences: Some(TOKEN_AUDIENCES.clone()),
-            ..Default::default()
-        }),
-    ) {
+    let claims = match verify_claims(request.refresh_token.as_str(), jwt_key, &clock) {
         Ok(claims) => claims,
         Err(e) => {
             tracing::warn!("token could not be verified: {}", e);
@@ -304,6 +552,7 @@ async fn handle_refresh<DB: AuthDb>(
     }
 
     let player = claims.subject.as_ref().unwrap().as_str();
+    limiter.check_account(player).map_err(reject::custom)?;
 
     // Confirm that this refresh token hasn't already been used. If it has, log out the player.
     let refresh_issued = match db.refresh_issued_secs(player).await {
@@ -344,7 +593,7 @@ async fn handle_refresh<DB: AuthDb>(
     };
 
     let (access_token, access_issued_secs, refresh_token, refresh_issued_secs) =
-        match generate_tokens(jwt_key, player, immortal) {
+        match generate_tokens(jwt_key, &clock, player, immortal) {
             Ok(result) => result,
             Err(err) => {
                 tracing::error!("failed to generate tokens: {}", err);
@@ -378,33 +627,77 @@ async fn handle_logout<DB: AuthDb>(db: DB, player: Player) -> Result<impl warp::
     Ok(warp::reply())
 }
 
-#[tracing::instrument(name = "generate tokens", skip(jwt_key))]
+// Verifies a token's signature, issuer, and audience, and that the server's clock is within its
+// lifetime. jwt-simple checks lifetimes against the system clock, so its tolerance is widened by
+// however far the server's clock has been moved ahead.
+fn verify_claims(
+    token: &str,
+    jwt_key: &ES256KeyPair,
+    clock: &Clock,
+) -> Result<JWTClaims<TokenData>, jwt_simple::Error> {
+    let claims = jwt_key.public_key().verify_token::<TokenData>(
+        token,
+        Some(VerificationOptions {
+            allowed_issuers: Some(TOKEN_ISSUERS.clone()),
+            allowed_audiences: Some(TOKEN_AUDIENCES.clone()),
+            accept_future: true,
+            time_tolerance: Some(
+                Duration::from_secs(TIME_TOLERANCE_SECS) + Duration::from(clock.skew()),
+            ),
+            ..Default::default()
+        }),
+    )?;
+
+    let now = Duration::from_secs(clock.unix_now() as u64);
+    if claims.invalid_before.map_or(false, |before| now < before) {
+        return Err(JWTError::TokenNotValidYet.into());
+    }
+    if claims.expires_at.map_or(false, |expires| expires < now) {
+        return Err(JWTError::TokenHasExpired.into());
+    }
+
+    Ok(claims)
+}
+
+// Creates claims which are valid for a time from the server's clock.
+fn claims_from(data: TokenData, clock: &Clock, valid_for: Duration) -> JWTClaims<TokenData> {
+    let now = Duration::from_secs(clock.unix_now() as u64);
+
+    let mut claims = Claims::with_custom_claims(data, valid_for)
+        .with_issuer(TOKEN_ISSUER)
+        .with_audience(TOKEN_AUDIENCE);
+    claims.issued_at = Some(now);
+    claims.invalid_before = Some(now);
+    claims.expires_at = Some(now + valid_for);
+
+    claims
+}
+
+#[tracing::instrument(name = "generate tokens", skip(jwt_key, clock))]
 fn generate_tokens(
     jwt_key: &ES256KeyPair,
+    clock: &Clock,
     player: &str,
     immortal: bool,
 ) -> anyhow::Result<(String, i64, String, i64)> {
     let mut scopes = vec![SCOPE_ACCESS.to_string()];
 
     if immortal {
-        scopes.push(SCOPE_SCRIPTS.to_string());
+        scopes.extend(API_TOKEN_SCOPES.iter().map(ToString::to_string));
+        scopes.push(SCOPE_WORLD.to_string());
     }
 
     let access_data = TokenData { scopes };
-    let access_claims = Claims::with_custom_claims(access_data, Duration::from_hours(1))
-        .with_issuer(TOKEN_ISSUER)
-        .with_audience(TOKEN_AUDIENCE)
-        .with_subject(player);
+    let access_claims =
+        claims_from(access_data, clock, Duration::from_hours(1)).with_subject(player);
     let access_issued = access_claims.issued_at.unwrap();
     let access_token = jwt_key.sign(access_claims)?;
 
     let refresh_data = TokenData {
         scopes: vec![SCOPE_REFRESH.to_string()],
     };
-    let refresh_claims = Claims::with_custom_claims(refresh_data, Duration::from_days(365))
-        .with_issuer(TOKEN_ISSUER)
-        .with_audience(TOKEN_AUDIENCE)
-        .with_subject(player);
+    let refresh_claims =
+        claims_from(refresh_data, clock, Duration::from_days(365)).with_subject(player);
     let refresh_issued = refresh_claims.issued_at.unwrap();
     let refresh_token = jwt_key.sign(refresh_claims)?;
 
@@ -426,3 +719,30 @@ fn with_scopes(
 ) -> impl Filter<Extract = (Vec<String>,), Error = std::convert::Infallible> + Clone {
     warp::any().map(move || scopes.clone())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        api_token_hash, grants, SCOPE_SCRIPTS_READ, SCOPE_SCRIPTS_WRITE, SCOPE_WORLD,
+        SCOPE_WORLD_READ,
+    };
+
+    #[test]
+    fn test_legacy_scopes() {
+        assert!(grants("scripts", SCOPE_SCRIPTS_READ));
+        assert!(grants("scripts", SCOPE_SCRIPTS_WRITE));
+        assert!(grants(SCOPE_WORLD, SCOPE_WORLD_READ));
+        assert!(grants(SCOPE_SCRIPTS_READ, SCOPE_SCRIPTS_READ));
+
+        assert!(!grants(SCOPE_SCRIPTS_READ, SCOPE_SCRIPTS_WRITE));
+        assert!(!grants(SCOPE_WORLD_READ, SCOPE_WORLD));
+        assert!(!grants("scripts", SCOPE_WORLD));
+    }
+
+    #[test]
+    fn test_api_token_hash() {
+        assert_eq!(api_token_hash("secret"), api_token_hash("secret"));
+        assert_ne!(api_token_hash("secret"), api_token_hash("secrets"));
+        assert_eq!(api_token_hash("secret").len(), 64);
+    }
+}
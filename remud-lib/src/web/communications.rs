@@ -0,0 +1,209 @@
+use std::{convert::TryFrom, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+use utoipa::{IntoParams, ToSchema};
+use warp::Filter;
+
+use crate::{
+    clock::Clock,
+    engine::db::AuthDb,
+    web::{
+        auth::{verify_access, SCOPE_WORLD_READ},
+        request_id::{request_id, RequestId},
+        send_to_engine, with_sender, ErrorMessage, InternalError, Player, ScriptsRequest,
+        ScriptsResponse, WebMessage,
+    },
+    world::types::{
+        communication::{
+            Channel, Communication, CommunicationFilter, CommunicationId, CommunicationPage,
+            DEFAULT_PAGE_LENGTH,
+        },
+        room::RoomId,
+    },
+};
+
+// The most entries returned in one page.
+const MAX_PAGE_LENGTH: usize = 200;
+
+pub fn communication_filters<DB>(
+    db: DB,
+    clock: Clock,
+    tx: mpsc::Sender<WebMessage>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    communications(db, clock, tx)
+}
+
+#[derive(Debug, Error)]
+pub enum CommunicationError {
+    #[error("bad communication query")]
+    BadQuery,
+}
+
+impl warp::reject::Reject for CommunicationError {}
+
+/// Filters and pages the communication log, newest first.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct JsonCommunicationsQuery {
+    /// The page to return, starting from 1.
+    page: Option<usize>,
+    /// The number of entries per page, at most 200. Defaults to 20.
+    limit: Option<usize>,
+    /// Only include communication from the player with this name, ignoring case.
+    player: Option<String>,
+    /// One of clan, emote, say, or send.
+    channel: Option<String>,
+    /// Only include communication in the room with this ID.
+    room: Option<i64>,
+    /// Only include communication containing this text, ignoring case.
+    q: Option<String>,
+}
+
+impl JsonCommunicationsQuery {
+    // Returns the filter, page, and page length the query asks for.
+    fn parse(self) -> Result<(CommunicationFilter, usize, usize), CommunicationError> {
+        let page = self.page.unwrap_or(1);
+        let limit = self.limit.unwrap_or(DEFAULT_PAGE_LENGTH);
+        if page == 0 || limit == 0 || limit > MAX_PAGE_LENGTH {
+            return Err(CommunicationError::BadQuery);
+        }
+
+        let channel = match self.channel {
+            Some(channel) => Some(
+                Channel::from_str(channel.as_str()).map_err(|_| CommunicationError::BadQuery)?,
+            ),
+            None => None,
+        };
+
+        let room = match self.room {
+            Some(room) => Some(RoomId::try_from(room).map_err(|_| CommunicationError::BadQuery)?),
+            None => None,
+        };
+
+        let filter = CommunicationFilter {
+            player: self.player,
+            channel,
+            room,
+            text: self.q,
+        };
+
+        Ok((filter, page, limit))
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonCommunication {
+    id: i64,
+    /// One of clan, emote, say, or send.
+    channel: String,
+    player: String,
+    /// The room the player was in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    room: Option<i64>,
+    /// The recipient of a sent message, or the clan spoken to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+    text: String,
+    /// When it was said, in seconds since the Unix epoch.
+    said_at: i64,
+}
+
+impl From<(CommunicationId, Communication)> for JsonCommunication {
+    fn from((id, communication): (CommunicationId, Communication)) -> Self {
+        JsonCommunication {
+            id: id.into(),
+            channel: communication.channel.to_string(),
+            player: communication.name,
+            room: communication.room.map(i64::from),
+            target: communication.target,
+            text: communication.text,
+            said_at: communication.said_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonCommunicationsResponse {
+    communications: Vec<JsonCommunication>,
+    /// The number of entries matching the query across all pages.
+    total: usize,
+    page: usize,
+    limit: usize,
+}
+
+#[utoipa::path(
+    get,
+    path = "/world/communications",
+    tag = "world",
+    params(JsonCommunicationsQuery),
+    responses(
+        (status = 200, description = "What players have said, emoted, sent, and said to their clans, newest first", body = JsonCommunicationsResponse),
+        (status = 400, description = "Bad page, limit, channel, or room", body = ErrorMessage),
+        (status = 401, description = "Missing or inadequate access token", body = ErrorMessage),
+        (status = 503, description = "The engine is too busy to take the request", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
+pub fn communications<DB>(
+    db: DB,
+    clock: Clock,
+    tx: mpsc::Sender<WebMessage>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path!("world" / "communications")
+        .and(warp::get())
+        .and(verify_access(db, clock, vec![SCOPE_WORLD_READ.to_string()]))
+        .and(warp::query::<JsonCommunicationsQuery>())
+        .and(request_id())
+        .and(with_sender(tx))
+        .and_then(handle_communications)
+}
+
+#[tracing::instrument(name = "read communications", skip_all, fields(player = player.name.as_str()))]
+async fn handle_communications(
+    player: Player,
+    query: JsonCommunicationsQuery,
+    request_id: RequestId,
+    sender: mpsc::Sender<WebMessage>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    tracing::debug!("player {} reading communications", player.name());
+
+    let (filter, page, limit) = query.parse().map_err(warp::reject::custom)?;
+
+    let (tx, rx) = oneshot::channel();
+    send_to_engine(
+        &sender,
+        WebMessage {
+            request_id,
+            response: tx,
+            request: ScriptsRequest::ReadCommunications(filter, page, limit),
+        },
+    )?;
+
+    match rx.await {
+        Ok(ScriptsResponse::Communications(found)) => {
+            let CommunicationPage { entries, total } = *found;
+            Ok(warp::reply::json(&JsonCommunicationsResponse {
+                communications: entries.into_iter().map(JsonCommunication::from).collect(),
+                total,
+                page,
+                limit,
+            }))
+        }
+        other => {
+            tracing::error!(
+                "received unexpected response to communications request: {:?}",
+                other
+            );
+            Err(warp::reject::custom(InternalError {}))
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+use warp::{Filter, Rejection};
+
+use crate::web::{
+    allowlist, auth, communications, economy, graphql, health, leaderboards, lint, market, news,
+    players, polls, scripts, settings::WebSettings, status, tokens, ws,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "ReMUD",
+        description = "Web API for authenticating, managing scripts and the allowlist, querying the world, and viewing players, leaderboards, the market, the news, and polls."
+    ),
+    paths(
+        auth::login,
+        auth::refresh,
+        auth::logout,
+        tokens::create,
+        tokens::read,
+        tokens::revoke,
+        players::achievements,
+        players::export,
+        players::request_erasure,
+        players::read_erasures,
+        players::approve_erasure,
+        leaderboards::leaderboard,
+        status::status,
+        health::healthz,
+        health::readyz,
+        market::market,
+        news::news,
+        polls::polls,
+        scripts::create,
+        scripts::read,
+        scripts::read_all,
+        scripts::update,
+        scripts::delete,
+        scripts::stats,
+        allowlist::read,
+        allowlist::add,
+        allowlist::remove,
+        graphql::graphql_filters,
+        lint::lint,
+        economy::economy,
+        communications::communications,
+        ws::play,
+        ws::admin,
+    ),
+    modifiers(&BearerAuth),
+    tags(
+        (name = "auth", description = "Token issuance and revocation, including API tokens for automation"),
+        (name = "players", description = "Player information, data exports, and erasure requests"),
+        (name = "leaderboards", description = "Public leaderboards, refreshed periodically"),
+        (name = "status", description = "Public server population statistics"),
+        (name = "health", description = "Liveness and readiness checks for orchestration"),
+        (name = "market", description = "Public player market listings"),
+        (name = "news", description = "Public news changelog"),
+        (name = "polls", description = "Public polls and their results"),
+        (name = "scripts", description = "Script management, requires immortal access"),
+        (name = "allowlist", description = "Allowlist management, requires immortal access"),
+        (name = "world", description = "World queries, requires immortal access"),
+        (name = "game", description = "Game sessions"),
+    )
+)]
+struct ApiDoc;
+
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer",
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .bearer_format("JWT")
+                        .build(),
+                ),
+            );
+        }
+    }
+}
+
+const SWAGGER_UI: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>ReMUD API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@4/swagger-ui.css" />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@4/swagger-ui-bundle.js"></script>
+  <script>
+    window.onload = () => {
+      window.ui = SwaggerUIBundle({ url: "/api/docs/openapi.json", dom_id: "#swagger-ui" });
+    };
+  </script>
+</body>
+</html>
+"##;
+
+/// Serves the OpenAPI specification at /api/docs/openapi.json and Swagger UI at /api/docs when
+/// enabled by configuration.
+pub fn docs_filters(
+    settings: WebSettings,
+) -> impl Filter<Extract = impl warp::Reply, Error = Rejection> + Clone {
+    let spec = Arc::new(ApiDoc::openapi());
+
+    let ui = warp::path::end().map(|| warp::reply::html(SWAGGER_UI));
+    let json = warp::path("openapi.json")
+        .and(warp::path::end())
+        .map(move || warp::reply::json(spec.as_ref()));
+
+    warp::path("api")
+        .and(warp::path("docs"))
+        .and(warp::get())
+        .and(docs_enabled(settings))
+        .and(ui.or(json))
+}
+
+fn docs_enabled(settings: WebSettings) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::any()
+        .and_then(move || {
+            let enabled = settings.docs();
+            async move {
+                if enabled {
+                    Ok(())
+                } else {
+                    Err(warp::reject::not_found())
+                }
+            }
+        })
+        .untuple_one()
+}
@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+use utoipa::{IntoParams, ToSchema};
+use warp::Filter;
+
+use crate::{
+    clock::Clock,
+    engine::db::AuthDb,
+    web::{
+        auth::{verify_access, SCOPE_WORLD_READ},
+        request_id::{request_id, RequestId},
+        send_to_engine, with_sender, ErrorMessage, InternalError, Player, ScriptsRequest,
+        ScriptsResponse, WebMessage,
+    },
+    world::types::economy::{format_day, CoinSource, EconomySummary, Flow, DEFAULT_REPORT_DAYS},
+};
+
+pub fn economy_filters<DB>(
+    db: DB,
+    clock: Clock,
+    tx: mpsc::Sender<WebMessage>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    economy(db, clock, tx)
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct JsonEconomyQuery {
+    /// The number of days to report, ending today. Defaults to 7.
+    days: Option<u32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonCoinFlow {
+    /// One of grant, loot, quest, script, or shop.
+    source: String,
+    created: i64,
+    destroyed: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonEconomyDay {
+    /// The day, such as 2021-11-24, in UTC.
+    date: String,
+    flows: Vec<JsonCoinFlow>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonEconomyResponse {
+    /// Each day with coins created or destroyed, earliest first.
+    days: Vec<JsonEconomyDay>,
+    /// The coins created and destroyed by each source over all of the days.
+    totals: Vec<JsonCoinFlow>,
+    /// The coins players are carrying.
+    carried: i64,
+    /// The coins players have in the bank.
+    banked: i64,
+}
+
+fn json_flows(flows: BTreeMap<CoinSource, Flow>) -> Vec<JsonCoinFlow> {
+    flows
+        .into_iter()
+        .map(|(source, flow)| JsonCoinFlow {
+            source: source.to_string(),
+            created: flow.created,
+            destroyed: flow.destroyed,
+        })
+        .collect()
+}
+
+impl From<EconomySummary> for JsonEconomyResponse {
+    fn from(summary: EconomySummary) -> Self {
+        JsonEconomyResponse {
+            days: summary
+                .by_day
+                .into_iter()
+                .map(|(day, flows)| JsonEconomyDay {
+                    date: format_day(day),
+                    flows: json_flows(flows),
+                })
+                .collect(),
+            totals: json_flows(summary.totals),
+            carried: summary.carried,
+            banked: summary.banked,
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/world/economy",
+    tag = "world",
+    params(JsonEconomyQuery),
+    responses(
+        (status = 200, description = "Coins created and destroyed each day by source", body = JsonEconomyResponse),
+        (status = 401, description = "Missing or inadequate access token", body = ErrorMessage),
+        (status = 503, description = "The engine is too busy to take the request", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
+pub fn economy<DB>(
+    db: DB,
+    clock: Clock,
+    tx: mpsc::Sender<WebMessage>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path!("world" / "economy")
+        .and(warp::get())
+        .and(verify_access(db, clock, vec![SCOPE_WORLD_READ.to_string()]))
+        .and(warp::query::<JsonEconomyQuery>())
+        .and(request_id())
+        .and(with_sender(tx))
+        .and_then(handle_economy)
+}
+
+#[tracing::instrument(name = "read economy", skip_all, fields(player = player.name.as_str()))]
+async fn handle_economy(
+    player: Player,
+    query: JsonEconomyQuery,
+    request_id: RequestId,
+    sender: mpsc::Sender<WebMessage>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    tracing::debug!("player {} reading economy", player.name());
+
+    let days = query.days.unwrap_or(DEFAULT_REPORT_DAYS).max(1);
+
+    let (tx, rx) = oneshot::channel();
+    send_to_engine(
+        &sender,
+        WebMessage {
+            request_id,
+            response: tx,
+            request: ScriptsRequest::ReadEconomy(days),
+        },
+    )?;
+
+    match rx.await {
+        Ok(ScriptsResponse::Economy(summary)) => {
+            Ok(warp::reply::json(&JsonEconomyResponse::from(*summary)))
+        }
+        other => {
+            tracing::error!(
+                "received unexpected response to economy request: {:?}",
+                other
+            );
+            Err(warp::reject::custom(InternalError {}))
+        }
+    }
+}
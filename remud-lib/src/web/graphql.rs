@@ -0,0 +1,817 @@
+use std::collections::HashMap;
+
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema, ID};
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+use utoipa::ToSchema;
+use warp::Filter;
+
+use crate::{
+    clock::Clock,
+    engine::db::AuthDb,
+    web::{
+        auth::{verify_access, SCOPE_WORLD_READ},
+        request_id::{request_id, RequestId},
+        send_to_engine, with_sender, ErrorMessage, InternalError, Player, ScriptsRequest,
+        ScriptsResponse, WebMessage,
+    },
+    world::{
+        scripting::{actions::read_all_scripts, ScriptHook, ScriptHooks, ScriptTrigger},
+        types::{
+            object::{Keywords, Object, ObjectId, Prototype, PrototypeId},
+            player::{self, PlayerId},
+            room::{Regions, Room, RoomId},
+            Contents, Description, Location, Named,
+        },
+    },
+};
+
+pub type WorldSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+#[utoipa::path(
+    post,
+    path = "/graphql",
+    tag = "world",
+    request_body = JsonGraphQlRequest,
+    responses(
+        (status = 200, description = "Query results and any query errors", body = JsonGraphQlResponse),
+        (status = 401, description = "Missing or inadequate access token", body = ErrorMessage),
+        (status = 503, description = "The engine is too busy to take the request", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
+pub fn graphql_filters<DB>(
+    db: DB,
+    clock: Clock,
+    tx: mpsc::Sender<WebMessage>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription).finish();
+
+    warp::path("graphql")
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(verify_access(db, clock, vec![SCOPE_WORLD_READ.to_string()]))
+        .and(warp::body::content_length_limit(1024 * 64).and(warp::body::json()))
+        .and(warp::any().map(move || schema.clone()))
+        .and(request_id())
+        .and(with_sender(tx))
+        .and_then(handle_query)
+}
+
+#[tracing::instrument(
+    name = "graphql query",
+    skip_all,
+    fields(player = player.name.as_str())
+)]
+async fn handle_query(
+    player: Player,
+    request: JsonGraphQlRequest,
+    schema: WorldSchema,
+    request_id: RequestId,
+    sender: mpsc::Sender<WebMessage>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    tracing::debug!("player {} querying world", player.name());
+
+    let (tx, rx) = oneshot::channel();
+    send_to_engine(
+        &sender,
+        WebMessage {
+            request_id,
+            response: tx,
+            request: ScriptsRequest::WorldSnapshot,
+        },
+    )?;
+
+    let snapshot = match rx.await {
+        Ok(ScriptsResponse::WorldSnapshot(snapshot)) => snapshot,
+        other => {
+            tracing::error!("received unexpected response to WorldSnapshot: {:?}", other);
+            return Err(warp::reject::custom(InternalError {}));
+        }
+    };
+
+    let mut query = async_graphql::Request::new(request.query).data(*snapshot);
+    if let Some(operation_name) = request.operation_name {
+        query = query.operation_name(operation_name);
+    }
+    if let Some(variables) = request.variables {
+        query = query.variables(async_graphql::Variables::from_json(variables));
+    }
+
+    let response = schema.execute(query).await;
+
+    Ok(warp::reply::json(&JsonGraphQlResponse {
+        data: response.data.into_json().unwrap_or_default(),
+        errors: response
+            .errors
+            .into_iter()
+            .map(|error| JsonGraphQlError {
+                message: error.message,
+            })
+            .collect_vec(),
+    }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonGraphQlRequest {
+    query: String,
+    operation_name: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    variables: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonGraphQlResponse {
+    #[schema(value_type = Object)]
+    data: serde_json::Value,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<JsonGraphQlError>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonGraphQlError {
+    message: String,
+}
+
+/// A copy of the queryable parts of the world taken at the end of a tick. Queries resolve
+/// entirely against the snapshot so nested lookups never wait on the engine.
+#[derive(Debug, Default)]
+pub struct WorldSnapshot {
+    rooms: Vec<RoomSnapshot>,
+    objects: Vec<ObjectSnapshot>,
+    prototypes: Vec<PrototypeSnapshot>,
+    players: Vec<PlayerSnapshot>,
+    scripts: Vec<ScriptSnapshot>,
+    room_index: HashMap<RoomId, usize>,
+    object_index: HashMap<ObjectId, usize>,
+    prototype_index: HashMap<PrototypeId, usize>,
+    player_index: HashMap<String, usize>,
+    script_index: HashMap<String, usize>,
+}
+
+#[derive(Debug)]
+struct RoomSnapshot {
+    id: RoomId,
+    name: String,
+    description: String,
+    regions: Vec<String>,
+    exits: Vec<(String, RoomId)>,
+    objects: Vec<ObjectId>,
+    players: Vec<String>,
+    hooks: Vec<ScriptHook>,
+}
+
+#[derive(Debug)]
+struct ObjectSnapshot {
+    id: ObjectId,
+    prototype: PrototypeId,
+    inherit_scripts: bool,
+    name: String,
+    description: String,
+    keywords: Vec<String>,
+    location: Option<Holder>,
+    hooks: Vec<ScriptHook>,
+}
+
+#[derive(Debug)]
+enum Holder {
+    Room(RoomId),
+    Player(String),
+}
+
+#[derive(Debug)]
+struct PrototypeSnapshot {
+    id: PrototypeId,
+    name: String,
+    description: String,
+    keywords: Vec<String>,
+    hooks: Vec<ScriptHook>,
+}
+
+#[derive(Debug)]
+struct PlayerSnapshot {
+    id: PlayerId,
+    name: String,
+    description: String,
+    room: Option<RoomId>,
+    inventory: Vec<ObjectId>,
+    hooks: Vec<ScriptHook>,
+}
+
+#[derive(Debug)]
+struct ScriptSnapshot {
+    name: String,
+    trigger: String,
+    code: String,
+    revision: i64,
+    tags: Vec<String>,
+    folder: Option<String>,
+    enabled: bool,
+    error: Option<String>,
+}
+
+impl WorldSnapshot {
+    pub fn capture(world: &mut World) -> Self {
+        let room_ids: HashMap<Entity, RoomId> = world
+            .query::<(Entity, &Room)>()
+            .iter(world)
+            .map(|(entity, room)| (entity, room.id()))
+            .collect();
+        let object_ids: HashMap<Entity, ObjectId> = world
+            .query::<(Entity, &Object)>()
+            .iter(world)
+            .map(|(entity, object)| (entity, object.id()))
+            .collect();
+        let prototype_ids: HashMap<Entity, PrototypeId> = world
+            .query::<(Entity, &Prototype)>()
+            .iter(world)
+            .map(|(entity, prototype)| (entity, prototype.id()))
+            .collect();
+        let player_names: HashMap<Entity, String> = world
+            .query::<(Entity, &player::Player, &Named)>()
+            .iter(world)
+            .map(|(entity, _, named)| (entity, named.to_string()))
+            .collect();
+
+        let objects_in = |contents: &Contents| {
+            contents
+                .objects()
+                .iter()
+                .filter_map(|object| object_ids.get(object).copied())
+                .collect_vec()
+        };
+        let hooks_of = |hooks: Option<&ScriptHooks>| {
+            hooks
+                .map(|hooks| hooks.hooks().to_vec())
+                .unwrap_or_default()
+        };
+
+        let mut snapshot = WorldSnapshot::default();
+
+        for (room, named, description, regions, contents, hooks) in world
+            .query::<(
+                &Room,
+                &Named,
+                &Description,
+                &Regions,
+                &Contents,
+                Option<&ScriptHooks>,
+            )>()
+            .iter(world)
+        {
+            let exits = room
+                .exits()
+                .iter()
+                .filter_map(|(direction, exit)| {
+                    room_ids
+                        .get(&exit.destination)
+                        .map(|id| (direction.to_string(), *id))
+                })
+                .sorted_by(|(a, _), (b, _)| a.cmp(b))
+                .collect_vec();
+
+            snapshot.room_index.insert(room.id(), snapshot.rooms.len());
+            snapshot.rooms.push(RoomSnapshot {
+                id: room.id(),
+                name: named.to_string(),
+                description: description.to_string(),
+                regions: regions.get_list(),
+                exits,
+                objects: objects_in(contents),
+                players: room
+                    .players()
+                    .iter()
+                    .filter_map(|player| player_names.get(player).cloned())
+                    .collect_vec(),
+                hooks: hooks_of(hooks),
+            });
+        }
+
+        for (object, named, description, keywords, location, hooks) in world
+            .query::<(
+                &Object,
+                &Named,
+                &Description,
+                &Keywords,
+                Option<&Location>,
+                Option<&ScriptHooks>,
+            )>()
+            .iter(world)
+        {
+            let prototype = match prototype_ids.get(&object.prototype()) {
+                Some(id) => *id,
+                None => continue,
+            };
+
+            let location = location.and_then(|location| {
+                if let Some(room) = room_ids.get(&location.entity()) {
+                    Some(Holder::Room(*room))
+                } else {
+                    player_names
+                        .get(&location.entity())
+                        .map(|name| Holder::Player(name.clone()))
+                }
+            });
+
+            snapshot
+                .object_index
+                .insert(object.id(), snapshot.objects.len());
+            snapshot.objects.push(ObjectSnapshot {
+                id: object.id(),
+                prototype,
+                inherit_scripts: object.inherit_scripts(),
+                name: named.to_string(),
+                description: description.to_string(),
+                keywords: keywords.get_list(),
+                location,
+                hooks: hooks_of(hooks),
+            });
+        }
+
+        for (prototype, named, description, keywords, hooks) in world
+            .query::<(
+                &Prototype,
+                &Named,
+                &Description,
+                &Keywords,
+                Option<&ScriptHooks>,
+            )>()
+            .iter(world)
+        {
+            snapshot
+                .prototype_index
+                .insert(prototype.id(), snapshot.prototypes.len());
+            snapshot.prototypes.push(PrototypeSnapshot {
+                id: prototype.id(),
+                name: named.to_string(),
+                description: description.to_string(),
+                keywords: keywords.get_list(),
+                hooks: hooks_of(hooks),
+            });
+        }
+
+        for (player, named, description, location, contents, hooks) in world
+            .query::<(
+                &player::Player,
+                &Named,
+                &Description,
+                &Location,
+                &Contents,
+                Option<&ScriptHooks>,
+            )>()
+            .iter(world)
+        {
+            snapshot
+                .player_index
+                .insert(named.to_string(), snapshot.players.len());
+            snapshot.players.push(PlayerSnapshot {
+                id: player.id(),
+                name: named.to_string(),
+                description: description.to_string(),
+                room: room_ids.get(&location.entity()).copied(),
+                inventory: objects_in(contents),
+                hooks: hooks_of(hooks),
+            });
+        }
+
+        for (script, error) in read_all_scripts(world) {
+            snapshot
+                .script_index
+                .insert(script.name().to_string(), snapshot.scripts.len());
+            snapshot.scripts.push(ScriptSnapshot {
+                name: script.name().to_string(),
+                trigger: script.trigger().to_string(),
+                code: script.code(),
+                revision: script.revision(),
+                tags: script.tags().to_vec(),
+                folder: script.folder().map(ToString::to_string),
+                enabled: script.enabled(),
+                error: error.map(|e| e.to_string()),
+            });
+        }
+
+        snapshot
+    }
+
+    fn room(&self, id: RoomId) -> Option<RoomNode<'_>> {
+        self.room_index.get(&id).map(|index| RoomNode {
+            world: self,
+            room: &self.rooms[*index],
+        })
+    }
+
+    fn object(&self, id: ObjectId) -> Option<ObjectNode<'_>> {
+        self.object_index.get(&id).map(|index| ObjectNode {
+            world: self,
+            object: &self.objects[*index],
+        })
+    }
+
+    fn prototype(&self, id: PrototypeId) -> Option<PrototypeNode<'_>> {
+        self.prototype_index.get(&id).map(|index| PrototypeNode {
+            world: self,
+            prototype: &self.prototypes[*index],
+        })
+    }
+
+    fn player(&self, name: &str) -> Option<PlayerNode<'_>> {
+        self.player_index.get(name).map(|index| PlayerNode {
+            world: self,
+            player: &self.players[*index],
+        })
+    }
+
+    fn script(&self, name: &str) -> Option<ScriptNode<'_>> {
+        self.script_index
+            .get(name)
+            .map(|index| ScriptNode(&self.scripts[*index]))
+    }
+
+    fn objects<'a>(&'a self, ids: &'a [ObjectId]) -> Vec<ObjectNode<'a>> {
+        ids.iter().filter_map(|id| self.object(*id)).collect_vec()
+    }
+
+    fn hooks<'a>(&'a self, hooks: &'a [ScriptHook]) -> Vec<HookNode<'a>> {
+        hooks
+            .iter()
+            .map(|hook| HookNode { world: self, hook })
+            .collect_vec()
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// All rooms, optionally limited to those in a region.
+    async fn rooms<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        region: Option<String>,
+    ) -> Vec<RoomNode<'ctx>> {
+        let world = ctx.data_unchecked::<WorldSnapshot>();
+        world
+            .rooms
+            .iter()
+            .filter(|room| match &region {
+                Some(region) => room.regions.contains(region),
+                None => true,
+            })
+            .map(|room| RoomNode { world, room })
+            .collect_vec()
+    }
+
+    async fn room<'ctx>(&self, ctx: &Context<'ctx>, id: ID) -> Option<RoomNode<'ctx>> {
+        let world = ctx.data_unchecked::<WorldSnapshot>();
+        id.parse().ok().and_then(|id| world.room(id))
+    }
+
+    async fn objects<'ctx>(&self, ctx: &Context<'ctx>) -> Vec<ObjectNode<'ctx>> {
+        let world = ctx.data_unchecked::<WorldSnapshot>();
+        world
+            .objects
+            .iter()
+            .map(|object| ObjectNode { world, object })
+            .collect_vec()
+    }
+
+    async fn object<'ctx>(&self, ctx: &Context<'ctx>, id: ID) -> Option<ObjectNode<'ctx>> {
+        let world = ctx.data_unchecked::<WorldSnapshot>();
+        id.parse().ok().and_then(|id| world.object(id))
+    }
+
+    async fn prototypes<'ctx>(&self, ctx: &Context<'ctx>) -> Vec<PrototypeNode<'ctx>> {
+        let world = ctx.data_unchecked::<WorldSnapshot>();
+        world
+            .prototypes
+            .iter()
+            .map(|prototype| PrototypeNode { world, prototype })
+            .collect_vec()
+    }
+
+    async fn prototype<'ctx>(&self, ctx: &Context<'ctx>, id: ID) -> Option<PrototypeNode<'ctx>> {
+        let world = ctx.data_unchecked::<WorldSnapshot>();
+        id.parse().ok().and_then(|id| world.prototype(id))
+    }
+
+    /// Players currently connected to the game.
+    async fn players_online<'ctx>(&self, ctx: &Context<'ctx>) -> Vec<PlayerNode<'ctx>> {
+        let world = ctx.data_unchecked::<WorldSnapshot>();
+        world
+            .players
+            .iter()
+            .map(|player| PlayerNode { world, player })
+            .collect_vec()
+    }
+
+    async fn player<'ctx>(&self, ctx: &Context<'ctx>, name: String) -> Option<PlayerNode<'ctx>> {
+        ctx.data_unchecked::<WorldSnapshot>().player(name.as_str())
+    }
+
+    async fn scripts<'ctx>(&self, ctx: &Context<'ctx>) -> Vec<ScriptNode<'ctx>> {
+        ctx.data_unchecked::<WorldSnapshot>()
+            .scripts
+            .iter()
+            .map(ScriptNode)
+            .collect_vec()
+    }
+
+    async fn script<'ctx>(&self, ctx: &Context<'ctx>, name: String) -> Option<ScriptNode<'ctx>> {
+        ctx.data_unchecked::<WorldSnapshot>().script(name.as_str())
+    }
+}
+
+pub struct RoomNode<'a> {
+    world: &'a WorldSnapshot,
+    room: &'a RoomSnapshot,
+}
+
+#[Object(name = "Room")]
+impl<'a> RoomNode<'a> {
+    async fn id(&self) -> ID {
+        ID(self.room.id.to_string())
+    }
+
+    async fn name(&self) -> &str {
+        self.room.name.as_str()
+    }
+
+    async fn description(&self) -> &str {
+        self.room.description.as_str()
+    }
+
+    async fn regions(&self) -> &[String] {
+        self.room.regions.as_slice()
+    }
+
+    async fn exits(&self) -> Vec<ExitNode<'a>> {
+        self.room
+            .exits
+            .iter()
+            .filter_map(|(direction, destination)| {
+                self.world.room(*destination).map(|room| ExitNode {
+                    direction: direction.as_str(),
+                    room,
+                })
+            })
+            .collect_vec()
+    }
+
+    async fn contents(&self) -> Vec<ObjectNode<'a>> {
+        self.world.objects(self.room.objects.as_slice())
+    }
+
+    async fn players(&self) -> Vec<PlayerNode<'a>> {
+        self.room
+            .players
+            .iter()
+            .filter_map(|name| self.world.player(name.as_str()))
+            .collect_vec()
+    }
+
+    async fn scripts(&self) -> Vec<HookNode<'a>> {
+        self.world.hooks(self.room.hooks.as_slice())
+    }
+}
+
+pub struct ExitNode<'a> {
+    direction: &'a str,
+    room: RoomNode<'a>,
+}
+
+#[Object(name = "Exit")]
+impl<'a> ExitNode<'a> {
+    async fn direction(&self) -> &str {
+        self.direction
+    }
+
+    async fn room(&self) -> &RoomNode<'a> {
+        &self.room
+    }
+}
+
+pub struct ObjectNode<'a> {
+    world: &'a WorldSnapshot,
+    object: &'a ObjectSnapshot,
+}
+
+#[Object(name = "Object")]
+impl<'a> ObjectNode<'a> {
+    async fn id(&self) -> ID {
+        ID(self.object.id.to_string())
+    }
+
+    async fn name(&self) -> &str {
+        self.object.name.as_str()
+    }
+
+    async fn description(&self) -> &str {
+        self.object.description.as_str()
+    }
+
+    async fn keywords(&self) -> &[String] {
+        self.object.keywords.as_slice()
+    }
+
+    async fn inherit_scripts(&self) -> bool {
+        self.object.inherit_scripts
+    }
+
+    async fn prototype(&self) -> Option<PrototypeNode<'a>> {
+        self.world.prototype(self.object.prototype)
+    }
+
+    /// The room containing the object, if it is not being carried.
+    async fn room(&self) -> Option<RoomNode<'a>> {
+        match &self.object.location {
+            Some(Holder::Room(id)) => self.world.room(*id),
+            _ => None,
+        }
+    }
+
+    /// The player carrying the object, if any.
+    async fn carried_by(&self) -> Option<PlayerNode<'a>> {
+        match &self.object.location {
+            Some(Holder::Player(name)) => self.world.player(name.as_str()),
+            _ => None,
+        }
+    }
+
+    async fn scripts(&self) -> Vec<HookNode<'a>> {
+        self.world.hooks(self.object.hooks.as_slice())
+    }
+}
+
+pub struct PrototypeNode<'a> {
+    world: &'a WorldSnapshot,
+    prototype: &'a PrototypeSnapshot,
+}
+
+#[Object(name = "Prototype")]
+impl<'a> PrototypeNode<'a> {
+    async fn id(&self) -> ID {
+        ID(self.prototype.id.to_string())
+    }
+
+    async fn name(&self) -> &str {
+        self.prototype.name.as_str()
+    }
+
+    async fn description(&self) -> &str {
+        self.prototype.description.as_str()
+    }
+
+    async fn keywords(&self) -> &[String] {
+        self.prototype.keywords.as_slice()
+    }
+
+    /// Objects created from this prototype.
+    async fn objects(&self) -> Vec<ObjectNode<'a>> {
+        let world = self.world;
+        world
+            .objects
+            .iter()
+            .filter(|object| object.prototype == self.prototype.id)
+            .map(|object| ObjectNode { world, object })
+            .collect_vec()
+    }
+
+    async fn scripts(&self) -> Vec<HookNode<'a>> {
+        self.world.hooks(self.prototype.hooks.as_slice())
+    }
+}
+
+pub struct PlayerNode<'a> {
+    world: &'a WorldSnapshot,
+    player: &'a PlayerSnapshot,
+}
+
+#[Object(name = "Player")]
+impl<'a> PlayerNode<'a> {
+    async fn id(&self) -> ID {
+        ID(self.player.id.to_string())
+    }
+
+    async fn name(&self) -> &str {
+        self.player.name.as_str()
+    }
+
+    async fn description(&self) -> &str {
+        self.player.description.as_str()
+    }
+
+    async fn room(&self) -> Option<RoomNode<'a>> {
+        self.player.room.and_then(|id| self.world.room(id))
+    }
+
+    async fn inventory(&self) -> Vec<ObjectNode<'a>> {
+        self.world.objects(self.player.inventory.as_slice())
+    }
+
+    async fn scripts(&self) -> Vec<HookNode<'a>> {
+        self.world.hooks(self.player.hooks.as_slice())
+    }
+}
+
+pub struct ScriptNode<'a>(&'a ScriptSnapshot);
+
+#[Object(name = "Script")]
+impl<'a> ScriptNode<'a> {
+    async fn name(&self) -> &str {
+        self.0.name.as_str()
+    }
+
+    async fn trigger(&self) -> &str {
+        self.0.trigger.as_str()
+    }
+
+    async fn code(&self) -> &str {
+        self.0.code.as_str()
+    }
+
+    async fn lines(&self) -> usize {
+        self.0.code.lines().count()
+    }
+
+    async fn revision(&self) -> i64 {
+        self.0.revision
+    }
+
+    async fn tags(&self) -> &[String] {
+        self.0.tags.as_slice()
+    }
+
+    async fn folder(&self) -> Option<&str> {
+        self.0.folder.as_deref()
+    }
+
+    /// Disabled scripts stay attached but do not run.
+    async fn enabled(&self) -> bool {
+        self.0.enabled
+    }
+
+    /// The compilation error for the script, if it failed to compile.
+    async fn error(&self) -> Option<&str> {
+        self.0.error.as_deref()
+    }
+}
+
+pub struct HookNode<'a> {
+    world: &'a WorldSnapshot,
+    hook: &'a ScriptHook,
+}
+
+#[Object(name = "ScriptHook")]
+impl<'a> HookNode<'a> {
+    /// One of Custom, Init, PreEvent, PostEvent, or Timer.
+    async fn kind(&self) -> String {
+        self.hook.trigger.kind().to_string()
+    }
+
+    /// The event which runs the script for PreEvent and PostEvent hooks.
+    async fn event(&self) -> Option<String> {
+        match &self.hook.trigger {
+            ScriptTrigger::PreEvent(event) | ScriptTrigger::PostEvent(event) => {
+                Some(event.to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// The timer name for Timer hooks.
+    async fn timer(&self) -> Option<&str> {
+        match &self.hook.trigger {
+            ScriptTrigger::Timer(name) => Some(name.as_str()),
+            _ => None,
+        }
+    }
+
+    /// The signal name for Custom hooks.
+    async fn signal(&self) -> Option<&str> {
+        match &self.hook.trigger {
+            ScriptTrigger::Custom(name) => Some(name.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Scripts hooked to the same trigger on an entity run highest priority first.
+    async fn priority(&self) -> i64 {
+        self.hook.priority
+    }
+
+    /// Disabled hooks stay attached but do not run their script.
+    async fn enabled(&self) -> bool {
+        self.hook.enabled
+    }
+
+    async fn script(&self) -> Option<ScriptNode<'a>> {
+        self.world.script(self.hook.script.to_string().as_str())
+    }
+}
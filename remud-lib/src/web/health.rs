@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use utoipa::ToSchema;
+use warp::{any, http::StatusCode, reply, Filter};
+
+use crate::{
+    engine::{db::AuthDb, Heartbeat},
+    web::with_db,
+};
+
+// The engine is considered stalled when it has not finished a tick for this long.
+const STALLED_ENGINE: Duration = Duration::from_secs(5);
+
+pub fn health_filters<DB>(
+    db: DB,
+    heartbeat: Heartbeat,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    healthz().or(readyz(db, heartbeat))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonHealthResponse {
+    ok: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonReadyResponse {
+    ok: bool,
+    /// Whether the engine has finished a tick in the last five seconds.
+    engine: bool,
+    /// Whether the database answered a query.
+    database: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "health",
+    responses(
+        (status = 200, description = "The web server is running", body = JsonHealthResponse),
+    )
+)]
+pub fn healthz() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("healthz")
+        .and(warp::get())
+        .map(|| reply::json(&JsonHealthResponse { ok: true }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "health",
+    responses(
+        (status = 200, description = "The engine is ticking and the database is reachable", body = JsonReadyResponse),
+        (status = 503, description = "The engine has stalled or the database is unreachable", body = JsonReadyResponse),
+    )
+)]
+pub fn readyz<DB>(
+    db: DB,
+    heartbeat: Heartbeat,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path!("readyz")
+        .and(warp::get())
+        .and(with_db(db))
+        .and(any().map(move || heartbeat.clone()))
+        .and_then(handle_readyz)
+}
+
+async fn handle_readyz<DB: AuthDb>(
+    db: DB,
+    heartbeat: Heartbeat,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let engine = matches!(heartbeat.since_last(), Some(since) if since < STALLED_ENGINE);
+
+    let database = match db.ping().await {
+        Ok(()) => true,
+        Err(err) => {
+            tracing::warn!("readiness check failed to reach the database: {}", err);
+            false
+        }
+    };
+
+    let ok = engine && database;
+    let status = if ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    Ok(reply::with_status(
+        reply::json(&JsonReadyResponse {
+            ok,
+            engine,
+            database,
+        }),
+        status,
+    ))
+}
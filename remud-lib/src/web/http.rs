@@ -0,0 +1,37 @@
+use std::{convert::Infallible, net::SocketAddr};
+
+use warp::{
+    hyper::{
+        server::conn::AddrStream,
+        service::{make_service_fn, service_fn},
+        Body, Request, Server,
+    },
+    Filter, Reply,
+};
+
+use crate::web::{limit::RemoteAddr, request_id::call_with_request_id};
+
+/// Serves the filter over plain HTTP. Like `serve_tls`, the connection's address and an ID are
+/// attached to each request.
+pub async fn serve_http<F>(filter: F, address: ([u8; 4], u16)) -> Result<(), warp::hyper::Error>
+where
+    F: Filter<Error = Infallible> + Clone + Send + Sync + 'static,
+    F::Extract: Reply,
+{
+    let service = warp::service(filter);
+
+    let make_service = make_service_fn(move |connection: &AddrStream| {
+        let peer = connection.remote_addr();
+        let service = service.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |mut request: Request<Body>| {
+                request.extensions_mut().insert(RemoteAddr(peer));
+                call_with_request_id(service.clone(), request)
+            }))
+        }
+    });
+
+    Server::bind(&SocketAddr::from(address))
+        .serve(make_service)
+        .await
+}
@@ -0,0 +1,144 @@
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use thiserror::Error;
+use utoipa::ToSchema;
+use warp::{reject, Filter};
+
+use crate::{
+    engine::db::AuthDb,
+    web::{with_db, ErrorMessage, InternalError},
+    world::types::leaderboard::{LeaderboardCategory, LeaderboardEntry},
+};
+
+// Leaderboards only change when they are aggregated, so responses are cached briefly rather than
+// reading the database for every request.
+const CACHE_SECS: u64 = 60;
+
+type CachedEntries = (Instant, Vec<LeaderboardEntry>);
+
+#[derive(Clone, Default)]
+struct LeaderboardCache {
+    entries: Arc<Mutex<HashMap<LeaderboardCategory, CachedEntries>>>,
+}
+
+impl LeaderboardCache {
+    fn get(&self, category: LeaderboardCategory) -> Option<Vec<LeaderboardEntry>> {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(&category)
+            .filter(|(cached, _)| cached.elapsed() < Duration::from_secs(CACHE_SECS))
+            .map(|(_, entries)| entries.clone())
+    }
+
+    fn insert(&self, category: LeaderboardCategory, entries: Vec<LeaderboardEntry>) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(category, (Instant::now(), entries));
+    }
+}
+
+pub fn leaderboard_filters<DB>(
+    db: DB,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    leaderboard(db)
+}
+
+#[derive(Debug, Error)]
+pub enum LeaderboardError {
+    #[error("leaderboard not found")]
+    LeaderboardNotFound,
+}
+
+impl warp::reject::Reject for LeaderboardError {}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonLeaderboardEntry {
+    rank: usize,
+    name: String,
+    /// The player's score: rooms visited for explored, seconds played for playtime.
+    value: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonLeaderboardResponse {
+    category: String,
+    entries: Vec<JsonLeaderboardEntry>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/leaderboards/{category}",
+    tag = "leaderboards",
+    params(("category" = String, Path, description = "The leaderboard category: explored or playtime")),
+    responses(
+        (status = 200, description = "The top players in the category, in rank order", body = JsonLeaderboardResponse),
+        (status = 404, description = "Leaderboard not found", body = ErrorMessage),
+    )
+)]
+pub fn leaderboard<DB>(
+    db: DB,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    let cache = LeaderboardCache::default();
+
+    warp::path!("leaderboards" / String)
+        .and(warp::get())
+        .and(with_db(db))
+        .and(warp::any().map(move || cache.clone()))
+        .and_then(handle_leaderboard)
+}
+
+async fn handle_leaderboard<DB: AuthDb>(
+    category: String,
+    db: DB,
+    cache: LeaderboardCache,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let category = LeaderboardCategory::from_str(category.as_str())
+        .map_err(|_| reject::custom(LeaderboardError::LeaderboardNotFound))?;
+
+    let entries = match cache.get(category) {
+        Some(entries) => entries,
+        None => match db.leaderboard(category).await {
+            Ok(entries) => {
+                cache.insert(category, entries.clone());
+                entries
+            }
+            Err(err) => {
+                tracing::error!("failed to retrieve leaderboard: {}", err);
+                return Err(reject::custom(InternalError {}));
+            }
+        },
+    };
+
+    let response = JsonLeaderboardResponse {
+        category: category.to_string(),
+        entries: entries
+            .into_iter()
+            .enumerate()
+            .map(|(rank, entry)| JsonLeaderboardEntry {
+                rank: rank + 1,
+                name: entry.name,
+                value: entry.value,
+            })
+            .collect(),
+    };
+
+    Ok(warp::reply::with_header(
+        warp::reply::json(&response),
+        "cache-control",
+        format!("public, max-age={}", CACHE_SECS),
+    ))
+}
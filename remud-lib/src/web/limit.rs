@@ -0,0 +1,354 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+use warp::{reject, Filter, Rejection};
+
+use crate::{
+    clock::Clock,
+    engine::db::AuthDb,
+    metrics::stats_incr,
+    web::{auth::verify_access, Player},
+};
+
+// Once this many buckets are tracked, buckets which have refilled are forgotten, and if none have,
+// the least recently used bucket is.
+const MAX_BUCKETS: usize = 4096;
+
+/// How many requests each address and account may make per minute after an initial burst, for
+/// logging in and refreshing tokens, and for changing scripts and the world. A rate of 0 disables
+/// the limit.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitOptions {
+    pub auth_per_minute: u32,
+    pub auth_burst: u32,
+    pub write_per_minute: u32,
+    pub write_burst: u32,
+}
+
+/// The address a request's connection was accepted from.
+#[derive(Debug, Clone, Copy)]
+pub struct RemoteAddr(pub SocketAddr);
+
+#[derive(Debug)]
+pub struct RateLimited {
+    pub retry_after: Duration,
+}
+
+impl reject::Reject for RateLimited {}
+
+/// The rate limiters shared by the web server's endpoints.
+#[derive(Debug, Clone)]
+pub struct RateLimits {
+    pub auth: RateLimiter,
+    pub writes: RateLimiter,
+}
+
+impl RateLimits {
+    pub fn new(options: RateLimitOptions, clock: Clock) -> Self {
+        RateLimits {
+            auth: RateLimiter::new(
+                "auth",
+                options.auth_per_minute,
+                options.auth_burst,
+                clock.clone(),
+            ),
+            writes: RateLimiter::new(
+                "write",
+                options.write_per_minute,
+                options.write_burst,
+                clock,
+            ),
+        }
+    }
+
+    /// Changes the limits in place, keeping the requests already counted against each key.
+    pub fn update(&self, options: RateLimitOptions) {
+        self.auth
+            .set_rate(options.auth_per_minute, options.auth_burst);
+        self.writes
+            .set_rate(options.write_per_minute, options.write_burst);
+    }
+}
+
+// A token bucket, which holds up to the burst and refills at the limiter's rate.
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn refilled(&self, now: Instant, per_sec: f64, capacity: f64) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        (self.tokens + elapsed * per_sec).min(capacity)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rate {
+    per_minute: u32,
+    burst: u32,
+}
+
+/// Limits requests by key with token buckets.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    name: &'static str,
+    rate: Arc<RwLock<Rate>>,
+    clock: Clock,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new(name: &'static str, per_minute: u32, burst: u32, clock: Clock) -> Self {
+        RateLimiter {
+            name,
+            rate: Arc::new(RwLock::new(Rate { per_minute, burst })),
+            clock,
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn set_rate(&self, per_minute: u32, burst: u32) {
+        *self.rate.write().unwrap() = Rate { per_minute, burst };
+    }
+
+    /// Takes a token from the key's bucket, or returns how long until one is available.
+    pub fn check(&self, key: &str) -> Result<(), RateLimited> {
+        self.take(key, true)
+    }
+
+    // Refills the key's bucket and, if asked to, takes a token from it. Returns how long until a
+    // token is available if the bucket is empty.
+    fn take(&self, key: &str, charge: bool) -> Result<(), RateLimited> {
+        let rate = *self.rate.read().unwrap();
+        if rate.per_minute == 0 {
+            return Ok(());
+        }
+
+        let now = self.clock.now();
+        let per_sec = f64::from(rate.per_minute) / 60.0;
+        let capacity = f64::from(rate.burst.max(1));
+
+        let tokens = {
+            let mut buckets = self.buckets.lock().unwrap();
+
+            if buckets.len() >= MAX_BUCKETS && !buckets.contains_key(key) {
+                buckets.retain(|_, bucket| bucket.refilled(now, per_sec, capacity) < capacity);
+
+                if buckets.len() >= MAX_BUCKETS {
+                    let oldest = buckets
+                        .iter()
+                        .min_by_key(|(_, bucket)| bucket.updated)
+                        .map(|(key, _)| key.clone());
+                    if let Some(oldest) = oldest {
+                        buckets.remove(&oldest);
+                    }
+                }
+            }
+
+            let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+                tokens: capacity,
+                updated: now,
+            });
+            bucket.tokens = bucket.refilled(now, per_sec, capacity);
+            bucket.updated = now;
+
+            if bucket.tokens >= 1.0 {
+                if charge {
+                    bucket.tokens -= 1.0;
+                }
+                return Ok(());
+            }
+
+            bucket.tokens
+        };
+
+        tracing::warn!("rate limited {} request from {}", self.name, key);
+        stats_incr(format!("web.rate_limited.{}", self.name).as_str());
+
+        Err(RateLimited {
+            retry_after: Duration::from_secs_f64((1.0 - tokens) / per_sec),
+        })
+    }
+
+    /// Takes a token from the bucket for a player or API token.
+    pub fn check_account(&self, name: &str) -> Result<(), RateLimited> {
+        self.check(account_key(name).as_str())
+    }
+
+    /// Returns how long until a player may try a password again, without taking a token, so that
+    /// only failed attempts lock a player out.
+    pub fn check_account_attempt(&self, name: &str) -> Result<(), RateLimited> {
+        self.take(account_key(name).as_str(), false)
+    }
+
+    /// Takes a token from a player's bucket for a failed password attempt.
+    pub fn charge_account_failure(&self, name: &str) {
+        // The attempt was already refused, so whether the bucket ran dry only matters next time.
+        let _ = self.check(account_key(name).as_str());
+    }
+
+    fn check_address(&self, address: IpAddr) -> Result<(), RateLimited> {
+        self.check(format!("address:{}", address).as_str())
+    }
+}
+
+fn account_key(name: &str) -> String {
+    format!("account:{}", name.to_lowercase())
+}
+
+/// Limits requests by the address they came from. Requests from unknown addresses are let
+/// through.
+pub fn limit_address(limiter: RateLimiter) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::ext::optional::<RemoteAddr>()
+        .and_then(move |remote: Option<RemoteAddr>| {
+            let limiter = limiter.clone();
+            async move {
+                match remote {
+                    Some(RemoteAddr(address)) => {
+                        limiter.check_address(address.ip()).map_err(reject::custom)
+                    }
+                    None => Ok(()),
+                }
+            }
+        })
+        .untuple_one()
+}
+
+/// Like `verify_access`, but limits requests by the address they came from and by the player or
+/// API token making them.
+pub fn limited_access<DB>(
+    db: DB,
+    clock: Clock,
+    scopes: Vec<String>,
+    limiter: RateLimiter,
+) -> impl Filter<Extract = (Player,), Error = Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    limit_address(limiter.clone())
+        .and(verify_access(db, clock, scopes))
+        .and_then(move |player: Player| {
+            let limiter = limiter.clone();
+            async move {
+                limiter
+                    .check_account(player.name())
+                    .map(|_| player)
+                    .map_err(reject::custom)
+            }
+        })
+}
+
+pub fn with_limiter(
+    limiter: RateLimiter,
+) -> impl Filter<Extract = (RateLimiter,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || limiter.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::clock::Clock;
+
+    use super::{RateLimitOptions, RateLimiter, RateLimits, MAX_BUCKETS};
+
+    #[test]
+    fn test_rate_limiter() {
+        let clock = Clock::manual();
+        let limiter = RateLimiter::new("test", 60, 2, clock.clone());
+
+        assert!(limiter.check("a").is_ok());
+        assert!(limiter.check("a").is_ok());
+        let limited = limiter.check("a").unwrap_err();
+        assert_eq!(limited.retry_after, Duration::from_secs(1));
+
+        // Keys have their own buckets
+        assert!(limiter.check("b").is_ok());
+
+        clock.advance(Duration::from_secs(1));
+        assert!(limiter.check("a").is_ok());
+        assert!(limiter.check("a").is_err());
+    }
+
+    #[test]
+    fn test_rate_limiter_evicts_oldest() {
+        let clock = Clock::manual();
+        let limiter = RateLimiter::new("test", 1, 1, clock.clone());
+
+        for i in 0..MAX_BUCKETS {
+            assert!(limiter.check(format!("{}", i).as_str()).is_ok());
+            clock.advance(Duration::from_millis(1));
+        }
+
+        // None have refilled, so the least recently used bucket makes room for the new key
+        assert!(limiter.check("new").is_ok());
+        assert_eq!(limiter.buckets.lock().unwrap().len(), MAX_BUCKETS);
+        assert!(limiter.check("0").is_ok());
+        assert!(limiter
+            .check(format!("{}", MAX_BUCKETS - 1).as_str())
+            .is_err());
+    }
+
+    #[test]
+    fn test_rate_limiter_account_failures() {
+        let clock = Clock::manual();
+        let limiter = RateLimiter::new("test", 60, 2, clock.clone());
+
+        // Attempts are free until they fail
+        for _ in 0..5 {
+            assert!(limiter.check_account_attempt("krixi").is_ok());
+        }
+
+        limiter.charge_account_failure("krixi");
+        assert!(limiter.check_account_attempt("Krixi").is_ok());
+        limiter.charge_account_failure("krixi");
+        let limited = limiter.check_account_attempt("krixi").unwrap_err();
+        assert_eq!(limited.retry_after, Duration::from_secs(1));
+
+        clock.advance(Duration::from_secs(1));
+        assert!(limiter.check_account_attempt("krixi").is_ok());
+    }
+
+    #[test]
+    fn test_rate_limiter_disabled() {
+        let limiter = RateLimiter::new("test", 0, 0, Clock::manual());
+        for _ in 0..100 {
+            assert!(limiter.check("a").is_ok());
+        }
+    }
+
+    #[test]
+    fn test_rate_limits_update() {
+        let clock = Clock::manual();
+        let limits = RateLimits::new(
+            RateLimitOptions {
+                auth_per_minute: 60,
+                auth_burst: 2,
+                ..RateLimitOptions::default()
+            },
+            clock,
+        );
+
+        assert!(limits.auth.check("a").is_ok());
+        assert!(limits.auth.check("a").is_ok());
+        assert!(limits.writes.check("a").is_ok());
+
+        limits.update(RateLimitOptions {
+            auth_per_minute: 60,
+            auth_burst: 3,
+            write_per_minute: 60,
+            write_burst: 1,
+        });
+
+        // Requests already made still count against the new limits
+        assert!(limits.auth.check("a").is_err());
+        assert!(limits.writes.check("a").is_ok());
+        assert!(limits.writes.check("a").is_err());
+    }
+}
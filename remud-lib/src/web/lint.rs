@@ -0,0 +1,118 @@
+use serde::Serialize;
+use tokio::sync::{mpsc, oneshot};
+use utoipa::ToSchema;
+use warp::Filter;
+
+use crate::{
+    clock::Clock,
+    engine::db::AuthDb,
+    web::{
+        auth::{verify_access, SCOPE_WORLD_READ},
+        request_id::{request_id, RequestId},
+        send_to_engine, with_sender, ErrorMessage, InternalError, Player, ScriptsRequest,
+        ScriptsResponse, WebMessage,
+    },
+    world::lint::LintReport,
+};
+
+pub fn lint_filters<DB>(
+    db: DB,
+    clock: Clock,
+    tx: mpsc::Sender<WebMessage>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    lint(db, clock, tx)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonLintFinding {
+    /// One of no_exits, one_way_exit, missing_description, empty_keywords, unused_trigger, or
+    /// unreachable.
+    kind: String,
+    /// The room, object, or prototype with the mistake, such as "room 5".
+    subject: String,
+    detail: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonLintResponse {
+    /// When the world was linted, in seconds since the Unix epoch.
+    generated_at: i64,
+    findings: Vec<JsonLintFinding>,
+}
+
+impl From<LintReport> for JsonLintResponse {
+    fn from(report: LintReport) -> Self {
+        JsonLintResponse {
+            generated_at: report.generated_at,
+            findings: report
+                .findings
+                .into_iter()
+                .map(|finding| JsonLintFinding {
+                    kind: finding.kind.to_string(),
+                    subject: finding.subject.to_string(),
+                    detail: finding.detail,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/world/lint",
+    tag = "world",
+    responses(
+        (status = 200, description = "The latest world lint report", body = JsonLintResponse),
+        (status = 401, description = "Missing or inadequate access token", body = ErrorMessage),
+        (status = 503, description = "The engine is too busy to take the request", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
+pub fn lint<DB>(
+    db: DB,
+    clock: Clock,
+    tx: mpsc::Sender<WebMessage>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path!("world" / "lint")
+        .and(warp::get())
+        .and(verify_access(db, clock, vec![SCOPE_WORLD_READ.to_string()]))
+        .and(request_id())
+        .and(with_sender(tx))
+        .and_then(handle_lint)
+}
+
+#[tracing::instrument(name = "read world lint", skip_all, fields(player = player.name.as_str()))]
+async fn handle_lint(
+    player: Player,
+    request_id: RequestId,
+    sender: mpsc::Sender<WebMessage>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    tracing::debug!("player {} reading world lint", player.name());
+
+    let (tx, rx) = oneshot::channel();
+    send_to_engine(
+        &sender,
+        WebMessage {
+            request_id,
+            response: tx,
+            request: ScriptsRequest::ReadLint,
+        },
+    )?;
+
+    match rx.await {
+        Ok(ScriptsResponse::Lint(report)) => {
+            Ok(warp::reply::json(&JsonLintResponse::from(*report)))
+        }
+        other => {
+            tracing::error!("received unexpected response to lint request: {:?}", other);
+            Err(warp::reject::custom(InternalError {}))
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+use warp::{reject, Filter};
+
+use crate::{
+    engine::db::{AuthDb, MarketListing},
+    web::{with_db, InternalError},
+};
+
+pub fn market_filters<DB>(
+    db: DB,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    market(db)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonMarketListing {
+    id: i64,
+    name: String,
+    /// The price of the listing, in coins.
+    price: i64,
+    seller: String,
+    /// When the listing expires, in seconds since the Unix epoch.
+    expires_at: i64,
+}
+
+impl From<MarketListing> for JsonMarketListing {
+    fn from(value: MarketListing) -> Self {
+        JsonMarketListing {
+            id: value.id,
+            name: value.name,
+            price: value.price,
+            seller: value.seller,
+            expires_at: value.expires_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonMarketResponse {
+    listings: Vec<JsonMarketListing>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/market",
+    tag = "market",
+    responses(
+        (status = 200, description = "Objects currently for sale on the market", body = JsonMarketResponse),
+    )
+)]
+pub fn market<DB>(
+    db: DB,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path!("market")
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(handle_market)
+}
+
+async fn handle_market<DB: AuthDb>(db: DB) -> Result<impl warp::Reply, warp::Rejection> {
+    match db.market_listings().await {
+        Ok(listings) => Ok(warp::reply::json(&JsonMarketResponse {
+            listings: listings.into_iter().map(JsonMarketListing::from).collect(),
+        })),
+        Err(err) => {
+            tracing::error!("failed to retrieve market listings: {}", err);
+            Err(reject::custom(InternalError {}))
+        }
+    }
+}
@@ -1,37 +1,94 @@
+mod allowlist;
 mod auth;
+mod communications;
+mod docs;
+mod economy;
+pub mod graphql;
+mod health;
+mod http;
+pub mod leaderboards;
+mod limit;
+mod lint;
+mod market;
+mod news;
+pub mod players;
+mod polls;
+mod request_id;
 pub mod scripts;
 mod security;
+mod settings;
+mod status;
+mod tls;
+mod tokens;
 pub mod ws;
 
-use std::{convert::Infallible, fmt, path::Path};
+use std::{convert::Infallible, fmt, path::Path, sync::Arc};
 
 use serde::Serialize;
 use thiserror::Error;
 use tokio::{
-    sync::{mpsc, oneshot},
+    sync::{
+        mpsc::{self, error::TrySendError},
+        oneshot,
+    },
     task::JoinHandle,
 };
+use utoipa::ToSchema;
 use warp::{
     any,
     http::HeaderValue,
     hyper::{
-        header::{CONTENT_TYPE, WWW_AUTHENTICATE},
+        header::{CONTENT_TYPE, RETRY_AFTER, WWW_AUTHENTICATE},
         Response, StatusCode,
     },
-    reject::Reject,
-    serve, Filter, Rejection, Reply, Server, TlsServer,
+    reject::{InvalidQuery, Reject},
+    Filter, Rejection, Reply,
 };
 
+pub(crate) use crate::web::auth::API_TOKEN_PLAYER_PREFIX;
+pub use crate::web::limit::RateLimitOptions;
+pub(crate) use crate::web::settings::WebSettings;
 use crate::web::ws::websocket_filters;
 use crate::{
-    engine::{db::AuthDb, ClientMessage},
+    clock::Clock,
+    engine::{db::AuthDb, ClientMessage, Heartbeat},
+    metrics::stats_incr,
     web::{
+        allowlist::{allowlist_filters, AllowlistError},
         auth::{auth_filters, AuthError},
+        communications::{communication_filters, CommunicationError},
+        docs::docs_filters,
+        economy::economy_filters,
+        graphql::{graphql_filters, WorldSnapshot},
+        health::health_filters,
+        http::serve_http,
+        leaderboards::{leaderboard_filters, LeaderboardError},
+        limit::RateLimited,
+        lint::lint_filters,
+        market::market_filters,
+        news::news_filters,
+        players::{player_filters, JsonPlayerExport, PlayerError},
+        polls::poll_filters,
+        request_id::{RequestId, REQUEST_ID_HEADER},
         scripts::{
-            script_filters, JsonParseError, JsonScript, JsonScriptInfo, JsonScriptName,
-            JsonScriptResponse, ScriptError,
+            script_filters, JsonParseError, JsonScript, JsonScriptName, JsonScriptResponse,
+            JsonScriptStatsResponse, JsonScriptsQuery, JsonScriptsResponse, ScriptError,
+        },
+        security::{
+            renew_certificate, retrieve_certificate, retrieve_jwt_key, CertificateError,
+            CertificateResolver, JwtError,
+        },
+        settings::{allow_origin, OriginNotAllowed},
+        status::status_filters,
+        tls::serve_tls,
+        tokens::{token_filters, TokenError},
+    },
+    world::{
+        lint::LintReport,
+        types::{
+            communication::{CommunicationFilter, CommunicationPage},
+            economy::EconomySummary,
         },
-        security::{retrieve_certificate, retrieve_jwt_key, CertificateError, JwtError},
     },
 };
 
@@ -70,15 +127,28 @@ impl<'a> WebOptions<'a> {
     }
 }
 
-#[derive(Debug)]
+const DEFAULT_CHALLENGE_PORT: u16 = 80;
+
+#[derive(Debug, Clone, Copy)]
 pub struct TlsOptions<'a> {
     domain: &'a str,
     email: &'a str,
+    challenge_port: u16,
 }
 
 impl<'a> TlsOptions<'a> {
     pub fn new(domain: &'a str, email: &'a str) -> Self {
-        TlsOptions { domain, email }
+        TlsOptions {
+            domain,
+            email,
+            challenge_port: DEFAULT_CHALLENGE_PORT,
+        }
+    }
+
+    /// Sets the port which serves ACME HTTP-01 challenges while certificates are requested.
+    pub fn with_challenge_port(mut self, port: u16) -> Self {
+        self.challenge_port = port;
+        self
     }
 }
 
@@ -90,93 +160,158 @@ pub enum Error {
     JwtError(#[from] JwtError),
 }
 
-#[tracing::instrument(name = "starting web server", skip(db, web_tx, client_tx))]
+#[tracing::instrument(
+    name = "starting web server",
+    skip(settings, db, web_tx, client_tx, heartbeat, clock)
+)]
 pub(crate) async fn run_web_server<'a, DB>(
     options: &WebOptions<'a>,
+    settings: WebSettings,
     db: DB,
     web_tx: mpsc::Sender<WebMessage>,
     client_tx: mpsc::Sender<ClientMessage>,
+    heartbeat: Heartbeat,
+    clock: Clock,
 ) -> Result<JoinHandle<()>, Error>
 where
     DB: AuthDb + Clone + Send + Sync + 'static,
 {
     let address = options.address();
     let handle = if let Some(tls) = &options.tls {
-        let web_server = build_tls_server(
+        let certificate =
+            retrieve_certificate(options.keys, tls.domain, tls.email, tls.challenge_port).await?;
+        let resolver = Arc::new(CertificateResolver::new(&certificate)?);
+
+        let routes = build_routes(
             db,
             web_tx,
             client_tx,
+            heartbeat,
+            clock,
             options.keys,
-            options.cors.as_slice(),
-            tls.domain,
-            tls.email,
+            settings,
         )
         .await?;
-        tokio::spawn(async move { web_server.run(address).await })
+
+        let renewal = renew_certificate(
+            resolver.clone(),
+            options.keys.to_path_buf(),
+            tls.domain.to_string(),
+            tls.email.to_string(),
+            tls.challenge_port,
+        );
+
+        tokio::spawn(async move {
+            tokio::select! {
+                result = serve_tls(routes, address, resolver) => {
+                    if let Err(e) = result {
+                        tracing::error!("TLS web server failed: {}", e);
+                    }
+                }
+                _ = renewal => (),
+            }
+        })
     } else {
-        let web_server =
-            build_web_server(db, web_tx, client_tx, options.keys, options.cors.as_slice()).await?;
-        tokio::spawn(async move { web_server.run(address).await })
+        let routes = build_routes(
+            db,
+            web_tx,
+            client_tx,
+            heartbeat,
+            clock,
+            options.keys,
+            settings,
+        )
+        .await?;
+        tokio::spawn(async move {
+            if let Err(e) = serve_http(routes, address).await {
+                tracing::error!("web server failed: {}", e);
+            }
+        })
     };
 
     Ok(handle)
 }
 
-async fn build_tls_server<DB>(
-    db: DB,
-    web_tx: mpsc::Sender<WebMessage>,
-    client_tx: mpsc::Sender<ClientMessage>,
-    key_path: &Path,
-    cors: &[&str],
-    domain: &str,
-    email: &str,
-) -> Result<TlsServer<impl Filter<Extract = impl Reply, Error = Rejection> + Clone>, Error>
-where
-    DB: AuthDb + Clone + Send + Sync + 'static,
-{
-    let certificate = retrieve_certificate(key_path, domain, email).await?;
-
-    Ok(build_web_server(db, web_tx, client_tx, key_path, cors)
-        .await?
-        .tls()
-        .key(certificate.private_key())
-        .cert(certificate.certificate()))
-}
-
-async fn build_web_server<DB>(
+async fn build_routes<DB>(
     db: DB,
     web_tx: mpsc::Sender<WebMessage>,
     client_tx: mpsc::Sender<ClientMessage>,
+    heartbeat: Heartbeat,
+    clock: Clock,
     key_path: &Path,
-    cors: &[&str],
-) -> Result<Server<impl Filter<Extract = impl Reply, Error = Rejection> + Clone>, Error>
+    settings: WebSettings,
+) -> Result<impl Filter<Extract = impl Reply, Error = Infallible> + Clone, Error>
 where
     DB: AuthDb + Clone + Send + Sync + 'static,
 {
     retrieve_jwt_key(key_path).await?;
 
-    let cors = if cors.is_empty() {
-        warp::cors().allow_any_origin()
-    } else {
-        warp::cors().allow_origins(cors.iter().copied())
-    }
-    .allow_methods(vec!["POST", "OPTIONS"])
-    .allow_headers(vec!["content-type", "x-requested-with", "authorization"]);
-
-    let routes = auth_filters(db.clone())
-        .or(script_filters(db, web_tx))
-        .or(websocket_filters(client_tx))
+    // Origins are checked by `allow_origin`, which reads the allowed origins for each request.
+    let cors = warp::cors()
+        .allow_any_origin()
+        .allow_methods(vec!["GET", "POST", "OPTIONS"])
+        .allow_headers(vec![
+            "content-type",
+            "x-requested-with",
+            "authorization",
+            REQUEST_ID_HEADER,
+        ])
+        .expose_headers(vec![REQUEST_ID_HEADER]);
+
+    let limits = settings.limits();
+
+    let routes = auth_filters(db.clone(), clock.clone(), limits.clone())
+        .or(token_filters(db.clone(), clock.clone(), limits.clone()))
+        .or(player_filters(
+            db.clone(),
+            clock.clone(),
+            web_tx.clone(),
+            limits.clone(),
+        ))
+        .or(leaderboard_filters(db.clone()))
+        .or(status_filters(db.clone()))
+        .or(health_filters(db.clone(), heartbeat))
+        .or(market_filters(db.clone()))
+        .or(news_filters(db.clone()))
+        .or(poll_filters(db.clone()))
+        .or(script_filters(
+            db.clone(),
+            clock.clone(),
+            web_tx.clone(),
+            limits.clone(),
+        ))
+        .or(allowlist_filters(
+            db.clone(),
+            clock.clone(),
+            web_tx.clone(),
+            limits,
+        ))
+        .or(lint_filters(db.clone(), clock.clone(), web_tx.clone()))
+        .or(economy_filters(db.clone(), clock.clone(), web_tx.clone()))
+        .or(communication_filters(
+            db.clone(),
+            clock.clone(),
+            web_tx.clone(),
+        ))
+        .or(graphql_filters(db.clone(), clock.clone(), web_tx))
+        .or(websocket_filters(db, clock, client_tx))
+        .or(docs_filters(settings.clone()))
         .recover(handle_rejection);
-    let wrapped = routes.with(cors);
 
-    Ok(serve(wrapped))
+    Ok(allow_origin(settings)
+        .and(routes.with(cors))
+        .recover(handle_rejection))
 }
 
 #[derive(Debug)]
 pub struct InternalError {}
 impl Reject for InternalError {}
 
-#[derive(Debug, Serialize)]
+#[derive(Debug)]
+pub struct EngineBusy {}
+impl Reject for EngineBusy {}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct JsonEmpty {}
 
 fn with_db<DB>(db: DB) -> impl Filter<Extract = (DB,), Error = std::convert::Infallible> + Clone
@@ -186,6 +321,40 @@ where
     any().map(move || db.clone())
 }
 
+fn with_clock(
+    clock: Clock,
+) -> impl Filter<Extract = (Clock,), Error = std::convert::Infallible> + Clone {
+    any().map(move || clock.clone())
+}
+
+fn with_sender(
+    tx: mpsc::Sender<WebMessage>,
+) -> impl Filter<Extract = (mpsc::Sender<WebMessage>,), Error = std::convert::Infallible> + Clone {
+    any().map(move || tx.clone())
+}
+
+/// Queues a message for the engine without waiting. Requests are shed rather than queued behind
+/// a full engine queue, so clients can back off.
+fn send_to_engine(sender: &mpsc::Sender<WebMessage>, message: WebMessage) -> Result<(), Rejection> {
+    let request_id = message.request_id.clone();
+
+    match sender.try_send(message) {
+        Ok(()) => Ok(()),
+        Err(TrySendError::Full(_)) => {
+            tracing::warn!("engine queue is full, shedding web request {}", request_id);
+            stats_incr("web.engine_busy");
+            Err(warp::reject::custom(EngineBusy {}))
+        }
+        Err(TrySendError::Closed(_)) => {
+            tracing::error!(
+                "failed to send web request {} to engine: engine stopped",
+                request_id
+            );
+            Err(warp::reject::custom(InternalError {}))
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Player {
     name: String,
@@ -198,6 +367,7 @@ impl Player {
 }
 
 pub struct WebMessage {
+    pub request_id: RequestId,
     pub response: oneshot::Sender<ScriptsResponse>,
     pub request: ScriptsRequest,
 }
@@ -205,6 +375,7 @@ pub struct WebMessage {
 impl fmt::Debug for WebMessage {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("WebMessage")
+            .field("request_id", &self.request_id)
             .field("request", &self.request)
             .finish()
     }
@@ -212,11 +383,24 @@ impl fmt::Debug for WebMessage {
 
 #[derive(Debug)]
 pub enum ScriptsRequest {
-    CreateScript(JsonScript),
+    // The player's name is given so the script records who wrote it
+    CreateScript(String, JsonScript),
     ReadScript(JsonScriptName),
-    ReadAllScripts,
-    UpdateScript(JsonScript),
-    DeleteScript(JsonScriptName),
+    ReadAllScripts(JsonScriptsQuery),
+    // The player's name is given so builders can be limited to the regions they own
+    UpdateScript(String, JsonScript),
+    DeleteScript(String, JsonScriptName),
+    ReadStats,
+    WorldSnapshot,
+    ReadLint,
+    ReadEconomy(u32),
+    // The filter, the page, and the number of entries per page
+    ReadCommunications(CommunicationFilter, usize, usize),
+    ReadAllowlist,
+    AddToAllowlist(String),
+    RemoveFromAllowlist(String),
+    ExportPlayer(String),
+    ErasePlayer(String),
 }
 
 #[derive(Debug)]
@@ -224,24 +408,42 @@ pub enum ScriptsResponse {
     Done,
     Error(ScriptError),
     Script(JsonScriptResponse),
-    ScriptCompiled(Option<JsonParseError>),
-    ScriptList(Vec<JsonScriptInfo>),
+    ScriptCompiled(Option<JsonParseError>, i64),
+    ScriptList(JsonScriptsResponse),
+    Stats(JsonScriptStatsResponse),
+    WorldSnapshot(Box<WorldSnapshot>),
+    Lint(Box<LintReport>),
+    Economy(Box<EconomySummary>),
+    Communications(Box<CommunicationPage>),
+    Allowlist(Vec<String>),
+    PlayerExport(Box<JsonPlayerExport>),
+    NotFound,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct ErrorMessage {
     code: u16,
     message: String,
+    /// The current version of a script when an update conflicts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current: Option<JsonScriptResponse>,
 }
 
 async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
     let code;
     let message;
+    let mut current = None;
     let mut headers = vec![(CONTENT_TYPE, HeaderValue::from_static("application/json"))];
 
     if err.is_not_found() {
         code = StatusCode::NOT_FOUND;
         message = "NOT_FOUND";
+    } else if let Some(err) = err.find::<RateLimited>() {
+        // Round up so clients waiting the advertised time are let through.
+        let seconds = err.retry_after.as_secs() + u64::from(err.retry_after.subsec_nanos() > 0);
+        headers.push((RETRY_AFTER, HeaderValue::from(seconds.max(1))));
+        code = StatusCode::TOO_MANY_REQUESTS;
+        message = "RATE_LIMITED";
     } else if let Some(err) = err.find::<ScriptError>() {
         match err {
             ScriptError::BadTrigger => {
@@ -252,6 +454,18 @@ async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
                 code = StatusCode::BAD_REQUEST;
                 message = "BAD_SCRIPT_NAME";
             }
+            ScriptError::BadTag => {
+                code = StatusCode::BAD_REQUEST;
+                message = "BAD_TAG";
+            }
+            ScriptError::BadFolder => {
+                code = StatusCode::BAD_REQUEST;
+                message = "BAD_FOLDER";
+            }
+            ScriptError::BadListQuery => {
+                code = StatusCode::BAD_REQUEST;
+                message = "BAD_QUERY";
+            }
             ScriptError::DuplicateName => {
                 code = StatusCode::CONFLICT;
                 message = "DUPLICATE_SCRIPT_NAME";
@@ -260,6 +474,86 @@ async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
                 code = StatusCode::NOT_FOUND;
                 message = "SCRIPT_NOT_FOUND";
             }
+            ScriptError::MissingRevision => {
+                code = StatusCode::PRECONDITION_REQUIRED;
+                message = "MISSING_REVISION";
+            }
+            ScriptError::OutsideOwnedRegions => {
+                code = StatusCode::FORBIDDEN;
+                message = "OUTSIDE_OWNED_REGIONS";
+            }
+            ScriptError::NotAuthor => {
+                code = StatusCode::FORBIDDEN;
+                message = "NOT_SCRIPT_AUTHOR";
+            }
+            ScriptError::UnknownAuthor => {
+                code = StatusCode::FORBIDDEN;
+                message = "UNKNOWN_AUTHOR";
+            }
+            ScriptError::RevisionConflict(script) => {
+                code = StatusCode::CONFLICT;
+                message = "REVISION_CONFLICT";
+                current = Some(script.as_ref().clone());
+            }
+        }
+    } else if let Some(err) = err.find::<PlayerError>() {
+        match err {
+            PlayerError::PlayerNotFound => {
+                code = StatusCode::NOT_FOUND;
+                message = "PLAYER_NOT_FOUND";
+            }
+            PlayerError::BadName => {
+                code = StatusCode::BAD_REQUEST;
+                message = "BAD_PLAYER_NAME";
+            }
+            PlayerError::ErasureNotFound => {
+                code = StatusCode::NOT_FOUND;
+                message = "ERASURE_NOT_FOUND";
+            }
+        }
+    } else if let Some(err) = err.find::<LeaderboardError>() {
+        match err {
+            LeaderboardError::LeaderboardNotFound => {
+                code = StatusCode::NOT_FOUND;
+                message = "LEADERBOARD_NOT_FOUND";
+            }
+        }
+    } else if let Some(err) = err.find::<CommunicationError>() {
+        match err {
+            CommunicationError::BadQuery => {
+                code = StatusCode::BAD_REQUEST;
+                message = "BAD_QUERY";
+            }
+        }
+    } else if let Some(err) = err.find::<AllowlistError>() {
+        match err {
+            AllowlistError::BadName => {
+                code = StatusCode::BAD_REQUEST;
+                message = "BAD_PLAYER_NAME";
+            }
+            AllowlistError::NameNotFound => {
+                code = StatusCode::NOT_FOUND;
+                message = "NAME_NOT_FOUND";
+            }
+        }
+    } else if let Some(err) = err.find::<TokenError>() {
+        match err {
+            TokenError::BadName => {
+                code = StatusCode::BAD_REQUEST;
+                message = "BAD_TOKEN_NAME";
+            }
+            TokenError::BadScope => {
+                code = StatusCode::BAD_REQUEST;
+                message = "BAD_SCOPE";
+            }
+            TokenError::DuplicateName => {
+                code = StatusCode::CONFLICT;
+                message = "DUPLICATE_TOKEN_NAME";
+            }
+            TokenError::TokenNotFound => {
+                code = StatusCode::NOT_FOUND;
+                message = "TOKEN_NOT_FOUND";
+            }
         }
     } else if let Some(err) = err.find::<AuthError>() {
         headers.push((
@@ -284,6 +578,18 @@ async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
                 message = "UNAUTHORIZED";
             }
         }
+    } else if err.find::<OriginNotAllowed>().is_some()
+        || err.find::<warp::cors::CorsForbidden>().is_some()
+    {
+        code = StatusCode::FORBIDDEN;
+        message = "CORS_FORBIDDEN";
+    } else if err.find::<InvalidQuery>().is_some() {
+        code = StatusCode::BAD_REQUEST;
+        message = "BAD_QUERY";
+    } else if err.find::<EngineBusy>().is_some() {
+        headers.push((RETRY_AFTER, HeaderValue::from_static("1")));
+        code = StatusCode::SERVICE_UNAVAILABLE;
+        message = "ENGINE_BUSY";
     } else if err.find::<InternalError>().is_some() {
         code = StatusCode::INTERNAL_SERVER_ERROR;
         message = "UNHANDLED_REJECTION";
@@ -296,6 +602,7 @@ async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
     let message = ErrorMessage {
         code: code.as_u16(),
         message: message.into(),
+        current,
     };
 
     let mut response = Response::builder()
@@ -0,0 +1,73 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+use warp::{reject, Filter};
+
+use crate::{
+    engine::db::{AuthDb, NewsItem},
+    text::plain,
+    web::{with_db, InternalError},
+};
+
+pub fn news_filters<DB>(
+    db: DB,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    news(db)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonNewsItem {
+    id: i64,
+    author: String,
+    body: String,
+    /// When the entry was posted, in seconds since the Unix epoch.
+    posted_at: i64,
+}
+
+impl From<NewsItem> for JsonNewsItem {
+    fn from(value: NewsItem) -> Self {
+        JsonNewsItem {
+            id: value.id,
+            author: value.author,
+            body: plain(value.body.as_str()),
+            posted_at: value.posted_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonNewsResponse {
+    news: Vec<JsonNewsItem>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/news",
+    tag = "news",
+    responses(
+        (status = 200, description = "News entries, newest first", body = JsonNewsResponse),
+    )
+)]
+pub fn news<DB>(db: DB) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path!("news")
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(handle_news)
+}
+
+async fn handle_news<DB: AuthDb>(db: DB) -> Result<impl warp::Reply, warp::Rejection> {
+    match db.news().await {
+        Ok(news) => Ok(warp::reply::json(&JsonNewsResponse {
+            news: news.into_iter().map(JsonNewsItem::from).collect(),
+        })),
+        Err(err) => {
+            tracing::error!("failed to retrieve news: {}", err);
+            Err(reject::custom(InternalError {}))
+        }
+    }
+}
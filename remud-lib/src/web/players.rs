@@ -0,0 +1,470 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+use utoipa::ToSchema;
+use warp::{reject, Filter};
+
+use crate::{
+    clock::Clock,
+    engine::{
+        db::{
+            AuthDb, EarnedAchievement, ErasureRequest, ExportedObject, FiledReport, PlayerExport,
+            PlayerSession,
+        },
+        name_valid,
+    },
+    web::{
+        auth::{verify_access, SCOPE_WORLD, SCOPE_WORLD_READ},
+        limit::{limited_access, RateLimiter, RateLimits},
+        request_id::{request_id, RequestId},
+        send_to_engine, with_db, with_sender, ErrorMessage, InternalError, JsonEmpty, Player,
+        ScriptsRequest, ScriptsResponse, WebMessage,
+    },
+    world::types::player::PlayerFlags,
+};
+
+pub fn player_filters<DB>(
+    db: DB,
+    clock: Clock,
+    tx: mpsc::Sender<WebMessage>,
+    limits: RateLimits,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    achievements(db.clone())
+        .or(export(db.clone(), clock.clone(), tx.clone()))
+        .or(request_erasure(
+            db.clone(),
+            clock.clone(),
+            limits.writes.clone(),
+        ))
+        .or(read_erasures(db.clone(), clock.clone()))
+        .or(approve_erasure(db, clock, tx, limits.writes))
+}
+
+#[derive(Debug, Error)]
+pub enum PlayerError {
+    #[error("player not found")]
+    PlayerNotFound,
+    #[error("bad player name")]
+    BadName,
+    #[error("player has not requested erasure")]
+    ErasureNotFound,
+}
+
+impl warp::reject::Reject for PlayerError {}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonAchievement {
+    id: String,
+    name: String,
+    description: String,
+    /// When the player earned the achievement, in UTC.
+    earned_at: String,
+}
+
+impl From<EarnedAchievement> for JsonAchievement {
+    fn from(value: EarnedAchievement) -> Self {
+        JsonAchievement {
+            id: value.id,
+            name: value.name,
+            description: value.description,
+            earned_at: value.earned_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonAchievementsResponse {
+    achievements: Vec<JsonAchievement>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/players/{name}/achievements",
+    tag = "players",
+    params(("name" = String, Path, description = "The player's name")),
+    responses(
+        (status = 200, description = "The player's achievements, in the order they were earned", body = JsonAchievementsResponse),
+        (status = 404, description = "Player not found", body = ErrorMessage),
+    )
+)]
+pub fn achievements<DB>(
+    db: DB,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path!("players" / String / "achievements")
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(handle_achievements)
+}
+
+async fn handle_achievements<DB: AuthDb>(
+    name: String,
+    db: DB,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match db.player_achievements(name.as_str()).await {
+        Ok(Some(achievements)) => Ok(warp::reply::json(&JsonAchievementsResponse {
+            achievements: achievements
+                .into_iter()
+                .map(JsonAchievement::from)
+                .collect(),
+        })),
+        Ok(None) => Err(reject::custom(PlayerError::PlayerNotFound)),
+        Err(err) => {
+            tracing::error!("failed to retrieve player achievements: {}", err);
+            Err(reject::custom(InternalError {}))
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonExportedObject {
+    id: i64,
+    name: String,
+}
+
+impl From<ExportedObject> for JsonExportedObject {
+    fn from(value: ExportedObject) -> Self {
+        JsonExportedObject {
+            id: value.id,
+            name: value.name,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonSession {
+    /// When the session started, in UTC.
+    connected_at: String,
+    /// When the session ended, in UTC, if it has.
+    disconnected_at: Option<String>,
+}
+
+impl From<PlayerSession> for JsonSession {
+    fn from(value: PlayerSession) -> Self {
+        JsonSession {
+            connected_at: value.connected_at,
+            disconnected_at: value.disconnected_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonFiledReport {
+    reported: String,
+    reason: String,
+    /// When the report was filed, in UTC.
+    created_at: String,
+}
+
+impl From<FiledReport> for JsonFiledReport {
+    fn from(value: FiledReport) -> Self {
+        JsonFiledReport {
+            reported: value.reported,
+            reason: value.reason,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonCommunication {
+    speaker: String,
+    message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonPlayerExport {
+    name: String,
+    description: String,
+    pronouns: String,
+    flags: String,
+    room: i64,
+    playtime_secs: i64,
+    inventory: Vec<JsonExportedObject>,
+    vault: Vec<JsonExportedObject>,
+    achievements: Vec<JsonAchievement>,
+    ignores: Vec<String>,
+    sessions: Vec<JsonSession>,
+    reports: Vec<JsonFiledReport>,
+    /// Communication received from other players during the current session, if online.
+    communication: Vec<JsonCommunication>,
+}
+
+impl JsonPlayerExport {
+    pub fn new(export: PlayerExport, communication: Vec<(String, String)>) -> Self {
+        JsonPlayerExport {
+            name: export.name,
+            description: export.description,
+            pronouns: export.pronouns,
+            flags: format!("{:?}", PlayerFlags::from(export.flags).get_flags()),
+            room: export.room,
+            playtime_secs: export.playtime,
+            inventory: export.inventory.into_iter().map(Into::into).collect(),
+            vault: export.vault.into_iter().map(Into::into).collect(),
+            achievements: export.achievements.into_iter().map(Into::into).collect(),
+            ignores: export.ignores,
+            sessions: export.sessions.into_iter().map(Into::into).collect(),
+            reports: export.reports.into_iter().map(Into::into).collect(),
+            communication: communication
+                .into_iter()
+                .map(|(speaker, message)| JsonCommunication { speaker, message })
+                .collect(),
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/players/me/export",
+    tag = "players",
+    responses(
+        (status = 200, description = "Everything stored about the authenticated player", body = JsonPlayerExport),
+        (status = 401, description = "Missing or invalid access token", body = ErrorMessage),
+        (status = 503, description = "The engine is too busy to take the request", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
+pub fn export<DB>(
+    db: DB,
+    clock: Clock,
+    tx: mpsc::Sender<WebMessage>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path!("players" / "me" / "export")
+        .and(warp::get())
+        .and(verify_access(db, clock, vec![]))
+        .and(request_id())
+        .and(with_sender(tx))
+        .and_then(handle_export)
+}
+
+#[tracing::instrument(name = "export player", skip_all, fields(player = player.name.as_str()))]
+async fn handle_export(
+    player: Player,
+    request_id: RequestId,
+    sender: mpsc::Sender<WebMessage>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match dispatch(
+        request_id,
+        sender,
+        ScriptsRequest::ExportPlayer(player.name),
+    )
+    .await?
+    {
+        ScriptsResponse::PlayerExport(export) => Ok(warp::reply::json(&export)),
+        ScriptsResponse::NotFound => Err(reject::custom(PlayerError::PlayerNotFound)),
+        _ => Err(reject::custom(InternalError {})),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/players/me/erasure",
+    tag = "players",
+    responses(
+        (status = 200, description = "Asked for the authenticated player's data to be erased", body = JsonEmpty),
+        (status = 401, description = "Missing or invalid access token", body = ErrorMessage),
+        (status = 429, description = "Too many changes from the address or by the player", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds until another change is allowed"))),
+    ),
+    security(("bearer" = []))
+)]
+pub fn request_erasure<DB>(
+    db: DB,
+    clock: Clock,
+    limiter: RateLimiter,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path!("players" / "me" / "erasure")
+        .and(warp::post())
+        .and(limited_access(db.clone(), clock, vec![], limiter))
+        .and(with_db(db))
+        .and_then(handle_request_erasure)
+}
+
+#[tracing::instrument(name = "request erasure", skip_all, fields(player = player.name.as_str()))]
+async fn handle_request_erasure<DB: AuthDb>(
+    player: Player,
+    db: DB,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match db.request_erasure(player.name()).await {
+        Ok(requested) => {
+            if requested {
+                tracing::info!("player {} requested erasure", player.name());
+            }
+            Ok(warp::reply::json(&JsonEmpty {}))
+        }
+        Err(err) => {
+            tracing::error!("failed to request erasure: {}", err);
+            Err(reject::custom(InternalError {}))
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonErasureRequest {
+    name: String,
+    /// When the player asked to be erased, in UTC.
+    requested_at: String,
+}
+
+impl From<ErasureRequest> for JsonErasureRequest {
+    fn from(value: ErasureRequest) -> Self {
+        JsonErasureRequest {
+            name: value.name,
+            requested_at: value.requested_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonErasuresResponse {
+    requests: Vec<JsonErasureRequest>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/players/erasures/read",
+    tag = "players",
+    responses(
+        (status = 200, description = "Outstanding erasure requests, oldest first", body = JsonErasuresResponse),
+        (status = 401, description = "Missing or inadequate access token", body = ErrorMessage),
+    ),
+    security(("bearer" = []))
+)]
+pub fn read_erasures<DB>(
+    db: DB,
+    clock: Clock,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path!("players" / "erasures" / "read")
+        .and(warp::post())
+        .and(verify_access(
+            db.clone(),
+            clock,
+            vec![SCOPE_WORLD_READ.to_string()],
+        ))
+        .and(with_db(db))
+        .and_then(handle_read_erasures)
+}
+
+#[tracing::instrument(name = "read erasures", skip_all, fields(player = player.name.as_str()))]
+async fn handle_read_erasures<DB: AuthDb>(
+    player: Player,
+    db: DB,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match db.erasure_requests().await {
+        Ok(requests) => Ok(warp::reply::json(&JsonErasuresResponse {
+            requests: requests.into_iter().map(Into::into).collect(),
+        })),
+        Err(err) => {
+            tracing::error!("failed to retrieve erasure requests: {}", err);
+            Err(reject::custom(InternalError {}))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct JsonErasureName {
+    name: String,
+}
+
+fn json_erasure_name() -> impl Filter<Extract = (JsonErasureName,), Error = warp::Rejection> + Clone
+{
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
+
+#[utoipa::path(
+    post,
+    path = "/players/erasures/approve",
+    tag = "players",
+    request_body = JsonErasureName,
+    responses(
+        (status = 200, description = "Disconnected the player and erased their data", body = JsonEmpty),
+        (status = 400, description = "Bad player name", body = ErrorMessage),
+        (status = 401, description = "Missing or inadequate access token", body = ErrorMessage),
+        (status = 404, description = "The player has not requested erasure", body = ErrorMessage),
+        (status = 429, description = "Too many changes from the address or by the player", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds until another change is allowed"))),
+        (status = 503, description = "The engine is too busy to take the request", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
+pub fn approve_erasure<DB>(
+    db: DB,
+    clock: Clock,
+    tx: mpsc::Sender<WebMessage>,
+    limiter: RateLimiter,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path!("players" / "erasures" / "approve")
+        .and(warp::post())
+        .and(limited_access(
+            db,
+            clock,
+            vec![SCOPE_WORLD.to_string()],
+            limiter,
+        ))
+        .and(json_erasure_name())
+        .and(request_id())
+        .and(with_sender(tx))
+        .and_then(handle_approve_erasure)
+}
+
+#[tracing::instrument(
+    name = "approve erasure",
+    skip_all,
+    fields(player = player.name.as_str(), name = name.name.as_str())
+)]
+async fn handle_approve_erasure(
+    player: Player,
+    name: JsonErasureName,
+    request_id: RequestId,
+    sender: mpsc::Sender<WebMessage>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if !name_valid(name.name.as_str()) {
+        return Err(reject::custom(PlayerError::BadName));
+    }
+
+    tracing::info!("player {} approved erasure of {}", player.name(), name.name);
+
+    match dispatch(request_id, sender, ScriptsRequest::ErasePlayer(name.name)).await? {
+        ScriptsResponse::Done => Ok(warp::reply::json(&JsonEmpty {})),
+        ScriptsResponse::NotFound => Err(reject::custom(PlayerError::ErasureNotFound)),
+        _ => Err(reject::custom(InternalError {})),
+    }
+}
+
+// Sends a player request to the engine. The engine drops the request if it fails.
+async fn dispatch(
+    request_id: RequestId,
+    sender: mpsc::Sender<WebMessage>,
+    request: ScriptsRequest,
+) -> Result<ScriptsResponse, warp::Rejection> {
+    let (tx, rx) = oneshot::channel();
+    send_to_engine(
+        &sender,
+        WebMessage {
+            request_id,
+            response: tx,
+            request,
+        },
+    )?;
+
+    rx.await.map_err(|_| reject::custom(InternalError {}))
+}
@@ -0,0 +1,96 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+use warp::{reject, Filter};
+
+use crate::{
+    engine::db::{AuthDb, PollOptionResults, PollResults},
+    text::plain,
+    web::{with_db, InternalError},
+};
+
+pub fn poll_filters<DB>(
+    db: DB,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    polls(db)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonPollOption {
+    text: String,
+    votes: i64,
+}
+
+impl From<PollOptionResults> for JsonPollOption {
+    fn from(value: PollOptionResults) -> Self {
+        JsonPollOption {
+            text: plain(value.text.as_str()),
+            votes: value.votes,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonPoll {
+    id: i64,
+    question: String,
+    author: String,
+    /// When the poll was opened, in seconds since the Unix epoch.
+    created_at: i64,
+    closed: bool,
+    options: Vec<JsonPollOption>,
+}
+
+impl From<PollResults> for JsonPoll {
+    fn from(value: PollResults) -> Self {
+        JsonPoll {
+            id: value.id,
+            question: plain(value.question.as_str()),
+            author: value.author,
+            created_at: value.created_at,
+            closed: value.closed,
+            options: value
+                .options
+                .into_iter()
+                .map(JsonPollOption::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonPollsResponse {
+    polls: Vec<JsonPoll>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/polls",
+    tag = "polls",
+    responses(
+        (status = 200, description = "Polls and their votes, newest first", body = JsonPollsResponse),
+    )
+)]
+pub fn polls<DB>(db: DB) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path!("polls")
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(handle_polls)
+}
+
+async fn handle_polls<DB: AuthDb>(db: DB) -> Result<impl warp::Reply, warp::Rejection> {
+    match db.polls().await {
+        Ok(polls) => Ok(warp::reply::json(&JsonPollsResponse {
+            polls: polls.into_iter().map(JsonPoll::from).collect(),
+        })),
+        Err(err) => {
+            tracing::error!("failed to retrieve polls: {}", err);
+            Err(reject::custom(InternalError {}))
+        }
+    }
+}
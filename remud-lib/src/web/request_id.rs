@@ -0,0 +1,119 @@
+use std::{convert::Infallible, fmt};
+
+use rand::random;
+use tracing::Instrument;
+use warp::{
+    http::HeaderValue,
+    hyper::{service::Service, Body, Request, Response},
+    Filter, Rejection,
+};
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+/// Identifies a web request in logs, in messages sent to the engine, and to the client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(String);
+
+impl RequestId {
+    fn generate() -> Self {
+        RequestId(format!("{:016x}", random::<u64>()))
+    }
+
+    // Clients may choose their own IDs, as long as they are short and safe to log.
+    fn from_header(value: &HeaderValue) -> Option<Self> {
+        let value = value.to_str().ok()?;
+
+        if !value.is_empty()
+            && value.len() <= MAX_REQUEST_ID_LEN
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        {
+            Some(RequestId(value.to_string()))
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.as_str())
+    }
+}
+
+/// Extracts the ID given to the request by `call_with_request_id`.
+pub fn request_id() -> impl Filter<Extract = (RequestId,), Error = Rejection> + Clone {
+    warp::ext::get::<RequestId>()
+}
+
+/// Calls the service with the request's ID, taken from its X-Request-Id header or generated, in a
+/// span carrying the ID. The ID is echoed in the response's X-Request-Id header.
+pub async fn call_with_request_id<S>(
+    mut service: S,
+    mut request: Request<Body>,
+) -> Result<Response<Body>, Infallible>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = Infallible>,
+{
+    let id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(RequestId::from_header)
+        .unwrap_or_else(RequestId::generate);
+
+    let span = tracing::info_span!(
+        "web request",
+        request_id = id.as_str(),
+        method = request.method().as_str(),
+        path = request.uri().path()
+    );
+
+    request.extensions_mut().insert(id.clone());
+
+    let mut response = service.call(request).instrument(span).await?;
+    // IDs are only ever made of visible ASCII.
+    response.headers_mut().insert(
+        REQUEST_ID_HEADER,
+        HeaderValue::from_str(id.as_str()).unwrap(),
+    );
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use warp::http::HeaderValue;
+
+    use super::RequestId;
+
+    #[test]
+    fn test_request_id_from_header() {
+        assert_eq!(
+            RequestId::from_header(&HeaderValue::from_static("abc-123_x.y")),
+            Some(RequestId("abc-123_x.y".to_string()))
+        );
+        assert_eq!(RequestId::from_header(&HeaderValue::from_static("")), None);
+        assert_eq!(
+            RequestId::from_header(&HeaderValue::from_static("has space")),
+            None
+        );
+        assert_eq!(
+            RequestId::from_header(&HeaderValue::from_str(&"a".repeat(65)).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_request_id_generate() {
+        let id = RequestId::generate();
+        assert_eq!(id.as_str().len(), 16);
+        assert!(RequestId::from_header(&HeaderValue::from_str(id.as_str()).unwrap()).is_some());
+    }
+}
@@ -1,34 +1,53 @@
+use std::{cmp::Ordering, str::FromStr, time::Duration};
+
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::sync::{mpsc, oneshot};
+use utoipa::{IntoParams, ToSchema};
 use warp::Filter;
 
 use crate::{
+    clock::Clock,
     engine::db::AuthDb,
     web::{
-        auth::{verify_access, SCOPE_SCRIPTS},
-        InternalError, JsonEmpty, Player, ScriptsRequest, ScriptsResponse, WebMessage,
+        auth::{verify_access, SCOPE_SCRIPTS_READ, SCOPE_SCRIPTS_WRITE},
+        limit::{limited_access, RateLimiter, RateLimits},
+        request_id::{request_id, RequestId},
+        send_to_engine, with_sender, ErrorMessage, InternalError, JsonEmpty, Player,
+        ScriptsRequest, ScriptsResponse, WebMessage,
     },
-    world::scripting,
+    world::scripting::{self, stats::ScriptStat, ScriptName, TriggerEvent},
 };
 
 pub fn script_filters<DB>(
     db: DB,
+    clock: Clock,
     tx: mpsc::Sender<WebMessage>,
+    limits: RateLimits,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
 where
     DB: AuthDb + Send + Sync + Clone + 'static,
 {
-    warp::path("scripts").and(warp::post()).and(
-        create(db.clone(), tx.clone())
-            .or(read_all(db.clone(), tx.clone()))
-            .or(read(db.clone(), tx.clone()))
-            .or(update(db.clone(), tx.clone()))
-            .or(delete(db, tx)),
+    warp::path("scripts").and(
+        warp::post()
+            .and(
+                create(db.clone(), clock.clone(), tx.clone(), limits.writes.clone())
+                    .or(read_all(db.clone(), clock.clone(), tx.clone()))
+                    .or(read(db.clone(), clock.clone(), tx.clone()))
+                    .or(update(
+                        db.clone(),
+                        clock.clone(),
+                        tx.clone(),
+                        limits.writes.clone(),
+                    ))
+                    .or(delete(db.clone(), clock.clone(), tx.clone(), limits.writes)),
+            )
+            .or(stats(db, clock, tx)),
     )
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct JsonScriptName {
     pub name: String,
 }
@@ -43,11 +62,24 @@ fn json_script_name() -> impl Filter<Extract = (JsonScriptName,), Error = warp::
     warp::body::content_length_limit(1024 * 16).and(warp::body::json())
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct JsonScript {
     pub name: String,
     pub trigger: String,
     pub code: String,
+    #[serde(default)]
+    pub revision: Option<i64>,
+    /// Labels for organizing scripts. Left unchanged by updates when omitted.
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// A slash separated folder path such as areas/town. Left unchanged by updates when omitted,
+    /// an empty path moves the script to the top level.
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// Whether the script runs. New scripts are enabled unless this is false, and updates leave
+    /// it unchanged when omitted.
+    #[serde(default)]
+    pub enabled: Option<bool>,
 }
 
 impl JsonScript {
@@ -60,62 +92,251 @@ fn json_script() -> impl Filter<Extract = (JsonScript,), Error = warp::Rejection
     warp::body::content_length_limit(1024 * 1024).and(warp::body::json())
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct JsonScriptResponse {
     pub name: String,
     pub trigger: String,
     pub code: String,
+    pub revision: i64,
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    pub enabled: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<JsonParseError>,
 }
 
 impl JsonScriptResponse {
     pub fn new(script: scripting::Script, error: Option<rhai::ParseError>) -> Self {
+        let revision = script.revision();
+        let tags = script.tags().to_vec();
+        let folder = script.folder().map(ToString::to_string);
+        let enabled = script.enabled();
         let (name, trigger, code) = script.into_parts();
 
         JsonScriptResponse {
             name: name.into_string(),
             trigger: trigger.to_string(),
             code,
+            revision,
+            tags,
+            folder,
+            enabled,
             error: error.map(|e| e.into()),
         }
     }
 }
 
-#[derive(Debug, Serialize)]
-struct JsonScriptsResponse {
+/// Filters, sorts, and pages the script list. Without a limit, every matching script is returned.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct JsonScriptsQuery {
+    /// The page to return, starting from 1.
+    page: Option<usize>,
+    /// The number of scripts per page.
+    limit: Option<usize>,
+    /// Only include scripts with this trigger.
+    trigger: Option<String>,
+    /// Only include scripts which failed (true) or succeeded (false) to compile.
+    error: Option<bool>,
+    /// Only include scripts which are enabled (true) or disabled (false).
+    enabled: Option<bool>,
+    /// Only include scripts whose names contain this text, ignoring case.
+    q: Option<String>,
+    /// Only include scripts with this tag.
+    tag: Option<String>,
+    /// Only include scripts in this folder or its subfolders.
+    folder: Option<String>,
+    /// One of name, trigger, or lines. Prefix with - to sort in descending order.
+    sort: Option<String>,
+}
+
+impl JsonScriptsQuery {
+    pub fn apply(&self, scripts: Vec<JsonScriptInfo>) -> Result<JsonScriptsResponse, ScriptError> {
+        let page = self.page.unwrap_or(1);
+        if page == 0 || self.limit == Some(0) {
+            return Err(ScriptError::BadListQuery);
+        }
+
+        let trigger = match &self.trigger {
+            Some(trigger) => Some(
+                TriggerEvent::from_str(trigger)
+                    .map_err(|_| ScriptError::BadTrigger)?
+                    .to_string(),
+            ),
+            None => None,
+        };
+
+        let compare: Option<fn(&JsonScriptInfo, &JsonScriptInfo) -> Ordering> = match self
+            .sort
+            .as_deref()
+        {
+            None => None,
+            Some("name") => Some(|a, b| a.name.cmp(&b.name)),
+            Some("-name") => Some(|a, b| b.name.cmp(&a.name)),
+            Some("trigger") => Some(|a, b| a.trigger.cmp(&b.trigger).then(a.name.cmp(&b.name))),
+            Some("-trigger") => Some(|a, b| b.trigger.cmp(&a.trigger).then(a.name.cmp(&b.name))),
+            Some("lines") => Some(|a, b| a.lines.cmp(&b.lines).then(a.name.cmp(&b.name))),
+            Some("-lines") => Some(|a, b| b.lines.cmp(&a.lines).then(a.name.cmp(&b.name))),
+            Some(_) => return Err(ScriptError::BadListQuery),
+        };
+
+        let search = self.q.as_ref().map(|q| q.to_lowercase());
+        let tag = match &self.tag {
+            Some(tag) => Some(scripting::parse_tag(tag).map_err(|_| ScriptError::BadTag)?),
+            None => None,
+        };
+        let folder = match &self.folder {
+            Some(folder) => scripting::parse_folder(folder).map_err(|_| ScriptError::BadFolder)?,
+            None => None,
+        };
+
+        let mut scripts = scripts
+            .into_iter()
+            .filter(|script| match &trigger {
+                Some(trigger) => &script.trigger == trigger,
+                None => true,
+            })
+            .filter(|script| match self.error {
+                Some(error) => script.error.is_some() == error,
+                None => true,
+            })
+            .filter(|script| match self.enabled {
+                Some(enabled) => script.enabled == enabled,
+                None => true,
+            })
+            .filter(|script| match &search {
+                Some(search) => script.name.to_lowercase().contains(search.as_str()),
+                None => true,
+            })
+            .filter(|script| match &tag {
+                Some(tag) => script.tags.contains(tag),
+                None => true,
+            })
+            .filter(|script| match &folder {
+                Some(folder) => match script.folder.as_deref() {
+                    Some(own) => own == folder || own.starts_with(format!("{}/", folder).as_str()),
+                    None => false,
+                },
+                None => true,
+            })
+            .collect_vec();
+
+        if let Some(compare) = compare {
+            scripts.sort_by(compare);
+        }
+
+        let total = scripts.len();
+        let scripts = match self.limit {
+            Some(limit) => scripts
+                .into_iter()
+                .skip((page - 1).saturating_mul(limit))
+                .take(limit)
+                .collect_vec(),
+            None if page > 1 => Vec::new(),
+            None => scripts,
+        };
+
+        Ok(JsonScriptsResponse {
+            scripts,
+            total,
+            page,
+            limit: self.limit,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonScriptsResponse {
     scripts: Vec<JsonScriptInfo>,
+    /// The number of scripts matching the query across all pages.
+    total: usize,
+    page: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct JsonScriptInfo {
     pub name: String,
     pub trigger: String,
     pub lines: usize,
+    pub revision: i64,
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    pub enabled: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<JsonParseError>,
 }
 
 impl JsonScriptInfo {
     pub fn new(script: scripting::Script, error: Option<rhai::ParseError>) -> Self {
+        let revision = script.revision();
+        let tags = script.tags().to_vec();
+        let folder = script.folder().map(ToString::to_string);
+        let enabled = script.enabled();
         let (name, trigger, code) = script.into_parts();
 
         JsonScriptInfo {
             name: name.into_string(),
             trigger: trigger.to_string(),
             lines: code.lines().count(),
+            revision,
+            tags,
+            folder,
+            enabled,
             error: error.map(|e| e.into()),
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonScriptStatsResponse {
+    /// Scripts which have run, those taking the most time in total first.
+    scripts: Vec<JsonScriptStats>,
+    /// Scripts averaging more microseconds than this per run are slow.
+    slow_threshold_us: u64,
+}
+
+impl JsonScriptStatsResponse {
+    pub fn new(stats: Vec<(ScriptName, ScriptStat)>, slow_threshold: Duration) -> Self {
+        JsonScriptStatsResponse {
+            scripts: stats
+                .into_iter()
+                .map(|(name, stat)| JsonScriptStats {
+                    name: name.into_string(),
+                    runs: stat.runs(),
+                    errors: stat.errors(),
+                    total_us: stat.total().as_micros() as u64,
+                    mean_us: stat.mean().as_micros() as u64,
+                    slow: stat.slow(),
+                })
+                .collect(),
+            slow_threshold_us: slow_threshold.as_micros() as u64,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonScriptStats {
+    name: String,
+    runs: u64,
+    /// Runs which ended in an execution error.
+    errors: u64,
+    total_us: u64,
+    mean_us: u64,
+    slow: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct CompileResponse {
+    revision: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<JsonParseError>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct JsonParseError {
     #[serde(skip_serializing_if = "Option::is_none")]
     line: Option<usize>,
@@ -140,44 +361,119 @@ pub enum ScriptError {
     BadTrigger,
     #[error("bad script name")]
     BadScriptName,
+    #[error("bad script tag")]
+    BadTag,
+    #[error("bad script folder")]
+    BadFolder,
     #[error("duplicate script found")]
     DuplicateName,
     #[error("script not found")]
     ScriptNotFound,
+    #[error("expected revision not supplied")]
+    MissingRevision,
+    #[error("script revision conflict")]
+    RevisionConflict(Box<JsonScriptResponse>),
+    #[error("script attached outside the builder's regions")]
+    OutsideOwnedRegions,
+    #[error("unattached script written by someone other than the builder")]
+    NotAuthor,
+    #[error("script change by an unknown player or API token")]
+    UnknownAuthor,
+    #[error("bad script list query")]
+    BadListQuery,
 }
 
 impl warp::reject::Reject for ScriptError {}
 
+#[utoipa::path(
+    post,
+    path = "/scripts/create",
+    tag = "scripts",
+    request_body = JsonScript,
+    responses(
+        (status = 200, description = "Created the script, which may have failed to compile", body = CompileResponse,
+            headers(("ETag" = String, description = "The script's revision"))),
+        (status = 400, description = "Bad script name, trigger, tag, or folder", body = ErrorMessage),
+        (status = 409, description = "A script with the name already exists", body = ErrorMessage),
+        (status = 429, description = "Too many changes from the address or by the player", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds until another change is allowed"))),
+        (status = 503, description = "The engine is too busy to take the request", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
 pub fn create<DB>(
     db: DB,
+    clock: Clock,
     tx: mpsc::Sender<WebMessage>,
+    limiter: RateLimiter,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
 where
     DB: AuthDb + Send + Sync + Clone + 'static,
 {
     warp::path("create")
-        .and(verify_access(db, vec![SCOPE_SCRIPTS.to_string()]))
+        .and(limited_access(
+            db,
+            clock,
+            vec![SCOPE_SCRIPTS_WRITE.to_string()],
+            limiter,
+        ))
         .and(json_script())
+        .and(request_id())
         .and(with_sender(tx))
         .and_then(handle_create)
 }
 
+#[utoipa::path(
+    post,
+    path = "/scripts/read",
+    tag = "scripts",
+    request_body = JsonScriptName,
+    responses(
+        (status = 200, description = "The script and its compilation status", body = JsonScriptResponse,
+            headers(("ETag" = String, description = "The script's revision"))),
+        (status = 404, description = "Script not found", body = ErrorMessage),
+        (status = 503, description = "The engine is too busy to take the request", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
 pub fn read<DB>(
     db: DB,
+    clock: Clock,
     tx: mpsc::Sender<WebMessage>,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
 where
     DB: AuthDb + Send + Sync + Clone + 'static,
 {
     warp::path("read")
-        .and(verify_access(db, vec![SCOPE_SCRIPTS.to_string()]))
+        .and(verify_access(
+            db,
+            clock,
+            vec![SCOPE_SCRIPTS_READ.to_string()],
+        ))
         .and(json_script_name())
+        .and(request_id())
         .and(with_sender(tx))
         .and_then(handle_read)
 }
 
+#[utoipa::path(
+    post,
+    path = "/scripts/read/all",
+    tag = "scripts",
+    params(JsonScriptsQuery),
+    responses(
+        (status = 200, description = "Matching scripts and their compilation status", body = JsonScriptsResponse),
+        (status = 400, description = "Bad trigger, tag, folder, page, limit, or sort", body = ErrorMessage),
+        (status = 503, description = "The engine is too busy to take the request", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
 pub fn read_all<DB>(
     db: DB,
+    clock: Clock,
     tx: mpsc::Sender<WebMessage>,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
 where
@@ -185,39 +481,131 @@ where
 {
     warp::path("read")
         .and(warp::path("all"))
-        .and(verify_access(db, vec![SCOPE_SCRIPTS.to_string()]))
+        .and(verify_access(
+            db,
+            clock,
+            vec![SCOPE_SCRIPTS_READ.to_string()],
+        ))
+        .and(warp::query::<JsonScriptsQuery>())
+        .and(request_id())
         .and(with_sender(tx))
         .and_then(handle_read_all)
 }
 
+#[utoipa::path(
+    post,
+    path = "/scripts/update",
+    tag = "scripts",
+    request_body = JsonScript,
+    params(
+        ("If-Match" = Option<String>, Header, description = "The expected revision, if not provided in the body"),
+    ),
+    responses(
+        (status = 200, description = "Updated the script, which may have failed to compile", body = CompileResponse,
+            headers(("ETag" = String, description = "The script's new revision"))),
+        (status = 403, description = "A builder's script is attached outside regions they own, or is attached to nothing and was written by someone else, or the player or API token is unknown", body = ErrorMessage),
+        (status = 404, description = "Script not found", body = ErrorMessage),
+        (status = 409, description = "The script was changed since the expected revision", body = ErrorMessage),
+        (status = 428, description = "No expected revision was provided", body = ErrorMessage),
+        (status = 429, description = "Too many changes from the address or by the player", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds until another change is allowed"))),
+        (status = 503, description = "The engine is too busy to take the request", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
 pub fn update<DB>(
     db: DB,
+    clock: Clock,
     tx: mpsc::Sender<WebMessage>,
+    limiter: RateLimiter,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
 where
     DB: AuthDb + Send + Sync + Clone + 'static,
 {
     warp::path("update")
-        .and(verify_access(db, vec![SCOPE_SCRIPTS.to_string()]))
+        .and(limited_access(
+            db,
+            clock,
+            vec![SCOPE_SCRIPTS_WRITE.to_string()],
+            limiter,
+        ))
         .and(json_script())
+        .and(warp::header::optional::<String>("if-match"))
+        .and(request_id())
         .and(with_sender(tx))
         .and_then(handle_update)
 }
 
+#[utoipa::path(
+    post,
+    path = "/scripts/delete",
+    tag = "scripts",
+    request_body = JsonScriptName,
+    responses(
+        (status = 200, description = "Deleted the script", body = JsonEmpty),
+        (status = 403, description = "A builder's script is attached outside regions they own, or is attached to nothing and was written by someone else, or the player or API token is unknown", body = ErrorMessage),
+        (status = 404, description = "Script not found", body = ErrorMessage),
+        (status = 429, description = "Too many changes from the address or by the player", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds until another change is allowed"))),
+        (status = 503, description = "The engine is too busy to take the request", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
 pub fn delete<DB>(
     db: DB,
+    clock: Clock,
     tx: mpsc::Sender<WebMessage>,
+    limiter: RateLimiter,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
 where
     DB: AuthDb + Send + Sync + Clone + 'static,
 {
     warp::path("delete")
-        .and(verify_access(db, vec![SCOPE_SCRIPTS.to_string()]))
+        .and(limited_access(
+            db,
+            clock,
+            vec![SCOPE_SCRIPTS_WRITE.to_string()],
+            limiter,
+        ))
         .and(json_script_name())
+        .and(request_id())
         .and(with_sender(tx))
         .and_then(handle_delete)
 }
 
+#[utoipa::path(
+    get,
+    path = "/scripts/stats",
+    tag = "scripts",
+    responses(
+        (status = 200, description = "Run counts and timings of the scripts which have run", body = JsonScriptStatsResponse),
+        (status = 503, description = "The engine is too busy to take the request", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
+pub fn stats<DB>(
+    db: DB,
+    clock: Clock,
+    tx: mpsc::Sender<WebMessage>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path("stats")
+        .and(warp::get())
+        .and(verify_access(
+            db,
+            clock,
+            vec![SCOPE_SCRIPTS_READ.to_string()],
+        ))
+        .and(request_id())
+        .and(with_sender(tx))
+        .and_then(handle_stats)
+}
+
 #[tracing::instrument(
     name = "create script",
     skip_all,
@@ -229,26 +617,26 @@ where
 async fn handle_create(
     player: Player,
     script: JsonScript,
+    request_id: RequestId,
     sender: mpsc::Sender<WebMessage>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     tracing::debug!("player {} creating script {}", player.name(), script.name());
 
     let (tx, rx) = oneshot::channel();
-    if let Err(err) = sender
-        .send(WebMessage {
+    send_to_engine(
+        &sender,
+        WebMessage {
+            request_id,
             response: tx,
-            request: ScriptsRequest::CreateScript(script),
-        })
-        .await
-    {
-        tracing::error!("failed to dispatch CreateScript to engine: {}", err);
-        return Err(warp::reject::custom(InternalError {}));
-    };
+            request: ScriptsRequest::CreateScript(player.name, script),
+        },
+    )?;
 
     match rx.await {
-        Ok(ScriptsResponse::ScriptCompiled(error)) => {
-            Ok(warp::reply::json(&CompileResponse { error }))
-        }
+        Ok(ScriptsResponse::ScriptCompiled(error, revision)) => Ok(with_etag(
+            warp::reply::json(&CompileResponse { revision, error }),
+            revision,
+        )),
         Ok(ScriptsResponse::Error(e)) => Err(warp::reject::custom(e)),
         other => {
             tracing::error!("received unexpected response to CreateScript: {:?}", other);
@@ -268,6 +656,7 @@ async fn handle_create(
 async fn handle_read(
     player: Player,
     script_name: JsonScriptName,
+    request_id: RequestId,
     sender: mpsc::Sender<WebMessage>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     tracing::debug!(
@@ -276,19 +665,20 @@ async fn handle_read(
         script_name.as_str()
     );
     let (tx, rx) = oneshot::channel();
-    if let Err(err) = sender
-        .send(WebMessage {
+    send_to_engine(
+        &sender,
+        WebMessage {
+            request_id,
             response: tx,
             request: ScriptsRequest::ReadScript(script_name),
-        })
-        .await
-    {
-        tracing::error!("failed to dispatch ReadScript to engine: {}", err);
-        return Err(warp::reject::custom(InternalError {}));
-    };
+        },
+    )?;
 
     match rx.await {
-        Ok(ScriptsResponse::Script(script)) => Ok(warp::reply::json(&script)),
+        Ok(ScriptsResponse::Script(script)) => {
+            let revision = script.revision;
+            Ok(with_etag(warp::reply::json(&script), revision))
+        }
         Ok(ScriptsResponse::Error(err)) => Err(warp::reject::custom(err)),
         other => {
             tracing::error!("received unexpected response to ReadScript: {:?}", other);
@@ -304,26 +694,24 @@ async fn handle_read(
 )]
 async fn handle_read_all(
     player: Player,
+    query: JsonScriptsQuery,
+    request_id: RequestId,
     sender: mpsc::Sender<WebMessage>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     tracing::debug!("player {} reading all scripts", player.name());
 
     let (tx, rx) = oneshot::channel();
-    if let Err(err) = sender
-        .send(WebMessage {
+    send_to_engine(
+        &sender,
+        WebMessage {
+            request_id,
             response: tx,
-            request: ScriptsRequest::ReadAllScripts,
-        })
-        .await
-    {
-        tracing::error!("failed to dispatch ReadScript to engine: {}", err);
-        return Err(warp::reject::custom(InternalError {}));
-    };
+            request: ScriptsRequest::ReadAllScripts(query),
+        },
+    )?;
 
     match rx.await {
-        Ok(ScriptsResponse::ScriptList(scripts)) => {
-            Ok(warp::reply::json(&JsonScriptsResponse { scripts }))
-        }
+        Ok(ScriptsResponse::ScriptList(scripts)) => Ok(warp::reply::json(&scripts)),
         Ok(ScriptsResponse::Error(err)) => Err(warp::reject::custom(err)),
         other => {
             tracing::error!("received unexpected response to ReadScript: {:?}", other);
@@ -336,32 +724,43 @@ async fn handle_read_all(
     name = "update script",
     skip_all,
     fields(
-        player = _player.name.as_str(),
+        player = player.name.as_str(),
         script = script.name.as_str()
     )
 )]
 async fn handle_update(
-    _player: Player,
-    script: JsonScript,
+    player: Player,
+    mut script: JsonScript,
+    if_match: Option<String>,
+    request_id: RequestId,
     sender: mpsc::Sender<WebMessage>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     tracing::debug!("start script update");
+
+    // The expected revision may be supplied in the body or as an If-Match header.
+    if script.revision.is_none() {
+        script.revision = if_match.as_deref().and_then(parse_etag);
+    }
+    if script.revision.is_none() {
+        return Err(warp::reject::custom(ScriptError::MissingRevision));
+    }
     let (tx, rx) = oneshot::channel();
-    if let Err(err) = sender
-        .send(WebMessage {
+    send_to_engine(
+        &sender,
+        WebMessage {
+            request_id,
             response: tx,
-            request: ScriptsRequest::UpdateScript(script),
-        })
-        .await
-    {
-        tracing::error!("failed to dispatch UpdateScript to engine: {}", err);
-        return Err(warp::reject::custom(InternalError {}));
-    };
+            request: ScriptsRequest::UpdateScript(player.name, script),
+        },
+    )?;
 
     match rx.await {
-        Ok(ScriptsResponse::ScriptCompiled(error)) => {
+        Ok(ScriptsResponse::ScriptCompiled(error, revision)) => {
             tracing::debug!("finished script update (success) w/err: {:?}", error);
-            Ok(warp::reply::json(&CompileResponse { error }))
+            Ok(with_etag(
+                warp::reply::json(&CompileResponse { revision, error }),
+                revision,
+            ))
         }
         Ok(ScriptsResponse::Error(err)) => Err(warp::reject::custom(err)),
         other => {
@@ -382,6 +781,7 @@ async fn handle_update(
 async fn handle_delete(
     player: Player,
     script_name: JsonScriptName,
+    request_id: RequestId,
     sender: mpsc::Sender<WebMessage>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     tracing::debug!(
@@ -391,16 +791,14 @@ async fn handle_delete(
     );
 
     let (tx, rx) = oneshot::channel();
-    if let Err(err) = sender
-        .send(WebMessage {
+    send_to_engine(
+        &sender,
+        WebMessage {
+            request_id,
             response: tx,
-            request: ScriptsRequest::DeleteScript(script_name),
-        })
-        .await
-    {
-        tracing::error!("failed to dispatch DeleteScript to engine: {}", err);
-        return Err(warp::reject::custom(InternalError {}));
-    };
+            request: ScriptsRequest::DeleteScript(player.name, script_name),
+        },
+    )?;
 
     match rx.await {
         Ok(ScriptsResponse::Done) => Ok(warp::reply::json(&JsonEmpty {})),
@@ -412,8 +810,46 @@ async fn handle_delete(
     }
 }
 
-fn with_sender(
-    tx: mpsc::Sender<WebMessage>,
-) -> impl Filter<Extract = (mpsc::Sender<WebMessage>,), Error = std::convert::Infallible> + Clone {
-    warp::any().map(move || tx.clone())
+#[tracing::instrument(
+    name = "script stats",
+    skip_all,
+    fields(player = player.name.as_str())
+)]
+async fn handle_stats(
+    player: Player,
+    request_id: RequestId,
+    sender: mpsc::Sender<WebMessage>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    tracing::debug!("player {} reading script stats", player.name());
+
+    let (tx, rx) = oneshot::channel();
+    send_to_engine(
+        &sender,
+        WebMessage {
+            request_id,
+            response: tx,
+            request: ScriptsRequest::ReadStats,
+        },
+    )?;
+
+    match rx.await {
+        Ok(ScriptsResponse::Stats(stats)) => Ok(warp::reply::json(&stats)),
+        other => {
+            tracing::error!("received unexpected response to ReadStats: {:?}", other);
+            Err(warp::reject::custom(InternalError {}))
+        }
+    }
+}
+
+fn with_etag(reply: impl warp::Reply, revision: i64) -> impl warp::Reply {
+    warp::reply::with_header(reply, "etag", format!("\"{}\"", revision))
+}
+
+fn parse_etag(value: &str) -> Option<i64> {
+    value
+        .trim()
+        .trim_start_matches("W/")
+        .trim_matches('"')
+        .parse()
+        .ok()
 }
@@ -1,28 +1,45 @@
 use std::{
-    fs::create_dir_all,
+    fs::{self, create_dir_all},
     io,
     path::{Path, PathBuf},
+    sync::{Arc, RwLock},
 };
 
 use acme_lib::{create_p384_key, persist::FilePersist, Certificate, Directory, DirectoryUrl};
 use jwt_simple::prelude::ES256KeyPair;
 use once_cell::sync::OnceCell;
+use rustls::{
+    internal::pemfile,
+    sign::{self, CertifiedKey},
+    ClientHello, ResolvesServerCert,
+};
 use thiserror::Error;
 use tokio::{
     fs::File,
     io::{AsyncReadExt, AsyncWriteExt},
+    task::JoinError,
+    time::Duration,
 };
 use warp::{Filter, Rejection};
 
+static ACME_CHALLENGE_DIR: &str = "acme";
 static JWT_KEY_FILE: &str = "jwt_key";
 static JWT_KEY: OnceCell<ES256KeyPair> = OnceCell::new();
 
+// Let's Encrypt certificates are valid for 90 days and should be renewed with 30 days remaining.
+const RENEWAL_DAYS: i64 = 30;
+const RENEWAL_CHECK: Duration = Duration::from_secs(12 * 60 * 60);
+
 #[derive(Debug, Error)]
 pub enum CertificateError {
     #[error("acme error: {0}")]
     AcmeError(#[from] acme_lib::Error),
     #[error("token save error: {0}")]
     ChallengeSave(#[from] io::Error),
+    #[error("certificate request failed: {0}")]
+    RequestFailed(#[from] JoinError),
+    #[error("invalid certificate: {0}")]
+    InvalidCertificate(&'static str),
 }
 
 #[derive(Debug, Error)]
@@ -33,27 +50,107 @@ pub enum JwtError {
     KeyError(#[from] jwt_simple::Error),
 }
 
+/// Loads the certificate for the domain from disk, requesting a new one if it is missing or close
+/// to expiring.
 #[tracing::instrument(name = "retrieve certificate")]
 pub async fn retrieve_certificate(
     key_path: &Path,
     domain: &str,
     email: &str,
+    challenge_port: u16,
 ) -> Result<Certificate, CertificateError> {
-    let certificate = match load_certificate(key_path, domain)? {
-        Some(certificate) => certificate,
-        None => {
-            let challenge_server = build_acme_challenge_server();
-            let challenge_handle =
-                tokio::spawn(async move { challenge_server.run(([0, 0, 0, 0], 80)).await });
+    match load_certificate(key_path, domain, email)? {
+        Some(certificate) if certificate.valid_days_left() > RENEWAL_DAYS => Ok(certificate),
+        _ => request_certificate(key_path, domain, email, challenge_port).await,
+    }
+}
+
+/// Periodically checks the certificate for the domain and renews it when it is close to expiring,
+/// replacing the certificate served by the resolver.
+#[tracing::instrument(name = "renew certificate", skip(resolver, key_path))]
+pub async fn renew_certificate(
+    resolver: Arc<CertificateResolver>,
+    key_path: PathBuf,
+    domain: String,
+    email: String,
+    challenge_port: u16,
+) {
+    let mut interval = tokio::time::interval(RENEWAL_CHECK);
+    // The first tick completes immediately, the certificate was just retrieved.
+    interval.tick().await;
 
-            let certificate = request_certificate(key_path, domain, email).await?;
+    loop {
+        interval.tick().await;
 
-            challenge_handle.abort();
-            certificate
+        let days_left = match load_certificate(key_path.as_path(), domain.as_str(), email.as_str())
+        {
+            Ok(Some(certificate)) => certificate.valid_days_left(),
+            Ok(None) => 0,
+            Err(e) => {
+                tracing::error!("failed to load TLS certificate: {}", e);
+                continue;
+            }
+        };
+
+        if days_left > RENEWAL_DAYS {
+            tracing::debug!("TLS certificate valid for {} more days", days_left);
+            continue;
         }
-    };
 
-    Ok(certificate)
+        tracing::info!("TLS certificate expires in {} days, renewing", days_left);
+        match request_certificate(
+            key_path.as_path(),
+            domain.as_str(),
+            email.as_str(),
+            challenge_port,
+        )
+        .await
+        .and_then(|certificate| resolver.replace(&certificate))
+        {
+            Ok(()) => tracing::info!("renewed TLS certificate"),
+            Err(e) => tracing::error!("failed to renew TLS certificate: {}", e),
+        }
+    }
+}
+
+/// Supplies the certificate presented to TLS clients. The certificate can be replaced while the
+/// server is running.
+pub struct CertificateResolver {
+    key: RwLock<CertifiedKey>,
+}
+
+impl CertificateResolver {
+    pub fn new(certificate: &Certificate) -> Result<Self, CertificateError> {
+        Ok(CertificateResolver {
+            key: RwLock::new(certified_key(certificate)?),
+        })
+    }
+
+    pub fn replace(&self, certificate: &Certificate) -> Result<(), CertificateError> {
+        let key = certified_key(certificate)?;
+        *self.key.write().unwrap() = key;
+        Ok(())
+    }
+}
+
+impl ResolvesServerCert for CertificateResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<CertifiedKey> {
+        Some(self.key.read().unwrap().clone())
+    }
+}
+
+fn certified_key(certificate: &Certificate) -> Result<CertifiedKey, CertificateError> {
+    let chain = pemfile::certs(&mut certificate.certificate().as_bytes())
+        .map_err(|_| CertificateError::InvalidCertificate("failed to parse certificate chain"))?;
+    let key = pemfile::pkcs8_private_keys(&mut certificate.private_key().as_bytes())
+        .map_err(|_| CertificateError::InvalidCertificate("failed to parse private key"))?
+        .into_iter()
+        .next()
+        .ok_or(CertificateError::InvalidCertificate("missing private key"))?;
+    let key = sign::any_supported_type(&key)
+        .map_err(|_| CertificateError::InvalidCertificate("unsupported private key"))?;
+
+    Ok(CertifiedKey::new(chain, Arc::new(key)))
 }
 
 #[tracing::instrument(name = "retrieve jwt key", skip_all, fields(key_file = JWT_KEY_FILE))]
@@ -86,26 +183,28 @@ pub async fn retrieve_jwt_key(path: &Path) -> Result<(), JwtError> {
 
 fn build_acme_challenge_server(
 ) -> warp::Server<impl Filter<Extract = impl warp::Reply, Error = Rejection> + Clone> {
-    let path = PathBuf::from("acme");
+    let path = PathBuf::from(ACME_CHALLENGE_DIR);
     create_dir_all(path.as_path()).unwrap();
 
     let routes = warp::path(".well-known")
         .and(warp::path("acme-challenge"))
-        .and(warp::filters::fs::dir("acme"));
+        .and(warp::filters::fs::dir(ACME_CHALLENGE_DIR));
 
     warp::serve(routes)
 }
 
+fn directory(key_path: &Path) -> Result<Directory<FilePersist>, CertificateError> {
+    let persist = FilePersist::new(key_path);
+    Ok(Directory::from_url(persist, DirectoryUrl::LetsEncrypt)?)
+}
+
 #[tracing::instrument(name = "load certificate", skip_all)]
 fn load_certificate(
     key_path: &Path,
     domain: &str,
+    email: &str,
 ) -> Result<Option<Certificate>, CertificateError> {
-    let url = DirectoryUrl::LetsEncryptStaging;
-    let persist = FilePersist::new(key_path);
-    let directory = Directory::from_url(persist, url)?;
-
-    let account = directory.account("sriler@gmail.com")?;
+    let account = directory(key_path)?.account(email)?;
     if let Some(certificate) = account.certificate(domain)? {
         tracing::info!("loading TLS certificate from disk");
         Ok(Some(certificate))
@@ -115,18 +214,39 @@ fn load_certificate(
     }
 }
 
+// Serves the HTTP-01 challenge on the challenge port while the certificate is being requested.
 #[tracing::instrument(name = "request certificate", skip_all)]
 async fn request_certificate(
     key_path: &Path,
     domain: &str,
     email: &str,
+    challenge_port: u16,
 ) -> Result<Certificate, CertificateError> {
-    tracing::info!("requesting new TLS certificate");
-    let url = DirectoryUrl::LetsEncrypt;
-    let persist = FilePersist::new(key_path);
-    let directory = Directory::from_url(persist, url)?;
+    let challenge_server = build_acme_challenge_server();
+    let challenge_handle =
+        tokio::spawn(async move { challenge_server.run(([0, 0, 0, 0], challenge_port)).await });
+
+    // The ACME client blocks while it waits for validation.
+    let key_path = key_path.to_path_buf();
+    let domain = domain.to_string();
+    let email = email.to_string();
+    let result = tokio::task::spawn_blocking(move || {
+        order_certificate(key_path.as_path(), domain.as_str(), email.as_str())
+    })
+    .await;
+
+    challenge_handle.abort();
+
+    result?
+}
 
-    let account = directory.account(email)?;
+fn order_certificate(
+    key_path: &Path,
+    domain: &str,
+    email: &str,
+) -> Result<Certificate, CertificateError> {
+    tracing::info!("requesting new TLS certificate");
+    let account = directory(key_path)?.account(email)?;
 
     let mut new_order = account.new_order(domain, &[])?;
 
@@ -138,7 +258,7 @@ async fn request_certificate(
         let auths = new_order.authorizations()?;
         let challenge = auths[0].http_challenge();
 
-        save_token(challenge.http_token(), challenge.http_proof()).await?;
+        save_token(challenge.http_token(), challenge.http_proof())?;
 
         challenge.validate(5000)?;
         new_order.refresh()?;
@@ -154,10 +274,9 @@ async fn request_certificate(
     Ok(certificate)
 }
 
-async fn save_token(token: &str, proof: String) -> Result<(), CertificateError> {
-    let path = PathBuf::from("acme");
-    let mut file = File::create(path.join(token)).await?;
-    file.write_all(proof.as_bytes()).await?;
+fn save_token(token: &str, proof: String) -> Result<(), CertificateError> {
+    let path = PathBuf::from(ACME_CHALLENGE_DIR);
+    fs::write(path.join(token), proof.as_bytes())?;
     Ok(())
 }
 
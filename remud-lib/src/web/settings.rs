@@ -0,0 +1,80 @@
+use std::sync::{Arc, RwLock};
+
+use warp::{reject, Filter, Rejection};
+
+use crate::{
+    clock::Clock,
+    web::limit::{RateLimitOptions, RateLimits},
+};
+
+#[derive(Debug)]
+pub struct OriginNotAllowed {}
+
+impl reject::Reject for OriginNotAllowed {}
+
+/// The web server's settings which may change while it runs. Filters read them for each request,
+/// so reloading the configuration applies changes without dropping connected clients or
+/// forgetting how many requests each address has made.
+#[derive(Debug, Clone)]
+pub struct WebSettings {
+    shared: Arc<RwLock<Shared>>,
+    limits: RateLimits,
+}
+
+#[derive(Debug)]
+struct Shared {
+    cors: Vec<String>,
+    docs: bool,
+}
+
+impl WebSettings {
+    pub fn new(cors: Vec<String>, docs: bool, limits: RateLimitOptions, clock: Clock) -> Self {
+        WebSettings {
+            shared: Arc::new(RwLock::new(Shared { cors, docs })),
+            limits: RateLimits::new(limits, clock),
+        }
+    }
+
+    /// Replaces the allowed CORS origins, whether docs are served, and the rate limits.
+    pub fn update(&self, cors: Vec<String>, docs: bool, limits: RateLimitOptions) {
+        {
+            let mut shared = self.shared.write().unwrap();
+            shared.cors = cors;
+            shared.docs = docs;
+        }
+        self.limits.update(limits);
+    }
+
+    pub fn limits(&self) -> RateLimits {
+        self.limits.clone()
+    }
+
+    pub fn docs(&self) -> bool {
+        self.shared.read().unwrap().docs
+    }
+
+    // Any origin is allowed when none are listed.
+    fn origin_allowed(&self, origin: &str) -> bool {
+        let shared = self.shared.read().unwrap();
+        shared.cors.is_empty() || shared.cors.iter().any(|allowed| allowed == origin)
+    }
+}
+
+/// Rejects cross-origin requests, including preflight requests, from origins which are not
+/// currently allowed. Requests without an origin are let through.
+pub fn allow_origin(settings: WebSettings) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<String>("origin")
+        .and_then(move |origin: Option<String>| {
+            let settings = settings.clone();
+            async move {
+                match origin {
+                    Some(origin) if !settings.origin_allowed(origin.as_str()) => {
+                        tracing::debug!("rejected request from origin {}", origin);
+                        Err(reject::custom(OriginNotAllowed {}))
+                    }
+                    _ => Ok(()),
+                }
+            }
+        })
+        .untuple_one()
+}
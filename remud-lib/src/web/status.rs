@@ -0,0 +1,75 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+use warp::{reject, Filter};
+
+use crate::{
+    engine::db::{AuthDb, SessionStats},
+    web::{with_db, InternalError},
+};
+
+pub fn status_filters<DB>(
+    db: DB,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    status(db)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonStatusResponse {
+    /// Players who have ever created a character.
+    players: i64,
+    players_online: i64,
+    /// Sessions started in the last 24 hours.
+    sessions_last_day: i64,
+    /// Distinct players who connected in the last 24 hours.
+    unique_players_last_day: i64,
+    /// The average length of completed sessions, in seconds.
+    average_session_secs: i64,
+    /// Total playtime across all players, in seconds.
+    total_playtime_secs: i64,
+}
+
+impl From<SessionStats> for JsonStatusResponse {
+    fn from(value: SessionStats) -> Self {
+        JsonStatusResponse {
+            players: value.players,
+            players_online: value.players_online,
+            sessions_last_day: value.sessions_last_day,
+            unique_players_last_day: value.unique_players_last_day,
+            average_session_secs: value.average_session_secs,
+            total_playtime_secs: value.total_playtime_secs,
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/status",
+    tag = "status",
+    responses(
+        (status = 200, description = "Player population and session statistics", body = JsonStatusResponse),
+    )
+)]
+pub fn status<DB>(
+    db: DB,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path!("status")
+        .and(warp::get())
+        .and(with_db(db))
+        .and_then(handle_status)
+}
+
+async fn handle_status<DB: AuthDb>(db: DB) -> Result<impl warp::Reply, warp::Rejection> {
+    match db.session_stats().await {
+        Ok(stats) => Ok(warp::reply::json(&JsonStatusResponse::from(stats))),
+        Err(err) => {
+            tracing::error!("failed to retrieve session statistics: {}", err);
+            Err(reject::custom(InternalError {}))
+        }
+    }
+}
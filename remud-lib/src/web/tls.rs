@@ -0,0 +1,69 @@
+use std::{convert::Infallible, io, net::SocketAddr, sync::Arc};
+
+use rustls::{NoClientAuth, ServerConfig};
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+use warp::{
+    hyper::{server::conn::Http, service::service_fn, Body, Request},
+    Filter, Reply,
+};
+
+use crate::web::{
+    limit::RemoteAddr, request_id::call_with_request_id, security::CertificateResolver,
+};
+
+/// Serves the filter over TLS. Certificates are looked up through the resolver for each
+/// handshake, so a renewed certificate is used by new connections as soon as it is installed.
+/// Warp can't see the address of connections it doesn't accept itself, so it is attached to each
+/// request along with the request's ID.
+pub async fn serve_tls<F>(
+    filter: F,
+    address: ([u8; 4], u16),
+    resolver: Arc<CertificateResolver>,
+) -> io::Result<()>
+where
+    F: Filter<Error = Infallible> + Clone + Send + Sync + 'static,
+    F::Extract: Reply,
+{
+    let mut config = ServerConfig::new(NoClientAuth::new());
+    config.cert_resolver = resolver;
+    config.set_protocols(&[b"h2".to_vec(), b"http/1.1".to_vec()]);
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+
+    let listener = TcpListener::bind(SocketAddr::from(address)).await?;
+    let service = warp::service(filter);
+
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                tracing::warn!("failed to accept TLS connection: {}", e);
+                continue;
+            }
+        };
+
+        let acceptor = acceptor.clone();
+        let service = service.clone();
+        let service = service_fn(move |mut request: Request<Body>| {
+            request.extensions_mut().insert(RemoteAddr(peer));
+            call_with_request_id(service.clone(), request)
+        });
+        tokio::spawn(async move {
+            let stream = match acceptor.accept(stream).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    tracing::debug!("TLS handshake failed: {}", e);
+                    return;
+                }
+            };
+
+            if let Err(e) = Http::new()
+                .serve_connection(stream, service)
+                .with_upgrades()
+                .await
+            {
+                tracing::debug!("TLS connection closed with error: {}", e);
+            }
+        });
+    }
+}
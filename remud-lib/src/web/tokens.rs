@@ -0,0 +1,321 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::ToSchema;
+use warp::{reject, Filter};
+
+use crate::{
+    clock::Clock,
+    engine::db::{ApiToken, AuthDb},
+    web::{
+        auth::{
+            api_token, api_token_hash, api_token_secret, verify_access, API_TOKEN_SCOPES,
+            SCOPE_WORLD,
+        },
+        limit::{limited_access, RateLimiter, RateLimits},
+        with_clock, with_db, ErrorMessage, InternalError, JsonEmpty, Player,
+    },
+};
+
+const MAX_TOKEN_NAME_LEN: usize = 32;
+
+pub fn token_filters<DB>(
+    db: DB,
+    clock: Clock,
+    limits: RateLimits,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path("auth")
+        .and(warp::path("tokens"))
+        .and(warp::post())
+        .and(
+            create(db.clone(), clock.clone(), limits.writes.clone())
+                .or(read(db.clone(), clock.clone()))
+                .or(revoke(db, clock, limits.writes)),
+        )
+}
+
+#[derive(Debug, Error)]
+pub enum TokenError {
+    #[error("bad token name")]
+    BadName,
+    #[error("bad token scope")]
+    BadScope,
+    #[error("a token with the name already exists")]
+    DuplicateName,
+    #[error("token not found")]
+    TokenNotFound,
+}
+
+impl warp::reject::Reject for TokenError {}
+
+// Token names are short identifiers made of lowercase letters, digits, dashes, and underscores.
+fn token_name_valid(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_TOKEN_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct JsonCreateToken {
+    /// Lowercase letters, digits, dashes, and underscores, at most 32 characters.
+    name: String,
+    /// Any of scripts:read, scripts:write, and world:read.
+    scopes: Vec<String>,
+}
+
+fn json_create_token() -> impl Filter<Extract = (JsonCreateToken,), Error = warp::Rejection> + Clone
+{
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct JsonTokenName {
+    name: String,
+}
+
+fn json_token_name() -> impl Filter<Extract = (JsonTokenName,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonCreatedToken {
+    name: String,
+    /// The token to present as a bearer token. It is not stored and cannot be retrieved again.
+    token: String,
+    scopes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonToken {
+    name: String,
+    scopes: Vec<String>,
+    created_by: String,
+    /// When the token was created, in seconds since the Unix epoch.
+    created_at: i64,
+    /// When the token was last used, in seconds since the Unix epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_used_at: Option<i64>,
+}
+
+impl From<ApiToken> for JsonToken {
+    fn from(value: ApiToken) -> Self {
+        JsonToken {
+            name: value.name,
+            scopes: value.scopes.split_whitespace().map(String::from).collect(),
+            created_by: value.created_by,
+            created_at: value.created_at,
+            last_used_at: value.last_used_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonTokensResponse {
+    /// The API tokens, by name.
+    tokens: Vec<JsonToken>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/tokens/create",
+    tag = "auth",
+    request_body = JsonCreateToken,
+    responses(
+        (status = 200, description = "Created the API token", body = JsonCreatedToken),
+        (status = 400, description = "Bad token name or scope", body = ErrorMessage),
+        (status = 401, description = "Missing or inadequate access token", body = ErrorMessage),
+        (status = 409, description = "A token with the name already exists", body = ErrorMessage),
+        (status = 429, description = "Too many changes from the address or by the player", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds until another change is allowed"))),
+    ),
+    security(("bearer" = []))
+)]
+pub fn create<DB>(
+    db: DB,
+    clock: Clock,
+    limiter: RateLimiter,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path("create")
+        .and(limited_access(
+            db.clone(),
+            clock.clone(),
+            vec![SCOPE_WORLD.to_string()],
+            limiter,
+        ))
+        .and(json_create_token())
+        .and(with_db(db))
+        .and(with_clock(clock))
+        .and_then(handle_create)
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/tokens/read",
+    tag = "auth",
+    responses(
+        (status = 200, description = "The API tokens, without their secrets", body = JsonTokensResponse),
+        (status = 401, description = "Missing or inadequate access token", body = ErrorMessage),
+    ),
+    security(("bearer" = []))
+)]
+pub fn read<DB>(
+    db: DB,
+    clock: Clock,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path("read")
+        .and(verify_access(
+            db.clone(),
+            clock,
+            vec![SCOPE_WORLD.to_string()],
+        ))
+        .and(with_db(db))
+        .and_then(handle_read)
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/tokens/revoke",
+    tag = "auth",
+    request_body = JsonTokenName,
+    responses(
+        (status = 200, description = "Revoked the API token", body = JsonEmpty),
+        (status = 401, description = "Missing or inadequate access token", body = ErrorMessage),
+        (status = 404, description = "Token not found", body = ErrorMessage),
+        (status = 429, description = "Too many changes from the address or by the player", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds until another change is allowed"))),
+    ),
+    security(("bearer" = []))
+)]
+pub fn revoke<DB>(
+    db: DB,
+    clock: Clock,
+    limiter: RateLimiter,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path("revoke")
+        .and(limited_access(
+            db.clone(),
+            clock,
+            vec![SCOPE_WORLD.to_string()],
+            limiter,
+        ))
+        .and(json_token_name())
+        .and(with_db(db))
+        .and_then(handle_revoke)
+}
+
+#[tracing::instrument(
+    name = "create API token",
+    skip_all,
+    fields(player = player.name.as_str(), name = request.name.as_str())
+)]
+async fn handle_create<DB: AuthDb>(
+    player: Player,
+    request: JsonCreateToken,
+    db: DB,
+    clock: Clock,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if !token_name_valid(request.name.as_str()) {
+        return Err(reject::custom(TokenError::BadName));
+    }
+
+    if request.scopes.is_empty()
+        || request
+            .scopes
+            .iter()
+            .any(|scope| !API_TOKEN_SCOPES.contains(&scope.as_str()))
+    {
+        return Err(reject::custom(TokenError::BadScope));
+    }
+    let scopes = request.scopes.into_iter().unique().collect_vec();
+
+    let secret = api_token_secret();
+    let hash = api_token_hash(secret.as_str());
+
+    let id = match db
+        .create_api_token(
+            request.name.as_str(),
+            hash.as_str(),
+            scopes.join(" ").as_str(),
+            player.name(),
+            clock.unix_now(),
+        )
+        .await
+    {
+        Ok(Some(id)) => id,
+        Ok(None) => return Err(reject::custom(TokenError::DuplicateName)),
+        Err(err) => {
+            tracing::error!("failed to store API token: {}", err);
+            return Err(reject::custom(InternalError {}));
+        }
+    };
+
+    tracing::info!(
+        "player {} created API token {} with scopes {}",
+        player.name(),
+        request.name,
+        scopes.join(" ")
+    );
+
+    Ok(warp::reply::json(&JsonCreatedToken {
+        name: request.name,
+        token: api_token(id, secret.as_str()),
+        scopes,
+    }))
+}
+
+#[tracing::instrument(name = "read API tokens", skip_all, fields(player = player.name.as_str()))]
+async fn handle_read<DB: AuthDb>(
+    player: Player,
+    db: DB,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match db.api_tokens().await {
+        Ok(tokens) => Ok(warp::reply::json(&JsonTokensResponse {
+            tokens: tokens.into_iter().map(JsonToken::from).collect(),
+        })),
+        Err(err) => {
+            tracing::error!("failed to retrieve API tokens: {}", err);
+            Err(reject::custom(InternalError {}))
+        }
+    }
+}
+
+#[tracing::instrument(
+    name = "revoke API token",
+    skip_all,
+    fields(player = player.name.as_str(), name = request.name.as_str())
+)]
+async fn handle_revoke<DB: AuthDb>(
+    player: Player,
+    request: JsonTokenName,
+    db: DB,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match db.revoke_api_token(request.name.as_str()).await {
+        Ok(true) => {
+            tracing::info!(
+                "player {} revoked API token {}",
+                player.name(),
+                request.name
+            );
+            Ok(warp::reply::json(&JsonEmpty {}))
+        }
+        Ok(false) => Err(reject::custom(TokenError::TokenNotFound)),
+        Err(err) => {
+            tracing::error!("failed to revoke API token: {}", err);
+            Err(reject::custom(InternalError {}))
+        }
+    }
+}
@@ -1,30 +1,160 @@
 use crate::{
-    color::{Color256, ColorTrue, COLOR_NAME_MAP, COLOR_TAG_MATCHER},
-    engine::{ClientMessage, EngineResponse, Output},
+    clock::Clock,
+    color::{Color256, ColorTrue, Style, COLOR_NAME_MAP, COLOR_TAG_MATCHER},
+    engine::{db::AuthDb, ClientMessage, Credentials, EngineResponse, Output, Text},
+    markup::strip_links,
     metrics::{stats_gauge, stats_incr},
+    web::{
+        auth::{optional_access, AuthError, SCOPE_WORLD},
+        EngineBusy, ErrorMessage, Player,
+    },
+    world::types::player::ClientCaps,
     ClientId, CLIENT_ID_COUNTER,
 };
 use futures::{SinkExt, StreamExt};
 use itertools::Itertools;
 use once_cell::sync::Lazy;
+use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::AtomicU64;
 use std::{borrow::Cow, convert::TryFrom, str::FromStr, sync::atomic::Ordering};
 use thiserror::Error;
-use tokio::sync::mpsc;
-use warp::{filters::ws::WebSocket, ws::Message, Filter, Rejection};
+use tokio::sync::{mpsc, oneshot};
+use warp::{filters::ws::WebSocket, reject, ws::Message, Filter, Rejection};
 
 static WS_CONNECTION_COUNTER: Lazy<AtomicU64> = Lazy::new(|| AtomicU64::new(0));
 
-pub(crate) fn websocket_filters(
+const RESUME_TOKEN_LEN: usize = 32;
+
+pub(crate) fn websocket_filters<DB>(
+    db: DB,
+    clock: Clock,
+    engine_tx: mpsc::Sender<ClientMessage>,
+) -> impl Filter<Extract = impl warp::Reply, Error = Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path("ws").and(
+        play(db.clone(), clock.clone(), engine_tx.clone())
+            .or(admin(db.clone(), clock.clone(), engine_tx.clone()))
+            // the original endpoint, kept for older clients
+            .or(warp::path::end().and(session(db, clock, engine_tx, false))),
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/ws/play",
+    tag = "game",
+    params(
+        ("token" = Option<String>, Query, description = "An access token, as an alternative to the Authorization header"),
+        ("resume" = Option<String>, Query, description = "A resume token from an earlier connection, to take back its session"),
+    ),
+    responses(
+        (status = 101, description = "Upgraded to a game session websocket. Without a token the session starts at the login prompt."),
+        (status = 401, description = "Invalid access token", body = ErrorMessage),
+        (status = 503, description = "The engine is too busy to start a session", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    )
+)]
+pub(crate) fn play<DB>(
+    db: DB,
+    clock: Clock,
+    engine_tx: mpsc::Sender<ClientMessage>,
+) -> impl Filter<Extract = impl warp::Reply, Error = Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path("play")
+        .and(warp::path::end())
+        .and(session(db, clock, engine_tx, false))
+}
+
+#[utoipa::path(
+    get,
+    path = "/ws/admin",
+    tag = "game",
+    params(
+        ("token" = Option<String>, Query, description = "An access token, as an alternative to the Authorization header"),
+        ("resume" = Option<String>, Query, description = "A resume token from an earlier connection, to take back its session"),
+    ),
+    responses(
+        (status = 101, description = "Upgraded to a game session websocket for an immortal"),
+        (status = 401, description = "Missing or invalid access token, or not an immortal", body = ErrorMessage),
+        (status = 503, description = "The engine is too busy to start a session", body = ErrorMessage,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+    security(("bearer" = []))
+)]
+pub(crate) fn admin<DB>(
+    db: DB,
+    clock: Clock,
     engine_tx: mpsc::Sender<ClientMessage>,
-) -> impl Filter<Extract = impl warp::Reply, Error = Rejection> + Clone {
-    warp::path("ws")
-        .and(warp::ws())
+) -> impl Filter<Extract = impl warp::Reply, Error = Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::path("admin")
+        .and(warp::path::end())
+        .and(session(db, clock, engine_tx, true))
+}
+
+fn session<DB>(
+    db: DB,
+    clock: Clock,
+    engine_tx: mpsc::Sender<ClientMessage>,
+    immortal: bool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    warp::ws()
+        .and(with_credentials(db, clock, immortal))
+        .and(warp::query::<ResumeQuery>())
         .and(with_engine_tx(engine_tx))
-        .map(|web_socket: warp::ws::Ws, engine_tx| {
-            web_socket.on_upgrade(move |socket| websocket_connect(socket, engine_tx))
-        })
+        .map(
+            |web_socket: warp::ws::Ws,
+             credentials: Option<Credentials>,
+             query: ResumeQuery,
+             engine_tx| {
+                web_socket.on_upgrade(move |socket| {
+                    websocket_connect(socket, engine_tx, credentials, query.resume)
+                })
+            },
+        )
+}
+
+#[derive(Debug, Deserialize)]
+struct ResumeQuery {
+    resume: Option<String>,
+}
+
+// Immortal sessions require a token with world access. Other sessions log in as the token's
+// player if one is provided, and through the login prompt otherwise.
+fn with_credentials<DB>(
+    db: DB,
+    clock: Clock,
+    immortal: bool,
+) -> impl Filter<Extract = (Option<Credentials>,), Error = Rejection> + Clone
+where
+    DB: AuthDb + Send + Sync + Clone + 'static,
+{
+    let scopes = if immortal {
+        vec![SCOPE_WORLD.to_string()]
+    } else {
+        vec![]
+    };
+
+    optional_access(db, clock, scopes).and_then(move |player: Option<Player>| async move {
+        match player {
+            Some(player) => Ok(Some(Credentials {
+                player: player.name,
+                immortal,
+            })),
+            None if immortal => Err(reject::custom(AuthError::InvalidAuthHeader)),
+            None => Ok(None),
+        }
+    })
 }
 
 #[derive(Debug, Deserialize)]
@@ -74,6 +204,9 @@ enum WsResponse {
         #[serde(skip_serializing_if = "is_false")]
         is_sensitive: bool,
     },
+    Resume {
+        token: String,
+    },
 }
 
 fn is_false(value: &bool) -> bool {
@@ -93,7 +226,7 @@ impl From<Output> for WsResponse {
             Output::Prompt { format, sensitive } => (true, format, sensitive),
         };
 
-        let segments = colorize_web(message.as_str());
+        let segments = colorize_web(strip_links(message.as_str()).as_str());
         WsResponse::Game {
             segments,
             is_prompt,
@@ -109,6 +242,10 @@ pub enum WsMessageSegment {
     ColorStart { color: ColorTrue },
     #[serde(rename(serialize = "ce"))]
     ColorEnd,
+    #[serde(rename(serialize = "ss"))]
+    StyleStart { style: Style },
+    #[serde(rename(serialize = "se"))]
+    StyleEnd { style: Style },
     #[serde(rename(serialize = "t"))]
     Text { text: String },
 }
@@ -122,6 +259,14 @@ impl WsMessageSegment {
         WsMessageSegment::ColorEnd
     }
 
+    pub fn style(style: Style) -> Self {
+        WsMessageSegment::StyleStart { style }
+    }
+
+    pub fn end_style(style: Style) -> Self {
+        WsMessageSegment::StyleEnd { style }
+    }
+
     pub fn text(text: Cow<'_, str>) -> Self {
         WsMessageSegment::Text {
             text: text.to_owned().to_string(),
@@ -129,55 +274,146 @@ impl WsMessageSegment {
     }
 }
 
+// New sessions are refused while the engine's client queue is full, rather than adding to it.
 fn with_engine_tx(
     engine_tx: mpsc::Sender<ClientMessage>,
-) -> impl Filter<Extract = (mpsc::Sender<ClientMessage>,), Error = std::convert::Infallible> + Clone
-{
-    warp::any().map(move || engine_tx.clone())
+) -> impl Filter<Extract = (mpsc::Sender<ClientMessage>,), Error = Rejection> + Clone {
+    warp::any().and_then(move || {
+        let engine_tx = engine_tx.clone();
+        async move {
+            if engine_tx.capacity() == 0 {
+                tracing::warn!("engine client queue is full, refusing websocket session");
+                stats_incr("web.engine_busy");
+                Err(reject::custom(EngineBusy {}))
+            } else {
+                Ok(engine_tx)
+            }
+        }
+    })
 }
 
 #[tracing::instrument(name = "websocket connect", skip_all)]
-async fn websocket_connect(websocket: WebSocket, client_tx: mpsc::Sender<ClientMessage>) {
+async fn websocket_connect(
+    websocket: WebSocket,
+    client_tx: mpsc::Sender<ClientMessage>,
+    credentials: Option<Credentials>,
+    resume: Option<String>,
+) {
     stats_incr("ws.client_connected");
     stats_gauge(
         "ws.num_clients",
         WS_CONNECTION_COUNTER.fetch_add(1, Ordering::SeqCst) + 1,
     );
-    let client_id = ClientId(CLIENT_ID_COUNTER.fetch_add(1, Ordering::SeqCst));
+    let connection_id = ClientId(CLIENT_ID_COUNTER.fetch_add(1, Ordering::SeqCst));
     let (engine_tx, engine_rx) = mpsc::channel(16);
 
+    // a client which lost its connection may take its session back instead of logging in again
+    let resumed = match resume {
+        Some(token) => resume_session(connection_id, token, &client_tx, engine_tx.clone()).await,
+        None => None,
+    };
+
+    let client_id = match resumed {
+        Some(client_id) => {
+            // the engine holds the sender now, so the session ends when the engine goes away
+            drop(engine_tx);
+            stats_incr("ws.client_resumed");
+            client_id
+        }
+        None => {
+            if client_tx
+                .send(ClientMessage::Connect(
+                    connection_id,
+                    client_tx.clone(),
+                    engine_tx,
+                ))
+                .await
+                .is_err()
+            {
+                return;
+            }
+
+            if client_tx
+                .send(ClientMessage::Capabilities(
+                    connection_id,
+                    ClientCaps::web(),
+                ))
+                .await
+                .is_err()
+            {
+                return;
+            }
+
+            let ready = match credentials {
+                Some(credentials) => ClientMessage::ReadyAs(connection_id, credentials),
+                None => ClientMessage::Ready(connection_id),
+            };
+            if client_tx.send(ready).await.is_err() {
+                return;
+            }
+
+            connection_id
+        }
+    };
+
+    let resume_token = new_resume_token();
     if client_tx
-        .send(ClientMessage::Connect(
-            client_id,
-            client_tx.clone(),
-            engine_tx,
-        ))
+        .send(ClientMessage::Resumable(client_id, resume_token.clone()))
         .await
         .is_err()
     {
         return;
     }
 
-    if client_tx
-        .send(ClientMessage::Ready(client_id))
+    process(client_id, websocket, client_tx, engine_rx, resume_token).await
+}
+
+async fn resume_session(
+    connection_id: ClientId,
+    token: String,
+    client_tx: &mpsc::Sender<ClientMessage>,
+    engine_tx: mpsc::Sender<EngineResponse>,
+) -> Option<ClientId> {
+    let (tx, rx) = oneshot::channel();
+    client_tx
+        .send(ClientMessage::Resume(connection_id, token, engine_tx, tx))
         .await
-        .is_err()
-    {
-        return;
-    }
+        .ok()?;
+    rx.await.ok().flatten()
+}
 
-    process(client_id, websocket, client_tx, engine_rx).await
+fn new_resume_token() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(RESUME_TOKEN_LEN)
+        .map(char::from)
+        .collect()
 }
 
-#[tracing::instrument(name = "process websocket", skip(websocket, client_tx, engine_rx))]
+#[tracing::instrument(
+    name = "process websocket",
+    skip(websocket, client_tx, engine_rx, resume_token)
+)]
 async fn process(
     client_id: ClientId,
     websocket: WebSocket,
     client_tx: mpsc::Sender<ClientMessage>,
     mut engine_rx: mpsc::Receiver<EngineResponse>,
+    resume_token: String,
 ) {
     let (mut ws_tx, mut ws_rx) = websocket.split();
 
+    let response = WsResponse::Resume {
+        token: resume_token,
+    };
+    if ws_tx.send(response.to_message()).await.is_err() {
+        client_tx
+            .send(ClientMessage::Disconnect(client_id))
+            .await
+            .ok();
+        return;
+    }
+
     loop {
         tokio::select! {
             maybe_message = ws_rx.next() => {
@@ -186,7 +422,7 @@ async fn process(
                         Ok(request) => {
                             match request {
                                 WsRequest::Game { message } => {
-                                    if client_tx.send(ClientMessage::Input(client_id, message)).await.is_err() {
+                                    if client_tx.send(ClientMessage::Input(client_id, Text::from(message))).await.is_err() {
                                         break
                                     }
                                 }
@@ -216,9 +452,10 @@ async fn process(
                                 }
                             }
                         },
+                        EngineResponse::Disconnect => break,
                     }
                 } else {
-                    let response = WsResponse::from(Output::Message("\r\nServer shutting down. Thanks for playing. <3\r\n".to_string()));
+                    let response = WsResponse::from(Output::Message(Text::from_static("\r\nServer shutting down. Thanks for playing. <3\r\n")));
                     if ws_tx.send(response.to_message()).await.is_err() {
                         break
                     }
@@ -241,7 +478,8 @@ async fn process(
 
 fn colorize_web(message: &str) -> Vec<WsMessageSegment> {
     let mut vec = Vec::new();
-    let mut open = 0;
+    // open tags, None for colors and the style for styles
+    let mut open: Vec<Option<Style>> = Vec::new();
     let mut next_start = 0;
 
     // move through the string one capture at a time, adding the text in between
@@ -262,7 +500,7 @@ fn colorize_web(message: &str) -> Vec<WsMessageSegment> {
             next_start += captures.get(0).unwrap().end();
             if let Ok(color) = Color256::from_str(m.as_str()) {
                 let color = ColorTrue::from(color);
-                open += 1;
+                open.push(None);
                 vec.push(WsMessageSegment::color(color));
             } else {
                 tracing::warn!("failed to capture matched 256 color: {}", m.as_str());
@@ -270,25 +508,27 @@ fn colorize_web(message: &str) -> Vec<WsMessageSegment> {
         } else if let Some(m) = captures.name("true") {
             next_start += captures.get(0).unwrap().end();
             if let Ok(color) = ColorTrue::from_str(m.as_str()) {
-                open += 1;
+                open.push(None);
                 vec.push(WsMessageSegment::color(color));
             } else {
                 tracing::warn!("failed to capture matched true color: {}", m.as_str());
             }
         } else if let Some(m) = captures.name("name") {
             next_start += captures.get(0).unwrap().end();
-            if let Some(index) = COLOR_NAME_MAP.get(m.as_str().to_lowercase().as_str()) {
+            if let Ok(style) = Style::from_str(m.as_str()) {
+                open.push(Some(style));
+                vec.push(WsMessageSegment::style(style));
+            } else if let Some(index) = COLOR_NAME_MAP.get(m.as_str().to_lowercase().as_str()) {
                 let color = ColorTrue::from(Color256::new(*index));
-                open += 1;
+                open.push(None);
                 vec.push(WsMessageSegment::color(color));
             } else {
                 tracing::warn!("failed to match color name: {}", m.as_str());
             }
         } else if captures.name("clear").is_some() {
             next_start += captures.get(0).unwrap().end();
-            if open > 0 {
-                open -= 1;
-                vec.push(WsMessageSegment::end_color());
+            if let Some(tag) = open.pop() {
+                vec.push(close_segment(tag));
             }
         } else {
             let capture = captures
@@ -311,18 +551,24 @@ fn colorize_web(message: &str) -> Vec<WsMessageSegment> {
     }
 
     // close all remaining open tags
-    while open > 0 {
-        vec.push(WsMessageSegment::end_color());
-        open -= 1;
+    while let Some(tag) = open.pop() {
+        vec.push(close_segment(tag));
     }
 
     vec
 }
 
+fn close_segment(tag: Option<Style>) -> WsMessageSegment {
+    match tag {
+        Some(style) => WsMessageSegment::end_style(style),
+        None => WsMessageSegment::end_color(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        color::ColorTrue,
+        color::{ColorTrue, Style},
         web::ws::{colorize_web, WsMessageSegment},
     };
 
@@ -381,6 +627,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn colorize_web_styles() {
+        let result = colorize_web("|bold|some |white|text|-|");
+        assert_eq!(
+            &[
+                WsMessageSegment::style(Style::Bold),
+                WsMessageSegment::text("some ".into()),
+                WsMessageSegment::color(ColorTrue::new(255, 255, 255)),
+                WsMessageSegment::text("text".into()),
+                WsMessageSegment::end_color(),
+                WsMessageSegment::end_style(Style::Bold),
+            ],
+            result.as_slice()
+        );
+    }
+
     #[test]
     fn colorize_web_pipe_escape() {
         let result = colorize_web("|white||||-|");
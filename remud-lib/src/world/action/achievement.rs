@@ -0,0 +1,141 @@
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+
+use crate::{
+    engine::persist::{self, Updates},
+    world::{
+        action::{into_action, Action},
+        types::{
+            achievement::{AchievementId, Achievements, EarnedAchievements},
+            player::{Messages, Player},
+            Named,
+        },
+    },
+};
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ShowAchievements {
+    pub actor: Entity,
+}
+
+into_action!(ShowAchievements);
+
+#[tracing::instrument(
+    name = "show achievements system",
+    skip_all,
+    fields(action = "ShowAchievements")
+)]
+pub fn show_achievements_system(
+    mut action_reader: EventReader<Action>,
+    achievements: Res<Achievements>,
+    earned_query: Query<&EarnedAchievements>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ShowAchievements(ShowAchievements { actor }) = action {
+            let earned = match earned_query.get(*actor) {
+                Ok(earned) => earned,
+                Err(_) => continue,
+            };
+
+            let lines = achievements
+                .iter()
+                .filter(|(id, _)| earned.contains(id))
+                .map(|(_, achievement)| {
+                    format!(
+                        "  |white|{}|-|: {}",
+                        achievement.name.replace("|", "||"),
+                        achievement.description.replace("|", "||")
+                    )
+                })
+                .collect_vec();
+
+            let message = if lines.is_empty() {
+                "You have not earned any achievements yet.".to_string()
+            } else {
+                format!(
+                    "You have earned {} of {} achievements:\r\n{}",
+                    lines.len(),
+                    achievements.len(),
+                    lines.join("\r\n")
+                )
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct GrantAchievement {
+    pub actor: Entity,
+    pub id: AchievementId,
+}
+
+into_action!(GrantAchievement);
+
+#[tracing::instrument(
+    name = "grant achievement system",
+    skip_all,
+    fields(action = "GrantAchievement")
+)]
+pub fn grant_achievement_system(
+    mut action_reader: EventReader<Action>,
+    mut achievements: ResMut<Achievements>,
+    mut updates: ResMut<Updates>,
+    mut earned_query: Query<(&Player, &Named, &mut EarnedAchievements)>,
+    player_query: Query<Entity, With<Player>>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::GrantAchievement(GrantAchievement { actor, id }) = action {
+            let achievement = match achievements.get_mut(id) {
+                Some(achievement) => achievement,
+                None => {
+                    tracing::warn!("attempted to grant unknown achievement {}", id);
+                    continue;
+                }
+            };
+
+            let (player, name) = match earned_query.get_mut(*actor) {
+                Ok((player, named, mut earned)) => {
+                    if !earned.insert(id.clone()) {
+                        continue;
+                    }
+                    (player.id(), named.to_string())
+                }
+                Err(_) => continue,
+            };
+
+            updates.persist(persist::achievement::Grant::new(player, id.clone()));
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!(
+                    "|Gold1|Achievement unlocked: {}!|-|",
+                    achievement.name.replace("|", "||")
+                ));
+            }
+
+            // The first player to earn an achievement is announced to everyone online.
+            if !achievement.earned {
+                achievement.earned = true;
+
+                let announcement = format!(
+                    "{} is the first to earn the achievement {}!",
+                    name,
+                    achievement.name.replace("|", "||")
+                );
+                for other in player_query.iter() {
+                    if other != *actor {
+                        if let Ok(mut messages) = messages_query.get_mut(other) {
+                            messages.queue(announcement.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,12 @@
 use crate::{
-    text::Tokenizer,
+    text::{format_duration, header, Table, Tokenizer, DEFAULT_WIDTH},
     world::{
         action::{into_action, Action},
-        types::{player::Messages, Attributes, Health},
+        types::{
+            faction::{Factions, Reputation, Standing},
+            player::{ClientCaps, Messages, Playtime},
+            Attributes, Health,
+        },
     },
 };
 use bevy_app::EventReader;
@@ -19,21 +23,55 @@ pub struct Stats {
 
 into_action!(Stats);
 
-#[tracing::instrument(name = "stats system", skip_all)]
+#[tracing::instrument(name = "stats system", skip_all, fields(action = "Stats"))]
 pub fn stats_system(
     mut action_reader: EventReader<Action>,
-    mut stats_query: Query<(&Health, &Attributes)>,
+    factions: Res<Factions>,
+    mut stats_query: Query<(
+        &Health,
+        &Attributes,
+        Option<&Playtime>,
+        Option<&Reputation>,
+        Option<&ClientCaps>,
+    )>,
     mut messages_query: Query<&mut Messages>,
 ) {
     for action in action_reader.iter() {
         if let Action::Stats(Stats { actor }) = action {
-            if let Ok((health, stats)) = stats_query.get_mut(*actor) {
+            if let Ok((health, stats, playtime, reputation, width)) = stats_query.get_mut(*actor) {
+                let mut fields =
+                    Table::fields(width.map(ClientCaps::width).unwrap_or(DEFAULT_WIDTH));
+
+                fields.field("Health", format!("{} / {}", health.current, health.max));
+                fields.field("Constitution", stats.constitution.to_string());
+                fields.field("Dexterity", stats.dexterity.to_string());
+                fields.field("Intellect", stats.intellect.to_string());
+                fields.field("Strength", stats.strength.to_string());
+
+                if let Some(playtime) = playtime {
+                    fields.field("Played", format_duration(playtime.total_seconds()));
+                }
+
+                if let Some(reputation) = reputation {
+                    let standings = factions
+                        .iter()
+                        .filter_map(|(id, faction)| {
+                            let value = reputation.get(id);
+                            (value != 0).then(|| {
+                                format!(
+                                    "{}: {} ({})",
+                                    faction.name.replace("|", "||"),
+                                    Standing::from_reputation(value),
+                                    value
+                                )
+                            })
+                        })
+                        .collect();
+                    fields.list_field("Reputation", standings);
+                }
+
                 if let Ok(mut messages) = messages_query.get_mut(*actor) {
-                    messages.queue(format!("Health {} / {}", health.current, health.max));
-                    messages.queue(format!(
-                        "Con {} / Dex {} / Int {} / Str {}",
-                        stats.constitution, stats.dexterity, stats.intellect, stats.strength
-                    ));
+                    messages.queue(format!("{}\r\n{}", header("Stats"), fields.render()));
                 }
             }
         }
@@ -0,0 +1,418 @@
+use bevy_app::EventReader;
+use bevy_ecs::{prelude::*, system::SystemParam};
+use itertools::Itertools;
+
+use crate::{
+    clock::Clock,
+    engine::persist::{self, UpdateGroup, Updates},
+    text::Tokenizer,
+    world::{
+        action::{into_action, Action},
+        types::{
+            bank::{format_coins, Bank, Vault, VAULT_CAPACITY},
+            economy::{CoinSource, Economy},
+            object::{Flags, Keywords, Object, ObjectFlags},
+            player::{Messages, Player, PlayerId},
+            Contents, Location, Named,
+        },
+    },
+};
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum Transfer {
+    Coins(i64),
+    Object(Vec<String>),
+}
+
+// Valid shapes:
+// deposit <amount> - deposits coins
+// deposit <keywords> - deposits an object in the vault
+pub fn parse_deposit(player: Entity, tokenizer: Tokenizer) -> Result<Action, String> {
+    let transfer = parse_transfer(tokenizer, "Deposit")?;
+
+    Ok(Action::from(Deposit {
+        actor: player,
+        transfer,
+    }))
+}
+
+// Valid shapes:
+// withdraw <amount> - withdraws coins
+// withdraw <keywords> - withdraws an object from the vault
+pub fn parse_withdraw(player: Entity, tokenizer: Tokenizer) -> Result<Action, String> {
+    let transfer = parse_transfer(tokenizer, "Withdraw")?;
+
+    Ok(Action::from(Withdraw {
+        actor: player,
+        transfer,
+    }))
+}
+
+fn parse_transfer(tokenizer: Tokenizer, verb: &str) -> Result<Transfer, String> {
+    if tokenizer.rest().is_empty() {
+        return Err(format!("{} what?", verb));
+    }
+
+    if let Ok(amount) = tokenizer.rest().parse::<i64>() {
+        if amount > 0 {
+            Ok(Transfer::Coins(amount))
+        } else {
+            Err(format!(
+                "You can only {} a positive number of coins.",
+                verb.to_lowercase()
+            ))
+        }
+    } else {
+        Ok(Transfer::Object(
+            tokenizer
+                .rest()
+                .split_whitespace()
+                .map(ToString::to_string)
+                .collect_vec(),
+        ))
+    }
+}
+
+// Whether a room's objects include one flagged as a bank
+fn is_bank(objects: &[Entity], flags_query: &Query<&ObjectFlags>) -> bool {
+    objects.iter().any(|object| {
+        flags_query
+            .get(*object)
+            .map(|flags| flags.contains(Flags::BANK))
+            .unwrap_or(false)
+    })
+}
+
+/// Gives and takes the coins players carry where coins enter or leave the game, recording each
+/// change in the economy. Coins moving between players go through the bank directly.
+#[derive(SystemParam)]
+pub struct Treasury<'a> {
+    clock: Res<'a, Clock>,
+    bank: ResMut<'a, Bank>,
+    economy: ResMut<'a, Economy>,
+}
+
+impl<'a> Treasury<'a> {
+    pub fn bank(&self) -> &Bank {
+        &self.bank
+    }
+
+    /// Gives a player coins from a source, or takes them when the amount is negative, returning
+    /// the updates to persist. Fails with the coins the player is carrying, changing nothing, when
+    /// they cannot cover the amount.
+    pub fn add_coins(
+        &mut self,
+        player: PlayerId,
+        name: &str,
+        source: CoinSource,
+        amount: i64,
+    ) -> Result<Box<UpdateGroup>, i64> {
+        let account = self.bank.open(player, name);
+
+        if account.coins + amount < 0 {
+            return Err(account.coins);
+        }
+
+        account.coins += amount;
+        let account = persist::bank::Account::new(player, account.coins, account.balance);
+
+        let (day, flow) = self.economy.record(self.clock.unix_now(), source, amount);
+
+        Ok(UpdateGroup::new(vec![
+            account,
+            persist::economy::Update::new(day, source, flow),
+        ]))
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Balance {
+    pub actor: Entity,
+}
+
+into_action!(Balance);
+
+#[tracing::instrument(name = "balance system", skip_all, fields(action = "Balance"))]
+pub fn balance_system(
+    mut action_reader: EventReader<Action>,
+    bank: Res<Bank>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<(&Player, &Location, &Vault)>,
+    contents_query: Query<&Contents>,
+    flags_query: Query<&ObjectFlags>,
+    object_query: Query<&Named, With<Object>>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Balance(Balance { actor }) = action {
+            let (player, location, vault) = match player_query.get(*actor) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+
+            let (coins, balance) = bank
+                .get(player.id())
+                .map(|account| (account.coins, account.balance))
+                .unwrap_or_default();
+
+            let mut message = format!(
+                "You are carrying {}. Your bank balance is {}.",
+                format_coins(coins),
+                format_coins(balance)
+            );
+
+            // Vault contents can only be seen from inside a bank.
+            let in_bank = contents_query
+                .get(location.entity())
+                .map(|contents| is_bank(contents.objects(), &flags_query))
+                .unwrap_or(false);
+
+            if in_bank && !vault.is_loaded() {
+                updates.load_vault(*actor, action.clone());
+                continue;
+            }
+
+            if in_bank {
+                message.push_str(
+                    format!(
+                        "\r\n|white|Vault ({}/{})|-|",
+                        vault.objects().len(),
+                        VAULT_CAPACITY
+                    )
+                    .as_str(),
+                );
+
+                if vault.objects().is_empty() {
+                    message.push_str("\r\n  Your vault is empty.");
+                }
+
+                vault
+                    .objects()
+                    .iter()
+                    .filter_map(|object| object_query.get(*object).ok())
+                    .for_each(|named| message.push_str(format!("\r\n  {}", named).as_str()));
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Deposit {
+    pub actor: Entity,
+    pub transfer: Transfer,
+}
+
+into_action!(Deposit);
+
+#[tracing::instrument(name = "deposit system", skip_all, fields(action = "Deposit"))]
+pub fn deposit_system(
+    mut action_reader: EventReader<Action>,
+    mut bank: ResMut<Bank>,
+    mut updates: ResMut<Updates>,
+    mut player_query: Query<(&Player, &Named, &Location, &mut Vault)>,
+    mut contents_query: Query<&mut Contents>,
+    flags_query: Query<&ObjectFlags>,
+    object_query: Query<(&Object, &Named, &Keywords)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Deposit(Deposit { actor, transfer }) = action {
+            let (player, named, location, mut vault) = match player_query.get_mut(*actor) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+
+            let in_bank = contents_query
+                .get_mut(location.entity())
+                .map(|contents| is_bank(contents.objects(), &flags_query))
+                .unwrap_or(false);
+
+            // Moving objects in or out needs the vault, which is loaded the first time it is used.
+            if in_bank && matches!(transfer, Transfer::Object(_)) && !vault.is_loaded() {
+                updates.load_vault(*actor, action.clone());
+                continue;
+            }
+
+            let message = if !in_bank {
+                "There is no bank here.".to_string()
+            } else {
+                match transfer {
+                    Transfer::Coins(amount) => {
+                        let account = bank.open(player.id(), named.as_str());
+
+                        if account.coins < *amount {
+                            format!("You only have {}.", format_coins(account.coins))
+                        } else {
+                            account.coins -= amount;
+                            account.balance += amount;
+
+                            updates.persist(persist::bank::Account::new(
+                                player.id(),
+                                account.coins,
+                                account.balance,
+                            ));
+
+                            format!(
+                                "You deposit {}. Your bank balance is {}.",
+                                format_coins(*amount),
+                                format_coins(account.balance)
+                            )
+                        }
+                    }
+                    Transfer::Object(keywords) => {
+                        let target = contents_query.get_mut(*actor).ok().and_then(|contents| {
+                            contents.find(|object| {
+                                object_query
+                                    .get(object)
+                                    .map(|(_, _, object_keywords)| {
+                                        object_keywords.contains_all(keywords.as_slice())
+                                    })
+                                    .unwrap_or(false)
+                            })
+                        });
+
+                        if let Some(object) = target {
+                            if vault.is_full() {
+                                "Your vault is full.".to_string()
+                            } else {
+                                let (object_id, name) = object_query
+                                    .get(object)
+                                    .map(|(object, named, _)| (object.id(), named.to_string()))
+                                    .unwrap();
+
+                                if let Ok(mut contents) = contents_query.get_mut(*actor) {
+                                    contents.remove(object);
+                                }
+                                vault.insert(object);
+
+                                updates.persist(UpdateGroup::new(vec![
+                                    persist::player::RemoveObject::new(player.id(), object_id),
+                                    persist::bank::AddObject::new(player.id(), object_id),
+                                ]));
+
+                                format!("You deposit {} in your vault.", name)
+                            }
+                        } else {
+                            format!("You don't have \"{}\".", keywords.join(" "))
+                        }
+                    }
+                }
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Withdraw {
+    pub actor: Entity,
+    pub transfer: Transfer,
+}
+
+into_action!(Withdraw);
+
+#[tracing::instrument(name = "withdraw system", skip_all, fields(action = "Withdraw"))]
+pub fn withdraw_system(
+    mut action_reader: EventReader<Action>,
+    mut bank: ResMut<Bank>,
+    mut updates: ResMut<Updates>,
+    mut player_query: Query<(&Player, &Named, &Location, &mut Vault)>,
+    mut contents_query: Query<&mut Contents>,
+    flags_query: Query<&ObjectFlags>,
+    object_query: Query<(&Object, &Named, &Keywords)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Withdraw(Withdraw { actor, transfer }) = action {
+            let (player, named, location, mut vault) = match player_query.get_mut(*actor) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+
+            let in_bank = contents_query
+                .get_mut(location.entity())
+                .map(|contents| is_bank(contents.objects(), &flags_query))
+                .unwrap_or(false);
+
+            // Moving objects in or out needs the vault, which is loaded the first time it is used.
+            if in_bank && matches!(transfer, Transfer::Object(_)) && !vault.is_loaded() {
+                updates.load_vault(*actor, action.clone());
+                continue;
+            }
+
+            let message = if !in_bank {
+                "There is no bank here.".to_string()
+            } else {
+                match transfer {
+                    Transfer::Coins(amount) => {
+                        let account = bank.open(player.id(), named.as_str());
+
+                        if account.balance < *amount {
+                            format!(
+                                "Your bank balance is only {}.",
+                                format_coins(account.balance)
+                            )
+                        } else {
+                            account.balance -= amount;
+                            account.coins += amount;
+
+                            updates.persist(persist::bank::Account::new(
+                                player.id(),
+                                account.coins,
+                                account.balance,
+                            ));
+
+                            format!(
+                                "You withdraw {}. Your bank balance is {}.",
+                                format_coins(*amount),
+                                format_coins(account.balance)
+                            )
+                        }
+                    }
+                    Transfer::Object(keywords) => {
+                        let target = vault.find(|object| {
+                            object_query
+                                .get(object)
+                                .map(|(_, _, object_keywords)| {
+                                    object_keywords.contains_all(keywords.as_slice())
+                                })
+                                .unwrap_or(false)
+                        });
+
+                        if let Some(object) = target {
+                            let (object_id, name) = object_query
+                                .get(object)
+                                .map(|(object, named, _)| (object.id(), named.to_string()))
+                                .unwrap();
+
+                            vault.remove(object);
+                            if let Ok(mut contents) = contents_query.get_mut(*actor) {
+                                contents.insert(object);
+                            }
+
+                            updates.persist(UpdateGroup::new(vec![
+                                persist::bank::RemoveObject::new(player.id(), object_id),
+                                persist::player::AddObject::new(player.id(), object_id),
+                            ]));
+
+                            format!("You withdraw {} from your vault.", name)
+                        } else {
+                            format!("Your vault holds no \"{}\".", keywords.join(" "))
+                        }
+                    }
+                }
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
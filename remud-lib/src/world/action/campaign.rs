@@ -0,0 +1,54 @@
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+
+use crate::world::{
+    action::{into_action, Action},
+    types::{campaign::Campaigns, player::Messages},
+};
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ShowCampaigns {
+    pub actor: Entity,
+}
+
+into_action!(ShowCampaigns);
+
+// Lists the campaigns under way and the goal of each one's current stage.
+#[tracing::instrument(
+    name = "show campaigns system",
+    skip_all,
+    fields(action = "ShowCampaigns")
+)]
+pub fn show_campaigns_system(
+    mut action_reader: EventReader<Action>,
+    campaigns: Res<Campaigns>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ShowCampaigns(ShowCampaigns { actor }) = action {
+            let lines = campaigns
+                .iter()
+                .filter_map(|(_, campaign)| {
+                    campaign.current().map(|stage| match &stage.goal {
+                        Some(goal) => format!(
+                            "  |white|{}|-|: {}",
+                            campaign.name.replace("|", "||"),
+                            goal.replace("|", "||")
+                        ),
+                        None => format!("  |white|{}|-|", campaign.name.replace("|", "||")),
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let message = if lines.is_empty() {
+                "There are no events under way.".to_string()
+            } else {
+                format!("Events under way:\r\n{}", lines.join("\r\n"))
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
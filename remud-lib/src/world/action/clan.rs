@@ -0,0 +1,783 @@
+use bevy_app::{EventReader, EventWriter};
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+
+use crate::{
+    engine::persist::{self, UpdateGroup, Updates},
+    logging::GAME_LOG_TARGET,
+    text::{plain, Tokenizer},
+    world::{
+        action::{
+            communicate::{ignorable, is_ignoring, record_communication, Transcript},
+            into_action,
+            movement::Teleport,
+            Action,
+        },
+        scripting::QueuedAction,
+        types::{
+            clan::{valid_clan_name, Clan, ClanMember, ClanRank, Clans, MAX_CLAN_NAME_LENGTH},
+            communication::Channel,
+            player::{CommunicationLog, Ignores, Messages, Player, PlayerFlags, Players},
+            room::Rooms,
+            Named,
+        },
+    },
+};
+
+// Valid shapes:
+// clan [info] - shows your clan
+// clan disband - disbands a clan you founded
+// clan found <name> - founds a new clan
+// clan hall - travels to your clan's hall
+// clan invite <player> - invites an online player to your clan
+// clan join <name> - joins a clan you have been invited to
+// clan kick <player> - removes a player from your clan
+// clan leave - leaves your clan
+// clan rank <player> <rank> - sets the rank of a member of your clan
+// clan say <message> - speaks to every online member of your clan
+pub fn parse_clan(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    match tokenizer.next() {
+        None | Some("info") => Ok(Action::from(ClanInfo {
+            actor: player,
+            name: None,
+        })),
+        Some("disband") => Ok(Action::from(ClanDisband {
+            actor: player,
+            name: None,
+        })),
+        Some("found") => match tokenizer.next() {
+            Some(name) if valid_clan_name(name) => Ok(Action::from(ClanFound {
+                actor: player,
+                name: name.to_string(),
+            })),
+            Some(_) => Err(format!(
+                "Clan names must be a single word of 3 to {} letters and digits.",
+                MAX_CLAN_NAME_LENGTH
+            )),
+            None => Err("Enter a name for your clan.".to_string()),
+        },
+        Some("hall") => Ok(Action::from(ClanHall { actor: player })),
+        Some("invite") => match tokenizer.next() {
+            Some(name) => Ok(Action::from(ClanInvite {
+                actor: player,
+                name: name.to_string(),
+            })),
+            None => Err("Invite whom?".to_string()),
+        },
+        Some("join") => match tokenizer.next() {
+            Some(name) => Ok(Action::from(ClanJoin {
+                actor: player,
+                name: name.to_string(),
+            })),
+            None => Err("Join which clan?".to_string()),
+        },
+        Some("kick") => match tokenizer.next() {
+            Some(name) => Ok(Action::from(ClanKick {
+                actor: player,
+                name: name.to_string(),
+            })),
+            None => Err("Kick whom?".to_string()),
+        },
+        Some("leave") => Ok(Action::from(ClanLeave { actor: player })),
+        Some("rank") => {
+            let name = match tokenizer.next() {
+                Some(name) => name.to_string(),
+                None => return Err("Enter a member and their new rank.".to_string()),
+            };
+
+            match tokenizer.next().map(str::parse::<ClanRank>) {
+                Some(Ok(rank)) => Ok(Action::from(ClanSetRank {
+                    actor: player,
+                    name,
+                    rank,
+                })),
+                Some(Err(e)) => Err(e.to_string()),
+                None => Err("Enter a rank: member, officer, or leader.".to_string()),
+            }
+        }
+        Some("say") => {
+            if tokenizer.rest().is_empty() {
+                Err("Say what?".to_string())
+            } else {
+                Ok(Action::from(ClanSay {
+                    actor: player,
+                    message: tokenizer.rest().to_string(),
+                }))
+            }
+        }
+        Some(_) => Err(
+            "Enter a valid clan subcommand: disband, found, hall, info, invite, join, kick, \
+             leave, rank, or say."
+                .to_string(),
+        ),
+    }
+}
+
+// Queues a message for every online member of a clan other than the actor.
+fn tell_members(
+    actor: Entity,
+    clan: &Clan,
+    message: &str,
+    players: &Players,
+    messages_query: &mut Query<&mut Messages>,
+) {
+    for (_, member) in clan.members() {
+        if let Some(mut messages) = players
+            .by_name(member.name.as_str())
+            .filter(|player| *player != actor)
+            .and_then(|player| messages_query.get_mut(player).ok())
+        {
+            messages.queue(message.to_string());
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ClanDisband {
+    pub actor: Entity,
+    // the clan to disband, for immortals. Players may only disband the clan they founded.
+    pub name: Option<String>,
+}
+
+into_action!(ClanDisband);
+
+#[tracing::instrument(name = "clan disband system", skip_all, fields(action = "ClanDisband"))]
+pub fn clan_disband_system(
+    mut action_reader: EventReader<Action>,
+    mut clans: ResMut<Clans>,
+    players: Res<Players>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<&Player>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ClanDisband(ClanDisband { actor, name }) = action {
+            let player = match player_query.get(*actor) {
+                Ok(player) => player.id(),
+                Err(_) => continue,
+            };
+
+            let result = match name {
+                Some(name) => clans
+                    .get(name)
+                    .map(|clan| clan.name.clone())
+                    .ok_or_else(|| format!("Clan '{}' not found.", name)),
+                None => match clans.by_player(player) {
+                    Some(clan) if clan.founder == player => Ok(clan.name.clone()),
+                    Some(_) => Err("Only the founder of a clan can disband it.".to_string()),
+                    None => Err("You are not in a clan.".to_string()),
+                },
+            };
+
+            let message = match result {
+                Ok(name) => {
+                    let clan = clans.remove(name.as_str()).unwrap();
+
+                    updates.persist(persist::clan::Delete::new(clan.name.clone()));
+
+                    tell_members(
+                        *actor,
+                        &clan,
+                        format!("The clan {} has been disbanded.", clan.name).as_str(),
+                        &players,
+                        &mut messages_query,
+                    );
+
+                    format!("You disband the clan {}.", clan.name)
+                }
+                Err(message) => message,
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ClanFound {
+    pub actor: Entity,
+    pub name: String,
+}
+
+into_action!(ClanFound);
+
+#[tracing::instrument(name = "clan found system", skip_all, fields(action = "ClanFound"))]
+pub fn clan_found_system(
+    mut action_reader: EventReader<Action>,
+    mut clans: ResMut<Clans>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<(&Player, &Named)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ClanFound(ClanFound { actor, name }) = action {
+            let (player, player_name) = match player_query.get(*actor) {
+                Ok((player, named)) => (player.id(), named.to_string()),
+                Err(_) => continue,
+            };
+
+            let message = if let Some(clan) = clans.by_player(player) {
+                format!("You are already a member of {}.", clan.name)
+            } else if let Some(clan) = clans.get(name) {
+                format!("The clan {} already exists.", clan.name)
+            } else {
+                clans.insert(Clan::new(name.clone(), player, None));
+                clans.join(
+                    name,
+                    player,
+                    ClanMember {
+                        name: player_name,
+                        rank: ClanRank::Leader,
+                    },
+                );
+
+                updates.persist(UpdateGroup::new(vec![
+                    persist::clan::Create::new(name.clone(), player),
+                    persist::clan::AddMember::new(name.clone(), player, ClanRank::Leader),
+                ]));
+
+                format!("You found the clan {}.", name)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ClanHall {
+    pub actor: Entity,
+}
+
+into_action!(ClanHall);
+
+#[tracing::instrument(name = "clan hall system", skip_all, fields(action = "ClanHall"))]
+pub fn clan_hall_system(
+    mut action_reader: EventReader<Action>,
+    mut action_writer: EventWriter<QueuedAction>,
+    clans: Res<Clans>,
+    rooms: Res<Rooms>,
+    player_query: Query<&Player>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ClanHall(ClanHall { actor }) = action {
+            let player = match player_query.get(*actor) {
+                Ok(player) => player.id(),
+                Err(_) => continue,
+            };
+
+            let message = match clans.by_player(player).map(|clan| clan.hall) {
+                Some(Some(hall)) if rooms.exists(hall) => {
+                    action_writer.send(
+                        Action::from(Teleport {
+                            actor: *actor,
+                            room_id: hall,
+                        })
+                        .into(),
+                    );
+                    continue;
+                }
+                Some(_) => "Your clan does not have a hall.",
+                None => "You are not in a clan.",
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message.to_string());
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ClanInfo {
+    pub actor: Entity,
+    // the clan to show, for immortals. Players are shown their own clan.
+    pub name: Option<String>,
+}
+
+into_action!(ClanInfo);
+
+#[tracing::instrument(name = "clan info system", skip_all, fields(action = "ClanInfo"))]
+pub fn clan_info_system(
+    mut action_reader: EventReader<Action>,
+    clans: Res<Clans>,
+    players: Res<Players>,
+    player_query: Query<&Player>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ClanInfo(ClanInfo { actor, name }) = action {
+            let player = match player_query.get(*actor) {
+                Ok(player) => player.id(),
+                Err(_) => continue,
+            };
+
+            let clan = match name {
+                Some(name) => clans
+                    .get(name)
+                    .ok_or_else(|| format!("Clan '{}' not found.", name)),
+                None => clans
+                    .by_player(player)
+                    .ok_or_else(|| "You are not in a clan.".to_string()),
+            };
+
+            let message = match clan {
+                Ok(clan) => {
+                    let mut message = format!("|white|Clan {}|-|", clan.name);
+
+                    message.push_str("\r\n  |white|founder|-|: ");
+                    message.push_str(
+                        clan.member(clan.founder)
+                            .map(|member| member.name.as_str())
+                            .unwrap_or("unknown"),
+                    );
+
+                    message.push_str("\r\n  |white|hall|-|: ");
+                    match clan.hall {
+                        Some(hall) => message.push_str(format!("room {}", hall).as_str()),
+                        None => message.push_str("none"),
+                    }
+
+                    message.push_str("\r\n  |white|members|-|:");
+                    clan.members()
+                        .map(|(_, member)| member)
+                        .sorted_by(|a, b| b.rank.cmp(&a.rank).then_with(|| a.name.cmp(&b.name)))
+                        .for_each(|member| {
+                            let online = if players.by_name(member.name.as_str()).is_some() {
+                                ", online"
+                            } else {
+                                ""
+                            };
+                            message.push_str(
+                                format!("\r\n    {} ({}{})", member.name, member.rank, online)
+                                    .as_str(),
+                            )
+                        });
+
+                    message
+                }
+                Err(message) => message,
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ClanInvite {
+    pub actor: Entity,
+    pub name: String,
+}
+
+into_action!(ClanInvite);
+
+#[tracing::instrument(name = "clan invite system", skip_all, fields(action = "ClanInvite"))]
+pub fn clan_invite_system(
+    mut action_reader: EventReader<Action>,
+    mut clans: ResMut<Clans>,
+    players: Res<Players>,
+    player_query: Query<(&Player, &Named)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ClanInvite(ClanInvite { actor, name }) = action {
+            let (player, player_name) = match player_query.get(*actor) {
+                Ok((player, named)) => (player.id(), named.to_string()),
+                Err(_) => continue,
+            };
+
+            let invitee = players
+                .by_name(name.as_str())
+                .and_then(|entity| player_query.get(entity).ok().map(|(p, _)| (entity, p.id())));
+
+            let message = match (
+                clans.by_player(player).map(|clan| clan.rank(player)),
+                invitee,
+            ) {
+                (None, _) => "You are not in a clan.".to_string(),
+                (Some(Some(ClanRank::Member)), _) => {
+                    "Only officers and leaders can invite players to your clan.".to_string()
+                }
+                (Some(_), None) => format!("Player '{}' not found.", name),
+                (Some(_), Some((_, invitee))) if clans.by_player(invitee).is_some() => {
+                    format!("{} is already in a clan.", name)
+                }
+                (Some(_), Some((entity, invitee))) => {
+                    let clan = clans.by_player_mut(player).unwrap();
+                    clan.invite(invitee);
+
+                    if let Ok(mut messages) = messages_query.get_mut(entity) {
+                        messages.queue(format!(
+                            "{} invites you to join the clan {}. Enter \"clan join {}\" to accept.",
+                            player_name, clan.name, clan.name
+                        ));
+                    }
+
+                    format!("You invite {} to join {}.", name, clan.name)
+                }
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ClanJoin {
+    pub actor: Entity,
+    pub name: String,
+}
+
+into_action!(ClanJoin);
+
+#[tracing::instrument(name = "clan join system", skip_all, fields(action = "ClanJoin"))]
+pub fn clan_join_system(
+    mut action_reader: EventReader<Action>,
+    mut clans: ResMut<Clans>,
+    players: Res<Players>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<(&Player, &Named)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ClanJoin(ClanJoin { actor, name }) = action {
+            let (player, player_name) = match player_query.get(*actor) {
+                Ok((player, named)) => (player.id(), named.to_string()),
+                Err(_) => continue,
+            };
+
+            let message = if let Some(clan) = clans.by_player(player) {
+                format!("You are already a member of {}.", clan.name)
+            } else {
+                match clans.get(name) {
+                    Some(clan) if clan.is_invited(player) => {
+                        let clan_name = clan.name.clone();
+
+                        tell_members(
+                            *actor,
+                            clan,
+                            format!("{} joins the clan.", player_name).as_str(),
+                            &players,
+                            &mut messages_query,
+                        );
+
+                        clans.join(
+                            clan_name.as_str(),
+                            player,
+                            ClanMember {
+                                name: player_name,
+                                rank: ClanRank::Member,
+                            },
+                        );
+
+                        updates.persist(persist::clan::AddMember::new(
+                            clan_name.clone(),
+                            player,
+                            ClanRank::Member,
+                        ));
+
+                        format!("You join the clan {}.", clan_name)
+                    }
+                    _ => format!("You have not been invited to join '{}'.", name),
+                }
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ClanKick {
+    pub actor: Entity,
+    pub name: String,
+}
+
+into_action!(ClanKick);
+
+#[tracing::instrument(name = "clan kick system", skip_all, fields(action = "ClanKick"))]
+pub fn clan_kick_system(
+    mut action_reader: EventReader<Action>,
+    mut clans: ResMut<Clans>,
+    players: Res<Players>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<&Player>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ClanKick(ClanKick { actor, name }) = action {
+            let player = match player_query.get(*actor) {
+                Ok(player) => player.id(),
+                Err(_) => continue,
+            };
+
+            let clan = if let Some(clan) = clans.by_player(player) {
+                clan
+            } else {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue("You are not in a clan.".to_string());
+                }
+                continue;
+            };
+
+            let rank = clan.rank(player).unwrap();
+            let target = clan.member_by_name(name.as_str());
+
+            let message = match target {
+                None => format!("{} is not a member of {}.", name, clan.name),
+                Some((target, _)) if target == player => {
+                    "Use \"clan leave\" to leave your clan.".to_string()
+                }
+                Some((target, member))
+                    if target == clan.founder
+                        || (player != clan.founder && member.rank >= rank)
+                        || rank == ClanRank::Member =>
+                {
+                    format!("You cannot kick {} from the clan.", member.name)
+                }
+                Some((target, member)) => {
+                    let member_name = member.name.clone();
+                    let clan_name = clan.name.clone();
+
+                    clans.leave(target);
+                    updates.persist(persist::clan::RemoveMember::new(target));
+
+                    if let Some(mut messages) = players
+                        .by_name(member_name.as_str())
+                        .and_then(|kicked| messages_query.get_mut(kicked).ok())
+                    {
+                        messages.queue(format!("You have been kicked from {}.", clan_name));
+                    }
+
+                    format!("You kick {} from {}.", member_name, clan_name)
+                }
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ClanLeave {
+    pub actor: Entity,
+}
+
+into_action!(ClanLeave);
+
+#[tracing::instrument(name = "clan leave system", skip_all, fields(action = "ClanLeave"))]
+pub fn clan_leave_system(
+    mut action_reader: EventReader<Action>,
+    mut clans: ResMut<Clans>,
+    players: Res<Players>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<(&Player, &Named)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ClanLeave(ClanLeave { actor }) = action {
+            let (player, player_name) = match player_query.get(*actor) {
+                Ok((player, named)) => (player.id(), named.to_string()),
+                Err(_) => continue,
+            };
+
+            let message = match clans.by_player(player) {
+                Some(clan) if clan.founder == player => {
+                    "Founders cannot leave their clan. Disband it instead.".to_string()
+                }
+                Some(clan) => {
+                    let clan_name = clan.name.clone();
+
+                    clans.leave(player);
+                    updates.persist(persist::clan::RemoveMember::new(player));
+
+                    tell_members(
+                        *actor,
+                        clans.get(clan_name.as_str()).unwrap(),
+                        format!("{} leaves the clan.", player_name).as_str(),
+                        &players,
+                        &mut messages_query,
+                    );
+
+                    format!("You leave the clan {}.", clan_name)
+                }
+                None => "You are not in a clan.".to_string(),
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ClanSay {
+    pub actor: Entity,
+    pub message: String,
+}
+
+into_action!(ClanSay);
+
+#[tracing::instrument(name = "clan say system", skip_all, fields(action = "ClanSay"))]
+pub fn clan_say_system(
+    mut action_reader: EventReader<Action>,
+    clans: Res<Clans>,
+    players: Res<Players>,
+    named_query: Query<&Named>,
+    speaker_query: Query<(&Player, &PlayerFlags)>,
+    ignores_query: Query<&Ignores>,
+    mut log_query: Query<&mut CommunicationLog>,
+    mut messages_query: Query<&mut Messages>,
+    mut transcript: Transcript,
+) {
+    for action in action_reader.iter() {
+        if let Action::ClanSay(ClanSay { actor, message }) = action {
+            let (player, name) = match (speaker_query.get(*actor), named_query.get(*actor)) {
+                (Ok((player, _)), Ok(named)) => (player.id(), named.as_str()),
+                _ => continue,
+            };
+
+            let clan = if let Some(clan) = clans.by_player(player) {
+                clan
+            } else {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue("You are not in a clan.".to_string());
+                }
+                continue;
+            };
+
+            tracing::info!(
+                target: GAME_LOG_TARGET,
+                player = name,
+                clan = clan.name.as_str(),
+                text = plain(message).as_str(),
+                "clan say"
+            );
+            transcript.record(
+                *actor,
+                Channel::Clan,
+                name,
+                Some(clan.name.as_str()),
+                message,
+            );
+
+            let message = format!("|white|[{}]|-| {}: {}", clan.name, name, message);
+            let speaker = ignorable(*actor, &speaker_query);
+
+            for (_, member) in clan.members() {
+                let listener = match players.by_name(member.name.as_str()) {
+                    Some(listener) => listener,
+                    None => continue,
+                };
+
+                if is_ignoring(listener, speaker, &ignores_query) {
+                    continue;
+                }
+
+                record_communication(
+                    listener,
+                    *actor,
+                    name,
+                    message.as_str(),
+                    &speaker_query,
+                    &mut log_query,
+                );
+
+                if let Ok(mut messages) = messages_query.get_mut(listener) {
+                    if listener == *actor {
+                        messages.queue(message.clone());
+                    } else {
+                        messages.queue_broadcast(message.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ClanSetRank {
+    pub actor: Entity,
+    pub name: String,
+    pub rank: ClanRank,
+}
+
+into_action!(ClanSetRank);
+
+#[tracing::instrument(
+    name = "clan set rank system",
+    skip_all,
+    fields(action = "ClanSetRank")
+)]
+pub fn clan_set_rank_system(
+    mut action_reader: EventReader<Action>,
+    mut clans: ResMut<Clans>,
+    players: Res<Players>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<&Player>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ClanSetRank(ClanSetRank { actor, name, rank }) = action {
+            let player = match player_query.get(*actor) {
+                Ok(player) => player.id(),
+                Err(_) => continue,
+            };
+
+            let clan = if let Some(clan) = clans.by_player_mut(player) {
+                clan
+            } else {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue("You are not in a clan.".to_string());
+                }
+                continue;
+            };
+
+            let target = clan
+                .member_by_name(name.as_str())
+                .map(|(target, member)| (target, member.name.clone()));
+
+            let message = match target {
+                _ if clan.rank(player) != Some(ClanRank::Leader) => {
+                    "Only leaders can change the ranks of members.".to_string()
+                }
+                None => format!("{} is not a member of {}.", name, clan.name),
+                Some((target, member_name)) if target == clan.founder => {
+                    format!("{} founded the clan, and remains a leader.", member_name)
+                }
+                Some((target, member_name)) => {
+                    clan.set_rank(target, *rank);
+                    updates.persist(persist::clan::UpdateMember::new(target, *rank));
+
+                    if let Some(mut messages) = players
+                        .by_name(member_name.as_str())
+                        .and_then(|member| messages_query.get_mut(member).ok())
+                    {
+                        messages.queue(format!("Your rank in {} is now {}.", clan.name, rank));
+                    }
+
+                    format!("{}'s rank in {} is now {}.", member_name, clan.name, rank)
+                }
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
@@ -5,20 +5,60 @@ use itertools::Itertools;
 use regex::Replacer;
 
 use crate::{
+    logging::GAME_LOG_TARGET,
     macros::regex,
     text::{sorted_word_list, Tokenizer},
     world::{
         action::{
+            achievement::ShowAchievements,
             attributes::parse_stats,
-            communicate::{parse_me, parse_say, parse_send},
+            bank::{parse_deposit, parse_withdraw, Balance},
+            campaign::ShowCampaigns,
+            clan::parse_clan,
+            communicate::{
+                parse_ignore, parse_me, parse_pronouns, parse_report, parse_say, parse_send,
+                parse_unignore,
+            },
+            explore::{parse_hints, Brief, Map},
             immortal::{
-                object::parse_object, player::parse_player, prototype::parse_prototype,
-                room::parse_room, script::parse_script, UpdateDescription,
+                achievement::parse_achievement,
+                allowlist::parse_allowlist,
+                bank::parse_bank,
+                campaign::parse_event,
+                clan::parse_clans,
+                communication::parse_comms,
+                economy::parse_economy,
+                faction::parse_faction,
+                force::parse_force,
+                global::parse_global,
+                loot::parse_loot,
+                object::parse_object,
+                player::parse_player,
+                possess::{parse_possess, Release},
+                prototype::parse_prototype,
+                region::parse_region,
+                room::parse_room,
+                script::parse_script,
+                season::parse_season,
+                shop::parse_shops,
+                snoop::parse_snoop,
+                spawn::parse_spawn,
+                stage::{parse_publish, parse_stage},
+                tutorial::parse_tutorial,
+                UpdateDescription,
             },
+            mail::parse_mail,
+            market::parse_market,
             movement::{parse_teleport, Move},
-            object::{parse_drop, parse_get, parse_use, Inventory},
-            observe::{parse_look, Exits, Who},
-            system::{Restart, Shutdown},
+            news::parse_news,
+            object::{parse_drink, parse_drop, parse_eat, parse_get, parse_use, Inventory},
+            observe::{parse_look, parse_recall, parse_top, Exits, Privacy, Who},
+            poll::{parse_poll, parse_vote},
+            shop::parse_shop,
+            system::{
+                parse_config, parse_db, parse_lint, parse_maintenance, parse_profile, Restart,
+                Shutdown,
+            },
             Action,
         },
         types::{room::Direction, ActionTarget},
@@ -73,6 +113,15 @@ impl Commands {
         tokenizer: Tokenizer,
         restricted: bool,
     ) -> Result<Action, String> {
+        // A complete command name is never ambiguous, even when it prefixes another command.
+        if let Some(command) = self
+            .commands
+            .get(name)
+            .filter(|command| !restricted || !command.restricted)
+        {
+            return command.run(actor, tokenizer);
+        }
+
         let matches = self
             .commands
             .iter()
@@ -97,7 +146,7 @@ impl Commands {
             .map(|(name, _)| name)
             .find(|n| n.starts_with(name))
         {
-            (self.commands.get(key).unwrap().parser)(actor, tokenizer)
+            self.commands.get(key).unwrap().run(actor, tokenizer)
         } else {
             Err("I don't know what that means.".to_string())
         }
@@ -181,6 +230,19 @@ impl Command {
         self.shortcut = Some(shortcut);
         self
     }
+
+    fn run(&self, actor: Entity, tokenizer: Tokenizer) -> Result<Action, String> {
+        if self.restricted {
+            tracing::info!(
+                target: GAME_LOG_TARGET,
+                command = self.name,
+                arguments = tokenizer.rest(),
+                "immortal command"
+            );
+        }
+
+        (self.parser)(actor, tokenizer)
+    }
 }
 
 struct Help {
@@ -262,6 +324,275 @@ impl Replacer for UsageColorizer {
 
 fn default_commands() -> Vec<Command> {
     let mut commands = Vec::new();
+    commands.push(
+        Command::new(
+            "achievement",
+            parse_achievement,
+            Help::new(
+                "achievement (list||new <id> <name>) || achievement <id> <subcommand>",
+                "Creates and modifies achievements. Scripts grant achievements to players by ID.",
+            )
+            .with_subhelp(
+                "desc",
+                Help::new(
+                    "achievement <id> desc <text>",
+                    "Sets an achievement's description, shown to players who have earned it.",
+                )
+                .with_example("achievement explorer desc Visited every corner of City Six."),
+            )
+            .with_subhelp(
+                "list",
+                Help::new("achievement list", "Lists all achievements by ID."),
+            )
+            .with_subhelp(
+                "name",
+                Help::new(
+                    "achievement <id> name <text>",
+                    "Sets an achievement's name.",
+                )
+                .with_example("achievement explorer name Intrepid Explorer"),
+            )
+            .with_subhelp(
+                "new",
+                Help::new(
+                    "achievement new <id> <name>",
+                    "Creates a new achievement. IDs may contain lowercase letters, digits, \
+                     dashes, and underscores.",
+                )
+                .with_example("achievement new explorer Explorer"),
+            ),
+        )
+        .restricted(),
+    );
+    commands.push(Command::new(
+        "achievements",
+        |actor, _| Ok(Action::from(ShowAchievements { actor })),
+        Help::new("achievements", "Lists the achievements you have earned."),
+    ));
+    commands.push(
+        Command::new(
+            "allowlist",
+            parse_allowlist,
+            Help::new(
+                "allowlist [(add||remove) <player>]",
+                "Lists or edits the players who may log in while the allowlist is enforced. \
+                 Immortals may always log in.",
+            )
+            .with_subhelp(
+                "add",
+                Help::new("allowlist add <player>", "Adds a player to the allowlist.")
+                    .with_example("allowlist add Shane"),
+            )
+            .with_subhelp(
+                "remove",
+                Help::new(
+                    "allowlist remove <player>",
+                    "Removes a player from the allowlist.",
+                ),
+            ),
+        )
+        .restricted(),
+    );
+    commands.push(Command::new(
+        "balance",
+        |actor, _| Ok(Action::from(Balance { actor })),
+        Help::new(
+            "balance",
+            "Shows the coins you are carrying and your bank balance. In a bank, also lists the \
+             items in your vault.",
+        ),
+    ));
+    commands.push(
+        Command::new(
+            "bank",
+            parse_bank,
+            Help::new(
+                "bank audit || bank <player> [grant <amount>]",
+                "Audits the coins held by players.",
+            )
+            .with_subhelp(
+                "audit",
+                Help::new(
+                    "bank audit",
+                    "Lists the coins carried and banked by every player, and the total held.",
+                ),
+            )
+            .with_subhelp(
+                "grant",
+                Help::new(
+                    "bank <player> grant <amount>",
+                    "Gives coins to a player. A negative amount takes coins away.",
+                )
+                .with_example("bank shane grant 100"),
+            )
+            .with_subhelp(
+                "player",
+                Help::new(
+                    "bank <player>",
+                    "Shows a player's coins and, if they are online, the contents of their vault.",
+                )
+                .with_example("bank shane"),
+            ),
+        )
+        .restricted(),
+    );
+    commands.push(Command::new(
+        "brief",
+        |actor, _| Ok(Action::from(Brief { actor })),
+        Help::new(
+            "brief",
+            "Toggles brief mode. In brief mode, the descriptions of rooms you have already \
+             visited are not shown when you enter them.",
+        ),
+    ));
+    commands.push(Command::new(
+        "clan",
+        parse_clan,
+        Help::new(
+            "clan [<subcommand>]",
+            "Commands for founding, joining, and running a clan. With no subcommand, shows your \
+             clan and its members.",
+        )
+        .with_subhelp(
+            "disband",
+            Help::new(
+                "clan disband",
+                "Disbands the clan you founded, removing all of its members.",
+            ),
+        )
+        .with_subhelp(
+            "found",
+            Help::new(
+                "clan found <name>",
+                "Founds a new clan with you as its leader. Clan names are a single word.",
+            )
+            .with_example("clan found Rats"),
+        )
+        .with_subhelp(
+            "hall",
+            Help::new(
+                "clan hall",
+                "Travels to your clan's hall. Only members of the clan may enter it.",
+            ),
+        )
+        .with_subhelp(
+            "invite",
+            Help::new(
+                "clan invite <player>",
+                "Invites an online player to join your clan. Officers and leaders can invite.",
+            )
+            .with_example("clan invite shane"),
+        )
+        .with_subhelp(
+            "join",
+            Help::new("clan join <name>", "Joins a clan you have been invited to."),
+        )
+        .with_subhelp(
+            "kick",
+            Help::new(
+                "clan kick <player>",
+                "Removes a lower ranked member from your clan. Officers and leaders can kick.",
+            ),
+        )
+        .with_subhelp("leave", Help::new("clan leave", "Leaves your clan."))
+        .with_subhelp(
+            "rank",
+            Help::new(
+                "clan rank <player> <rank>",
+                "Sets a member's rank: member, officer, or leader. Only leaders can change ranks.",
+            )
+            .with_example("clan rank shane officer"),
+        )
+        .with_subhelp(
+            "say",
+            Help::new(
+                "clan say <text>",
+                "Says something to every online member of your clan.",
+            ),
+        ),
+    ));
+    commands.push(
+        Command::new(
+            "clans",
+            parse_clans,
+            Help::new(
+                "clans [<name> [<subcommand>]]",
+                "Oversees clans. With no name, lists every clan. With a name, shows the clan's \
+                 founder, hall, and members.",
+            )
+            .with_subhelp(
+                "disband",
+                Help::new("clans <name> disband", "Disbands a clan."),
+            )
+            .with_subhelp(
+                "hall",
+                Help::new(
+                    "clans <name> hall <room ID> || clans <name> hall clear",
+                    "Sets or clears a clan's hall. Only members of the clan and immortals may \
+                     enter a clan hall.",
+                )
+                .with_example("clans Rats hall 12"),
+            ),
+        )
+        .restricted(),
+    );
+    commands.push(
+        Command::new(
+            "comms",
+            parse_comms,
+            Help::new(
+                "comms [player <name>] [channel <channel>] [room <room ID>] [page <page>] [text \
+                 <text>]",
+                "Searches what players have said, emoted, sent, and said to their clans, newest \
+                 first. Filters may be combined; channels are clan, emote, say, and send. Text is \
+                 matched ignoring case and runs to the end of the command. Communication is kept \
+                 for the retention period in the server configuration.",
+            )
+            .with_example("comms player Ted channel send text gold"),
+        )
+        .restricted(),
+    );
+    commands.push(
+        Command::new(
+            "config",
+            parse_config,
+            Help::new(
+                "config <subcommand>",
+                "Commands for managing the server's configuration file.",
+            )
+            .with_subhelp(
+                "reload",
+                Help::new(
+                    "config reload",
+                    "Re-reads the configuration file and applies the new settings without \
+                     restarting ReMUD. The tick rate, metrics target, CORS origins, and message \
+                     of the day are updated.",
+                ),
+            ),
+        )
+        .restricted(),
+    );
+    commands.push(
+        Command::new(
+            "db",
+            parse_db,
+            Help::new(
+                "db <subcommand>",
+                "Commands for maintaining the world database.",
+            )
+            .with_subhelp(
+                "check",
+                Help::new(
+                    "db check [repair]",
+                    "Reports objects pointing at missing prototypes, exits leading to missing \
+                     rooms, and script hooks naming missing scripts. With repair, the dangling \
+                     records are deleted from the database; restart ReMUD to drop them from the \
+                     running world.",
+                ),
+            ),
+        )
+        .restricted(),
+    );
     commands.push(Command::new(
         "down",
         |actor, _| {
@@ -284,6 +615,25 @@ fn default_commands() -> Vec<Command> {
         Help::new("description <text>", "Sets your character's description.")
             .with_example("description A fine looking being."),
     ));
+    commands.push(Command::new(
+        "deposit",
+        parse_deposit,
+        Help::new(
+            "deposit <amount> || deposit <keyword> [<keyword>..]",
+            "Deposits coins, or the item indicated by the specified keyword(s), in the bank. \
+             Items are kept in your vault, which holds up to 20 items. You must be in a bank.",
+        )
+        .with_example("deposit fuzzy bear"),
+    ));
+    commands.push(Command::new(
+        "drink",
+        parse_drink,
+        Help::new(
+            "drink <keyword> [<keyword>..]",
+            "Drinks the item indicated by the specified keyword(s), using it up.",
+        )
+        .with_example("drink water"),
+    ));
     commands.push(Command::new(
         "drop",
         parse_drop,
@@ -303,6 +653,29 @@ fn default_commands() -> Vec<Command> {
         },
         Help::new("east", "Moves you to the room to the east, if possible."),
     ));
+    commands.push(Command::new(
+        "eat",
+        parse_eat,
+        Help::new(
+            "eat <keyword> [<keyword>..]",
+            "Eats the item indicated by the specified keyword(s), using it up.",
+        )
+        .with_example("eat bread"),
+    ));
+    commands.push(
+        Command::new(
+            "economy",
+            parse_economy,
+            Help::new(
+                "economy report [<days>]",
+                "Reports the coins created and destroyed over the last week, or the given \
+                 number of days, by day and by source, with the coins held by players. Coins \
+                 moving between players are not counted.",
+            )
+            .with_example("economy report 30"),
+        )
+        .restricted(),
+    );
     commands.push(
         Command::new(
             "emote",
@@ -316,11 +689,121 @@ fn default_commands() -> Vec<Command> {
         )
         .with_shortcut('\''),
     );
+    commands.push(
+        Command::new(
+            "event",
+            parse_event,
+            Help::new(
+                "event (list||new <id> <name>) || event <id> [<subcommand>]",
+                "Creates and runs world events: campaigns of stages which are announced to \
+                 everyone online as they begin. With just an ID, shows the event.",
+            )
+            .with_subhelp(
+                "advance",
+                Help::new(
+                    "event <id> advance",
+                    "Moves a running event on to its next stage, or concludes it after the last.",
+                ),
+            )
+            .with_subhelp(
+                "delete",
+                Help::new("event <id> delete", "Deletes an event."),
+            )
+            .with_subhelp("list", Help::new("event list", "Lists all events by ID."))
+            .with_subhelp(
+                "name",
+                Help::new("event <id> name <text>", "Sets an event's name.")
+                    .with_example("event siege name The Siege of City Six"),
+            )
+            .with_subhelp(
+                "new",
+                Help::new(
+                    "event new <id> <name>",
+                    "Creates a new event. IDs may contain lowercase letters, digits, dashes, and \
+                     underscores.",
+                )
+                .with_example("event new siege Siege"),
+            )
+            .with_subhelp(
+                "stage",
+                Help::new(
+                    "event <id> stage new <announcement> || event <id> stage <number> (announce \
+                     <text>||goal <text>||spawn <prototype ID> <room ID>||remove)",
+                    "Adds and changes an event's stages. A stage's goal is shown to players by \
+                     the events command, and its spawns are created when it begins.",
+                )
+                .with_example("event siege stage 1 spawn 12 40"),
+            )
+            .with_subhelp(
+                "start",
+                Help::new("event <id> start", "Starts an event at its first stage."),
+            )
+            .with_subhelp(
+                "stop",
+                Help::new(
+                    "event <id> stop",
+                    "Stops a running event without announcing anything.",
+                ),
+            ),
+        )
+        .restricted(),
+    );
+    commands.push(Command::new(
+        "events",
+        |actor, _| Ok(Action::from(ShowCampaigns { actor })),
+        Help::new(
+            "events",
+            "Lists the world events under way and what each one needs of you.",
+        ),
+    ));
     commands.push(Command::new(
         "exits",
         |actor, _| Ok(Action::from(Exits { actor })),
         Help::new("exits", "Lists the exits from the current room."),
     ));
+    commands.push(
+        Command::new(
+            "faction",
+            parse_faction,
+            Help::new(
+                "faction (list||new <id> <name>) || faction <id> name <text>",
+                "Creates and renames factions. Scripts adjust players' reputation with factions \
+                 by ID.",
+            )
+            .with_subhelp(
+                "list",
+                Help::new("faction list", "Lists all factions by ID."),
+            )
+            .with_subhelp(
+                "name",
+                Help::new("faction <id> name <text>", "Sets a faction's name.")
+                    .with_example("faction syndicate name The Syndicate"),
+            )
+            .with_subhelp(
+                "new",
+                Help::new(
+                    "faction new <id> <name>",
+                    "Creates a new faction. IDs may contain lowercase letters, digits, dashes, \
+                     and underscores.",
+                )
+                .with_example("faction new syndicate Syndicate"),
+            ),
+        )
+        .restricted(),
+    );
+    commands.push(
+        Command::new(
+            "force",
+            parse_force,
+            Help::new(
+                "force <player> <command>",
+                "Runs a command as if the player had typed it. Mortals cannot be forced to use \
+                 immortal commands.",
+            )
+            .with_example("force Shane look"),
+        )
+        .restricted(),
+    );
     commands.push(Command::new(
         "get",
         parse_get,
@@ -330,11 +813,81 @@ fn default_commands() -> Vec<Command> {
         )
         .with_example("get fuzzy bear"),
     ));
+    commands.push(
+        Command::new(
+            "global",
+            parse_global,
+            Help::new(
+                "global (list||set <key> <value>||remove <key>)",
+                "Shows and changes the world's global values, which scripts use to coordinate \
+                 events across rooms and objects. Values are persisted.",
+            )
+            .with_subhelp(
+                "list",
+                Help::new("global list", "Lists all globals and their values."),
+            )
+            .with_subhelp(
+                "remove",
+                Help::new("global remove <key>", "Removes a global."),
+            )
+            .with_subhelp(
+                "set",
+                Help::new(
+                    "global set <key> <value>",
+                    "Sets a global. true and false are booleans, whole numbers are integers, and \
+                     anything else is a string. Quote a value to make it a string.",
+                )
+                .with_example("global set invasion_stage 2"),
+            ),
+        )
+        .restricted(),
+    );
+    commands.push(Command::new(
+        "hints",
+        parse_hints,
+        Help::new(
+            "hints [on || off]",
+            "Turns on or off the hints shown the first time you use some commands. New characters \
+             start with hints on. Shows whether hints are on when neither is given.",
+        )
+        .with_example("hints off"),
+    ));
+    commands.push(Command::new(
+        "ignore",
+        parse_ignore,
+        Help::new(
+            "ignore [<player>]",
+            "Hides what the specified player says, emotes, and sends to you. Lists the players \
+             you are ignoring when no player is specified. Immortals cannot be ignored.",
+        )
+        .with_example("ignore Ted"),
+    ));
     commands.push(Command::new(
         "inventory",
         |actor, _| Ok(Action::from(Inventory { actor })),
         Help::new("inventory", "Displays a list of items in your inventory."),
     ));
+    commands.push(
+        Command::new(
+            "lint",
+            parse_lint,
+            Help::new(
+                "lint <subcommand>",
+                "Commands for finding common building mistakes.",
+            )
+            .with_subhelp(
+                "world",
+                Help::new(
+                    "lint world",
+                    "Reports rooms with no exits, one-way exits, rooms and prototypes without \
+                     descriptions, objects without keywords, script hooks which never run their \
+                     script, and rooms which cannot be reached from the spawn room. The world is \
+                     also linted periodically, and the latest report can be viewed on the web.",
+                ),
+            ),
+        )
+        .restricted(),
+    );
     commands.push(Command::new(
         "look",
         parse_look,
@@ -345,6 +898,180 @@ fn default_commands() -> Vec<Command> {
         )
         .with_example("look west || look at fuzzy bear"),
     ));
+    commands.push(
+        Command::new(
+            "loot",
+            parse_loot,
+            Help::new(
+                "loot (list||new <id>) || loot <id> <subcommand>",
+                "Creates and modifies loot tables. Each time a table drops loot it is rolled a \
+                 number of times, and each roll picks one entry by weight. Scripts drop loot from \
+                 a table by ID.",
+            )
+            .with_subhelp(
+                "add",
+                Help::new(
+                    "loot <id> add (<prototype ID>||nothing) <rarity> [<min>[-<max>]] [<weight>]",
+                    "Adds an entry to a loot table. Rarities are common, uncommon, rare, epic, \
+                     and legendary, and set the entry's weight unless one is given: 100, 40, 10, \
+                     3, and 1. Entries drop one object unless a quantity is given.",
+                )
+                .with_example("loot goblin add 7 rare 1-3"),
+            )
+            .with_subhelp(
+                "delete",
+                Help::new("loot <id> delete", "Deletes a loot table."),
+            )
+            .with_subhelp(
+                "info",
+                Help::new(
+                    "loot <id> info",
+                    "Shows a loot table's entries and their expected drop rates.",
+                ),
+            )
+            .with_subhelp(
+                "remove",
+                Help::new(
+                    "loot <id> remove <entry>",
+                    "Removes an entry, by its number in the table's info, from a loot table.",
+                ),
+            )
+            .with_subhelp(
+                "roll",
+                Help::new(
+                    "loot <id> roll",
+                    "Rolls a loot table and shows what it would drop, without creating anything.",
+                ),
+            )
+            .with_subhelp(
+                "rolls",
+                Help::new(
+                    "loot <id> rolls <count>",
+                    "Sets how many times a loot table is rolled each time it drops loot.",
+                ),
+            ),
+        )
+        .restricted(),
+    );
+    commands.push(Command::new(
+        "mail",
+        parse_mail,
+        Help::new(
+            "mail [list || read <id> || send <player> <message> || claim <id> || delete <id>]",
+            "Sends and receives letters, even to players who are offline. Coins and items can be \
+             attached to a letter and are held until the recipient claims them. Unclaimed \
+             attachments are returned to the sender when the letter is deleted or expires.",
+        )
+        .with_subhelp(
+            "claim",
+            Help::new(
+                "mail claim <id>",
+                "Takes the coins and item attached to a letter.",
+            ),
+        )
+        .with_subhelp(
+            "delete",
+            Help::new(
+                "mail delete <id>",
+                "Deletes a letter, returning anything unclaimed to its sender.",
+            ),
+        )
+        .with_subhelp(
+            "list",
+            Help::new("mail list", "Lists the letters you have received."),
+        )
+        .with_subhelp(
+            "read",
+            Help::new("mail read <id>", "Reads a letter.").with_example("mail read 3"),
+        )
+        .with_subhelp(
+            "send",
+            Help::new(
+                "mail send <player> [with <amount || keywords>:] <message>",
+                "Sends a letter to a player. Coins or the item indicated by the specified \
+                 keyword(s) can be attached with \"with\", followed by an optional message after \
+                 a colon.",
+            )
+            .with_example("mail send Shane meet me at the docks")
+            .with_example("mail send Shane with 50: for the sword"),
+        ),
+    ));
+    commands.push(
+        Command::new(
+            "maintenance",
+            parse_maintenance,
+            Help::new(
+                "maintenance <on [<seconds>] || off>",
+                "Toggles maintenance mode. While it is on, only immortals may log in and \
+                 connecting players are shown the maintenance notice. Maintenance mode persists \
+                 across restarts.",
+            )
+            .with_subhelp(
+                "on",
+                Help::new(
+                    "maintenance on [<seconds>]",
+                    "Turns on maintenance mode. Mortals already online stay connected, unless a \
+                     countdown is given, in which case they are warned and disconnected once it \
+                     runs out.",
+                )
+                .with_example("maintenance on 60"),
+            )
+            .with_subhelp(
+                "off",
+                Help::new(
+                    "maintenance off",
+                    "Turns off maintenance mode, cancelling any countdown.",
+                ),
+            ),
+        )
+        .restricted(),
+    );
+    commands.push(Command::new(
+        "map",
+        |actor, _| Ok(Action::from(Map { actor })),
+        Help::new(
+            "map",
+            "Shows a map of the rooms you have explored around you.",
+        ),
+    ));
+    commands.push(Command::new(
+        "market",
+        parse_market,
+        Help::new(
+            "market [list || sell <price> <keywords> || buy <id> || cancel <id>]",
+            "Buys and sells items with other players. Listed items are held by the market until \
+             they sell, are cancelled, or expire, and are then returned to you. Sale proceeds are \
+             deposited in your bank account.",
+        )
+        .with_subhelp(
+            "buy",
+            Help::new(
+                "market buy <id>",
+                "Buys a listed item with the coins you are carrying.",
+            )
+            .with_example("market buy 3"),
+        )
+        .with_subhelp(
+            "cancel",
+            Help::new(
+                "market cancel <id>",
+                "Cancels one of your listings and returns the item to you.",
+            ),
+        )
+        .with_subhelp(
+            "list",
+            Help::new("market list", "Lists the items for sale on the market."),
+        )
+        .with_subhelp(
+            "sell",
+            Help::new(
+                "market sell <price> <keywords>",
+                "Lists the item indicated by the specified keyword(s) for sale at the given price \
+                 in coins.",
+            )
+            .with_example("market sell 50 rusty sword"),
+        ),
+    ));
     commands.push(
         Command::new(
             "me",
@@ -352,12 +1079,34 @@ fn default_commands() -> Vec<Command> {
             Help::new(
                 "me <text> || /<text>",
                 "Causes your character to emote the given text. The example would read: \"Ted \
-                 dances around.\" for someone named Ted",
+                 dances around.\" for someone named Ted. $s, $e, $m, and $f are replaced with \
+                 your pronouns: his, he, him, and himself for Ted.",
             )
-            .with_example("me dances around. || /dances around."),
+            .with_example("me dances around. || /scratches $s head."),
         )
         .with_shortcut('/'),
     );
+    commands.push(Command::new(
+        "news",
+        parse_news,
+        Help::new(
+            "news [post <message> || delete <id>]",
+            "Shows the newest changes to the game, marking them as read. You are told at login \
+             when there is news you have not read.",
+        )
+        .with_subhelp(
+            "delete",
+            Help::new("news delete <id>", "Deletes a news entry. Immortals only."),
+        )
+        .with_subhelp(
+            "post",
+            Help::new(
+                "news post <message>",
+                "Posts a news entry for every player to read. Immortals only.",
+            )
+            .with_example("news post The docks are open again."),
+        ),
+    ));
     commands.push(Command::new(
         "north",
         |actor, _| {
@@ -398,12 +1147,25 @@ fn default_commands() -> Vec<Command> {
             .with_subhelp(
                 "flags",
                 Help::new_simple(
-                    "Flags are used to set binary properties on objects.\r\n  |white|fixed|-|: \
-                     prevents the object from being picked up.\r\n  |white|subtle|-|: prevents \
-                     the object from being listed in the rooms item list when the look command is \
-                     used. It can still be looked at, however.",
+                    "Flags are used to set binary properties on objects.\r\n  |white|bank|-|: \
+                     makes the object's room a bank, where players can deposit and withdraw coins \
+                     and items.\r\n  |white|fixed|-|: prevents the object from being picked \
+                     up.\r\n  |white|subtle|-|: prevents the object from being listed in the \
+                     rooms item list when the look command is used. It can still be looked at, \
+                     however.",
                 ),
             )
+            .with_subhelp(
+                "field",
+                Help::new(
+                    "object <id> field <key> [<value>]",
+                    "Sets a custom field on the object, overriding the prototype's value, or \
+                     removes it when no value is given. true and false are booleans, whole \
+                     numbers are integers, and anything else is a string. Quote a value to force \
+                     a string.",
+                )
+                .with_example("object 2 field weight 12"),
+            )
             .with_subhelp(
                 "info",
                 Help::new("object <id> info", "Displays information about an object.")
@@ -412,7 +1174,7 @@ fn default_commands() -> Vec<Command> {
             .with_subhelp(
                 "inherit",
                 Help::new(
-                    "object <id> inherit [name] [desc] [flags] [keywords] [scripts]",
+                    "object <id> inherit [name] [desc] [fields] [flags] [keywords] [scripts]",
                     "Resumes inheriting the specified fields from the prototype object.",
                 )
                 .with_example("object 2 inherit name scripts"),
@@ -507,7 +1269,10 @@ fn default_commands() -> Vec<Command> {
                 "flags",
                 Help::new_simple(
                     "Flags are used to set binary properties on players.\r\n  |white|immortal|-|: \
-                     grants the player access to all immortal commands.",
+                     grants the player access to all immortal commands.\r\n  |white|admin|-|: \
+                     lets an immortal snoop without another immortal's approval.\r\n  \
+                     |white|builder|-|: limits an immortal to scripting rooms and objects in \
+                     regions they own.",
                 ),
             )
             .with_subhelp(
@@ -524,6 +1289,15 @@ fn default_commands() -> Vec<Command> {
                 )
                 .with_example("player Ted init"),
             )
+            .with_subhelp(
+                "rename",
+                Help::new(
+                    "player <name> rename <new name>",
+                    "Renames a player, revoking their web tokens. If they are online, they are \
+                     disconnected and must log in with the new name.",
+                )
+                .with_example("player Ted rename Fred"),
+            )
             .with_subhelp(
                 "set",
                 Help::new(
@@ -545,6 +1319,78 @@ fn default_commands() -> Vec<Command> {
         )
         .restricted(),
     );
+    commands.push(Command::new(
+        "poll",
+        parse_poll,
+        Help::new(
+            "poll [list || <id> || create <question>: <options> || close <id>]",
+            "Lists the polls put to the players, or shows a poll's options and votes. Vote in a \
+             poll with \"vote\".",
+        )
+        .with_subhelp(
+            "close",
+            Help::new(
+                "poll close <id>",
+                "Closes a poll and announces its results to everyone online. Immortals only.",
+            ),
+        )
+        .with_subhelp(
+            "create",
+            Help::new(
+                "poll create <question>: <option>, <option>[, <option>..]",
+                "Opens a poll and announces it to everyone online. Immortals only.",
+            )
+            .with_example("poll create Which feature next?: boats, pets, housing"),
+        )
+        .with_subhelp("list", Help::new("poll list", "Lists the polls.")),
+    ));
+    commands.push(
+        Command::new(
+            "possess",
+            parse_possess,
+            Help::new(
+                "possess <keywords>",
+                "Takes control of an object in the room. Until you release it, your commands act \
+                 through the object, which may speak, emote, move, and look. What it looks at is \
+                 shown to you.",
+            )
+            .with_example("possess old beggar"),
+        )
+        .restricted(),
+    );
+    commands.push(Command::new(
+        "privacy",
+        |actor, _| Ok(Action::from(Privacy { actor })),
+        Help::new(
+            "privacy",
+            "Toggles privacy. While private, players who look at you see how you are faring but \
+             not your description.",
+        ),
+    ));
+    commands.push(
+        Command::new(
+            "profile",
+            parse_profile,
+            Help::new(
+                "profile <ticks>",
+                "Captures timings for the engine's steps, phases, and systems over the given \
+                 number of ticks. The capture is written to the server's profiles directory as a \
+                 Chrome trace, which can be opened in Perfetto or chrome://tracing.",
+            )
+            .with_example("profile 100"),
+        )
+        .restricted(),
+    );
+    commands.push(Command::new(
+        "pronouns",
+        parse_pronouns,
+        Help::new(
+            "pronouns [he || she || they || it]",
+            "Sets the pronouns others see you referred to by. Shows your pronouns when none are \
+             given. Characters start as they/them.",
+        )
+        .with_example("pronouns she"),
+    ));
     commands.push(
         Command::new(
             "prototype",
@@ -562,6 +1408,16 @@ fn default_commands() -> Vec<Command> {
                 )
                 .with_example("prototype 2 desc An adorable teddy bear. It looks well loved."),
             )
+            .with_subhelp(
+                "field",
+                Help::new(
+                    "prototype <id> field <key> [<value>]",
+                    "Sets a custom field on the prototype, or removes it when no value is given. \
+                     Objects share their prototype's fields unless they set their own. Use \"help \
+                     object field\" for how values are read.",
+                )
+                .with_example("prototype 2 field price 30"),
+            )
             .with_subhelp(
                 "info",
                 Help::new(
@@ -620,6 +1476,83 @@ fn default_commands() -> Vec<Command> {
         )
         .restricted(),
     );
+    commands.push(
+        Command::new(
+            "publish",
+            parse_publish,
+            Help::new(
+                "publish [force]",
+                "Merges the region you are staging into the live world. All staged rooms are \
+                 updated at once. Changes made to the live rooms since staging are kept, unless \
+                 you changed the same name, description, or exit, when nothing is published \
+                 unless forced.",
+            ),
+        )
+        .restricted(),
+    );
+    commands.push(Command::new(
+        "recall",
+        parse_recall,
+        Help::new(
+            "recall [<lines>]",
+            "Replays the last 20 lines of output, or the specified number of lines.",
+        )
+        .with_example("recall 50"),
+    ));
+    commands.push(
+        Command::new(
+            "region",
+            parse_region,
+            Help::new(
+                "region || region owner <region> <player> || region disown <region> || region \
+                 loaded",
+                "Shows the owners of regions. Immortals with the builder flag may only attach \
+                 and detach scripts on rooms and objects in regions they own, and cannot change \
+                 who owns regions.",
+            )
+            .with_subhelp(
+                "owner",
+                Help::new(
+                    "region owner <region> <player>",
+                    "Gives the region to an online player, replacing any previous owner.",
+                )
+                .with_example("region owner docks Shane"),
+            )
+            .with_subhelp(
+                "disown",
+                Help::new("region disown <region>", "Removes the owner of a region.")
+                    .with_example("region disown docks"),
+            )
+            .with_subhelp(
+                "loaded",
+                Help::new(
+                    "region loaded",
+                    "Lists the regions in the world when regions are loaded lazily. Regions are \
+                     loaded when players come near them and leave the world once no one has \
+                     needed them for a while.",
+                ),
+            ),
+        )
+        .restricted(),
+    );
+    commands.push(
+        Command::new(
+            "release",
+            |actor, _| Ok(Action::from(Release { actor })),
+            Help::new("release", "Releases the object you are possessing."),
+        )
+        .restricted(),
+    );
+    commands.push(Command::new(
+        "report",
+        parse_report,
+        Help::new(
+            "report <player> <reason>",
+            "Reports a player to the moderators. Recent communication you received from the \
+             player is attached to the report.",
+        )
+        .with_example("report Ted spamming the market"),
+    ));
     commands.push(
         Command::new(
             "restart",
@@ -748,13 +1681,78 @@ fn default_commands() -> Vec<Command> {
             "scripts",
             parse_script,
             Help::new(
-                "scripts <script name> <subcommand>",
-                "Attaches or detaches a script to or from an object, player, or room.",
+                "scripts <script name> <subcommand> || scripts list [tag:<tag>] [folder:<path>] \
+                 || scripts api [<event>] || scripts stats || scripts (enable||disable) <script \
+                 name> [<target>]",
+                "Lists scripts and how they have run, describes what scripts can read from the \
+                 events they run for, attaches or detaches a script to or from an object, player, \
+                 or room, or enables or disables a script.",
+            )
+            .with_subhelp(
+                "api",
+                Help::new(
+                    "scripts api [<event>]",
+                    "Describes the EVENT scripts are given. With an event, such as Say or Move, \
+                     lists the parameters scripts can read from it.",
+                )
+                .with_example("scripts api move"),
+            )
+            .with_subhelp(
+                "disable",
+                Help::new(
+                    "scripts disable <script name> [(object||prototype||player||room) <id/name>]",
+                    "Stops a script from running without detaching it. With a target, only the \
+                     script's hooks on that object, prototype, player, or room are disabled. \
+                     Disabled scripts are kept through restarts until enabled again.",
+                )
+                .with_example("scripts disable greet_player object 2"),
+            )
+            .with_subhelp(
+                "enable",
+                Help::new(
+                    "scripts enable <script name> [(object||prototype||player||room) <id/name>]",
+                    "Lets a disabled script run again. With a target, only the script's hooks on \
+                     that object, prototype, player, or room are enabled.",
+                )
+                .with_example("scripts enable greet_player"),
+            )
+            .with_subhelp(
+                "list",
+                Help::new(
+                    "scripts list [tag:<tag>] [folder:<path>]",
+                    "Lists scripts with their triggers, folders, and tags. Filtering by folder \
+                     includes scripts in its subfolders. Tags and folders are set through the web \
+                     API.",
+                )
+                .with_example("scripts list tag:quest folder:areas/town"),
+            )
+            .with_subhelp(
+                "stats",
+                Help::new(
+                    "scripts stats",
+                    "Lists how often each script has run, its average and total runtime, and how \
+                     many runs ended in an error, with the scripts taking the most time first. \
+                     Slow scripts are highlighted.",
+                )
+                .with_example("scripts stats"),
+            )
+            .with_subhelp(
+                "attach-custom",
+                Help::new(
+                    "scripts <script name> attach-custom <signal> \
+                     (object||prototype||player||room) <id/name> [priority:<n>]",
+                    "Attaches the script to the given object, prototype, player, or room as a \
+                     custom script. These are processed when any script emits the named signal \
+                     with WORLD.emit. Objects, prototypes, and rooms are indicated by their ID \
+                     and players by their name.",
+                )
+                .with_example("scripts guard_alarm attach-custom alarm object 3"),
             )
             .with_subhelp(
                 "attach-init",
                 Help::new(
-                    "scripts <script name> attach-init (object||prototype||player||room) <id/name>",
+                    "scripts <script name> attach-init (object||prototype||player||room) \
+                     <id/name> [priority:<n>]",
                     "Attaches the script to the given object, prototype, player, or room as an \
                      init script. These run once on target load and can initialize the target. \
                      Objects, prototypes, and rooms are indicated by their ID and players by \
@@ -765,7 +1763,8 @@ fn default_commands() -> Vec<Command> {
             .with_subhelp(
                 "attach-post",
                 Help::new(
-                    "scripts <script name> attach-post (object||prototype||player||room) <id/name>",
+                    "scripts <script name> attach-post (object||prototype||player||room) \
+                     <id/name> [priority:<n>]",
                     "Attaches the script to the given object, prototype, player, or room as a \
                      post-action script. These are processed after the triggering action has been \
                      executed. Objects, prototypes, and rooms are indicated by their ID and \
@@ -776,19 +1775,22 @@ fn default_commands() -> Vec<Command> {
             .with_subhelp(
                 "attach-pre",
                 Help::new(
-                    "scripts <script name> attach-pre (object||prototype||player||room) <id/name>",
+                    "scripts <script name> attach-pre (object||prototype||player||room) <id/name> \
+                     [priority:<n>]",
                     "Attaches the script to the given object, prototype, player, or room as a \
                      pre-action script. These are processed before the triggering action is \
-                     executed and can prevent the action from occurring. Objects, prototypes, and \
-                     rooms are indicated by their ID and players by their name.",
+                     executed and can prevent the action from occurring. An entity's scripts run \
+                     highest priority first, and the first to prevent the action stops the rest. \
+                     Objects, prototypes, and rooms are indicated by their ID and players by \
+                     their name.",
                 )
-                .with_example("scripts check_for_keycard pre-action room 4"),
+                .with_example("scripts check_for_keycard attach-pre room 4 priority:10"),
             )
             .with_subhelp(
                 "attach-timer",
                 Help::new(
                     "scripts <script name> attach-timer <timer name> \
-                     (object||prototype||player||room) <id/name>",
+                     (object||prototype||player||room) <id/name> [priority:<n>]",
                     "Attaches the script to the given object, prototype, player, or room as a \
                      timer script. These are processed when the named timer elapses. Timers can \
                      be initialized in init scripts. Objects, prototypes, and rooms are indicated \
@@ -809,10 +1811,46 @@ fn default_commands() -> Vec<Command> {
         )
         .restricted(),
     );
+    commands.push(
+        Command::new(
+            "season",
+            parse_season,
+            Help::new(
+                "season [list] || season <name> [info] || season <name> tag <content> || season \
+                 untag <content>",
+                "Lists seasons and the content tagged with them. Rooms, exits, spawn tables, and \
+                 scripts tagged with a season are only active while it is under way, between the \
+                 dates given in the server configuration, so holiday content can be built ahead \
+                 of time. Immortals still see and use out of season rooms and exits.",
+            )
+            .with_subhelp(
+                "tag",
+                Help::new(
+                    "season <name> tag (room <room ID>||exit <room ID> <exit>||spawn <spawn \
+                     table ID>||script <script name>)",
+                    "Tags content with a season, replacing any season it was tagged with.",
+                )
+                .with_example("season winter tag exit 12 enter grotto"),
+            )
+            .with_subhelp(
+                "untag",
+                Help::new(
+                    "season untag (room <room ID>||exit <room ID> <exit>||spawn <spawn table \
+                     ID>||script <script name>)",
+                    "Removes the season tag from content, so it is always active.",
+                )
+                .with_example("season untag spawn snowmen"),
+            ),
+        )
+        .restricted(),
+    );
     commands.push(Command::new(
         "stats",
         parse_stats,
-        Help::new("stats", "Displays your vital statistics."),
+        Help::new(
+            "stats",
+            "Displays your vital statistics and your standing with factions.",
+        ),
     ));
     commands.push(Command::new(
         "send",
@@ -823,6 +1861,102 @@ fn default_commands() -> Vec<Command> {
         )
         .with_example("send Ted Hello Ted."),
     ));
+    commands.push(Command::new(
+        "shop",
+        parse_shop,
+        Help::new(
+            "shop [list || buy <item> || haggle <item>]",
+            "Buys items from the shop you are in. Shops restock from time to time, and their rare \
+             items only stay on the shelves until the next restock.",
+        )
+        .with_subhelp(
+            "buy",
+            Help::new(
+                "shop buy <item>",
+                "Buys an item, by its number in the shop's list, with the coins you are carrying.",
+            )
+            .with_example("shop buy 2"),
+        )
+        .with_subhelp(
+            "haggle",
+            Help::new(
+                "shop haggle <item>",
+                "Haggles over the price of an item. Any discount you win comes off your next \
+                 purchase in the shop.",
+            ),
+        )
+        .with_subhelp(
+            "list",
+            Help::new(
+                "shop list",
+                "Lists the items for sale in the shop you are in.",
+            ),
+        ),
+    ));
+    commands.push(
+        Command::new(
+            "shops",
+            parse_shops,
+            Help::new(
+                "shops (list||new <id>) || shops <id> <subcommand>",
+                "Creates and modifies shops. Shops restock from a loot table every interval, \
+                 rotating out any rare or better stock left from the last restock. Prices are a \
+                 markup on a base price for each rarity, and the coins players pay leave the game.",
+            )
+            .with_subhelp(
+                "cap",
+                Help::new(
+                    "shops <id> cap <count>",
+                    "Sets the most items a shop stocks at once.",
+                ),
+            )
+            .with_subhelp("delete", Help::new("shops <id> delete", "Deletes a shop."))
+            .with_subhelp(
+                "info",
+                Help::new(
+                    "shops <id> info",
+                    "Shows a shop's settings, its stock, and the coins it has taken out of the \
+                     game.",
+                ),
+            )
+            .with_subhelp(
+                "interval",
+                Help::new(
+                    "shops <id> interval <seconds>",
+                    "Sets how often a shop restocks.",
+                ),
+            )
+            .with_subhelp(
+                "loot",
+                Help::new(
+                    "shops <id> loot (<loot table ID>||none)",
+                    "Sets the loot table a shop restocks from. Each restock rolls the table once.",
+                )
+                .with_example("shops armory loot weapons"),
+            )
+            .with_subhelp(
+                "markup",
+                Help::new(
+                    "shops <id> markup <percent>",
+                    "Sets a shop's prices as a percentage of the base price for each rarity: 10, \
+                     50, 200, 1000, and 5000 coins from common to legendary.",
+                )
+                .with_example("shops armory markup 150"),
+            )
+            .with_subhelp(
+                "new",
+                Help::new(
+                    "shops new <id>",
+                    "Opens a shop in the room you are in. Each room has at most one shop.",
+                ),
+            )
+            .with_subhelp(
+                "restock",
+                Help::new("shops <id> restock", "Restocks a shop immediately."),
+            ),
+        )
+        .restricted(),
+    );
     commands.push(
         Command::new(
             "shutdown",
@@ -831,6 +1965,49 @@ fn default_commands() -> Vec<Command> {
         )
         .restricted(),
     );
+    commands.push(
+        Command::new(
+            "snoop",
+            parse_snoop,
+            Help::new(
+                "snoop <player> || snoop approve <immortal> || snoop off",
+                "Shows you a player's commands and the output they see, for a limited time. \
+                 Password prompts are never shown. Snoops are recorded in the game log.",
+            )
+            .with_subhelp(
+                "approve",
+                Help::new(
+                    "snoop approve <immortal>",
+                    "Allows another immortal's snoop, when snoops need approval. Administrators \
+                     do not need approval.",
+                )
+                .with_example("snoop approve Shane"),
+            )
+            .with_subhelp(
+                "off",
+                Help::new(
+                    "snoop off",
+                    "Stops snooping, or withdraws a snoop awaiting approval.",
+                ),
+            )
+            .with_example("snoop Ted"),
+        )
+        .restricted(),
+    );
+    commands.push(
+        Command::new(
+            "stage",
+            parse_stage,
+            Help::new(
+                "stage <region> || stage discard",
+                "Stages the rooms in a region for editing. While staging, your changes to room \
+                 names, descriptions, and exits in the region are only visible to you until they \
+                 are published with \"publish\" or thrown away with \"stage discard\".",
+            )
+            .with_example("stage market"),
+        )
+        .restricted(),
+    );
     commands.push(Command::new(
         "south",
         |actor, _| {
@@ -841,6 +2018,75 @@ fn default_commands() -> Vec<Command> {
         },
         Help::new("south", "Moves you to the room to the south, if possible."),
     ));
+    commands.push(
+        Command::new(
+            "spawn",
+            parse_spawn,
+            Help::new(
+                "spawn (list||new <id>) || spawn <id> <subcommand>",
+                "Creates and modifies spawn tables, which keep rooms stocked with objects. Each \
+                 interval, every room a table covers that holds fewer than the table's cap of \
+                 objects from it gains one more, chosen at random by weight.",
+            )
+            .with_subhelp(
+                "add",
+                Help::new(
+                    "spawn <id> add <prototype ID> [<weight>]",
+                    "Adds a prototype to a spawn table, or changes its weight. Weights default to \
+                     1.",
+                )
+                .with_example("spawn rats add 12 3"),
+            )
+            .with_subhelp(
+                "attach",
+                Help::new(
+                    "spawn <id> attach (room <room ID>||region <region>)",
+                    "Spawns the table's objects in a room, or in every room in a region.",
+                )
+                .with_example("spawn rats attach region sewers"),
+            )
+            .with_subhelp(
+                "cap",
+                Help::new(
+                    "spawn <id> cap <count>",
+                    "Sets the most objects from the table each room may hold.",
+                ),
+            )
+            .with_subhelp(
+                "delete",
+                Help::new("spawn <id> delete", "Deletes a spawn table."),
+            )
+            .with_subhelp(
+                "detach",
+                Help::new(
+                    "spawn <id> detach (room <room ID>||region <region>)",
+                    "Stops spawning the table's objects in a room or region.",
+                ),
+            )
+            .with_subhelp(
+                "info",
+                Help::new(
+                    "spawn <id> info",
+                    "Shows a spawn table's settings and the chance of each prototype.",
+                ),
+            )
+            .with_subhelp(
+                "interval",
+                Help::new(
+                    "spawn <id> interval <seconds>",
+                    "Sets how often the table tops up its rooms.",
+                ),
+            )
+            .with_subhelp(
+                "remove",
+                Help::new(
+                    "spawn <id> remove <prototype ID>",
+                    "Removes a prototype from a spawn table.",
+                ),
+            ),
+        )
+        .restricted(),
+    );
     commands.push(
         Command::new(
             "teleport",
@@ -849,6 +2095,52 @@ fn default_commands() -> Vec<Command> {
         )
         .restricted(),
     );
+    commands.push(Command::new(
+        "top",
+        parse_top,
+        Help::new(
+            "top <category>",
+            "Shows the top players in a leaderboard category: explored or playtime.",
+        )
+        .with_example("top explored"),
+    ));
+    commands.push(
+        Command::new(
+            "tutorial",
+            parse_tutorial,
+            Help::new(
+                "tutorial [<subcommand>]",
+                "Commands for setting up the tutorial for new characters. With no subcommand, \
+                 shows where new characters begin and the hints they are shown.",
+            )
+            .with_subhelp(
+                "hint",
+                Help::new(
+                    "tutorial hint <trigger> (<message> || clear)",
+                    "Sets or clears the hint shown to a player in the tutorial the first time \
+                     they use a command. Triggers: look, move, say, get, drop, inventory.",
+                )
+                .with_example(
+                    "tutorial hint look Type 'look' again any time to see your surroundings.",
+                ),
+            )
+            .with_subhelp(
+                "start",
+                Help::new(
+                    "tutorial start (<room ID> || clear)",
+                    "Sets or clears the room new characters begin in. Without one, they begin in \
+                     the spawn room.",
+                ),
+            ),
+        )
+        .restricted(),
+    );
+    commands.push(Command::new(
+        "unignore",
+        parse_unignore,
+        Help::new("unignore <player>", "Stops ignoring the specified player.")
+            .with_example("unignore Ted"),
+    ));
     commands.push(Command::new(
         "up",
         |actor, _| {
@@ -877,10 +2169,31 @@ fn default_commands() -> Vec<Command> {
             "Uses the item indicated by the specified keyword(s), if possible.",
         ),
     ));
+    commands.push(Command::new(
+        "vote",
+        parse_vote,
+        Help::new(
+            "vote [<poll id>] <option>",
+            "Votes for an option, by its number or text, in an open poll. The poll ID can be left \
+             out when only one poll is open. Everyone gets one vote per poll.",
+        )
+        .with_example("vote 2")
+        .with_example("vote 3 boats"),
+    ));
     commands.push(Command::new(
         "who",
         |actor, _| Ok(Action::from(Who { actor })),
         Help::new("who", "Retrieves a list of online players."),
     ));
+    commands.push(Command::new(
+        "withdraw",
+        parse_withdraw,
+        Help::new(
+            "withdraw <amount> || withdraw <keyword> [<keyword>..]",
+            "Withdraws coins, or the item indicated by the specified keyword(s), from the bank. \
+             You must be in a bank.",
+        )
+        .with_example("withdraw 50"),
+    ));
     commands
 }
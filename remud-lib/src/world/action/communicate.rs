@@ -1,18 +1,120 @@
 use bevy_app::EventReader;
-use bevy_ecs::prelude::*;
+use bevy_ecs::{prelude::*, system::SystemParam};
+use itertools::Itertools;
 
 use crate::{
-    text::Tokenizer,
+    clock::Clock,
+    engine::persist::{self, Updates},
+    logging::GAME_LOG_TARGET,
+    text::{plain, Tokenizer},
     world::{
         action::{get_room_std, into_action, Action},
+        scripting::time::Cutscenes,
         types::{
-            player::{Messages, Players},
+            communication::{Channel, Communication, Communications},
+            grammar::{act, Pronouns, Subject},
+            player::{
+                self, CommunicationLog, Ignores, Messages, Player, PlayerFlags, PlayerId, Players,
+            },
+            report::Reports,
             room::Room,
             Location, Named,
         },
     },
 };
 
+// Returns the ID of the player whose communication may be ignored. Immortals cannot be ignored.
+pub(super) fn ignorable(
+    actor: Entity,
+    speaker_query: &Query<(&Player, &PlayerFlags)>,
+) -> Option<PlayerId> {
+    match speaker_query.get(actor) {
+        Ok((player, flags)) if !flags.contains(player::Flags::IMMORTAL) => Some(player.id()),
+        _ => None,
+    }
+}
+
+// Records communication from players for reports.
+pub(super) fn record_communication(
+    listener: Entity,
+    actor: Entity,
+    name: &str,
+    message: &str,
+    speaker_query: &Query<(&Player, &PlayerFlags)>,
+    log_query: &mut Query<&mut CommunicationLog>,
+) {
+    if listener == actor {
+        return;
+    }
+
+    if let (Ok((speaker, _)), Ok(mut log)) = (speaker_query.get(actor), log_query.get_mut(listener))
+    {
+        log.record(speaker.id(), name, plain(message));
+    }
+}
+
+/// Records what players say, emote, send, and say to their clans in the communication log, which
+/// immortals search when moderating. Communication from objects is not recorded.
+#[derive(SystemParam)]
+pub struct Transcript<'a> {
+    clock: Res<'a, Clock>,
+    communications: ResMut<'a, Communications>,
+    updates: ResMut<'a, Updates>,
+    players: Query<'a, &'static Player>,
+    locations: Query<'a, &'static Location>,
+    rooms: Query<'a, &'static Room>,
+}
+
+impl<'a> Transcript<'a> {
+    /// Records a player's message, along with the room they are in. The target is the recipient
+    /// of a sent message or the clan spoken to.
+    pub fn record(
+        &mut self,
+        actor: Entity,
+        channel: Channel,
+        name: &str,
+        target: Option<&str>,
+        text: &str,
+    ) {
+        let player = match self.players.get(actor) {
+            Ok(player) => player.id(),
+            Err(_) => return,
+        };
+
+        let room = self
+            .locations
+            .get(actor)
+            .ok()
+            .and_then(|location| self.rooms.get(location.entity()).ok())
+            .map(Room::id);
+
+        let communication = Communication {
+            channel,
+            player,
+            name: name.to_string(),
+            room,
+            target: target.map(ToString::to_string),
+            text: plain(text),
+            said_at: self.clock.unix_now(),
+        };
+
+        let id = self.communications.record(communication.clone());
+        self.updates
+            .persist(persist::communication::Record::new(id, communication));
+    }
+}
+
+pub(super) fn is_ignoring(
+    listener: Entity,
+    speaker: Option<PlayerId>,
+    ignores_query: &Query<&Ignores>,
+) -> bool {
+    match (speaker, ignores_query.get(listener)) {
+        (Some(speaker), Ok(ignores)) => ignores.contains(speaker),
+        _ => false,
+    }
+}
+
 pub fn parse_me(player: Entity, tokenizer: Tokenizer) -> Result<Action, String> {
     if tokenizer.rest().is_empty() {
         Err("Do what?".to_string())
@@ -32,39 +134,207 @@ pub struct Emote {
 
 into_action!(Emote);
 
-#[tracing::instrument(name = "emote system", skip_all)]
+#[tracing::instrument(name = "emote system", skip_all, fields(action = "Emote"))]
 pub fn emote_system(
     mut action_reader: EventReader<Action>,
     location_query: Query<(Option<&Location>, Option<&Room>)>,
-    named_query: Query<&Named>,
+    named_query: Query<(&Named, Option<&Pronouns>)>,
     room_query: Query<&Room>,
+    speaker_query: Query<(&Player, &PlayerFlags)>,
+    ignores_query: Query<&Ignores>,
+    mut log_query: Query<&mut CommunicationLog>,
     mut present_query: Query<&mut Messages>,
+    mut transcript: Transcript,
 ) {
     for action in action_reader.iter() {
         if let Action::Emote(Emote { actor, emote }) = action {
             let room_entity = get_room_std(*actor, &location_query);
-            let name = if let Ok(named) = named_query.get(*actor) {
-                named.as_str()
+            let (name, pronouns) = if let Ok((named, pronouns)) = named_query.get(*actor) {
+                (named.as_str(), pronouns.copied().unwrap_or(Pronouns::It))
             } else {
                 tracing::warn!("Entity {:?} cannot emote without Named.", actor);
                 continue;
             };
 
-            let message = format!("{} {}", name, emote);
+            tracing::info!(
+                target: GAME_LOG_TARGET,
+                player = name,
+                text = plain(emote).as_str(),
+                "emote"
+            );
+            transcript.record(*actor, Channel::Emote, name, None, emote);
+
+            let message = act(
+                format!("$n {}", emote).as_str(),
+                Subject::new(name, pronouns),
+                None,
+            );
+            let speaker = ignorable(*actor, &speaker_query);
 
             let room = room_query
                 .get(room_entity)
                 .expect("Location contains a valid room.");
 
             for player in room.players() {
+                if is_ignoring(*player, speaker, &ignores_query) {
+                    continue;
+                }
+
+                record_communication(
+                    *player,
+                    *actor,
+                    name,
+                    message.as_str(),
+                    &speaker_query,
+                    &mut log_query,
+                );
+
                 if let Ok(mut messages) = present_query.get_mut(*player) {
-                    messages.queue(message.clone());
+                    if *player == *actor {
+                        messages.queue(message.clone());
+                    } else {
+                        messages.queue_broadcast(message.clone());
+                    }
                 }
             }
         }
     }
 }
 
+// Valid shapes:
+// ignore - lists ignored players
+// ignore <player> - hides communication from an online player
+pub fn parse_ignore(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    Ok(Action::from(Ignore {
+        actor: player,
+        player: tokenizer.next().map(ToString::to_string),
+    }))
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Ignore {
+    pub actor: Entity,
+    pub player: Option<String>,
+}
+
+into_action!(Ignore);
+
+#[tracing::instrument(name = "ignore system", skip_all, fields(action = "Ignore"))]
+pub fn ignore_system(
+    mut action_reader: EventReader<Action>,
+    players: Res<Players>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<(&Player, &PlayerFlags, &Named)>,
+    mut ignores_query: Query<&mut Ignores>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Ignore(Ignore { actor, player }) = action {
+            let mut ignores = match ignores_query.get_mut(*actor) {
+                Ok(ignores) => ignores,
+                Err(_) => continue,
+            };
+
+            let name = match player {
+                Some(name) => name,
+                None => {
+                    let message = if ignores.names().next().is_none() {
+                        "You are not ignoring anyone.".to_string()
+                    } else {
+                        format!("You are ignoring: {}", ignores.names().sorted().join(", "))
+                    };
+
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(message);
+                    }
+                    continue;
+                }
+            };
+
+            let message = match players
+                .by_name(name.as_str())
+                .and_then(|entity| player_query.get(entity).ok().map(|p| (entity, p)))
+            {
+                None => format!("There is no one named {} online.", name),
+                Some((entity, _)) if entity == *actor => "You cannot ignore yourself.".to_string(),
+                Some((_, (_, flags, _))) if flags.contains(player::Flags::IMMORTAL) => {
+                    "Immortals cannot be ignored.".to_string()
+                }
+                Some((_, (ignored, _, named))) => {
+                    if ignores.insert(ignored.id(), named.to_string()) {
+                        if let Ok((player, _, _)) = player_query.get(*actor) {
+                            updates
+                                .persist(persist::player::Ignore::new(player.id(), ignored.id()));
+                        }
+
+                        format!(
+                            "You are now ignoring {}. Use \"unignore {}\" to hear from them again.",
+                            named.as_str(),
+                            named.as_str()
+                        )
+                    } else {
+                        format!("You are already ignoring {}.", named.as_str())
+                    }
+                }
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+// Valid shapes:
+// pronouns - shows the player's pronouns
+// pronouns <pronouns> - sets the pronouns others see the player referred to by
+pub fn parse_pronouns(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    let pronouns = match tokenizer.next() {
+        Some(pronouns) => Some(pronouns.parse::<Pronouns>().map_err(|e| e.to_string())?),
+        None => None,
+    };
+
+    Ok(Action::from(SetPronouns {
+        actor: player,
+        pronouns,
+    }))
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct SetPronouns {
+    pub actor: Entity,
+    pub pronouns: Option<Pronouns>,
+}
+
+into_action!(SetPronouns);
+
+#[tracing::instrument(name = "pronouns system", skip_all, fields(action = "SetPronouns"))]
+pub fn pronouns_system(
+    mut action_reader: EventReader<Action>,
+    mut updates: ResMut<Updates>,
+    mut player_query: Query<(&Player, &mut Pronouns, &mut Messages)>,
+) {
+    for action in action_reader.iter() {
+        if let Action::SetPronouns(SetPronouns { actor, pronouns }) = action {
+            let (player, mut current, mut messages) = match player_query.get_mut(*actor) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+
+            let message = match pronouns {
+                Some(pronouns) => {
+                    *current = *pronouns;
+                    updates.persist(persist::player::Pronouns::new(player.id(), *pronouns));
+                    format!("Others will now refer to you as {}.", pronouns)
+                }
+                None => format!("Others refer to you as {}.", *current),
+            };
+
+            messages.queue(message);
+        }
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct Message {
     pub actor: Entity,
@@ -73,7 +343,7 @@ pub struct Message {
 
 into_action!(Message);
 
-#[tracing::instrument(name = "message system", skip_all)]
+#[tracing::instrument(name = "message system", skip_all, fields(action = "Message"))]
 pub fn message_system(
     mut action_reader: EventReader<Action>,
     location_query: Query<(Option<&Location>, Option<&Room>)>,
@@ -90,9 +360,64 @@ pub fn message_system(
 
             for player in room.players() {
                 if let Ok(mut messages) = present_query.get_mut(*player) {
+                    if *player == *actor {
+                        messages.queue(message.clone());
+                    } else {
+                        messages.queue_broadcast(message.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+// A line of a cutscene, sent to the player watching it when it comes due.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct CutsceneLine {
+    pub actor: Entity,
+    pub cutscene: u64,
+    pub message: String,
+    // whether the line is followed by a prompt
+    pub prompt: bool,
+    pub last: bool,
+}
+
+into_action!(CutsceneLine);
+
+#[tracing::instrument(
+    name = "cutscene line system",
+    skip_all,
+    fields(action = "CutsceneLine")
+)]
+pub fn cutscene_line_system(
+    mut action_reader: EventReader<Action>,
+    mut cutscenes: ResMut<Cutscenes>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::CutsceneLine(CutsceneLine {
+            actor,
+            cutscene,
+            message,
+            prompt,
+            last,
+        }) = action
+        {
+            if !cutscenes.is_playing(*actor, *cutscene) {
+                continue;
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                if *prompt {
                     messages.queue(message.clone());
+                } else {
+                    messages.queue_without_prompt(message.clone());
                 }
             }
+
+            if *last {
+                cutscenes.finish(*actor, *cutscene);
+            }
         }
     }
 }
@@ -116,25 +441,43 @@ pub struct Say {
 
 into_action!(Say);
 
-#[tracing::instrument(name = "say system", skip_all)]
+#[tracing::instrument(name = "say system", skip_all, fields(action = "Say"))]
 pub fn say_system(
     mut action_reader: EventReader<Action>,
     location_query: Query<(Option<&Location>, Option<&Room>)>,
-    saying_query: Query<&Named>,
+    saying_query: Query<(&Named, Option<&Pronouns>)>,
+    speaker_query: Query<(&Player, &PlayerFlags)>,
+    ignores_query: Query<&Ignores>,
+    mut log_query: Query<&mut CommunicationLog>,
     mut present_query: Query<&mut Messages>,
     room_query: Query<&Room>,
+    mut transcript: Transcript,
 ) {
     for action in action_reader.iter() {
         if let Action::Say(Say { actor, message }) = action {
             let room_entity = get_room_std(*actor, &location_query);
-            let name = if let Ok(named) = saying_query.get(*actor) {
-                named.as_str()
+            let (name, pronouns) = if let Ok((named, pronouns)) = saying_query.get(*actor) {
+                (named.as_str(), pronouns.copied().unwrap_or(Pronouns::It))
             } else {
                 tracing::warn!("entity {:?} cannot say without Named.", actor);
                 continue;
             };
 
-            let other_message = format!("{} says \"{}\"", name, message);
+            tracing::info!(
+                target: GAME_LOG_TARGET,
+                player = name,
+                text = plain(message).as_str(),
+                "say"
+            );
+            transcript.record(*actor, Channel::Say, name, None, message);
+
+            // The message is added after the template is filled in so players cannot use tokens.
+            let other_message = format!(
+                "{} \"{}\"",
+                act("$n says", Subject::new(name, pronouns), None),
+                message
+            );
+            let speaker = ignorable(*actor, &speaker_query);
 
             let room = room_query
                 .get(room_entity)
@@ -145,8 +488,18 @@ pub fn say_system(
                     if let Ok(mut messages) = present_query.get_mut(*player) {
                         messages.queue(format!("You say \"{}\"", message));
                     }
+                } else if is_ignoring(*player, speaker, &ignores_query) {
+                    continue;
                 } else if let Ok(mut messages) = present_query.get_mut(*player) {
-                    messages.queue(other_message.clone());
+                    record_communication(
+                        *player,
+                        *actor,
+                        name,
+                        other_message.as_str(),
+                        &speaker_query,
+                        &mut log_query,
+                    );
+                    messages.queue_broadcast(other_message.clone());
                 }
             }
         }
@@ -182,17 +535,21 @@ impl From<SendMessage> for Action {
     }
 }
 
-#[tracing::instrument(name = "send message system", skip_all)]
+#[tracing::instrument(name = "send message system", skip_all, fields(action = "Send"))]
 pub fn send_message_system(
     mut action_reader: EventReader<Action>,
     players: Res<Players>,
     saying_query: Query<&Named>,
+    speaker_query: Query<(&Player, &PlayerFlags)>,
+    ignores_query: Query<&Ignores>,
+    mut log_query: Query<&mut CommunicationLog>,
     mut messages_query: Query<&mut Messages>,
+    mut transcript: Transcript,
 ) {
     for action in action_reader.iter() {
         if let Action::Send(SendMessage {
             actor,
-            recipient,
+            recipient: recipient_name,
             message,
         }) = action
         {
@@ -203,13 +560,13 @@ pub fn send_message_system(
                 continue;
             };
 
-            let recipient = if let Some(recipient) = players.by_name(recipient.as_str()) {
+            let recipient = if let Some(recipient) = players.by_name(recipient_name.as_str()) {
                 recipient
             } else {
                 if let Ok(mut messages) = messages_query.get_mut(*actor) {
                     messages.queue(format!(
                         "Your term beeps in irritation: \"User '{}' not found.\"",
-                        recipient
+                        recipient_name
                     ));
                 };
 
@@ -224,10 +581,38 @@ pub fn send_message_system(
                 continue;
             }
 
-            messages_query
-                .get_mut(recipient)
-                .expect("Recipient player has Messages.")
-                .queue(format!("{} sends \"{}\"", name, message));
+            tracing::info!(
+                target: GAME_LOG_TARGET,
+                player = name,
+                recipient = recipient_name.as_str(),
+                text = plain(message).as_str(),
+                "send"
+            );
+            transcript.record(
+                *actor,
+                Channel::Send,
+                name,
+                Some(recipient_name.as_str()),
+                message,
+            );
+
+            // Senders are not told when they are ignored.
+            if !is_ignoring(recipient, ignorable(*actor, &speaker_query), &ignores_query) {
+                let message = format!("{} sends \"{}\"", name, message);
+                record_communication(
+                    recipient,
+                    *actor,
+                    name,
+                    message.as_str(),
+                    &speaker_query,
+                    &mut log_query,
+                );
+
+                messages_query
+                    .get_mut(recipient)
+                    .expect("Recipient player has Messages.")
+                    .queue(message);
+            }
 
             if let Ok(mut messages) = messages_query.get_mut(*actor) {
                 messages.queue("Your term chirps happily: \"Message sent.\"".to_string());
@@ -245,7 +630,7 @@ pub struct Whisper {
 
 into_action!(Whisper);
 
-#[tracing::instrument(name = "whisper system", skip_all)]
+#[tracing::instrument(name = "whisper system", skip_all, fields(action = "Whisper"))]
 pub fn whisper_system(
     mut action_reader: EventReader<Action>,
     mut target_query: Query<&mut Messages>,
@@ -264,3 +649,170 @@ pub fn whisper_system(
         }
     }
 }
+
+pub fn parse_unignore(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(name) = tokenizer.next() {
+        Ok(Action::from(Unignore {
+            actor: player,
+            player: name.to_string(),
+        }))
+    } else {
+        Err("Stop ignoring whom?".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Unignore {
+    pub actor: Entity,
+    pub player: String,
+}
+
+into_action!(Unignore);
+
+#[tracing::instrument(name = "unignore system", skip_all, fields(action = "Unignore"))]
+pub fn unignore_system(
+    mut action_reader: EventReader<Action>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<&Player>,
+    mut ignores_query: Query<&mut Ignores>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Unignore(Unignore { actor, player }) = action {
+            let mut ignores = match ignores_query.get_mut(*actor) {
+                Ok(ignores) => ignores,
+                Err(_) => continue,
+            };
+
+            let message = if let Some(ignored) = ignores.remove(player.as_str()) {
+                if let Ok(player) = player_query.get(*actor) {
+                    updates.persist(persist::player::Unignore::new(player.id(), ignored));
+                }
+
+                format!("You are no longer ignoring {}.", player)
+            } else {
+                format!("You are not ignoring {}.", player)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+// Valid shapes:
+// report <player> <reason> - files a report about a player for moderators to review
+pub fn parse_report(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(target) = tokenizer.next() {
+        if tokenizer.rest().is_empty() {
+            Err(format!("Why are you reporting {}?", target))
+        } else {
+            Ok(Action::from(Report {
+                actor: player,
+                player: target.to_string(),
+                reason: tokenizer.rest().to_string(),
+            }))
+        }
+    } else {
+        Err("Report whom?".to_string())
+    }
+}
+
+// The number of recent communications from the reported player attached to a report.
+const REPORT_CONTEXT_LENGTH: usize = 20;
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Report {
+    pub actor: Entity,
+    pub player: String,
+    pub reason: String,
+}
+
+into_action!(Report);
+
+#[tracing::instrument(name = "report system", skip_all, fields(action = "Report"))]
+pub fn report_system(
+    mut action_reader: EventReader<Action>,
+    players: Res<Players>,
+    mut reports: ResMut<Reports>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<(Entity, &Player, &PlayerFlags, &Named)>,
+    log_query: Query<&CommunicationLog>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Report(Report {
+            actor,
+            player,
+            reason,
+        }) = action
+        {
+            let (reporter, reporter_name) = match player_query.get(*actor) {
+                Ok((_, reporter, _, named)) => (reporter.id(), named.to_string()),
+                Err(_) => continue,
+            };
+            let log = match log_query.get(*actor) {
+                Ok(log) => log,
+                Err(_) => continue,
+            };
+
+            // Players may be reported after they leave if they recently communicated.
+            let reported = players
+                .by_name(player.as_str())
+                .and_then(|entity| player_query.get(entity).ok())
+                .map(|(_, reported, _, _)| reported.id())
+                .or_else(|| log.speaker_by_name(player.as_str()));
+
+            let reported = match reported {
+                Some(reported) if reported == reporter => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue("You cannot report yourself.".to_string());
+                    }
+                    continue;
+                }
+                Some(reported) => reported,
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("There is no one named {} to report.", player));
+                    }
+                    continue;
+                }
+            };
+
+            let context = log
+                .by_speaker(reported, REPORT_CONTEXT_LENGTH)
+                .into_iter()
+                .map(ToString::to_string)
+                .collect_vec();
+
+            let id = reports.next_id();
+            updates.persist(persist::report::Create::new(
+                id,
+                reporter,
+                reported,
+                reason.clone(),
+                context,
+            ));
+
+            let notice = format!(
+                "Report #{}: {} reported {}: {}",
+                id, reporter_name, player, reason
+            );
+            for (moderator, _, flags, _) in player_query.iter() {
+                if moderator != *actor && flags.contains(player::Flags::IMMORTAL) {
+                    if let Ok(mut messages) = messages_query.get_mut(moderator) {
+                        messages.queue(notice.clone());
+                    }
+                }
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!(
+                    "Your report about {} has been filed as #{}. Thank you.",
+                    player, id
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+
+use crate::{
+    engine::persist::{self, Updates},
+    text::Tokenizer,
+    world::{
+        action::{into_action, Action},
+        types::{
+            player::{self, Messages, Player, PlayerFlags, VisitedRooms},
+            room::{Direction, Room},
+            tutorial::{HintQueue, SeenHints, Tutorial},
+            Location,
+        },
+    },
+};
+
+// The number of rooms shown in each direction from the player on the map.
+const MAP_RADIUS: i32 = 3;
+
+const MAP_DIRECTIONS: [(Direction, (i32, i32)); 4] = [
+    (Direction::North, (0, -1)),
+    (Direction::East, (1, 0)),
+    (Direction::South, (0, 1)),
+    (Direction::West, (-1, 0)),
+];
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Brief {
+    pub actor: Entity,
+}
+
+into_action!(Brief);
+
+#[tracing::instrument(name = "brief system", skip_all, fields(action = "Brief"))]
+pub fn brief_system(
+    mut action_reader: EventReader<Action>,
+    mut updates: ResMut<Updates>,
+    mut player_query: Query<(&Player, &mut PlayerFlags)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Brief(Brief { actor }) = action {
+            let (player, mut flags) = match player_query.get_mut(*actor) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+
+            let message = if flags.contains(player::Flags::BRIEF) {
+                flags.remove(player::Flags::BRIEF);
+                "Brief mode off. You will see the description of every room you enter."
+            } else {
+                flags.insert(player::Flags::BRIEF);
+                "Brief mode on. You will not see the descriptions of rooms you have visited when \
+                 you enter them."
+            };
+
+            updates.persist(persist::player::Flags::new(player.id(), flags.get_flags()));
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message.to_string());
+            }
+        }
+    }
+}
+
+pub fn parse_hints(actor: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    let enabled = match tokenizer.next() {
+        None => None,
+        Some("on") => Some(true),
+        Some("off") => Some(false),
+        Some(_) => return Err("Enter on or off.".to_string()),
+    };
+
+    Ok(Action::from(Hints { actor, enabled }))
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Hints {
+    pub actor: Entity,
+    pub enabled: Option<bool>,
+}
+
+into_action!(Hints);
+
+#[tracing::instrument(name = "hints system", skip_all, fields(action = "Hints"))]
+pub fn hints_system(
+    mut action_reader: EventReader<Action>,
+    mut updates: ResMut<Updates>,
+    mut player_query: Query<(&Player, &mut PlayerFlags)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Hints(Hints { actor, enabled }) = action {
+            let (player, mut flags) = match player_query.get_mut(*actor) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+
+            let message = match enabled {
+                Some(true) => {
+                    flags.insert(player::Flags::TUTORIAL);
+                    "Hints on. You will be shown a hint the first time you use some commands."
+                }
+                Some(false) => {
+                    flags.remove(player::Flags::TUTORIAL);
+                    "Hints off."
+                }
+                None => {
+                    if flags.contains(player::Flags::TUTORIAL) {
+                        "Hints are on."
+                    } else {
+                        "Hints are off."
+                    }
+                }
+            };
+
+            if enabled.is_some() {
+                updates.persist(persist::player::Flags::new(player.id(), flags.get_flags()));
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message.to_string());
+            }
+        }
+    }
+}
+
+// Shows players in the tutorial the hint for a command the first time they use it.
+#[tracing::instrument(name = "show hints system", skip_all)]
+pub fn show_hints_system(
+    tutorial: Res<Tutorial>,
+    mut queue: ResMut<HintQueue>,
+    mut updates: ResMut<Updates>,
+    mut player_query: Query<(&Player, &PlayerFlags, &mut SeenHints, &mut Messages)>,
+) {
+    for (actor, trigger) in queue.take() {
+        let hint = match tutorial.hint(trigger) {
+            Some(hint) => hint,
+            None => continue,
+        };
+
+        let (player, flags, mut seen, mut messages) = match player_query.get_mut(actor) {
+            Ok(result) => result,
+            Err(_) => continue,
+        };
+
+        if !flags.contains(player::Flags::TUTORIAL) || !seen.insert(trigger) {
+            continue;
+        }
+
+        updates.persist(persist::tutorial::SeeHint::new(player.id(), trigger));
+        messages.queue(format!("|SteelBlue3|Hint:|-| {}", hint));
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Map {
+    pub actor: Entity,
+}
+
+into_action!(Map);
+
+#[tracing::instrument(name = "map system", skip_all, fields(action = "Map"))]
+pub fn map_system(
+    mut action_reader: EventReader<Action>,
+    player_query: Query<(&Location, &VisitedRooms)>,
+    room_query: Query<&Room>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Map(Map { actor }) = action {
+            let (location, visited) = match player_query.get(*actor) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+
+            let map = render_map(location.entity(), visited, &room_query);
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!(
+                    "{}\r\n|white|@|-| you  |white|#|-| visited  |white|?|-| unexplored",
+                    map
+                ));
+            }
+        }
+    }
+}
+
+// Lays rooms out on a grid by following exits outward from the player's room. Only visited rooms
+// are explored further, so unvisited rooms appear at the edge of the map as unexplored and
+// anything beyond them is hidden.
+fn render_map(origin: Entity, visited: &VisitedRooms, room_query: &Query<&Room>) -> String {
+    let is_visited = |entity: Entity| {
+        entity == origin
+            || room_query
+                .get(entity)
+                .map(|room| visited.contains(&room.id()))
+                .unwrap_or(false)
+    };
+
+    let mut positions = HashMap::new();
+    let mut placed = HashSet::new();
+    let mut connections = HashSet::new();
+    let mut queue = VecDeque::new();
+
+    positions.insert((0, 0), origin);
+    placed.insert(origin);
+    queue.push_back((origin, (0, 0)));
+
+    while let Some((entity, (x, y))) = queue.pop_front() {
+        let room = match room_query.get(entity) {
+            Ok(room) => room,
+            Err(_) => continue,
+        };
+
+        for (direction, (dx, dy)) in MAP_DIRECTIONS.iter() {
+            let destination = match room.exit(direction) {
+                Some(destination) => destination,
+                None => continue,
+            };
+
+            let position = (x + dx, y + dy);
+            if position.0.abs() > MAP_RADIUS || position.1.abs() > MAP_RADIUS {
+                continue;
+            }
+
+            match positions.get(&position) {
+                Some(existing) if *existing == destination => {
+                    connections.insert((2 * x + dx, 2 * y + dy));
+                }
+                Some(_) => (),
+                None => {
+                    if placed.insert(destination) {
+                        positions.insert(position, destination);
+                        connections.insert((2 * x + dx, 2 * y + dy));
+                        if is_visited(destination) {
+                            queue.push_back((destination, position));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let (min_x, max_x) = positions
+        .keys()
+        .map(|(x, _)| *x)
+        .minmax()
+        .into_option()
+        .unwrap_or_default();
+    let (min_y, max_y) = positions
+        .keys()
+        .map(|(_, y)| *y)
+        .minmax()
+        .into_option()
+        .unwrap_or_default();
+
+    (2 * min_y..=2 * max_y)
+        .map(|row| {
+            (2 * min_x..=2 * max_x)
+                .map(|column| {
+                    if row % 2 == 0 && column % 2 == 0 {
+                        match positions.get(&(column / 2, row / 2)) {
+                            Some(entity) if *entity == origin => "|white|@|-|",
+                            Some(entity) if is_visited(*entity) => "#",
+                            Some(_) => "?",
+                            None => " ",
+                        }
+                    } else if connections.contains(&(column, row)) {
+                        if row % 2 == 0 {
+                            "-"
+                        } else {
+                            "||"
+                        }
+                    } else {
+                        " "
+                    }
+                })
+                .join("")
+                .trim_end()
+                .to_string()
+        })
+        .join("\r\n")
+}
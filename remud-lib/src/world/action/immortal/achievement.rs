@@ -0,0 +1,224 @@
+use std::str::FromStr;
+
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+
+use crate::{
+    engine::persist::{self, Updates},
+    text::Tokenizer,
+    world::{
+        action::{into_action, Action},
+        types::{
+            achievement::{
+                Achievement, AchievementId, Achievements, DEFAULT_ACHIEVEMENT_DESCRIPTION,
+            },
+            player::Messages,
+        },
+    },
+};
+
+pub fn parse_achievement(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(token) = tokenizer.next() {
+        match token {
+            "list" => Ok(Action::from(AchievementList { actor: player })),
+            "new" => {
+                let id = match tokenizer.next() {
+                    Some(id) => AchievementId::from_str(id).map_err(|e| e.to_string())?,
+                    None => return Err("Enter an ID for the new achievement.".to_string()),
+                };
+
+                if tokenizer.rest().is_empty() {
+                    Err("Enter a name for the new achievement.".to_string())
+                } else {
+                    Ok(Action::from(AchievementCreate {
+                        actor: player,
+                        id,
+                        name: tokenizer.rest().to_string(),
+                    }))
+                }
+            }
+            maybe_id => {
+                let id = AchievementId::from_str(maybe_id).map_err(|e| e.to_string())?;
+
+                if let Some(token) = tokenizer.next() {
+                    match token {
+                        "desc" => {
+                            if tokenizer.rest().is_empty() {
+                                Err("Enter a description.".to_string())
+                            } else {
+                                Ok(Action::from(AchievementUpdate {
+                                    actor: player,
+                                    id,
+                                    name: None,
+                                    description: Some(tokenizer.rest().to_string()),
+                                }))
+                            }
+                        }
+                        "name" => {
+                            if tokenizer.rest().is_empty() {
+                                Err("Enter a name.".to_string())
+                            } else {
+                                Ok(Action::from(AchievementUpdate {
+                                    actor: player,
+                                    id,
+                                    name: Some(tokenizer.rest().to_string()),
+                                    description: None,
+                                }))
+                            }
+                        }
+                        _ => Err("Enter a valid achievement subcommand: desc or name.".to_string()),
+                    }
+                } else {
+                    Err("Enter an achievement subcommand: desc or name.".to_string())
+                }
+            }
+        }
+    } else {
+        Err("Enter an achievement ID or subcommand: list or new.".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct AchievementCreate {
+    pub actor: Entity,
+    pub id: AchievementId,
+    pub name: String,
+}
+
+into_action!(AchievementCreate);
+
+#[tracing::instrument(
+    name = "achievement create system",
+    skip_all,
+    fields(action = "AchievementCreate")
+)]
+pub fn achievement_create_system(
+    mut action_reader: EventReader<Action>,
+    mut achievements: ResMut<Achievements>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::AchievementCreate(AchievementCreate { actor, id, name }) = action {
+            if achievements.contains(id) {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(format!("Achievement {} already exists.", id));
+                }
+                continue;
+            }
+
+            let description = DEFAULT_ACHIEVEMENT_DESCRIPTION.to_string();
+
+            updates.persist(persist::achievement::Create::new(
+                id.clone(),
+                name.clone(),
+                description.clone(),
+            ));
+
+            achievements.insert(
+                id.clone(),
+                Achievement::new(name.clone(), description, false),
+            );
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!("Created achievement {}.", id));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct AchievementList {
+    pub actor: Entity,
+}
+
+into_action!(AchievementList);
+
+#[tracing::instrument(
+    name = "achievement list system",
+    skip_all,
+    fields(action = "AchievementList")
+)]
+pub fn achievement_list_system(
+    mut action_reader: EventReader<Action>,
+    achievements: Res<Achievements>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::AchievementList(AchievementList { actor }) = action {
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                if achievements.is_empty() {
+                    messages.queue("There are no achievements.".to_string());
+                }
+
+                for (id, achievement) in achievements.iter() {
+                    messages.queue(format!(
+                        "|white|{}|-|\t{}: {}",
+                        id,
+                        achievement.name.replace("|", "||"),
+                        achievement.description.replace("|", "||")
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct AchievementUpdate {
+    pub actor: Entity,
+    pub id: AchievementId,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+into_action!(AchievementUpdate);
+
+#[tracing::instrument(
+    name = "achievement update system",
+    skip_all,
+    fields(action = "AchievementUpdate")
+)]
+pub fn achievement_update_system(
+    mut action_reader: EventReader<Action>,
+    mut achievements: ResMut<Achievements>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::AchievementUpdate(AchievementUpdate {
+            actor,
+            id,
+            name,
+            description,
+        }) = action
+        {
+            let achievement = match achievements.get_mut(id) {
+                Some(achievement) => achievement,
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Achievement {} not found.", id));
+                    }
+                    continue;
+                }
+            };
+
+            if let Some(name) = name {
+                achievement.name = name.clone();
+            }
+            if let Some(description) = description {
+                achievement.description = description.clone();
+            }
+
+            updates.persist(persist::achievement::Update::new(
+                id.clone(),
+                achievement.name.clone(),
+                achievement.description.clone(),
+            ));
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!("Updated achievement {}.", id));
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+
+use crate::{
+    engine::{
+        name_valid,
+        persist::{self, Updates},
+    },
+    text::Tokenizer,
+    world::{
+        action::{into_action, Action},
+        types::player::{Allowlist, Messages},
+    },
+};
+
+pub fn parse_allowlist(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(token) = tokenizer.next() {
+        let add = match token {
+            "add" => true,
+            "remove" => false,
+            _ => return Err("Enter a valid allowlist subcommand: add or remove.".to_string()),
+        };
+
+        let name = match tokenizer.next() {
+            Some(name) if name_valid(name) => name.to_string(),
+            Some(_) => return Err("Enter a valid player name.".to_string()),
+            None => return Err("Enter a player name.".to_string()),
+        };
+
+        if add {
+            Ok(Action::from(AllowlistAdd {
+                actor: player,
+                name,
+            }))
+        } else {
+            Ok(Action::from(AllowlistRemove {
+                actor: player,
+                name,
+            }))
+        }
+    } else {
+        Ok(Action::from(AllowlistShow { actor: player }))
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct AllowlistAdd {
+    pub actor: Entity,
+    pub name: String,
+}
+
+into_action!(AllowlistAdd);
+
+#[tracing::instrument(
+    name = "allowlist add system",
+    skip_all,
+    fields(action = "AllowlistAdd")
+)]
+pub fn allowlist_add_system(
+    mut action_reader: EventReader<Action>,
+    mut allowlist: ResMut<Allowlist>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::AllowlistAdd(AllowlistAdd { actor, name }) = action {
+            let message = if allowlist.insert(name) {
+                updates.persist(persist::allowlist::Add::new(name.clone()));
+                format!("Added {} to the allowlist.", name)
+            } else {
+                format!("{} is already on the allowlist.", name)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct AllowlistRemove {
+    pub actor: Entity,
+    pub name: String,
+}
+
+into_action!(AllowlistRemove);
+
+#[tracing::instrument(
+    name = "allowlist remove system",
+    skip_all,
+    fields(action = "AllowlistRemove")
+)]
+pub fn allowlist_remove_system(
+    mut action_reader: EventReader<Action>,
+    mut allowlist: ResMut<Allowlist>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::AllowlistRemove(AllowlistRemove { actor, name }) = action {
+            let message = if allowlist.remove(name) {
+                updates.persist(persist::allowlist::Remove::new(name.clone()));
+                format!("Removed {} from the allowlist.", name)
+            } else {
+                format!("{} is not on the allowlist.", name)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct AllowlistShow {
+    pub actor: Entity,
+}
+
+into_action!(AllowlistShow);
+
+#[tracing::instrument(
+    name = "allowlist show system",
+    skip_all,
+    fields(action = "AllowlistShow")
+)]
+pub fn allowlist_show_system(
+    mut action_reader: EventReader<Action>,
+    allowlist: Res<Allowlist>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::AllowlistShow(AllowlistShow { actor }) = action {
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                if allowlist.is_empty() {
+                    messages.queue("The allowlist is empty.".to_string());
+                } else {
+                    messages.queue("|white|Allowlist|-|".to_string());
+                    for name in allowlist.iter() {
+                        messages.queue(format!("  {}", name));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+
+use crate::{
+    engine::persist::Updates,
+    text::Tokenizer,
+    world::{
+        action::{bank::Treasury, into_action, Action},
+        types::{
+            bank::{format_coins, Bank, Vault, VAULT_CAPACITY},
+            economy::CoinSource,
+            object::Object,
+            player::{Messages, Player, Players},
+            Named,
+        },
+    },
+};
+
+// Valid shapes:
+// bank audit - lists every account and the coins held in total
+// bank <player> - shows a player's account
+// bank <player> grant <amount> - gives or takes coins carried by a player
+pub fn parse_bank(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(token) = tokenizer.next() {
+        if token == "audit" {
+            return Ok(Action::from(BankAudit { actor: player }));
+        }
+
+        let name = token.to_string();
+
+        match tokenizer.next() {
+            None => Ok(Action::from(BankInfo {
+                actor: player,
+                name,
+            })),
+            Some("grant") => match tokenizer.next().map(str::parse::<i64>) {
+                Some(Ok(amount)) => Ok(Action::from(BankGrant {
+                    actor: player,
+                    name,
+                    amount,
+                })),
+                Some(Err(_)) => Err("The amount must be a whole number.".to_string()),
+                None => Err("Enter an amount of coins to grant.".to_string()),
+            },
+            Some(_) => Err("Enter a valid bank subcommand: grant.".to_string()),
+        }
+    } else {
+        Err("Enter a bank subcommand: audit or a player name.".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct BankAudit {
+    pub actor: Entity,
+}
+
+into_action!(BankAudit);
+
+#[tracing::instrument(name = "bank audit system", skip_all, fields(action = "BankAudit"))]
+pub fn bank_audit_system(
+    mut action_reader: EventReader<Action>,
+    bank: Res<Bank>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::BankAudit(BankAudit { actor }) = action {
+            let accounts = bank
+                .iter()
+                .map(|(_, account)| account)
+                .filter(|account| account.coins != 0 || account.balance != 0)
+                .sorted_by(|a, b| a.name.cmp(&b.name))
+                .collect_vec();
+
+            let message = if accounts.is_empty() {
+                "There are no bank accounts.".to_string()
+            } else {
+                let mut message = "|white|Bank accounts|-|".to_string();
+
+                for account in accounts.iter() {
+                    message.push_str(
+                        format!(
+                            "\r\n  |white|{}|-|: {} carried, {} banked",
+                            account.name,
+                            format_coins(account.coins),
+                            format_coins(account.balance)
+                        )
+                        .as_str(),
+                    );
+                }
+
+                let coins = accounts.iter().map(|account| account.coins).sum::<i64>();
+                let balance = accounts.iter().map(|account| account.balance).sum::<i64>();
+
+                message.push_str(
+                    format!(
+                        "\r\n|white|Total|-|: {} carried, {} banked",
+                        format_coins(coins),
+                        format_coins(balance)
+                    )
+                    .as_str(),
+                );
+
+                message
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct BankGrant {
+    pub actor: Entity,
+    pub name: String,
+    pub amount: i64,
+}
+
+into_action!(BankGrant);
+
+#[tracing::instrument(name = "bank grant system", skip_all, fields(action = "BankGrant"))]
+pub fn bank_grant_system(
+    mut action_reader: EventReader<Action>,
+    mut treasury: Treasury,
+    mut updates: ResMut<Updates>,
+    players: Res<Players>,
+    player_query: Query<&Player>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::BankGrant(BankGrant {
+            actor,
+            name,
+            amount,
+        }) = action
+        {
+            let id = players
+                .by_name(name)
+                .and_then(|player| player_query.get(player).ok())
+                .map(|player| player.id())
+                .or_else(|| treasury.bank().by_name(name));
+
+            let message = if let Some(id) = id {
+                match treasury.add_coins(id, name, CoinSource::Grant, *amount) {
+                    Ok(update) => {
+                        updates.persist(update);
+
+                        let coins = treasury.bank().get(id).map_or(0, |account| account.coins);
+                        format!("{} is now carrying {}.", name, format_coins(coins))
+                    }
+                    Err(coins) => format!("{} is only carrying {}.", name, format_coins(coins)),
+                }
+            } else {
+                format!("Player '{}' not found.", name)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct BankInfo {
+    pub actor: Entity,
+    pub name: String,
+}
+
+into_action!(BankInfo);
+
+#[tracing::instrument(name = "bank info system", skip_all, fields(action = "BankInfo"))]
+pub fn bank_info_system(
+    mut action_reader: EventReader<Action>,
+    bank: Res<Bank>,
+    players: Res<Players>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<(&Player, &Vault)>,
+    object_query: Query<(&Object, &Named)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::BankInfo(BankInfo { actor, name }) = action {
+            let online = players.by_name(name).and_then(|entity| {
+                player_query
+                    .get(entity)
+                    .ok()
+                    .map(|(player, vault)| (entity, player, vault))
+            });
+
+            let id = online
+                .map(|(_, player, _)| player.id())
+                .or_else(|| bank.by_name(name));
+
+            let message = if let Some(id) = id {
+                let (coins, balance) = bank
+                    .get(id)
+                    .map(|account| (account.coins, account.balance))
+                    .unwrap_or_default();
+
+                let mut message = format!("|white|Bank account of {}|-|", name);
+
+                message.push_str("\r\n  |white|carried|-|: ");
+                message.push_str(format_coins(coins).as_str());
+
+                message.push_str("\r\n  |white|balance|-|: ");
+                message.push_str(format_coins(balance).as_str());
+
+                // Vaults are only loaded while their owner is online.
+                if let Some((entity, _, vault)) = online {
+                    if !vault.is_loaded() {
+                        updates.load_vault(entity, action.clone());
+                        continue;
+                    }
+
+                    message.push_str(
+                        format!(
+                            "\r\n  |white|vault|-|: {}/{}",
+                            vault.objects().len(),
+                            VAULT_CAPACITY
+                        )
+                        .as_str(),
+                    );
+                    vault
+                        .objects()
+                        .iter()
+                        .filter_map(|object| object_query.get(*object).ok())
+                        .for_each(|(object, named)| {
+                            message.push_str(
+                                format!("\r\n    object {}: {}", object.id(), named).as_str(),
+                            )
+                        });
+                }
+
+                message
+            } else {
+                format!("Player '{}' not found.", name)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
@@ -0,0 +1,661 @@
+use std::str::FromStr;
+
+use bevy_app::{EventReader, EventWriter};
+use bevy_ecs::prelude::*;
+
+use crate::{
+    engine::persist::{self, UpdateGroup, Updates},
+    text::Tokenizer,
+    world::{
+        action::{into_action, Action},
+        scripting::{RunInitScript, ScriptHooks, ScriptTrigger},
+        types::{
+            campaign::{Campaign, CampaignId, CampaignSpawn, CampaignStage, Campaigns},
+            object::{
+                Keywords, Object, ObjectBundle, ObjectFlags, Objects, PrototypeId, Prototypes,
+            },
+            player::{Messages, Player},
+            room::{Room, RoomId, Rooms},
+            Contents, Description, Id, Location, Named,
+        },
+    },
+};
+
+pub fn parse_event(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(token) = tokenizer.next() {
+        match token {
+            "list" => Ok(Action::from(CampaignList { actor: player })),
+            "new" => {
+                let id = match tokenizer.next() {
+                    Some(id) => CampaignId::from_str(id).map_err(|e| e.to_string())?,
+                    None => return Err("Enter an ID for the new event.".to_string()),
+                };
+
+                if tokenizer.rest().is_empty() {
+                    Err("Enter a name for the new event.".to_string())
+                } else {
+                    Ok(Action::from(CampaignCreate {
+                        actor: player,
+                        id,
+                        name: tokenizer.rest().to_string(),
+                    }))
+                }
+            }
+            maybe_id => {
+                let id = CampaignId::from_str(maybe_id).map_err(|e| e.to_string())?;
+
+                let change =
+                    match tokenizer.next() {
+                        Some("advance") => {
+                            return Ok(Action::from(CampaignProgress {
+                                actor: player,
+                                id,
+                                step: CampaignStep::Advance,
+                            }))
+                        }
+                        Some("delete") => {
+                            return Ok(Action::from(CampaignRemove { actor: player, id }))
+                        }
+                        Some("info") | None => {
+                            return Ok(Action::from(CampaignInfo { actor: player, id }))
+                        }
+                        Some("name") => {
+                            if tokenizer.rest().is_empty() {
+                                return Err("Enter a name.".to_string());
+                            }
+                            CampaignChange::Name(tokenizer.rest().to_string())
+                        }
+                        Some("stage") => parse_stage(tokenizer)?,
+                        Some("start") => {
+                            return Ok(Action::from(CampaignProgress {
+                                actor: player,
+                                id,
+                                step: CampaignStep::Start,
+                            }))
+                        }
+                        Some("stop") => {
+                            return Ok(Action::from(CampaignProgress {
+                                actor: player,
+                                id,
+                                step: CampaignStep::Stop,
+                            }))
+                        }
+                        Some(_) => {
+                            return Err("Enter a valid event subcommand: advance, delete, info, \
+                                        name, stage, start, or stop."
+                                .to_string())
+                        }
+                    };
+
+                Ok(Action::from(CampaignUpdate {
+                    actor: player,
+                    id,
+                    change,
+                }))
+            }
+        }
+    } else {
+        Err("Enter an event ID or subcommand: list or new.".to_string())
+    }
+}
+
+// Parses "new <announcement>" or "<stage> (announce <text>|goal <text>|spawn <prototype ID>
+// <room ID>|remove)" into a change to an event's stages.
+fn parse_stage(mut tokenizer: Tokenizer) -> Result<CampaignChange, String> {
+    let stage = match tokenizer.next() {
+        Some("new") => {
+            return if tokenizer.rest().is_empty() {
+                Err("Enter an announcement for the new stage.".to_string())
+            } else {
+                Ok(CampaignChange::AddStage(tokenizer.rest().to_string()))
+            }
+        }
+        Some(stage) => match stage.parse::<usize>() {
+            Ok(stage) if stage > 0 => stage,
+            _ => return Err("Enter the number of a stage.".to_string()),
+        },
+        None => return Err("Enter new or the number of a stage.".to_string()),
+    };
+
+    match tokenizer.next() {
+        Some("announce") => {
+            if tokenizer.rest().is_empty() {
+                Err("Enter an announcement.".to_string())
+            } else {
+                Ok(CampaignChange::Announcement(
+                    stage,
+                    tokenizer.rest().to_string(),
+                ))
+            }
+        }
+        Some("goal") => {
+            if tokenizer.rest().is_empty() {
+                Err("Enter a goal.".to_string())
+            } else {
+                Ok(CampaignChange::Goal(stage, tokenizer.rest().to_string()))
+            }
+        }
+        Some("remove") => Ok(CampaignChange::RemoveStage(stage)),
+        Some("spawn") => {
+            let prototype = match tokenizer.next() {
+                Some(id) => PrototypeId::from_str(id).map_err(|e| e.to_string())?,
+                None => return Err("Enter a prototype ID.".to_string()),
+            };
+            let room = match tokenizer.next() {
+                Some(id) => RoomId::from_str(id).map_err(|e| e.to_string())?,
+                None => return Err("Enter a room ID.".to_string()),
+            };
+            Ok(CampaignChange::Spawn(
+                stage,
+                CampaignSpawn { prototype, room },
+            ))
+        }
+        Some(_) => {
+            Err("Enter a valid stage subcommand: announce, goal, remove, or spawn.".to_string())
+        }
+        None => Err("Enter a stage subcommand: announce, goal, remove, or spawn.".to_string()),
+    }
+}
+
+fn format_status(campaign: &Campaign) -> String {
+    match campaign.stage() {
+        Some(stage) => format!("running, stage {} of {}", stage + 1, campaign.stages.len()),
+        None => "stopped".to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum CampaignChange {
+    AddStage(String),
+    // stages are numbered from 1, as shown by info
+    Announcement(usize, String),
+    Goal(usize, String),
+    Name(String),
+    RemoveStage(usize),
+    Spawn(usize, CampaignSpawn),
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum CampaignStep {
+    Start,
+    Advance,
+    Stop,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct CampaignCreate {
+    pub actor: Entity,
+    pub id: CampaignId,
+    pub name: String,
+}
+
+into_action!(CampaignCreate);
+
+#[tracing::instrument(
+    name = "campaign create system",
+    skip_all,
+    fields(action = "CampaignCreate")
+)]
+pub fn campaign_create_system(
+    mut action_reader: EventReader<Action>,
+    mut campaigns: ResMut<Campaigns>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::CampaignCreate(CampaignCreate { actor, id, name }) = action {
+            if campaigns.contains(id) {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(format!("Event {} already exists.", id));
+                }
+                continue;
+            }
+
+            let campaign = Campaign::new(name.clone());
+
+            updates.persist(persist::campaign::Update::new(id.clone(), &campaign));
+
+            campaigns.insert(id.clone(), campaign);
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!("Created event {}.", id));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct CampaignInfo {
+    pub actor: Entity,
+    pub id: CampaignId,
+}
+
+into_action!(CampaignInfo);
+
+#[tracing::instrument(
+    name = "campaign info system",
+    skip_all,
+    fields(action = "CampaignInfo")
+)]
+pub fn campaign_info_system(
+    mut action_reader: EventReader<Action>,
+    campaigns: Res<Campaigns>,
+    prototypes: Res<Prototypes>,
+    named_query: Query<&Named>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::CampaignInfo(CampaignInfo { actor, id }) = action {
+            let campaign = match campaigns.get(id) {
+                Some(campaign) => campaign,
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Event {} not found.", id));
+                    }
+                    continue;
+                }
+            };
+
+            let mut message = format!("|white|Event {}|-|", id);
+            message.push_str(
+                format!("\r\n  |white|name|-|: {}", campaign.name.replace("|", "||")).as_str(),
+            );
+            message
+                .push_str(format!("\r\n  |white|status|-|: {}", format_status(campaign)).as_str());
+
+            message.push_str("\r\n  |white|stages|-|:");
+            if campaign.stages.is_empty() {
+                message.push_str(" none");
+            }
+            for (index, stage) in campaign.stages.iter().enumerate() {
+                message.push_str(
+                    format!(
+                        "\r\n    {}. {}",
+                        index + 1,
+                        stage.announcement.replace("|", "||")
+                    )
+                    .as_str(),
+                );
+                if let Some(goal) = &stage.goal {
+                    message
+                        .push_str(format!("\r\n       goal: {}", goal.replace("|", "||")).as_str());
+                }
+                for spawn in stage.spawns.iter() {
+                    let name = prototypes
+                        .by_id(spawn.prototype)
+                        .and_then(|entity| named_query.get(entity).ok())
+                        .map(|named| named.escaped())
+                        .unwrap_or_else(|| format!("(missing prototype {})", spawn.prototype));
+                    message.push_str(
+                        format!("\r\n       spawn: {} in room {}", name, spawn.room).as_str(),
+                    );
+                }
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct CampaignList {
+    pub actor: Entity,
+}
+
+into_action!(CampaignList);
+
+#[tracing::instrument(
+    name = "campaign list system",
+    skip_all,
+    fields(action = "CampaignList")
+)]
+pub fn campaign_list_system(
+    mut action_reader: EventReader<Action>,
+    campaigns: Res<Campaigns>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::CampaignList(CampaignList { actor }) = action {
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                if campaigns.is_empty() {
+                    messages.queue("There are no events.".to_string());
+                }
+
+                for (id, campaign) in campaigns.iter() {
+                    messages.queue(format!(
+                        "|white|{}|-|\t{}: {}",
+                        id,
+                        campaign.name.replace("|", "||"),
+                        format_status(campaign)
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct CampaignProgress {
+    pub actor: Entity,
+    pub id: CampaignId,
+    pub step: CampaignStep,
+}
+
+into_action!(CampaignProgress);
+
+// Starts, advances, and stops campaigns. Each stage that begins is announced to everyone online
+// and creates its spawns.
+#[tracing::instrument(
+    name = "campaign progress system",
+    skip_all,
+    fields(action = "CampaignProgress")
+)]
+pub fn campaign_progress_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    mut init_writer: EventWriter<RunInitScript>,
+    mut campaigns: ResMut<Campaigns>,
+    prototypes: Res<Prototypes>,
+    rooms: Res<Rooms>,
+    mut objects: ResMut<Objects>,
+    mut updates: ResMut<Updates>,
+    prototypes_query: Query<(
+        &Named,
+        &Description,
+        &ObjectFlags,
+        &Keywords,
+        Option<&ScriptHooks>,
+    )>,
+    player_query: Query<Entity, With<Player>>,
+    mut room_query: Query<(&Room, &mut Contents)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::CampaignProgress(CampaignProgress { actor, id, step }) = action {
+            let campaign = match campaigns.get_mut(id) {
+                Some(campaign) => campaign,
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Event {} not found.", id));
+                    }
+                    continue;
+                }
+            };
+
+            // The rooms the next stage spawns into are loaded before it begins.
+            let next = match step {
+                CampaignStep::Start if !campaign.is_running() => campaign.stages.first(),
+                CampaignStep::Advance => campaign
+                    .stage()
+                    .and_then(|stage| campaign.stages.get(stage + 1)),
+                _ => None,
+            };
+            if let Some(room) = next
+                .into_iter()
+                .flat_map(|stage| stage.spawns.iter())
+                .map(|spawn| spawn.room)
+                .find(|room| rooms.is_unloaded(*room))
+            {
+                updates.load_room(room, action.clone());
+                continue;
+            }
+
+            let result = match step {
+                CampaignStep::Start if campaign.is_running() => {
+                    Err(format!("Event {} is already running.", id))
+                }
+                CampaignStep::Start => match campaign.start().cloned() {
+                    Some(stage) => Ok((Some(stage), format!("Started event {}.", id))),
+                    None => Err(format!("Event {} has no stages.", id)),
+                },
+                CampaignStep::Advance | CampaignStep::Stop if !campaign.is_running() => {
+                    Err(format!("Event {} is not running.", id))
+                }
+                CampaignStep::Advance => match campaign.advance().cloned() {
+                    Some(stage) => Ok((
+                        Some(stage),
+                        format!(
+                            "Event {} is now at stage {} of {}.",
+                            id,
+                            campaign.stage().unwrap_or_default() + 1,
+                            campaign.stages.len()
+                        ),
+                    )),
+                    None => {
+                        let announcement =
+                            format!("{} has concluded.", campaign.name.replace("|", "||"));
+                        for player in player_query.iter() {
+                            if let Ok(mut messages) = messages_query.get_mut(player) {
+                                messages.queue(announcement.clone());
+                            }
+                        }
+                        Ok((None, format!("Event {} is complete.", id)))
+                    }
+                },
+                CampaignStep::Stop => {
+                    campaign.stop();
+                    Ok((None, format!("Stopped event {}.", id)))
+                }
+            };
+
+            let (stage, message) = match result {
+                Ok(result) => result,
+                Err(message) => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(message);
+                    }
+                    continue;
+                }
+            };
+
+            updates.persist(persist::campaign::Progress::new(
+                id.clone(),
+                campaign.stage(),
+            ));
+
+            if let Some(CampaignStage {
+                announcement,
+                spawns,
+                ..
+            }) = stage
+            {
+                for player in player_query.iter() {
+                    if let Ok(mut messages) = messages_query.get_mut(player) {
+                        messages.queue(announcement.clone());
+                    }
+                }
+
+                for CampaignSpawn { prototype, room } in spawns {
+                    let (prototype_entity, room_entity) =
+                        match (prototypes.by_id(prototype), rooms.by_id(room)) {
+                            (Some(prototype), Some(room)) => (prototype, room),
+                            _ => {
+                                tracing::warn!(
+                                    "event {} cannot spawn prototype {} in room {}",
+                                    id,
+                                    prototype,
+                                    room
+                                );
+                                continue;
+                            }
+                        };
+
+                    let (named, description, flags, keywords, hooks) =
+                        prototypes_query.get(prototype_entity).unwrap();
+
+                    let object_id = objects.next_id();
+
+                    let mut e = commands.spawn_bundle(ObjectBundle {
+                        object: Object::new(object_id, prototype_entity, true),
+                        id: Id::Object(object_id),
+                        name: named.clone(),
+                        description: description.clone(),
+                        flags: flags.clone(),
+                        keywords: keywords.clone(),
+                        location: Location::from(room_entity),
+                    });
+
+                    if let Some(hooks) = hooks {
+                        e.insert(hooks.clone());
+                    }
+
+                    let object_entity = e.id();
+
+                    if let Some(hooks) = hooks {
+                        for script in hooks.by_trigger(ScriptTrigger::Init) {
+                            init_writer.send(RunInitScript::new(object_entity, script));
+                        }
+                    }
+
+                    if let Ok((_, mut contents)) = room_query.get_mut(room_entity) {
+                        contents.insert(object_entity);
+                    }
+
+                    updates.persist(UpdateGroup::new(vec![
+                        persist::object::Create::new(object_id, prototype),
+                        persist::room::AddObject::new(room, object_id),
+                    ]));
+
+                    objects.insert(object_id, object_entity);
+                }
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct CampaignRemove {
+    pub actor: Entity,
+    pub id: CampaignId,
+}
+
+into_action!(CampaignRemove);
+
+#[tracing::instrument(
+    name = "campaign remove system",
+    skip_all,
+    fields(action = "CampaignRemove")
+)]
+pub fn campaign_remove_system(
+    mut action_reader: EventReader<Action>,
+    mut campaigns: ResMut<Campaigns>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::CampaignRemove(CampaignRemove { actor, id }) = action {
+            let message = if campaigns.remove(id).is_some() {
+                updates.persist(persist::campaign::Remove::new(id.clone()));
+                format!("Deleted event {}.", id)
+            } else {
+                format!("Event {} not found.", id)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct CampaignUpdate {
+    pub actor: Entity,
+    pub id: CampaignId,
+    pub change: CampaignChange,
+}
+
+into_action!(CampaignUpdate);
+
+#[tracing::instrument(
+    name = "campaign update system",
+    skip_all,
+    fields(action = "CampaignUpdate")
+)]
+pub fn campaign_update_system(
+    mut action_reader: EventReader<Action>,
+    mut campaigns: ResMut<Campaigns>,
+    prototypes: Res<Prototypes>,
+    rooms: Res<Rooms>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::CampaignUpdate(CampaignUpdate { actor, id, change }) = action {
+            let campaign = match campaigns.get_mut(id) {
+                Some(campaign) => campaign,
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Event {} not found.", id));
+                    }
+                    continue;
+                }
+            };
+
+            let stage_count = campaign.stages.len();
+            let result = match change {
+                CampaignChange::AddStage(announcement) => {
+                    campaign
+                        .stages
+                        .push(CampaignStage::new(announcement.clone()));
+                    Ok(())
+                }
+                CampaignChange::Name(name) => {
+                    campaign.name = name.clone();
+                    Ok(())
+                }
+                CampaignChange::Announcement(stage, _)
+                | CampaignChange::Goal(stage, _)
+                | CampaignChange::RemoveStage(stage)
+                | CampaignChange::Spawn(stage, _)
+                    if *stage > stage_count =>
+                {
+                    Err(format!("Event {} has no stage {}.", id, stage))
+                }
+                CampaignChange::Announcement(stage, announcement) => {
+                    campaign.stages[stage - 1].announcement = announcement.clone();
+                    Ok(())
+                }
+                CampaignChange::Goal(stage, goal) => {
+                    campaign.stages[stage - 1].goal = Some(goal.clone());
+                    Ok(())
+                }
+                CampaignChange::RemoveStage(_) if campaign.is_running() => {
+                    Err(format!("Stop event {} before removing its stages.", id))
+                }
+                CampaignChange::RemoveStage(stage) => {
+                    campaign.stages.remove(stage - 1);
+                    Ok(())
+                }
+                CampaignChange::Spawn(_, spawn) if prototypes.by_id(spawn.prototype).is_none() => {
+                    Err(format!("Prototype {} does not exist.", spawn.prototype))
+                }
+                CampaignChange::Spawn(_, spawn) if !rooms.exists(spawn.room) => {
+                    Err(format!("Room {} does not exist.", spawn.room))
+                }
+                CampaignChange::Spawn(stage, spawn) => {
+                    campaign.stages[stage - 1].spawns.push(*spawn);
+                    Ok(())
+                }
+            };
+
+            let message = match result {
+                Ok(()) => {
+                    updates.persist(persist::campaign::Update::new(id.clone(), campaign));
+                    format!("Updated event {}.", id)
+                }
+                Err(message) => message,
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
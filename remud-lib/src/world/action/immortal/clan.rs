@@ -0,0 +1,162 @@
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+
+use crate::{
+    engine::persist::{self, Updates},
+    text::Tokenizer,
+    world::{
+        action::{
+            clan::{ClanDisband, ClanInfo},
+            into_action, Action,
+        },
+        types::{
+            clan::Clans,
+            player::Messages,
+            room::{RoomId, Rooms},
+        },
+    },
+};
+
+// Valid shapes:
+// clans - lists every clan
+// clans <name> - shows a clan's founder, hall, and members
+// clans <name> disband - disbands a clan
+// clans <name> hall <room id> - makes a room the clan's hall
+// clans <name> hall clear - removes the clan's hall
+pub fn parse_clans(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    let name = match tokenizer.next() {
+        Some(name) => name.to_string(),
+        None => return Ok(Action::from(ClanList { actor: player })),
+    };
+
+    match tokenizer.next() {
+        None => Ok(Action::from(ClanInfo {
+            actor: player,
+            name: Some(name),
+        })),
+        Some("disband") => Ok(Action::from(ClanDisband {
+            actor: player,
+            name: Some(name),
+        })),
+        Some("hall") => match tokenizer.next() {
+            Some("clear") => Ok(Action::from(ClanUpdateHall {
+                actor: player,
+                name,
+                hall: None,
+            })),
+            Some(token) => match token.parse::<RoomId>() {
+                Ok(room_id) => Ok(Action::from(ClanUpdateHall {
+                    actor: player,
+                    name,
+                    hall: Some(room_id),
+                })),
+                Err(e) => Err(e.to_string()),
+            },
+            None => Err("Enter a room ID or \"clear\".".to_string()),
+        },
+        Some(_) => Err("Enter a valid clans subcommand: disband or hall.".to_string()),
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ClanList {
+    pub actor: Entity,
+}
+
+into_action!(ClanList);
+
+#[tracing::instrument(name = "clan list system", skip_all, fields(action = "ClanList"))]
+pub fn clan_list_system(
+    mut action_reader: EventReader<Action>,
+    clans: Res<Clans>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ClanList(ClanList { actor }) = action {
+            let message = if clans.is_empty() {
+                "There are no clans.".to_string()
+            } else {
+                let mut message = "|white|Clans|-|".to_string();
+
+                for clan in clans.iter() {
+                    let members = clan.members().count();
+                    message.push_str(
+                        format!(
+                            "\r\n  |white|{}|-|: {} {}",
+                            clan.name,
+                            members,
+                            if members == 1 { "member" } else { "members" }
+                        )
+                        .as_str(),
+                    );
+                    if let Some(hall) = clan.hall {
+                        message.push_str(format!(", hall in room {}", hall).as_str());
+                    }
+                }
+
+                message
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ClanUpdateHall {
+    pub actor: Entity,
+    pub name: String,
+    pub hall: Option<RoomId>,
+}
+
+into_action!(ClanUpdateHall);
+
+#[tracing::instrument(
+    name = "clan update hall system",
+    skip_all,
+    fields(action = "ClanUpdateHall")
+)]
+pub fn clan_update_hall_system(
+    mut action_reader: EventReader<Action>,
+    mut clans: ResMut<Clans>,
+    rooms: Res<Rooms>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ClanUpdateHall(ClanUpdateHall { actor, name, hall }) = action {
+            let owner = hall
+                .and_then(|hall| clans.by_hall(hall))
+                .map(|clan| clan.name.clone());
+
+            let message = match (hall, owner) {
+                (Some(hall), _) if !rooms.exists(*hall) => {
+                    format!("Room {} doesn't exist.", hall)
+                }
+                (Some(hall), Some(owner)) => {
+                    format!("Room {} is already the hall of {}.", hall, owner)
+                }
+                _ => match clans.get_mut(name) {
+                    Some(clan) => {
+                        clan.hall = *hall;
+                        updates.persist(persist::clan::Hall::new(clan.name.clone(), *hall));
+
+                        match hall {
+                            Some(hall) => {
+                                format!("Room {} is now the hall of {}.", hall, clan.name)
+                            }
+                            None => format!("{} no longer has a hall.", clan.name),
+                        }
+                    }
+                    None => format!("Clan '{}' not found.", name),
+                },
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
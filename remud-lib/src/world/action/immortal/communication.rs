@@ -0,0 +1,150 @@
+use std::str::FromStr;
+
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+
+use crate::{
+    clock::Clock,
+    text::{format_duration, Tokenizer},
+    world::{
+        action::{into_action, Action},
+        types::{
+            communication::{
+                Channel, Communication, CommunicationFilter, Communications, DEFAULT_PAGE_LENGTH,
+            },
+            player::Messages,
+            room::RoomId,
+        },
+    },
+};
+
+// Valid shapes:
+// comms [player <name>] [channel <channel>] [room <room ID>] [page <page>] [text <text>] -
+//   searches the communication log, newest first. Text runs to the end of the command.
+pub fn parse_comms(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    let mut filter = CommunicationFilter::default();
+    let mut page = 1;
+
+    while let Some(token) = tokenizer.next() {
+        match token {
+            "channel" => match tokenizer.next().map(Channel::from_str) {
+                Some(Ok(channel)) => filter.channel = Some(channel),
+                Some(Err(e)) => return Err(e.to_string()),
+                None => return Err("Enter a channel.".to_string()),
+            },
+            "page" => match tokenizer.next().map(str::parse::<usize>) {
+                Some(Ok(number)) if number > 0 => page = number,
+                _ => return Err("Enter a page number greater than 0.".to_string()),
+            },
+            "player" => match tokenizer.next() {
+                Some(name) => filter.player = Some(name.to_string()),
+                None => return Err("Enter a player's name.".to_string()),
+            },
+            "room" => match tokenizer.next().map(RoomId::from_str) {
+                Some(Ok(room)) => filter.room = Some(room),
+                Some(Err(e)) => return Err(e.to_string()),
+                None => return Err("Enter a room ID.".to_string()),
+            },
+            "text" => {
+                let text = tokenizer.rest().trim();
+                if text.is_empty() {
+                    return Err("Enter the text to search for.".to_string());
+                }
+                filter.text = Some(text.to_string());
+                break;
+            }
+            _ => {
+                return Err(
+                    "Enter a valid comms filter: channel, page, player, room, or text.".to_string(),
+                )
+            }
+        }
+    }
+
+    Ok(Action::from(CommunicationSearch {
+        actor: player,
+        filter,
+        page,
+    }))
+}
+
+fn describe(communication: &Communication, now: i64) -> String {
+    let mut description = format!(
+        "{} ago, {} {}",
+        format_duration((now - communication.said_at).max(0)),
+        communication.channel,
+        communication.name
+    );
+
+    if let Some(target) = &communication.target {
+        description.push_str(format!(" to {}", target).as_str());
+    }
+
+    if let Some(room) = communication.room {
+        description.push_str(format!(" in room {}", room).as_str());
+    }
+
+    description.push_str(format!(": {}", communication.text.replace('|', "||")).as_str());
+
+    description
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct CommunicationSearch {
+    pub actor: Entity,
+    pub filter: CommunicationFilter,
+    pub page: usize,
+}
+
+into_action!(CommunicationSearch);
+
+#[tracing::instrument(
+    name = "communication search system",
+    skip_all,
+    fields(action = "CommunicationSearch")
+)]
+pub fn communication_search_system(
+    mut action_reader: EventReader<Action>,
+    clock: Res<Clock>,
+    communications: Res<Communications>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::CommunicationSearch(CommunicationSearch {
+            actor,
+            filter,
+            page,
+        }) = action
+        {
+            let now = clock.unix_now();
+            let found = communications.page(filter, *page, DEFAULT_PAGE_LENGTH);
+
+            let message = if found.total == 0 {
+                "No communication matches.".to_string()
+            } else if found.entries.is_empty() {
+                format!("There is no page {}.", page)
+            } else {
+                let first = (page - 1) * DEFAULT_PAGE_LENGTH + 1;
+                let mut message = format!(
+                    "|white|Communication|-| ({} to {} of {}, newest first)",
+                    first,
+                    first + found.entries.len() - 1,
+                    found.total
+                );
+
+                for (id, communication) in found.entries.iter() {
+                    message.push_str(
+                        format!("\r\n  |white|{}|-|. {}", id, describe(communication, now))
+                            .as_str(),
+                    );
+                }
+
+                message
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
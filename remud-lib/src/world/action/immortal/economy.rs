@@ -0,0 +1,67 @@
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+
+use crate::{
+    clock::Clock,
+    text::Tokenizer,
+    world::{
+        action::{into_action, Action},
+        types::{
+            bank::Bank,
+            economy::{Economy, DEFAULT_REPORT_DAYS},
+            player::Messages,
+        },
+    },
+};
+
+// Valid shapes:
+// economy report [<days>] - summarizes the coins created and destroyed over recent days
+pub fn parse_economy(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    match tokenizer.next() {
+        Some("report") => {
+            let days = match tokenizer.next().map(str::parse::<u32>) {
+                Some(Ok(days)) if days > 0 => days,
+                Some(_) => return Err("Enter a number of days greater than 0.".to_string()),
+                None => DEFAULT_REPORT_DAYS,
+            };
+
+            Ok(Action::from(EconomyReport {
+                actor: player,
+                days,
+            }))
+        }
+        Some(_) => Err("Enter a valid economy subcommand: report.".to_string()),
+        None => Err("Enter an economy subcommand: report.".to_string()),
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct EconomyReport {
+    pub actor: Entity,
+    pub days: u32,
+}
+
+into_action!(EconomyReport);
+
+#[tracing::instrument(
+    name = "economy report system",
+    skip_all,
+    fields(action = "EconomyReport")
+)]
+pub fn economy_report_system(
+    mut action_reader: EventReader<Action>,
+    clock: Res<Clock>,
+    economy: Res<Economy>,
+    bank: Res<Bank>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::EconomyReport(EconomyReport { actor, days }) = action {
+            let summary = economy.summary(clock.unix_now(), *days, &bank);
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(summary.describe());
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::str::FromStr;
+
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+
+use crate::{
+    engine::persist::{self, Updates},
+    text::Tokenizer,
+    world::{
+        action::{into_action, Action},
+        types::{
+            faction::{Faction, FactionId, Factions},
+            player::Messages,
+        },
+    },
+};
+
+pub fn parse_faction(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(token) = tokenizer.next() {
+        match token {
+            "list" => Ok(Action::from(FactionList { actor: player })),
+            "new" => {
+                let id = match tokenizer.next() {
+                    Some(id) => FactionId::from_str(id).map_err(|e| e.to_string())?,
+                    None => return Err("Enter an ID for the new faction.".to_string()),
+                };
+
+                if tokenizer.rest().is_empty() {
+                    Err("Enter a name for the new faction.".to_string())
+                } else {
+                    Ok(Action::from(FactionCreate {
+                        actor: player,
+                        id,
+                        name: tokenizer.rest().to_string(),
+                    }))
+                }
+            }
+            maybe_id => {
+                let id = FactionId::from_str(maybe_id).map_err(|e| e.to_string())?;
+
+                if let Some(token) = tokenizer.next() {
+                    match token {
+                        "name" => {
+                            if tokenizer.rest().is_empty() {
+                                Err("Enter a name.".to_string())
+                            } else {
+                                Ok(Action::from(FactionUpdate {
+                                    actor: player,
+                                    id,
+                                    name: tokenizer.rest().to_string(),
+                                }))
+                            }
+                        }
+                        _ => Err("Enter a valid faction subcommand: name.".to_string()),
+                    }
+                } else {
+                    Err("Enter a faction subcommand: name.".to_string())
+                }
+            }
+        }
+    } else {
+        Err("Enter a faction ID or subcommand: list or new.".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct FactionCreate {
+    pub actor: Entity,
+    pub id: FactionId,
+    pub name: String,
+}
+
+into_action!(FactionCreate);
+
+#[tracing::instrument(
+    name = "faction create system",
+    skip_all,
+    fields(action = "FactionCreate")
+)]
+pub fn faction_create_system(
+    mut action_reader: EventReader<Action>,
+    mut factions: ResMut<Factions>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::FactionCreate(FactionCreate { actor, id, name }) = action {
+            if factions.contains(id) {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(format!("Faction {} already exists.", id));
+                }
+                continue;
+            }
+
+            updates.persist(persist::faction::Create::new(id.clone(), name.clone()));
+
+            factions.insert(id.clone(), Faction { name: name.clone() });
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!("Created faction {}.", id));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct FactionList {
+    pub actor: Entity,
+}
+
+into_action!(FactionList);
+
+#[tracing::instrument(name = "faction list system", skip_all, fields(action = "FactionList"))]
+pub fn faction_list_system(
+    mut action_reader: EventReader<Action>,
+    factions: Res<Factions>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::FactionList(FactionList { actor }) = action {
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                if factions.is_empty() {
+                    messages.queue("There are no factions.".to_string());
+                }
+
+                for (id, faction) in factions.iter() {
+                    messages.queue(format!(
+                        "|white|{}|-|\t{}",
+                        id,
+                        faction.name.replace("|", "||")
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct FactionUpdate {
+    pub actor: Entity,
+    pub id: FactionId,
+    pub name: String,
+}
+
+into_action!(FactionUpdate);
+
+#[tracing::instrument(
+    name = "faction update system",
+    skip_all,
+    fields(action = "FactionUpdate")
+)]
+pub fn faction_update_system(
+    mut action_reader: EventReader<Action>,
+    mut factions: ResMut<Factions>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::FactionUpdate(FactionUpdate { actor, id, name }) = action {
+            let faction = match factions.get_mut(id) {
+                Some(faction) => faction,
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Faction {} not found.", id));
+                    }
+                    continue;
+                }
+            };
+
+            faction.name = name.clone();
+
+            updates.persist(persist::faction::Update::new(id.clone(), name.clone()));
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!("Updated faction {}.", id));
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+
+use crate::{
+    logging::GAME_LOG_TARGET,
+    text::Tokenizer,
+    world::{
+        action::{into_action, Action},
+        types::{
+            player::{ForcedCommand, ForcedCommands, Messages, Players},
+            Named,
+        },
+    },
+};
+
+// Valid shapes:
+// force <player> <command> - runs the command as if the player had typed it
+pub fn parse_force(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(target) = tokenizer.next() {
+        if tokenizer.rest().is_empty() {
+            Err(format!("Force {} to do what?", target))
+        } else {
+            Ok(Action::from(Force {
+                actor: player,
+                target: target.to_string(),
+                command: tokenizer.rest().to_string(),
+            }))
+        }
+    } else {
+        Err("Force whom?".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Force {
+    pub actor: Entity,
+    pub target: String,
+    pub command: String,
+}
+
+into_action!(Force);
+
+#[tracing::instrument(name = "force system", skip_all, fields(action = "Force"))]
+pub fn force_system(
+    mut action_reader: EventReader<Action>,
+    players: Res<Players>,
+    mut forced: ResMut<ForcedCommands>,
+    named_query: Query<&Named>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Force(Force {
+            actor,
+            target,
+            command,
+        }) = action
+        {
+            let player = if let Some(player) = players.by_name(target.as_str()) {
+                player
+            } else {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(format!("{} is not online.", target));
+                }
+                continue;
+            };
+
+            if let Ok(named) = named_query.get(*actor) {
+                tracing::info!(
+                    target: GAME_LOG_TARGET,
+                    player = named.as_str(),
+                    target = target.as_str(),
+                    command = command.as_str(),
+                    "force"
+                );
+            }
+
+            // The command is parsed with the target's privileges, so mortals are never forced to
+            // use immortal commands.
+            forced.push(ForcedCommand {
+                player,
+                command: command.clone(),
+                privileged: true,
+            });
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!("You force {} to \"{}\".", target, command));
+            }
+        }
+    }
+}
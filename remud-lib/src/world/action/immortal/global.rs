@@ -0,0 +1,126 @@
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+
+use crate::{
+    engine::persist::{self, Updates},
+    text::Tokenizer,
+    world::{
+        action::{immortal::object::format_field, into_action, Action},
+        types::{global::Globals, object::FieldValue, player::Messages},
+    },
+};
+
+pub fn parse_global(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(token) = tokenizer.next() {
+        match token {
+            "list" => Ok(Action::from(GlobalList { actor: player })),
+            "remove" => match tokenizer.next() {
+                Some(key) => Ok(Action::from(GlobalUpdate {
+                    actor: player,
+                    key: key.to_string(),
+                    value: None,
+                })),
+                None => Err("Enter a global name.".to_string()),
+            },
+            "set" => {
+                let key = match tokenizer.next() {
+                    Some(key) => key.to_string(),
+                    None => return Err("Enter a global name.".to_string()),
+                };
+
+                if tokenizer.rest().is_empty() {
+                    Err("Enter a value.".to_string())
+                } else {
+                    Ok(Action::from(GlobalUpdate {
+                        actor: player,
+                        key,
+                        value: Some(FieldValue::parse(tokenizer.rest())),
+                    }))
+                }
+            }
+            _ => Err("Enter a valid global subcommand: list, remove, or set.".to_string()),
+        }
+    } else {
+        Err("Enter a global subcommand: list, remove, or set.".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct GlobalList {
+    pub actor: Entity,
+}
+
+into_action!(GlobalList);
+
+#[tracing::instrument(name = "global list system", skip_all, fields(action = "GlobalList"))]
+pub fn global_list_system(
+    mut action_reader: EventReader<Action>,
+    globals: Res<Globals>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::GlobalList(GlobalList { actor }) = action {
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                if globals.is_empty() {
+                    messages.queue("There are no globals.".to_string());
+                }
+
+                for (key, value) in globals.iter() {
+                    messages.queue(format_field(key, value));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct GlobalUpdate {
+    pub actor: Entity,
+    pub key: String,
+    // the new value, or None to remove the global
+    pub value: Option<FieldValue>,
+}
+
+into_action!(GlobalUpdate);
+
+#[tracing::instrument(
+    name = "global update system",
+    skip_all,
+    fields(action = "GlobalUpdate")
+)]
+pub fn global_update_system(
+    mut action_reader: EventReader<Action>,
+    mut globals: ResMut<Globals>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::GlobalUpdate(GlobalUpdate { actor, key, value }) = action {
+            let message = match value {
+                Some(value) => {
+                    globals.insert(key.clone(), value.clone());
+                    format!(
+                        "Set global {} to {}.",
+                        key,
+                        value.to_string().replace('|', "||")
+                    )
+                }
+                None => {
+                    if globals.remove(key).is_none() {
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(format!("No global {}.", key));
+                        }
+                        continue;
+                    }
+                    format!("Removed global {}.", key)
+                }
+            };
+
+            updates.persist(persist::global::Update::new(key.clone(), value.clone()));
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
@@ -0,0 +1,498 @@
+use std::str::FromStr;
+
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+use rand::thread_rng;
+
+use crate::{
+    engine::persist::{self, Updates},
+    text::Tokenizer,
+    world::{
+        action::{into_action, Action},
+        types::{
+            loot::{LootEntry, LootTable, LootTableId, LootTables, Rarity, DEFAULT_LOOT_ROLLS},
+            object::{PrototypeId, Prototypes},
+            player::Messages,
+            shop::Shops,
+            Named,
+        },
+    },
+};
+
+pub fn parse_loot(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(token) = tokenizer.next() {
+        match token {
+            "list" => Ok(Action::from(LootTableList { actor: player })),
+            "new" => match tokenizer.next() {
+                Some(id) => Ok(Action::from(LootTableCreate {
+                    actor: player,
+                    id: LootTableId::from_str(id).map_err(|e| e.to_string())?,
+                })),
+                None => Err("Enter an ID for the new loot table.".to_string()),
+            },
+            maybe_id => {
+                let id = LootTableId::from_str(maybe_id).map_err(|e| e.to_string())?;
+
+                let change = match tokenizer.next() {
+                    Some("add") => LootTableChange::Add(parse_entry(tokenizer)?),
+                    Some("delete") => {
+                        return Ok(Action::from(LootTableRemove { actor: player, id }))
+                    }
+                    Some("info") => return Ok(Action::from(LootTableInfo { actor: player, id })),
+                    Some("remove") => match tokenizer.next().map(str::parse::<usize>) {
+                        Some(Ok(entry)) if entry > 0 => LootTableChange::Remove(entry),
+                        _ => return Err("Enter the number of the entry to remove.".to_string()),
+                    },
+                    Some("roll") => return Ok(Action::from(LootTableRoll { actor: player, id })),
+                    Some("rolls") => match tokenizer.next().map(str::parse::<u32>) {
+                        Some(Ok(rolls)) if rolls > 0 => LootTableChange::Rolls(rolls),
+                        _ => return Err("Enter a number of rolls greater than 0.".to_string()),
+                    },
+                    Some(_) => {
+                        return Err("Enter a valid loot table subcommand: add, delete, info, \
+                                    remove, roll, or rolls."
+                            .to_string())
+                    }
+                    None => {
+                        return Err("Enter a loot table subcommand: add, delete, info, remove, \
+                                    roll, or rolls."
+                            .to_string())
+                    }
+                };
+
+                Ok(Action::from(LootTableUpdate {
+                    actor: player,
+                    id,
+                    change,
+                }))
+            }
+        }
+    } else {
+        Err("Enter a loot table ID or subcommand: list or new.".to_string())
+    }
+}
+
+// Parses "(<prototype ID>|nothing) <rarity> [<min>[-<max>]] [<weight>]" into a loot entry.
+fn parse_entry(mut tokenizer: Tokenizer) -> Result<LootEntry, String> {
+    let prototype = match tokenizer.next() {
+        Some("nothing") => None,
+        Some(id) => Some(PrototypeId::from_str(id).map_err(|e| e.to_string())?),
+        None => return Err("Enter a prototype ID or nothing.".to_string()),
+    };
+
+    let rarity = match tokenizer.next() {
+        Some(rarity) => Rarity::from_str(rarity).map_err(|e| e.to_string())?,
+        None => return Err("Enter a rarity.".to_string()),
+    };
+
+    let (min, max) = match tokenizer.next() {
+        Some(quantity) => {
+            let range = match quantity.split_once('-') {
+                Some((min, max)) => min.parse::<u32>().and_then(|min| Ok((min, max.parse()?))),
+                None => quantity.parse::<u32>().map(|quantity| (quantity, quantity)),
+            };
+            match range {
+                Ok((min, max)) if min <= max => (min, max),
+                _ => {
+                    return Err(
+                        "Enter a quantity, or a range of quantities such as 1-3.".to_string()
+                    )
+                }
+            }
+        }
+        None => (1, 1),
+    };
+
+    let weight = match tokenizer.next() {
+        Some(weight) => match weight.parse::<u32>() {
+            Ok(weight) if weight > 0 => weight,
+            _ => return Err("Enter a weight greater than 0.".to_string()),
+        },
+        None => rarity.default_weight(),
+    };
+
+    Ok(LootEntry {
+        prototype,
+        weight,
+        min,
+        max,
+        rarity,
+    })
+}
+
+fn format_quantity(entry: &LootEntry) -> String {
+    if entry.min == entry.max {
+        entry.min.to_string()
+    } else {
+        format!("{}-{}", entry.min, entry.max)
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum LootTableChange {
+    Add(LootEntry),
+    // the 1-based number of the entry, as shown by info
+    Remove(usize),
+    Rolls(u32),
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct LootTableCreate {
+    pub actor: Entity,
+    pub id: LootTableId,
+}
+
+into_action!(LootTableCreate);
+
+#[tracing::instrument(
+    name = "loot table create system",
+    skip_all,
+    fields(action = "LootTableCreate")
+)]
+pub fn loot_table_create_system(
+    mut action_reader: EventReader<Action>,
+    mut loot_tables: ResMut<LootTables>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::LootTableCreate(LootTableCreate { actor, id }) = action {
+            if loot_tables.contains(id) {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(format!("Loot table {} already exists.", id));
+                }
+                continue;
+            }
+
+            let table = LootTable::new(DEFAULT_LOOT_ROLLS);
+
+            updates.persist(persist::loot::Update::new(id.clone(), &table));
+
+            loot_tables.insert(id.clone(), table);
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!("Created loot table {}.", id));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct LootTableInfo {
+    pub actor: Entity,
+    pub id: LootTableId,
+}
+
+into_action!(LootTableInfo);
+
+#[tracing::instrument(
+    name = "loot table info system",
+    skip_all,
+    fields(action = "LootTableInfo")
+)]
+pub fn loot_table_info_system(
+    mut action_reader: EventReader<Action>,
+    loot_tables: Res<LootTables>,
+    prototypes: Res<Prototypes>,
+    named_query: Query<&Named>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::LootTableInfo(LootTableInfo { actor, id }) = action {
+            let table = match loot_tables.get(id) {
+                Some(table) => table,
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Loot table {} not found.", id));
+                    }
+                    continue;
+                }
+            };
+
+            let chances = table.chances();
+
+            let mut message = format!("|white|Loot table {}|-|", id);
+            message.push_str(format!("\r\n  |white|rolls|-|: {}", table.rolls).as_str());
+
+            message.push_str("\r\n  |white|entries|-|:");
+            if table.entries.is_empty() {
+                message.push_str(" none");
+            }
+            for (index, (entry, chance)) in table.entries.iter().zip(chances.iter()).enumerate() {
+                let item = match entry.prototype {
+                    Some(prototype) => format!(
+                        "{} x {}",
+                        format_quantity(entry),
+                        prototypes
+                            .by_id(prototype)
+                            .and_then(|entity| named_query.get(entity).ok())
+                            .map(|named| named.escaped())
+                            .unwrap_or_else(|| format!("(missing prototype {})", prototype))
+                    ),
+                    None => "nothing".to_string(),
+                };
+                message.push_str(
+                    format!(
+                        "\r\n    {}. {} ({}, weight {}): {:.1}% per roll, {:.2} per drop",
+                        index + 1,
+                        item,
+                        entry.rarity,
+                        entry.weight,
+                        chance * 100.0,
+                        table.rolls as f64 * chance * entry.expected_quantity()
+                    )
+                    .as_str(),
+                );
+            }
+
+            // The chance of each rarity dropping from a single roll
+            let rarities = Rarity::ALL
+                .iter()
+                .filter_map(|rarity| {
+                    let chance: f64 = table
+                        .entries
+                        .iter()
+                        .zip(chances.iter())
+                        .filter(|(entry, _)| entry.prototype.is_some() && entry.rarity == *rarity)
+                        .map(|(entry, chance)| chance * (1.0 - entry.chance_of_nothing()))
+                        .sum();
+                    (chance > 0.0).then(|| format!("{} {:.1}%", rarity, chance * 100.0))
+                })
+                .join(", ");
+            message.push_str("\r\n  |white|rarities per roll|-|: ");
+            if rarities.is_empty() {
+                message.push_str("none");
+            } else {
+                message.push_str(rarities.as_str());
+            }
+
+            let empty_roll: f64 = table
+                .entries
+                .iter()
+                .zip(chances.iter())
+                .map(|(entry, chance)| chance * entry.chance_of_nothing())
+                .sum();
+            let nothing = if table.entries.is_empty() {
+                1.0
+            } else {
+                empty_roll.powi(table.rolls as i32)
+            };
+            message.push_str(
+                format!("\r\n  |white|drops nothing|-|: {:.1}%", nothing * 100.0).as_str(),
+            );
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct LootTableList {
+    pub actor: Entity,
+}
+
+into_action!(LootTableList);
+
+#[tracing::instrument(
+    name = "loot table list system",
+    skip_all,
+    fields(action = "LootTableList")
+)]
+pub fn loot_table_list_system(
+    mut action_reader: EventReader<Action>,
+    loot_tables: Res<LootTables>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::LootTableList(LootTableList { actor }) = action {
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                if loot_tables.is_empty() {
+                    messages.queue("There are no loot tables.".to_string());
+                }
+
+                for (id, table) in loot_tables.iter() {
+                    messages.queue(format!(
+                        "|white|{}|-|\t{} entries, {} rolls",
+                        id,
+                        table.entries.len(),
+                        table.rolls
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct LootTableRemove {
+    pub actor: Entity,
+    pub id: LootTableId,
+}
+
+into_action!(LootTableRemove);
+
+#[tracing::instrument(
+    name = "loot table remove system",
+    skip_all,
+    fields(action = "LootTableRemove")
+)]
+pub fn loot_table_remove_system(
+    mut action_reader: EventReader<Action>,
+    mut loot_tables: ResMut<LootTables>,
+    mut shops: ResMut<Shops>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::LootTableRemove(LootTableRemove { actor, id }) = action {
+            let message = if loot_tables.remove(id).is_some() {
+                updates.persist(persist::loot::Remove::new(id.clone()));
+
+                // Shops which restocked from the table keep their stock but no longer restock.
+                for (shop_id, shop) in shops.iter_mut() {
+                    if shop.loot_table.as_ref() == Some(id) {
+                        shop.loot_table = None;
+                        updates.persist(persist::shop::Update::new(shop_id.clone(), shop));
+                    }
+                }
+
+                format!("Deleted loot table {}.", id)
+            } else {
+                format!("Loot table {} not found.", id)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct LootTableRoll {
+    pub actor: Entity,
+    pub id: LootTableId,
+}
+
+into_action!(LootTableRoll);
+
+// Rolls a loot table without creating anything, so immortals can try out their tables.
+#[tracing::instrument(
+    name = "loot table roll system",
+    skip_all,
+    fields(action = "LootTableRoll")
+)]
+pub fn loot_table_roll_system(
+    mut action_reader: EventReader<Action>,
+    loot_tables: Res<LootTables>,
+    prototypes: Res<Prototypes>,
+    named_query: Query<&Named>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::LootTableRoll(LootTableRoll { actor, id }) = action {
+            let message = match loot_tables.get(id) {
+                Some(table) => {
+                    let drops = table.roll(&mut thread_rng());
+                    if drops.is_empty() {
+                        "Nothing dropped.".to_string()
+                    } else {
+                        let mut message = "Dropped:".to_string();
+                        for drop in drops {
+                            let name = prototypes
+                                .by_id(drop.prototype)
+                                .and_then(|entity| named_query.get(entity).ok())
+                                .map(|named| named.escaped())
+                                .unwrap_or_else(|| format!("prototype {}", drop.prototype));
+                            message.push_str(
+                                format!("\r\n  {} x {} ({})", drop.quantity, name, drop.rarity)
+                                    .as_str(),
+                            );
+                        }
+                        message
+                    }
+                }
+                None => format!("Loot table {} not found.", id),
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct LootTableUpdate {
+    pub actor: Entity,
+    pub id: LootTableId,
+    pub change: LootTableChange,
+}
+
+into_action!(LootTableUpdate);
+
+#[tracing::instrument(
+    name = "loot table update system",
+    skip_all,
+    fields(action = "LootTableUpdate")
+)]
+pub fn loot_table_update_system(
+    mut action_reader: EventReader<Action>,
+    mut loot_tables: ResMut<LootTables>,
+    prototypes: Res<Prototypes>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::LootTableUpdate(LootTableUpdate { actor, id, change }) = action {
+            let table = match loot_tables.get_mut(id) {
+                Some(table) => table,
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Loot table {} not found.", id));
+                    }
+                    continue;
+                }
+            };
+
+            let result = match change {
+                LootTableChange::Add(entry) => match entry.prototype {
+                    Some(prototype) if prototypes.by_id(prototype).is_none() => {
+                        Err(format!("Prototype {} does not exist.", prototype))
+                    }
+                    _ => {
+                        table.entries.push(entry.clone());
+                        Ok(())
+                    }
+                },
+                LootTableChange::Remove(entry) => {
+                    if *entry <= table.entries.len() {
+                        table.entries.remove(entry - 1);
+                        Ok(())
+                    } else {
+                        Err(format!("Loot table {} has no entry {}.", id, entry))
+                    }
+                }
+                LootTableChange::Rolls(rolls) => {
+                    table.rolls = *rolls;
+                    Ok(())
+                }
+            };
+
+            let message = match result {
+                Ok(()) => {
+                    updates.persist(persist::loot::Update::new(id.clone(), table));
+                    format!("Updated loot table {}.", id)
+                }
+                Err(message) => message,
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
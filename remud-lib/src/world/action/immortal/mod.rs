@@ -1,8 +1,27 @@
+pub mod achievement;
+pub mod allowlist;
+pub mod bank;
+pub mod campaign;
+pub mod clan;
+pub mod communication;
+pub mod economy;
+pub mod faction;
+pub mod force;
+pub mod global;
+pub mod loot;
 pub mod object;
 pub mod player;
+pub mod possess;
 pub mod prototype;
+pub mod region;
 pub mod room;
 pub mod script;
+pub mod season;
+pub mod shop;
+pub mod snoop;
+pub mod spawn;
+pub mod stage;
+pub mod tutorial;
 
 use bevy_app::EventReader;
 use bevy_ecs::prelude::*;
@@ -20,7 +39,7 @@ use crate::{
         types::{
             object::{Objects, Prototypes},
             player::{Messages, Player, Players},
-            room::Room,
+            room::{Room, Staging},
             ActionTarget, Description, Id, Location, Named,
         },
     },
@@ -34,7 +53,7 @@ pub struct Initialize {
 
 into_action!(Initialize);
 
-#[tracing::instrument(name = "initialize system", skip_all)]
+#[tracing::instrument(name = "initialize system", skip_all, fields(action = "Initialize"))]
 pub fn initialize_system(
     mut commands: Commands,
     mut action_reader: EventReader<Action>,
@@ -227,12 +246,17 @@ pub struct UpdateDescription {
 
 into_action!(UpdateDescription);
 
-#[tracing::instrument(name = "update description system", skip_all)]
+#[tracing::instrument(
+    name = "update description system",
+    skip_all,
+    fields(action = "UpdateDescription")
+)]
 pub fn update_description_system(
     mut action_reader: EventReader<Action>,
     objects: Res<Objects>,
     players: Res<Players>,
     prototypes: Res<Prototypes>,
+    staging: Res<Staging>,
     mut updates: ResMut<Updates>,
     player_query: Query<&Player>,
     location_query: Query<(Option<&Location>, Option<&Room>)>,
@@ -250,6 +274,19 @@ pub fn update_description_system(
             let (id, entity) = match target {
                 ActionTarget::CurrentRoom => {
                     let room = get_room_std(*actor, &location_query);
+
+                    if let Some(shadow) = staging.shadow(*actor, room) {
+                        description_query
+                            .get_mut(shadow)
+                            .unwrap()
+                            .set_text(description.clone());
+
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(format!("Updated staged {} description.", target));
+                        }
+                        continue;
+                    }
+
                     let id = room_query.get(room).unwrap().id();
                     (Id::Room(id), room)
                 }
@@ -330,11 +367,12 @@ pub struct UpdateName {
 
 into_action!(UpdateName);
 
-#[tracing::instrument(name = "update name system", skip_all)]
+#[tracing::instrument(name = "update name system", skip_all, fields(action = "UpdateName"))]
 pub fn update_name_system(
     mut action_reader: EventReader<Action>,
     objects: Res<Objects>,
     prototypes: Res<Prototypes>,
+    staging: Res<Staging>,
     mut updates: ResMut<Updates>,
     location_query: Query<(Option<&Location>, Option<&Room>)>,
     room_query: Query<&Room>,
@@ -351,6 +389,16 @@ pub fn update_name_system(
             let (id, entity) = match target {
                 ActionTarget::CurrentRoom => {
                     let room = get_room_std(*actor, &location_query);
+
+                    if let Some(shadow) = staging.shadow(*actor, room) {
+                        name_query.get_mut(shadow).unwrap().set_name(name.clone());
+
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(format!("Updated staged {} name.", target));
+                        }
+                        continue;
+                    }
+
                     let id = room_query.get(room).unwrap().id();
                     (Id::Room(id), room)
                 }
@@ -6,7 +6,7 @@ use itertools::Itertools;
 
 use crate::{
     engine::persist::{self, UpdateGroup, Updates},
-    text::Tokenizer,
+    text::{Table, Tokenizer, DEFAULT_WIDTH},
     world::{
         action::{
             get_room_std,
@@ -15,15 +15,16 @@ use crate::{
         },
         fsm::StateMachine,
         scripting::{
-            time::Timers, ExecutionErrors, RunInitScript, ScriptData, ScriptHook, ScriptHooks,
-            ScriptName, ScriptTrigger,
+            time::Timers, ExecutionErrors, RunInitScript, ScriptData, ScriptHooks, ScriptName,
+            ScriptTrigger,
         },
         types::{
             object::{
-                Flags, InheritableFields, Keywords, Object, ObjectBundle, ObjectFlags, ObjectId,
-                ObjectOrPrototype, Objects, Prototype, PrototypeId, Prototypes,
+                FieldValue, Fields, Flags, InheritableFields, Keywords, Object, ObjectBundle,
+                ObjectFlags, ObjectId, ObjectOrPrototype, Objects, Prototype, PrototypeId,
+                Prototypes,
             },
-            player::{Messages, Player},
+            player::{ClientCaps, Messages, Player},
             room::Room,
             ActionTarget, Contents, Description, Id, Location, Named,
         },
@@ -74,6 +75,7 @@ pub fn parse_object(player: Entity, mut tokenizer: Tokenizer) -> Result<Action,
                                 }))
                             }
                         }
+                        "field" => parse_field(player, ObjectOrPrototype::Object(id), tokenizer),
                         "info" => Ok(Action::from(ObjectInfo { actor: player, id })),
                         "inherit" => {
                             if tokenizer.rest().is_empty() {
@@ -91,8 +93,8 @@ pub fn parse_object(player: Entity, mut tokenizer: Tokenizer) -> Result<Action,
                                         id,
                                         fields,
                                     })),
-                                    Err(_) => Err("Enter valid inheritable fields: desc, flags, \
-                                                   keywords, name, and scripts"
+                                    Err(_) => Err("Enter valid inheritable fields: desc, fields, \
+                                                   flags, keywords, name, and scripts"
                                         .to_string()),
                                 }
                             }
@@ -147,11 +149,9 @@ pub fn parse_object(player: Entity, mut tokenizer: Tokenizer) -> Result<Action,
                         "remove" => Ok(Action::from(ObjectRemove { actor: player, id })),
                         "set" => {
                             if tokenizer.rest().is_empty() {
-                                Err(
-                                    "Enter a space separated list of flags. Valid flags: fixed, \
-                                     subtle."
-                                        .to_string(),
-                                )
+                                Err("Enter a space separated list of flags. Valid flags: bank, \
+                                     drink, fixed, food, subtle."
+                                    .to_string())
                             } else {
                                 Ok(Action::from(UpdateObjectFlags {
                                     actor: player,
@@ -168,11 +168,9 @@ pub fn parse_object(player: Entity, mut tokenizer: Tokenizer) -> Result<Action,
                         }
                         "unset" => {
                             if tokenizer.rest().is_empty() {
-                                Err(
-                                    "Enter a space separated list of flags. Valid flags: fixed, \
-                                     subtle."
-                                        .to_string(),
-                                )
+                                Err("Enter a space separated list of flags. Valid flags: bank, \
+                                     drink, fixed, food, subtle."
+                                    .to_string())
                             } else {
                                 Ok(Action::from(UpdateObjectFlags {
                                     actor: player,
@@ -188,15 +186,15 @@ pub fn parse_object(player: Entity, mut tokenizer: Tokenizer) -> Result<Action,
                             }
                         }
                         _ => Err(
-                            "Enter a valid object subcommand: desc, info, keywords, name, remove, \
-                             set, or unset."
+                            "Enter a valid object subcommand: desc, field, info, keywords, name, \
+                             remove, set, or unset."
                                 .to_string(),
                         ),
                     }
                 } else {
                     Err(
-                        "Enter an object subcommand: desc, info, keywords, name, remove, set, or \
-                         unset."
+                        "Enter an object subcommand: desc, field, info, keywords, name, remove, \
+                         set, or unset."
                             .to_string(),
                     )
                 }
@@ -207,6 +205,31 @@ pub fn parse_object(player: Entity, mut tokenizer: Tokenizer) -> Result<Action,
     }
 }
 
+// Parses "<key> [<value>]" into a field update, removing the field when no value is given.
+pub fn parse_field(
+    player: Entity,
+    id: ObjectOrPrototype,
+    mut tokenizer: Tokenizer,
+) -> Result<Action, String> {
+    let key = match tokenizer.next() {
+        Some(key) => key.to_string(),
+        None => return Err("Enter a field name.".to_string()),
+    };
+
+    let value = if tokenizer.rest().is_empty() {
+        None
+    } else {
+        Some(FieldValue::parse(tokenizer.rest()))
+    };
+
+    Ok(Action::from(UpdateField {
+        actor: player,
+        id,
+        key,
+        value,
+    }))
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct ObjectCreate {
     pub actor: Entity,
@@ -215,7 +238,11 @@ pub struct ObjectCreate {
 
 into_action!(ObjectCreate);
 
-#[tracing::instrument(name = "object create system", skip_all)]
+#[tracing::instrument(
+    name = "object create system",
+    skip_all,
+    fields(action = "ObjectCreate")
+)]
 pub fn object_create_system(
     mut commands: Commands,
     mut action_reader: EventReader<Action>,
@@ -307,7 +334,7 @@ pub struct ObjectInfo {
 
 into_action!(ObjectInfo);
 
-#[tracing::instrument(name = "object info system", skip_all)]
+#[tracing::instrument(name = "object info system", skip_all, fields(action = "ObjectInfo"))]
 pub fn object_info_system(
     mut action_reader: EventReader<Action>,
     objects: Res<Objects>,
@@ -323,10 +350,12 @@ pub fn object_info_system(
         Option<&StateMachine>,
         Option<&ScriptData>,
         Option<&ExecutionErrors>,
+        Option<&Fields>,
     )>,
-    prototype_query: Query<&Prototype>,
+    prototype_query: Query<(&Prototype, Option<&Fields>)>,
     room_query: Query<&Room>,
     player_query: Query<&Named, With<Player>>,
+    caps_query: Query<&ClientCaps>,
     mut messages_query: Query<&mut Messages>,
 ) {
     for action in action_reader.iter() {
@@ -352,106 +381,122 @@ pub fn object_info_system(
                 fsm,
                 data,
                 errors,
+                custom_fields,
             ) = object_query.get(object_entity).unwrap();
 
-            let prototype_id = prototype_query.get(object.prototype()).unwrap().id();
-
-            let mut message = format!("|white|Object {}|-|", object.id());
-
-            message.push_str("\r\n  |white|prototype|-|: ");
-            message.push_str(prototype_id.to_string().as_str());
-
-            message.push_str("\r\n  |white|inherit scripts|-|: ");
-            message.push_str(object.inherit_scripts().to_string().as_str());
-
-            message.push_str("\r\n  |white|name|-|: ");
-            message.push_str(named.escaped().as_str());
-
-            message.push_str("\r\n  |white|description|-|: ");
-            message.push_str(description.escaped().as_str());
-
-            message.push_str("\r\n  |white|flags|-|: ");
-            message.push_str(format!("{:?}", flags.get_flags()).as_str());
-
-            message.push_str("\r\n  |white|keywords|-|: ");
-            message.push_str(keywords.as_word_list().as_str());
-
-            message.push_str("\r\n  |white|location|-|: ");
-            if let Some(location) = location {
-                if let Ok(room) = room_query.get(location.entity()) {
-                    message.push_str("room ");
-                    message.push_str(room.id().to_string().as_str());
-                } else if let Ok(named) = player_query.get(location.entity()) {
-                    message.push_str("player ");
-                    message.push_str(named.as_str());
-                } else {
-                    message.push_str("other ");
-                    message.push_str(format!("{:?}", location.entity()).as_str());
-                }
-            }
-
-            message.push_str("\r\n  |white|script hooks|-|:");
-            if let Some(hooks) = hooks {
-                if hooks.is_empty() {
-                    message.push_str(" none");
-                } else {
-                    for ScriptHook { trigger, script } in hooks.hooks().iter() {
-                        message.push_str(format!("\r\n    {:?} -> {}", trigger, script).as_str());
-
-                        if errors.map(|e| e.has_error(script)).unwrap_or(false) {
-                            message.push_str(" |red|(error)|-|");
-                        }
-                    }
-                }
-            } else {
-                message.push_str(" none");
-            }
-
-            message.push_str("\r\n  |white|script data|-|:");
-            if let Some(data) = data {
-                if data.is_empty() {
-                    message.push_str(" none");
-                } else {
-                    for (k, v) in data.map() {
-                        message.push_str(format!("\r\n    {} -> {:?}", k, v).as_str());
+            let (prototype, prototype_fields) = prototype_query.get(object.prototype()).unwrap();
+            let prototype_id = prototype.id();
+
+            let width = caps_query
+                .get(*actor)
+                .map(ClientCaps::width)
+                .unwrap_or(DEFAULT_WIDTH);
+            let mut fields = Table::fields(width);
+
+            fields.field("prototype", prototype_id.to_string());
+            fields.field("inherit scripts", object.inherit_scripts().to_string());
+            fields.field("name", named.escaped());
+            fields.field("description", description.escaped());
+            fields.field("flags", format!("{:?}", flags.get_flags()));
+            fields.field("keywords", keywords.as_word_list());
+
+            let own_fields = custom_fields
+                .into_iter()
+                .flat_map(|fields| fields.iter().map(|(key, value)| format_field(key, value)));
+            let inherited_fields = prototype_fields.into_iter().flat_map(|fields| {
+                fields
+                    .iter()
+                    .filter(|(key, _)| {
+                        custom_fields
+                            .map(|own| own.get(key.as_str()).is_none())
+                            .unwrap_or(true)
+                    })
+                    .map(|(key, value)| {
+                        format!("{} |Gray46|(inherited)|-|", format_field(key, value))
+                    })
+            });
+            fields.list_field(
+                "fields",
+                own_fields.chain(inherited_fields).sorted().collect_vec(),
+            );
+
+            let location = location
+                .map(|location| {
+                    if let Ok(room) = room_query.get(location.entity()) {
+                        format!("room {}", room.id())
+                    } else if let Ok(named) = player_query.get(location.entity()) {
+                        format!("player {}", named.as_str())
+                    } else {
+                        format!("other {:?}", location.entity())
                     }
-                }
-            } else {
-                message.push_str(" none");
-            }
-
-            message.push_str("\r\n  |white|timers|-|:");
-            if let Some(timers) = timers {
-                if timers.timers().is_empty() {
-                    message.push_str(" none");
-                }
-                for (name, timer) in timers.timers().iter() {
-                    message.push_str(
-                        format!(
-                            "\r\n    {}: {}/{}ms",
-                            name,
-                            timer.elapsed().as_millis(),
-                            timer.duration().as_millis()
-                        )
-                        .as_str(),
-                    )
-                }
-            } else {
-                message.push_str(" none");
-            }
+                })
+                .unwrap_or_default();
+            fields.field("location", location);
+
+            let hooks = hooks
+                .map(|hooks| {
+                    hooks
+                        .hooks()
+                        .iter()
+                        .map(|hook| {
+                            if errors.map(|e| e.has_error(&hook.script)).unwrap_or(false) {
+                                format!("{} |red|(error)|-|", hook)
+                            } else {
+                                hook.to_string()
+                            }
+                        })
+                        .collect_vec()
+                })
+                .unwrap_or_default();
+            fields.list_field("script hooks", hooks);
+
+            let data = data
+                .map(|data| {
+                    data.map()
+                        .iter()
+                        .map(|(k, v)| format!("{} -> {:?}", k, v))
+                        .sorted()
+                        .collect_vec()
+                })
+                .unwrap_or_default();
+            fields.list_field("script data", data);
+
+            let timers = timers
+                .map(|timers| {
+                    timers
+                        .timers()
+                        .iter()
+                        .map(|(name, timer)| {
+                            format!(
+                                "{}: {}/{}ms",
+                                name,
+                                timer.elapsed().as_millis(),
+                                timer.duration().as_millis()
+                            )
+                        })
+                        .collect_vec()
+                })
+                .unwrap_or_default();
+            fields.list_field("timers", timers);
+
+            let states = fsm
+                .map(|StateMachine { states, current }| {
+                    states
+                        .keys()
+                        .sorted()
+                        .map(|state| {
+                            if current == state {
+                                format!("{:?} <-", state)
+                            } else {
+                                format!("{:?}", state)
+                            }
+                        })
+                        .collect_vec()
+                })
+                .unwrap_or_default();
+            fields.list_field("fsm", states);
 
-            message.push_str("\r\n  |white|fsm|-|:");
-            if let Some(StateMachine { states, current }) = fsm {
-                for state in states.keys().sorted() {
-                    let mut current_indicator = "";
-                    if current == state {
-                        current_indicator = "<-";
-                    }
-                    message.push_str(format!("\r\n    {:?} {}", state, current_indicator).as_str());
-                }
-            } else {
-                message.push_str(" none");
-            }
+            let message = format!("|white|Object {}|-|\r\n{}", object.id(), fields.render());
 
             if let Ok(mut messages) = messages_query.get_mut(*actor) {
                 messages.queue(message);
@@ -460,6 +505,11 @@ pub fn object_info_system(
     }
 }
 
+// Formats a custom field for an info screen, escaping any color tags in it.
+pub fn format_field(key: &str, value: &FieldValue) -> String {
+    format!("{} = {}", key, value).replace('|', "||")
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct ObjectInheritFields {
     pub actor: Entity,
@@ -469,7 +519,11 @@ pub struct ObjectInheritFields {
 
 into_action!(ObjectInheritFields);
 
-#[tracing::instrument(name = "object inherit fields system", skip_all)]
+#[tracing::instrument(
+    name = "object inherit fields system",
+    skip_all,
+    fields(action = "ObjectInheritFields")
+)]
 pub fn object_inherit_fields_system(
     mut commands: Commands,
     mut action_reader: EventReader<Action>,
@@ -509,6 +563,9 @@ pub fn object_inherit_fields_system(
                     InheritableFields::Description => {
                         commands.entity(object_entity).insert(description.clone());
                     }
+                    InheritableFields::Fields => {
+                        commands.entity(object_entity).remove::<Fields>();
+                    }
                     InheritableFields::Flags => {
                         commands.entity(object_entity).insert(flags.clone());
                     }
@@ -545,7 +602,11 @@ pub struct UpdateObjectFlags {
 
 into_action!(UpdateObjectFlags);
 
-#[tracing::instrument(name = "object flags system", skip_all)]
+#[tracing::instrument(
+    name = "object flags system",
+    skip_all,
+    fields(action = "UpdateObjectFlags")
+)]
 pub fn update_object_flags(
     mut action_reader: EventReader<Action>,
     objects: Res<Objects>,
@@ -630,7 +691,11 @@ pub struct UpdateKeywords {
 
 into_action!(UpdateKeywords);
 
-#[tracing::instrument(name = "object keywords system", skip_all)]
+#[tracing::instrument(
+    name = "object keywords system",
+    skip_all,
+    fields(action = "UpdateKeywords")
+)]
 pub fn update_keywords_system(
     mut action_reader: EventReader<Action>,
     objects: Res<Objects>,
@@ -707,6 +772,104 @@ pub fn update_keywords_system(
     }
 }
 
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct UpdateField {
+    pub actor: Entity,
+    pub id: ObjectOrPrototype,
+    pub key: String,
+    pub value: Option<FieldValue>,
+}
+
+into_action!(UpdateField);
+
+#[tracing::instrument(name = "object field system", skip_all, fields(action = "UpdateField"))]
+pub fn update_field_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    objects: Res<Objects>,
+    prototypes: Res<Prototypes>,
+    mut updates: ResMut<Updates>,
+    mut fields_query: Query<&mut Fields>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::UpdateField(UpdateField {
+            actor,
+            id,
+            key,
+            value,
+        }) = action
+        {
+            let entity = match id {
+                ObjectOrPrototype::Object(id) => {
+                    if let Some(object) = objects.by_id(*id) {
+                        object
+                    } else {
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(format!("Object {} not found.", id));
+                        }
+                        continue;
+                    }
+                }
+                ObjectOrPrototype::Prototype(id) => {
+                    if let Some(prototype) = prototypes.by_id(*id) {
+                        prototype
+                    } else {
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(format!("Prototype {} not found.", id));
+                        }
+                        continue;
+                    }
+                }
+            };
+
+            let message = match (value, fields_query.get_mut(entity)) {
+                (Some(value), Ok(mut fields)) => {
+                    fields.insert(key.clone(), value.clone());
+                    format!("Set {} field {} to {}.", id, key, value)
+                }
+                (Some(value), Err(_)) => {
+                    commands
+                        .entity(entity)
+                        .insert(Fields::new_with_entry(key.clone(), value.clone()));
+                    format!("Set {} field {} to {}.", id, key, value)
+                }
+                (None, result) => {
+                    let removed = result
+                        .map(|mut fields| fields.remove(key.as_str()).is_some())
+                        .unwrap_or(false);
+
+                    if !removed {
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(format!("No field {} on {}.", key, id));
+                        }
+                        continue;
+                    }
+
+                    format!("Removed {} field {}.", id, key)
+                }
+            };
+
+            match id {
+                ObjectOrPrototype::Object(id) => {
+                    updates.persist(persist::object::Field::new(*id, key.clone(), value.clone()));
+                }
+                ObjectOrPrototype::Prototype(id) => {
+                    updates.persist(persist::prototype::Field::new(
+                        *id,
+                        key.clone(),
+                        value.clone(),
+                    ));
+                }
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message.replace('|', "||"));
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct ObjectRemove {
     pub actor: Entity,
@@ -715,7 +878,11 @@ pub struct ObjectRemove {
 
 into_action!(ObjectRemove);
 
-#[tracing::instrument(name = "remove object system", skip_all)]
+#[tracing::instrument(
+    name = "remove object system",
+    skip_all,
+    fields(action = "ObjectRemove")
+)]
 pub fn object_remove_system(
     mut commands: Commands,
     mut action_reader: EventReader<Action>,
@@ -5,27 +5,30 @@ use bevy_ecs::prelude::*;
 use itertools::Itertools;
 
 use crate::{
-    engine::persist::{self, Updates},
-    text::Tokenizer,
+    engine::{
+        name_valid,
+        persist::{self, Updates},
+    },
+    text::{format_duration, Tokenizer},
     world::{
         action::{
             immortal::{Initialize, ShowError},
             into_action, Action,
         },
-        scripting::{
-            time::Timers, ExecutionErrors, ScriptData, ScriptHook, ScriptHooks, ScriptName,
-        },
+        scripting::{time::Timers, ExecutionErrors, ScriptData, ScriptHooks, ScriptName},
         types::{
             object::Object,
-            player::{self, Messages, Player, PlayerFlags, Players},
+            player::{self, Messages, Player, PlayerFlags, Players, Playtime},
             room::Room,
-            ActionTarget, Contents, Description, Location, Named,
+            ActionTarget, Configuration, Contents, Description, Location, Named,
+            PlayerRenameRequest,
         },
     },
 };
 
 // Valid shapes:
 // player <name> info - displays information about the player
+// player <name> rename <new name> - renames the player
 pub fn parse_player(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
     if let Some(name) = tokenizer.next() {
         if let Some(token) = tokenizer.next() {
@@ -51,10 +54,20 @@ pub fn parse_player(player: Entity, mut tokenizer: Tokenizer) -> Result<Action,
                     actor: player,
                     target: ActionTarget::Player(name.to_string()),
                 })),
+                "rename" => match tokenizer.next() {
+                    Some(new) if name_valid(new) => Ok(Action::from(PlayerRename {
+                        actor: player,
+                        old: name.to_string(),
+                        new: new.to_string(),
+                    })),
+                    Some(_) => Err("Enter a valid player name.".to_string()),
+                    None => Err("Enter a new name for the player.".to_string()),
+                },
                 "set" => {
                     if tokenizer.rest().is_empty() {
                         Err(
-                            "Enter a space separated list of flags. Valid flags: immortal."
+                            "Enter a space separated list of flags. Valid flags: immortal, brief, \
+                             tutorial, admin, builder."
                                 .to_string(),
                         )
                     } else {
@@ -74,7 +87,8 @@ pub fn parse_player(player: Entity, mut tokenizer: Tokenizer) -> Result<Action,
                 "unset" => {
                     if tokenizer.rest().is_empty() {
                         Err(
-                            "Enter a space separated list of flags. Valid flags: immortal."
+                            "Enter a space separated list of flags. Valid flags: immortal, brief, \
+                             tutorial, admin, builder."
                                 .to_string(),
                         )
                     } else {
@@ -91,10 +105,10 @@ pub fn parse_player(player: Entity, mut tokenizer: Tokenizer) -> Result<Action,
                         }))
                     }
                 }
-                _ => Err("Enter a valid player subcommand: info.".to_string()),
+                _ => Err("Enter a valid player subcommand: info, rename.".to_string()),
             }
         } else {
-            Err("Enter a player subcommand: info.".to_string())
+            Err("Enter a player subcommand: info, rename.".to_string())
         }
     } else {
         Err("Enter a player name.".to_string())
@@ -109,7 +123,7 @@ pub struct PlayerInfo {
 
 into_action!(PlayerInfo);
 
-#[tracing::instrument(name = "player info system", skip_all)]
+#[tracing::instrument(name = "player info system", skip_all, fields(action = "PlayerInfo"))]
 pub fn player_info_system(
     mut action_reader: EventReader<Action>,
     players: Res<Players>,
@@ -119,6 +133,7 @@ pub fn player_info_system(
         &Description,
         &Contents,
         &Location,
+        &Playtime,
         Option<&ScriptHooks>,
         Option<&Timers>,
         Option<&ScriptData>,
@@ -139,8 +154,18 @@ pub fn player_info_system(
                 continue;
             };
 
-            let (player, flags, description, contents, location, hooks, timers, data, errors) =
-                player_query.get(player).unwrap();
+            let (
+                player,
+                flags,
+                description,
+                contents,
+                location,
+                playtime,
+                hooks,
+                timers,
+                data,
+                errors,
+            ) = player_query.get(player).unwrap();
             let (room, room_name) = room_query.get(location.entity()).unwrap();
 
             let mut message = format!("|white|Player {}|-|", name);
@@ -157,6 +182,9 @@ pub fn player_info_system(
             message.push_str("\r\n  |white|room|-|: ");
             message.push_str(format!("{} (room {})", room_name.as_str(), room.id()).as_str());
 
+            message.push_str("\r\n  |white|playtime|-|: ");
+            message.push_str(format_duration(playtime.total_seconds()).as_str());
+
             message.push_str("\r\n  |white|inventory|-|:");
             contents
                 .objects()
@@ -176,10 +204,10 @@ pub fn player_info_system(
                 if hooks.is_empty() {
                     message.push_str(" none");
                 }
-                for ScriptHook { trigger, script } in hooks.hooks().iter() {
-                    message.push_str(format!("\r\n    {:?} -> {}", trigger, script).as_str());
+                for hook in hooks.hooks().iter() {
+                    message.push_str(format!("\r\n    {}", hook).as_str());
 
-                    if errors.map(|e| e.has_error(script)).unwrap_or(false) {
+                    if errors.map(|e| e.has_error(&hook.script)).unwrap_or(false) {
                         message.push_str(" |red|(error)|-|");
                     }
                 }
@@ -227,6 +255,46 @@ pub fn player_info_system(
     }
 }
 
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct PlayerRename {
+    pub actor: Entity,
+    pub old: String,
+    pub new: String,
+}
+
+into_action!(PlayerRename);
+
+// The rename is carried out by the engine, which reports back to the actor once it is saved.
+#[tracing::instrument(
+    name = "player rename system",
+    skip_all,
+    fields(action = "PlayerRename")
+)]
+pub fn player_rename_system(
+    mut action_reader: EventReader<Action>,
+    mut config: ResMut<Configuration>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::PlayerRename(PlayerRename { actor, old, new }) = action {
+            let message = if config.player_rename.is_some() {
+                "A rename is already in progress."
+            } else {
+                config.player_rename = Some(PlayerRenameRequest {
+                    actor: *actor,
+                    old: old.clone(),
+                    new: new.clone(),
+                });
+                "Renaming the player."
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message.to_string());
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct PlayerUpdateFlags {
     pub actor: Entity,
@@ -237,7 +305,11 @@ pub struct PlayerUpdateFlags {
 
 into_action!(PlayerUpdateFlags);
 
-#[tracing::instrument(name = "update player flags system", skip_all)]
+#[tracing::instrument(
+    name = "update player flags system",
+    skip_all,
+    fields(action = "PlayerUpdateFlags")
+)]
 pub fn player_update_flags_system(
     mut action_reader: EventReader<Action>,
     players: Res<Players>,
@@ -253,6 +325,19 @@ pub fn player_update_flags_system(
             clear,
         }) = action
         {
+            // Flags grant admin, which skips snoop approval, and builder, which limits what a
+            // player may change, so only admins may change them. Builders are never admins.
+            let admin = player_query
+                .get_mut(*actor)
+                .map(|(_, flags)| flags.contains(player::Flags::ADMIN))
+                .unwrap_or(false);
+            if !admin {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue("Only admins may change player flags.".to_string());
+                }
+                continue;
+            }
+
             let player_entity = if let Some(player) = players.by_name(name.as_str()) {
                 player
             } else {
@@ -0,0 +1,166 @@
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+
+use crate::{
+    logging::GAME_LOG_TARGET,
+    text::Tokenizer,
+    world::{
+        action::{
+            get_room_std, into_action,
+            targeting::{Params, Target, TargetFinder},
+            Action,
+        },
+        types::{
+            player::{Messages, Possessions},
+            room::Room,
+            Location, Named,
+        },
+    },
+};
+
+// Valid shapes:
+// possess <keywords> - acts as an object in the room until released
+pub fn parse_possess(player: Entity, tokenizer: Tokenizer) -> Result<Action, String> {
+    if tokenizer.rest().is_empty() {
+        Err("Possess what?".to_string())
+    } else {
+        let keywords = tokenizer
+            .rest()
+            .split_whitespace()
+            .map(ToString::to_string)
+            .collect_vec();
+
+        Ok(Action::from(Possess {
+            actor: player,
+            keywords,
+        }))
+    }
+}
+
+/// Whether an action may be performed through a possessed object.
+pub fn possessed_may_perform(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::Emote(_)
+            | Action::Exits(_)
+            | Action::Look(_)
+            | Action::LookAt(_)
+            | Action::Move(_)
+            | Action::Say(_)
+    )
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Possess {
+    pub actor: Entity,
+    pub keywords: Vec<String>,
+}
+
+into_action!(Possess);
+
+#[tracing::instrument(name = "possess system", skip_all, fields(action = "Possess"))]
+pub fn possess_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    mut possessions: ResMut<Possessions>,
+    location_query: Query<(Option<&Location>, Option<&Room>)>,
+    target_finder: TargetFinder,
+    named_query: Query<&Named>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Possess(Possess { actor, keywords }) = action {
+            if possessions.target(*actor).is_some() {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(
+                        "You are already possessing something. Release it first.".to_string(),
+                    );
+                }
+                continue;
+            }
+
+            let room = get_room_std(*actor, &location_query);
+            let mut params = Params::new(*actor, room, Some(keywords.clone()));
+            params.players_by_name = false;
+            params.objects_carried = false;
+
+            let (target, name) = match target_finder.resolve(params) {
+                Some(Target { entity, name, .. }) => (entity, name),
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!(
+                            "You find nothing called \"{}\" to possess.",
+                            keywords.join(" ")
+                        ));
+                    }
+                    continue;
+                }
+            };
+
+            if possessions.controller(target).is_some() {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(format!("{} is already possessed.", name));
+                }
+                continue;
+            }
+
+            possessions.insert(*actor, target);
+
+            // The possessed object collects the output it sees, which is relayed to its controller.
+            commands.entity(target).insert(Messages::default());
+
+            if let Ok(named) = named_query.get(*actor) {
+                tracing::info!(
+                    target: GAME_LOG_TARGET,
+                    player = named.as_str(),
+                    target = name.as_str(),
+                    "possess"
+                );
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!(
+                    "You possess {}. Your commands act through it until you release it.",
+                    name
+                ));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Release {
+    pub actor: Entity,
+}
+
+into_action!(Release);
+
+#[tracing::instrument(name = "release system", skip_all, fields(action = "Release"))]
+pub fn release_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    mut possessions: ResMut<Possessions>,
+    named_query: Query<&Named>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Release(Release { actor }) = action {
+            let message = match possessions.remove(*actor) {
+                Some(target) => {
+                    if let Ok(named) = named_query.get(target) {
+                        commands.entity(target).remove::<Messages>();
+                        format!("You release {}.", named)
+                    } else {
+                        "You release your possession.".to_string()
+                    }
+                }
+                None => "You are not possessing anything.".to_string(),
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
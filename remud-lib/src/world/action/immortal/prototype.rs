@@ -10,18 +10,19 @@ use crate::{
     world::{
         action::{
             immortal::{
-                object::{UpdateKeywords, UpdateObjectFlags},
+                object::{format_field, parse_field, UpdateKeywords, UpdateObjectFlags},
                 UpdateDescription, UpdateName,
             },
             into_action, Action, Mode,
         },
-        scripting::{ScriptHook, ScriptHooks},
+        scripting::ScriptHooks,
         types::{
             object::{
-                Keywords, ObjectFlags, ObjectOrPrototype, Prototype, PrototypeBundle, PrototypeId,
-                Prototypes,
+                Behavior, BehaviorKind, Behaviors, Bulk, Fields, Keywords, ObjectFlags,
+                ObjectOrPrototype, Prototype, PrototypeBundle, PrototypeId, Prototypes,
             },
             player::Messages,
+            room::Rooms,
             ActionTarget, Description, Named,
         },
     },
@@ -44,7 +45,21 @@ pub fn parse_prototype(player: Entity, mut tokenizer: Tokenizer) -> Result<Actio
 
                 if let Some(token) = tokenizer.next() {
                     match token {
+                        "behavior" => parse_behavior(player, id, tokenizer),
+                        "field" => parse_field(player, ObjectOrPrototype::Prototype(id), tokenizer),
                         "info" => Ok(Action::from(PrototypeInfo { actor: player, id })),
+                        "size" => Ok(Action::from(PrototypeBulk {
+                            actor: player,
+                            id,
+                            weight: None,
+                            size: Some(parse_bulk(tokenizer.next(), "size")?),
+                        })),
+                        "weight" => Ok(Action::from(PrototypeBulk {
+                            actor: player,
+                            id,
+                            weight: Some(parse_bulk(tokenizer.next(), "weight")?),
+                            size: None,
+                        })),
                         "keywords" => {
                             if let Some(mode) = tokenizer.next() {
                                 let mode = match Mode::from_str(mode) {
@@ -90,11 +105,9 @@ pub fn parse_prototype(player: Entity, mut tokenizer: Tokenizer) -> Result<Actio
                         }
                         "set" => {
                             if tokenizer.rest().is_empty() {
-                                Err(
-                                    "Enter a space separated list of flags. Valid flags: fixed, \
-                                     subtle."
-                                        .to_string(),
-                                )
+                                Err("Enter a space separated list of flags. Valid flags: bank, \
+                                     drink, fixed, food, subtle."
+                                    .to_string())
                             } else {
                                 Ok(Action::from(UpdateObjectFlags {
                                     actor: player,
@@ -122,11 +135,9 @@ pub fn parse_prototype(player: Entity, mut tokenizer: Tokenizer) -> Result<Actio
                         }
                         "unset" => {
                             if tokenizer.rest().is_empty() {
-                                Err(
-                                    "Enter a space separated list of flags. Valid flags: fixed, \
-                                     subtle."
-                                        .to_string(),
-                                )
+                                Err("Enter a space separated list of flags. Valid flags: bank, \
+                                     drink, fixed, food, subtle."
+                                    .to_string())
                             } else {
                                 Ok(Action::from(UpdateObjectFlags {
                                     actor: player,
@@ -141,13 +152,16 @@ pub fn parse_prototype(player: Entity, mut tokenizer: Tokenizer) -> Result<Actio
                                 }))
                             }
                         }
-                        _ => Err("Enter a valid prototype subcommand: desc, info, keywords, \
-                                  name, set, or unset."
-                            .to_string()),
+                        _ => Err(
+                            "Enter a valid prototype subcommand: behavior, desc, field, info, \
+                             keywords, name, set, size, unset, or weight."
+                                .to_string(),
+                        ),
                     }
                 } else {
                     Err(
-                        "Enter a prototype subcommand: desc, info, keywords, name, set, or unset."
+                        "Enter a prototype subcommand: behavior, desc, field, info, keywords, \
+                         name, set, size, unset, or weight."
                             .to_string(),
                     )
                 }
@@ -158,6 +172,187 @@ pub fn parse_prototype(player: Entity, mut tokenizer: Tokenizer) -> Result<Actio
     }
 }
 
+fn parse_behavior(
+    player: Entity,
+    id: PrototypeId,
+    mut tokenizer: Tokenizer,
+) -> Result<Action, String> {
+    let kind = match tokenizer.next() {
+        Some(kind) => BehaviorKind::from_str(kind)
+            .map_err(|_| "Enter a valid behavior: message, teleport, or trap.".to_string())?,
+        None => return Err("Enter a behavior: message, teleport, or trap.".to_string()),
+    };
+
+    let behavior = if tokenizer.rest().is_empty() {
+        None
+    } else {
+        Some(Behavior::parse(kind, tokenizer.rest())?)
+    };
+
+    Ok(Action::from(PrototypeBehavior {
+        actor: player,
+        id,
+        kind,
+        behavior,
+    }))
+}
+
+fn parse_bulk(token: Option<&str>, measure: &str) -> Result<u32, String> {
+    match token.map(str::parse::<u32>) {
+        Some(Ok(value)) => Ok(value),
+        _ => Err(format!("Enter a {} of zero or more.", measure)),
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct PrototypeBehavior {
+    pub actor: Entity,
+    pub id: PrototypeId,
+    pub kind: BehaviorKind,
+    pub behavior: Option<Behavior>,
+}
+
+into_action!(PrototypeBehavior);
+
+#[tracing::instrument(
+    name = "prototype behavior system",
+    skip_all,
+    fields(action = "PrototypeBehavior")
+)]
+pub fn prototype_behavior_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    prototypes: Res<Prototypes>,
+    rooms: Res<Rooms>,
+    mut updates: ResMut<Updates>,
+    mut behaviors_query: Query<&mut Behaviors>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::PrototypeBehavior(PrototypeBehavior {
+            actor,
+            id,
+            kind,
+            behavior,
+        }) = action
+        {
+            let prototype_entity = if let Some(prototype) = prototypes.by_id(*id) {
+                prototype
+            } else {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(format!("Prototype {} not found.", id));
+                }
+                continue;
+            };
+
+            if let Some(Behavior::Teleport(room_id)) = behavior {
+                if !rooms.exists(*room_id) {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Room {} does not exist.", room_id));
+                    }
+                    continue;
+                }
+            }
+
+            let message = match (behavior, behaviors_query.get_mut(prototype_entity)) {
+                (Some(behavior), Ok(mut behaviors)) => {
+                    behaviors.insert(behavior.clone());
+                    format!("Set prototype {} behavior: {}.", id, behavior)
+                }
+                (Some(behavior), Err(_)) => {
+                    commands
+                        .entity(prototype_entity)
+                        .insert(Behaviors::from(behavior.clone()));
+                    format!("Set prototype {} behavior: {}.", id, behavior)
+                }
+                (None, result) => {
+                    let removed = result
+                        .map(|mut behaviors| behaviors.remove(*kind).is_some())
+                        .unwrap_or(false);
+
+                    if !removed {
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(format!("Prototype {} has no {} behavior.", id, kind));
+                        }
+                        continue;
+                    }
+
+                    format!("Removed prototype {} {} behavior.", id, kind)
+                }
+            };
+
+            updates.persist(persist::prototype::Behavior::new(
+                *id,
+                *kind,
+                behavior.clone(),
+            ));
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message.replace('|', "||"));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct PrototypeBulk {
+    pub actor: Entity,
+    pub id: PrototypeId,
+    pub weight: Option<u32>,
+    pub size: Option<u32>,
+}
+
+into_action!(PrototypeBulk);
+
+#[tracing::instrument(
+    name = "prototype bulk system",
+    skip_all,
+    fields(action = "PrototypeBulk")
+)]
+pub fn prototype_bulk_system(
+    mut action_reader: EventReader<Action>,
+    prototypes: Res<Prototypes>,
+    mut updates: ResMut<Updates>,
+    mut bulk_query: Query<&mut Bulk>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::PrototypeBulk(PrototypeBulk {
+            actor,
+            id,
+            weight,
+            size,
+        }) = action
+        {
+            let mut bulk = match prototypes
+                .by_id(*id)
+                .and_then(|prototype| bulk_query.get_mut(prototype).ok())
+            {
+                Some(bulk) => bulk,
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Prototype {} not found.", id));
+                    }
+                    continue;
+                }
+            };
+
+            if let Some(weight) = weight {
+                bulk.weight = *weight;
+            }
+            if let Some(size) = size {
+                bulk.size = *size;
+            }
+
+            updates.persist(persist::prototype::Bulk::new(*id, *bulk));
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!("Updated prototype {} {}.", id, *bulk));
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct PrototypeCreate {
     pub actor: Entity,
@@ -165,7 +360,11 @@ pub struct PrototypeCreate {
 
 into_action!(PrototypeCreate);
 
-#[tracing::instrument(name = "create prototype system", skip_all)]
+#[tracing::instrument(
+    name = "create prototype system",
+    skip_all,
+    fields(action = "PrototypeCreate")
+)]
 pub fn prototype_create_system(
     mut commands: Commands,
     mut action_reader: EventReader<Action>,
@@ -183,6 +382,7 @@ pub fn prototype_create_system(
                 description: Description::from(DEFAULT_PROTOTYPE_DESCRIPTION.to_string()),
                 flags: ObjectFlags::default(),
                 keywords: Keywords::from(vec![DEFAULT_PROTOTYPE_KEYWORD.to_string()]),
+                bulk: Bulk::default(),
             };
 
             updates.persist(persist::prototype::Create::new(
@@ -211,7 +411,11 @@ pub struct PrototypeList {
 
 into_action!(PrototypeList);
 
-#[tracing::instrument(name = "prototype list system", skip_all)]
+#[tracing::instrument(
+    name = "prototype list system",
+    skip_all,
+    fields(action = "PrototypeList")
+)]
 pub fn prototype_list_system(
     mut action_reader: EventReader<Action>,
     mut prototypes: ResMut<Prototypes>,
@@ -238,7 +442,11 @@ pub struct PrototypeInfo {
 
 into_action!(PrototypeInfo);
 
-#[tracing::instrument(name = "prototype info system", skip_all)]
+#[tracing::instrument(
+    name = "prototype info system",
+    skip_all,
+    fields(action = "PrototypeInfo")
+)]
 pub fn prototype_info_system(
     mut action_reader: EventReader<Action>,
     prototypes: Res<Prototypes>,
@@ -249,6 +457,9 @@ pub fn prototype_info_system(
         &Named,
         &Description,
         Option<&ScriptHooks>,
+        Option<&Fields>,
+        Option<&Behaviors>,
+        &Bulk,
     )>,
     mut messages_query: Query<&mut Messages>,
 ) {
@@ -263,7 +474,7 @@ pub fn prototype_info_system(
                 continue;
             };
 
-            let (prototype, flags, keywords, named, description, hooks) =
+            let (prototype, flags, keywords, named, description, hooks, fields, behaviors, bulk) =
                 prototype_query.get(prototype_entity).unwrap();
 
             let mut message = format!("|white|Prototype {}|-|", prototype.id());
@@ -278,13 +489,44 @@ pub fn prototype_info_system(
             message.push_str("\r\n  |white|keywords|-|: ");
             message.push_str(sorted_word_list(keywords.get_list()).as_str());
 
+            // Weight and size are only listed once a builder has set them.
+            if !bulk.is_empty() {
+                message.push_str(
+                    format!(
+                        "\r\n  |white|weight|-|: {}\r\n  |white|size|-|: {}",
+                        bulk.weight, bulk.size
+                    )
+                    .as_str(),
+                );
+            }
+
+            message.push_str("\r\n  |white|fields|-|:");
+            match fields {
+                Some(fields) if !fields.is_empty() => {
+                    for (key, value) in fields.iter() {
+                        message.push_str(format!("\r\n    {}", format_field(key, value)).as_str());
+                    }
+                }
+                _ => message.push_str(" none"),
+            }
+
+            // Behaviors are only listed once a builder has set some.
+            if let Some(behaviors) = behaviors.filter(|behaviors| !behaviors.is_empty()) {
+                message.push_str("\r\n  |white|behaviors|-|:");
+                for behavior in behaviors.iter() {
+                    message.push_str(
+                        format!("\r\n    {}", behavior.to_string().replace('|', "||")).as_str(),
+                    );
+                }
+            }
+
             message.push_str("\r\n  |white|script hooks|-|:");
             if let Some(hooks) = hooks {
                 if hooks.is_empty() {
                     message.push_str(" none");
                 }
-                for ScriptHook { trigger, script } in hooks.hooks().iter() {
-                    message.push_str(format!("\r\n    {:?} -> {}", trigger, script).as_str());
+                for hook in hooks.hooks().iter() {
+                    message.push_str(format!("\r\n    {}", hook).as_str());
                 }
             } else {
                 message.push_str(" none");
@@ -0,0 +1,293 @@
+use bevy_app::EventReader;
+use bevy_ecs::{prelude::*, system::SystemParam};
+use itertools::Itertools;
+
+use crate::{
+    engine::persist::{self, Updates},
+    logging::GAME_LOG_TARGET,
+    text::Tokenizer,
+    world::{
+        action::{into_action, Action},
+        types::{
+            player::{self, Messages, Player, PlayerFlags, PlayerId, Players},
+            room::{LoadedRegions, RegionOwners, Regions},
+            Location, Named,
+        },
+    },
+};
+
+// Valid shapes:
+// region - shows the owners of regions
+// region owner <region> <player> - gives the region to the player
+// region disown <region> - removes the region's owner
+// region loaded - lists the regions in the world
+pub fn parse_region(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(token) = tokenizer.next() {
+        match token {
+            "owner" => {
+                let region = match tokenizer.next() {
+                    Some(region) => region.to_string(),
+                    None => return Err("Enter a region.".to_string()),
+                };
+
+                if let Some(owner) = tokenizer.next() {
+                    Ok(Action::from(RegionOwner {
+                        actor: player,
+                        region,
+                        owner: owner.to_string(),
+                    }))
+                } else {
+                    Err(format!("Who should own {}?", region))
+                }
+            }
+            "disown" => {
+                if let Some(region) = tokenizer.next() {
+                    Ok(Action::from(RegionDisown {
+                        actor: player,
+                        region: region.to_string(),
+                    }))
+                } else {
+                    Err("Enter a region.".to_string())
+                }
+            }
+            "loaded" => Ok(Action::from(RegionLoaded { actor: player })),
+            _ => Err("Enter a valid region subcommand: owner, disown, or loaded.".to_string()),
+        }
+    } else {
+        Ok(Action::from(RegionShow { actor: player }))
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct RegionOwner {
+    pub actor: Entity,
+    pub region: String,
+    pub owner: String,
+}
+
+into_action!(RegionOwner);
+
+#[tracing::instrument(name = "region owner system", skip_all, fields(action = "RegionOwner"))]
+pub fn region_owner_system(
+    mut action_reader: EventReader<Action>,
+    players: Res<Players>,
+    mut owners: ResMut<RegionOwners>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<(&Player, &Named, &PlayerFlags)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::RegionOwner(RegionOwner {
+            actor,
+            region,
+            owner,
+        }) = action
+        {
+            let message = if player_query
+                .get(*actor)
+                .map_or(false, |(_, _, flags)| is_builder(flags))
+            {
+                "Builders cannot change who owns regions.".to_string()
+            } else if let Some((player, _, _)) = players
+                .by_name(owner.as_str())
+                .and_then(|owner| player_query.get(owner).ok())
+            {
+                owners.set(region.clone(), player.id());
+                updates.persist(persist::region::SetOwner::new(region.clone(), player.id()));
+
+                if let Ok((_, named, _)) = player_query.get(*actor) {
+                    tracing::info!(
+                        target: GAME_LOG_TARGET,
+                        player = named.as_str(),
+                        target = owner.as_str(),
+                        region = region.as_str(),
+                        "region owner"
+                    );
+                }
+
+                format!("{} now owns region {}.", owner, region)
+            } else {
+                format!("{} is not online.", owner)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct RegionDisown {
+    pub actor: Entity,
+    pub region: String,
+}
+
+into_action!(RegionDisown);
+
+#[tracing::instrument(
+    name = "region disown system",
+    skip_all,
+    fields(action = "RegionDisown")
+)]
+pub fn region_disown_system(
+    mut action_reader: EventReader<Action>,
+    mut owners: ResMut<RegionOwners>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<(&Player, &Named, &PlayerFlags)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::RegionDisown(RegionDisown { actor, region }) = action {
+            let message = if player_query
+                .get(*actor)
+                .map_or(false, |(_, _, flags)| is_builder(flags))
+            {
+                "Builders cannot change who owns regions.".to_string()
+            } else if owners.remove(region.as_str()).is_some() {
+                updates.persist(persist::region::RemoveOwner::new(region.clone()));
+
+                if let Ok((_, named, _)) = player_query.get(*actor) {
+                    tracing::info!(
+                        target: GAME_LOG_TARGET,
+                        player = named.as_str(),
+                        region = region.as_str(),
+                        "region disown"
+                    );
+                }
+
+                format!("Region {} no longer has an owner.", region)
+            } else {
+                format!("Region {} has no owner.", region)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct RegionShow {
+    pub actor: Entity,
+}
+
+into_action!(RegionShow);
+
+#[tracing::instrument(name = "region show system", skip_all, fields(action = "RegionShow"))]
+pub fn region_show_system(
+    mut action_reader: EventReader<Action>,
+    players: Res<Players>,
+    owners: Res<RegionOwners>,
+    named_query: Query<&Named, With<Player>>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::RegionShow(RegionShow { actor }) = action {
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                if owners.iter().next().is_none() {
+                    messages.queue("No regions have owners.".to_string());
+                    continue;
+                }
+
+                messages.queue("|white|Region owners|-|".to_string());
+                for (region, owner) in owners.iter().sorted_by(|(a, _), (b, _)| a.cmp(b)) {
+                    // Owners who are offline are shown by ID.
+                    let owner = players
+                        .by_id(*owner)
+                        .and_then(|player| named_query.get(player).ok())
+                        .map(ToString::to_string)
+                        .unwrap_or_else(|| format!("player {}", owner));
+                    messages.queue(format!("  {}: {}", region, owner));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct RegionLoaded {
+    pub actor: Entity,
+}
+
+into_action!(RegionLoaded);
+
+#[tracing::instrument(
+    name = "region loaded system",
+    skip_all,
+    fields(action = "RegionLoaded")
+)]
+pub fn region_loaded_system(
+    mut action_reader: EventReader<Action>,
+    loaded: Res<LoadedRegions>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::RegionLoaded(RegionLoaded { actor }) = action {
+            let message = if !loaded.is_lazy() {
+                "Regions are not loaded lazily, so every room is loaded.".to_string()
+            } else if loaded.iter().next().is_none() {
+                "No regions are loaded.".to_string()
+            } else {
+                format!("Loaded regions: {}", loaded.iter().sorted().join(", "))
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+/// Whether the player is a builder, who may only script within regions they own.
+/// Administrators are never limited.
+pub fn is_builder(flags: &PlayerFlags) -> bool {
+    flags.contains(player::Flags::BUILDER) && !flags.contains(player::Flags::ADMIN)
+}
+
+/// Decides what builders may script: rooms in regions they own, and objects lying in those rooms.
+/// Objects carried by players or held in containers are found in the room their holder is in.
+#[derive(SystemParam)]
+pub struct RegionScope<'a> {
+    owners: Res<'a, RegionOwners>,
+    players: Query<'a, (&'static Player, &'static PlayerFlags)>,
+    locations: Query<'a, &'static Location>,
+    regions: Query<'a, &'static Regions>,
+}
+
+impl<'a> RegionScope<'a> {
+    /// The ID of the player if they are limited to the regions they own.
+    pub fn builder(&self, player: Entity) -> Option<PlayerId> {
+        self.players
+            .get(player)
+            .ok()
+            .filter(|(_, flags)| is_builder(flags))
+            .map(|(player, _)| player.id())
+    }
+
+    /// Whether the player may script the target entity.
+    pub fn permits(&self, player: Entity, target: Entity) -> bool {
+        let builder = if let Some(builder) = self.builder(player) {
+            builder
+        } else {
+            return true;
+        };
+
+        if self.players.get(target).is_ok() {
+            return false;
+        }
+
+        let mut entity = target;
+        loop {
+            if let Ok(regions) = self.regions.get(entity) {
+                return self.owners.owns_any(builder, regions);
+            }
+
+            match self.locations.get(entity) {
+                Ok(location) => entity = location.entity(),
+                Err(_) => return false,
+            }
+        }
+    }
+}
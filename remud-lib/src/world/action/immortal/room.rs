@@ -6,23 +6,25 @@ use itertools::Itertools;
 
 use crate::{
     engine::persist::{self, UpdateGroup, Updates},
-    text::{sorted_word_list, Tokenizer},
+    text::{sorted_word_list, Table, Tokenizer, DEFAULT_WIDTH},
     world::{
         action::{
             get_room_std,
-            immortal::{Initialize, ShowError, UpdateDescription, UpdateName},
+            immortal::{region::is_builder, Initialize, ShowError, UpdateDescription, UpdateName},
             into_action,
             observe::Look,
             Action, Mode,
         },
         scripting::{
-            time::Timers, ExecutionErrors, QueuedAction, ScriptData, ScriptHook, ScriptHooks,
-            ScriptName,
+            time::Timers, ExecutionErrors, QueuedAction, ScriptData, ScriptHooks, ScriptName,
         },
         types::{
             object::Object,
-            player::{Messages, Player},
-            room::{Direction, Regions, Room, RoomBundle, RoomId, Rooms},
+            player::{ClientCaps, Messages, Player, PlayerFlags},
+            room::{
+                Direction, Echo, Exit, RegionOwners, Regions, Room, RoomBundle, RoomId, Rooms,
+                Shadow, Staging,
+            },
             ActionTarget, Contents, Description, Id, Location, Named,
         },
         VOID_ROOM_ID,
@@ -34,16 +36,26 @@ pub const DEFAULT_ROOM_DESCRIPTION: &str = "An empty room.";
 
 // Valid shapes:
 // room info - displays information about the room
+// room arrive [template] - sets the message shown when something arrives in this room
+// room exit [exit] (arrive|leave) [template] - sets the message shown when something takes an exit
+// room leave [template] - sets the message shown when something leaves this room
 // room new - creates a new unlinked room
 // room new [direction] - creates a room to the [Direction] of this one with a two way link
 // room desc [description] - sets the description of a room
-// room link [direction] [room ID] - links the current room to another in a given direction (one way)
-// room unlink [direction] - removes an exit from this room
+// room link [exit] [room ID] [message] - links the current room to another through an exit named
+//   by a direction or anything else (one way), optionally telling players who take it a message
+// room unlink [exit] - removes an exit from this room
 // room region - sets the list of regions for the current room
 // room remove - removes the current room and moves everything in it to the void room
 pub fn parse_room(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
     if let Some(subcommand) = tokenizer.next() {
         match subcommand.to_lowercase().as_str() {
+            "arrive" | "leave" => Ok(Action::from(RoomEcho {
+                actor: player,
+                exit: None,
+                echo: Echo::from_str(subcommand).unwrap(),
+                template: parse_template(tokenizer.rest()),
+            })),
             "errors" => {
                 if tokenizer.rest().is_empty() {
                     Err("Enter a script to look for its errors.".to_string())
@@ -68,39 +80,70 @@ pub fn parse_room(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, St
                     }))
                 }
             }
+            "exit" => {
+                if tokenizer.rest().is_empty() {
+                    return Err("Enter an exit.".to_string());
+                }
+
+                // The exit's name runs up to the message being set.
+                let mut name = Vec::new();
+                let echo = loop {
+                    match tokenizer.next() {
+                        Some(token) => match Echo::from_str(token) {
+                            Ok(echo) => break echo,
+                            Err(_) => name.push(token),
+                        },
+                        None => return Err("Enter a message to set: arrive or leave.".to_string()),
+                    }
+                };
+
+                Ok(Action::from(RoomEcho {
+                    actor: player,
+                    exit: Some(parse_exit(name.join(" ").as_str())?),
+                    echo,
+                    template: parse_template(tokenizer.rest()),
+                }))
+            }
             "info" => Ok(Action::from(RoomInfo { actor: player })),
             "init" => Ok(Action::from(Initialize {
                 actor: player,
                 target: ActionTarget::CurrentRoom,
             })),
             "link" => {
-                if let Some(direction) = tokenizer.next() {
-                    let direction = match Direction::from_str(direction) {
-                        Ok(direction) => direction,
-                        Err(_) => {
-                            return Err("Enter a valid direction: up, down, north, east, south, \
-                                        west."
-                                .to_string())
-                        }
-                    };
-
-                    if let Some(destination) = tokenizer.next() {
-                        let destination = match destination.parse::<RoomId>() {
-                            Ok(destination) => destination,
-                            Err(e) => return Err(e.to_string()),
-                        };
+                if tokenizer.rest().is_empty() {
+                    return Err("Enter an exit.".to_string());
+                }
 
-                        Ok(Action::from(RoomLink {
-                            actor: player,
-                            direction,
-                            destination,
-                        }))
-                    } else {
-                        Err("Enter a destination room ID.".to_string())
+                // The exit's name runs up to the destination room ID.
+                let mut name = Vec::new();
+                let destination = loop {
+                    match tokenizer.next() {
+                        Some(token) => match token.parse::<RoomId>() {
+                            Ok(destination) => {
+                                // Another ID after it means the name had a number in it, like
+                                // "gate 2 1", which exit names may not.
+                                if let Some(next) = tokenizer.rest().split_whitespace().next() {
+                                    if next.parse::<RoomId>().is_ok() {
+                                        name.push(token);
+                                    }
+                                }
+                                break destination;
+                            }
+                            Err(_) => name.push(token),
+                        },
+                        None => return Err("Enter a destination room ID.".to_string()),
                     }
-                } else {
-                    Err("Enter a direction.".to_string())
-                }
+                };
+
+                let direction = parse_exit(name.join(" ").as_str())?;
+                let message = parse_template(tokenizer.rest());
+
+                Ok(Action::from(RoomLink {
+                    actor: player,
+                    direction,
+                    destination,
+                    message,
+                }))
             }
             "name" => {
                 if tokenizer.rest().is_empty() {
@@ -168,37 +211,44 @@ pub fn parse_room(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, St
             }
             "remove" => Ok(Action::from(RoomRemove { actor: player })),
             "unlink" => {
-                if let Some(direction) = tokenizer.next() {
-                    let direction = match Direction::from_str(direction) {
-                        Ok(direction) => direction,
-                        Err(_) => {
-                            return Err("Enter a valid direction: up, down, north, east, south, \
-                                        west."
-                                .to_string())
-                        }
-                    };
-
+                if tokenizer.rest().is_empty() {
+                    Err("Enter an exit.".to_string())
+                } else {
                     Ok(Action::from(RoomUnlink {
                         actor: player,
-                        direction,
+                        direction: parse_exit(tokenizer.rest())?,
                     }))
-                } else {
-                    Err("Enter a direction.".to_string())
                 }
             }
             _ => Err(
-                "Enter a valid room subcommand: info, desc, link, new, regions, remove, or unlink."
+                "Enter a valid room subcommand: arrive, info, desc, exit, leave, link, new, \
+                 regions, remove, or unlink."
                     .to_string(),
             ),
         }
     } else {
         Err(
-            "Enter a room subcommand: info, desc, link, new, regions, remove, or unlink."
+            "Enter a room subcommand: arrive, info, desc, exit, leave, link, new, regions, \
+             remove, or unlink."
                 .to_string(),
         )
     }
 }
 
+// An empty template clears the message, restoring the usual one.
+fn parse_template(template: &str) -> Option<String> {
+    Some(template.trim())
+        .filter(|template| !template.is_empty())
+        .map(ToString::to_string)
+}
+
+fn parse_exit(name: &str) -> Result<Direction, String> {
+    Direction::named(name).ok_or_else(|| {
+        "Enter a valid exit: a direction such as north, or a name without numbers such as portal."
+            .to_string()
+    })
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct RoomCreate {
     pub actor: Entity,
@@ -237,8 +287,8 @@ pub fn room_create_system(
 
             let new_room_id = rooms.next_id();
             let mut exits = HashMap::new();
-            if let Some(direction) = direction {
-                exits.insert(direction.opposite(), current_room_entity);
+            if let Some(opposite) = direction.as_ref().and_then(Direction::opposite) {
+                exits.insert(opposite, Exit::from(current_room_entity));
             }
 
             let new_room_entity = commands
@@ -259,7 +309,7 @@ pub fn room_create_system(
                     .q1_mut()
                     .get_mut(current_room_entity)
                     .unwrap()
-                    .insert_exit(*direction, new_room_entity);
+                    .insert_exit(direction.clone(), Exit::from(new_room_entity));
             }
 
             let current_room_id = room_set.q1_mut().get_mut(current_room_entity).unwrap().id();
@@ -272,20 +322,24 @@ pub fn room_create_system(
                 update.append(persist::room::AddExit::new(
                     current_room_id,
                     new_room_id,
-                    *direction,
-                ));
-                update.append(persist::room::AddExit::new(
-                    new_room_id,
-                    current_room_id,
-                    direction.opposite(),
+                    direction.clone(),
+                    Exit::from(new_room_entity),
                 ));
+                if let Some(opposite) = direction.opposite() {
+                    update.append(persist::room::AddExit::new(
+                        new_room_id,
+                        current_room_id,
+                        opposite,
+                        Exit::from(current_room_entity),
+                    ));
+                }
             }
             updates.persist(update);
 
             let mut message = format!("Created room {}", new_room_id);
             if let Some(direction) = direction {
                 message.push(' ');
-                message.push_str(direction.as_to_str());
+                message.push_str(direction.as_to_str().as_str());
             }
             message.push('.');
             if let Ok(mut messages) = messages_query.get_mut(*actor) {
@@ -295,6 +349,103 @@ pub fn room_create_system(
     }
 }
 
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct RoomEcho {
+    pub actor: Entity,
+    pub exit: Option<Direction>,
+    pub echo: Echo,
+    pub template: Option<String>,
+}
+
+into_action!(RoomEcho);
+
+#[tracing::instrument(name = "room echo system", skip_all, fields(action = "RoomEcho"))]
+pub fn room_echo_system(
+    mut action_reader: EventReader<Action>,
+    staging: Res<Staging>,
+    mut updates: ResMut<Updates>,
+    mut room_set: QuerySet<(Query<(Option<&Location>, Option<&Room>)>, Query<&mut Room>)>,
+    mut shadow_query: Query<&mut Shadow>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::RoomEcho(RoomEcho {
+            actor,
+            exit,
+            echo,
+            template,
+        }) = action
+        {
+            let room_entity = get_room_std(*actor, room_set.q0());
+
+            let message = match exit {
+                Some(direction) => {
+                    if let Some(shadow) = staging.shadow(*actor, room_entity) {
+                        let mut shadow = shadow_query.get_mut(shadow).unwrap();
+                        if let Some(mut exit) = shadow.remove_exit(direction) {
+                            exit.set_echo(*echo, template.clone());
+                            shadow.insert_exit(direction.clone(), exit);
+                            format!("Updated staged exit {} {} message.", direction, echo)
+                        } else {
+                            format!("There is no staged exit {}.", direction.as_to_str())
+                        }
+                    } else {
+                        let (room_id, exit) = {
+                            let mut room = room_set.q1_mut().get_mut(room_entity).unwrap();
+                            match room.remove_exit(direction) {
+                                Some(mut exit) => {
+                                    exit.set_echo(*echo, template.clone());
+                                    room.insert_exit(direction.clone(), exit.clone());
+                                    (room.id(), Some(exit))
+                                }
+                                None => (room.id(), None),
+                            }
+                        };
+
+                        if let Some(exit) = exit {
+                            let destination_id = room_set
+                                .q1_mut()
+                                .get_mut(exit.destination)
+                                .map(|room| room.id())
+                                .unwrap();
+
+                            updates.persist(UpdateGroup::new(vec![
+                                persist::room::RemoveExit::new(room_id, direction.clone()),
+                                persist::room::AddExit::new(
+                                    room_id,
+                                    destination_id,
+                                    direction.clone(),
+                                    exit,
+                                ),
+                            ]));
+
+                            format!("Updated exit {} {} message.", direction, echo)
+                        } else {
+                            format!("There is no exit {}.", direction.as_to_str())
+                        }
+                    }
+                }
+                None => {
+                    let mut room = room_set.q1_mut().get_mut(room_entity).unwrap();
+                    room.set_echo(*echo, template.clone());
+
+                    updates.persist(persist::room::Echoes::new(
+                        room.id(),
+                        room.echo(Echo::Leave).map(ToString::to_string),
+                        room.echo(Echo::Arrive).map(ToString::to_string),
+                    ));
+
+                    format!("Updated room {} {} message.", room.id(), echo)
+                }
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct RoomInfo {
     pub actor: Entity,
@@ -302,7 +453,7 @@ pub struct RoomInfo {
 
 into_action!(RoomInfo);
 
-#[tracing::instrument(name = "room info system", skip_all)]
+#[tracing::instrument(name = "room info system", skip_all, fields(action = "RoomInfo"))]
 pub fn room_info_system(
     mut action_reader: EventReader<Action>,
     location_query: Query<(Option<&Location>, Option<&Room>)>,
@@ -319,6 +470,7 @@ pub fn room_info_system(
     )>,
     named_query: Query<&Named>,
     object_query: Query<(&Object, &Named)>,
+    caps_query: Query<&ClientCaps>,
     mut messages_query: Query<&mut Messages>,
 ) {
     for action in action_reader.iter() {
@@ -328,110 +480,115 @@ pub fn room_info_system(
             let (room, named, description, regions, contents, hooks, timers, data, errors) =
                 room_query.get(room_entity).unwrap();
 
-            let mut message = format!("|white|Room {}|-|", room.id());
-
-            message.push_str("\r\n  |white|name|-|: ");
-            message.push_str(named.escaped().as_str());
+            let width = caps_query
+                .get(*actor)
+                .map(ClientCaps::width)
+                .unwrap_or(DEFAULT_WIDTH);
+            let mut fields = Table::fields(width);
 
-            message.push_str("\r\n  |white|description|-|: ");
-            message.push_str(description.escaped().as_str());
+            fields.field("name", named.escaped());
+            fields.field("description", description.escaped());
 
-            message.push_str("\r\n  |white|exits|-|:");
-            room.exits()
+            let exits = room
+                .exits()
                 .iter()
-                .filter_map(|(direction, room)| {
+                .filter_map(|(direction, exit)| {
                     room_query
-                        .get(*room)
+                        .get(exit.destination)
                         .map(|(room, named, _, _, _, _, _, _, _)| {
-                            (direction, named.as_str(), room.id())
+                            let mut line =
+                                format!("{}: {} (room {})", direction, named.as_str(), room.id());
+                            if let Some(message) = &exit.message {
+                                line.push_str(format!(" \"{}\"", message).as_str());
+                            }
+                            for echo in [Echo::Leave, Echo::Arrive] {
+                                if let Some(template) = exit.echo(echo) {
+                                    line.push_str(format!(" ({}: {})", echo, template).as_str());
+                                }
+                            }
+                            line
                         })
                         .ok()
                 })
-                .for_each(|(direction, name, room_id)| {
-                    message.push_str(
-                        format!("\r\n    {}: {} (room {})", direction, name, room_id).as_str(),
-                    )
-                });
+                .collect_vec();
+            fields.list_field("exits", exits);
+
+            for echo in [Echo::Leave, Echo::Arrive] {
+                fields.field(
+                    format!("{} message", echo).as_str(),
+                    room.echo(echo).unwrap_or("default").to_string(),
+                );
+            }
 
-            message.push_str("\r\n  |white|regions|-|: ");
             if regions.is_empty() {
-                message.push_str("none");
+                fields.field("regions", "none".to_string());
             } else {
-                message.push_str(sorted_word_list(regions.get_list()).as_str());
+                fields.field("regions", sorted_word_list(regions.get_list()));
             }
 
-            message.push_str("\r\n  |white|players|-|:");
-            room.players()
+            let players = room
+                .players()
                 .iter()
                 .filter_map(|player| named_query.get(*player).ok())
-                .map(|named| named.as_str())
-                .for_each(|name| message.push_str(format!("\r\n    {}", name).as_str()));
+                .map(|named| named.to_string())
+                .collect_vec();
+            fields.list_field("players", players);
 
-            message.push_str("\r\n  |white|objects|-|:");
-            contents
+            let objects = contents
                 .objects()
                 .iter()
                 .filter_map(|object| object_query.get(*object).ok())
-                .map(|(object, named)| (object.id(), named.as_str()))
-                .for_each(|(id, name)| {
-                    message.push_str(
-                        format!(
-                            "\r\n    object {}: {}",
-                            id,
-                            name.replace("|", "||").as_str()
-                        )
-                        .as_str(),
-                    )
-                });
-
-            message.push_str("\r\n  |white|script hooks|-|:");
-            if let Some(hooks) = hooks {
-                if hooks.is_empty() {
-                    message.push_str(" none");
-                }
-                for ScriptHook { trigger, script } in hooks.hooks().iter() {
-                    message.push_str(format!("\r\n    {:?} -> {}", trigger, script).as_str());
-
-                    if errors.map(|e| e.has_error(script)).unwrap_or(false) {
-                        message.push_str(" |red|(error)|-|");
-                    }
-                }
-            } else {
-                message.push_str(" none");
-            }
-
-            message.push_str("\r\n  |white|script data|-|:");
-            if let Some(data) = data {
-                if data.is_empty() {
-                    message.push_str(" none");
-                } else {
-                    for (k, v) in data.map() {
-                        message.push_str(format!("\r\n    {} -> {:?}", k, v).as_str());
-                    }
-                }
-            } else {
-                message.push_str(" none");
-            }
+                .map(|(object, named)| format!("object {}: {}", object.id(), named.escaped()))
+                .collect_vec();
+            fields.list_field("objects", objects);
+
+            let hooks = hooks
+                .map(|hooks| {
+                    hooks
+                        .hooks()
+                        .iter()
+                        .map(|hook| {
+                            if errors.map(|e| e.has_error(&hook.script)).unwrap_or(false) {
+                                format!("{} |red|(error)|-|", hook)
+                            } else {
+                                hook.to_string()
+                            }
+                        })
+                        .collect_vec()
+                })
+                .unwrap_or_default();
+            fields.list_field("script hooks", hooks);
+
+            let data = data
+                .map(|data| {
+                    data.map()
+                        .iter()
+                        .map(|(k, v)| format!("{} -> {:?}", k, v))
+                        .sorted()
+                        .collect_vec()
+                })
+                .unwrap_or_default();
+            fields.list_field("script data", data);
+
+            let timers = timers
+                .map(|timers| {
+                    timers
+                        .timers()
+                        .iter()
+                        .map(|(name, timer)| {
+                            format!(
+                                "{}: {}/{}ms",
+                                name,
+                                timer.elapsed().as_millis(),
+                                timer.duration().as_millis()
+                            )
+                        })
+                        .collect_vec()
+                })
+                .unwrap_or_default();
+            fields.list_field("timers", timers);
 
-            message.push_str("\r\n  |white|timers|-|:");
-            if let Some(timers) = timers {
-                if timers.timers().is_empty() {
-                    message.push_str(" none");
-                }
-                for (name, timer) in timers.timers().iter() {
-                    message.push_str(
-                        format!(
-                            "\r\n    {}: {}/{}ms",
-                            name,
-                            timer.elapsed().as_millis(),
-                            timer.duration().as_millis()
-                        )
-                        .as_str(),
-                    )
-                }
-            } else {
-                message.push_str(" none");
-            }
+            let message = format!("|white|Room {}|-|\r\n{}", room.id(), fields.render());
 
             if let Ok(mut messages) = messages_query.get_mut(*actor) {
                 messages.queue(message);
@@ -445,16 +602,19 @@ pub struct RoomLink {
     pub actor: Entity,
     pub direction: Direction,
     pub destination: RoomId,
+    pub message: Option<String>,
 }
 
 into_action!(RoomLink);
 
-#[tracing::instrument(name = "room link system", skip_all)]
+#[tracing::instrument(name = "room link system", skip_all, fields(action = "RoomLink"))]
 pub fn room_link_system(
     mut action_reader: EventReader<Action>,
     rooms: Res<Rooms>,
+    staging: Res<Staging>,
     mut updates: ResMut<Updates>,
     mut room_set: QuerySet<(Query<(Option<&Location>, Option<&Room>)>, Query<&mut Room>)>,
+    mut shadow_query: Query<&mut Shadow>,
     mut messages_query: Query<&mut Messages>,
 ) {
     for action in action_reader.iter() {
@@ -462,8 +622,14 @@ pub fn room_link_system(
             actor,
             direction,
             destination,
+            message,
         }) = action
         {
+            if rooms.is_unloaded(*destination) {
+                updates.load_room(*destination, action.clone());
+                continue;
+            }
+
             let to_room_entity = if let Some(room) = rooms.by_id(*destination) {
                 room
             } else {
@@ -475,17 +641,33 @@ pub fn room_link_system(
 
             let from_room_entity = get_room_std(*actor, room_set.q0());
 
+            if let Some(shadow) = staging.shadow(*actor, from_room_entity) {
+                shadow_query.get_mut(shadow).unwrap().insert_exit(
+                    direction.clone(),
+                    Exit::new(to_room_entity, message.clone()),
+                );
+
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(format!(
+                        "Linked staged {} exit to room {}.",
+                        direction, destination
+                    ));
+                }
+                continue;
+            }
+
+            let exit = Exit::new(to_room_entity, message.clone());
             let from_room_id = {
                 let mut from_room = room_set.q1_mut().get_mut(from_room_entity).unwrap();
-                from_room.insert_exit(*direction, to_room_entity);
+                from_room.insert_exit(direction.clone(), exit.clone());
                 from_room.id()
             };
 
-            updates.persist(persist::room::AddExit::new(
-                from_room_id,
-                *destination,
-                *direction,
-            ));
+            // Relinking an exit replaces it.
+            updates.persist(UpdateGroup::new(vec![
+                persist::room::RemoveExit::new(from_room_id, direction.clone()),
+                persist::room::AddExit::new(from_room_id, *destination, direction.clone(), exit),
+            ]));
 
             if let Ok(mut messages) = messages_query.get_mut(*actor) {
                 messages.queue(format!(
@@ -504,7 +686,7 @@ pub struct RoomRemove {
 
 into_action!(RoomRemove);
 
-#[tracing::instrument(name = "remove room system", skip_all)]
+#[tracing::instrument(name = "remove room system", skip_all, fields(action = "RoomRemove"))]
 pub fn room_remove_system(
     mut commands: Commands,
     mut action_reader: EventReader<Action>,
@@ -565,6 +747,7 @@ pub fn room_remove_system(
                     Action::from(Look {
                         actor: *player,
                         direction: None,
+                        brief: false,
                     })
                     .into(),
                 );
@@ -588,8 +771,8 @@ pub fn room_remove_system(
                 let to_remove = room
                     .exits()
                     .iter()
-                    .filter(|(_, entity)| **entity == room_entity)
-                    .map(|(direction, _)| *direction)
+                    .filter(|(_, exit)| exit.destination == room_entity)
+                    .map(|(direction, _)| direction.clone())
                     .collect_vec();
 
                 for direction in to_remove {
@@ -648,22 +831,43 @@ pub struct RoomUnlink {
 
 into_action!(RoomUnlink);
 
-#[tracing::instrument(name = "room unlink system", skip_all)]
+#[tracing::instrument(name = "room unlink system", skip_all, fields(action = "RoomUnlink"))]
 pub fn room_unlink_system(
     mut action_reader: EventReader<Action>,
+    staging: Res<Staging>,
     mut updates: ResMut<Updates>,
     mut room_set: QuerySet<(Query<(Option<&Location>, Option<&Room>)>, Query<&mut Room>)>,
+    mut shadow_query: Query<&mut Shadow>,
     mut messages_query: Query<&mut Messages>,
 ) {
     for action in action_reader.iter() {
         if let Action::RoomUnlink(RoomUnlink { actor, direction }) = action {
             let room_entity = get_room_std(*actor, room_set.q0());
 
+            if let Some(shadow) = staging.shadow(*actor, room_entity) {
+                let removed = shadow_query
+                    .get_mut(shadow)
+                    .unwrap()
+                    .remove_exit(direction)
+                    .is_some();
+
+                let message = if removed {
+                    format!("Removed staged exit {}.", direction.as_to_str())
+                } else {
+                    format!("There is no staged exit {}.", direction.as_to_str())
+                };
+
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(message);
+                }
+                continue;
+            }
+
             let mut room = room_set.q1_mut().get_mut(room_entity).unwrap();
 
             let removed = room.remove_exit(direction).is_some();
 
-            updates.persist(persist::room::RemoveExit::new(room.id(), *direction));
+            updates.persist(persist::room::RemoveExit::new(room.id(), direction.clone()));
 
             let message = if removed {
                 format!("Removed exit {}.", direction.as_to_str())
@@ -687,10 +891,16 @@ pub struct RoomUpdateRegions {
 
 into_action!(RoomUpdateRegions);
 
-#[tracing::instrument(name = "update room regions system", skip_all)]
+#[tracing::instrument(
+    name = "update room regions system",
+    skip_all,
+    fields(action = "RoomUpdateRegions")
+)]
 pub fn room_update_regions_system(
     mut action_reader: EventReader<Action>,
+    owners: Res<RegionOwners>,
     mut updates: ResMut<Updates>,
+    builder_query: Query<(&Player, &PlayerFlags)>,
     location_query: Query<(Option<&Location>, Option<&Room>)>,
     mut room_query: Query<(&Room, &mut Regions)>,
     mut messages_query: Query<&mut Messages>,
@@ -706,6 +916,34 @@ pub fn room_update_regions_system(
 
             let (room, mut room_regions) = room_query.get_mut(room_entity).unwrap();
 
+            // Builders may only move rooms they own between regions they own, so they cannot
+            // claim rooms outside their regions for scripting.
+            if let Some((builder, _)) = builder_query
+                .get(*actor)
+                .ok()
+                .filter(|(_, flags)| is_builder(flags))
+            {
+                let mut changed = regions.clone();
+                if *mode == Mode::Set {
+                    changed.extend(room_regions.get_list());
+                }
+
+                if !owners.owns_any(builder.id(), &room_regions)
+                    || changed
+                        .iter()
+                        .any(|region| owners.owner(region.as_str()) != Some(builder.id()))
+                {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(
+                            "Builders may only change regions they own on rooms in regions they \
+                             own."
+                                .to_string(),
+                        );
+                    }
+                    continue;
+                }
+            }
+
             match mode {
                 Mode::Add => {
                     let mut changed_regions = Vec::new();
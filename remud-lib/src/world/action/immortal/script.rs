@@ -1,35 +1,73 @@
-use std::convert::TryFrom;
+use std::{convert::TryFrom, time::Duration};
 
 use bevy_app::EventReader;
 use bevy_ecs::prelude::*;
 use either::Either;
+use itertools::Itertools;
 
 use crate::{
     engine::persist::{self, Updates},
-    text::Tokenizer,
+    text::{header, Align, Table, Tokenizer, DEFAULT_WIDTH},
     world::{
-        action::{into_action, Action},
+        action::{immortal::region::RegionScope, into_action, Action},
         scripting::{
-            Script, ScriptHook, ScriptHooks, ScriptName, ScriptTrigger, Scripts, TriggerKind,
+            parse_folder, parse_tag, stats::ScriptStats, CompilationError, Script, ScriptHook,
+            ScriptHooks, ScriptName, ScriptTrigger, Scripts, TriggerEvent, TriggerKind,
         },
         types::{
             object::{Object, ObjectId, Objects, Prototype, PrototypeId, Prototypes},
-            player::{Messages, Player, Players},
+            player::{ClientCaps, Messages, Player, Players},
             room::{RoomId, Rooms},
             Id,
         },
     },
 };
 
-// script <name> attach-pre [object|player|room] <id/name>
-// script <name> attach [object|player|room] <id/name>
+const OUTSIDE_OWNED_REGIONS: &str =
+    "Builders may only script rooms and objects in regions they own.";
+
+// script list [tag:<tag>] [folder:<path>]
+// script api [<event>]
+// script stats
+// script (enable|disable) <name> [[object|player|room] <id/name>]
+// script <name> attach-custom <signal> [object|player|room] <id/name> [priority:<n>]
+// script <name> attach-pre [object|player|room] <id/name> [priority:<n>]
+// script <name> attach [object|player|room] <id/name> [priority:<n>]
 // script <name> detach [object|player|room] <id/name>
 pub fn parse_script(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
     if let Some(script) = tokenizer.next() {
+        if script == "list" {
+            return parse_list(player, tokenizer);
+        }
+
+        if script == "api" {
+            return parse_api(player, tokenizer);
+        }
+
+        if script == "stats" {
+            return Ok(Action::from(ScriptStatsList { actor: player }));
+        }
+
+        if script == "enable" || script == "disable" {
+            return parse_toggle(player, tokenizer, script == "enable");
+        }
+
         let script = ScriptName::try_from(script.to_string()).map_err(|e| e.to_string())?;
 
         if let Some(command) = tokenizer.next() {
             match command {
+                "attach-custom" => {
+                    if let Some(signal) = tokenizer.next() {
+                        parse_params(
+                            player,
+                            script,
+                            tokenizer,
+                            ScriptCommand::AttachCustom(signal.to_string()),
+                        )
+                    } else {
+                        Err("Enter a signal name.".to_string())
+                    }
+                }
                 "attach-init" => parse_params(player, script, tokenizer, ScriptCommand::AttachInit),
                 "attach-post" => {
                     parse_params(player, script, tokenizer, ScriptCommand::AttachPostAction)
@@ -51,15 +89,15 @@ pub fn parse_script(player: Entity, mut tokenizer: Tokenizer) -> Result<Action,
                 }
                 "detach" => parse_params(player, script, tokenizer, ScriptCommand::Detach),
                 _ => Err(
-                    "Enter a valid subcommand: attach-init, attach-post, attach-pre, \
-                     attach-timer, or detach."
+                    "Enter a valid subcommand: attach-custom, attach-init, attach-post, \
+                     attach-pre, attach-timer, or detach."
                         .to_string(),
                 ),
             }
         } else {
             Err(
-                "Enter a subcommand: attach-init, attach-post, attach-pre, attach-timer, or \
-                 detach."
+                "Enter a subcommand: attach-custom, attach-init, attach-post, attach-pre, \
+                 attach-timer, or detach."
                     .to_string(),
             )
         }
@@ -68,30 +106,80 @@ pub fn parse_script(player: Entity, mut tokenizer: Tokenizer) -> Result<Action,
     }
 }
 
+fn parse_api(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    let event = match tokenizer.next() {
+        Some(name) => Some(
+            TriggerEvent::ALL
+                .iter()
+                .find(|event| event.to_string().eq_ignore_ascii_case(name))
+                .copied()
+                .ok_or_else(|| format!("There is no event called {}.", name))?,
+        ),
+        None => None,
+    };
+
+    Ok(Action::from(ScriptApi {
+        actor: player,
+        event,
+    }))
+}
+
+fn parse_list(player: Entity, tokenizer: Tokenizer) -> Result<Action, String> {
+    let mut tag = None;
+    let mut folder = None;
+
+    for filter in tokenizer {
+        if let Some(value) = filter.strip_prefix("tag:") {
+            tag = Some(parse_tag(value).map_err(|e| e.to_string())?);
+        } else if let Some(value) = filter.strip_prefix("folder:") {
+            folder = parse_folder(value).map_err(|e| e.to_string())?;
+        } else {
+            return Err("Enter a valid filter: tag:<tag> or folder:<path>.".to_string());
+        }
+    }
+
+    Ok(Action::from(ScriptList {
+        actor: player,
+        tag,
+        folder,
+    }))
+}
+
 fn parse_params(
     player: Entity,
     script: ScriptName,
     mut tokenizer: Tokenizer,
     command: ScriptCommand,
 ) -> Result<Action, String> {
+    let target = parse_target(&mut tokenizer)?;
+
+    let priority = match tokenizer.next() {
+        Some(token) => match token.strip_prefix("priority:") {
+            Some(value) if !matches!(command, ScriptCommand::Detach) => value
+                .parse::<i64>()
+                .map_err(|_| "Script priorities must be whole numbers.".to_string())?,
+            Some(_) => return Err("Priorities are only given when attaching scripts.".to_string()),
+            None => return Err("Enter a valid option: priority:<number>.".to_string()),
+        },
+        None => 0,
+    };
+
+    Ok(command.into_action(player, script, target, priority))
+}
+
+fn parse_target(tokenizer: &mut Tokenizer) -> Result<Either<Id, String>, String> {
     if let Some(target_type) = tokenizer.next() {
         if let Some(id) = tokenizer.next() {
             match target_type {
-                "object" => Ok(command.into_action(
-                    player,
-                    script,
-                    Either::Left(id.parse::<ObjectId>().map_err(|e| e.to_string())?.into()),
+                "object" => Ok(Either::Left(
+                    id.parse::<ObjectId>().map_err(|e| e.to_string())?.into(),
                 )),
-                "player" => Ok(command.into_action(player, script, Either::Right(id.to_string()))),
-                "prototype" => Ok(command.into_action(
-                    player,
-                    script,
-                    Either::Left(id.parse::<PrototypeId>().map_err(|e| e.to_string())?.into()),
+                "player" => Ok(Either::Right(id.to_string())),
+                "prototype" => Ok(Either::Left(
+                    id.parse::<PrototypeId>().map_err(|e| e.to_string())?.into(),
                 )),
-                "room" => Ok(command.into_action(
-                    player,
-                    script,
-                    Either::Left(id.parse::<RoomId>().map_err(|e| e.to_string())?.into()),
+                "room" => Ok(Either::Left(
+                    id.parse::<RoomId>().map_err(|e| e.to_string())?.into(),
                 )),
                 _ => Err(
                     "Enter a valid target type: object, player, prototype, or room.".to_string(),
@@ -105,7 +193,28 @@ fn parse_params(
     }
 }
 
+fn parse_toggle(player: Entity, mut tokenizer: Tokenizer, enabled: bool) -> Result<Action, String> {
+    let script = match tokenizer.next() {
+        Some(script) => ScriptName::try_from(script.to_string()).map_err(|e| e.to_string())?,
+        None => return Err("Enter a script name.".to_string()),
+    };
+
+    let target = if tokenizer.rest().is_empty() {
+        None
+    } else {
+        Some(parse_target(&mut tokenizer)?)
+    };
+
+    Ok(Action::from(ScriptToggle {
+        actor: player,
+        script,
+        enabled,
+        target,
+    }))
+}
+
 enum ScriptCommand {
+    AttachCustom(String),
     AttachInit,
     AttachPostAction,
     AttachPreAction,
@@ -114,14 +223,30 @@ enum ScriptCommand {
 }
 
 impl ScriptCommand {
-    fn into_action(self, actor: Entity, script: ScriptName, id: Either<Id, String>) -> Action {
+    fn into_action(
+        self,
+        actor: Entity,
+        script: ScriptName,
+        id: Either<Id, String>,
+        priority: i64,
+    ) -> Action {
         match self {
+            ScriptCommand::AttachCustom(signal) => ScriptAttach {
+                actor,
+                script,
+                trigger: TriggerKind::Custom,
+                target: id,
+                priority,
+                name: Some(signal),
+            }
+            .into(),
             ScriptCommand::AttachInit => ScriptAttach {
                 actor,
                 script,
                 trigger: TriggerKind::Init,
                 target: id,
-                timer: None,
+                priority,
+                name: None,
             }
             .into(),
             ScriptCommand::AttachPostAction => ScriptAttach {
@@ -129,7 +254,8 @@ impl ScriptCommand {
                 script,
                 trigger: TriggerKind::PostEvent,
                 target: id,
-                timer: None,
+                priority,
+                name: None,
             }
             .into(),
             ScriptCommand::AttachPreAction => ScriptAttach {
@@ -137,7 +263,8 @@ impl ScriptCommand {
                 script,
                 trigger: TriggerKind::PreEvent,
                 target: id,
-                timer: None,
+                priority,
+                name: None,
             }
             .into(),
             ScriptCommand::AttachTimer(name) => ScriptAttach {
@@ -145,7 +272,8 @@ impl ScriptCommand {
                 script,
                 trigger: TriggerKind::Timer,
                 target: id,
-                timer: Some(name),
+                priority,
+                name: Some(name),
             }
             .into(),
             ScriptCommand::Detach => ScriptDetach {
@@ -164,12 +292,18 @@ pub struct ScriptAttach {
     pub script: ScriptName,
     pub trigger: TriggerKind,
     pub target: Either<Id, String>,
-    pub timer: Option<String>,
+    pub priority: i64,
+    // the timer or signal name for Timer and Custom hooks
+    pub name: Option<String>,
 }
 
 into_action!(ScriptAttach);
 
-#[tracing::instrument(name = "script attach system", skip_all)]
+#[tracing::instrument(
+    name = "script attach system",
+    skip_all,
+    fields(action = "ScriptAttach")
+)]
 pub fn script_attach_system(
     mut commands: Commands,
     mut action_reader: EventReader<Action>,
@@ -185,6 +319,7 @@ pub fn script_attach_system(
     mut object_query: Query<&mut Object>,
     mut hook_query: Query<&mut ScriptHooks>,
     mut messages_query: Query<&mut Messages>,
+    scope: RegionScope,
 ) {
     for action in action_reader.iter() {
         if let Action::ScriptAttach(ScriptAttach {
@@ -192,7 +327,8 @@ pub fn script_attach_system(
             script,
             trigger,
             target,
-            timer,
+            priority,
+            name,
         }) = action
         {
             let script_entity = if let Some(script) = scripts.by_name(script) {
@@ -229,6 +365,9 @@ pub fn script_attach_system(
                     Id::Room(id) => {
                         if let Some(room) = rooms.by_id(*id) {
                             room
+                        } else if rooms.is_unloaded(*id) {
+                            updates.load_room(*id, action.clone());
+                            continue;
                         } else {
                             if let Ok(mut messages) = messages_query.get_mut(*actor) {
                                 messages.queue(format!("Target room {} not found.", id));
@@ -250,19 +389,29 @@ pub fn script_attach_system(
                 }
             };
 
+            if !scope.permits(*actor, target_entity) {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(OUTSIDE_OWNED_REGIONS.to_string());
+                }
+                continue;
+            }
+
             let script_trigger = {
                 let trigger_event = &script_query.get(script_entity).unwrap().trigger();
                 match trigger {
+                    TriggerKind::Custom => ScriptTrigger::Custom(name.clone().unwrap()),
                     TriggerKind::PreEvent => ScriptTrigger::PreEvent(*trigger_event),
                     TriggerKind::PostEvent => ScriptTrigger::PostEvent(*trigger_event),
                     TriggerKind::Init => ScriptTrigger::Init,
-                    TriggerKind::Timer => ScriptTrigger::Timer(timer.clone().unwrap()),
+                    TriggerKind::Timer => ScriptTrigger::Timer(name.clone().unwrap()),
                 }
             };
 
             let hook = ScriptHook {
                 trigger: script_trigger.clone(),
                 script: script.clone(),
+                priority: *priority,
+                enabled: true,
             };
 
             if let Ok(mut hooks) = hook_query.get_mut(target_entity) {
@@ -301,6 +450,7 @@ pub fn script_attach_system(
                 id,
                 script.clone(),
                 script_trigger,
+                *priority,
                 copy,
             ));
 
@@ -331,7 +481,11 @@ pub struct ScriptDetach {
 
 into_action!(ScriptDetach);
 
-#[tracing::instrument(name = "script detach system", skip_all)]
+#[tracing::instrument(
+    name = "script detach system",
+    skip_all,
+    fields(action = "ScriptDetach")
+)]
 pub fn script_detach_system(
     mut action_reader: EventReader<Action>,
     prototypes: Res<Prototypes>,
@@ -344,6 +498,7 @@ pub fn script_detach_system(
     mut object_query: Query<&mut Object>,
     mut hook_query: Query<&mut ScriptHooks>,
     mut messages_query: Query<&mut Messages>,
+    scope: RegionScope,
 ) {
     for action in action_reader.iter() {
         if let Action::ScriptDetach(ScriptDetach {
@@ -377,6 +532,9 @@ pub fn script_detach_system(
                     Id::Room(id) => {
                         if let Some(room) = rooms.by_id(*id) {
                             room
+                        } else if rooms.is_unloaded(*id) {
+                            updates.load_room(*id, action.clone());
+                            continue;
                         } else {
                             if let Ok(mut messages) = messages_query.get_mut(*actor) {
                                 messages.queue(format!("Target room {} not found.", id));
@@ -398,6 +556,13 @@ pub fn script_detach_system(
                 }
             };
 
+            if !scope.permits(*actor, target_entity) {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(OUTSIDE_OWNED_REGIONS.to_string());
+                }
+                continue;
+            }
+
             let mut remove_trigger = None;
             if let Ok(mut hooks) = hook_query.get_mut(target_entity) {
                 if let Some(hook) = hooks.remove(script) {
@@ -454,3 +619,393 @@ pub fn script_detach_system(
         }
     }
 }
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ScriptToggle {
+    pub actor: Entity,
+    pub script: ScriptName,
+    pub enabled: bool,
+    // toggles the script everywhere when no target is given
+    pub target: Option<Either<Id, String>>,
+}
+
+into_action!(ScriptToggle);
+
+#[tracing::instrument(
+    name = "script toggle system",
+    skip_all,
+    fields(action = "ScriptToggle")
+)]
+pub fn script_toggle_system(
+    mut action_reader: EventReader<Action>,
+    scripts: Res<Scripts>,
+    prototypes: Res<Prototypes>,
+    objects: Res<Objects>,
+    rooms: Res<Rooms>,
+    players: Res<Players>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<&Player>,
+    prototype_query: Query<&Prototype>,
+    mut script_query: Query<&mut Script>,
+    mut object_query: Query<&mut Object>,
+    mut hook_query: Query<&mut ScriptHooks>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ScriptToggle(ScriptToggle {
+            actor,
+            script,
+            enabled,
+            target,
+        }) = action
+        {
+            let state = if *enabled { "enabled" } else { "disabled" };
+
+            let target = match target {
+                Some(target) => target,
+                None => {
+                    let mut script_component = if let Some(script_component) = scripts
+                        .by_name(script)
+                        .and_then(|entity| script_query.get_mut(entity).ok())
+                    {
+                        script_component
+                    } else {
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(format!("Script {} not found.", script));
+                        }
+                        continue;
+                    };
+
+                    if script_component.enabled() == *enabled {
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(format!("Script {} is already {}.", script, state));
+                        }
+                        continue;
+                    }
+
+                    script_component.set_enabled(*enabled);
+                    updates.persist(persist::script::Enable::new(script.clone(), *enabled));
+
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Script {} {}.", script, state));
+                    }
+                    continue;
+                }
+            };
+
+            let target_entity = match target {
+                Either::Left(id) => match id {
+                    Id::Prototype(id) => {
+                        if let Some(prototype) = prototypes.by_id(*id) {
+                            prototype
+                        } else {
+                            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                                messages.queue(format!("Target prototype {} not found.", id));
+                            }
+                            continue;
+                        }
+                    }
+                    Id::Object(id) => {
+                        if let Some(object) = objects.by_id(*id) {
+                            object
+                        } else {
+                            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                                messages.queue(format!("Target object {} not found.", id));
+                            }
+                            continue;
+                        }
+                    }
+                    Id::Room(id) => {
+                        if let Some(room) = rooms.by_id(*id) {
+                            room
+                        } else if rooms.is_unloaded(*id) {
+                            updates.load_room(*id, action.clone());
+                            continue;
+                        } else {
+                            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                                messages.queue(format!("Target room {} not found.", id));
+                            }
+                            continue;
+                        }
+                    }
+                    Id::Player(_) => unreachable!("Players are referenced by name."),
+                },
+                Either::Right(name) => {
+                    if let Some(player) = players.by_name(name) {
+                        player
+                    } else {
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(format!("Target player {} not found.", name));
+                        }
+                        continue;
+                    }
+                }
+            };
+
+            let found = hook_query
+                .get_mut(target_entity)
+                .map(|mut hooks| hooks.set_enabled(script, *enabled))
+                .unwrap_or(false);
+
+            if !found {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(format!("Script {} not found on target.", script));
+                }
+                continue;
+            }
+
+            let id = match target {
+                Either::Left(id) => *id,
+                Either::Right(_) => Id::Player(player_query.get(target_entity).unwrap().id()),
+            };
+
+            let copy = match id {
+                Id::Object(_) => {
+                    let mut object = object_query.get_mut(target_entity).unwrap();
+                    if object.inherit_scripts() {
+                        object.set_inherit_scripts(false);
+                        Some(prototype_query.get(object.prototype()).unwrap().id())
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            };
+
+            updates.persist(persist::script::EnableHook::new(
+                id,
+                script.clone(),
+                *enabled,
+                copy,
+            ));
+
+            if let Id::Prototype(id) = id {
+                updates.reload(id);
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                let verb = if *enabled { "Enabled" } else { "Disabled" };
+                match target {
+                    Either::Left(id) => {
+                        messages.queue(format!("{} script {} on {}.", verb, script, id))
+                    }
+                    Either::Right(name) => {
+                        messages.queue(format!("{} script {} on player {}.", verb, script, name))
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ScriptList {
+    pub actor: Entity,
+    pub tag: Option<String>,
+    pub folder: Option<String>,
+}
+
+into_action!(ScriptList);
+
+#[tracing::instrument(name = "script list system", skip_all, fields(action = "ScriptList"))]
+pub fn script_list_system(
+    mut action_reader: EventReader<Action>,
+    script_query: Query<(&Script, Option<&CompilationError>)>,
+    caps_query: Query<&ClientCaps>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ScriptList(ScriptList { actor, tag, folder }) = action {
+            let width = caps_query
+                .get(*actor)
+                .map(ClientCaps::width)
+                .unwrap_or(DEFAULT_WIDTH);
+            let mut table = Table::new(width)
+                .column("Name", Align::Left)
+                .column("Trigger", Align::Left)
+                .truncated_column("Folder", Align::Left)
+                .truncated_column("Tags", Align::Left)
+                .column("Status", Align::Left);
+
+            script_query
+                .iter()
+                .filter(|(script, _)| match tag {
+                    Some(tag) => script.has_tag(tag),
+                    None => true,
+                })
+                .filter(|(script, _)| match folder {
+                    Some(folder) => script.in_folder(folder),
+                    None => true,
+                })
+                .sorted_by(|(a, _), (b, _)| {
+                    a.folder()
+                        .cmp(&b.folder())
+                        .then(a.name().to_string().cmp(&b.name().to_string()))
+                })
+                .for_each(|(script, error)| {
+                    let status = if error.is_some() {
+                        "|red|failed to compile|-|"
+                    } else if !script.enabled() {
+                        "|yellow|disabled|-|"
+                    } else {
+                        "|green|ok|-|"
+                    };
+
+                    table.row(vec![
+                        script.name().to_string(),
+                        script.trigger().to_string(),
+                        script.folder().unwrap_or_default().to_string(),
+                        script.tags().join(", "),
+                        status.to_string(),
+                    ]);
+                });
+
+            let message = if table.is_empty() {
+                "No scripts found.".to_string()
+            } else {
+                format!("{}\r\n{}", header("Scripts"), table.render())
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ScriptStatsList {
+    pub actor: Entity,
+}
+
+into_action!(ScriptStatsList);
+
+#[tracing::instrument(
+    name = "script stats system",
+    skip_all,
+    fields(action = "ScriptStatsList")
+)]
+pub fn script_stats_system(
+    mut action_reader: EventReader<Action>,
+    stats: Res<ScriptStats>,
+    caps_query: Query<&ClientCaps>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ScriptStatsList(ScriptStatsList { actor }) = action {
+            let width = caps_query
+                .get(*actor)
+                .map(ClientCaps::width)
+                .unwrap_or(DEFAULT_WIDTH);
+            let mut table = Table::new(width)
+                .column("Name", Align::Left)
+                .column("Runs", Align::Right)
+                .column("Errors", Align::Right)
+                .column("Mean", Align::Right)
+                .column("Total", Align::Right);
+
+            for (name, stat) in stats.by_total() {
+                let mean = if stat.slow() {
+                    format!("|yellow|{}|-|", millis(stat.mean()))
+                } else {
+                    millis(stat.mean())
+                };
+
+                table.row(vec![
+                    name.to_string(),
+                    stat.runs().to_string(),
+                    stat.errors().to_string(),
+                    mean,
+                    millis(stat.total()),
+                ]);
+            }
+
+            let message = if table.is_empty() {
+                "No scripts have run.".to_string()
+            } else {
+                format!(
+                    "{}\r\n{}\r\nScripts averaging over {} are slow.",
+                    header("Script Stats"),
+                    table.render(),
+                    millis(stats.slow_threshold())
+                )
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ScriptApi {
+    pub actor: Entity,
+    pub event: Option<TriggerEvent>,
+}
+
+into_action!(ScriptApi);
+
+#[tracing::instrument(name = "script api system", skip_all, fields(action = "ScriptApi"))]
+pub fn script_api_system(
+    mut action_reader: EventReader<Action>,
+    caps_query: Query<&ClientCaps>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ScriptApi(ScriptApi { actor, event }) = action {
+            let width = caps_query
+                .get(*actor)
+                .map(ClientCaps::width)
+                .unwrap_or(DEFAULT_WIDTH);
+            let mut fields = Table::fields(width);
+
+            let message = match event {
+                Some(event @ (TriggerEvent::Custom | TriggerEvent::Init | TriggerEvent::Timer)) => {
+                    format!("{} scripts are not given an EVENT.", event)
+                }
+                Some(event) => {
+                    fields.field("actor", "the entity that sent the event".to_string());
+                    fields.field("kind", format!("\"{}\"", event));
+                    for (name, description) in event.params() {
+                        fields.field(name, description.to_string());
+                    }
+                    format!("|white|{} EVENT|-|\r\n{}", event, fields.render())
+                }
+                None => {
+                    fields.field("actor", "the entity that sent the event".to_string());
+                    fields.field("kind", "the kind of event, such as \"Say\"".to_string());
+                    fields.field(
+                        "params",
+                        "a map of the event's parameters, which are also fields of EVENT"
+                            .to_string(),
+                    );
+                    let events = TriggerEvent::ALL
+                        .iter()
+                        .filter(|event| {
+                            !matches!(
+                                event,
+                                TriggerEvent::Custom | TriggerEvent::Init | TriggerEvent::Timer
+                            )
+                        })
+                        .map(ToString::to_string)
+                        .join(", ");
+                    fields.field("events", events);
+                    format!(
+                        "|white|Script EVENT|-|\r\n{}\r\nUse \"script api <event>\" to see an \
+                         event's parameters. Parameters an event doesn't have are unit.",
+                        fields.render()
+                    )
+                }
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
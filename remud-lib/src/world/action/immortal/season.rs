@@ -0,0 +1,271 @@
+use std::{convert::TryFrom, str::FromStr};
+
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+
+use crate::{
+    engine::persist::{self, Updates},
+    text::Tokenizer,
+    world::{
+        action::{into_action, Action},
+        scripting::{ScriptName, Scripts},
+        types::{
+            player::Messages,
+            room::{Direction, Room, RoomId, Rooms},
+            season::{parse_season_name, Seasonal, Seasons},
+            spawn::{SpawnTableId, SpawnTables},
+        },
+    },
+};
+
+// Valid shapes:
+// season [list] - lists the seasons and whether they are under way
+// season <name> [info] - shows a season's dates and the content tagged with it
+// season <name> tag <content> - tags content with a season
+// season untag <content> - removes the season tag from content
+//
+// Content is one of: room <room ID>, exit <room ID> <exit>, spawn <spawn table ID>, or
+// script <script name>.
+pub fn parse_season(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    match tokenizer.next() {
+        None | Some("list") => Ok(Action::from(SeasonList { actor: player })),
+        Some("untag") => Ok(Action::from(SeasonTag {
+            actor: player,
+            content: parse_content(tokenizer)?,
+            season: None,
+        })),
+        Some(name) => {
+            let season = parse_season_name(name).map_err(|e| e.to_string())?;
+
+            match tokenizer.next() {
+                None | Some("info") => Ok(Action::from(SeasonInfo {
+                    actor: player,
+                    season,
+                })),
+                Some("tag") => Ok(Action::from(SeasonTag {
+                    actor: player,
+                    content: parse_content(tokenizer)?,
+                    season: Some(season),
+                })),
+                Some(_) => Err("Enter a valid season subcommand: info or tag.".to_string()),
+            }
+        }
+    }
+}
+
+fn parse_content(mut tokenizer: Tokenizer) -> Result<Seasonal, String> {
+    match tokenizer.next() {
+        Some("exit") => {
+            let room = parse_room_id(tokenizer.next())?;
+            match Direction::named(tokenizer.rest()) {
+                Some(direction) => Ok(Seasonal::Exit(room, direction)),
+                None => Err("Enter an exit.".to_string()),
+            }
+        }
+        Some("room") => Ok(Seasonal::Room(parse_room_id(tokenizer.next())?)),
+        Some("script") => match tokenizer.next() {
+            Some(name) => ScriptName::try_from(name.to_string())
+                .map(Seasonal::Script)
+                .map_err(|e| e.to_string()),
+            None => Err("Enter a script name.".to_string()),
+        },
+        Some("spawn") => match tokenizer.next() {
+            Some(id) => SpawnTableId::from_str(id)
+                .map(Seasonal::SpawnTable)
+                .map_err(|e| e.to_string()),
+            None => Err("Enter a spawn table ID.".to_string()),
+        },
+        _ => Err("Enter the content to tag: exit, room, script, or spawn.".to_string()),
+    }
+}
+
+fn parse_room_id(token: Option<&str>) -> Result<RoomId, String> {
+    match token {
+        Some(token) => RoomId::from_str(token).map_err(|e| e.to_string()),
+        None => Err("Enter a room ID.".to_string()),
+    }
+}
+
+fn describe_window(seasons: &Seasons, season: &str) -> String {
+    match seasons.window(season) {
+        Some(window) if seasons.is_under_way(season) => format!("{}, under way", window),
+        Some(window) => format!("{}, dormant", window),
+        None => "no dates configured, dormant".to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct SeasonInfo {
+    pub actor: Entity,
+    pub season: String,
+}
+
+into_action!(SeasonInfo);
+
+#[tracing::instrument(name = "season info system", skip_all, fields(action = "SeasonInfo"))]
+pub fn season_info_system(
+    mut action_reader: EventReader<Action>,
+    seasons: Res<Seasons>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::SeasonInfo(SeasonInfo { actor, season }) = action {
+            let tagged = seasons.tagged(season);
+
+            let message = if seasons.window(season).is_none() && tagged.is_empty() {
+                format!("There is no season {}.", season)
+            } else {
+                let mut message = format!("|white|Season {}|-|", season);
+                message.push_str(
+                    format!(
+                        "\r\n  |white|dates|-|: {}",
+                        describe_window(&seasons, season)
+                    )
+                    .as_str(),
+                );
+
+                if tagged.is_empty() {
+                    message.push_str("\r\n  Nothing is tagged with this season.");
+                } else {
+                    message.push_str("\r\n  |white|tagged|-|:");
+                    for content in tagged {
+                        message.push_str(format!("\r\n    {}", content).as_str());
+                    }
+                }
+
+                message
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct SeasonList {
+    pub actor: Entity,
+}
+
+into_action!(SeasonList);
+
+#[tracing::instrument(name = "season list system", skip_all, fields(action = "SeasonList"))]
+pub fn season_list_system(
+    mut action_reader: EventReader<Action>,
+    seasons: Res<Seasons>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::SeasonList(SeasonList { actor }) = action {
+            let names = seasons.names();
+
+            let message = if names.is_empty() {
+                "There are no seasons.".to_string()
+            } else {
+                let mut message = "|white|Seasons|-|".to_string();
+
+                for season in names {
+                    message.push_str(
+                        format!(
+                            "\r\n  |white|{}|-|: {}, {} tagged",
+                            season,
+                            describe_window(&seasons, season),
+                            seasons.tagged(season).len()
+                        )
+                        .as_str(),
+                    );
+                }
+
+                message
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct SeasonTag {
+    pub actor: Entity,
+    pub content: Seasonal,
+    // the season to tag the content with, or None to remove its tag
+    pub season: Option<String>,
+}
+
+into_action!(SeasonTag);
+
+#[tracing::instrument(name = "season tag system", skip_all, fields(action = "SeasonTag"))]
+pub fn season_tag_system(
+    mut action_reader: EventReader<Action>,
+    mut seasons: ResMut<Seasons>,
+    rooms: Res<Rooms>,
+    scripts: Res<Scripts>,
+    spawn_tables: Res<SpawnTables>,
+    mut updates: ResMut<Updates>,
+    room_query: Query<&Room>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::SeasonTag(SeasonTag {
+            actor,
+            content,
+            season,
+        }) = action
+        {
+            if let Seasonal::Exit(room, _) = content {
+                if rooms.is_unloaded(*room) {
+                    updates.load_room(*room, action.clone());
+                    continue;
+                }
+            }
+
+            let message = match season {
+                Some(season) => {
+                    let exists = match content {
+                        Seasonal::Room(room) => rooms.exists(*room),
+                        Seasonal::Exit(room, direction) => rooms
+                            .by_id(*room)
+                            .and_then(|room| room_query.get(room).ok())
+                            .map(|room| room.exits().contains_key(direction))
+                            .unwrap_or(false),
+                        Seasonal::SpawnTable(table) => spawn_tables.contains(table),
+                        Seasonal::Script(script) => scripts.by_name(script).is_some(),
+                    };
+
+                    if exists {
+                        seasons.tag(content.clone(), season.clone());
+                        updates.persist(persist::season::Tag::new(content.clone(), season.clone()));
+
+                        let mut message = format!("Tagged {} with season {}.", content, season);
+                        if seasons.window(season).is_none() {
+                            message.push_str(
+                                format!(
+                                    " Season {} has no dates configured, so it stays dormant.",
+                                    season
+                                )
+                                .as_str(),
+                            );
+                        }
+                        message
+                    } else {
+                        format!("There is no {}.", content)
+                    }
+                }
+                None => match seasons.untag(content) {
+                    Some(season) => {
+                        updates.persist(persist::season::Untag::new(content.clone()));
+                        format!("Removed {} from season {}.", content, season)
+                    }
+                    None => format!("There is no season tag on {}.", content),
+                },
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use std::{str::FromStr, time::Duration};
+
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+use rand::thread_rng;
+
+use crate::{
+    engine::persist::{self, Updates},
+    text::Tokenizer,
+    world::{
+        action::{into_action, Action},
+        types::{
+            bank::format_coins,
+            loot::{LootTableId, LootTables},
+            object::Prototypes,
+            player::Messages,
+            room::Room,
+            shop::{Shop, ShopId, Shops, DEFAULT_RESTOCK_INTERVAL_SECS},
+            Location, Named,
+        },
+    },
+};
+
+pub fn parse_shops(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(token) = tokenizer.next() {
+        match token {
+            "list" => Ok(Action::from(ShopsList { actor: player })),
+            "new" => match tokenizer.next() {
+                Some(id) => Ok(Action::from(ShopCreate {
+                    actor: player,
+                    id: ShopId::from_str(id).map_err(|e| e.to_string())?,
+                })),
+                None => Err("Enter an ID for the new shop.".to_string()),
+            },
+            maybe_id => {
+                let id = ShopId::from_str(maybe_id).map_err(|e| e.to_string())?;
+
+                let change = match tokenizer.next() {
+                    Some("cap") => match tokenizer.next().map(str::parse::<u32>) {
+                        Some(Ok(cap)) => ShopChange::Cap(cap),
+                        _ => return Err("Enter a whole number cap.".to_string()),
+                    },
+                    Some("delete") => return Ok(Action::from(ShopRemove { actor: player, id })),
+                    Some("info") => return Ok(Action::from(ShopInfo { actor: player, id })),
+                    Some("interval") => match tokenizer.next().map(str::parse::<u64>) {
+                        Some(Ok(seconds)) if seconds > 0 => ShopChange::Interval(seconds),
+                        _ => return Err("Enter an interval in seconds greater than 0.".to_string()),
+                    },
+                    Some("loot") => match tokenizer.next() {
+                        Some("none") => ShopChange::Loot(None),
+                        Some(table) => ShopChange::Loot(Some(
+                            LootTableId::from_str(table).map_err(|e| e.to_string())?,
+                        )),
+                        None => return Err("Enter a loot table ID or none.".to_string()),
+                    },
+                    Some("markup") => match tokenizer.next().map(str::parse::<u32>) {
+                        Some(Ok(markup)) if markup > 0 => ShopChange::Markup(markup),
+                        _ => return Err("Enter a markup percentage greater than 0.".to_string()),
+                    },
+                    Some("restock") => ShopChange::Restock,
+                    Some(_) => {
+                        return Err("Enter a valid shop subcommand: cap, delete, info, \
+                                    interval, loot, markup, or restock."
+                            .to_string())
+                    }
+                    None => {
+                        return Err("Enter a shop subcommand: cap, delete, info, interval, \
+                                    loot, markup, or restock."
+                            .to_string())
+                    }
+                };
+
+                Ok(Action::from(ShopUpdate {
+                    actor: player,
+                    id,
+                    change,
+                }))
+            }
+        }
+    } else {
+        Err("Enter a shop ID or subcommand: list or new.".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum ShopChange {
+    Cap(u32),
+    // seconds between restocks
+    Interval(u64),
+    Loot(Option<LootTableId>),
+    // percentage of the base price
+    Markup(u32),
+    Restock,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ShopCreate {
+    pub actor: Entity,
+    pub id: ShopId,
+}
+
+into_action!(ShopCreate);
+
+// Opens a shop in the room the immortal is standing in.
+#[tracing::instrument(name = "shop create system", skip_all, fields(action = "ShopCreate"))]
+pub fn shop_create_system(
+    mut action_reader: EventReader<Action>,
+    mut shops: ResMut<Shops>,
+    mut updates: ResMut<Updates>,
+    location_query: Query<&Location>,
+    room_query: Query<&Room>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ShopCreate(ShopCreate { actor, id }) = action {
+            let room = match location_query
+                .get(*actor)
+                .ok()
+                .and_then(|location| room_query.get(location.entity()).ok())
+            {
+                Some(room) => room.id(),
+                None => continue,
+            };
+
+            let message = if shops.contains(id) {
+                format!("Shop {} already exists.", id)
+            } else if let Some((existing, _)) = shops.by_room(room) {
+                format!("Room {} already has shop {}.", room, existing)
+            } else {
+                let shop = Shop::new(room, Duration::from_secs(DEFAULT_RESTOCK_INTERVAL_SECS));
+
+                updates.persist(persist::shop::Update::new(id.clone(), &shop));
+
+                shops.insert(id.clone(), shop);
+
+                format!("Created shop {} in room {}.", id, room)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ShopInfo {
+    pub actor: Entity,
+    pub id: ShopId,
+}
+
+into_action!(ShopInfo);
+
+#[tracing::instrument(name = "shop info system", skip_all, fields(action = "ShopInfo"))]
+pub fn shop_info_system(
+    mut action_reader: EventReader<Action>,
+    shops: Res<Shops>,
+    prototypes: Res<Prototypes>,
+    named_query: Query<&Named>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ShopInfo(ShopInfo { actor, id }) = action {
+            let shop = match shops.get(id) {
+                Some(shop) => shop,
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Shop {} not found.", id));
+                    }
+                    continue;
+                }
+            };
+
+            let mut message = format!("|white|Shop {}|-|", id);
+
+            message.push_str(format!("\r\n  |white|room|-|: {}", shop.room).as_str());
+            message.push_str(
+                format!(
+                    "\r\n  |white|loot table|-|: {}",
+                    shop.loot_table
+                        .as_ref()
+                        .map_or_else(|| "none".to_string(), ToString::to_string)
+                )
+                .as_str(),
+            );
+            message.push_str(format!("\r\n  |white|markup|-|: {}%", shop.markup).as_str());
+            message.push_str(format!("\r\n  |white|cap|-|: {}", shop.cap).as_str());
+            message.push_str(
+                format!(
+                    "\r\n  |white|interval|-|: {} seconds",
+                    shop.interval().as_secs()
+                )
+                .as_str(),
+            );
+            message.push_str(
+                format!("\r\n  |white|coins sunk|-|: {}", format_coins(shop.sunk)).as_str(),
+            );
+
+            message.push_str("\r\n  |white|stock|-|:");
+            if shop.stock.is_empty() {
+                message.push_str(" none");
+            }
+            for (index, item) in shop.stock.iter().enumerate() {
+                let name = prototypes
+                    .by_id(item.prototype)
+                    .and_then(|entity| named_query.get(entity).ok())
+                    .map(|named| named.escaped())
+                    .unwrap_or_else(|| format!("(missing prototype {})", item.prototype));
+                message.push_str(
+                    format!(
+                        "\r\n    {}. {} x {} ({}, {})",
+                        index + 1,
+                        item.quantity,
+                        name,
+                        item.rarity,
+                        format_coins(shop.list_price(item.rarity))
+                    )
+                    .as_str(),
+                );
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ShopsList {
+    pub actor: Entity,
+}
+
+into_action!(ShopsList);
+
+#[tracing::instrument(name = "shops list system", skip_all, fields(action = "ShopsList"))]
+pub fn shops_list_system(
+    mut action_reader: EventReader<Action>,
+    shops: Res<Shops>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ShopsList(ShopsList { actor }) = action {
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                if shops.is_empty() {
+                    messages.queue("There are no shops.".to_string());
+                }
+
+                for (id, shop) in shops.iter() {
+                    messages.queue(format!(
+                        "|white|{}|-|\troom {}, {} items in stock, {} sunk",
+                        id,
+                        shop.room,
+                        shop.count(),
+                        format_coins(shop.sunk)
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ShopRemove {
+    pub actor: Entity,
+    pub id: ShopId,
+}
+
+into_action!(ShopRemove);
+
+#[tracing::instrument(name = "shop remove system", skip_all, fields(action = "ShopRemove"))]
+pub fn shop_remove_system(
+    mut action_reader: EventReader<Action>,
+    mut shops: ResMut<Shops>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ShopRemove(ShopRemove { actor, id }) = action {
+            let message = if shops.remove(id).is_some() {
+                updates.persist(persist::shop::Remove::new(id.clone()));
+                format!("Deleted shop {}.", id)
+            } else {
+                format!("Shop {} not found.", id)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ShopUpdate {
+    pub actor: Entity,
+    pub id: ShopId,
+    pub change: ShopChange,
+}
+
+into_action!(ShopUpdate);
+
+#[tracing::instrument(name = "shop update system", skip_all, fields(action = "ShopUpdate"))]
+pub fn shop_update_system(
+    mut action_reader: EventReader<Action>,
+    mut shops: ResMut<Shops>,
+    loot_tables: Res<LootTables>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ShopUpdate(ShopUpdate { actor, id, change }) = action {
+            let shop = match shops.get_mut(id) {
+                Some(shop) => shop,
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Shop {} not found.", id));
+                    }
+                    continue;
+                }
+            };
+
+            let result = match change {
+                ShopChange::Cap(cap) => {
+                    shop.cap = *cap;
+                    Ok(())
+                }
+                ShopChange::Interval(seconds) => {
+                    shop.set_interval(Duration::from_secs(*seconds));
+                    Ok(())
+                }
+                ShopChange::Loot(Some(table)) if !loot_tables.contains(table) => {
+                    Err(format!("Loot table {} does not exist.", table))
+                }
+                ShopChange::Loot(table) => {
+                    shop.loot_table = table.clone();
+                    Ok(())
+                }
+                ShopChange::Markup(markup) => {
+                    shop.markup = *markup;
+                    Ok(())
+                }
+                ShopChange::Restock => {
+                    match shop.loot_table.as_ref().and_then(|id| loot_tables.get(id)) {
+                        Some(table) => {
+                            shop.restock(table, &mut thread_rng());
+                            Ok(())
+                        }
+                        None => Err(format!("Shop {} has no loot table to restock from.", id)),
+                    }
+                }
+            };
+
+            let message = match result {
+                Ok(()) => {
+                    updates.persist(persist::shop::Update::new(id.clone(), shop));
+                    format!("Updated shop {}.", id)
+                }
+                Err(message) => message,
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+
+use crate::{
+    clock::Clock,
+    logging::GAME_LOG_TARGET,
+    text::{format_duration, Tokenizer},
+    world::{
+        action::{into_action, Action},
+        types::{
+            player::{self, Messages, Player, PlayerFlags, Players, Snoops},
+            Named,
+        },
+    },
+};
+
+// Valid shapes:
+// snoop <player> - shows the player's input and output to you
+// snoop approve <immortal> - allows another immortal's snoop
+// snoop off - stops snooping, or withdraws a request to snoop
+pub fn parse_snoop(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    match tokenizer.next() {
+        Some("approve") => {
+            if let Some(moderator) = tokenizer.next() {
+                Ok(Action::from(SnoopApprove {
+                    actor: player,
+                    moderator: moderator.to_string(),
+                }))
+            } else {
+                Err("Approve whose snoop?".to_string())
+            }
+        }
+        Some("off") => Ok(Action::from(SnoopStop { actor: player })),
+        Some(target) => Ok(Action::from(SnoopStart {
+            actor: player,
+            target: target.to_string(),
+        })),
+        None => Err("Snoop whom?".to_string()),
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct SnoopStart {
+    pub actor: Entity,
+    pub target: String,
+}
+
+into_action!(SnoopStart);
+
+#[tracing::instrument(name = "snoop start system", skip_all, fields(action = "SnoopStart"))]
+pub fn snoop_start_system(
+    mut action_reader: EventReader<Action>,
+    clock: Res<Clock>,
+    players: Res<Players>,
+    mut snoops: ResMut<Snoops>,
+    player_query: Query<(Entity, &Named, &PlayerFlags), With<Player>>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::SnoopStart(SnoopStart { actor, target }) = action {
+            let (name, admin) = if let Ok((_, named, flags)) = player_query.get(*actor) {
+                (named.to_string(), flags.contains(player::Flags::ADMIN))
+            } else {
+                continue;
+            };
+
+            let message = if let Some(snooped) = snoops.target(*actor) {
+                let snooped = player_query
+                    .get(snooped)
+                    .map(|(_, named, _)| named.to_string())
+                    .unwrap_or_default();
+                format!(
+                    "You are already snooping {}. Use \"snoop off\" first.",
+                    snooped
+                )
+            } else if let Some(target_entity) = players.by_name(target.as_str()) {
+                if target_entity == *actor {
+                    "You cannot snoop yourself.".to_string()
+                } else if snoops.requires_approval() && !admin {
+                    snoops.request(*actor, target_entity);
+
+                    tracing::info!(
+                        target: GAME_LOG_TARGET,
+                        player = name.as_str(),
+                        target = target.as_str(),
+                        "snoop requested"
+                    );
+
+                    let notice = format!(
+                        "|Gold1|{} asks to snoop {}. Use \"snoop approve {}\" to allow it.|-|",
+                        name, target, name
+                    );
+                    for (immortal, _, flags) in player_query.iter() {
+                        if immortal != *actor && flags.contains(player::Flags::IMMORTAL) {
+                            if let Ok(mut messages) = messages_query.get_mut(immortal) {
+                                messages.queue(notice.clone());
+                            }
+                        }
+                    }
+
+                    format!(
+                        "Your request to snoop {} awaits another immortal's approval.",
+                        target
+                    )
+                } else {
+                    snoops.start(*actor, target_entity, clock.now());
+
+                    tracing::info!(
+                        target: GAME_LOG_TARGET,
+                        player = name.as_str(),
+                        target = target.as_str(),
+                        "snoop started"
+                    );
+
+                    format!(
+                        "You are snooping {} for {}.",
+                        target,
+                        format_duration(snoops.duration().as_secs() as i64)
+                    )
+                }
+            } else {
+                format!("{} is not online.", target)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct SnoopApprove {
+    pub actor: Entity,
+    pub moderator: String,
+}
+
+into_action!(SnoopApprove);
+
+#[tracing::instrument(
+    name = "snoop approve system",
+    skip_all,
+    fields(action = "SnoopApprove")
+)]
+pub fn snoop_approve_system(
+    mut action_reader: EventReader<Action>,
+    clock: Res<Clock>,
+    players: Res<Players>,
+    mut snoops: ResMut<Snoops>,
+    named_query: Query<&Named, With<Player>>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::SnoopApprove(SnoopApprove { actor, moderator }) = action {
+            let approver = if let Ok(named) = named_query.get(*actor) {
+                named.to_string()
+            } else {
+                continue;
+            };
+
+            let moderator_entity = match players.by_name(moderator.as_str()) {
+                Some(entity) if entity == *actor => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue("You cannot approve your own snoop.".to_string());
+                    }
+                    continue;
+                }
+                Some(entity) => entity,
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("{} is not online.", moderator));
+                    }
+                    continue;
+                }
+            };
+
+            let (target, target_name) = match snoops
+                .take_request(moderator_entity)
+                .and_then(|target| named_query.get(target).ok().map(|named| (target, named)))
+            {
+                Some((target, named)) => (target, named.to_string()),
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("{} is not waiting to snoop anyone.", moderator));
+                    }
+                    continue;
+                }
+            };
+
+            snoops.start(moderator_entity, target, clock.now());
+
+            tracing::info!(
+                target: GAME_LOG_TARGET,
+                player = moderator.as_str(),
+                target = target_name.as_str(),
+                approver = approver.as_str(),
+                "snoop started"
+            );
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!(
+                    "You approve {}'s snoop of {}.",
+                    moderator, target_name
+                ));
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(moderator_entity) {
+                messages.queue(format!(
+                    "{} approved your snoop. You are snooping {} for {}.",
+                    approver,
+                    target_name,
+                    format_duration(snoops.duration().as_secs() as i64)
+                ));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct SnoopStop {
+    pub actor: Entity,
+}
+
+into_action!(SnoopStop);
+
+#[tracing::instrument(name = "snoop stop system", skip_all, fields(action = "SnoopStop"))]
+pub fn snoop_stop_system(
+    mut action_reader: EventReader<Action>,
+    mut snoops: ResMut<Snoops>,
+    named_query: Query<&Named, With<Player>>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::SnoopStop(SnoopStop { actor }) = action {
+            let name = named_query
+                .get(*actor)
+                .map(|named| named.to_string())
+                .unwrap_or_default();
+            let target_name = |target: Entity| {
+                named_query
+                    .get(target)
+                    .map(|named| named.to_string())
+                    .unwrap_or_default()
+            };
+
+            let message = if let Some(target) = snoops.stop(*actor) {
+                let target = target_name(target);
+                tracing::info!(
+                    target: GAME_LOG_TARGET,
+                    player = name.as_str(),
+                    target = target.as_str(),
+                    "snoop ended"
+                );
+                format!("You stop snooping {}.", target)
+            } else if let Some(target) = snoops.take_request(*actor) {
+                format!(
+                    "You withdraw your request to snoop {}.",
+                    target_name(target)
+                )
+            } else {
+                "You are not snooping anyone.".to_string()
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
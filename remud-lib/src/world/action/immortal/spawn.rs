@@ -0,0 +1,446 @@
+use std::{str::FromStr, time::Duration};
+
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+
+use crate::{
+    engine::persist::{self, Updates},
+    text::Tokenizer,
+    world::{
+        action::{into_action, Action},
+        types::{
+            object::{PrototypeId, Prototypes},
+            player::Messages,
+            room::{RoomId, Rooms},
+            spawn::{
+                SpawnTable, SpawnTableId, SpawnTables, DEFAULT_SPAWN_CAP,
+                DEFAULT_SPAWN_INTERVAL_SECS,
+            },
+            Named,
+        },
+    },
+};
+
+pub fn parse_spawn(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(token) = tokenizer.next() {
+        match token {
+            "list" => Ok(Action::from(SpawnTableList { actor: player })),
+            "new" => match tokenizer.next() {
+                Some(id) => Ok(Action::from(SpawnTableCreate {
+                    actor: player,
+                    id: SpawnTableId::from_str(id).map_err(|e| e.to_string())?,
+                })),
+                None => Err("Enter an ID for the new spawn table.".to_string()),
+            },
+            maybe_id => {
+                let id = SpawnTableId::from_str(maybe_id).map_err(|e| e.to_string())?;
+
+                let change = match tokenizer.next() {
+                    Some("add") => {
+                        let prototype = parse_prototype_id(tokenizer.next())?;
+                        let weight = match tokenizer.next() {
+                            Some(weight) => match weight.parse::<u32>() {
+                                Ok(weight) if weight > 0 => weight,
+                                _ => return Err("Enter a weight greater than 0.".to_string()),
+                            },
+                            None => 1,
+                        };
+                        SpawnTableChange::Add(prototype, weight)
+                    }
+                    Some("attach") => SpawnTableChange::Attach(parse_target(tokenizer)?),
+                    Some("cap") => match tokenizer.next().map(str::parse::<u32>) {
+                        Some(Ok(cap)) => SpawnTableChange::Cap(cap),
+                        _ => return Err("Enter a whole number cap.".to_string()),
+                    },
+                    Some("delete") => {
+                        return Ok(Action::from(SpawnTableRemove { actor: player, id }))
+                    }
+                    Some("detach") => SpawnTableChange::Detach(parse_target(tokenizer)?),
+                    Some("info") => return Ok(Action::from(SpawnTableInfo { actor: player, id })),
+                    Some("interval") => match tokenizer.next().map(str::parse::<u64>) {
+                        Some(Ok(seconds)) if seconds > 0 => SpawnTableChange::Interval(seconds),
+                        _ => return Err("Enter an interval in seconds greater than 0.".to_string()),
+                    },
+                    Some("remove") => {
+                        SpawnTableChange::Remove(parse_prototype_id(tokenizer.next())?)
+                    }
+                    Some(_) => {
+                        return Err("Enter a valid spawn table subcommand: add, attach, cap, \
+                                    delete, detach, info, interval, or remove."
+                            .to_string())
+                    }
+                    None => {
+                        return Err("Enter a spawn table subcommand: add, attach, cap, delete, \
+                                    detach, info, interval, or remove."
+                            .to_string())
+                    }
+                };
+
+                Ok(Action::from(SpawnTableUpdate {
+                    actor: player,
+                    id,
+                    change,
+                }))
+            }
+        }
+    } else {
+        Err("Enter a spawn table ID or subcommand: list or new.".to_string())
+    }
+}
+
+fn parse_prototype_id(token: Option<&str>) -> Result<PrototypeId, String> {
+    match token {
+        Some(id) => PrototypeId::from_str(id).map_err(|e| e.to_string()),
+        None => Err("Enter a prototype ID.".to_string()),
+    }
+}
+
+fn parse_target(mut tokenizer: Tokenizer) -> Result<SpawnTarget, String> {
+    match tokenizer.next() {
+        Some("room") => match tokenizer.next() {
+            Some(id) => Ok(SpawnTarget::Room(
+                RoomId::from_str(id).map_err(|e| e.to_string())?,
+            )),
+            None => Err("Enter a room ID.".to_string()),
+        },
+        Some("region") => match tokenizer.next() {
+            Some(region) => Ok(SpawnTarget::Region(region.to_string())),
+            None => Err("Enter a region.".to_string()),
+        },
+        _ => Err("Enter room <room ID> or region <region>.".to_string()),
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum SpawnTarget {
+    Room(RoomId),
+    Region(String),
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum SpawnTableChange {
+    // a prototype and its weight
+    Add(PrototypeId, u32),
+    Attach(SpawnTarget),
+    Cap(u32),
+    Detach(SpawnTarget),
+    // seconds between spawns
+    Interval(u64),
+    Remove(PrototypeId),
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct SpawnTableCreate {
+    pub actor: Entity,
+    pub id: SpawnTableId,
+}
+
+into_action!(SpawnTableCreate);
+
+#[tracing::instrument(
+    name = "spawn table create system",
+    skip_all,
+    fields(action = "SpawnTableCreate")
+)]
+pub fn spawn_table_create_system(
+    mut action_reader: EventReader<Action>,
+    mut spawn_tables: ResMut<SpawnTables>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::SpawnTableCreate(SpawnTableCreate { actor, id }) = action {
+            if spawn_tables.contains(id) {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(format!("Spawn table {} already exists.", id));
+                }
+                continue;
+            }
+
+            let table = SpawnTable::new(
+                DEFAULT_SPAWN_CAP,
+                Duration::from_secs(DEFAULT_SPAWN_INTERVAL_SECS),
+            );
+
+            updates.persist(persist::spawn::Update::new(id.clone(), &table));
+
+            spawn_tables.insert(id.clone(), table);
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!("Created spawn table {}.", id));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct SpawnTableInfo {
+    pub actor: Entity,
+    pub id: SpawnTableId,
+}
+
+into_action!(SpawnTableInfo);
+
+#[tracing::instrument(
+    name = "spawn table info system",
+    skip_all,
+    fields(action = "SpawnTableInfo")
+)]
+pub fn spawn_table_info_system(
+    mut action_reader: EventReader<Action>,
+    spawn_tables: Res<SpawnTables>,
+    prototypes: Res<Prototypes>,
+    named_query: Query<&Named>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::SpawnTableInfo(SpawnTableInfo { actor, id }) = action {
+            let table = match spawn_tables.get(id) {
+                Some(table) => table,
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Spawn table {} not found.", id));
+                    }
+                    continue;
+                }
+            };
+
+            let mut message = format!("|white|Spawn table {}|-|", id);
+
+            message.push_str(format!("\r\n  |white|cap|-|: {}", table.cap).as_str());
+            message.push_str(
+                format!(
+                    "\r\n  |white|interval|-|: {} seconds",
+                    table.interval().as_secs()
+                )
+                .as_str(),
+            );
+
+            message.push_str("\r\n  |white|prototypes|-|:");
+            if table.entries.is_empty() {
+                message.push_str(" none");
+            }
+            let total: u64 = table.entries.values().map(|weight| *weight as u64).sum();
+            for (prototype, weight) in table.entries.iter() {
+                let name = prototypes
+                    .by_id(*prototype)
+                    .and_then(|entity| named_query.get(entity).ok())
+                    .map(|named| named.escaped())
+                    .unwrap_or_else(|| "(missing)".to_string());
+                message.push_str(
+                    format!(
+                        "\r\n    {}: {} (weight {}, {:.0}%)",
+                        prototype,
+                        name,
+                        weight,
+                        *weight as f64 * 100.0 / total as f64
+                    )
+                    .as_str(),
+                );
+            }
+
+            message.push_str("\r\n  |white|rooms|-|: ");
+            if table.rooms.is_empty() {
+                message.push_str("none");
+            } else {
+                message.push_str(table.rooms.iter().join(", ").as_str());
+            }
+
+            message.push_str("\r\n  |white|regions|-|: ");
+            if table.regions.is_empty() {
+                message.push_str("none");
+            } else {
+                message.push_str(table.regions.iter().join(", ").replace('|', "||").as_str());
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct SpawnTableList {
+    pub actor: Entity,
+}
+
+into_action!(SpawnTableList);
+
+#[tracing::instrument(
+    name = "spawn table list system",
+    skip_all,
+    fields(action = "SpawnTableList")
+)]
+pub fn spawn_table_list_system(
+    mut action_reader: EventReader<Action>,
+    spawn_tables: Res<SpawnTables>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::SpawnTableList(SpawnTableList { actor }) = action {
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                if spawn_tables.is_empty() {
+                    messages.queue("There are no spawn tables.".to_string());
+                }
+
+                for (id, table) in spawn_tables.iter() {
+                    messages.queue(format!(
+                        "|white|{}|-|\t{} prototypes, cap {}, every {} seconds",
+                        id,
+                        table.entries.len(),
+                        table.cap,
+                        table.interval().as_secs()
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct SpawnTableRemove {
+    pub actor: Entity,
+    pub id: SpawnTableId,
+}
+
+into_action!(SpawnTableRemove);
+
+#[tracing::instrument(
+    name = "spawn table remove system",
+    skip_all,
+    fields(action = "SpawnTableRemove")
+)]
+pub fn spawn_table_remove_system(
+    mut action_reader: EventReader<Action>,
+    mut spawn_tables: ResMut<SpawnTables>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::SpawnTableRemove(SpawnTableRemove { actor, id }) = action {
+            let message = if spawn_tables.remove(id).is_some() {
+                updates.persist(persist::spawn::Remove::new(id.clone()));
+                format!("Deleted spawn table {}.", id)
+            } else {
+                format!("Spawn table {} not found.", id)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct SpawnTableUpdate {
+    pub actor: Entity,
+    pub id: SpawnTableId,
+    pub change: SpawnTableChange,
+}
+
+into_action!(SpawnTableUpdate);
+
+#[tracing::instrument(
+    name = "spawn table update system",
+    skip_all,
+    fields(action = "SpawnTableUpdate")
+)]
+pub fn spawn_table_update_system(
+    mut action_reader: EventReader<Action>,
+    mut spawn_tables: ResMut<SpawnTables>,
+    prototypes: Res<Prototypes>,
+    rooms: Res<Rooms>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::SpawnTableUpdate(SpawnTableUpdate { actor, id, change }) = action {
+            let table = match spawn_tables.get_mut(id) {
+                Some(table) => table,
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Spawn table {} not found.", id));
+                    }
+                    continue;
+                }
+            };
+
+            let result = match change {
+                SpawnTableChange::Add(prototype, weight) => {
+                    if prototypes.by_id(*prototype).is_some() {
+                        table.entries.insert(*prototype, *weight);
+                        Ok(())
+                    } else {
+                        Err(format!("Prototype {} does not exist.", prototype))
+                    }
+                }
+                SpawnTableChange::Attach(SpawnTarget::Room(room)) => {
+                    if rooms.exists(*room) {
+                        table.rooms.insert(*room);
+                        Ok(())
+                    } else {
+                        Err(format!("Room {} does not exist.", room))
+                    }
+                }
+                SpawnTableChange::Attach(SpawnTarget::Region(region)) => {
+                    table.regions.insert(region.clone());
+                    Ok(())
+                }
+                SpawnTableChange::Cap(cap) => {
+                    table.cap = *cap;
+                    Ok(())
+                }
+                SpawnTableChange::Detach(SpawnTarget::Room(room)) => {
+                    if table.rooms.remove(room) {
+                        Ok(())
+                    } else {
+                        Err(format!(
+                            "Spawn table {} is not attached to room {}.",
+                            id, room
+                        ))
+                    }
+                }
+                SpawnTableChange::Detach(SpawnTarget::Region(region)) => {
+                    if table.regions.remove(region) {
+                        Ok(())
+                    } else {
+                        Err(format!(
+                            "Spawn table {} is not attached to region {}.",
+                            id,
+                            region.replace('|', "||")
+                        ))
+                    }
+                }
+                SpawnTableChange::Interval(seconds) => {
+                    table.set_interval(Duration::from_secs(*seconds));
+                    Ok(())
+                }
+                SpawnTableChange::Remove(prototype) => {
+                    if table.entries.remove(prototype).is_some() {
+                        Ok(())
+                    } else {
+                        Err(format!(
+                            "Prototype {} is not in spawn table {}.",
+                            prototype, id
+                        ))
+                    }
+                }
+            };
+
+            let message = match result {
+                Ok(()) => {
+                    updates.persist(persist::spawn::Update::new(id.clone(), table));
+                    format!("Updated spawn table {}.", id)
+                }
+                Err(message) => message,
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
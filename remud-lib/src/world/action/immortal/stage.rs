@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+
+use crate::{
+    engine::persist::{self, Updates},
+    text::Tokenizer,
+    world::{
+        action::{into_action, Action},
+        types::{
+            player::Messages,
+            room::{
+                LoadedRegions, Regions, Room, RoomSnapshot, Shadow, ShadowBundle, Stage, Staging,
+            },
+            Description, Named,
+        },
+    },
+};
+
+// Valid shapes:
+// stage <region> - stages all rooms in the region for editing
+// stage discard - throws away all staged changes
+pub fn parse_stage(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(token) = tokenizer.next() {
+        match token.to_lowercase().as_str() {
+            "discard" => Ok(Action::from(StageDiscard { actor: player })),
+            _ => Ok(Action::from(StageRegion {
+                actor: player,
+                region: token.to_string(),
+            })),
+        }
+    } else {
+        Err("Enter a region to stage.".to_string())
+    }
+}
+
+// Valid shapes:
+// publish - publishes the staged region unless its live rooms changed where it did
+// publish force - publishes the staged region over any changes to its live rooms
+pub fn parse_publish(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    match tokenizer.next() {
+        None => Ok(Action::from(Publish {
+            actor: player,
+            force: false,
+        })),
+        Some(token) if token.eq_ignore_ascii_case("force") => Ok(Action::from(Publish {
+            actor: player,
+            force: true,
+        })),
+        Some(_) => Err("Enter \"publish\" or \"publish force\".".to_string()),
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Publish {
+    pub actor: Entity,
+    pub force: bool,
+}
+
+into_action!(Publish);
+
+#[tracing::instrument(name = "publish system", skip_all, fields(action = "Publish"))]
+pub fn publish_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    mut staging: ResMut<Staging>,
+    mut updates: ResMut<Updates>,
+    mut room_set: QuerySet<(
+        Query<(&Shadow, &Named, &Description)>,
+        Query<(&Room, &Named, &Description)>,
+        Query<(&mut Room, &mut Named, &mut Description)>,
+    )>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Publish(Publish { actor, force }) = action {
+            let stage = if let Some(stage) = staging.remove(*actor) {
+                stage
+            } else {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue("You are not staging a region.".to_string());
+                }
+                continue;
+            };
+
+            let staged = stage
+                .shadows()
+                .filter_map(|shadow| {
+                    room_set
+                        .q0()
+                        .get(shadow)
+                        .map(|(shadow, named, description)| {
+                            (
+                                shadow.room(),
+                                shadow.base().clone(),
+                                RoomSnapshot {
+                                    name: named.to_string(),
+                                    description: description.to_string(),
+                                    exits: shadow.exits().clone(),
+                                },
+                            )
+                        })
+                        .ok()
+                })
+                .collect_vec();
+
+            // Rooms removed since staging began are skipped.
+            let mut merged = Vec::new();
+            let mut conflicts = Vec::new();
+            for (room_entity, base, staged) in staged {
+                let (room, named, description) = match room_set.q1().get(room_entity) {
+                    Ok(room) => room,
+                    Err(_) => continue,
+                };
+
+                let live = RoomSnapshot {
+                    name: named.to_string(),
+                    description: description.to_string(),
+                    exits: room.exits().clone(),
+                };
+
+                match merge(&base, live, staged, *force) {
+                    Ok(snapshot) => merged.push((room_entity, snapshot)),
+                    Err(parts) => conflicts.push(format!("room {} ({})", room.id(), parts)),
+                }
+            }
+
+            if !conflicts.is_empty() {
+                let message = format!(
+                    "Changes were made to the live {} since you staged it. Publish with \
+                     \"publish force\" to replace them, or throw away your changes with \
+                     \"stage discard\".",
+                    conflicts.join(", ")
+                );
+                staging.insert(*actor, stage);
+
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(message);
+                }
+                continue;
+            }
+
+            let mut published = Vec::new();
+            for (room_entity, snapshot) in merged {
+                // Exits to rooms removed since staging began are dropped.
+                let exits = snapshot
+                    .exits
+                    .into_iter()
+                    .filter_map(|(direction, exit)| {
+                        room_set
+                            .q1()
+                            .get(exit.destination)
+                            .map(|(room, _, _)| (direction, exit, room.id()))
+                            .ok()
+                    })
+                    .collect_vec();
+
+                let (mut room, mut room_name, mut room_description) =
+                    room_set.q2_mut().get_mut(room_entity).unwrap();
+
+                room_name.set_name(snapshot.name.clone());
+                room_description.set_text(snapshot.description.clone());
+
+                let directions = room.exits().keys().cloned().collect_vec();
+                for direction in directions {
+                    room.remove_exit(&direction);
+                }
+                for (direction, exit, _) in exits.iter() {
+                    room.insert_exit(direction.clone(), exit.clone());
+                }
+
+                published.push(persist::room::Published::new(
+                    room.id(),
+                    snapshot.name,
+                    snapshot.description,
+                    exits
+                        .into_iter()
+                        .map(|(direction, exit, id)| (direction, id, exit))
+                        .collect_vec(),
+                ));
+            }
+
+            for shadow in stage.shadows() {
+                commands.entity(shadow).despawn();
+            }
+
+            let count = published.len();
+            updates.persist(persist::room::Publish::new(published));
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!(
+                    "Published {} staged rooms in region {}.",
+                    count,
+                    stage.region()
+                ));
+            }
+        }
+    }
+}
+
+// Merges a staged room into the live room as it is now. Each part of the room the builder left
+// alone keeps any change made to the live room since staging. A part changed both ways is a
+// conflict, named in the error, unless forced, when the staged change wins.
+fn merge(
+    base: &RoomSnapshot,
+    live: RoomSnapshot,
+    staged: RoomSnapshot,
+    force: bool,
+) -> Result<RoomSnapshot, String> {
+    let mut conflicts = Vec::new();
+
+    let name = merge_part(&base.name, live.name, staged.name, force).unwrap_or_else(|name| {
+        conflicts.push("name".to_string());
+        name
+    });
+    let description = merge_part(
+        &base.description,
+        live.description,
+        staged.description,
+        force,
+    )
+    .unwrap_or_else(|description| {
+        conflicts.push("description".to_string());
+        description
+    });
+
+    let directions = live
+        .exits
+        .keys()
+        .chain(staged.exits.keys())
+        .cloned()
+        .unique()
+        .sorted_by_key(ToString::to_string)
+        .collect_vec();
+
+    let mut exits = HashMap::new();
+    for direction in directions {
+        let exit = merge_part(
+            &base.exits.get(&direction).cloned(),
+            live.exits.get(&direction).cloned(),
+            staged.exits.get(&direction).cloned(),
+            force,
+        )
+        .unwrap_or_else(|exit| {
+            conflicts.push(format!("{} exit", direction));
+            exit
+        });
+
+        if let Some(exit) = exit {
+            exits.insert(direction, exit);
+        }
+    }
+
+    if conflicts.is_empty() {
+        Ok(RoomSnapshot {
+            name,
+            description,
+            exits,
+        })
+    } else {
+        Err(conflicts.join(", "))
+    }
+}
+
+// Takes the staged value if it changed, unless the live value changed differently, which is
+// returned as the error.
+fn merge_part<T: PartialEq>(base: &T, live: T, staged: T, force: bool) -> Result<T, T> {
+    if staged == *base {
+        Ok(live)
+    } else if force || live == *base || live == staged {
+        Ok(staged)
+    } else {
+        Err(staged)
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct StageDiscard {
+    pub actor: Entity,
+}
+
+into_action!(StageDiscard);
+
+#[tracing::instrument(
+    name = "stage discard system",
+    skip_all,
+    fields(action = "StageDiscard")
+)]
+pub fn stage_discard_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    mut staging: ResMut<Staging>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::StageDiscard(StageDiscard { actor }) = action {
+            let message = if let Some(stage) = staging.remove(*actor) {
+                for shadow in stage.shadows() {
+                    commands.entity(shadow).despawn();
+                }
+
+                format!("Discarded staged changes to region {}.", stage.region())
+            } else {
+                "You are not staging a region.".to_string()
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct StageRegion {
+    pub actor: Entity,
+    pub region: String,
+}
+
+into_action!(StageRegion);
+
+#[tracing::instrument(name = "stage region system", skip_all, fields(action = "StageRegion"))]
+pub fn stage_region_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    loaded: Res<LoadedRegions>,
+    mut staging: ResMut<Staging>,
+    mut updates: ResMut<Updates>,
+    room_query: Query<(Entity, &Room, &Named, &Description, &Regions)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::StageRegion(StageRegion { actor, region }) = action {
+            if let Some(stage) = staging.by_builder(*actor) {
+                let message = format!(
+                    "You are already staging region {}. Publish or discard it first.",
+                    stage.region()
+                );
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(message);
+                }
+                continue;
+            }
+
+            // Every room of the region is staged, so it is loaded first.
+            if !loaded.is_loaded(region.as_str()) {
+                updates.load_region(region.clone(), action.clone());
+                continue;
+            }
+
+            let rooms = room_query
+                .iter()
+                .filter(|(_, _, _, _, regions)| regions.contains(region))
+                .collect_vec();
+
+            let error = if rooms.is_empty() {
+                Some(format!("There are no rooms in region {}.", region))
+            } else if staging.by_region(region.as_str()).is_some()
+                || rooms
+                    .iter()
+                    .any(|(entity, _, _, _, _)| staging.is_staged(*entity))
+            {
+                Some(format!(
+                    "Rooms in region {} are already being staged.",
+                    region
+                ))
+            } else {
+                None
+            };
+
+            if let Some(error) = error {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(error);
+                }
+                continue;
+            }
+
+            let mut shadows = HashMap::new();
+            for (entity, room, named, description, _) in rooms.iter() {
+                let shadow = commands
+                    .spawn_bundle(ShadowBundle {
+                        shadow: Shadow::new(
+                            *entity,
+                            RoomSnapshot {
+                                name: named.to_string(),
+                                description: description.to_string(),
+                                exits: room.exits().clone(),
+                            },
+                        ),
+                        name: (*named).clone(),
+                        description: (*description).clone(),
+                    })
+                    .id();
+                shadows.insert(*entity, shadow);
+            }
+
+            staging.insert(*actor, Stage::new(region.clone(), shadows));
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!(
+                    "Staged {} rooms in region {}. Changes will not be visible to players until \
+                     published.",
+                    rooms.len(),
+                    region
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+
+use crate::{
+    engine::persist::{self, Updates},
+    text::{Table, Tokenizer, DEFAULT_WIDTH},
+    world::{
+        action::{into_action, Action},
+        types::{
+            player::{ClientCaps, Messages},
+            room::{RoomId, Rooms},
+            tutorial::{HintTrigger, HintTriggerParseError, Tutorial},
+        },
+    },
+};
+
+pub fn parse_tutorial(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    match tokenizer.next() {
+        None => Ok(Action::from(TutorialInfo { actor: player })),
+        Some("start") => match tokenizer.next() {
+            Some("clear") => Ok(Action::from(TutorialStart {
+                actor: player,
+                room: None,
+            })),
+            Some(token) => match token.parse::<RoomId>() {
+                Ok(room_id) => Ok(Action::from(TutorialStart {
+                    actor: player,
+                    room: Some(room_id),
+                })),
+                Err(e) => Err(e.to_string()),
+            },
+            None => Err("Enter a room ID or \"clear\".".to_string()),
+        },
+        Some("hint") => {
+            let trigger = match tokenizer.next() {
+                Some(token) => token.parse::<HintTrigger>().map_err(|e| e.to_string())?,
+                None => return Err(HintTriggerParseError {}.to_string()),
+            };
+
+            let message = match tokenizer.rest() {
+                "" => return Err("Enter a hint or \"clear\".".to_string()),
+                "clear" => None,
+                message => Some(message.to_string()),
+            };
+
+            Ok(Action::from(TutorialHint {
+                actor: player,
+                trigger,
+                message,
+            }))
+        }
+        Some(_) => Err("Enter a valid tutorial subcommand: start or hint.".to_string()),
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct TutorialInfo {
+    pub actor: Entity,
+}
+
+into_action!(TutorialInfo);
+
+#[tracing::instrument(
+    name = "tutorial info system",
+    skip_all,
+    fields(action = "TutorialInfo")
+)]
+pub fn tutorial_info_system(
+    mut action_reader: EventReader<Action>,
+    tutorial: Res<Tutorial>,
+    caps_query: Query<&ClientCaps>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::TutorialInfo(TutorialInfo { actor }) = action {
+            let width = caps_query
+                .get(*actor)
+                .map(ClientCaps::width)
+                .unwrap_or(DEFAULT_WIDTH);
+            let mut fields = Table::fields(width);
+
+            match tutorial.start() {
+                Some(room) => fields.field("start", format!("room {}", room)),
+                None => fields.field("start", "none".to_string()),
+            }
+
+            let hints = tutorial
+                .hints()
+                .map(|(trigger, message)| format!("{}: {}", trigger, message.replace('|', "||")))
+                .collect_vec();
+            fields.list_field("hints", hints);
+
+            let message = format!("|white|Tutorial|-|\r\n{}", fields.render());
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct TutorialStart {
+    pub actor: Entity,
+    pub room: Option<RoomId>,
+}
+
+into_action!(TutorialStart);
+
+#[tracing::instrument(
+    name = "tutorial start system",
+    skip_all,
+    fields(action = "TutorialStart")
+)]
+pub fn tutorial_start_system(
+    mut action_reader: EventReader<Action>,
+    mut tutorial: ResMut<Tutorial>,
+    rooms: Res<Rooms>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::TutorialStart(TutorialStart { actor, room }) = action {
+            let message = match room {
+                Some(room) if !rooms.exists(*room) => {
+                    format!("Room {} doesn't exist.", room)
+                }
+                _ => {
+                    tutorial.set_start(*room);
+                    updates.persist(persist::tutorial::Start::new(*room));
+
+                    match room {
+                        Some(room) => format!("New characters will begin in room {}.", room),
+                        None => "New characters will begin in the spawn room.".to_string(),
+                    }
+                }
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct TutorialHint {
+    pub actor: Entity,
+    pub trigger: HintTrigger,
+    pub message: Option<String>,
+}
+
+into_action!(TutorialHint);
+
+#[tracing::instrument(
+    name = "tutorial hint system",
+    skip_all,
+    fields(action = "TutorialHint")
+)]
+pub fn tutorial_hint_system(
+    mut action_reader: EventReader<Action>,
+    mut tutorial: ResMut<Tutorial>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::TutorialHint(TutorialHint {
+            actor,
+            trigger,
+            message,
+        }) = action
+        {
+            let reply = match message {
+                Some(message) => {
+                    tutorial.set_hint(*trigger, message.clone());
+                    updates.persist(persist::tutorial::Hint::new(*trigger, message.clone()));
+                    format!("Updated the {} hint.", trigger)
+                }
+                None => {
+                    if tutorial.remove_hint(*trigger) {
+                        updates.persist(persist::tutorial::RemoveHint::new(*trigger));
+                        format!("Removed the {} hint.", trigger)
+                    } else {
+                        format!("There is no {} hint.", trigger)
+                    }
+                }
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(reply);
+            }
+        }
+    }
+}
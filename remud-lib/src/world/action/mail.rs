@@ -0,0 +1,598 @@
+use std::str::FromStr;
+
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+
+use crate::{
+    clock::Clock,
+    engine::persist::{self, UpdateGroup, Updates},
+    text::{format_duration, Tokenizer},
+    world::{
+        action::{bank::Transfer, into_action, object::carry_limit, Action},
+        mail::{describe_attachments, return_mail},
+        types::{
+            bank::{format_coins, Bank},
+            mail::{Mail, MailId, Mailbox},
+            object::{Bulk, Keywords, Object, Objects},
+            player::{Messages, Player, Players},
+            room::Rooms,
+            Attributes, Contents, Location, Named,
+        },
+        VOID_ROOM_ID,
+    },
+};
+
+// Valid shapes:
+// mail [list] - lists the mail you have received
+// mail read <id> - reads a letter
+// mail send <player> <message> - sends a letter
+// mail send <player> with <amount || keywords>[: <message>] - sends coins or an object
+// mail claim <id> - takes the coins and object attached to a letter
+// mail delete <id> - deletes a letter, returning anything unclaimed to its sender
+pub fn parse_mail(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    match tokenizer.next() {
+        None | Some("list") => Ok(Action::from(MailList { actor: player })),
+        Some("claim") => Ok(Action::from(MailClaim {
+            actor: player,
+            id: parse_mail_id(tokenizer.next())?,
+        })),
+        Some("delete") => Ok(Action::from(MailDelete {
+            actor: player,
+            id: parse_mail_id(tokenizer.next())?,
+        })),
+        Some("read") => Ok(Action::from(MailRead {
+            actor: player,
+            id: parse_mail_id(tokenizer.next())?,
+        })),
+        Some("send") => {
+            let recipient = match tokenizer.next() {
+                Some(recipient) => recipient.to_string(),
+                None => return Err("Send mail to whom?".to_string()),
+            };
+
+            let (attachment, body) = match tokenizer.rest().strip_prefix("with ") {
+                Some(rest) => {
+                    let (attachment, body) = rest.split_once(':').unwrap_or((rest, ""));
+                    (Some(parse_attachment(attachment.trim())?), body.trim())
+                }
+                None => (None, tokenizer.rest()),
+            };
+
+            if attachment.is_none() && body.is_empty() {
+                return Err(format!("Send what to {}?", recipient));
+            }
+
+            Ok(Action::from(MailSend {
+                actor: player,
+                recipient,
+                attachment,
+                body: body.to_string(),
+            }))
+        }
+        Some(_) => {
+            Err("Enter a valid mail subcommand: claim, delete, list, read, or send.".to_string())
+        }
+    }
+}
+
+fn parse_mail_id(token: Option<&str>) -> Result<MailId, String> {
+    match token {
+        Some(token) => MailId::from_str(token).map_err(|e| e.to_string()),
+        None => Err("Enter a mail ID.".to_string()),
+    }
+}
+
+fn parse_attachment(attachment: &str) -> Result<Transfer, String> {
+    if attachment.is_empty() {
+        return Err("Attach what?".to_string());
+    }
+
+    match attachment.parse::<i64>() {
+        Ok(amount) if amount > 0 => Ok(Transfer::Coins(amount)),
+        Ok(_) => Err("You can only attach a positive number of coins.".to_string()),
+        Err(_) => Ok(Transfer::Object(
+            attachment
+                .split_whitespace()
+                .map(ToString::to_string)
+                .collect_vec(),
+        )),
+    }
+}
+
+// Finds mail a player has received, or the message to show them when they have not.
+fn received<'a>(mailbox: &'a Mailbox, id: MailId, player: &Player) -> Result<&'a Mail, String> {
+    match mailbox.get(id) {
+        Some(mail) if mail.recipient == player.id() => Ok(mail),
+        _ => Err(format!("You have no mail {}.", id)),
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct MailClaim {
+    pub actor: Entity,
+    pub id: MailId,
+}
+
+into_action!(MailClaim);
+
+#[tracing::instrument(name = "mail claim system", skip_all, fields(action = "MailClaim"))]
+pub fn mail_claim_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    mut mailbox: ResMut<Mailbox>,
+    mut bank: ResMut<Bank>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<(&Player, &Named)>,
+    object_query: Query<(&Object, &Named)>,
+    mut contents_query: Query<&mut Contents, With<Player>>,
+    attributes_query: Query<&Attributes>,
+    prototype_query: Query<&Object>,
+    bulk_query: Query<&Bulk>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::MailClaim(MailClaim { actor, id }) = action {
+            let (player, name) = match player_query.get(*actor) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+
+            let (coins, object) = match received(&mailbox, *id, player) {
+                Ok(mail) if mail.has_attachments() => (mail.coins, mail.object),
+                Ok(_) => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Mail {} has nothing attached.", id));
+                    }
+                    continue;
+                }
+                Err(message) => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(message);
+                    }
+                    continue;
+                }
+            };
+
+            let object = match object.map(|object| object_query.get(object).map(|o| (object, o))) {
+                Some(Ok((object, (found, named)))) => Some((object, found.id(), named.to_string())),
+                Some(Err(_)) => continue,
+                None => None,
+            };
+
+            if let Some((object, _, object_name)) = object.as_ref() {
+                if let Some(message) = contents_query.get_mut(*actor).ok().and_then(|carried| {
+                    carry_limit(
+                        *actor,
+                        carried.objects(),
+                        *object,
+                        object_name.as_str(),
+                        &attributes_query,
+                        &prototype_query,
+                        &bulk_query,
+                    )
+                }) {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(message);
+                    }
+                    continue;
+                }
+            }
+
+            let mut update = UpdateGroup::new(vec![persist::mail::Claim::new(*id)]);
+            let mut claimed = Vec::new();
+
+            if coins > 0 {
+                let account = bank.open(player.id(), name.as_str());
+                account.coins += coins;
+                update.append(persist::bank::Account::new(
+                    player.id(),
+                    account.coins,
+                    account.balance,
+                ));
+                claimed.push(format_coins(coins));
+            }
+
+            if let Some((object, object_id, object_name)) = object {
+                if let Ok(mut contents) = contents_query.get_mut(*actor) {
+                    contents.insert(object);
+                }
+                commands.entity(object).insert(Location::from(*actor));
+                update.append(persist::player::AddObject::new(player.id(), object_id));
+                claimed.push(object_name);
+            }
+
+            let mail = mailbox.get_mut(*id).unwrap();
+            mail.coins = 0;
+            mail.object = None;
+
+            updates.persist(update);
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!(
+                    "You claim {} from mail {}.",
+                    claimed.join(" and "),
+                    id
+                ));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct MailDelete {
+    pub actor: Entity,
+    pub id: MailId,
+}
+
+into_action!(MailDelete);
+
+#[tracing::instrument(name = "mail delete system", skip_all, fields(action = "MailDelete"))]
+pub fn mail_delete_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    mut mailbox: ResMut<Mailbox>,
+    mut bank: ResMut<Bank>,
+    players: Res<Players>,
+    mut objects: ResMut<Objects>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<(&Player, &Named)>,
+    object_query: Query<&Object>,
+    named_query: Query<&Named>,
+    mut contents_query: Query<&mut Contents, With<Player>>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::MailDelete(MailDelete { actor, id }) = action {
+            let (player, name) = match player_query.get(*actor) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+
+            if let Err(message) = received(&mailbox, *id, player) {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(message);
+                }
+                continue;
+            }
+
+            let mail = mailbox.remove(*id).unwrap();
+
+            let message = match describe_attachments(&mail, &named_query) {
+                Some(attachments) => {
+                    let sender = return_mail(
+                        *id,
+                        &mail,
+                        &mut commands,
+                        &mut bank,
+                        &players,
+                        &mut objects,
+                        &mut updates,
+                        &object_query,
+                        &mut contents_query,
+                    );
+
+                    if let Some(mut messages) =
+                        sender.and_then(|sender| messages_query.get_mut(sender).ok())
+                    {
+                        messages.queue(format!(
+                            "{} deleted your mail, returning {} to you.",
+                            name, attachments
+                        ));
+                    }
+
+                    format!(
+                        "You delete mail {}, returning {} to {}.",
+                        id, attachments, mail.sender_name
+                    )
+                }
+                None => {
+                    updates.persist(persist::mail::Remove::new(*id));
+                    format!("You delete mail {}.", id)
+                }
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct MailList {
+    pub actor: Entity,
+}
+
+into_action!(MailList);
+
+#[tracing::instrument(name = "mail list system", skip_all, fields(action = "MailList"))]
+pub fn mail_list_system(
+    mut action_reader: EventReader<Action>,
+    clock: Res<Clock>,
+    mailbox: Res<Mailbox>,
+    player_query: Query<&Player>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::MailList(MailList { actor }) = action {
+            let player = match player_query.get(*actor) {
+                Ok(player) => player.id(),
+                Err(_) => continue,
+            };
+
+            let now = clock.unix_now();
+            let mut message = "|white|Mail|-|".to_string();
+            let mut empty = true;
+
+            for (id, mail) in mailbox.received(player) {
+                empty = false;
+
+                message.push_str(
+                    format!(
+                        "\r\n  |white|{}|-|. from {}, {} ago",
+                        id,
+                        mail.sender_name,
+                        format_duration((now - mail.sent_at).max(0))
+                    )
+                    .as_str(),
+                );
+
+                if mail.has_attachments() {
+                    message.push_str(", with attachments");
+                }
+
+                if !mail.read {
+                    message.push_str(" |SteelBlue3|(unread)|-|");
+                }
+            }
+
+            if empty {
+                message = "You have no mail.".to_string();
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct MailRead {
+    pub actor: Entity,
+    pub id: MailId,
+}
+
+into_action!(MailRead);
+
+#[tracing::instrument(name = "mail read system", skip_all, fields(action = "MailRead"))]
+pub fn mail_read_system(
+    mut action_reader: EventReader<Action>,
+    clock: Res<Clock>,
+    mut mailbox: ResMut<Mailbox>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<&Player>,
+    named_query: Query<&Named>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::MailRead(MailRead { actor, id }) = action {
+            let player = match player_query.get(*actor) {
+                Ok(player) => player,
+                Err(_) => continue,
+            };
+
+            let (message, unread) = match received(&mailbox, *id, player) {
+                Ok(mail) => {
+                    let mut message = format!(
+                        "|white|Mail {} from {}|-|, sent {} ago",
+                        id,
+                        mail.sender_name,
+                        format_duration((clock.unix_now() - mail.sent_at).max(0))
+                    );
+
+                    if !mail.body.is_empty() {
+                        message.push_str(format!("\r\n{}", mail.body).as_str());
+                    }
+
+                    if let Some(attachments) = describe_attachments(mail, &named_query) {
+                        message.push_str(
+                            format!(
+                                "\r\n|white|Attached|-|: {}. Type \"mail claim {}\" to claim it.",
+                                attachments, id
+                            )
+                            .as_str(),
+                        );
+                    }
+
+                    (message, !mail.read)
+                }
+                Err(message) => (message, false),
+            };
+
+            if unread {
+                mailbox.get_mut(*id).unwrap().read = true;
+                updates.persist(persist::mail::Read::new(*id));
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct MailSend {
+    pub actor: Entity,
+    pub recipient: String,
+    pub attachment: Option<Transfer>,
+    pub body: String,
+}
+
+into_action!(MailSend);
+
+// Sends mail to a player, online or not. Attached coins and objects are taken from the sender and
+// held in escrow until the recipient claims them.
+#[tracing::instrument(name = "mail send system", skip_all, fields(action = "MailSend"))]
+pub fn mail_send_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    clock: Res<Clock>,
+    mut mailbox: ResMut<Mailbox>,
+    mut bank: ResMut<Bank>,
+    players: Res<Players>,
+    rooms: Res<Rooms>,
+    mut updates: ResMut<Updates>,
+    mut player_query: Query<(&Player, &Named, &mut Contents)>,
+    object_query: Query<(&Object, &Named, &Keywords)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::MailSend(MailSend {
+            actor,
+            recipient: recipient_name,
+            attachment,
+            body,
+        }) = action
+        {
+            let (player, sender_name, mut contents) = match player_query.get_mut(*actor) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+
+            let recipient = match mailbox.address(recipient_name.as_str()) {
+                Some(recipient) if recipient == player.id() => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue("You cannot send mail to yourself.".to_string());
+                    }
+                    continue;
+                }
+                Some(recipient) => recipient,
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("There is no player named {}.", recipient_name));
+                    }
+                    continue;
+                }
+            };
+
+            let mut update = UpdateGroup::new(vec![]);
+            let mut coins = 0;
+            let mut object = None;
+            let mut attached = None;
+
+            match attachment {
+                Some(Transfer::Coins(amount)) => {
+                    let account = bank.open(player.id(), sender_name.as_str());
+
+                    if account.coins < *amount {
+                        let message =
+                            format!("You are only carrying {}.", format_coins(account.coins));
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(message);
+                        }
+                        continue;
+                    }
+
+                    account.coins -= amount;
+                    update.append(persist::bank::Account::new(
+                        player.id(),
+                        account.coins,
+                        account.balance,
+                    ));
+                    coins = *amount;
+                    attached = Some(format_coins(coins));
+                }
+                Some(Transfer::Object(keywords)) => {
+                    let target = contents.find(|object| {
+                        object_query
+                            .get(object)
+                            .map(|(_, _, object_keywords)| {
+                                object_keywords.contains_all(keywords.as_slice())
+                            })
+                            .unwrap_or(false)
+                    });
+
+                    let target = match target {
+                        Some(target) => target,
+                        None => {
+                            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                                messages
+                                    .queue(format!("You don't have \"{}\".", keywords.join(" ")));
+                            }
+                            continue;
+                        }
+                    };
+
+                    let (object_id, name) = object_query
+                        .get(target)
+                        .map(|(object, named, _)| (object.id(), named.to_string()))
+                        .unwrap();
+
+                    // Attached objects are held in the void room, out of sight of everyone.
+                    let void_room = rooms.by_id(*VOID_ROOM_ID).unwrap();
+
+                    contents.remove(target);
+                    commands.entity(target).insert(Location::from(void_room));
+
+                    update.append(persist::player::RemoveObject::new(player.id(), object_id));
+                    object = Some((target, object_id));
+                    attached = Some(name);
+                }
+                None => (),
+            }
+
+            let id = mailbox.next_id();
+            let now = clock.unix_now();
+            let expires_at = now + mailbox.expiry().as_secs() as i64;
+
+            let mail = Mail {
+                sender: player.id(),
+                sender_name: sender_name.to_string(),
+                recipient,
+                recipient_name: recipient_name.clone(),
+                body: body.clone(),
+                coins,
+                object: object.map(|(object, _)| object),
+                sent_at: now,
+                expires_at,
+                read: false,
+            };
+
+            update.append(persist::mail::Create::new(
+                id,
+                player.id(),
+                recipient,
+                body.clone(),
+                coins,
+                object.map(|(_, object_id)| object_id),
+                now,
+                expires_at,
+            ));
+            updates.persist(update);
+
+            mailbox.insert(id, mail);
+
+            let message = match attached {
+                Some(attached) => format!("You send {} to {} by mail.", attached, recipient_name),
+                None => format!("You send mail to {}.", recipient_name),
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+
+            if let Some(mut messages) = players
+                .by_id(recipient)
+                .and_then(|recipient| messages_query.get_mut(recipient).ok())
+            {
+                messages.queue(format!(
+                    "You have new mail from {}. Type \"mail\" to see it.",
+                    sender_name
+                ));
+            }
+        }
+    }
+}
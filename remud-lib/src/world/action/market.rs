@@ -0,0 +1,430 @@
+use std::str::FromStr;
+
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+
+use crate::{
+    clock::Clock,
+    engine::persist::{self, UpdateGroup, Updates},
+    text::{format_duration, Tokenizer},
+    world::{
+        action::{into_action, object::carry_limit, Action},
+        market::return_listing,
+        types::{
+            bank::{format_coins, Bank},
+            market::{Listing, ListingId, Market},
+            object::{Bulk, Keywords, Object, Objects},
+            player::{Messages, Player, Players},
+            room::Rooms,
+            Attributes, Contents, Location, Named,
+        },
+        VOID_ROOM_ID,
+    },
+};
+
+// Valid shapes:
+// market [list] - lists the objects for sale
+// market sell <price> <keywords> - lists a carried object for sale
+// market buy <listing id> - buys a listed object
+// market cancel <listing id> - takes back an object you listed
+pub fn parse_market(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    match tokenizer.next() {
+        None | Some("list") => Ok(Action::from(MarketList { actor: player })),
+        Some("buy") => Ok(Action::from(MarketBuy {
+            actor: player,
+            id: parse_listing_id(tokenizer.next())?,
+        })),
+        Some("cancel") => Ok(Action::from(MarketCancel {
+            actor: player,
+            id: parse_listing_id(tokenizer.next())?,
+        })),
+        Some("sell") => {
+            let price = match tokenizer.next().map(str::parse::<i64>) {
+                Some(Ok(price)) if price > 0 => price,
+                Some(_) => return Err("The price must be a positive number of coins.".to_string()),
+                None => return Err("Enter a price and the item to sell.".to_string()),
+            };
+
+            if tokenizer.rest().is_empty() {
+                return Err("Sell what?".to_string());
+            }
+
+            Ok(Action::from(MarketSell {
+                actor: player,
+                price,
+                keywords: tokenizer
+                    .rest()
+                    .split_whitespace()
+                    .map(ToString::to_string)
+                    .collect_vec(),
+            }))
+        }
+        Some(_) => Err("Enter a valid market subcommand: buy, cancel, list, or sell.".to_string()),
+    }
+}
+
+fn parse_listing_id(token: Option<&str>) -> Result<ListingId, String> {
+    match token {
+        Some(token) => ListingId::from_str(token).map_err(|e| e.to_string()),
+        None => Err("Enter a listing ID.".to_string()),
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct MarketBuy {
+    pub actor: Entity,
+    pub id: ListingId,
+}
+
+into_action!(MarketBuy);
+
+#[tracing::instrument(name = "market buy system", skip_all, fields(action = "MarketBuy"))]
+pub fn market_buy_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    mut bank: ResMut<Bank>,
+    mut market: ResMut<Market>,
+    players: Res<Players>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<(&Player, &Named)>,
+    object_query: Query<(&Object, &Named)>,
+    mut contents_query: Query<&mut Contents, With<Player>>,
+    attributes_query: Query<&Attributes>,
+    prototype_query: Query<&Object>,
+    bulk_query: Query<&Bulk>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::MarketBuy(MarketBuy { actor, id }) = action {
+            let (buyer, buyer_name) = match player_query.get(*actor) {
+                Ok((player, named)) => (player.id(), named.to_string()),
+                Err(_) => continue,
+            };
+
+            let (object, object_id, name, price, seller, seller_name) = match market.get(*id) {
+                Some(Listing {
+                    seller,
+                    seller_name,
+                    object,
+                    price,
+                    ..
+                }) => match object_query.get(*object) {
+                    Ok((found, named)) => (
+                        *object,
+                        found.id(),
+                        named.to_string(),
+                        *price,
+                        *seller,
+                        seller_name.clone(),
+                    ),
+                    Err(_) => continue,
+                },
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue(format!("Listing {} not found.", id));
+                    }
+                    continue;
+                }
+            };
+
+            if seller == buyer {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(
+                        "You cannot buy your own listing. Cancel it to take it back.".to_string(),
+                    );
+                }
+                continue;
+            }
+
+            if let Some(message) = contents_query.get_mut(*actor).ok().and_then(|carried| {
+                carry_limit(
+                    *actor,
+                    carried.objects(),
+                    object,
+                    name.as_str(),
+                    &attributes_query,
+                    &prototype_query,
+                    &bulk_query,
+                )
+            }) {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(message);
+                }
+                continue;
+            }
+
+            let buyer_account = bank.open(buyer, buyer_name.as_str());
+            if buyer_account.coins < price {
+                let message = format!(
+                    "{} costs {}, but you are only carrying {}.",
+                    name,
+                    format_coins(price),
+                    format_coins(buyer_account.coins)
+                );
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(message);
+                }
+                continue;
+            }
+
+            // The buyer's coins go straight into the seller's bank account, so sellers are paid
+            // while they are offline.
+            buyer_account.coins -= price;
+            let buyer_update =
+                persist::bank::Account::new(buyer, buyer_account.coins, buyer_account.balance);
+
+            let seller_account = bank.open(seller, seller_name.as_str());
+            seller_account.balance += price;
+            let seller_update =
+                persist::bank::Account::new(seller, seller_account.coins, seller_account.balance);
+
+            let listing = market.remove(*id).unwrap();
+
+            if let Ok(mut contents) = contents_query.get_mut(*actor) {
+                contents.insert(listing.object);
+            }
+            commands
+                .entity(listing.object)
+                .insert(Location::from(*actor));
+
+            updates.persist(UpdateGroup::new(vec![
+                persist::market::Remove::new(*id),
+                persist::player::AddObject::new(buyer, object_id),
+                buyer_update,
+                seller_update,
+            ]));
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!(
+                    "You buy {} from {} for {}.",
+                    name,
+                    seller_name,
+                    format_coins(price)
+                ));
+            }
+
+            if let Some(mut messages) = players
+                .by_name(seller_name.as_str())
+                .and_then(|seller| messages_query.get_mut(seller).ok())
+            {
+                messages.queue(format!(
+                    "{} bought {} from you for {}, which have been deposited in your bank account.",
+                    buyer_name,
+                    name,
+                    format_coins(price)
+                ));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct MarketCancel {
+    pub actor: Entity,
+    pub id: ListingId,
+}
+
+into_action!(MarketCancel);
+
+#[tracing::instrument(
+    name = "market cancel system",
+    skip_all,
+    fields(action = "MarketCancel")
+)]
+pub fn market_cancel_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    mut market: ResMut<Market>,
+    players: Res<Players>,
+    mut objects: ResMut<Objects>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<&Player>,
+    object_query: Query<&Object>,
+    named_query: Query<&Named>,
+    mut contents_query: Query<&mut Contents, With<Player>>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::MarketCancel(MarketCancel { actor, id }) = action {
+            let player = match player_query.get(*actor) {
+                Ok(player) => player.id(),
+                Err(_) => continue,
+            };
+
+            let message = match market.get(*id) {
+                Some(listing) if listing.seller == player => {
+                    let listing = market.remove(*id).unwrap();
+
+                    let name = named_query
+                        .get(listing.object)
+                        .map(|named| named.to_string())
+                        .unwrap_or_default();
+
+                    return_listing(
+                        *id,
+                        &listing,
+                        &mut commands,
+                        &players,
+                        &mut objects,
+                        &mut updates,
+                        &object_query,
+                        &mut contents_query,
+                    );
+
+                    format!("You cancel listing {} and take back {}.", id, name)
+                }
+                Some(_) => format!("Listing {} is not yours.", id),
+                None => format!("Listing {} not found.", id),
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct MarketList {
+    pub actor: Entity,
+}
+
+into_action!(MarketList);
+
+#[tracing::instrument(name = "market list system", skip_all, fields(action = "MarketList"))]
+pub fn market_list_system(
+    mut action_reader: EventReader<Action>,
+    clock: Res<Clock>,
+    market: Res<Market>,
+    named_query: Query<&Named, With<Object>>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::MarketList(MarketList { actor }) = action {
+            let message = if market.is_empty() {
+                "There is nothing for sale on the market.".to_string()
+            } else {
+                let now = clock.unix_now();
+                let mut message = "|white|Market listings|-|".to_string();
+
+                for (id, listing) in market.iter() {
+                    let name = match named_query.get(listing.object) {
+                        Ok(named) => named,
+                        Err(_) => continue,
+                    };
+
+                    message.push_str(
+                        format!(
+                            "\r\n  |white|{}|-|. {} for {} from {}, expires in {}",
+                            id,
+                            name,
+                            format_coins(listing.price),
+                            listing.seller_name,
+                            format_duration((listing.expires_at - now).max(0))
+                        )
+                        .as_str(),
+                    );
+                }
+
+                message
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct MarketSell {
+    pub actor: Entity,
+    pub price: i64,
+    pub keywords: Vec<String>,
+}
+
+into_action!(MarketSell);
+
+#[tracing::instrument(name = "market sell system", skip_all, fields(action = "MarketSell"))]
+pub fn market_sell_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    clock: Res<Clock>,
+    mut market: ResMut<Market>,
+    rooms: Res<Rooms>,
+    mut updates: ResMut<Updates>,
+    mut player_query: Query<(&Player, &Named, &mut Contents)>,
+    object_query: Query<(&Object, &Named, &Keywords)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::MarketSell(MarketSell {
+            actor,
+            price,
+            keywords,
+        }) = action
+        {
+            let (player, seller_name, mut contents) = match player_query.get_mut(*actor) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+
+            let target = contents.find(|object| {
+                object_query
+                    .get(object)
+                    .map(|(_, _, object_keywords)| {
+                        object_keywords.contains_all(keywords.as_slice())
+                    })
+                    .unwrap_or(false)
+            });
+
+            let message = if let Some(object) = target {
+                let (object_id, name) = object_query
+                    .get(object)
+                    .map(|(object, named, _)| (object.id(), named.to_string()))
+                    .unwrap();
+
+                // Listed objects are held in the void room, out of sight of everyone.
+                let void_room = rooms.by_id(*VOID_ROOM_ID).unwrap();
+
+                contents.remove(object);
+                commands.entity(object).insert(Location::from(void_room));
+
+                let id = market.next_id();
+                let duration = market.listing_duration();
+                let expires_at = clock.unix_now() + duration.as_secs() as i64;
+
+                market.insert(
+                    id,
+                    Listing {
+                        seller: player.id(),
+                        seller_name: seller_name.to_string(),
+                        object,
+                        price: *price,
+                        expires_at,
+                    },
+                );
+
+                updates.persist(UpdateGroup::new(vec![
+                    persist::player::RemoveObject::new(player.id(), object_id),
+                    persist::market::Create::new(id, player.id(), object_id, *price, expires_at),
+                ]));
+
+                format!(
+                    "You list {} for {} as listing {}. It expires in {}.",
+                    name,
+                    format_coins(*price),
+                    id,
+                    format_duration(duration.as_secs() as i64)
+                )
+            } else {
+                format!("You don't have \"{}\".", keywords.join(" "))
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
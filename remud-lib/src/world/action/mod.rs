@@ -1,57 +1,181 @@
+pub mod achievement;
 pub mod attributes;
+pub mod bank;
+pub mod campaign;
+pub mod clan;
 pub mod commands;
 pub mod communicate;
+pub mod explore;
 pub mod immortal;
+pub mod mail;
+pub mod market;
 pub mod movement;
+pub mod news;
 pub mod object;
 pub mod observe;
+pub mod poll;
+pub mod shop;
 pub mod system;
-mod targeting;
+pub mod targeting;
 
 use bevy_ecs::prelude::*;
-use strum::EnumString;
+use strum::{EnumString, IntoStaticStr};
 
 use crate::{
-    ecs::{Ecs, Phase, Plugin, Step},
+    ecs::{Ecs, GuardSystem, Phase, Plugin, Step},
     world::{
         action::{
+            achievement::{
+                grant_achievement_system, show_achievements_system, GrantAchievement,
+                ShowAchievements,
+            },
             attributes::{stats_system, Stats},
+            bank::{balance_system, deposit_system, withdraw_system, Balance, Deposit, Withdraw},
+            campaign::{show_campaigns_system, ShowCampaigns},
+            clan::{
+                clan_disband_system, clan_found_system, clan_hall_system, clan_info_system,
+                clan_invite_system, clan_join_system, clan_kick_system, clan_leave_system,
+                clan_say_system, clan_set_rank_system, ClanDisband, ClanFound, ClanHall, ClanInfo,
+                ClanInvite, ClanJoin, ClanKick, ClanLeave, ClanSay, ClanSetRank,
+            },
             communicate::{
-                emote_system, message_system, say_system, send_message_system, whisper_system,
-                Emote, Message, Say, SendMessage, Whisper,
+                cutscene_line_system, emote_system, ignore_system, message_system, pronouns_system,
+                report_system, say_system, send_message_system, unignore_system, whisper_system,
+                CutsceneLine, Emote, Ignore, Message, Report, Say, SendMessage, SetPronouns,
+                Unignore, Whisper,
+            },
+            explore::{
+                brief_system, hints_system, map_system, show_hints_system, Brief, Hints, Map,
             },
             immortal::{
+                achievement::{
+                    achievement_create_system, achievement_list_system, achievement_update_system,
+                    AchievementCreate, AchievementList, AchievementUpdate,
+                },
+                allowlist::{
+                    allowlist_add_system, allowlist_remove_system, allowlist_show_system,
+                    AllowlistAdd, AllowlistRemove, AllowlistShow,
+                },
+                bank::{
+                    bank_audit_system, bank_grant_system, bank_info_system, BankAudit, BankGrant,
+                    BankInfo,
+                },
+                campaign::{
+                    campaign_create_system, campaign_info_system, campaign_list_system,
+                    campaign_progress_system, campaign_remove_system, campaign_update_system,
+                    CampaignCreate, CampaignInfo, CampaignList, CampaignProgress, CampaignRemove,
+                    CampaignUpdate,
+                },
+                clan::{clan_list_system, clan_update_hall_system, ClanList, ClanUpdateHall},
+                communication::{communication_search_system, CommunicationSearch},
+                economy::{economy_report_system, EconomyReport},
+                faction::{
+                    faction_create_system, faction_list_system, faction_update_system,
+                    FactionCreate, FactionList, FactionUpdate,
+                },
+                force::{force_system, Force},
+                global::{global_list_system, global_update_system, GlobalList, GlobalUpdate},
                 initialize_system,
+                loot::{
+                    loot_table_create_system, loot_table_info_system, loot_table_list_system,
+                    loot_table_remove_system, loot_table_roll_system, loot_table_update_system,
+                    LootTableCreate, LootTableInfo, LootTableList, LootTableRemove, LootTableRoll,
+                    LootTableUpdate,
+                },
                 object::{
                     object_create_system, object_info_system, object_inherit_fields_system,
-                    object_remove_system, update_keywords_system, update_object_flags,
-                    ObjectCreate, ObjectInfo, ObjectInheritFields, ObjectRemove, UpdateKeywords,
-                    UpdateObjectFlags,
+                    object_remove_system, update_field_system, update_keywords_system,
+                    update_object_flags, ObjectCreate, ObjectInfo, ObjectInheritFields,
+                    ObjectRemove, UpdateField, UpdateKeywords, UpdateObjectFlags,
                 },
                 player::{
-                    player_info_system, player_update_flags_system, PlayerInfo, PlayerUpdateFlags,
+                    player_info_system, player_rename_system, player_update_flags_system,
+                    PlayerInfo, PlayerRename, PlayerUpdateFlags,
                 },
+                possess::{possess_system, release_system, Possess, Release},
                 prototype::{
-                    prototype_create_system, prototype_info_system, PrototypeCreate, PrototypeInfo,
-                    PrototypeList,
+                    prototype_behavior_system, prototype_bulk_system, prototype_create_system,
+                    prototype_info_system, PrototypeBehavior, PrototypeBulk, PrototypeCreate,
+                    PrototypeInfo, PrototypeList,
+                },
+                region::{
+                    region_disown_system, region_loaded_system, region_owner_system,
+                    region_show_system, RegionDisown, RegionLoaded, RegionOwner, RegionShow,
                 },
                 room::{
-                    room_create_system, room_info_system, room_link_system, room_remove_system,
-                    room_unlink_system, room_update_regions_system, RoomCreate, RoomInfo, RoomLink,
-                    RoomRemove, RoomUnlink, RoomUpdateRegions,
+                    room_create_system, room_echo_system, room_info_system, room_link_system,
+                    room_remove_system, room_unlink_system, room_update_regions_system, RoomCreate,
+                    RoomEcho, RoomInfo, RoomLink, RoomRemove, RoomUnlink, RoomUpdateRegions,
+                },
+                script::{
+                    script_api_system, script_attach_system, script_detach_system,
+                    script_list_system, script_stats_system, script_toggle_system, ScriptApi,
+                    ScriptAttach, ScriptDetach, ScriptList, ScriptStatsList, ScriptToggle,
+                },
+                season::{
+                    season_info_system, season_list_system, season_tag_system, SeasonInfo,
+                    SeasonList, SeasonTag,
+                },
+                shop::{
+                    shop_create_system, shop_info_system, shop_remove_system, shop_update_system,
+                    shops_list_system, ShopCreate, ShopInfo, ShopRemove, ShopUpdate, ShopsList,
+                },
+                show_error_system,
+                snoop::{
+                    snoop_approve_system, snoop_start_system, snoop_stop_system, SnoopApprove,
+                    SnoopStart, SnoopStop,
+                },
+                spawn::{
+                    spawn_table_create_system, spawn_table_info_system, spawn_table_list_system,
+                    spawn_table_remove_system, spawn_table_update_system, SpawnTableCreate,
+                    SpawnTableInfo, SpawnTableList, SpawnTableRemove, SpawnTableUpdate,
+                },
+                stage::{
+                    publish_system, stage_discard_system, stage_region_system, Publish,
+                    StageDiscard, StageRegion,
                 },
-                script::{script_attach_system, script_detach_system, ScriptAttach, ScriptDetach},
-                show_error_system, update_description_system, update_name_system, Initialize,
-                ShowError, UpdateDescription, UpdateName,
+                tutorial::{
+                    tutorial_hint_system, tutorial_info_system, tutorial_start_system,
+                    TutorialHint, TutorialInfo, TutorialStart,
+                },
+                update_description_system, update_name_system, Initialize, ShowError,
+                UpdateDescription, UpdateName,
+            },
+            mail::{
+                mail_claim_system, mail_delete_system, mail_list_system, mail_read_system,
+                mail_send_system, MailClaim, MailDelete, MailList, MailRead, MailSend,
+            },
+            market::{
+                market_buy_system, market_cancel_system, market_list_system, market_sell_system,
+                MarketBuy, MarketCancel, MarketList, MarketSell,
+            },
+            movement::{move_system, record_visits_system, teleport_system, Move, Teleport},
+            news::{
+                news_delete_system, news_list_system, news_post_system, NewsDelete, NewsList,
+                NewsPost,
             },
-            movement::{move_system, teleport_system, Move, Teleport},
             object::{
-                drop_system, get_system, inventory_system, use_system, Drop, Get, Inventory, Use,
+                consume_system, drop_system, get_system, inventory_system, use_system, Drink, Drop,
+                Eat, Get, Inventory, Use,
             },
             observe::{
-                exits_system, look_at_system, look_system, who_system, Exits, Look, LookAt, Who,
+                exits_system, look_at_system, look_system, privacy_system, recall_system,
+                top_system, who_system, Exits, Look, LookAt, Privacy, Recall, Top, Who,
+            },
+            poll::{
+                poll_close_system, poll_create_system, poll_info_system, poll_list_system,
+                vote_system, PollClose, PollCreate, PollInfo, PollList, Vote,
+            },
+            shop::{
+                shop_buy_system, shop_haggle_system, shop_list_system, ShopBuy, ShopHaggle,
+                ShopList,
+            },
+            system::{
+                config_reload_system, db_check_system, lint_world_system, login_system,
+                maintenance_off_system, maintenance_on_system, profile_system, restart_system,
+                shutdown_system, ConfigReload, DbCheck, LintWorld, Login, MaintenanceOff,
+                MaintenanceOn, Profile, Restart, Shutdown,
             },
-            system::{login_system, restart_system, shutdown_system, Login, Restart, Shutdown},
         },
         scripting::QueuedAction,
         types::{room::Room, Location},
@@ -99,145 +223,523 @@ fn get_room_std(entity: Entity, query: &Query<(Option<&Location>, Option<&Room>)
 use crate::world::action::immortal::prototype::prototype_list_system;
 pub(crate) use into_action;
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, IntoStaticStr)]
 pub enum Action {
+    AchievementCreate(AchievementCreate),
+    AchievementList(AchievementList),
+    AchievementUpdate(AchievementUpdate),
+    AllowlistAdd(AllowlistAdd),
+    AllowlistRemove(AllowlistRemove),
+    AllowlistShow(AllowlistShow),
+    Balance(Balance),
+    BankAudit(BankAudit),
+    BankGrant(BankGrant),
+    BankInfo(BankInfo),
+    Brief(Brief),
+    CampaignCreate(CampaignCreate),
+    CampaignInfo(CampaignInfo),
+    CampaignList(CampaignList),
+    CampaignProgress(CampaignProgress),
+    CampaignRemove(CampaignRemove),
+    CampaignUpdate(CampaignUpdate),
+    ClanDisband(ClanDisband),
+    ClanFound(ClanFound),
+    ClanHall(ClanHall),
+    ClanInfo(ClanInfo),
+    ClanInvite(ClanInvite),
+    ClanJoin(ClanJoin),
+    ClanKick(ClanKick),
+    ClanLeave(ClanLeave),
+    ClanList(ClanList),
+    ClanSay(ClanSay),
+    ClanSetRank(ClanSetRank),
+    ClanUpdateHall(ClanUpdateHall),
+    CommunicationSearch(CommunicationSearch),
+    ConfigReload(ConfigReload),
+    CutsceneLine(CutsceneLine),
+    DbCheck(DbCheck),
+    Deposit(Deposit),
+    Drink(Drink),
     Drop(Drop),
+    Eat(Eat),
+    EconomyReport(EconomyReport),
     Emote(Emote),
     Exits(Exits),
+    FactionCreate(FactionCreate),
+    FactionList(FactionList),
+    FactionUpdate(FactionUpdate),
+    Force(Force),
     Get(Get),
+    GlobalList(GlobalList),
+    GlobalUpdate(GlobalUpdate),
+    GrantAchievement(GrantAchievement),
+    Hints(Hints),
+    Ignore(Ignore),
     Initialize(Initialize),
     Inventory(Inventory),
+    LintWorld(LintWorld),
     Login(Login),
     Look(Look),
     LookAt(LookAt),
+    LootTableCreate(LootTableCreate),
+    LootTableInfo(LootTableInfo),
+    LootTableList(LootTableList),
+    LootTableRemove(LootTableRemove),
+    LootTableRoll(LootTableRoll),
+    LootTableUpdate(LootTableUpdate),
+    MailClaim(MailClaim),
+    MailDelete(MailDelete),
+    MailList(MailList),
+    MailRead(MailRead),
+    MailSend(MailSend),
+    MaintenanceOff(MaintenanceOff),
+    MaintenanceOn(MaintenanceOn),
+    Map(Map),
+    MarketBuy(MarketBuy),
+    MarketCancel(MarketCancel),
+    MarketList(MarketList),
+    MarketSell(MarketSell),
     Message(Message),
     Move(Move),
+    NewsDelete(NewsDelete),
+    NewsList(NewsList),
+    NewsPost(NewsPost),
     ObjectCreate(ObjectCreate),
     ObjectInfo(ObjectInfo),
     ObjectInheritFields(ObjectInheritFields),
     ObjectRemove(ObjectRemove),
     PlayerInfo(PlayerInfo),
+    PlayerRename(PlayerRename),
     PlayerUpdateFlags(PlayerUpdateFlags),
+    PollClose(PollClose),
+    PollCreate(PollCreate),
+    PollInfo(PollInfo),
+    PollList(PollList),
+    Possess(Possess),
+    Privacy(Privacy),
+    Profile(Profile),
+    PrototypeBehavior(PrototypeBehavior),
+    PrototypeBulk(PrototypeBulk),
     PrototypeCreate(PrototypeCreate),
     PrototypeInfo(PrototypeInfo),
     PrototypeList(PrototypeList),
+    Publish(Publish),
+    Recall(Recall),
+    RegionDisown(RegionDisown),
+    RegionLoaded(RegionLoaded),
+    RegionOwner(RegionOwner),
+    RegionShow(RegionShow),
+    Release(Release),
+    Report(Report),
     Restart(Restart),
     RoomCreate(RoomCreate),
+    RoomEcho(RoomEcho),
     RoomInfo(RoomInfo),
     RoomLink(RoomLink),
     RoomRemove(RoomRemove),
     RoomUnlink(RoomUnlink),
     RoomUpdateRegions(RoomUpdateRegions),
     Say(Say),
+    ScriptApi(ScriptApi),
     ScriptAttach(ScriptAttach),
     ScriptDetach(ScriptDetach),
+    ScriptList(ScriptList),
+    ScriptStatsList(ScriptStatsList),
+    ScriptToggle(ScriptToggle),
+    SeasonInfo(SeasonInfo),
+    SeasonList(SeasonList),
+    SeasonTag(SeasonTag),
     Send(SendMessage),
+    SetPronouns(SetPronouns),
+    ShopBuy(ShopBuy),
+    ShopCreate(ShopCreate),
+    ShopHaggle(ShopHaggle),
+    ShopInfo(ShopInfo),
+    ShopList(ShopList),
+    ShopRemove(ShopRemove),
+    ShopUpdate(ShopUpdate),
+    ShopsList(ShopsList),
+    ShowAchievements(ShowAchievements),
+    ShowCampaigns(ShowCampaigns),
     ShowError(ShowError),
     Shutdown(Shutdown),
+    SnoopApprove(SnoopApprove),
+    SnoopStart(SnoopStart),
+    SnoopStop(SnoopStop),
+    SpawnTableCreate(SpawnTableCreate),
+    SpawnTableInfo(SpawnTableInfo),
+    SpawnTableList(SpawnTableList),
+    SpawnTableRemove(SpawnTableRemove),
+    SpawnTableUpdate(SpawnTableUpdate),
+    StageDiscard(StageDiscard),
+    StageRegion(StageRegion),
     Stats(Stats),
     Teleport(Teleport),
+    Top(Top),
+    TutorialHint(TutorialHint),
+    TutorialInfo(TutorialInfo),
+    TutorialStart(TutorialStart),
+    Unignore(Unignore),
     UpdateDescription(UpdateDescription),
+    UpdateField(UpdateField),
     UpdateKeywords(UpdateKeywords),
     UpdateName(UpdateName),
     UpdateObjectFlags(UpdateObjectFlags),
     Use(Use),
+    Vote(Vote),
     Whisper(Whisper),
     Who(Who),
+    Withdraw(Withdraw),
 }
 
 impl Action {
     pub fn actor(&self) -> Entity {
         match self {
+            Action::AchievementCreate(action) => action.actor,
+            Action::AchievementList(action) => action.actor,
+            Action::AchievementUpdate(action) => action.actor,
+            Action::AllowlistAdd(action) => action.actor,
+            Action::AllowlistRemove(action) => action.actor,
+            Action::AllowlistShow(action) => action.actor,
+            Action::Balance(action) => action.actor,
+            Action::BankAudit(action) => action.actor,
+            Action::BankGrant(action) => action.actor,
+            Action::BankInfo(action) => action.actor,
+            Action::Brief(action) => action.actor,
+            Action::CampaignCreate(action) => action.actor,
+            Action::CampaignInfo(action) => action.actor,
+            Action::CampaignList(action) => action.actor,
+            Action::CampaignProgress(action) => action.actor,
+            Action::CampaignRemove(action) => action.actor,
+            Action::CampaignUpdate(action) => action.actor,
+            Action::ClanDisband(action) => action.actor,
+            Action::ClanFound(action) => action.actor,
+            Action::ClanHall(action) => action.actor,
+            Action::ClanInfo(action) => action.actor,
+            Action::ClanInvite(action) => action.actor,
+            Action::ClanJoin(action) => action.actor,
+            Action::ClanKick(action) => action.actor,
+            Action::ClanLeave(action) => action.actor,
+            Action::ClanList(action) => action.actor,
+            Action::ClanSay(action) => action.actor,
+            Action::ClanSetRank(action) => action.actor,
+            Action::ClanUpdateHall(action) => action.actor,
+            Action::CommunicationSearch(action) => action.actor,
+            Action::ConfigReload(action) => action.actor,
+            Action::CutsceneLine(action) => action.actor,
+            Action::DbCheck(action) => action.actor,
+            Action::Deposit(action) => action.actor,
+            Action::Drink(action) => action.actor,
             Action::Drop(action) => action.actor,
+            Action::Eat(action) => action.actor,
+            Action::EconomyReport(action) => action.actor,
             Action::Emote(action) => action.actor,
             Action::Exits(action) => action.actor,
+            Action::FactionCreate(action) => action.actor,
+            Action::FactionList(action) => action.actor,
+            Action::FactionUpdate(action) => action.actor,
+            Action::Force(action) => action.actor,
             Action::Get(action) => action.actor,
+            Action::GlobalList(action) => action.actor,
+            Action::GlobalUpdate(action) => action.actor,
+            Action::GrantAchievement(action) => action.actor,
+            Action::Hints(action) => action.actor,
+            Action::Ignore(action) => action.actor,
             Action::Initialize(action) => action.actor,
             Action::Inventory(action) => action.actor,
+            Action::LintWorld(action) => action.actor,
             Action::Login(action) => action.actor,
             Action::Look(action) => action.actor,
             Action::LookAt(action) => action.actor,
+            Action::LootTableCreate(action) => action.actor,
+            Action::LootTableInfo(action) => action.actor,
+            Action::LootTableList(action) => action.actor,
+            Action::LootTableRemove(action) => action.actor,
+            Action::LootTableRoll(action) => action.actor,
+            Action::LootTableUpdate(action) => action.actor,
+            Action::MailClaim(action) => action.actor,
+            Action::MailDelete(action) => action.actor,
+            Action::MailList(action) => action.actor,
+            Action::MailRead(action) => action.actor,
+            Action::MailSend(action) => action.actor,
+            Action::MaintenanceOff(action) => action.actor,
+            Action::MaintenanceOn(action) => action.actor,
+            Action::Map(action) => action.actor,
+            Action::MarketBuy(action) => action.actor,
+            Action::MarketCancel(action) => action.actor,
+            Action::MarketList(action) => action.actor,
+            Action::MarketSell(action) => action.actor,
             Action::Message(action) => action.actor,
             Action::Move(action) => action.actor,
+            Action::NewsDelete(action) => action.actor,
+            Action::NewsList(action) => action.actor,
+            Action::NewsPost(action) => action.actor,
             Action::ObjectCreate(action) => action.actor,
             Action::ObjectInfo(action) => action.actor,
             Action::ObjectInheritFields(action) => action.actor,
             Action::ObjectRemove(action) => action.actor,
             Action::PlayerInfo(action) => action.actor,
+            Action::PlayerRename(action) => action.actor,
             Action::PlayerUpdateFlags(action) => action.actor,
+            Action::PollClose(action) => action.actor,
+            Action::PollCreate(action) => action.actor,
+            Action::PollInfo(action) => action.actor,
+            Action::PollList(action) => action.actor,
+            Action::Possess(action) => action.actor,
+            Action::Privacy(action) => action.actor,
+            Action::Profile(action) => action.actor,
+            Action::PrototypeBehavior(action) => action.actor,
+            Action::PrototypeBulk(action) => action.actor,
             Action::PrototypeCreate(action) => action.actor,
             Action::PrototypeInfo(action) => action.actor,
             Action::PrototypeList(action) => action.actor,
+            Action::Publish(action) => action.actor,
+            Action::Recall(action) => action.actor,
+            Action::RegionDisown(action) => action.actor,
+            Action::RegionLoaded(action) => action.actor,
+            Action::RegionOwner(action) => action.actor,
+            Action::RegionShow(action) => action.actor,
+            Action::Release(action) => action.actor,
+            Action::Report(action) => action.actor,
             Action::Restart(action) => action.actor,
             Action::RoomCreate(action) => action.actor,
+            Action::RoomEcho(action) => action.actor,
             Action::RoomInfo(action) => action.actor,
             Action::RoomLink(action) => action.actor,
             Action::RoomRemove(action) => action.actor,
             Action::RoomUnlink(action) => action.actor,
             Action::RoomUpdateRegions(action) => action.actor,
             Action::Say(action) => action.actor,
+            Action::ScriptApi(action) => action.actor,
             Action::ScriptAttach(action) => action.actor,
             Action::ScriptDetach(action) => action.actor,
+            Action::ScriptList(action) => action.actor,
+            Action::ScriptStatsList(action) => action.actor,
+            Action::ScriptToggle(action) => action.actor,
+            Action::SeasonInfo(action) => action.actor,
+            Action::SeasonList(action) => action.actor,
+            Action::SeasonTag(action) => action.actor,
             Action::Send(action) => action.actor,
+            Action::SetPronouns(action) => action.actor,
+            Action::ShopBuy(action) => action.actor,
+            Action::ShopCreate(action) => action.actor,
+            Action::ShopHaggle(action) => action.actor,
+            Action::ShopInfo(action) => action.actor,
+            Action::ShopList(action) => action.actor,
+            Action::ShopRemove(action) => action.actor,
+            Action::ShopUpdate(action) => action.actor,
+            Action::ShopsList(action) => action.actor,
+            Action::ShowAchievements(action) => action.actor,
+            Action::ShowCampaigns(action) => action.actor,
             Action::ShowError(action) => action.actor,
             Action::Shutdown(action) => action.actor,
+            Action::SnoopApprove(action) => action.actor,
+            Action::SnoopStart(action) => action.actor,
+            Action::SnoopStop(action) => action.actor,
+            Action::SpawnTableCreate(action) => action.actor,
+            Action::SpawnTableInfo(action) => action.actor,
+            Action::SpawnTableList(action) => action.actor,
+            Action::SpawnTableRemove(action) => action.actor,
+            Action::SpawnTableUpdate(action) => action.actor,
+            Action::StageDiscard(action) => action.actor,
+            Action::StageRegion(action) => action.actor,
             Action::Stats(action) => action.actor,
             Action::Teleport(action) => action.actor,
+            Action::Top(action) => action.actor,
+            Action::TutorialHint(action) => action.actor,
+            Action::TutorialInfo(action) => action.actor,
+            Action::TutorialStart(action) => action.actor,
+            Action::Unignore(action) => action.actor,
             Action::UpdateDescription(action) => action.actor,
+            Action::UpdateField(action) => action.actor,
             Action::UpdateKeywords(action) => action.actor,
             Action::UpdateName(action) => action.actor,
             Action::UpdateObjectFlags(action) => action.actor,
             Action::Use(action) => action.actor,
+            Action::Vote(action) => action.actor,
             Action::Whisper(action) => action.actor,
             Action::Who(action) => action.actor,
+            Action::Withdraw(action) => action.actor,
         }
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, SystemLabel)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, SystemLabel, IntoStaticStr)]
 pub enum ActionSystem {
+    AchievementCreate,
+    AchievementList,
+    AchievementUpdate,
+    AllowlistAdd,
+    AllowlistRemove,
+    AllowlistShow,
+    Balance,
+    BankAudit,
+    BankGrant,
+    BankInfo,
+    Brief,
+    CampaignCreate,
+    CampaignInfo,
+    CampaignList,
+    CampaignProgress,
+    CampaignRemove,
+    CampaignUpdate,
+    ClanDisband,
+    ClanFound,
+    ClanHall,
+    ClanInfo,
+    ClanInvite,
+    ClanJoin,
+    ClanKick,
+    ClanLeave,
+    ClanList,
+    ClanSay,
+    ClanSetRank,
+    ClanUpdateHall,
+    CommunicationSearch,
+    ConfigReload,
+    Consume,
+    CutsceneLine,
+    DbCheck,
+    Deposit,
     Drop,
+    EconomyReport,
     Emote,
     Exits,
+    FactionCreate,
+    FactionList,
+    FactionUpdate,
+    Force,
     Get,
+    GlobalList,
+    GlobalUpdate,
+    GrantAchievement,
+    Hints,
+    Ignore,
     Initialize,
     Inventory,
+    LintWorld,
     Login,
     Look,
     LookAt,
+    LootTableCreate,
+    LootTableInfo,
+    LootTableList,
+    LootTableRemove,
+    LootTableRoll,
+    LootTableUpdate,
+    MailClaim,
+    MailDelete,
+    MailList,
+    MailRead,
+    MailSend,
+    MaintenanceOff,
+    MaintenanceOn,
+    Map,
+    MarketBuy,
+    MarketCancel,
+    MarketList,
+    MarketSell,
     Message,
     Move,
+    NewsDelete,
+    NewsList,
+    NewsPost,
     ObjectCreate,
     ObjectInfo,
     ObjectInheritFields,
     ObjectRemove,
     PlayerInfo,
+    PlayerRename,
     PlayerUpdateFlags,
+    PollClose,
+    PollCreate,
+    PollInfo,
+    PollList,
+    Possess,
+    Privacy,
+    Profile,
+    PrototypeBehavior,
+    PrototypeBulk,
     PrototypeCreate,
     PrototypeInfo,
     PrototypeList,
+    Publish,
+    Recall,
+    RegionDisown,
+    RegionLoaded,
+    RegionOwner,
+    RegionShow,
+    Release,
+    Report,
     Restart,
     RoomCreate,
+    RoomEcho,
     RoomInfo,
     RoomLink,
     RoomRemove,
     RoomUnlink,
     RoomUpdateRegions,
     Say,
+    ScriptApi,
     ScriptAttach,
     ScriptDetach,
+    ScriptList,
+    ScriptStatsList,
+    ScriptToggle,
+    SeasonInfo,
+    SeasonList,
+    SeasonTag,
     Send,
+    SetPronouns,
+    ShopBuy,
+    ShopCreate,
+    ShopHaggle,
+    ShopInfo,
+    ShopList,
+    ShopRemove,
+    ShopUpdate,
+    ShopsList,
+    ShowAchievements,
+    ShowCampaigns,
     ShowError,
     Shutdown,
+    SnoopApprove,
+    SnoopStart,
+    SnoopStop,
+    SpawnTableCreate,
+    SpawnTableInfo,
+    SpawnTableList,
+    SpawnTableRemove,
+    SpawnTableUpdate,
+    StageDiscard,
+    StageRegion,
     Stats,
     Teleport,
+    Top,
+    TutorialHint,
+    TutorialInfo,
+    TutorialStart,
+    Unignore,
     UpdateDescription,
+    UpdateField,
     UpdateKeywords,
     UpdateName,
     UpdateObjectFlags,
     Use,
+    Vote,
     Whisper,
     Who,
+    Withdraw,
+}
+impl ActionSystem {
+    /// Whether this system handles the action. Systems are named after the action they handle,
+    /// except for consuming, which handles both drinking and eating.
+    pub fn handles(&self, action: &Action) -> bool {
+        match self {
+            ActionSystem::Consume => matches!(action, Action::Drink(_) | Action::Eat(_)),
+            system => <&'static str>::from(system) == <&'static str>::from(action),
+        }
+    }
 }
 
 #[derive(Default)]
@@ -250,250 +752,976 @@ impl Plugin for ActionsPlugin {
             .add_system(
                 Step::Main,
                 Phase::Update,
-                drop_system.system().label(ActionSystem::Drop),
+                achievement_create_system
+                    .guarded()
+                    .label(ActionSystem::AchievementCreate),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                achievement_list_system
+                    .guarded()
+                    .label(ActionSystem::AchievementList),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                achievement_update_system
+                    .guarded()
+                    .label(ActionSystem::AchievementUpdate),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                allowlist_add_system
+                    .guarded()
+                    .label(ActionSystem::AllowlistAdd),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                allowlist_remove_system
+                    .guarded()
+                    .label(ActionSystem::AllowlistRemove),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                allowlist_show_system
+                    .guarded()
+                    .label(ActionSystem::AllowlistShow),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                balance_system.guarded().label(ActionSystem::Balance),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                bank_audit_system.guarded().label(ActionSystem::BankAudit),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                bank_grant_system.guarded().label(ActionSystem::BankGrant),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                bank_info_system.guarded().label(ActionSystem::BankInfo),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                brief_system.guarded().label(ActionSystem::Brief),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                campaign_create_system
+                    .guarded()
+                    .label(ActionSystem::CampaignCreate),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                campaign_info_system
+                    .guarded()
+                    .label(ActionSystem::CampaignInfo),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                campaign_list_system
+                    .guarded()
+                    .label(ActionSystem::CampaignList),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                campaign_progress_system
+                    .guarded()
+                    .label(ActionSystem::CampaignProgress),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                campaign_remove_system
+                    .guarded()
+                    .label(ActionSystem::CampaignRemove),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                campaign_update_system
+                    .guarded()
+                    .label(ActionSystem::CampaignUpdate),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                clan_disband_system
+                    .guarded()
+                    .label(ActionSystem::ClanDisband),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                clan_found_system.guarded().label(ActionSystem::ClanFound),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                clan_hall_system.guarded().label(ActionSystem::ClanHall),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                clan_info_system.guarded().label(ActionSystem::ClanInfo),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                clan_invite_system.guarded().label(ActionSystem::ClanInvite),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                clan_join_system.guarded().label(ActionSystem::ClanJoin),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                clan_kick_system.guarded().label(ActionSystem::ClanKick),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                clan_leave_system.guarded().label(ActionSystem::ClanLeave),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                clan_list_system.guarded().label(ActionSystem::ClanList),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                clan_say_system.guarded().label(ActionSystem::ClanSay),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                clan_set_rank_system
+                    .guarded()
+                    .label(ActionSystem::ClanSetRank),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                clan_update_hall_system
+                    .guarded()
+                    .label(ActionSystem::ClanUpdateHall),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                communication_search_system
+                    .guarded()
+                    .label(ActionSystem::CommunicationSearch),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                config_reload_system
+                    .guarded()
+                    .label(ActionSystem::ConfigReload),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                cutscene_line_system
+                    .guarded()
+                    .label(ActionSystem::CutsceneLine),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                consume_system.guarded().label(ActionSystem::Consume),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                db_check_system.guarded().label(ActionSystem::DbCheck),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                deposit_system.guarded().label(ActionSystem::Deposit),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                drop_system.guarded().label(ActionSystem::Drop),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                economy_report_system
+                    .guarded()
+                    .label(ActionSystem::EconomyReport),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
                 emote_system
-                    .system()
+                    .guarded()
                     .label(ActionSystem::Emote)
                     .after(ActionSystem::Look),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                exits_system.system().label(ActionSystem::Exits),
+                exits_system.guarded().label(ActionSystem::Exits),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                get_system.system().label(ActionSystem::Get),
+                faction_create_system
+                    .guarded()
+                    .label(ActionSystem::FactionCreate),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                initialize_system.system().label(ActionSystem::Initialize),
+                faction_list_system
+                    .guarded()
+                    .label(ActionSystem::FactionList),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                inventory_system.system().label(ActionSystem::Inventory),
+                faction_update_system
+                    .guarded()
+                    .label(ActionSystem::FactionUpdate),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                login_system.system().label(ActionSystem::Login),
+                force_system.guarded().label(ActionSystem::Force),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                look_at_system.system().label(ActionSystem::LookAt),
+                get_system.guarded().label(ActionSystem::Get),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                look_system.system().label(ActionSystem::Look),
+                global_list_system.guarded().label(ActionSystem::GlobalList),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                message_system.system().label(ActionSystem::Message),
+                global_update_system
+                    .guarded()
+                    .label(ActionSystem::GlobalUpdate),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                move_system.system().label(ActionSystem::Move),
+                grant_achievement_system
+                    .guarded()
+                    .label(ActionSystem::GrantAchievement),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                object_create_system
-                    .system()
-                    .label(ActionSystem::ObjectCreate),
+                hints_system.guarded().label(ActionSystem::Hints),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                object_info_system.system().label(ActionSystem::ObjectInfo),
+                ignore_system.guarded().label(ActionSystem::Ignore),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                object_inherit_fields_system
-                    .system()
-                    .label(ActionSystem::ObjectInheritFields),
+                initialize_system.guarded().label(ActionSystem::Initialize),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                object_remove_system
-                    .system()
-                    .label(ActionSystem::ObjectRemove),
+                inventory_system.guarded().label(ActionSystem::Inventory),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                player_info_system.system().label(ActionSystem::PlayerInfo),
+                lint_world_system.guarded().label(ActionSystem::LintWorld),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                player_update_flags_system
-                    .system()
-                    .label(ActionSystem::PlayerUpdateFlags),
+                login_system.guarded().label(ActionSystem::Login),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                prototype_create_system
-                    .system()
-                    .label(ActionSystem::PrototypeCreate),
+                look_at_system.guarded().label(ActionSystem::LookAt),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                prototype_info_system
-                    .system()
-                    .label(ActionSystem::PrototypeInfo),
+                look_system.guarded().label(ActionSystem::Look),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                prototype_list_system
-                    .system()
-                    .label(ActionSystem::PrototypeList),
+                loot_table_create_system
+                    .guarded()
+                    .label(ActionSystem::LootTableCreate),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                restart_system.system().label(ActionSystem::Restart),
+                loot_table_info_system
+                    .guarded()
+                    .label(ActionSystem::LootTableInfo),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                room_create_system.system().label(ActionSystem::RoomCreate),
+                loot_table_list_system
+                    .guarded()
+                    .label(ActionSystem::LootTableList),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                room_info_system.system().label(ActionSystem::RoomInfo),
+                loot_table_remove_system
+                    .guarded()
+                    .label(ActionSystem::LootTableRemove),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                room_link_system.system().label(ActionSystem::RoomLink),
+                loot_table_roll_system
+                    .guarded()
+                    .label(ActionSystem::LootTableRoll),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                room_remove_system.system().label(ActionSystem::RoomRemove),
+                loot_table_update_system
+                    .guarded()
+                    .label(ActionSystem::LootTableUpdate),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                room_unlink_system.system().label(ActionSystem::RoomUnlink),
+                mail_claim_system.guarded().label(ActionSystem::MailClaim),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                room_update_regions_system
-                    .system()
-                    .label(ActionSystem::RoomUpdateRegions),
+                mail_delete_system.guarded().label(ActionSystem::MailDelete),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                say_system
-                    .system()
-                    .label(ActionSystem::Say)
-                    .after(ActionSystem::Look),
+                mail_list_system.guarded().label(ActionSystem::MailList),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                script_attach_system
-                    .system()
-                    .label(ActionSystem::ScriptAttach),
+                mail_read_system.guarded().label(ActionSystem::MailRead),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                script_detach_system
-                    .system()
-                    .label(ActionSystem::ScriptDetach),
+                mail_send_system.guarded().label(ActionSystem::MailSend),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                send_message_system
-                    .system()
-                    .label(ActionSystem::Send)
-                    .after(ActionSystem::Look),
+                maintenance_off_system
+                    .guarded()
+                    .label(ActionSystem::MaintenanceOff),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                show_error_system.system().label(ActionSystem::ShowError),
+                maintenance_on_system
+                    .guarded()
+                    .label(ActionSystem::MaintenanceOn),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                shutdown_system.system().label(ActionSystem::Shutdown),
+                map_system.guarded().label(ActionSystem::Map),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                stats_system.system().label(ActionSystem::Stats),
+                market_buy_system.guarded().label(ActionSystem::MarketBuy),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                teleport_system.system().label(ActionSystem::Teleport),
+                market_cancel_system
+                    .guarded()
+                    .label(ActionSystem::MarketCancel),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                update_description_system
-                    .system()
-                    .label(ActionSystem::UpdateDescription),
+                market_list_system.guarded().label(ActionSystem::MarketList),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                update_keywords_system
-                    .system()
-                    .label(ActionSystem::UpdateKeywords),
+                market_sell_system.guarded().label(ActionSystem::MarketSell),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                update_name_system.system().label(ActionSystem::UpdateName),
+                message_system.guarded().label(ActionSystem::Message),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                update_object_flags
-                    .system()
-                    .label(ActionSystem::UpdateObjectFlags),
+                move_system.guarded().label(ActionSystem::Move),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                use_system.system().label(ActionSystem::Use),
+                news_delete_system.guarded().label(ActionSystem::NewsDelete),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                whisper_system
-                    .system()
-                    .label(ActionSystem::Whisper)
-                    .after(ActionSystem::Look),
+                news_list_system.guarded().label(ActionSystem::NewsList),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                news_post_system.guarded().label(ActionSystem::NewsPost),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                object_create_system
+                    .guarded()
+                    .label(ActionSystem::ObjectCreate),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                object_info_system.guarded().label(ActionSystem::ObjectInfo),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                object_inherit_fields_system
+                    .guarded()
+                    .label(ActionSystem::ObjectInheritFields),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                object_remove_system
+                    .guarded()
+                    .label(ActionSystem::ObjectRemove),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                player_info_system.guarded().label(ActionSystem::PlayerInfo),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                player_rename_system
+                    .guarded()
+                    .label(ActionSystem::PlayerRename),
             )
             .add_system(
                 Step::Main,
                 Phase::Update,
-                who_system.system().label(ActionSystem::Who),
-            );
+                player_update_flags_system
+                    .guarded()
+                    .label(ActionSystem::PlayerUpdateFlags),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                poll_close_system.guarded().label(ActionSystem::PollClose),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                poll_create_system.guarded().label(ActionSystem::PollCreate),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                poll_info_system.guarded().label(ActionSystem::PollInfo),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                poll_list_system.guarded().label(ActionSystem::PollList),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                possess_system.guarded().label(ActionSystem::Possess),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                privacy_system.guarded().label(ActionSystem::Privacy),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                profile_system.guarded().label(ActionSystem::Profile),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                prototype_behavior_system
+                    .guarded()
+                    .label(ActionSystem::PrototypeBehavior),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                prototype_bulk_system
+                    .guarded()
+                    .label(ActionSystem::PrototypeBulk),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                prototype_create_system
+                    .guarded()
+                    .label(ActionSystem::PrototypeCreate),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                prototype_info_system
+                    .guarded()
+                    .label(ActionSystem::PrototypeInfo),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                prototype_list_system
+                    .guarded()
+                    .label(ActionSystem::PrototypeList),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                publish_system.guarded().label(ActionSystem::Publish),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                recall_system.guarded().label(ActionSystem::Recall),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                region_disown_system
+                    .guarded()
+                    .label(ActionSystem::RegionDisown),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                region_loaded_system
+                    .guarded()
+                    .label(ActionSystem::RegionLoaded),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                region_owner_system
+                    .guarded()
+                    .label(ActionSystem::RegionOwner),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                region_show_system.guarded().label(ActionSystem::RegionShow),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                release_system.guarded().label(ActionSystem::Release),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                report_system.guarded().label(ActionSystem::Report),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                restart_system.guarded().label(ActionSystem::Restart),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                room_create_system.guarded().label(ActionSystem::RoomCreate),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                room_echo_system.guarded().label(ActionSystem::RoomEcho),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                room_info_system.guarded().label(ActionSystem::RoomInfo),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                room_link_system.guarded().label(ActionSystem::RoomLink),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                room_remove_system.guarded().label(ActionSystem::RoomRemove),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                room_unlink_system.guarded().label(ActionSystem::RoomUnlink),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                room_update_regions_system
+                    .guarded()
+                    .label(ActionSystem::RoomUpdateRegions),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                say_system
+                    .guarded()
+                    .label(ActionSystem::Say)
+                    .after(ActionSystem::Look),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                script_api_system.guarded().label(ActionSystem::ScriptApi),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                script_attach_system
+                    .guarded()
+                    .label(ActionSystem::ScriptAttach),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                script_detach_system
+                    .guarded()
+                    .label(ActionSystem::ScriptDetach),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                script_list_system.guarded().label(ActionSystem::ScriptList),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                script_stats_system
+                    .guarded()
+                    .label(ActionSystem::ScriptStatsList),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                script_toggle_system
+                    .guarded()
+                    .label(ActionSystem::ScriptToggle),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                season_info_system.guarded().label(ActionSystem::SeasonInfo),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                season_list_system.guarded().label(ActionSystem::SeasonList),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                season_tag_system.guarded().label(ActionSystem::SeasonTag),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                send_message_system
+                    .guarded()
+                    .label(ActionSystem::Send)
+                    .after(ActionSystem::Look),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                pronouns_system.guarded().label(ActionSystem::SetPronouns),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                shop_buy_system.guarded().label(ActionSystem::ShopBuy),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                shop_create_system.guarded().label(ActionSystem::ShopCreate),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                shop_haggle_system.guarded().label(ActionSystem::ShopHaggle),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                shop_info_system.guarded().label(ActionSystem::ShopInfo),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                shop_list_system.guarded().label(ActionSystem::ShopList),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                shop_remove_system.guarded().label(ActionSystem::ShopRemove),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                shop_update_system.guarded().label(ActionSystem::ShopUpdate),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                shops_list_system.guarded().label(ActionSystem::ShopsList),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                show_achievements_system
+                    .guarded()
+                    .label(ActionSystem::ShowAchievements),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                show_campaigns_system
+                    .guarded()
+                    .label(ActionSystem::ShowCampaigns),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                show_error_system.guarded().label(ActionSystem::ShowError),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                shutdown_system.guarded().label(ActionSystem::Shutdown),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                snoop_approve_system
+                    .guarded()
+                    .label(ActionSystem::SnoopApprove),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                snoop_start_system.guarded().label(ActionSystem::SnoopStart),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                snoop_stop_system.guarded().label(ActionSystem::SnoopStop),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                spawn_table_create_system
+                    .guarded()
+                    .label(ActionSystem::SpawnTableCreate),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                spawn_table_info_system
+                    .guarded()
+                    .label(ActionSystem::SpawnTableInfo),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                spawn_table_list_system
+                    .guarded()
+                    .label(ActionSystem::SpawnTableList),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                spawn_table_remove_system
+                    .guarded()
+                    .label(ActionSystem::SpawnTableRemove),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                spawn_table_update_system
+                    .guarded()
+                    .label(ActionSystem::SpawnTableUpdate),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                stage_discard_system
+                    .guarded()
+                    .label(ActionSystem::StageDiscard),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                stage_region_system
+                    .guarded()
+                    .label(ActionSystem::StageRegion),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                stats_system.guarded().label(ActionSystem::Stats),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                teleport_system.guarded().label(ActionSystem::Teleport),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                top_system.guarded().label(ActionSystem::Top),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                tutorial_hint_system
+                    .guarded()
+                    .label(ActionSystem::TutorialHint),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                tutorial_info_system
+                    .guarded()
+                    .label(ActionSystem::TutorialInfo),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                tutorial_start_system
+                    .guarded()
+                    .label(ActionSystem::TutorialStart),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                unignore_system.guarded().label(ActionSystem::Unignore),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                update_description_system
+                    .guarded()
+                    .label(ActionSystem::UpdateDescription),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                update_field_system
+                    .guarded()
+                    .label(ActionSystem::UpdateField),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                update_keywords_system
+                    .guarded()
+                    .label(ActionSystem::UpdateKeywords),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                update_name_system.guarded().label(ActionSystem::UpdateName),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                update_object_flags
+                    .guarded()
+                    .label(ActionSystem::UpdateObjectFlags),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                use_system.guarded().label(ActionSystem::Use),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                vote_system.guarded().label(ActionSystem::Vote),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                whisper_system
+                    .guarded()
+                    .label(ActionSystem::Whisper)
+                    .after(ActionSystem::Look),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                who_system.guarded().label(ActionSystem::Who),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                withdraw_system.guarded().label(ActionSystem::Withdraw),
+            )
+            .add_system(
+                Step::PostEvent,
+                Phase::Update,
+                record_visits_system.guarded(),
+            )
+            .add_system(Step::PostEvent, Phase::Update, show_hints_system.guarded());
     }
 }
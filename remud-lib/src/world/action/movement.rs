@@ -12,8 +12,11 @@ use crate::{
         action::{observe::Look, Action},
         scripting::QueuedAction,
         types::{
-            player::Messages,
-            room::{Direction, Room, RoomId, Rooms},
+            clan::Clans,
+            grammar::{act, Pronouns, Subject},
+            player::{self, Messages, Player, PlayerFlags, VisitedRooms},
+            room::{Direction, Echo, Room, RoomId, Rooms},
+            season::Seasons,
             Id, Location, Named,
         },
     },
@@ -27,12 +30,21 @@ pub struct Move {
 
 into_action!(Move);
 
-#[tracing::instrument(name = "move system", skip_all)]
+#[tracing::instrument(name = "move system", skip_all, fields(action = "Move"))]
 pub fn move_system(
     mut action_reader: EventReader<Action>,
     mut pre_events: EventWriter<QueuedAction>,
+    clans: Res<Clans>,
+    seasons: Res<Seasons>,
     mut updates: ResMut<Updates>,
-    mut moving_query: Query<(&Id, &Named, &mut Location)>,
+    mut moving_query: Query<(
+        &Id,
+        &Named,
+        Option<&Pronouns>,
+        &mut Location,
+        Option<&PlayerFlags>,
+        Option<&VisitedRooms>,
+    )>,
     mut room_query: Query<&mut Room>,
     mut contents_query: Query<&mut Contents>,
     mut messages_query: Query<&mut Messages>,
@@ -40,9 +52,18 @@ pub fn move_system(
     for action in action_reader.iter() {
         if let Action::Move(Move { actor, direction }) = action {
             // Retrieve information about the moving entity.
-            let (id, name, mut location) =
-                if let Ok((id, named, location)) = moving_query.get_mut(*actor) {
-                    (id, named.as_str(), location)
+            let (id, name, pronouns, mut location, flags, visited) =
+                if let Ok((id, named, pronouns, location, flags, visited)) =
+                    moving_query.get_mut(*actor)
+                {
+                    (
+                        id,
+                        named.as_str(),
+                        pronouns.copied().unwrap_or(Pronouns::It),
+                        location,
+                        flags,
+                        visited,
+                    )
                 } else {
                     tracing::warn!("cannot move {:?} without Named and Location.", actor);
                     continue;
@@ -56,32 +77,88 @@ pub fn move_system(
                 continue;
             };
 
-            let (destination, origin_players, room_id) =
-                if let Some(destination) = current_room.exit(direction) {
-                    (
-                        destination,
-                        current_room
-                            .players()
-                            .iter()
-                            .filter(|present_player| **present_player != *actor)
-                            .copied()
-                            .collect_vec(),
-                        current_room.id(),
-                    )
-                } else {
+            let (
+                destination,
+                travel_message,
+                leave_template,
+                arrive_template,
+                origin_players,
+                room_id,
+            ) = if let Some(exit) = current_room.exits().get(direction) {
+                (
+                    exit.destination,
+                    exit.message.clone(),
+                    exit.echo(Echo::Leave)
+                        .or_else(|| current_room.echo(Echo::Leave))
+                        .map(ToString::to_string),
+                    exit.echo(Echo::Arrive).map(ToString::to_string),
+                    current_room
+                        .players()
+                        .iter()
+                        .filter(|present_player| **present_player != *actor)
+                        .copied()
+                        .collect_vec(),
+                    current_room.id(),
+                )
+            } else {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(format!("There is no exit {}.", direction.as_to_str()));
+                }
+                continue;
+            };
+
+            let immortal = flags
+                .map(|flags| flags.contains(player::Flags::IMMORTAL))
+                .unwrap_or(false);
+
+            // Exits and rooms belonging to a season which is not under way do not exist, except
+            // to immortals building them.
+            if !immortal {
+                let passable = room_query
+                    .get_mut(destination)
+                    .map(|room| seasons.is_passable(room_id, direction, room.id()))
+                    .unwrap_or(true);
+
+                if !passable {
                     if let Ok(mut messages) = messages_query.get_mut(*actor) {
                         messages.queue(format!("There is no exit {}.", direction.as_to_str()));
                     }
                     continue;
-                };
+                }
+            }
 
-            // Notify players in the origin room that something is leaving.
-            let leave_message = format!("{} leaves {}.", name, direction.as_to_str());
+            // Clan halls are closed to players outside of the clan, except immortals.
+            if let Id::Player(player_id) = id {
+                let hall = room_query
+                    .get_mut(destination)
+                    .ok()
+                    .and_then(|room| clans.by_hall(room.id()));
+
+                if let Some(clan) = hall {
+                    if !immortal && clan.member(*player_id).is_none() {
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(format!(
+                                "Only members of {} may enter their clan hall.",
+                                clan.name
+                            ));
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            // Notify players in the origin room that something is leaving. Exits and rooms may
+            // replace the usual message with their own.
+            let subject = Subject::new(name, pronouns);
+            let leave_message = match leave_template {
+                Some(template) => act(template.as_str(), subject, None),
+                None => format!("{} leaves {}.", name, direction.as_to_str()),
+            };
             for player in origin_players {
                 messages_query
                     .get_mut(player)
                     .unwrap_or_else(|_| panic!("Player {:?} has Messages.", player))
-                    .queue(leave_message.clone());
+                    .queue_broadcast(leave_message.clone());
             }
 
             // Retrieve information about the destination room.
@@ -91,12 +168,17 @@ pub fn move_system(
 
             let destination_id = destination_room.id();
 
-            let from_direction = destination_room
-                .exits()
-                .iter()
-                .find(|(_, room)| **room == location.entity())
-                .map(|(direction, _)| direction)
-                .copied();
+            // Players in brief mode skip the descriptions of rooms they have already visited.
+            let brief = flags
+                .map(|flags| flags.contains(player::Flags::BRIEF))
+                .unwrap_or(false)
+                && visited
+                    .map(|visited| visited.contains(&destination_id))
+                    .unwrap_or(false);
+
+            let from_direction = destination_room.exit_to(location.entity()).cloned();
+            let arrive_template = arrive_template
+                .or_else(|| destination_room.echo(Echo::Arrive).map(ToString::to_string));
 
             let destination_players = destination_room
                 .players()
@@ -131,27 +213,38 @@ pub fn move_system(
             location.set_entity(destination);
 
             // Notify players in the destination room that something has arrived.
-            let arrive_message = from_direction.map_or_else(
-                || format!("{} appears.", name),
-                |from| format!("{} arrives {}.", name, from.as_from_str()),
-            );
+            let arrive_message = match arrive_template {
+                Some(template) => act(template.as_str(), subject, None),
+                None => from_direction.map_or_else(
+                    || format!("{} appears.", name),
+                    |from| format!("{} arrives {}.", name, from.as_from_str()),
+                ),
+            };
 
             for player in destination_players {
                 messages_query
                     .get_mut(player)
                     .unwrap_or_else(|_| panic!("Player {:?} has Messages.", player))
-                    .queue(arrive_message.clone());
+                    .queue_broadcast(arrive_message.clone());
             }
 
             // Dispatch a storage update to the new location.
             match id {
                 Id::Player(id) => {
+                    // Exits may tell those taking them about the journey.
+                    if let Some(message) = travel_message {
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(message);
+                        }
+                    }
+
                     // TODO: why is there a * below?
                     updates.persist(persist::player::Room::new(*id, destination_id));
                     pre_events.send(
                         Action::Look(Look {
                             actor: *actor,
                             direction: None,
+                            brief,
                         })
                         .into(),
                     );
@@ -192,7 +285,7 @@ pub struct Teleport {
 
 into_action!(Teleport);
 
-#[tracing::instrument(name = "teleport system", skip_all)]
+#[tracing::instrument(name = "teleport system", skip_all, fields(action = "Teleport"))]
 pub fn teleport_system(
     mut action_reader: EventReader<Action>,
     mut pre_events: EventWriter<QueuedAction>,
@@ -204,6 +297,11 @@ pub fn teleport_system(
 ) {
     for action in action_reader.iter() {
         if let Action::Teleport(Teleport { actor, room_id }) = action {
+            if rooms.is_unloaded(*room_id) {
+                updates.load_room(*room_id, action.clone());
+                continue;
+            }
+
             let destination = if let Some(entity) = rooms.by_id(*room_id) {
                 entity
             } else {
@@ -243,7 +341,7 @@ pub fn teleport_system(
                 messages_query
                     .get_mut(player)
                     .unwrap_or_else(|_| panic!("Player {:?} has Messages.", player))
-                    .queue(leave_message.clone());
+                    .queue_broadcast(leave_message.clone());
             }
 
             // Retrieve information about the destination room.
@@ -281,7 +379,7 @@ pub fn teleport_system(
                 messages_query
                     .get_mut(player)
                     .unwrap_or_else(|_| panic!("Player {:?} has Messages.", player))
-                    .queue(arrive_message.clone());
+                    .queue_broadcast(arrive_message.clone());
             }
 
             // Dispatch a storage update to the new location.
@@ -292,6 +390,7 @@ pub fn teleport_system(
                         action: Action::Look(Look {
                             actor: *actor,
                             direction: None,
+                            brief: false,
                         }),
                     });
                 }
@@ -302,3 +401,19 @@ pub fn teleport_system(
         }
     }
 }
+
+// Records the rooms players enter, which feed the rooms explored leaderboard.
+#[tracing::instrument(name = "record visits system", skip_all)]
+pub fn record_visits_system(
+    mut updates: ResMut<Updates>,
+    mut player_query: Query<(&Player, &Location, &mut VisitedRooms), Changed<Location>>,
+    room_query: Query<&Room>,
+) {
+    for (player, location, mut visited) in player_query.iter_mut() {
+        if let Ok(room) = room_query.get(location.entity()) {
+            if visited.insert(room.id()) {
+                updates.persist(persist::player::Visit::new(player.id(), room.id()));
+            }
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use std::str::FromStr;
+
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+
+use crate::{
+    clock::Clock,
+    engine::persist::{self, UpdateGroup, Updates},
+    text::{format_duration, Tokenizer},
+    world::{
+        action::{into_action, Action},
+        types::{
+            news::{News, NewsEntry, NewsId, NewsRead},
+            player::{self, Messages, Player, PlayerFlags, Players},
+            Named,
+        },
+    },
+};
+
+// how many of the newest entries are shown by the news command
+const RECENT_NEWS: usize = 10;
+
+// Valid shapes:
+// news - shows the newest entries, marking them read
+// news post <message> - posts an entry, immortals only
+// news delete <id> - deletes an entry, immortals only
+pub fn parse_news(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    match tokenizer.next() {
+        None => Ok(Action::from(NewsList { actor: player })),
+        Some("delete") => match tokenizer.next() {
+            Some(token) => Ok(Action::from(NewsDelete {
+                actor: player,
+                id: NewsId::from_str(token).map_err(|e| e.to_string())?,
+            })),
+            None => Err("Enter a news ID.".to_string()),
+        },
+        Some("post") => {
+            if tokenizer.rest().is_empty() {
+                return Err("Post what?".to_string());
+            }
+
+            Ok(Action::from(NewsPost {
+                actor: player,
+                body: tokenizer.rest().to_string(),
+            }))
+        }
+        Some(_) => Err("Enter a valid news subcommand: delete or post.".to_string()),
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct NewsDelete {
+    pub actor: Entity,
+    pub id: NewsId,
+}
+
+into_action!(NewsDelete);
+
+#[tracing::instrument(name = "news delete system", skip_all, fields(action = "NewsDelete"))]
+pub fn news_delete_system(
+    mut action_reader: EventReader<Action>,
+    mut news: ResMut<News>,
+    mut updates: ResMut<Updates>,
+    flags_query: Query<&PlayerFlags>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::NewsDelete(NewsDelete { actor, id }) = action {
+            let message = if !flags_query
+                .get(*actor)
+                .map(|flags| flags.contains(player::Flags::IMMORTAL))
+                .unwrap_or(false)
+            {
+                "Only immortals may delete news.".to_string()
+            } else if news.remove(*id).is_some() {
+                updates.persist(persist::news::Remove::new(*id));
+                format!("You delete news {}.", id)
+            } else {
+                format!("There is no news {}.", id)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct NewsList {
+    pub actor: Entity,
+}
+
+into_action!(NewsList);
+
+#[tracing::instrument(name = "news list system", skip_all, fields(action = "NewsList"))]
+pub fn news_list_system(
+    mut action_reader: EventReader<Action>,
+    clock: Res<Clock>,
+    news: Res<News>,
+    mut updates: ResMut<Updates>,
+    mut player_query: Query<(&Player, &mut NewsRead)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::NewsList(NewsList { actor }) = action {
+            let (player, mut read) = match player_query.get_mut(*actor) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+
+            let now = clock.unix_now();
+            let mut message = "|white|News|-|".to_string();
+
+            for (id, entry) in news.recent(RECENT_NEWS) {
+                message.push_str(
+                    format!(
+                        "\r\n  |white|{}|-|. from {}, {} ago",
+                        id,
+                        entry.author,
+                        format_duration((now - entry.posted_at).max(0))
+                    )
+                    .as_str(),
+                );
+
+                if read.is_unread(*id) {
+                    message.push_str(" |SteelBlue3|(new)|-|");
+                }
+
+                message.push_str(format!("\r\n    {}", entry.body).as_str());
+            }
+
+            match news.latest() {
+                Some(latest) => {
+                    if read.mark(latest) {
+                        updates.persist(persist::news::Read::new(player.id(), read.last()));
+                    }
+                }
+                None => message = "There is no news.".to_string(),
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct NewsPost {
+    pub actor: Entity,
+    pub body: String,
+}
+
+into_action!(NewsPost);
+
+#[tracing::instrument(name = "news post system", skip_all, fields(action = "NewsPost"))]
+pub fn news_post_system(
+    mut action_reader: EventReader<Action>,
+    clock: Res<Clock>,
+    mut news: ResMut<News>,
+    players: Res<Players>,
+    mut updates: ResMut<Updates>,
+    mut player_query: Query<(&Player, &Named, &PlayerFlags, &mut NewsRead)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::NewsPost(NewsPost { actor, body }) = action {
+            let (player, name, flags, mut read) = match player_query.get_mut(*actor) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+
+            if !flags.contains(player::Flags::IMMORTAL) {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue("Only immortals may post news.".to_string());
+                }
+                continue;
+            }
+
+            let id = news.next_id();
+            let posted_at = clock.unix_now();
+
+            news.insert(
+                id,
+                NewsEntry {
+                    author: name.to_string(),
+                    body: body.clone(),
+                    posted_at,
+                },
+            );
+
+            // The author has obviously read what they just posted.
+            read.mark(id);
+
+            updates.persist(UpdateGroup::new(vec![
+                persist::news::Create::new(id, name.to_string(), body.clone(), posted_at),
+                persist::news::Read::new(player.id(), id),
+            ]));
+
+            let announcement = format!(
+                "|SteelBlue3|{} posted news. Type \"news\" to read it.|-|",
+                name
+            );
+
+            for (_, online) in players.iter().filter(|(_, online)| *online != *actor) {
+                if let Ok(mut messages) = messages_query.get_mut(online) {
+                    messages.queue(announcement.clone());
+                }
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!("You post news {}.", id));
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use bevy_app::EventReader;
+use bevy_app::{EventReader, EventWriter};
 use bevy_ecs::prelude::*;
 use itertools::Itertools;
 
@@ -6,18 +6,261 @@ use crate::world::action::get_room_std;
 use crate::world::scripting::{ScriptHooks, TriggerEvent};
 use crate::{
     engine::persist::{self, Updates},
-    text::Tokenizer,
+    text::{header, Align, Table, Tokenizer, DEFAULT_WIDTH},
     world::{
         action::{into_action, Action},
+        behavior::{self, BehaviorEvent},
         types::{
-            object::{Flags, Keywords, Object, ObjectFlags},
-            player::Messages,
+            grammar::{act, Pronouns, Subject},
+            object::{Behaviors, Bulk, Flags, Keywords, Object, ObjectFlags, Objects},
+            player::{ClientCaps, Messages, Needs},
             room::Room,
-            Contents, Id, Location, Named,
+            Attributes, Contents, Id, Location, Named,
         },
     },
 };
 
+// Tells the other players present that the actor did something with an object.
+fn notify_room(
+    template: &str,
+    actor: Entity,
+    object_name: &str,
+    present: &[Entity],
+    actor_query: &Query<(&Named, Option<&Pronouns>)>,
+    messages_query: &mut Query<&mut Messages>,
+) {
+    let actor_subject = match actor_query.get(actor) {
+        Ok((named, pronouns)) => {
+            Subject::new(named.as_str(), pronouns.copied().unwrap_or(Pronouns::It))
+        }
+        Err(_) => return,
+    };
+
+    let message = act(
+        template,
+        actor_subject,
+        Some(Subject::new(object_name, Pronouns::It)),
+    );
+
+    for player in present.iter().filter(|player| **player != actor) {
+        if let Ok(mut messages) = messages_query.get_mut(*player) {
+            messages.queue_broadcast(message.clone());
+        }
+    }
+}
+
+// The weight and size of an object, taken from the prototype it was made from.
+fn object_bulk(
+    object: Entity,
+    prototype_query: &Query<&Object>,
+    bulk_query: &Query<&Bulk>,
+) -> Bulk {
+    prototype_query
+        .get(object)
+        .ok()
+        .and_then(|object| bulk_query.get(object.prototype()).ok())
+        .copied()
+        .unwrap_or_default()
+}
+
+/// The total weight and size of a list of carried objects.
+pub fn carried_bulk(
+    carried: &[Entity],
+    prototype_query: &Query<&Object>,
+    bulk_query: &Query<&Bulk>,
+) -> Bulk {
+    carried.iter().fold(Bulk::default(), |total, object| {
+        total + object_bulk(*object, prototype_query, bulk_query)
+    })
+}
+
+/// Explains why a carrier cannot take on another object, if it would leave them carrying more
+/// than their attributes allow. Carriers without attributes have no limit.
+pub fn carry_limit(
+    carrier: Entity,
+    carried: &[Entity],
+    object: Entity,
+    name: &str,
+    attributes_query: &Query<&Attributes>,
+    prototype_query: &Query<&Object>,
+    bulk_query: &Query<&Bulk>,
+) -> Option<String> {
+    bulk_limit(
+        carrier,
+        carried,
+        object_bulk(object, prototype_query, bulk_query),
+        name,
+        attributes_query,
+        prototype_query,
+        bulk_query,
+    )
+}
+
+/// As carry_limit, for something of the given bulk which is not an object yet, such as an item
+/// for sale in a shop.
+pub fn bulk_limit(
+    carrier: Entity,
+    carried: &[Entity],
+    bulk: Bulk,
+    name: &str,
+    attributes_query: &Query<&Attributes>,
+    prototype_query: &Query<&Object>,
+    bulk_query: &Query<&Bulk>,
+) -> Option<String> {
+    let capacity = attributes_query.get(carrier).ok()?.carry_capacity();
+    let total = carried_bulk(carried, prototype_query, bulk_query) + bulk;
+
+    if total.weight > capacity.weight {
+        Some(format!(
+            "You cannot carry the weight of {} along with everything else.",
+            name
+        ))
+    } else if total.size > capacity.size {
+        Some(format!("You have no room left to carry {}.", name))
+    } else {
+        None
+    }
+}
+
+pub fn parse_drink(player: Entity, tokenizer: Tokenizer) -> Result<Action, String> {
+    if tokenizer.rest().is_empty() {
+        return Err("Drink what?".to_string());
+    }
+
+    let keywords = tokenizer
+        .rest()
+        .split_whitespace()
+        .map(ToString::to_string)
+        .collect_vec();
+
+    Ok(Action::from(Drink {
+        actor: player,
+        keywords,
+    }))
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Drink {
+    pub actor: Entity,
+    pub keywords: Vec<String>,
+}
+
+into_action!(Drink);
+
+pub fn parse_eat(player: Entity, tokenizer: Tokenizer) -> Result<Action, String> {
+    if tokenizer.rest().is_empty() {
+        return Err("Eat what?".to_string());
+    }
+
+    let keywords = tokenizer
+        .rest()
+        .split_whitespace()
+        .map(ToString::to_string)
+        .collect_vec();
+
+    Ok(Action::from(Eat {
+        actor: player,
+        keywords,
+    }))
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Eat {
+    pub actor: Entity,
+    pub keywords: Vec<String>,
+}
+
+into_action!(Eat);
+
+// Eating and drinking use up a carried object flagged as food or drink, satisfying the
+// consumer's hunger or thirst.
+#[tracing::instrument(
+    name = "consume system",
+    skip_all,
+    fields(action = tracing::field::Empty)
+)]
+pub fn consume_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    mut objects: ResMut<Objects>,
+    mut updates: ResMut<Updates>,
+    mut consumer_query: Query<(&Location, &mut Contents, Option<&mut Needs>), Without<Room>>,
+    actor_query: Query<(&Named, Option<&Pronouns>)>,
+    object_query: Query<(&Object, &Named, &Keywords, &ObjectFlags)>,
+    room_query: Query<&Room>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        let (actor, keywords, flag) = match action {
+            Action::Drink(Drink { actor, keywords }) => (actor, keywords, Flags::DRINK),
+            Action::Eat(Eat { actor, keywords }) => (actor, keywords, Flags::FOOD),
+            _ => continue,
+        };
+        tracing::Span::current().record("action", &<&str>::from(action));
+        let (verb, template) = if flag == Flags::FOOD {
+            ("eat", "$n eats $N.")
+        } else {
+            ("drink", "$n drinks $N.")
+        };
+
+        let (location, mut contents, needs) = match consumer_query.get_mut(*actor) {
+            Ok(consumer) => consumer,
+            Err(_) => {
+                tracing::warn!("entity {:?} without Contents cannot {}.", actor, verb);
+                continue;
+            }
+        };
+
+        let target = contents.find(|object| {
+            object_query
+                .get(object)
+                .map(|(_, _, object_keywords, _)| object_keywords.contains_all(keywords.as_slice()))
+                .unwrap_or(false)
+        });
+
+        let message = if let Some(entity) = target {
+            let (object, named, _, flags) = object_query.get(entity).unwrap();
+            let name = named.as_str();
+
+            if flags.contains(flag) {
+                contents.remove(entity);
+                objects.remove(object.id());
+                commands.entity(entity).despawn();
+                updates.persist(persist::object::Remove::new(object.id()));
+
+                if let Some(mut needs) = needs {
+                    if flag == Flags::FOOD {
+                        needs.eat();
+                    } else {
+                        needs.drink();
+                    }
+                }
+
+                if let Ok(room) = room_query.get(location.entity()) {
+                    notify_room(
+                        template,
+                        *actor,
+                        name,
+                        room.players(),
+                        &actor_query,
+                        &mut messages_query,
+                    );
+                }
+
+                format!("You {} {}.", verb, name)
+            } else {
+                format!("You cannot {} {}.", verb, name)
+            }
+        } else {
+            format!("You don't have \"{}\".", keywords.join(" "))
+        };
+
+        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+            messages.queue(message);
+        }
+    }
+}
+
 pub fn parse_drop(player: Entity, tokenizer: Tokenizer) -> Result<Action, String> {
     if tokenizer.rest().is_empty() {
         return Err("Drop what?".to_string());
@@ -43,12 +286,13 @@ pub struct Drop {
 
 into_action!(Drop);
 
-#[tracing::instrument(name = "drop system", skip_all)]
+#[tracing::instrument(name = "drop system", skip_all, fields(action = "Drop"))]
 pub fn drop_system(
     mut commands: Commands,
     mut action_reader: EventReader<Action>,
     mut updates: ResMut<Updates>,
     mut dropping_query: Query<(&Id, &Location, &mut Contents), Without<Room>>,
+    actor_query: Query<(&Named, Option<&Pronouns>)>,
     object_query: Query<(&Object, &Named, &Keywords)>,
     mut room_query: Query<(&Room, &mut Contents), With<Room>>,
     mut messages_query: Query<&mut Messages>,
@@ -81,7 +325,7 @@ pub fn drop_system(
                     .map(|(_, _, mut contents)| contents.remove(entity))
                     .unwrap();
 
-                let room_id = {
+                let (room_id, present) = {
                     let (room, mut contents) = if let Ok(room) = room_query.get_mut(room_entity) {
                         room
                     } else {
@@ -93,7 +337,7 @@ pub fn drop_system(
                     };
 
                     contents.insert(entity);
-                    room.id()
+                    (room.id(), room.players().to_vec())
                 };
 
                 let (object_id, name) = {
@@ -114,6 +358,15 @@ pub fn drop_system(
                 }
                 updates.persist(persist::room::AddObject::new(room_id, object_id));
 
+                notify_room(
+                    "$n drops $N.",
+                    *actor,
+                    name,
+                    present.as_slice(),
+                    &actor_query,
+                    &mut messages_query,
+                );
+
                 format!("You drop {}.", name)
             } else {
                 format!("You don't have \"{}\".", keywords.join(" "))
@@ -151,14 +404,19 @@ pub struct Get {
 
 into_action!(Get);
 
-#[tracing::instrument(name = "get system", skip_all)]
+#[tracing::instrument(name = "get system", skip_all, fields(action = "Get"))]
 pub fn get_system(
     mut commands: Commands,
     mut action_reader: EventReader<Action>,
+    mut behavior_writer: EventWriter<BehaviorEvent>,
     mut updates: ResMut<Updates>,
     mut getting_query: Query<(&Id, &Location, &mut Contents), Without<Room>>,
+    actor_query: Query<(&Named, Option<&Pronouns>)>,
     object_query: Query<(&Object, &Named, &Keywords, &ObjectFlags)>,
     mut room_query: Query<(&Room, &mut Contents), With<Room>>,
+    attributes_query: Query<&Attributes>,
+    prototype_query: Query<&Object>,
+    bulk_query: Query<&Bulk>,
     mut messages_query: Query<&mut Messages>,
 ) {
     for action in action_reader.iter() {
@@ -192,7 +450,7 @@ pub fn get_system(
 
             let message = if let Some(entity) = target {
                 // Move the object from the room to the entity
-                let (room_id, object_entity) = {
+                let (room_id, object_entity, present) = {
                     let (room, mut contents) = if let Ok(room) = room_query.get_mut(room_entity) {
                         room
                     } else {
@@ -213,9 +471,31 @@ pub fn get_system(
                         continue;
                     }
 
+                    if let Some(message) =
+                        getting_query
+                            .get_mut(*actor)
+                            .ok()
+                            .and_then(|(_, _, carried)| {
+                                carry_limit(
+                                    *actor,
+                                    carried.objects(),
+                                    entity,
+                                    named.as_str(),
+                                    &attributes_query,
+                                    &prototype_query,
+                                    &bulk_query,
+                                )
+                            })
+                    {
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(message);
+                        }
+                        continue;
+                    }
+
                     contents.remove(entity);
 
-                    (room.id(), entity)
+                    (room.id(), entity, room.players().to_vec())
                 };
 
                 getting_query
@@ -244,6 +524,20 @@ pub fn get_system(
                 }
                 updates.persist(persist::room::RemoveObject::new(room_id, object_id));
 
+                notify_room(
+                    "$n picks up $N.",
+                    *actor,
+                    name,
+                    present.as_slice(),
+                    &actor_query,
+                    &mut messages_query,
+                );
+
+                behavior_writer.send(BehaviorEvent::Taken {
+                    actor: *actor,
+                    object: object_entity,
+                });
+
                 format!("You pick up {}.", name)
             } else {
                 format!(
@@ -266,17 +560,19 @@ pub struct Inventory {
 
 into_action!(Inventory);
 
-#[tracing::instrument(name = "inventory system", skip_all)]
+#[tracing::instrument(name = "inventory system", skip_all, fields(action = "Inventory"))]
 pub fn inventory_system(
     mut action_reader: EventReader<Action>,
     inventory_query: Query<&Contents>,
     object_query: Query<&Named>,
+    attributes_query: Query<&Attributes>,
+    prototype_query: Query<&Object>,
+    bulk_query: Query<&Bulk>,
+    caps_query: Query<&ClientCaps>,
     mut messages_query: Query<&mut Messages>,
 ) {
     for action in action_reader.iter() {
         if let Action::Inventory(Inventory { actor }) = action {
-            let mut message = "|white|You have".to_string();
-
             let contents = if let Ok(contents) = inventory_query.get(*actor) {
                 contents
             } else {
@@ -287,16 +583,49 @@ pub fn inventory_system(
                 continue;
             };
 
-            if contents.is_empty() {
-                message.push_str(" nothing.|-|");
+            let message = if contents.is_empty() {
+                "|white|You have nothing.|-|".to_string()
             } else {
-                message.push_str(":|-|");
-                for object_entity in contents.objects().iter() {
-                    let named = object_query.get(*object_entity).unwrap();
-                    message.push_str("\r\n  ");
-                    message.push_str(named.as_str());
+                let width = caps_query
+                    .get(*actor)
+                    .map(ClientCaps::width)
+                    .unwrap_or(DEFAULT_WIDTH);
+                let mut table = Table::new(width)
+                    .truncated_column("", Align::Left)
+                    .column("", Align::Right);
+
+                // Identical objects are listed once with a count.
+                contents
+                    .objects()
+                    .iter()
+                    .map(|object| object_query.get(*object).unwrap().to_string())
+                    .sorted()
+                    .dedup_with_count()
+                    .for_each(|(count, name)| {
+                        if count > 1 {
+                            table.row(vec![name, format!("x{}", count)]);
+                        } else {
+                            table.row(vec![name]);
+                        }
+                    });
+
+                let mut message = format!("{}\r\n{}", header("You have:"), table.render());
+
+                // Players also see how much more they can carry.
+                if let Ok(attributes) = attributes_query.get(*actor) {
+                    let carried = carried_bulk(contents.objects(), &prototype_query, &bulk_query);
+                    let capacity = attributes.carry_capacity();
+                    message.push_str(
+                        format!(
+                            "\r\nCarrying weight {}/{}, size {}/{}.",
+                            carried.weight, capacity.weight, carried.size, capacity.size
+                        )
+                        .as_str(),
+                    );
                 }
-            }
+
+                message
+            };
 
             if let Ok(mut messages) = messages_query.get_mut(*actor) {
                 messages.queue(message);
@@ -330,13 +659,15 @@ pub struct Use {
 
 into_action!(Use);
 
-#[tracing::instrument(name = "use system", skip_all)]
+#[tracing::instrument(name = "use system", skip_all, fields(action = "Use"))]
 pub fn use_system(
     mut action_reader: EventReader<Action>,
+    mut behavior_writer: EventWriter<BehaviorEvent>,
     location_query: Query<(Option<&Location>, Option<&Room>)>,
     mut room_query: Query<(&Room, &mut Contents), With<Room>>,
     object_query: Query<(&Object, &Named, &Keywords)>,
     scripts_query: Query<&ScriptHooks>,
+    behaviors_query: Query<&Behaviors>,
     mut messages_query: Query<&mut Messages>,
 ) {
     for action in action_reader.iter() {
@@ -363,15 +694,19 @@ pub fn use_system(
                 .expect("Location has a valid room.");
 
             let message = if let Some(entity) = target {
-                let name = {
-                    let (_, named, _) = object_query.get(entity).unwrap();
-                    named.as_str()
-                };
-                if scripts_query
+                let (object, named, _) = object_query.get(entity).unwrap();
+                let name = named.as_str();
+
+                let scripted = scripts_query
                     .get(entity)
                     .map(|script_hooks| script_hooks.triggers_on(TriggerEvent::Use))
-                    .unwrap_or(false)
-                {
+                    .unwrap_or(false);
+
+                if scripted || behavior::usable(object, &behaviors_query) {
+                    behavior_writer.send(BehaviorEvent::Used {
+                        actor: *actor,
+                        object: entity,
+                    });
                     format!("You use {}.", name)
                 } else {
                     format!("You can't figure out how to use {}.", name)
@@ -6,14 +6,23 @@ use itertools::Itertools;
 
 use crate::world::action::targeting::{Params, Target, TargetFinder};
 use crate::{
-    text::{sorted_word_list, Tokenizer},
+    clock::Clock,
+    engine::persist::{self, Updates},
+    markup::link,
+    text::{header, sorted_word_list, word_list, Align, Table, Tokenizer, DEFAULT_WIDTH},
     world::{
         action::{get_room_std, into_action, Action},
+        scripting::ScriptData,
         types::{
-            object::{Flags, ObjectFlags},
-            player::{Messages, Player},
-            room::{Direction, Room},
-            Contents, Description, Location, Named,
+            environment::{TimeOfDay, Weather},
+            grammar::{act, Pronouns, Subject},
+            leaderboard::{LeaderboardCategory, Leaderboards},
+            object::{Bulk, Flags, Keywords, Object, ObjectFlags},
+            player::{self, ClientCaps, Messages, Player, PlayerFlags, HISTORY_LENGTH},
+            room::{Direction, Regions, Room, Shadow, Staging},
+            season::Seasons,
+            template::{render_description, TemplateContext},
+            Contents, Description, Health, Location, Named,
         },
     },
 };
@@ -40,6 +49,7 @@ pub fn parse_look(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, St
                 Ok(Action::from(Look {
                     actor: player,
                     direction: Some(direction),
+                    brief: false,
                 }))
             } else {
                 Err(format!("I don't know how to look {}.", token))
@@ -48,6 +58,7 @@ pub fn parse_look(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, St
         None => Ok(Action::from(Look {
             actor: player,
             direction: None,
+            brief: false,
         })),
     }
 }
@@ -56,27 +67,62 @@ pub fn parse_look(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, St
 pub struct Look {
     pub actor: Entity,
     pub direction: Option<Direction>,
+    // omits the room's description, for players in brief mode entering rooms they have visited
+    pub brief: bool,
 }
 
 into_action!(Look);
 
-#[tracing::instrument(name = "look system", skip_all)]
+#[tracing::instrument(name = "look system", skip_all, fields(action = "Look"))]
 pub fn look_system(
     mut action_reader: EventReader<Action>,
+    clock: Res<Clock>,
+    staging: Res<Staging>,
+    weather: Res<Weather>,
+    seasons: Res<Seasons>,
     looker_query: Query<(Option<&Location>, Option<&Room>)>,
-    room_query: Query<(&Room, &Named, &Description, &Contents)>,
+    room_query: Query<(
+        &Room,
+        &Named,
+        &Description,
+        &Contents,
+        &Regions,
+        Option<&ScriptData>,
+    )>,
+    shadow_query: Query<(&Named, &Description), With<Shadow>>,
     player_query: Query<&Named>,
-    object_query: Query<(&Named, &ObjectFlags)>,
+    object_query: Query<(&Named, &ObjectFlags, &Keywords)>,
+    flags_query: Query<&PlayerFlags>,
     mut messages_query: Query<&mut Messages>,
 ) {
     for action in action_reader.iter() {
-        if let Action::Look(Look { actor, direction }) = action {
+        if let Action::Look(Look {
+            actor,
+            direction,
+            brief,
+        }) = action
+        {
             let current_room = get_room_std(*actor, &looker_query);
 
             let target_room = if let Some(direction) = direction {
+                let immortal = flags_query
+                    .get(*actor)
+                    .map(|flags| flags.contains(player::Flags::IMMORTAL))
+                    .unwrap_or(false);
+
                 if let Some(room) = room_query
                     .get(current_room)
-                    .map(|(room, _, _, _)| room.exit(direction))
+                    .map(|(room, _, _, _, _, _)| {
+                        room.exit(direction).filter(|destination| {
+                            immortal
+                                || room_query
+                                    .get(*destination)
+                                    .map(|(destination, _, _, _, _, _)| {
+                                        seasons.is_passable(room.id(), direction, destination.id())
+                                    })
+                                    .unwrap_or(true)
+                        })
+                    })
                     .expect("Location has a valid room.")
                 {
                     room
@@ -90,11 +136,34 @@ pub fn look_system(
                 current_room
             };
 
-            let (room, named, description, contents) = room_query.get(target_room).unwrap();
+            let (room, named, description, contents, regions, data) =
+                room_query.get(target_room).unwrap();
+
+            // Builders see the staged version of rooms they are staging.
+            let (mut message, named, description) =
+                if let Some(shadow) = staging.shadow(*actor, target_room) {
+                    let (named, description) = shadow_query.get(shadow).unwrap();
+                    ("|yellow|(staged)|-| ".to_string(), named, description)
+                } else {
+                    (String::new(), named, description)
+                };
 
-            let mut message = format!("|white|{}|-|\r\n", named.as_str());
+            message.push_str(format!("|white|{}|-|", named.as_str()).as_str());
+
+            if !brief {
+                let regions = regions.get_list();
+                let context = TemplateContext {
+                    time: TimeOfDay::at(clock.system_time()),
+                    weather: regions
+                        .iter()
+                        .filter_map(|region| weather.get(region.as_str()))
+                        .collect(),
+                    data,
+                };
 
-            message.push_str(description.as_str());
+                message.push_str("\r\n");
+                message.push_str(render_description(description.as_str(), &context).as_str());
+            }
 
             let present_names = room
                 .players()
@@ -119,17 +188,25 @@ pub fn look_system(
                 message.push_str(player_list.as_str());
             }
 
+            // Clicking an object offers to look at it or pick it up.
             let objects = contents
                 .objects()
                 .iter()
                 .filter_map(|object| object_query.get(*object).ok())
-                .filter(|(_, flags)| !flags.contains(Flags::SUBTLE))
-                .map(|(named, _)| named.to_string())
+                .filter(|(_, flags, _)| !flags.contains(Flags::SUBTLE))
+                .sorted_by(|(a, _, _), (b, _, _)| a.as_str().cmp(b.as_str()))
+                .map(|(named, _, keywords)| match keywords.get_list().first() {
+                    Some(keyword) => link(
+                        &[format!("look at {}", keyword), format!("get {}", keyword)],
+                        named.as_str(),
+                    ),
+                    None => named.to_string(),
+                })
                 .collect_vec();
 
             if !objects.is_empty() {
                 message.push_str("\r\nYou see ");
-                message.push_str(sorted_word_list(objects).as_str());
+                message.push_str(word_list(objects).as_str());
                 message.push('.');
             }
 
@@ -148,11 +225,14 @@ pub struct LookAt {
 
 into_action!(LookAt);
 
-#[tracing::instrument(name = "look at system", skip_all)]
+#[tracing::instrument(name = "look at system", skip_all, fields(action = "LookAt"))]
 pub fn look_at_system(
     mut action_reader: EventReader<Action>,
     looker_query: Query<(Option<&Location>, Option<&Room>)>,
     target_finder: TargetFinder,
+    player_query: Query<(&PlayerFlags, &Pronouns, &Health), With<Player>>,
+    prototype_query: Query<&Object>,
+    bulk_query: Query<&Bulk>,
     mut messages_query: Query<&mut Messages>,
 ) {
     for action in action_reader.iter() {
@@ -162,8 +242,41 @@ pub fn look_at_system(
             let target_info =
                 target_finder.resolve(Params::new(*actor, current_room, Some(keywords.clone())));
 
-            let resp: Vec<String> = if let Some(Target { name, desc, .. }) = target_info {
-                vec![format!("|white|{}|-|", name), desc.to_string()]
+            let resp: Vec<String> = if let Some(Target { entity, name, desc }) = target_info {
+                let mut resp = vec![format!("|white|{}|-|", name)];
+
+                match player_query.get(entity) {
+                    // Private players only show how they are faring, except to themselves and
+                    // immortals.
+                    Ok((flags, pronouns, health)) => {
+                        let subject = Subject::new(name.as_str(), *pronouns);
+                        let immortal = player_query.get(*actor).map_or(false, |(flags, _, _)| {
+                            flags.contains(player::Flags::IMMORTAL)
+                        });
+                        if !flags.contains(player::Flags::PRIVATE) || entity == *actor || immortal {
+                            resp.push(desc);
+                        } else {
+                            resp.push(act("$e $v[keeps|keep] to $f.", subject, None));
+                        }
+                        resp.push(act(condition(health), subject, None));
+                    }
+                    Err(_) => resp.push(desc),
+                }
+
+                // Objects with any weight or size say how much they would burden a carrier.
+                if let Some(bulk) = prototype_query
+                    .get(entity)
+                    .ok()
+                    .and_then(|object| bulk_query.get(object.prototype()).ok())
+                    .filter(|bulk| !bulk.is_empty())
+                {
+                    resp.push(format!(
+                        "It has a weight of {} and a size of {}.",
+                        bulk.weight, bulk.size
+                    ));
+                }
+
+                resp
             } else {
                 vec![format!(
                     "You find nothing called \"{}\" to look at.",
@@ -180,6 +293,65 @@ pub fn look_at_system(
     }
 }
 
+// Describes how hurt a player looks, as a template about them.
+fn condition(health: &Health) -> &'static str {
+    let fraction = if health.max > 0.0 {
+        health.current / health.max
+    } else {
+        1.0
+    };
+
+    if fraction >= 1.0 {
+        "$e $v[is|are] in perfect health."
+    } else if fraction >= 0.75 {
+        "$e $v[has|have] a few scratches."
+    } else if fraction >= 0.5 {
+        "$e $v[is|are] wounded."
+    } else if fraction >= 0.25 {
+        "$e $v[is|are] badly wounded."
+    } else {
+        "$e $v[is|are] barely standing."
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Privacy {
+    pub actor: Entity,
+}
+
+into_action!(Privacy);
+
+#[tracing::instrument(name = "privacy system", skip_all, fields(action = "Privacy"))]
+pub fn privacy_system(
+    mut action_reader: EventReader<Action>,
+    mut updates: ResMut<Updates>,
+    mut player_query: Query<(&Player, &mut PlayerFlags)>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Privacy(Privacy { actor }) = action {
+            let (player, mut flags) = match player_query.get_mut(*actor) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+
+            let message = if flags.contains(player::Flags::PRIVATE) {
+                flags.remove(player::Flags::PRIVATE);
+                "Privacy off. Players who look at you will see your description."
+            } else {
+                flags.insert(player::Flags::PRIVATE);
+                "Privacy on. Players who look at you will only see how you are faring."
+            };
+
+            updates.persist(persist::player::Flags::new(player.id(), flags.get_flags()));
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message.to_string());
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct Exits {
     pub actor: Entity,
@@ -187,33 +359,183 @@ pub struct Exits {
 
 into_action!(Exits);
 
-#[tracing::instrument(name = "exits system", skip_all)]
+#[tracing::instrument(name = "exits system", skip_all, fields(action = "Exits"))]
 pub fn exits_system(
     mut action_reader: EventReader<Action>,
+    staging: Res<Staging>,
+    seasons: Res<Seasons>,
     exiter_query: Query<(Option<&Location>, Option<&Room>)>,
     room_query: Query<&Room>,
+    shadow_query: Query<&Shadow>,
+    flags_query: Query<&PlayerFlags>,
     mut messages_query: Query<&mut Messages>,
 ) {
     for action in action_reader.iter() {
         if let Action::Exits(Exits { actor }) = action {
             let current_room = get_room_std(*actor, &exiter_query);
 
-            let exits = room_query
-                .get(current_room)
-                .unwrap()
-                .exits()
-                .keys()
+            let immortal = flags_query
+                .get(*actor)
+                .map(|flags| flags.contains(player::Flags::IMMORTAL))
+                .unwrap_or(false);
+
+            // Builders see the staged exits of rooms they are staging. Exits out of season are
+            // hidden from mortals.
+            let exits = if let Some(shadow) = staging.shadow(*actor, current_room) {
+                shadow_query
+                    .get(shadow)
+                    .unwrap()
+                    .exits()
+                    .keys()
+                    .collect_vec()
+            } else {
+                let room = room_query.get(current_room).unwrap();
+                room.exits()
+                    .iter()
+                    .filter(|(direction, exit)| {
+                        immortal
+                            || room_query
+                                .get(exit.destination)
+                                .map(|destination| {
+                                    seasons.is_passable(room.id(), direction, destination.id())
+                                })
+                                .unwrap_or(true)
+                    })
+                    .map(|(direction, _)| direction)
+                    .collect_vec()
+            };
+
+            let exits = exits
+                .into_iter()
                 .map(Direction::as_str)
-                .map(ToString::to_string)
                 .sorted()
+                .map(|direction| link(&[direction], direction))
                 .collect_vec();
 
             let message = if exits.is_empty() {
                 "This room has no obvious exits.".to_string()
             } else if exits.len() == 1 {
-                format!("There is an exit {}.", sorted_word_list(exits))
+                format!("There is an exit {}.", word_list(exits))
             } else {
-                format!("There are exits {}.", sorted_word_list(exits))
+                format!("There are exits {}.", word_list(exits))
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+// Valid shapes:
+// recall - replays the last 20 lines of output
+// recall <n> - replays the last n lines of output
+pub fn parse_recall(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    let lines = match tokenizer.next() {
+        Some(token) => match token.parse::<usize>() {
+            Ok(lines) if (1..=HISTORY_LENGTH).contains(&lines) => lines,
+            _ => {
+                return Err(format!(
+                    "Enter a number of lines between 1 and {} to recall.",
+                    HISTORY_LENGTH
+                ))
+            }
+        },
+        None => DEFAULT_RECALL_LINES,
+    };
+
+    Ok(Action::from(Recall {
+        actor: player,
+        lines,
+    }))
+}
+
+const DEFAULT_RECALL_LINES: usize = 20;
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Recall {
+    pub actor: Entity,
+    pub lines: usize,
+}
+
+into_action!(Recall);
+
+#[tracing::instrument(name = "recall system", skip_all, fields(action = "Recall"))]
+pub fn recall_system(
+    mut action_reader: EventReader<Action>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Recall(Recall { actor, lines }) = action {
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                let history = messages.history(*lines).collect_vec();
+
+                if history.is_empty() {
+                    messages.queue_unrecorded("There is no output to recall.".to_string());
+                    continue;
+                }
+
+                let message = format!(
+                    "Recalling the last {} {}:\r\n{}",
+                    history.len(),
+                    if history.len() == 1 { "line" } else { "lines" },
+                    history.iter().join("\r\n")
+                );
+                messages.queue_unrecorded(message);
+            }
+        }
+    }
+}
+
+// Valid shapes:
+// top <category> - shows the leaders of the given leaderboard category
+pub fn parse_top(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    let category = match tokenizer.next() {
+        Some(token) => LeaderboardCategory::from_str(token).map_err(|e| e.to_string())?,
+        None => return Err(LeaderboardCategory::from_str("").unwrap_err().to_string()),
+    };
+
+    Ok(Action::from(Top {
+        actor: player,
+        category,
+    }))
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Top {
+    pub actor: Entity,
+    pub category: LeaderboardCategory,
+}
+
+into_action!(Top);
+
+#[tracing::instrument(name = "top system", skip_all, fields(action = "Top"))]
+pub fn top_system(
+    mut action_reader: EventReader<Action>,
+    leaderboards: Res<Leaderboards>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Top(Top { actor, category }) = action {
+            let entries = leaderboards.get(*category);
+
+            let message = if entries.is_empty() {
+                "No one is on the leaderboard yet.".to_string()
+            } else {
+                format!(
+                    "Top players by {}:\r\n{}",
+                    category.title(),
+                    entries
+                        .iter()
+                        .enumerate()
+                        .map(|(rank, entry)| format!(
+                            "  {:>2}. {}: {}",
+                            rank + 1,
+                            entry.name,
+                            category.format_value(entry.value)
+                        ))
+                        .join("\r\n")
+                )
             };
 
             if let Ok(mut messages) = messages_query.get_mut(*actor) {
@@ -230,21 +552,35 @@ pub struct Who {
 
 into_action!(Who);
 
-#[tracing::instrument(name = "who system", skip_all)]
+#[tracing::instrument(name = "who system", skip_all, fields(action = "Who"))]
 pub fn who_system(
     mut action_reader: EventReader<Action>,
-    player_query: Query<&Named, With<Player>>,
+    player_query: Query<(&Named, &PlayerFlags), With<Player>>,
+    caps_query: Query<&ClientCaps>,
     mut messages_query: Query<&mut Messages>,
 ) {
     for action in action_reader.iter() {
         if let Action::Who(Who { actor }) = action {
-            let players = player_query
+            let width = caps_query
+                .get(*actor)
+                .map(ClientCaps::width)
+                .unwrap_or(DEFAULT_WIDTH);
+            let mut table = Table::new(width)
+                .truncated_column("", Align::Left)
+                .column("", Align::Left);
+
+            player_query
                 .iter()
-                .map(|named| format!("  {}", named.as_str()))
-                .sorted()
-                .join("\r\n");
+                .sorted_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()))
+                .for_each(|(named, flags)| {
+                    if flags.contains(player::Flags::IMMORTAL) {
+                        table.row(vec![named.to_string(), "|Gray46|immortal|-|".to_string()]);
+                    } else {
+                        table.row(vec![named.to_string()]);
+                    }
+                });
 
-            let message = format!("Online players:\r\n{}", players);
+            let message = format!("{}\r\n{}", header("Online players"), table.render());
 
             if let Ok(mut messages) = messages_query.get_mut(*actor) {
                 messages.queue(message);
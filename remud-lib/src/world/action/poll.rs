@@ -0,0 +1,419 @@
+use std::str::FromStr;
+
+use bevy_app::EventReader;
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+
+use crate::{
+    clock::Clock,
+    engine::persist::{self, Updates},
+    text::{format_duration, Tokenizer},
+    world::{
+        action::{into_action, Action},
+        types::{
+            player::{self, Messages, Player, PlayerFlags, Players},
+            poll::{Poll, PollId, Polls},
+            Named,
+        },
+    },
+};
+
+// Valid shapes:
+// poll [list] - lists the polls
+// poll <id> - shows a poll's options and votes
+// poll create <question>: <option>, <option>[, ...] - opens a poll, immortals only
+// poll close <id> - closes a poll and announces its results, immortals only
+pub fn parse_poll(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    match tokenizer.next() {
+        None | Some("list") => Ok(Action::from(PollList { actor: player })),
+        Some("close") => Ok(Action::from(PollClose {
+            actor: player,
+            id: parse_poll_id(tokenizer.next())?,
+        })),
+        Some("create") => {
+            let (question, options) = match tokenizer.rest().split_once(':') {
+                Some((question, options)) => (
+                    question.trim().to_string(),
+                    options
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|option| !option.is_empty())
+                        .map(ToString::to_string)
+                        .collect_vec(),
+                ),
+                None => {
+                    return Err(
+                        "Enter a question, a colon, and a comma separated list of options."
+                            .to_string(),
+                    )
+                }
+            };
+
+            if question.is_empty() {
+                return Err("Ask what?".to_string());
+            }
+
+            if options.len() < 2 {
+                return Err("A poll needs at least two options.".to_string());
+            }
+
+            Ok(Action::from(PollCreate {
+                actor: player,
+                question,
+                options,
+            }))
+        }
+        Some(id) => Ok(Action::from(PollInfo {
+            actor: player,
+            id: parse_poll_id(Some(id))?,
+        })),
+    }
+}
+
+// Valid shapes:
+// vote <option> - votes in the only open poll, by option number or text
+// vote <poll id> <option> - votes in a poll
+pub fn parse_vote(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    let input = tokenizer.rest().trim();
+
+    let (poll, option) = match tokenizer.next() {
+        Some(first) if !tokenizer.rest().trim().is_empty() => match PollId::from_str(first) {
+            Ok(id) => (Some(id), tokenizer.rest().trim()),
+            Err(_) => (None, input),
+        },
+        Some(_) => (None, input),
+        None => return Err("Vote for what?".to_string()),
+    };
+
+    Ok(Action::from(Vote {
+        actor: player,
+        poll,
+        option: option.to_string(),
+    }))
+}
+
+fn parse_poll_id(token: Option<&str>) -> Result<PollId, String> {
+    match token {
+        Some(token) => PollId::from_str(token).map_err(|e| e.to_string()),
+        None => Err("Enter a poll ID.".to_string()),
+    }
+}
+
+fn is_immortal(actor: Entity, flags_query: &Query<&PlayerFlags>) -> bool {
+    flags_query
+        .get(actor)
+        .map(|flags| flags.contains(player::Flags::IMMORTAL))
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct PollClose {
+    pub actor: Entity,
+    pub id: PollId,
+}
+
+into_action!(PollClose);
+
+#[tracing::instrument(name = "poll close system", skip_all, fields(action = "PollClose"))]
+pub fn poll_close_system(
+    mut action_reader: EventReader<Action>,
+    mut polls: ResMut<Polls>,
+    players: Res<Players>,
+    mut updates: ResMut<Updates>,
+    flags_query: Query<&PlayerFlags>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::PollClose(PollClose { actor, id }) = action {
+            let message = if !is_immortal(*actor, &flags_query) {
+                "Only immortals may close polls.".to_string()
+            } else {
+                match polls.get_mut(*id) {
+                    Some(poll) if poll.closed => format!("Poll {} is already closed.", id),
+                    Some(poll) => {
+                        poll.closed = true;
+                        updates.persist(persist::poll::Close::new(*id));
+
+                        let announcement = format!(
+                            "|SteelBlue3|Poll {} has closed. {} Results: {}.|-|",
+                            id,
+                            poll.question,
+                            poll.results()
+                        );
+
+                        for (_, online) in players.iter().filter(|(_, online)| *online != *actor) {
+                            if let Ok(mut messages) = messages_query.get_mut(online) {
+                                messages.queue(announcement.clone());
+                            }
+                        }
+
+                        format!("You close poll {}. Results: {}.", id, poll.results())
+                    }
+                    None => format!("There is no poll {}.", id),
+                }
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct PollCreate {
+    pub actor: Entity,
+    pub question: String,
+    pub options: Vec<String>,
+}
+
+into_action!(PollCreate);
+
+#[tracing::instrument(name = "poll create system", skip_all, fields(action = "PollCreate"))]
+pub fn poll_create_system(
+    mut action_reader: EventReader<Action>,
+    clock: Res<Clock>,
+    mut polls: ResMut<Polls>,
+    players: Res<Players>,
+    mut updates: ResMut<Updates>,
+    flags_query: Query<&PlayerFlags>,
+    named_query: Query<&Named>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::PollCreate(PollCreate {
+            actor,
+            question,
+            options,
+        }) = action
+        {
+            if !is_immortal(*actor, &flags_query) {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue("Only immortals may create polls.".to_string());
+                }
+                continue;
+            }
+
+            let name = match named_query.get(*actor) {
+                Ok(named) => named.to_string(),
+                Err(_) => continue,
+            };
+
+            let id = polls.next_id();
+            let created_at = clock.unix_now();
+
+            polls.insert(
+                id,
+                Poll::new(question.clone(), name.clone(), options.clone(), created_at),
+            );
+
+            updates.persist(persist::poll::Create::new(
+                id,
+                question.clone(),
+                name.clone(),
+                options.clone(),
+                created_at,
+            ));
+
+            let announcement = format!(
+                "|SteelBlue3|{} opened poll {}: {} Type \"poll {}\" to see the options.|-|",
+                name, id, question, id
+            );
+
+            for (_, online) in players.iter().filter(|(_, online)| *online != *actor) {
+                if let Ok(mut messages) = messages_query.get_mut(online) {
+                    messages.queue(announcement.clone());
+                }
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!("You open poll {}.", id));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct PollInfo {
+    pub actor: Entity,
+    pub id: PollId,
+}
+
+into_action!(PollInfo);
+
+#[tracing::instrument(name = "poll info system", skip_all, fields(action = "PollInfo"))]
+pub fn poll_info_system(
+    mut action_reader: EventReader<Action>,
+    clock: Res<Clock>,
+    polls: Res<Polls>,
+    player_query: Query<&Player>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::PollInfo(PollInfo { actor, id }) = action {
+            let player = match player_query.get(*actor) {
+                Ok(player) => player.id(),
+                Err(_) => continue,
+            };
+
+            let message = match polls.get(*id) {
+                Some(poll) => {
+                    let mut message = format!(
+                        "|white|Poll {}|-|: {}\r\nOpened by {} {} ago.",
+                        id,
+                        poll.question,
+                        poll.author,
+                        format_duration((clock.unix_now() - poll.created_at).max(0))
+                    );
+
+                    for (index, (option, count)) in
+                        poll.options.iter().zip(poll.tally()).enumerate()
+                    {
+                        message.push_str(
+                            format!(
+                                "\r\n  |white|{}|-|. {} ({} {})",
+                                index + 1,
+                                option,
+                                count,
+                                if count == 1 { "vote" } else { "votes" }
+                            )
+                            .as_str(),
+                        );
+                    }
+
+                    match poll.votes.get(&player) {
+                        Some(option) => message.push_str(
+                            format!("\r\nYou voted for {}.", poll.options[*option]).as_str(),
+                        ),
+                        None if !poll.closed => message
+                            .push_str(format!("\r\nVote with \"vote {} <option>\".", id).as_str()),
+                        None => (),
+                    }
+
+                    if poll.closed {
+                        message.push_str("\r\nThis poll is closed.");
+                    }
+
+                    message
+                }
+                None => format!("There is no poll {}.", id),
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct PollList {
+    pub actor: Entity,
+}
+
+into_action!(PollList);
+
+#[tracing::instrument(name = "poll list system", skip_all, fields(action = "PollList"))]
+pub fn poll_list_system(
+    mut action_reader: EventReader<Action>,
+    polls: Res<Polls>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::PollList(PollList { actor }) = action {
+            let mut message = "|white|Polls|-|".to_string();
+            let mut empty = true;
+
+            for (id, poll) in polls.iter() {
+                empty = false;
+
+                let votes = poll.votes.len();
+                message.push_str(
+                    format!(
+                        "\r\n  |white|{}|-|. {} ({}, {} {})",
+                        id,
+                        poll.question,
+                        if poll.closed { "closed" } else { "open" },
+                        votes,
+                        if votes == 1 { "vote" } else { "votes" }
+                    )
+                    .as_str(),
+                );
+            }
+
+            if empty {
+                message = "There are no polls.".to_string();
+            }
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Vote {
+    pub actor: Entity,
+    pub poll: Option<PollId>,
+    pub option: String,
+}
+
+into_action!(Vote);
+
+#[tracing::instrument(name = "vote system", skip_all, fields(action = "Vote"))]
+pub fn vote_system(
+    mut action_reader: EventReader<Action>,
+    mut polls: ResMut<Polls>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<&Player>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Vote(Vote {
+            actor,
+            poll,
+            option,
+        }) = action
+        {
+            let player = match player_query.get(*actor) {
+                Ok(player) => player.id(),
+                Err(_) => continue,
+            };
+
+            let id = match poll {
+                Some(id) => Ok(*id),
+                None => match polls.open().map(|(id, _)| *id).exactly_one() {
+                    Ok(id) => Ok(id),
+                    Err(mut open) => Err(if open.next().is_none() {
+                        "There are no open polls.".to_string()
+                    } else {
+                        "Several polls are open. Enter the poll to vote in, as in \"vote 2 yes\"."
+                            .to_string()
+                    }),
+                },
+            };
+
+            let message = match id.map(|id| (id, polls.get_mut(id))) {
+                Err(message) => message,
+                Ok((id, None)) => format!("There is no poll {}.", id),
+                Ok((id, Some(poll))) if poll.closed => format!("Poll {} is closed.", id),
+                Ok((id, Some(poll))) if poll.votes.contains_key(&player) => {
+                    format!("You have already voted in poll {}.", id)
+                }
+                Ok((id, Some(poll))) => match poll.option(option) {
+                    Some(index) => {
+                        poll.votes.insert(player, index);
+                        updates.persist(persist::poll::Vote::new(id, player, index));
+                        format!("You vote for {} in poll {}.", poll.options[index], id)
+                    }
+                    None => format!("Poll {} has no option \"{}\".", id, option),
+                },
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use bevy_app::{EventReader, EventWriter};
+use bevy_ecs::prelude::*;
+
+use crate::{
+    engine::persist::{self, Updates},
+    text::Tokenizer,
+    world::{
+        action::{bank::Treasury, into_action, object::bulk_limit, Action},
+        scripting::{RunInitScript, ScriptHooks, ScriptTrigger},
+        types::{
+            bank::format_coins,
+            economy::CoinSource,
+            object::{
+                Bulk, Keywords, Object, ObjectBundle, ObjectFlags, Objects, PrototypeId, Prototypes,
+            },
+            player::{Messages, Player},
+            room::{Room, RoomId},
+            shop::Shops,
+            Attributes, Contents, Description, Id, Location, Named,
+        },
+    },
+};
+
+// Valid shapes:
+// shop [list] - lists the items for sale in the shop you are in
+// shop buy <item> - buys an item by its number in the list
+// shop haggle <item> - haggles over the price of an item
+pub fn parse_shop(player: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    match tokenizer.next() {
+        None | Some("list") => Ok(Action::from(ShopList { actor: player })),
+        Some("buy") => Ok(Action::from(ShopBuy {
+            actor: player,
+            item: parse_item(tokenizer.next())?,
+        })),
+        Some("haggle") => Ok(Action::from(ShopHaggle {
+            actor: player,
+            item: parse_item(tokenizer.next())?,
+        })),
+        Some(_) => Err("Enter a valid shop subcommand: buy, haggle, or list.".to_string()),
+    }
+}
+
+// Parses the 1-based number of an item, as shown by the shop's list.
+fn parse_item(token: Option<&str>) -> Result<usize, String> {
+    match token.map(str::parse::<usize>) {
+        Some(Ok(item)) if item > 0 => Ok(item),
+        Some(_) => Err("Enter the number of an item in the shop's list.".to_string()),
+        None => Err("Enter an item number.".to_string()),
+    }
+}
+
+// The ID of the room an actor is standing in.
+fn actor_room(
+    actor: Entity,
+    location_query: &Query<&Location>,
+    room_query: &Query<&Room>,
+) -> Option<RoomId> {
+    location_query
+        .get(actor)
+        .ok()
+        .and_then(|location| room_query.get(location.entity()).ok())
+        .map(Room::id)
+}
+
+fn prototype_name(
+    prototype: PrototypeId,
+    prototypes: &Prototypes,
+    named_query: &Query<&Named>,
+) -> Option<String> {
+    prototypes
+        .by_id(prototype)
+        .and_then(|entity| named_query.get(entity).ok())
+        .map(ToString::to_string)
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ShopBuy {
+    pub actor: Entity,
+    // the 1-based number of the item, as shown by the shop's list
+    pub item: usize,
+}
+
+into_action!(ShopBuy);
+
+// Sells a player a newly made object from a shop's stock. The coins paid are taken out of the
+// game rather than paid to anyone.
+#[tracing::instrument(name = "shop buy system", skip_all, fields(action = "ShopBuy"))]
+pub fn shop_buy_system(
+    mut commands: Commands,
+    mut action_reader: EventReader<Action>,
+    mut init_writer: EventWriter<RunInitScript>,
+    mut treasury: Treasury,
+    mut shops: ResMut<Shops>,
+    prototypes: Res<Prototypes>,
+    mut objects: ResMut<Objects>,
+    mut updates: ResMut<Updates>,
+    player_query: Query<(&Player, &Named, &Location)>,
+    room_query: Query<&Room>,
+    prototypes_query: Query<(
+        &Named,
+        &Description,
+        &ObjectFlags,
+        &Keywords,
+        Option<&ScriptHooks>,
+    )>,
+    mut contents_query: Query<&mut Contents, With<Player>>,
+    attributes_query: Query<&Attributes>,
+    object_query: Query<&Object>,
+    bulk_query: Query<&Bulk>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ShopBuy(ShopBuy { actor, item }) = action {
+            let (buyer, buyer_name, location) = match player_query.get(*actor) {
+                Ok((player, named, location)) => (player.id(), named.to_string(), location),
+                Err(_) => continue,
+            };
+
+            let (id, shop) = match room_query
+                .get(location.entity())
+                .ok()
+                .and_then(|room| shops.by_room_mut(room.id()))
+            {
+                Some((id, shop)) => (id.clone(), shop),
+                None => {
+                    if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                        messages.queue("There is no shop here.".to_string());
+                    }
+                    continue;
+                }
+            };
+
+            let (prototype_id, prototype, rarity) =
+                match shop.stock.get(item - 1).and_then(|stock| {
+                    prototypes
+                        .by_id(stock.prototype)
+                        .map(|prototype| (stock.prototype, prototype, stock.rarity))
+                }) {
+                    Some(found) => found,
+                    None => {
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(format!("There is no item {} for sale here.", item));
+                        }
+                        continue;
+                    }
+                };
+
+            let (named, description, flags, keywords, hooks) = match prototypes_query.get(prototype)
+            {
+                Ok(components) => components,
+                Err(_) => continue,
+            };
+
+            let bulk = bulk_query.get(prototype).copied().unwrap_or_default();
+            if let Some(message) = contents_query.get_mut(*actor).ok().and_then(|carried| {
+                bulk_limit(
+                    *actor,
+                    carried.objects(),
+                    bulk,
+                    named.as_str(),
+                    &attributes_query,
+                    &object_query,
+                    &bulk_query,
+                )
+            }) {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(message);
+                }
+                continue;
+            }
+
+            let price = shop.price(rarity, buyer);
+
+            let mut update =
+                match treasury.add_coins(buyer, buyer_name.as_str(), CoinSource::Shop, -price) {
+                    Ok(update) => update,
+                    Err(coins) => {
+                        let message = format!(
+                            "{} costs {}, but you are only carrying {}.",
+                            named,
+                            format_coins(price),
+                            format_coins(coins)
+                        );
+                        if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                            messages.queue(message);
+                        }
+                        continue;
+                    }
+                };
+
+            shop.sell(item - 1, buyer, price);
+
+            let object_id = objects.next_id();
+
+            let mut e = commands.spawn_bundle(ObjectBundle {
+                object: Object::new(object_id, prototype, true),
+                id: Id::Object(object_id),
+                name: named.clone(),
+                description: description.clone(),
+                flags: flags.clone(),
+                keywords: keywords.clone(),
+                location: Location::from(*actor),
+            });
+
+            if let Some(hooks) = hooks {
+                e.insert(hooks.clone());
+            }
+
+            let object_entity = e.id();
+
+            if let Some(hooks) = hooks {
+                for script in hooks.by_trigger(ScriptTrigger::Init) {
+                    init_writer.send(RunInitScript::new(object_entity, script));
+                }
+            }
+
+            if let Ok(mut contents) = contents_query.get_mut(*actor) {
+                contents.insert(object_entity);
+            }
+
+            objects.insert(object_id, object_entity);
+
+            update.append(persist::object::Create::new(object_id, prototype_id));
+            update.append(persist::player::AddObject::new(buyer, object_id));
+            update.append(persist::shop::Update::new(id, shop));
+            updates.persist(update);
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(format!("You buy {} for {}.", named, format_coins(price)));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ShopHaggle {
+    pub actor: Entity,
+    // the 1-based number of the item, as shown by the shop's list
+    pub item: usize,
+}
+
+into_action!(ShopHaggle);
+
+// Reports the outcome of haggling. Shops never haggle on their own: pre-event Haggle scripts
+// decide whether to offer a discount, which this system then reports.
+#[tracing::instrument(name = "shop haggle system", skip_all, fields(action = "ShopHaggle"))]
+pub fn shop_haggle_system(
+    mut action_reader: EventReader<Action>,
+    shops: Res<Shops>,
+    prototypes: Res<Prototypes>,
+    player_query: Query<&Player>,
+    location_query: Query<&Location>,
+    room_query: Query<&Room>,
+    named_query: Query<&Named>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ShopHaggle(ShopHaggle { actor, item }) = action {
+            let player = match player_query.get(*actor) {
+                Ok(player) => player.id(),
+                Err(_) => continue,
+            };
+
+            let message = match actor_room(*actor, &location_query, &room_query)
+                .and_then(|room| shops.by_room(room))
+            {
+                Some((_, shop)) => match shop.stock.get(item - 1).and_then(|stock| {
+                    prototype_name(stock.prototype, &prototypes, &named_query)
+                        .map(|name| (name, stock.rarity))
+                }) {
+                    Some((name, rarity)) => match shop.discount(player) {
+                        Some(discount) if discount > 0 => format!(
+                            "You haggle the price of {} down to {}.",
+                            name,
+                            format_coins(shop.price(rarity, player))
+                        ),
+                        _ => format!(
+                            "Your haggling gets you nowhere. {} still costs {}.",
+                            name,
+                            format_coins(shop.price(rarity, player))
+                        ),
+                    },
+                    None => format!("There is no item {} for sale here.", item),
+                },
+                None => "There is no shop here.".to_string(),
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ShopList {
+    pub actor: Entity,
+}
+
+into_action!(ShopList);
+
+#[tracing::instrument(name = "shop list system", skip_all, fields(action = "ShopList"))]
+pub fn shop_list_system(
+    mut action_reader: EventReader<Action>,
+    shops: Res<Shops>,
+    prototypes: Res<Prototypes>,
+    player_query: Query<&Player>,
+    location_query: Query<&Location>,
+    room_query: Query<&Room>,
+    named_query: Query<&Named>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ShopList(ShopList { actor }) = action {
+            let player = match player_query.get(*actor) {
+                Ok(player) => player.id(),
+                Err(_) => continue,
+            };
+
+            let message = match actor_room(*actor, &location_query, &room_query)
+                .and_then(|room| shops.by_room(room))
+            {
+                Some((_, shop)) if shop.stock.is_empty() => {
+                    "There is nothing for sale here. Come back later.".to_string()
+                }
+                Some((_, shop)) => {
+                    let mut message = "|white|For sale|-|".to_string();
+
+                    for (index, stock) in shop.stock.iter().enumerate() {
+                        let name = match prototype_name(stock.prototype, &prototypes, &named_query)
+                        {
+                            Some(name) => name,
+                            None => continue,
+                        };
+
+                        message.push_str(
+                            format!(
+                                "\r\n  |white|{}|-|. {} ({}) for {}, {} left",
+                                index + 1,
+                                name,
+                                stock.rarity,
+                                format_coins(shop.price(stock.rarity, player)),
+                                stock.quantity
+                            )
+                            .as_str(),
+                        );
+                    }
+
+                    message
+                }
+                None => "There is no shop here.".to_string(),
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
@@ -1,16 +1,170 @@
+use std::time::Duration;
+
 use bevy_app::EventReader;
 use bevy_ecs::prelude::*;
 use itertools::Itertools;
 
-use crate::world::{
-    action::{get_room_std, into_action, Action},
-    types::{
-        player::{Messages, Player},
-        room::Room,
-        Configuration, Location, Named,
+use crate::{
+    clock::Clock,
+    engine::persist::{self, Updates},
+    text::Tokenizer,
+    world::{
+        action::{get_room_std, into_action, Action},
+        types::{
+            mail::Mailbox,
+            news::{News, NewsRead},
+            player::{Messages, Player},
+            room::Room,
+            Configuration, DbCheckRequest, Location, Named, ProfileRequest,
+        },
     },
 };
 
+const MAX_PROFILE_TICKS: u32 = 10_000;
+const MAX_MAINTENANCE_COUNTDOWN_SECS: u64 = 3600;
+
+pub fn parse_config(actor: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(subcommand) = tokenizer.next() {
+        match subcommand.to_lowercase().as_str() {
+            "reload" => Ok(Action::from(ConfigReload { actor })),
+            _ => Err("Enter a valid config subcommand: reload.".to_string()),
+        }
+    } else {
+        Err("Enter a config subcommand: reload.".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ConfigReload {
+    pub actor: Entity,
+}
+
+into_action!(ConfigReload);
+
+#[tracing::instrument(
+    name = "config reload system",
+    skip_all,
+    fields(action = "ConfigReload")
+)]
+pub fn config_reload_system(
+    mut action_reader: EventReader<Action>,
+    mut config: ResMut<Configuration>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::ConfigReload(ConfigReload { actor }) = action {
+            if config.config_reload.is_some() {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue("A configuration reload is already in progress.".to_string());
+                }
+                continue;
+            }
+
+            config.config_reload = Some(*actor);
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue("Reloading configuration.".to_string());
+            }
+        }
+    }
+}
+
+pub fn parse_db(actor: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(subcommand) = tokenizer.next() {
+        match subcommand.to_lowercase().as_str() {
+            "check" => match tokenizer.next() {
+                Some(option) if option.eq_ignore_ascii_case("repair") => {
+                    Ok(Action::from(DbCheck {
+                        actor,
+                        repair: true,
+                    }))
+                }
+                Some(_) => Err("Enter a valid db check option: repair.".to_string()),
+                None => Ok(Action::from(DbCheck {
+                    actor,
+                    repair: false,
+                })),
+            },
+            _ => Err("Enter a valid db subcommand: check.".to_string()),
+        }
+    } else {
+        Err("Enter a db subcommand: check.".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct DbCheck {
+    pub actor: Entity,
+    pub repair: bool,
+}
+
+into_action!(DbCheck);
+
+#[tracing::instrument(name = "db check system", skip_all, fields(action = "DbCheck"))]
+pub fn db_check_system(
+    mut action_reader: EventReader<Action>,
+    mut config: ResMut<Configuration>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::DbCheck(DbCheck { actor, repair }) = action {
+            let message = if config.db_check.is_some() {
+                "A database check is already in progress."
+            } else {
+                config.db_check = Some(DbCheckRequest {
+                    actor: *actor,
+                    repair: *repair,
+                });
+                "Checking the database."
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message.to_string());
+            }
+        }
+    }
+}
+
+pub fn parse_lint(actor: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(subcommand) = tokenizer.next() {
+        match subcommand.to_lowercase().as_str() {
+            "world" => Ok(Action::from(LintWorld { actor })),
+            _ => Err("Enter a valid lint subcommand: world.".to_string()),
+        }
+    } else {
+        Err("Enter a lint subcommand: world.".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct LintWorld {
+    pub actor: Entity,
+}
+
+into_action!(LintWorld);
+
+#[tracing::instrument(name = "lint world system", skip_all, fields(action = "LintWorld"))]
+pub fn lint_world_system(
+    mut action_reader: EventReader<Action>,
+    mut config: ResMut<Configuration>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::LintWorld(LintWorld { actor }) = action {
+            let message = if config.lint_request.is_some() {
+                "A world lint is already in progress."
+            } else {
+                config.lint_request = Some(*actor);
+                "Linting the world."
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message.to_string());
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub struct Login {
     pub actor: Entity,
@@ -18,22 +172,55 @@ pub struct Login {
 
 into_action!(Login);
 
-#[tracing::instrument(name = "login system", skip_all)]
+#[tracing::instrument(name = "login system", skip_all, fields(action = "Login"))]
 pub fn login_system(
     mut action_reader: EventReader<Action>,
+    mut mailbox: ResMut<Mailbox>,
+    news: Res<News>,
+    mut updates: ResMut<Updates>,
     location_query: Query<(Option<&Location>, Option<&Room>)>,
-    player_query: Query<&Named, With<Player>>,
+    player_query: Query<(&Player, &Named, &NewsRead)>,
     room_query: Query<&Room>,
     mut messages_query: Query<&mut Messages>,
 ) {
     for action in action_reader.iter() {
         if let Action::Login(Login { actor }) = action {
             let room = get_room_std(*actor, &location_query);
-            let name = player_query
+            let (player, name, news_read) = player_query
                 .get(*actor)
-                .map(|named| named.as_str())
+                .map(|(player, named, news_read)| (player.id(), named.as_str(), news_read))
                 .unwrap();
 
+            updates.persist(persist::player::StartSession::new(player));
+
+            // New and renamed players become reachable by mail once they log in.
+            mailbox.register(player, name);
+
+            let unread = mailbox.unread(player);
+            if unread > 0 {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(format!(
+                        "|SteelBlue3|You have {} unread {}. Type \"mail\" to see {}.|-|",
+                        unread,
+                        if unread == 1 { "letter" } else { "letters" },
+                        if unread == 1 { "it" } else { "them" }
+                    ));
+                }
+            }
+
+            let unread = news.unread(news_read);
+            if unread > 0 {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(format!(
+                        "|SteelBlue3|There {} {} unread news {}. Type \"news\" to read {}.|-|",
+                        if unread == 1 { "is" } else { "are" },
+                        unread,
+                        if unread == 1 { "entry" } else { "entries" },
+                        if unread == 1 { "it" } else { "them" }
+                    ));
+                }
+            }
+
             let players = room_query
                 .get(room)
                 .unwrap()
@@ -47,7 +234,7 @@ pub fn login_system(
 
             for player in players {
                 if let Ok(mut messages) = messages_query.get_mut(player) {
-                    messages.queue(message.clone());
+                    messages.queue_broadcast(message.clone());
                 }
             }
         }
@@ -61,7 +248,7 @@ pub struct Restart {
 
 into_action!(Restart);
 
-#[tracing::instrument(name = "restart system", skip_all)]
+#[tracing::instrument(name = "restart system", skip_all, fields(action = "Restart"))]
 pub fn restart_system(mut action_reader: EventReader<Action>, mut config: ResMut<Configuration>) {
     for action in action_reader.iter() {
         if let Action::Restart(Restart { .. }) = action {
@@ -77,7 +264,7 @@ pub struct Shutdown {
 
 into_action!(Shutdown);
 
-#[tracing::instrument(name = "shutdown system", skip_all)]
+#[tracing::instrument(name = "shutdown system", skip_all, fields(action = "Shutdown"))]
 pub fn shutdown_system(mut action_reader: EventReader<Action>, mut config: ResMut<Configuration>) {
     for action in action_reader.iter() {
         if let Action::Shutdown(Shutdown { .. }) = action {
@@ -85,3 +272,189 @@ pub fn shutdown_system(mut action_reader: EventReader<Action>, mut config: ResMu
         }
     }
 }
+
+pub fn parse_maintenance(actor: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(subcommand) = tokenizer.next() {
+        match subcommand.to_lowercase().as_str() {
+            "on" => {
+                let countdown = match tokenizer.next() {
+                    Some(seconds) => match seconds.parse::<u64>() {
+                        Ok(seconds) if seconds > 0 && seconds <= MAX_MAINTENANCE_COUNTDOWN_SECS => {
+                            Some(seconds)
+                        }
+                        _ => {
+                            return Err(format!(
+                                "The countdown must be between 1 and {} seconds.",
+                                MAX_MAINTENANCE_COUNTDOWN_SECS
+                            ))
+                        }
+                    },
+                    None => None,
+                };
+
+                Ok(Action::from(MaintenanceOn { actor, countdown }))
+            }
+            "off" => Ok(Action::from(MaintenanceOff { actor })),
+            _ => Err("Enter a valid maintenance subcommand: on or off.".to_string()),
+        }
+    } else {
+        Err("Enter a maintenance subcommand: on or off.".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct MaintenanceOn {
+    pub actor: Entity,
+    pub countdown: Option<u64>,
+}
+
+into_action!(MaintenanceOn);
+
+#[tracing::instrument(
+    name = "maintenance on system",
+    skip_all,
+    fields(action = "MaintenanceOn")
+)]
+pub fn maintenance_on_system(
+    mut action_reader: EventReader<Action>,
+    clock: Res<Clock>,
+    mut config: ResMut<Configuration>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<(Entity, &mut Messages), With<Player>>,
+) {
+    for action in action_reader.iter() {
+        if let Action::MaintenanceOn(MaintenanceOn { actor, countdown }) = action {
+            if config.maintenance && countdown.is_none() {
+                if let Ok((_, mut messages)) = messages_query.get_mut(*actor) {
+                    messages.queue("Maintenance mode is already on.".to_string());
+                }
+                continue;
+            }
+
+            if !config.maintenance {
+                config.maintenance = true;
+                updates.persist(persist::config::Maintenance::new(true));
+            }
+
+            let message = if let Some(seconds) = countdown {
+                config.maintenance_boot = Some(clock.now() + Duration::from_secs(*seconds));
+
+                let warning = format!(
+                    "|Gold1|City Six is going down for maintenance in {} seconds. Mortals will be \
+                     disconnected.|-|",
+                    seconds
+                );
+                for (player, mut messages) in messages_query.iter_mut() {
+                    if player != *actor {
+                        messages.queue(warning.clone());
+                    }
+                }
+
+                format!(
+                    "Maintenance mode enabled. Mortals will be disconnected in {} seconds.",
+                    seconds
+                )
+            } else {
+                "Maintenance mode enabled. Only immortals may log in.".to_string()
+            };
+
+            if let Ok((_, mut messages)) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct MaintenanceOff {
+    pub actor: Entity,
+}
+
+into_action!(MaintenanceOff);
+
+#[tracing::instrument(
+    name = "maintenance off system",
+    skip_all,
+    fields(action = "MaintenanceOff")
+)]
+pub fn maintenance_off_system(
+    mut action_reader: EventReader<Action>,
+    mut config: ResMut<Configuration>,
+    mut updates: ResMut<Updates>,
+    mut messages_query: Query<(Entity, &mut Messages), With<Player>>,
+) {
+    for action in action_reader.iter() {
+        if let Action::MaintenanceOff(MaintenanceOff { actor }) = action {
+            let message = if config.maintenance {
+                config.maintenance = false;
+                updates.persist(persist::config::Maintenance::new(false));
+
+                if config.maintenance_boot.take().is_some() {
+                    for (player, mut messages) in messages_query.iter_mut() {
+                        if player != *actor {
+                            messages
+                                .queue("|Gold1|Maintenance has been called off.|-|".to_string());
+                        }
+                    }
+                }
+
+                "Maintenance mode disabled."
+            } else {
+                "Maintenance mode is already off."
+            };
+
+            if let Ok((_, mut messages)) = messages_query.get_mut(*actor) {
+                messages.queue(message.to_string());
+            }
+        }
+    }
+}
+
+pub fn parse_profile(actor: Entity, mut tokenizer: Tokenizer) -> Result<Action, String> {
+    if let Some(ticks) = tokenizer.next() {
+        match ticks.parse::<u32>() {
+            Ok(ticks) if ticks > 0 && ticks <= MAX_PROFILE_TICKS => {
+                Ok(Action::from(Profile { actor, ticks }))
+            }
+            _ => Err(format!(
+                "Profile duration must be between 1 and {} ticks.",
+                MAX_PROFILE_TICKS
+            )),
+        }
+    } else {
+        Err("Profile for how many ticks?".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Profile {
+    pub actor: Entity,
+    pub ticks: u32,
+}
+
+into_action!(Profile);
+
+#[tracing::instrument(name = "profile system", skip_all, fields(action = "Profile"))]
+pub fn profile_system(
+    mut action_reader: EventReader<Action>,
+    mut config: ResMut<Configuration>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for action in action_reader.iter() {
+        if let Action::Profile(Profile { actor, ticks }) = action {
+            let message = if config.profile.is_some() {
+                "A profile is already being captured.".to_string()
+            } else {
+                config.profile = Some(ProfileRequest {
+                    actor: *actor,
+                    ticks: *ticks,
+                });
+                format!("Profiling the next {} ticks.", ticks)
+            };
+
+            if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                messages.queue(message);
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use bevy_app::{EventReader, EventWriter};
+use bevy_ecs::prelude::*;
+
+use crate::{
+    ecs::{Ecs, GuardSystem, Phase, Plugin, Step},
+    world::{
+        action::{movement::Teleport, Action},
+        scripting::QueuedAction,
+        types::{
+            object::{Behavior, BehaviorKind, Behaviors, Object},
+            player::{Messages, Player},
+            Contents, Health, Location, Named,
+        },
+    },
+};
+
+/// Something a player did to an object which may set off the behaviors of its prototype.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BehaviorEvent {
+    Used { actor: Entity, object: Entity },
+    Taken { actor: Entity, object: Entity },
+}
+
+#[derive(Default)]
+pub struct BehaviorPlugin {}
+
+impl Plugin for BehaviorPlugin {
+    fn build(&self, ecs: &mut Ecs) {
+        ecs.add_event::<BehaviorEvent>()
+            .add_system(Step::PostEvent, Phase::Update, teleporter_system.guarded())
+            .add_system(
+                Step::PostEvent,
+                Phase::Update,
+                pickup_message_system.guarded(),
+            )
+            .add_system(Step::Main, Phase::First, trap_system.guarded());
+    }
+}
+
+// Finds a behavior of the prototype an object was made from.
+fn behavior<'a>(
+    object: Entity,
+    object_query: &Query<&Object>,
+    behaviors_query: &'a Query<&Behaviors>,
+    kind: BehaviorKind,
+) -> Option<&'a Behavior> {
+    let prototype = object_query.get(object).ok()?.prototype();
+    behaviors_query.get(prototype).ok()?.get(kind)
+}
+
+/// Whether an object does something when used, without any scripts.
+pub fn usable(object: &Object, behaviors_query: &Query<&Behaviors>) -> bool {
+    behaviors_query
+        .get(object.prototype())
+        .map(|behaviors| behaviors.get(BehaviorKind::Teleport).is_some())
+        .unwrap_or(false)
+}
+
+// Sends players who use teleporters to their destination.
+#[tracing::instrument(name = "teleporter system", skip_all)]
+pub fn teleporter_system(
+    mut event_reader: EventReader<BehaviorEvent>,
+    mut pre_events: EventWriter<QueuedAction>,
+    object_query: Query<&Object>,
+    behaviors_query: Query<&Behaviors>,
+) {
+    for event in event_reader.iter() {
+        if let BehaviorEvent::Used { actor, object } = event {
+            if let Some(Behavior::Teleport(room_id)) = behavior(
+                *object,
+                &object_query,
+                &behaviors_query,
+                BehaviorKind::Teleport,
+            ) {
+                pre_events.send(QueuedAction::from(Action::from(Teleport {
+                    actor: *actor,
+                    room_id: *room_id,
+                })));
+            }
+        }
+    }
+}
+
+// Tells players who pick up an object with a message what it says.
+#[tracing::instrument(name = "pickup message system", skip_all)]
+pub fn pickup_message_system(
+    mut event_reader: EventReader<BehaviorEvent>,
+    object_query: Query<&Object>,
+    behaviors_query: Query<&Behaviors>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    for event in event_reader.iter() {
+        if let BehaviorEvent::Taken { actor, object } = event {
+            if let Some(Behavior::Message(message)) = behavior(
+                *object,
+                &object_query,
+                &behaviors_query,
+                BehaviorKind::Message,
+            ) {
+                if let Ok(mut messages) = messages_query.get_mut(*actor) {
+                    messages.queue(message.clone());
+                }
+            }
+        }
+    }
+}
+
+// Springs the traps in rooms players have just entered. Players logging in are spared. This runs
+// ahead of the actions on the tick after a move, so the trap goes off along with the look at the
+// room the move queued.
+#[tracing::instrument(name = "trap system", skip_all)]
+pub fn trap_system(
+    mut player_query: Query<
+        (
+            &Location,
+            ChangeTrackers<Location>,
+            &mut Health,
+            &mut Messages,
+        ),
+        (With<Player>, Changed<Location>),
+    >,
+    contents_query: Query<&Contents>,
+    object_query: Query<&Object>,
+    named_query: Query<&Named>,
+    behaviors_query: Query<&Behaviors>,
+) {
+    for (location, tracker, mut health, mut messages) in player_query.iter_mut() {
+        if tracker.is_added() {
+            continue;
+        }
+
+        let contents = match contents_query.get(location.entity()) {
+            Ok(contents) => contents,
+            Err(_) => continue,
+        };
+
+        for object in contents.objects() {
+            if let Some(Behavior::Trap(damage)) =
+                behavior(*object, &object_query, &behaviors_query, BehaviorKind::Trap)
+            {
+                health.damage(*damage as f32);
+
+                if let Ok(named) = named_query.get(*object) {
+                    messages.queue(format!("You set off {}!", named.as_str()));
+                }
+            }
+        }
+    }
+}
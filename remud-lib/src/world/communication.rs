@@ -0,0 +1,41 @@
+use bevy_ecs::prelude::*;
+
+use crate::{
+    clock::Clock,
+    ecs::{CoreSystem, Ecs, GuardSystem, Phase, Plugin, Step},
+    engine::persist::{self, Updates},
+    world::types::communication::Communications,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, SystemLabel)]
+pub enum CommunicationSystem {
+    Retention,
+}
+
+#[derive(Default)]
+pub struct CommunicationPlugin {}
+
+impl Plugin for CommunicationPlugin {
+    fn build(&self, ecs: &mut Ecs) {
+        ecs.add_system(
+            Step::Main,
+            Phase::Update,
+            communication_retention_system
+                .guarded()
+                .label(CommunicationSystem::Retention)
+                .after(CoreSystem::Time),
+        );
+    }
+}
+
+// Drops communication older than the retention period from the log and the database.
+#[tracing::instrument(name = "communication retention system", skip_all)]
+pub fn communication_retention_system(
+    clock: Res<Clock>,
+    mut communications: ResMut<Communications>,
+    mut updates: ResMut<Updates>,
+) {
+    if let Some(before) = communications.take_expired(clock.unix_now()) {
+        updates.persist(persist::communication::Prune::new(before));
+    }
+}
@@ -145,7 +145,7 @@ impl State for WanderState {
             let exit = exits.keys().choose(&mut thread_rng());
 
             // If a suitable exit was found, queue a move action.
-            if let Some(exit) = exit.copied() {
+            if let Some(exit) = exit.cloned() {
                 let mut events = world.get_resource_mut::<Events<QueuedAction>>().unwrap();
                 events.send(QueuedAction {
                     action: Action::Move(Move {
@@ -269,7 +269,7 @@ impl State for FollowState {
             .unwrap()
             .exits()
             .iter()
-            .for_each(|(d, r)| rooms.push((Some(*d), *r)));
+            .for_each(|(d, exit)| rooms.push((Some(d.clone()), exit.destination)));
 
         // Check each room to determine if it contains the target, extracting the movement direction.
         let mut target_found = false;
@@ -311,7 +311,7 @@ impl State for FollowState {
     #[tracing::instrument(name = "follow act")]
     fn act(&mut self, entity: Entity, world: &mut World) {
         // We know where the target is, follow them.
-        if let Some(direction) = self.move_direction {
+        if let Some(direction) = &self.move_direction {
             let mut done_waiting = false;
             world.with_timers(entity, |t| done_waiting = t.finished(Self::TIMER_NAME));
 
@@ -320,7 +320,7 @@ impl State for FollowState {
                 events.send(QueuedAction {
                     action: Action::Move(Move {
                         actor: entity,
-                        direction,
+                        direction: direction.clone(),
                     }),
                 });
 
@@ -0,0 +1,363 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use bevy_ecs::prelude::*;
+
+use crate::world::types::{
+    bank::Vault,
+    mail::Mailbox,
+    market::Market,
+    object::Object,
+    player::{Player, Players},
+    room::Room,
+    Contents, Id, Location, Named,
+};
+
+// Violations found since the process started, across every world.
+static VIOLATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of invariant violations found since the process started. Property tests and soak
+/// runs check this is still zero once they finish.
+pub fn violations() -> usize {
+    VIOLATIONS.load(Ordering::Relaxed)
+}
+
+/// A broken link between entities which the world's systems should always keep consistent.
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub subject: String,
+    pub detail: String,
+}
+
+impl Violation {
+    fn new(world: &World, subject: Entity, detail: String) -> Self {
+        Violation {
+            subject: describe(world, subject),
+            detail,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.subject, self.detail)
+    }
+}
+
+/// Checks that the links between players, rooms, objects and the player index agree with each
+/// other, logging and counting any violations.
+pub fn check(world: &mut World) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    check_players(world, &mut violations);
+    check_player_index(world, &mut violations);
+    check_room_players(world, &mut violations);
+    check_contents(world, &mut violations);
+    check_objects(world, &mut violations);
+
+    for violation in violations.iter() {
+        tracing::error!("world invariant violated by {}", violation);
+    }
+    VIOLATIONS.fetch_add(violations.len(), Ordering::Relaxed);
+
+    violations
+}
+
+// Every player stands in a room which lists them, and is indexed under their name and ID.
+fn check_players(world: &mut World, violations: &mut Vec<Violation>) {
+    let players = world
+        .query::<(Entity, &Player, &Named, &Location)>()
+        .iter(world)
+        .map(|(entity, player, named, location)| {
+            (entity, player.id(), named.to_string(), location.entity())
+        })
+        .collect::<Vec<_>>();
+
+    let index = world.get_resource::<Players>().unwrap();
+
+    for (entity, id, name, room) in players {
+        match world.get::<Room>(room) {
+            Some(listing) if !listing.players().contains(&entity) => {
+                violations.push(Violation::new(
+                    world,
+                    entity,
+                    format!("is not listed by its location, {}", describe(world, room)),
+                ))
+            }
+            Some(_) => (),
+            None => violations.push(Violation::new(
+                world,
+                entity,
+                format!(
+                    "is located in {}, which is not a room",
+                    describe(world, room)
+                ),
+            )),
+        }
+
+        if index.by_name(name.as_str()) != Some(entity) {
+            violations.push(Violation::new(
+                world,
+                entity,
+                format!("is not indexed by name as {}", name),
+            ));
+        }
+
+        if index.by_id(id) != Some(entity) {
+            violations.push(Violation::new(
+                world,
+                entity,
+                "is not indexed by ID".to_string(),
+            ));
+        }
+    }
+}
+
+// Every indexed name belongs to a player with that name.
+fn check_player_index(world: &mut World, violations: &mut Vec<Violation>) {
+    let index = world.get_resource::<Players>().unwrap();
+
+    for (name, entity) in index.iter() {
+        let named = match (world.get::<Player>(entity), world.get::<Named>(entity)) {
+            (Some(_), Some(named)) => named,
+            _ => {
+                violations.push(Violation {
+                    subject: format!("player index entry {}", name),
+                    detail: format!(
+                        "points to {}, which is not a player",
+                        describe(world, entity)
+                    ),
+                });
+                continue;
+            }
+        };
+
+        if named.as_str() != name {
+            violations.push(Violation {
+                subject: format!("player index entry {}", name),
+                detail: format!("points to a player named {}", named),
+            });
+        }
+    }
+}
+
+// Every player a room lists is located in that room.
+fn check_room_players(world: &mut World, violations: &mut Vec<Violation>) {
+    let rooms = world
+        .query::<(Entity, &Room)>()
+        .iter(world)
+        .map(|(entity, room)| (entity, room.get_players()))
+        .collect::<Vec<_>>();
+
+    for (room, players) in rooms {
+        for player in players {
+            let located = world
+                .get::<Location>(player)
+                .map(Location::entity)
+                .filter(|_| world.get::<Player>(player).is_some());
+
+            if located != Some(room) {
+                violations.push(Violation::new(
+                    world,
+                    room,
+                    format!(
+                        "lists {}, which is not located there",
+                        describe(world, player)
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+// Every object held in contents is located in its holder.
+fn check_contents(world: &mut World, violations: &mut Vec<Violation>) {
+    let holders = world
+        .query::<(Entity, &Contents)>()
+        .iter(world)
+        .map(|(entity, contents)| (entity, contents.get_objects()))
+        .collect::<Vec<_>>();
+
+    for (holder, objects) in holders {
+        for object in objects {
+            let located = world
+                .get::<Location>(object)
+                .map(Location::entity)
+                .filter(|_| world.get::<Object>(object).is_some());
+
+            if located != Some(holder) {
+                violations.push(Violation::new(
+                    world,
+                    holder,
+                    format!(
+                        "holds {}, which is not located there",
+                        describe(world, object)
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+// Every object is held in the contents of its location, unless it is tucked away in a vault, listed
+// on the market or attached to mail.
+fn check_objects(world: &mut World, violations: &mut Vec<Violation>) {
+    let objects = world
+        .query::<(Entity, &Object, &Location)>()
+        .iter(world)
+        .map(|(entity, _, location)| (entity, location.entity()))
+        .collect::<Vec<_>>();
+
+    let mut stored = world
+        .query::<&Vault>()
+        .iter(world)
+        .flat_map(|vault| vault.get_objects())
+        .collect::<HashSet<_>>();
+    if let Some(market) = world.get_resource::<Market>() {
+        stored.extend(market.iter().map(|(_, listing)| listing.object));
+    }
+    if let Some(mailbox) = world.get_resource::<Mailbox>() {
+        stored.extend(mailbox.iter().filter_map(|(_, mail)| mail.object));
+    }
+
+    for (object, location) in objects {
+        if stored.contains(&object) {
+            continue;
+        }
+
+        match world.get::<Contents>(location) {
+            Some(contents) if contents.contains(&object) => (),
+            Some(_) => violations.push(Violation::new(
+                world,
+                object,
+                format!("is not held by its location, {}", describe(world, location)),
+            )),
+            None => violations.push(Violation::new(
+                world,
+                object,
+                format!(
+                    "is located in {}, which holds nothing",
+                    describe(world, location)
+                ),
+            )),
+        }
+    }
+}
+
+// Names an entity by its ID, falling back to the entity itself when it has none.
+fn describe(world: &World, entity: Entity) -> String {
+    match world.get::<Id>(entity) {
+        Some(id) => id.to_string(),
+        None => format!("entity {:?}", entity),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::world::types::{object::ObjectId, player::PlayerId, room::RoomId};
+
+    use std::convert::TryFrom;
+
+    fn spawn_room(world: &mut World, id: i64) -> Entity {
+        let id = RoomId::try_from(id).unwrap();
+        world
+            .spawn()
+            .insert_bundle((Id::Room(id), Room::from(id), Contents::default()))
+            .id()
+    }
+
+    #[test]
+    fn test_consistent_world() {
+        let mut world = World::new();
+        world.insert_resource(Players::default());
+
+        let room = spawn_room(&mut world, 1);
+        let id = PlayerId::try_from(1).unwrap();
+        let player = world
+            .spawn()
+            .insert_bundle((
+                Id::Player(id),
+                Player::from(id),
+                Named::from("Shane".to_string()),
+                Location::from(room),
+                Contents::default(),
+            ))
+            .id();
+        let object_id = ObjectId::try_from(1).unwrap();
+        let prototype = world.spawn().id();
+        let object = world
+            .spawn()
+            .insert_bundle((
+                Id::Object(object_id),
+                Object::new(object_id, prototype, false),
+                Location::from(player),
+            ))
+            .id();
+
+        world.get_mut::<Room>(room).unwrap().insert_player(player);
+        world.get_mut::<Contents>(player).unwrap().insert(object);
+        world
+            .get_resource_mut::<Players>()
+            .unwrap()
+            .insert(player, "Shane".to_string(), id);
+
+        assert!(check(&mut world).is_empty());
+
+        // the object is dropped without being moved into the room
+        world.get_mut::<Contents>(player).unwrap().remove(object);
+        world.get_mut::<Contents>(room).unwrap().insert(object);
+
+        let violations = check(&mut world);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(
+            violations[0].to_string(),
+            "room 1: holds object 1, which is not located there"
+        );
+        assert_eq!(
+            violations[1].to_string(),
+            "object 1: is not held by its location, player 1"
+        );
+        assert!(super::violations() >= 2);
+    }
+
+    #[test]
+    fn test_player_links() {
+        let mut world = World::new();
+        world.insert_resource(Players::default());
+
+        let room = spawn_room(&mut world, 1);
+        let other = spawn_room(&mut world, 2);
+        let id = PlayerId::try_from(1).unwrap();
+        let player = world
+            .spawn()
+            .insert_bundle((
+                Id::Player(id),
+                Player::from(id),
+                Named::from("Shane".to_string()),
+                Location::from(room),
+            ))
+            .id();
+
+        // listed by the wrong room and never indexed
+        world.get_mut::<Room>(other).unwrap().insert_player(player);
+
+        let violations = check(&mut world)
+            .into_iter()
+            .map(|violation| violation.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            violations,
+            vec![
+                "player 1: is not listed by its location, room 1",
+                "player 1: is not indexed by name as Shane",
+                "player 1: is not indexed by ID",
+                "room 2: lists player 1, which is not located there",
+            ]
+        );
+    }
+}
@@ -0,0 +1,384 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+};
+
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+
+use crate::{
+    clock::Clock,
+    world::{
+        action::immortal::{
+            prototype::DEFAULT_PROTOTYPE_DESCRIPTION, room::DEFAULT_ROOM_DESCRIPTION,
+        },
+        scripting::{Script, ScriptHooks, ScriptTrigger, Scripts},
+        types::{
+            object::{Keywords, Object, Prototype},
+            room::{LoadedRegions, Room, Rooms},
+            Configuration, Description, Id,
+        },
+        VOID_ROOM_ID,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LintKind {
+    NoExits,
+    OneWayExit,
+    MissingDescription,
+    EmptyKeywords,
+    UnusedTrigger,
+    Unreachable,
+}
+
+impl LintKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintKind::NoExits => "no_exits",
+            LintKind::OneWayExit => "one_way_exit",
+            LintKind::MissingDescription => "missing_description",
+            LintKind::EmptyKeywords => "empty_keywords",
+            LintKind::UnusedTrigger => "unused_trigger",
+            LintKind::Unreachable => "unreachable",
+        }
+    }
+}
+
+impl fmt::Display for LintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A building mistake found in the world.
+#[derive(Debug, Clone)]
+pub struct LintFinding {
+    pub kind: LintKind,
+    pub subject: Id,
+    pub detail: String,
+}
+
+impl LintFinding {
+    fn new(kind: LintKind, subject: Id, detail: String) -> Self {
+        LintFinding {
+            kind,
+            subject,
+            detail,
+        }
+    }
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.subject, self.detail)
+    }
+}
+
+/// The most recent lint of the world, kept as a resource so it can be viewed without linting
+/// again.
+#[derive(Debug, Clone)]
+pub struct LintReport {
+    // seconds since the Unix epoch
+    pub generated_at: i64,
+    pub findings: Vec<LintFinding>,
+}
+
+impl LintReport {
+    /// The number of findings of each kind, in kind order.
+    pub fn summary(&self) -> Vec<(LintKind, usize)> {
+        self.findings
+            .iter()
+            .map(|finding| finding.kind)
+            .sorted()
+            .dedup_with_count()
+            .map(|(count, kind)| (kind, count))
+            .collect_vec()
+    }
+
+    pub fn describe(&self) -> String {
+        if self.findings.is_empty() {
+            return "World lint found no issues.".to_string();
+        }
+
+        let plural = if self.findings.len() == 1 { "" } else { "s" };
+
+        format!(
+            "World lint found {} issue{}:\r\n{}",
+            self.findings.len(),
+            plural,
+            self.findings
+                .iter()
+                .map(|finding| format!("  {}", finding))
+                .join("\r\n")
+        )
+    }
+}
+
+/// Checks the world for common building mistakes, storing the report as a resource.
+pub fn lint(world: &mut World) -> LintReport {
+    let mut findings = Vec::new();
+
+    // Exits into regions which are not loaded are missing from the world, so the rooms and how
+    // they connect are only checked while every room is loaded.
+    let lazy = world.get_resource::<LoadedRegions>().unwrap().is_lazy();
+
+    if !lazy {
+        lint_rooms(world, &mut findings);
+    }
+    lint_descriptions(world, &mut findings);
+    lint_keywords(world, &mut findings);
+    lint_hooks(world, &mut findings);
+    if !lazy {
+        lint_reachability(world, &mut findings);
+    }
+
+    let report = LintReport {
+        generated_at: world.get_resource::<Clock>().unwrap().unix_now(),
+        findings,
+    };
+
+    world.insert_resource(report.clone());
+
+    report
+}
+
+// Rooms with no way out and exits with no way back.
+fn lint_rooms(world: &mut World, findings: &mut Vec<LintFinding>) {
+    let rooms: HashMap<Entity, &Room> = world.query::<(Entity, &Room)>().iter(world).collect();
+
+    for room in rooms
+        .values()
+        .filter(|room| room.id() != *VOID_ROOM_ID)
+        .sorted_by_key(|room| room.id())
+    {
+        if room.exits().is_empty() {
+            findings.push(LintFinding::new(
+                LintKind::NoExits,
+                Id::Room(room.id()),
+                "has no exits".to_string(),
+            ));
+            continue;
+        }
+
+        for (direction, exit) in room
+            .exits()
+            .iter()
+            .sorted_by_key(|(direction, _)| direction.as_str())
+        {
+            let destination = match rooms.get(&exit.destination) {
+                Some(destination) => destination,
+                None => continue,
+            };
+
+            let leads_here =
+                |back: &Entity| rooms.get(back).map_or(false, |back| back.id() == room.id());
+
+            // Custom exits may return by any exit, directions by the opposite direction.
+            let (returns, back) = match direction.opposite() {
+                Some(opposite) => (
+                    destination
+                        .exit(&opposite)
+                        .map_or(false, |back| leads_here(&back)),
+                    format!(" {}", opposite),
+                ),
+                None => (
+                    destination
+                        .exits()
+                        .values()
+                        .any(|back| leads_here(&back.destination)),
+                    String::new(),
+                ),
+            };
+
+            if !returns {
+                findings.push(LintFinding::new(
+                    LintKind::OneWayExit,
+                    Id::Room(room.id()),
+                    format!(
+                        "exit {} leads to room {}, which has no exit{} back",
+                        direction,
+                        destination.id(),
+                        back
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+// Rooms and prototypes left with an empty or placeholder description. Objects take their
+// descriptions from their prototypes, so only those changed to nothing are reported.
+fn lint_descriptions(world: &mut World, findings: &mut Vec<LintFinding>) {
+    let missing = |description: &Description, default: &str| {
+        let text = description.as_str().trim();
+        text.is_empty() || text == default
+    };
+
+    for (room, _) in world
+        .query::<(&Room, &Description)>()
+        .iter(world)
+        .filter(|(room, _)| room.id() != *VOID_ROOM_ID)
+        .filter(|(_, description)| missing(description, DEFAULT_ROOM_DESCRIPTION))
+        .sorted_by_key(|(room, _)| room.id())
+    {
+        findings.push(LintFinding::new(
+            LintKind::MissingDescription,
+            Id::Room(room.id()),
+            "has no description".to_string(),
+        ));
+    }
+
+    for (prototype, _) in world
+        .query::<(&Prototype, &Description)>()
+        .iter(world)
+        .filter(|(_, description)| missing(description, DEFAULT_PROTOTYPE_DESCRIPTION))
+        .sorted_by_key(|(prototype, _)| prototype.id())
+    {
+        findings.push(LintFinding::new(
+            LintKind::MissingDescription,
+            Id::Prototype(prototype.id()),
+            "has no description".to_string(),
+        ));
+    }
+
+    for (object, _) in world
+        .query::<(&Object, &Description)>()
+        .iter(world)
+        .filter(|(_, description)| description.as_str().trim().is_empty())
+    {
+        findings.push(LintFinding::new(
+            LintKind::MissingDescription,
+            Id::Object(object.id()),
+            "has no description".to_string(),
+        ));
+    }
+}
+
+// Objects and prototypes which players have no way to refer to.
+fn lint_keywords(world: &mut World, findings: &mut Vec<LintFinding>) {
+    for (prototype, _) in world
+        .query::<(&Prototype, &Keywords)>()
+        .iter(world)
+        .filter(|(_, keywords)| keywords.get_list().is_empty())
+        .sorted_by_key(|(prototype, _)| prototype.id())
+    {
+        findings.push(LintFinding::new(
+            LintKind::EmptyKeywords,
+            Id::Prototype(prototype.id()),
+            "has no keywords".to_string(),
+        ));
+    }
+
+    for (object, _) in world
+        .query::<(&Object, &Keywords)>()
+        .iter(world)
+        .filter(|(_, keywords)| keywords.get_list().is_empty())
+    {
+        findings.push(LintFinding::new(
+            LintKind::EmptyKeywords,
+            Id::Object(object.id()),
+            "has no keywords".to_string(),
+        ));
+    }
+}
+
+// Hooks which never run their script: the script is gone, or it was changed to trigger on a
+// different event than the one it is attached to.
+fn lint_hooks(world: &mut World, findings: &mut Vec<LintFinding>) {
+    let hooked = world
+        .query::<(&ScriptHooks, Option<&Id>, Option<&Prototype>)>()
+        .iter(world)
+        .filter_map(|(hooks, id, prototype)| {
+            let subject = match (id, prototype) {
+                (Some(id), _) => *id,
+                (None, Some(prototype)) => Id::Prototype(prototype.id()),
+                (None, None) => return None,
+            };
+            Some((subject, hooks.hooks().to_vec()))
+        })
+        .collect_vec();
+
+    for (subject, hooks) in hooked {
+        for hook in hooks {
+            let script = world
+                .get_resource::<Scripts>()
+                .and_then(|scripts| scripts.by_name(&hook.script))
+                .and_then(|entity| world.get::<Script>(entity));
+
+            let script = match script {
+                Some(script) => script,
+                None => {
+                    findings.push(LintFinding::new(
+                        LintKind::UnusedTrigger,
+                        subject,
+                        format!("hook runs missing script {}", hook.script),
+                    ));
+                    continue;
+                }
+            };
+
+            if let ScriptTrigger::PreEvent(event) | ScriptTrigger::PostEvent(event) = hook.trigger {
+                if script.trigger() != event {
+                    findings.push(LintFinding::new(
+                        LintKind::UnusedTrigger,
+                        subject,
+                        format!(
+                            "script {} is attached to {} but triggers on {}",
+                            hook.script,
+                            event,
+                            script.trigger()
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+// Rooms players cannot walk to from the spawn room.
+fn lint_reachability(world: &mut World, findings: &mut Vec<LintFinding>) {
+    let spawn = world
+        .get_resource::<Configuration>()
+        .map(|configuration| configuration.spawn_room)
+        .and_then(|spawn_room| {
+            world
+                .get_resource::<Rooms>()
+                .and_then(|rooms| rooms.by_id(spawn_room))
+        });
+
+    let spawn = match spawn {
+        Some(spawn) => spawn,
+        None => return,
+    };
+
+    let rooms: HashMap<Entity, &Room> = world.query::<(Entity, &Room)>().iter(world).collect();
+
+    let mut reached = HashSet::new();
+    let mut queue = VecDeque::new();
+    reached.insert(spawn);
+    queue.push_back(spawn);
+
+    while let Some(entity) = queue.pop_front() {
+        if let Some(room) = rooms.get(&entity) {
+            for exit in room.exits().values() {
+                if reached.insert(exit.destination) {
+                    queue.push_back(exit.destination);
+                }
+            }
+        }
+    }
+
+    for room in rooms
+        .iter()
+        .filter(|(entity, room)| !reached.contains(*entity) && room.id() != *VOID_ROOM_ID)
+        .map(|(_, room)| room)
+        .sorted_by_key(|room| room.id())
+    {
+        findings.push(LintFinding::new(
+            LintKind::Unreachable,
+            Id::Room(room.id()),
+            "cannot be reached from the spawn room".to_string(),
+        ));
+    }
+}
@@ -0,0 +1,147 @@
+use bevy_ecs::prelude::*;
+
+use crate::{
+    clock::Clock,
+    ecs::{CoreSystem, Ecs, GuardSystem, Phase, Plugin, Step},
+    engine::persist::{self, UpdateGroup, Updates},
+    world::types::{
+        bank::{format_coins, Bank},
+        mail::{Mail, MailId, Mailbox},
+        object::{Object, Objects},
+        player::{Messages, Player, Players},
+        Contents, Location, Named,
+    },
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, SystemLabel)]
+pub enum MailSystem {
+    Expiry,
+}
+
+#[derive(Default)]
+pub struct MailPlugin {}
+
+impl Plugin for MailPlugin {
+    fn build(&self, ecs: &mut Ecs) {
+        ecs.add_system(
+            Step::Main,
+            Phase::Update,
+            mail_expiry_system
+                .guarded()
+                .label(MailSystem::Expiry)
+                .after(CoreSystem::Time),
+        );
+    }
+}
+
+/// Removes mail and returns any unclaimed attachments to the sender. Coins are deposited in the
+/// sender's bank account. Senders who are online receive the object immediately, others find it
+/// in their inventory when they next log in. Returns the sender's entity if they are online.
+pub fn return_mail(
+    id: MailId,
+    mail: &Mail,
+    commands: &mut Commands,
+    bank: &mut Bank,
+    players: &Players,
+    objects: &mut Objects,
+    updates: &mut Updates,
+    object_query: &Query<&Object>,
+    contents_query: &mut Query<&mut Contents, With<Player>>,
+) -> Option<Entity> {
+    let mut update = UpdateGroup::new(vec![persist::mail::Remove::new(id)]);
+
+    if mail.coins > 0 {
+        let account = bank.open(mail.sender, mail.sender_name.as_str());
+        account.balance += mail.coins;
+        update.append(persist::bank::Account::new(
+            mail.sender,
+            account.coins,
+            account.balance,
+        ));
+    }
+
+    let sender = players.by_id(mail.sender);
+
+    if let Some(object) = mail.object {
+        if let Ok(found) = object_query.get(object) {
+            update.append(persist::player::AddObject::new(mail.sender, found.id()));
+
+            match sender.and_then(|sender| contents_query.get_mut(sender).ok()) {
+                Some(mut contents) => {
+                    contents.insert(object);
+                    commands
+                        .entity(object)
+                        .insert(Location::from(sender.unwrap()));
+                }
+                None => {
+                    commands.entity(object).despawn();
+                    objects.remove_entity(object);
+                }
+            }
+        }
+    }
+
+    updates.persist(update);
+
+    sender
+}
+
+/// Describes the attachments of a piece of mail, such as "50 coins and a rusty sword".
+pub fn describe_attachments(mail: &Mail, named_query: &Query<&Named>) -> Option<String> {
+    let object = mail
+        .object
+        .and_then(|object| named_query.get(object).ok())
+        .map(ToString::to_string);
+
+    match (mail.coins > 0, object) {
+        (true, Some(object)) => Some(format!("{} and {}", format_coins(mail.coins), object)),
+        (true, None) => Some(format_coins(mail.coins)),
+        (false, Some(object)) => Some(object),
+        (false, None) => None,
+    }
+}
+
+// Returns the unclaimed attachments of expired mail to their senders.
+#[tracing::instrument(name = "mail expiry system", skip_all)]
+pub fn mail_expiry_system(
+    mut commands: Commands,
+    clock: Res<Clock>,
+    mut mailbox: ResMut<Mailbox>,
+    mut bank: ResMut<Bank>,
+    players: Res<Players>,
+    mut objects: ResMut<Objects>,
+    mut updates: ResMut<Updates>,
+    object_query: Query<&Object>,
+    named_query: Query<&Named>,
+    mut contents_query: Query<&mut Contents, With<Player>>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    if mailbox.is_empty() {
+        return;
+    }
+
+    for (id, mail) in mailbox.take_expired(clock.unix_now()) {
+        let attachments = describe_attachments(&mail, &named_query);
+
+        let sender = return_mail(
+            id,
+            &mail,
+            &mut commands,
+            &mut bank,
+            &players,
+            &mut objects,
+            &mut updates,
+            &object_query,
+            &mut contents_query,
+        );
+
+        if let (Some(attachments), Some(sender)) = (attachments, sender) {
+            if let Ok(mut messages) = messages_query.get_mut(sender) {
+                messages.queue(format!(
+                    "Your mail to {} expired unclaimed, returning {} to you.",
+                    mail.recipient_name, attachments
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use bevy_ecs::prelude::*;
+
+use crate::{
+    clock::Clock,
+    ecs::{CoreSystem, Ecs, GuardSystem, Phase, Plugin, Step},
+    engine::persist::{self, UpdateGroup, Updates},
+    world::types::{
+        market::{Listing, ListingId, Market},
+        object::{Object, Objects},
+        player::{Messages, Player, Players},
+        Contents, Location, Named,
+    },
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, SystemLabel)]
+pub enum MarketSystem {
+    Expiry,
+}
+
+#[derive(Default)]
+pub struct MarketPlugin {}
+
+impl Plugin for MarketPlugin {
+    fn build(&self, ecs: &mut Ecs) {
+        ecs.add_system(
+            Step::Main,
+            Phase::Update,
+            market_expiry_system
+                .guarded()
+                .label(MarketSystem::Expiry)
+                .after(CoreSystem::Time),
+        );
+    }
+}
+
+/// Returns a listed object to its seller. Sellers who are online receive the object immediately,
+/// others find it in their inventory when they next log in. Returns the seller's entity if they
+/// are online.
+pub fn return_listing(
+    id: ListingId,
+    listing: &Listing,
+    commands: &mut Commands,
+    players: &Players,
+    objects: &mut Objects,
+    updates: &mut Updates,
+    object_query: &Query<&Object>,
+    contents_query: &mut Query<&mut Contents, With<Player>>,
+) -> Option<Entity> {
+    let object_id = match object_query.get(listing.object) {
+        Ok(object) => object.id(),
+        Err(_) => return None,
+    };
+
+    updates.persist(UpdateGroup::new(vec![
+        persist::market::Remove::new(id),
+        persist::player::AddObject::new(listing.seller, object_id),
+    ]));
+
+    let seller = players.by_name(listing.seller_name.as_str());
+
+    match seller.and_then(|seller| contents_query.get_mut(seller).ok()) {
+        Some(mut contents) => {
+            contents.insert(listing.object);
+            commands
+                .entity(listing.object)
+                .insert(Location::from(seller.unwrap()));
+            seller
+        }
+        None => {
+            commands.entity(listing.object).despawn();
+            objects.remove_entity(listing.object);
+            None
+        }
+    }
+}
+
+// Returns the objects of expired listings to their sellers.
+#[tracing::instrument(name = "market expiry system", skip_all)]
+pub fn market_expiry_system(
+    mut commands: Commands,
+    clock: Res<Clock>,
+    mut market: ResMut<Market>,
+    players: Res<Players>,
+    mut objects: ResMut<Objects>,
+    mut updates: ResMut<Updates>,
+    object_query: Query<&Object>,
+    named_query: Query<&Named>,
+    mut contents_query: Query<&mut Contents, With<Player>>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    if market.is_empty() {
+        return;
+    }
+
+    for (id, listing) in market.take_expired(clock.unix_now()) {
+        let name = named_query
+            .get(listing.object)
+            .map(|named| named.to_string())
+            .unwrap_or_default();
+
+        if let Some(seller) = return_listing(
+            id,
+            &listing,
+            &mut commands,
+            &players,
+            &mut objects,
+            &mut updates,
+            &object_query,
+            &mut contents_query,
+        ) {
+            if let Ok(mut messages) = messages_query.get_mut(seller) {
+                messages.queue(format!(
+                    "Your market listing of {} expired and was returned to you.",
+                    name
+                ));
+            }
+        }
+    }
+}
@@ -1,11 +1,27 @@
 #![allow(clippy::type_complexity)]
 
 pub mod action;
+pub mod behavior;
+pub mod communication;
 pub mod fsm;
+#[cfg(feature = "invariants")]
+pub mod invariants;
+pub mod lint;
+pub mod mail;
+pub mod market;
+pub mod region;
 pub mod scripting;
+pub mod season;
+pub mod shop;
+pub mod spawn;
 pub mod types;
 
-use std::{collections::VecDeque, convert::TryFrom, str::FromStr};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    convert::TryFrom,
+    str::FromStr,
+    time::Duration,
+};
 
 use bevy_app::Events;
 use bevy_ecs::prelude::{Entity, With, World};
@@ -14,21 +30,52 @@ use once_cell::sync::Lazy;
 use rhai::ParseError;
 
 use crate::{
-    ecs::{Ecs, Step},
-    engine::persist::{self, DynPersist, Updates},
-    web::scripts::ScriptError,
+    clock::Clock,
+    ecs::{Ecs, Step, SystemPanic},
+    engine::persist::{self, DynPersist, RoomLoad, Updates, Write},
+    logging::GAME_LOG_TARGET,
+    text::{
+        banner::{banner, fit_art},
+        Tokenizer,
+    },
+    web::{graphql::WorldSnapshot, scripts::ScriptError},
     world::{
-        action::{commands::Commands, Action},
+        action::{
+            commands::Commands, immortal::possess::possessed_may_perform, movement::Move, Action,
+            ActionSystem,
+        },
+        lint::LintReport,
+        region::RegionPlan,
         scripting::{
-            actions::compile_scripts, run_init_scripts, run_post_action_scripts,
-            run_pre_action_scripts, run_timed_scripts, QueuedAction, Script, ScriptName,
-            TriggerEvent,
+            actions::compile_scripts,
+            http::{HttpRequest, HttpRequests, HttpResponse},
+            parse_folder, parse_tag, run_continuations, run_init_scripts, run_post_action_scripts,
+            run_pre_action_scripts, run_timed_scripts,
+            stats::{ScriptStat, ScriptStats},
+            time::Cutscenes,
+            QueuedAction, Script, ScriptName, TriggerEvent,
         },
         types::{
-            object::{Objects, PrototypeId},
-            player::{self, Messages, Player, PlayerFlags, Players},
-            room::{Regions, Room, RoomBundle, RoomId, Rooms},
-            Configuration, Contents, Description, Id, Location, Named,
+            art::Art,
+            bank::{Bank, Vault},
+            communication::{CommunicationFilter, CommunicationPage, Communications},
+            economy::{Economy, EconomySummary},
+            grammar::Pronouns,
+            mail::Mailbox,
+            market::Market,
+            news::News,
+            object::{Object, Objects, PrototypeId},
+            player::{
+                self, Allowlist, ClientCaps, CommunicationLog, Departed, ForcedCommand,
+                ForcedCommands, Messages, Needs, Player, PlayerFlags, PlayerId, Players,
+                Possessions, Snoops,
+            },
+            poll::Polls,
+            room::{Direction, LoadedRegions, Regions, Room, RoomBundle, RoomId, Rooms, Staging},
+            season::{SeasonWindow, Seasons},
+            tutorial::{HintQueue, HintTrigger, Tutorial},
+            Configuration, Contents, DbCheckRequest, Description, Id, Location, Named,
+            PlayerRenameRequest, ProfileRequest,
         },
     },
 };
@@ -59,7 +106,8 @@ impl GameWorld {
 
     #[tracing::instrument(name = "run pre/init", skip_all)]
     pub fn run_pre_init(&mut self) {
-        self.ecs.run(Step::PreEvent);
+        let panics = self.ecs.run(Step::PreEvent);
+        self.report_system_panics(panics);
 
         self.ecs.with_shared_world(|world| {
             run_init_scripts(world.clone());
@@ -69,14 +117,52 @@ impl GameWorld {
 
     #[tracing::instrument(name = "run main", skip_all)]
     pub fn run_main(&mut self) {
-        self.ecs.run(Step::Main);
-        self.ecs.run(Step::PostEvent);
+        let mut panics = self.ecs.run(Step::Main);
+        panics.extend(self.ecs.run(Step::PostEvent));
+        self.report_system_panics(panics);
+    }
+
+    // Tells the players whose actions were being handled by a system which panicked that they
+    // failed. Actions handled by the other systems are unaffected.
+    fn report_system_panics(&mut self, panics: Vec<SystemPanic>) {
+        if panics.is_empty() {
+            return;
+        }
+
+        let mut failed = Vec::new();
+        for panic in panics {
+            let systems = panic
+                .labels
+                .iter()
+                .filter_map(|label| label.as_dyn_eq().as_any().downcast_ref::<ActionSystem>())
+                .copied()
+                .collect_vec();
+
+            if systems.is_empty() {
+                tracing::warn!("no actions failed with system {}", panic.system);
+            }
+
+            failed.extend(systems);
+        }
+
+        let world = self.ecs.world_mut();
+        let actors = world
+            .get_resource::<Events<Action>>()
+            .unwrap()
+            .iter_current_update_events()
+            .filter(|action| failed.iter().any(|system| system.handles(action)))
+            .map(Action::actor)
+            .unique()
+            .collect_vec();
+
+        report_failure(world, actors);
     }
 
     #[tracing::instrument(name = "run post/timed", skip_all)]
     pub fn run_post_timed(&mut self) {
         self.ecs.with_shared_world(|world| {
             run_timed_scripts(world.clone());
+            run_continuations(world.clone());
             run_post_action_scripts(world);
         })
     }
@@ -95,10 +181,501 @@ impl GameWorld {
             .map_or(false, |configuration| configuration.restart)
     }
 
+    pub fn profile_request(&self) -> Option<ProfileRequest> {
+        self.ecs
+            .world()
+            .get_resource::<Configuration>()
+            .and_then(|configuration| configuration.profile)
+    }
+
+    pub fn finish_profile(&mut self, message: String) {
+        let world = self.ecs.world_mut();
+
+        if let Some(request) = world
+            .get_resource_mut::<Configuration>()
+            .and_then(|mut configuration| configuration.profile.take())
+        {
+            if let Some(mut messages) = world.get_mut::<Messages>(request.actor) {
+                messages.queue(message);
+            }
+        }
+    }
+
+    pub fn take_config_reload(&mut self) -> Option<Entity> {
+        self.ecs
+            .world_mut()
+            .get_resource_mut::<Configuration>()
+            .and_then(|mut configuration| configuration.config_reload.take())
+    }
+
+    pub fn finish_config_reload(&mut self, requester: Entity, message: String) {
+        if let Some(mut messages) = self.ecs.world_mut().get_mut::<Messages>(requester) {
+            messages.queue(message);
+        }
+    }
+
+    pub fn take_db_check(&mut self) -> Option<DbCheckRequest> {
+        self.ecs
+            .world_mut()
+            .get_resource_mut::<Configuration>()
+            .and_then(|mut configuration| configuration.db_check.take())
+    }
+
+    pub fn finish_db_check(&mut self, requester: Entity, message: String) {
+        if let Some(mut messages) = self.ecs.world_mut().get_mut::<Messages>(requester) {
+            messages.queue(message);
+        }
+    }
+
+    pub fn take_lint_request(&mut self) -> Option<Entity> {
+        self.ecs
+            .world_mut()
+            .get_resource_mut::<Configuration>()
+            .and_then(|mut configuration| configuration.lint_request.take())
+    }
+
+    /// Lints the world, replacing the latest report.
+    pub fn lint(&mut self) -> LintReport {
+        lint::lint(self.ecs.world_mut())
+    }
+
+    /// Checks the links between players, rooms and objects, logging any which are broken.
+    #[cfg(feature = "invariants")]
+    pub fn check_invariants(&mut self) -> Vec<invariants::Violation> {
+        invariants::check(self.ecs.world_mut())
+    }
+
+    pub fn finish_lint(&mut self, requester: Entity, report: &LintReport) {
+        if let Some(mut messages) = self.ecs.world_mut().get_mut::<Messages>(requester) {
+            messages.queue(report.describe());
+        }
+    }
+
+    /// The latest lint report, linting the world if it has not been linted yet.
+    pub fn lint_report(&mut self) -> LintReport {
+        match self.ecs.world().get_resource::<LintReport>() {
+            Some(report) => report.clone(),
+            None => self.lint(),
+        }
+    }
+
+    /// The coins created and destroyed over the last `days` days.
+    pub fn economy_summary(&self, days: u32) -> EconomySummary {
+        let world = self.ecs.world();
+
+        world.get_resource::<Economy>().unwrap().summary(
+            self.clock().unix_now(),
+            days,
+            world.get_resource::<Bank>().unwrap(),
+        )
+    }
+
+    pub fn communications(
+        &self,
+        filter: &CommunicationFilter,
+        page: usize,
+        length: usize,
+    ) -> CommunicationPage {
+        self.ecs
+            .world()
+            .get_resource::<Communications>()
+            .unwrap()
+            .page(filter, page, length)
+    }
+
+    pub fn take_player_rename(&mut self) -> Option<PlayerRenameRequest> {
+        self.ecs
+            .world_mut()
+            .get_resource_mut::<Configuration>()
+            .and_then(|mut configuration| configuration.player_rename.take())
+    }
+
+    // Brings the world in line with a rename once it has been saved, returning the renamed player
+    // if they are online.
+    pub fn finish_player_rename(
+        &mut self,
+        request: &PlayerRenameRequest,
+        renamed: bool,
+        message: String,
+    ) -> Option<Entity> {
+        let world = self.ecs.world_mut();
+
+        if let Some(mut messages) = world.get_mut::<Messages>(request.actor) {
+            messages.queue(message);
+        }
+
+        if !renamed {
+            return None;
+        }
+
+        forget_departed(world, request.old.as_str());
+
+        let mut allowlist = world.get_resource_mut::<Allowlist>().unwrap();
+        if allowlist.remove(request.old.as_str()) {
+            allowlist.insert(request.new.as_str());
+        }
+
+        world
+            .get_resource_mut::<News>()
+            .unwrap()
+            .rename_author(request.old.as_str(), request.new.as_str());
+        world
+            .get_resource_mut::<Polls>()
+            .unwrap()
+            .rename_author(request.old.as_str(), request.new.as_str());
+
+        let player = world
+            .get_resource::<Players>()
+            .unwrap()
+            .by_name(request.old.as_str())?;
+        let id = world.get::<Player>(player).unwrap().id();
+
+        let mut players = world.get_resource_mut::<Players>().unwrap();
+        players.remove(request.old.as_str());
+        players.insert(player, request.new.clone(), id);
+
+        world
+            .get_mut::<Named>(player)
+            .unwrap()
+            .set_name(request.new.clone());
+
+        Some(player)
+    }
+
+    // The communication an online player has received, as speaker and message pairs, oldest
+    // first. Players who are offline have none.
+    pub fn communication_log(&self, name: &str) -> Vec<(String, String)> {
+        let world = self.ecs.world();
+
+        world
+            .get_resource::<Players>()
+            .unwrap()
+            .by_name(name)
+            .and_then(|player| world.get::<CommunicationLog>(player))
+            .map(|log| {
+                log.entries()
+                    .map(|(speaker, message)| (speaker.to_string(), message.to_string()))
+                    .collect_vec()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn motd(&self) -> Option<String> {
+        self.ecs
+            .world()
+            .get_resource::<Configuration>()
+            .and_then(|configuration| configuration.motd.clone())
+    }
+
+    pub fn set_motd(&mut self, motd: Option<String>) {
+        if let Some(mut configuration) = self.ecs.world_mut().get_resource_mut::<Configuration>() {
+            configuration.motd = motd;
+        }
+    }
+
+    pub fn set_art(&mut self, art: Art) {
+        self.ecs.world_mut().insert_resource(art);
+    }
+
+    pub fn set_login_banner(&mut self, login_banner: Option<String>) {
+        if let Some(mut configuration) = self.ecs.world_mut().get_resource_mut::<Configuration>() {
+            configuration.login_banner = login_banner;
+        }
+    }
+
+    /// The banner shown to clients as they connect, fitted to their window: the art with the
+    /// banner's name, or else the banner drawn in block letters.
+    pub fn login_banner(&self, width: usize) -> Option<String> {
+        let world = self.ecs.world();
+        let login_banner = world
+            .get_resource::<Configuration>()?
+            .login_banner
+            .as_deref()?;
+
+        match world
+            .get_resource::<Art>()
+            .and_then(|art| art.get(login_banner))
+        {
+            Some(art) => Some(fit_art(art, width)),
+            None => Some(banner(login_banner, width)),
+        }
+    }
+
+    pub fn set_max_players(&mut self, max_players: Option<usize>) {
+        if let Some(mut configuration) = self.ecs.world_mut().get_resource_mut::<Configuration>() {
+            configuration.max_players = max_players;
+        }
+    }
+
+    pub fn set_allowlist(&mut self, enforced: bool, rejection: String) {
+        if let Some(mut configuration) = self.ecs.world_mut().get_resource_mut::<Configuration>() {
+            configuration.allowlist_rejection = if enforced { Some(rejection) } else { None };
+        }
+    }
+
+    // Returns the message to reject a player with if the allowlist is enforced and does not
+    // contain their name.
+    pub fn allowlist_rejection(&self, name: &str) -> Option<String> {
+        let world = self.ecs.world();
+
+        if world.get_resource::<Allowlist>().unwrap().contains(name) {
+            None
+        } else {
+            world
+                .get_resource::<Configuration>()
+                .and_then(|configuration| configuration.allowlist_rejection.clone())
+        }
+    }
+
+    pub fn set_maintenance_notice(&mut self, notice: String) {
+        if let Some(mut configuration) = self.ecs.world_mut().get_resource_mut::<Configuration>() {
+            configuration.maintenance_notice = notice;
+        }
+    }
+
+    // Returns the notice shown to connecting players while maintenance mode is on.
+    pub fn maintenance_notice(&self) -> Option<String> {
+        self.ecs
+            .world()
+            .get_resource::<Configuration>()
+            .filter(|configuration| configuration.maintenance)
+            .map(|configuration| configuration.maintenance_notice.clone())
+    }
+
+    /// Returns the mortals to disconnect once a maintenance countdown has run out, along with the
+    /// notice to show them.
+    pub fn take_maintenance_boot(&mut self) -> Option<(String, Vec<Entity>)> {
+        let world = self.ecs.world_mut();
+        let now = world.get_resource::<Clock>().unwrap().now();
+
+        let notice = {
+            let mut configuration = world.get_resource_mut::<Configuration>()?;
+            match configuration.maintenance_boot {
+                Some(boot) if boot <= now => {
+                    configuration.maintenance_boot = None;
+                    configuration.maintenance_notice.clone()
+                }
+                _ => return None,
+            }
+        };
+
+        let mortals = world
+            .query_filtered::<(Entity, &PlayerFlags), With<Player>>()
+            .iter(world)
+            .filter(|(_, flags)| !flags.contains(player::Flags::IMMORTAL))
+            .map(|(player, _)| player)
+            .collect_vec();
+
+        Some((notice, mortals))
+    }
+
+    pub fn set_degraded(&mut self, degraded: bool) {
+        if let Some(mut configuration) = self.ecs.world_mut().get_resource_mut::<Configuration>() {
+            configuration.degraded = degraded;
+        }
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        self.ecs
+            .world()
+            .get_resource::<Configuration>()
+            .map(|configuration| configuration.degraded)
+            .unwrap_or(false)
+    }
+
+    pub fn notify_immortals(&mut self, message: String) {
+        let world = self.ecs.world_mut();
+
+        let immortals = world
+            .query::<(Entity, &PlayerFlags)>()
+            .iter(world)
+            .filter(|(_, flags)| flags.contains(player::Flags::IMMORTAL))
+            .map(|(player, _)| player)
+            .collect_vec();
+
+        for immortal in immortals {
+            if let Some(mut messages) = world.get_mut::<Messages>(immortal) {
+                messages.queue(message.clone());
+            }
+        }
+    }
+
+    pub fn allowlist(&self) -> Vec<String> {
+        self.ecs
+            .world()
+            .get_resource::<Allowlist>()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect_vec()
+    }
+
+    /// Adds a name to the allowlist, returning false if it was already present.
+    pub fn allow(&mut self, name: &str) -> bool {
+        let world = self.ecs.world_mut();
+
+        if !world.get_resource_mut::<Allowlist>().unwrap().insert(name) {
+            return false;
+        }
+
+        world
+            .get_resource_mut::<Updates>()
+            .unwrap()
+            .persist(persist::allowlist::Add::new(name.to_string()));
+
+        true
+    }
+
+    /// Removes a name from the allowlist, returning false if it was not present.
+    pub fn disallow(&mut self, name: &str) -> bool {
+        let world = self.ecs.world_mut();
+
+        if !world.get_resource_mut::<Allowlist>().unwrap().remove(name) {
+            return false;
+        }
+
+        world
+            .get_resource_mut::<Updates>()
+            .unwrap()
+            .persist(persist::allowlist::Remove::new(name.to_string()));
+
+        true
+    }
+
+    pub fn set_snoop_policy(&mut self, duration: Duration, approval: bool) {
+        if let Some(mut snoops) = self.ecs.world_mut().get_resource_mut::<Snoops>() {
+            snoops.set_policy(duration, approval);
+        }
+    }
+
+    pub fn set_communication_retention(&mut self, retention: Duration) {
+        if let Some(mut communications) = self.ecs.world_mut().get_resource_mut::<Communications>()
+        {
+            communications.set_retention(retention);
+        }
+    }
+
+    pub fn set_listing_duration(&mut self, duration: Duration) {
+        if let Some(mut market) = self.ecs.world_mut().get_resource_mut::<Market>() {
+            market.set_listing_duration(duration);
+        }
+    }
+
+    pub fn set_mail_expiry(&mut self, expiry: Duration) {
+        if let Some(mut mailbox) = self.ecs.world_mut().get_resource_mut::<Mailbox>() {
+            mailbox.set_expiry(expiry);
+        }
+    }
+
+    pub fn set_seasons(&mut self, windows: BTreeMap<String, SeasonWindow>) {
+        if let Some(mut seasons) = self.ecs.world_mut().get_resource_mut::<Seasons>() {
+            seasons.set_windows(windows);
+        }
+    }
+
+    pub fn set_departed_policy(&mut self, duration: Duration, capacity: usize) {
+        let world = self.ecs.world_mut();
+
+        world
+            .get_resource_mut::<Departed>()
+            .unwrap()
+            .set_policy(duration, capacity);
+
+        // Shrinking the cache or turning it off forgets players who no longer fit.
+        self.expire_departed();
+    }
+
+    /// Forgets players who departed too long ago to be restored.
+    pub fn expire_departed(&mut self) {
+        let world = self.ecs.world_mut();
+
+        let now = world.get_resource::<Clock>().unwrap().now();
+        let expired = world
+            .get_resource_mut::<Departed>()
+            .unwrap()
+            .take_expired(now);
+        for player in expired {
+            despawn_departed(world, player);
+        }
+    }
+
+    /// Returns a recently departed player to the world as they were when they left, if they are
+    /// still remembered.
+    #[tracing::instrument(name = "restoring player", skip(self))]
+    pub fn restore_player(&mut self, name: &str) -> Option<Entity> {
+        let world = self.ecs.world_mut();
+
+        let (player, id) = world.get_resource_mut::<Departed>().unwrap().take(name)?;
+        let location = world.get::<Location>(player)?.entity();
+
+        // The room they left from may have been removed while they were away.
+        let room = if world.get::<Room>(location).is_some() {
+            location
+        } else {
+            let void = world
+                .get_resource::<Rooms>()
+                .unwrap()
+                .by_id(*VOID_ROOM_ID)
+                .unwrap();
+            world.get_mut::<Location>(player).unwrap().set_entity(void);
+            void
+        };
+
+        world
+            .entity_mut(player)
+            .insert_bundle((Player::from(id), ClientCaps::default()));
+        if let Some(mut playtime) = world.get_mut::<player::Playtime>(player) {
+            playtime.resume();
+        }
+        // Their output history is kept, but not what was said to them while they were away.
+        if let Some(mut messages) = world.get_mut::<Messages>(player) {
+            messages.discard_queue();
+        }
+
+        world.get_mut::<Room>(room).unwrap().insert_player(player);
+        world
+            .get_resource_mut::<Players>()
+            .unwrap()
+            .insert(player, name.to_string(), id);
+
+        Some(player)
+    }
+
+    /// Forgets a departed player so they are loaded afresh when they next log in.
+    pub fn forget_departed(&mut self, name: &str) {
+        forget_departed(self.ecs.world_mut(), name);
+    }
+
+    /// Forgets the mail of a deleted player, along with the objects attached to it. Their rows are
+    /// removed from the database with the player.
+    pub fn forget_communications(&mut self, name: &str) {
+        self.ecs
+            .world_mut()
+            .get_resource_mut::<Communications>()
+            .unwrap()
+            .forget(name);
+    }
+
+    pub fn forget_mail(&mut self, name: &str) {
+        let world = self.ecs.world_mut();
+
+        let mail = world.get_resource_mut::<Mailbox>().unwrap().forget(name);
+        for object in mail.into_iter().filter_map(|mail| mail.object) {
+            world.despawn(object);
+            world
+                .get_resource_mut::<Objects>()
+                .unwrap()
+                .remove_entity(object);
+        }
+    }
+
     #[tracing::instrument(name = "despawning player", skip_all, fields(player = player.to_bits()))]
     pub fn despawn_player(&mut self, player: Entity) -> anyhow::Result<()> {
         let world = self.ecs.world_mut();
 
+        // The player's progress is saved in full before they and their objects leave the world.
+        queue_player_state(world, player);
+
         let (name, room) = world
             .query::<(&Named, &Location)>()
             .get(&*world, player)
@@ -114,54 +691,216 @@ impl GameWorld {
             .copied()
             .collect_vec();
 
+        tracing::info!(target: GAME_LOG_TARGET, player = name.as_str(), "logout");
+
         let message = format!("{} leaves.", name);
 
         for player in players {
             if let Some(mut messages) = world.get_mut::<Messages>(player) {
-                messages.queue(message.clone());
+                messages.queue_broadcast(message.clone());
+            }
+        }
+
+        let id = world.get::<Player>(player).unwrap().id();
+        let seconds = world
+            .get_mut::<player::Playtime>(player)
+            .map(|mut playtime| playtime.take_seconds())
+            .unwrap_or_default();
+        {
+            let mut updates = world.get_resource_mut::<Updates>().unwrap();
+            if seconds > 0 {
+                updates.persist(persist::player::Playtime::new(id, seconds));
             }
+            updates.persist(persist::player::EndSession::new(id));
+
+            // The player's rows may change while they are away, such as by being erased.
+            updates.evict("players", id);
+            updates.evict("player_reputation", id);
+        }
+
+        // Possessed objects are released when their controller leaves.
+        let possessed = world
+            .get_resource_mut::<Possessions>()
+            .unwrap()
+            .remove(player);
+        if let Some(mut target) = possessed.and_then(|target| world.get_entity_mut(target)) {
+            target.remove::<Messages>();
+        }
+
+        // Snoops end when either the moderator or the player being snooped leaves.
+        let moderators = world
+            .get_resource_mut::<Snoops>()
+            .unwrap()
+            .remove_player(player);
+        for moderator in moderators {
+            end_snoop(world, moderator, player, "has ended");
+        }
+
+        // Staged changes are discarded when their builder leaves.
+        let stage = world.get_resource_mut::<Staging>().unwrap().remove(player);
+        if let Some(stage) = stage {
+            for shadow in stage.shadows() {
+                world.despawn(shadow);
+            }
+        }
+
+        world
+            .get_resource_mut::<Players>()
+            .unwrap()
+            .remove(name.as_str());
+        world.get_mut::<Room>(room).unwrap().remove_player(player);
+
+        // Recently departed players are kept out of sight, ready to be restored if they return.
+        if world.get_resource::<Departed>().unwrap().is_enabled() {
+            world.entity_mut(player).remove::<Player>();
+
+            let now = world.get_resource::<Clock>().unwrap().now();
+            let forgotten = world
+                .get_resource_mut::<Departed>()
+                .unwrap()
+                .insert(name, player, id, now);
+            for forgotten in forgotten {
+                despawn_departed(world, forgotten);
+            }
+        } else {
+            despawn_departed(world, player);
         }
 
-        if let Some(objects) = world
-            .get::<Contents>(player)
-            .map(|contents| contents.get_objects())
-        {
-            for object in objects {
-                world.despawn(object);
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "player input",
+        skip_all,
+        fields(player = tracing::field::Empty, action = tracing::field::Empty)
+    )]
+    pub fn player_input(&mut self, player: Entity, input: &str) -> Result<(), String> {
+        if let Some(name) = self.ecs.world().get::<Named>(player) {
+            tracing::Span::current().record("player", &name.as_str());
+        }
+
+        relay_to_snoops(
+            self.ecs.world_mut(),
+            player,
+            vec![format!("> {}", input)],
+            false,
+        );
+
+        // Any input skips the rest of a cutscene.
+        self.ecs
+            .world_mut()
+            .get_resource_mut::<Cutscenes>()
+            .unwrap()
+            .cancel(player);
+
+        let immortal = self
+            .ecs
+            .world()
+            .get::<PlayerFlags>(player)
+            .unwrap()
+            .contains(player::Flags::IMMORTAL);
+
+        // Immortals possessing an object act through it, except to release it.
+        let world = self.ecs.world_mut();
+        let possessed = match world.get_resource::<Possessions>().unwrap().target(player) {
+            Some(target) if world.get_entity(target).is_none() => {
                 world
-                    .get_resource_mut::<Objects>()
+                    .get_resource_mut::<Possessions>()
                     .unwrap()
-                    .remove_entity(object);
+                    .remove(player);
+                None
+            }
+            possessed => possessed,
+        };
+        let releasing = Tokenizer::new(input)
+            .next()
+            .map_or(false, |command| command == "release");
+        let (actor, restricted) = match possessed {
+            Some(target) if !releasing => (target, true),
+            _ => (player, !immortal),
+        };
+
+        // Custom exits are taken by name, ahead of any command they share a name with.
+        let parsed = match custom_exit(self.ecs.world(), actor, input) {
+            Some(direction) => Ok(Action::from(Move { actor, direction })),
+            None => self.commands.parse(actor, input, restricted),
+        };
+
+        let result = match parsed {
+            Ok(action) => {
+                tracing::Span::current().record("action", &<&str>::from(&action));
+                if actor != player && !possessed_may_perform(&action) {
+                    Err(
+                        "A possessed object may only speak, emote, move, and look. Release it \
+                         to do anything else."
+                            .to_string(),
+                    )
+                } else {
+                    if actor == player {
+                        if let Some(trigger) = HintTrigger::from_action(&action) {
+                            self.ecs
+                                .world_mut()
+                                .get_resource_mut::<HintQueue>()
+                                .unwrap()
+                                .push(player, trigger);
+                        }
+                    }
+                    self.player_action(action);
+                    Ok(())
+                }
             }
+            Err(message) => Err(message),
+        };
+
+        // Errors are sent straight to the player, so snooping moderators are shown them here.
+        if let Err(message) = &result {
+            relay_to_snoops(self.ecs.world_mut(), player, vec![message.clone()], true);
         }
-        world.despawn(player);
-        world
-            .get_resource_mut::<Players>()
-            .unwrap()
-            .remove(name.as_str());
-        world.get_mut::<Room>(room).unwrap().remove_player(player);
 
-        Ok(())
+        result
     }
 
-    #[tracing::instrument(name = "player input", skip_all)]
-    pub fn player_input(&mut self, player: Entity, input: &str) -> Result<(), String> {
-        let immortal = self
+    /// Parses and queues the commands forced on players by immortals and scripts.
+    #[tracing::instrument(name = "run forced commands", skip_all)]
+    pub fn run_forced_commands(&mut self) {
+        let forced = self
             .ecs
-            .world()
-            .get::<PlayerFlags>(player)
+            .world_mut()
+            .get_resource_mut::<ForcedCommands>()
             .unwrap()
-            .contains(player::Flags::IMMORTAL);
+            .take();
 
-        match self.commands.parse(player, input, !immortal) {
-            Ok(action) => self.player_action(action),
-            Err(message) => return Err(message),
-        }
+        for ForcedCommand {
+            player,
+            command,
+            privileged,
+        } in forced
+        {
+            // Players may have left since the command was forced.
+            let immortal = match self.ecs.world().get::<PlayerFlags>(player) {
+                Some(flags) => flags.contains(player::Flags::IMMORTAL),
+                None => continue,
+            };
 
-        Ok(())
+            match self
+                .commands
+                .parse(player, command.as_str(), !(privileged && immortal))
+            {
+                Ok(action) => self.player_action(action),
+                Err(message) => {
+                    if let Some(mut messages) = self.ecs.world_mut().get_mut::<Messages>(player) {
+                        messages.queue(message);
+                    }
+                }
+            }
+        }
     }
 
-    #[tracing::instrument(name = "player action", skip_all)]
+    #[tracing::instrument(
+        name = "player action",
+        skip_all,
+        fields(action = <&str>::from(&action))
+    )]
     pub fn player_action(&mut self, action: Action) {
         self.ecs
             .world_mut()
@@ -171,12 +910,66 @@ impl GameWorld {
     }
 
     pub fn player_online(&self, name: &str) -> bool {
+        self.online_player(name).is_some()
+    }
+
+    pub fn online_player(&self, name: &str) -> Option<Entity> {
         self.ecs
             .world()
             .get_resource::<Players>()
             .unwrap()
             .by_name(name)
-            .is_some()
+    }
+
+    pub fn set_needs(&mut self, needs: bool) {
+        if let Some(mut configuration) = self.ecs.world_mut().get_resource_mut::<Configuration>() {
+            configuration.needs = needs;
+        }
+    }
+
+    pub fn set_takeover(&mut self, takeover: bool) {
+        if let Some(mut configuration) = self.ecs.world_mut().get_resource_mut::<Configuration>() {
+            configuration.takeover = takeover;
+        }
+    }
+
+    pub fn set_deletion_grace(&mut self, grace: Duration) {
+        if let Some(mut configuration) = self.ecs.world_mut().get_resource_mut::<Configuration>() {
+            configuration.deletion_grace = grace;
+        }
+    }
+
+    pub fn clock(&self) -> &Clock {
+        self.ecs.world().get_resource::<Clock>().unwrap()
+    }
+
+    // How long a deleted character is kept, during which logging in cancels the deletion.
+    pub fn deletion_grace(&self) -> Duration {
+        self.ecs
+            .world()
+            .get_resource::<Configuration>()
+            .map_or(Duration::default(), |configuration| {
+                configuration.deletion_grace
+            })
+    }
+
+    // Whether logging in as a player who is already online takes over their session.
+    pub fn takeover(&self) -> bool {
+        self.ecs
+            .world()
+            .get_resource::<Configuration>()
+            .map_or(false, |configuration| configuration.takeover)
+    }
+
+    // Whether the world has reached its player limit. Immortals may still log in.
+    pub fn is_full(&self) -> bool {
+        let world = self.ecs.world();
+        let online = world.get_resource::<Players>().unwrap().online();
+
+        world
+            .get_resource::<Configuration>()
+            .and_then(|configuration| configuration.max_players)
+            .map_or(false, |max_players| online >= max_players)
     }
 
     pub fn spawn_room(&self) -> RoomId {
@@ -187,9 +980,71 @@ impl GameWorld {
             .spawn_room
     }
 
+    // New characters begin the tutorial, if there is one.
+    pub fn new_player_room(&self) -> RoomId {
+        self.ecs
+            .world()
+            .get_resource::<Tutorial>()
+            .unwrap()
+            .start()
+            .unwrap_or_else(|| self.spawn_room())
+    }
+
     #[tracing::instrument(name = "dispatching messages", skip_all)]
-    pub fn messages(&mut self) -> Vec<(Entity, VecDeque<String>)> {
+    /// Takes the output waiting for each player, with whether it is followed by a prompt and
+    /// whether it is only broadcasts.
+    pub fn messages(&mut self) -> Vec<(Entity, VecDeque<String>, bool, bool)> {
         let world = self.ecs.world_mut();
+        let now = world.get_resource::<Clock>().unwrap().now();
+
+        // Snoops which have run out of time end before any more output is shown.
+        let expired = world
+            .get_resource_mut::<Snoops>()
+            .unwrap()
+            .take_expired(now);
+        for (moderator, target) in expired {
+            end_snoop(world, moderator, target, "has expired");
+        }
+
+        // Output seen by possessed objects is relayed to the immortals possessing them.
+        let possessions = world
+            .get_resource::<Possessions>()
+            .unwrap()
+            .iter()
+            .collect_vec();
+        for (controller, target) in possessions {
+            let relayed = match world.get_mut::<Messages>(target) {
+                Some(mut messages) if !messages.is_empty() => messages.take_queue(),
+                _ => continue,
+            };
+            let name = world
+                .get::<Named>(target)
+                .map(|named| named.to_string())
+                .unwrap_or_default();
+            if let Some(mut messages) = world.get_mut::<Messages>(controller) {
+                for message in relayed {
+                    messages.queue(format!("|Gray69|[{}]|-| {}", name, message));
+                }
+            }
+        }
+
+        // Moderators see a copy of the output of the players they are snooping.
+        let snooped = world
+            .get_resource::<Snoops>()
+            .unwrap()
+            .iter()
+            .map(|(_, target)| target)
+            .unique()
+            .collect_vec();
+        for target in snooped {
+            let output = match world.get::<Messages>(target) {
+                Some(messages) if !messages.is_empty() => {
+                    messages.queued().map(ToString::to_string).collect_vec()
+                }
+                _ => continue,
+            };
+            relay_to_snoops(world, target, output, true);
+        }
 
         let players_with_messages = world
             .query_filtered::<Entity, (With<Player>, With<Messages>)>()
@@ -205,12 +1060,83 @@ impl GameWorld {
                 continue;
             }
 
-            outgoing.push((player, messages.take_queue()));
+            let prompt = messages.take_prompt();
+            let broadcast = messages.take_broadcast();
+            outgoing.push((player, messages.take_queue(), prompt, broadcast));
         }
 
         outgoing
     }
 
+    pub fn set_client_caps(&mut self, player: Entity, caps: player::ClientCaps) {
+        if let Some(mut client_caps) = self.ecs.world_mut().get_mut::<player::ClientCaps>(player) {
+            *client_caps = caps;
+        }
+    }
+
+    /// Queues a full save of every online player's state. Values which were already written are
+    /// skipped.
+    pub fn save_players(&mut self) {
+        let world = self.ecs.world_mut();
+
+        let players = world
+            .query_filtered::<Entity, With<Player>>()
+            .iter(world)
+            .collect_vec();
+
+        for player in players {
+            queue_player_state(world, player);
+        }
+    }
+
+    /// Queues a full save of a single player's state.
+    pub fn save_player(&mut self, player: Entity) {
+        queue_player_state(self.ecs.world_mut(), player);
+    }
+
+    /// Makes every online mortal a little hungrier and thirstier, telling them when a need becomes
+    /// more pressing. Does nothing while hunger and thirst are turned off.
+    pub fn grow_needs(&mut self) {
+        let world = self.ecs.world_mut();
+
+        if !world
+            .get_resource::<Configuration>()
+            .map_or(false, |configuration| configuration.needs)
+        {
+            return;
+        }
+
+        for (flags, mut needs, mut messages) in world
+            .query_filtered::<(&PlayerFlags, &mut Needs, &mut Messages), With<Player>>()
+            .iter_mut(world)
+        {
+            if flags.contains(player::Flags::IMMORTAL) {
+                continue;
+            }
+
+            for message in needs.grow() {
+                messages.queue(message.to_string());
+            }
+        }
+    }
+
+    /// Queues persistence of the time each online player has played since it was last recorded.
+    pub fn record_playtime(&mut self) {
+        let world = self.ecs.world_mut();
+
+        let playtimes = world
+            .query::<(&Player, &mut player::Playtime)>()
+            .iter_mut(world)
+            .map(|(player, mut playtime)| (player.id(), playtime.take_seconds()))
+            .filter(|(_, seconds)| *seconds > 0)
+            .collect_vec();
+
+        let mut updates = world.get_resource_mut::<Updates>().unwrap();
+        for (id, seconds) in playtimes {
+            updates.persist(persist::player::Playtime::new(id, seconds));
+        }
+    }
+
     pub fn updates(&mut self) -> Vec<DynPersist> {
         self.ecs
             .world_mut()
@@ -219,6 +1145,14 @@ impl GameWorld {
             .take_updates()
     }
 
+    pub fn record_writes(&mut self, writes: Vec<Write>) {
+        self.ecs
+            .world_mut()
+            .get_resource_mut::<Updates>()
+            .unwrap()
+            .record_writes(writes)
+    }
+
     pub fn prototype_reloads(&mut self) -> Vec<PrototypeId> {
         self.ecs
             .world_mut()
@@ -227,6 +1161,86 @@ impl GameWorld {
             .take_reloads()
     }
 
+    pub fn vault_loads(&mut self) -> Vec<(Entity, Action)> {
+        self.ecs
+            .world_mut()
+            .get_resource_mut::<Updates>()
+            .unwrap()
+            .take_vault_loads()
+    }
+
+    pub fn room_loads(&mut self) -> Vec<(RoomLoad, Action)> {
+        self.ecs
+            .world_mut()
+            .get_resource_mut::<Updates>()
+            .unwrap()
+            .take_room_loads()
+    }
+
+    pub fn plan_regions(&mut self) -> Option<RegionPlan> {
+        region::plan(self.ecs.world_mut())
+    }
+
+    pub fn evict_region(&mut self, region: &str) -> bool {
+        region::evict(self.ecs.world_mut(), region)
+    }
+
+    pub fn set_region_idle(&mut self, idle: Duration) {
+        self.ecs
+            .world_mut()
+            .get_resource_mut::<LoadedRegions>()
+            .unwrap()
+            .set_idle(idle);
+    }
+
+    pub fn set_http_allowlist(&mut self, allowlist: Vec<String>) {
+        self.ecs
+            .world_mut()
+            .get_resource_mut::<HttpRequests>()
+            .unwrap()
+            .set_allowlist(allowlist);
+    }
+
+    pub fn set_slow_script_threshold(&mut self, threshold: Duration) {
+        self.ecs
+            .world_mut()
+            .get_resource_mut::<ScriptStats>()
+            .unwrap()
+            .set_slow_threshold(threshold);
+    }
+
+    pub fn slow_script_threshold(&self) -> Duration {
+        self.ecs
+            .world()
+            .get_resource::<ScriptStats>()
+            .unwrap()
+            .slow_threshold()
+    }
+
+    pub fn script_stats(&self) -> Vec<(ScriptName, ScriptStat)> {
+        self.ecs
+            .world()
+            .get_resource::<ScriptStats>()
+            .unwrap()
+            .by_total()
+    }
+
+    pub fn http_requests(&mut self) -> Vec<HttpRequest> {
+        self.ecs
+            .world_mut()
+            .get_resource_mut::<HttpRequests>()
+            .unwrap()
+            .take_pending()
+    }
+
+    pub fn http_response(&mut self, response: HttpResponse) {
+        self.ecs
+            .world_mut()
+            .get_resource_mut::<HttpRequests>()
+            .unwrap()
+            .respond(response);
+    }
+
     pub fn world_mut(&mut self) -> &mut World {
         self.ecs.world_mut()
     }
@@ -236,12 +1250,25 @@ impl GameWorld {
         name: String,
         trigger: String,
         code: String,
-    ) -> Result<Option<ParseError>, ScriptError> {
+        tags: Vec<String>,
+        folder: Option<String>,
+        enabled: bool,
+        author: PlayerId,
+    ) -> Result<(Option<ParseError>, i64), ScriptError> {
         let name = ScriptName::try_from(name).map_err(|_| ScriptError::BadScriptName)?;
         let trigger =
             TriggerEvent::from_str(trigger.as_str()).map_err(|_| ScriptError::BadTrigger)?;
+        let tags = parse_tags(tags)?;
+        let folder = match folder {
+            Some(folder) => parse_folder(folder.as_str()).map_err(|_| ScriptError::BadFolder)?,
+            None => None,
+        };
 
-        let script = Script::new(name, trigger, code);
+        let script = Script::new(name, trigger, code)
+            .with_tags(tags)
+            .with_folder(folder)
+            .with_enabled(enabled)
+            .with_author(Some(author));
 
         scripting::actions::create_script(&mut *self.ecs.world_mut(), script)
     }
@@ -259,28 +1286,219 @@ impl GameWorld {
         scripting::actions::read_all_scripts(&mut *self.ecs.world_mut())
     }
 
+    pub fn snapshot(&mut self) -> WorldSnapshot {
+        WorldSnapshot::capture(&mut *self.ecs.world_mut())
+    }
+
     pub fn update_script(
         &mut self,
         name: String,
         trigger: String,
         code: String,
-    ) -> Result<Option<ParseError>, ScriptError> {
+        tags: Option<Vec<String>>,
+        folder: Option<String>,
+        enabled: Option<bool>,
+        revision: i64,
+        builder: Option<PlayerId>,
+    ) -> Result<(Option<ParseError>, i64), ScriptError> {
         let name = ScriptName::try_from(name).map_err(|_| ScriptError::BadScriptName)?;
         let trigger =
             TriggerEvent::from_str(trigger.as_str()).map_err(|_| ScriptError::BadTrigger)?;
 
-        let script = Script::new(name, trigger, code);
+        if let Some(builder) = builder {
+            scripting::actions::check_owned_regions(&mut *self.ecs.world_mut(), &name, builder)?;
+        }
+
+        // Tags, folder, and whether the script is enabled are left unchanged when they are not
+        // provided.
+        let (current, _) = scripting::actions::read_script(&*self.ecs.world(), name.clone())?;
+        let tags = match tags {
+            Some(tags) => parse_tags(tags)?,
+            None => current.tags().to_vec(),
+        };
+        let folder = match folder {
+            Some(folder) => parse_folder(folder.as_str()).map_err(|_| ScriptError::BadFolder)?,
+            None => current.folder().map(ToString::to_string),
+        };
+        let enabled = enabled.unwrap_or_else(|| current.enabled());
+
+        let script = Script::new(name, trigger, code)
+            .with_tags(tags)
+            .with_folder(folder)
+            .with_enabled(enabled)
+            .with_author(current.author());
 
-        scripting::actions::update_script(&mut *self.ecs.world_mut(), script)
+        scripting::actions::update_script(&mut *self.ecs.world_mut(), script, revision)
     }
 
-    pub fn delete_script(&mut self, name: String) -> Result<(), ScriptError> {
+    pub fn delete_script(
+        &mut self,
+        name: String,
+        builder: Option<PlayerId>,
+    ) -> Result<(), ScriptError> {
         let name = ScriptName::try_from(name).map_err(|_| ScriptError::BadScriptName)?;
 
+        if let Some(builder) = builder {
+            scripting::actions::check_owned_regions(&mut *self.ecs.world_mut(), &name, builder)?;
+        }
+
         scripting::actions::delete_script(&mut *self.ecs.world_mut(), name)
     }
 }
 
+fn parse_tags(tags: Vec<String>) -> Result<Vec<String>, ScriptError> {
+    let mut parsed = Vec::new();
+    for tag in tags {
+        let tag = parse_tag(tag.as_str()).map_err(|_| ScriptError::BadTag)?;
+        if !parsed.contains(&tag) {
+            parsed.push(tag);
+        }
+    }
+    Ok(parsed)
+}
+
+/// Tells players that something they did failed unexpectedly, such as a system or script
+/// panicking, without revealing the details.
+pub(crate) fn report_failure(world: &mut World, players: impl IntoIterator<Item = Entity>) {
+    for player in players {
+        if let Some(mut messages) = world.get_mut::<Messages>(player) {
+            messages.queue("|Red1|Something went wrong.|-|".to_string());
+        }
+    }
+}
+
+// Queues writes of everything a player saves: where they are, how they are described and what
+// they carry. This catches any progress an incremental update missed.
+fn queue_player_state(world: &mut World, player: Entity) {
+    let (id, location, description, flags, pronouns, needs, objects) = match world
+        .query::<(
+            &Player,
+            &Location,
+            &Description,
+            &PlayerFlags,
+            &Pronouns,
+            &Needs,
+            &Contents,
+        )>()
+        .get(&*world, player)
+    {
+        Ok((player, location, description, flags, pronouns, needs, contents)) => (
+            player.id(),
+            location.entity(),
+            description.to_string(),
+            flags.get_flags(),
+            *pronouns,
+            *needs,
+            contents.get_objects(),
+        ),
+        Err(_) => return,
+    };
+
+    let room = world.get::<Room>(location).map(Room::id);
+    let objects = objects
+        .into_iter()
+        .filter_map(|object| world.get::<Object>(object).map(Object::id))
+        .collect_vec();
+
+    let mut updates = world.get_resource_mut::<Updates>().unwrap();
+    if let Some(room) = room {
+        updates.persist(persist::player::Room::new(id, room));
+    }
+    updates.persist(persist::player::Description::new(id, description));
+    updates.persist(persist::player::Flags::new(id, flags));
+    updates.persist(persist::player::Pronouns::new(id, pronouns));
+    updates.persist(persist::player::Needs::new(id, needs));
+    updates.persist(persist::player::Objects::new(id, objects));
+}
+
+// The custom exit from an entity's room which the input names, if any.
+fn custom_exit(world: &World, entity: Entity, input: &str) -> Option<Direction> {
+    let direction = match Direction::named(input)? {
+        direction @ Direction::Custom(_) => direction,
+        _ => return None,
+    };
+
+    let room = world.get::<Location>(entity)?.entity();
+    world.get::<Room>(room)?.exit(&direction).map(|_| direction)
+}
+
+// Forgets a departed player, such as one whose name or saved state has changed underneath them.
+fn forget_departed(world: &mut World, name: &str) {
+    let departed = world.get_resource_mut::<Departed>().unwrap().take(name);
+    if let Some((player, _)) = departed {
+        despawn_departed(world, player);
+    }
+}
+
+// Removes a player who has left the world for good, along with everything they carry.
+fn despawn_departed(world: &mut World, player: Entity) {
+    let objects = world
+        .get::<Contents>(player)
+        .map(|contents| contents.get_objects())
+        .into_iter()
+        .chain(world.get::<Vault>(player).map(|vault| vault.get_objects()))
+        .flatten()
+        .collect_vec();
+    for object in objects {
+        world.despawn(object);
+        world
+            .get_resource_mut::<Objects>()
+            .unwrap()
+            .remove_entity(object);
+    }
+
+    world.despawn(player);
+}
+
+// Shows moderators snooping a player lines of the player's input or output. Input is shown without
+// a prompt, which follows the output it produces.
+fn relay_to_snoops(world: &mut World, player: Entity, lines: Vec<String>, prompt: bool) {
+    let moderators = world.get_resource::<Snoops>().unwrap().moderators(player);
+    if moderators.is_empty() {
+        return;
+    }
+
+    let name = world
+        .get::<Named>(player)
+        .map(|named| named.to_string())
+        .unwrap_or_default();
+
+    for moderator in moderators {
+        if let Some(mut messages) = world.get_mut::<Messages>(moderator) {
+            for line in lines.iter() {
+                let line = format!("|Gray46|(snoop {})|-| {}", name, line);
+                if prompt {
+                    messages.queue(line);
+                } else {
+                    messages.queue_without_prompt(line);
+                }
+            }
+        }
+    }
+}
+
+// Records the end of a snoop and tells the moderator why it ended.
+fn end_snoop(world: &mut World, moderator: Entity, target: Entity, reason: &str) {
+    let name = |entity| {
+        world
+            .get::<Named>(entity)
+            .map(|named| named.to_string())
+            .unwrap_or_default()
+    };
+    let (moderator_name, target_name) = (name(moderator), name(target));
+
+    tracing::info!(
+        target: GAME_LOG_TARGET,
+        player = moderator_name.as_str(),
+        target = target_name.as_str(),
+        "snoop ended"
+    );
+
+    if let Some(mut messages) = world.get_mut::<Messages>(moderator) {
+        messages.queue(format!("Your snoop of {} {}.", target_name, reason));
+    }
+}
+
 fn add_void_room(world: &mut World) {
     if world
         .get_resource::<Rooms>()
@@ -312,3 +1530,120 @@ fn add_void_room(world: &mut World) {
         tracing::warn!("void room was created.");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::{EventReader, Events};
+    use bevy_ecs::prelude::*;
+
+    use crate::{
+        ecs::{Ecs, GuardSystem, Phase, Step},
+        world::{
+            action::{
+                commands::Commands,
+                communicate::{Emote, Say},
+                Action, ActionSystem,
+            },
+            types::player::Messages,
+            GameWorld,
+        },
+    };
+
+    fn explode(mut actions: EventReader<Action>) {
+        for action in actions.iter() {
+            if matches!(action, Action::Say(Say { message, .. }) if message == "explode") {
+                panic!("exploded");
+            }
+        }
+    }
+
+    fn reply(mut actions: EventReader<Action>, mut messages: Query<&mut Messages>) {
+        for action in actions.iter() {
+            if let Action::Emote(Emote { actor, .. }) = action {
+                if let Ok(mut messages) = messages.get_mut(*actor) {
+                    messages.queue("done".to_string());
+                }
+            }
+        }
+    }
+
+    fn send(game_world: &mut GameWorld, actions: Vec<Action>) {
+        let world = game_world.ecs.world_mut();
+        let mut events = world.get_resource_mut::<Events<Action>>().unwrap();
+        events.update();
+        for action in actions {
+            events.send(action);
+        }
+    }
+
+    fn say(actor: Entity, message: &str) -> Action {
+        Action::from(Say {
+            actor,
+            message: message.to_string(),
+        })
+    }
+
+    fn emote(actor: Entity) -> Action {
+        Action::from(Emote {
+            actor,
+            emote: "waves.".to_string(),
+        })
+    }
+
+    fn queued(game_world: &GameWorld, player: Entity) -> Vec<String> {
+        game_world
+            .ecs
+            .world()
+            .get::<Messages>(player)
+            .unwrap()
+            .queued()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_system_panic() {
+        let mut ecs = Ecs::new();
+        ecs.add_event::<Action>()
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                explode.guarded().label(ActionSystem::Say),
+            )
+            .add_system(
+                Step::Main,
+                Phase::Update,
+                reply.guarded().label(ActionSystem::Emote),
+            );
+
+        let actor = ecs.world_mut().spawn().insert(Messages::default()).id();
+        let bystander = ecs.world_mut().spawn().insert(Messages::default()).id();
+
+        let mut game_world = GameWorld {
+            ecs,
+            commands: Commands::default(),
+        };
+
+        // The actor is told their action failed, while the other system in the phase still
+        // carries out the bystander's action
+        send(
+            &mut game_world,
+            vec![say(actor, "explode"), emote(bystander)],
+        );
+        game_world.run_main();
+        assert_eq!(
+            queued(&game_world, actor),
+            vec!["|Red1|Something went wrong.|-|"]
+        );
+        assert_eq!(queued(&game_world, bystander), vec!["done"]);
+
+        // The next tick carries out new actions without repeating the old ones
+        send(&mut game_world, vec![emote(bystander)]);
+        game_world.run_main();
+        assert_eq!(
+            queued(&game_world, actor),
+            vec!["|Red1|Something went wrong.|-|"]
+        );
+        assert_eq!(queued(&game_world, bystander), vec!["done", "done"]);
+    }
+}
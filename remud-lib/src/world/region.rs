@@ -0,0 +1,206 @@
+use std::collections::HashSet;
+
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+
+use crate::{
+    clock::Clock,
+    world::{
+        types::{
+            object::Objects,
+            player::Messages,
+            room::{LoadedRegions, Regions, Room, RoomId, Rooms, Shadow, Staging},
+            Configuration, Contents, Location,
+        },
+        VOID_ROOM_ID,
+    },
+};
+
+/// What must change about the regions in the world, when regions are loaded lazily.
+#[derive(Debug, Default)]
+pub struct RegionPlan {
+    // rooms which must always be in the world but are not loaded
+    pub rooms: Vec<RoomId>,
+    pub load: Vec<String>,
+    pub evict: Vec<String>,
+}
+
+/// Works out which regions are needed, noting when each loaded region was last needed, and which
+/// regions should be loaded or have gone unneeded long enough to leave the world. Returns nothing
+/// unless regions are loaded lazily.
+///
+/// A region is needed while anyone is in it or a builder is staging it, along with the regions
+/// its exits lead to and those next to the rooms which are always loaded.
+pub fn plan(world: &mut World) -> Option<RegionPlan> {
+    if !world.get_resource::<LoadedRegions>().unwrap().is_lazy() {
+        return None;
+    }
+
+    let mut plan = RegionPlan::default();
+    let mut active = HashSet::new();
+
+    let pinned = [
+        *VOID_ROOM_ID,
+        world.get_resource::<Configuration>().unwrap().spawn_room,
+    ];
+    for room in pinned.iter() {
+        let rooms = world.get_resource::<Rooms>().unwrap();
+        if let Some(room) = rooms.by_id(*room) {
+            if let Some(regions) = world.get::<Regions>(room) {
+                active.extend(regions.iter().cloned());
+            }
+        } else if rooms.is_unloaded(*room) {
+            plan.rooms.push(*room);
+        }
+    }
+
+    // Players, including those who have departed and the objects immortals possess, keep the
+    // regions they are in loaded.
+    let occupied = world
+        .query_filtered::<&Location, With<Messages>>()
+        .iter(world)
+        .map(|location| location.entity())
+        .collect_vec();
+    for room in occupied {
+        if let Some(regions) = world.get::<Regions>(room) {
+            active.extend(regions.iter().cloned());
+        }
+    }
+
+    active.extend(
+        world
+            .get_resource::<Staging>()
+            .unwrap()
+            .regions()
+            .map(ToString::to_string),
+    );
+
+    let mut needed = active.clone();
+    {
+        let loaded = world.get_resource::<LoadedRegions>().unwrap();
+        needed.extend(loaded.anchored().iter().cloned());
+        for region in active.iter() {
+            needed.extend(loaded.neighbors(region).cloned());
+        }
+    }
+
+    // Exits made since a region was loaded are not among its neighbors, so the exits out of the
+    // rooms in the needed regions are followed as well.
+    let mut destinations = world
+        .query::<(&Room, &Regions)>()
+        .iter(world)
+        .filter(|(_, regions)| {
+            regions.iter().next().is_none() || regions.iter().any(|region| active.contains(region))
+        })
+        .flat_map(|(room, _)| room.exits().values().map(|exit| exit.destination))
+        .collect_vec();
+    destinations.extend(
+        world
+            .query::<&Shadow>()
+            .iter(world)
+            .flat_map(|shadow| shadow.exits().values().map(|exit| exit.destination)),
+    );
+    for destination in destinations {
+        if let Some(regions) = world.get::<Regions>(destination) {
+            needed.extend(regions.iter().cloned());
+        }
+    }
+
+    let now = world.get_resource::<Clock>().unwrap().now();
+    let mut loaded = world.get_resource_mut::<LoadedRegions>().unwrap();
+
+    for region in needed.iter() {
+        if loaded.is_loaded(region.as_str()) {
+            loaded.mark_needed(region.as_str(), now);
+        } else {
+            plan.load.push(region.clone());
+        }
+    }
+
+    plan.evict = loaded
+        .idle(now)
+        .into_iter()
+        .filter(|region| !needed.contains(region))
+        .collect();
+
+    Some(plan)
+}
+
+/// Takes the rooms of a region out of the world along with the objects in them, unless someone is
+/// in one of them or it is being staged. Rooms also in another loaded region stay. Returns whether
+/// the region was evicted.
+///
+/// Nothing is written, so everything about the rooms must already be saved.
+pub fn evict(world: &mut World, region: &str) -> bool {
+    let loaded = world
+        .get_resource::<LoadedRegions>()
+        .unwrap()
+        .iter()
+        .cloned()
+        .collect::<HashSet<_>>();
+    let evicted = world
+        .query::<(Entity, &Room, &Regions)>()
+        .iter(world)
+        .filter(|(_, _, regions)| {
+            regions.iter().any(|name| name == region)
+                && regions
+                    .iter()
+                    .all(|name| name == region || !loaded.contains(name))
+        })
+        .map(|(entity, room, _)| (entity, room.id()))
+        .collect_vec();
+
+    let staging = world.get_resource::<Staging>().unwrap();
+    let busy = evicted.iter().any(|(entity, _)| {
+        staging.is_staged(*entity)
+            || world
+                .get::<Room>(*entity)
+                .map_or(false, |room| !room.players().is_empty())
+    });
+    if busy {
+        return false;
+    }
+
+    let entities = evicted
+        .iter()
+        .map(|(entity, _)| *entity)
+        .collect::<HashSet<_>>();
+
+    // Exits into the region are linked again when it is next loaded.
+    let mut rooms = world.query::<&mut Room>();
+    for mut room in rooms.iter_mut(world) {
+        let directions = room
+            .exits()
+            .iter()
+            .filter(|(_, exit)| entities.contains(&exit.destination))
+            .map(|(direction, _)| direction.clone())
+            .collect_vec();
+        for direction in directions {
+            room.remove_exit(&direction);
+        }
+    }
+
+    for (entity, id) in evicted {
+        let objects = world
+            .get::<Contents>(entity)
+            .map(|contents| contents.get_objects())
+            .unwrap_or_default();
+        for object in objects {
+            world.despawn(object);
+            world
+                .get_resource_mut::<Objects>()
+                .unwrap()
+                .remove_entity(object);
+        }
+
+        world.despawn(entity);
+        world.get_resource_mut::<Rooms>().unwrap().unload(id);
+    }
+
+    world
+        .get_resource_mut::<LoadedRegions>()
+        .unwrap()
+        .remove(region);
+
+    true
+}
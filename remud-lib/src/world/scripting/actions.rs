@@ -1,17 +1,28 @@
 use crate::{
     engine::persist::{self, Updates},
-    web::scripts::ScriptError,
-    world::scripting::{
-        CompilationError, CompiledScript, FailedScript, Script, ScriptAst, ScriptEngine,
-        ScriptName, Scripts,
+    web::scripts::{JsonScriptResponse, ScriptError},
+    world::{
+        scripting::{
+            stats::ScriptStats, CompilationError, CompiledScript, FailedScript, Script, ScriptAst,
+            ScriptEngine, ScriptHooks, ScriptName, Scripts,
+        },
+        types::{
+            player::{Player, PlayerId},
+            room::{RegionOwners, Regions},
+            Location,
+        },
     },
 };
 
 use bevy_ecs::prelude::*;
 use either::Either;
+use itertools::Itertools;
 use rhai::ParseError;
 
-pub fn create_script(world: &mut World, script: Script) -> Result<Option<ParseError>, ScriptError> {
+pub fn create_script(
+    world: &mut World,
+    script: Script,
+) -> Result<(Option<ParseError>, i64), ScriptError> {
     tracing::debug!("creating {:?}.", script.name);
 
     if world
@@ -61,9 +72,13 @@ pub fn create_script(world: &mut World, script: Script) -> Result<Option<ParseEr
             script.name.to_string(),
             script.trigger.to_string(),
             script.code,
+            script.tags,
+            script.folder,
+            script.enabled,
+            script.author,
         ));
 
-    Ok(error)
+    Ok((error, script.revision))
 }
 
 pub fn read_script(
@@ -102,7 +117,11 @@ pub fn read_all_scripts(world: &mut World) -> Vec<(Script, Option<ParseError>)>
     scripts
 }
 
-pub fn update_script(world: &mut World, script: Script) -> Result<Option<ParseError>, ScriptError> {
+pub fn update_script(
+    world: &mut World,
+    script: Script,
+    expected_revision: i64,
+) -> Result<(Option<ParseError>, i64), ScriptError> {
     tracing::debug!("updating {:?}.", script.name);
 
     let script_entity = if let Some(entity) = world
@@ -115,6 +134,27 @@ pub fn update_script(world: &mut World, script: Script) -> Result<Option<ParseEr
         return Err(ScriptError::ScriptNotFound);
     };
 
+    // Reject the update if the script has changed since the editor last read it.
+    let current = world.get::<Script>(script_entity).unwrap().clone();
+    if current.revision != expected_revision {
+        let error = world
+            .get::<CompilationError>(script_entity)
+            .map(|e| e.error.clone());
+        return Err(ScriptError::RevisionConflict(Box::new(
+            JsonScriptResponse::new(current, error),
+        )));
+    }
+
+    // Timings of the old code say nothing about the new code.
+    if current.code != script.code {
+        world
+            .get_resource_mut::<ScriptStats>()
+            .unwrap()
+            .remove(&script.name);
+    }
+
+    let script = script.with_revision(current.revision + 1);
+
     let engine = world.get_resource::<ScriptEngine>().unwrap().get();
     let error = match engine.read().unwrap().compile(script.code.as_str()) {
         Ok(ast) => {
@@ -148,9 +188,64 @@ pub fn update_script(world: &mut World, script: Script) -> Result<Option<ParseEr
             script.name.to_string(),
             script.trigger.to_string(),
             script.code,
+            script.revision,
+            script.tags,
+            script.folder,
+            script.enabled,
         ));
 
-    Ok(error)
+    Ok((error, script.revision))
+}
+
+/// Builders may only change scripts which are attached to nothing but rooms, and objects lying in
+/// rooms, within regions they own. Scripts which are not attached to anything may only be changed
+/// by the builder who wrote them.
+pub fn check_owned_regions(
+    world: &mut World,
+    name: &ScriptName,
+    builder: PlayerId,
+) -> Result<(), ScriptError> {
+    let author = match world.get_resource::<Scripts>().unwrap().by_name(name) {
+        Some(entity) => world.get::<Script>(entity).unwrap().author(),
+        None => return Err(ScriptError::ScriptNotFound),
+    };
+
+    let attached = world
+        .query::<(Entity, &ScriptHooks)>()
+        .iter(world)
+        .filter(|(_, hooks)| hooks.hooks().iter().any(|hook| &hook.script == name))
+        .map(|(entity, _)| entity)
+        .collect_vec();
+
+    let owners = world.get_resource::<RegionOwners>().unwrap();
+    let permitted = |mut entity: Entity| {
+        if world.get::<Player>(entity).is_some() {
+            return false;
+        }
+
+        loop {
+            if let Some(regions) = world.get::<Regions>(entity) {
+                return owners.owns_any(builder, regions);
+            }
+
+            match world.get::<Location>(entity) {
+                Some(location) => entity = location.entity(),
+                None => return false,
+            }
+        }
+    };
+
+    if attached.is_empty() {
+        if author == Some(builder) {
+            Ok(())
+        } else {
+            Err(ScriptError::NotAuthor)
+        }
+    } else if attached.into_iter().all(permitted) {
+        Ok(())
+    } else {
+        Err(ScriptError::OutsideOwnedRegions)
+    }
 }
 
 pub fn delete_script(world: &mut World, name: ScriptName) -> Result<(), ScriptError> {
@@ -166,6 +261,10 @@ pub fn delete_script(world: &mut World, name: ScriptName) -> Result<(), ScriptEr
     world.despawn(script_entity);
 
     world.get_resource_mut::<Scripts>().unwrap().remove(&name);
+    world
+        .get_resource_mut::<ScriptStats>()
+        .unwrap()
+        .remove(&name);
 
     world
         .get_resource_mut::<Updates>()
@@ -1,10 +1,13 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
 
 use crate::{
     ecs::SharedWorld,
-    world::{
-        action::Action,
-        scripting::{modules::Me, ExecutionErrors, ScriptAst, ScriptEngine, ScriptName, Scripts},
+    world::scripting::{
+        modules::Me, stats::ScriptStats, ExecutionErrors, Script, ScriptAst, ScriptEngine,
+        ScriptEvent, ScriptName, Scripts,
     },
 };
 
@@ -27,11 +30,16 @@ pub fn run_init_script(world: SharedWorld, entity: Entity, script: ScriptName) {
         Me {
             world: world.clone(),
             entity,
+            script: script.clone(),
         },
     );
     scope.push_constant("WORLD", world.clone());
 
-    match engine.read().unwrap().run_ast_with_scope(&mut scope, &ast) {
+    let started = Instant::now();
+    let result = engine.read().unwrap().run_ast_with_scope(&mut scope, &ast);
+    record_run(&world, &script, started.elapsed(), result.is_err());
+
+    match result {
         Ok(_) => (),
         Err(error) => {
             stats_incr("scripts.run-init.error");
@@ -59,7 +67,7 @@ pub fn run_init_script(world: SharedWorld, entity: Entity, script: ScriptName) {
 
 pub fn run_post_event_script(
     world: SharedWorld,
-    event: &Action,
+    event: &ScriptEvent,
     entity: Entity,
     script: ScriptName,
 ) {
@@ -75,12 +83,17 @@ pub fn run_post_event_script(
         Me {
             world: world.clone(),
             entity,
+            script: script.clone(),
         },
     );
     scope.push_constant("WORLD", world.clone());
     scope.push_constant("EVENT", event.clone());
 
-    match engine.read().unwrap().run_ast_with_scope(&mut scope, &ast) {
+    let started = Instant::now();
+    let result = engine.read().unwrap().run_ast_with_scope(&mut scope, &ast);
+    record_run(&world, &script, started.elapsed(), result.is_err());
+
+    match result {
         Ok(_) => (),
         Err(error) => {
             stats_incr("scripts.run-post-event.error");
@@ -108,7 +121,7 @@ pub fn run_post_event_script(
 
 pub fn run_pre_event_script(
     world: SharedWorld,
-    event: &Action,
+    event: &ScriptEvent,
     entity: Entity,
     script: ScriptName,
 ) -> bool {
@@ -124,13 +137,18 @@ pub fn run_pre_event_script(
         Me {
             world: world.clone(),
             entity,
+            script: script.clone(),
         },
     );
     scope.push_constant("WORLD", world.clone());
     scope.push_constant("EVENT", event.clone());
     scope.push_dynamic("allow_action", Dynamic::from(true));
 
-    match engine.read().unwrap().run_ast_with_scope(&mut scope, &ast) {
+    let started = Instant::now();
+    let result = engine.read().unwrap().run_ast_with_scope(&mut scope, &ast);
+    record_run(&world, &script, started.elapsed(), result.is_err());
+
+    match result {
         Ok(_) => (),
         Err(error) => {
             stats_incr("scripts.run-pre-event.error");
@@ -171,11 +189,16 @@ pub fn run_timed_script(world: SharedWorld, entity: Entity, script: ScriptName)
         Me {
             world: world.clone(),
             entity,
+            script: script.clone(),
         },
     );
     scope.push_constant("WORLD", world.clone());
 
-    match engine.read().unwrap().run_ast_with_scope(&mut scope, &ast) {
+    let started = Instant::now();
+    let result = engine.read().unwrap().run_ast_with_scope(&mut scope, &ast);
+    record_run(&world, &script, started.elapsed(), result.is_err());
+
+    match result {
         Ok(_) => (),
         Err(error) => {
             stats_incr("scripts.run-timed.error");
@@ -201,6 +224,71 @@ pub fn run_timed_script(world: SharedWorld, entity: Entity, script: ScriptName)
     };
 }
 
+pub fn run_continuation(world: SharedWorld, entity: Entity, script: ScriptName, function: String) {
+    stats_incr("scripts.run-continuation");
+    let (ast, engine) = match prepare_script_execution(&*world.read().unwrap(), &script) {
+        Some(results) => results,
+        None => return,
+    };
+
+    // Script functions cannot see the scope, so SELF is provided to them as `this`.
+    let mut this = Dynamic::from(Me {
+        world: world.clone(),
+        entity,
+        script: script.clone(),
+    });
+
+    let started = Instant::now();
+    let result = engine.read().unwrap().call_fn_dynamic(
+        &mut Scope::new(),
+        &ast,
+        false,
+        function.as_str(),
+        Some(&mut this),
+        [],
+    );
+    record_run(&world, &script, started.elapsed(), result.is_err());
+
+    match result {
+        Ok(_) => (),
+        Err(error) => {
+            stats_incr("scripts.run-continuation.error");
+            tracing::warn!(
+                "script {} continuation {} execution error: {}",
+                script,
+                function,
+                error
+            );
+            let error = {
+                if let Some(mut errors) = world.write().unwrap().get_mut::<ExecutionErrors>(entity)
+                {
+                    errors.insert(script.clone(), error);
+                    None
+                } else {
+                    Some(error)
+                }
+            };
+            if let Some(error) = error {
+                world
+                    .write()
+                    .unwrap()
+                    .get_entity_mut(entity)
+                    .unwrap()
+                    .insert(ExecutionErrors::new_with_error(script, error));
+            }
+        }
+    };
+}
+
+fn record_run(world: &SharedWorld, script: &ScriptName, elapsed: Duration, error: bool) {
+    world
+        .write()
+        .unwrap()
+        .get_resource_mut::<ScriptStats>()
+        .unwrap()
+        .record(script, elapsed, error);
+}
+
 fn prepare_script_execution(world: &World, script: &ScriptName) -> Option<(AST, SharedEngine)> {
     let script = {
         if let Some(script) = world.get_resource::<Scripts>().unwrap().by_name(script) {
@@ -214,6 +302,11 @@ fn prepare_script_execution(world: &World, script: &ScriptName) -> Option<(AST,
         }
     };
 
+    if !world.get::<Script>(script).unwrap().enabled() {
+        tracing::debug!("skipping execution of {:?}, script is disabled.", script);
+        return None;
+    }
+
     let ast = {
         if let Some(ast) = world
             .get::<ScriptAst>(script)
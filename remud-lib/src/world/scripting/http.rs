@@ -0,0 +1,218 @@
+use std::{collections::HashMap, time::Duration};
+
+use bevy_ecs::prelude::*;
+use reqwest::Url;
+use rhai::{Dynamic, Map};
+
+use crate::{
+    config::ScriptingConfig,
+    world::scripting::{ScriptData, ScriptHooks, ScriptRun, ScriptRuns, ScriptTrigger},
+};
+
+/// Requests made by scripts with `http_get`, waiting for the engine to perform them, and the
+/// responses waiting to be delivered back to the scripts.
+#[derive(Default)]
+pub struct HttpRequests {
+    allowlist: Vec<String>,
+    pending: Vec<HttpRequest>,
+    responses: Vec<HttpResponse>,
+}
+
+impl HttpRequests {
+    pub fn set_allowlist(&mut self, allowlist: Vec<String>) {
+        self.allowlist = allowlist;
+    }
+
+    /// Queues a request to be performed by the engine. Requests for URLs which are not allowed
+    /// are answered immediately with an error.
+    pub fn request(&mut self, entity: Entity, url: &str, callback: String) {
+        match check_url(url, self.allowlist.as_slice()) {
+            Ok(url) => self.pending.push(HttpRequest {
+                entity,
+                url,
+                callback,
+            }),
+            Err(error) => self.responses.push(HttpResponse {
+                entity,
+                callback,
+                result: Err(error.to_string()),
+            }),
+        }
+    }
+
+    pub fn take_pending(&mut self) -> Vec<HttpRequest> {
+        std::mem::take(&mut self.pending)
+    }
+
+    pub fn respond(&mut self, response: HttpResponse) {
+        self.responses.push(response);
+    }
+}
+
+#[derive(Debug)]
+pub struct HttpRequest {
+    entity: Entity,
+    url: Url,
+    callback: String,
+}
+
+#[derive(Debug)]
+pub struct HttpResponse {
+    entity: Entity,
+    callback: String,
+    // the status code and body, or why the request failed
+    result: Result<(u16, String), String>,
+}
+
+impl HttpResponse {
+    fn into_map(self) -> Map {
+        let mut map = Map::new();
+
+        match self.result {
+            Ok((status, body)) => {
+                map.insert("status".into(), Dynamic::from(status as i64));
+                map.insert("body".into(), Dynamic::from(body));
+            }
+            Err(error) => {
+                map.insert("error".into(), Dynamic::from(error));
+            }
+        }
+
+        map
+    }
+}
+
+// Only plain http(s) URLs to hosts on the allowlist, or their subdomains, may be fetched.
+fn check_url(url: &str, allowlist: &[String]) -> Result<Url, &'static str> {
+    let url = Url::parse(url).map_err(|_| "invalid URL")?;
+
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err("URL scheme must be http or https");
+    }
+
+    let host = url.host_str().ok_or("URL has no host")?;
+    let allowed = allowlist.iter().any(|allowed| {
+        host.eq_ignore_ascii_case(allowed)
+            || host
+                .to_ascii_lowercase()
+                .ends_with(format!(".{}", allowed.to_ascii_lowercase()).as_str())
+    });
+
+    if allowed {
+        Ok(url)
+    } else {
+        Err("host not allowed")
+    }
+}
+
+/// Performs script HTTP requests off of the game loop, within the configured time and size
+/// limits.
+#[derive(Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    timeout: Duration,
+    max_bytes: usize,
+}
+
+impl HttpClient {
+    pub fn new(config: &ScriptingConfig) -> Self {
+        HttpClient {
+            client: reqwest::Client::builder()
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .unwrap_or_default(),
+            timeout: config.http_timeout(),
+            max_bytes: config.http_max_bytes,
+        }
+    }
+
+    pub fn configure(&mut self, config: &ScriptingConfig) {
+        self.timeout = config.http_timeout();
+        self.max_bytes = config.http_max_bytes;
+    }
+
+    #[tracing::instrument(name = "script http request", skip_all, fields(url = %request.url))]
+    pub async fn fetch(&self, request: HttpRequest) -> HttpResponse {
+        let result = match tokio::time::timeout(self.timeout, self.get(request.url)).await {
+            Ok(result) => result,
+            Err(_) => Err("request timed out".to_string()),
+        };
+
+        if let Err(e) = &result {
+            tracing::debug!("script http request failed: {}", e);
+        }
+
+        HttpResponse {
+            entity: request.entity,
+            callback: request.callback,
+            result,
+        }
+    }
+
+    async fn get(&self, url: Url) -> Result<(u16, String), String> {
+        let mut response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| format!("request failed: {}", e))?;
+
+        let status = response.status().as_u16();
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("failed to read response: {}", e))?
+        {
+            if body.len() + chunk.len() > self.max_bytes {
+                return Err("response too large".to_string());
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok((status, String::from_utf8_lossy(&body).into_owned()))
+    }
+}
+
+#[tracing::instrument(name = "http responses system", skip_all)]
+pub fn http_responses_system(
+    mut commands: Commands,
+    mut requests: ResMut<HttpRequests>,
+    mut script_runs: ResMut<ScriptRuns>,
+    mut data_query: Query<&mut ScriptData>,
+    hooks_query: Query<&ScriptHooks>,
+) {
+    // Several responses may arrive for an entity without script data, which must be gathered
+    // into a single component.
+    let mut new_data: HashMap<Entity, ScriptData> = HashMap::new();
+
+    for response in requests.responses.drain(..) {
+        let entity = response.entity;
+        let callback = response.callback.clone();
+        let value = Dynamic::from(response.into_map());
+
+        // The entity may have been removed while the request was in flight.
+        let hooks = match hooks_query.get(entity) {
+            Ok(hooks) => hooks,
+            Err(_) => continue,
+        };
+
+        if let Ok(mut data) = data_query.get_mut(entity) {
+            data.insert(callback.as_str().into(), value);
+        } else {
+            new_data
+                .entry(entity)
+                .or_default()
+                .insert(callback.as_str().into(), value);
+        }
+
+        for script in hooks.by_trigger(ScriptTrigger::Timer(callback)) {
+            script_runs.timed_runs.push(ScriptRun { entity, script });
+        }
+    }
+
+    for (entity, data) in new_data {
+        commands.entity(entity).insert(data);
+    }
+}
@@ -1,14 +1,19 @@
 pub mod actions;
 pub mod execution;
+pub mod http;
 mod modules;
+pub mod signal;
+pub mod stats;
 mod systems;
 pub mod time;
 
 use std::{
+    cmp::Reverse,
     collections::HashMap,
     convert::TryFrom,
     fmt,
-    sync::{Arc, RwLock},
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, PoisonError, RwLock},
 };
 
 use bevy_app::Events;
@@ -20,32 +25,44 @@ use strum::EnumString;
 use thiserror::Error;
 
 use crate::{
-    ecs::{CoreSystem, Ecs, Phase, Plugin, SharedWorld, Step},
+    ecs::{panic_message, CoreSystem, Ecs, GuardSystem, Phase, Plugin, SharedWorld, Step},
+    metrics::stats_incr,
     world::{
         action::Action,
         fsm::{StateId, StateMachineBuilder, Transition},
+        report_failure,
         scripting::{
             execution::{
-                run_init_script, run_post_event_script, run_pre_event_script, run_timed_script,
-                SharedEngine,
+                run_continuation, run_init_script, run_post_event_script, run_pre_event_script,
+                run_timed_script, SharedEngine,
             },
+            http::{http_responses_system, HttpRequests},
             modules::{
                 event_api, rand_api, self_api, states_api, time_api, transitions_api, world_api,
             },
+            signal::{signals_system, Signals},
+            stats::ScriptStats,
             systems::{
                 init_script_runs_system, post_action_script_runs_system,
                 pre_event_script_runs_system, timed_script_runs_system,
             },
-            time::{tick_timers_system, timed_actions_system, timer_cleanup_system, TimedActions},
+            time::{
+                continuations_system, tick_timers_system, timed_actions_system,
+                timer_cleanup_system, Continuations, Cutscenes, TimedActions,
+            },
         },
+        types::{player::PlayerId, Id},
     },
 };
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, SystemLabel)]
 pub enum ScriptSystem {
+    Continuations,
+    HttpResponses,
     InitScriptRuns,
     PostActionScriptRuns,
     PreEventScriptRuns,
+    Signals,
     TickTimers,
     TimedActions,
     TimerCleanup,
@@ -59,13 +76,18 @@ impl Plugin for ScriptPlugin {
     fn build(&self, ecs: &mut Ecs) {
         ecs.init_resource::<ScriptRuns>()
             .init_resource::<TimedActions>()
+            .init_resource::<Cutscenes>()
+            .init_resource::<Continuations>()
             .init_resource::<ScriptEngine>()
+            .init_resource::<HttpRequests>()
+            .init_resource::<Signals>()
+            .init_resource::<ScriptStats>()
             .add_event::<RunInitScript>()
             .add_system(
                 Step::PreEvent,
                 Phase::First,
                 timer_cleanup_system
-                    .system()
+                    .guarded()
                     .label(ScriptSystem::TimerCleanup)
                     .before(ScriptSystem::TickTimers),
             )
@@ -73,7 +95,7 @@ impl Plugin for ScriptPlugin {
                 Step::PreEvent,
                 Phase::First,
                 tick_timers_system
-                    .system()
+                    .guarded()
                     .label(ScriptSystem::TickTimers)
                     .after(CoreSystem::Time),
             )
@@ -81,7 +103,7 @@ impl Plugin for ScriptPlugin {
                 Step::PreEvent,
                 Phase::Update,
                 timed_actions_system
-                    .system()
+                    .guarded()
                     .label(ScriptSystem::TimedActions)
                     .before(ScriptSystem::PreEventScriptRuns),
             )
@@ -89,29 +111,53 @@ impl Plugin for ScriptPlugin {
                 Step::PreEvent,
                 Phase::Update,
                 timed_script_runs_system
-                    .system()
+                    .guarded()
                     .label(ScriptSystem::TimedScriptRuns)
                     .before(ScriptSystem::PreEventScriptRuns),
             )
+            .add_system(
+                Step::PreEvent,
+                Phase::Update,
+                continuations_system
+                    .guarded()
+                    .label(ScriptSystem::Continuations)
+                    .before(ScriptSystem::PreEventScriptRuns),
+            )
+            .add_system(
+                Step::PreEvent,
+                Phase::Update,
+                http_responses_system
+                    .guarded()
+                    .label(ScriptSystem::HttpResponses)
+                    .before(ScriptSystem::PreEventScriptRuns),
+            )
+            .add_system(
+                Step::PreEvent,
+                Phase::Update,
+                signals_system
+                    .guarded()
+                    .label(ScriptSystem::Signals)
+                    .before(ScriptSystem::PreEventScriptRuns),
+            )
             .add_system(
                 Step::PreEvent,
                 Phase::Update,
                 init_script_runs_system
-                    .system()
+                    .guarded()
                     .label(ScriptSystem::InitScriptRuns),
             )
             .add_system(
                 Step::PreEvent,
                 Phase::Update,
                 pre_event_script_runs_system
-                    .system()
+                    .guarded()
                     .label(ScriptSystem::PreEventScriptRuns),
             )
             .add_system(
                 Step::PostEvent,
                 Phase::Update,
                 post_action_script_runs_system
-                    .system()
+                    .guarded()
                     .label(ScriptSystem::PostActionScriptRuns),
             );
     }
@@ -134,6 +180,11 @@ pub struct Script {
     name: ScriptName,
     trigger: TriggerEvent,
     code: String,
+    revision: i64,
+    tags: Vec<String>,
+    folder: Option<String>,
+    enabled: bool,
+    author: Option<PlayerId>,
 }
 
 impl Script {
@@ -142,9 +193,39 @@ impl Script {
             name,
             trigger,
             code,
+            revision: 1,
+            tags: Vec::new(),
+            folder: None,
+            enabled: true,
+            author: None,
         }
     }
 
+    pub fn with_revision(mut self, revision: i64) -> Self {
+        self.revision = revision;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_folder(mut self, folder: Option<String>) -> Self {
+        self.folder = folder;
+        self
+    }
+
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn with_author(mut self, author: Option<PlayerId>) -> Self {
+        self.author = author;
+        self
+    }
+
     pub fn name(&self) -> &ScriptName {
         &self.name
     }
@@ -161,6 +242,48 @@ impl Script {
         self.code.as_str()
     }
 
+    // Incremented each time the script is updated, used to detect conflicting edits.
+    pub fn revision(&self) -> i64 {
+        self.revision
+    }
+
+    // Free-form labels used to organize and filter scripts.
+    pub fn tags(&self) -> &[String] {
+        self.tags.as_slice()
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    // A slash separated path, such as "areas/town", used to organize scripts.
+    pub fn folder(&self) -> Option<&str> {
+        self.folder.as_deref()
+    }
+
+    // Returns true if the script is in the folder or one of its subfolders.
+    pub fn in_folder(&self, folder: &str) -> bool {
+        match self.folder.as_deref() {
+            Some(own) => own == folder || own.starts_with(format!("{}/", folder).as_str()),
+            None => false,
+        }
+    }
+
+    // Disabled scripts stay attached, but are skipped wherever they would run.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    // The player who created the script. Scripts created before authors were recorded, and those
+    // whose author was deleted, have none.
+    pub fn author(&self) -> Option<PlayerId> {
+        self.author
+    }
+
     pub fn into_parts(self) -> (ScriptName, TriggerEvent, String) {
         (self.name, self.trigger, self.code)
     }
@@ -202,7 +325,13 @@ impl Default for ScriptEngine {
     fn default() -> Self {
         let mut engine = rhai::Engine::default();
 
+        // Use the release limits in every build so continuation functions, which are subject to
+        // the tighter function limit, behave the same in development.
+        engine.set_max_expr_depths(64, 32);
+
         engine.register_type_with_name::<SharedWorld>("World");
+        engine.register_type_with_name::<ScriptEvent>("Event");
+        engine.register_type_with_name::<Id>("Id");
 
         engine.register_type_with_name::<StateMachineBuilder>("StateMachineBuilder");
         engine.register_fn("fsm_builder", StateMachineBuilder::default);
@@ -225,7 +354,7 @@ impl Default for ScriptEngine {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ScriptName(String);
 
 impl ScriptName {
@@ -256,6 +385,46 @@ impl fmt::Display for ScriptName {
 #[error("Failed to parse script name: must be ASCII and contain no whitespace.")]
 pub struct ScriptNameParseError {}
 
+// Tags are stored comma separated, so they are limited to lowercase ASCII without whitespace or
+// commas.
+pub fn parse_tag(tag: &str) -> Result<String, ScriptTagParseError> {
+    let tag = tag.trim().to_lowercase();
+    if !tag.is_empty()
+        && tag
+            .chars()
+            .all(|c| c.is_ascii() && !c.is_whitespace() && c != ',')
+    {
+        Ok(tag)
+    } else {
+        Err(ScriptTagParseError {})
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Failed to parse script tag: must be ASCII and contain no whitespace or commas.")]
+pub struct ScriptTagParseError {}
+
+// Leading and trailing slashes are ignored, and an empty folder places the script at the top level.
+pub fn parse_folder(folder: &str) -> Result<Option<String>, ScriptFolderParseError> {
+    let folder = folder.trim().trim_matches('/');
+    if folder.is_empty() {
+        Ok(None)
+    } else if folder
+        .split('/')
+        .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii() && !c.is_whitespace()))
+    {
+        Ok(Some(folder.to_string()))
+    } else {
+        Err(ScriptFolderParseError {})
+    }
+}
+
+#[derive(Debug, Error)]
+#[error(
+    "Failed to parse script folder: must be ASCII, contain no whitespace, and have no empty parts."
+)]
+pub struct ScriptFolderParseError {}
+
 #[derive(Default)]
 pub struct Scripts {
     by_name: HashMap<ScriptName, Entity>,
@@ -278,8 +447,10 @@ impl Scripts {
 #[derive(Default, Debug)]
 pub struct ScriptRuns {
     init_runs: Vec<ScriptRun>,
-    runs: Vec<(Action, Vec<ScriptRun>)>,
+    runs: Vec<(ScriptEvent, Vec<ScriptRun>)>,
     timed_runs: Vec<ScriptRun>,
+    // runs which resume a waiting script by calling the named function
+    continuation_runs: Vec<(ScriptRun, String)>,
 }
 
 impl ScriptRuns {
@@ -343,14 +514,28 @@ impl ScriptHooks {
         self.list.as_slice()
     }
 
+    // Hooks are the same if they run the same script for the same trigger, whatever their
+    // priorities.
     pub fn contains(&self, hook: &ScriptHook) -> bool {
-        self.list.contains(hook)
+        self.list
+            .iter()
+            .any(|h| h.trigger == hook.trigger && h.script == hook.script)
     }
 
     pub fn insert(&mut self, hook: ScriptHook) {
         self.list.push(hook)
     }
 
+    /// Enables or disables every hook for the script, returning false if there are none.
+    pub fn set_enabled(&mut self, script: &ScriptName, enabled: bool) -> bool {
+        let mut found = false;
+        for hook in self.list.iter_mut().filter(|hook| &hook.script == script) {
+            hook.enabled = enabled;
+            found = true;
+        }
+        found
+    }
+
     pub fn remove(&mut self, script: &ScriptName) -> Option<ScriptHook> {
         if let Some(pos) = self.list.iter().position(|h| &h.script == script) {
             Some(self.list.remove(pos))
@@ -359,10 +544,13 @@ impl ScriptHooks {
         }
     }
 
+    /// The scripts to run for the trigger, highest priority first. Scripts with the same priority
+    /// run in the order they were attached.
     pub fn by_trigger(&self, trigger: ScriptTrigger) -> Vec<ScriptName> {
         self.list
             .iter()
-            .filter(|hook| hook.trigger == trigger)
+            .filter(|hook| hook.enabled && hook.trigger == trigger)
+            .sorted_by_key(|hook| Reverse(hook.priority))
             .map(|hook| hook.script.clone())
             .collect_vec()
     }
@@ -381,6 +569,21 @@ impl ScriptHooks {
 pub struct ScriptHook {
     pub trigger: ScriptTrigger,
     pub script: ScriptName,
+    pub priority: i64,
+    pub enabled: bool,
+}
+
+impl fmt::Display for ScriptHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} -> {}", self.trigger, self.script)?;
+        if self.priority != 0 {
+            write!(f, " (priority {})", self.priority)?;
+        }
+        if !self.enabled {
+            write!(f, " (disabled)")?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Default, Clone)]
@@ -418,6 +621,8 @@ impl ScriptData {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ScriptTrigger {
+    // runs when a script emits the named signal
+    Custom(String),
     Init,
     PostEvent(TriggerEvent),
     PreEvent(TriggerEvent),
@@ -427,6 +632,7 @@ pub enum ScriptTrigger {
 impl ScriptTrigger {
     pub fn kind(&self) -> TriggerKind {
         match self {
+            ScriptTrigger::Custom(_) => TriggerKind::Custom,
             ScriptTrigger::Init => TriggerKind::Init,
             ScriptTrigger::PostEvent(_) => TriggerKind::PostEvent,
             ScriptTrigger::PreEvent(_) => TriggerKind::PreEvent,
@@ -438,6 +644,7 @@ impl ScriptTrigger {
 impl ToString for ScriptTrigger {
     fn to_string(&self) -> String {
         match self {
+            ScriptTrigger::Custom(name) => name.clone(),
             ScriptTrigger::Init => String::new(),
             ScriptTrigger::PostEvent(event) => event.to_string(),
             ScriptTrigger::PreEvent(event) => event.to_string(),
@@ -448,6 +655,7 @@ impl ToString for ScriptTrigger {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString)]
 pub enum TriggerKind {
+    Custom,
     Init,
     PostEvent,
     PreEvent,
@@ -457,6 +665,7 @@ pub enum TriggerKind {
 impl fmt::Display for TriggerKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            TriggerKind::Custom => write!(f, "Custom"),
             TriggerKind::Init => write!(f, "Init"),
             TriggerKind::PostEvent => write!(f, "PostEvent"),
             TriggerKind::PreEvent => write!(f, "PreEvent"),
@@ -465,68 +674,219 @@ impl fmt::Display for TriggerKind {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, EnumString)]
 pub enum TriggerEvent {
+    Buy,
+    Custom,
+    Drink,
     Drop,
+    Eat,
     Emote,
     Exits,
     Get,
+    Haggle,
     Init,
     Inventory,
     Look,
     LookAt,
     Move,
     Say,
+    Sell,
     Send,
     Timer,
     Use,
 }
 
 impl TriggerEvent {
+    pub const ALL: [TriggerEvent; 19] = [
+        TriggerEvent::Buy,
+        TriggerEvent::Custom,
+        TriggerEvent::Drink,
+        TriggerEvent::Drop,
+        TriggerEvent::Eat,
+        TriggerEvent::Emote,
+        TriggerEvent::Exits,
+        TriggerEvent::Get,
+        TriggerEvent::Haggle,
+        TriggerEvent::Init,
+        TriggerEvent::Inventory,
+        TriggerEvent::Look,
+        TriggerEvent::LookAt,
+        TriggerEvent::Move,
+        TriggerEvent::Say,
+        TriggerEvent::Sell,
+        TriggerEvent::Send,
+        TriggerEvent::Timer,
+        TriggerEvent::Use,
+    ];
+
     fn from_action(value: &Action) -> Option<Self> {
         match value {
+            Action::AchievementCreate(_) => None,
+            Action::AchievementList(_) => None,
+            Action::AchievementUpdate(_) => None,
+            Action::AllowlistAdd(_) => None,
+            Action::AllowlistRemove(_) => None,
+            Action::AllowlistShow(_) => None,
+            Action::Balance(_) => None,
+            Action::BankAudit(_) => None,
+            Action::BankGrant(_) => None,
+            Action::BankInfo(_) => None,
+            Action::Brief(_) => None,
+            Action::CampaignCreate(_) => None,
+            Action::CampaignInfo(_) => None,
+            Action::CampaignList(_) => None,
+            Action::CampaignProgress(_) => None,
+            Action::CampaignRemove(_) => None,
+            Action::CampaignUpdate(_) => None,
+            Action::ClanDisband(_) => None,
+            Action::ClanFound(_) => None,
+            Action::ClanHall(_) => None,
+            Action::ClanInfo(_) => None,
+            Action::ClanInvite(_) => None,
+            Action::ClanJoin(_) => None,
+            Action::ClanKick(_) => None,
+            Action::ClanLeave(_) => None,
+            Action::ClanList(_) => None,
+            Action::ClanSay(_) => None,
+            Action::ClanSetRank(_) => None,
+            Action::ClanUpdateHall(_) => None,
+            Action::CommunicationSearch(_) => None,
+            Action::ConfigReload(_) => None,
+            Action::CutsceneLine(_) => None,
+            Action::DbCheck(_) => None,
+            Action::Deposit(_) => None,
+            Action::Drink(_) => Some(TriggerEvent::Drink),
             Action::Drop(_) => Some(TriggerEvent::Drop),
+            Action::Eat(_) => Some(TriggerEvent::Eat),
+            Action::EconomyReport(_) => None,
             Action::Emote(_) => Some(TriggerEvent::Emote),
             Action::Exits(_) => Some(TriggerEvent::Exits),
+            Action::FactionCreate(_) => None,
+            Action::FactionList(_) => None,
+            Action::FactionUpdate(_) => None,
+            Action::Force(_) => None,
             Action::Get(_) => Some(TriggerEvent::Get),
+            Action::GlobalList(_) => None,
+            Action::GlobalUpdate(_) => None,
+            Action::GrantAchievement(_) => None,
+            Action::Hints(_) => None,
+            Action::Ignore(_) => None,
             Action::Initialize(_) => None,
             Action::Inventory(_) => Some(TriggerEvent::Inventory),
+            Action::LintWorld(_) => None,
             Action::Login(_) => None,
             Action::Look(_) => Some(TriggerEvent::Look),
             Action::LookAt(_) => Some(TriggerEvent::LookAt),
+            Action::LootTableCreate(_) => None,
+            Action::LootTableInfo(_) => None,
+            Action::LootTableList(_) => None,
+            Action::LootTableRemove(_) => None,
+            Action::LootTableRoll(_) => None,
+            Action::LootTableUpdate(_) => None,
+            Action::MailClaim(_) => None,
+            Action::MailDelete(_) => None,
+            Action::MailList(_) => None,
+            Action::MailRead(_) => None,
+            Action::MailSend(_) => None,
+            Action::MaintenanceOff(_) => None,
+            Action::MaintenanceOn(_) => None,
+            Action::Map(_) => None,
+            Action::MarketBuy(_) => Some(TriggerEvent::Buy),
+            Action::MarketCancel(_) => None,
+            Action::MarketList(_) => None,
+            Action::MarketSell(_) => Some(TriggerEvent::Sell),
             Action::Message(_) => None,
             Action::Move(_) => Some(TriggerEvent::Move),
+            Action::NewsDelete(_) => None,
+            Action::NewsList(_) => None,
+            Action::NewsPost(_) => None,
             Action::ObjectCreate(_) => None,
             Action::ObjectInfo(_) => None,
             Action::ObjectInheritFields(_) => None,
             Action::ObjectRemove(_) => None,
             Action::PlayerInfo(_) => None,
+            Action::PlayerRename(_) => None,
             Action::PlayerUpdateFlags(_) => None,
+            Action::PollClose(_) => None,
+            Action::PollCreate(_) => None,
+            Action::PollInfo(_) => None,
+            Action::PollList(_) => None,
+            Action::Possess(_) => None,
+            Action::Privacy(_) => None,
+            Action::Profile(_) => None,
+            Action::PrototypeBehavior(_) => None,
+            Action::PrototypeBulk(_) => None,
             Action::PrototypeCreate(_) => None,
             Action::PrototypeInfo(_) => None,
             Action::PrototypeList(_) => None,
+            Action::Publish(_) => None,
+            Action::Recall(_) => None,
+            Action::RegionDisown(_) => None,
+            Action::RegionLoaded(_) => None,
+            Action::RegionOwner(_) => None,
+            Action::RegionShow(_) => None,
+            Action::Release(_) => None,
+            Action::Report(_) => None,
             Action::Restart(_) => None,
             Action::RoomCreate(_) => None,
+            Action::RoomEcho(_) => None,
             Action::RoomInfo(_) => None,
             Action::RoomLink(_) => None,
             Action::RoomRemove(_) => None,
             Action::RoomUnlink(_) => None,
             Action::RoomUpdateRegions(_) => None,
             Action::Say(_) => Some(TriggerEvent::Say),
+            Action::ScriptApi(_) => None,
             Action::ScriptAttach(_) => None,
             Action::ScriptDetach(_) => None,
+            Action::ScriptList(_) => None,
+            Action::ScriptStatsList(_) => None,
+            Action::ScriptToggle(_) => None,
+            Action::SeasonInfo(_) => None,
+            Action::SeasonList(_) => None,
+            Action::SeasonTag(_) => None,
             Action::Send(_) => Some(TriggerEvent::Send),
+            Action::SetPronouns(_) => None,
+            Action::ShopBuy(_) => None,
+            Action::ShopCreate(_) => None,
+            Action::ShopHaggle(_) => Some(TriggerEvent::Haggle),
+            Action::ShopInfo(_) => None,
+            Action::ShopList(_) => None,
+            Action::ShopRemove(_) => None,
+            Action::ShopUpdate(_) => None,
+            Action::ShopsList(_) => None,
+            Action::ShowAchievements(_) => None,
+            Action::ShowCampaigns(_) => None,
             Action::ShowError(_) => None,
             Action::Shutdown(_) => None,
+            Action::SnoopApprove(_) => None,
+            Action::SnoopStart(_) => None,
+            Action::SnoopStop(_) => None,
+            Action::SpawnTableCreate(_) => None,
+            Action::SpawnTableInfo(_) => None,
+            Action::SpawnTableList(_) => None,
+            Action::SpawnTableRemove(_) => None,
+            Action::SpawnTableUpdate(_) => None,
+            Action::StageDiscard(_) => None,
+            Action::StageRegion(_) => None,
             Action::Stats(_) => None,
             Action::Teleport(_) => None,
+            Action::Top(_) => None,
+            Action::TutorialHint(_) => None,
+            Action::TutorialInfo(_) => None,
+            Action::TutorialStart(_) => None,
+            Action::Unignore(_) => None,
             Action::UpdateDescription(_) => None,
+            Action::UpdateField(_) => None,
             Action::UpdateKeywords(_) => None,
             Action::UpdateName(_) => None,
             Action::UpdateObjectFlags(_) => None,
             Action::Use(_) => Some(TriggerEvent::Use),
+            Action::Vote(_) => None,
             Action::Whisper(_) => None,
             Action::Who(_) => None,
+            Action::Withdraw(_) => None,
         }
     }
 }
@@ -534,16 +894,22 @@ impl TriggerEvent {
 impl fmt::Display for TriggerEvent {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            TriggerEvent::Buy => write!(f, "Buy"),
+            TriggerEvent::Custom => write!(f, "Custom"),
+            TriggerEvent::Drink => write!(f, "Drink"),
             TriggerEvent::Drop => write!(f, "Drop"),
+            TriggerEvent::Eat => write!(f, "Eat"),
             TriggerEvent::Emote => write!(f, "Emote"),
             TriggerEvent::Exits => write!(f, "Exits"),
             TriggerEvent::Get => write!(f, "Get"),
+            TriggerEvent::Haggle => write!(f, "Haggle"),
             TriggerEvent::Init => write!(f, "Init"),
             TriggerEvent::Inventory => write!(f, "Inventory"),
             TriggerEvent::Look => write!(f, "Look"),
             TriggerEvent::LookAt => write!(f, "LookAt"),
             TriggerEvent::Move => write!(f, "Move"),
             TriggerEvent::Say => write!(f, "Say"),
+            TriggerEvent::Sell => write!(f, "Sell"),
             TriggerEvent::Send => write!(f, "Send"),
             TriggerEvent::Timer => write!(f, "Timer"),
             TriggerEvent::Use => write!(f, "Use"),
@@ -551,6 +917,157 @@ impl fmt::Display for TriggerEvent {
     }
 }
 
+impl TriggerEvent {
+    /// The parameters scripts can read from events of this kind, besides the actor and kind
+    /// every event has, paired with their descriptions.
+    pub fn params(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            TriggerEvent::Buy => &[
+                (
+                    "object",
+                    "the object being bought, or unit if the listing doesn't exist",
+                ),
+                ("listing", "the listing ID"),
+                (
+                    "price",
+                    "the price in coins, or unit if the listing doesn't exist",
+                ),
+                (
+                    "seller",
+                    "the name of the seller, or unit if the listing doesn't exist",
+                ),
+            ],
+            TriggerEvent::Custom => &[],
+            TriggerEvent::Drink => &[
+                (
+                    "object",
+                    "the object being drunk, or unit if the actor isn't carrying it",
+                ),
+                ("keywords", "the keywords the actor used, as an array"),
+            ],
+            TriggerEvent::Drop => &[
+                (
+                    "object",
+                    "the object being dropped, or unit if the actor isn't carrying it",
+                ),
+                ("keywords", "the keywords the actor used, as an array"),
+            ],
+            TriggerEvent::Eat => &[
+                (
+                    "object",
+                    "the object being eaten, or unit if the actor isn't carrying it",
+                ),
+                ("keywords", "the keywords the actor used, as an array"),
+            ],
+            TriggerEvent::Emote => &[("text", "the emote")],
+            TriggerEvent::Exits => &[("room", "the room whose exits are shown")],
+            TriggerEvent::Get => &[
+                (
+                    "object",
+                    "the object being picked up, or unit if there is none",
+                ),
+                ("keywords", "the keywords the actor used, as an array"),
+            ],
+            TriggerEvent::Haggle => &[
+                ("item", "the number of the item in the shop's list"),
+                (
+                    "prototype",
+                    "the prototype ID of the item, or unit if there is no such item",
+                ),
+                (
+                    "price",
+                    "the price the actor would pay in coins, or unit if there is no such item",
+                ),
+            ],
+            TriggerEvent::Init => &[],
+            TriggerEvent::Inventory => &[],
+            TriggerEvent::Look => &[
+                (
+                    "room",
+                    "the room being looked at, or unit if there is no exit that way",
+                ),
+                (
+                    "direction",
+                    "the direction looked in, or unit for the current room",
+                ),
+            ],
+            TriggerEvent::LookAt => &[
+                (
+                    "target",
+                    "the entity being looked at, or unit if nothing matches",
+                ),
+                ("keywords", "the keywords the actor used, as an array"),
+            ],
+            TriggerEvent::Move => &[
+                ("direction", "the direction of travel"),
+                ("from", "the room being left"),
+                (
+                    "to",
+                    "the room being entered, or unit if there is no exit that way",
+                ),
+            ],
+            TriggerEvent::Say => &[
+                ("speaker", "the entity speaking"),
+                ("text", "what was said"),
+            ],
+            TriggerEvent::Sell => &[
+                (
+                    "object",
+                    "the object being listed, or unit if the actor isn't carrying it",
+                ),
+                ("keywords", "the keywords the actor used, as an array"),
+                ("price", "the asking price in coins"),
+            ],
+            TriggerEvent::Send => &[
+                ("speaker", "the entity sending the message"),
+                ("recipient", "the name of the player the message is for"),
+                ("text", "the message"),
+            ],
+            TriggerEvent::Timer => &[],
+            TriggerEvent::Use => &[
+                ("object", "the object being used, or unit if there is none"),
+                ("keywords", "the keywords the actor used, as an array"),
+            ],
+        }
+    }
+}
+
+/// An action as scripts see it, with the parameters resolved when the action was queued, before
+/// it took effect. Pre-event and post-event scripts see the same parameters.
+#[derive(Debug, Clone)]
+pub struct ScriptEvent {
+    action: Action,
+    kind: TriggerEvent,
+    params: rhai::Map,
+}
+
+impl ScriptEvent {
+    pub fn new(action: Action, kind: TriggerEvent, params: rhai::Map) -> Self {
+        ScriptEvent {
+            action,
+            kind,
+            params,
+        }
+    }
+
+    pub fn action(&self) -> &Action {
+        &self.action
+    }
+
+    pub fn kind(&self) -> TriggerEvent {
+        self.kind
+    }
+
+    /// Returns the named parameter, or unit if events of this kind don't have it.
+    pub fn param(&self, name: &str) -> Dynamic {
+        self.params.get(name).cloned().unwrap_or(Dynamic::UNIT)
+    }
+
+    pub fn params(&self) -> rhai::Map {
+        self.params.clone()
+    }
+}
+
 pub struct QueuedAction {
     pub action: Action,
 }
@@ -585,7 +1102,11 @@ pub fn run_init_scripts(world: SharedWorld) {
     );
 
     runs.into_par_iter()
-        .for_each(|ScriptRun { entity, script }| run_init_script(world.clone(), entity, script))
+        .for_each(|ScriptRun { entity, script }| {
+            isolate(&world, &script, None, || {
+                run_init_script(world.clone(), entity, script.clone())
+            });
+        })
 }
 
 pub fn run_pre_action_scripts(world: SharedWorld) {
@@ -600,21 +1121,29 @@ pub fn run_pre_action_scripts(world: SharedWorld) {
             .runs,
     );
 
-    runs.into_par_iter().for_each(|(action, runs)| {
-        let allowed: Vec<bool> = runs
+    // An entity's scripts run in priority order, and the first to deny the action stops the rest.
+    // The action goes ahead only if no entity's scripts deny it.
+    runs.into_par_iter().for_each(|(event, runs)| {
+        let allowed: Vec<bool> = by_entity(runs)
             .into_par_iter()
-            .map(|ScriptRun { entity, script }| {
-                run_pre_event_script(world.clone(), &action, entity, script)
+            .map(|runs| {
+                runs.into_iter().all(|ScriptRun { entity, script }| {
+                    // A script which panics denies the action, as one which errors does.
+                    isolate(&world, &script, Some(event.action.actor()), || {
+                        run_pre_event_script(world.clone(), &event, entity, script.clone())
+                    })
+                    .unwrap_or(false)
+                })
             })
             .collect();
 
         if allowed.into_iter().all(|allowed| allowed) {
             world
                 .write()
-                .unwrap()
+                .unwrap_or_else(PoisonError::into_inner)
                 .get_resource_mut::<Events<Action>>()
                 .unwrap()
-                .send(action);
+                .send(event.action);
         }
     });
 }
@@ -631,11 +1160,14 @@ pub fn run_post_action_scripts(world: SharedWorld) {
             .runs,
     );
 
-    runs.into_par_iter().for_each(|(action, runs)| {
-        runs.into_par_iter()
-            .for_each(|ScriptRun { entity, script }| {
-                run_post_event_script(world.clone(), &action, entity, script);
-            });
+    runs.into_par_iter().for_each(|(event, runs)| {
+        by_entity(runs).into_par_iter().for_each(|runs| {
+            for ScriptRun { entity, script } in runs {
+                isolate(&world, &script, Some(event.action.actor()), || {
+                    run_post_event_script(world.clone(), &event, entity, script.clone())
+                });
+            }
+        });
     });
 }
 
@@ -651,6 +1183,113 @@ pub fn run_timed_scripts(world: SharedWorld) {
             .timed_runs,
     );
 
+    by_entity(runs).into_par_iter().for_each(|runs| {
+        for ScriptRun { entity, script } in runs {
+            isolate(&world, &script, None, || {
+                run_timed_script(world.clone(), entity, script.clone())
+            });
+        }
+    })
+}
+
+// Groups consecutive runs for the same entity, which are queued in priority order. Each group runs
+// its scripts one at a time, while the groups run in parallel.
+fn by_entity(runs: Vec<ScriptRun>) -> Vec<Vec<ScriptRun>> {
+    let mut groups: Vec<Vec<ScriptRun>> = Vec::new();
+
+    for run in runs {
+        match groups.last_mut() {
+            Some(group) if group[0].entity == run.entity => group.push(run),
+            _ => groups.push(vec![run]),
+        }
+    }
+
+    groups
+}
+
+pub fn run_continuations(world: SharedWorld) {
+    let mut runs = Vec::new();
+    std::mem::swap(
+        &mut runs,
+        &mut world
+            .write()
+            .unwrap()
+            .get_resource_mut::<ScriptRuns>()
+            .unwrap()
+            .continuation_runs,
+    );
+
     runs.into_par_iter()
-        .for_each(|ScriptRun { entity, script }| run_timed_script(world.clone(), entity, script))
+        .for_each(|(ScriptRun { entity, script }, function)| {
+            isolate(&world, &script, None, || {
+                run_continuation(world.clone(), entity, script.clone(), function)
+            });
+        })
+}
+
+// Runs a script, catching a panic so that it fails like a script which errors rather than taking
+// down the engine. The player whose action triggered the script, if any, is told it failed.
+fn isolate<T>(
+    world: &SharedWorld,
+    script: &ScriptName,
+    actor: Option<Entity>,
+    run: impl FnOnce() -> T,
+) -> Option<T> {
+    match panic::catch_unwind(AssertUnwindSafe(run)) {
+        Ok(result) => Some(result),
+        Err(payload) => {
+            stats_incr("scripts.panic");
+            tracing::error!("script {} panicked: {}", script, panic_message(&*payload));
+
+            if let Some(actor) = actor {
+                report_failure(
+                    &mut world.write().unwrap_or_else(PoisonError::into_inner),
+                    [actor],
+                );
+            }
+
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::TryFrom,
+        sync::{Arc, RwLock},
+    };
+
+    use bevy_ecs::prelude::*;
+
+    use crate::world::{
+        scripting::{isolate, ScriptName},
+        types::player::Messages,
+    };
+
+    #[test]
+    fn test_isolate_reports_panic() {
+        let mut world = World::new();
+        let actor = world.spawn().insert(Messages::default()).id();
+        let bystander = world.spawn().insert(Messages::default()).id();
+        let world = Arc::new(RwLock::new(world));
+        let script = ScriptName::try_from("broken".to_string()).unwrap();
+
+        let result: Option<()> = isolate(&world, &script, Some(actor), || panic!("broken"));
+        assert!(result.is_none());
+
+        // Only the player whose action ran the script is told, and scripts keep running after
+        assert_eq!(isolate(&world, &script, Some(actor), || 1), Some(1));
+
+        let world = world.read().unwrap();
+        assert_eq!(
+            world
+                .get::<Messages>(actor)
+                .unwrap()
+                .queued()
+                .collect::<Vec<_>>(),
+            vec!["|Red1|Something went wrong.|-|"]
+        );
+        assert!(world.get::<Messages>(bystander).unwrap().is_empty());
+    }
 }
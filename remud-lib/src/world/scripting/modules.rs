@@ -1,59 +1,454 @@
-use bevy_ecs::prelude::Entity;
+use std::{str::FromStr, time::Duration};
+
+use bevy_app::Events;
+use bevy_ecs::prelude::{Entity, World};
 use rhai::plugin::*;
 
-use crate::ecs::SharedWorld;
+use crate::{
+    clock::Clock,
+    ecs::SharedWorld,
+    engine::persist::{self, UpdateGroup, Updates},
+    world::{
+        action::{communicate::CutsceneLine, immortal::object::UpdateField, Action},
+        scripting::{
+            time::{Cutscenes, TimedActions},
+            QueuedAction, ScriptName,
+        },
+        types::{
+            bank::Bank,
+            economy::{CoinSource, Economy},
+            faction::{FactionId, Factions, Reputation},
+            global::Globals,
+            loot::{LootDrop, LootTableId, LootTables},
+            object::{FieldValue, ObjectOrPrototype},
+            player::Player,
+            room::Room,
+            Id, Named,
+        },
+    },
+};
 
 #[derive(Clone)]
 pub struct Me {
     pub entity: Entity,
     pub world: SharedWorld,
+    // the script being run
+    pub script: ScriptName,
+}
+
+// Queues a change to an object or prototype's custom fields, removing the field when there is no
+// value.
+fn queue_field_update(me: &Me, target: Entity, key: String, value: Option<FieldValue>) {
+    let mut world = me.world.write().unwrap();
+
+    let id = match world.get::<Id>(target) {
+        Some(Id::Object(id)) => ObjectOrPrototype::Object(*id),
+        Some(Id::Prototype(id)) => ObjectOrPrototype::Prototype(*id),
+        _ => {
+            tracing::warn!("script requested to update a field on a non-object");
+            return;
+        }
+    };
+
+    world
+        .get_resource_mut::<Events<QueuedAction>>()
+        .unwrap()
+        .send(
+            Action::from(UpdateField {
+                actor: me.entity,
+                id,
+                key,
+                value,
+            })
+            .into(),
+        );
+}
+
+// Sets or, when there is no value, removes a global, persisting the change.
+fn store_global(world: &mut World, key: String, value: Option<FieldValue>) {
+    let mut globals = world.get_resource_mut::<Globals>().unwrap();
+    match &value {
+        Some(value) => globals.insert(key.clone(), value.clone()),
+        None => {
+            globals.remove(key.as_str());
+        }
+    }
+
+    world
+        .get_resource_mut::<Updates>()
+        .unwrap()
+        .persist(persist::global::Update::new(key, value));
+}
+
+// Adds to the coins a player is carrying, persisting the change and recording it in the economy
+// under the source. Returns false, changing nothing, when the player would be left with fewer than
+// no coins.
+fn add_coins(world: &mut World, player: Entity, source: CoinSource, amount: i64) -> bool {
+    let (id, name) = match (world.get::<Player>(player), world.get::<Named>(player)) {
+        (Some(player), Some(named)) => (player.id(), named.to_string()),
+        _ => {
+            tracing::warn!("script requested to give coins to a non-player");
+            return false;
+        }
+    };
+
+    let mut bank = world.get_resource_mut::<Bank>().unwrap();
+    let account = bank.open(id, name.as_str());
+
+    if account.coins + amount < 0 {
+        return false;
+    }
+
+    account.coins += amount;
+    let account = persist::bank::Account::new(id, account.coins, account.balance);
+
+    let now = world.get_resource::<Clock>().unwrap().unix_now();
+    let (day, flow) = world
+        .get_resource_mut::<Economy>()
+        .unwrap()
+        .record(now, source, amount);
+
+    world
+        .get_resource_mut::<Updates>()
+        .unwrap()
+        .persist(UpdateGroup::new(vec![
+            account,
+            persist::economy::Update::new(day, source, flow),
+        ]));
+
+    true
+}
+
+// Parses a faction ID from a script, returning None when the faction does not exist.
+fn script_faction(world: &World, id: &str) -> Option<FactionId> {
+    let id = match FactionId::from_str(id) {
+        Ok(id) => id,
+        Err(_) => {
+            tracing::warn!("script requested invalid faction ID {}", id);
+            return None;
+        }
+    };
+
+    if world.get_resource::<Factions>().unwrap().contains(&id) {
+        Some(id)
+    } else {
+        tracing::warn!("script requested missing faction {}", id);
+        None
+    }
+}
+
+// Adjusts a player's reputation with a faction, persisting the change. Returns the new reputation,
+// or None when the player or faction does not exist.
+fn adjust_reputation(world: &mut World, player: Entity, faction: &str, amount: i64) -> Option<i64> {
+    let faction = script_faction(world, faction)?;
+
+    let id = match world.get::<Player>(player) {
+        Some(player) => player.id(),
+        None => {
+            tracing::warn!("script requested to adjust the reputation of a non-player");
+            return None;
+        }
+    };
+
+    let reputation = world
+        .get_mut::<Reputation>(player)
+        .unwrap()
+        .adjust(&faction, amount);
+
+    world
+        .get_resource_mut::<Updates>()
+        .unwrap()
+        .persist(persist::faction::Reputation::new(id, faction, reputation));
+
+    Some(reputation)
+}
+
+// Parses a cutscene step: a string, or a map with text and an optional delay and color. The delay
+// is a duration or a number of milliseconds after the previous line.
+fn cutscene_step(step: Dynamic) -> Option<(Duration, String)> {
+    if step.is::<ImmutableString>() {
+        return Some((Duration::ZERO, step.cast::<ImmutableString>().to_string()));
+    }
+
+    let step = step.try_cast::<rhai::Map>()?;
+
+    let text = step.get("text")?.clone().try_cast::<ImmutableString>()?;
+    let delay = match step.get("delay") {
+        Some(delay) if delay.is::<Duration>() => delay.clone().cast::<Duration>(),
+        Some(delay) => Duration::from_millis(delay.as_int().ok()?.max(0) as u64),
+        None => Duration::ZERO,
+    };
+    let text = match step.get("color") {
+        Some(color) => format!(
+            "|{}|{}|-|",
+            color.clone().try_cast::<ImmutableString>()?,
+            text
+        ),
+        None => text.to_string(),
+    };
+
+    Some((delay, text))
+}
+
+// Plays a cutscene to a player, or to every player in a room. Each player's cutscene is skipped
+// when they enter a command.
+fn play_cutscene(world: &mut World, target: Entity, steps: rhai::Array, prompts: bool) {
+    let players = if world.entity(target).contains::<Player>() {
+        vec![target]
+    } else if let Some(room) = world.get::<Room>(target) {
+        room.players().to_vec()
+    } else {
+        tracing::warn!("script requested to play a cutscene to a non-player, non-room entity");
+        return;
+    };
+
+    let lines = match steps
+        .into_iter()
+        .map(cutscene_step)
+        .collect::<Option<Vec<_>>>()
+    {
+        Some(lines) => lines,
+        None => {
+            tracing::warn!("script requested to play a cutscene with an invalid step");
+            return;
+        }
+    };
+
+    for player in players {
+        let cutscene = world.get_resource_mut::<Cutscenes>().unwrap().start(player);
+
+        let mut after = Duration::ZERO;
+        let mut timed_actions = world.get_resource_mut::<TimedActions>().unwrap();
+        for (index, (delay, message)) in lines.iter().enumerate() {
+            let last = index + 1 == lines.len();
+            after += *delay;
+
+            timed_actions.send_after(
+                Action::from(CutsceneLine {
+                    actor: player,
+                    cutscene,
+                    message: message.clone(),
+                    prompt: prompts || last,
+                    last,
+                }),
+                after,
+            );
+        }
+    }
+}
+
+// Rolls a loot table, returning None when the table does not exist.
+fn roll_loot(world: &World, id: &str) -> Option<Vec<LootDrop>> {
+    let id = match LootTableId::from_str(id) {
+        Ok(id) => id,
+        Err(_) => {
+            tracing::warn!("script requested to roll invalid loot table ID {}", id);
+            return None;
+        }
+    };
+
+    match world.get_resource::<LootTables>().unwrap().get(&id) {
+        Some(table) => Some(table.roll(&mut rand::thread_rng())),
+        None => {
+            tracing::warn!("script requested to roll missing loot table {}", id);
+            None
+        }
+    }
+}
+
+// Describes drops to scripts as an array of maps with prototype, quantity, and rarity keys.
+fn drops_to_array(drops: &[LootDrop]) -> rhai::Array {
+    drops
+        .iter()
+        .map(|drop| {
+            let mut map = rhai::Map::new();
+            map.insert("prototype".into(), Dynamic::from(i64::from(drop.prototype)));
+            map.insert("quantity".into(), Dynamic::from(drop.quantity as i64));
+            map.insert("rarity".into(), Dynamic::from(drop.rarity.to_string()));
+            Dynamic::from(map)
+        })
+        .collect()
+}
+
+// Replaces the entities in a value a script keeps with their IDs, so the value still refers to the
+// same things after they are reloaded or a player logs back in.
+fn entities_to_ids(world: &World, value: Dynamic) -> Dynamic {
+    if value.is::<Entity>() {
+        let entity = value.cast::<Entity>();
+        match world.get::<Id>(entity) {
+            Some(id) => Dynamic::from(*id),
+            None => Dynamic::from(entity),
+        }
+    } else if value.is::<rhai::Array>() {
+        Dynamic::from(
+            value
+                .cast::<rhai::Array>()
+                .into_iter()
+                .map(|value| entities_to_ids(world, value))
+                .collect::<rhai::Array>(),
+        )
+    } else if value.is::<rhai::Map>() {
+        Dynamic::from(
+            value
+                .cast::<rhai::Map>()
+                .into_iter()
+                .map(|(key, value)| (key, entities_to_ids(world, value)))
+                .collect::<rhai::Map>(),
+        )
+    } else {
+        value
+    }
+}
+
+// Replaces the IDs in a value kept by a script with the entities currently loaded for them, or ()
+// for those which are not loaded, such as players who are offline.
+fn ids_to_entities(world: &World, value: Dynamic) -> Dynamic {
+    if value.is::<Id>() {
+        match value.cast::<Id>().entity(world) {
+            Some(entity) => Dynamic::from(entity),
+            None => Dynamic::UNIT,
+        }
+    } else if value.is::<rhai::Array>() {
+        Dynamic::from(
+            value
+                .cast::<rhai::Array>()
+                .into_iter()
+                .map(|value| ids_to_entities(world, value))
+                .collect::<rhai::Array>(),
+        )
+    } else if value.is::<rhai::Map>() {
+        Dynamic::from(
+            value
+                .cast::<rhai::Map>()
+                .into_iter()
+                .map(|(key, value)| (key, ids_to_entities(world, value)))
+                .collect::<rhai::Map>(),
+        )
+    } else {
+        value
+    }
 }
 
 #[export_module]
 pub mod event_api {
     use rhai::Dynamic;
 
-    use crate::world::action::{communicate::Emote, movement::Move, Action};
+    use crate::world::scripting::{ScriptEvent, TriggerEvent};
 
     #[rhai_fn(get = "actor", pure)]
-    pub fn get_actor(action_event: &mut Action) -> Dynamic {
-        Dynamic::from(action_event.actor())
+    pub fn get_actor(event: &mut ScriptEvent) -> Dynamic {
+        Dynamic::from(event.action().actor())
+    }
+
+    // The kind of event, such as "Say" or "Move", as used when attaching scripts.
+    #[rhai_fn(get = "kind", pure)]
+    pub fn get_kind(event: &mut ScriptEvent) -> Dynamic {
+        Dynamic::from(event.kind().to_string())
+    }
+
+    // All of the event's parameters as a map.
+    #[rhai_fn(get = "params", pure)]
+    pub fn get_params(event: &mut ScriptEvent) -> Dynamic {
+        Dynamic::from(event.params())
+    }
+
+    #[rhai_fn(index_get, pure)]
+    pub fn get_param(event: &mut ScriptEvent, name: &str) -> Dynamic {
+        event.param(name)
     }
 
     #[rhai_fn(get = "direction", pure)]
-    pub fn get_direction(action_event: &mut Action) -> Dynamic {
-        if let Action::Move(Move { direction, .. }) = action_event {
-            Dynamic::from(rhai::ImmutableString::from(direction.as_str()))
-        } else {
-            Dynamic::UNIT
-        }
+    pub fn get_direction(event: &mut ScriptEvent) -> Dynamic {
+        event.param("direction")
     }
 
     #[rhai_fn(get = "emote", pure)]
-    pub fn get_emote(action_event: &mut Action) -> Dynamic {
-        if let Action::Emote(Emote { emote, .. }) = action_event {
-            Dynamic::from(rhai::ImmutableString::from(emote.as_str()))
+    pub fn get_emote(event: &mut ScriptEvent) -> Dynamic {
+        if event.kind() == TriggerEvent::Emote {
+            event.param("text")
         } else {
             Dynamic::UNIT
         }
     }
 
+    #[rhai_fn(get = "from", pure)]
+    pub fn get_from(event: &mut ScriptEvent) -> Dynamic {
+        event.param("from")
+    }
+
+    #[rhai_fn(get = "item", pure)]
+    pub fn get_item(event: &mut ScriptEvent) -> Dynamic {
+        event.param("item")
+    }
+
+    #[rhai_fn(get = "keywords", pure)]
+    pub fn get_keywords(event: &mut ScriptEvent) -> Dynamic {
+        event.param("keywords")
+    }
+
+    #[rhai_fn(get = "listing", pure)]
+    pub fn get_listing(event: &mut ScriptEvent) -> Dynamic {
+        event.param("listing")
+    }
+
+    #[rhai_fn(get = "object", pure)]
+    pub fn get_object(event: &mut ScriptEvent) -> Dynamic {
+        event.param("object")
+    }
+
+    #[rhai_fn(get = "price", pure)]
+    pub fn get_price(event: &mut ScriptEvent) -> Dynamic {
+        event.param("price")
+    }
+
+    #[rhai_fn(get = "prototype", pure)]
+    pub fn get_prototype(event: &mut ScriptEvent) -> Dynamic {
+        event.param("prototype")
+    }
+
+    #[rhai_fn(get = "recipient", pure)]
+    pub fn get_recipient(event: &mut ScriptEvent) -> Dynamic {
+        event.param("recipient")
+    }
+
+    #[rhai_fn(get = "room", pure)]
+    pub fn get_room(event: &mut ScriptEvent) -> Dynamic {
+        event.param("room")
+    }
+
+    #[rhai_fn(get = "seller", pure)]
+    pub fn get_seller(event: &mut ScriptEvent) -> Dynamic {
+        event.param("seller")
+    }
+
+    #[rhai_fn(get = "speaker", pure)]
+    pub fn get_speaker(event: &mut ScriptEvent) -> Dynamic {
+        event.param("speaker")
+    }
+
+    #[rhai_fn(get = "target", pure)]
+    pub fn get_target(event: &mut ScriptEvent) -> Dynamic {
+        event.param("target")
+    }
+
+    #[rhai_fn(get = "text", pure)]
+    pub fn get_text(event: &mut ScriptEvent) -> Dynamic {
+        event.param("text")
+    }
+
+    #[rhai_fn(get = "to", pure)]
+    pub fn get_to(event: &mut ScriptEvent) -> Dynamic {
+        event.param("to")
+    }
+
     #[rhai_fn(get = "is_move", pure)]
-    pub fn get_is_move(action_event: &mut Action) -> Dynamic {
-        if matches!(action_event, Action::Move(_)) {
-            Dynamic::TRUE
-        } else {
-            Dynamic::FALSE
-        }
+    pub fn get_is_move(event: &mut ScriptEvent) -> Dynamic {
+        Dynamic::from(event.kind() == TriggerEvent::Move)
     }
 
     #[rhai_fn(get = "is_emote", pure)]
-    pub fn get_is_emote(action_event: &mut Action) -> Dynamic {
-        if matches!(action_event, Action::Emote(_)) {
-            Dynamic::TRUE
-        } else {
-            Dynamic::FALSE
-        }
+    pub fn get_is_emote(event: &mut ScriptEvent) -> Dynamic {
+        Dynamic::from(event.kind() == TriggerEvent::Emote)
     }
 }
 
@@ -72,17 +467,37 @@ pub mod time_api {
 
 #[export_module]
 pub mod world_api {
+    use std::str::FromStr;
 
+    use bevy_app::Events;
     use bevy_ecs::prelude::Entity;
     use rhai::Dynamic;
 
     use crate::{
         ecs::SharedWorld,
-        world::types::{
-            object::{Keywords, Object},
-            player::Player,
-            room::Room,
-            Contents, Description, Location, Named,
+        text::{
+            banner::{banner as draw_banner, fit_art},
+            DEFAULT_WIDTH,
+        },
+        world::{
+            action::{achievement::GrantAchievement, Action},
+            scripting::{signal::Signals, QueuedAction},
+            types::{
+                achievement::AchievementId,
+                art::Art,
+                bank::Bank,
+                campaign::{Campaign, CampaignId, Campaigns},
+                economy::CoinSource,
+                environment::Weather,
+                faction::{Reputation, Standing},
+                global::Globals,
+                grammar::{self, Pronouns, Subject},
+                object::{resolve_field, FieldValue, Keywords, Object},
+                player::{ClientCaps, ForcedCommand, ForcedCommands, Player, VisitedRooms},
+                room::Room,
+                shop::Shops,
+                Contents, Description, Id, Location, Named,
+            },
         },
     };
 
@@ -101,6 +516,186 @@ pub mod world_api {
         world.read().unwrap().entity(entity).contains::<Object>()
     }
 
+    // Fills in a message template about an actor, such as "$n scratches $s head.", using their
+    // name and pronouns. Returns () if the actor has no name.
+    #[rhai_fn(pure, name = "act")]
+    pub fn act(world: &mut SharedWorld, template: String, actor: Entity) -> Dynamic {
+        let world = world.read().unwrap();
+        match Subject::of(&world, actor) {
+            Some(actor) => Dynamic::from(grammar::act(template.as_str(), actor, None)),
+            None => Dynamic::UNIT,
+        }
+    }
+
+    // As above, with uppercase tokens such as $N and $M referring to the target.
+    #[rhai_fn(pure, name = "act")]
+    pub fn act_target(
+        world: &mut SharedWorld,
+        template: String,
+        actor: Entity,
+        target: Entity,
+    ) -> Dynamic {
+        let world = world.read().unwrap();
+        match (Subject::of(&world, actor), Subject::of(&world, target)) {
+            (Some(actor), Some(target)) => {
+                Dynamic::from(grammar::act(template.as_str(), actor, Some(target)))
+            }
+            _ => Dynamic::UNIT,
+        }
+    }
+
+    // Gives a player coins, or takes them when the amount is negative. Returns false if the player
+    // does not have enough coins to take.
+    #[rhai_fn(pure, name = "add_coins")]
+    pub fn add_coins(world: &mut SharedWorld, player: Entity, amount: i64) -> bool {
+        super::add_coins(
+            &mut world.write().unwrap(),
+            player,
+            CoinSource::Script,
+            amount,
+        )
+    }
+
+    // As above, recording the coins in the economy under a source such as "loot" or "quest".
+    #[rhai_fn(pure, name = "add_coins")]
+    pub fn add_coins_from(
+        world: &mut SharedWorld,
+        player: Entity,
+        amount: i64,
+        source: String,
+    ) -> bool {
+        let source = match CoinSource::from_str(source.as_str()) {
+            Ok(source) => source,
+            Err(_) => {
+                tracing::warn!("script requested invalid coin source {}", source);
+                return false;
+            }
+        };
+
+        super::add_coins(&mut world.write().unwrap(), player, source, amount)
+    }
+
+    // Adds to an integer global, treating a missing global as 0, and returns the new value.
+    // Scripts run concurrently, so this is the safe way to count across them.
+    #[rhai_fn(pure)]
+    pub fn add_global(world: &mut SharedWorld, key: String, amount: i64) -> Dynamic {
+        let mut world = world.write().unwrap();
+
+        let current = match world.get_resource::<Globals>().unwrap().get(key.as_str()) {
+            Some(FieldValue::Int(current)) => *current,
+            Some(_) => {
+                tracing::warn!("script requested to add to non-integer global {}", key);
+                return Dynamic::UNIT;
+            }
+            None => 0,
+        };
+
+        let value = current + amount;
+        super::store_global(&mut world, key, Some(FieldValue::Int(value)));
+
+        Dynamic::from(value)
+    }
+
+    // Adjusts a player's reputation with a faction and returns the new reputation.
+    #[rhai_fn(pure)]
+    pub fn adjust_rep(
+        world: &mut SharedWorld,
+        player: Entity,
+        faction: String,
+        amount: i64,
+    ) -> Dynamic {
+        match super::adjust_reputation(
+            &mut world.write().unwrap(),
+            player,
+            faction.as_str(),
+            amount,
+        ) {
+            Some(reputation) => Dynamic::from(reputation),
+            None => Dynamic::UNIT,
+        }
+    }
+
+    // Returns the ASCII art with the given name, or unit if there is none.
+    #[rhai_fn(pure, name = "art")]
+    pub fn art(world: &mut SharedWorld, name: String) -> Dynamic {
+        match world.read().unwrap().get_resource::<Art>() {
+            Some(art) => art
+                .get(name.as_str())
+                .map(|art| Dynamic::from(art.to_string()))
+                .unwrap_or(Dynamic::UNIT),
+            None => Dynamic::UNIT,
+        }
+    }
+
+    // As above, cutting off lines wider than the given width.
+    #[rhai_fn(pure, name = "art")]
+    pub fn art_width(world: &mut SharedWorld, name: String, width: i64) -> Dynamic {
+        match world.read().unwrap().get_resource::<Art>() {
+            Some(art) => art
+                .get(name.as_str())
+                .map(|art| Dynamic::from(fit_art(art, width.max(0) as usize)))
+                .unwrap_or(Dynamic::UNIT),
+            None => Dynamic::UNIT,
+        }
+    }
+
+    #[rhai_fn(pure)]
+    pub fn bank_balance(world: &mut SharedWorld, player: Entity) -> Dynamic {
+        let world = world.read().unwrap();
+
+        match world.get::<Player>(player) {
+            Some(player) => Dynamic::from(
+                world
+                    .get_resource::<Bank>()
+                    .unwrap()
+                    .get(player.id())
+                    .map(|account| account.balance)
+                    .unwrap_or_default(),
+            ),
+            None => Dynamic::UNIT,
+        }
+    }
+
+    // Returns what a player's client supports, such as its window width and whether it
+    // understands MXP links or GMCP.
+    // Draws text in block letters, fitted to the default window width.
+    #[rhai_fn(pure, name = "banner")]
+    pub fn banner(_world: &mut SharedWorld, text: String) -> String {
+        draw_banner(text.as_str(), DEFAULT_WIDTH)
+    }
+
+    // As above, fitted to the given width.
+    #[rhai_fn(pure, name = "banner")]
+    pub fn banner_width(_world: &mut SharedWorld, text: String, width: i64) -> String {
+        draw_banner(text.as_str(), width.max(0) as usize)
+    }
+
+    #[rhai_fn(pure)]
+    pub fn client_caps(world: &mut SharedWorld, player: Entity) -> Dynamic {
+        if let Some(caps) = world.read().unwrap().get::<ClientCaps>(player) {
+            Dynamic::from(caps.as_map())
+        } else {
+            Dynamic::UNIT
+        }
+    }
+
+    #[rhai_fn(pure)]
+    pub fn coins(world: &mut SharedWorld, player: Entity) -> Dynamic {
+        let world = world.read().unwrap();
+
+        match world.get::<Player>(player) {
+            Some(player) => Dynamic::from(
+                world
+                    .get_resource::<Bank>()
+                    .unwrap()
+                    .get(player.id())
+                    .map(|account| account.coins)
+                    .unwrap_or_default(),
+            ),
+            None => Dynamic::UNIT,
+        }
+    }
+
     #[rhai_fn(pure)]
     pub fn contains(world: &mut SharedWorld, container: Entity, object: Entity) -> Dynamic {
         if let Some(contents) = world.read().unwrap().get::<Contents>(container) {
@@ -128,6 +723,154 @@ pub mod world_api {
         }
     }
 
+    // Returns the number of the running stage of an event, counting from 1.
+    // Runs the scripts attached to the named signal with attach-custom at the start of the next
+    // tick. They can read the data with SELF.get(signal).
+    #[rhai_fn(pure, name = "emit")]
+    pub fn emit(world: &mut SharedWorld, signal: String) {
+        emit_data(world, signal, Dynamic::UNIT)
+    }
+
+    #[rhai_fn(pure, name = "emit")]
+    pub fn emit_data(world: &mut SharedWorld, signal: String, data: Dynamic) {
+        let mut world = world.write().unwrap();
+        let data = super::entities_to_ids(&world, data);
+        world
+            .get_resource_mut::<Signals>()
+            .unwrap()
+            .emit(signal, data);
+    }
+
+    #[rhai_fn(pure)]
+    pub fn event_stage(world: &mut SharedWorld, id: String) -> Dynamic {
+        let id = match CampaignId::from_str(id.as_str()) {
+            Ok(id) => id,
+            Err(_) => {
+                tracing::warn!("script requested invalid event ID {}", id);
+                return Dynamic::UNIT;
+            }
+        };
+
+        match world
+            .read()
+            .unwrap()
+            .get_resource::<Campaigns>()
+            .unwrap()
+            .get(&id)
+            .and_then(Campaign::stage)
+        {
+            Some(stage) => Dynamic::from(stage as i64 + 1),
+            None => Dynamic::UNIT,
+        }
+    }
+
+    #[rhai_fn(pure)]
+    pub fn field(world: &mut SharedWorld, entity: Entity, key: String) -> Dynamic {
+        match resolve_field(&world.read().unwrap(), entity, key.as_str()) {
+            Some(value) => value.to_dynamic(),
+            None => Dynamic::UNIT,
+        }
+    }
+
+    // Runs a command as if the player had typed it. Immortal commands are never run, even for
+    // immortals. Returns false if the entity is not a player.
+    #[rhai_fn(pure)]
+    pub fn force(world: &mut SharedWorld, player: Entity, command: String) -> bool {
+        let mut world = world.write().unwrap();
+
+        if !world.entity(player).contains::<Player>() {
+            return false;
+        }
+
+        world
+            .get_resource_mut::<ForcedCommands>()
+            .unwrap()
+            .push(ForcedCommand {
+                player,
+                command,
+                privileged: false,
+            });
+
+        true
+    }
+
+    #[rhai_fn(pure)]
+    pub fn get_global(world: &mut SharedWorld, key: String) -> Dynamic {
+        match world
+            .read()
+            .unwrap()
+            .get_resource::<Globals>()
+            .unwrap()
+            .get(key.as_str())
+        {
+            Some(value) => value.to_dynamic(),
+            None => Dynamic::UNIT,
+        }
+    }
+
+    #[rhai_fn(pure)]
+    pub fn grant_achievement(world: &mut SharedWorld, player: Entity, id: String) {
+        let id = match AchievementId::from_str(id.as_str()) {
+            Ok(id) => id,
+            Err(_) => {
+                tracing::warn!("script requested to grant invalid achievement ID {}", id);
+                return;
+            }
+        };
+
+        let mut world = world.write().unwrap();
+
+        if !world.entity(player).contains::<Player>() {
+            tracing::warn!("script requested to grant an achievement to a non-player");
+            return;
+        }
+
+        world
+            .get_resource_mut::<Events<QueuedAction>>()
+            .unwrap()
+            .send(Action::from(GrantAchievement { actor: player, id }).into());
+    }
+
+    // Whether a player's standing with a faction is at least the given standing, for gating
+    // dialog, shops, and the like.
+    #[rhai_fn(pure)]
+    pub fn has_standing(
+        world: &mut SharedWorld,
+        player: Entity,
+        faction: String,
+        standing: String,
+    ) -> Dynamic {
+        let world = world.read().unwrap();
+
+        let standing = match Standing::from_str(standing.as_str()) {
+            Ok(standing) => standing,
+            Err(_) => {
+                tracing::warn!("script requested invalid standing {}", standing);
+                return Dynamic::UNIT;
+            }
+        };
+
+        match (
+            super::script_faction(&world, faction.as_str()),
+            world.get::<Reputation>(player),
+        ) {
+            (Some(faction), Some(reputation)) => {
+                Dynamic::from(reputation.standing(&faction) >= standing)
+            }
+            _ => Dynamic::UNIT,
+        }
+    }
+
+    #[rhai_fn(pure)]
+    pub fn has_visited(world: &mut SharedWorld, player: Entity, room: Entity) -> Dynamic {
+        let world = world.read().unwrap();
+
+        match (world.get::<VisitedRooms>(player), world.get::<Room>(room)) {
+            (Some(visited), Some(room)) => Dynamic::from(visited.contains(&room.id())),
+            _ => Dynamic::UNIT,
+        }
+    }
+
     #[rhai_fn(pure)]
     pub fn keywords(world: &mut SharedWorld, entity: Entity) -> Dynamic {
         if let Some(keywords) = world.read().unwrap().get::<Keywords>(entity) {
@@ -155,6 +898,31 @@ pub mod world_api {
         }
     }
 
+    // Takes a percentage off the next purchase a player makes in the shop they are standing in,
+    // for pre-event Haggle scripts. Returns false if the player is not in a shop.
+    #[rhai_fn(pure)]
+    pub fn offer_discount(world: &mut SharedWorld, player: Entity, percent: i64) -> bool {
+        let mut world = world.write().unwrap();
+
+        let (player, room) = match (
+            world.get::<Player>(player),
+            world
+                .get::<Location>(player)
+                .and_then(|location| world.get::<Room>(location.entity())),
+        ) {
+            (Some(player), Some(room)) => (player.id(), room.id()),
+            _ => return false,
+        };
+
+        match world.get_resource_mut::<Shops>().unwrap().by_room_mut(room) {
+            Some((_, shop)) => {
+                shop.offer_discount(player, percent.clamp(0, 100) as u32);
+                true
+            }
+            None => false,
+        }
+    }
+
     #[rhai_fn(pure)]
     pub fn players(world: &mut SharedWorld, entity: Entity) -> Dynamic {
         if let Some(room) = world.read().unwrap().get::<Room>(entity) {
@@ -164,6 +932,100 @@ pub mod world_api {
         }
     }
 
+    // Returns an entity's subject pronoun: "he", "she", "they", or "it".
+    #[rhai_fn(pure)]
+    pub fn pronouns(world: &mut SharedWorld, entity: Entity) -> String {
+        world
+            .read()
+            .unwrap()
+            .get::<Pronouns>(entity)
+            .copied()
+            .unwrap_or(Pronouns::It)
+            .as_str()
+            .to_string()
+    }
+
+    #[rhai_fn(pure)]
+    pub fn remove_global(world: &mut SharedWorld, key: String) {
+        super::store_global(&mut world.write().unwrap(), key, None);
+    }
+
+    // Rolls a loot table, returning what it would drop without creating anything.
+    #[rhai_fn(pure)]
+    pub fn reputation(world: &mut SharedWorld, player: Entity, faction: String) -> Dynamic {
+        let world = world.read().unwrap();
+
+        match (
+            super::script_faction(&world, faction.as_str()),
+            world.get::<Reputation>(player),
+        ) {
+            (Some(faction), Some(reputation)) => Dynamic::from(reputation.get(&faction)),
+            _ => Dynamic::UNIT,
+        }
+    }
+
+    #[rhai_fn(pure)]
+    pub fn roll_loot(world: &mut SharedWorld, id: String) -> Dynamic {
+        match super::roll_loot(&world.read().unwrap(), id.as_str()) {
+            Some(drops) => Dynamic::from(super::drops_to_array(drops.as_slice())),
+            None => Dynamic::UNIT,
+        }
+    }
+
+    #[rhai_fn(pure)]
+    pub fn set_global(world: &mut SharedWorld, key: String, value: Dynamic) {
+        let value = match FieldValue::try_from(value) {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!("script requested to set global {}: {}", key, e);
+                return;
+            }
+        };
+
+        super::store_global(&mut world.write().unwrap(), key, Some(value));
+    }
+
+    #[rhai_fn(pure)]
+    pub fn set_weather(world: &mut SharedWorld, region: String, weather: String) {
+        let mut world = world.write().unwrap();
+        let mut current = world.get_resource_mut::<Weather>().unwrap();
+
+        if weather.is_empty() {
+            current.clear(region.as_str());
+        } else {
+            current.set(region, weather);
+        }
+    }
+
+    #[rhai_fn(pure)]
+    pub fn standing(world: &mut SharedWorld, player: Entity, faction: String) -> Dynamic {
+        let world = world.read().unwrap();
+
+        match (
+            super::script_faction(&world, faction.as_str()),
+            world.get::<Reputation>(player),
+        ) {
+            (Some(faction), Some(reputation)) => {
+                Dynamic::from(reputation.standing(&faction).to_string())
+            }
+            _ => Dynamic::UNIT,
+        }
+    }
+
+    #[rhai_fn(pure)]
+    pub fn weather(world: &mut SharedWorld, region: String) -> Dynamic {
+        match world
+            .read()
+            .unwrap()
+            .get_resource::<Weather>()
+            .unwrap()
+            .get(region.as_str())
+        {
+            Some(weather) => Dynamic::from(weather.to_string()),
+            None => Dynamic::UNIT,
+        }
+    }
+
     #[rhai_fn(pure, name = "!=")]
     pub fn entity_ne(a: &mut Entity, b: Entity) -> bool {
         *a != b
@@ -173,6 +1035,56 @@ pub mod world_api {
     pub fn entity_eq(a: &mut Entity, b: Entity) -> bool {
         *a == b
     }
+
+    // The stable ID of a player, prototype, object, or room, which outlives the entity.
+    #[rhai_fn(pure)]
+    pub fn id(world: &mut SharedWorld, entity: Entity) -> Dynamic {
+        match world.read().unwrap().get::<Id>(entity) {
+            Some(id) => Dynamic::from(*id),
+            None => Dynamic::UNIT,
+        }
+    }
+
+    // The entity currently loaded for an ID, or () if there is none.
+    #[rhai_fn(pure, name = "entity")]
+    pub fn entity(world: &mut SharedWorld, id: Id) -> Dynamic {
+        match id.entity(&world.read().unwrap()) {
+            Some(entity) => Dynamic::from(entity),
+            None => Dynamic::UNIT,
+        }
+    }
+
+    // Looks up an ID kept as text, such as "player 3".
+    #[rhai_fn(pure, name = "entity")]
+    pub fn entity_by_text(world: &mut SharedWorld, id: String) -> Dynamic {
+        match Id::from_str(id.as_str()) {
+            Ok(id) => entity(world, id),
+            Err(_) => {
+                tracing::warn!("script requested entity for invalid ID {}", id);
+                Dynamic::UNIT
+            }
+        }
+    }
+
+    #[rhai_fn(pure, name = "!=")]
+    pub fn id_ne(a: &mut Id, b: Id) -> bool {
+        *a != b
+    }
+
+    #[rhai_fn(pure, name = "==")]
+    pub fn id_eq(a: &mut Id, b: Id) -> bool {
+        *a == b
+    }
+
+    #[rhai_fn(pure, name = "to_string")]
+    pub fn id_to_string(id: &mut Id) -> String {
+        id.to_string()
+    }
+
+    #[rhai_fn(pure, name = "to_debug")]
+    pub fn id_to_debug(id: &mut Id) -> String {
+        id.to_string()
+    }
 }
 
 #[export_module]
@@ -182,10 +1094,12 @@ pub mod self_api {
     use bevy_app::Events;
     use rhai::{Dynamic, ImmutableString};
 
+    use crate::ecs::SharedWorld;
     use crate::world::{
         action::{
             communicate::{Emote, Message, Say, SendMessage, Whisper},
             immortal::{
+                campaign::{CampaignProgress, CampaignStep},
                 object::{ObjectCreate, ObjectRemove, UpdateKeywords},
                 UpdateDescription, UpdateName,
             },
@@ -193,22 +1107,77 @@ pub mod self_api {
         },
         fsm::{StateMachineBuilder, StateMachines},
         scripting::{
+            http::HttpRequests,
             modules::Me,
-            time::{TimedActions, Timers},
+            time::{Continuations, TimedActions, Timers},
             QueuedAction, ScriptData,
         },
-        types::object::{Object, ObjectOrPrototype, PrototypeId},
+        types::campaign::CampaignId,
+        types::object::{FieldValue, Object, ObjectOrPrototype, PrototypeId},
         types::{ActionTarget, Id},
     };
     use bevy_ecs::prelude::Entity;
     use itertools::Itertools;
-    use std::convert::TryFrom;
+    use std::{convert::TryFrom, str::FromStr};
 
     #[rhai_fn(pure, get = "entity")]
     pub fn get_entity(me: &mut Me) -> Entity {
         me.entity
     }
 
+    #[rhai_fn(pure, get = "world")]
+    pub fn get_world(me: &mut Me) -> SharedWorld {
+        me.world.clone()
+    }
+
+    // Moves a running event on to its next stage, such as when a stage's goal has been met.
+    #[rhai_fn(pure)]
+    pub fn advance_event(me: &mut Me, id: String) {
+        match CampaignId::from_str(id.as_str()) {
+            Ok(id) => me
+                .world
+                .write()
+                .unwrap()
+                .get_resource_mut::<Events<QueuedAction>>()
+                .unwrap()
+                .send(
+                    Action::from(CampaignProgress {
+                        actor: me.entity,
+                        id,
+                        step: CampaignStep::Advance,
+                    })
+                    .into(),
+                ),
+            Err(_) => tracing::warn!("script requested to advance invalid event ID {}", id),
+        }
+    }
+
+    // Rolls a loot table and creates what it drops in the room, returning the drops.
+    #[rhai_fn(pure)]
+    pub fn drop_loot(me: &mut Me, id: String) -> Dynamic {
+        let mut world = me.world.write().unwrap();
+
+        let drops = match super::roll_loot(&world, id.as_str()) {
+            Some(drops) => drops,
+            None => return Dynamic::UNIT,
+        };
+
+        let mut events = world.get_resource_mut::<Events<QueuedAction>>().unwrap();
+        for drop in drops.iter() {
+            for _ in 0..drop.quantity {
+                events.send(
+                    Action::from(ObjectCreate {
+                        actor: me.entity,
+                        prototype_id: drop.prototype,
+                    })
+                    .into(),
+                );
+            }
+        }
+
+        Dynamic::from(super::drops_to_array(drops.as_slice()))
+    }
+
     #[rhai_fn(pure)]
     pub fn emote(me: &mut Me, emote: String) {
         me.world
@@ -243,13 +1212,24 @@ pub mod self_api {
 
     #[rhai_fn(pure)]
     pub fn get(me: &mut Me, key: ImmutableString) -> Dynamic {
-        if let Some(data) = me.world.read().unwrap().get::<ScriptData>(me.entity) {
-            data.get(key)
+        let world = me.world.read().unwrap();
+        if let Some(data) = world.get::<ScriptData>(me.entity) {
+            super::ids_to_entities(&world, data.get(key))
         } else {
             Dynamic::UNIT
         }
     }
 
+    #[rhai_fn(pure)]
+    pub fn http_get(me: &mut Me, url: String, callback: String) {
+        me.world
+            .write()
+            .unwrap()
+            .get_resource_mut::<HttpRequests>()
+            .unwrap()
+            .request(me.entity, url.as_str(), callback);
+    }
+
     #[rhai_fn(pure)]
     pub fn message(me: &mut Me, message: String) {
         me.world
@@ -266,6 +1246,18 @@ pub mod self_api {
             );
     }
 
+    // Plays a timed sequence of lines to a player, or to everyone in a room.
+    #[rhai_fn(pure, name = "play_cutscene")]
+    pub fn play_cutscene(me: &mut Me, target: Entity, steps: rhai::Array) {
+        super::play_cutscene(&mut me.world.write().unwrap(), target, steps, true);
+    }
+
+    // As above, but only prompting after the last line when prompts is false.
+    #[rhai_fn(pure, name = "play_cutscene")]
+    pub fn play_cutscene_prompts(me: &mut Me, target: Entity, steps: rhai::Array, prompts: bool) {
+        super::play_cutscene(&mut me.world.write().unwrap(), target, steps, prompts);
+    }
+
     #[rhai_fn(pure)]
     pub fn message_after(me: &mut Me, duration: Duration, message: String) {
         me.world
@@ -313,8 +1305,10 @@ pub mod self_api {
 
     #[rhai_fn(pure)]
     pub fn remove(me: &mut Me, key: ImmutableString) -> Dynamic {
-        if let Some(mut data) = me.world.write().unwrap().get_mut::<ScriptData>(me.entity) {
-            data.remove(key)
+        let mut world = me.world.write().unwrap();
+        if let Some(mut data) = world.get_mut::<ScriptData>(me.entity) {
+            let value = data.remove(key);
+            super::ids_to_entities(&world, value)
         } else {
             tracing::info!("script data not found, returning unit");
             Dynamic::UNIT
@@ -390,6 +1384,7 @@ pub mod self_api {
     #[rhai_fn(pure)]
     pub fn set(me: &mut Me, key: ImmutableString, value: Dynamic) {
         let mut world = me.world.write().unwrap();
+        let value = super::entities_to_ids(&world, value);
         if let Some(mut data) = world.get_mut::<ScriptData>(me.entity) {
             data.insert(key, value)
         } else {
@@ -447,6 +1442,19 @@ pub mod self_api {
             );
     }
 
+    #[rhai_fn(pure)]
+    pub fn remove_field(me: &mut Me, target: Entity, key: String) {
+        super::queue_field_update(me, target, key, None);
+    }
+
+    #[rhai_fn(pure)]
+    pub fn set_field(me: &mut Me, target: Entity, key: String, value: Dynamic) {
+        match FieldValue::try_from(value) {
+            Ok(value) => super::queue_field_update(me, target, key, Some(value)),
+            Err(e) => tracing::warn!("script requested to set field {}: {}", key, e),
+        }
+    }
+
     #[rhai_fn(pure)]
     pub fn set_keywords(me: &mut Me, target: Entity, keywords: String) {
         let mut world = me.world.write().unwrap();
@@ -542,6 +1550,16 @@ pub mod self_api {
         }
     }
 
+    #[rhai_fn(pure)]
+    pub fn wait(me: &mut Me, duration: Duration, function: String) {
+        me.world
+            .write()
+            .unwrap()
+            .get_resource_mut::<Continuations>()
+            .unwrap()
+            .resume_after(me.entity, me.script.clone(), function, duration);
+    }
+
     #[rhai_fn(pure)]
     pub fn whisper(me: &mut Me, target: Entity, message: String) {
         me.world
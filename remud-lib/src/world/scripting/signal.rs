@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use bevy_ecs::prelude::*;
+use rhai::Dynamic;
+
+use crate::world::{
+    scripting::{ScriptData, ScriptHooks, ScriptRun, ScriptRuns, ScriptTrigger},
+    types::{
+        object::Prototype,
+        season::{Seasonal, Seasons},
+    },
+};
+
+/// Signals emitted by scripts, delivered to the scripts hooked to them at the start of the next
+/// tick.
+#[derive(Default)]
+pub struct Signals {
+    pending: Vec<(String, Dynamic)>,
+}
+
+impl Signals {
+    pub fn emit(&mut self, name: String, data: Dynamic) {
+        self.pending.push((name, data));
+    }
+}
+
+#[tracing::instrument(name = "signals system", skip_all)]
+pub fn signals_system(
+    mut commands: Commands,
+    mut signals: ResMut<Signals>,
+    seasons: Res<Seasons>,
+    mut script_runs: ResMut<ScriptRuns>,
+    mut data_query: Query<&mut ScriptData>,
+    hooks_query: Query<(Entity, &ScriptHooks), Without<Prototype>>,
+) {
+    // Several signals may be delivered to an entity without script data, which must be gathered
+    // into a single component.
+    let mut new_data: HashMap<Entity, ScriptData> = HashMap::new();
+
+    for (name, data) in signals.pending.drain(..) {
+        for (entity, hooks) in hooks_query.iter() {
+            let scripts = hooks
+                .by_trigger(ScriptTrigger::Custom(name.clone()))
+                .into_iter()
+                .filter(|script| seasons.is_active(&Seasonal::Script(script.clone())))
+                .collect::<Vec<_>>();
+            if scripts.is_empty() {
+                continue;
+            }
+
+            if let Ok(mut script_data) = data_query.get_mut(entity) {
+                script_data.insert(name.as_str().into(), data.clone());
+            } else {
+                new_data
+                    .entry(entity)
+                    .or_default()
+                    .insert(name.as_str().into(), data.clone());
+            }
+
+            for script in scripts {
+                script_runs.timed_runs.push(ScriptRun { entity, script });
+            }
+        }
+    }
+
+    for (entity, data) in new_data {
+        commands.entity(entity).insert(data);
+    }
+}
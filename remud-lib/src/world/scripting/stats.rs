@@ -0,0 +1,144 @@
+use std::{collections::HashMap, time::Duration};
+
+use crate::world::scripting::ScriptName;
+
+const DEFAULT_SLOW_THRESHOLD: Duration = Duration::from_millis(5);
+
+/// Run counts and timings for each script since the server started or the script was last
+/// changed.
+pub struct ScriptStats {
+    stats: HashMap<ScriptName, ScriptStat>,
+    slow_threshold: Duration,
+}
+
+impl ScriptStats {
+    pub fn set_slow_threshold(&mut self, threshold: Duration) {
+        self.slow_threshold = threshold;
+        for stat in self.stats.values_mut() {
+            stat.slow = stat.mean() > threshold;
+        }
+    }
+
+    pub fn slow_threshold(&self) -> Duration {
+        self.slow_threshold
+    }
+
+    /// Records a run of a script, warning once when its average runtime grows past the slow
+    /// threshold.
+    pub fn record(&mut self, script: &ScriptName, elapsed: Duration, error: bool) {
+        let stat = self.stats.entry(script.clone()).or_default();
+
+        stat.runs += 1;
+        stat.total += elapsed;
+        if error {
+            stat.errors += 1;
+        }
+
+        let slow = stat.mean() > self.slow_threshold;
+        if slow && !stat.slow {
+            tracing::warn!(
+                "script {} is slow, averaging {:?} over {} runs",
+                script,
+                stat.mean(),
+                stat.runs
+            );
+        }
+        stat.slow = slow;
+    }
+
+    pub fn remove(&mut self, script: &ScriptName) {
+        self.stats.remove(script);
+    }
+
+    /// Lists the scripts which have run, those taking the most time in total first.
+    pub fn by_total(&self) -> Vec<(ScriptName, ScriptStat)> {
+        let mut stats = self
+            .stats
+            .iter()
+            .map(|(name, stat)| (name.clone(), stat.clone()))
+            .collect::<Vec<_>>();
+        stats.sort_by(|(a_name, a), (b_name, b)| {
+            b.total.cmp(&a.total).then_with(|| a_name.cmp(b_name))
+        });
+        stats
+    }
+}
+
+impl Default for ScriptStats {
+    fn default() -> Self {
+        ScriptStats {
+            stats: HashMap::new(),
+            slow_threshold: DEFAULT_SLOW_THRESHOLD,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ScriptStat {
+    runs: u64,
+    errors: u64,
+    total: Duration,
+    // whether the average runtime is over the slow threshold
+    slow: bool,
+}
+
+impl ScriptStat {
+    pub fn runs(&self) -> u64 {
+        self.runs
+    }
+
+    pub fn errors(&self) -> u64 {
+        self.errors
+    }
+
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    pub fn mean(&self) -> Duration {
+        if self.runs == 0 {
+            Duration::ZERO
+        } else {
+            self.total / self.runs as u32
+        }
+    }
+
+    pub fn slow(&self) -> bool {
+        self.slow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::TryFrom, time::Duration};
+
+    use super::ScriptStats;
+    use crate::world::scripting::ScriptName;
+
+    #[test]
+    fn test_record() {
+        let fast = ScriptName::try_from("fast".to_string()).unwrap();
+        let slow = ScriptName::try_from("slow".to_string()).unwrap();
+
+        let mut stats = ScriptStats::default();
+        stats.set_slow_threshold(Duration::from_millis(10));
+
+        stats.record(&fast, Duration::from_millis(1), false);
+        stats.record(&fast, Duration::from_millis(3), true);
+        stats.record(&slow, Duration::from_millis(30), false);
+
+        let by_total = stats.by_total();
+        assert_eq!(by_total[0].0, slow);
+        assert!(by_total[0].1.slow());
+
+        let (_, stat) = &by_total[1];
+        assert_eq!(stat.runs(), 2);
+        assert_eq!(stat.errors(), 1);
+        assert_eq!(stat.total(), Duration::from_millis(4));
+        assert_eq!(stat.mean(), Duration::from_millis(2));
+        assert!(!stat.slow());
+
+        stats.set_slow_threshold(Duration::from_millis(1));
+        assert!(stats.by_total()[1].1.slow());
+    }
+}
@@ -1,20 +1,44 @@
 use bevy_app::{EventReader, EventWriter};
 use bevy_ecs::prelude::*;
 
+use rhai::Dynamic;
+
 use crate::world::{
-    action::Action,
+    action::{
+        communicate::{Emote, Say, SendMessage},
+        market::{MarketBuy, MarketSell},
+        movement::Move,
+        object::{Drink, Drop, Eat, Get, Use},
+        observe::{Look, LookAt},
+        shop::ShopHaggle,
+        targeting::{Params, TargetFinder},
+        Action,
+    },
     scripting::{
-        time::Timers, QueuedAction, RunInitScript, ScriptHooks, ScriptRun, ScriptRuns,
+        time::Timers, QueuedAction, RunInitScript, ScriptEvent, ScriptHooks, ScriptRun, ScriptRuns,
         ScriptTrigger, TriggerEvent,
     },
-    types::{room::Room, Contents, Location},
+    types::{
+        market::Market,
+        player::Player,
+        room::Room,
+        season::{Seasonal, Seasons},
+        shop::Shops,
+        Contents, Location,
+    },
 };
 
+#[tracing::instrument(name = "init script runs system", skip_all)]
 pub fn init_script_runs_system(
     mut init_reader: EventReader<RunInitScript>,
+    seasons: Res<Seasons>,
     mut script_runs: ResMut<ScriptRuns>,
 ) {
     for RunInitScript { entity, script } in init_reader.iter() {
+        if !seasons.is_active(&Seasonal::Script(script.clone())) {
+            continue;
+        }
+
         script_runs.init_runs.push(ScriptRun {
             entity: *entity,
             script: script.clone(),
@@ -22,19 +46,26 @@ pub fn init_script_runs_system(
     }
 }
 
+#[tracing::instrument(name = "timed script runs system", skip_all)]
 pub fn timed_script_runs_system(
     mut timers_query: Query<(Entity, &mut Timers, &ScriptHooks)>,
+    seasons: Res<Seasons>,
     mut script_runs: ResMut<ScriptRuns>,
 ) {
     for (entity, mut timers, hooks) in timers_query.iter_mut() {
         for name in timers.list_finished() {
-            for script in hooks.by_trigger(ScriptTrigger::Timer(name)) {
+            for script in hooks
+                .by_trigger(ScriptTrigger::Timer(name))
+                .into_iter()
+                .filter(|script| seasons.is_active(&Seasonal::Script(script.clone())))
+            {
                 script_runs.timed_runs.push(ScriptRun { entity, script })
             }
         }
     }
 }
 
+#[tracing::instrument(name = "pre-event script runs system", skip_all)]
 pub fn pre_event_script_runs_system(
     mut queued_action_reader: EventReader<QueuedAction>,
     mut action_writer: EventWriter<Action>,
@@ -43,6 +74,11 @@ pub fn pre_event_script_runs_system(
     location_query: Query<&Location>,
     contents_query: Query<&Contents>,
     hooks_query: Query<&ScriptHooks>,
+    target_finder: TargetFinder,
+    market: Res<Market>,
+    shops: Res<Shops>,
+    seasons: Res<Seasons>,
+    player_query: Query<&Player>,
 ) {
     for QueuedAction { action } in queued_action_reader.iter() {
         let trigger_event = match TriggerEvent::from_action(action) {
@@ -65,16 +101,28 @@ pub fn pre_event_script_runs_system(
             &hooks_query,
             &contents_query,
             &room_query,
+            &seasons,
         );
 
         if runs.is_empty() {
             action_writer.send(action.clone());
         } else {
-            script_runs.runs.push((action.clone(), runs));
+            let params = event_params(
+                action,
+                room,
+                &room_query,
+                &target_finder,
+                &market,
+                &shops,
+                &player_query,
+            );
+            let event = ScriptEvent::new(action.clone(), trigger_event, params);
+            script_runs.runs.push((event, runs));
         }
     }
 }
 
+#[tracing::instrument(name = "post-action script runs system", skip_all)]
 pub fn post_action_script_runs_system(
     mut queued_action_reader: EventReader<QueuedAction>,
     mut script_runs: ResMut<ScriptRuns>,
@@ -82,6 +130,11 @@ pub fn post_action_script_runs_system(
     location_query: Query<&Location>,
     contents_query: Query<&Contents>,
     hooks_query: Query<&ScriptHooks>,
+    target_finder: TargetFinder,
+    market: Res<Market>,
+    shops: Res<Shops>,
+    seasons: Res<Seasons>,
+    player_query: Query<&Player>,
 ) {
     for QueuedAction { action } in queued_action_reader.iter() {
         let trigger_event = match TriggerEvent::from_action(action) {
@@ -99,12 +152,191 @@ pub fn post_action_script_runs_system(
             &hooks_query,
             &contents_query,
             &room_query,
+            &seasons,
         );
 
         if !runs.is_empty() {
-            script_runs.runs.push((action.clone(), runs));
+            let params = event_params(
+                action,
+                room,
+                &room_query,
+                &target_finder,
+                &market,
+                &shops,
+                &player_query,
+            );
+            let event = ScriptEvent::new(action.clone(), trigger_event, params);
+            script_runs.runs.push((event, runs));
+        }
+    }
+}
+
+// Resolves the parameters scripts see for an action, such as the object being picked up, before
+// the action takes effect.
+fn event_params(
+    action: &Action,
+    room: Entity,
+    room_query: &Query<&Room>,
+    target_finder: &TargetFinder,
+    market: &Market,
+    shops: &Shops,
+    player_query: &Query<&Player>,
+) -> rhai::Map {
+    let mut params = rhai::Map::new();
+
+    let exit = |direction| {
+        room_query
+            .get(room)
+            .ok()
+            .and_then(|room| room.exit(direction))
+            .map_or(Dynamic::UNIT, Dynamic::from)
+    };
+    let object = |keywords: &[String], container| {
+        target_finder
+            .resolve_object(keywords, container)
+            .map_or(Dynamic::UNIT, |target| Dynamic::from(target.entity))
+    };
+    let keywords = |keywords: &[String]| {
+        Dynamic::from(
+            keywords
+                .iter()
+                .map(|keyword| Dynamic::from(keyword.clone()))
+                .collect::<rhai::Array>(),
+        )
+    };
+
+    match action {
+        Action::Drink(Drink {
+            actor,
+            keywords: words,
+        })
+        | Action::Drop(Drop {
+            actor,
+            keywords: words,
+        })
+        | Action::Eat(Eat {
+            actor,
+            keywords: words,
+        }) => {
+            params.insert("object".into(), object(words, *actor));
+            params.insert("keywords".into(), keywords(words));
+        }
+        Action::Emote(Emote { emote, .. }) => {
+            params.insert("text".into(), Dynamic::from(emote.clone()));
         }
+        Action::Exits(_) => {
+            params.insert("room".into(), Dynamic::from(room));
+        }
+        Action::Get(Get {
+            keywords: words, ..
+        }) => {
+            params.insert("object".into(), object(words, room));
+            params.insert("keywords".into(), keywords(words));
+        }
+        Action::Look(Look { direction, .. }) => match direction {
+            Some(direction) => {
+                params.insert("room".into(), exit(direction));
+                params.insert(
+                    "direction".into(),
+                    Dynamic::from(direction.as_str().to_string()),
+                );
+            }
+            None => {
+                params.insert("room".into(), Dynamic::from(room));
+                params.insert("direction".into(), Dynamic::UNIT);
+            }
+        },
+        Action::LookAt(LookAt {
+            actor,
+            keywords: words,
+        }) => {
+            let target = target_finder
+                .resolve(Params::new(*actor, room, Some(words.clone())))
+                .map_or(Dynamic::UNIT, |target| Dynamic::from(target.entity));
+            params.insert("target".into(), target);
+            params.insert("keywords".into(), keywords(words));
+        }
+        Action::MarketBuy(MarketBuy { id, .. }) => {
+            let listing = market.get(*id);
+            params.insert(
+                "object".into(),
+                listing.map_or(Dynamic::UNIT, |listing| Dynamic::from(listing.object)),
+            );
+            params.insert("listing".into(), Dynamic::from(i64::from(*id)));
+            params.insert(
+                "price".into(),
+                listing.map_or(Dynamic::UNIT, |listing| Dynamic::from(listing.price)),
+            );
+            params.insert(
+                "seller".into(),
+                listing.map_or(Dynamic::UNIT, |listing| {
+                    Dynamic::from(listing.seller_name.clone())
+                }),
+            );
+        }
+        Action::MarketSell(MarketSell {
+            actor,
+            price,
+            keywords: words,
+        }) => {
+            params.insert("object".into(), object(words, *actor));
+            params.insert("keywords".into(), keywords(words));
+            params.insert("price".into(), Dynamic::from(*price));
+        }
+        Action::Move(Move { direction, .. }) => {
+            params.insert(
+                "direction".into(),
+                Dynamic::from(direction.as_str().to_string()),
+            );
+            params.insert("from".into(), Dynamic::from(room));
+            params.insert("to".into(), exit(direction));
+        }
+        Action::Say(Say { actor, message }) => {
+            params.insert("speaker".into(), Dynamic::from(*actor));
+            params.insert("text".into(), Dynamic::from(message.clone()));
+        }
+        Action::Send(SendMessage {
+            actor,
+            recipient,
+            message,
+        }) => {
+            params.insert("speaker".into(), Dynamic::from(*actor));
+            params.insert("recipient".into(), Dynamic::from(recipient.clone()));
+            params.insert("text".into(), Dynamic::from(message.clone()));
+        }
+        Action::ShopHaggle(ShopHaggle { actor, item }) => {
+            let priced = room_query
+                .get(room)
+                .ok()
+                .and_then(|room| shops.by_room(room.id()))
+                .zip(player_query.get(*actor).ok())
+                .and_then(|((_, shop), player)| {
+                    shop.stock
+                        .get(item - 1)
+                        .map(|stock| (stock.prototype, shop.price(stock.rarity, player.id())))
+                });
+            params.insert("item".into(), Dynamic::from(*item as i64));
+            params.insert(
+                "prototype".into(),
+                priced.map_or(Dynamic::UNIT, |(prototype, _)| {
+                    Dynamic::from(i64::from(prototype))
+                }),
+            );
+            params.insert(
+                "price".into(),
+                priced.map_or(Dynamic::UNIT, |(_, price)| Dynamic::from(price)),
+            );
+        }
+        Action::Use(Use {
+            keywords: words, ..
+        }) => {
+            params.insert("object".into(), object(words, room));
+            params.insert("keywords".into(), keywords(words));
+        }
+        _ => (),
     }
+
+    params
 }
 
 fn action_room(enactor: Entity, location_query: &Query<&Location>) -> Entity {
@@ -123,6 +355,7 @@ fn get_script_runs(
     hooks_query: &Query<&ScriptHooks>,
     contents_query: &Query<&Contents>,
     room_query: &Query<&Room>,
+    seasons: &Seasons,
 ) -> Vec<ScriptRun> {
     let mut runs = Vec::new();
 
@@ -171,5 +404,8 @@ fn get_script_runs(
         }
     }
 
+    // Scripts belonging to a season which is not under way do not run.
+    runs.retain(|run| seasons.is_active(&Seasonal::Script(run.script.clone())));
+
     runs
 }
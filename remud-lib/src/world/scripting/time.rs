@@ -5,11 +5,17 @@ use std::{
 };
 
 use bevy_app::EventWriter;
-use bevy_core::{Time, Timer};
+use bevy_core::Timer;
 use bevy_ecs::prelude::*;
 use priority_queue::PriorityQueue;
 
-use crate::world::{action::Action, scripting::QueuedAction};
+use crate::{
+    clock::{Clock, Time},
+    world::{
+        action::Action,
+        scripting::{QueuedAction, ScriptName, ScriptRun, ScriptRuns},
+    },
+};
 
 #[derive(Default)]
 pub struct Timers {
@@ -50,6 +56,7 @@ impl Timers {
     }
 }
 
+#[tracing::instrument(name = "tick timers system", skip_all)]
 pub fn tick_timers_system(time: Res<Time>, mut timers_query: Query<&mut Timers>) {
     for mut timers in timers_query.iter_mut() {
         for timer in timers.map.values_mut() {
@@ -58,6 +65,7 @@ pub fn tick_timers_system(time: Res<Time>, mut timers_query: Query<&mut Timers>)
     }
 }
 
+#[tracing::instrument(name = "timer cleanup system", skip_all)]
 pub fn timer_cleanup_system(mut timers_query: Query<&mut Timers>) {
     for mut timers in timers_query.iter_mut() {
         let mut to_remove = Vec::new();
@@ -87,12 +95,22 @@ impl TimedAction {
     }
 }
 
-#[derive(Default)]
 pub struct TimedActions {
+    clock: Clock,
     pub id: u64,
     pub queue: PriorityQueue<TimedAction, Reverse<Instant>>,
 }
 
+impl FromWorld for TimedActions {
+    fn from_world(world: &mut World) -> Self {
+        TimedActions {
+            clock: world.get_resource::<Clock>().cloned().unwrap_or_default(),
+            id: 0,
+            queue: PriorityQueue::new(),
+        }
+    }
+}
+
 impl TimedActions {
     pub fn send_at(&mut self, action: Action, instant: Instant) {
         self.queue
@@ -101,13 +119,13 @@ impl TimedActions {
     }
 
     pub fn send_after(&mut self, action: Action, duration: Duration) {
-        self.send_at(action, Instant::now() + duration);
+        self.send_at(action, self.clock.now() + duration);
     }
 
     pub fn pop_ready(&mut self) -> Option<Action> {
         if let Some((_, time)) = self.queue.peek() {
             // Use the un-reversed time to check if it has passed
-            if time.0 <= Instant::now() {
+            if time.0 <= self.clock.now() {
                 return self.queue.pop().map(|(action, _)| action.action);
             }
         }
@@ -115,6 +133,7 @@ impl TimedActions {
     }
 }
 
+#[tracing::instrument(name = "timed actions system", skip_all)]
 pub fn timed_actions_system(
     mut queued_action_writer: EventWriter<QueuedAction>,
     mut actions: ResMut<TimedActions>,
@@ -123,3 +142,113 @@ pub fn timed_actions_system(
         queued_action_writer.send(action.into());
     }
 }
+
+// Tracks the cutscene each player is watching. Lines of a cutscene which has since been cancelled
+// or replaced are dropped when they come due.
+#[derive(Default)]
+pub struct Cutscenes {
+    next_id: u64,
+    playing: HashMap<Entity, u64>,
+}
+
+impl Cutscenes {
+    /// Starts a new cutscene for a player, replacing any they are watching, and returns its ID.
+    pub fn start(&mut self, player: Entity) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.playing.insert(player, id);
+        id
+    }
+
+    pub fn is_playing(&self, player: Entity, id: u64) -> bool {
+        self.playing.get(&player) == Some(&id)
+    }
+
+    pub fn finish(&mut self, player: Entity, id: u64) {
+        if self.is_playing(player, id) {
+            self.playing.remove(&player);
+        }
+    }
+
+    /// Stops the cutscene a player is watching, if any.
+    pub fn cancel(&mut self, player: Entity) {
+        self.playing.remove(&player);
+    }
+}
+
+// A script waiting to resume by calling one of its functions.
+#[derive(Debug, Hash, Eq, PartialEq)]
+pub struct Continuation {
+    id: u64,
+    entity: Entity,
+    script: ScriptName,
+    function: String,
+}
+
+pub struct Continuations {
+    clock: Clock,
+    pub id: u64,
+    pub queue: PriorityQueue<Continuation, Reverse<Instant>>,
+}
+
+impl FromWorld for Continuations {
+    fn from_world(world: &mut World) -> Self {
+        Continuations {
+            clock: world.get_resource::<Clock>().cloned().unwrap_or_default(),
+            id: 0,
+            queue: PriorityQueue::new(),
+        }
+    }
+}
+
+impl Continuations {
+    pub fn resume_after(
+        &mut self,
+        entity: Entity,
+        script: ScriptName,
+        function: String,
+        duration: Duration,
+    ) {
+        let continuation = Continuation {
+            id: self.id,
+            entity,
+            script,
+            function,
+        };
+
+        self.queue
+            .push(continuation, Reverse(self.clock.now() + duration));
+        self.id += 1;
+    }
+
+    pub fn pop_ready(&mut self) -> Option<Continuation> {
+        if let Some((_, time)) = self.queue.peek() {
+            if time.0 <= self.clock.now() {
+                return self.queue.pop().map(|(continuation, _)| continuation);
+            }
+        }
+        None
+    }
+}
+
+#[tracing::instrument(name = "continuations system", skip_all)]
+pub fn continuations_system(
+    mut continuations: ResMut<Continuations>,
+    mut script_runs: ResMut<ScriptRuns>,
+    entity_query: Query<Entity>,
+) {
+    while let Some(Continuation {
+        entity,
+        script,
+        function,
+        ..
+    }) = continuations.pop_ready()
+    {
+        // The entity may have been removed while the script was waiting.
+        if entity_query.get(entity).is_ok() {
+            script_runs
+                .continuation_runs
+                .push((ScriptRun { entity, script }, function));
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use bevy_ecs::prelude::*;
+
+use crate::{
+    clock::Clock,
+    ecs::{CoreSystem, Ecs, GuardSystem, Phase, Plugin, Step},
+    logging::GAME_LOG_TARGET,
+    world::types::season::Seasons,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, SystemLabel)]
+pub enum SeasonSystem {
+    Scheduler,
+}
+
+#[derive(Default)]
+pub struct SeasonPlugin {}
+
+impl Plugin for SeasonPlugin {
+    fn build(&self, ecs: &mut Ecs) {
+        ecs.add_system(
+            Step::Main,
+            Phase::Update,
+            season_scheduler_system
+                .guarded()
+                .label(SeasonSystem::Scheduler)
+                .after(CoreSystem::Time),
+        );
+    }
+}
+
+// Works out which seasons are under way whenever the day changes or the season windows are
+// reconfigured, waking and putting to sleep the content tagged with them.
+#[tracing::instrument(name = "season scheduler system", skip_all)]
+pub fn season_scheduler_system(clock: Res<Clock>, mut seasons: ResMut<Seasons>) {
+    if let Some(changes) = seasons.evaluate(clock.unix_now()) {
+        for season in changes.started {
+            tracing::info!(target: GAME_LOG_TARGET, season = season.as_str(), "season started");
+        }
+
+        for season in changes.ended {
+            tracing::info!(target: GAME_LOG_TARGET, season = season.as_str(), "season ended");
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use bevy_ecs::prelude::*;
+use rand::thread_rng;
+
+use crate::{
+    clock::Time,
+    ecs::{CoreSystem, Ecs, GuardSystem, Phase, Plugin, Step},
+    engine::persist::{self, Updates},
+    world::types::{
+        loot::LootTables,
+        player::Messages,
+        room::{Room, Rooms},
+        shop::Shops,
+    },
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, SystemLabel)]
+pub enum ShopSystem {
+    Restock,
+}
+
+#[derive(Default)]
+pub struct ShopPlugin {}
+
+impl Plugin for ShopPlugin {
+    fn build(&self, ecs: &mut Ecs) {
+        ecs.add_system(
+            Step::Main,
+            Phase::Update,
+            restock_system
+                .guarded()
+                .label(ShopSystem::Restock)
+                .after(CoreSystem::Time),
+        );
+    }
+}
+
+// Restocks each shop whose interval has elapsed from its loot table, letting anyone in the shop
+// know.
+#[tracing::instrument(name = "restock system", skip_all)]
+pub fn restock_system(
+    time: Res<Time>,
+    mut shops: ResMut<Shops>,
+    loot_tables: Res<LootTables>,
+    rooms: Res<Rooms>,
+    mut updates: ResMut<Updates>,
+    room_query: Query<&Room>,
+    mut messages_query: Query<&mut Messages>,
+) {
+    let mut rng = thread_rng();
+
+    for (id, shop) in shops.iter_mut() {
+        if !shop.tick(time.delta()) {
+            continue;
+        }
+
+        // Shops in rooms which are not loaded still restock, with no one there to tell.
+        if !rooms.exists(shop.room) {
+            continue;
+        }
+
+        let table = match shop.loot_table.as_ref().and_then(|id| loot_tables.get(id)) {
+            Some(table) => table,
+            None => continue,
+        };
+
+        shop.restock(table, &mut rng);
+
+        updates.persist(persist::shop::Update::new(id.clone(), shop));
+
+        let room = match rooms
+            .by_id(shop.room)
+            .and_then(|room| room_query.get(room).ok())
+        {
+            Some(room) => room,
+            None => continue,
+        };
+
+        for player in room.players() {
+            if let Ok(mut messages) = messages_query.get_mut(*player) {
+                messages.queue_broadcast("Fresh stock is set out for sale.".to_string());
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use bevy_app::EventWriter;
+use bevy_ecs::prelude::*;
+use rand::thread_rng;
+
+use crate::{
+    clock::Time,
+    ecs::{CoreSystem, Ecs, GuardSystem, Phase, Plugin, Step},
+    engine::persist::{self, UpdateGroup, Updates},
+    world::{
+        scripting::{RunInitScript, ScriptHooks, ScriptTrigger},
+        types::{
+            object::{Keywords, Object, ObjectBundle, ObjectFlags, Objects, Prototypes},
+            room::{Regions, Room},
+            season::{Seasonal, Seasons},
+            spawn::SpawnTables,
+            Contents, Description, Id, Location, Named,
+        },
+    },
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, SystemLabel)]
+pub enum SpawnSystem {
+    Spawner,
+}
+
+#[derive(Default)]
+pub struct SpawnPlugin {}
+
+impl Plugin for SpawnPlugin {
+    fn build(&self, ecs: &mut Ecs) {
+        ecs.add_system(
+            Step::Main,
+            Phase::Update,
+            spawner_system
+                .guarded()
+                .label(SpawnSystem::Spawner)
+                .after(CoreSystem::Time),
+        );
+    }
+}
+
+// Tops up the rooms covered by each active spawn table whose interval has elapsed, adding at most
+// one object to each room per interval.
+#[tracing::instrument(name = "spawner system", skip_all)]
+pub fn spawner_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut init_writer: EventWriter<RunInitScript>,
+    mut spawn_tables: ResMut<SpawnTables>,
+    seasons: Res<Seasons>,
+    prototypes: Res<Prototypes>,
+    mut objects: ResMut<Objects>,
+    mut updates: ResMut<Updates>,
+    prototypes_query: Query<(
+        &Named,
+        &Description,
+        &ObjectFlags,
+        &Keywords,
+        Option<&ScriptHooks>,
+    )>,
+    object_query: Query<&Object>,
+    mut room_query: Query<(Entity, &Room, &Regions, &mut Contents)>,
+) {
+    let mut rng = thread_rng();
+
+    for (id, table) in spawn_tables.iter_mut() {
+        if !table.tick(time.delta()) {
+            continue;
+        }
+
+        // Tables belonging to a season which is not under way rest until it comes around.
+        if !seasons.is_active(&Seasonal::SpawnTable(id.clone())) {
+            continue;
+        }
+
+        let table_prototypes = table
+            .entries
+            .keys()
+            .filter_map(|id| prototypes.by_id(*id))
+            .collect::<Vec<_>>();
+
+        for (room_entity, room, regions, mut contents) in room_query.iter_mut() {
+            if !table.covers(room.id(), regions.get_list().as_slice()) {
+                continue;
+            }
+
+            let population = contents
+                .objects()
+                .iter()
+                .filter_map(|object| object_query.get(*object).ok())
+                .filter(|object| table_prototypes.contains(&object.prototype()))
+                .count();
+
+            if population >= table.cap as usize {
+                continue;
+            }
+
+            let (prototype_id, prototype) = match table
+                .choose(&mut rng)
+                .and_then(|id| prototypes.by_id(id).map(|entity| (id, entity)))
+            {
+                Some(chosen) => chosen,
+                None => continue,
+            };
+
+            let (named, description, flags, keywords, hooks) = match prototypes_query.get(prototype)
+            {
+                Ok(components) => components,
+                Err(_) => continue,
+            };
+
+            let id = objects.next_id();
+
+            let mut e = commands.spawn_bundle(ObjectBundle {
+                object: Object::new(id, prototype, true),
+                id: Id::Object(id),
+                name: named.clone(),
+                description: description.clone(),
+                flags: flags.clone(),
+                keywords: keywords.clone(),
+                location: Location::from(room_entity),
+            });
+
+            if let Some(hooks) = hooks {
+                e.insert(hooks.clone());
+            }
+
+            let object_entity = e.id();
+
+            if let Some(hooks) = hooks {
+                for script in hooks.by_trigger(ScriptTrigger::Init) {
+                    init_writer.send(RunInitScript::new(object_entity, script));
+                }
+            }
+
+            contents.insert(object_entity);
+
+            updates.persist(UpdateGroup::new(vec![
+                persist::object::Create::new(id, prototype_id),
+                persist::room::AddObject::new(room.id(), id),
+            ]));
+
+            objects.insert(id, object_entity);
+        }
+    }
+}
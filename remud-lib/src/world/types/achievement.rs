@@ -0,0 +1,107 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+pub const DEFAULT_ACHIEVEMENT_DESCRIPTION: &str = "An accomplishment of some renown.";
+
+// Achievement IDs are referenced by scripts, so they are kept simple: lowercase ASCII letters,
+// digits, dashes, and underscores.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct AchievementId(String);
+
+impl FromStr for AchievementId {
+    type Err = AchievementIdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            Ok(AchievementId(s.to_string()))
+        } else {
+            Err(AchievementIdParseError {})
+        }
+    }
+}
+
+impl fmt::Display for AchievementId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Achievement IDs must contain only lowercase letters, digits, dashes, and underscores.")]
+pub struct AchievementIdParseError {}
+
+#[derive(Debug, Clone)]
+pub struct Achievement {
+    pub name: String,
+    pub description: String,
+    // whether any player has earned the achievement, used to announce the first
+    pub earned: bool,
+}
+
+impl Achievement {
+    pub fn new(name: String, description: String, earned: bool) -> Self {
+        Achievement {
+            name,
+            description,
+            earned,
+        }
+    }
+}
+
+// Resource holding every achievement definition
+#[derive(Default)]
+pub struct Achievements {
+    by_id: BTreeMap<AchievementId, Achievement>,
+}
+
+impl Achievements {
+    pub fn insert(&mut self, id: AchievementId, achievement: Achievement) {
+        self.by_id.insert(id, achievement);
+    }
+
+    pub fn contains(&self, id: &AchievementId) -> bool {
+        self.by_id.contains_key(id)
+    }
+
+    pub fn get_mut(&mut self, id: &AchievementId) -> Option<&mut Achievement> {
+        self.by_id.get_mut(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&AchievementId, &Achievement)> {
+        self.by_id.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_id.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+}
+
+// Component tracking the achievements a player has earned
+#[derive(Debug, Default)]
+pub struct EarnedAchievements {
+    earned: HashSet<AchievementId>,
+}
+
+impl EarnedAchievements {
+    pub fn contains(&self, id: &AchievementId) -> bool {
+        self.earned.contains(id)
+    }
+
+    /// Records an earned achievement, returning false if it was already earned.
+    pub fn insert(&mut self, id: AchievementId) -> bool {
+        self.earned.insert(id)
+    }
+}
@@ -0,0 +1,54 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use itertools::Itertools;
+
+// ASCII art is read from files with this extension, named by the rest of the file name.
+const ART_EXTENSION: &str = "txt";
+
+/// ASCII art which can be shown by name, such as on the login screen or from scripts. Art is
+/// shown as written: pipes are escaped so they are not mistaken for color tags.
+#[derive(Debug, Default)]
+pub struct Art {
+    pieces: BTreeMap<String, String>,
+}
+
+impl Art {
+    /// Reads each .txt file in the directory as a piece of art named by its file name. Files which
+    /// are not plain ASCII cannot be sent to every client and are skipped.
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let mut pieces = BTreeMap::new();
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if path.extension().and_then(|extension| extension.to_str()) != Some(ART_EXTENSION) {
+                continue;
+            }
+
+            let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(name) => name.to_lowercase(),
+                None => continue,
+            };
+
+            let text = fs::read_to_string(&path)?;
+            if !text.is_ascii() {
+                tracing::warn!("skipping ASCII art {:?} which is not plain ASCII", path);
+                continue;
+            }
+
+            let art = text
+                .trim_end()
+                .lines()
+                .map(|line| line.trim_end().replace('|', "||"))
+                .join("\r\n");
+
+            pieces.insert(name, art);
+        }
+
+        Ok(Art { pieces })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.pieces.get(&name.to_lowercase()).map(String::as_str)
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use bevy_ecs::prelude::*;
+
+use crate::world::types::player::PlayerId;
+
+// The number of objects a player can keep in their vault
+pub const VAULT_CAPACITY: usize = 20;
+
+pub fn format_coins(amount: i64) -> String {
+    if amount == 1 {
+        "1 coin".to_string()
+    } else {
+        format!("{} coins", amount)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Account {
+    pub name: String,
+    // coins carried by the player
+    pub coins: i64,
+    // coins deposited in the bank
+    pub balance: i64,
+}
+
+// Resource holding the coins of every player. Accounts are kept for offline players as well so
+// that immortals can audit them.
+#[derive(Debug, Default)]
+pub struct Bank {
+    by_player: HashMap<PlayerId, Account>,
+}
+
+impl Bank {
+    pub fn get(&self, id: PlayerId) -> Option<&Account> {
+        self.by_player.get(&id)
+    }
+
+    /// Retrieves a player's account, opening an empty one if they do not have one yet.
+    pub fn open(&mut self, id: PlayerId, name: &str) -> &mut Account {
+        self.by_player.entry(id).or_insert_with(|| Account {
+            name: name.to_string(),
+            ..Default::default()
+        })
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<PlayerId> {
+        self.by_player
+            .iter()
+            .find(|(_, account)| account.name == name)
+            .map(|(id, _)| *id)
+    }
+
+    pub fn insert(&mut self, id: PlayerId, account: Account) {
+        self.by_player.insert(id, account);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&PlayerId, &Account)> {
+        self.by_player.iter()
+    }
+}
+
+// Objects a player has deposited in the bank. Vaulted objects stay located with the player, but
+// are not part of their contents. Vaults are loaded from the database the first time they are
+// needed rather than when the player logs in.
+#[derive(Debug, Default)]
+pub struct Vault {
+    objects: Vec<Entity>,
+    loaded: bool,
+}
+
+impl Vault {
+    pub fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    pub fn set_loaded(&mut self) {
+        self.loaded = true;
+    }
+
+    pub fn insert(&mut self, object: Entity) {
+        self.objects.push(object);
+    }
+
+    pub fn remove(&mut self, object: Entity) -> bool {
+        if let Some(index) = self.objects.iter().position(|o| *o == object) {
+            self.objects.remove(index);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn find(&self, mut predicate: impl FnMut(Entity) -> bool) -> Option<Entity> {
+        self.objects
+            .iter()
+            .find(|entity| predicate(**entity))
+            .copied()
+    }
+
+    pub fn objects(&self) -> &[Entity] {
+        self.objects.as_slice()
+    }
+
+    pub fn get_objects(&self) -> Vec<Entity> {
+        self.objects.clone()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.objects.len() >= VAULT_CAPACITY
+    }
+}
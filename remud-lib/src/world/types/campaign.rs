@@ -0,0 +1,193 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use thiserror::Error;
+
+use crate::world::types::{object::PrototypeId, room::RoomId};
+
+// Campaign IDs are typed by immortals and referenced by scripts, so they are kept simple:
+// lowercase ASCII letters, digits, dashes, and underscores.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct CampaignId(String);
+
+impl FromStr for CampaignId {
+    type Err = CampaignIdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            Ok(CampaignId(s.to_string()))
+        } else {
+            Err(CampaignIdParseError {})
+        }
+    }
+}
+
+impl fmt::Display for CampaignId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Event IDs must contain only lowercase letters, digits, dashes, and underscores.")]
+pub struct CampaignIdParseError {}
+
+/// An object created in a room when a stage begins.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct CampaignSpawn {
+    pub prototype: PrototypeId,
+    pub room: RoomId,
+}
+
+/// One step of a campaign. Its announcement is sent to every online player when it begins.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct CampaignStage {
+    pub announcement: String,
+    // what players must accomplish to move the campaign along, shown by the events command
+    pub goal: Option<String>,
+    pub spawns: Vec<CampaignSpawn>,
+}
+
+impl CampaignStage {
+    pub fn new(announcement: String) -> Self {
+        CampaignStage {
+            announcement,
+            goal: None,
+            spawns: Vec::new(),
+        }
+    }
+}
+
+/// A multi-stage world event. Immortals start campaigns and move them through their stages, as
+/// can scripts once a stage's goal is met.
+#[derive(Debug, Clone)]
+pub struct Campaign {
+    pub name: String,
+    pub stages: Vec<CampaignStage>,
+    // the index of the current stage while the campaign is running
+    stage: Option<usize>,
+}
+
+impl Campaign {
+    pub fn new(name: String) -> Self {
+        Campaign {
+            name,
+            stages: Vec::new(),
+            stage: None,
+        }
+    }
+
+    /// Restores a campaign's progress, ignoring stages which no longer exist.
+    pub fn with_stage(mut self, stage: Option<usize>) -> Self {
+        self.stage = stage.filter(|stage| *stage < self.stages.len());
+        self
+    }
+
+    pub fn stage(&self) -> Option<usize> {
+        self.stage
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.stage.is_some()
+    }
+
+    pub fn current(&self) -> Option<&CampaignStage> {
+        self.stage.and_then(|stage| self.stages.get(stage))
+    }
+
+    /// Begins the first stage, returning it. Returns None if the campaign has no stages.
+    pub fn start(&mut self) -> Option<&CampaignStage> {
+        self.stage = if self.stages.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.current()
+    }
+
+    /// Moves on to the next stage, returning it. Returns None, stopping the campaign, when the
+    /// last stage is complete.
+    pub fn advance(&mut self) -> Option<&CampaignStage> {
+        self.stage = self
+            .stage
+            .map(|stage| stage + 1)
+            .filter(|stage| *stage < self.stages.len());
+        self.current()
+    }
+
+    pub fn stop(&mut self) {
+        self.stage = None;
+    }
+}
+
+// Resource holding every campaign
+#[derive(Default)]
+pub struct Campaigns {
+    by_id: BTreeMap<CampaignId, Campaign>,
+}
+
+impl Campaigns {
+    pub fn insert(&mut self, id: CampaignId, campaign: Campaign) {
+        self.by_id.insert(id, campaign);
+    }
+
+    pub fn remove(&mut self, id: &CampaignId) -> Option<Campaign> {
+        self.by_id.remove(id)
+    }
+
+    pub fn contains(&self, id: &CampaignId) -> bool {
+        self.by_id.contains_key(id)
+    }
+
+    pub fn get(&self, id: &CampaignId) -> Option<&Campaign> {
+        self.by_id.get(id)
+    }
+
+    pub fn get_mut(&mut self, id: &CampaignId) -> Option<&mut Campaign> {
+        self.by_id.get_mut(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&CampaignId, &Campaign)> {
+        self.by_id.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_campaign_stages() {
+        let mut campaign = Campaign::new("Siege".to_string());
+        assert!(campaign.start().is_none());
+
+        campaign
+            .stages
+            .push(CampaignStage::new("The gates are breached!".to_string()));
+        campaign
+            .stages
+            .push(CampaignStage::new("The keep holds.".to_string()));
+
+        assert_eq!(
+            campaign.start().map(|stage| stage.announcement.as_str()),
+            Some("The gates are breached!")
+        );
+        assert_eq!(campaign.stage(), Some(0));
+        assert_eq!(
+            campaign.advance().map(|stage| stage.announcement.as_str()),
+            Some("The keep holds.")
+        );
+        assert!(campaign.advance().is_none());
+        assert!(!campaign.is_running());
+
+        let campaign = campaign.with_stage(Some(5));
+        assert!(!campaign.is_running());
+    }
+}
@@ -0,0 +1,242 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+use crate::world::types::{player::PlayerId, room::RoomId};
+
+// Clan names are used as a single word in commands.
+pub const MAX_CLAN_NAME_LENGTH: usize = 20;
+
+pub fn valid_clan_name(name: &str) -> bool {
+    (3..=MAX_CLAN_NAME_LENGTH).contains(&name.len())
+        && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ClanRank {
+    Member,
+    Officer,
+    Leader,
+}
+
+impl ClanRank {
+    pub const ALL: [ClanRank; 3] = [ClanRank::Member, ClanRank::Officer, ClanRank::Leader];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClanRank::Member => "member",
+            ClanRank::Officer => "officer",
+            ClanRank::Leader => "leader",
+        }
+    }
+}
+
+impl FromStr for ClanRank {
+    type Err = ClanRankParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ClanRank::ALL
+            .iter()
+            .find(|rank| rank.as_str() == s)
+            .copied()
+            .ok_or(ClanRankParseError {})
+    }
+}
+
+impl fmt::Display for ClanRank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Clan ranks are member, officer, and leader.")]
+pub struct ClanRankParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClanMember {
+    pub name: String,
+    pub rank: ClanRank,
+}
+
+#[derive(Debug, Clone)]
+pub struct Clan {
+    pub name: String,
+    pub founder: PlayerId,
+    // a room only members of the clan may enter
+    pub hall: Option<RoomId>,
+    members: HashMap<PlayerId, ClanMember>,
+    // invitations are forgotten when the server restarts
+    invited: HashSet<PlayerId>,
+}
+
+impl Clan {
+    pub fn new(name: String, founder: PlayerId, hall: Option<RoomId>) -> Self {
+        Clan {
+            name,
+            founder,
+            hall,
+            members: HashMap::new(),
+            invited: HashSet::new(),
+        }
+    }
+
+    pub fn member(&self, id: PlayerId) -> Option<&ClanMember> {
+        self.members.get(&id)
+    }
+
+    pub fn member_by_name(&self, name: &str) -> Option<(PlayerId, &ClanMember)> {
+        self.members
+            .iter()
+            .find(|(_, member)| member.name == name)
+            .map(|(id, member)| (*id, member))
+    }
+
+    pub fn members(&self) -> impl Iterator<Item = (&PlayerId, &ClanMember)> {
+        self.members.iter()
+    }
+
+    pub fn rank(&self, id: PlayerId) -> Option<ClanRank> {
+        self.members.get(&id).map(|member| member.rank)
+    }
+
+    pub fn set_rank(&mut self, id: PlayerId, rank: ClanRank) {
+        if let Some(member) = self.members.get_mut(&id) {
+            member.rank = rank;
+        }
+    }
+
+    pub fn invite(&mut self, id: PlayerId) {
+        self.invited.insert(id);
+    }
+
+    pub fn is_invited(&self, id: PlayerId) -> bool {
+        self.invited.contains(&id)
+    }
+}
+
+// Resource holding every clan, keyed by lowercase name, and the clan each player belongs to.
+#[derive(Debug, Default)]
+pub struct Clans {
+    by_name: BTreeMap<String, Clan>,
+    by_player: HashMap<PlayerId, String>,
+}
+
+impl Clans {
+    pub fn insert(&mut self, clan: Clan) {
+        self.by_name.insert(clan.name.to_lowercase(), clan);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Clan> {
+        let clan = self.by_name.remove(name.to_lowercase().as_str())?;
+        self.by_player
+            .retain(|_, key| *key != clan.name.to_lowercase());
+        Some(clan)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Clan> {
+        self.by_name.get(name.to_lowercase().as_str())
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Clan> {
+        self.by_name.get_mut(name.to_lowercase().as_str())
+    }
+
+    pub fn by_player(&self, id: PlayerId) -> Option<&Clan> {
+        self.by_player
+            .get(&id)
+            .and_then(|name| self.by_name.get(name))
+    }
+
+    pub fn by_player_mut(&mut self, id: PlayerId) -> Option<&mut Clan> {
+        match self.by_player.get(&id) {
+            Some(name) => self.by_name.get_mut(name),
+            None => None,
+        }
+    }
+
+    pub fn by_hall(&self, room: RoomId) -> Option<&Clan> {
+        self.by_name.values().find(|clan| clan.hall == Some(room))
+    }
+
+    /// Adds a player to a clan, clearing any invitation they had to it.
+    pub fn join(&mut self, name: &str, id: PlayerId, member: ClanMember) {
+        if let Some(clan) = self.by_name.get_mut(name.to_lowercase().as_str()) {
+            clan.invited.remove(&id);
+            clan.members.insert(id, member);
+            self.by_player.insert(id, name.to_lowercase());
+        }
+    }
+
+    pub fn leave(&mut self, id: PlayerId) -> Option<ClanMember> {
+        let name = self.by_player.remove(&id)?;
+        self.by_name
+            .get_mut(name.as_str())
+            .and_then(|clan| clan.members.remove(&id))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Clan> {
+        self.by_name.values()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_name.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+
+    #[test]
+    fn test_clan_membership() {
+        let founder = PlayerId::try_from(1).unwrap();
+        let recruit = PlayerId::try_from(2).unwrap();
+
+        let mut clans = Clans::default();
+        clans.insert(Clan::new("Rats".to_string(), founder, None));
+        clans.join(
+            "rats",
+            founder,
+            ClanMember {
+                name: "krixi".to_string(),
+                rank: ClanRank::Leader,
+            },
+        );
+
+        clans.get_mut("RATS").unwrap().invite(recruit);
+        assert!(clans.get("rats").unwrap().is_invited(recruit));
+
+        clans.join(
+            "Rats",
+            recruit,
+            ClanMember {
+                name: "shane".to_string(),
+                rank: ClanRank::Member,
+            },
+        );
+        assert!(!clans.get("rats").unwrap().is_invited(recruit));
+        assert_eq!(clans.by_player(recruit).unwrap().name, "Rats");
+
+        assert_eq!(clans.leave(recruit).unwrap().name, "shane");
+        assert!(clans.by_player(recruit).is_none());
+
+        clans.remove("rats");
+        assert!(clans.by_player(founder).is_none());
+        assert!(clans.is_empty());
+    }
+
+    #[test]
+    fn test_clan_names() {
+        assert!(valid_clan_name("Rats"));
+        assert!(!valid_clan_name("ab"));
+        assert!(!valid_clan_name("sewer rats"));
+        assert!(!valid_clan_name("abcdefghijklmnopqrstu"));
+    }
+}
@@ -0,0 +1,321 @@
+use std::{collections::VecDeque, convert::TryFrom, fmt, str::FromStr, time::Duration};
+
+use thiserror::Error;
+
+use crate::world::types::{player::PlayerId, room::RoomId};
+
+/// The number of communications shown or returned at once when no limit is given.
+pub const DEFAULT_PAGE_LENGTH: usize = 20;
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct CommunicationId(i64);
+
+impl TryFrom<i64> for CommunicationId {
+    type Error = CommunicationIdParseError;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        if value >= 0 {
+            Ok(CommunicationId(value))
+        } else {
+            Err(CommunicationIdParseError {})
+        }
+    }
+}
+
+impl From<CommunicationId> for i64 {
+    fn from(id: CommunicationId) -> Self {
+        id.0
+    }
+}
+
+impl fmt::Display for CommunicationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Communication IDs must be non-negative integers.")]
+pub struct CommunicationIdParseError {}
+
+/// How a player communicated.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Channel {
+    Clan,
+    Emote,
+    Say,
+    Send,
+}
+
+impl Channel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Channel::Clan => "clan",
+            Channel::Emote => "emote",
+            Channel::Say => "say",
+            Channel::Send => "send",
+        }
+    }
+}
+
+impl FromStr for Channel {
+    type Err = ChannelParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "clan" => Ok(Channel::Clan),
+            "emote" => Ok(Channel::Emote),
+            "say" => Ok(Channel::Say),
+            "send" => Ok(Channel::Send),
+            _ => Err(ChannelParseError {}),
+        }
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Channels are one of clan, emote, say, or send.")]
+pub struct ChannelParseError {}
+
+/// Something a player said, emoted, sent, or said to their clan, without color markup.
+#[derive(Debug, Clone)]
+pub struct Communication {
+    pub channel: Channel,
+    pub player: PlayerId,
+    pub name: String,
+    // the room the player was in, if any
+    pub room: Option<RoomId>,
+    // the recipient of a sent message, or the clan spoken to
+    pub target: Option<String>,
+    pub text: String,
+    // seconds since the Unix epoch
+    pub said_at: i64,
+}
+
+/// Narrows a search of the communication log. Empty filters match everything.
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+pub struct CommunicationFilter {
+    pub player: Option<String>,
+    pub channel: Option<Channel>,
+    pub room: Option<RoomId>,
+    // matched against the text, ignoring case
+    pub text: Option<String>,
+}
+
+impl CommunicationFilter {
+    pub fn matches(&self, communication: &Communication) -> bool {
+        if let Some(player) = &self.player {
+            if !communication.name.eq_ignore_ascii_case(player) {
+                return false;
+            }
+        }
+
+        if let Some(channel) = self.channel {
+            if communication.channel != channel {
+                return false;
+            }
+        }
+
+        if let Some(room) = self.room {
+            if communication.room != Some(room) {
+                return false;
+            }
+        }
+
+        match &self.text {
+            Some(text) => communication
+                .text
+                .to_lowercase()
+                .contains(text.to_lowercase().as_str()),
+            None => true,
+        }
+    }
+}
+
+/// A page of the entries matching a filter, newest first.
+#[derive(Debug)]
+pub struct CommunicationPage {
+    pub entries: Vec<(CommunicationId, Communication)>,
+    // the number of entries matching the filter across all pages
+    pub total: usize,
+}
+
+// Resource holding the communication between players, oldest first. Entries are kept until they
+// are older than the retention period.
+pub struct Communications {
+    entries: VecDeque<(CommunicationId, Communication)>,
+    highest_id: i64,
+    retention: Duration,
+}
+
+impl Communications {
+    pub fn new(highest_id: i64, entries: VecDeque<(CommunicationId, Communication)>) -> Self {
+        Communications {
+            entries,
+            highest_id,
+            retention: Duration::default(),
+        }
+    }
+
+    pub fn set_retention(&mut self, retention: Duration) {
+        self.retention = retention;
+    }
+
+    pub fn record(&mut self, communication: Communication) -> CommunicationId {
+        self.highest_id += 1;
+        let id = CommunicationId(self.highest_id);
+        self.entries.push_back((id, communication));
+        id
+    }
+
+    /// Drops the entries older than the retention period, returning the time before which they
+    /// were said when any were dropped.
+    pub fn take_expired(&mut self, now: i64) -> Option<i64> {
+        let before = now - self.retention.as_secs() as i64;
+        let mut expired = false;
+
+        while matches!(self.entries.front(), Some((_, entry)) if entry.said_at < before) {
+            self.entries.pop_front();
+            expired = true;
+        }
+
+        if expired {
+            Some(before)
+        } else {
+            None
+        }
+    }
+
+    /// Forgets everything a deleted player said.
+    pub fn forget(&mut self, name: &str) {
+        self.entries.retain(|(_, entry)| entry.name != name);
+    }
+
+    /// Finds the entries matching a filter, newest first.
+    pub fn search<'a>(
+        &'a self,
+        filter: &'a CommunicationFilter,
+    ) -> impl Iterator<Item = (CommunicationId, &'a Communication)> + 'a {
+        self.entries
+            .iter()
+            .rev()
+            .filter(move |(_, entry)| filter.matches(entry))
+            .map(|(id, entry)| (*id, entry))
+    }
+
+    /// Finds a page of the entries matching a filter, newest first. Pages start from 1.
+    pub fn page(
+        &self,
+        filter: &CommunicationFilter,
+        page: usize,
+        length: usize,
+    ) -> CommunicationPage {
+        let skip = page.saturating_sub(1).saturating_mul(length);
+        let mut entries = Vec::new();
+        let mut total = 0;
+
+        for (id, entry) in self.search(filter) {
+            if total >= skip && entries.len() < length {
+                entries.push((id, entry.clone()));
+            }
+            total += 1;
+        }
+
+        CommunicationPage { entries, total }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, convert::TryFrom, time::Duration};
+
+    use crate::world::types::{player::PlayerId, room::RoomId};
+
+    use super::{Channel, Communication, CommunicationFilter, Communications};
+
+    fn said(player: i64, name: &str, channel: Channel, text: &str, said_at: i64) -> Communication {
+        Communication {
+            channel,
+            player: PlayerId::try_from(player).unwrap(),
+            name: name.to_string(),
+            room: Some(RoomId::try_from(1).unwrap()),
+            target: None,
+            text: text.to_string(),
+            said_at,
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let mut communications = Communications::new(0, VecDeque::new());
+        communications.record(said(1, "krixi", Channel::Say, "Hello there", 10));
+        communications.record(said(2, "Shane", Channel::Emote, "waves hello", 20));
+        communications.record(said(1, "krixi", Channel::Send, "Meet me at the docks", 30));
+
+        let filter = CommunicationFilter {
+            text: Some("HELLO".to_string()),
+            ..Default::default()
+        };
+        let found = communications
+            .search(&filter)
+            .map(|(_, entry)| entry.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec!["Shane", "krixi"]);
+
+        let filter = CommunicationFilter {
+            player: Some("Krixi".to_string()),
+            channel: Some(Channel::Send),
+            ..Default::default()
+        };
+        let found = communications
+            .search(&filter)
+            .map(|(id, _)| i64::from(id))
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec![3]);
+    }
+
+    #[test]
+    fn test_page() {
+        let mut communications = Communications::new(0, VecDeque::new());
+        for said_at in 0..5 {
+            communications.record(said(1, "krixi", Channel::Say, "hello", said_at));
+        }
+
+        let filter = CommunicationFilter::default();
+        let page = communications.page(&filter, 2, 2);
+        assert_eq!(page.total, 5);
+        assert_eq!(
+            page.entries
+                .iter()
+                .map(|(id, _)| i64::from(*id))
+                .collect::<Vec<_>>(),
+            vec![3, 2]
+        );
+        assert!(communications.page(&filter, 4, 2).entries.is_empty());
+    }
+
+    #[test]
+    fn test_take_expired() {
+        let mut communications = Communications::new(0, VecDeque::new());
+        communications.set_retention(Duration::from_secs(100));
+        communications.record(said(1, "krixi", Channel::Say, "first", 10));
+        communications.record(said(1, "krixi", Channel::Say, "second", 50));
+
+        assert_eq!(communications.take_expired(100), None);
+        assert_eq!(communications.take_expired(120), Some(20));
+        assert_eq!(communications.take_expired(120), None);
+
+        let filter = CommunicationFilter::default();
+        let found = communications
+            .search(&filter)
+            .map(|(_, entry)| entry.text.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec!["second"]);
+    }
+}
@@ -0,0 +1,299 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use thiserror::Error;
+
+use crate::world::types::bank::{format_coins, Bank};
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+pub const DEFAULT_REPORT_DAYS: u32 = 7;
+
+/// Where coins entering or leaving the game came from or went to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CoinSource {
+    // granted or taken by an immortal
+    Grant,
+    Loot,
+    Quest,
+    // scripts which do not name a source
+    Script,
+    Shop,
+}
+
+impl CoinSource {
+    pub const ALL: [CoinSource; 5] = [
+        CoinSource::Grant,
+        CoinSource::Loot,
+        CoinSource::Quest,
+        CoinSource::Script,
+        CoinSource::Shop,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CoinSource::Grant => "grant",
+            CoinSource::Loot => "loot",
+            CoinSource::Quest => "quest",
+            CoinSource::Script => "script",
+            CoinSource::Shop => "shop",
+        }
+    }
+}
+
+impl FromStr for CoinSource {
+    type Err = CoinSourceParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CoinSource::ALL
+            .iter()
+            .find(|source| source.as_str() == s)
+            .copied()
+            .ok_or(CoinSourceParseError {})
+    }
+}
+
+impl fmt::Display for CoinSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Coin sources are grant, loot, quest, script, and shop.")]
+pub struct CoinSourceParseError {}
+
+/// Coins created and destroyed by a source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Flow {
+    pub created: i64,
+    pub destroyed: i64,
+}
+
+impl Flow {
+    pub fn net(&self) -> i64 {
+        self.created - self.destroyed
+    }
+
+    fn add(&mut self, other: Flow) {
+        self.created += other.created;
+        self.destroyed += other.destroyed;
+    }
+}
+
+/// The day containing a time, in days since the Unix epoch.
+pub fn day_of(unix: i64) -> i64 {
+    unix.div_euclid(SECONDS_PER_DAY)
+}
+
+/// Formats a day since the Unix epoch as a date, such as 2021-11-24.
+pub fn format_day(day: i64) -> String {
+    // Converts days to a proleptic Gregorian date, counting in 400 year eras from March 1st so that
+    // leap days fall at the end of each year.
+    let z = day + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+// Resource tracking the coins created and destroyed each day, by source, so that inflation can be
+// spotted before it ruins the market. Coins moving between players, or in and out of the bank, are
+// neither created nor destroyed.
+#[derive(Debug, Default)]
+pub struct Economy {
+    by_day: BTreeMap<i64, BTreeMap<CoinSource, Flow>>,
+}
+
+impl Economy {
+    pub fn insert(&mut self, day: i64, source: CoinSource, flow: Flow) {
+        self.by_day.entry(day).or_default().insert(source, flow);
+    }
+
+    /// Records coins created, when the amount is positive, or destroyed, when it is negative, on
+    /// the day containing `now`. Returns the day and its new flow for the source, to be persisted.
+    pub fn record(&mut self, now: i64, source: CoinSource, amount: i64) -> (i64, Flow) {
+        let day = day_of(now);
+        let flow = self
+            .by_day
+            .entry(day)
+            .or_default()
+            .entry(source)
+            .or_default();
+
+        if amount > 0 {
+            flow.created += amount;
+        } else {
+            flow.destroyed -= amount;
+        }
+
+        (day, *flow)
+    }
+
+    /// The flows of each day from `from` onwards, earliest first.
+    pub fn days(&self, from: i64) -> impl Iterator<Item = (&i64, &BTreeMap<CoinSource, Flow>)> {
+        self.by_day.range(from..)
+    }
+
+    /// The total flow of each source from `from` onwards.
+    pub fn totals(&self, from: i64) -> BTreeMap<CoinSource, Flow> {
+        let mut totals = BTreeMap::<CoinSource, Flow>::new();
+
+        for (_, flows) in self.days(from) {
+            for (source, flow) in flows {
+                totals.entry(*source).or_default().add(*flow);
+            }
+        }
+
+        totals
+    }
+
+    /// Summarizes the last `days` days up to the day containing `now`, with the coins players
+    /// hold in the bank.
+    pub fn summary(&self, now: i64, days: u32, bank: &Bank) -> EconomySummary {
+        let from = day_of(now) - days as i64 + 1;
+
+        let (carried, banked) = bank.iter().fold((0, 0), |(carried, banked), (_, account)| {
+            (carried + account.coins, banked + account.balance)
+        });
+
+        EconomySummary {
+            days,
+            by_day: self
+                .days(from)
+                .map(|(day, flows)| (*day, flows.clone()))
+                .collect(),
+            totals: self.totals(from),
+            carried,
+            banked,
+        }
+    }
+}
+
+/// The coins created and destroyed over recent days, and the coins players hold.
+#[derive(Debug, Clone)]
+pub struct EconomySummary {
+    pub days: u32,
+    // each day with records, earliest first
+    pub by_day: Vec<(i64, BTreeMap<CoinSource, Flow>)>,
+    pub totals: BTreeMap<CoinSource, Flow>,
+    pub carried: i64,
+    pub banked: i64,
+}
+
+impl EconomySummary {
+    pub fn total(&self) -> Flow {
+        let mut total = Flow::default();
+        for flow in self.totals.values() {
+            total.add(*flow);
+        }
+        total
+    }
+
+    pub fn describe(&self) -> String {
+        let mut message = if self.days == 1 {
+            "|white|Economy today|-|".to_string()
+        } else {
+            format!("|white|Economy over the last {} days|-|", self.days)
+        };
+
+        if self.by_day.is_empty() {
+            message.push_str("\r\n  No coins were created or destroyed.");
+        }
+
+        for (day, flows) in self.by_day.iter() {
+            let mut total = Flow::default();
+            for flow in flows.values() {
+                total.add(*flow);
+            }
+
+            message.push_str(
+                format!(
+                    "\r\n  |white|{}|-|: {}",
+                    format_day(*day),
+                    describe_flow(total)
+                )
+                .as_str(),
+            );
+        }
+
+        if !self.totals.is_empty() {
+            message.push_str("\r\n|white|By source|-|");
+
+            for (source, flow) in self.totals.iter() {
+                message.push_str(
+                    format!("\r\n  |white|{}|-|: {}", source, describe_flow(*flow)).as_str(),
+                );
+            }
+        }
+
+        message.push_str(format!("\r\n|white|Total|-|: {}", describe_flow(self.total())).as_str());
+        message.push_str(
+            format!(
+                "\r\n|white|Held by players|-|: {} carried, {} banked",
+                format_coins(self.carried),
+                format_coins(self.banked)
+            )
+            .as_str(),
+        );
+
+        message
+    }
+}
+
+fn describe_flow(flow: Flow) -> String {
+    format!(
+        "{} created, {} destroyed, net {:+}",
+        format_coins(flow.created),
+        format_coins(flow.destroyed),
+        flow.net()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_by_day() {
+        let mut economy = Economy::default();
+
+        assert_eq!(
+            economy.record(10, CoinSource::Loot, 30),
+            (
+                0,
+                Flow {
+                    created: 30,
+                    destroyed: 0
+                }
+            )
+        );
+        economy.record(20, CoinSource::Loot, -5);
+        economy.record(SECONDS_PER_DAY + 1, CoinSource::Loot, 12);
+        economy.record(SECONDS_PER_DAY + 2, CoinSource::Shop, -40);
+
+        assert_eq!(economy.days(1).count(), 1);
+
+        let totals = economy.totals(0);
+        assert_eq!(
+            totals.get(&CoinSource::Loot),
+            Some(&Flow {
+                created: 42,
+                destroyed: 5
+            })
+        );
+        assert_eq!(totals.get(&CoinSource::Shop).map(Flow::net), Some(-40));
+    }
+
+    #[test]
+    fn test_format_day() {
+        assert_eq!(format_day(0), "1970-01-01");
+        assert_eq!(format_day(18_955), "2021-11-24");
+        assert_eq!(format_day(11_016), "2000-02-29");
+        assert_eq!(format_day(-1), "1969-12-31");
+    }
+}
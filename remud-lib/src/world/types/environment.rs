@@ -0,0 +1,50 @@
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// The UTC hours, inclusive, during which it is day.
+const DAY_START_HOUR: u64 = 6;
+const DAY_END_HOUR: u64 = 17;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeOfDay {
+    Day,
+    Night,
+}
+
+impl TimeOfDay {
+    /// The time of day in City Six at the given time, which follows the server's UTC clock.
+    pub fn at(time: SystemTime) -> Self {
+        let hour = time
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() / 3600 % 24)
+            .unwrap_or_default();
+
+        if (DAY_START_HOUR..=DAY_END_HOUR).contains(&hour) {
+            TimeOfDay::Day
+        } else {
+            TimeOfDay::Night
+        }
+    }
+}
+
+// Resource holding the current weather in each region, set by scripts
+#[derive(Debug, Default)]
+pub struct Weather {
+    by_region: HashMap<String, String>,
+}
+
+impl Weather {
+    pub fn get(&self, region: &str) -> Option<&str> {
+        self.by_region.get(region).map(String::as_str)
+    }
+
+    pub fn set(&mut self, region: String, weather: String) {
+        self.by_region.insert(region, weather);
+    }
+
+    pub fn clear(&mut self, region: &str) {
+        self.by_region.remove(region);
+    }
+}
@@ -0,0 +1,203 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+// Reputation is kept within these bounds so a long grind in either direction can be undone.
+pub const MIN_REPUTATION: i64 = -1000;
+pub const MAX_REPUTATION: i64 = 1000;
+
+// Faction IDs are referenced by scripts, so they follow the same rules as achievement IDs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct FactionId(String);
+
+impl FromStr for FactionId {
+    type Err = FactionIdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            Ok(FactionId(s.to_string()))
+        } else {
+            Err(FactionIdParseError {})
+        }
+    }
+}
+
+impl fmt::Display for FactionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Faction IDs must contain only lowercase letters, digits, dashes, and underscores.")]
+pub struct FactionIdParseError {}
+
+#[derive(Debug, Clone)]
+pub struct Faction {
+    pub name: String,
+}
+
+// Resource holding every faction definition
+#[derive(Default)]
+pub struct Factions {
+    by_id: BTreeMap<FactionId, Faction>,
+}
+
+impl Factions {
+    pub fn insert(&mut self, id: FactionId, faction: Faction) {
+        self.by_id.insert(id, faction);
+    }
+
+    pub fn contains(&self, id: &FactionId) -> bool {
+        self.by_id.contains_key(id)
+    }
+
+    pub fn get_mut(&mut self, id: &FactionId) -> Option<&mut Faction> {
+        self.by_id.get_mut(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&FactionId, &Faction)> {
+        self.by_id.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+}
+
+// Named bands of reputation, from worst to best
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Standing {
+    Hated,
+    Hostile,
+    Unfriendly,
+    Neutral,
+    Friendly,
+    Honored,
+    Exalted,
+}
+
+impl Standing {
+    pub const ALL: [Standing; 7] = [
+        Standing::Hated,
+        Standing::Hostile,
+        Standing::Unfriendly,
+        Standing::Neutral,
+        Standing::Friendly,
+        Standing::Honored,
+        Standing::Exalted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Standing::Hated => "hated",
+            Standing::Hostile => "hostile",
+            Standing::Unfriendly => "unfriendly",
+            Standing::Neutral => "neutral",
+            Standing::Friendly => "friendly",
+            Standing::Honored => "honored",
+            Standing::Exalted => "exalted",
+        }
+    }
+
+    pub fn from_reputation(reputation: i64) -> Self {
+        match reputation {
+            i64::MIN..=-600 => Standing::Hated,
+            -599..=-200 => Standing::Hostile,
+            -199..=-50 => Standing::Unfriendly,
+            -49..=49 => Standing::Neutral,
+            50..=199 => Standing::Friendly,
+            200..=599 => Standing::Honored,
+            _ => Standing::Exalted,
+        }
+    }
+}
+
+impl FromStr for Standing {
+    type Err = StandingParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Standing::ALL
+            .iter()
+            .find(|standing| standing.as_str() == s)
+            .copied()
+            .ok_or(StandingParseError {})
+    }
+}
+
+impl fmt::Display for Standing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Standings are hated, hostile, unfriendly, neutral, friendly, honored, and exalted.")]
+pub struct StandingParseError {}
+
+// Component tracking a player's reputation with each faction. Factions a player has no
+// reputation with are neutral.
+#[derive(Debug, Default)]
+pub struct Reputation {
+    by_faction: HashMap<FactionId, i64>,
+}
+
+impl Reputation {
+    pub fn get(&self, id: &FactionId) -> i64 {
+        self.by_faction.get(id).copied().unwrap_or_default()
+    }
+
+    pub fn standing(&self, id: &FactionId) -> Standing {
+        Standing::from_reputation(self.get(id))
+    }
+
+    pub fn insert(&mut self, id: FactionId, reputation: i64) {
+        self.by_faction.insert(id, reputation);
+    }
+
+    /// Adjusts reputation with a faction, keeping it within bounds, and returns the new value.
+    pub fn adjust(&mut self, id: &FactionId, amount: i64) -> i64 {
+        let reputation = self
+            .get(id)
+            .saturating_add(amount)
+            .clamp(MIN_REPUTATION, MAX_REPUTATION);
+        self.by_faction.insert(id.clone(), reputation);
+        reputation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standing_thresholds() {
+        assert_eq!(Standing::from_reputation(MIN_REPUTATION), Standing::Hated);
+        assert_eq!(Standing::from_reputation(-600), Standing::Hated);
+        assert_eq!(Standing::from_reputation(-599), Standing::Hostile);
+        assert_eq!(Standing::from_reputation(-50), Standing::Unfriendly);
+        assert_eq!(Standing::from_reputation(0), Standing::Neutral);
+        assert_eq!(Standing::from_reputation(50), Standing::Friendly);
+        assert_eq!(Standing::from_reputation(200), Standing::Honored);
+        assert_eq!(Standing::from_reputation(MAX_REPUTATION), Standing::Exalted);
+    }
+
+    #[test]
+    fn test_reputation_bounds() {
+        let syndicate = FactionId::from_str("syndicate").unwrap();
+        let mut reputation = Reputation::default();
+
+        assert_eq!(reputation.standing(&syndicate), Standing::Neutral);
+        assert_eq!(reputation.adjust(&syndicate, 5000), MAX_REPUTATION);
+        assert_eq!(reputation.adjust(&syndicate, -1100), -100);
+        assert_eq!(reputation.standing(&syndicate), Standing::Unfriendly);
+    }
+}
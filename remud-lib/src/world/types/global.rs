@@ -0,0 +1,31 @@
+use std::collections::BTreeMap;
+
+use crate::world::types::object::FieldValue;
+
+// Resource holding the world-level values scripts use to coordinate, which are persisted
+#[derive(Debug, Default)]
+pub struct Globals {
+    by_key: BTreeMap<String, FieldValue>,
+}
+
+impl Globals {
+    pub fn get(&self, key: &str) -> Option<&FieldValue> {
+        self.by_key.get(key)
+    }
+
+    pub fn insert(&mut self, key: String, value: FieldValue) {
+        self.by_key.insert(key, value);
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<FieldValue> {
+        self.by_key.remove(key)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_key.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &FieldValue)> {
+        self.by_key.iter()
+    }
+}
@@ -0,0 +1,261 @@
+use std::{fmt, str::FromStr};
+
+use bevy_ecs::prelude::*;
+
+use crate::world::types::Named;
+
+/// How an entity is referred to in messages. Players choose their own, other entities are "it".
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Pronouns {
+    He,
+    She,
+    #[default]
+    They,
+    It,
+}
+
+impl Pronouns {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Pronouns::He => "he",
+            Pronouns::She => "she",
+            Pronouns::They => "they",
+            Pronouns::It => "it",
+        }
+    }
+
+    pub fn subject(&self) -> &'static str {
+        self.as_str()
+    }
+
+    pub fn object(&self) -> &'static str {
+        match self {
+            Pronouns::He => "him",
+            Pronouns::She => "her",
+            Pronouns::They => "them",
+            Pronouns::It => "it",
+        }
+    }
+
+    pub fn possessive(&self) -> &'static str {
+        match self {
+            Pronouns::He => "his",
+            Pronouns::She => "her",
+            Pronouns::They => "their",
+            Pronouns::It => "its",
+        }
+    }
+
+    pub fn reflexive(&self) -> &'static str {
+        match self {
+            Pronouns::He => "himself",
+            Pronouns::She => "herself",
+            Pronouns::They => "themself",
+            Pronouns::It => "itself",
+        }
+    }
+
+    /// Whether verbs following the subject pronoun take their plural form, as in "they are".
+    pub fn plural(&self) -> bool {
+        matches!(self, Pronouns::They)
+    }
+}
+
+impl FromStr for Pronouns {
+    type Err = PronounsParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "he" | "him" | "his" => Ok(Pronouns::He),
+            "she" | "her" | "hers" => Ok(Pronouns::She),
+            "they" | "them" | "their" => Ok(Pronouns::They),
+            "it" | "its" => Ok(Pronouns::It),
+            _ => Err(PronounsParseError {}),
+        }
+    }
+}
+
+impl fmt::Display for Pronouns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.subject(), self.object())
+    }
+}
+
+#[derive(Debug)]
+pub struct PronounsParseError {}
+
+impl std::error::Error for PronounsParseError {}
+
+impl fmt::Display for PronounsParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Enter pronouns: he, she, they, or it.")
+    }
+}
+
+/// An entity as it appears in a message: its name and how it is referred to.
+#[derive(Debug, Clone, Copy)]
+pub struct Subject<'a> {
+    name: &'a str,
+    pronouns: Pronouns,
+}
+
+impl<'a> Subject<'a> {
+    pub fn new(name: &'a str, pronouns: Pronouns) -> Self {
+        Subject { name, pronouns }
+    }
+
+    /// Looks up an entity's name and pronouns. Entities without pronouns are "it".
+    pub fn of(world: &'a World, entity: Entity) -> Option<Self> {
+        let name = world.get::<Named>(entity)?.as_str();
+        let pronouns = world
+            .get::<Pronouns>(entity)
+            .copied()
+            .unwrap_or(Pronouns::It);
+        Some(Subject::new(name, pronouns))
+    }
+}
+
+/// Fills in a message template about an actor and an optional target. Lowercase tokens refer to
+/// the actor and uppercase tokens to the target:
+///
+/// - `$n` / `$N` - name
+/// - `$e` / `$E` - subject pronoun (he, she, they, it)
+/// - `$m` / `$M` - object pronoun (him, her, them, it)
+/// - `$s` / `$S` - possessive pronoun (his, her, their, its)
+/// - `$f` / `$F` - reflexive pronoun (himself, herself, themself, itself)
+/// - `$v[is|are]` / `$V[is|are]` - the first verb form, or the second for plural pronouns
+/// - `$$` - a dollar sign
+///
+/// Tokens which cannot be filled in are left as they are. A pronoun starting the message is
+/// capitalized.
+pub fn act(template: &str, actor: Subject, target: Option<Subject>) -> String {
+    let mut message = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(index) = rest.find('$') {
+        message.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+
+        let token = match rest.chars().next() {
+            Some(token) => token,
+            None => {
+                message.push('$');
+                break;
+            }
+        };
+
+        if token == '$' {
+            message.push('$');
+            rest = &rest[1..];
+            continue;
+        }
+
+        let subject = if token.is_ascii_uppercase() {
+            target
+        } else {
+            Some(actor)
+        };
+
+        let subject = match subject {
+            Some(subject) => subject,
+            None => {
+                message.push('$');
+                continue;
+            }
+        };
+
+        let after = &rest[token.len_utf8()..];
+        let (word, after) = match token.to_ascii_lowercase() {
+            'n' => (subject.name, after),
+            'e' => (subject.pronouns.subject(), after),
+            'm' => (subject.pronouns.object(), after),
+            's' => (subject.pronouns.possessive(), after),
+            'f' => (subject.pronouns.reflexive(), after),
+            'v' => match verb_forms(after) {
+                Some((singular, plural, after)) => {
+                    if subject.pronouns.plural() {
+                        (plural, after)
+                    } else {
+                        (singular, after)
+                    }
+                }
+                None => {
+                    message.push('$');
+                    continue;
+                }
+            },
+            _ => {
+                message.push('$');
+                continue;
+            }
+        };
+
+        // Names are left as they are, but a pronoun starting the message is capitalized.
+        if message.is_empty() && !token.eq_ignore_ascii_case(&'n') {
+            message.push_str(capitalize(word).as_str());
+        } else {
+            message.push_str(word);
+        }
+        rest = after;
+    }
+
+    message.push_str(rest);
+
+    message
+}
+
+// Splits "[singular|plural]rest" into its parts.
+fn verb_forms(text: &str) -> Option<(&str, &str, &str)> {
+    let (forms, rest) = text.strip_prefix('[')?.split_once(']')?;
+    let (singular, plural) = forms.split_once('|')?;
+    Some((singular, plural, rest))
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{act, Pronouns, Subject};
+
+    #[test]
+    fn test_act() {
+        let shane = Subject::new("Shane", Pronouns::He);
+        let ada = Subject::new("Ada", Pronouns::They);
+
+        assert_eq!(
+            act("$n scratches $s head.", shane, None),
+            "Shane scratches his head."
+        );
+        assert_eq!(
+            act("$n waves at $N, who ignores $m.", shane, Some(ada)),
+            "Shane waves at Ada, who ignores him."
+        );
+        assert_eq!(
+            act("$E $V[is|are] beside $f.", shane, Some(ada)),
+            "They are beside himself."
+        );
+        assert_eq!(act("$e $v[is|are] here.", shane, None), "He is here.");
+        assert_eq!(
+            act("$n pays $$5 to $N.", shane, None),
+            "Shane pays $5 to $N."
+        );
+        assert_eq!(act("$x marks the spot.", shane, None), "$x marks the spot.");
+        assert_eq!(
+            act("$n waves.", Subject::new("krixi", Pronouns::She), None),
+            "krixi waves."
+        );
+    }
+
+    #[test]
+    fn test_pronouns_parse() {
+        assert_eq!("She".parse::<Pronouns>().unwrap(), Pronouns::She);
+        assert_eq!("them".parse::<Pronouns>().unwrap(), Pronouns::They);
+        assert!("xe".parse::<Pronouns>().is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use itertools::Itertools;
+
+use crate::text::format_duration;
+
+/// The number of players ranked on each leaderboard.
+pub const LEADERBOARD_SIZE: i64 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LeaderboardCategory {
+    Explored,
+    Playtime,
+}
+
+impl LeaderboardCategory {
+    pub const ALL: [LeaderboardCategory; 2] =
+        [LeaderboardCategory::Explored, LeaderboardCategory::Playtime];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaderboardCategory::Explored => "explored",
+            LeaderboardCategory::Playtime => "playtime",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            LeaderboardCategory::Explored => "rooms explored",
+            LeaderboardCategory::Playtime => "time played",
+        }
+    }
+
+    pub fn format_value(&self, value: i64) -> String {
+        match self {
+            LeaderboardCategory::Explored => {
+                if value == 1 {
+                    "1 room".to_string()
+                } else {
+                    format!("{} rooms", value)
+                }
+            }
+            LeaderboardCategory::Playtime => format_duration(value),
+        }
+    }
+}
+
+impl FromStr for LeaderboardCategory {
+    type Err = LeaderboardCategoryParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LeaderboardCategory::ALL
+            .iter()
+            .find(|category| category.as_str() == s.to_lowercase())
+            .copied()
+            .ok_or(LeaderboardCategoryParseError {})
+    }
+}
+
+impl fmt::Display for LeaderboardCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct LeaderboardCategoryParseError {}
+
+impl std::error::Error for LeaderboardCategoryParseError {}
+
+impl fmt::Display for LeaderboardCategoryParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Enter a leaderboard category: {}.",
+            LeaderboardCategory::ALL
+                .iter()
+                .map(LeaderboardCategory::as_str)
+                .join(" or ")
+        )
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub value: i64,
+}
+
+// Resource holding the most recently aggregated leaderboards
+#[derive(Default)]
+pub struct Leaderboards {
+    boards: HashMap<LeaderboardCategory, Vec<LeaderboardEntry>>,
+}
+
+impl Leaderboards {
+    pub fn get(&self, category: LeaderboardCategory) -> &[LeaderboardEntry] {
+        self.boards
+            .get(&category)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn set(&mut self, category: LeaderboardCategory, entries: Vec<LeaderboardEntry>) {
+        self.boards.insert(category, entries);
+    }
+}
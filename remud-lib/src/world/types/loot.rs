@@ -0,0 +1,303 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use rand::Rng;
+use thiserror::Error;
+
+use crate::world::types::object::PrototypeId;
+
+pub const DEFAULT_LOOT_ROLLS: u32 = 1;
+
+// Loot table IDs are referenced by scripts, so they are kept simple: lowercase ASCII letters,
+// digits, dashes, and underscores.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct LootTableId(String);
+
+impl FromStr for LootTableId {
+    type Err = LootTableIdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            Ok(LootTableId(s.to_string()))
+        } else {
+            Err(LootTableIdParseError {})
+        }
+    }
+}
+
+impl fmt::Display for LootTableId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Loot table IDs must contain only lowercase letters, digits, dashes, and underscores.")]
+pub struct LootTableIdParseError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl Rarity {
+    pub const ALL: [Rarity; 5] = [
+        Rarity::Common,
+        Rarity::Uncommon,
+        Rarity::Rare,
+        Rarity::Epic,
+        Rarity::Legendary,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Rarity::Common => "common",
+            Rarity::Uncommon => "uncommon",
+            Rarity::Rare => "rare",
+            Rarity::Epic => "epic",
+            Rarity::Legendary => "legendary",
+        }
+    }
+
+    // The weight given to entries of this rarity when none is specified
+    pub fn default_weight(&self) -> u32 {
+        match self {
+            Rarity::Common => 100,
+            Rarity::Uncommon => 40,
+            Rarity::Rare => 10,
+            Rarity::Epic => 3,
+            Rarity::Legendary => 1,
+        }
+    }
+
+    // The price in coins shops charge for items of this rarity before their markup
+    pub fn base_price(&self) -> i64 {
+        match self {
+            Rarity::Common => 10,
+            Rarity::Uncommon => 50,
+            Rarity::Rare => 200,
+            Rarity::Epic => 1000,
+            Rarity::Legendary => 5000,
+        }
+    }
+}
+
+impl FromStr for Rarity {
+    type Err = RarityParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Rarity::ALL
+            .iter()
+            .find(|rarity| rarity.as_str() == s)
+            .copied()
+            .ok_or(RarityParseError {})
+    }
+}
+
+impl fmt::Display for Rarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Rarities are common, uncommon, rare, epic, and legendary.")]
+pub struct RarityParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LootEntry {
+    // the prototype dropped, or None when the entry drops nothing
+    pub prototype: Option<PrototypeId>,
+    pub weight: u32,
+    pub min: u32,
+    pub max: u32,
+    pub rarity: Rarity,
+}
+
+impl LootEntry {
+    pub fn expected_quantity(&self) -> f64 {
+        if self.prototype.is_some() {
+            (self.min + self.max) as f64 / 2.0
+        } else {
+            0.0
+        }
+    }
+
+    // The chance that the entry drops nothing when it is picked
+    pub fn chance_of_nothing(&self) -> f64 {
+        if self.prototype.is_none() {
+            1.0
+        } else if self.min == 0 {
+            1.0 / (self.max + 1) as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LootDrop {
+    pub prototype: PrototypeId,
+    pub quantity: u32,
+    pub rarity: Rarity,
+}
+
+/// A weighted list of drops. Each roll picks one entry by weight, and a table is rolled `rolls`
+/// times each time it drops loot.
+#[derive(Debug, Clone)]
+pub struct LootTable {
+    pub rolls: u32,
+    pub entries: Vec<LootEntry>,
+}
+
+impl LootTable {
+    pub fn new(rolls: u32) -> Self {
+        LootTable {
+            rolls,
+            entries: Vec::new(),
+        }
+    }
+
+    fn total_weight(&self) -> u64 {
+        self.entries.iter().map(|entry| entry.weight as u64).sum()
+    }
+
+    /// The chance of each entry being picked by a single roll.
+    pub fn chances(&self) -> Vec<f64> {
+        let total = self.total_weight();
+
+        self.entries
+            .iter()
+            .map(|entry| {
+                if total == 0 {
+                    0.0
+                } else {
+                    entry.weight as f64 / total as f64
+                }
+            })
+            .collect()
+    }
+
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> Vec<LootDrop> {
+        let total = self.total_weight();
+        if total == 0 {
+            return Vec::new();
+        }
+
+        let mut drops = Vec::new();
+
+        for _ in 0..self.rolls {
+            let mut roll = rng.gen_range(0..total);
+            let entry = self.entries.iter().find(|entry| {
+                if roll < entry.weight as u64 {
+                    true
+                } else {
+                    roll -= entry.weight as u64;
+                    false
+                }
+            });
+
+            if let Some(LootEntry {
+                prototype: Some(prototype),
+                min,
+                max,
+                rarity,
+                ..
+            }) = entry
+            {
+                let quantity = rng.gen_range(*min..=*max);
+                if quantity > 0 {
+                    drops.push(LootDrop {
+                        prototype: *prototype,
+                        quantity,
+                        rarity: *rarity,
+                    });
+                }
+            }
+        }
+
+        drops
+    }
+}
+
+// Resource holding every loot table
+#[derive(Default)]
+pub struct LootTables {
+    by_id: BTreeMap<LootTableId, LootTable>,
+}
+
+impl LootTables {
+    pub fn insert(&mut self, id: LootTableId, table: LootTable) {
+        self.by_id.insert(id, table);
+    }
+
+    pub fn contains(&self, id: &LootTableId) -> bool {
+        self.by_id.contains_key(id)
+    }
+
+    pub fn get(&self, id: &LootTableId) -> Option<&LootTable> {
+        self.by_id.get(id)
+    }
+
+    pub fn get_mut(&mut self, id: &LootTableId) -> Option<&mut LootTable> {
+        self.by_id.get_mut(id)
+    }
+
+    pub fn remove(&mut self, id: &LootTableId) -> Option<LootTable> {
+        self.by_id.remove(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&LootTableId, &LootTable)> {
+        self.by_id.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_roll() {
+        let mut table = LootTable::new(3);
+        table.entries.push(LootEntry {
+            prototype: None,
+            weight: 1,
+            min: 1,
+            max: 1,
+            rarity: Rarity::Common,
+        });
+        table.entries.push(LootEntry {
+            prototype: Some(PrototypeId::try_from(1).unwrap()),
+            weight: 3,
+            min: 2,
+            max: 4,
+            rarity: Rarity::Rare,
+        });
+
+        assert_eq!(table.chances(), vec![0.25, 0.75]);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            let drops = table.roll(&mut rng);
+            assert!(drops.len() <= 3);
+            assert!(drops
+                .iter()
+                .all(|drop| (2..=4).contains(&drop.quantity) && drop.rarity == Rarity::Rare));
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    fmt,
+    str::FromStr,
+    time::Duration,
+};
+
+use bevy_ecs::prelude::*;
+use thiserror::Error;
+
+use crate::world::types::player::PlayerId;
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct MailId(i64);
+
+impl TryFrom<i64> for MailId {
+    type Error = MailIdParseError;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        if value >= 0 {
+            Ok(MailId(value))
+        } else {
+            Err(MailIdParseError {})
+        }
+    }
+}
+
+impl FromStr for MailId {
+    type Err = MailIdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<i64>()
+            .map_err(|_| MailIdParseError {})
+            .and_then(MailId::try_from)
+    }
+}
+
+impl From<MailId> for i64 {
+    fn from(id: MailId) -> Self {
+        id.0
+    }
+}
+
+impl fmt::Display for MailId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Mail IDs must be non-negative integers.")]
+pub struct MailIdParseError {}
+
+/// A letter from one player to another. Attached coins and objects are held in escrow, the
+/// object out of sight, until the recipient claims them, or until the letter is deleted or
+/// expires and they are returned to the sender.
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub sender: PlayerId,
+    pub sender_name: String,
+    pub recipient: PlayerId,
+    pub recipient_name: String,
+    pub body: String,
+    pub coins: i64,
+    pub object: Option<Entity>,
+    // seconds since the Unix epoch
+    pub sent_at: i64,
+    pub expires_at: i64,
+    pub read: bool,
+}
+
+impl Mail {
+    pub fn has_attachments(&self) -> bool {
+        self.coins > 0 || self.object.is_some()
+    }
+}
+
+// Resource holding every player's mail, along with the name of every player so that mail can
+// be sent to players who are offline.
+pub struct Mailbox {
+    mail: BTreeMap<MailId, Mail>,
+    addresses: HashMap<String, PlayerId>,
+    highest_id: i64,
+    expiry: Duration,
+}
+
+impl Mailbox {
+    pub fn new(
+        highest_id: i64,
+        mail: BTreeMap<MailId, Mail>,
+        addresses: HashMap<String, PlayerId>,
+    ) -> Self {
+        Mailbox {
+            mail,
+            addresses,
+            highest_id,
+            expiry: Duration::default(),
+        }
+    }
+
+    pub fn next_id(&mut self) -> MailId {
+        self.highest_id += 1;
+        MailId(self.highest_id)
+    }
+
+    pub fn expiry(&self) -> Duration {
+        self.expiry
+    }
+
+    pub fn set_expiry(&mut self, expiry: Duration) {
+        self.expiry = expiry;
+    }
+
+    pub fn address(&self, name: &str) -> Option<PlayerId> {
+        self.addresses.get(name).copied()
+    }
+
+    /// Records a player's current name, forgetting any name they had before.
+    pub fn register(&mut self, id: PlayerId, name: &str) {
+        self.addresses.retain(|_, player| *player != id);
+        self.addresses.insert(name.to_string(), id);
+    }
+
+    /// Forgets a player who has been deleted, removing and returning the mail they sent or
+    /// received.
+    pub fn forget(&mut self, name: &str) -> Vec<Mail> {
+        let id = match self.addresses.remove(name) {
+            Some(id) => id,
+            None => return Vec::new(),
+        };
+
+        self.take(|mail| mail.sender == id || mail.recipient == id)
+            .into_iter()
+            .map(|(_, mail)| mail)
+            .collect()
+    }
+
+    pub fn insert(&mut self, id: MailId, mail: Mail) {
+        self.mail.insert(id, mail);
+    }
+
+    pub fn get(&self, id: MailId) -> Option<&Mail> {
+        self.mail.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: MailId) -> Option<&mut Mail> {
+        self.mail.get_mut(&id)
+    }
+
+    pub fn remove(&mut self, id: MailId) -> Option<Mail> {
+        self.mail.remove(&id)
+    }
+
+    #[cfg(feature = "invariants")]
+    pub fn iter(&self) -> impl Iterator<Item = (&MailId, &Mail)> {
+        self.mail.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mail.is_empty()
+    }
+
+    /// The mail a player has received, oldest first.
+    pub fn received(&self, recipient: PlayerId) -> impl Iterator<Item = (&MailId, &Mail)> {
+        self.mail
+            .iter()
+            .filter(move |(_, mail)| mail.recipient == recipient)
+    }
+
+    pub fn unread(&self, recipient: PlayerId) -> usize {
+        self.received(recipient)
+            .filter(|(_, mail)| !mail.read)
+            .count()
+    }
+
+    /// Removes and returns the mail which has expired by the given time.
+    pub fn take_expired(&mut self, now: i64) -> Vec<(MailId, Mail)> {
+        self.take(|mail| mail.expires_at <= now)
+    }
+
+    fn take(&mut self, predicate: impl Fn(&Mail) -> bool) -> Vec<(MailId, Mail)> {
+        let taken = self
+            .mail
+            .iter()
+            .filter(|(_, mail)| predicate(mail))
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        taken
+            .into_iter()
+            .filter_map(|id| self.mail.remove(&id).map(|mail| (id, mail)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letter(sender: i64, recipient: i64, expires_at: i64) -> Mail {
+        Mail {
+            sender: PlayerId::try_from(sender).unwrap(),
+            sender_name: "sender".to_string(),
+            recipient: PlayerId::try_from(recipient).unwrap(),
+            recipient_name: "recipient".to_string(),
+            body: "hello".to_string(),
+            coins: 0,
+            object: None,
+            sent_at: 0,
+            expires_at,
+            read: false,
+        }
+    }
+
+    #[test]
+    fn test_take_expired() {
+        let mut mailbox = Mailbox::new(0, BTreeMap::new(), HashMap::new());
+
+        let first = mailbox.next_id();
+        mailbox.insert(first, letter(1, 2, 10));
+        let second = mailbox.next_id();
+        mailbox.insert(second, letter(1, 2, 20));
+
+        let expired = mailbox.take_expired(15);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].0, first);
+        assert!(mailbox.get(second).is_some());
+    }
+
+    #[test]
+    fn test_register_forgets_old_names() {
+        let mut mailbox = Mailbox::new(0, BTreeMap::new(), HashMap::new());
+        let id = PlayerId::try_from(2).unwrap();
+
+        mailbox.register(id, "Shane");
+        mailbox.register(id, "Krixi");
+
+        assert_eq!(mailbox.address("Shane"), None);
+        assert_eq!(mailbox.address("Krixi"), Some(id));
+
+        let unread = mailbox.next_id();
+        mailbox.insert(unread, letter(1, 2, 10));
+        let read = mailbox.next_id();
+        mailbox.insert(read, letter(1, 2, 10));
+        mailbox.get_mut(read).unwrap().read = true;
+
+        assert_eq!(mailbox.unread(id), 1);
+        assert_eq!(mailbox.forget("Krixi").len(), 2);
+        assert!(mailbox.is_empty());
+    }
+}
@@ -0,0 +1,125 @@
+use std::{collections::BTreeMap, convert::TryFrom, fmt, str::FromStr, time::Duration};
+
+use bevy_ecs::prelude::*;
+use thiserror::Error;
+
+use crate::world::types::player::PlayerId;
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct ListingId(i64);
+
+impl TryFrom<i64> for ListingId {
+    type Error = ListingIdParseError;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        if value >= 0 {
+            Ok(ListingId(value))
+        } else {
+            Err(ListingIdParseError {})
+        }
+    }
+}
+
+impl FromStr for ListingId {
+    type Err = ListingIdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<i64>()
+            .map_err(|_| ListingIdParseError {})
+            .and_then(ListingId::try_from)
+    }
+}
+
+impl From<ListingId> for i64 {
+    fn from(id: ListingId) -> Self {
+        id.0
+    }
+}
+
+impl fmt::Display for ListingId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Listing IDs must be non-negative integers.")]
+pub struct ListingIdParseError {}
+
+/// An object offered for sale. The object is held in escrow, out of sight, until it is
+/// bought, the listing is cancelled, or the listing expires.
+#[derive(Debug, Clone)]
+pub struct Listing {
+    pub seller: PlayerId,
+    pub seller_name: String,
+    pub object: Entity,
+    pub price: i64,
+    // seconds since the Unix epoch
+    pub expires_at: i64,
+}
+
+// Resource holding every market listing
+pub struct Market {
+    listings: BTreeMap<ListingId, Listing>,
+    highest_id: i64,
+    listing_duration: Duration,
+}
+
+impl Market {
+    pub fn new(highest_id: i64, listings: BTreeMap<ListingId, Listing>) -> Self {
+        Market {
+            listings,
+            highest_id,
+            listing_duration: Duration::default(),
+        }
+    }
+
+    pub fn next_id(&mut self) -> ListingId {
+        self.highest_id += 1;
+        ListingId(self.highest_id)
+    }
+
+    pub fn listing_duration(&self) -> Duration {
+        self.listing_duration
+    }
+
+    pub fn set_listing_duration(&mut self, duration: Duration) {
+        self.listing_duration = duration;
+    }
+
+    pub fn insert(&mut self, id: ListingId, listing: Listing) {
+        self.listings.insert(id, listing);
+    }
+
+    pub fn get(&self, id: ListingId) -> Option<&Listing> {
+        self.listings.get(&id)
+    }
+
+    pub fn remove(&mut self, id: ListingId) -> Option<Listing> {
+        self.listings.remove(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&ListingId, &Listing)> {
+        self.listings.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.listings.is_empty()
+    }
+
+    /// Removes and returns the listings which have expired by the given time.
+    pub fn take_expired(&mut self, now: i64) -> Vec<(ListingId, Listing)> {
+        let expired = self
+            .listings
+            .iter()
+            .filter(|(_, listing)| listing.expires_at <= now)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        expired
+            .into_iter()
+            .filter_map(|id| self.listings.remove(&id).map(|listing| (id, listing)))
+            .collect()
+    }
+}
@@ -1,27 +1,69 @@
-use std::{fmt, ops::Index};
+use std::{
+    error, fmt,
+    ops::Index,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
-use bevy_ecs::prelude::Entity;
+use bevy_ecs::prelude::{Entity, World};
 use itertools::Itertools;
 
 use crate::{
     ecs::{Ecs, Plugin},
     world::types::{
-        object::{ObjectId, PrototypeId},
-        player::{PlayerId, Players},
-        room::RoomId,
+        art::Art,
+        environment::Weather,
+        leaderboard::Leaderboards,
+        object::{Bulk, ObjectId, Objects, PrototypeId, Prototypes},
+        player::{Departed, ForcedCommands, PlayerId, Players, Possessions, Snoops},
+        room::{LoadedRegions, RoomId, Rooms, Staging},
+        tutorial::HintQueue,
     },
 };
 
+pub mod achievement;
+pub mod art;
+pub mod bank;
+pub mod campaign;
+pub mod clan;
+pub mod communication;
+pub mod economy;
+pub mod environment;
+pub mod faction;
+pub mod global;
+pub mod grammar;
+pub mod leaderboard;
+pub mod loot;
+pub mod mail;
+pub mod market;
+pub mod news;
 pub mod object;
 pub mod player;
+pub mod poll;
+pub mod report;
 pub mod room;
+pub mod season;
+pub mod shop;
+pub mod spawn;
+pub mod template;
+pub mod tutorial;
 
 #[derive(Default)]
 pub struct TypesPlugin {}
 
 impl Plugin for TypesPlugin {
     fn build(&self, ecs: &mut Ecs) {
-        ecs.init_resource::<Players>();
+        ecs.init_resource::<Players>()
+            .init_resource::<Art>()
+            .init_resource::<Departed>()
+            .init_resource::<ForcedCommands>()
+            .init_resource::<Possessions>()
+            .init_resource::<Snoops>()
+            .init_resource::<Staging>()
+            .init_resource::<LoadedRegions>()
+            .init_resource::<Leaderboards>()
+            .init_resource::<HintQueue>()
+            .init_resource::<Weather>();
     }
 }
 
@@ -33,6 +75,19 @@ pub enum Id {
     Room(RoomId),
 }
 
+impl Id {
+    /// Finds the entity currently loaded for the ID. Entities are replaced when the world is
+    /// loaded or a player logs back in, while IDs are kept.
+    pub fn entity(&self, world: &World) -> Option<Entity> {
+        match self {
+            Id::Player(id) => world.get_resource::<Players>()?.by_id(*id),
+            Id::Prototype(id) => world.get_resource::<Prototypes>()?.by_id(*id),
+            Id::Object(id) => world.get_resource::<Objects>()?.by_id(*id),
+            Id::Room(id) => world.get_resource::<Rooms>()?.by_id(*id),
+        }
+    }
+}
+
 impl fmt::Display for Id {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -44,6 +99,37 @@ impl fmt::Display for Id {
     }
 }
 
+/// Parses IDs as they are displayed, such as "object 5".
+impl FromStr for Id {
+    type Err = IdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, id) = s.trim().split_once(' ').ok_or(IdParseError {})?;
+        let id = id.trim();
+        match kind {
+            "player" => Ok(Id::Player(id.parse().map_err(|_| IdParseError {})?)),
+            "prototype" => Ok(Id::Prototype(id.parse().map_err(|_| IdParseError {})?)),
+            "object" => Ok(Id::Object(id.parse().map_err(|_| IdParseError {})?)),
+            "room" => Ok(Id::Room(id.parse().map_err(|_| IdParseError {})?)),
+            _ => Err(IdParseError {}),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct IdParseError {}
+
+impl fmt::Display for IdParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "IDs are a player, prototype, object, or room followed by a number."
+        )
+    }
+}
+
+impl error::Error for IdParseError {}
+
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub enum ActionTarget {
     CurrentRoom,
@@ -217,6 +303,49 @@ pub struct Configuration {
     pub restart: bool,
     pub shutdown: bool,
     pub spawn_room: RoomId,
+    pub profile: Option<ProfileRequest>,
+    pub config_reload: Option<Entity>,
+    pub db_check: Option<DbCheckRequest>,
+    pub lint_request: Option<Entity>,
+    pub player_rename: Option<PlayerRenameRequest>,
+    pub motd: Option<String>,
+    // the art or text shown in large letters to connecting clients
+    pub login_banner: Option<String>,
+    pub max_players: Option<usize>,
+    // the message shown to players who are not on the allowlist, while it is enforced
+    pub allowlist_rejection: Option<String>,
+    // while on, only immortals may log in
+    pub maintenance: bool,
+    pub maintenance_notice: String,
+    // when mortals still online are disconnected for maintenance
+    pub maintenance_boot: Option<Instant>,
+    // while on, the database is unavailable and no one may log in
+    pub degraded: bool,
+    // while on, logging in as an online player takes over their session
+    pub takeover: bool,
+    // how long a deleted character is kept before it is purged
+    pub deletion_grace: Duration,
+    // while on, mortals grow hungry and thirsty over time
+    pub needs: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ProfileRequest {
+    pub actor: Entity,
+    pub ticks: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DbCheckRequest {
+    pub actor: Entity,
+    pub repair: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlayerRenameRequest {
+    pub actor: Entity,
+    pub old: String,
+    pub new: String,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -238,6 +367,16 @@ impl Default for Attributes {
     }
 }
 
+impl Attributes {
+    /// The total weight and size of the objects a player with these attributes can carry.
+    pub fn carry_capacity(&self) -> Bulk {
+        Bulk::new(
+            (self.strength * 10.0).max(0.0) as u32,
+            (self.strength + self.dexterity).max(0.0) as u32,
+        )
+    }
+}
+
 #[derive(Debug)]
 pub struct Health {
     pub current: f32,
@@ -251,4 +390,9 @@ impl Health {
             max: attr.constitution * 5.0,
         }
     }
+
+    /// Takes health away, leaving at least one point since nothing yet handles death.
+    pub fn damage(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(1.0_f32.min(self.current));
+    }
 }
@@ -0,0 +1,178 @@
+use std::{collections::BTreeMap, convert::TryFrom, fmt, str::FromStr};
+
+use thiserror::Error;
+
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct NewsId(i64);
+
+impl TryFrom<i64> for NewsId {
+    type Error = NewsIdParseError;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        if value >= 0 {
+            Ok(NewsId(value))
+        } else {
+            Err(NewsIdParseError {})
+        }
+    }
+}
+
+impl FromStr for NewsId {
+    type Err = NewsIdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<i64>()
+            .map_err(|_| NewsIdParseError {})
+            .and_then(NewsId::try_from)
+    }
+}
+
+impl From<NewsId> for i64 {
+    fn from(id: NewsId) -> Self {
+        id.0
+    }
+}
+
+impl fmt::Display for NewsId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("News IDs must be non-negative integers.")]
+pub struct NewsIdParseError {}
+
+/// An entry in the changelog, posted by an immortal.
+#[derive(Debug, Clone)]
+pub struct NewsEntry {
+    pub author: String,
+    pub body: String,
+    // seconds since the Unix epoch
+    pub posted_at: i64,
+}
+
+// Resource holding every news entry. IDs only ever grow, so a player has read everything up to
+// the highest ID they have seen.
+pub struct News {
+    entries: BTreeMap<NewsId, NewsEntry>,
+    highest_id: i64,
+}
+
+impl News {
+    pub fn new(highest_id: i64, entries: BTreeMap<NewsId, NewsEntry>) -> Self {
+        News {
+            entries,
+            highest_id,
+        }
+    }
+
+    pub fn next_id(&mut self) -> NewsId {
+        self.highest_id += 1;
+        NewsId(self.highest_id)
+    }
+
+    pub fn insert(&mut self, id: NewsId, entry: NewsEntry) {
+        self.entries.insert(id, entry);
+    }
+
+    pub fn remove(&mut self, id: NewsId) -> Option<NewsEntry> {
+        self.entries.remove(&id)
+    }
+
+    /// Credits a renamed player's entries to their new name.
+    pub fn rename_author(&mut self, old: &str, new: &str) {
+        for entry in self.entries.values_mut() {
+            if entry.author == old {
+                entry.author = new.to_string();
+            }
+        }
+    }
+
+    /// The ID of the newest entry, if there is any news.
+    pub fn latest(&self) -> Option<NewsId> {
+        self.entries.keys().next_back().copied()
+    }
+
+    /// Up to `count` of the newest entries, newest first.
+    pub fn recent(&self, count: usize) -> impl Iterator<Item = (&NewsId, &NewsEntry)> {
+        self.entries.iter().rev().take(count)
+    }
+
+    /// The number of entries posted after the given entry.
+    pub fn unread(&self, read: &NewsRead) -> usize {
+        self.entries.keys().filter(|id| **id > read.0).count()
+    }
+}
+
+/// The newest news entry a player has read.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NewsRead(NewsId);
+
+impl NewsRead {
+    pub fn last(&self) -> NewsId {
+        self.0
+    }
+
+    /// Records that the player has read up to the given entry, returning true if it is newer
+    /// than anything they had read before.
+    pub fn mark(&mut self, id: NewsId) -> bool {
+        if id > self.0 {
+            self.0 = id;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether the given entry was posted since the player last read the news.
+    pub fn is_unread(&self, id: NewsId) -> bool {
+        id > self.0
+    }
+}
+
+impl From<NewsId> for NewsRead {
+    fn from(id: NewsId) -> Self {
+        NewsRead(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(body: &str) -> NewsEntry {
+        NewsEntry {
+            author: "krixi".to_string(),
+            body: body.to_string(),
+            posted_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_unread() {
+        let mut news = News::new(0, BTreeMap::new());
+        let mut read = NewsRead::default();
+
+        let first = news.next_id();
+        news.insert(first, entry("first"));
+        let second = news.next_id();
+        news.insert(second, entry("second"));
+
+        assert_eq!(news.unread(&read), 2);
+        assert_eq!(news.latest(), Some(second));
+
+        assert!(read.mark(first));
+        assert_eq!(news.unread(&read), 1);
+        assert!(read.is_unread(second));
+
+        assert!(read.mark(second));
+        assert!(!read.mark(first));
+        assert_eq!(news.unread(&read), 0);
+
+        news.remove(second);
+        let third = news.next_id();
+        assert!(third > second);
+    }
+}
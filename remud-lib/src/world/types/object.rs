@@ -1,14 +1,19 @@
-use std::{collections::HashMap, convert::TryFrom, error, fmt, str::FromStr};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    error, fmt,
+    str::FromStr,
+};
 
 use bevy_ecs::prelude::*;
 use bitflags::bitflags;
-use rhai::ImmutableString;
+use rhai::{Dynamic, ImmutableString};
 use strum::EnumString;
 use thiserror::Error;
 
 use crate::{
     text::sorted_word_list,
-    world::types::{Description, Id, Location, Named},
+    world::types::{room::RoomId, Description, Id, Location, Named},
 };
 use itertools::Itertools;
 
@@ -19,6 +24,7 @@ pub struct PrototypeBundle {
     pub description: Description,
     pub flags: ObjectFlags,
     pub keywords: Keywords,
+    pub bulk: Bulk,
 }
 
 #[derive(Debug, Bundle)]
@@ -97,6 +103,41 @@ impl Object {
     }
 }
 
+/// How heavy and how large objects made from a prototype are, counted against what players can
+/// carry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bulk {
+    pub weight: u32,
+    pub size: u32,
+}
+
+impl Bulk {
+    pub fn new(weight: u32, size: u32) -> Self {
+        Bulk { weight, size }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weight == 0 && self.size == 0
+    }
+}
+
+impl std::ops::Add for Bulk {
+    type Output = Bulk;
+
+    fn add(self, other: Bulk) -> Bulk {
+        Bulk {
+            weight: self.weight.saturating_add(other.weight),
+            size: self.size.saturating_add(other.size),
+        }
+    }
+}
+
+impl fmt::Display for Bulk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "weight {}, size {}", self.weight, self.size)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Keywords {
     list: Vec<String>,
@@ -182,6 +223,277 @@ impl From<i64> for ObjectFlags {
     }
 }
 
+/// A value stored in an object or prototype's custom fields.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum FieldValue {
+    Bool(bool),
+    Int(i64),
+    String(String),
+}
+
+impl FieldValue {
+    /// Parses a value entered by a builder: true and false are booleans, whole numbers are
+    /// integers, and anything else is a string. Quotes force a string, as in "42".
+    pub fn parse(value: &str) -> Self {
+        if let Some(quoted) = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+        {
+            return FieldValue::String(quoted.to_string());
+        }
+
+        match value {
+            "true" => FieldValue::Bool(true),
+            "false" => FieldValue::Bool(false),
+            value => match value.parse::<i64>() {
+                Ok(int) => FieldValue::Int(int),
+                Err(_) => FieldValue::String(value.to_string()),
+            },
+        }
+    }
+
+    /// Recreates a value from its kind and text as stored in the database.
+    pub fn from_stored(kind: &str, value: String) -> Option<Self> {
+        match kind {
+            "bool" => value.parse().ok().map(FieldValue::Bool),
+            "int" => value.parse().ok().map(FieldValue::Int),
+            "string" => Some(FieldValue::String(value)),
+            _ => None,
+        }
+    }
+
+    /// The value as text for storage, without the quotes shown around strings.
+    pub fn stored_value(&self) -> String {
+        match self {
+            FieldValue::String(value) => value.clone(),
+            value => value.to_string(),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FieldValue::Bool(_) => "bool",
+            FieldValue::Int(_) => "int",
+            FieldValue::String(_) => "string",
+        }
+    }
+
+    pub fn to_dynamic(&self) -> Dynamic {
+        match self {
+            FieldValue::Bool(value) => Dynamic::from(*value),
+            FieldValue::Int(value) => Dynamic::from(*value),
+            FieldValue::String(value) => Dynamic::from(value.clone()),
+        }
+    }
+}
+
+impl TryFrom<Dynamic> for FieldValue {
+    type Error = &'static str;
+
+    fn try_from(value: Dynamic) -> Result<Self, Self::Error> {
+        if value.is::<bool>() {
+            Ok(FieldValue::Bool(value.cast()))
+        } else if value.is::<i64>() {
+            Ok(FieldValue::Int(value.cast()))
+        } else if value.is::<ImmutableString>() {
+            Ok(FieldValue::String(
+                value.cast::<ImmutableString>().to_string(),
+            ))
+        } else {
+            Err("field values must be strings, integers, or booleans")
+        }
+    }
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::Bool(value) => write!(f, "{}", value),
+            FieldValue::Int(value) => write!(f, "{}", value),
+            FieldValue::String(value) => write!(f, "{:?}", value),
+        }
+    }
+}
+
+/// Custom fields set on an object or prototype. Objects only hold the fields set on them
+/// directly, and fall back to their prototype's fields for the rest.
+#[derive(Debug, Clone, Default)]
+pub struct Fields {
+    map: BTreeMap<String, FieldValue>,
+}
+
+impl Fields {
+    pub fn new_with_entry(key: String, value: FieldValue) -> Self {
+        let mut map = BTreeMap::new();
+        map.insert(key, value);
+        Fields { map }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&FieldValue> {
+        self.map.get(key)
+    }
+
+    pub fn insert(&mut self, key: String, value: FieldValue) {
+        self.map.insert(key, value);
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<FieldValue> {
+        self.map.remove(key)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &FieldValue)> {
+        self.map.iter()
+    }
+}
+
+/// Looks up a custom field on an object or prototype. Fields which are not set on an object are
+/// taken from its prototype.
+pub fn resolve_field(world: &World, entity: Entity, key: &str) -> Option<FieldValue> {
+    if let Some(value) = world
+        .get::<Fields>(entity)
+        .and_then(|fields| fields.get(key))
+    {
+        return Some(value.clone());
+    }
+
+    let prototype = world.get::<Object>(entity)?.prototype();
+    world
+        .get::<Fields>(prototype)
+        .and_then(|fields| fields.get(key))
+        .cloned()
+}
+
+/// A built-in effect which objects made from a prototype have without any scripts.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum Behavior {
+    /// Moves players who use the object to a room.
+    Teleport(RoomId),
+    /// Hurts players who enter the room the object is in by an amount of health.
+    Trap(u32),
+    /// Tells players who pick the object up something.
+    Message(String),
+}
+
+impl Behavior {
+    /// Parses a behavior of the given kind from what a builder entered.
+    pub fn parse(kind: BehaviorKind, value: &str) -> Result<Self, String> {
+        match kind {
+            BehaviorKind::Teleport => value
+                .parse::<RoomId>()
+                .map(Behavior::Teleport)
+                .map_err(|e| e.to_string()),
+            BehaviorKind::Trap => match value.parse::<u32>() {
+                Ok(damage) if damage > 0 => Ok(Behavior::Trap(damage)),
+                _ => Err("Enter the damage the trap deals, such as 10.".to_string()),
+            },
+            BehaviorKind::Message => Ok(Behavior::Message(value.to_string())),
+        }
+    }
+
+    /// Recreates a behavior from its kind and value as stored in the database.
+    pub fn from_stored(kind: &str, value: String) -> Option<Self> {
+        match BehaviorKind::from_str(kind).ok()? {
+            BehaviorKind::Teleport => value.parse().ok().map(Behavior::Teleport),
+            BehaviorKind::Trap => value.parse().ok().map(Behavior::Trap),
+            BehaviorKind::Message => Some(Behavior::Message(value)),
+        }
+    }
+
+    /// The behavior's value as text for storage.
+    pub fn stored_value(&self) -> String {
+        match self {
+            Behavior::Teleport(room) => room.to_string(),
+            Behavior::Trap(damage) => damage.to_string(),
+            Behavior::Message(message) => message.clone(),
+        }
+    }
+
+    pub fn kind(&self) -> BehaviorKind {
+        match self {
+            Behavior::Teleport(_) => BehaviorKind::Teleport,
+            Behavior::Trap(_) => BehaviorKind::Trap,
+            Behavior::Message(_) => BehaviorKind::Message,
+        }
+    }
+}
+
+impl fmt::Display for Behavior {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Behavior::Teleport(room) => write!(f, "teleport to room {}", room),
+            Behavior::Trap(damage) => write!(f, "trap dealing {} damage", damage),
+            Behavior::Message(message) => write!(f, "message {:?}", message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, EnumString)]
+pub enum BehaviorKind {
+    #[strum(serialize = "teleport")]
+    Teleport,
+    #[strum(serialize = "trap")]
+    Trap,
+    #[strum(serialize = "message")]
+    Message,
+}
+
+impl BehaviorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BehaviorKind::Teleport => "teleport",
+            BehaviorKind::Trap => "trap",
+            BehaviorKind::Message => "message",
+        }
+    }
+}
+
+impl fmt::Display for BehaviorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The behaviors set on a prototype, at most one of each kind. Objects always behave as their
+/// prototype does.
+#[derive(Debug, Clone, Default)]
+pub struct Behaviors {
+    map: BTreeMap<BehaviorKind, Behavior>,
+}
+
+impl Behaviors {
+    pub fn get(&self, kind: BehaviorKind) -> Option<&Behavior> {
+        self.map.get(&kind)
+    }
+
+    pub fn insert(&mut self, behavior: Behavior) {
+        self.map.insert(behavior.kind(), behavior);
+    }
+
+    pub fn remove(&mut self, kind: BehaviorKind) -> Option<Behavior> {
+        self.map.remove(&kind)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Behavior> {
+        self.map.values()
+    }
+}
+
+impl From<Behavior> for Behaviors {
+    fn from(behavior: Behavior) -> Self {
+        let mut behaviors = Behaviors::default();
+        behaviors.insert(behavior);
+        behaviors
+    }
+}
+
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, EnumString)]
 pub enum InheritableFields {
     #[strum(serialize = "flags")]
@@ -190,6 +502,8 @@ pub enum InheritableFields {
     Name,
     #[strum(serialize = "desc")]
     Description,
+    #[strum(serialize = "fields")]
+    Fields,
     #[strum(serialize = "keywords")]
     Keywords,
     #[strum(serialize = "scripts")]
@@ -200,6 +514,9 @@ bitflags! {
     pub struct Flags: i64 {
         const FIXED = 0b0001;
         const SUBTLE = 0b0010;
+        const BANK = 0b0100;
+        const FOOD = 0b1000;
+        const DRINK = 0b10000;
     }
 }
 
@@ -213,6 +530,9 @@ impl TryFrom<&[String]> for Flags {
             match flag.to_lowercase().as_str() {
                 "fixed" => flags.insert(Flags::FIXED),
                 "subtle" => flags.insert(Flags::SUBTLE),
+                "bank" => flags.insert(Flags::BANK),
+                "drink" => flags.insert(Flags::DRINK),
+                "food" => flags.insert(Flags::FOOD),
                 _ => {
                     return Err(FlagsParseError {
                         invalid_flag: flag.to_string(),
@@ -226,7 +546,7 @@ impl TryFrom<&[String]> for Flags {
 }
 
 #[derive(Debug, Error)]
-#[error("Invalid object flag: {invalid_flag}. Valid flags: fixed, subtle.")]
+#[error("Invalid object flag: {invalid_flag}. Valid flags: bank, drink, fixed, food, subtle.")]
 pub struct FlagsParseError {
     invalid_flag: String,
 }
@@ -262,6 +582,12 @@ impl From<PrototypeId> for Id {
     }
 }
 
+impl From<PrototypeId> for i64 {
+    fn from(prototype: PrototypeId) -> Self {
+        prototype.0
+    }
+}
+
 impl fmt::Display for PrototypeId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}", self.0)
@@ -314,6 +640,12 @@ impl From<ObjectId> for Id {
     }
 }
 
+impl From<ObjectId> for i64 {
+    fn from(id: ObjectId) -> Self {
+        id.0
+    }
+}
+
 #[derive(Debug)]
 pub struct ObjectIdParseError {}
 impl fmt::Display for ObjectIdParseError {
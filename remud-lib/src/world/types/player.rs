@@ -1,27 +1,49 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
     convert::TryFrom,
     error, fmt,
+    str::FromStr,
+    time::{Duration, Instant},
 };
 
 use bevy_ecs::prelude::*;
 use bitflags::bitflags;
 use thiserror::Error;
 
-use crate::world::types::{Attributes, Contents, Description, Health, Id, Location, Named};
+use crate::{
+    color::ColorSupport,
+    text::DEFAULT_WIDTH,
+    world::types::{
+        achievement::EarnedAchievements, bank::Vault, faction::Reputation, grammar::Pronouns,
+        news::NewsRead, room::RoomId, tutorial::SeenHints, Attributes, Contents, Description,
+        Health, Id, Location, Named,
+    },
+};
 
 #[derive(Bundle)]
 pub struct PlayerBundle {
     pub id: Id,
     pub player: Player,
     pub messages: Messages,
+    pub ignores: Ignores,
+    pub communication_log: CommunicationLog,
+    pub achievements: EarnedAchievements,
+    pub reputation: Reputation,
+    pub playtime: Playtime,
+    pub visited: VisitedRooms,
+    pub seen_hints: SeenHints,
+    pub client_caps: ClientCaps,
     pub name: Named,
+    pub pronouns: Pronouns,
     pub description: Description,
     pub flags: PlayerFlags,
     pub location: Location,
     pub contents: Contents,
+    pub vault: Vault,
     pub attributes: Attributes,
     pub health: Health,
+    pub needs: Needs,
+    pub news_read: NewsRead,
 }
 
 pub struct Player {
@@ -40,6 +62,77 @@ impl From<PlayerId> for Player {
     }
 }
 
+/// How hungry and thirsty a player is, from 0 when sated up to [`Needs::MAX`]. Needs only grow
+/// while hunger and thirst are turned on in the configuration.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct Needs {
+    pub hunger: u8,
+    pub thirst: u8,
+}
+
+impl Needs {
+    pub const MAX: u8 = 100;
+    // how much each need grows every interval
+    const GROWTH: u8 = 10;
+    // how much eating or drinking something satisfies a need
+    const RELIEF: u8 = 50;
+
+    pub fn new(hunger: u8, thirst: u8) -> Self {
+        Needs {
+            hunger: hunger.min(Needs::MAX),
+            thirst: thirst.min(Needs::MAX),
+        }
+    }
+
+    /// Grows both needs, returning a message for each one which has become more pressing.
+    pub fn grow(&mut self) -> Vec<&'static str> {
+        let mut messages = Vec::new();
+
+        let hunger = (self.hunger + Needs::GROWTH).min(Needs::MAX);
+        if let Some(message) = need_message(self.hunger, hunger, HUNGER_MESSAGES) {
+            messages.push(message);
+        }
+        self.hunger = hunger;
+
+        let thirst = (self.thirst + Needs::GROWTH).min(Needs::MAX);
+        if let Some(message) = need_message(self.thirst, thirst, THIRST_MESSAGES) {
+            messages.push(message);
+        }
+        self.thirst = thirst;
+
+        messages
+    }
+
+    pub fn eat(&mut self) {
+        self.hunger = self.hunger.saturating_sub(Needs::RELIEF);
+    }
+
+    pub fn drink(&mut self) {
+        self.thirst = self.thirst.saturating_sub(Needs::RELIEF);
+    }
+}
+
+// What players are told as a need passes each level.
+const HUNGER_MESSAGES: [(u8, &str); 3] = [
+    (30, "You are getting hungry."),
+    (60, "You are hungry."),
+    (90, "You are starving!"),
+];
+const THIRST_MESSAGES: [(u8, &str); 3] = [
+    (30, "You are getting thirsty."),
+    (60, "You are thirsty."),
+    (90, "You are parched!"),
+];
+
+// The message for the highest level a need passed as it grew, if any.
+fn need_message(before: u8, after: u8, levels: [(u8, &'static str); 3]) -> Option<&'static str> {
+    levels
+        .iter()
+        .rev()
+        .find(|(level, _)| before < *level && after >= *level)
+        .map(|(_, message)| *message)
+}
+
 #[derive(Debug, Clone)]
 pub struct PlayerFlags {
     flags: Flags,
@@ -82,6 +175,11 @@ impl From<i64> for PlayerFlags {
 bitflags! {
     pub struct Flags: i64 {
         const IMMORTAL = 0b0001;
+        const BRIEF = 0b0010;
+        const TUTORIAL = 0b0100;
+        const ADMIN = 0b1000;
+        const BUILDER = 0b10000;
+        const PRIVATE = 0b100000;
     }
 }
 
@@ -94,6 +192,11 @@ impl TryFrom<&[String]> for Flags {
         for flag in strs {
             match flag.to_lowercase().as_str() {
                 "immortal" => flags.insert(Flags::IMMORTAL),
+                "brief" => flags.insert(Flags::BRIEF),
+                "tutorial" => flags.insert(Flags::TUTORIAL),
+                "admin" => flags.insert(Flags::ADMIN),
+                "builder" => flags.insert(Flags::BUILDER),
+                "private" => flags.insert(Flags::PRIVATE),
                 _ => {
                     return Err(FlagsParseError {
                         invalid_flag: flag.to_string(),
@@ -107,14 +210,245 @@ impl TryFrom<&[String]> for Flags {
 }
 
 #[derive(Debug, Error)]
-#[error("Invalid player flag: {invalid_flag}. Valid flags: immortal.")]
+#[error("Invalid player flag: {invalid_flag}. Valid flags: immortal, brief, tutorial, admin, builder, private.")]
 pub struct FlagsParseError {
     invalid_flag: String,
 }
 
+/// Players whose communication is hidden from this player.
+#[derive(Debug, Default)]
+pub struct Ignores {
+    players: HashMap<PlayerId, String>,
+}
+
+impl Ignores {
+    pub fn contains(&self, id: PlayerId) -> bool {
+        self.players.contains_key(&id)
+    }
+
+    pub fn insert(&mut self, id: PlayerId, name: String) -> bool {
+        self.players.insert(id, name).is_none()
+    }
+
+    /// Stops ignoring the named player, returning their ID if they were ignored.
+    pub fn remove(&mut self, name: &str) -> Option<PlayerId> {
+        let id = self
+            .players
+            .iter()
+            .find(|(_, ignored)| ignored.eq_ignore_ascii_case(name))
+            .map(|(id, _)| *id)?;
+
+        self.players.remove(&id);
+        Some(id)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.players.values().map(String::as_str)
+    }
+}
+
+/// What the player's client is known to support, as negotiated when it connected. Used to lay
+/// out output and available to scripts.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClientCaps {
+    // the width of the client's window in characters, if it has reported one
+    pub width: Option<usize>,
+    pub color: ColorSupport,
+    // the terminal type chosen during negotiation, such as "XTERM-256COLOR"
+    pub terminal: Option<String>,
+    // names of the MTTS features the terminal reported, such as "utf_8" or "screen_reader"
+    pub features: Vec<String>,
+    pub mxp: bool,
+    pub gmcp: bool,
+}
+
+impl ClientCaps {
+    /// The capabilities of web clients, which render every color.
+    pub fn web() -> Self {
+        ClientCaps {
+            color: ColorSupport::TrueColor,
+            ..ClientCaps::default()
+        }
+    }
+
+    /// The width to lay out output for, falling back to the default for clients which have not
+    /// reported one.
+    pub fn width(&self) -> usize {
+        self.width.unwrap_or(DEFAULT_WIDTH)
+    }
+
+    pub fn as_map(&self) -> rhai::Map {
+        let mut map = rhai::Map::new();
+
+        map.insert(
+            "width".into(),
+            self.width
+                .map(|width| rhai::Dynamic::from(width as i64))
+                .unwrap_or(rhai::Dynamic::UNIT),
+        );
+        map.insert(
+            "color".into(),
+            rhai::Dynamic::from(self.color.as_str().to_string()),
+        );
+        map.insert(
+            "terminal".into(),
+            self.terminal
+                .clone()
+                .map(rhai::Dynamic::from)
+                .unwrap_or(rhai::Dynamic::UNIT),
+        );
+        map.insert(
+            "features".into(),
+            rhai::Dynamic::from(
+                self.features
+                    .iter()
+                    .cloned()
+                    .map(rhai::Dynamic::from)
+                    .collect::<rhai::Array>(),
+            ),
+        );
+        map.insert("mxp".into(), rhai::Dynamic::from(self.mxp));
+        map.insert("gmcp".into(), rhai::Dynamic::from(self.gmcp));
+
+        map
+    }
+}
+
+impl Default for ClientCaps {
+    fn default() -> Self {
+        ClientCaps {
+            width: None,
+            color: ColorSupport::None,
+            terminal: None,
+            features: Vec::new(),
+            mxp: false,
+            gmcp: false,
+        }
+    }
+}
+
+/// A player's total playtime, and the time played since it was last recorded.
+pub struct Playtime {
+    // seconds already recorded to the player's total
+    total: i64,
+    since: Instant,
+}
+
+impl Playtime {
+    pub fn new(total: i64) -> Self {
+        Playtime {
+            total,
+            since: Instant::now(),
+        }
+    }
+
+    /// Returns the whole seconds played since the last call, carrying the remainder forward.
+    pub fn take_seconds(&mut self) -> i64 {
+        let seconds = self.since.elapsed().as_secs();
+        self.since += Duration::from_secs(seconds);
+        self.total += seconds as i64;
+        seconds as i64
+    }
+
+    /// Discards the time not yet recorded, such as time spent logged out.
+    pub fn resume(&mut self) {
+        self.since = Instant::now();
+    }
+
+    /// The player's total playtime, including the time not yet recorded.
+    pub fn total_seconds(&self) -> i64 {
+        self.total + self.since.elapsed().as_secs() as i64
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct VisitedRooms {
+    rooms: HashSet<RoomId>,
+}
+
+impl VisitedRooms {
+    pub fn contains(&self, room: &RoomId) -> bool {
+        self.rooms.contains(room)
+    }
+
+    /// Records a visit, returning true if the room had not been visited before.
+    pub fn insert(&mut self, room: RoomId) -> bool {
+        self.rooms.insert(room)
+    }
+}
+
+/// The number of communications from other players kept for each player.
+pub const COMMUNICATION_LOG_LENGTH: usize = 100;
+
+#[derive(Debug)]
+struct Communication {
+    speaker: PlayerId,
+    name: String,
+    message: String,
+}
+
+/// Communication a player has received from other players, kept as context for reports.
+#[derive(Debug, Default)]
+pub struct CommunicationLog {
+    entries: VecDeque<Communication>,
+}
+
+impl CommunicationLog {
+    pub fn record(&mut self, speaker: PlayerId, name: &str, message: String) {
+        if self.entries.len() == COMMUNICATION_LOG_LENGTH {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(Communication {
+            speaker,
+            name: name.to_string(),
+            message,
+        });
+    }
+
+    /// Finds a player who has communicated with this player by name, even if they are offline.
+    pub fn speaker_by_name(&self, name: &str) -> Option<PlayerId> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.name == name)
+            .map(|entry| entry.speaker)
+    }
+
+    /// Returns the speakers' names and their messages, oldest first.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.message.as_str()))
+    }
+
+    /// Returns up to the last count messages from the speaker, oldest first.
+    pub fn by_speaker(&self, speaker: PlayerId, count: usize) -> Vec<&str> {
+        let mut messages = self
+            .entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.speaker == speaker)
+            .take(count)
+            .map(|entry| entry.message.as_str())
+            .collect::<Vec<_>>();
+        messages.reverse();
+        messages
+    }
+}
+
+/// The number of lines of sent output kept for each player.
+pub const HISTORY_LENGTH: usize = 100;
+
 #[derive(Default)]
 pub struct Messages {
-    queue: VecDeque<String>,
+    // messages paired with whether they are recorded in the history
+    queue: VecDeque<(String, bool)>,
+    history: VecDeque<String>,
+    // whether the queued messages are followed by a prompt
+    prompt: bool,
+    // whether any queued message is meant for the player rather than broadcast to those present
+    direct: bool,
 }
 
 impl Messages {
@@ -123,14 +457,78 @@ impl Messages {
     }
 
     pub fn queue(&mut self, message: String) {
-        self.queue.push_back(message);
+        self.queue.push_back((message, true));
+        self.prompt = true;
+        self.direct = true;
+    }
+
+    /// Queues a message broadcast to everyone in a room or on a channel, such as someone else
+    /// speaking or arriving. Output made up only of broadcasts may be dropped for a connection
+    /// which has fallen behind.
+    pub fn queue_broadcast(&mut self, message: String) {
+        self.queue.push_back((message, true));
+        self.prompt = true;
+    }
+
+    /// Queues a message which is not recorded in the history, such as recalled output.
+    pub fn queue_unrecorded(&mut self, message: String) {
+        self.queue.push_back((message, false));
+        self.prompt = true;
+        self.direct = true;
+    }
+
+    /// Queues a message which is not followed by a prompt unless other queued messages are, such
+    /// as a line of a cutscene with more to come.
+    pub fn queue_without_prompt(&mut self, message: String) {
+        self.queue.push_back((message, true));
+        self.direct = true;
+    }
+
+    /// Whether the messages being sent should be followed by a prompt.
+    pub fn take_prompt(&mut self) -> bool {
+        std::mem::replace(&mut self.prompt, false)
+    }
+
+    /// Whether the messages being sent are only broadcasts.
+    pub fn take_broadcast(&mut self) -> bool {
+        !std::mem::replace(&mut self.direct, false)
+    }
+
+    /// Returns up to the last count lines of sent output, oldest first.
+    pub fn history(&self, count: usize) -> impl Iterator<Item = &str> {
+        let start = self.history.len().saturating_sub(count);
+        self.history.range(start..).map(String::as_str)
+    }
+
+    /// The messages waiting to be sent, oldest first.
+    pub fn queued(&self) -> impl Iterator<Item = &str> {
+        self.queue.iter().map(|(message, _)| message.as_str())
+    }
+
+    /// Drops the messages waiting to be sent without recording them, such as those queued for a
+    /// player while they were away.
+    pub fn discard_queue(&mut self) {
+        self.queue.clear();
+        self.prompt = false;
+        self.direct = false;
     }
 
     pub fn take_queue(&mut self) -> VecDeque<String> {
         let mut queue = VecDeque::new();
         std::mem::swap(&mut queue, &mut self.queue);
 
-        queue
+        let recorded = queue
+            .iter()
+            .filter(|(_, record)| *record)
+            .flat_map(|(message, _)| message.split("\r\n"));
+        for line in recorded {
+            if self.history.len() == HISTORY_LENGTH {
+                self.history.pop_front();
+            }
+            self.history.push_back(line.to_string());
+        }
+
+        queue.into_iter().map(|(message, _)| message).collect()
     }
 }
 
@@ -150,6 +548,15 @@ impl TryFrom<i64> for PlayerId {
     }
 }
 
+impl FromStr for PlayerId {
+    type Err = PlayerIdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let int = s.parse::<i64>().map_err(|_| PlayerIdParseError {})?;
+        PlayerId::try_from(int)
+    }
+}
+
 impl fmt::Display for PlayerId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -168,6 +575,7 @@ impl error::Error for PlayerIdParseError {}
 #[derive(Default)]
 pub struct Players {
     by_name: HashMap<String, Entity>,
+    by_id: HashMap<PlayerId, Entity>,
     id_by_name: HashMap<String, PlayerId>,
 }
 
@@ -176,13 +584,328 @@ impl Players {
         self.by_name.get(name).copied()
     }
 
+    pub fn by_id(&self, id: PlayerId) -> Option<Entity> {
+        self.by_id.get(&id).copied()
+    }
+
     pub fn insert(&mut self, player: Entity, name: String, id: PlayerId) {
         self.by_name.insert(name.clone(), player);
+        self.by_id.insert(id, player);
         self.id_by_name.insert(name, id);
     }
 
     pub fn remove(&mut self, name: &str) {
         self.by_name.remove(name);
-        self.id_by_name.remove(name);
+        if let Some(id) = self.id_by_name.remove(name) {
+            self.by_id.remove(&id);
+        }
+    }
+
+    pub fn online(&self) -> usize {
+        self.by_name.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Entity)> {
+        self.by_name
+            .iter()
+            .map(|(name, player)| (name.as_str(), *player))
+    }
+}
+
+// A player who logged out recently, still in the world but without their Player component.
+struct DepartedPlayer {
+    name: String,
+    player: Entity,
+    id: PlayerId,
+    until: Instant,
+}
+
+// Resource holding the players who logged out recently, oldest first, so a quick reconnect can
+// restore them as they were instead of loading them again. Players are kept until they expire or
+// room is needed for someone who left after them.
+#[derive(Default)]
+pub struct Departed {
+    players: VecDeque<DepartedPlayer>,
+    duration: Duration,
+    capacity: usize,
+}
+
+impl Departed {
+    pub fn set_policy(&mut self, duration: Duration, capacity: usize) {
+        self.duration = duration;
+        self.capacity = capacity;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.duration.is_zero() && self.capacity > 0
+    }
+
+    /// Keeps a player who has just logged out, returning the players forgotten to make room.
+    pub fn insert(
+        &mut self,
+        name: String,
+        player: Entity,
+        id: PlayerId,
+        now: Instant,
+    ) -> Vec<Entity> {
+        self.players.push_back(DepartedPlayer {
+            name,
+            player,
+            id,
+            until: now + self.duration,
+        });
+
+        self.take_expired(now)
+    }
+
+    /// Removes the named player so they can be restored.
+    pub fn take(&mut self, name: &str) -> Option<(Entity, PlayerId)> {
+        let index = self
+            .players
+            .iter()
+            .position(|departed| departed.name == name)?;
+        self.players
+            .remove(index)
+            .map(|departed| (departed.player, departed.id))
+    }
+
+    /// Removes the players who have been kept too long, or who no longer fit.
+    pub fn take_expired(&mut self, now: Instant) -> Vec<Entity> {
+        let mut expired = Vec::new();
+
+        while let Some(departed) = self.players.front() {
+            if departed.until > now && self.players.len() <= self.capacity {
+                break;
+            }
+            expired.extend(self.players.pop_front().map(|departed| departed.player));
+        }
+
+        expired
+    }
+}
+
+// Resource tracking the objects immortals are possessing. A possessing immortal's input acts as
+// the object they possess.
+#[derive(Default)]
+pub struct Possessions {
+    by_controller: HashMap<Entity, Entity>,
+}
+
+impl Possessions {
+    pub fn insert(&mut self, controller: Entity, target: Entity) {
+        self.by_controller.insert(controller, target);
+    }
+
+    pub fn target(&self, controller: Entity) -> Option<Entity> {
+        self.by_controller.get(&controller).copied()
+    }
+
+    pub fn controller(&self, target: Entity) -> Option<Entity> {
+        self.by_controller
+            .iter()
+            .find(|(_, possessed)| **possessed == target)
+            .map(|(controller, _)| *controller)
+    }
+
+    pub fn remove(&mut self, controller: Entity) -> Option<Entity> {
+        self.by_controller.remove(&controller)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Entity)> + '_ {
+        self.by_controller
+            .iter()
+            .map(|(controller, target)| (*controller, *target))
+    }
+}
+
+// A moderator's view of a player's input and output.
+#[derive(Debug, Clone, Copy)]
+pub struct Snoop {
+    pub target: Entity,
+    pub expires: Instant,
+}
+
+// Resource tracking the players immortals are snooping, and the snoops awaiting another
+// immortal's approval.
+#[derive(Default)]
+pub struct Snoops {
+    by_moderator: HashMap<Entity, Snoop>,
+    requests: HashMap<Entity, Entity>,
+    duration: Duration,
+    approval: bool,
+}
+
+impl Snoops {
+    pub fn set_policy(&mut self, duration: Duration, approval: bool) {
+        self.duration = duration;
+        self.approval = approval;
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    // Whether immortals who are not administrators need their snoops approved.
+    pub fn requires_approval(&self) -> bool {
+        self.approval
+    }
+
+    pub fn start(&mut self, moderator: Entity, target: Entity, now: Instant) {
+        self.requests.remove(&moderator);
+        self.by_moderator.insert(
+            moderator,
+            Snoop {
+                target,
+                expires: now + self.duration,
+            },
+        );
+    }
+
+    pub fn request(&mut self, moderator: Entity, target: Entity) {
+        self.requests.insert(moderator, target);
+    }
+
+    pub fn take_request(&mut self, moderator: Entity) -> Option<Entity> {
+        self.requests.remove(&moderator)
+    }
+
+    pub fn target(&self, moderator: Entity) -> Option<Entity> {
+        self.by_moderator.get(&moderator).map(|snoop| snoop.target)
+    }
+
+    pub fn stop(&mut self, moderator: Entity) -> Option<Entity> {
+        self.by_moderator
+            .remove(&moderator)
+            .map(|snoop| snoop.target)
+    }
+
+    pub fn moderators(&self, target: Entity) -> Vec<Entity> {
+        self.by_moderator
+            .iter()
+            .filter(|(_, snoop)| snoop.target == target)
+            .map(|(moderator, _)| *moderator)
+            .collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Entity)> + '_ {
+        self.by_moderator
+            .iter()
+            .map(|(moderator, snoop)| (*moderator, snoop.target))
+    }
+
+    /// Ends the snoops which have run out of time, returning their moderators and targets.
+    pub fn take_expired(&mut self, now: Instant) -> Vec<(Entity, Entity)> {
+        let expired = self
+            .by_moderator
+            .iter()
+            .filter(|(_, snoop)| snoop.expires <= now)
+            .map(|(moderator, snoop)| (*moderator, snoop.target))
+            .collect::<Vec<_>>();
+
+        for (moderator, _) in expired.iter() {
+            self.by_moderator.remove(moderator);
+        }
+
+        expired
+    }
+
+    /// Ends the snoops and requests of a player who is leaving, returning the moderators who were
+    /// snooping them.
+    pub fn remove_player(&mut self, player: Entity) -> Vec<Entity> {
+        self.by_moderator.remove(&player);
+        self.requests.remove(&player);
+        self.requests.retain(|_, target| *target != player);
+
+        let moderators = self.moderators(player);
+        for moderator in moderators.iter() {
+            self.by_moderator.remove(moderator);
+        }
+
+        moderators
+    }
+}
+
+// A command entered on a player's behalf, run as if they had typed it.
+#[derive(Debug, Clone)]
+pub struct ForcedCommand {
+    pub player: Entity,
+    pub command: String,
+    // whether an immortal target's immortal-only commands may be used
+    pub privileged: bool,
+}
+
+// Resource queueing forced commands until the world can parse them.
+#[derive(Default)]
+pub struct ForcedCommands {
+    queue: Vec<ForcedCommand>,
+}
+
+impl ForcedCommands {
+    pub fn push(&mut self, forced: ForcedCommand) {
+        self.queue.push(forced);
+    }
+
+    pub fn take(&mut self) -> Vec<ForcedCommand> {
+        std::mem::take(&mut self.queue)
+    }
+}
+
+// Resource holding the names of players who may log in while the allowlist is enforced. Names
+// are compared without regard to case.
+#[derive(Default)]
+pub struct Allowlist {
+    names: BTreeSet<String>,
+}
+
+impl Allowlist {
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(&name.to_lowercase())
+    }
+
+    /// Adds a name to the allowlist, returning false if it was already present.
+    pub fn insert(&mut self, name: &str) -> bool {
+        self.names.insert(name.to_lowercase())
+    }
+
+    /// Removes a name from the allowlist, returning false if it was not present.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.names.remove(&name.to_lowercase())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.names.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_needs_grow_with_messages() {
+        let mut needs = Needs::default();
+
+        assert!(needs.grow().is_empty());
+        assert!(needs.grow().is_empty());
+        assert_eq!(
+            needs.grow(),
+            vec!["You are getting hungry.", "You are getting thirsty."]
+        );
+
+        for _ in 0..10 {
+            needs.grow();
+        }
+        assert_eq!(needs, Needs::new(Needs::MAX, Needs::MAX));
+        assert!(needs.grow().is_empty());
+
+        needs.eat();
+        assert_eq!(needs, Needs::new(50, Needs::MAX));
+        needs.drink();
+        needs.drink();
+        assert_eq!(needs, Needs::new(50, 0));
     }
 }
@@ -0,0 +1,184 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    fmt,
+    str::FromStr,
+};
+
+use itertools::Itertools;
+use thiserror::Error;
+
+use crate::world::types::player::PlayerId;
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct PollId(i64);
+
+impl TryFrom<i64> for PollId {
+    type Error = PollIdParseError;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        if value >= 0 {
+            Ok(PollId(value))
+        } else {
+            Err(PollIdParseError {})
+        }
+    }
+}
+
+impl FromStr for PollId {
+    type Err = PollIdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<i64>()
+            .map_err(|_| PollIdParseError {})
+            .and_then(PollId::try_from)
+    }
+}
+
+impl From<PollId> for i64 {
+    fn from(id: PollId) -> Self {
+        id.0
+    }
+}
+
+impl fmt::Display for PollId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Poll IDs must be non-negative integers.")]
+pub struct PollIdParseError {}
+
+/// A question put to the players by an immortal. Every player may vote for one of its options
+/// once, until the poll is closed.
+#[derive(Debug, Clone)]
+pub struct Poll {
+    pub question: String,
+    pub author: String,
+    pub options: Vec<String>,
+    // the index of the option each player voted for
+    pub votes: HashMap<PlayerId, usize>,
+    // seconds since the Unix epoch
+    pub created_at: i64,
+    pub closed: bool,
+}
+
+impl Poll {
+    pub fn new(question: String, author: String, options: Vec<String>, created_at: i64) -> Self {
+        Poll {
+            question,
+            author,
+            options,
+            votes: HashMap::new(),
+            created_at,
+            closed: false,
+        }
+    }
+
+    /// Finds an option by its number, counting from 1, or by its text.
+    pub fn option(&self, option: &str) -> Option<usize> {
+        match option.parse::<usize>() {
+            Ok(number) if (1..=self.options.len()).contains(&number) => Some(number - 1),
+            _ => self
+                .options
+                .iter()
+                .position(|text| text.eq_ignore_ascii_case(option)),
+        }
+    }
+
+    /// The number of votes for each option, in order.
+    pub fn tally(&self) -> Vec<usize> {
+        let mut tally = vec![0; self.options.len()];
+        for option in self.votes.values() {
+            if let Some(count) = tally.get_mut(*option) {
+                *count += 1;
+            }
+        }
+        tally
+    }
+
+    /// Describes the votes for each option, such as "yes (3), no (1)".
+    pub fn results(&self) -> String {
+        self.options
+            .iter()
+            .zip(self.tally())
+            .map(|(option, count)| format!("{} ({})", option, count))
+            .join(", ")
+    }
+}
+
+// Resource holding every poll, open and closed.
+pub struct Polls {
+    polls: BTreeMap<PollId, Poll>,
+    highest_id: i64,
+}
+
+impl Polls {
+    pub fn new(highest_id: i64, polls: BTreeMap<PollId, Poll>) -> Self {
+        Polls { polls, highest_id }
+    }
+
+    pub fn next_id(&mut self) -> PollId {
+        self.highest_id += 1;
+        PollId(self.highest_id)
+    }
+
+    pub fn insert(&mut self, id: PollId, poll: Poll) {
+        self.polls.insert(id, poll);
+    }
+
+    pub fn get(&self, id: PollId) -> Option<&Poll> {
+        self.polls.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: PollId) -> Option<&mut Poll> {
+        self.polls.get_mut(&id)
+    }
+
+    /// Credits a renamed player's polls to their new name.
+    pub fn rename_author(&mut self, old: &str, new: &str) {
+        for poll in self.polls.values_mut() {
+            if poll.author == old {
+                poll.author = new.to_string();
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&PollId, &Poll)> {
+        self.polls.iter()
+    }
+
+    pub fn open(&self) -> impl Iterator<Item = (&PollId, &Poll)> {
+        self.polls.iter().filter(|(_, poll)| !poll.closed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options_and_tally() {
+        let mut poll = Poll::new(
+            "Which feature next?".to_string(),
+            "krixi".to_string(),
+            vec!["Boats".to_string(), "Pets".to_string()],
+            0,
+        );
+
+        assert_eq!(poll.option("1"), Some(0));
+        assert_eq!(poll.option("pets"), Some(1));
+        assert_eq!(poll.option("3"), None);
+        assert_eq!(poll.option("Dragons"), None);
+
+        poll.votes.insert(PlayerId::try_from(1).unwrap(), 1);
+        poll.votes.insert(PlayerId::try_from(2).unwrap(), 1);
+        poll.votes.insert(PlayerId::try_from(3).unwrap(), 0);
+
+        assert_eq!(poll.tally(), vec![1, 2]);
+        assert_eq!(poll.results(), "Boats (1), Pets (2)");
+    }
+}
@@ -0,0 +1,27 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct ReportId(i64);
+
+impl fmt::Display for ReportId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// Resource allocating IDs for moderation reports
+pub struct Reports {
+    highest_id: i64,
+}
+
+impl Reports {
+    pub fn new(highest_id: i64) -> Self {
+        Reports { highest_id }
+    }
+
+    pub fn next_id(&mut self) -> ReportId {
+        self.highest_id += 1;
+        ReportId(self.highest_id)
+    }
+}
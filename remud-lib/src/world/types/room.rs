@@ -1,8 +1,15 @@
-use std::{collections::HashMap, convert::TryFrom, error, fmt, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    error, fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 use bevy_ecs::prelude::*;
+use itertools::Itertools;
 
-use crate::world::types::{Contents, Description, Id, Named};
+use crate::world::types::{player::PlayerId, Contents, Description, Id, Named};
 
 #[derive(Bundle)]
 pub struct RoomBundle {
@@ -16,13 +23,21 @@ pub struct RoomBundle {
 
 pub struct Room {
     id: RoomId,
-    exits: HashMap<Direction, Entity>,
+    exits: HashMap<Direction, Exit>,
     players: Vec<Entity>,
+    leave: Option<String>,
+    arrive: Option<String>,
 }
 
 impl Room {
-    pub fn new(id: RoomId, exits: HashMap<Direction, Entity>, players: Vec<Entity>) -> Self {
-        Room { id, exits, players }
+    pub fn new(id: RoomId, exits: HashMap<Direction, Exit>, players: Vec<Entity>) -> Self {
+        Room {
+            id,
+            exits,
+            players,
+            leave: None,
+            arrive: None,
+        }
     }
 
     pub fn id(&self) -> RoomId {
@@ -30,21 +45,29 @@ impl Room {
     }
 
     pub fn exit(&self, direction: &Direction) -> Option<Entity> {
-        self.exits.get(direction).copied()
+        self.exits.get(direction).map(|exit| exit.destination)
     }
 
-    pub fn exits(&self) -> &HashMap<Direction, Entity> {
+    pub fn exits(&self) -> &HashMap<Direction, Exit> {
         &self.exits
     }
 
-    pub fn insert_exit(&mut self, direction: Direction, destination: Entity) {
-        self.exits.insert(direction, destination);
+    pub fn insert_exit(&mut self, direction: Direction, exit: Exit) {
+        self.exits.insert(direction, exit);
     }
 
-    pub fn remove_exit(&mut self, direction: &Direction) -> Option<Entity> {
+    pub fn remove_exit(&mut self, direction: &Direction) -> Option<Exit> {
         self.exits.remove(direction)
     }
 
+    /// Finds the exit leading to a room, if any.
+    pub fn exit_to(&self, destination: Entity) -> Option<&Direction> {
+        self.exits
+            .iter()
+            .find(|(_, exit)| exit.destination == destination)
+            .map(|(direction, _)| direction)
+    }
+
     pub fn players(&self) -> &[Entity] {
         self.players.as_slice()
     }
@@ -62,6 +85,21 @@ impl Room {
             self.players.remove(index);
         }
     }
+
+    /// The template shown when something moves through any exit of this room lacking its own.
+    pub fn echo(&self, echo: Echo) -> Option<&str> {
+        match echo {
+            Echo::Leave => self.leave.as_deref(),
+            Echo::Arrive => self.arrive.as_deref(),
+        }
+    }
+
+    pub fn set_echo(&mut self, echo: Echo, template: Option<String>) {
+        match echo {
+            Echo::Leave => self.leave = template,
+            Echo::Arrive => self.arrive = template,
+        }
+    }
 }
 
 impl From<RoomId> for Room {
@@ -70,10 +108,143 @@ impl From<RoomId> for Room {
             id,
             exits: HashMap::new(),
             players: Vec::new(),
+            leave: None,
+            arrive: None,
+        }
+    }
+}
+
+/// A staged copy of a live room, edited in place of the room while a builder stages its region.
+/// Shadows are never indexed by [`Rooms`] and have no [`Room`] component, so players cannot
+/// reach them. The live room as it was when staged is kept, so publishing can tell which changes
+/// were made to the live room in the meantime.
+pub struct Shadow {
+    room: Entity,
+    exits: HashMap<Direction, Exit>,
+    base: RoomSnapshot,
+}
+
+impl Shadow {
+    pub fn new(room: Entity, base: RoomSnapshot) -> Self {
+        Shadow {
+            room,
+            exits: base.exits.clone(),
+            base,
+        }
+    }
+
+    pub fn room(&self) -> Entity {
+        self.room
+    }
+
+    pub fn base(&self) -> &RoomSnapshot {
+        &self.base
+    }
+
+    pub fn exits(&self) -> &HashMap<Direction, Exit> {
+        &self.exits
+    }
+
+    pub fn insert_exit(&mut self, direction: Direction, exit: Exit) {
+        self.exits.insert(direction, exit);
+    }
+
+    pub fn remove_exit(&mut self, direction: &Direction) -> Option<Exit> {
+        self.exits.remove(direction)
+    }
+}
+
+/// The parts of a room which may be staged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomSnapshot {
+    pub name: String,
+    pub description: String,
+    pub exits: HashMap<Direction, Exit>,
+}
+
+/// Where an exit leads, and what players taking it are told, if anything beyond the usual.
+/// Exits may also replace the messages others see when something leaves or arrives through them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exit {
+    pub destination: Entity,
+    pub message: Option<String>,
+    pub leave: Option<String>,
+    pub arrive: Option<String>,
+}
+
+impl Exit {
+    pub fn new(destination: Entity, message: Option<String>) -> Self {
+        Exit {
+            destination,
+            message,
+            leave: None,
+            arrive: None,
+        }
+    }
+
+    pub fn echo(&self, echo: Echo) -> Option<&str> {
+        match echo {
+            Echo::Leave => self.leave.as_deref(),
+            Echo::Arrive => self.arrive.as_deref(),
+        }
+    }
+
+    pub fn set_echo(&mut self, echo: Echo, template: Option<String>) {
+        match echo {
+            Echo::Leave => self.leave = template,
+            Echo::Arrive => self.arrive = template,
+        }
+    }
+}
+
+impl From<Entity> for Exit {
+    fn from(destination: Entity) -> Self {
+        Exit::new(destination, None)
+    }
+}
+
+/// The messages shown to onlookers as something moves between rooms. Builders may replace them
+/// with templates such as "$n squeezes through the vent.", see [`crate::world::types::grammar::act`].
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Echo {
+    Leave,
+    Arrive,
+}
+
+impl Echo {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Echo::Leave => "leave",
+            Echo::Arrive => "arrive",
         }
     }
 }
 
+impl FromStr for Echo {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "leave" => Ok(Echo::Leave),
+            "arrive" => Ok(Echo::Arrive),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Echo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Bundle)]
+pub struct ShadowBundle {
+    pub shadow: Shadow,
+    pub name: Named,
+    pub description: Description,
+}
+
 #[derive(Default)]
 pub struct Regions {
     list: Vec<String>,
@@ -101,6 +272,10 @@ impl Regions {
         self.list.clone()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.list.iter()
+    }
+
     pub fn set_list(&mut self, list: Vec<String>) {
         self.list = list;
     }
@@ -114,7 +289,40 @@ impl Regions {
     }
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, sqlx::Type)]
+/// The players who own regions. Builders may only script rooms and objects within regions they
+/// own.
+#[derive(Default)]
+pub struct RegionOwners {
+    owners: HashMap<String, PlayerId>,
+}
+
+impl RegionOwners {
+    pub fn set(&mut self, region: String, owner: PlayerId) {
+        self.owners.insert(region, owner);
+    }
+
+    pub fn remove(&mut self, region: &str) -> Option<PlayerId> {
+        self.owners.remove(region)
+    }
+
+    pub fn owner(&self, region: &str) -> Option<PlayerId> {
+        self.owners.get(region).copied()
+    }
+
+    /// Whether the player owns any of the regions.
+    pub fn owns_any(&self, player: PlayerId, regions: &Regions) -> bool {
+        regions
+            .list
+            .iter()
+            .any(|region| self.owner(region.as_str()) == Some(player))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &PlayerId)> {
+        self.owners.iter()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Ord, PartialOrd, sqlx::Type)]
 #[sqlx(transparent)]
 pub struct RoomId(i64);
 
@@ -145,6 +353,12 @@ impl fmt::Display for RoomId {
     }
 }
 
+impl From<RoomId> for i64 {
+    fn from(id: RoomId) -> Self {
+        id.0
+    }
+}
+
 impl From<RoomId> for Id {
     fn from(id: RoomId) -> Self {
         Id::Room(id)
@@ -160,7 +374,9 @@ impl fmt::Display for RoomIdParseError {
 }
 impl error::Error for RoomIdParseError {}
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// The name of an exit. Besides the cardinal directions, exits may be given any name, such as
+/// "portal" or "enter shop".
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Direction {
     North,
     East,
@@ -168,28 +384,43 @@ pub enum Direction {
     West,
     Up,
     Down,
+    Custom(String),
 }
 
 impl Direction {
-    pub fn as_from_str(&self) -> &str {
+    /// The direction with the given name, which is a custom exit unless it names a cardinal
+    /// direction. Custom names are lowercase with single spaces between words.
+    pub fn named(name: &str) -> Option<Direction> {
+        let name = name.split_whitespace().join(" ").to_lowercase();
+
+        if name.is_empty() || name.split(' ').any(|word| word.parse::<i64>().is_ok()) {
+            None
+        } else {
+            Some(Direction::from_str(name.as_str()).unwrap_or(Direction::Custom(name)))
+        }
+    }
+
+    pub fn as_from_str(&self) -> String {
         match self {
-            Direction::North => "from the north",
-            Direction::East => "from the east",
-            Direction::South => "from the south",
-            Direction::West => "from the west",
-            Direction::Up => "from above",
-            Direction::Down => "from below",
+            Direction::North => "from the north".to_string(),
+            Direction::East => "from the east".to_string(),
+            Direction::South => "from the south".to_string(),
+            Direction::West => "from the west".to_string(),
+            Direction::Up => "from above".to_string(),
+            Direction::Down => "from below".to_string(),
+            Direction::Custom(name) => format!("via {}", name),
         }
     }
 
-    pub fn as_to_str(&self) -> &str {
+    pub fn as_to_str(&self) -> String {
         match self {
-            Direction::North => "to the north",
-            Direction::East => "to the east",
-            Direction::South => "to the south",
-            Direction::West => "to the west",
-            Direction::Up => "up",
-            Direction::Down => "down",
+            Direction::North => "to the north".to_string(),
+            Direction::East => "to the east".to_string(),
+            Direction::South => "to the south".to_string(),
+            Direction::West => "to the west".to_string(),
+            Direction::Up => "up".to_string(),
+            Direction::Down => "down".to_string(),
+            Direction::Custom(name) => format!("via {}", name),
         }
     }
 
@@ -201,21 +432,25 @@ impl Direction {
             Direction::West => "west",
             Direction::Up => "up",
             Direction::Down => "down",
+            Direction::Custom(name) => name.as_str(),
         }
     }
 
-    pub fn opposite(self) -> Direction {
+    /// The direction leading back the way this one came. Custom exits have no opposite.
+    pub fn opposite(&self) -> Option<Direction> {
         match self {
-            Direction::North => Direction::South,
-            Direction::East => Direction::West,
-            Direction::South => Direction::North,
-            Direction::West => Direction::East,
-            Direction::Up => Direction::Down,
-            Direction::Down => Direction::Up,
+            Direction::North => Some(Direction::South),
+            Direction::East => Some(Direction::West),
+            Direction::South => Some(Direction::North),
+            Direction::West => Some(Direction::East),
+            Direction::Up => Some(Direction::Down),
+            Direction::Down => Some(Direction::Up),
+            Direction::Custom(_) => None,
         }
     }
 }
 
+// Parses cardinal directions only, see Direction::named for custom exits.
 impl FromStr for Direction {
     type Err = ();
 
@@ -234,30 +469,35 @@ impl FromStr for Direction {
 
 impl fmt::Display for Direction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Direction::North => write!(f, "north"),
-            Direction::East => write!(f, "east"),
-            Direction::South => write!(f, "south"),
-            Direction::West => write!(f, "west"),
-            Direction::Up => write!(f, "up"),
-            Direction::Down => write!(f, "down"),
-        }
+        write!(f, "{}", self.as_str())
     }
 }
 
 // Resource used as index for room/player lookups
 pub struct Rooms {
     by_id: HashMap<RoomId, Entity>,
+    // rooms which exist but are out of the world, while their regions are not needed
+    unloaded: HashSet<RoomId>,
     highest_id: i64,
 }
 
 impl Rooms {
     pub fn new(by_id: HashMap<RoomId, Entity>, highest_id: i64) -> Self {
-        Rooms { by_id, highest_id }
+        Rooms {
+            by_id,
+            unloaded: HashSet::new(),
+            highest_id,
+        }
+    }
+
+    pub fn with_unloaded(mut self, unloaded: HashSet<RoomId>) -> Self {
+        self.unloaded = unloaded;
+        self
     }
 
     pub fn insert(&mut self, id: RoomId, room: Entity) {
         self.by_id.insert(id, room);
+        self.unloaded.remove(&id);
     }
 
     pub fn by_id(&self, id: RoomId) -> Option<Entity> {
@@ -268,8 +508,178 @@ impl Rooms {
         self.by_id.remove(&id);
     }
 
+    /// Takes a room out of the world, remembering that it still exists.
+    pub fn unload(&mut self, id: RoomId) {
+        if self.by_id.remove(&id).is_some() {
+            self.unloaded.insert(id);
+        }
+    }
+
+    /// Whether the room exists, in the world or not.
+    pub fn exists(&self, id: RoomId) -> bool {
+        self.by_id.contains_key(&id) || self.unloaded.contains(&id)
+    }
+
+    /// Whether the room exists but must be loaded before it can be used.
+    pub fn is_unloaded(&self, id: RoomId) -> bool {
+        self.unloaded.contains(&id)
+    }
+
     pub fn next_id(&mut self) -> RoomId {
         self.highest_id += 1;
         RoomId(self.highest_id)
     }
 }
+
+// Resource tracking the regions builders are staging
+#[derive(Default)]
+pub struct Staging {
+    by_builder: HashMap<Entity, Stage>,
+}
+
+impl Staging {
+    pub fn insert(&mut self, builder: Entity, stage: Stage) {
+        self.by_builder.insert(builder, stage);
+    }
+
+    pub fn by_builder(&self, builder: Entity) -> Option<&Stage> {
+        self.by_builder.get(&builder)
+    }
+
+    pub fn by_region(&self, region: &str) -> Option<Entity> {
+        self.by_builder
+            .iter()
+            .find(|(_, stage)| stage.region() == region)
+            .map(|(builder, _)| *builder)
+    }
+
+    pub fn is_staged(&self, room: Entity) -> bool {
+        self.by_builder
+            .values()
+            .any(|stage| stage.shadows.contains_key(&room))
+    }
+
+    // Returns the shadow of the room if the builder is staging a region containing it.
+    pub fn shadow(&self, builder: Entity, room: Entity) -> Option<Entity> {
+        self.by_builder
+            .get(&builder)
+            .and_then(|stage| stage.shadows.get(&room))
+            .copied()
+    }
+
+    pub fn remove(&mut self, builder: Entity) -> Option<Stage> {
+        self.by_builder.remove(&builder)
+    }
+
+    pub fn regions(&self) -> impl Iterator<Item = &str> {
+        self.by_builder.values().map(Stage::region)
+    }
+}
+
+pub struct Stage {
+    region: String,
+    shadows: HashMap<Entity, Entity>,
+}
+
+impl Stage {
+    pub fn new(region: String, shadows: HashMap<Entity, Entity>) -> Self {
+        Stage { region, shadows }
+    }
+
+    pub fn region(&self) -> &str {
+        self.region.as_str()
+    }
+
+    pub fn shadows(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.shadows.values().copied()
+    }
+}
+
+/// Resource tracking which regions are in the world when regions are loaded lazily, for worlds too
+/// large to keep in memory. Rooms in no region are always in the world, as are the regions players
+/// are in and the regions next to those. Other regions are loaded when something needs them, and
+/// leave the world once they have gone unneeded for the idle duration. Otherwise, every room is
+/// in the world.
+#[derive(Default)]
+pub struct LoadedRegions {
+    lazy: bool,
+    idle: Duration,
+    regions: HashMap<String, LoadedRegion>,
+    // regions next to rooms in no region, which are always needed
+    anchored: HashSet<String>,
+}
+
+// A region in the world, with when it was last needed and the regions its exits lead to.
+struct LoadedRegion {
+    needed_at: Instant,
+    neighbors: HashSet<String>,
+}
+
+impl LoadedRegions {
+    pub fn enable(&mut self, idle: Duration) {
+        self.lazy = true;
+        self.idle = idle;
+    }
+
+    pub fn set_idle(&mut self, idle: Duration) {
+        self.idle = idle;
+    }
+
+    pub fn is_lazy(&self) -> bool {
+        self.lazy
+    }
+
+    /// Whether every room in the region is in the world.
+    pub fn is_loaded(&self, region: &str) -> bool {
+        !self.lazy || self.regions.contains_key(region)
+    }
+
+    pub fn insert(&mut self, region: String, neighbors: HashSet<String>, now: Instant) {
+        self.regions.insert(
+            region,
+            LoadedRegion {
+                needed_at: now,
+                neighbors,
+            },
+        );
+    }
+
+    pub fn remove(&mut self, region: &str) {
+        self.regions.remove(region);
+    }
+
+    pub fn set_anchored(&mut self, anchored: HashSet<String>) {
+        self.anchored = anchored;
+    }
+
+    pub fn anchored(&self) -> &HashSet<String> {
+        &self.anchored
+    }
+
+    /// The regions the region's exits lead to when it was loaded.
+    pub fn neighbors(&self, region: &str) -> impl Iterator<Item = &String> {
+        self.regions
+            .get(region)
+            .into_iter()
+            .flat_map(|region| region.neighbors.iter())
+    }
+
+    pub fn mark_needed(&mut self, region: &str, now: Instant) {
+        if let Some(region) = self.regions.get_mut(region) {
+            region.needed_at = now;
+        }
+    }
+
+    /// The regions in the world which have gone unneeded for the idle duration.
+    pub fn idle(&self, now: Instant) -> Vec<String> {
+        self.regions
+            .iter()
+            .filter(|(_, region)| now.saturating_duration_since(region.needed_at) >= self.idle)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.regions.keys()
+    }
+}
@@ -0,0 +1,393 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    convert::TryFrom,
+    fmt,
+    str::FromStr,
+};
+
+use itertools::Itertools;
+use thiserror::Error;
+
+use crate::world::{
+    scripting::ScriptName,
+    types::{
+        economy::SECONDS_PER_DAY,
+        room::{Direction, RoomId},
+        spawn::SpawnTableId,
+    },
+};
+
+// Season names are typed by immortals and builders, so they follow the same rules as spawn table
+// IDs: lowercase ASCII letters, digits, dashes, and underscores.
+pub fn parse_season_name(name: &str) -> Result<String, SeasonNameParseError> {
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        Ok(name.to_string())
+    } else {
+        Err(SeasonNameParseError {})
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Season names must contain only lowercase letters, digits, dashes, and underscores.")]
+pub struct SeasonNameParseError {}
+
+/// A day of the year, without the year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MonthDay {
+    month: u32,
+    day: u32,
+}
+
+impl MonthDay {
+    /// The day of the year in UTC at a time given in seconds since the Unix epoch.
+    pub fn from_unix(secs: i64) -> Self {
+        // Converts days since the epoch to a civil date, counting years from March so that leap
+        // days fall at the end of each year.
+        let days = secs.div_euclid(SECONDS_PER_DAY) + 719_468;
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+
+        MonthDay {
+            month: month as u32,
+            day: (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32,
+        }
+    }
+}
+
+impl FromStr for MonthDay {
+    type Err = MonthDayParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (month, day) = s.split_once('-').ok_or(MonthDayParseError {})?;
+        let month = month.parse::<u32>().map_err(|_| MonthDayParseError {})?;
+        let day = day.parse::<u32>().map_err(|_| MonthDayParseError {})?;
+
+        let days_in_month = match month {
+            2 => 29,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => return Err(MonthDayParseError {}),
+        };
+
+        if (1..=days_in_month).contains(&day) {
+            Ok(MonthDay { month, day })
+        } else {
+            Err(MonthDayParseError {})
+        }
+    }
+}
+
+impl fmt::Display for MonthDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}-{:02}", self.month, self.day)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Dates must be given as MM-DD, such as 12-24.")]
+pub struct MonthDayParseError {}
+
+/// The days of the year a season is under way, inclusive. A window whose end comes before its
+/// start wraps past the new year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeasonWindow {
+    pub start: MonthDay,
+    pub end: MonthDay,
+}
+
+impl SeasonWindow {
+    pub fn new(start: MonthDay, end: MonthDay) -> Self {
+        SeasonWindow { start, end }
+    }
+
+    pub fn contains(&self, day: MonthDay) -> bool {
+        if self.start <= self.end {
+            self.start <= day && day <= self.end
+        } else {
+            day >= self.start || day <= self.end
+        }
+    }
+}
+
+impl fmt::Display for SeasonWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} to {}", self.start, self.end)
+    }
+}
+
+/// World content which can be tagged with a season, and so only exists while it is under way.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Seasonal {
+    Room(RoomId),
+    Exit(RoomId, Direction),
+    SpawnTable(SpawnTableId),
+    Script(ScriptName),
+}
+
+impl Seasonal {
+    /// Rebuilds content from the kind and target it is stored under.
+    pub fn parse(kind: &str, target: &str) -> Option<Self> {
+        match kind {
+            "room" => RoomId::from_str(target).ok().map(Seasonal::Room),
+            "exit" => {
+                let (room, direction) = target.split_once(' ')?;
+                Some(Seasonal::Exit(
+                    RoomId::from_str(room).ok()?,
+                    Direction::named(direction)?,
+                ))
+            }
+            "spawn" => SpawnTableId::from_str(target)
+                .ok()
+                .map(Seasonal::SpawnTable),
+            "script" => ScriptName::try_from(target.to_string())
+                .ok()
+                .map(Seasonal::Script),
+            _ => None,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Seasonal::Room(_) => "room",
+            Seasonal::Exit(_, _) => "exit",
+            Seasonal::SpawnTable(_) => "spawn",
+            Seasonal::Script(_) => "script",
+        }
+    }
+
+    pub fn target(&self) -> String {
+        match self {
+            Seasonal::Room(room) => room.to_string(),
+            Seasonal::Exit(room, direction) => format!("{} {}", room, direction),
+            Seasonal::SpawnTable(table) => table.to_string(),
+            Seasonal::Script(script) => script.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Seasonal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind(), self.target())
+    }
+}
+
+/// The seasons which have started or ended since the active seasons were last worked out.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SeasonChanges {
+    pub started: Vec<String>,
+    pub ended: Vec<String>,
+}
+
+// Resource holding the configured season windows and the content tagged with each season.
+// Untagged content is always active, tagged content only while its season is under way.
+#[derive(Debug, Default)]
+pub struct Seasons {
+    windows: BTreeMap<String, SeasonWindow>,
+    tags: HashMap<Seasonal, String>,
+    active: BTreeSet<String>,
+    // the day, counted from the Unix epoch, the active seasons were last worked out for
+    evaluated_day: Option<i64>,
+}
+
+impl Seasons {
+    pub fn new(tags: HashMap<Seasonal, String>) -> Self {
+        Seasons {
+            tags,
+            ..Seasons::default()
+        }
+    }
+
+    /// Replaces the configured windows. The active seasons are worked out again on the next
+    /// evaluation.
+    pub fn set_windows(&mut self, windows: BTreeMap<String, SeasonWindow>) {
+        self.windows = windows;
+        self.evaluated_day = None;
+    }
+
+    pub fn window(&self, season: &str) -> Option<&SeasonWindow> {
+        self.windows.get(season)
+    }
+
+    /// Every season which is configured or has tagged content, in name order.
+    pub fn names(&self) -> Vec<&str> {
+        self.windows
+            .keys()
+            .map(String::as_str)
+            .chain(self.tags.values().map(String::as_str))
+            .sorted()
+            .dedup()
+            .collect_vec()
+    }
+
+    /// Works out which seasons are under way at the given time, in seconds since the Unix epoch.
+    /// Returns the changes since the last evaluation, or None if the day has not changed.
+    pub fn evaluate(&mut self, now: i64) -> Option<SeasonChanges> {
+        let day = now.div_euclid(SECONDS_PER_DAY);
+        if self.evaluated_day == Some(day) {
+            return None;
+        }
+        self.evaluated_day = Some(day);
+
+        let today = MonthDay::from_unix(now);
+        let active = self
+            .windows
+            .iter()
+            .filter(|(_, window)| window.contains(today))
+            .map(|(season, _)| season.clone())
+            .collect::<BTreeSet<_>>();
+
+        let changes = SeasonChanges {
+            started: active.difference(&self.active).cloned().collect_vec(),
+            ended: self.active.difference(&active).cloned().collect_vec(),
+        };
+
+        self.active = active;
+
+        Some(changes)
+    }
+
+    pub fn is_under_way(&self, season: &str) -> bool {
+        self.active.contains(season)
+    }
+
+    /// Tags content with a season, returning the season it was previously tagged with.
+    pub fn tag(&mut self, content: Seasonal, season: String) -> Option<String> {
+        self.tags.insert(content, season)
+    }
+
+    /// Removes the season tag from content, returning the season it was tagged with.
+    pub fn untag(&mut self, content: &Seasonal) -> Option<String> {
+        self.tags.remove(content)
+    }
+
+    /// The content tagged with a season, sorted by kind and target.
+    pub fn tagged(&self, season: &str) -> Vec<&Seasonal> {
+        self.tags
+            .iter()
+            .filter(|(_, tagged)| tagged.as_str() == season)
+            .map(|(content, _)| content)
+            .sorted_by_key(|content| content.to_string())
+            .collect_vec()
+    }
+
+    pub fn is_active(&self, content: &Seasonal) -> bool {
+        match self.tags.get(content) {
+            Some(season) => self.active.contains(season),
+            None => true,
+        }
+    }
+
+    /// Whether an exit may be used, which requires both the exit and the room it leads to be
+    /// active.
+    pub fn is_passable(&self, room: RoomId, direction: &Direction, destination: RoomId) -> bool {
+        self.tags.is_empty()
+            || (self.is_active(&Seasonal::Exit(room, direction.clone()))
+                && self.is_active(&Seasonal::Room(destination)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> MonthDay {
+        MonthDay::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_month_day() {
+        assert_eq!(MonthDay::from_unix(0), day("01-01"));
+        assert_eq!(MonthDay::from_unix(1_709_208_000), day("02-29"));
+        assert_eq!(MonthDay::from_unix(1_640_952_000), day("12-31"));
+        assert_eq!(MonthDay::from_unix(-43_200), day("12-31"));
+
+        assert!(MonthDay::from_str("02-30").is_err());
+        assert!(MonthDay::from_str("13-01").is_err());
+        assert!(MonthDay::from_str("1224").is_err());
+        assert_eq!(day("2-3").to_string(), "02-03");
+    }
+
+    #[test]
+    fn test_window_contains() {
+        let summer = SeasonWindow::new(day("06-01"), day("08-31"));
+        assert!(summer.contains(day("06-01")));
+        assert!(summer.contains(day("08-31")));
+        assert!(!summer.contains(day("09-01")));
+
+        let winter = SeasonWindow::new(day("12-20"), day("01-05"));
+        assert!(winter.contains(day("12-25")));
+        assert!(winter.contains(day("01-01")));
+        assert!(!winter.contains(day("01-06")));
+        assert!(!winter.contains(day("12-19")));
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let room = RoomId::try_from(1).unwrap();
+
+        let mut tags = HashMap::new();
+        tags.insert(Seasonal::Room(room), "winter".to_string());
+
+        let mut seasons = Seasons::new(tags);
+        let mut windows = BTreeMap::new();
+        windows.insert(
+            "winter".to_string(),
+            SeasonWindow::new(day("12-20"), day("01-05")),
+        );
+        seasons.set_windows(windows);
+
+        assert!(!seasons.is_active(&Seasonal::Room(room)));
+        assert!(seasons.is_active(&Seasonal::Room(RoomId::try_from(2).unwrap())));
+
+        // 2021-12-31
+        assert_eq!(
+            seasons.evaluate(1_640_952_000),
+            Some(SeasonChanges {
+                started: vec!["winter".to_string()],
+                ended: vec![]
+            })
+        );
+        assert!(seasons.is_active(&Seasonal::Room(room)));
+        assert_eq!(seasons.evaluate(1_640_952_000 + 3600), None);
+
+        // 2024-02-29
+        assert_eq!(
+            seasons.evaluate(1_709_208_000),
+            Some(SeasonChanges {
+                started: vec![],
+                ended: vec!["winter".to_string()]
+            })
+        );
+        assert!(!seasons.is_active(&Seasonal::Room(room)));
+    }
+
+    #[test]
+    fn test_parse_seasonal() {
+        let exit = Seasonal::Exit(
+            RoomId::try_from(3).unwrap(),
+            Direction::named("enter grotto").unwrap(),
+        );
+
+        assert_eq!(
+            Seasonal::parse(exit.kind(), exit.target().as_str()),
+            Some(exit)
+        );
+        assert_eq!(
+            Seasonal::parse("room", "4"),
+            Some(Seasonal::Room(RoomId::try_from(4).unwrap()))
+        );
+        assert_eq!(Seasonal::parse("object", "4"), None);
+    }
+}
@@ -0,0 +1,319 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    str::FromStr,
+    time::Duration,
+};
+
+use bevy_core::Timer;
+use rand::Rng;
+use thiserror::Error;
+
+use crate::world::types::{
+    loot::{LootTable, LootTableId, Rarity},
+    object::PrototypeId,
+    player::PlayerId,
+    room::RoomId,
+};
+
+pub const DEFAULT_SHOP_CAP: u32 = 20;
+pub const DEFAULT_SHOP_MARKUP: u32 = 100;
+pub const DEFAULT_RESTOCK_INTERVAL_SECS: u64 = 600;
+
+// Shop IDs are typed by immortals, so they are kept simple: lowercase ASCII letters, digits,
+// dashes, and underscores.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct ShopId(String);
+
+impl FromStr for ShopId {
+    type Err = ShopIdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            Ok(ShopId(s.to_string()))
+        } else {
+            Err(ShopIdParseError {})
+        }
+    }
+}
+
+impl fmt::Display for ShopId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Shop IDs must contain only lowercase letters, digits, dashes, and underscores.")]
+pub struct ShopIdParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StockItem {
+    pub prototype: PrototypeId,
+    pub rarity: Rarity,
+    pub quantity: u32,
+}
+
+/// A room where players buy objects for coins. Shops restock from a loot table every interval,
+/// and the coins they take leave the game entirely.
+#[derive(Debug, Clone)]
+pub struct Shop {
+    pub room: RoomId,
+    pub loot_table: Option<LootTableId>,
+    // the percentage of each rarity's base price the shop charges
+    pub markup: u32,
+    // the most items the shop stocks at once
+    pub cap: u32,
+    pub stock: Vec<StockItem>,
+    // coins taken out of the game by sales
+    pub sunk: i64,
+    // percentages taken off players' next purchases, offered by haggling scripts
+    discounts: HashMap<PlayerId, u32>,
+    timer: Timer,
+}
+
+impl Shop {
+    pub fn new(room: RoomId, interval: Duration) -> Self {
+        Shop {
+            room,
+            loot_table: None,
+            markup: DEFAULT_SHOP_MARKUP,
+            cap: DEFAULT_SHOP_CAP,
+            stock: Vec::new(),
+            sunk: 0,
+            discounts: HashMap::new(),
+            timer: Timer::new(interval, true),
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.timer.duration()
+    }
+
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.timer.set_duration(interval);
+        self.timer.reset();
+    }
+
+    /// Advances the shop's timer, returning true when it is time to restock.
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        self.timer.tick(delta).just_finished()
+    }
+
+    /// The number of items in stock.
+    pub fn count(&self) -> u32 {
+        self.stock.iter().map(|item| item.quantity).sum()
+    }
+
+    /// The price of an item of the given rarity, before any discount.
+    pub fn list_price(&self, rarity: Rarity) -> i64 {
+        (rarity.base_price() * self.markup as i64 / 100).max(1)
+    }
+
+    /// The price a player pays for an item of the given rarity, after any discount they have
+    /// haggled for.
+    pub fn price(&self, rarity: Rarity, player: PlayerId) -> i64 {
+        let price = self.list_price(rarity);
+        match self.discounts.get(&player) {
+            Some(discount) => (price * (100 - *discount as i64) / 100).max(1),
+            None => price,
+        }
+    }
+
+    pub fn discount(&self, player: PlayerId) -> Option<u32> {
+        self.discounts.get(&player).copied()
+    }
+
+    pub fn offer_discount(&mut self, player: PlayerId, percent: u32) {
+        self.discounts.insert(player, percent.min(100));
+    }
+
+    /// Removes one of the numbered item from stock, spending any discount the buyer had.
+    pub fn sell(&mut self, index: usize, player: PlayerId, price: i64) -> Option<StockItem> {
+        let item = self.stock.get_mut(index)?;
+        item.quantity -= 1;
+
+        let sold = StockItem {
+            quantity: 1,
+            ..item.clone()
+        };
+
+        if item.quantity == 0 {
+            self.stock.remove(index);
+        }
+
+        self.discounts.remove(&player);
+        self.sunk += price;
+
+        Some(sold)
+    }
+
+    /// Restocks the shop from a roll of its loot table. Rare and better stock left over from the
+    /// last restock is rotated out first, and the shop never stocks more than its cap.
+    pub fn restock<R: Rng>(&mut self, table: &LootTable, rng: &mut R) {
+        self.stock.retain(|item| item.rarity < Rarity::Rare);
+
+        for drop in table.roll(rng) {
+            let quantity = drop.quantity.min(self.cap.saturating_sub(self.count()));
+            if quantity == 0 {
+                break;
+            }
+
+            match self
+                .stock
+                .iter_mut()
+                .find(|item| item.prototype == drop.prototype && item.rarity == drop.rarity)
+            {
+                Some(item) => item.quantity += quantity,
+                None => self.stock.push(StockItem {
+                    prototype: drop.prototype,
+                    rarity: drop.rarity,
+                    quantity,
+                }),
+            }
+        }
+    }
+}
+
+// Resource holding every shop
+#[derive(Default)]
+pub struct Shops {
+    by_id: BTreeMap<ShopId, Shop>,
+}
+
+impl Shops {
+    pub fn insert(&mut self, id: ShopId, shop: Shop) {
+        self.by_id.insert(id, shop);
+    }
+
+    pub fn contains(&self, id: &ShopId) -> bool {
+        self.by_id.contains_key(id)
+    }
+
+    pub fn get(&self, id: &ShopId) -> Option<&Shop> {
+        self.by_id.get(id)
+    }
+
+    pub fn get_mut(&mut self, id: &ShopId) -> Option<&mut Shop> {
+        self.by_id.get_mut(id)
+    }
+
+    pub fn by_room(&self, room: RoomId) -> Option<(&ShopId, &Shop)> {
+        self.by_id.iter().find(|(_, shop)| shop.room == room)
+    }
+
+    pub fn by_room_mut(&mut self, room: RoomId) -> Option<(&ShopId, &mut Shop)> {
+        self.by_id.iter_mut().find(|(_, shop)| shop.room == room)
+    }
+
+    pub fn remove(&mut self, id: &ShopId) -> Option<Shop> {
+        self.by_id.remove(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&ShopId, &Shop)> {
+        self.by_id.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&ShopId, &mut Shop)> {
+        self.by_id.iter_mut()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::world::types::loot::LootEntry;
+
+    use super::*;
+
+    fn entry(prototype: i64, rarity: Rarity, max: u32) -> LootEntry {
+        LootEntry {
+            prototype: Some(PrototypeId::try_from(prototype).unwrap()),
+            weight: 1,
+            min: 1,
+            max,
+            rarity,
+        }
+    }
+
+    #[test]
+    fn test_restock_rotates_rare_stock() {
+        let mut shop = Shop::new(RoomId::try_from(1).unwrap(), Duration::from_secs(1));
+        shop.cap = 5;
+        shop.stock.push(StockItem {
+            prototype: PrototypeId::try_from(1).unwrap(),
+            rarity: Rarity::Common,
+            quantity: 2,
+        });
+        shop.stock.push(StockItem {
+            prototype: PrototypeId::try_from(3).unwrap(),
+            rarity: Rarity::Epic,
+            quantity: 1,
+        });
+
+        let mut table = LootTable::new(10);
+        table.entries.push(entry(2, Rarity::Rare, 1));
+
+        let mut rng = StdRng::seed_from_u64(7);
+        shop.restock(&table, &mut rng);
+
+        assert_eq!(
+            shop.stock,
+            vec![
+                StockItem {
+                    prototype: PrototypeId::try_from(1).unwrap(),
+                    rarity: Rarity::Common,
+                    quantity: 2,
+                },
+                StockItem {
+                    prototype: PrototypeId::try_from(2).unwrap(),
+                    rarity: Rarity::Rare,
+                    quantity: 3,
+                },
+            ]
+        );
+
+        table.entries.push(entry(1, Rarity::Common, 3));
+        for _ in 0..20 {
+            shop.restock(&table, &mut rng);
+            assert!(shop.count() <= 5);
+        }
+    }
+
+    #[test]
+    fn test_discounts_are_spent() {
+        let mut shop = Shop::new(RoomId::try_from(1).unwrap(), Duration::from_secs(1));
+        shop.markup = 150;
+        shop.stock.push(StockItem {
+            prototype: PrototypeId::try_from(1).unwrap(),
+            rarity: Rarity::Common,
+            quantity: 2,
+        });
+
+        let player = PlayerId::try_from(1).unwrap();
+        assert_eq!(shop.price(Rarity::Common, player), 15);
+
+        shop.offer_discount(player, 20);
+        assert_eq!(shop.price(Rarity::Common, player), 12);
+
+        assert!(shop.sell(0, player, 12).is_some());
+        assert_eq!(shop.price(Rarity::Common, player), 15);
+        assert_eq!(shop.sunk, 12);
+        assert_eq!(shop.count(), 1);
+
+        assert!(shop.sell(0, player, 15).is_some());
+        assert!(shop.stock.is_empty());
+        assert!(shop.sell(0, player, 15).is_none());
+    }
+}
@@ -0,0 +1,175 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    str::FromStr,
+    time::Duration,
+};
+
+use bevy_core::Timer;
+use rand::Rng;
+use thiserror::Error;
+
+use crate::world::types::{object::PrototypeId, room::RoomId};
+
+pub const DEFAULT_SPAWN_CAP: u32 = 1;
+pub const DEFAULT_SPAWN_INTERVAL_SECS: u64 = 300;
+
+// Spawn table IDs are typed by immortals, so they are kept simple: lowercase ASCII letters,
+// digits, dashes, and underscores.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct SpawnTableId(String);
+
+impl FromStr for SpawnTableId {
+    type Err = SpawnTableIdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            Ok(SpawnTableId(s.to_string()))
+        } else {
+            Err(SpawnTableIdParseError {})
+        }
+    }
+}
+
+impl fmt::Display for SpawnTableId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Spawn table IDs must contain only lowercase letters, digits, dashes, and underscores.")]
+pub struct SpawnTableIdParseError {}
+
+/// A weighted list of prototypes kept populated in a set of rooms. Every interval, each room the
+/// table covers which holds fewer than `cap` objects from the table receives one more, chosen by
+/// weight.
+#[derive(Debug, Clone)]
+pub struct SpawnTable {
+    pub cap: u32,
+    // prototypes and their relative weights
+    pub entries: BTreeMap<PrototypeId, u32>,
+    pub rooms: BTreeSet<RoomId>,
+    pub regions: BTreeSet<String>,
+    timer: Timer,
+}
+
+impl SpawnTable {
+    pub fn new(cap: u32, interval: Duration) -> Self {
+        SpawnTable {
+            cap,
+            entries: BTreeMap::new(),
+            rooms: BTreeSet::new(),
+            regions: BTreeSet::new(),
+            timer: Timer::new(interval, true),
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.timer.duration()
+    }
+
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.timer.set_duration(interval);
+        self.timer.reset();
+    }
+
+    /// Advances the table's timer, returning true when it is time to spawn.
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        self.timer.tick(delta).just_finished()
+    }
+
+    /// Whether the table covers a room with the given ID and regions.
+    pub fn covers(&self, id: RoomId, regions: &[String]) -> bool {
+        self.rooms.contains(&id) || regions.iter().any(|region| self.regions.contains(region))
+    }
+
+    /// Picks a prototype at random by weight.
+    pub fn choose<R: Rng>(&self, rng: &mut R) -> Option<PrototypeId> {
+        let total: u64 = self.entries.values().map(|weight| *weight as u64).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = rng.gen_range(0..total);
+        for (prototype, weight) in self.entries.iter() {
+            if roll < *weight as u64 {
+                return Some(*prototype);
+            }
+            roll -= *weight as u64;
+        }
+
+        None
+    }
+}
+
+// Resource holding every spawn table
+#[derive(Default)]
+pub struct SpawnTables {
+    by_id: BTreeMap<SpawnTableId, SpawnTable>,
+}
+
+impl SpawnTables {
+    pub fn insert(&mut self, id: SpawnTableId, table: SpawnTable) {
+        self.by_id.insert(id, table);
+    }
+
+    pub fn contains(&self, id: &SpawnTableId) -> bool {
+        self.by_id.contains_key(id)
+    }
+
+    pub fn get(&self, id: &SpawnTableId) -> Option<&SpawnTable> {
+        self.by_id.get(id)
+    }
+
+    pub fn get_mut(&mut self, id: &SpawnTableId) -> Option<&mut SpawnTable> {
+        self.by_id.get_mut(id)
+    }
+
+    pub fn remove(&mut self, id: &SpawnTableId) -> Option<SpawnTable> {
+        self.by_id.remove(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&SpawnTableId, &SpawnTable)> {
+        self.by_id.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&SpawnTableId, &mut SpawnTable)> {
+        self.by_id.iter_mut()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_choose_skips_zero_weights() {
+        let mut table = SpawnTable::new(1, Duration::from_secs(1));
+        table.entries.insert(PrototypeId::try_from(1).unwrap(), 0);
+        table.entries.insert(PrototypeId::try_from(2).unwrap(), 5);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            assert_eq!(
+                table.choose(&mut rng),
+                Some(PrototypeId::try_from(2).unwrap())
+            );
+        }
+
+        table.entries.clear();
+        assert_eq!(table.choose(&mut rng), None);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::world::{scripting::ScriptData, types::environment::TimeOfDay};
+
+/// The state of a room that its description's fragments are evaluated against.
+pub struct TemplateContext<'a> {
+    pub time: TimeOfDay,
+    // the weather in each of the room's regions
+    pub weather: Vec<&'a str>,
+    pub data: Option<&'a ScriptData>,
+}
+
+enum Condition<'a> {
+    Day,
+    Night,
+    Weather(&'a str),
+    Flag(&'a str),
+}
+
+impl<'a> Condition<'a> {
+    fn parse(condition: &'a str) -> Option<Self> {
+        let mut words = condition.split_whitespace();
+
+        let condition = match (words.next()?, words.next()) {
+            ("day", None) => Condition::Day,
+            ("night", None) => Condition::Night,
+            ("weather", Some(weather)) => Condition::Weather(weather),
+            ("flag", Some(flag)) => Condition::Flag(flag),
+            _ => return None,
+        };
+
+        if words.next().is_some() {
+            None
+        } else {
+            Some(condition)
+        }
+    }
+
+    fn holds(&self, context: &TemplateContext) -> bool {
+        match self {
+            Condition::Day => context.time == TimeOfDay::Day,
+            Condition::Night => context.time == TimeOfDay::Night,
+            Condition::Weather(weather) => context
+                .weather
+                .iter()
+                .any(|current| current.eq_ignore_ascii_case(weather)),
+            Condition::Flag(flag) => context
+                .data
+                .map(|data| {
+                    let value = data.get((*flag).into());
+                    value.as_bool().unwrap_or_else(|_| !value.is::<()>())
+                })
+                .unwrap_or(false),
+        }
+    }
+}
+
+// Parses the inside of a fragment, "[!]<condition>: <text>", into whether the condition is
+// negated, the condition, and the text.
+fn parse_fragment(fragment: &str) -> Option<(bool, Condition<'_>, &str)> {
+    let (condition, text) = fragment.split_once(':')?;
+
+    let condition = condition.trim();
+    let (negated, condition) = match condition.strip_prefix('!') {
+        Some(condition) => (true, condition),
+        None => (false, condition),
+    };
+
+    Some((negated, Condition::parse(condition)?, text.trim()))
+}
+
+/// Renders a description containing conditional fragments such as `{night: Lamps flicker.}`,
+/// keeping the text of fragments whose condition holds. Braces that do not form a fragment are
+/// left as they are, so descriptions without fragments render unchanged.
+pub fn render_description(description: &str, context: &TemplateContext) -> String {
+    let mut rendered = String::with_capacity(description.len());
+    let mut rest = description;
+
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        let fragment = after
+            .find('}')
+            .and_then(|end| parse_fragment(&after[..end]).map(|fragment| (end, fragment)));
+
+        match fragment {
+            Some((end, (negated, condition, text))) => {
+                rest = &after[end + 1..];
+
+                if condition.holds(context) != negated {
+                    rendered.push_str(text);
+                } else if (rendered.is_empty() || rendered.ends_with(char::is_whitespace))
+                    && rest.starts_with(' ')
+                {
+                    // Avoid doubled spaces where a fragment was left out.
+                    rest = &rest[1..];
+                }
+            }
+            None => {
+                rendered.push('{');
+                rest = after;
+            }
+        }
+    }
+
+    rendered.push_str(rest);
+    rendered.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use rhai::Dynamic;
+
+    use super::*;
+
+    fn context(time: TimeOfDay) -> TemplateContext<'static> {
+        TemplateContext {
+            time,
+            weather: Vec::new(),
+            data: None,
+        }
+    }
+
+    #[test]
+    fn test_time_of_day() {
+        let description =
+            "A quiet street. {day: Vendors hawk their wares.} {night: Lamps flicker.}";
+
+        assert_eq!(
+            render_description(description, &context(TimeOfDay::Day)),
+            "A quiet street. Vendors hawk their wares."
+        );
+        assert_eq!(
+            render_description(description, &context(TimeOfDay::Night)),
+            "A quiet street. Lamps flicker."
+        );
+    }
+
+    #[test]
+    fn test_weather_and_flags() {
+        let data = ScriptData::new_with_entry("lit".into(), Dynamic::from(true));
+        let context = TemplateContext {
+            time: TimeOfDay::Day,
+            weather: vec!["rain"],
+            data: Some(&data),
+        };
+
+        assert_eq!(
+            render_description(
+                "{weather rain: Puddles gather.} {weather snow: Snow drifts.} {flag lit: A fire \
+                 crackles.} {!flag open: The door is shut.}",
+                &context
+            ),
+            "Puddles gather. A fire crackles. The door is shut."
+        );
+    }
+
+    #[test]
+    fn test_leading_fragment() {
+        assert_eq!(
+            render_description(
+                "{day: Sunlight pours in.} A hall.",
+                &context(TimeOfDay::Night)
+            ),
+            "A hall."
+        );
+    }
+
+    #[test]
+    fn test_literal_braces() {
+        assert_eq!(
+            render_description(
+                "A sign reads {closed} {fish: today}.",
+                &context(TimeOfDay::Day)
+            ),
+            "A sign reads {closed} {fish: today}."
+        );
+    }
+}
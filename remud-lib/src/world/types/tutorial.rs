@@ -0,0 +1,167 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    str::FromStr,
+};
+
+use bevy_ecs::prelude::*;
+use itertools::Itertools;
+
+use crate::world::{action::Action, types::room::RoomId};
+
+/// A core command whose first use shows a new player a hint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HintTrigger {
+    Look,
+    Move,
+    Say,
+    Get,
+    Drop,
+    Inventory,
+}
+
+impl HintTrigger {
+    pub const ALL: [HintTrigger; 6] = [
+        HintTrigger::Look,
+        HintTrigger::Move,
+        HintTrigger::Say,
+        HintTrigger::Get,
+        HintTrigger::Drop,
+        HintTrigger::Inventory,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HintTrigger::Look => "look",
+            HintTrigger::Move => "move",
+            HintTrigger::Say => "say",
+            HintTrigger::Get => "get",
+            HintTrigger::Drop => "drop",
+            HintTrigger::Inventory => "inventory",
+        }
+    }
+
+    /// The trigger for a command a player entered, if it has one.
+    pub fn from_action(action: &Action) -> Option<Self> {
+        match action {
+            Action::Look(_) => Some(HintTrigger::Look),
+            Action::Move(_) => Some(HintTrigger::Move),
+            Action::Say(_) => Some(HintTrigger::Say),
+            Action::Get(_) => Some(HintTrigger::Get),
+            Action::Drop(_) => Some(HintTrigger::Drop),
+            Action::Inventory(_) => Some(HintTrigger::Inventory),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for HintTrigger {
+    type Err = HintTriggerParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HintTrigger::ALL
+            .iter()
+            .find(|trigger| trigger.as_str() == s.to_lowercase())
+            .copied()
+            .ok_or(HintTriggerParseError {})
+    }
+}
+
+impl fmt::Display for HintTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct HintTriggerParseError {}
+
+impl std::error::Error for HintTriggerParseError {}
+
+impl fmt::Display for HintTriggerParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Enter a hint trigger: {}.",
+            HintTrigger::ALL.iter().map(HintTrigger::as_str).join(", ")
+        )
+    }
+}
+
+/// Where new characters begin, and the hints shown to them while they learn the ropes. Both are
+/// set by immortals, so the tutorial can be rebuilt without changing the server.
+#[derive(Debug, Default)]
+pub struct Tutorial {
+    start: Option<RoomId>,
+    hints: BTreeMap<HintTrigger, String>,
+}
+
+impl Tutorial {
+    pub fn start(&self) -> Option<RoomId> {
+        self.start
+    }
+
+    pub fn set_start(&mut self, start: Option<RoomId>) {
+        self.start = start;
+    }
+
+    pub fn hint(&self, trigger: HintTrigger) -> Option<&str> {
+        self.hints.get(&trigger).map(String::as_str)
+    }
+
+    pub fn hints(&self) -> impl Iterator<Item = (&HintTrigger, &String)> {
+        self.hints.iter()
+    }
+
+    pub fn set_hint(&mut self, trigger: HintTrigger, message: String) {
+        self.hints.insert(trigger, message);
+    }
+
+    /// Removes a hint, returning true if one was set.
+    pub fn remove_hint(&mut self, trigger: HintTrigger) -> bool {
+        self.hints.remove(&trigger).is_some()
+    }
+}
+
+/// Commands players have typed this tick that may earn them a hint. Hints are shown after the
+/// command runs, so they follow its output.
+#[derive(Debug, Default)]
+pub struct HintQueue {
+    queued: Vec<(Entity, HintTrigger)>,
+}
+
+impl HintQueue {
+    pub fn push(&mut self, player: Entity, trigger: HintTrigger) {
+        self.queued.push((player, trigger));
+    }
+
+    pub fn take(&mut self) -> Vec<(Entity, HintTrigger)> {
+        std::mem::take(&mut self.queued)
+    }
+}
+
+/// The hints a player has already been shown.
+#[derive(Debug, Default)]
+pub struct SeenHints {
+    triggers: HashSet<HintTrigger>,
+}
+
+impl SeenHints {
+    /// Records a hint as seen, returning true if it had not been seen before.
+    pub fn insert(&mut self, trigger: HintTrigger) -> bool {
+        self.triggers.insert(trigger)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HintTrigger;
+
+    #[test]
+    fn test_hint_trigger_round_trip() {
+        for trigger in HintTrigger::ALL {
+            assert_eq!(trigger.as_str().parse::<HintTrigger>().unwrap(), trigger);
+        }
+        assert!("dance".parse::<HintTrigger>().is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use crate::support::{JsonScript, Server, StatusCode, Trigger};
+
+#[tokio::test]
+async fn test_achievements() {
+    const SCRIPT: &str = "explorer_script";
+
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let web = server.login_web(&t).await;
+
+    t.test(
+        "create an achievement",
+        "achievement new explorer Explorer",
+        vec!["Created achievement explorer."],
+    )
+    .await;
+
+    t.test(
+        "describe the achievement",
+        "achievement explorer desc Looked around.",
+        vec!["Updated achievement explorer."],
+    )
+    .await;
+
+    t.test(
+        "list achievements",
+        "achievement list",
+        vec!["Explorer: Looked around."],
+    )
+    .await;
+
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+        SCRIPT,
+        Trigger::Look,
+        r#"WORLD.grant_achievement(EVENT.actor, "explorer");"#,
+    ))
+    .await;
+
+    t.command("create prototype", "prototype new").await;
+    t.command(
+        "attach script",
+        format!("script {} attach-post prototype 1", SCRIPT),
+    )
+    .await;
+    t.command("create object", "object new 1").await;
+
+    t2.test(
+        "no achievements have been earned",
+        "achievements",
+        vec!["You have not earned any achievements yet."],
+    )
+    .await;
+
+    t2.command("looking grants the achievement", "look").await;
+    t2.consume_prompt().await;
+    t2.line_contains("Achievement unlocked: Explorer!").await;
+    t2.assert_prompt().await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane is the first to earn the achievement Explorer!")
+        .await;
+    t.assert_prompt().await;
+
+    t2.test_exclude(
+        "achievements are only granted once",
+        "look",
+        vec!["Achievement unlocked"],
+    )
+    .await;
+
+    t2.test(
+        "list earned achievements",
+        "achievements",
+        vec![
+            "You have earned 1 of 1 achievements:",
+            "Explorer: Looked around.",
+        ],
+    )
+    .await;
+
+    let web = server.connect_web();
+
+    let achievements: serde_json::Value = serde_json::from_str(
+        web.get("/players/Shane/achievements")
+            .await
+            .unwrap()
+            .as_str(),
+    )
+    .unwrap();
+    assert_eq!(achievements["achievements"][0]["id"], "explorer");
+    assert_eq!(achievements["achievements"][0]["name"], "Explorer");
+
+    let achievements: serde_json::Value = serde_json::from_str(
+        web.get("/players/krixi/achievements")
+            .await
+            .unwrap()
+            .as_str(),
+    )
+    .unwrap();
+    assert_eq!(achievements["achievements"].as_array().unwrap().len(), 0);
+
+    match web.get("/players/Bob/achievements").await {
+        Err(StatusCode::NOT_FOUND) => (),
+        e => panic!("expected not found, got: {:?}", e),
+    }
+}
@@ -0,0 +1,67 @@
+use std::path::PathBuf;
+
+use remud_lib::admin::{self, AdminError};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("remud-test-{}-{}", std::process::id(), name))
+}
+
+#[tokio::test]
+async fn test_create_admin_and_reset_password() {
+    let path = temp_path("admin.db");
+    let db = path.to_str().unwrap();
+
+    admin::create_admin(db, "krixi", "password").await.unwrap();
+
+    assert!(matches!(
+        admin::create_admin(db, "krixi", "password").await,
+        Err(AdminError::PlayerExists(_))
+    ));
+    assert!(matches!(
+        admin::create_admin(db, "Shane", "pw").await,
+        Err(AdminError::InvalidPassword)
+    ));
+    assert!(matches!(
+        admin::reset_password(db, "Shane", "password").await,
+        Err(AdminError::PlayerNotFound(_))
+    ));
+
+    admin::reset_password(db, "krixi", "new password")
+        .await
+        .unwrap();
+
+    std::fs::remove_file(path).ok();
+}
+
+#[tokio::test]
+async fn test_export_import_world() {
+    let source_path = temp_path("export-source.db");
+    let target_path = temp_path("export-target.db");
+    let export_path = temp_path("world.json");
+    let source = source_path.to_str().unwrap();
+    let target = target_path.to_str().unwrap();
+
+    admin::create_admin(source, "krixi", "password")
+        .await
+        .unwrap();
+    let exported = admin::export_world(source, export_path.as_path())
+        .await
+        .unwrap();
+
+    assert!(
+        admin::import_world(source, export_path.as_path())
+            .await
+            .is_err(),
+        "importing into a database with players is refused"
+    );
+
+    let imported = admin::import_world(target, export_path.as_path())
+        .await
+        .unwrap();
+    assert_eq!(exported, imported);
+    assert!(admin::check_db(target, false).await.unwrap().is_empty());
+
+    std::fs::remove_file(source_path).ok();
+    std::fs::remove_file(target_path).ok();
+    std::fs::remove_file(export_path).ok();
+}
@@ -0,0 +1,85 @@
+use reqwest::StatusCode;
+
+use crate::support::Server;
+
+#[tokio::test]
+async fn test_allowlist() {
+    let mut server = Server::new_with_config("[login]\nallowlist = false\n").await;
+    let mut t = server.create_player("krixi", "password").await;
+
+    t.test(
+        "the allowlist starts empty",
+        "allowlist",
+        vec!["The allowlist is empty."],
+    )
+    .await;
+
+    t.test(
+        "add a name to the allowlist",
+        "allowlist add Shane",
+        vec!["Added Shane to the allowlist."],
+    )
+    .await;
+
+    t.test(
+        "add a name twice",
+        "allowlist add shane",
+        vec!["shane is already on the allowlist."],
+    )
+    .await;
+
+    t.test(
+        "remove a missing name",
+        "allowlist remove Ada",
+        vec!["Ada is not on the allowlist."],
+    )
+    .await;
+
+    // enforce the allowlist by reloading the configuration
+    std::fs::write(
+        std::env::temp_dir().join(format!("remud-test-{}.toml", server.web())),
+        "[login]\nallowlist = true\nrejection = \"Closed for testing.\"\n",
+    )
+    .unwrap();
+    t.test(
+        "enforce the allowlist",
+        "config reload",
+        vec!["Reloading configuration."],
+    )
+    .await;
+    t.consume_prompt().await;
+    t.line_contains("Configuration reloaded.").await;
+    t.assert_prompt().await;
+
+    let mut rejected = server.connect_telnet();
+    rejected.line_contains("Connected to").await;
+    rejected.line_contains("Name?").await;
+    rejected.assert_prompt().await;
+    rejected
+        .test(
+            "an unlisted name is rejected",
+            "Ada",
+            vec!["Closed for testing.", "Name?"],
+        )
+        .await;
+
+    let _shane = server.create_player("Shane", "password").await;
+
+    let web = server.login_web(&t).await;
+    assert_eq!(web.read_allowlist().await.unwrap(), vec!["shane"]);
+    assert_eq!(web.allow("Ada").await.unwrap(), vec!["ada", "shane"]);
+    assert_eq!(web.allow("no!").await, Err(StatusCode::BAD_REQUEST));
+    assert_eq!(web.disallow("Shane").await.unwrap(), vec!["ada"]);
+    assert_eq!(web.disallow("Shane").await, Err(StatusCode::NOT_FOUND));
+
+    let _ada = server.create_player("Ada", "password").await;
+
+    let mut t = server.restart(t).await;
+
+    t.test(
+        "the allowlist is persisted",
+        "allowlist",
+        vec!["Allowlist", "ada"],
+    )
+    .await;
+}
@@ -0,0 +1,306 @@
+use crate::support::{JsonScript, Server, Trigger};
+
+#[tokio::test]
+async fn test_bank() {
+    const SCRIPT: &str = "payday_script";
+
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let web = server.login_web(&t).await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+            SCRIPT,
+            Trigger::Use,
+            r#"if WORLD.add_coins(EVENT.actor, 9) {
+    SELF.whisper(EVENT.actor, `${WORLD.coins(EVENT.actor)} carried, ${WORLD.bank_balance(EVENT.actor)} banked`);
+}"#,
+        ))
+        .await;
+
+    t.test(
+        "players start with no coins",
+        "balance",
+        vec!["You are carrying 0 coins. Your bank balance is 0 coins."],
+    )
+    .await;
+    t.test(
+        "there are no accounts to start",
+        "bank audit",
+        vec!["There are no bank accounts."],
+    )
+    .await;
+    t.test(
+        "banking needs a bank",
+        "deposit 10",
+        vec!["There is no bank here."],
+    )
+    .await;
+    t.test(
+        "immortals can grant coins",
+        "bank krixi grant 100",
+        vec!["krixi is now carrying 100 coins."],
+    )
+    .await;
+    t.test(
+        "coins cannot go below zero",
+        "bank krixi grant -500",
+        vec!["krixi is only carrying 100 coins."],
+    )
+    .await;
+
+    t.command("create a room", "room new north").await;
+    t.command("go to the room", "north").await;
+    t.command("create teller prototype", "prototype new").await;
+    t.command("name teller prototype", "prototype 1 name a bank teller")
+        .await;
+    t.command("create book prototype", "prototype new").await;
+    t.command("name book prototype", "prototype 2 name a ledger")
+        .await;
+    t.command("key book prototype", "prototype 2 keywords set ledger")
+        .await;
+    t.command("spawn the teller", "object new 1").await;
+    t.test(
+        "flag the teller as a bank",
+        "object 1 set bank fixed",
+        vec!["Updated object 1 flags."],
+    )
+    .await;
+    t.command("spawn the book", "object new 2").await;
+    t.command("pick up the book", "get ledger").await;
+
+    t.test(
+        "cannot deposit more than carried",
+        "deposit 150",
+        vec!["You only have 100 coins."],
+    )
+    .await;
+    t.test(
+        "deposit coins",
+        "deposit 60",
+        vec!["You deposit 60 coins. Your bank balance is 60 coins."],
+    )
+    .await;
+    t.test(
+        "cannot withdraw more than the balance",
+        "withdraw 61",
+        vec!["Your bank balance is only 60 coins."],
+    )
+    .await;
+    t.test(
+        "withdraw coins",
+        "withdraw 1",
+        vec!["You withdraw 1 coin. Your bank balance is 59 coins."],
+    )
+    .await;
+    t.test(
+        "deposit an item",
+        "deposit ledger",
+        vec!["You deposit a ledger in your vault."],
+    )
+    .await;
+    t.test(
+        "the item is in the vault",
+        "balance",
+        vec![
+            "You are carrying 41 coins. Your bank balance is 59 coins.",
+            "Vault (1/20)",
+            "a ledger",
+        ],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "the vault is persisted",
+        "withdraw ledger",
+        vec!["You withdraw a ledger from your vault."],
+    )
+    .await;
+    t.test(
+        "the item is back in the inventory",
+        "inventory",
+        vec!["a ledger"],
+    )
+    .await;
+
+    t.command(
+        "attach script",
+        format!("script {} attach-pre object 2", SCRIPT),
+    )
+    .await;
+
+    t.command("drop the book", "drop ledger").await;
+    t.test("use the book", "use ledger", vec!["You use a ledger."])
+        .await;
+    t.consume_prompt().await;
+    t.line_contains("50 carried, 59 banked").await;
+    t.assert_prompt().await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "balances are audited",
+        "bank audit",
+        vec![
+            "Bank accounts",
+            "krixi: 50 coins carried, 59 coins banked",
+            "Total: 50 coins carried, 59 coins banked",
+        ],
+    )
+    .await;
+    t.test(
+        "a player's account can be inspected",
+        "bank krixi",
+        vec![
+            "Bank account of krixi",
+            "carried: 50 coins",
+            "balance: 59 coins",
+            "vault: 0/20",
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_vault_prototype_reload() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.command("create a room", "room new north").await;
+    t.command("go to the room", "north").await;
+    t.command("create teller prototype", "prototype new").await;
+    t.command("name teller prototype", "prototype 1 name a bank teller")
+        .await;
+    t.command("create book prototype", "prototype new").await;
+    t.command("name book prototype", "prototype 2 name a ledger")
+        .await;
+    t.command("key book prototype", "prototype 2 keywords set ledger")
+        .await;
+    t.command("spawn the teller", "object new 1").await;
+    t.command("flag the teller as a bank", "object 1 set bank fixed")
+        .await;
+    t.command("spawn the first book", "object new 2").await;
+    t.command("spawn the second book", "object new 2").await;
+    t.command("pick up a book", "get ledger").await;
+    t.command("pick up the other book", "get ledger").await;
+    t.command("deposit a book", "deposit ledger").await;
+
+    t.command("rename book prototype", "prototype 2 name a journal")
+        .await;
+    t.test("held objects are reloaded", "inventory", vec!["a journal"])
+        .await;
+    t.test(
+        "vaulted objects are reloaded",
+        "balance",
+        vec!["Vault (1/20)", "a journal"],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    // The vault is not loaded until the player uses the bank
+    t.command("rename book prototype", "prototype 2 name a diary")
+        .await;
+    t.test(
+        "held objects are reloaded after login",
+        "inventory",
+        vec!["a diary"],
+    )
+    .await;
+    t.test(
+        "the vault loads with the latest prototype",
+        "balance",
+        vec!["Vault (1/20)", "a diary"],
+    )
+    .await;
+
+    t.command("rename book prototype", "prototype 2 name a notebook")
+        .await;
+    t.test(
+        "vaulted objects are reloaded once the vault is loaded",
+        "balance",
+        vec!["Vault (1/20)", "a notebook"],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_economy_report() {
+    const SCRIPT: &str = "reward_script";
+
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let web = server.login_web(&t).await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+        SCRIPT,
+        Trigger::Use,
+        r#"WORLD.add_coins(EVENT.actor, 5, "quest");"#,
+    ))
+    .await;
+
+    t.test(
+        "the economy starts quiet",
+        "economy report",
+        vec![
+            "Economy over the last 7 days",
+            "No coins were created or destroyed.",
+            "Total: 0 coins created, 0 coins destroyed, net +0",
+        ],
+    )
+    .await;
+    t.test(
+        "reports need at least a day",
+        "economy report 0",
+        vec!["Enter a number of days greater than 0."],
+    )
+    .await;
+
+    t.command("grant coins", "bank krixi grant 100").await;
+    t.command("take coins", "bank krixi grant -30").await;
+
+    t.command("create idol prototype", "prototype new").await;
+    t.command("name idol prototype", "prototype 1 name a golden idol")
+        .await;
+    t.command("key idol prototype", "prototype 1 keywords set idol")
+        .await;
+    t.command("spawn the idol", "object new 1").await;
+    t.command(
+        "attach script",
+        format!("script {} attach-pre object 1", SCRIPT),
+    )
+    .await;
+    t.test("use the idol", "use idol", vec!["You use a golden idol."])
+        .await;
+
+    t.test(
+        "coins are reported by source",
+        "economy report 1",
+        vec![
+            "Economy today",
+            ": 105 coins created, 30 coins destroyed, net +75",
+            "By source",
+            "grant: 100 coins created, 30 coins destroyed, net +70",
+            "quest: 5 coins created, 0 coins destroyed, net +5",
+            "Total: 105 coins created, 30 coins destroyed, net +75",
+            "Held by players: 75 coins carried, 0 coins banked",
+        ],
+    )
+    .await;
+
+    let report = web.economy(7).await.unwrap();
+    let totals = report["totals"].as_array().unwrap();
+    assert_eq!(totals.len(), 2);
+    assert_eq!(totals[0]["source"], "grant");
+    assert_eq!(totals[0]["created"], 100);
+    assert_eq!(totals[0]["destroyed"], 30);
+    assert_eq!(report["days"].as_array().unwrap().len(), 1);
+    assert_eq!(report["carried"], 75);
+
+    t = server.restart(t).await;
+
+    t.test(
+        "the economy is persisted",
+        "economy report",
+        vec!["quest: 5 coins created, 0 coins destroyed, net +5"],
+    )
+    .await;
+}
@@ -0,0 +1,173 @@
+use crate::support::{JsonScript, Server, Trigger};
+
+#[tokio::test]
+async fn test_campaign() {
+    const SCRIPT: &str = "sabotage_script";
+
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let web = server.login_web(&t).await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+        SCRIPT,
+        Trigger::Use,
+        r#"if WORLD.event_stage("siege") == 1 {
+    SELF.advance_event("siege");
+}"#,
+    ))
+    .await;
+
+    t.test(
+        "there are no events to start",
+        "event list",
+        vec!["There are no events."],
+    )
+    .await;
+    t.test(
+        "create an event",
+        "event new siege Siege",
+        vec!["Created event siege."],
+    )
+    .await;
+    t.test(
+        "events need stages to start",
+        "event siege start",
+        vec!["Event siege has no stages."],
+    )
+    .await;
+
+    t.command("create the spawn room", "room new north").await;
+    t.command("go to the spawn room", "north").await;
+    t.command("create engine prototype", "prototype new").await;
+    t.command("name engine prototype", "prototype 1 name a siege engine")
+        .await;
+    t.command("key engine prototype", "prototype 1 keywords set engine")
+        .await;
+    t.command(
+        "attach script",
+        format!("script {} attach-post prototype 1", SCRIPT),
+    )
+    .await;
+
+    t.test(
+        "add a stage",
+        "event siege stage new Raiders mass at the gates!",
+        vec!["Updated event siege."],
+    )
+    .await;
+    t.test(
+        "set a goal",
+        "event siege stage 1 goal Sabotage the siege engine.",
+        vec!["Updated event siege."],
+    )
+    .await;
+    t.test(
+        "spawns need a prototype",
+        "event siege stage 1 spawn 2 1",
+        vec!["Prototype 2 does not exist."],
+    )
+    .await;
+    t.test(
+        "add a spawn",
+        "event siege stage 1 spawn 1 1",
+        vec!["Updated event siege."],
+    )
+    .await;
+    t.test(
+        "add another stage",
+        "event siege stage new The raiders retreat.",
+        vec!["Updated event siege."],
+    )
+    .await;
+    t.test(
+        "the event is shown",
+        "event siege",
+        vec![
+            "Event siege",
+            "name: Siege",
+            "status: stopped",
+            "1. Raiders mass at the gates!",
+            "goal: Sabotage the siege engine.",
+            "spawn: a siege engine in room 1",
+            "2. The raiders retreat.",
+        ],
+    )
+    .await;
+
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t2.test(
+        "no events are under way",
+        "events",
+        vec!["There are no events under way."],
+    )
+    .await;
+    t.test(
+        "start the event",
+        "event siege start",
+        vec!["Raiders mass at the gates!", "Started event siege."],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("Raiders mass at the gates!").await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "the event is under way",
+        "events",
+        vec!["Events under way:", "Siege: Sabotage the siege engine."],
+    )
+    .await;
+    t2.test(
+        "the stage spawned its object",
+        "use engine",
+        vec!["You use a siege engine."],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("The raiders retreat.").await;
+    t2.assert_prompt().await;
+
+    t.consume_prompt().await;
+    t.line_contains("The raiders retreat.").await;
+    t.assert_prompt().await;
+
+    t2.test(
+        "scripts check the stage",
+        "use engine",
+        vec!["You use a siege engine."],
+    )
+    .await;
+
+    drop(t2);
+
+    t.consume_prompt().await;
+    t.line_contains("Shane leaves.").await;
+    t.assert_prompt().await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "event progress is persisted",
+        "event list",
+        vec!["siege", "Siege: running, stage 2 of 2"],
+    )
+    .await;
+    t.test(
+        "advancing past the last stage concludes the event",
+        "event siege advance",
+        vec!["Siege has concluded.", "Event siege is complete."],
+    )
+    .await;
+    t.test(
+        "the event is stopped",
+        "event siege",
+        vec!["status: stopped"],
+    )
+    .await;
+}
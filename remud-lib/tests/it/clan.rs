@@ -0,0 +1,177 @@
+use crate::support::Server;
+
+#[tokio::test]
+async fn test_clan() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.test(
+        "players start clanless",
+        "clan",
+        vec!["You are not in a clan."],
+    )
+    .await;
+    t.test(
+        "clan names are validated",
+        "clan found ab",
+        vec!["Clan names must be a single word of 3 to 20 letters and digits."],
+    )
+    .await;
+    t.test(
+        "found a clan",
+        "clan found Rats",
+        vec!["You found the clan Rats."],
+    )
+    .await;
+    t.test("clans are listed", "clans", vec!["Clans", "Rats: 1 member"])
+        .await;
+    t.command("create the spawn room", "room new north").await;
+    t.command("go to the spawn room", "north").await;
+    t.command("create the hall", "room new north").await;
+    t.test(
+        "set a clan hall",
+        "clans rats hall 2",
+        vec!["Room 2 is now the hall of Rats."],
+    )
+    .await;
+
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t2.test(
+        "clan halls are closed to outsiders",
+        "north",
+        vec!["Only members of Rats may enter their clan hall."],
+    )
+    .await;
+    t2.test(
+        "joining needs an invitation",
+        "clan join Rats",
+        vec!["You have not been invited to join 'Rats'."],
+    )
+    .await;
+    t.test(
+        "invite a player",
+        "clan invite Shane",
+        vec!["You invite Shane to join Rats."],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("krixi invites you to join the clan Rats.")
+        .await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "join the clan",
+        "clan join rats",
+        vec!["You join the clan Rats."],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane joins the clan.").await;
+    t.assert_prompt().await;
+
+    t2.test(
+        "members cannot invite",
+        "clan invite krixi",
+        vec!["Only officers and leaders can invite players to your clan."],
+    )
+    .await;
+    t2.test(
+        "clan chat reaches members",
+        "clan say for the sewers",
+        vec!["[Rats] Shane: for the sewers"],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains("[Rats] Shane: for the sewers").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "leaders set ranks",
+        "clan rank Shane officer",
+        vec!["Shane's rank in Rats is now officer."],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("Your rank in Rats is now officer.").await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "founders cannot be kicked",
+        "clan kick krixi",
+        vec!["You cannot kick krixi from the clan."],
+    )
+    .await;
+    t2.test(
+        "only leaders set ranks",
+        "clan rank krixi member",
+        vec!["Only leaders can change the ranks of members."],
+    )
+    .await;
+    t.test(
+        "the clan is shown",
+        "clan",
+        vec![
+            "Clan Rats",
+            "founder: krixi",
+            "hall: room 2",
+            "krixi (leader, online)",
+            "Shane (officer, online)",
+        ],
+    )
+    .await;
+
+    drop(t2);
+
+    t.consume_prompt().await;
+    t.line_contains("Shane leaves.").await;
+    t.assert_prompt().await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "clans are persisted",
+        "clans rats",
+        vec![
+            "Clan Rats",
+            "hall: room 2",
+            "krixi (leader, online)",
+            "Shane (officer)",
+        ],
+    )
+    .await;
+    t.test(
+        "kick an offline member",
+        "clan kick Shane",
+        vec!["You kick Shane from Rats."],
+    )
+    .await;
+    t.test(
+        "founders cannot leave",
+        "clan leave",
+        vec!["Founders cannot leave their clan. Disband it instead."],
+    )
+    .await;
+    t.test(
+        "disband the clan",
+        "clan disband",
+        vec!["You disband the clan Rats."],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "disbanding is persisted",
+        "clans",
+        vec!["There are no clans."],
+    )
+    .await;
+}
@@ -1,4 +1,4 @@
-use crate::support::{Matcher, Server};
+use crate::support::{Matcher, Server, StatusCode};
 
 #[tokio::test]
 async fn test_communicate_emote() {
@@ -32,6 +32,141 @@ async fn test_communicate_emote() {
     t.assert_prompt().await;
 }
 
+#[tokio::test]
+async fn test_communicate_pronouns() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t2.test(
+        "players start as they/them",
+        "pronouns",
+        vec!["Others refer to you as they/them."],
+    )
+    .await;
+    t2.test(
+        "pronouns are validated",
+        "pronouns xe",
+        vec!["Enter pronouns: he, she, they, or it."],
+    )
+    .await;
+    t2.test(
+        "set pronouns",
+        "pronouns she",
+        vec!["Others will now refer to you as she/her."],
+    )
+    .await;
+    t2.test(
+        "emotes use pronouns",
+        "/scratches $s head and $v[shrugs|shrug].",
+        vec!["Shane scratches her head and shrugs."],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane scratches her head and shrugs.")
+        .await;
+    t.assert_prompt().await;
+
+    t2.test(
+        "said text is not filled in",
+        "say It costs $s and $5.",
+        vec![r#"You say "It costs $s and $5.""#],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains(r#"Shane says "It costs $s and $5.""#).await;
+    t.assert_prompt().await;
+
+    let mut t = server.restart(t).await;
+    let mut t2 = server.login_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t2.test(
+        "pronouns are persisted",
+        "pronouns",
+        vec!["Others refer to you as she/her."],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_communicate_look_at_player() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.command(
+        "describe yourself",
+        "description A tall figure in a grey coat.",
+    )
+    .await;
+
+    t2.test(
+        "looking at a player shows their description and condition",
+        "look at krixi",
+        vec![
+            "krixi",
+            "A tall figure in a grey coat.",
+            "They are in perfect health.",
+        ],
+    )
+    .await;
+
+    t.test(
+        "turn on privacy",
+        "privacy",
+        vec!["Privacy on. Players who look at you will only see how you are faring."],
+    )
+    .await;
+
+    t2.test_matches(
+        "private players hide their description",
+        "look at krixi",
+        Matcher::exact_includes(vec![
+            "krixi",
+            "They keep to themself.",
+            "They are in perfect health.",
+        ]),
+    )
+    .await;
+
+    t.test(
+        "players always see their own description",
+        "look at krixi",
+        vec!["A tall figure in a grey coat."],
+    )
+    .await;
+
+    t = server.restart(t).await;
+    let mut t2 = server.login_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t2.test_matches(
+        "privacy is persisted",
+        "look at krixi",
+        Matcher::exact_includes(vec![
+            "krixi",
+            "They keep to themself.",
+            "They are in perfect health.",
+        ]),
+    )
+    .await;
+}
+
 #[tokio::test]
 async fn test_communicate_say() {
     let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
@@ -92,7 +227,7 @@ async fn test_communicate_who() {
     t.test_matches(
         "who with just krixi",
         "who",
-        Matcher::exact_includes(vec!["Online players:", "krixi"]),
+        Matcher::exact_includes(vec!["Online players", "krixi"]),
     )
     .await;
 
@@ -105,7 +240,7 @@ async fn test_communicate_who() {
     t.test_matches(
         "who with krixi and Shane",
         "who",
-        Matcher::exact_includes(vec!["Online players:", "Shane", "krixi"]),
+        Matcher::exact_includes(vec!["Online players", "Shane", "krixi"]),
     )
     .await;
 
@@ -118,7 +253,378 @@ async fn test_communicate_who() {
     t.test_matches(
         "who with just krixi again",
         "who",
-        Matcher::exact_includes(vec!["Online players:", "krixi"]),
+        Matcher::exact_includes(vec!["Online players", "krixi"]),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_communicate_recall() {
+    let (_server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.test(
+        "recall requires a valid line count",
+        "recall 0",
+        vec!["Enter a number of lines between 1 and 100 to recall."],
+    )
+    .await;
+
+    t.test("say something", "say Hello?", vec![r#"You say "Hello?""#])
+        .await;
+
+    t.test_matches(
+        "recall the last line",
+        "recall 1",
+        Matcher::exact_includes(vec!["Recalling the last 1 line:", r#"You say "Hello?""#]),
+    )
+    .await;
+
+    t.test_matches(
+        "recalled output is not recorded",
+        "recall 1",
+        Matcher::exact_includes(vec!["Recalling the last 1 line:", r#"You say "Hello?""#]),
+    )
+    .await;
+
+    t.test(
+        "recall more lines",
+        "recall",
+        vec!["The Void", r#"You say "Hello?""#],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_communicate_ignore() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    let mut t3 = server.create_player("Ted", "jk34j;l2k3j4").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Ted arrives.").await;
+    t.assert_prompt().await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("Ted arrives.").await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "nobody is ignored",
+        "ignore",
+        vec!["You are not ignoring anyone."],
+    )
+    .await;
+
+    t2.test(
+        "immortals cannot be ignored",
+        "ignore krixi",
+        vec!["Immortals cannot be ignored."],
+    )
+    .await;
+
+    t2.test(
+        "ignore Ted",
+        "ignore Ted",
+        vec!["You are now ignoring Ted."],
+    )
+    .await;
+
+    t3.test("Ted says hello", "say Hello?", vec![r#"You say "Hello?""#])
+        .await;
+
+    t.consume_prompt().await;
+    t.line_contains(r#"Ted says "Hello?""#).await;
+    t.assert_prompt().await;
+
+    t.test("krixi says hello", "say Hi.", vec![r#"You say "Hi.""#])
+        .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains(r#"krixi says "Hi.""#).await;
+    t2.assert_prompt().await;
+
+    t3.consume_prompt().await;
+    t3.line_contains(r#"krixi says "Hi.""#).await;
+    t3.assert_prompt().await;
+
+    drop(t2);
+
+    t.consume_prompt().await;
+    t.line_contains("Shane leaves.").await;
+    t.assert_prompt().await;
+
+    t3.consume_prompt().await;
+    t3.line_contains("Shane leaves.").await;
+    t3.assert_prompt().await;
+
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    let mut t2 = server.login_player("Shane", "lkja;jf89 f").await;
+
+    t2.test(
+        "ignores are kept between sessions",
+        "ignore",
+        vec!["You are ignoring: Ted"],
+    )
+    .await;
+
+    t2.test(
+        "unignore Ted",
+        "unignore Ted",
+        vec!["You are no longer ignoring Ted."],
     )
     .await;
+
+    t3.consume_prompt().await;
+    t3.line_contains("Shane arrives.").await;
+    t3.assert_prompt().await;
+
+    t3.test(
+        "Ted says hello again",
+        "say Hello!",
+        vec![r#"You say "Hello!""#],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains(r#"Ted says "Hello!""#).await;
+    t2.assert_prompt().await;
+}
+
+#[tokio::test]
+async fn test_communicate_report() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    let mut t3 = server.create_player("Ted", "jk34j;l2k3j4").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Ted arrives.").await;
+    t.assert_prompt().await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("Ted arrives.").await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "a report requires a reason",
+        "report Ted",
+        vec!["Why are you reporting Ted?"],
+    )
+    .await;
+
+    t2.test(
+        "players cannot report themselves",
+        "report Shane for being too cool",
+        vec!["You cannot report yourself."],
+    )
+    .await;
+
+    t2.test(
+        "reported players must exist",
+        "report Bob for not existing",
+        vec!["There is no one named Bob to report."],
+    )
+    .await;
+
+    t3.test("Ted spams", "say Buy gold!", vec![r#"You say "Buy gold!""#])
+        .await;
+
+    t.consume_prompt().await;
+    t.line_contains(r#"Ted says "Buy gold!""#).await;
+    t.assert_prompt().await;
+
+    t2.consume_prompt().await;
+    t2.line_contains(r#"Ted says "Buy gold!""#).await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "report Ted",
+        "report Ted spamming",
+        vec!["Your report about Ted has been filed as #1."],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains("Report #1: Shane reported Ted: spamming")
+        .await;
+    t.assert_prompt().await;
+
+    drop(t3);
+
+    t.consume_prompt().await;
+    t.line_contains("Ted leaves.").await;
+    t.assert_prompt().await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("Ted leaves.").await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "players who recently communicated can be reported after leaving",
+        "report Ted still spamming",
+        vec!["Your report about Ted has been filed as #2."],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_communicate_force() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "forcing someone offline fails",
+        "force Ted look",
+        vec!["Ted is not online."],
+    )
+    .await;
+
+    t.test(
+        "force Shane to speak",
+        "force Shane say I am forced.",
+        vec![r#"You force Shane to "say I am forced.""#],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains(r#"You say "I am forced.""#).await;
+    t2.assert_prompt().await;
+
+    t.consume_prompt().await;
+    t.line_contains(r#"Shane says "I am forced.""#).await;
+    t.assert_prompt().await;
+
+    t.test(
+        "force Shane to use an immortal command",
+        "force Shane shutdown",
+        vec![r#"You force Shane to "shutdown"."#],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("I don't know what that means.").await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "mortals cannot force",
+        "force krixi say hi",
+        vec!["I don't know what that means."],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_communication_log() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "nothing has been said yet",
+        "comms",
+        vec!["No communication matches."],
+    )
+    .await;
+
+    t2.test(
+        "say something",
+        "say Meet me at the docks.",
+        vec![r#"You say "Meet me at the docks.""#],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains(r#"Shane says "Meet me at the docks.""#)
+        .await;
+    t.assert_prompt().await;
+
+    t2.test(
+        "send something with markup",
+        "send krixi The |white|gold|-| is hidden.",
+        vec!["Your term chirps happily"],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane sends").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "search the communication log",
+        "comms",
+        vec![
+            "Communication (1 to 2 of 2, newest first)",
+            "ago, send Shane to krixi in room",
+            ": The gold is hidden.",
+            "ago, say Shane in room",
+            ": Meet me at the docks.",
+        ],
+    )
+    .await;
+    t.test(
+        "filter the communication log",
+        "comms channel say text DOCKS",
+        vec!["Communication (1 to 1 of 1, newest first)"],
+    )
+    .await;
+    t.test(
+        "filters are validated",
+        "comms channel shout",
+        vec!["Channels are one of clan, emote, say, or send."],
+    )
+    .await;
+    t.test(
+        "pages past the end are empty",
+        "comms page 2",
+        vec!["There is no page 2."],
+    )
+    .await;
+    t2.test(
+        "mortals cannot search the communication log",
+        "comms",
+        vec!["I don't know what that means."],
+    )
+    .await;
+
+    drop(t2);
+
+    t.consume_prompt().await;
+    t.line_contains("Shane leaves.").await;
+    t.assert_prompt().await;
+
+    t = server.restart(t).await;
+
+    let web = server.login_web(&t).await;
+    let found = web.communications("player=shane&limit=1").await.unwrap();
+    assert_eq!(found["total"], 2);
+    assert_eq!(found["communications"][0]["channel"], "send");
+    assert_eq!(found["communications"][0]["target"], "krixi");
+    assert_eq!(found["communications"][0]["text"], "The gold is hidden.");
+
+    let found = web
+        .communications("player=shane&limit=1&page=2")
+        .await
+        .unwrap();
+    assert_eq!(found["communications"][0]["text"], "Meet me at the docks.");
+
+    assert_eq!(
+        web.communications("channel=shout").await,
+        Err(StatusCode::BAD_REQUEST)
+    );
 }
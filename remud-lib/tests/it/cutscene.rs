@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use crate::support::{JsonScript, Server, Trigger};
+
+#[tokio::test]
+async fn test_cutscene() {
+    const INTRO: &str = "intro_script";
+    const AMBUSH: &str = "ambush_script";
+
+    let (server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let web = server.login_web(&t).await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+        INTRO,
+        Trigger::Use,
+        r#"SELF.play_cutscene(EVENT.actor, [
+    #{ text: "The lights dim.", delay: ms(50) },
+    #{ text: "A voice booms.", delay: ms(100), color: "Red" },
+], false);"#,
+    ))
+    .await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+        AMBUSH,
+        Trigger::Use,
+        r#"SELF.play_cutscene(EVENT.actor, [
+    #{ text: "The floor creaks.", delay: ms(50) },
+    #{ text: "Something lunges!", delay: secs(1) },
+]);"#,
+    ))
+    .await;
+
+    t.command("create stage prototype", "prototype new").await;
+    t.command("name stage prototype", "prototype 1 name a stage")
+        .await;
+    t.command("key stage prototype", "prototype 1 keywords set stage")
+        .await;
+    t.command(
+        "attach intro script",
+        format!("script {} attach-post prototype 1", INTRO),
+    )
+    .await;
+    t.command("create stage", "object new 1").await;
+    t.command("create trapdoor prototype", "prototype new")
+        .await;
+    t.command("name trapdoor prototype", "prototype 2 name a trapdoor")
+        .await;
+    t.command(
+        "key trapdoor prototype",
+        "prototype 2 keywords set trapdoor",
+    )
+    .await;
+    t.command(
+        "attach ambush script",
+        format!("script {} attach-post prototype 2", AMBUSH),
+    )
+    .await;
+
+    t.test("play a cutscene", "use stage", vec!["You use a stage."])
+        .await;
+
+    t.consume_prompt().await;
+    t.line_contains("The lights dim.").await;
+    t.line_contains("A voice booms.").await;
+    t.assert_prompt().await;
+
+    t.command("create the cellar", "room new north").await;
+    t.command("go to the cellar", "north").await;
+    t.command("create trapdoor", "object new 2").await;
+
+    t.test(
+        "play a prompted cutscene",
+        "use trapdoor",
+        vec!["You use a trapdoor."],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains("The floor creaks.").await;
+    t.assert_prompt().await;
+
+    t.test_exclude(
+        "input skips the cutscene",
+        "look",
+        vec!["Something lunges!"],
+    )
+    .await;
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+
+    t.test_exclude(
+        "skipped lines are never sent",
+        "look",
+        vec!["Something lunges!"],
+    )
+    .await;
+}
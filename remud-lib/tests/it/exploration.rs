@@ -0,0 +1,77 @@
+use crate::support::{JsonScript, Server, Trigger};
+
+#[tokio::test]
+async fn test_exploration() {
+    const SCRIPT: &str = "visited_script";
+
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let web = server.login_web(&t).await;
+
+    t.test("turn on brief mode", "brief", vec!["Brief mode on."])
+        .await;
+
+    t.command("create a room to the north", "room new north")
+        .await;
+    t.command("create a room to the east", "room new east")
+        .await;
+
+    t.test(
+        "new rooms are described in brief mode",
+        "north",
+        vec!["An empty room."],
+    )
+    .await;
+
+    t.test_exclude(
+        "visited rooms are not described in brief mode",
+        "south",
+        vec!["A dark void"],
+    )
+    .await;
+
+    t.test(
+        "looking always describes the room",
+        "look",
+        vec!["A dark void"],
+    )
+    .await;
+
+    t.test(
+        "the map hides unexplored rooms",
+        "map",
+        vec!["#", "-?", "visited"],
+    )
+    .await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+        SCRIPT,
+        Trigger::Look,
+        r#"let here = WORLD.has_visited(EVENT.actor, WORLD.location(EVENT.actor));
+               let nonsense = WORLD.has_visited(EVENT.actor, EVENT.actor);
+               SELF.say(`visited ${here} ${nonsense == ()}`);"#,
+    ))
+    .await;
+
+    t.command("create prototype", "prototype new").await;
+    t.command(
+        "attach script",
+        format!("script {} attach-post prototype 1", SCRIPT),
+    )
+    .await;
+    t.command("create object", "object new 1").await;
+
+    t.command("scripts can check visited rooms", "look").await;
+    t.consume_prompt().await;
+    t.line_contains("says \"visited true true\"").await;
+    t.assert_prompt().await;
+
+    t.command("go north", "north").await;
+    let mut t = server.restart(t).await;
+
+    t.test_exclude(
+        "brief mode and visited rooms persist",
+        "south",
+        vec!["A dark void"],
+    )
+    .await;
+}
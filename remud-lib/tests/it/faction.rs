@@ -0,0 +1,85 @@
+use crate::support::{JsonScript, Server, Trigger};
+
+#[tokio::test]
+async fn test_faction() {
+    const SCRIPT: &str = "snitch_script";
+
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let web = server.login_web(&t).await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+        SCRIPT,
+        Trigger::Use,
+        r#"let rep = WORLD.adjust_rep(EVENT.actor, "syndicate", -100);
+let standing = WORLD.standing(EVENT.actor, "syndicate");
+let welcome = WORLD.has_standing(EVENT.actor, "syndicate", "neutral");
+SELF.whisper(EVENT.actor, `${rep} ${standing} ${welcome}`);"#,
+    ))
+    .await;
+
+    t.test(
+        "there are no factions to start",
+        "faction list",
+        vec!["There are no factions."],
+    )
+    .await;
+    t.test(
+        "faction IDs are validated",
+        "faction new Syndicate Syndicate",
+        vec!["Faction IDs must contain only lowercase letters, digits, dashes, and underscores."],
+    )
+    .await;
+    t.test(
+        "create a faction",
+        "faction new syndicate Syndicate",
+        vec!["Created faction syndicate."],
+    )
+    .await;
+    t.test(
+        "rename a faction",
+        "faction syndicate name The Syndicate",
+        vec!["Updated faction syndicate."],
+    )
+    .await;
+    t.test(
+        "factions are listed",
+        "faction list",
+        vec!["syndicate", "The Syndicate"],
+    )
+    .await;
+    t.test(
+        "players start with no reputation",
+        "stats",
+        vec!["Stats", "Reputation: none"],
+    )
+    .await;
+
+    t.command("create a room", "room new north").await;
+    t.command("go to the room", "north").await;
+    t.command("create wire prototype", "prototype new").await;
+    t.command("name wire prototype", "prototype 1 name a police wire")
+        .await;
+    t.command("key wire prototype", "prototype 1 keywords set wire")
+        .await;
+    t.command("spawn the wire", "object new 1").await;
+    t.command(
+        "attach script",
+        format!("script {} attach-pre object 1", SCRIPT),
+    )
+    .await;
+
+    t.test("use the wire", "use wire", vec!["You use a police wire."])
+        .await;
+    t.consume_prompt().await;
+    t.line_contains("-100 unfriendly false").await;
+    t.assert_prompt().await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "reputation is persisted",
+        "stats",
+        vec!["Reputation: The Syndicate: unfriendly (-100)"],
+    )
+    .await;
+}
@@ -0,0 +1,96 @@
+use crate::support::{JsonScript, Server, Trigger};
+
+#[tokio::test]
+async fn test_globals() {
+    const SCRIPT: &str = "invasion_script";
+
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let web = server.login_web(&t).await;
+
+    t.test(
+        "there are no globals to start",
+        "global list",
+        vec!["There are no globals."],
+    )
+    .await;
+
+    t.test(
+        "set a global",
+        "global set invasion_stage 1",
+        vec!["Set global invasion_stage to 1."],
+    )
+    .await;
+
+    t.test(
+        "set a string global",
+        "global set invader \"the Syndicate\"",
+        vec![r#"Set global invader to "the Syndicate"."#],
+    )
+    .await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+        SCRIPT,
+        Trigger::Use,
+        r#"let stage = WORLD.add_global("invasion_stage", 1);
+let invader = WORLD.get_global("invader");
+SELF.whisper(EVENT.actor, `${invader} reach stage ${stage}`);
+if stage >= 3 {
+  WORLD.set_global("invaded", true);
+  WORLD.remove_global("invader");
+}"#,
+    ))
+    .await;
+
+    t.command("create prototype", "prototype new").await;
+    t.command("name prototype", "prototype 1 name beacon").await;
+    t.command("key prototype", "prototype 1 keywords set beacon")
+        .await;
+    t.command(
+        "attach script",
+        format!("script {} attach-pre prototype 1", SCRIPT),
+    )
+    .await;
+    t.command("create object", "object new 1").await;
+
+    t.test("use the beacon", "use beacon", vec!["You use beacon."])
+        .await;
+    t.consume_prompt().await;
+    t.line_contains("the Syndicate reach stage 2").await;
+    t.assert_prompt().await;
+
+    t.test("use it again", "use beacon", vec!["You use beacon."])
+        .await;
+    t.consume_prompt().await;
+    t.line_contains("the Syndicate reach stage 3").await;
+    t.assert_prompt().await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "globals set by scripts are persisted",
+        "global list",
+        vec!["invaded = true", "invasion_stage = 3"],
+    )
+    .await;
+
+    t.test_exclude(
+        "removed globals stay removed",
+        "global list",
+        vec!["invader"],
+    )
+    .await;
+
+    t.test(
+        "remove a global",
+        "global remove invaded",
+        vec!["Removed global invaded."],
+    )
+    .await;
+
+    t.test(
+        "removing a missing global",
+        "global remove invaded",
+        vec!["No global invaded."],
+    )
+    .await;
+}
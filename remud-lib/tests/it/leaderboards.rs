@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use crate::support::{Server, StatusCode};
+
+#[tokio::test]
+async fn test_leaderboards() {
+    let mut server = Server::new_with_config("[engine]\nleaderboard_secs = 1\n").await;
+    let mut t = server.create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.command("create a room to explore", "room new north")
+        .await;
+    t.test("explore the new room", "north", vec!["An empty room"])
+        .await;
+
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    // Give the leaderboards time to be aggregated.
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+
+    t2.test(
+        "leaderboards rank players by rooms explored",
+        "top explored",
+        vec![
+            "Top players by rooms explored:",
+            "1. krixi: 2 rooms",
+            "2. Shane: 1 room",
+        ],
+    )
+    .await;
+
+    t2.test(
+        "leaderboards rank players by time played",
+        "top playtime",
+        vec!["Top players by time played:", "1. krixi:"],
+    )
+    .await;
+
+    t2.test(
+        "unknown categories are rejected",
+        "top kills",
+        vec!["Enter a leaderboard category: explored or playtime."],
+    )
+    .await;
+
+    let web = server.connect_web();
+
+    let leaderboard: serde_json::Value =
+        serde_json::from_str(web.get("/leaderboards/explored").await.unwrap().as_str()).unwrap();
+    assert_eq!(leaderboard["category"], "explored");
+    assert_eq!(leaderboard["entries"][0]["name"], "krixi");
+    assert_eq!(leaderboard["entries"][0]["value"], 2);
+    assert_eq!(leaderboard["entries"][1]["name"], "Shane");
+
+    match web.get("/leaderboards/kills").await {
+        Err(StatusCode::NOT_FOUND) => (),
+        e => panic!("expected not found, got: {:?}", e),
+    }
+}
@@ -1,4 +1,8 @@
-use std::time::Duration;
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    time::Duration,
+};
 
 use crate::support::Server;
 
@@ -7,6 +11,19 @@ async fn test_login_create_player() {
     let (_server, _t) = Server::new_create_player("Shane", "s;kladjf").await;
 }
 
+#[tokio::test]
+async fn test_login_banner() {
+    let server = Server::new_with_config("[login]\nbanner = \"hi\"\n").await;
+    let mut t = server.connect_telnet();
+
+    for line in ["#   # ###", "#   #  #", "#####  #", "#   #  #", "#   # ###"] {
+        t.line_contains(line).await;
+    }
+    t.line_contains("Connected to").await;
+    t.line_contains("Name?").await;
+    t.assert_prompt().await;
+}
+
 #[tokio::test]
 async fn test_login_create_verify_failed() {
     let (_server, mut t) = Server::new_connect_telnet().await;
@@ -53,6 +70,230 @@ async fn test_login_create_verify_failed() {
     t.assert_prompt().await;
 }
 
+#[tokio::test]
+async fn test_login_hides_password() {
+    let (_server, mut t) = Server::new_connect_telnet().await;
+
+    t.line_contains("Connected to").await;
+    t.line_contains("Name?").await;
+    t.assert_prompt().await;
+    assert!(!t.echo_hidden());
+
+    t.test(
+        "enter name",
+        "Shane",
+        vec!["New user detected", "Password?"],
+    )
+    .await;
+    assert!(t.echo_hidden());
+
+    t.test(
+        "enter password",
+        "some pw",
+        vec!["Password accepted.", "Verify?"],
+    )
+    .await;
+    assert!(t.echo_hidden());
+
+    t.test(
+        "verify password",
+        "some pw",
+        vec!["Password verified.", "Welcome to City Six", "The Void"],
+    )
+    .await;
+    assert!(!t.echo_hidden());
+}
+
+#[tokio::test]
+async fn test_login_negotiation_fallback() {
+    let server = Server::new().await;
+    let port = server.telnet();
+
+    // A raw client which ignores telnet negotiation, like netcat.
+    let received = tokio::task::spawn_blocking(move || {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(15)))
+            .unwrap();
+
+        let mut received = Vec::new();
+        let mut read_until = |stream: &mut TcpStream, text: &str| {
+            let mut buffer = [0; 1024];
+            while !String::from_utf8_lossy(&received).contains(text) {
+                let read = stream
+                    .read(&mut buffer)
+                    .expect("timed out waiting for server");
+                assert!(read > 0, "server closed the connection");
+                received.extend_from_slice(&buffer[..read]);
+            }
+        };
+
+        read_until(&mut stream, "Name?");
+        stream.write_all(b"Shane\r\n").unwrap();
+        read_until(&mut stream, "New user detected.");
+
+        received
+    })
+    .await
+    .unwrap();
+
+    // The server asks for the terminal type and proceeds without an answer.
+    assert_eq!(&received[..3], &[255, 253, 24]);
+}
+
+#[tokio::test]
+async fn test_login_character_mode() {
+    let server = Server::new().await;
+    let port = server.telnet();
+
+    // A raw client which sends each key as it is pressed and relies on the server to echo.
+    let received = tokio::task::spawn_blocking(move || {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(15)))
+            .unwrap();
+
+        let mut received = Vec::new();
+        let mut read_until = |stream: &mut TcpStream, text: &[u8], count: usize| {
+            let mut buffer = [0; 1024];
+            while received.windows(text.len()).filter(|w| *w == text).count() < count {
+                let read = stream
+                    .read(&mut buffer)
+                    .expect("timed out waiting for server");
+                assert!(read > 0, "server closed the connection");
+                received.extend_from_slice(&buffer[..read]);
+            }
+        };
+
+        // DO TTYPE, DO LINEMODE, DO NAWS, WILL SGA, WILL MXP, WILL GMCP
+        read_until(&mut stream, &[255, 251, 201], 1);
+        // WONT TTYPE, WONT LINEMODE, WONT NAWS, DO SGA, DONT MXP, DONT GMCP
+        stream
+            .write_all(&[
+                255, 252, 24, 255, 252, 34, 255, 252, 31, 255, 253, 3, 255, 254, 91, 255, 254, 201,
+            ])
+            .unwrap();
+
+        // WILL ECHO
+        read_until(&mut stream, &[255, 251, 1], 1);
+        stream.write_all(&[255, 253, 1]).unwrap();
+
+        read_until(&mut stream, b"Name?", 1);
+        for key in b"Shane\r\0" {
+            stream.write_all(&[*key]).unwrap();
+        }
+        read_until(&mut stream, b"Password?", 1);
+        stream.write_all(b"hunter22\r\0").unwrap();
+        read_until(&mut stream, b"Verify?", 1);
+        stream.write_all(b"hunter22\r\0").unwrap();
+        read_until(&mut stream, b"The Void", 1);
+
+        stream.write_all(b"say hi\r\0").unwrap();
+        read_until(&mut stream, b"You say \"hi\"", 1);
+
+        // up arrow recalls the last line
+        stream.write_all(b"\x1b[A\r\0").unwrap();
+        read_until(&mut stream, b"You say \"hi\"", 2);
+
+        received
+    })
+    .await
+    .unwrap();
+
+    let received = String::from_utf8_lossy(&received);
+    assert!(received.contains("Shane\r\n"));
+    assert!(!received.contains("hunter22"));
+}
+
+#[tokio::test]
+async fn test_login_mxp() {
+    let server = Server::new().await;
+    let port = server.telnet();
+
+    // A raw line mode client which understands MXP.
+    let received = tokio::task::spawn_blocking(move || {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(15)))
+            .unwrap();
+
+        let mut received = Vec::new();
+        let mut read_until = |stream: &mut TcpStream, text: &[u8]| {
+            let mut buffer = [0; 1024];
+            while !received.windows(text.len()).any(|w| w == text) {
+                let read = stream
+                    .read(&mut buffer)
+                    .expect("timed out waiting for server");
+                assert!(read > 0, "server closed the connection");
+                received.extend_from_slice(&buffer[..read]);
+            }
+        };
+
+        // DO TTYPE, DO LINEMODE, DO NAWS, WILL SGA, WILL MXP, WILL GMCP
+        read_until(&mut stream, &[255, 251, 201]);
+        // WONT TTYPE, WONT LINEMODE, WONT NAWS, DONT SGA, DO MXP, DONT GMCP
+        stream
+            .write_all(&[
+                255, 252, 24, 255, 252, 34, 255, 252, 31, 255, 254, 3, 255, 253, 91, 255, 254, 201,
+            ])
+            .unwrap();
+
+        // MXP starts in locked mode
+        read_until(&mut stream, &[255, 250, 91, 255, 240]);
+        read_until(&mut stream, b"\x1b[7z");
+
+        read_until(&mut stream, b"Name?");
+        stream.write_all(b"Shane\r\n").unwrap();
+        read_until(&mut stream, b"Password?");
+        stream.write_all(b"hunter22\r\n").unwrap();
+        read_until(&mut stream, b"Verify?");
+        stream.write_all(b"hunter22\r\n").unwrap();
+        read_until(&mut stream, b"The Void");
+
+        stream.write_all(b"room new north\r\n").unwrap();
+        read_until(&mut stream, b"Created room 1");
+
+        // Exits are links which move the player
+        stream.write_all(b"exits\r\n").unwrap();
+        read_until(&mut stream, b"<send href=\"north\">north</send>");
+
+        received
+    })
+    .await
+    .unwrap();
+
+    let received = String::from_utf8_lossy(&received);
+    assert!(received.contains("\x1b[1z"));
+}
+
+#[tokio::test]
+async fn test_login_idle_disconnect() {
+    let server =
+        Server::new_with_config("[telnet]\nkeepalive_secs = 1\nidle_timeout_secs = 3\n").await;
+    let port = server.telnet();
+
+    // A client which connects and never sends anything.
+    let received = tokio::task::spawn_blocking(move || {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(15)))
+            .unwrap();
+
+        let mut received = Vec::new();
+        stream
+            .read_to_end(&mut received)
+            .expect("timed out waiting for disconnect");
+
+        received
+    })
+    .await
+    .unwrap();
+
+    // Keepalives are sent while the client is connected.
+    assert!(received.windows(2).any(|bytes| bytes == [255, 241]));
+    assert!(String::from_utf8_lossy(&received).contains("Disconnected for inactivity."));
+}
+
 #[tokio::test]
 async fn test_login_login_player() {
     let (mut server, mut t) = Server::new_create_player("Shane", "s;kladjf").await;
@@ -65,6 +306,26 @@ async fn test_login_login_player() {
     t.assert_prompt().await;
 }
 
+#[tokio::test]
+async fn test_login_relog_restores_player() {
+    let (mut server, mut t) = Server::new_create_player("Shane", "s;kladjf").await;
+
+    t.test("say something", "say Hello?", vec![r#"You say "Hello?""#])
+        .await;
+
+    drop(t);
+    std::thread::sleep(Duration::from_secs(1));
+
+    let mut t = server.login_player("Shane", "s;kladjf").await;
+
+    t.test(
+        "output from before logging out is recalled",
+        "recall",
+        vec![r#"You say "Hello?""#],
+    )
+    .await;
+}
+
 #[tokio::test]
 async fn test_login_already_online() {
     let (server, mut t) = Server::new_create_player("Shane", "some pw").await;
@@ -97,6 +358,113 @@ async fn test_login_already_online() {
     t2.assert_prompt().await;
 }
 
+#[tokio::test]
+async fn test_login_takeover() {
+    let mut server = Server::new_with_config("[login]\ntakeover = true\n").await;
+    let mut t = server.create_player("Shane", "some pw").await;
+    t.assert_prompt().await;
+
+    let mut t2 = server.connect_telnet();
+    t2.line_contains("Connected to").await;
+    t2.line_contains("Name?").await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "enter name of an online player",
+        "Shane",
+        vec!["will disconnect their other session", "Password?"],
+    )
+    .await;
+
+    t2.test(
+        "verify password",
+        "some pw",
+        vec!["Password verified.", "Welcome back to City Six", "The Void"],
+    )
+    .await;
+    t2.assert_prompt().await;
+
+    t.disconnected_with("You have logged in from another connection.")
+        .await;
+
+    t2.test("the player was not logged out", "who", vec!["Shane"])
+        .await;
+}
+
+#[tokio::test]
+async fn test_login_queue() {
+    let mut server = Server::new_with_config("[engine]\nmax_players = 1\n").await;
+    let t = server.create_player("krixi", "(*&%(*#&%*&").await;
+
+    let mut t2 = server.connect_telnet();
+    t2.line_contains("Connected to").await;
+    t2.line_contains("Name?").await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "enter name",
+        "Shane",
+        vec!["New user detected", "Password?"],
+    )
+    .await;
+    t2.test(
+        "enter password",
+        "some pw",
+        vec!["Password accepted.", "Verify?"],
+    )
+    .await;
+    t2.test(
+        "full servers queue logins",
+        "some pw",
+        vec![
+            "Password verified.",
+            "City Six is full. You have been placed in a queue.",
+            "You are number 1 in the queue.",
+        ],
+    )
+    .await;
+    t2.test(
+        "input waits in the queue",
+        "look",
+        vec!["Please wait, you will be connected when there is room."],
+    )
+    .await;
+
+    let mut t3 = server.connect_telnet();
+    t3.line_contains("Connected to").await;
+    t3.line_contains("Name?").await;
+    t3.assert_prompt().await;
+
+    t3.test("enter name", "Ted", vec!["New user detected", "Password?"])
+        .await;
+    t3.test(
+        "enter password",
+        "other pw",
+        vec!["Password accepted.", "Verify?"],
+    )
+    .await;
+    t3.test(
+        "the queue is first come, first served",
+        "other pw",
+        vec!["You are number 2 in the queue."],
+    )
+    .await;
+
+    drop(t);
+
+    t2.consume_prompt().await;
+    t2.line_contains("Welcome to City Six").await;
+
+    t3.consume_prompt().await;
+    t3.line_contains("You are number 1 in the queue.").await;
+    t3.assert_prompt().await;
+
+    std::thread::sleep(Duration::from_secs(1));
+    let mut t = server.login_player("krixi", "(*&%(*#&%*&").await;
+    t.test("immortals skip the queue", "who", vec!["krixi", "Shane"])
+        .await;
+}
+
 #[tokio::test]
 async fn test_login_bad_player_name() {
     let (_server, mut t) = Server::new_connect_telnet().await;
@@ -298,3 +666,200 @@ async fn test_login_create_player_change_password() {
 
     let _ = server.restart(t).await;
 }
+
+#[tokio::test]
+async fn test_login_delete_character() {
+    let mut server = Server::new().await;
+    let mut t = server.create_player("Shane", "some pw").await;
+
+    t.test(
+        "deleting a character asks for the password",
+        "delete character",
+        vec!["Character Deletion", "Enter your password to confirm."],
+    )
+    .await;
+
+    t.test(
+        "a bad password keeps the character",
+        "wrong pw",
+        vec!["Character not deleted."],
+    )
+    .await;
+
+    t.test(
+        "deleting a character asks for the password",
+        "delete character",
+        vec!["Enter your password to confirm."],
+    )
+    .await;
+
+    t.info("deleting a character disconnects");
+    t.send("some pw").await;
+    t.disconnected_with("Your character will be deleted.").await;
+
+    drop(t);
+    std::thread::sleep(Duration::from_secs(1));
+
+    let mut t = server.connect_telnet();
+    t.line_contains("Connected to").await;
+    t.line_contains("Name?").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "the character is kept during the grace period",
+        "Shane",
+        vec!["User located.", "Password?"],
+    )
+    .await;
+
+    t.test(
+        "logging in cancels the deletion",
+        "some pw",
+        vec![
+            "Password verified.",
+            "Welcome to City Six",
+            "deletion has been cancelled",
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_login_purge_deleted_character() {
+    let mut server =
+        Server::new_with_config("[engine]\nleaderboard_secs = 1\n[login]\ndeletion_secs = 0\n")
+            .await;
+    let mut t = server.create_player("Shane", "some pw").await;
+
+    t.test(
+        "deleting a character asks for the password",
+        "delete character",
+        vec!["Enter your password to confirm."],
+    )
+    .await;
+
+    t.info("deleting a character disconnects");
+    t.send("some pw").await;
+    t.disconnected_with("Your character will be deleted.").await;
+
+    drop(t);
+    // Give the deleted players time to be purged.
+    std::thread::sleep(Duration::from_secs(2));
+
+    let mut t = server.connect_telnet();
+    t.line_contains("Connected to").await;
+    t.line_contains("Name?").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "the character is gone once purged",
+        "Shane",
+        vec!["New user detected.", "Password?"],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_login_rename_player() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let mut t2 = server.create_player("Shane", "some pw").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.command("make Shane an immortal", "player Shane set immortal")
+        .await;
+
+    t2.test(
+        "post news to be renamed",
+        "news post Boats are coming.",
+        vec!["You post news 1."],
+    )
+    .await;
+    t.consume_prompt().await;
+    t.line_contains("Shane posted news.").await;
+    t.assert_prompt().await;
+
+    t2.test(
+        "open a poll to be renamed",
+        "poll create Boats?: yes, no",
+        vec!["You open poll 1."],
+    )
+    .await;
+    t.consume_prompt().await;
+    t.line_contains("Shane opened poll 1: Boats?").await;
+    t.assert_prompt().await;
+
+    let admin = server.login_web(&t).await;
+    server
+        .login_web(&t2)
+        .await
+        .create_api_token("shane-bot", &["scripts:read"])
+        .await
+        .unwrap();
+
+    t.test(
+        "a name must be valid",
+        "player Shane rename !!",
+        vec!["Enter a valid player name."],
+    )
+    .await;
+
+    t.test(
+        "a name must not be taken",
+        "player Shane rename krixi",
+        vec!["Renaming the player."],
+    )
+    .await;
+    t.consume_prompt().await;
+    t.line_contains("The name krixi is taken.").await;
+
+    t.test(
+        "rename a player",
+        "player Shane rename Sam",
+        vec!["Renaming the player."],
+    )
+    .await;
+    t.consume_prompt().await;
+    t.line_contains("Renamed Shane to Sam.").await;
+
+    t2.disconnected_with("You have been renamed to Sam.").await;
+
+    drop(t2);
+    std::thread::sleep(Duration::from_secs(1));
+
+    t.consume_prompt().await;
+    t.line_contains("Sam leaves.").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "news is credited to the new name",
+        "news",
+        vec!["1. from Sam"],
+    )
+    .await;
+    t.test(
+        "polls are credited to the new name",
+        "poll 1",
+        vec!["Opened by Sam"],
+    )
+    .await;
+
+    let tokens = admin.api_tokens().await.unwrap();
+    assert_eq!(tokens["tokens"][0]["name"], "shane-bot");
+    assert_eq!(tokens["tokens"][0]["created_by"], "Sam");
+
+    t = server.restart(t).await;
+
+    t.test("renamed news is persisted", "news", vec!["1. from Sam"])
+        .await;
+    t.test(
+        "renamed polls are persisted",
+        "poll 1",
+        vec!["Opened by Sam"],
+    )
+    .await;
+
+    let _t2 = server.login_player("Sam", "some pw").await;
+}
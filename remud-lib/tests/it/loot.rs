@@ -0,0 +1,139 @@
+use crate::support::{JsonScript, Server, Trigger};
+
+#[tokio::test]
+async fn test_loot_tables() {
+    const SCRIPT: &str = "chest_script";
+
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let web = server.login_web(&t).await;
+
+    t.test(
+        "there are no loot tables to start",
+        "loot list",
+        vec!["There are no loot tables."],
+    )
+    .await;
+
+    t.command("create a room", "room new north").await;
+    t.command("go to the room", "north").await;
+    t.command("create coin prototype", "prototype new").await;
+    t.command("name coin prototype", "prototype 1 name a gold coin")
+        .await;
+
+    t.test(
+        "create a loot table",
+        "loot new coins",
+        vec!["Created loot table coins."],
+    )
+    .await;
+    t.test(
+        "missing prototypes are rejected",
+        "loot coins add 5 common",
+        vec!["Prototype 5 does not exist."],
+    )
+    .await;
+    t.test(
+        "rarities are checked",
+        "loot coins add 1 mythic",
+        vec!["Rarities are common, uncommon, rare, epic, and legendary."],
+    )
+    .await;
+    t.test(
+        "add an entry",
+        "loot coins add 1 rare 3",
+        vec!["Updated loot table coins."],
+    )
+    .await;
+    t.test(
+        "add a nothing entry",
+        "loot coins add nothing common 1 10",
+        vec!["Updated loot table coins."],
+    )
+    .await;
+    t.test(
+        "preview the drop rates",
+        "loot coins info",
+        vec![
+            "1. 3 x a gold coin (rare, weight 10): 50.0% per roll, 1.50 per drop",
+            "2. nothing (common, weight 10): 50.0% per roll, 0.00 per drop",
+            "rarities per roll: rare 50.0%",
+            "drops nothing: 50.0%",
+        ],
+    )
+    .await;
+    t.test(
+        "remove the nothing entry",
+        "loot coins remove 2",
+        vec!["Updated loot table coins."],
+    )
+    .await;
+    t.test(
+        "set the rolls",
+        "loot coins rolls 2",
+        vec!["Updated loot table coins."],
+    )
+    .await;
+    t.test(
+        "roll the table",
+        "loot coins roll",
+        vec!["Dropped:", "3 x a gold coin (rare)"],
+    )
+    .await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+        SCRIPT,
+        Trigger::Use,
+        r#"let drops = SELF.drop_loot("coins");
+SELF.whisper(EVENT.actor, `${drops.len()} drops of ${drops[0].quantity} ${drops[0].rarity}`);"#,
+    ))
+    .await;
+
+    t.command("create chest prototype", "prototype new").await;
+    t.command("name chest prototype", "prototype 2 name a chest")
+        .await;
+    t.command("key chest prototype", "prototype 2 keywords set chest")
+        .await;
+    t.command(
+        "attach script",
+        format!("script {} attach-pre prototype 2", SCRIPT),
+    )
+    .await;
+    t.command("create chest", "object new 2").await;
+
+    t.test("open the chest", "use chest", vec!["You use a chest."])
+        .await;
+    t.consume_prompt().await;
+    t.line_contains("2 drops of 3 rare").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "the loot is dropped in the room",
+        "room info",
+        vec!["object 2: a gold coin", "object 7: a gold coin"],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "loot tables are persisted",
+        "loot coins info",
+        vec![
+            "rolls: 2",
+            "1. 3 x a gold coin (rare, weight 10): 100.0% per roll, 6.00 per drop",
+        ],
+    )
+    .await;
+    t.test(
+        "delete the table",
+        "loot coins delete",
+        vec!["Deleted loot table coins."],
+    )
+    .await;
+    t.test(
+        "deleted tables are gone",
+        "loot list",
+        vec!["There are no loot tables."],
+    )
+    .await;
+}
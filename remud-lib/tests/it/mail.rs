@@ -0,0 +1,223 @@
+use std::time::Duration;
+
+use crate::support::Server;
+
+#[tokio::test]
+async fn test_mail() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    drop(t2);
+
+    t.consume_prompt().await;
+    t.line_contains("Shane leaves.").await;
+    t.assert_prompt().await;
+
+    t.command("create sword prototype", "prototype new").await;
+    t.command("name sword prototype", "prototype 1 name a rusty sword")
+        .await;
+    t.command("key sword prototype", "prototype 1 keywords set sword")
+        .await;
+    t.command("spawn the sword", "object new 1").await;
+    t.command("pick up the sword", "get sword").await;
+    t.command("grant coins", "bank krixi grant 100").await;
+
+    t.test(
+        "mail needs a known recipient",
+        "mail send Ted hello",
+        vec!["There is no player named Ted."],
+    )
+    .await;
+    t.test(
+        "mail cannot be sent to yourself",
+        "mail send krixi hello",
+        vec!["You cannot send mail to yourself."],
+    )
+    .await;
+    t.test(
+        "offline players can be sent letters",
+        "mail send Shane Meet me at the docks.",
+        vec!["You send mail to Shane."],
+    )
+    .await;
+    t.test(
+        "attached coins must be carried",
+        "mail send Shane with 500: too much",
+        vec!["You are only carrying 100 coins."],
+    )
+    .await;
+    t.test(
+        "attach coins",
+        "mail send Shane with 40: for the trip",
+        vec!["You send 40 coins to Shane by mail."],
+    )
+    .await;
+    t.test(
+        "attach an object",
+        "mail send Shane with sword",
+        vec!["You send a rusty sword to Shane by mail."],
+    )
+    .await;
+    t.test(
+        "attachments are held in escrow",
+        "balance",
+        vec!["You are carrying 60 coins."],
+    )
+    .await;
+    t.test_exclude("the sword is gone", "inventory", vec!["a rusty sword"])
+        .await;
+
+    t = server.restart(t).await;
+
+    let mut t2 = server.login_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t2.test(
+        "unread mail is announced at login",
+        "recall",
+        vec!["You have 3 unread letters."],
+    )
+    .await;
+    t2.test(
+        "mail is persisted",
+        "mail",
+        vec![
+            "Mail",
+            "1. from krixi",
+            "2. from krixi",
+            "with attachments",
+            "3. from krixi",
+        ],
+    )
+    .await;
+    t2.test(
+        "read a letter",
+        "mail read 1",
+        vec!["Mail 1 from krixi", "Meet me at the docks."],
+    )
+    .await;
+    t2.test(
+        "only received mail can be read",
+        "mail read 4",
+        vec!["You have no mail 4."],
+    )
+    .await;
+    t2.test(
+        "letters without attachments have nothing to claim",
+        "mail claim 1",
+        vec!["Mail 1 has nothing attached."],
+    )
+    .await;
+    t2.test(
+        "attachments are shown",
+        "mail read 2",
+        vec!["for the trip", "Attached: 40 coins."],
+    )
+    .await;
+    t2.test(
+        "claim coins",
+        "mail claim 2",
+        vec!["You claim 40 coins from mail 2."],
+    )
+    .await;
+    t2.test(
+        "claimed coins are carried",
+        "balance",
+        vec!["You are carrying 40 coins."],
+    )
+    .await;
+    t2.test(
+        "attachments can only be claimed once",
+        "mail claim 2",
+        vec!["Mail 2 has nothing attached."],
+    )
+    .await;
+    t2.test(
+        "deleting a letter returns its attachments",
+        "mail delete 3",
+        vec!["You delete mail 3, returning a rusty sword to krixi."],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane deleted your mail, returning a rusty sword to you.")
+        .await;
+    t.assert_prompt().await;
+
+    t.test(
+        "the sender gets the object back",
+        "inventory",
+        vec!["a rusty sword"],
+    )
+    .await;
+
+    t2.test(
+        "delete a letter",
+        "mail delete 1",
+        vec!["You delete mail 1."],
+    )
+    .await;
+    t2.test(
+        "deleted mail is gone",
+        "mail read 1",
+        vec!["You have no mail 1."],
+    )
+    .await;
+
+    t.test(
+        "online recipients are notified",
+        "mail send Shane Thanks.",
+        vec!["You send mail to Shane."],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("You have new mail from krixi.").await;
+    t2.assert_prompt().await;
+}
+
+#[tokio::test]
+async fn test_mail_expiry() {
+    let mut server = Server::new_with_config("[mail]\nexpiry_secs = 1\n").await;
+    let mut t = server.create_player("krixi", "(*&%(*#&%*&").await;
+    let t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    drop(t2);
+
+    t.consume_prompt().await;
+    t.line_contains("Shane leaves.").await;
+    t.assert_prompt().await;
+
+    t.command("grant coins", "bank krixi grant 50").await;
+    t.test(
+        "attach coins",
+        "mail send Shane with 50",
+        vec!["You send 50 coins to Shane by mail."],
+    )
+    .await;
+
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    t.consume_prompt().await;
+    t.line_contains("Your mail to Shane expired unclaimed, returning 50 coins to you.")
+        .await;
+    t.assert_prompt().await;
+
+    t.test(
+        "returned coins are deposited in the bank",
+        "balance",
+        vec!["You are carrying 0 coins. Your bank balance is 50 coins."],
+    )
+    .await;
+}
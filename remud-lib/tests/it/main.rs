@@ -1,8 +1,26 @@
+mod achievements;
+mod admin;
+mod allowlist;
+mod bank;
+mod campaign;
+mod clan;
 mod communication;
+mod cutscene;
+mod exploration;
+mod faction;
+mod globals;
+mod leaderboards;
 mod login;
+mod loot;
+mod mail;
+mod market;
+mod moderation;
+mod news;
 mod object;
+mod poll;
 mod prototype;
 mod room;
+mod room_template;
 mod scripts_object_attachment;
 mod scripts_object_trigger;
 mod scripts_player_attachment;
@@ -12,6 +30,16 @@ mod scripts_room_attachment;
 mod scripts_room_trigger;
 mod scripts_self;
 mod scripts_world;
+mod season;
+mod shop;
+mod spawn;
 mod support;
+mod system;
+mod tutorial;
 mod web_auth;
+mod web_docs;
+mod web_graphql;
+mod web_players;
 mod web_script;
+mod web_status;
+mod web_ws;
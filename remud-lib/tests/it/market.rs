@@ -0,0 +1,196 @@
+use std::time::Duration;
+
+use crate::support::Server;
+
+#[tokio::test]
+async fn test_market() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.command("create sword prototype", "prototype new").await;
+    t.command("name sword prototype", "prototype 1 name a rusty sword")
+        .await;
+    t.command(
+        "key sword prototype",
+        "prototype 1 keywords set rusty sword",
+    )
+    .await;
+    t.command("spawn the sword", "object new 1").await;
+    t.command("pick up the sword", "get sword").await;
+
+    t.test(
+        "the market starts empty",
+        "market",
+        vec!["There is nothing for sale on the market."],
+    )
+    .await;
+    t.test(
+        "prices must be positive",
+        "market sell 0 sword",
+        vec!["The price must be a positive number of coins."],
+    )
+    .await;
+    t.test(
+        "only carried items can be sold",
+        "market sell 10 shield",
+        vec!["You don't have \"shield\"."],
+    )
+    .await;
+    t.test(
+        "list an item for sale",
+        "market sell 50 sword",
+        vec!["You list a rusty sword for 50 coins as listing 1. It expires in 72h 0m."],
+    )
+    .await;
+    t.test(
+        "the listed item is held by the market",
+        "market sell 50 sword",
+        vec!["You don't have \"sword\"."],
+    )
+    .await;
+    t.test(
+        "listings are shown",
+        "market list",
+        vec![
+            "Market listings",
+            "1. a rusty sword for 50 coins from krixi",
+        ],
+    )
+    .await;
+    t.test(
+        "sellers cannot buy their own listings",
+        "market buy 1",
+        vec!["You cannot buy your own listing. Cancel it to take it back."],
+    )
+    .await;
+
+    let web = server.connect_web();
+    let market: serde_json::Value =
+        serde_json::from_str(web.get("/market").await.unwrap().as_str()).unwrap();
+    assert_eq!(market["listings"][0]["id"], 1);
+    assert_eq!(market["listings"][0]["name"], "a rusty sword");
+    assert_eq!(market["listings"][0]["price"], 50);
+    assert_eq!(market["listings"][0]["seller"], "krixi");
+
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t2.test(
+        "buyers need enough coins",
+        "market buy 1",
+        vec!["a rusty sword costs 50 coins, but you are only carrying 0 coins."],
+    )
+    .await;
+    t.command("grant coins", "bank Shane grant 60").await;
+    t2.test(
+        "buy the item",
+        "market buy 1",
+        vec!["You buy a rusty sword from krixi for 50 coins."],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane bought a rusty sword from you for 50 coins")
+        .await;
+    t.assert_prompt().await;
+
+    t.test(
+        "the seller is paid into their bank account",
+        "balance",
+        vec!["You are carrying 0 coins. Your bank balance is 50 coins."],
+    )
+    .await;
+    t2.test(
+        "the buyer pays with carried coins",
+        "balance",
+        vec!["You are carrying 10 coins. Your bank balance is 0 coins."],
+    )
+    .await;
+    t2.test(
+        "list the bought item",
+        "market sell 20 rusty sword",
+        vec!["You list a rusty sword for 20 coins as listing 2."],
+    )
+    .await;
+    t.test(
+        "only the seller can cancel a listing",
+        "market cancel 2",
+        vec!["Listing 2 is not yours."],
+    )
+    .await;
+    t.test(
+        "missing listings are reported",
+        "market buy 1",
+        vec!["Listing 1 not found."],
+    )
+    .await;
+
+    drop(t2);
+
+    t.consume_prompt().await;
+    t.line_contains("Shane leaves.").await;
+    t.assert_prompt().await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "listings are persisted",
+        "market",
+        vec!["2. a rusty sword for 20 coins from Shane"],
+    )
+    .await;
+
+    let mut t2 = server.login_player("Shane", "lkja;jf89 f").await;
+
+    t2.test(
+        "cancel a listing",
+        "market cancel 2",
+        vec!["You cancel listing 2 and take back a rusty sword."],
+    )
+    .await;
+    t2.test("the item is returned", "inventory", vec!["a rusty sword"])
+        .await;
+}
+
+#[tokio::test]
+async fn test_market_expiry() {
+    let mut server = Server::new_with_config("[market]\nlisting_secs = 1\n").await;
+    let mut t = server.create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.command("create sword prototype", "prototype new").await;
+    t.command("name sword prototype", "prototype 1 name a rusty sword")
+        .await;
+    t.command("key sword prototype", "prototype 1 keywords set sword")
+        .await;
+    t.command("spawn the sword", "object new 1").await;
+    t.command("pick up the sword", "get sword").await;
+    t.test(
+        "list an item for sale",
+        "market sell 50 sword",
+        vec!["You list a rusty sword for 50 coins as listing 1. It expires in 1s."],
+    )
+    .await;
+
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    t.consume_prompt().await;
+    t.line_contains("Your market listing of a rusty sword expired and was returned to you.")
+        .await;
+    t.assert_prompt().await;
+
+    let web = server.connect_web();
+    let market: serde_json::Value =
+        serde_json::from_str(web.get("/market").await.unwrap().as_str()).unwrap();
+    assert_eq!(market["listings"].as_array().unwrap().len(), 0);
+
+    t = server.restart(t).await;
+
+    t.test(
+        "the expired item is returned",
+        "inventory",
+        vec!["a rusty sword"],
+    )
+    .await;
+}
@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use crate::support::Server;
+
+#[tokio::test]
+async fn test_moderation_snoop() {
+    let mut server = Server::new_with_config("[moderation]\nsnoop_secs = 2\n").await;
+    let mut t = server.create_player("krixi", "(*&%(*#&%*&").await;
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "snooping someone offline fails",
+        "snoop Ted",
+        vec!["Ted is not online."],
+    )
+    .await;
+
+    t.test(
+        "snooping yourself fails",
+        "snoop krixi",
+        vec!["You cannot snoop yourself."],
+    )
+    .await;
+
+    t.command("dig a room", "room new north").await;
+    t.command("leave the room", "north").await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("krixi leaves to the north.").await;
+    t2.assert_prompt().await;
+
+    t.test(
+        "snoop Shane",
+        "snoop Shane",
+        vec!["You are snooping Shane for 2s."],
+    )
+    .await;
+
+    t2.test("Shane speaks", "say hello", vec![r#"You say "hello""#])
+        .await;
+
+    t.consume_prompt().await;
+    t.line_contains("(snoop Shane) > say hello").await;
+    t.line_contains(r#"(snoop Shane) You say "hello""#).await;
+    t.assert_prompt().await;
+
+    std::thread::sleep(Duration::from_secs(2));
+
+    t.consume_prompt().await;
+    t.line_contains("Your snoop of Shane has expired.").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "the snoop is over",
+        "snoop off",
+        vec!["You are not snooping anyone."],
+    )
+    .await;
+
+    t2.test(
+        "mortals cannot snoop",
+        "snoop krixi",
+        vec!["I don't know what that means."],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_moderation_snoop_approval() {
+    let mut server = Server::new_with_config("[moderation]\nsnoop_approval = true\n").await;
+    let mut t = server.create_player("krixi", "(*&%(*#&%*&").await;
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.command("make Shane an immortal", "player Shane set immortal")
+        .await;
+
+    let t3 = server.create_player("Ted", "s3cr3t p4ss").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Ted arrives.").await;
+    t.assert_prompt().await;
+    t2.consume_prompt().await;
+    t2.line_contains("Ted arrives.").await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "immortals cannot make themselves administrators",
+        "player Shane set admin",
+        vec!["Only admins may change player flags."],
+    )
+    .await;
+
+    t2.test(
+        "non-administrators need approval",
+        "snoop Ted",
+        vec!["Your request to snoop Ted awaits another immortal's approval."],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains(r#"Shane asks to snoop Ted. Use "snoop approve Shane" to allow it."#)
+        .await;
+    t.assert_prompt().await;
+
+    t2.test(
+        "immortals cannot approve their own snoops",
+        "snoop approve Shane",
+        vec!["You cannot approve your own snoop."],
+    )
+    .await;
+
+    t.test(
+        "approve the snoop",
+        "snoop approve Shane",
+        vec!["You approve Shane's snoop of Ted."],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("krixi approved your snoop. You are snooping Ted for 10m 0s.")
+        .await;
+    t2.assert_prompt().await;
+
+    t.test(
+        "approvals are used up",
+        "snoop approve Shane",
+        vec!["Shane is not waiting to snoop anyone."],
+    )
+    .await;
+
+    t.test(
+        "administrators do not need approval",
+        "snoop Ted",
+        vec!["You are snooping Ted for 10m 0s."],
+    )
+    .await;
+
+    drop(t3);
+    std::thread::sleep(Duration::from_secs(1));
+
+    t.consume_prompt().await;
+    t.line_contains("Ted leaves.").await;
+    t.line_contains("Your snoop of Ted has ended.").await;
+    t.assert_prompt().await;
+}
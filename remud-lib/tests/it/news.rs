@@ -0,0 +1,123 @@
+use crate::support::Server;
+
+#[tokio::test]
+async fn test_news() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    drop(t2);
+
+    t.consume_prompt().await;
+    t.line_contains("Shane leaves.").await;
+    t.assert_prompt().await;
+
+    t.test("there is no news yet", "news", vec!["There is no news."])
+        .await;
+    t.test("news needs a message", "news post", vec!["Post what?"])
+        .await;
+    t.test(
+        "post news",
+        "news post The docks are open again.",
+        vec!["You post news 1."],
+    )
+    .await;
+    t.test(
+        "post more news",
+        "news post Mail has arrived.",
+        vec!["You post news 2."],
+    )
+    .await;
+    t.test(
+        "news is shown newest first",
+        "news",
+        vec![
+            "News",
+            "2. from krixi",
+            "Mail has arrived.",
+            "1. from krixi",
+            "The docks are open again.",
+        ],
+    )
+    .await;
+    t.test_exclude("authors have read their own news", "news", vec!["(new)"])
+        .await;
+    t.test(
+        "only existing news can be deleted",
+        "news delete 3",
+        vec!["There is no news 3."],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    let mut t2 = server.login_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t2.test(
+        "unread news is announced at login",
+        "recall",
+        vec!["There are 2 unread news entries."],
+    )
+    .await;
+    t2.test(
+        "mortals cannot post news",
+        "news post Free coins!",
+        vec!["Only immortals may post news."],
+    )
+    .await;
+    t2.test(
+        "mortals cannot delete news",
+        "news delete 1",
+        vec!["Only immortals may delete news."],
+    )
+    .await;
+    t2.test(
+        "news is persisted",
+        "news",
+        vec!["2. from krixi", "(new)", "The docks are open again."],
+    )
+    .await;
+    t2.test_exclude("reading news marks it read", "news", vec!["(new)"])
+        .await;
+
+    let web = server.connect_web();
+    let news: serde_json::Value =
+        serde_json::from_str(web.get("/news").await.unwrap().as_str()).unwrap();
+    assert_eq!(news["news"][0]["id"], 2);
+    assert_eq!(news["news"][0]["author"], "krixi");
+    assert_eq!(news["news"][0]["body"], "Mail has arrived.");
+    assert_eq!(news["news"][1]["id"], 1);
+
+    t.test("delete news", "news delete 1", vec!["You delete news 1."])
+        .await;
+    t.test(
+        "online players are told about new posts",
+        "news post Welcome back.",
+        vec!["You post news 3."],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("krixi posted news.").await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "deleted news is gone",
+        "news",
+        vec!["3. from krixi", "Welcome back.", "2. from krixi"],
+    )
+    .await;
+    t2.test_exclude(
+        "deleted news is not shown",
+        "news",
+        vec!["The docks are open again."],
+    )
+    .await;
+}
@@ -175,3 +175,445 @@ async fn test_object_remove() {
     )
     .await;
 }
+
+#[tokio::test]
+async fn test_inventory_counts_identical_objects() {
+    let (_server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.command("create a prototype", "prototype new").await;
+    t.command("create a second prototype", "prototype new")
+        .await;
+    t.command("name it", "prototype 2 name a copper coin").await;
+    t.command("key it", "prototype 2 keywords set copper coin")
+        .await;
+    t.command("spawn an object", "object new 1").await;
+    t.command("spawn a coin", "object new 2").await;
+    t.command("spawn another coin", "object new 2").await;
+    t.command("pick up the object", "get object").await;
+    t.command("pick up a coin", "get coin").await;
+    t.command("pick up another coin", "get coin").await;
+
+    t.test_matches(
+        "identical objects are listed once with a count",
+        "inventory",
+        Matcher::exact_includes(vec![
+            "You have:",
+            "a copper coin  x2",
+            "object",
+            "Carrying weight 0/100, size 0/20.",
+        ]),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_carry_capacity() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.command("create a prototype", "prototype new").await;
+    t.command("name it", "prototype 1 name an anvil").await;
+    t.command("key it", "prototype 1 keywords set anvil").await;
+
+    t.test(
+        "weights must be numbers",
+        "prototype 1 weight heavy",
+        vec!["Enter a weight of zero or more."],
+    )
+    .await;
+
+    t.test(
+        "make it heavy",
+        "prototype 1 weight 60",
+        vec!["Updated prototype 1 weight 60, size 0."],
+    )
+    .await;
+
+    t.test(
+        "make it large",
+        "prototype 1 size 5",
+        vec!["Updated prototype 1 weight 60, size 5."],
+    )
+    .await;
+
+    t.command("spawn an anvil", "object new 1").await;
+    t.command("spawn another anvil", "object new 1").await;
+
+    t.test(
+        "examining shows weight and size",
+        "look at anvil",
+        vec!["It has a weight of 60 and a size of 5."],
+    )
+    .await;
+
+    t.test(
+        "pick up an anvil",
+        "get anvil",
+        vec!["You pick up an anvil."],
+    )
+    .await;
+
+    t.test(
+        "a second anvil is too heavy",
+        "get anvil",
+        vec!["You cannot carry the weight of an anvil along with everything else."],
+    )
+    .await;
+
+    t.test(
+        "inventory shows the load",
+        "inventory",
+        vec!["Carrying weight 60/100, size 5/20."],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "weight and size persist",
+        "prototype 1 info",
+        vec!["weight", "60", "size", "5"],
+    )
+    .await;
+
+    t.command("lighten the anvil", "prototype 1 weight 1").await;
+    t.command("enlarge the anvil", "prototype 1 size 15").await;
+
+    t.test(
+        "a second anvil takes too much room",
+        "get anvil",
+        vec!["You have no room left to carry an anvil."],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_eat_and_drink() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.command("create a prototype", "prototype new").await;
+    t.command("name it", "prototype 1 name a loaf of bread")
+        .await;
+    t.command("key it", "prototype 1 keywords set bread").await;
+
+    t.test(
+        "make it food",
+        "prototype 1 set food",
+        vec!["Updated prototype 1 flags."],
+    )
+    .await;
+
+    t.command("spawn some bread", "object new 1").await;
+
+    t.test(
+        "only carried things can be eaten",
+        "eat bread",
+        vec!["You don't have \"bread\"."],
+    )
+    .await;
+
+    t.command("pick up the bread", "get bread").await;
+
+    t.test(
+        "food cannot be drunk",
+        "drink bread",
+        vec!["You cannot drink a loaf of bread."],
+    )
+    .await;
+
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "eat the bread",
+        "eat bread",
+        vec!["You eat a loaf of bread."],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("krixi eats a loaf of bread.").await;
+    t2.assert_prompt().await;
+
+    t.test("the bread is gone", "inventory", vec!["You have nothing."])
+        .await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "the bread stays gone",
+        "inventory",
+        vec!["You have nothing."],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_get_and_drop_are_seen() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.command("create a prototype", "prototype new").await;
+    t.command("name it", "prototype 1 name a copper coin").await;
+    t.command("key it", "prototype 1 keywords set copper coin")
+        .await;
+    t.command("spawn a coin", "object new 1").await;
+
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t2.command("pick up the coin", "get coin").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane picks up a copper coin.").await;
+    t.assert_prompt().await;
+
+    t2.command("drop the coin", "drop coin").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane drops a copper coin.").await;
+    t.assert_prompt().await;
+}
+
+#[tokio::test]
+async fn test_inventory_saved_on_disconnect() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.command("create a prototype", "prototype new").await;
+    t.command("name it", "prototype 1 name a copper coin").await;
+    t.command("key it", "prototype 1 keywords set copper coin")
+        .await;
+    t.command("spawn a coin", "object new 1").await;
+    t.command("pick up the coin", "get coin").await;
+
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    drop(t);
+
+    t2.consume_prompt().await;
+    t2.line_contains("krixi leaves.").await;
+    t2.assert_prompt().await;
+
+    let mut t = server.login_player("krixi", "(*&%(*#&%*&").await;
+
+    t.test(
+        "the coin was saved when krixi left",
+        "inventory",
+        vec!["You have", "a copper coin"],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_inventory_fits_window() {
+    let (_server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.command("create a prototype", "prototype new").await;
+    t.command(
+        "give it a long name",
+        "prototype 1 name an exceedingly heavy iron anvil",
+    )
+    .await;
+    t.command("spawn an object", "object new 1").await;
+    t.command("pick up the object", "get object").await;
+
+    t.test_matches(
+        "names are shown in full in a wide window",
+        "inventory",
+        Matcher::exact_includes(vec![
+            "You have:",
+            "an exceedingly heavy iron anvil",
+            "Carrying weight",
+        ]),
+    )
+    .await;
+
+    t.resize(20).await;
+
+    t.test_matches(
+        "long names are truncated to fit a narrow window",
+        "inventory",
+        Matcher::exact_includes(vec!["You have:", "an exceedingl...", "Carrying weight"]),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_object_fields() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.command("create a prototype", "prototype new").await;
+    t.test(
+        "set an integer field on the prototype",
+        "prototype 1 field weight 12",
+        vec!["Set prototype 1 field weight to 12."],
+    )
+    .await;
+    t.test(
+        "quoted values are strings",
+        "prototype 1 field price \"30\"",
+        vec!["Set prototype 1 field price to \"30\"."],
+    )
+    .await;
+    t.command("spawn an object", "object new 1").await;
+    t.test(
+        "override a field on the object",
+        "object 1 field weight heavy",
+        vec!["Set object 1 field weight to \"heavy\"."],
+    )
+    .await;
+    t.test(
+        "set a boolean field on the object",
+        "object 1 field cursed true",
+        vec!["Set object 1 field cursed to true."],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    t.test_matches(
+        "fields persist and are inherited from the prototype",
+        "object 1 info",
+        Matcher::unordered(vec![
+            Match::include("cursed = true"),
+            Match::include("price = \"30\" (inherited)"),
+            Match::include("weight = \"heavy\""),
+            Match::exclude("weight = 12"),
+        ]),
+    )
+    .await;
+
+    t.command("pick up the object", "get object").await;
+    t.test(
+        "remove a field from the object",
+        "object 1 field cursed",
+        vec!["Removed object 1 field cursed."],
+    )
+    .await;
+    t.test(
+        "removing a missing field is reported",
+        "object 1 field cursed",
+        vec!["No field cursed on object 1."],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    t.test_matches(
+        "fields of carried objects persist",
+        "object 1 info",
+        Matcher::unordered(vec![
+            Match::include("weight = \"heavy\""),
+            Match::exclude("cursed"),
+        ]),
+    )
+    .await;
+
+    t.command("inherit fields again", "object 1 inherit fields")
+        .await;
+    t.test_matches(
+        "inherited fields come from the prototype",
+        "object 1 info",
+        Matcher::unordered(vec![
+            Match::include("price = \"30\" (inherited)"),
+            Match::include("weight = 12 (inherited)"),
+        ]),
+    )
+    .await;
+}
+
+// test possessing and releasing an object
+#[tokio::test]
+async fn test_object_possess() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    t.command("create a prototype", "prototype new").await;
+    t.command("name it", "prototype 1 name Chonky Cat").await;
+    t.command("key it", "prototype 1 keywords set chonky cat")
+        .await;
+    t.command("spawn an object", "object new 1").await;
+
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "possessing something missing fails",
+        "possess dog",
+        vec![r#"You find nothing called "dog" to possess."#],
+    )
+    .await;
+
+    t.test(
+        "possess the cat",
+        "possess cat",
+        vec!["You possess Chonky Cat."],
+    )
+    .await;
+
+    t.test(
+        "speak as the cat",
+        "say Meow.",
+        vec![r#"Chonky Cat says "Meow.""#],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains(r#"Chonky Cat says "Meow.""#).await;
+    t2.assert_prompt().await;
+
+    t.test(
+        "what the cat looks at is relayed",
+        "look at cat",
+        vec!["[Chonky Cat]"],
+    )
+    .await;
+
+    t.test(
+        "the cat cannot do everything",
+        "inventory",
+        vec!["A possessed object may only speak, emote, move, and look."],
+    )
+    .await;
+
+    t.test(
+        "release the cat",
+        "release",
+        vec!["You release Chonky Cat."],
+    )
+    .await;
+
+    t.test(
+        "speak as yourself again",
+        "say Good cat.",
+        vec![r#"You say "Good cat.""#],
+    )
+    .await;
+
+    t.test(
+        "releasing again fails",
+        "release",
+        vec!["You are not possessing anything."],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains(r#"krixi says "Good cat.""#).await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "mortals cannot possess",
+        "possess cat",
+        vec!["I don't know what that means."],
+    )
+    .await;
+}
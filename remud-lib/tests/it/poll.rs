@@ -0,0 +1,188 @@
+use crate::support::Server;
+
+#[tokio::test]
+async fn test_poll() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "there are no polls yet",
+        "poll",
+        vec!["There are no polls."],
+    )
+    .await;
+    t2.test(
+        "votes need an open poll",
+        "vote yes",
+        vec!["There are no open polls."],
+    )
+    .await;
+    t.test(
+        "polls need two options",
+        "poll create Which feature next?: boats",
+        vec!["A poll needs at least two options."],
+    )
+    .await;
+    t.test(
+        "create a poll",
+        "poll create Which feature next?: boats, pets",
+        vec!["You open poll 1."],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("krixi opened poll 1: Which feature next?")
+        .await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "mortals cannot create polls",
+        "poll create Free coins?: yes, no",
+        vec!["Only immortals may create polls."],
+    )
+    .await;
+    t2.test(
+        "votes must be for an option",
+        "vote dragons",
+        vec!["Poll 1 has no option \"dragons\"."],
+    )
+    .await;
+    t2.test(
+        "vote by option text",
+        "vote pets",
+        vec!["You vote for pets in poll 1."],
+    )
+    .await;
+    t2.test(
+        "only one vote per poll",
+        "vote boats",
+        vec!["You have already voted in poll 1."],
+    )
+    .await;
+    t.test(
+        "vote by poll and option number",
+        "vote 1 1",
+        vec!["You vote for boats in poll 1."],
+    )
+    .await;
+    t.test(
+        "create another poll",
+        "poll create Open the docks?: yes, no",
+        vec!["You open poll 2."],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("krixi opened poll 2: Open the docks?")
+        .await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "the poll must be given when several are open",
+        "vote yes",
+        vec!["Several polls are open."],
+    )
+    .await;
+    t2.test(
+        "vote in a given poll",
+        "vote 2 yes",
+        vec!["You vote for yes in poll 2."],
+    )
+    .await;
+    t2.test(
+        "show a poll",
+        "poll 1",
+        vec![
+            "Poll 1: Which feature next?",
+            "Opened by krixi",
+            "1. boats (1 vote)",
+            "2. pets (1 vote)",
+            "You voted for pets.",
+        ],
+    )
+    .await;
+
+    drop(t2);
+
+    t.consume_prompt().await;
+    t.line_contains("Shane leaves.").await;
+    t.assert_prompt().await;
+
+    t = server.restart(t).await;
+
+    let mut t2 = server.login_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t2.test(
+        "votes are persisted",
+        "poll 2",
+        vec![
+            "Poll 2: Open the docks?",
+            "1. yes (1 vote)",
+            "You voted for yes.",
+        ],
+    )
+    .await;
+    t2.test(
+        "persisted votes still count once",
+        "vote 2 no",
+        vec!["You have already voted in poll 2."],
+    )
+    .await;
+    t2.test(
+        "mortals cannot close polls",
+        "poll close 1",
+        vec!["Only immortals may close polls."],
+    )
+    .await;
+    t.test(
+        "close a poll",
+        "poll close 1",
+        vec!["You close poll 1. Results: boats (1), pets (1)."],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("Poll 1 has closed. Which feature next? Results: boats (1), pets (1).")
+        .await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "closed polls cannot be voted in",
+        "vote 1 boats",
+        vec!["Poll 1 is closed."],
+    )
+    .await;
+    t.test(
+        "polls close once",
+        "poll close 1",
+        vec!["Poll 1 is already closed."],
+    )
+    .await;
+    t.test(
+        "list polls",
+        "poll list",
+        vec![
+            "1. Which feature next? (closed, 2 votes)",
+            "2. Open the docks? (open, 1 vote)",
+        ],
+    )
+    .await;
+
+    let web = server.connect_web();
+    let polls: serde_json::Value =
+        serde_json::from_str(web.get("/polls").await.unwrap().as_str()).unwrap();
+    assert_eq!(polls["polls"][0]["id"], 2);
+    assert_eq!(polls["polls"][0]["closed"], false);
+    assert_eq!(polls["polls"][1]["question"], "Which feature next?");
+    assert_eq!(polls["polls"][1]["closed"], true);
+    assert_eq!(polls["polls"][1]["options"][1]["text"], "pets");
+    assert_eq!(polls["polls"][1]["options"][1]["votes"], 1);
+}
@@ -20,6 +20,7 @@ async fn test_prototype_new() {
             "description: A nondescript object.",
             "flags: (empty)",
             "keywords: object",
+            "fields: none",
             "script hooks: none",
         ]),
     )
@@ -43,6 +44,7 @@ async fn test_prototype_new() {
             "description: A nondescript object.",
             "flags: (empty)",
             "keywords: object",
+            "fields: none",
             "location: room 0",
             "script hooks: none",
             "script data: none",
@@ -63,6 +65,7 @@ async fn test_prototype_new() {
             "description: A nondescript object.",
             "flags: (empty)",
             "keywords: object",
+            "fields: none",
             "script hooks: none",
         ]),
     )
@@ -79,6 +82,7 @@ async fn test_prototype_new() {
             "description: A nondescript object.",
             "flags: (empty)",
             "keywords: object",
+            "fields: none",
             "location: room 0",
             "script hooks: none",
             "script data: none",
@@ -663,3 +667,120 @@ async fn test_prototype_remove() {
     )
     .await;
 }
+
+#[tokio::test]
+async fn test_prototype_behaviors() {
+    let (mut server, mut t) = Server::new_create_player("shane", "password").await;
+
+    t.test(
+        "create a room to the north",
+        "room new north",
+        vec!["Created room 1 to the north."],
+    )
+    .await;
+
+    t.command("create a teleporter", "prototype new").await;
+    t.command("name it", "prototype 1 name a glowing stone")
+        .await;
+    t.command("give it a keyword", "prototype 1 keywords set stone")
+        .await;
+
+    t.test(
+        "teleporting to a missing room is not allowed",
+        "prototype 1 behavior teleport 5",
+        vec!["Room 5 does not exist."],
+    )
+    .await;
+
+    t.test(
+        "make the stone teleport to the new room",
+        "prototype 1 behavior teleport 1",
+        vec!["Set prototype 1 behavior: teleport to room 1."],
+    )
+    .await;
+
+    t.test(
+        "make the stone speak when picked up",
+        "prototype 1 behavior message The stone hums in your hand.",
+        vec!["Set prototype 1 behavior: message \"The stone hums in your hand.\"."],
+    )
+    .await;
+
+    t.command("create a trap", "prototype new").await;
+    t.command("name it", "prototype 2 name a rusty bear trap")
+        .await;
+
+    t.test(
+        "traps must deal damage",
+        "prototype 2 behavior trap none",
+        vec!["Enter the damage the trap deals, such as 10."],
+    )
+    .await;
+
+    t.test(
+        "make it a trap",
+        "prototype 2 behavior trap 5",
+        vec!["Set prototype 2 behavior: trap dealing 5 damage."],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "behaviors persist",
+        "prototype 1 info",
+        vec!["teleport to room 1", "The stone hums in your hand."],
+    )
+    .await;
+
+    t.test("go north", "north", vec!["An empty room"]).await;
+    t.command("set the trap", "object new 2").await;
+    t.command("go back south", "south").await;
+
+    t.test(
+        "walking into the trap springs it",
+        "north",
+        vec!["You set off a rusty bear trap!"],
+    )
+    .await;
+
+    t.command("go back south", "south").await;
+    t.command("create a stone", "object new 1").await;
+
+    t.test(
+        "picking up the stone shows its message",
+        "get stone",
+        vec![
+            "You pick up a glowing stone.",
+            "The stone hums in your hand.",
+        ],
+    )
+    .await;
+
+    t.command("drop the stone", "drop stone").await;
+
+    t.test(
+        "using the stone teleports",
+        "use stone",
+        vec!["You use a glowing stone."],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains("You set off a rusty bear trap!").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "the stone teleported us to the new room",
+        "room info",
+        vec!["Room 1"],
+    )
+    .await;
+
+    t.test(
+        "remove the teleport behavior",
+        "prototype 1 behavior teleport",
+        vec!["Removed prototype 1 teleport behavior."],
+    )
+    .await;
+}
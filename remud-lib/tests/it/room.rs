@@ -1,4 +1,6 @@
-use crate::support::{Server, TelnetPlayer};
+use std::time::Duration;
+
+use crate::support::{JsonScript, JsonScriptName, Server, StatusCode, TelnetPlayer, Trigger};
 
 // Validate a room connection
 async fn assert_there_and_back_again(t: &mut TelnetPlayer, from: (u32, &str), to: (u32, &str)) {
@@ -240,6 +242,129 @@ async fn test_room_link_and_unlink() {
     assert_link_and_unlink(&mut t, "up", "down").await;
 }
 
+#[tokio::test]
+async fn test_room_link_custom_exit() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "password").await;
+
+    t.test("create a new room", "room new", vec!["Created room 1"])
+        .await;
+
+    t.test(
+        "numbers are not allowed in exit names",
+        "room link gate 2 1",
+        vec!["Enter a valid exit: a direction such as north, or a name without numbers such as portal."],
+    )
+    .await;
+
+    t.test(
+        "link a custom exit with a message",
+        "room link Shimmering  Portal 1 You step through the shimmering portal.",
+        vec!["Linked shimmering portal exit to room 1."],
+    )
+    .await;
+
+    t.test(
+        "exits lists the custom exit",
+        "exits",
+        vec!["shimmering portal"],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "take the custom exit",
+        "shimmering portal",
+        vec!["You step through the shimmering portal."],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains("Room").await;
+    t.line_contains("An empty room").await;
+    t.assert_prompt().await;
+
+    t.test("should be in new room", "room info", vec!["Room 1"])
+        .await;
+
+    t.test("teleport back to the void", "teleport 0", vec!["The Void"])
+        .await;
+
+    t.test(
+        "unlink the custom exit",
+        "room unlink shimmering portal",
+        vec!["Removed exit via shimmering portal."],
+    )
+    .await;
+
+    t.test(
+        "the custom exit is gone",
+        "shimmering portal",
+        vec!["I don't know what that means."],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_room_echoes() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "password").await;
+    let mut t2 = server.create_player("Shane", "password").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "create a room to the north",
+        "room new north",
+        vec!["Created room 1 to the north."],
+    )
+    .await;
+
+    t.test(
+        "set the exit's leave message",
+        "room exit north leave $n squeezes through the vent.",
+        vec!["Updated exit north leave message."],
+    )
+    .await;
+
+    t.test(
+        "set the room's arrive message",
+        "room arrive $n tumbles out of the vent, dusting $f off.",
+        vec!["Updated room 0 arrive message."],
+    )
+    .await;
+
+    t.test("take the vent", "north", vec!["An empty room"])
+        .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("krixi squeezes through the vent.").await;
+    t2.assert_prompt().await;
+
+    t.test("come back", "south", vec!["The Void"]).await;
+
+    t2.consume_prompt().await;
+    t2.line_contains("krixi tumbles out of the vent").await;
+    t2.assert_prompt().await;
+
+    t.test(
+        "clear the room's arrive message",
+        "room arrive",
+        vec!["Updated room 0 arrive message."],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "the exit's message persists",
+        "room info",
+        vec!["leave: $n squeezes through the vent.", "default"],
+    )
+    .await;
+}
+
 #[tokio::test]
 async fn test_room_region() {
     let (mut server, mut t) = Server::new_create_player("krixi", "password").await;
@@ -368,6 +493,285 @@ async fn test_room_region() {
     .await;
 }
 
+#[tokio::test]
+async fn test_room_region_lazy_loading() {
+    let mut server = Server::new_with_config("[regions]\nlazy = true\nidle_secs = 0\n").await;
+    let mut t = server.create_player("krixi", "password").await;
+
+    // A road north from the void, each room in a region of its own.
+    for (room, region) in [(1, "one"), (2, "two"), (3, "three"), (4, "four")] {
+        t.test(
+            format!("make room {}", room),
+            "room new north",
+            vec![format!("Created room {}", room)],
+        )
+        .await;
+        t.test("move to it", "north", vec!["An empty room"]).await;
+        t.test(
+            format!("put it in region {}", region),
+            format!("room regions set {}", region),
+            vec![format!("Updated room {} regions.", room)],
+        )
+        .await;
+    }
+
+    t.command("create a prototype", "prototype new").await;
+    t.command("name it", "prototype 1 name Brass Lantern").await;
+    t.test(
+        "leave an object at the end of the road",
+        "object new 1",
+        vec!["Created object 1."],
+    )
+    .await;
+
+    t.test("return to the void", "teleport 0", vec!["The Void"])
+        .await;
+
+    // Give the regions no one is near time to leave the world.
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    t.test(
+        "only the region next to the void is loaded",
+        "region loaded",
+        vec!["Loaded regions: one"],
+    )
+    .await;
+
+    t.test(
+        "teleporting to a room which is not loaded loads it",
+        "teleport 4",
+        vec!["Brass Lantern"],
+    )
+    .await;
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    t.test(
+        "the regions around it are loaded",
+        "region loaded",
+        vec!["Loaded regions: four, one, three"],
+    )
+    .await;
+
+    t.test("its exits are linked", "south", vec!["An empty room"])
+        .await;
+    t.test("and lead back", "room info", vec!["Room 3", "north"])
+        .await;
+    t.test("to the lantern", "north", vec!["Brass Lantern"])
+        .await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "logging in to a room which is not loaded loads it",
+        "room info",
+        vec!["Room 4", "regions: four"],
+    )
+    .await;
+    t.test("along with its objects", "look", vec!["Brass Lantern"])
+        .await;
+}
+
+#[tokio::test]
+async fn test_room_region_builder() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "password").await;
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.command("make Shane a builder", "player Shane set immortal builder")
+        .await;
+    t.command("put the room in the docks", "room regions add docks")
+        .await;
+
+    t.test(
+        "give Shane the docks",
+        "region owner docks Shane",
+        vec!["Shane now owns region docks."],
+    )
+    .await;
+
+    t.test("region owners are listed", "region", vec!["docks: Shane"])
+        .await;
+
+    t.command("dig a room outside the docks", "room new north")
+        .await;
+
+    let web = server.login_web(&t).await;
+    web.create_script(&JsonScript::new("greeter", Trigger::Say, "let x = 1;"))
+        .await
+        .unwrap();
+
+    t2.test(
+        "builders may script rooms in their regions",
+        "script greeter attach-pre room 0",
+        vec!["Script greeter attached to room 0."],
+    )
+    .await;
+
+    t2.test(
+        "builders may not script rooms outside their regions",
+        "script greeter attach-pre room 1",
+        vec!["Builders may only script rooms and objects in regions they own."],
+    )
+    .await;
+
+    t2.test(
+        "builders may not script players",
+        "script greeter attach-pre player krixi",
+        vec!["Builders may only script rooms and objects in regions they own."],
+    )
+    .await;
+
+    t2.test(
+        "builders may not add regions they do not own",
+        "room regions add market",
+        vec!["Builders may only change regions they own on rooms in regions they own."],
+    )
+    .await;
+
+    t2.test(
+        "builders may not give regions away",
+        "region owner docks krixi",
+        vec!["Builders cannot change who owns regions."],
+    )
+    .await;
+
+    t2.test(
+        "builders may not make themselves admins",
+        "player Shane set admin",
+        vec!["Only admins may change player flags."],
+    )
+    .await;
+
+    t2.test(
+        "builders may not stop being builders",
+        "player Shane unset builder",
+        vec!["Only admins may change player flags."],
+    )
+    .await;
+
+    t2.test(
+        "builders may not change admins",
+        "player krixi unset admin",
+        vec!["Only admins may change player flags."],
+    )
+    .await;
+
+    let builder_web = server.login_web(&t2).await;
+    builder_web
+        .update_script(&JsonScript::new("greeter", Trigger::Say, "let x = 2;").with_revision(1))
+        .await
+        .unwrap();
+
+    t.command(
+        "attach the script outside the docks",
+        "script greeter attach-pre room 1",
+    )
+    .await;
+
+    match builder_web
+        .update_script(&JsonScript::new("greeter", Trigger::Say, "let x = 3;").with_revision(2))
+        .await
+    {
+        Err(StatusCode::FORBIDDEN) => (),
+        e => panic!("expected forbidden, got: {:?}", e),
+    }
+
+    match builder_web
+        .delete_script(&JsonScriptName::from("greeter"))
+        .await
+    {
+        Err(StatusCode::FORBIDDEN) => (),
+        e => panic!("expected forbidden, got: {:?}", e),
+    }
+
+    // Scripts attached to nothing may only be changed by the builder who wrote them
+    web.create_script(&JsonScript::new("unused", Trigger::Say, "let x = 1;"))
+        .await
+        .unwrap();
+    match builder_web
+        .update_script(&JsonScript::new("unused", Trigger::Say, "let x = 2;").with_revision(1))
+        .await
+    {
+        Err(StatusCode::FORBIDDEN) => (),
+        e => panic!("expected forbidden, got: {:?}", e),
+    }
+    match builder_web
+        .delete_script(&JsonScriptName::from("unused"))
+        .await
+    {
+        Err(StatusCode::FORBIDDEN) => (),
+        e => panic!("expected forbidden, got: {:?}", e),
+    }
+
+    builder_web
+        .create_script(&JsonScript::new("draft", Trigger::Say, "let x = 1;"))
+        .await
+        .unwrap();
+    builder_web
+        .update_script(&JsonScript::new("draft", Trigger::Say, "let x = 2;").with_revision(1))
+        .await
+        .unwrap();
+
+    // API tokens are limited like the builder who created them
+    let token = builder_web
+        .create_api_token("builder-bot", &["scripts:write"])
+        .await
+        .unwrap();
+    let token_web = server.connect_web().with_api_token(token.as_str());
+    match token_web
+        .update_script(&JsonScript::new("greeter", Trigger::Say, "let x = 3;").with_revision(2))
+        .await
+    {
+        Err(StatusCode::FORBIDDEN) => (),
+        e => panic!("expected forbidden, got: {:?}", e),
+    }
+    match token_web
+        .delete_script(&JsonScriptName::from("greeter"))
+        .await
+    {
+        Err(StatusCode::FORBIDDEN) => (),
+        e => panic!("expected forbidden, got: {:?}", e),
+    }
+
+    t2.test(
+        "builders may detach scripts in their regions",
+        "script greeter detach room 0",
+        vec!["Detached script greeter from room 0."],
+    )
+    .await;
+
+    // Tokens stay limited like their creator, and credit them, after the creator is renamed
+    token_web
+        .create_script(&JsonScript::new("bot-draft", Trigger::Say, "let x = 1;"))
+        .await
+        .unwrap();
+
+    t.test(
+        "rename the builder",
+        "player Shane rename Sam",
+        vec!["Renaming the player."],
+    )
+    .await;
+    t.consume_prompt().await;
+    t.line_contains("Renamed Shane to Sam.").await;
+
+    token_web
+        .update_script(&JsonScript::new("bot-draft", Trigger::Say, "let x = 2;").with_revision(1))
+        .await
+        .unwrap();
+    match token_web
+        .update_script(&JsonScript::new("unused", Trigger::Say, "let x = 2;").with_revision(1))
+        .await
+    {
+        Err(StatusCode::FORBIDDEN) => (),
+        e => panic!("expected forbidden, got: {:?}", e),
+    }
+}
+
 #[tokio::test]
 async fn test_room_remove() {
     let (mut server, mut t) = Server::new_create_player("krixi", "password").await;
@@ -450,3 +854,227 @@ async fn test_room_remove() {
     )
     .await;
 }
+
+#[tokio::test]
+async fn test_room_stage_publish() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "password").await;
+    let mut t2 = server.create_player("Shane", "password").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "add the void room to a region",
+        "room regions add market",
+        vec!["Updated room 0 regions."],
+    )
+    .await;
+
+    t.test(
+        "stage the region",
+        "stage market",
+        vec!["Staged 1 rooms in region market."],
+    )
+    .await;
+
+    t.test(
+        "staging a second region is not allowed",
+        "stage docks",
+        vec!["You are already staging region market."],
+    )
+    .await;
+
+    t.test(
+        "rename the staged room",
+        "room name Market Square",
+        vec!["Updated staged current room name."],
+    )
+    .await;
+
+    t.test(
+        "link a staged exit",
+        "room link north 0",
+        vec!["Linked staged north exit to room 0."],
+    )
+    .await;
+
+    t.test(
+        "the builder sees the staged room",
+        "look",
+        vec!["(staged)", "Market Square"],
+    )
+    .await;
+
+    t.test("the builder sees staged exits", "exits", vec!["north"])
+        .await;
+
+    t2.test_exclude(
+        "other players do not see the staged room",
+        "look",
+        vec!["Market Square"],
+    )
+    .await;
+
+    t2.test(
+        "other players do not see staged exits",
+        "exits",
+        vec!["This room has no obvious exits."],
+    )
+    .await;
+
+    t.test(
+        "publish the staged region",
+        "publish",
+        vec!["Published 1 staged rooms in region market."],
+    )
+    .await;
+
+    t2.test(
+        "other players see the published room",
+        "look",
+        vec!["Market Square"],
+    )
+    .await;
+
+    t2.test(
+        "other players see published exits",
+        "exits",
+        vec!["There is an exit north."],
+    )
+    .await;
+
+    t.test(
+        "nothing is left to publish",
+        "publish",
+        vec!["You are not staging a region."],
+    )
+    .await;
+
+    drop(t2);
+    t = server.restart(t).await;
+
+    t.test(
+        "published changes are persisted",
+        "room info",
+        vec!["Market Square", "north: Market Square (room 0)"],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_room_stage_publish_conflicts() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "password").await;
+    let mut t2 = server.create_player("Shane", "password").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.command("make Shane an immortal", "player Shane set immortal")
+        .await;
+    t.command("add the void room to a region", "room regions add market")
+        .await;
+    t.command("stage the region", "stage market").await;
+    t.command("rename the staged room", "room name Market Square")
+        .await;
+
+    t2.test(
+        "another immortal changes the live description",
+        "room desc A quiet plaza.",
+        vec!["Updated current room description."],
+    )
+    .await;
+
+    t.test(
+        "changes to other parts of the live room do not conflict",
+        "publish",
+        vec!["Published 1 staged rooms in region market."],
+    )
+    .await;
+
+    t2.test(
+        "the live change is kept",
+        "look",
+        vec!["Market Square", "A quiet plaza."],
+    )
+    .await;
+
+    t.command("stage the region again", "stage market").await;
+    t.command("rename the staged room", "room name Bazaar")
+        .await;
+
+    t2.test(
+        "another immortal renames the live room",
+        "room name Old Square",
+        vec!["Updated current room name."],
+    )
+    .await;
+
+    t.test(
+        "conflicting changes are not published",
+        "publish",
+        vec!["Changes were made to the live room 0 (name) since you staged it."],
+    )
+    .await;
+
+    t2.test("the live room is unchanged", "look", vec!["Old Square"])
+        .await;
+
+    t.test(
+        "publishing can be forced",
+        "publish force",
+        vec!["Published 1 staged rooms in region market."],
+    )
+    .await;
+
+    t2.test("the staged change wins", "look", vec!["Bazaar"])
+        .await;
+}
+
+#[tokio::test]
+async fn test_room_stage_discard() {
+    let (_server, mut t) = Server::new_create_player("krixi", "password").await;
+
+    t.test(
+        "staging an empty region fails",
+        "stage market",
+        vec!["There are no rooms in region market."],
+    )
+    .await;
+
+    t.test(
+        "add the void room to a region",
+        "room regions add market",
+        vec!["Updated room 0 regions."],
+    )
+    .await;
+
+    t.test(
+        "stage the region",
+        "stage market",
+        vec!["Staged 1 rooms in region market."],
+    )
+    .await;
+
+    t.test(
+        "update the staged description",
+        "room desc A bustling market.",
+        vec!["Updated staged current room description."],
+    )
+    .await;
+
+    t.test(
+        "discard the staged changes",
+        "stage discard",
+        vec!["Discarded staged changes to region market."],
+    )
+    .await;
+
+    t.test_exclude(
+        "the staged description was discarded",
+        "look",
+        vec!["A bustling market."],
+    )
+    .await;
+}
@@ -0,0 +1,61 @@
+use crate::support::{Hook, JsonScript, Server, Trigger};
+
+#[tokio::test]
+async fn test_room_description_fragments() {
+    const SCRIPT: &str = "lamplighter";
+
+    let (server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let web = server.login_web(&t).await;
+
+    t.command("create a room to the north", "room new north")
+        .await;
+    t.command("go north", "north").await;
+    t.command("add the room to a region", "room regions set docks")
+        .await;
+    t.command(
+        "describe the room with fragments",
+        "room desc A narrow pier. {weather rain: Rain lashes the planks.} {flag lit: A lantern \
+         burns.} {!flag lit: The lantern is dark.} A sign reads {closed}.",
+    )
+    .await;
+
+    t.test_exclude(
+        "fragments whose conditions do not hold are left out",
+        "look",
+        vec!["Rain lashes", "A lantern burns", "{weather"],
+    )
+    .await;
+
+    t.test(
+        "negated fragments and literal braces are shown",
+        "look",
+        vec!["A narrow pier. The lantern is dark. A sign reads {closed}."],
+    )
+    .await;
+
+    web.attach_to_room(
+        &mut t,
+        &JsonScript::new(
+            SCRIPT,
+            Trigger::Say,
+            r#"SELF.set("lit", true);
+               WORLD.set_weather("docks", "rain");
+               SELF.say(`weather is ${WORLD.weather("docks")}`);"#,
+        ),
+        Hook::Post,
+        1,
+    )
+    .await;
+
+    t.command("light the lantern", "say light").await;
+    t.consume_prompt().await;
+    t.line_contains("weather is rain").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "weather and flag fragments follow scripts",
+        "look",
+        vec!["A narrow pier. Rain lashes the planks. A lantern burns. A sign reads {closed}."],
+    )
+    .await;
+}
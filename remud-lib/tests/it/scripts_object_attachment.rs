@@ -9,15 +9,12 @@ async fn test_script_object_attachment_attach_init() {
     let web = server.login_web(&t).await;
 
     const SCRIPT: &'static str = "the_script";
-    let error = web
-        .create_script(&JsonScript::new(
-            SCRIPT,
-            Trigger::Init,
-            r#"SELF.say("hello");"#,
-        ))
-        .await
-        .unwrap();
-    assert!(error.is_none());
+    web.create_script_expect_ok(&JsonScript::new(
+        SCRIPT,
+        Trigger::Init,
+        r#"SELF.say("hello");"#,
+    ))
+    .await;
 
     t.test(
         "create prototype",
@@ -118,15 +115,12 @@ async fn test_script_object_attachment_attach_pre() {
     let web = server.login_web(&t).await;
 
     const SCRIPT: &'static str = "the_script";
-    let error = web
-        .create_script(&JsonScript::new(
-            SCRIPT,
-            Trigger::Say,
-            r#"if WORLD.is_player(EVENT.actor) { SELF.say("hello"); }"#,
-        ))
-        .await
-        .unwrap();
-    assert!(error.is_none());
+    web.create_script_expect_ok(&JsonScript::new(
+        SCRIPT,
+        Trigger::Say,
+        r#"if WORLD.is_player(EVENT.actor) { SELF.say("hello"); }"#,
+    ))
+    .await;
 
     t.test(
         "create prototype",
@@ -236,15 +230,12 @@ async fn test_script_object_attachment_attach_pre_disallow_action() {
     let web = server.login_web(&t).await;
 
     const SCRIPT: &'static str = "the_script";
-    let error = web
-        .create_script(&JsonScript::new(
-            SCRIPT,
-            Trigger::Say,
-            r#"if WORLD.is_player(EVENT.actor) { allow_action = false; SELF.say("shh..."); }"#,
-        ))
-        .await
-        .unwrap();
-    assert!(error.is_none());
+    web.create_script_expect_ok(&JsonScript::new(
+        SCRIPT,
+        Trigger::Say,
+        r#"if WORLD.is_player(EVENT.actor) { allow_action = false; SELF.say("shh..."); }"#,
+    ))
+    .await;
 
     t.test(
         "create prototype",
@@ -325,15 +316,12 @@ async fn test_script_object_attachment_attach_post() {
     let web = server.login_web(&t).await;
 
     const SCRIPT: &'static str = "the_script";
-    let error = web
-        .create_script(&JsonScript::new(
-            SCRIPT,
-            Trigger::Say,
-            r#"if WORLD.is_player(EVENT.actor) { SELF.say("hello"); }"#,
-        ))
-        .await
-        .unwrap();
-    assert!(error.is_none());
+    web.create_script_expect_ok(&JsonScript::new(
+        SCRIPT,
+        Trigger::Say,
+        r#"if WORLD.is_player(EVENT.actor) { SELF.say("hello"); }"#,
+    ))
+    .await;
 
     t.test(
         "create prototype",
@@ -436,29 +424,23 @@ async fn test_script_object_attachment_attach_timer() {
     const SAY_SCRIPT: &'static str = "say_script";
     const TIMER_SCRIPT: &'static str = "timer_script";
 
-    let error = web
-        .create_script(&JsonScript::new(
-            SAY_SCRIPT,
-            Trigger::Say,
-            format!(
-                r#"if WORLD.is_player(EVENT.actor) {{ SELF.timer("{}", ms(100)); }}"#,
-                TIMER_NAME
-            )
-            .as_str(),
-        ))
-        .await
-        .unwrap();
-    assert!(error.is_none());
-
-    let error = web
-        .create_script(&JsonScript::new(
-            TIMER_SCRIPT,
-            Trigger::Timer,
-            r#"SELF.say("What's all this?");"#,
-        ))
-        .await
-        .unwrap();
-    assert!(error.is_none());
+    web.create_script_expect_ok(&JsonScript::new(
+        SAY_SCRIPT,
+        Trigger::Say,
+        format!(
+            r#"if WORLD.is_player(EVENT.actor) {{ SELF.timer("{}", ms(100)); }}"#,
+            TIMER_NAME
+        )
+        .as_str(),
+    ))
+    .await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+        TIMER_SCRIPT,
+        Trigger::Timer,
+        r#"SELF.say("What's all this?");"#,
+    ))
+    .await;
 
     t.test(
         "create prototype",
@@ -593,6 +575,319 @@ async fn test_script_object_attachment_attach_timer() {
     .await;
 }
 
+#[tokio::test]
+async fn test_script_object_attachment_attach_custom() {
+    let (mut server, mut t) = Server::new_create_player("Shane", "p@55w0rd").await;
+    let web = server.login_web(&t).await;
+
+    const SIGNAL: &'static str = "alarm";
+    const BELL_SCRIPT: &'static str = "bell_script";
+    const GUARD_SCRIPT: &'static str = "guard_script";
+
+    web.create_script_expect_ok(&JsonScript::new(
+        BELL_SCRIPT,
+        Trigger::Say,
+        format!(
+            r#"if WORLD.is_player(EVENT.actor) {{ WORLD.emit("{}", EVENT.text); }}"#,
+            SIGNAL
+        )
+        .as_str(),
+    ))
+    .await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+        GUARD_SCRIPT,
+        Trigger::Custom,
+        format!(r#"SELF.say(`Heard: ${{SELF.get("{}")}}`);"#, SIGNAL).as_str(),
+    ))
+    .await;
+
+    t.command("create bell prototype", "prototype new").await;
+    t.command("name the bell", "prototype 1 name a bell").await;
+    t.command("create guard prototype", "prototype new").await;
+    t.command("name the guard", "prototype 2 name a guard")
+        .await;
+
+    t.test(
+        "attach bell script to prototype",
+        format!("scripts {} attach-post prototype 1", BELL_SCRIPT),
+        vec![format!("Script {} attached to prototype 1.", BELL_SCRIPT)],
+    )
+    .await;
+
+    t.test(
+        "attach custom script to prototype",
+        format!(
+            "scripts {} attach-custom {} prototype 2",
+            GUARD_SCRIPT, SIGNAL
+        ),
+        vec![format!("Script {} attached to prototype 2.", GUARD_SCRIPT)],
+    )
+    .await;
+
+    t.test(
+        "a signal is required",
+        format!("scripts {} attach-custom", GUARD_SCRIPT),
+        vec!["Enter a signal name."],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "ensure script is attached to prototype",
+        "prototype 2 info",
+        vec![
+            "Prototype 2",
+            format!(r#"Custom("{}") -> {}"#, SIGNAL, GUARD_SCRIPT).as_str(),
+        ],
+    )
+    .await;
+
+    t.command("create bell", "object new 1").await;
+    t.command("create guard", "object new 2").await;
+
+    t.test("ring the bell", "say fire", vec![r#"You say "fire""#])
+        .await;
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    t.consume_prompt().await;
+    t.line_contains(r#"a guard says "Heard: fire""#).await;
+    t.assert_prompt().await;
+
+    t.test(
+        "custom scripts have no event",
+        "script api custom",
+        vec!["Custom scripts are not given an EVENT."],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_script_object_attachment_priority() {
+    let (mut server, mut t) = Server::new_create_player("Shane", "p@55w0rd").await;
+    let web = server.login_web(&t).await;
+
+    for (name, trigger, code) in [
+        (
+            "guard",
+            Trigger::Say,
+            r#"if WORLD.is_player(EVENT.actor) && EVENT.text == "open" { allow_action = false; SELF.say("No."); }"#,
+        ),
+        (
+            "greeter",
+            Trigger::Say,
+            r#"if WORLD.is_player(EVENT.actor) { SELF.say("Welcome."); }"#,
+        ),
+        (
+            "first",
+            Trigger::Emote,
+            r#"if WORLD.is_player(EVENT.actor) { SELF.say("first"); }"#,
+        ),
+        (
+            "second",
+            Trigger::Emote,
+            r#"if WORLD.is_player(EVENT.actor) { SELF.say("second"); }"#,
+        ),
+    ] {
+        web.create_script_expect_ok(&JsonScript::new(name, trigger, code))
+            .await;
+    }
+
+    t.command("create prototype", "prototype new").await;
+    t.command("name prototype", "prototype 1 name a doorman")
+        .await;
+
+    t.test(
+        "attach greeter",
+        "scripts greeter attach-pre prototype 1",
+        vec!["Script greeter attached to prototype 1."],
+    )
+    .await;
+    t.test(
+        "attach guard with a higher priority",
+        "scripts guard attach-pre prototype 1 priority:10",
+        vec!["Script guard attached to prototype 1."],
+    )
+    .await;
+    t.test(
+        "attach second emote script",
+        "scripts second attach-post prototype 1 priority:-1",
+        vec!["Script second attached to prototype 1."],
+    )
+    .await;
+    t.test(
+        "attach first emote script",
+        "scripts first attach-post prototype 1",
+        vec!["Script first attached to prototype 1."],
+    )
+    .await;
+    t.test(
+        "priorities must be numbers",
+        "scripts first attach-post prototype 1 priority:high",
+        vec!["Script priorities must be whole numbers."],
+    )
+    .await;
+    t.test(
+        "priorities are only given when attaching",
+        "scripts first detach prototype 1 priority:1",
+        vec!["Priorities are only given when attaching scripts."],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "priorities are shown",
+        "prototype 1 info",
+        vec![
+            "PreEvent(Say) -> greeter",
+            "PreEvent(Say) -> guard (priority 10)",
+            "PostEvent(Emote) -> second (priority -1)",
+        ],
+    )
+    .await;
+
+    t.command("create object", "object new 1").await;
+
+    // the guard runs first and denies the action, so the greeter does not run
+    t.send("say open").await;
+    t.line_contains(r#"a doorman says "No.""#).await;
+    t.assert_prompt().await;
+
+    t.test("speak freely", "say hello", vec![r#"You say "hello""#])
+        .await;
+
+    t.consume_prompt().await;
+    t.line_contains(r#"a doorman says "Welcome.""#).await;
+    t.assert_prompt().await;
+
+    t.test("wave", "me waves", vec!["Shane waves"]).await;
+
+    t.consume_prompt().await;
+    t.line_contains(r#"a doorman says "first""#).await;
+    t.line_contains(r#"a doorman says "second""#).await;
+    t.assert_prompt().await;
+}
+
+#[tokio::test]
+async fn test_script_object_attachment_enable_disable() {
+    let (mut server, mut t) = Server::new_create_player("Shane", "p@55w0rd").await;
+    let web = server.login_web(&t).await;
+
+    const SCRIPT: &'static str = "guard";
+    web.create_script_expect_ok(&JsonScript::new(
+            SCRIPT,
+            Trigger::Say,
+            r#"if WORLD.is_player(EVENT.actor) && EVENT.text == "open" { allow_action = false; SELF.say("No."); }"#,
+        ))
+        .await;
+
+    t.command("create prototype", "prototype new").await;
+    t.command("name prototype", "prototype 1 name a doorman")
+        .await;
+    t.command(
+        "attach guard",
+        format!("scripts {} attach-pre prototype 1", SCRIPT),
+    )
+    .await;
+    t.command("create object", "object new 1").await;
+
+    t.send("say open").await;
+    t.line_contains(r#"a doorman says "No.""#).await;
+    t.assert_prompt().await;
+
+    t.test(
+        "disable script",
+        format!("scripts disable {}", SCRIPT),
+        vec![format!("Script {} disabled.", SCRIPT)],
+    )
+    .await;
+
+    t.test(
+        "disable script again",
+        format!("scripts disable {}", SCRIPT),
+        vec![format!("Script {} is already disabled.", SCRIPT)],
+    )
+    .await;
+
+    t.test(
+        "script is listed as disabled",
+        "scripts list",
+        vec!["disabled"],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "disabled script does not run",
+        "say open",
+        vec![r#"You say "open""#],
+    )
+    .await;
+
+    t.test(
+        "enable script",
+        format!("scripts enable {}", SCRIPT),
+        vec![format!("Script {} enabled.", SCRIPT)],
+    )
+    .await;
+
+    t.send("say open").await;
+    t.line_contains(r#"a doorman says "No.""#).await;
+    t.assert_prompt().await;
+
+    t.test(
+        "disable script on object",
+        format!("scripts disable {} object 1", SCRIPT),
+        vec![format!("Disabled script {} on object 1.", SCRIPT)],
+    )
+    .await;
+
+    t.test(
+        "disable script on missing hook",
+        format!("scripts disable {} room 0", SCRIPT),
+        vec![format!("Script {} not found on target.", SCRIPT)],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "hook is shown as disabled",
+        "object 1 info",
+        vec![format!("PreEvent(Say) -> {} (disabled)", SCRIPT).as_str()],
+    )
+    .await;
+
+    t.test_exclude(
+        "prototype hook is still enabled",
+        "prototype 1 info",
+        vec!["(disabled)"],
+    )
+    .await;
+
+    t.test(
+        "disabled hook does not run",
+        "say open",
+        vec![r#"You say "open""#],
+    )
+    .await;
+
+    t.test(
+        "enable script on object",
+        format!("scripts enable {} object 1", SCRIPT),
+        vec![format!("Enabled script {} on object 1.", SCRIPT)],
+    )
+    .await;
+
+    t.send("say open").await;
+    t.line_contains(r#"a doorman says "No.""#).await;
+    t.assert_prompt().await;
+}
+
 // removal from prototype reflected in object is tested above
 #[tokio::test]
 async fn test_script_object_attachment_inherit_scripts() {
@@ -601,24 +896,18 @@ async fn test_script_object_attachment_inherit_scripts() {
 
     const SCRIPT: &'static str = "the_script";
     const OTHER_SCRIPT: &'static str = "the_other_script";
-    let error = web
-        .create_script(&JsonScript::new(
-            SCRIPT,
-            Trigger::Say,
-            r#"if WORLD.is_player(EVENT.actor) { SELF.say("hello"); }"#,
-        ))
-        .await
-        .unwrap();
-    assert!(error.is_none());
-    let error = web
-        .create_script(&JsonScript::new(
-            OTHER_SCRIPT,
-            Trigger::Say,
-            r#"if WORLD.is_player(EVENT.actor) { SELF.say("hello there"); }"#,
-        ))
-        .await
-        .unwrap();
-    assert!(error.is_none());
+    web.create_script_expect_ok(&JsonScript::new(
+        SCRIPT,
+        Trigger::Say,
+        r#"if WORLD.is_player(EVENT.actor) { SELF.say("hello"); }"#,
+    ))
+    .await;
+    web.create_script_expect_ok(&JsonScript::new(
+        OTHER_SCRIPT,
+        Trigger::Say,
+        r#"if WORLD.is_player(EVENT.actor) { SELF.say("hello there"); }"#,
+    ))
+    .await;
 
     t.test(
         "create prototype",
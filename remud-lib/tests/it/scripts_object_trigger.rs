@@ -7,18 +7,15 @@ async fn configure_test_object(
     message: &str,
 ) {
     const SCRIPT: &'static str = "say_script";
-    let error = web
-        .create_script(&JsonScript::new(
-            SCRIPT,
-            trigger,
-            format!(
-                r#"if WORLD.is_player(EVENT.actor) {{ SELF.say("{}");}}"#,
-                message
-            ),
-        ))
-        .await
-        .unwrap();
-    assert!(error.is_none());
+    web.create_script_expect_ok(&JsonScript::new(
+        SCRIPT,
+        trigger,
+        format!(
+            r#"if WORLD.is_player(EVENT.actor) {{ SELF.say("{}");}}"#,
+            message
+        ),
+    ))
+    .await;
 
     t.command("create prototype", "prototype new").await;
     t.command("prototype name", "prototype 1 name talking rock")
@@ -223,3 +220,135 @@ async fn test_script_object_trigger_send() {
         .await;
     t.assert_prompt().await;
 }
+
+#[tokio::test]
+async fn test_script_object_trigger_params() {
+    let (server, mut t) = Server::new_create_player("Shane", "p@55w0rd").await;
+    let web = server.login_web(&t).await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+        "say_params",
+        Trigger::Say,
+        r#"if WORLD.is_player(EVENT.speaker) { SELF.say(`${EVENT.kind}: ${EVENT["text"]}`); }"#,
+    ))
+    .await;
+    web.create_script_expect_ok(&JsonScript::new(
+        "get_params",
+        Trigger::Get,
+        r#"if WORLD.is_player(EVENT.actor) {
+    SELF.say(`${WORLD.name(EVENT.object)} by ${EVENT.params.keywords.len()} keyword`);
+}"#,
+    ))
+    .await;
+
+    t.command("create prototype", "prototype new").await;
+    t.command("prototype name", "prototype 1 name talking rock")
+        .await;
+    t.command(
+        "attach say script",
+        "script say_params attach-post prototype 1",
+    )
+    .await;
+    t.command(
+        "attach get script",
+        "script get_params attach-pre prototype 1",
+    )
+    .await;
+    t.command("create object", "object new 1").await;
+    t.command("create coin prototype", "prototype new").await;
+    t.command("name the coin", "prototype 2 name a copper coin")
+        .await;
+    t.command("key the coin", "prototype 2 keywords set copper coin")
+        .await;
+    t.command("create coin", "object new 2").await;
+
+    t.command("say something", "say hello rock").await;
+
+    t.consume_prompt().await;
+    t.line_contains(r#"talking rock says "Say: hello rock""#)
+        .await;
+    t.assert_prompt().await;
+
+    t.command("pick up the coin", "get coin").await;
+
+    t.consume_prompt().await;
+    t.line_contains(r#"talking rock says "a copper coin by 1 keyword""#)
+        .await;
+    t.assert_prompt().await;
+
+    t.test(
+        "describe the event api",
+        "script api",
+        vec!["Script EVENT", "actor", "Drink, Drop, Eat, Emote, Exits"],
+    )
+    .await;
+    t.test(
+        "describe move events",
+        "script api move",
+        vec!["Move EVENT", "from", "the room being left"],
+    )
+    .await;
+    t.test(
+        "init scripts have no event",
+        "script api init",
+        vec!["Init scripts are not given an EVENT."],
+    )
+    .await;
+    t.test(
+        "events are validated",
+        "script api dance",
+        vec!["There is no event called dance."],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_script_object_trigger_sell() {
+    let (server, mut t) = Server::new_create_player("Shane", "p@55w0rd").await;
+    let web = server.login_web(&t).await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+        "fair_prices",
+        Trigger::Sell,
+        r#"if EVENT.price > 100 {
+    allow_action = false;
+    SELF.say(`Nobody will pay ${EVENT.price} for ${WORLD.name(EVENT.object)}.`);
+}"#,
+    ))
+    .await;
+
+    t.command("create prototype", "prototype new").await;
+    t.command("prototype name", "prototype 1 name a shrewd merchant")
+        .await;
+    t.command(
+        "attach sell script",
+        "script fair_prices attach-pre prototype 1",
+    )
+    .await;
+    t.command("create object", "object new 1").await;
+    t.command("create sword prototype", "prototype new").await;
+    t.command("name the sword", "prototype 2 name a rusty sword")
+        .await;
+    t.command("key the sword", "prototype 2 keywords set rusty sword")
+        .await;
+    t.command("create sword", "object new 2").await;
+    t.command("pick up the sword", "get sword").await;
+
+    t.send("market sell 500 sword").await;
+    t.line_contains(r#"a shrewd merchant says "Nobody will pay 500 for a rusty sword.""#)
+        .await;
+    t.assert_prompt().await;
+
+    t.test(
+        "fair prices are allowed",
+        "market sell 50 sword",
+        vec!["You list a rusty sword for 50 coins as listing 1."],
+    )
+    .await;
+    t.test(
+        "describe sell events",
+        "script api sell",
+        vec!["Sell EVENT", "price", "the asking price in coins"],
+    )
+    .await;
+}
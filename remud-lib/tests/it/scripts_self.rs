@@ -1,5 +1,7 @@
 use std::time::Duration;
 
+use warp::Filter;
+
 use crate::support::{AuthenticatedWebClient, JsonScript, Server, TelnetPlayer, Trigger};
 
 async fn configure_test_object(
@@ -9,11 +11,8 @@ async fn configure_test_object(
     script_name: &str,
     code: &str,
 ) {
-    let error = web
-        .create_script(&JsonScript::new(script_name, trigger, code))
-        .await
-        .unwrap();
-    assert!(error.is_none(), "{:?}", error.unwrap());
+    web.create_script_expect_ok(&JsonScript::new(script_name, trigger, code))
+        .await;
 
     t.command("create prototype", "prototype new").await;
     t.command("prototype name", "prototype 1 name widget").await;
@@ -210,3 +209,199 @@ async fn test_self_set_keywords() {
     )
     .await;
 }
+
+#[tokio::test]
+async fn test_self_fields() {
+    let (server, mut t) = Server::new_create_player("krixi", "let me in").await;
+    let web = server.login_web(&t).await;
+
+    configure_test_object(
+        &web,
+        &mut t,
+        Trigger::Use,
+        "test_self_script",
+        r#"let charges = WORLD.field(SELF.entity, "charges");
+SELF.set_field(SELF.entity, "charges", charges - 1);
+SELF.whisper(EVENT.actor, "charges: " + charges);"#,
+    )
+    .await;
+
+    t.command("give the prototype charges", "prototype 1 field charges 3")
+        .await;
+
+    t.test("use the widget", "use widget", vec!["You use widget."])
+        .await;
+    t.consume_prompt().await;
+    t.line_contains("charges: 3").await;
+    t.assert_prompt().await;
+
+    t.test("use it again", "use widget", vec!["You use widget."])
+        .await;
+    t.consume_prompt().await;
+    t.line_contains("charges: 2").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "the object has its own field while the prototype is unchanged",
+        "object 1 info",
+        vec!["charges = 1"],
+    )
+    .await;
+    t.test(
+        "the prototype keeps its value",
+        "prototype 1 info",
+        vec!["charges = 3"],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_self_http_get() {
+    let route = warp::path("weather").map(|| "raining");
+    let (address, serve) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(serve);
+
+    let mut server =
+        Server::new_with_config("[scripting]\nhttp_allowlist = [\"127.0.0.1\"]\n").await;
+    let mut t = server.create_player("krixi", "let me in").await;
+    let web = server.login_web(&t).await;
+
+    let scripts = [
+        (
+            "fetch_script",
+            Trigger::Use,
+            format!(
+                r#"if type_of(SELF.get("fetched")) == "()" {{
+  SELF.http_get("http://{}/weather", "fetched");
+}} else {{
+  SELF.http_get("http://localhost:{}/weather", "refused");
+}}"#,
+                address,
+                address.port()
+            ),
+        ),
+        (
+            "fetched_script",
+            Trigger::Timer,
+            r#"let response = SELF.get("fetched");
+SELF.say(`${response.status}: ${response.body}`);"#
+                .to_string(),
+        ),
+        (
+            "refused_script",
+            Trigger::Timer,
+            r#"SELF.say(SELF.get("refused").error);"#.to_string(),
+        ),
+    ];
+
+    for (name, trigger, code) in scripts {
+        web.create_script_expect_ok(&JsonScript::new(name, trigger, code))
+            .await;
+    }
+
+    t.command("create prototype", "prototype new").await;
+    t.command("prototype name", "prototype 1 name widget").await;
+    t.command("prototype keywords", "prototype 1 keywords set widget")
+        .await;
+    t.command(
+        "attach fetch script",
+        "script fetch_script attach-pre prototype 1",
+    )
+    .await;
+    t.command(
+        "attach fetched script",
+        "script fetched_script attach-timer fetched prototype 1",
+    )
+    .await;
+    t.command(
+        "attach refused script",
+        "script refused_script attach-timer refused prototype 1",
+    )
+    .await;
+    t.command("create the widget", "object new 1").await;
+
+    t.test("use the widget", "use widget", vec!["You use widget."])
+        .await;
+    t.consume_prompt().await;
+    t.line_contains(r#"widget says "200: raining""#).await;
+    t.assert_prompt().await;
+
+    t.test("use it again", "use widget", vec!["You use widget."])
+        .await;
+    t.consume_prompt().await;
+    t.line_contains(r#"widget says "host not allowed""#).await;
+    t.assert_prompt().await;
+}
+
+#[tokio::test]
+async fn test_self_wait() {
+    let (server, mut t) = Server::new_create_player("krixi", "let me in").await;
+    let web = server.login_web(&t).await;
+
+    configure_test_object(
+        &web,
+        &mut t,
+        Trigger::Use,
+        "test_self_script",
+        r#"fn second() {
+  this.say("second");
+  this.wait(ms(100), "third");
+}
+
+fn third() {
+  let name = this.world.name(this.entity);
+  this.say(`third from ${name}`);
+}
+
+SELF.say("first");
+SELF.wait(ms(100), "second");"#,
+    )
+    .await;
+
+    t.test("use the widget", "use widget", vec!["You use widget."])
+        .await;
+    t.consume_prompt().await;
+    t.line_contains(r#"widget says "first""#).await;
+    t.consume_prompt().await;
+    t.line_contains(r#"widget says "second""#).await;
+    t.consume_prompt().await;
+    t.line_contains(r#"widget says "third from widget""#).await;
+    t.assert_prompt().await;
+}
+
+#[tokio::test]
+async fn test_self_wait_follows_server_clock() {
+    let mut server = Server::new_with_manual_clock().await;
+    let mut t = server.create_player("krixi", "let me in").await;
+    let web = server.login_web(&t).await;
+
+    configure_test_object(
+        &web,
+        &mut t,
+        Trigger::Use,
+        "test_self_script",
+        r#"fn later() {
+  this.say("an hour later");
+}
+
+SELF.say("now");
+SELF.wait(secs(3600), "later");"#,
+    )
+    .await;
+
+    t.test("use the widget", "use widget", vec!["You use widget."])
+        .await;
+    t.consume_prompt().await;
+    t.line_contains(r#"widget says "now""#).await;
+    t.assert_prompt().await;
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    t.test_exclude("clock has not moved", "look", vec!["an hour later"])
+        .await;
+
+    server.advance(Duration::from_secs(3600));
+
+    t.consume_prompt().await;
+    t.line_contains(r#"widget says "an hour later""#).await;
+    t.assert_prompt().await;
+}
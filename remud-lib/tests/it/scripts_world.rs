@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use crate::support::{AuthenticatedWebClient, JsonScript, Server, TelnetPlayer, Trigger};
 
 async fn configure_test_object(
@@ -6,20 +8,17 @@ async fn configure_test_object(
     trigger: Trigger,
 ) {
     const SCRIPT: &'static str = "say_script";
-    let error = web
-        .create_script(&JsonScript::new(
-            SCRIPT,
-            trigger,
-            // testing WORLD.name() works correctly
-            format!(
-                r#"let name = WORLD.name(EVENT.actor); 
+    web.create_script_expect_ok(&JsonScript::new(
+        SCRIPT,
+        trigger,
+        // testing WORLD.name() works correctly
+        format!(
+            r#"let name = WORLD.name(EVENT.actor); 
                    SELF.say(`i see you ${{name}}`);
                "#
-            ),
-        ))
-        .await
-        .unwrap();
-    assert!(error.is_none());
+        ),
+    ))
+    .await;
 
     t.command("create prototype", "prototype new").await;
     t.command("prototype name", "prototype 1 name talking rock")
@@ -55,3 +54,158 @@ async fn test_world_get_entity_name() {
         .await;
     t.assert_prompt().await;
 }
+
+#[tokio::test]
+async fn test_world_entity_ids_survive_login() {
+    const PLAYER_NAME: &'static str = "krixi";
+    const PASSWORD: &'static str = "let me in";
+    let (mut server, mut t) = Server::new_create_player(PLAYER_NAME, PASSWORD).await;
+    let web = server.login_web(&t).await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+            "remember",
+            Trigger::Say,
+            r#"if WORLD.is_player(EVENT.actor) {
+                 if EVENT.text == "remember me" {
+                     SELF.set("friends", [EVENT.actor]);
+                     SELF.set("id", WORLD.id(EVENT.actor).to_string());
+                 } else if EVENT.text == "who" {
+                     let friend = SELF.get("friends")[0];
+                     let by_id = WORLD.entity(SELF.get("id"));
+                     SELF.say(`${WORLD.name(friend)} ${friend == EVENT.actor} ${by_id == EVENT.actor}`);
+                 }
+               }"#,
+        ))
+        .await;
+
+    t.command("create prototype", "prototype new").await;
+    t.command("prototype name", "prototype 1 name talking rock")
+        .await;
+    t.command("attach script", "script remember attach-pre prototype 1")
+        .await;
+    t.command("create object", "object new 1").await;
+
+    t.test("be remembered", "say remember me", vec!["You say"])
+        .await;
+
+    // logging back in gives the player a new entity
+    drop(t);
+    std::thread::sleep(Duration::from_secs(1));
+    let mut t = server.login_player(PLAYER_NAME, PASSWORD).await;
+
+    t.test("ask who was remembered", "say who", vec!["You say"])
+        .await;
+    t.consume_prompt().await;
+    t.line_contains(format!(r#"talking rock says "{} true true""#, PLAYER_NAME))
+        .await;
+    t.assert_prompt().await;
+}
+
+#[tokio::test]
+async fn test_world_force() {
+    let (server, mut t) = Server::new_create_player("krixi", "let me in").await;
+    let web = server.login_web(&t).await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+        "dance_floor",
+        Trigger::Say,
+        r#"if EVENT.text == "music" {
+                 WORLD.force(EVENT.actor, "me dances.");
+               } else if EVENT.text == "stop" && !WORLD.force(SELF.entity, "look") {
+                 WORLD.force(EVENT.actor, "shutdown");
+               }"#,
+    ))
+    .await;
+
+    t.command("create prototype", "prototype new").await;
+    t.command("prototype name", "prototype 1 name jukebox")
+        .await;
+    t.command(
+        "attach script",
+        "script dance_floor attach-post prototype 1",
+    )
+    .await;
+    t.command("create object", "object new 1").await;
+
+    t.test("start the music", "say music", vec!["You say"])
+        .await;
+    t.consume_prompt().await;
+    t.line_contains("krixi dances.").await;
+    t.assert_prompt().await;
+
+    t.test("stop the music", "say stop", vec!["You say"]).await;
+    t.consume_prompt().await;
+    t.line_contains("I don't know what that means.").await;
+    t.assert_prompt().await;
+}
+
+#[tokio::test]
+async fn test_world_client_caps() {
+    let (server, mut t) = Server::new_create_player("krixi", "let me in").await;
+    let web = server.login_web(&t).await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+            "caps_script",
+            Trigger::Look,
+            r#"let caps = WORLD.client_caps(EVENT.actor);
+               let none = WORLD.client_caps(SELF.entity) == ();
+               SELF.say(`width ${caps.width} color ${caps.color} mxp ${caps.mxp} gmcp ${caps.gmcp} ${none}`);"#,
+        ))
+        .await;
+
+    t.command("create prototype", "prototype new").await;
+    t.command("prototype name", "prototype 1 name mirror").await;
+    t.command(
+        "attach script",
+        "script caps_script attach-post prototype 1",
+    )
+    .await;
+    t.command("create object", "object new 1").await;
+
+    t.command("scripts can read the client's capabilities", "look")
+        .await;
+    t.consume_prompt().await;
+    t.line_contains("says \"width 80 color none mxp false gmcp false true\"")
+        .await;
+    t.assert_prompt().await;
+
+    t.resize(100).await;
+
+    t.command("capabilities follow the client's window", "look")
+        .await;
+    t.consume_prompt().await;
+    t.line_contains("says \"width 100 color none").await;
+    t.assert_prompt().await;
+}
+
+#[tokio::test]
+async fn test_world_art_and_banners() {
+    let art_dir = std::env::temp_dir().join(format!("remud-test-art-{}", std::process::id()));
+    std::fs::create_dir_all(&art_dir).unwrap();
+    std::fs::write(art_dir.join("cat.txt"), "=^.^= meow\n").unwrap();
+
+    let config = format!("art_dir = {:?}\n", art_dir.to_str().unwrap());
+    let mut server = Server::new_with_config(config.as_str()).await;
+    let mut t = server.create_player("krixi", "let me in").await;
+    let web = server.login_web(&t).await;
+
+    web.create_script_expect_ok(&JsonScript::new(
+        "art_script",
+        Trigger::Look,
+        r#"let cat = WORLD.art("cat", 3);
+               let dog = WORLD.art("dog") == ();
+               SELF.say(`${cat} ${dog} ${WORLD.banner("hi", 3)}`);"#,
+    ))
+    .await;
+
+    t.command("create prototype", "prototype new").await;
+    t.command("prototype name", "prototype 1 name easel").await;
+    t.command("attach script", "script art_script attach-post prototype 1")
+        .await;
+    t.command("create object", "object new 1").await;
+
+    t.command("scripts can show art and banners", "look").await;
+    t.consume_prompt().await;
+    t.line_contains("says \"=^. true hi\"").await;
+    t.assert_prompt().await;
+}
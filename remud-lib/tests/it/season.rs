@@ -0,0 +1,138 @@
+use crate::support::Server;
+
+#[tokio::test]
+async fn test_season() {
+    let mut server =
+        Server::new_with_config("[seasons.always]\nstart = \"01-01\"\nend = \"12-31\"\n").await;
+    let mut t = server.create_player("krixi", "(*&%(*#&%*&").await;
+    let mut t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    // New players start in room 1, so it is only made once both are in the void.
+    t.command("create a room to the north", "room new north")
+        .await;
+
+    t.test(
+        "configured seasons are listed",
+        "season",
+        vec!["always: 01-01 to 12-31, under way, 0 tagged"],
+    )
+    .await;
+    t.test(
+        "only existing content can be tagged",
+        "season winter tag spawn rats",
+        vec!["There is no spawn rats."],
+    )
+    .await;
+    t.test(
+        "tag an exit with a season without dates",
+        "season winter tag exit 0 north",
+        vec!["Tagged exit 0 north with season winter. Season winter has no dates configured"],
+    )
+    .await;
+    t2.test(
+        "mortals do not see dormant exits",
+        "exits",
+        vec!["This room has no obvious exits."],
+    )
+    .await;
+    t2.test(
+        "mortals cannot use dormant exits",
+        "north",
+        vec!["There is no exit to the north."],
+    )
+    .await;
+    t2.test(
+        "mortals cannot look through dormant exits",
+        "look north",
+        vec!["There is no room to the north."],
+    )
+    .await;
+    t.test("immortals still see dormant exits", "exits", vec!["north"])
+        .await;
+    t.test(
+        "retag the exit with a season under way",
+        "season always tag exit 0 north",
+        vec!["Tagged exit 0 north with season always."],
+    )
+    .await;
+    t2.test("exits in season are shown", "exits", vec!["north"])
+        .await;
+    t.test(
+        "tag the room the exit leads to",
+        "season winter tag room 1",
+        vec!["Tagged room 1 with season winter."],
+    )
+    .await;
+    t2.test(
+        "exits into dormant rooms cannot be used",
+        "north",
+        vec!["There is no exit to the north."],
+    )
+    .await;
+    t.test(
+        "list seasons with tagged content",
+        "season list",
+        vec![
+            "always: 01-01 to 12-31, under way, 1 tagged",
+            "winter: no dates configured, dormant, 1 tagged",
+        ],
+    )
+    .await;
+    t.test(
+        "show a season",
+        "season winter",
+        vec![
+            "Season winter",
+            "dates: no dates configured, dormant",
+            "room 1",
+        ],
+    )
+    .await;
+
+    drop(t2);
+
+    t.consume_prompt().await;
+    t.line_contains("Shane leaves.").await;
+    t.assert_prompt().await;
+
+    t = server.restart(t).await;
+
+    let mut t2 = server.login_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t2.test(
+        "season tags are persisted",
+        "north",
+        vec!["There is no exit to the north."],
+    )
+    .await;
+    t.test(
+        "untag the room",
+        "season untag room 1",
+        vec!["Removed room 1 from season winter."],
+    )
+    .await;
+    t.test(
+        "content can only be untagged once",
+        "season untag room 1",
+        vec!["There is no season tag on room 1."],
+    )
+    .await;
+    t2.test(
+        "untagged rooms are always active",
+        "north",
+        vec!["An empty room"],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane leaves to the north.").await;
+    t.assert_prompt().await;
+}
@@ -0,0 +1,120 @@
+use crate::support::Server;
+
+#[tokio::test]
+async fn test_shop() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.command("create bread prototype", "prototype new").await;
+    t.command("name bread prototype", "prototype 1 name a loaf of bread")
+        .await;
+    t.command("key bread prototype", "prototype 1 keywords set loaf bread")
+        .await;
+    t.command("create loot table", "loot new wares").await;
+    t.command("stock the loot table", "loot wares add 1 common 2")
+        .await;
+
+    t.test(
+        "create a shop",
+        "shops new store",
+        vec!["Created shop store in room 0."],
+    )
+    .await;
+    t.test(
+        "one shop per room",
+        "shops new other",
+        vec!["Room 0 already has shop store."],
+    )
+    .await;
+    t.test(
+        "restocking needs a loot table",
+        "shops store restock",
+        vec!["Shop store has no loot table to restock from."],
+    )
+    .await;
+    t.test(
+        "missing loot tables are rejected",
+        "shops store loot missing",
+        vec!["Loot table missing does not exist."],
+    )
+    .await;
+    t.test(
+        "the shop starts empty",
+        "shop",
+        vec!["There is nothing for sale here. Come back later."],
+    )
+    .await;
+    t.test(
+        "set the loot table",
+        "shops store loot wares",
+        vec!["Updated shop store."],
+    )
+    .await;
+    t.test(
+        "restock the shop",
+        "shops store restock",
+        vec!["Updated shop store."],
+    )
+    .await;
+    t.test(
+        "list the stock",
+        "shop list",
+        vec!["1. a loaf of bread (common) for 10 coins, 2 left"],
+    )
+    .await;
+    t.test(
+        "buying needs coins",
+        "shop buy 1",
+        vec!["a loaf of bread costs 10 coins, but you are only carrying 0 coins."],
+    )
+    .await;
+    t.test(
+        "missing items are rejected",
+        "shop buy 2",
+        vec!["There is no item 2 for sale here."],
+    )
+    .await;
+    t.test(
+        "haggling without a script",
+        "shop haggle 1",
+        vec!["Your haggling gets you nowhere. a loaf of bread still costs 10 coins."],
+    )
+    .await;
+
+    t.command("grant coins", "bank krixi grant 20").await;
+
+    t.test(
+        "buy an item",
+        "shop buy 1",
+        vec!["You buy a loaf of bread for 10 coins."],
+    )
+    .await;
+    t.test("the item is carried", "inventory", vec!["a loaf of bread"])
+        .await;
+    t.test(
+        "the coins are spent",
+        "balance",
+        vec!["You are carrying 10 coins."],
+    )
+    .await;
+    t.test(
+        "the coins are sunk",
+        "shops list",
+        vec!["room 0, 1 items in stock, 10 coins sunk"],
+    )
+    .await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "stock is persisted",
+        "shop",
+        vec!["1. a loaf of bread (common) for 10 coins, 1 left"],
+    )
+    .await;
+    t.test(
+        "the bought item is persisted",
+        "inventory",
+        vec!["a loaf of bread"],
+    )
+    .await;
+}
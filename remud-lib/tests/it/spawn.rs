@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use crate::support::Server;
+
+#[tokio::test]
+async fn test_spawn_tables() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.test(
+        "there are no spawn tables to start",
+        "spawn list",
+        vec!["There are no spawn tables."],
+    )
+    .await;
+
+    t.command("create a room", "room new north").await;
+    t.command("go to the room", "north").await;
+    t.command("create prototype", "prototype new").await;
+    t.command("name prototype", "prototype 1 name a sewer rat")
+        .await;
+
+    t.test(
+        "create a spawn table",
+        "spawn new rats",
+        vec!["Created spawn table rats."],
+    )
+    .await;
+    t.test(
+        "spawn table IDs are unique",
+        "spawn new rats",
+        vec!["Spawn table rats already exists."],
+    )
+    .await;
+    t.test(
+        "missing prototypes are rejected",
+        "spawn rats add 2",
+        vec!["Prototype 2 does not exist."],
+    )
+    .await;
+    t.test(
+        "add a prototype",
+        "spawn rats add 1 3",
+        vec!["Updated spawn table rats."],
+    )
+    .await;
+    t.test(
+        "set the cap",
+        "spawn rats cap 2",
+        vec!["Updated spawn table rats."],
+    )
+    .await;
+    t.test(
+        "set the interval",
+        "spawn rats interval 1",
+        vec!["Updated spawn table rats."],
+    )
+    .await;
+    t.test(
+        "attach a region",
+        "spawn rats attach region sewers",
+        vec!["Updated spawn table rats."],
+    )
+    .await;
+    t.test(
+        "attach the current room",
+        "spawn rats attach room 1",
+        vec!["Updated spawn table rats."],
+    )
+    .await;
+
+    // Give the table time to top up the room and to try to exceed its cap.
+    tokio::time::sleep(Duration::from_millis(3500)).await;
+
+    t.test(
+        "the room is stocked up to the cap",
+        "room info",
+        vec!["object 1: a sewer rat", "object 2: a sewer rat"],
+    )
+    .await;
+    t.test_exclude("no more than the cap", "room info", vec!["object 3"])
+        .await;
+
+    t = server.restart(t).await;
+
+    t.test(
+        "spawn tables are persisted",
+        "spawn rats info",
+        vec![
+            "cap: 2",
+            "interval: 1 seconds",
+            "1: a sewer rat (weight 3, 100%)",
+            "rooms: 1",
+            "regions: sewers",
+        ],
+    )
+    .await;
+    t.test(
+        "spawned objects are persisted",
+        "room info",
+        vec!["object 1: a sewer rat", "object 2: a sewer rat"],
+    )
+    .await;
+
+    t.test(
+        "detach the room",
+        "spawn rats detach room 1",
+        vec!["Updated spawn table rats."],
+    )
+    .await;
+    t.test(
+        "delete the table",
+        "spawn rats delete",
+        vec!["Deleted spawn table rats."],
+    )
+    .await;
+    t.test(
+        "deleted tables are gone",
+        "spawn list",
+        vec!["There are no spawn tables."],
+    )
+    .await;
+}
@@ -2,24 +2,29 @@
 
 mod telnet;
 mod web;
+mod ws;
 
 use std::{
     borrow::Cow,
     io::{self},
-    path::Path,
+    path::{Path, PathBuf},
     sync::atomic::{AtomicU16, Ordering},
     time::Duration,
 };
 
 use once_cell::sync::Lazy;
-use remud_lib::{run_remud, RemudError, WebOptions};
+use remud_lib::{run_remud, Clock, MigrationOptions, ProfileLayer, RemudError, WebOptions};
 use tokio::time::timeout;
-use tracing_subscriber::{fmt::MakeWriter, EnvFilter, FmtSubscriber};
+use tracing_subscriber::{
+    fmt::MakeWriter, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, FmtSubscriber,
+};
 
 pub use crate::support::telnet::{Match, Matcher, TelnetConnection, TelnetPlayer};
 pub use crate::support::web::{
-    AuthenticatedWebClient, JsonScript, JsonScriptName, JsonScriptResponse, Trigger, WebClient,
+    AuthenticatedWebClient, Hook, JsonScript, JsonScriptName, JsonScriptResponse, Trigger,
+    WebClient,
 };
+pub use crate::support::ws::WsConnection;
 pub use reqwest::StatusCode;
 
 static PORT_COUNTER: Lazy<AtomicU16> = Lazy::new(|| AtomicU16::new(49152));
@@ -32,12 +37,27 @@ pub static TRACING: Lazy<()> = Lazy::new(|| {
 pub struct Server {
     telnet: u16,
     web: u16,
+    clock: Clock,
     #[allow(dead_code)]
     ready: tokio::sync::mpsc::Receiver<()>,
 }
 
 impl Server {
     pub async fn new() -> Self {
+        Server::start(None, Clock::system()).await
+    }
+
+    /// Starts a server with a configuration file containing the provided TOML.
+    pub async fn new_with_config(config: &str) -> Self {
+        Server::start(Some(config), Clock::system()).await
+    }
+
+    /// Starts a server whose clock stands still until advanced with `advance`.
+    pub async fn new_with_manual_clock() -> Self {
+        Server::start(None, Clock::manual()).await
+    }
+
+    async fn start(config: Option<&str>, clock: Clock) -> Self {
         Lazy::force(&TRACING);
 
         let mut telnet_port;
@@ -55,10 +75,26 @@ impl Server {
             );
             let (ready_tx, mut ready_rx) = tokio::sync::mpsc::channel(16);
 
-            let spawn =
-                tokio::spawn(
-                    async move { run_remud(None, telnet_port, web, Some(ready_tx)).await },
-                );
+            let config_path = config.map(|config| {
+                let path: PathBuf =
+                    std::env::temp_dir().join(format!("remud-test-{}.toml", web_port));
+                std::fs::write(&path, config).unwrap();
+                path
+            });
+
+            let run_clock = clock.clone();
+            let spawn = tokio::spawn(async move {
+                run_remud(
+                    None,
+                    config_path.as_deref(),
+                    telnet_port,
+                    web,
+                    MigrationOptions::default(),
+                    run_clock,
+                    Some(ready_tx),
+                )
+                .await
+            });
 
             tokio::select! {
                 join_result = spawn => {
@@ -89,6 +125,7 @@ impl Server {
         Server {
             telnet: telnet_port,
             web: web_port,
+            clock,
             ready: ready_rx,
         }
     }
@@ -129,6 +166,11 @@ impl Server {
         }
     }
 
+    /// Moves the server's clock forward. Only servers started with a manual clock can be advanced.
+    pub fn advance(&self, duration: Duration) {
+        self.clock.advance(duration);
+    }
+
     pub fn telnet(&self) -> u16 {
         self.telnet
     }
@@ -145,6 +187,10 @@ impl Server {
         WebClient::new(self.web())
     }
 
+    pub async fn connect_ws(&self, path: &str) -> WsConnection {
+        WsConnection::connect(self.web(), path).await
+    }
+
     pub async fn login_web(&self, player: &TelnetPlayer) -> AuthenticatedWebClient {
         let client = self.connect_web();
         client
@@ -263,5 +309,7 @@ pub fn init_subscriber(env_filter: String, sink: impl MakeWriter + Send + Sync +
         .with_env_filter(env_filter)
         .with_writer(sink)
         .with_level(true)
+        .finish()
+        .with(ProfileLayer::default())
         .init();
 }
@@ -10,9 +10,23 @@ use tokio::sync::mpsc;
 #[derive(Debug)]
 pub enum TelnetRequest {
     Recv,
+    Resize(u16),
     Send(String),
 }
 
+// The telnet option code for the MUD eXtension Protocol.
+const MXP: u8 = 91;
+const GMCP: u8 = 201;
+
+// The window size reported by test clients unless they resize.
+const DEFAULT_WINDOW: (u16, u16) = (80, 24);
+
+fn window_size(width: u16, height: u16) -> [u8; 4] {
+    let [width_high, width_low] = width.to_be_bytes();
+    let [height_high, height_low] = height.to_be_bytes();
+    [width_high, width_low, height_high, height_low]
+}
+
 /// TelnetConnection maintains a scratch buffer for holding incoming data and an output list which consists
 /// of lines between one prompt and another. When a prompt is found in the received output, deserializing
 /// into the output list is halted and the output is made available for inspection either line-by-line or
@@ -26,6 +40,7 @@ pub struct TelnetConnection {
     output: VecDeque<String>,
     req_tx: mpsc::Sender<TelnetRequest>,
     event_rx: mpsc::Receiver<TelnetEvent>,
+    echo_hidden: bool,
 }
 
 impl TelnetConnection {
@@ -39,19 +54,69 @@ impl TelnetConnection {
             let mut connection =
                 Telnet::connect(("127.0.0.1", port), 1024).expect("failed to connect to ReMUD");
 
-            match connection
-                .read_timeout(Duration::from_secs(10))
-                .expect("did not receive DO TTYPE")
-            {
-                TelnetEvent::Negotiation(NegotiationAction::Do, TelnetOption::TTYPE) => {
-                    connection
-                        .negotiate(&NegotiationAction::Wont, TelnetOption::TTYPE)
-                        .unwrap();
+            // refuse everything but window size, like a basic line mode client
+            for expected in [
+                "DO TTYPE",
+                "DO LINEMODE",
+                "DO NAWS",
+                "WILL SGA",
+                "WILL MXP",
+                "WILL GMCP",
+            ] {
+                match connection
+                    .read_timeout(Duration::from_secs(10))
+                    .unwrap_or_else(|_| panic!("did not receive {}", expected))
+                {
+                    TelnetEvent::Negotiation(NegotiationAction::Do, TelnetOption::TTYPE) => {
+                        connection
+                            .negotiate(&NegotiationAction::Wont, TelnetOption::TTYPE)
+                            .unwrap();
+                    }
+                    TelnetEvent::Negotiation(NegotiationAction::Do, TelnetOption::Linemode) => {
+                        connection
+                            .negotiate(&NegotiationAction::Wont, TelnetOption::Linemode)
+                            .unwrap();
+                    }
+                    TelnetEvent::Negotiation(NegotiationAction::Do, TelnetOption::NAWS) => {
+                        connection
+                            .negotiate(&NegotiationAction::Will, TelnetOption::NAWS)
+                            .unwrap();
+                        connection
+                            .subnegotiate(
+                                TelnetOption::NAWS,
+                                &window_size(DEFAULT_WINDOW.0, DEFAULT_WINDOW.1),
+                            )
+                            .unwrap();
+                    }
+                    TelnetEvent::Negotiation(
+                        NegotiationAction::Will,
+                        TelnetOption::SuppressGoAhead,
+                    ) => {
+                        connection
+                            .negotiate(&NegotiationAction::Dont, TelnetOption::SuppressGoAhead)
+                            .unwrap();
+                    }
+                    TelnetEvent::Negotiation(
+                        NegotiationAction::Will,
+                        TelnetOption::UnknownOption(MXP),
+                    ) => {
+                        connection
+                            .negotiate(&NegotiationAction::Dont, TelnetOption::UnknownOption(MXP))
+                            .unwrap();
+                    }
+                    TelnetEvent::Negotiation(
+                        NegotiationAction::Will,
+                        TelnetOption::UnknownOption(GMCP),
+                    ) => {
+                        connection
+                            .negotiate(&NegotiationAction::Dont, TelnetOption::UnknownOption(GMCP))
+                            .unwrap();
+                    }
+                    other => panic!(
+                        "received unexpected message waiting for {}: {:?}",
+                        expected, other
+                    ),
                 }
-                other => panic!(
-                    "received unexpected message waiting for DO TTYPE: {:?}",
-                    other
-                ),
             }
 
             while let Some(req) = req_rx.blocking_recv() {
@@ -60,6 +125,11 @@ impl TelnetConnection {
                         let event = connection.read_timeout(Duration::from_secs(10)).unwrap();
                         event_tx.blocking_send(event).unwrap();
                     }
+                    TelnetRequest::Resize(width) => {
+                        connection
+                            .subnegotiate(TelnetOption::NAWS, &window_size(width, DEFAULT_WINDOW.1))
+                            .unwrap();
+                    }
                     TelnetRequest::Send(message) => {
                         connection.write(message.as_bytes()).unwrap();
                     }
@@ -72,9 +142,23 @@ impl TelnetConnection {
             output: VecDeque::new(),
             req_tx,
             event_rx,
+            echo_hidden: false,
         }
     }
 
+    /// Returns true when the server has offered to echo input, asking the client to hide it.
+    pub fn echo_hidden(&self) -> bool {
+        self.echo_hidden
+    }
+
+    /// Reports a new window width to the server.
+    pub async fn resize(&mut self, width: u16) {
+        self.req_tx
+            .send(TelnetRequest::Resize(width))
+            .await
+            .unwrap();
+    }
+
     /// Prints an info line to the logs
     pub fn info<'a, S>(&mut self, text: S)
     where
@@ -152,6 +236,36 @@ impl TelnetConnection {
         }
     }
 
+    /// Consumes output until the server closes the connection, asserting if text is missing from
+    /// it. Notices sent as a client is disconnected are not followed by a prompt.
+    pub async fn disconnected_with<'a, S>(&mut self, text: S)
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let text = text.into();
+        let mut received = std::mem::take(&mut self.buffer);
+
+        loop {
+            self.req_tx.send(TelnetRequest::Recv).await.unwrap();
+            match self.event_rx.recv().await {
+                Some(TelnetEvent::Data(data)) => received.push_str(
+                    std::str::from_utf8(&data).expect("server sent invalid UTF-8 string"),
+                ),
+                Some(TelnetEvent::UnknownIAC(_)) | Some(TelnetEvent::Negotiation(_, _)) => (),
+                _ => break,
+            }
+        }
+
+        tracing::info!("received before disconnect: {:?}", received);
+
+        assert!(
+            received.contains(text.as_ref()),
+            "did not find '{}' before disconnect in {:?}",
+            text,
+            received,
+        )
+    }
+
     /// Asserts that the client is waiting at a prompt and clears it.
     /// Automatically called when using the send method.
     pub async fn consume_prompt(&mut self) {
@@ -302,12 +416,21 @@ impl TelnetConnection {
             loop {
                 self.req_tx.send(TelnetRequest::Recv).await.unwrap();
                 if let Some(event) = self.event_rx.recv().await {
-                    if let TelnetEvent::Data(data) = event {
-                        let new =
-                            std::str::from_utf8(&data).expect("server sent invalid UTF-8 string");
-                        self.buffer.push_str(new);
-                    } else {
-                        panic!("did not receive expected DATA event, got: {:?}", event);
+                    match event {
+                        TelnetEvent::Data(data) => {
+                            let new = std::str::from_utf8(&data)
+                                .expect("server sent invalid UTF-8 string");
+                            self.buffer.push_str(new);
+                        }
+                        // keepalives
+                        TelnetEvent::UnknownIAC(_) => (),
+                        TelnetEvent::Negotiation(NegotiationAction::Will, TelnetOption::Echo) => {
+                            self.echo_hidden = true;
+                        }
+                        TelnetEvent::Negotiation(NegotiationAction::Wont, TelnetOption::Echo) => {
+                            self.echo_hidden = false;
+                        }
+                        event => panic!("did not receive expected DATA event, got: {:?}", event),
                     }
                 } else {
                     panic!("failed to read from telnet: channel closed")
@@ -5,6 +5,8 @@ use std::{borrow::Cow, time::Duration};
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 
+use crate::support::TelnetPlayer;
+
 #[derive(Debug, Serialize, Deserialize)]
 struct Empty {}
 
@@ -27,6 +29,8 @@ struct JsonRefresh {
 
 #[derive(Debug, strum::Display, strum::EnumString)]
 pub enum Trigger {
+    Buy,
+    Custom,
     Drop,
     Emote,
     Exits,
@@ -37,6 +41,7 @@ pub enum Trigger {
     LookAt,
     Move,
     Say,
+    Sell,
     Send,
     Timer,
     Use,
@@ -56,6 +61,14 @@ pub struct JsonScript {
     name: String,
     trigger: Trigger,
     code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    revision: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    folder: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
 }
 
 impl JsonScript {
@@ -68,10 +81,57 @@ impl JsonScript {
             name: name.into().to_owned().to_string(),
             trigger,
             code: code.into().to_owned().to_string(),
+            revision: None,
+            tags: None,
+            folder: None,
+            enabled: None,
+        }
+    }
+
+    pub fn with_revision(mut self, revision: i64) -> Self {
+        self.revision = Some(revision);
+        self
+    }
+
+    pub fn with_tags(mut self, tags: &[&str]) -> Self {
+        self.tags = Some(tags.iter().map(ToString::to_string).collect());
+        self
+    }
+
+    pub fn with_folder(mut self, folder: &str) -> Self {
+        self.folder = Some(folder.to_string());
+        self
+    }
+
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = Some(enabled);
+        self
+    }
+}
+
+/// The hook a script is attached to an entity with in game.
+#[derive(Debug)]
+pub enum Hook {
+    Pre,
+    Post,
+    Timer(&'static str),
+}
+
+impl Hook {
+    fn command(&self) -> String {
+        match self {
+            Hook::Pre => "attach-pre".to_string(),
+            Hook::Post => "attach-post".to_string(),
+            Hook::Timer(name) => format!("attach-timer {}", name),
         }
     }
 }
 
+#[derive(Debug, Serialize)]
+struct JsonGraphQlQuery {
+    query: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct JsonScriptName {
     name: String,
@@ -85,9 +145,33 @@ impl From<&str> for JsonScriptName {
     }
 }
 
+#[derive(Debug, Serialize)]
+struct JsonPlayerName {
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonCreateToken {
+    name: String,
+    scopes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonCreatedToken {
+    token: String,
+}
+
 #[derive(Debug, Deserialize)]
-struct JsonListResponse {
-    scripts: Vec<JsonScriptInfo>,
+struct JsonAllowlistResponse {
+    names: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JsonListResponse {
+    pub scripts: Vec<JsonScriptInfo>,
+    pub total: usize,
+    pub page: usize,
+    pub limit: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -95,22 +179,52 @@ pub struct JsonScriptResponse {
     pub name: String,
     pub trigger: String,
     pub code: String,
+    pub revision: i64,
+    pub tags: Vec<String>,
+    pub folder: Option<String>,
+    pub enabled: bool,
     pub error: Option<JsonErrorInfo>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct JsonErrorResponse {
+    pub revision: i64,
     pub error: Option<JsonErrorInfo>,
 }
 
+#[derive(Debug, Deserialize)]
+struct JsonConflictResponse {
+    current: JsonScriptResponse,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct JsonScriptInfo {
     pub name: String,
     pub trigger: String,
     pub lines: i64,
+    pub revision: i64,
+    pub tags: Vec<String>,
+    pub folder: Option<String>,
+    pub enabled: bool,
     pub error: Option<JsonErrorInfo>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct JsonScriptStatsResponse {
+    pub scripts: Vec<JsonScriptStats>,
+    pub slow_threshold_us: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JsonScriptStats {
+    pub name: String,
+    pub runs: u64,
+    pub errors: u64,
+    pub total_us: u64,
+    pub mean_us: u64,
+    pub slow: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct JsonErrorInfo {
     pub line: Option<i64>,
@@ -168,10 +282,100 @@ impl WebClient {
         }
     }
 
+    /// Authenticates with an API token rather than logging in. API tokens cannot be refreshed.
+    pub fn with_api_token(self, token: &str) -> AuthenticatedWebClient {
+        AuthenticatedWebClient {
+            client: self,
+            access_token: token.to_string(),
+            refresh_token: String::new(),
+        }
+    }
+
+    pub async fn get(&self, path: &str) -> Result<String, StatusCode> {
+        match self
+            .client
+            .get(format!("{}:{}{}", Self::URL, self.port, path))
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+        {
+            Ok(response) => {
+                if response.status().is_success() {
+                    Ok(response.text().await.unwrap())
+                } else {
+                    Err(response.status())
+                }
+            }
+            Err(e) => Err(e.status().unwrap()),
+        }
+    }
+
+    /// Gets the path, optionally sending a request ID, returning the response status and the
+    /// request ID it echoed.
+    pub async fn get_request_id(
+        &self,
+        path: &str,
+        request_id: Option<&str>,
+    ) -> (StatusCode, Option<String>) {
+        let mut request = self.get_request(path).timeout(Duration::from_secs(10));
+        if let Some(request_id) = request_id {
+            request = request.header("X-Request-Id", request_id);
+        }
+
+        let response = request.send().await.unwrap();
+        let request_id = response
+            .headers()
+            .get("x-request-id")
+            .map(|value| value.to_str().unwrap().to_string());
+        (response.status(), request_id)
+    }
+
+    /// Gets the path from a page served by the origin, returning the response status and the
+    /// origin the server allowed.
+    pub async fn get_from_origin(&self, path: &str, origin: &str) -> (StatusCode, Option<String>) {
+        let response = self
+            .get_request(path)
+            .header("Origin", origin)
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+            .unwrap();
+        let allowed = response
+            .headers()
+            .get("access-control-allow-origin")
+            .map(|value| value.to_str().unwrap().to_string());
+        (response.status(), allowed)
+    }
+
+    /// Requests a websocket upgrade, returning the response status.
+    pub async fn upgrade(&self, path: &str) -> StatusCode {
+        Self::send_upgrade(self.get_request(path)).await
+    }
+
+    async fn send_upgrade(request: reqwest::RequestBuilder) -> StatusCode {
+        match request
+            .header("Connection", "Upgrade")
+            .header("Upgrade", "websocket")
+            .header("Sec-WebSocket-Version", "13")
+            .header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==")
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+        {
+            Ok(response) => response.status(),
+            Err(e) => e.status().unwrap(),
+        }
+    }
+
     fn post(&self, path: &str) -> reqwest::RequestBuilder {
         self.client
             .post(format!("{}:{}{}", WebClient::URL, self.port, path))
     }
+
+    fn get_request(&self, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .get(format!("{}:{}{}", WebClient::URL, self.port, path))
+    }
 }
 
 #[derive(Clone)]
@@ -189,6 +393,22 @@ impl AuthenticatedWebClient {
             .bearer_auth(self.access_token.as_str())
     }
 
+    fn get_auth(&self, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .get_request(path)
+            .timeout(Duration::from_secs(10))
+            .bearer_auth(self.access_token.as_str())
+    }
+
+    pub fn access_token(&self) -> &str {
+        self.access_token.as_str()
+    }
+
+    /// Requests a websocket upgrade with the access token, returning the response status.
+    pub async fn upgrade(&self, path: &str) -> StatusCode {
+        WebClient::send_upgrade(self.get_auth(path)).await
+    }
+
     pub async fn refresh_auth(&mut self) -> Result<(), StatusCode> {
         match self
             .client
@@ -229,6 +449,54 @@ impl AuthenticatedWebClient {
         }
     }
 
+    pub async fn create_api_token(
+        &self,
+        name: &str,
+        scopes: &[&str],
+    ) -> Result<String, StatusCode> {
+        match self
+            .post_auth("/auth/tokens/create")
+            .json(&JsonCreateToken {
+                name: name.to_string(),
+                scopes: scopes.iter().map(ToString::to_string).collect(),
+            })
+            .send()
+            .await
+        {
+            Ok(response) => {
+                if response.status().is_success() {
+                    Ok(response.json::<JsonCreatedToken>().await.unwrap().token)
+                } else {
+                    Err(response.status())
+                }
+            }
+            Err(e) => Err(e.status().unwrap()),
+        }
+    }
+
+    pub async fn api_tokens(&self) -> Result<serde_json::Value, StatusCode> {
+        match self.post_auth("/auth/tokens/read").send().await {
+            Ok(response) => {
+                if response.status().is_success() {
+                    Ok(response.json::<serde_json::Value>().await.unwrap())
+                } else {
+                    Err(response.status())
+                }
+            }
+            Err(e) => Err(e.status().unwrap()),
+        }
+    }
+
+    pub async fn revoke_api_token(&self, name: &str) -> Result<(), StatusCode> {
+        self.post_empty(
+            "/auth/tokens/revoke",
+            &JsonPlayerName {
+                name: name.to_string(),
+            },
+        )
+        .await
+    }
+
     pub async fn create_script(
         &self,
         script: &JsonScript,
@@ -245,6 +513,33 @@ impl AuthenticatedWebClient {
         }
     }
 
+    /// Creates a script, panicking if it is rejected or does not compile.
+    pub async fn create_script_expect_ok(&self, script: &JsonScript) {
+        match self.create_script(script).await {
+            Ok(None) => (),
+            Ok(Some(error)) => panic!("script {} failed to compile: {:?}", script.name, error),
+            Err(status) => panic!("failed to create script {}: {}", script.name, status),
+        }
+    }
+
+    /// Creates a script and attaches it to a room with the player's game connection.
+    pub async fn attach_to_room(
+        &self,
+        t: &mut TelnetPlayer,
+        script: &JsonScript,
+        hook: Hook,
+        room: i64,
+    ) {
+        self.create_script_expect_ok(script).await;
+
+        t.test(
+            "attach script to room",
+            format!("script {} {} room {}", script.name, hook.command(), room),
+            vec![format!("Script {} attached to room {}.", script.name, room)],
+        )
+        .await;
+    }
+
     pub async fn read_script(
         &self,
         script: &JsonScriptName,
@@ -262,16 +557,34 @@ impl AuthenticatedWebClient {
     }
 
     pub async fn list_scripts(&self) -> Result<Vec<JsonScriptInfo>, StatusCode> {
+        self.query_scripts("")
+            .await
+            .map(|response| response.scripts)
+    }
+
+    pub async fn query_scripts(&self, query: &str) -> Result<JsonListResponse, StatusCode> {
         match self
-            .post_auth("/scripts/read/all")
+            .post_auth(format!("/scripts/read/all{}", query).as_str())
             .json(&Empty {})
             .send()
             .await
         {
             Ok(response) => {
                 if response.status().is_success() {
-                    let response = response.json::<JsonListResponse>().await.unwrap();
-                    Ok(response.scripts)
+                    Ok(response.json::<JsonListResponse>().await.unwrap())
+                } else {
+                    Err(response.status())
+                }
+            }
+            Err(e) => Err(e.status().unwrap()),
+        }
+    }
+
+    pub async fn script_stats(&self) -> Result<JsonScriptStatsResponse, StatusCode> {
+        match self.get_auth("/scripts/stats").send().await {
+            Ok(response) => {
+                if response.status().is_success() {
+                    Ok(response.json::<JsonScriptStatsResponse>().await.unwrap())
                 } else {
                     Err(response.status())
                 }
@@ -296,6 +609,211 @@ impl AuthenticatedWebClient {
         }
     }
 
+    /// Updates a script with code which is expected to fail to compile on the given line.
+    pub async fn update_and_assert_error_line(
+        &self,
+        script: &JsonScript,
+        line: i64,
+    ) -> JsonErrorResponse {
+        let response = match self.update_script(script).await {
+            Ok(response) => response,
+            Err(status) => panic!("failed to update script {}: {}", script.name, status),
+        };
+
+        match &response.error {
+            Some(error) => assert_eq!(
+                error.line,
+                Some(line),
+                "script {} failed to compile on an unexpected line: {:?}",
+                script.name,
+                error
+            ),
+            None => panic!("expected script {} to fail to compile", script.name),
+        }
+
+        response
+    }
+
+    /// Attempts an update which is expected to conflict, returning the current server version.
+    pub async fn update_script_conflict(&self, script: &JsonScript) -> Option<JsonScriptResponse> {
+        match self.post_auth("/scripts/update").json(script).send().await {
+            Ok(response) if response.status() == StatusCode::CONFLICT => Some(
+                response
+                    .json::<JsonConflictResponse>()
+                    .await
+                    .unwrap()
+                    .current,
+            ),
+            _ => None,
+        }
+    }
+
+    pub async fn graphql(&self, query: &str) -> Result<serde_json::Value, StatusCode> {
+        match self
+            .post_auth("/graphql")
+            .json(&JsonGraphQlQuery {
+                query: query.to_string(),
+            })
+            .send()
+            .await
+        {
+            Ok(response) => {
+                if response.status().is_success() {
+                    Ok(response.json::<serde_json::Value>().await.unwrap())
+                } else {
+                    Err(response.status())
+                }
+            }
+            Err(e) => Err(e.status().unwrap()),
+        }
+    }
+
+    pub async fn read_allowlist(&self) -> Result<Vec<String>, StatusCode> {
+        self.post_allowlist("/allowlist/read", &Empty {}).await
+    }
+
+    pub async fn allow(&self, name: &str) -> Result<Vec<String>, StatusCode> {
+        self.post_allowlist(
+            "/allowlist/add",
+            &JsonPlayerName {
+                name: name.to_string(),
+            },
+        )
+        .await
+    }
+
+    pub async fn disallow(&self, name: &str) -> Result<Vec<String>, StatusCode> {
+        self.post_allowlist(
+            "/allowlist/remove",
+            &JsonPlayerName {
+                name: name.to_string(),
+            },
+        )
+        .await
+    }
+
+    async fn post_allowlist<T: Serialize>(
+        &self,
+        path: &str,
+        body: &T,
+    ) -> Result<Vec<String>, StatusCode> {
+        match self.post_auth(path).json(body).send().await {
+            Ok(response) => {
+                if response.status().is_success() {
+                    Ok(response
+                        .json::<JsonAllowlistResponse>()
+                        .await
+                        .unwrap()
+                        .names)
+                } else {
+                    Err(response.status())
+                }
+            }
+            Err(e) => Err(e.status().unwrap()),
+        }
+    }
+
+    pub async fn export_player(&self) -> Result<serde_json::Value, StatusCode> {
+        match self.get_auth("/players/me/export").send().await {
+            Ok(response) => {
+                if response.status().is_success() {
+                    Ok(response.json::<serde_json::Value>().await.unwrap())
+                } else {
+                    Err(response.status())
+                }
+            }
+            Err(e) => Err(e.status().unwrap()),
+        }
+    }
+
+    pub async fn lint_world(&self) -> Result<serde_json::Value, StatusCode> {
+        match self.get_auth("/world/lint").send().await {
+            Ok(response) => {
+                if response.status().is_success() {
+                    Ok(response.json::<serde_json::Value>().await.unwrap())
+                } else {
+                    Err(response.status())
+                }
+            }
+            Err(e) => Err(e.status().unwrap()),
+        }
+    }
+
+    pub async fn economy(&self, days: u32) -> Result<serde_json::Value, StatusCode> {
+        match self
+            .get_auth(format!("/world/economy?days={}", days).as_str())
+            .send()
+            .await
+        {
+            Ok(response) => {
+                if response.status().is_success() {
+                    Ok(response.json::<serde_json::Value>().await.unwrap())
+                } else {
+                    Err(response.status())
+                }
+            }
+            Err(e) => Err(e.status().unwrap()),
+        }
+    }
+
+    pub async fn communications(&self, query: &str) -> Result<serde_json::Value, StatusCode> {
+        match self
+            .get_auth(format!("/world/communications?{}", query).as_str())
+            .send()
+            .await
+        {
+            Ok(response) => {
+                if response.status().is_success() {
+                    Ok(response.json::<serde_json::Value>().await.unwrap())
+                } else {
+                    Err(response.status())
+                }
+            }
+            Err(e) => Err(e.status().unwrap()),
+        }
+    }
+
+    pub async fn request_erasure(&self) -> Result<(), StatusCode> {
+        self.post_empty("/players/me/erasure", &Empty {}).await
+    }
+
+    pub async fn erasure_requests(&self) -> Result<serde_json::Value, StatusCode> {
+        match self.post_auth("/players/erasures/read").send().await {
+            Ok(response) => {
+                if response.status().is_success() {
+                    Ok(response.json::<serde_json::Value>().await.unwrap())
+                } else {
+                    Err(response.status())
+                }
+            }
+            Err(e) => Err(e.status().unwrap()),
+        }
+    }
+
+    pub async fn approve_erasure(&self, name: &str) -> Result<(), StatusCode> {
+        self.post_empty(
+            "/players/erasures/approve",
+            &JsonPlayerName {
+                name: name.to_string(),
+            },
+        )
+        .await
+    }
+
+    async fn post_empty<T: Serialize>(&self, path: &str, body: &T) -> Result<(), StatusCode> {
+        match self.post_auth(path).json(body).send().await {
+            Ok(response) => {
+                if response.status().is_success() {
+                    response.json::<Empty>().await.expect("empty JSON response");
+                    Ok(())
+                } else {
+                    Err(response.status())
+                }
+            }
+            Err(e) => Err(e.status().unwrap()),
+        }
+    }
+
     pub async fn delete_script(&self, script: &JsonScriptName) -> Result<(), StatusCode> {
         match self.post_auth("/scripts/delete").json(script).send().await {
             Ok(response) => {
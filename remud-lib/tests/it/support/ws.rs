@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::{net::TcpStream, time::timeout};
+use tokio_tungstenite::{connect_async, tungstenite::Message, WebSocketStream};
+
+pub struct WsConnection {
+    stream: WebSocketStream<TcpStream>,
+}
+
+impl WsConnection {
+    pub async fn connect(port: u16, path: &str) -> Self {
+        let (stream, _) = connect_async(format!("ws://127.0.0.1:{}{}", port, path))
+            .await
+            .expect("websocket connection");
+        WsConnection { stream }
+    }
+
+    pub async fn send(&mut self, message: &str) {
+        let request = json!({"type": "game", "data": {"message": message}});
+        self.stream
+            .send(Message::text(request.to_string()))
+            .await
+            .unwrap();
+    }
+
+    /// Reads messages until the server hands out a resume token, returning it.
+    pub async fn resume_token(&mut self) -> String {
+        loop {
+            let response = self.read().await;
+            if response["type"] == "resume" {
+                return response["data"]["token"].as_str().unwrap().to_string();
+            }
+        }
+    }
+
+    /// Reads game output until a line contains the text, asserting if the connection closes
+    /// first.
+    pub async fn wait_for(&mut self, text: &str) {
+        loop {
+            let response = self.read().await;
+            if response["type"] != "game" {
+                continue;
+            }
+
+            let line = response["data"]["segments"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter_map(|segment| segment["d"]["text"].as_str())
+                .collect::<String>();
+            tracing::info!("ws <- {}", line);
+            if line.contains(text) {
+                return;
+            }
+        }
+    }
+
+    async fn read(&mut self) -> Value {
+        loop {
+            let message = timeout(Duration::from_secs(5), self.stream.next())
+                .await
+                .expect("timed out waiting for websocket message")
+                .expect("websocket closed")
+                .unwrap();
+            if message.is_text() {
+                return serde_json::from_str(message.to_text().unwrap()).unwrap();
+            }
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use std::time::Duration;
+
+use crate::support::Server;
+
+#[tokio::test]
+async fn test_profile() {
+    let (_server, mut t) = Server::new_create_player("krixi", "password").await;
+
+    t.test(
+        "profile requires a tick count",
+        "profile",
+        vec!["Profile for how many ticks?"],
+    )
+    .await;
+
+    t.test(
+        "profile rejects a zero tick count",
+        "profile 0",
+        vec!["Profile duration must be between 1 and 10000 ticks."],
+    )
+    .await;
+
+    t.test(
+        "start a profile capture",
+        "profile 50",
+        vec!["Profiling the next 50 ticks."],
+    )
+    .await;
+
+    t.test(
+        "only one capture may run at a time",
+        "profile 5",
+        vec!["A profile is already being captured."],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains("Profile complete").await;
+    t.assert_prompt().await;
+}
+
+#[tokio::test]
+async fn test_config_reload() {
+    let (_server, mut t) = Server::new_create_player("krixi", "password").await;
+
+    t.test(
+        "config requires a subcommand",
+        "config",
+        vec!["Enter a config subcommand: reload."],
+    )
+    .await;
+
+    t.test(
+        "reload the configuration",
+        "config reload",
+        vec!["Reloading configuration."],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains(
+        "Failed to reload configuration: no configuration file was provided at startup",
+    )
+    .await;
+    t.assert_prompt().await;
+}
+
+#[tokio::test]
+async fn test_db_check() {
+    let (_server, mut t) = Server::new_create_player("krixi", "password").await;
+
+    t.test(
+        "db requires a subcommand",
+        "db",
+        vec!["Enter a db subcommand: check."],
+    )
+    .await;
+
+    t.test(
+        "db check rejects unknown options",
+        "db check everything",
+        vec!["Enter a valid db check option: repair."],
+    )
+    .await;
+
+    t.test(
+        "check the database",
+        "db check",
+        vec!["Checking the database."],
+    )
+    .await;
+
+    t.consume_prompt().await;
+    t.line_contains("Database check found no issues.").await;
+    t.assert_prompt().await;
+}
+
+#[tokio::test]
+async fn test_lint_world() {
+    let (server, mut t) = Server::new_create_player("krixi", "password").await;
+
+    t.test(
+        "lint requires a subcommand",
+        "lint",
+        vec!["Enter a lint subcommand: world."],
+    )
+    .await;
+
+    t.command("dig a room", "room new north").await;
+    t.command("create a room with no way in", "room new").await;
+
+    t.test("lint the world", "lint world", vec!["Linting the world."])
+        .await;
+
+    t.consume_prompt().await;
+    t.line_contains("World lint found 4 issues:").await;
+    t.line_contains("room 2: has no exits").await;
+    t.line_contains("room 1: has no description").await;
+    t.line_contains("room 2: has no description").await;
+    t.line_contains("room 2: cannot be reached from the spawn room")
+        .await;
+    t.assert_prompt().await;
+
+    let web = server.login_web(&t).await;
+    let report = web.lint_world().await.unwrap();
+    let findings = report["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 4);
+    assert_eq!(findings[0]["kind"], "no_exits");
+    assert_eq!(findings[0]["subject"], "room 2");
+}
+
+#[tokio::test]
+async fn test_maintenance() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "password").await;
+    let mut shane = server.create_player("Shane", "password").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "maintenance requires a subcommand",
+        "maintenance",
+        vec!["Enter a maintenance subcommand: on or off."],
+    )
+    .await;
+
+    t.test(
+        "turn on maintenance mode",
+        "maintenance on",
+        vec!["Maintenance mode enabled. Only immortals may log in."],
+    )
+    .await;
+
+    let mut rejected = server.connect_telnet();
+    rejected.line_contains("Connected to").await;
+    rejected.line_contains("down for maintenance").await;
+    rejected.line_contains("Name?").await;
+    rejected.assert_prompt().await;
+    rejected
+        .test(
+            "mortals cannot log in",
+            "Ada",
+            vec!["down for maintenance", "Name?"],
+        )
+        .await;
+
+    t.test(
+        "start a countdown",
+        "maintenance on 2",
+        vec!["Maintenance mode enabled. Mortals will be disconnected in 2 seconds."],
+    )
+    .await;
+
+    shane.consume_prompt().await;
+    shane
+        .line_contains("City Six is going down for maintenance in 2 seconds.")
+        .await;
+    shane.assert_prompt().await;
+
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    t.consume_prompt().await;
+    t.line_contains("Shane leaves.").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "turn off maintenance mode",
+        "maintenance off",
+        vec!["Maintenance mode disabled."],
+    )
+    .await;
+
+    t.test(
+        "maintenance mode is already off",
+        "maintenance off",
+        vec!["Maintenance mode is already off."],
+    )
+    .await;
+
+    // Shane can only log in again once the countdown has disconnected him.
+    let _shane = server.login_player("Shane", "password").await;
+    drop(shane);
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "turn maintenance mode back on",
+        "maintenance on",
+        vec!["Maintenance mode enabled."],
+    )
+    .await;
+
+    t.send("restart").await;
+    drop(t);
+    server.ready().await;
+
+    // Maintenance mode survives the restart, but immortals may still log in.
+    let mut t = server.connect_telnet();
+    t.line_contains("Connected to").await;
+    t.line_contains("down for maintenance").await;
+    t.line_contains("Name?").await;
+    t.assert_prompt().await;
+    t.test("enter name", "krixi", vec!["User located.", "Password?"])
+        .await;
+    t.test(
+        "enter password",
+        "password",
+        vec!["Password verified.", "Welcome to City Six."],
+    )
+    .await;
+}
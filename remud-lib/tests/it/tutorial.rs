@@ -0,0 +1,117 @@
+use crate::support::Server;
+
+#[tokio::test]
+async fn test_tutorial() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "password").await;
+
+    t.command("create the tutorial room", "room new north")
+        .await;
+    t.test(
+        "set the tutorial start room",
+        "tutorial start 1",
+        vec!["New characters will begin in room 1."],
+    )
+    .await;
+    t.test(
+        "hint triggers are validated",
+        "tutorial hint dance Try dancing.",
+        vec!["Enter a hint trigger: look, move, say, get, drop, inventory."],
+    )
+    .await;
+    t.test(
+        "set a hint",
+        "tutorial hint look Use look at <keywords> to examine things.",
+        vec!["Updated the look hint."],
+    )
+    .await;
+    t.test(
+        "show the tutorial",
+        "tutorial",
+        vec!["Tutorial", "room 1", "look: Use look at"],
+    )
+    .await;
+
+    let mut shane = server.create_player("Shane", "password").await;
+
+    shane
+        .test(
+            "the first look shows a hint",
+            "look",
+            vec!["Hint: Use look at <keywords> to examine things."],
+        )
+        .await;
+    shane
+        .test_exclude("hints are only shown once", "look", vec!["Hint:"])
+        .await;
+    shane
+        .test("hints start on", "hints", vec!["Hints are on."])
+        .await;
+    shane
+        .test("turn hints off", "hints off", vec!["Hints off."])
+        .await;
+    shane
+        .test("hints stay off", "hints", vec!["Hints are off."])
+        .await;
+
+    t.test(
+        "clear the tutorial start room",
+        "tutorial start clear",
+        vec!["New characters will begin in the spawn room."],
+    )
+    .await;
+    t.test(
+        "remove a hint",
+        "tutorial hint look clear",
+        vec!["Removed the look hint."],
+    )
+    .await;
+    t.test(
+        "removing a missing hint",
+        "tutorial hint look clear",
+        vec!["There is no look hint."],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_tutorial_persists() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "password").await;
+
+    t.command("create the tutorial room", "room new north")
+        .await;
+    t.command("set the tutorial start room", "tutorial start 1")
+        .await;
+    t.command("set a hint", "tutorial hint look Try look at <keywords>.")
+        .await;
+    t.command(
+        "set another hint",
+        "tutorial hint say Others nearby hear you.",
+    )
+    .await;
+
+    let mut shane = server.create_player("Shane", "password").await;
+    shane
+        .test("see the look hint", "look", vec!["Hint: Try look at"])
+        .await;
+    drop(shane);
+
+    let mut t = server.restart(t).await;
+    t.test(
+        "the tutorial survives a restart",
+        "tutorial",
+        vec!["room 1", "look: Try look at", "say: Others nearby"],
+    )
+    .await;
+
+    let mut shane = server.login_player("Shane", "password").await;
+    shane
+        .test_exclude("seen hints survive a restart", "look", vec!["Hint:"])
+        .await;
+    shane
+        .test(
+            "unseen hints are still shown",
+            "say hi",
+            vec!["Hint: Others nearby"],
+        )
+        .await;
+}
@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use crate::support::{Server, StatusCode};
+use crate::support::{JsonScript, Server, StatusCode, Trigger};
 
 #[tokio::test]
 async fn test_web_auth_success() {
@@ -81,3 +81,135 @@ async fn test_web_auth_old_refresh_kills_tokens() {
         e => panic!("expected unauthorized, got: {:?}", e),
     }
 }
+
+#[tokio::test]
+async fn test_web_auth_access_token_expires() {
+    let mut server = Server::new_with_manual_clock().await;
+    let t = server.create_player("Shane", "p@55w0rd").await;
+    let mut web = server.login_web(&t).await;
+
+    web.list_scripts().await.unwrap();
+
+    // Access tokens last an hour
+    server.advance(Duration::from_secs(2 * 60 * 60));
+
+    match web.list_scripts().await {
+        Err(StatusCode::UNAUTHORIZED) => (),
+        e => panic!("expected unauthorized, got {:?}", e),
+    }
+
+    // Refresh tokens are still good, and issue new access tokens from the advanced clock
+    web.refresh_auth().await.unwrap();
+    web.list_scripts().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_web_auth_api_tokens() {
+    let (mut server, t) = Server::new_create_player("krixi", "password").await;
+    let t2 = server.create_player("Shane", "some pw").await;
+    let admin = server.login_web(&t).await;
+    let web = server.login_web(&t2).await;
+
+    // Only immortals may manage API tokens
+    assert_eq!(
+        web.create_api_token("ci-bot", &["scripts:read"]).await,
+        Err(StatusCode::UNAUTHORIZED)
+    );
+
+    assert_eq!(
+        admin.create_api_token("CI Bot", &["scripts:read"]).await,
+        Err(StatusCode::BAD_REQUEST)
+    );
+    assert_eq!(
+        admin.create_api_token("ci-bot", &["world"]).await,
+        Err(StatusCode::BAD_REQUEST)
+    );
+    assert_eq!(
+        admin.create_api_token("ci-bot", &[]).await,
+        Err(StatusCode::BAD_REQUEST)
+    );
+
+    let token = admin
+        .create_api_token("ci-bot", &["scripts:read", "scripts:write"])
+        .await
+        .unwrap();
+    assert_eq!(
+        admin.create_api_token("ci-bot", &["world:read"]).await,
+        Err(StatusCode::CONFLICT)
+    );
+
+    let bot = server.connect_web().with_api_token(token.as_str());
+    bot.create_script_expect_ok(&JsonScript::new("synced", Trigger::Say, "let x = 1;"))
+        .await;
+    assert_eq!(bot.list_scripts().await.unwrap().len(), 1);
+
+    // Tokens only grant their scopes and never act as a player
+    assert_eq!(
+        bot.lint_world().await.unwrap_err(),
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        bot.export_player().await.unwrap_err(),
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        bot.create_api_token("other", &["scripts:read"]).await,
+        Err(StatusCode::UNAUTHORIZED)
+    );
+
+    let forged = format!("{}x", token);
+    let forged = server.connect_web().with_api_token(forged.as_str());
+    assert_eq!(
+        forged.list_scripts().await.unwrap_err(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    let tokens = admin.api_tokens().await.unwrap();
+    assert_eq!(tokens["tokens"][0]["name"], "ci-bot");
+    assert_eq!(tokens["tokens"][0]["created_by"], "krixi");
+    assert_eq!(tokens["tokens"][0]["scopes"][1], "scripts:write");
+    assert!(tokens["tokens"][0]["last_used_at"].is_i64());
+    assert!(tokens["tokens"][0].get("hash").is_none());
+
+    admin.revoke_api_token("ci-bot").await.unwrap();
+    assert_eq!(
+        bot.list_scripts().await.unwrap_err(),
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        admin.revoke_api_token("ci-bot").await,
+        Err(StatusCode::NOT_FOUND)
+    );
+}
+
+#[tokio::test]
+async fn test_web_auth_rate_limits() {
+    let mut server = Server::new_with_config(
+        "[web]\nauth_per_minute = 1\nauth_burst = 2\nwrite_per_minute = 1\nwrite_burst = 1\n",
+    )
+    .await;
+    let t = server.create_player("krixi", "password").await;
+
+    // Failed logins count against the address too
+    match server.connect_web().login("krixi", "wrong").await {
+        Err(StatusCode::UNAUTHORIZED) => (),
+        _ => panic!("expected unauthorized"),
+    }
+    let web = server.login_web(&t).await;
+    match server.connect_web().login("krixi", "password").await {
+        Err(StatusCode::TOO_MANY_REQUESTS) => (),
+        _ => panic!("expected too many requests"),
+    }
+
+    web.create_script_expect_ok(&JsonScript::new("one", Trigger::Say, "let x = 1;"))
+        .await;
+    assert_eq!(
+        web.create_script(&JsonScript::new("two", Trigger::Say, "let x = 1;"))
+            .await
+            .unwrap_err(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+
+    // Reads aren't limited
+    assert_eq!(web.list_scripts().await.unwrap().len(), 1);
+}
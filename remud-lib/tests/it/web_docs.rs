@@ -0,0 +1,47 @@
+use crate::support::{Server, StatusCode};
+
+#[tokio::test]
+async fn test_web_docs_disabled() {
+    let server = Server::new().await;
+    let web = server.connect_web();
+
+    match web.get("/api/docs/openapi.json").await {
+        Err(StatusCode::NOT_FOUND) => (),
+        e => panic!("expected not found, got: {:?}", e),
+    }
+}
+
+#[tokio::test]
+async fn test_web_docs_enabled() {
+    let server = Server::new_with_config("[web]\ndocs = true\n").await;
+    let web = server.connect_web();
+
+    let spec: serde_json::Value =
+        serde_json::from_str(web.get("/api/docs/openapi.json").await.unwrap().as_str()).unwrap();
+    for path in [
+        "/auth/login",
+        "/players/{name}/achievements",
+        "/players/me/export",
+        "/players/erasures/approve",
+        "/leaderboards/{category}",
+        "/status",
+        "/healthz",
+        "/readyz",
+        "/market",
+        "/news",
+        "/polls",
+        "/scripts/create",
+        "/scripts/update",
+        "/scripts/stats",
+        "/allowlist/read",
+        "/graphql",
+        "/ws/play",
+        "/ws/admin",
+    ] {
+        assert!(spec["paths"].get(path).is_some(), "missing {}", path);
+    }
+    assert!(spec["components"]["schemas"].get("JsonScript").is_some());
+
+    let ui = web.get("/api/docs").await.unwrap();
+    assert!(ui.contains("swagger-ui"));
+}
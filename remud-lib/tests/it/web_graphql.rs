@@ -0,0 +1,65 @@
+use crate::support::{JsonScript, Server, Trigger};
+
+#[tokio::test]
+async fn test_web_graphql_nested_query() {
+    let (server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let web = server.login_web(&t).await;
+
+    web.create_script(&JsonScript::new("purr", Trigger::Look, "let x = 1;"))
+        .await
+        .unwrap();
+
+    t.command("create prototype", "prototype new").await;
+    t.command("name prototype", "prototype 1 name Chonky Cat")
+        .await;
+    t.command("attach script", "script purr attach-pre prototype 1")
+        .await;
+    t.command("spawn object", "object new 1").await;
+
+    let response = web
+        .graphql(
+            r#"{
+                playersOnline {
+                    name
+                    room {
+                        contents {
+                            id
+                            name
+                            prototype {
+                                id
+                                scripts { kind event script { name revision } }
+                            }
+                        }
+                    }
+                }
+            }"#,
+        )
+        .await
+        .unwrap();
+
+    assert!(response.get("errors").is_none(), "{}", response);
+
+    let player = &response["data"]["playersOnline"][0];
+    assert_eq!(player["name"], "krixi");
+
+    let object = &player["room"]["contents"][0];
+    assert_eq!(object["id"], "1");
+    assert_eq!(object["name"], "Chonky Cat");
+    assert_eq!(object["prototype"]["id"], "1");
+
+    let hook = &object["prototype"]["scripts"][0];
+    assert_eq!(hook["kind"], "PreEvent");
+    assert_eq!(hook["event"], "Look");
+    assert_eq!(hook["script"]["name"], "purr");
+    assert_eq!(hook["script"]["revision"], 1);
+}
+
+#[tokio::test]
+async fn test_web_graphql_unknown_field() {
+    let (server, t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+    let web = server.login_web(&t).await;
+
+    let response = web.graphql("{ rooms { secret } }").await.unwrap();
+
+    assert!(response.get("errors").is_some());
+}
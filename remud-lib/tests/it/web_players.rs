@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use crate::support::{Server, StatusCode};
+
+#[tokio::test]
+async fn test_web_players_export() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "password").await;
+    let mut t2 = server.create_player("Shane", "some pw").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "send Shane a message",
+        "send Shane Hi Shane!",
+        vec!["Your term chirps happily"],
+    )
+    .await;
+
+    t2.consume_prompt().await;
+    t2.line_contains(r#"krixi sends "Hi Shane!""#).await;
+
+    let web = server.login_web(&t2).await;
+    let export = web.export_player().await.unwrap();
+    assert_eq!(export["name"], "Shane");
+    assert!(export["inventory"].as_array().unwrap().is_empty());
+    assert!(export["sessions"].is_array());
+    assert_eq!(export["communication"][0]["speaker"], "krixi");
+    assert_eq!(
+        export["communication"][0]["message"],
+        r#"krixi sends "Hi Shane!""#
+    );
+}
+
+#[tokio::test]
+async fn test_web_players_erasure() {
+    let (mut server, t) = Server::new_create_player("krixi", "password").await;
+    let mut t2 = server.create_player("Shane", "some pw").await;
+
+    let admin = server.login_web(&t).await;
+    let web = server.login_web(&t2).await;
+
+    assert_eq!(
+        admin.approve_erasure("Shane").await,
+        Err(StatusCode::NOT_FOUND)
+    );
+    assert_eq!(
+        web.erasure_requests().await.unwrap_err(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    web.request_erasure().await.unwrap();
+    web.request_erasure().await.unwrap();
+
+    let requests = admin.erasure_requests().await.unwrap();
+    assert_eq!(requests["requests"].as_array().unwrap().len(), 1);
+    assert_eq!(requests["requests"][0]["name"], "Shane");
+
+    assert_eq!(
+        admin.approve_erasure("no!").await,
+        Err(StatusCode::BAD_REQUEST)
+    );
+    admin.approve_erasure("Shane").await.unwrap();
+
+    t2.disconnected_with("Your character has been erased.").await;
+
+    drop(t2);
+    std::thread::sleep(Duration::from_secs(1));
+
+    let mut t2 = server.connect_telnet();
+    t2.line_contains("Connected to").await;
+    t2.line_contains("Name?").await;
+    t2.assert_prompt().await;
+
+    t2.test(
+        "enter the name of the erased player",
+        "Shane",
+        vec!["User located.", "Password?"],
+    )
+    .await;
+
+    t2.test(
+        "erased players may not log in",
+        "some pw",
+        vec!["This character has been deleted."],
+    )
+    .await;
+}
@@ -65,7 +65,9 @@ async fn test_web_script_read() {
             name,
             trigger,
             code,
+            revision,
             error,
+            ..
         }) => {
             assert_eq!(name.as_str(), S1_NAME);
             assert!(matches!(
@@ -73,6 +75,7 @@ async fn test_web_script_read() {
                 Trigger::Init
             ));
             assert_eq!(code.as_str(), S1_CODE);
+            assert_eq!(revision, 1);
             assert!(error.is_none());
         }
         Err(_) => panic!("expected script response"),
@@ -91,7 +94,9 @@ async fn test_web_script_read() {
             name,
             trigger,
             code,
+            revision,
             error,
+            ..
         }) => {
             assert_eq!(name.as_str(), S2_NAME);
             assert!(matches!(
@@ -99,6 +104,7 @@ async fn test_web_script_read() {
                 Trigger::Init
             ));
             assert_eq!(code.as_str(), S2_CODE);
+            assert_eq!(revision, 1);
             assert!(error.is_some());
         }
         Err(_) => panic!("expected script response"),
@@ -115,7 +121,7 @@ async fn test_web_script_update() {
 
     // test update nonexistent
     match web
-        .update_script(&JsonScript::new(S1_NAME, Trigger::Init, S1_CODE))
+        .update_script(&JsonScript::new(S1_NAME, Trigger::Init, S1_CODE).with_revision(1))
         .await
     {
         Err(StatusCode::NOT_FOUND) => (),
@@ -134,13 +140,13 @@ async fn test_web_script_update() {
     const BAD_CODE: &'static str = "kj asldjkf kjlasdfj sdf ;;;;;;;;";
 
     // update script with code that doesn't compile
-    match web
-        .update_script(&JsonScript::new(S1_NAME, Trigger::Init, BAD_CODE))
-        .await
-    {
-        Ok(response) => assert!(response.error.is_some()),
-        _ => panic!("expected no errors"),
-    }
+    let response = web
+        .update_and_assert_error_line(
+            &JsonScript::new(S1_NAME, Trigger::Init, BAD_CODE).with_revision(1),
+            1,
+        )
+        .await;
+    assert_eq!(response.revision, 2);
 
     // confirm script reads as expected
     match web.read_script(&JsonScriptName::from(S1_NAME)).await {
@@ -148,7 +154,9 @@ async fn test_web_script_update() {
             name,
             trigger,
             code,
+            revision,
             error,
+            ..
         }) => {
             assert_eq!(name.as_str(), S1_NAME);
             assert!(matches!(
@@ -156,6 +164,7 @@ async fn test_web_script_update() {
                 Trigger::Init
             ));
             assert_eq!(code.as_str(), BAD_CODE);
+            assert_eq!(revision, 2);
             assert!(error.is_some());
         }
         Err(_) => panic!("expected script response"),
@@ -165,10 +174,13 @@ async fn test_web_script_update() {
 
     // update script with code that compiles again
     match web
-        .update_script(&JsonScript::new(S1_NAME, Trigger::Init, GOOD_CODE))
+        .update_script(&JsonScript::new(S1_NAME, Trigger::Init, GOOD_CODE).with_revision(2))
         .await
     {
-        Ok(response) => assert!(response.error.is_none()),
+        Ok(response) => {
+            assert_eq!(response.revision, 3);
+            assert!(response.error.is_none());
+        }
         _ => panic!("expected no errors"),
     }
 
@@ -178,7 +190,9 @@ async fn test_web_script_update() {
             name,
             trigger,
             code,
+            revision,
             error,
+            ..
         }) => {
             assert_eq!(name.as_str(), S1_NAME);
             assert!(matches!(
@@ -186,10 +200,88 @@ async fn test_web_script_update() {
                 Trigger::Init
             ));
             assert_eq!(code.as_str(), GOOD_CODE);
+            assert_eq!(revision, 3);
             assert!(error.is_none());
         }
         Err(_) => panic!("expected script response"),
     }
+
+    // disable the script, leaving its code as it is
+    match web
+        .update_script(
+            &JsonScript::new(S1_NAME, Trigger::Init, GOOD_CODE)
+                .with_revision(3)
+                .with_enabled(false),
+        )
+        .await
+    {
+        Ok(response) => assert_eq!(response.revision, 4),
+        _ => panic!("expected no errors"),
+    }
+
+    match web.read_script(&JsonScriptName::from(S1_NAME)).await {
+        Ok(JsonScriptResponse { enabled, .. }) => assert!(!enabled),
+        Err(_) => panic!("expected script response"),
+    }
+
+    match web.query_scripts("?enabled=false").await {
+        Ok(response) => {
+            assert_eq!(response.total, 1);
+            assert!(!response.scripts[0].enabled);
+        }
+        Err(_) => panic!("expected script list"),
+    }
+}
+
+#[tokio::test]
+async fn test_web_script_update_conflict() {
+    let (server, t) = Server::new_create_player("Shane", "p@55w0rd").await;
+    let web = server.login_web(&t).await;
+
+    const S1_NAME: &'static str = "ts_1";
+    const S1_CODE: &'static str = "let x = 1;";
+    const S1_EDIT_A: &'static str = "let x = 2;";
+    const S1_EDIT_B: &'static str = "let x = 3;";
+
+    web.create_script(&JsonScript::new(S1_NAME, Trigger::Init, S1_CODE))
+        .await
+        .unwrap();
+
+    // updates without an expected revision are refused
+    match web
+        .update_script(&JsonScript::new(S1_NAME, Trigger::Init, S1_EDIT_A))
+        .await
+    {
+        Err(StatusCode::PRECONDITION_REQUIRED) => (),
+        e => panic!("expected precondition required, got: {:?}", e),
+    }
+
+    // first editor saves against revision 1
+    web.update_script(&JsonScript::new(S1_NAME, Trigger::Init, S1_EDIT_A).with_revision(1))
+        .await
+        .unwrap();
+
+    // second editor also started from revision 1 and receives the current version
+    match web
+        .update_script_conflict(
+            &JsonScript::new(S1_NAME, Trigger::Init, S1_EDIT_B).with_revision(1),
+        )
+        .await
+    {
+        Some(JsonScriptResponse { code, revision, .. }) => {
+            assert_eq!(code.as_str(), S1_EDIT_A);
+            assert_eq!(revision, 2);
+        }
+        None => panic!("expected revision conflict"),
+    }
+
+    // the conflicting edit was not applied
+    let script = web
+        .read_script(&JsonScriptName::from(S1_NAME))
+        .await
+        .unwrap();
+    assert_eq!(script.code.as_str(), S1_EDIT_A);
+    assert_eq!(script.revision, 2);
 }
 
 #[tokio::test]
@@ -218,3 +310,241 @@ async fn test_web_script_delete() {
         .await
         .unwrap();
 }
+
+#[tokio::test]
+async fn test_web_script_list_query() {
+    let (server, t) = Server::new_create_player("Shane", "p@55w0rd").await;
+    let web = server.login_web(&t).await;
+
+    const GOOD_CODE: &'static str = "let x = 1;";
+    const LONG_CODE: &'static str = "let x = 1;\nlet y = 2;\nlet z = 3;";
+    const BAD_CODE: &'static str = "kj asldjkf kjlasdfj sdf ;;;;;;;;";
+
+    for (name, trigger, code) in [
+        ("greet_say", Trigger::Say, GOOD_CODE),
+        ("greet_look", Trigger::Look, LONG_CODE),
+        ("broken_say", Trigger::Say, BAD_CODE),
+        ("farewell_say", Trigger::Say, GOOD_CODE),
+    ] {
+        web.create_script(&JsonScript::new(name, trigger, code))
+            .await
+            .unwrap();
+    }
+
+    // no parameters returns everything
+    let list = web.query_scripts("").await.unwrap();
+    assert_eq!(list.total, 4);
+    assert_eq!(list.scripts.len(), 4);
+
+    // filter by trigger and sort by name
+    let list = web.query_scripts("?trigger=Say&sort=name").await.unwrap();
+    let names: Vec<&str> = list.scripts.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["broken_say", "farewell_say", "greet_say"]);
+
+    // filter by compilation errors
+    let list = web.query_scripts("?error=true").await.unwrap();
+    assert_eq!(list.total, 1);
+    assert_eq!(list.scripts[0].name.as_str(), "broken_say");
+
+    // search by name, sorted by descending line count
+    let list = web.query_scripts("?q=GREET&sort=-lines").await.unwrap();
+    let names: Vec<&str> = list.scripts.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["greet_look", "greet_say"]);
+
+    // paginate
+    let list = web
+        .query_scripts("?sort=name&limit=3&page=2")
+        .await
+        .unwrap();
+    assert_eq!(list.total, 4);
+    assert_eq!(list.page, 2);
+    assert_eq!(list.limit, Some(3));
+    assert_eq!(list.scripts.len(), 1);
+    assert_eq!(list.scripts[0].name.as_str(), "greet_say");
+
+    // bad parameters are rejected
+    for query in ["?trigger=Nope", "?sort=size", "?page=0", "?limit=abc"] {
+        match web.query_scripts(query).await {
+            Err(StatusCode::BAD_REQUEST) => (),
+            e => panic!("expected bad request for {}, got: {:?}", query, e),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_web_script_tags() {
+    let (server, mut t) = Server::new_create_player("Shane", "p@55w0rd").await;
+    let web = server.login_web(&t).await;
+
+    const CODE: &'static str = "let x = 1;";
+
+    for script in [
+        JsonScript::new("town_greet", Trigger::Say, CODE)
+            .with_tags(&["Quest", "npc"])
+            .with_folder("/areas/town/"),
+        JsonScript::new("town_shop", Trigger::Say, CODE)
+            .with_tags(&["npc"])
+            .with_folder("areas/town/shops"),
+        JsonScript::new("forest_quest", Trigger::Look, CODE)
+            .with_tags(&["quest"])
+            .with_folder("areas/forest"),
+        JsonScript::new("loose", Trigger::Init, CODE),
+    ] {
+        web.create_script(&script).await.unwrap();
+    }
+
+    // tags are normalized and folders trimmed
+    let script = web
+        .read_script(&JsonScriptName::from("town_greet"))
+        .await
+        .unwrap();
+    assert_eq!(script.tags, vec!["quest", "npc"]);
+    assert_eq!(script.folder.as_deref(), Some("areas/town"));
+
+    // filter by tag
+    let list = web.query_scripts("?tag=quest&sort=name").await.unwrap();
+    let names: Vec<&str> = list.scripts.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["forest_quest", "town_greet"]);
+
+    // filter by folder, including subfolders
+    let list = web
+        .query_scripts("?folder=areas/town&sort=name")
+        .await
+        .unwrap();
+    let names: Vec<&str> = list.scripts.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["town_greet", "town_shop"]);
+
+    // a folder prefix which is not a whole path part does not match
+    let list = web.query_scripts("?folder=areas/to").await.unwrap();
+    assert_eq!(list.total, 0);
+
+    // updates without tags or a folder leave them unchanged
+    web.update_script(&JsonScript::new("town_shop", Trigger::Say, "let y = 2;").with_revision(1))
+        .await
+        .unwrap();
+    let script = web
+        .read_script(&JsonScriptName::from("town_shop"))
+        .await
+        .unwrap();
+    assert_eq!(script.tags, vec!["npc"]);
+    assert_eq!(script.folder.as_deref(), Some("areas/town/shops"));
+
+    // an empty folder moves the script to the top level
+    web.update_script(
+        &JsonScript::new("town_shop", Trigger::Say, "let y = 2;")
+            .with_revision(2)
+            .with_tags(&[])
+            .with_folder(""),
+    )
+    .await
+    .unwrap();
+    let script = web
+        .read_script(&JsonScriptName::from("town_shop"))
+        .await
+        .unwrap();
+    assert!(script.tags.is_empty());
+    assert_eq!(script.folder, None);
+
+    // bad tags are rejected
+    match web
+        .create_script(&JsonScript::new("bad_tag", Trigger::Say, CODE).with_tags(&["a,b"]))
+        .await
+    {
+        Err(StatusCode::BAD_REQUEST) => (),
+        e => panic!("expected bad request, got: {:?}", e),
+    }
+
+    t.test(
+        "list scripts by tag",
+        "scripts list tag:quest",
+        vec![
+            "Scripts",
+            "Name",
+            "forest_quest",
+            "areas/forest",
+            "town_greet",
+            "areas/town",
+            "quest, npc",
+        ],
+    )
+    .await;
+
+    t.test(
+        "list scripts by folder",
+        "scripts list folder:areas",
+        vec!["forest_quest", "town_greet"],
+    )
+    .await;
+
+    t.test_exclude(
+        "list scripts by folder excludes others",
+        "scripts list folder:areas",
+        vec!["town_shop", "loose"],
+    )
+    .await;
+
+    t.test(
+        "list scripts with bad filter",
+        "scripts list size:3",
+        vec!["Enter a valid filter"],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_web_script_stats() {
+    let (server, mut t) = Server::new_create_player("Shane", "p@55w0rd").await;
+    let web = server.login_web(&t).await;
+
+    for (name, code) in [
+        (
+            "broken",
+            r#"if WORLD.is_player(EVENT.actor) { throw "oops"; }"#,
+        ),
+        (
+            "greeter",
+            r#"if WORLD.is_player(EVENT.actor) { let greeting = "hello"; }"#,
+        ),
+    ] {
+        web.create_script_expect_ok(&JsonScript::new(name, Trigger::Say, code))
+            .await;
+    }
+
+    let stats = web.script_stats().await.unwrap();
+    assert!(stats.scripts.is_empty());
+    assert_eq!(stats.slow_threshold_us, 5000);
+
+    t.command("create prototype", "prototype new").await;
+    t.command("attach broken", "scripts broken attach-pre prototype 1")
+        .await;
+    t.command("attach greeter", "scripts greeter attach-pre prototype 1")
+        .await;
+    t.command("create object", "object new 1").await;
+
+    // pre-event scripts have run by the time the action is carried out
+    t.test("greet object", "say hi", vec!["You say"]).await;
+
+    let stats = web.script_stats().await.unwrap();
+    assert_eq!(stats.scripts.len(), 2);
+    for script in stats.scripts.iter() {
+        assert_eq!(script.runs, 1);
+        assert_eq!(script.mean_us, script.total_us);
+        match script.name.as_str() {
+            "broken" => assert_eq!(script.errors, 1),
+            "greeter" => assert_eq!(script.errors, 0),
+            other => panic!("unexpected script {}", other),
+        }
+    }
+
+    t.test("list stats", "scripts stats", vec!["broken", "greeter"])
+        .await;
+
+    // changing a script's code resets its timings
+    web.update_script(&JsonScript::new("greeter", Trigger::Say, "let x = 1;").with_revision(1))
+        .await
+        .unwrap();
+
+    let stats = web.script_stats().await.unwrap();
+    assert_eq!(stats.scripts.len(), 1);
+    assert_eq!(stats.scripts[0].name.as_str(), "broken");
+}
@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use crate::support::{Server, StatusCode};
+
+#[tokio::test]
+async fn test_web_status() {
+    let (mut server, mut t) = Server::new_create_player("krixi", "(*&%(*#&%*&").await;
+
+    t.test("stats include total playtime", "stats", vec!["Played: "])
+        .await;
+
+    let t2 = server.create_player("Shane", "lkja;jf89 f").await;
+
+    t.consume_prompt().await;
+    t.line_contains("Shane arrives.").await;
+    t.assert_prompt().await;
+
+    t.test(
+        "player info includes playtime",
+        "player Shane info",
+        vec!["playtime: "],
+    )
+    .await;
+
+    let web = server.connect_web();
+
+    let status: serde_json::Value =
+        serde_json::from_str(web.get("/status").await.unwrap().as_str()).unwrap();
+    assert_eq!(status["players"], 2);
+    assert_eq!(status["players_online"], 2);
+    assert_eq!(status["sessions_last_day"], 2);
+    assert_eq!(status["unique_players_last_day"], 2);
+
+    drop(t2);
+
+    t.consume_prompt().await;
+    t.line_contains("Shane leaves.").await;
+    t.assert_prompt().await;
+
+    // Give the session's end time to be persisted.
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    let status: serde_json::Value =
+        serde_json::from_str(web.get("/status").await.unwrap().as_str()).unwrap();
+    assert_eq!(status["players_online"], 1);
+    assert_eq!(status["sessions_last_day"], 2);
+}
+
+#[tokio::test]
+async fn test_web_health() {
+    let server = Server::new().await;
+    let web = server.connect_web();
+
+    let health: serde_json::Value =
+        serde_json::from_str(web.get("/healthz").await.unwrap().as_str()).unwrap();
+    assert_eq!(health["ok"], true);
+
+    // Give the engine time to finish its first tick.
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    let ready: serde_json::Value =
+        serde_json::from_str(web.get("/readyz").await.unwrap().as_str()).unwrap();
+    assert_eq!(ready["ok"], true);
+    assert_eq!(ready["engine"], true);
+    assert_eq!(ready["database"], true);
+}
+
+#[tokio::test]
+async fn test_web_request_id() {
+    let server = Server::new().await;
+    let web = server.connect_web();
+
+    let (status, generated) = web.get_request_id("/status", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let generated = generated.unwrap();
+    assert_eq!(generated.len(), 16);
+
+    // Each request gets its own ID
+    let (_, other) = web.get_request_id("/status", None).await;
+    assert_ne!(other.unwrap(), generated);
+
+    // IDs sent by clients are kept, and echoed in errors
+    let (status, echoed) = web.get_request_id("/nowhere", Some("trace-123")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(echoed.as_deref(), Some("trace-123"));
+
+    // Unsafe IDs are replaced
+    let (_, replaced) = web.get_request_id("/status", Some("a b")).await;
+    assert_ne!(replaced.as_deref(), Some("a b"));
+}
@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use crate::support::{Server, StatusCode};
+
+#[tokio::test]
+async fn test_web_ws_play_without_token() {
+    let server = Server::new().await;
+    let web = server.connect_web();
+
+    assert_eq!(
+        web.upgrade("/ws/play").await,
+        StatusCode::SWITCHING_PROTOCOLS
+    );
+    assert_eq!(web.upgrade("/ws").await, StatusCode::SWITCHING_PROTOCOLS);
+}
+
+#[tokio::test]
+async fn test_web_ws_play_invalid_token() {
+    let server = Server::new().await;
+    let web = server.connect_web();
+
+    assert_eq!(
+        web.upgrade("/ws/play?token=garbage").await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[tokio::test]
+async fn test_web_ws_play_token() {
+    let (server, t) = Server::new_create_player("Shane", "p@55w0rd").await;
+    let web = server.login_web(&t).await;
+
+    assert_eq!(
+        web.upgrade("/ws/play").await,
+        StatusCode::SWITCHING_PROTOCOLS
+    );
+
+    let path = format!("/ws/play?token={}", web.access_token());
+    assert_eq!(
+        server.connect_web().upgrade(path.as_str()).await,
+        StatusCode::SWITCHING_PROTOCOLS
+    );
+}
+
+#[tokio::test]
+async fn test_web_ws_admin() {
+    let (mut server, t) = Server::new_create_player("Shane", "p@55w0rd").await;
+    let immortal = server.login_web(&t).await;
+
+    assert_eq!(
+        server.connect_web().upgrade("/ws/admin").await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        immortal.upgrade("/ws/admin").await,
+        StatusCode::SWITCHING_PROTOCOLS
+    );
+
+    let t2 = server.create_player("Ted", "other pw").await;
+    let mortal = server.login_web(&t2).await;
+    assert_eq!(mortal.upgrade("/ws/admin").await, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        mortal.upgrade("/ws/play").await,
+        StatusCode::SWITCHING_PROTOCOLS
+    );
+}
+
+#[tokio::test]
+async fn test_web_ws_resume() {
+    let (server, t) = Server::new_create_player("Shane", "p@55w0rd").await;
+    drop(t);
+    std::thread::sleep(Duration::from_secs(1));
+
+    let mut ws = server.connect_ws("/ws/play").await;
+    let token = ws.resume_token().await;
+    ws.wait_for("Name?").await;
+    ws.send("Shane").await;
+    ws.wait_for("Password?").await;
+    ws.send("p@55w0rd").await;
+    ws.wait_for("Welcome to City Six.").await;
+    drop(ws);
+
+    // the player stays in the world, so the page can reload without logging in again
+    let mut ws = server
+        .connect_ws(format!("/ws/play?resume={}", token).as_str())
+        .await;
+    let next_token = ws.resume_token().await;
+    assert_ne!(token, next_token);
+    ws.wait_for("Session resumed.").await;
+    ws.send("who").await;
+    ws.wait_for("Shane").await;
+    drop(ws);
+
+    // tokens are spent once used
+    let mut ws = server
+        .connect_ws(format!("/ws/play?resume={}", token).as_str())
+        .await;
+    ws.wait_for("Name?").await;
+}
+
+#[tokio::test]
+async fn test_web_ws_config_reload() {
+    let mut server = Server::new_with_config("[web]\ncors = [\"http://one.example\"]\n").await;
+    let mut t = server.create_player("krixi", "password").await;
+    let web = server.connect_web();
+
+    let mut ws = server.connect_ws("/ws/play").await;
+    ws.wait_for("Name?").await;
+
+    assert_eq!(
+        web.get_from_origin("/status", "http://one.example").await,
+        (StatusCode::OK, Some("http://one.example".to_string()))
+    );
+    assert_eq!(
+        web.get_from_origin("/status", "http://two.example").await.0,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        web.get("/api/docs/openapi.json").await,
+        Err(StatusCode::NOT_FOUND)
+    );
+
+    std::fs::write(
+        std::env::temp_dir().join(format!("remud-test-{}.toml", server.web())),
+        "[web]\ncors = [\"http://two.example\"]\ndocs = true\n",
+    )
+    .unwrap();
+    t.test(
+        "reload the web configuration",
+        "config reload",
+        vec!["Reloading configuration."],
+    )
+    .await;
+    t.consume_prompt().await;
+    t.line_contains("Configuration reloaded.").await;
+    t.assert_prompt().await;
+
+    // the changes apply to new requests while connected clients stay connected
+    assert_eq!(
+        web.get_from_origin("/status", "http://one.example").await.0,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        web.get_from_origin("/status", "http://two.example").await,
+        (StatusCode::OK, Some("http://two.example".to_string()))
+    );
+    assert!(web.get("/api/docs/openapi.json").await.is_ok());
+
+    ws.send("Shane").await;
+    ws.wait_for("New user detected.").await;
+}
@@ -0,0 +1,184 @@
+use std::time::{Duration, Instant};
+
+use anyhow::bail;
+use rand::{distributions::WeightedIndex, prelude::*};
+
+use crate::{stats::Stats, telnet::Connection};
+
+const DIRECTIONS: [&str; 6] = ["north", "east", "south", "west", "up", "down"];
+
+const CHATTER: [&str; 5] = [
+    "Hello there.",
+    "Anyone around?",
+    "Nice weather in City Six today.",
+    "Just passing through.",
+    "Has anyone seen the market?",
+];
+
+/// What a bot does each time it acts, and how often.
+#[derive(Debug, Clone)]
+pub struct Behavior {
+    /// Commands sent per second by each bot.
+    pub rate: f64,
+    /// Relative weights of wandering, chatting and looking.
+    pub weights: [u32; 3],
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Activity {
+    Wander,
+    Chat,
+    Look,
+}
+
+/// Where and as whom a bot connects.
+#[derive(Debug, Clone)]
+pub struct Login {
+    pub address: String,
+    pub name: String,
+    pub password: String,
+    pub read_timeout: Duration,
+}
+
+/// Logs a bot in, creating its player if needed, then acts until the deadline. Failures end the
+/// bot early and are counted as errors.
+pub async fn run(login: Login, behavior: Behavior, deadline: Instant) -> Stats {
+    let mut stats = Stats::default();
+
+    if let Err(e) = run_bot(&login, &behavior, deadline, &mut stats).await {
+        tracing::warn!("bot {} stopped: {}", login.name, e);
+        stats.error();
+    }
+
+    stats
+}
+
+async fn run_bot(
+    login: &Login,
+    behavior: &Behavior,
+    deadline: Instant,
+    stats: &mut Stats,
+) -> anyhow::Result<()> {
+    let start = Instant::now();
+    let mut connection = Connection::connect(login.address.as_str(), login.read_timeout).await?;
+    authenticate(&mut connection, login).await?;
+    stats.record("login", start.elapsed());
+
+    let mut rng = StdRng::from_entropy();
+    let activities = WeightedIndex::new(behavior.weights)?;
+    let mut exits: Vec<String> = Vec::new();
+
+    loop {
+        // jitter the delay between commands so bots don't act in lockstep
+        let delay = Duration::from_secs_f64(rng.gen_range(0.5..1.5) / behavior.rate);
+        if Instant::now() + delay >= deadline {
+            break;
+        }
+        tokio::time::sleep(delay).await;
+
+        let (kind, command) =
+            match [Activity::Wander, Activity::Chat, Activity::Look][activities.sample(&mut rng)] {
+                Activity::Wander => match exits.choose(&mut rng) {
+                    Some(direction) => ("move", direction.clone()),
+                    None => ("exits", "exits".to_string()),
+                },
+                Activity::Chat => ("say", format!("say {}", CHATTER.choose(&mut rng).unwrap())),
+                Activity::Look => ("look", "look".to_string()),
+            };
+
+        let sent = Instant::now();
+        connection.send(command.as_str()).await?;
+        let output = connection.read_to_prompt().await?;
+        stats.record(kind, sent.elapsed());
+
+        // moving changes the exits on offer, so they are looked up again before the next move
+        match kind {
+            "exits" => exits = parse_exits(output.as_slice()),
+            "move" => exits.clear(),
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
+
+async fn authenticate(connection: &mut Connection, login: &Login) -> anyhow::Result<()> {
+    expect(connection, "Name?").await?;
+
+    connection.send(login.name.as_str()).await?;
+    let output = connection.read_to_prompt().await?;
+
+    if contains(output.as_slice(), "New user detected.") {
+        connection.send(login.password.as_str()).await?;
+        expect(connection, "Verify?").await?;
+        connection.send(login.password.as_str()).await?;
+    } else if contains(output.as_slice(), "User located.") {
+        connection.send(login.password.as_str()).await?;
+    } else {
+        bail!("unable to log in: {:?}", output);
+    }
+
+    expect(connection, "Welcome to").await
+}
+
+async fn expect(connection: &mut Connection, text: &str) -> anyhow::Result<()> {
+    let output = connection.read_to_prompt().await?;
+    if !contains(output.as_slice(), text) {
+        bail!("expected {:?}, found: {:?}", text, output);
+    }
+    Ok(())
+}
+
+fn contains(output: &[String], text: &str) -> bool {
+    output.iter().any(|line| line.contains(text))
+}
+
+// Picks the directions out of "There are exits north, east, and up.", ignoring color codes.
+fn parse_exits(output: &[String]) -> Vec<String> {
+    output
+        .iter()
+        .filter(|line| line.contains("exit"))
+        .flat_map(|line| {
+            strip_escapes(line)
+                .split(|c: char| !c.is_ascii_alphabetic())
+                .filter(|word| DIRECTIONS.contains(word))
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+// Removes ANSI escape sequences, which run from the escape character to the first letter.
+fn strip_escapes(line: &str) -> String {
+    let mut stripped = String::with_capacity(line.len());
+    let mut escaped = false;
+
+    for c in line.chars() {
+        if escaped {
+            escaped = !c.is_ascii_alphabetic();
+        } else if c == '\u{1b}' {
+            escaped = true;
+        } else {
+            stripped.push(c);
+        }
+    }
+
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_exits() {
+        let output = vec![
+            "A quiet alley runs north.".to_string(),
+            "There are exits \u{1b}[38;5;39mnorth\u{1b}[0m, east, and up.".to_string(),
+        ];
+        assert_eq!(parse_exits(output.as_slice()), vec!["north", "east", "up"]);
+
+        let output = vec!["This room has no obvious exits.".to_string()];
+        assert!(parse_exits(output.as_slice()).is_empty());
+    }
+}
@@ -0,0 +1,222 @@
+mod bot;
+mod stats;
+mod telnet;
+
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use anyhow::bail;
+use clap::{App, Arg, ArgMatches};
+use tracing_subscriber::EnvFilter;
+
+use crate::{
+    bot::{Behavior, Login},
+    stats::Stats,
+};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let matches = App::new("ReMUD load test")
+        .version("0.1")
+        .author("Shaen & krixi - https://github.com/siler/remud")
+        .about(
+            "Connects scripted telnet bots to a ReMUD server which log in, wander, chat and look, \
+             then reports the latency of each command from sending it to the next prompt.",
+        )
+        .arg(
+            Arg::new("bots")
+                .short('b')
+                .long("bots")
+                .default_value("10")
+                .about("Sets the number of bots to connect.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("chat")
+                .long("chat")
+                .default_value("1")
+                .about("Sets the relative weight of chatting among bot activities.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("duration")
+                .short('d')
+                .long("duration")
+                .default_value("60")
+                .about("Sets how long to run for, in seconds.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("host")
+                .long("host")
+                .default_value("127.0.0.1")
+                .about("Sets the host of the server to test.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("look")
+                .long("look")
+                .default_value("1")
+                .about("Sets the relative weight of looking among bot activities.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("password")
+                .long("password")
+                .default_value("loadtest")
+                .about("Sets the password bots create or log in to their players with.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("prefix")
+                .long("prefix")
+                .default_value("loadbot")
+                .about("Sets the prefix of bot player names, which are numbered from 1.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("ramp")
+                .long("ramp")
+                .default_value("100")
+                .about("Sets the delay between connecting each bot, in milliseconds.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("rate")
+                .short('r')
+                .long("rate")
+                .default_value("1.0")
+                .about("Sets the number of commands each bot sends per second.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("telnet")
+                .short('t')
+                .long("telnet")
+                .default_value("2004")
+                .about("Sets the telnet port of the server to test.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .default_value("10")
+                .about("Sets how long a bot waits for a prompt before giving up, in seconds.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("wander")
+                .long("wander")
+                .default_value("1")
+                .about("Sets the relative weight of wandering among bot activities.")
+                .takes_value(true),
+        )
+        .get_matches();
+
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| "warn".into()))
+        .init();
+
+    run(&matches).await
+}
+
+async fn run(matches: &ArgMatches) -> anyhow::Result<()> {
+    let bots: usize = parse(matches, "bots")?;
+    let duration = Duration::from_secs(parse(matches, "duration")?);
+    let ramp = Duration::from_millis(parse(matches, "ramp")?);
+    let read_timeout = Duration::from_secs(parse(matches, "timeout")?);
+
+    let behavior = Behavior {
+        rate: parse(matches, "rate")?,
+        weights: [
+            parse(matches, "wander")?,
+            parse(matches, "chat")?,
+            parse(matches, "look")?,
+        ],
+    };
+
+    if !behavior.rate.is_finite() || behavior.rate <= 0.0 {
+        bail!("rate should be greater than zero.");
+    }
+    if behavior.weights.iter().all(|weight| *weight == 0) {
+        bail!("at least one of wander, chat and look should have a weight above zero.");
+    }
+
+    let address = format!(
+        "{}:{}",
+        matches.value_of("host").unwrap(),
+        matches.value_of("telnet").unwrap()
+    );
+    let prefix = matches.value_of("prefix").unwrap();
+    let password = matches.value_of("password").unwrap();
+
+    println!(
+        "Connecting {} bots to {} for {}s at {} commands per second each.",
+        bots,
+        address,
+        duration.as_secs(),
+        behavior.rate
+    );
+
+    let start = Instant::now();
+    let deadline = start + duration;
+
+    let mut handles = Vec::with_capacity(bots);
+    for n in 1..=bots {
+        let login = Login {
+            address: address.clone(),
+            name: format!("{}{}", prefix, n),
+            password: password.to_string(),
+            read_timeout,
+        };
+        handles.push(tokio::spawn(bot::run(login, behavior.clone(), deadline)));
+
+        if n < bots {
+            tokio::time::sleep(ramp).await;
+        }
+    }
+
+    let mut stats = Stats::default();
+    for handle in handles {
+        match handle.await {
+            Ok(bot) => stats.merge(bot),
+            Err(e) => {
+                tracing::error!("bot task failed: {}", e);
+                stats.error();
+            }
+        }
+    }
+
+    report(&stats, start.elapsed());
+
+    Ok(())
+}
+
+fn report(stats: &Stats, elapsed: Duration) {
+    println!();
+    println!(
+        "{:<8} {:>8} {:>10} {:>10} {:>10} {:>10}",
+        "command", "count", "p50", "p90", "p99", "max"
+    );
+    for summary in stats.summaries() {
+        println!("{}", summary);
+    }
+    println!();
+    println!(
+        "{} commands in {:.1}s ({:.1}/s), {} bots failed.",
+        stats.commands(),
+        elapsed.as_secs_f64(),
+        stats.commands() as f64 / elapsed.as_secs_f64(),
+        stats.errors()
+    );
+}
+
+fn parse<T: FromStr>(matches: &ArgMatches, name: &str) -> anyhow::Result<T> {
+    let value = matches.value_of(name).unwrap();
+    match value.parse::<T>() {
+        Ok(value) => Ok(value),
+        Err(_) => bail!("invalid value for {}: {}", name, value),
+    }
+}
@@ -0,0 +1,152 @@
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+/// Prompt round-trip latencies recorded by bots, grouped by the kind of command sent.
+#[derive(Debug, Default)]
+pub struct Stats {
+    latencies: BTreeMap<&'static str, Vec<Duration>>,
+    errors: usize,
+}
+
+impl Stats {
+    pub fn record(&mut self, kind: &'static str, latency: Duration) {
+        self.latencies.entry(kind).or_default().push(latency);
+    }
+
+    pub fn error(&mut self) {
+        self.errors += 1;
+    }
+
+    pub fn merge(&mut self, other: Stats) {
+        for (kind, mut latencies) in other.latencies {
+            self.latencies
+                .entry(kind)
+                .or_default()
+                .append(&mut latencies);
+        }
+        self.errors += other.errors;
+    }
+
+    pub fn commands(&self) -> usize {
+        self.latencies
+            .iter()
+            .filter(|(kind, _)| **kind != "login")
+            .map(|(_, latencies)| latencies.len())
+            .sum()
+    }
+
+    pub fn summaries(&self) -> Vec<Summary> {
+        let mut all = Vec::new();
+        let mut summaries = Vec::new();
+
+        for (kind, latencies) in &self.latencies {
+            if *kind != "login" {
+                all.extend_from_slice(latencies.as_slice());
+            }
+            if let Some(summary) = Summary::new(kind, latencies.clone()) {
+                summaries.push(summary);
+            }
+        }
+
+        if let Some(summary) = Summary::new("all", all) {
+            summaries.push(summary);
+        }
+
+        summaries
+    }
+
+    pub fn errors(&self) -> usize {
+        self.errors
+    }
+}
+
+/// Latency percentiles for one kind of command.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Summary {
+    pub kind: &'static str,
+    pub count: usize,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl Summary {
+    fn new(kind: &'static str, mut latencies: Vec<Duration>) -> Option<Self> {
+        if latencies.is_empty() {
+            return None;
+        }
+
+        latencies.sort_unstable();
+
+        Some(Summary {
+            kind,
+            count: latencies.len(),
+            p50: percentile(latencies.as_slice(), 50),
+            p90: percentile(latencies.as_slice(), 90),
+            p99: percentile(latencies.as_slice(), 99),
+            max: *latencies.last().unwrap(),
+        })
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<8} {:>8} {:>10.2?} {:>10.2?} {:>10.2?} {:>10.2?}",
+            self.kind, self.count, self.p50, self.p90, self.p99, self.max
+        )
+    }
+}
+
+// Nearest-rank percentile of sorted, non-empty latencies.
+fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    let rank = (percent * sorted.len()).div_ceil(100);
+    sorted[rank.max(1) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles() {
+        let mut stats = Stats::default();
+        for ms in (1..=100).rev() {
+            stats.record("look", Duration::from_millis(ms));
+        }
+        stats.record("login", Duration::from_secs(1));
+
+        let summaries = stats.summaries();
+        let look = summaries.iter().find(|s| s.kind == "look").unwrap();
+        assert_eq!(look.count, 100);
+        assert_eq!(look.p50, Duration::from_millis(50));
+        assert_eq!(look.p90, Duration::from_millis(90));
+        assert_eq!(look.p99, Duration::from_millis(99));
+        assert_eq!(look.max, Duration::from_millis(100));
+
+        // logins are reported but not counted as commands
+        let all = summaries.iter().find(|s| s.kind == "all").unwrap();
+        assert_eq!(all.count, 100);
+        assert_eq!(stats.commands(), 100);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut first = Stats::default();
+        first.record("say", Duration::from_millis(5));
+        first.error();
+
+        let mut second = Stats::default();
+        second.record("say", Duration::from_millis(7));
+        second.error();
+
+        first.merge(second);
+        assert_eq!(first.commands(), 2);
+        assert_eq!(first.errors(), 2);
+        assert_eq!(
+            percentile(&[Duration::from_millis(7)], 50),
+            Duration::from_millis(7)
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    time::timeout,
+};
+
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+
+const ECHO: u8 = 1;
+
+// Where the telnet parser is within the stream of bytes from the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Data,
+    Iac,
+    Negotiate(u8),
+    Subnegotiate,
+    SubnegotiateIac,
+}
+
+/// A bare line mode telnet client. Every option the server offers is refused except echo, so
+/// output arrives as plain lines followed by a "> " prompt.
+pub struct Connection {
+    stream: TcpStream,
+    state: State,
+    buffer: Vec<u8>,
+    read_timeout: Duration,
+}
+
+impl Connection {
+    pub async fn connect(address: &str, read_timeout: Duration) -> anyhow::Result<Self> {
+        let stream = TcpStream::connect(address).await?;
+        stream.set_nodelay(true)?;
+
+        Ok(Connection {
+            stream,
+            state: State::Data,
+            buffer: Vec::new(),
+            read_timeout,
+        })
+    }
+
+    /// Sends a line of input to the server.
+    pub async fn send(&mut self, line: &str) -> anyhow::Result<()> {
+        self.stream.write_all(line.as_bytes()).await?;
+        self.stream.write_all(b"\r\n").await?;
+        Ok(())
+    }
+
+    /// Reads output until the server prompts for input, returning the lines received before the
+    /// prompt.
+    pub async fn read_to_prompt(&mut self) -> anyhow::Result<Vec<String>> {
+        let mut scratch = [0u8; 4096];
+
+        while !self.buffer.ends_with(b"> ") {
+            let read = timeout(self.read_timeout, self.stream.read(&mut scratch))
+                .await
+                .map_err(|_| anyhow!("timed out waiting for a prompt"))??;
+
+            if read == 0 {
+                bail!("connection closed by server");
+            }
+
+            let replies = self.parse(&scratch[..read]);
+            if !replies.is_empty() {
+                self.stream.write_all(replies.as_slice()).await?;
+            }
+        }
+
+        let output = String::from_utf8_lossy(&self.buffer[..self.buffer.len() - 2]).to_string();
+        self.buffer.clear();
+
+        Ok(output
+            .split("\r\n")
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && *line != ">")
+            .map(ToString::to_string)
+            .collect())
+    }
+
+    // Moves data into the buffer, returning any negotiation replies owed to the server.
+    fn parse(&mut self, bytes: &[u8]) -> Vec<u8> {
+        let mut replies = Vec::new();
+
+        for byte in bytes.iter().copied() {
+            self.state = match (self.state, byte) {
+                (State::Data, IAC) => State::Iac,
+                (State::Data, byte) => {
+                    self.buffer.push(byte);
+                    State::Data
+                }
+                (State::Iac, IAC) => {
+                    self.buffer.push(IAC);
+                    State::Data
+                }
+                (State::Iac, SB) => State::Subnegotiate,
+                (State::Iac, command @ (DO | DONT | WILL | WONT)) => State::Negotiate(command),
+                // go ahead, no-op and other single byte commands
+                (State::Iac, _) => State::Data,
+                (State::Negotiate(command), option) => {
+                    let reply = match command {
+                        WILL if option == ECHO => Some(DO),
+                        WILL => Some(DONT),
+                        DO => Some(WONT),
+                        _ => None,
+                    };
+                    if let Some(reply) = reply {
+                        replies.extend_from_slice(&[IAC, reply, option]);
+                    }
+                    State::Data
+                }
+                (State::Subnegotiate, IAC) => State::SubnegotiateIac,
+                (State::Subnegotiate, _) => State::Subnegotiate,
+                (State::SubnegotiateIac, SE) => State::Data,
+                (State::SubnegotiateIac, _) => State::Subnegotiate,
+            };
+        }
+
+        replies
+    }
+}
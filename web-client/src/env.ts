@@ -8,8 +8,8 @@ export const ScriptApiBaseUrl = () => {
 
 export const WebsocketBaseUrl = () => {
   if (process.env.NODE_ENV === "production") {
-    return "wss://uplink.city-six.com/ws";
+    return "wss://uplink.city-six.com/ws/play";
   } else {
-    return "ws://localhost:2080/ws";
+    return "ws://localhost:2080/ws/play";
   }
 };
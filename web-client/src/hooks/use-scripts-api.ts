@@ -74,15 +74,19 @@ export const useScriptsApi = (baseURL: string) => {
   );
 
   const checkForErr = useCallback(
-    async (req: Promise<AjaxResponse<any>>): Promise<CompileError | void> => {
-      return new Promise<CompileError | void>((resolve, reject) => {
+    async (req: Promise<AjaxResponse<any>>): Promise<number | undefined> => {
+      return new Promise<number | undefined>((resolve, reject) => {
         req
           .then((r) => {
             const resp = r.response as ScriptAPIResp;
             if (resp && resp.error) {
-              return reject({ ...resp.error, isSaved: true });
+              return reject({
+                ...resp.error,
+                isSaved: true,
+                revision: resp.revision,
+              });
             }
-            return resolve();
+            return resolve(resp?.revision);
           })
           .catch((err) => {
             let reason: CompileError = {
@@ -90,7 +94,10 @@ export const useScriptsApi = (baseURL: string) => {
               message: err.message,
             };
             if (err.status === 409) {
-              reason.message = `A script with that name already exists.`;
+              reason.message =
+                err.response?.message === "REVISION_CONFLICT"
+                  ? `This script was changed by someone else since you opened it. Reload to see their changes.`
+                  : `A script with that name already exists.`;
             }
             return reject(reason);
           });
@@ -138,7 +145,7 @@ export const useScriptsApi = (baseURL: string) => {
   );
 
   const upsert = useCallback(
-    async (script: Script, isCreate: boolean): Promise<CompileError | void> => {
+    async (script: Script, isCreate: boolean): Promise<number | undefined> => {
       if (isCreate) {
         return checkForErr(send(script, "create"));
       }
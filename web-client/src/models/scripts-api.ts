@@ -18,6 +18,9 @@ export interface Script {
   name: string;
   trigger: Trigger;
   code: string;
+  revision?: number;
+  tags?: string[];
+  folder?: string;
   error?: CompileError;
 }
 
@@ -25,12 +28,18 @@ export interface ScriptInfo {
   name: string;
   trigger?: Trigger;
   lines?: number;
+  revision?: number;
+  tags?: string[];
+  folder?: string;
   error?: CompileError;
 }
 
 export interface ListScriptsReq {}
 export interface ListScriptsResp {
   scripts: ScriptInfo[];
+  total: number;
+  page: number;
+  limit?: number;
 }
 
 export interface GetScriptReq {
@@ -39,6 +48,7 @@ export interface GetScriptReq {
 
 export interface CompileError {
   isSaved?: boolean;
+  revision?: number;
   line?: number;
   position?: number;
   message: string;
@@ -46,5 +56,6 @@ export interface CompileError {
 
 // for both save and compile checks
 export interface ScriptAPIResp {
+  revision: number;
   error?: CompileError;
 }
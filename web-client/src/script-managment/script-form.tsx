@@ -41,6 +41,7 @@ export const ScriptForm: React.FC<ScriptFormProps> = ({ isCreate, script }) => {
   const [name, setName] = useState("");
   const [trigger, setTrigger] = useState<Trigger>(Trigger.Say);
   const [code, setCode] = useState("");
+  const [revision, setRevision] = useState<number | undefined>();
   const [err, setErr] = useState<CompileError | undefined>();
   const [create, setCreate] = useState(isCreate);
   const [saved, setSaved] = useState("");
@@ -49,6 +50,7 @@ export const ScriptForm: React.FC<ScriptFormProps> = ({ isCreate, script }) => {
     setName(script?.name || "");
     setCode(script?.code || "");
     setTrigger(script?.trigger || Trigger.Say);
+    setRevision(script?.revision);
     setErr(script?.error);
   }, [script]);
 
@@ -72,8 +74,12 @@ export const ScriptForm: React.FC<ScriptFormProps> = ({ isCreate, script }) => {
 
   const submitForm = async (e: FormEvent) => {
     setSaved("");
-    await upsert({ name: script?.name || name, trigger, code }, create)
-      .then(() => {
+    await upsert(
+      { name: script?.name || name, trigger, code, revision },
+      create
+    )
+      .then((updated) => {
+        setRevision(updated);
         if (create) {
           history.push(`/scripts/${name}`);
         } else {
@@ -86,6 +92,7 @@ export const ScriptForm: React.FC<ScriptFormProps> = ({ isCreate, script }) => {
         setSaved(isSaved ? "Saved with errors 🤷" : "");
         if (isSaved) {
           setCreate(false);
+          setRevision(reason.revision);
         }
         setErr(reason);
       });
@@ -7,6 +7,14 @@ import React, {
 } from "react";
 import { NewSocketService, SocketService } from "./socket.service";
 import { WebsocketBaseUrl } from "../env";
+import { Message } from "../models/ws-api";
+
+// lets a reloaded page take back its session instead of logging in again
+const RESUME_TOKEN_KEY = "remud-resume-token";
+
+interface ResumeToken {
+  token: string;
+}
 
 const SocketContext = createContext<SocketService | undefined>(undefined);
 
@@ -14,6 +22,10 @@ export const SocketContextProvider: React.FC = ({ children }) => {
   const [socket, setSocket] = useState<SocketService | undefined>();
 
   const uri = useMemo(() => {
+    const token = sessionStorage.getItem(RESUME_TOKEN_KEY);
+    if (token) {
+      return `${WebsocketBaseUrl()}?resume=${encodeURIComponent(token)}`;
+    }
     return WebsocketBaseUrl();
   }, []);
 
@@ -21,6 +33,16 @@ export const SocketContextProvider: React.FC = ({ children }) => {
     setSocket(NewSocketService(uri));
   }, [uri]);
 
+  useEffect(() => {
+    if (!socket) {
+      return;
+    }
+    const s = socket.on<Message<ResumeToken>>("resume").subscribe({
+      next: (value) => sessionStorage.setItem(RESUME_TOKEN_KEY, value.data.token),
+    });
+    return () => s.unsubscribe();
+  }, [socket]);
+
   return (
     <SocketContext.Provider value={socket}>{children}</SocketContext.Provider>
   );